@@ -23,8 +23,8 @@ use std::{
     time::{Duration, Instant},
 };
 use ui::{
-    ButtonLike, CommonAnimationExt, ContextMenu, PopoverMenu, PopoverMenuHandle, Tooltip,
-    prelude::*,
+    ButtonLike, CommonAnimationExt, ContextMenu, PopoverMenu, PopoverMenuHandle, ProgressBar,
+    Tooltip, prelude::*,
 };
 use util::truncate_and_trailoff;
 use workspace::{StatusItemView, Workspace, item::ItemHandle};
@@ -179,6 +179,7 @@ impl ActivityIndicator {
                 &project.read(cx).environment().clone(),
                 |_, _, event, cx| match event {
                     ProjectEnvironmentEvent::ErrorsUpdated => cx.notify(),
+                    ProjectEnvironmentEvent::OverridesChanged(_) => {}
                 },
             )
             .detach();
@@ -399,24 +400,53 @@ impl ActivityIndicator {
             });
         }
 
-        let current_job = self
-            .project
-            .read(cx)
-            .active_repository(cx)
+        let active_repository = self.project.read(cx).active_repository(cx);
+        let current_job = active_repository
+            .as_ref()
             .map(|r| r.read(cx))
             .and_then(Repository::current_job);
         // Show any long-running git command
         if let Some(job_info) = current_job
             && Instant::now() - job_info.start >= GIT_OPERATION_DELAY
         {
+            let remote_progress = active_repository
+                .as_ref()
+                .and_then(|repository| repository.read(cx).remote_progress().cloned());
+            // Fetches, pushes and pulls report how far along they are, once git knows.
+            let icon = match remote_progress
+                .as_ref()
+                .and_then(|progress| progress.percent)
+            {
+                Some(percent) => div()
+                    .w_16()
+                    .child(ProgressBar::new(
+                        "git-remote-progress",
+                        percent as f32,
+                        100.,
+                        cx,
+                    ))
+                    .into_any_element(),
+                None => Icon::new(IconName::ArrowCircle)
+                    .size(IconSize::Small)
+                    .with_rotate_animation(2)
+                    .into_any_element(),
+            };
+            let message = match remote_progress {
+                Some(progress) => match (progress.percent, progress.total) {
+                    (Some(percent), Some(total)) => format!(
+                        "{}: {} {percent}% ({}/{total})",
+                        job_info.message, progress.phase, progress.completed
+                    ),
+                    _ => format!(
+                        "{}: {} {}",
+                        job_info.message, progress.phase, progress.completed
+                    ),
+                },
+                None => job_info.message.into(),
+            };
             return Some(Content {
-                icon: Some(
-                    Icon::new(IconName::ArrowCircle)
-                        .size(IconSize::Small)
-                        .with_rotate_animation(2)
-                        .into_any_element(),
-                ),
-                message: job_info.message.into(),
+                icon: Some(icon),
+                message,
                 on_click: None,
                 tooltip_message: None,
             });
@@ -55,7 +55,7 @@ Examples:
           Open your project in Zed
     `zed -n path-to-file `
           Open file/folder in a new window",
-    after_help = "To read from stdin, append '-', e.g. 'ps axf | zed -'"
+    after_help = "To read from stdin, append '-', e.g. 'ps axf | zed -' or 'git show HEAD:file | zed --diff - file'"
 )]
 struct Args {
     /// Wait for all of the given paths to be opened/closed before exiting.
@@ -106,6 +106,10 @@ struct Args {
     /// Will attempt to give the correct command to run
     #[arg(long)]
     system_specs: bool,
+    /// Not supported in Zed CLI, only supported on Zed binary
+    /// Will attempt to give the correct command to run
+    #[arg(long)]
+    timings: bool,
     /// Pairs of file paths to diff. Can be specified multiple times.
     #[arg(long, action = clap::ArgAction::Append, num_args = 2, value_names = ["OLD_PATH", "NEW_PATH"])]
     diff: Vec<String>,
@@ -218,6 +222,8 @@ fn main() -> Result<()> {
     let user_data_dir = args.user_data_dir.clone();
     if let Some(dir) = &user_data_dir {
         paths::set_custom_data_dir(dir);
+    } else if let Some(dir) = paths::portable_data_dir() {
+        paths::set_custom_data_dir(&dir.to_string_lossy());
     }
 
     #[cfg(target_os = "linux")]
@@ -240,6 +246,16 @@ fn main() -> Result<()> {
         anyhow::bail!(msg.join("\n"));
     }
 
+    if args.timings {
+        let path = app.path();
+        let msg = [
+            "The `--timings` argument is not supported in the Zed CLI, only on Zed binary.",
+            "To time startup, quit Zed and run the following command:",
+            &format!("{} --timings", path.display()),
+        ];
+        anyhow::bail!(msg.join("\n"));
+    }
+
     #[cfg(all(
         any(target_os = "linux", target_os = "macos"),
         not(feature = "no-bundled-uninstall")
@@ -314,11 +330,29 @@ fn main() -> Result<()> {
     let mut stdin_tmp_file: Option<fs::File> = None;
     let mut anonymous_fd_tmp_files = vec![];
 
+    // Stdin can only be read once, so `-` may appear once, either as a path or as one side of a
+    // diff.
+    let mut stdin_tmp_path = || -> anyhow::Result<String> {
+        anyhow::ensure!(
+            stdin_tmp_file.is_none(),
+            "'-' can only be given once, as stdin can only be read once"
+        );
+        let file = NamedTempFile::new()?;
+        let path = file.path().to_string_lossy().into_owned();
+        let (file, _) = file.keep()?;
+        stdin_tmp_file = Some(file);
+        Ok(path)
+    };
+
     for path in args.diff.chunks(2) {
-        diff_paths.push([
-            parse_path_with_position(&path[0])?,
-            parse_path_with_position(&path[1])?,
-        ]);
+        let mut diff_path = |path: &str| {
+            if path == "-" {
+                stdin_tmp_path()
+            } else {
+                parse_path_with_position(path)
+            }
+        };
+        diff_paths.push([diff_path(&path[0])?, diff_path(&path[1])?]);
     }
 
     #[cfg(target_os = "windows")]
@@ -329,11 +363,8 @@ fn main() -> Result<()> {
     for path in args.paths_with_position.iter() {
         if URL_PREFIX.iter().any(|&prefix| path.starts_with(prefix)) {
             urls.push(path.to_string());
-        } else if path == "-" && args.paths_with_position.len() == 1 {
-            let file = NamedTempFile::new()?;
-            paths.push(file.path().to_string_lossy().into_owned());
-            let (file, _) = file.keep()?;
-            stdin_tmp_file = Some(file);
+        } else if path == "-" {
+            paths.push(stdin_tmp_path()?);
         } else if let Some(file) = anonymous_fd(path) {
             let tmp_file = NamedTempFile::new()?;
             paths.push(tmp_file.path().to_string_lossy().into_owned());
@@ -20,7 +20,10 @@ use futures::{
     channel::oneshot, future::BoxFuture,
 };
 use gpui::{App, AsyncApp, Entity, Global, Task, WeakEntity, actions};
-use http_client::{HttpClient, HttpClientWithUrl, http, read_proxy_from_env};
+use http_client::{
+    HttpClient, HttpClientWithUrl, http, is_proxy_bypassed, read_no_proxy_from_env,
+    read_proxy_from_env,
+};
 use parking_lot::RwLock;
 use postage::watch;
 use proxy::connect_proxy_stream;
@@ -113,6 +116,7 @@ impl Settings for ClientSettings {
 #[derive(Deserialize, Default)]
 pub struct ProxySettings {
     pub proxy: Option<String>,
+    pub no_proxy: Option<String>,
 }
 
 impl ProxySettings {
@@ -127,12 +131,19 @@ impl ProxySettings {
             })
             .or_else(read_proxy_from_env)
     }
+
+    /// The hosts that bypass the proxy, from the `no_proxy` setting or the `NO_PROXY` environment
+    /// variable.
+    pub fn no_proxy(&self) -> Option<String> {
+        self.no_proxy.clone().or_else(read_no_proxy_from_env)
+    }
 }
 
 impl Settings for ProxySettings {
     fn from_settings(content: &settings::SettingsContent) -> Self {
         Self {
             proxy: content.proxy.clone(),
+            no_proxy: content.no_proxy.clone(),
         }
     }
 
@@ -1236,6 +1247,11 @@ impl Client {
             .ok()
             .unwrap_or_default();
 
+        let no_proxy = cx
+            .update(|cx| ProxySettings::get_global(cx).no_proxy())
+            .ok()
+            .flatten();
+
         let http = self.http.clone();
         let proxy = http.proxy().cloned();
         let user_agent = http.user_agent().cloned();
@@ -1264,6 +1280,11 @@ impl Client {
                         .host_str()
                         .zip(rpc_url.port_or_known_default())
                         .context("missing host in rpc url")?;
+                    let proxy = proxy.filter(|_| {
+                        no_proxy
+                            .as_deref()
+                            .is_none_or(|no_proxy| !is_proxy_bypassed(rpc_host.0, no_proxy))
+                    });
                     Ok(match proxy {
                         Some(proxy) => connect_proxy_stream(&proxy, rpc_host).await?,
                         None => Box::new(TcpStream::connect(rpc_host).await?),
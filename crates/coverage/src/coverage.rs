@@ -0,0 +1,94 @@
+mod coverage_gutter;
+mod coverage_report;
+mod coverage_store;
+
+use std::sync::Arc;
+
+use editor::Editor;
+use fs::Fs;
+use gpui::{App, Context, PathPromptOptions, Window, actions};
+use project::DirectoryLister;
+use util::ResultExt as _;
+use workspace::Workspace;
+
+pub use crate::{coverage_report::FileCoverage, coverage_store::CoverageStore};
+
+actions!(
+    coverage,
+    [
+        /// Loads an LCOV or Cobertura coverage report, and shows which lines it covers.
+        LoadCoverageReport,
+        /// Clears the loaded coverage report.
+        ClearCoverage,
+    ]
+);
+
+pub fn init(fs: Arc<dyn Fs>, cx: &mut App) {
+    CoverageStore::init_global(fs, cx);
+    cx.observe_new(|workspace: &mut Workspace, _, _| {
+        workspace.register_action(load_coverage_report);
+        workspace.register_action(|_, _: &ClearCoverage, _, cx| {
+            if let Some(store) = CoverageStore::global(cx) {
+                store.update(cx, |store, cx| store.clear(cx));
+            }
+        });
+    })
+    .detach();
+    cx.observe_new(|editor: &mut Editor, window, cx| {
+        if let Some(window) = window {
+            coverage_gutter::register_editor(editor, window, cx);
+        }
+    })
+    .detach();
+}
+
+fn load_coverage_report(
+    workspace: &mut Workspace,
+    _: &LoadCoverageReport,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let Some(store) = CoverageStore::global(cx) else {
+        return;
+    };
+    if !workspace.project().read(cx).is_local() {
+        workspace.show_error(
+            &"Coverage reports can only be loaded for local projects",
+            cx,
+        );
+        return;
+    }
+
+    let paths = workspace.prompt_for_open_path(
+        PathPromptOptions {
+            files: true,
+            directories: false,
+            multiple: false,
+            prompt: Some("Load Coverage Report".into()),
+        },
+        DirectoryLister::Local(
+            workspace.project().clone(),
+            workspace.app_state().fs.clone(),
+        ),
+        window,
+        cx,
+    );
+    cx.spawn(async move |workspace, cx| {
+        let Some(report_path) = paths
+            .await
+            .log_err()
+            .flatten()
+            .and_then(|mut paths| paths.pop())
+        else {
+            return Ok(());
+        };
+        let load_report = store.update(cx, |store, cx| store.load_report(report_path, cx))?;
+        if let Err(error) = load_report.await {
+            workspace.update(cx, |workspace, cx| {
+                workspace.show_error(&format!("Failed to load coverage report: {error:#}"), cx);
+            })?;
+        }
+        anyhow::Ok(())
+    })
+    .detach_and_log_err(cx);
+}
@@ -0,0 +1,69 @@
+use std::any::Any;
+
+use editor::Editor;
+use gpui::{Context, Entity, Subscription, Window};
+use language::{Bias, Point};
+use theme::ActiveTheme as _;
+
+use crate::CoverageStore;
+
+enum CoveredLines {}
+enum UncoveredLines {}
+
+/// Keeps the coverage markers in an editor's gutter up to date with the loaded report.
+struct CoverageGutterAddon {
+    _coverage_subscription: Subscription,
+}
+
+impl editor::Addon for CoverageGutterAddon {
+    fn to_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+pub(crate) fn register_editor(editor: &mut Editor, window: &mut Window, cx: &mut Context<Editor>) {
+    if !editor.mode().is_full() || editor.buffer().read(cx).as_singleton().is_none() {
+        return;
+    }
+    let Some(store) = CoverageStore::global(cx) else {
+        return;
+    };
+
+    let coverage_subscription = cx.observe_in(&store, window, |editor, store, _, cx| {
+        update_gutter(editor, &store, cx);
+    });
+    editor.register_addon(CoverageGutterAddon {
+        _coverage_subscription: coverage_subscription,
+    });
+    update_gutter(editor, &store, cx);
+}
+
+fn update_gutter(editor: &mut Editor, store: &Entity<CoverageStore>, cx: &mut Context<Editor>) {
+    let coverage = editor
+        .buffer()
+        .read(cx)
+        .as_singleton()
+        .and_then(|buffer| Some(buffer.read(cx).file()?.as_local()?.abs_path(cx)))
+        .and_then(|abs_path| store.read(cx).coverage_for_path(&abs_path).cloned());
+    let Some(coverage) = coverage else {
+        editor.clear_gutter_highlights::<CoveredLines>(cx);
+        editor.clear_gutter_highlights::<UncoveredLines>(cx);
+        return;
+    };
+
+    let snapshot = editor.buffer().read(cx).snapshot(cx);
+    let max_row = snapshot.max_point().row;
+    let mut covered_ranges = Vec::new();
+    let mut uncovered_ranges = Vec::new();
+    for (&row, &hits) in coverage.line_hits.range(..=max_row) {
+        let range = snapshot.anchor_before(Point::new(row, 0))
+            ..snapshot.anchor_after(snapshot.clip_point(Point::new(row, u32::MAX), Bias::Left));
+        if hits > 0 {
+            covered_ranges.push(range);
+        } else {
+            uncovered_ranges.push(range);
+        }
+    }
+    editor.highlight_gutter::<CoveredLines>(covered_ranges, |cx| cx.theme().status().success, cx);
+    editor.highlight_gutter::<UncoveredLines>(uncovered_ranges, |cx| cx.theme().status().error, cx);
+}
@@ -0,0 +1,247 @@
+use std::{borrow::Cow, collections::BTreeMap, path::PathBuf};
+
+use anyhow::{Context as _, Result};
+
+/// The lines of a file that a coverage report instruments, and whether they ran.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct FileCoverage {
+    /// How many times each instrumented line ran, by zero-based row.
+    pub line_hits: BTreeMap<u32, u64>,
+}
+
+impl FileCoverage {
+    fn record_hits(&mut self, line_number: u32, hits: u64) {
+        // Reports can list a line more than once, such as once for its class and once for its
+        // method in Cobertura reports, so the counts are not added up.
+        let row = line_number.saturating_sub(1);
+        let line_hits = self.line_hits.entry(row).or_default();
+        *line_hits = (*line_hits).max(hits);
+    }
+
+    pub fn covered_line_count(&self) -> usize {
+        self.line_hits.values().filter(|hits| **hits > 0).count()
+    }
+
+    /// The percentage of instrumented lines that ran, if any lines are instrumented.
+    pub fn percentage(&self) -> Option<f32> {
+        if self.line_hits.is_empty() {
+            return None;
+        }
+        Some(self.covered_line_count() as f32 * 100. / self.line_hits.len() as f32)
+    }
+}
+
+/// The coverage of the files in a report, by the paths the report gives them, which may be
+/// relative to the directory the tests ran in or to one of `source_dirs`.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct CoverageReport {
+    pub files: BTreeMap<PathBuf, FileCoverage>,
+    pub source_dirs: Vec<PathBuf>,
+}
+
+/// Parses an LCOV or a Cobertura XML report, telling them apart by their content.
+pub fn parse_report(text: &str) -> Result<CoverageReport> {
+    if text.trim_start().starts_with('<') {
+        parse_cobertura(text)
+    } else {
+        parse_lcov(text)
+    }
+}
+
+fn parse_lcov(text: &str) -> Result<CoverageReport> {
+    let mut report = CoverageReport::default();
+    let mut current_path: Option<PathBuf> = None;
+    for (index, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if let Some(path) = line.strip_prefix("SF:") {
+            current_path = Some(PathBuf::from(path));
+        } else if line == "end_of_record" {
+            current_path = None;
+        } else if let Some(line_data) = line.strip_prefix("DA:") {
+            let Some(path) = &current_path else {
+                continue;
+            };
+            let mut fields = line_data.split(',');
+            let (Some(line_number), Some(hits)) = (fields.next(), fields.next()) else {
+                anyhow::bail!("Invalid line data on line {}", index + 1);
+            };
+            let line_number = line_number
+                .parse()
+                .with_context(|| format!("Invalid line number on line {}", index + 1))?;
+            // Some tools report negative or fractional counts when counters overflow.
+            let hits = hits.parse::<f64>().map_or(0, |hits| hits.max(0.) as u64);
+            report
+                .files
+                .entry(path.clone())
+                .or_default()
+                .record_hits(line_number, hits);
+        }
+    }
+    anyhow::ensure!(
+        !report.files.is_empty(),
+        "The report doesn't cover any files"
+    );
+    Ok(report)
+}
+
+fn parse_cobertura(text: &str) -> Result<CoverageReport> {
+    let mut report = CoverageReport::default();
+    let mut current_path: Option<PathBuf> = None;
+    let mut rest = text;
+    while let Some(tag_start) = rest.find('<') {
+        let text_before_tag = &rest[..tag_start];
+        let tag_length = rest[tag_start..]
+            .find('>')
+            .context("The report ends in the middle of a tag")?;
+        let tag = &rest[tag_start + 1..tag_start + tag_length];
+        rest = &rest[tag_start + tag_length + 1..];
+
+        let tag = tag.strip_suffix('/').unwrap_or(tag);
+        let (name, attributes) = tag.split_once(char::is_whitespace).unwrap_or((tag, ""));
+        match name {
+            "/source" => {
+                let source_dir = unescape(text_before_tag.trim());
+                if !source_dir.is_empty() {
+                    report.source_dirs.push(PathBuf::from(source_dir.as_ref()));
+                }
+            }
+            "class" => {
+                current_path = attribute(attributes, "filename")
+                    .map(|filename| PathBuf::from(filename.as_ref()));
+            }
+            "/class" => current_path = None,
+            "line" => {
+                let Some(path) = &current_path else {
+                    continue;
+                };
+                let line_number = attribute(attributes, "number")
+                    .and_then(|number| number.parse().ok())
+                    .context("A line is missing its number")?;
+                let hits = attribute(attributes, "hits")
+                    .and_then(|hits| hits.parse::<f64>().ok())
+                    .map_or(0, |hits| hits.max(0.) as u64);
+                report
+                    .files
+                    .entry(path.clone())
+                    .or_default()
+                    .record_hits(line_number, hits);
+            }
+            _ => {}
+        }
+    }
+    anyhow::ensure!(
+        !report.files.is_empty(),
+        "The report doesn't cover any files"
+    );
+    Ok(report)
+}
+
+fn attribute<'a>(attributes: &'a str, name: &str) -> Option<Cow<'a, str>> {
+    let mut rest = attributes;
+    loop {
+        let (attribute_name, after_name) = rest.split_once('=')?;
+        let after_name = after_name.trim_start();
+        let quote = after_name.chars().next()?;
+        let (value, after_value) = after_name[1..].split_once(quote)?;
+        if attribute_name.trim() == name {
+            return Some(unescape(value));
+        }
+        rest = after_value;
+    }
+}
+
+fn unescape(text: &str) -> Cow<'_, str> {
+    if !text.contains('&') {
+        return Cow::Borrowed(text);
+    }
+    Cow::Owned(
+        text.replace("&lt;", "<")
+            .replace("&gt;", ">")
+            .replace("&quot;", "\"")
+            .replace("&apos;", "'")
+            .replace("&amp;", "&"),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+
+    #[test]
+    fn test_parse_lcov() {
+        let report = parse_report(indoc! {"
+            TN:
+            SF:src/lib.rs
+            FN:1,main
+            DA:1,3
+            DA:2,0
+            DA:4,1,checksum
+            end_of_record
+            SF:/abs/src/util.rs
+            DA:10,0
+            end_of_record
+        "})
+        .unwrap();
+
+        assert_eq!(report.source_dirs, Vec::<PathBuf>::new());
+        let lib = &report.files[&PathBuf::from("src/lib.rs")];
+        assert_eq!(lib.line_hits, BTreeMap::from_iter([(0, 3), (1, 0), (3, 1)]));
+        assert_eq!(lib.covered_line_count(), 2);
+        assert_eq!(
+            lib.percentage().map(|percentage| percentage.round()),
+            Some(67.)
+        );
+        assert_eq!(
+            report.files[&PathBuf::from("/abs/src/util.rs")].percentage(),
+            Some(0.)
+        );
+
+        assert!(parse_report("TN:\n").is_err());
+    }
+
+    #[test]
+    fn test_parse_cobertura() {
+        let report = parse_report(indoc! {r#"
+            <?xml version="1.0" ?>
+            <!DOCTYPE coverage SYSTEM "http://cobertura.sourceforge.net/xml/coverage-04.dtd">
+            <coverage line-rate="0.5" version="7.4">
+                <sources>
+                    <source>/home/user/project</source>
+                </sources>
+                <packages>
+                    <package name="app">
+                        <classes>
+                            <class name="main.py" filename="app/main &amp; more.py" line-rate="0.5">
+                                <methods>
+                                    <method name="run">
+                                        <lines><line number="2" hits="4"/></lines>
+                                    </method>
+                                </methods>
+                                <lines>
+                                    <line number="1" hits="1"/>
+                                    <line number="2" hits="4" branch="false"/>
+                                    <line hits='0' number='3'/>
+                                </lines>
+                            </class>
+                        </classes>
+                    </package>
+                </packages>
+            </coverage>
+        "#})
+        .unwrap();
+
+        assert_eq!(
+            report,
+            CoverageReport {
+                files: BTreeMap::from_iter([(
+                    PathBuf::from("app/main & more.py"),
+                    FileCoverage {
+                        line_hits: BTreeMap::from_iter([(0, 1), (1, 4), (2, 0)]),
+                    },
+                )]),
+                source_dirs: vec![PathBuf::from("/home/user/project")],
+            }
+        );
+    }
+}
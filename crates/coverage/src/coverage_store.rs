@@ -0,0 +1,148 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+
+use anyhow::{Context as _, Result};
+use collections::HashMap;
+use fs::Fs;
+use futures::{StreamExt as _, channel::oneshot};
+use gpui::{App, AppContext as _, Context, Entity, Global, Task};
+use util::ResultExt as _;
+
+use crate::coverage_report::{self, FileCoverage};
+
+const WATCH_LATENCY: Duration = Duration::from_millis(500);
+
+struct GlobalCoverageStore(Entity<CoverageStore>);
+
+impl Global for GlobalCoverageStore {}
+
+/// The coverage of each file in the loaded report, which is shared by every window.
+pub struct CoverageStore {
+    fs: Arc<dyn Fs>,
+    report_path: Option<PathBuf>,
+    files: HashMap<PathBuf, Arc<FileCoverage>>,
+    watch_task: Task<()>,
+}
+
+impl CoverageStore {
+    pub(crate) fn init_global(fs: Arc<dyn Fs>, cx: &mut App) {
+        let store = cx.new(|_| Self {
+            fs,
+            report_path: None,
+            files: HashMap::default(),
+            watch_task: Task::ready(()),
+        });
+        cx.set_global(GlobalCoverageStore(store));
+    }
+
+    pub fn global(cx: &App) -> Option<Entity<Self>> {
+        cx.try_global::<GlobalCoverageStore>()
+            .map(|store| store.0.clone())
+    }
+
+    pub fn report_path(&self) -> Option<&Path> {
+        self.report_path.as_deref()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    pub fn coverage_for_path(&self, abs_path: &Path) -> Option<&Arc<FileCoverage>> {
+        self.files.get(abs_path)
+    }
+
+    /// Loads the report at `report_path`, then loads it again whenever it changes, such as when a
+    /// task runs the tests again. The returned task resolves once the report has first loaded.
+    pub fn load_report(
+        &mut self,
+        report_path: PathBuf,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        self.report_path = Some(report_path.clone());
+        let fs = self.fs.clone();
+        let (loaded_tx, loaded_rx) = oneshot::channel();
+        self.watch_task = cx.spawn(async move |this, cx| {
+            let (mut events, _watcher) = fs.watch(&report_path, WATCH_LATENCY).await;
+            let mut loaded_tx = Some(loaded_tx);
+            loop {
+                let result = match read_report(&fs, &report_path).await {
+                    Ok(files) => this.update(cx, |this, cx| {
+                        this.files = files;
+                        cx.notify();
+                    }),
+                    Err(error) => Err(error),
+                };
+                match loaded_tx.take() {
+                    Some(loaded_tx) => {
+                        loaded_tx.send(result).ok();
+                    }
+                    // The report may be read while it is still being written, so the coverage
+                    // from the last complete read is kept.
+                    None => {
+                        result
+                            .with_context(|| format!("reloading {}", report_path.display()))
+                            .log_err();
+                    }
+                }
+                if events.next().await.is_none() {
+                    return;
+                }
+            }
+        });
+        cx.background_spawn(async move { loaded_rx.await? })
+    }
+
+    pub fn clear(&mut self, cx: &mut Context<Self>) {
+        self.report_path = None;
+        self.files.clear();
+        self.watch_task = Task::ready(());
+        cx.notify();
+    }
+}
+
+async fn read_report(
+    fs: &Arc<dyn Fs>,
+    report_path: &Path,
+) -> Result<HashMap<PathBuf, Arc<FileCoverage>>> {
+    let text = fs.load(report_path).await?;
+    let report = coverage_report::parse_report(&text)?;
+
+    let report_dir = report_path.parent().unwrap_or(report_path);
+    // Relative paths are usually relative to the directory the tests ran in, which is the
+    // report's directory or one of its ancestors, or to one of the report's source directories.
+    let base_dirs = report
+        .source_dirs
+        .iter()
+        .map(|source_dir| report_dir.join(source_dir))
+        .chain(report_dir.ancestors().map(Path::to_path_buf))
+        .collect::<Vec<_>>();
+
+    let mut files = HashMap::default();
+    for (path, coverage) in report.files {
+        let abs_path = if path.is_absolute() {
+            Some(path)
+        } else {
+            let mut abs_path = None;
+            for base_dir in &base_dirs {
+                let candidate = base_dir.join(&path);
+                if fs.is_file(&candidate).await {
+                    abs_path = Some(candidate);
+                    break;
+                }
+            }
+            abs_path
+        };
+        if let Some(abs_path) = abs_path {
+            files.insert(abs_path, Arc::new(coverage));
+        }
+    }
+    anyhow::ensure!(
+        !files.is_empty(),
+        "None of the files in the report were found"
+    );
+    Ok(files)
+}
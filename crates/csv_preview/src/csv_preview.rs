@@ -0,0 +1,25 @@
+use gpui::{App, actions};
+use workspace::Workspace;
+
+pub mod csv_preview_view;
+pub mod csv_table;
+
+actions!(
+    csv,
+    [
+        /// Opens a table view for the current CSV or TSV file.
+        OpenPreview,
+        /// Opens a table view for the current CSV or TSV file in a split pane.
+        OpenPreviewToTheSide
+    ]
+);
+
+pub fn init(cx: &mut App) {
+    cx.observe_new(|workspace: &mut Workspace, window, cx| {
+        let Some(window) = window else {
+            return;
+        };
+        crate::csv_preview_view::CsvPreviewView::register(workspace, window, cx);
+    })
+    .detach();
+}
@@ -0,0 +1,509 @@
+use std::time::Duration;
+
+use anyhow::Result;
+use editor::{Editor, EditorEvent, actions::SelectAll};
+use gpui::{
+    App, ClickEvent, Context, Div, Entity, EventEmitter, FocusHandle, Focusable, FontWeight,
+    IntoElement, ParentElement, Render, Stateful, Styled, Subscription, Task,
+    UniformListScrollHandle, Window, uniform_list,
+};
+use language::{Buffer, BufferEvent, BufferSnapshot};
+use settings::Settings;
+use theme::ThemeSettings;
+use ui::prelude::*;
+use workspace::item::Item;
+use workspace::{Pane, Workspace};
+
+use crate::csv_table::{CsvRow, compare_cells, escape_cell, is_numeric_column, parse_csv};
+use crate::{OpenPreview, OpenPreviewToTheSide};
+
+const REPARSE_DEBOUNCE: Duration = Duration::from_millis(200);
+const MIN_COLUMN_CHARS: usize = 3;
+const MAX_COLUMN_CHARS: usize = 40;
+
+pub struct CsvPreviewView {
+    focus_handle: FocusHandle,
+    editor: Entity<Editor>,
+    buffer: Entity<Buffer>,
+    delimiter: char,
+    /// The buffer contents the rows were parsed from, used to map cell edits back to the buffer
+    /// even if it has changed since.
+    snapshot: Option<BufferSnapshot>,
+    rows: Vec<CsvRow>,
+    columns: Vec<CsvColumn>,
+    /// The indices of the rows below the header, in the order they are displayed.
+    row_order: Vec<usize>,
+    sort: Option<(usize, SortDirection)>,
+    selected_cell: Option<(usize, usize)>,
+    cell_editor: Option<CellEditor>,
+    scroll_handle: UniformListScrollHandle,
+    parse_task: Option<Task<Result<()>>>,
+    _buffer_subscription: Subscription,
+}
+
+struct CsvColumn {
+    width_in_chars: usize,
+    is_numeric: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SortDirection {
+    Ascending,
+    Descending,
+}
+
+struct CellEditor {
+    row: usize,
+    column: usize,
+    editor: Entity<Editor>,
+    _subscription: Subscription,
+}
+
+impl CsvPreviewView {
+    pub fn register(workspace: &mut Workspace, _window: &mut Window, _cx: &mut Context<Workspace>) {
+        workspace.register_action(move |workspace, _: &OpenPreview, window, cx| {
+            if let Some((editor, buffer, delimiter)) =
+                Self::resolve_active_csv_editor(workspace, cx)
+            {
+                let view = cx.new(|cx| Self::new(editor.clone(), buffer, delimiter, window, cx));
+                workspace.active_pane().update(cx, |pane, cx| {
+                    if let Some(existing_view_idx) = Self::find_existing_view_idx(pane, &editor, cx)
+                    {
+                        pane.activate_item(existing_view_idx, true, true, window, cx);
+                    } else {
+                        pane.add_item(Box::new(view), true, true, None, window, cx)
+                    }
+                });
+                cx.notify();
+            }
+        });
+
+        workspace.register_action(move |workspace, _: &OpenPreviewToTheSide, window, cx| {
+            if let Some((editor, buffer, delimiter)) =
+                Self::resolve_active_csv_editor(workspace, cx)
+            {
+                let view = cx.new(|cx| Self::new(editor.clone(), buffer, delimiter, window, cx));
+                let pane = workspace
+                    .find_pane_in_direction(workspace::SplitDirection::Right, cx)
+                    .unwrap_or_else(|| {
+                        workspace.split_pane(
+                            workspace.active_pane().clone(),
+                            workspace::SplitDirection::Right,
+                            window,
+                            cx,
+                        )
+                    });
+                pane.update(cx, |pane, cx| {
+                    if let Some(existing_view_idx) = Self::find_existing_view_idx(pane, &editor, cx)
+                    {
+                        pane.activate_item(existing_view_idx, true, true, window, cx);
+                    } else {
+                        pane.add_item(Box::new(view), false, false, None, window, cx)
+                    }
+                });
+                editor.focus_handle(cx).focus(window);
+                cx.notify();
+            }
+        });
+    }
+
+    fn find_existing_view_idx(pane: &Pane, editor: &Entity<Editor>, cx: &App) -> Option<usize> {
+        pane.items_of_type::<CsvPreviewView>()
+            .find(|view| view.read(cx).editor == *editor)
+            .and_then(|view| pane.index_for_item(&view))
+    }
+
+    /// Returns the active editor and its buffer if it is editing a CSV or TSV file, along with
+    /// the delimiter of the file.
+    fn resolve_active_csv_editor(
+        workspace: &Workspace,
+        cx: &App,
+    ) -> Option<(Entity<Editor>, Entity<Buffer>, char)> {
+        let editor = workspace
+            .active_item(cx)
+            .and_then(|item| item.act_as::<Editor>(cx))?;
+        let buffer = editor.read(cx).buffer().read(cx).as_singleton()?;
+        let file = buffer.read(cx).file()?;
+        let delimiter = match file.path().extension()? {
+            extension if extension.eq_ignore_ascii_case("csv") => ',',
+            extension if extension.eq_ignore_ascii_case("tsv") => '\t',
+            _ => return None,
+        };
+        Some((editor, buffer, delimiter))
+    }
+
+    pub fn new(
+        editor: Entity<Editor>,
+        buffer: Entity<Buffer>,
+        delimiter: char,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let buffer_subscription = cx.subscribe_in(
+            &buffer,
+            window,
+            |this, _, event: &BufferEvent, window, cx| {
+                if let BufferEvent::Edited | BufferEvent::Reloaded = event {
+                    this.parse(true, window, cx);
+                }
+            },
+        );
+
+        let mut this = Self {
+            focus_handle: cx.focus_handle(),
+            editor,
+            buffer,
+            delimiter,
+            snapshot: None,
+            rows: Vec::new(),
+            columns: Vec::new(),
+            row_order: Vec::new(),
+            sort: None,
+            selected_cell: None,
+            cell_editor: None,
+            scroll_handle: UniformListScrollHandle::new(),
+            parse_task: None,
+            _buffer_subscription: buffer_subscription,
+        };
+        this.parse(false, window, cx);
+        this
+    }
+
+    fn parse(&mut self, wait_for_debounce: bool, window: &mut Window, cx: &mut Context<Self>) {
+        let delimiter = self.delimiter;
+        self.parse_task = Some(cx.spawn_in(window, async move |this, cx| {
+            if wait_for_debounce {
+                // Wait for the user to stop typing
+                cx.background_executor().timer(REPARSE_DEBOUNCE).await;
+            }
+
+            let snapshot = this.update(cx, |this, cx| this.buffer.read(cx).snapshot())?;
+            let (snapshot, rows, columns) = cx
+                .background_spawn(async move {
+                    let rows = parse_csv(&snapshot.text(), delimiter);
+                    let columns = measure_columns(&rows);
+                    (snapshot, rows, columns)
+                })
+                .await;
+            this.update(cx, |this, cx| {
+                this.snapshot = Some(snapshot);
+                this.rows = rows;
+                this.columns = columns;
+                this.update_row_order();
+                cx.notify();
+            })
+        }));
+    }
+
+    fn update_row_order(&mut self) {
+        self.row_order = (1..self.rows.len()).collect();
+        let Some((column, direction)) = self.sort else {
+            return;
+        };
+        let is_numeric = self
+            .columns
+            .get(column)
+            .is_some_and(|column| column.is_numeric);
+        let rows = &self.rows;
+        let cell_value = |row: usize| {
+            rows[row]
+                .cells
+                .get(column)
+                .map_or("", |cell| cell.value.as_str())
+        };
+        self.row_order.sort_by(|a, b| {
+            let ordering = compare_cells(cell_value(*a), cell_value(*b), is_numeric);
+            match direction {
+                SortDirection::Ascending => ordering,
+                SortDirection::Descending => ordering.reverse(),
+            }
+        });
+    }
+
+    fn toggle_sort(&mut self, column: usize, cx: &mut Context<Self>) {
+        self.sort = match self.sort {
+            Some((sorted_column, SortDirection::Ascending)) if sorted_column == column => {
+                Some((column, SortDirection::Descending))
+            }
+            Some((sorted_column, SortDirection::Descending)) if sorted_column == column => None,
+            _ => Some((column, SortDirection::Ascending)),
+        };
+        self.update_row_order();
+        cx.notify();
+    }
+
+    fn cell_value(&self, row: usize, column: usize) -> &str {
+        self.rows
+            .get(row)
+            .and_then(|row| row.cells.get(column))
+            .map_or("", |cell| cell.value.as_str())
+    }
+
+    fn start_editing(
+        &mut self,
+        row: usize,
+        column: usize,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.commit_cell_edit(window, cx);
+        let value = self.cell_value(row, column).to_string();
+        let editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_text(value, window, cx);
+            editor.select_all(&SelectAll, window, cx);
+            editor
+        });
+        let subscription = cx.subscribe_in(
+            &editor,
+            window,
+            |this, _, event: &EditorEvent, window, cx| {
+                if let EditorEvent::Blurred = event {
+                    this.commit_cell_edit(window, cx);
+                }
+            },
+        );
+        editor.focus_handle(cx).focus(window);
+        self.selected_cell = Some((row, column));
+        self.cell_editor = Some(CellEditor {
+            row,
+            column,
+            editor,
+            _subscription: subscription,
+        });
+        cx.notify();
+    }
+
+    /// Writes the value of the cell being edited back to the buffer.
+    fn commit_cell_edit(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(cell_editor) = self.cell_editor.take() else {
+            return;
+        };
+        window.focus(&self.focus_handle);
+        cx.notify();
+
+        let new_value = cell_editor.editor.read(cx).text(cx);
+        let (Some(snapshot), Some(row)) = (&self.snapshot, self.rows.get(cell_editor.row)) else {
+            return;
+        };
+        let (range, new_text) = match row.cells.get(cell_editor.column) {
+            Some(cell) if cell.value == new_value => return,
+            Some(cell) => (
+                cell.source_range.clone(),
+                escape_cell(&new_value, self.delimiter).into_owned(),
+            ),
+            // Rows with fewer cells than the header are extended up to the edited cell.
+            None if new_value.is_empty() => return,
+            None => {
+                let missing_cells = cell_editor.column + 1 - row.cells.len();
+                (
+                    row.source_range.end..row.source_range.end,
+                    self.delimiter.to_string().repeat(missing_cells)
+                        + &escape_cell(&new_value, self.delimiter),
+                )
+            }
+        };
+        let range = snapshot.anchor_before(range.start)..snapshot.anchor_after(range.end);
+        self.buffer.update(cx, |buffer, cx| {
+            buffer.edit([(range, new_text)], None, cx);
+        });
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        if self.cell_editor.is_some() {
+            self.commit_cell_edit(window, cx);
+        } else if let Some((row, column)) = self.selected_cell {
+            self.start_editing(row, column, window, cx);
+        }
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, window: &mut Window, cx: &mut Context<Self>) {
+        if self.cell_editor.take().is_some() {
+            window.focus(&self.focus_handle);
+            cx.notify();
+        } else {
+            cx.propagate();
+        }
+    }
+
+    fn column_width(&self, column: &CsvColumn, cx: &App) -> Pixels {
+        // Cells use the monospace buffer font, whose characters are about 0.6em wide.
+        let char_width = ThemeSettings::get_global(cx).buffer_font_size(cx) * 0.6;
+        char_width * column.width_in_chars as f32 + DynamicSpacing::Base16.px(cx)
+    }
+
+    fn render_row(&self, row: usize, cx: &mut Context<Self>) -> Stateful<Div> {
+        let is_header = row == 0;
+        let colors = cx.theme().colors();
+        h_flex()
+            .id(("csv-row", row))
+            .when(is_header, |this| {
+                this.bg(colors.editor_subheader_background)
+                    .border_b_1()
+                    .border_color(colors.border)
+            })
+            .children(self.columns.iter().enumerate().map(|(column_ix, column)| {
+                let is_selected = self.selected_cell == Some((row, column_ix));
+                let cell_editor = self.cell_editor.as_ref().filter(|cell_editor| {
+                    cell_editor.row == row && cell_editor.column == column_ix
+                });
+                let value = self.cell_value(row, column_ix);
+                let first_line = value.lines().next().unwrap_or_default().to_string();
+
+                h_flex()
+                    .id(("csv-cell", column_ix))
+                    .flex_none()
+                    .w(self.column_width(column, cx))
+                    .px_2()
+                    .py_0p5()
+                    .overflow_hidden()
+                    .border_r_1()
+                    .border_color(colors.border_variant)
+                    .when(column.is_numeric && !is_header, |this| this.justify_end())
+                    .when(is_selected, |this| this.bg(colors.element_selected))
+                    .map(|this| match cell_editor {
+                        Some(cell_editor) => this.child(cell_editor.editor.clone()),
+                        None => this.child(
+                            Label::new(first_line)
+                                .buffer_font(cx)
+                                .single_line()
+                                .truncate()
+                                .when(is_header, |label| label.weight(FontWeight::SEMIBOLD)),
+                        ),
+                    })
+                    .when(is_header, |this| {
+                        this.gap_1().when_some(
+                            self.sort
+                                .filter(|(sorted_column, _)| *sorted_column == column_ix),
+                            |this, (_, direction)| {
+                                this.child(
+                                    Icon::new(match direction {
+                                        SortDirection::Ascending => IconName::ArrowUp,
+                                        SortDirection::Descending => IconName::ArrowDown,
+                                    })
+                                    .size(IconSize::XSmall)
+                                    .color(Color::Muted),
+                                )
+                            },
+                        )
+                    })
+                    .on_click(cx.listener(move |this, event: &ClickEvent, window, cx| {
+                        if is_header && event.click_count() == 1 {
+                            this.toggle_sort(column_ix, cx);
+                        } else if event.click_count() >= 2 {
+                            this.start_editing(row, column_ix, window, cx);
+                        } else {
+                            this.commit_cell_edit(window, cx);
+                            this.selected_cell = Some((row, column_ix));
+                            window.focus(&this.focus_handle);
+                            cx.notify();
+                        }
+                    }))
+            }))
+    }
+}
+
+fn measure_columns(rows: &[CsvRow]) -> Vec<CsvColumn> {
+    let column_count = rows.iter().map(|row| row.cells.len()).max().unwrap_or(0);
+    (0..column_count)
+        .map(|column| {
+            let values = rows
+                .iter()
+                .filter_map(move |row| row.cells.get(column))
+                .map(|cell| cell.value.as_str());
+            let width_in_chars = values
+                .clone()
+                .map(|value| value.lines().next().unwrap_or_default().chars().count())
+                .max()
+                .unwrap_or(0)
+                .clamp(MIN_COLUMN_CHARS, MAX_COLUMN_CHARS);
+            CsvColumn {
+                width_in_chars,
+                is_numeric: is_numeric_column(values.skip(1)),
+            }
+        })
+        .collect()
+}
+
+impl Focusable for CsvPreviewView {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl EventEmitter<()> for CsvPreviewView {}
+
+impl Item for CsvPreviewView {
+    type Event = ();
+
+    fn tab_icon(&self, _window: &Window, _cx: &App) -> Option<Icon> {
+        Some(Icon::new(IconName::FileDoc))
+    }
+
+    fn tab_content_text(&self, _detail: usize, cx: &App) -> SharedString {
+        self.buffer
+            .read(cx)
+            .file()
+            .and_then(|file| file.path().file_name())
+            .map(|name| format!("Table {name}").into())
+            .unwrap_or_else(|| SharedString::from("Table"))
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        Some("CSV Preview Opened")
+    }
+
+    fn to_item_events(_event: &Self::Event, _f: impl FnMut(workspace::item::ItemEvent)) {}
+}
+
+impl Render for CsvPreviewView {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let table_width = self
+            .columns
+            .iter()
+            .map(|column| self.column_width(column, cx))
+            .fold(px(0.), |total, width| total + width);
+
+        v_flex()
+            .id("CsvPreview")
+            .key_context("CsvPreview")
+            .track_focus(&self.focus_handle(cx))
+            .on_action(cx.listener(Self::confirm))
+            .on_action(cx.listener(Self::cancel))
+            .size_full()
+            .bg(cx.theme().colors().editor_background)
+            .map(|this| {
+                if self.rows.is_empty() {
+                    return this.child(
+                        h_flex()
+                            .size_full()
+                            .justify_center()
+                            .child(Label::new("No rows").color(Color::Muted)),
+                    );
+                }
+                this.child(
+                    div().id("csv-table").size_full().overflow_x_scroll().child(
+                        v_flex()
+                            .h_full()
+                            .w(table_width)
+                            // The header is kept outside of the list, so that it stays visible
+                            // while scrolling.
+                            .child(self.render_row(0, cx))
+                            .child(
+                                uniform_list(
+                                    "csv-rows",
+                                    self.row_order.len(),
+                                    cx.processor(|this, range: std::ops::Range<usize>, _, cx| {
+                                        range
+                                            .map(|ix| this.render_row(this.row_order[ix], cx))
+                                            .collect::<Vec<_>>()
+                                    }),
+                                )
+                                .track_scroll(self.scroll_handle.clone())
+                                .flex_1(),
+                            ),
+                    ),
+                )
+            })
+    }
+}
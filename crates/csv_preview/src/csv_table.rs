@@ -0,0 +1,205 @@
+use std::{borrow::Cow, cmp::Ordering, ops::Range};
+
+/// A row of a CSV file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CsvRow {
+    /// The range of the row in the source, excluding the line ending.
+    pub source_range: Range<usize>,
+    pub cells: Vec<CsvCell>,
+}
+
+/// A cell of a CSV file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CsvCell {
+    /// The range of the cell in the source, including any quotes.
+    pub source_range: Range<usize>,
+    /// The contents of the cell, with quotes removed and escaped quotes unescaped.
+    pub value: String,
+}
+
+/// Parses delimiter-separated values, following RFC 4180.
+///
+/// Quoted cells may contain delimiters, line endings and escaped (doubled) quotes. Blank lines
+/// are skipped.
+pub fn parse_csv(text: &str, delimiter: char) -> Vec<CsvRow> {
+    let bytes = text.as_bytes();
+    let delimiter = delimiter as u8;
+    let mut rows = Vec::new();
+    let mut cells = Vec::new();
+    let mut row_start = 0;
+    let mut position = 0;
+
+    while position <= bytes.len() {
+        let cell_start = position;
+        let mut value = String::new();
+        if bytes.get(position) == Some(&b'"') {
+            position += 1;
+            let mut segment_start = position;
+            while position < bytes.len() {
+                if bytes[position] == b'"' {
+                    value.push_str(&text[segment_start..position]);
+                    if bytes.get(position + 1) == Some(&b'"') {
+                        value.push('"');
+                        position += 2;
+                        segment_start = position;
+                        continue;
+                    }
+                    position += 1;
+                    segment_start = position;
+                    break;
+                }
+                position += 1;
+            }
+            // Anything between the closing quote and the end of the cell is kept as is.
+            while position < bytes.len() && !is_cell_end(bytes, position, delimiter) {
+                position += 1;
+            }
+            value.push_str(&text[segment_start.min(position)..position]);
+        } else {
+            while position < bytes.len() && !is_cell_end(bytes, position, delimiter) {
+                position += 1;
+            }
+            value.push_str(&text[cell_start..position]);
+        }
+        cells.push(CsvCell {
+            source_range: cell_start..position,
+            value,
+        });
+
+        if bytes.get(position) == Some(&delimiter) {
+            position += 1;
+            continue;
+        }
+
+        let row_end = position;
+        let is_blank = cells.len() == 1 && cells[0].source_range.is_empty();
+        let cells = std::mem::take(&mut cells);
+        if !is_blank {
+            rows.push(CsvRow {
+                source_range: row_start..row_end,
+                cells,
+            });
+        }
+        if position >= bytes.len() {
+            break;
+        }
+        position += if bytes[position] == b'\r' { 2 } else { 1 };
+        row_start = position;
+    }
+
+    rows
+}
+
+fn is_cell_end(bytes: &[u8], position: usize, delimiter: u8) -> bool {
+    match bytes[position] {
+        b'\n' => true,
+        b'\r' => bytes.get(position + 1) == Some(&b'\n'),
+        byte => byte == delimiter,
+    }
+}
+
+/// Quotes a cell value if it couldn't otherwise be parsed back.
+pub fn escape_cell(value: &str, delimiter: char) -> Cow<'_, str> {
+    if value.contains([delimiter, '"', '\n', '\r']) {
+        Cow::Owned(format!("\"{}\"", value.replace('"', "\"\"")))
+    } else {
+        Cow::Borrowed(value)
+    }
+}
+
+/// Whether every non-empty value in the column is a number, in which case the column is
+/// right-aligned and sorted numerically.
+pub fn is_numeric_column<'a>(values: impl IntoIterator<Item = &'a str>) -> bool {
+    let mut has_numbers = false;
+    for value in values {
+        let value = value.trim();
+        if value.is_empty() {
+            continue;
+        }
+        if value.parse::<f64>().is_err() {
+            return false;
+        }
+        has_numbers = true;
+    }
+    has_numbers
+}
+
+/// Compares two cell values, numerically if `numeric` is set. Empty values sort last.
+pub fn compare_cells(a: &str, b: &str, numeric: bool) -> Ordering {
+    let (a, b) = (a.trim(), b.trim());
+    match (a.is_empty(), b.is_empty()) {
+        (true, true) => return Ordering::Equal,
+        (true, false) => return Ordering::Greater,
+        (false, true) => return Ordering::Less,
+        (false, false) => {}
+    }
+    if numeric && let (Ok(a), Ok(b)) = (a.parse::<f64>(), b.parse::<f64>()) {
+        return a.total_cmp(&b);
+    }
+    a.to_lowercase().cmp(&b.to_lowercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values(rows: &[CsvRow]) -> Vec<Vec<&str>> {
+        rows.iter()
+            .map(|row| row.cells.iter().map(|cell| cell.value.as_str()).collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_csv() {
+        let text = "name,notes\r\nalice,\"says \"\"hi\"\", waves\"\n\nbob,\"two\nlines\"\ncarol,\n";
+        let rows = parse_csv(text, ',');
+        assert_eq!(
+            values(&rows),
+            vec![
+                vec!["name", "notes"],
+                vec!["alice", "says \"hi\", waves"],
+                vec!["bob", "two\nlines"],
+                vec!["carol", ""],
+            ]
+        );
+        assert_eq!(&text[rows[0].source_range.clone()], "name,notes");
+        assert_eq!(
+            &text[rows[1].cells[1].source_range.clone()],
+            "\"says \"\"hi\"\", waves\""
+        );
+        assert_eq!(
+            rows[3].cells[1].source_range,
+            text.len() - 1..text.len() - 1
+        );
+
+        let rows = parse_csv("a\tb,c\n1\t2", '\t');
+        assert_eq!(values(&rows), vec![vec!["a", "b,c"], vec!["1", "2"]]);
+    }
+
+    #[test]
+    fn test_escape_cell() {
+        assert_eq!(escape_cell("plain", ','), "plain");
+        assert_eq!(escape_cell("a,b", ','), "\"a,b\"");
+        assert_eq!(escape_cell("a,b", '\t'), "a,b");
+        assert_eq!(escape_cell("say \"hi\"", ','), "\"say \"\"hi\"\"\"");
+
+        let text = format!("{},x", escape_cell("multi\nline \"cell\"", ','));
+        assert_eq!(
+            values(&parse_csv(&text, ',')),
+            vec![vec!["multi\nline \"cell\"", "x"]]
+        );
+    }
+
+    #[test]
+    fn test_sorting_values() {
+        assert!(is_numeric_column(["1", " 2.5", "", "-3e2"]));
+        assert!(!is_numeric_column(["1", "two"]));
+        assert!(!is_numeric_column(["", ""]));
+
+        let mut values = vec!["10", "", "9", "100"];
+        values.sort_by(|a, b| compare_cells(a, b, true));
+        assert_eq!(values, ["9", "10", "100", ""]);
+        values.sort_by(|a, b| compare_cells(a, b, false));
+        assert_eq!(values, ["10", "100", "9", ""]);
+    }
+}
@@ -1,6 +1,9 @@
 use anyhow::{Context as _, bail};
 use collections::{FxHashMap, HashMap, HashSet};
-use language::{LanguageName, LanguageRegistry};
+use language::{
+    LanguageName, LanguageRegistry,
+    language_settings::{LanguageDebugSettings, language_settings},
+};
 use std::{
     borrow::Cow,
     path::{Path, PathBuf},
@@ -213,6 +216,26 @@ impl NewProcessModal {
                                     .ok();
                             }
 
+                            if let Some(location) = task_contexts.location().cloned() {
+                                configure_mode
+                                    .update_in(cx, |configure_mode, window, cx| {
+                                        let buffer = location.buffer.read(cx);
+                                        let debug_defaults = language_settings(
+                                            buffer.language().map(|language| language.name()),
+                                            buffer.file(),
+                                            cx,
+                                        )
+                                        .debug
+                                        .clone();
+                                        configure_mode.load_language_defaults(
+                                            &debug_defaults,
+                                            window,
+                                            cx,
+                                        );
+                                    })
+                                    .ok();
+                            }
+
                             if let Some(active_cwd) = task_contexts
                                 .active_context()
                                 .and_then(|context| context.cwd.clone())
@@ -792,6 +815,9 @@ impl RenderOnce for AttachMode {
 pub(super) struct ConfigureMode {
     program: Entity<Editor>,
     cwd: Entity<Editor>,
+    /// Environment from the active language's `debug` settings, applied
+    /// underneath any variables typed before the program.
+    default_env: FxHashMap<String, String>,
     stop_on_entry: ToggleState,
     save_to_debug_json: ToggleState,
 }
@@ -811,11 +837,39 @@ impl ConfigureMode {
         cx.new(|_| Self {
             program,
             cwd,
+            default_env: FxHashMap::default(),
             stop_on_entry: ToggleState::Unselected,
             save_to_debug_json: ToggleState::Unselected,
         })
     }
 
+    pub(super) fn load_language_defaults(
+        &mut self,
+        defaults: &LanguageDebugSettings,
+        window: &mut Window,
+        cx: &mut App,
+    ) {
+        if let Some(program) = &defaults.program {
+            self.program.update(cx, |editor, cx| {
+                if editor.is_empty(cx) {
+                    editor.set_text(program.as_str(), window, cx);
+                }
+            });
+        }
+        if let Some(cwd) = &defaults.cwd {
+            self.cwd.update(cx, |editor, cx| {
+                if editor.is_empty(cx) {
+                    editor.set_text(cwd.as_str(), window, cx);
+                }
+            });
+        }
+        self.default_env = defaults
+            .env
+            .iter()
+            .map(|(name, value)| (name.clone(), value.clone()))
+            .collect();
+    }
+
     fn load(&mut self, cwd: PathBuf, window: &mut Window, cx: &mut App) {
         self.cwd.update(cx, |editor, cx| {
             if editor.is_empty(cx) {
@@ -837,12 +891,12 @@ impl ConfigureMode {
                 program: self.program.read(cx).text(cx),
                 cwd,
                 args: Default::default(),
-                env: Default::default(),
+                env: self.default_env.clone(),
             };
         }
         let command = self.program.read(cx).text(cx);
         let mut args = shlex::split(&command).into_iter().flatten().peekable();
-        let mut env = FxHashMap::default();
+        let mut env = self.default_env.clone();
         while args.peek().is_some_and(|arg| arg.contains('=')) {
             let arg = args.next().unwrap();
             let (lhs, rhs) = arg.split_once('=').unwrap();
@@ -852,7 +906,7 @@ impl ConfigureMode {
         let program = if let Some(program) = args.next() {
             program
         } else {
-            env = FxHashMap::default();
+            env = self.default_env.clone();
             command
         };
 
@@ -1283,7 +1337,7 @@ impl PickerDelegate for DebugDelegate {
         };
 
         let args = args.collect::<Vec<_>>();
-        let task = task::TaskTemplate {
+        let mut task = task::TaskTemplate {
             label: "one-off".to_owned(), // TODO: rename using command as label
             env,
             command: program,
@@ -1301,21 +1355,28 @@ impl PickerDelegate for DebugDelegate {
         let file = location.buffer.read(cx).file();
         let language = location.buffer.read(cx).language();
         let language_name = language.as_ref().map(|l| l.name());
-        let Some(adapter): Option<DebugAdapterName> =
-            language::language_settings::language_settings(language_name, file, cx)
-                .debuggers
-                .first()
-                .map(SharedString::from)
-                .map(Into::into)
-                .or_else(|| {
-                    language.and_then(|l| {
-                        l.config()
-                            .debuggers
-                            .first()
-                            .map(SharedString::from)
-                            .map(Into::into)
-                    })
+        let language_settings = language_settings(language_name, file, cx);
+        // Variables typed into the prompt take precedence over the language's defaults.
+        for (name, value) in &language_settings.debug.env {
+            task.env
+                .entry(name.clone())
+                .or_insert_with(|| value.clone());
+        }
+        task.cwd = language_settings.debug.cwd.clone();
+        let Some(adapter): Option<DebugAdapterName> = language_settings
+            .debuggers
+            .first()
+            .map(SharedString::from)
+            .map(Into::into)
+            .or_else(|| {
+                language.and_then(|l| {
+                    l.config()
+                        .debuggers
+                        .first()
+                        .map(SharedString::from)
+                        .map(Into::into)
                 })
+            })
         else {
             return;
         };
@@ -4,8 +4,8 @@ use super::{
 };
 use alacritty_terminal::vte::ansi;
 use anyhow::Result;
-use collections::HashMap;
-use dap::{CompletionItem, CompletionItemType, OutputEvent};
+use collections::{HashMap, HashSet};
+use dap::{CompletionItem, CompletionItemType, OutputEvent, OutputEventCategory};
 use editor::{Bias, CompletionProvider, Editor, EditorElement, EditorStyle, ExcerptId};
 use fuzzy::StringMatchCandidate;
 use gpui::{
@@ -24,7 +24,7 @@ use settings::Settings;
 use std::fmt::Write;
 use std::{cell::RefCell, ops::Range, rc::Rc, usize};
 use theme::{Theme, ThemeSettings};
-use ui::{ContextMenu, Divider, PopoverMenu, SplitButton, Tooltip, prelude::*};
+use ui::{ContextMenu, Divider, PopoverMenu, SplitButton, TintColor, Tooltip, prelude::*};
 use util::ResultExt;
 
 actions!(
@@ -35,6 +35,39 @@ actions!(
     ]
 );
 
+/// The groups of output events that can be shown or hidden in the console.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub(crate) enum OutputCategoryFilter {
+    Console,
+    Stdout,
+    Stderr,
+}
+
+impl OutputCategoryFilter {
+    const ALL: [Self; 3] = [Self::Console, Self::Stdout, Self::Stderr];
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::Console => "Console",
+            Self::Stdout => "Stdout",
+            Self::Stderr => "Stderr",
+        }
+    }
+
+    fn for_event(event: &OutputEvent) -> Option<Self> {
+        match event.category.as_ref() {
+            // Per the DAP spec, output without a category is console output.
+            None | Some(OutputEventCategory::Console | OutputEventCategory::Important) => {
+                Some(Self::Console)
+            }
+            Some(OutputEventCategory::Stdout) => Some(Self::Stdout),
+            Some(OutputEventCategory::Stderr) => Some(Self::Stderr),
+            Some(OutputEventCategory::Telemetry) => None,
+            Some(_) => Some(Self::Console),
+        }
+    }
+}
+
 pub struct Console {
     console: Entity<Editor>,
     query_bar: Entity<Editor>,
@@ -47,6 +80,8 @@ pub struct Console {
     focus_handle: FocusHandle,
     history: SearchHistory,
     cursor: SearchHistoryCursor,
+    visible_categories: HashSet<OutputCategoryFilter>,
+    errors_only: bool,
 }
 
 impl Console {
@@ -57,26 +92,7 @@ impl Console {
         window: &mut Window,
         cx: &mut Context<Self>,
     ) -> Self {
-        let console = cx.new(|cx| {
-            let mut editor = Editor::multi_line(window, cx);
-            editor.move_to_end(&editor::actions::MoveToEnd, window, cx);
-            editor.set_read_only(true);
-            editor.disable_scrollbars_and_minimap(window, cx);
-            editor.set_show_gutter(false, cx);
-            editor.set_show_runnables(false, cx);
-            editor.set_show_breakpoints(false, cx);
-            editor.set_show_code_actions(false, cx);
-            editor.set_show_line_numbers(false, cx);
-            editor.set_show_git_diff_gutter(false, cx);
-            editor.set_autoindent(false);
-            editor.set_input_enabled(false);
-            editor.set_use_autoclose(false);
-            editor.set_show_wrap_guides(false, cx);
-            editor.set_show_indent_guides(false, cx);
-            editor.set_use_modal_editing(false);
-            editor.set_soft_wrap_mode(language::language_settings::SoftWrap::EditorWidth, cx);
-            editor
-        });
+        let console = Self::new_console_editor(window, cx);
         let focus_handle = cx.focus_handle();
 
         let this = cx.weak_entity();
@@ -116,9 +132,34 @@ impl Console {
                 project::search_history::QueryInsertionBehavior::ReplacePreviousIfContains,
             ),
             cursor: Default::default(),
+            visible_categories: OutputCategoryFilter::ALL.into_iter().collect(),
+            errors_only: false,
         }
     }
 
+    fn new_console_editor(window: &mut Window, cx: &mut Context<Self>) -> Entity<Editor> {
+        cx.new(|cx| {
+            let mut editor = Editor::multi_line(window, cx);
+            editor.move_to_end(&editor::actions::MoveToEnd, window, cx);
+            editor.set_read_only(true);
+            editor.disable_scrollbars_and_minimap(window, cx);
+            editor.set_show_gutter(false, cx);
+            editor.set_show_runnables(false, cx);
+            editor.set_show_breakpoints(false, cx);
+            editor.set_show_code_actions(false, cx);
+            editor.set_show_line_numbers(false, cx);
+            editor.set_show_git_diff_gutter(false, cx);
+            editor.set_autoindent(false);
+            editor.set_input_enabled(false);
+            editor.set_use_autoclose(false);
+            editor.set_show_wrap_guides(false, cx);
+            editor.set_show_indent_guides(false, cx);
+            editor.set_use_modal_editing(false);
+            editor.set_soft_wrap_mode(language::language_settings::SoftWrap::EditorWidth, cx);
+            editor
+        })
+    }
+
     #[cfg(test)]
     pub(crate) fn editor(&self) -> &Entity<Editor> {
         &self.console
@@ -144,6 +185,49 @@ impl Console {
         self.session.read(cx).has_new_output(self.last_token)
     }
 
+    fn is_output_visible(&self, event: &OutputEvent) -> bool {
+        let Some(category) = OutputCategoryFilter::for_event(event) else {
+            return false;
+        };
+        if self.errors_only
+            && !matches!(
+                event.category,
+                Some(OutputEventCategory::Stderr | OutputEventCategory::Important)
+            )
+        {
+            return false;
+        }
+        self.visible_categories.contains(&category)
+    }
+
+    pub(crate) fn toggle_category(
+        &mut self,
+        category: OutputCategoryFilter,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if !self.visible_categories.remove(&category) {
+            self.visible_categories.insert(category);
+        }
+        self.reload_output(window, cx);
+    }
+
+    pub(crate) fn toggle_errors_only(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.errors_only = !self.errors_only;
+        self.reload_output(window, cx);
+    }
+
+    /// Re-renders all output received so far with the current filters.
+    fn reload_output(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        // The ANSI color highlights are keyed by buffer offset, so starting from a fresh
+        // editor is the only way to drop them along with the text they applied to.
+        self.console = Self::new_console_editor(window, cx);
+        self.update_output_task = None;
+        self.last_token = OutputToken(0);
+        self.update_output(window, cx);
+        cx.notify();
+    }
+
     fn add_messages(
         &mut self,
         events: Vec<OutputEvent>,
@@ -374,6 +458,33 @@ impl Console {
             .anchor(Corner::TopRight)
     }
 
+    fn render_filter_bar(&self, cx: &Context<Self>) -> impl IntoElement {
+        h_flex()
+            .p_1()
+            .gap_1()
+            .children(OutputCategoryFilter::ALL.map(|category| {
+                Button::new(category.label(), category.label())
+                    .label_size(LabelSize::Small)
+                    .toggle_state(self.visible_categories.contains(&category))
+                    .selected_style(ButtonStyle::Tinted(TintColor::Accent))
+                    .on_click(cx.listener(move |console, _, window, cx| {
+                        console.toggle_category(category, window, cx)
+                    }))
+            }))
+            .child(Divider::vertical())
+            .child(
+                IconButton::new("console-filter-errors-only", IconName::XCircle)
+                    .icon_size(IconSize::Small)
+                    .toggle_state(self.errors_only)
+                    .tooltip(Tooltip::text("Show Only Errors"))
+                    .on_click(
+                        cx.listener(|console, _, window, cx| {
+                            console.toggle_errors_only(window, cx)
+                        }),
+                    ),
+            )
+    }
+
     fn render_console(&self, cx: &Context<Self>) -> impl IntoElement {
         EditorElement::new(&self.console, Self::editor_style(&self.console, cx))
     }
@@ -422,10 +533,11 @@ impl Console {
                         if last_processed_token == this.last_token {
                             return None;
                         }
-                        Some((
-                            last_processed_token,
-                            this.add_messages(output.cloned().collect(), window, cx),
-                        ))
+                        let output = output
+                            .filter(|event| this.is_output_visible(event))
+                            .cloned()
+                            .collect();
+                        Some((last_processed_token, this.add_messages(output, window, cx)))
                     })
                     .ok()
                     .flatten()
@@ -460,6 +572,8 @@ impl Render for Console {
             .size_full()
             .border_2()
             .bg(cx.theme().colors().editor_background)
+            .child(self.render_filter_bar(cx))
+            .child(Divider::horizontal())
             .child(self.render_console(cx))
             .when(self.is_running(cx), |this| {
                 this.child(Divider::horizontal()).child(
@@ -1,4 +1,5 @@
 use crate::{
+    session::running::console::OutputCategoryFilter,
     tests::{active_debug_session_panel, start_debug_session},
     *,
 };
@@ -356,6 +357,122 @@ async fn test_escape_code_processing(executor: BackgroundExecutor, cx: &mut Test
         .unwrap();
 }
 
+#[gpui::test]
+async fn test_output_category_filters(executor: BackgroundExecutor, cx: &mut TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(executor.clone());
+
+    fs.insert_tree(
+        path!("/project"),
+        json!({
+            "main.rs": "First line\nSecond line\nThird line\nFourth line",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, [path!("/project").as_ref()], cx).await;
+    let workspace = init_test_workspace(&project, cx).await;
+    let cx = &mut VisualTestContext::from_window(*workspace, cx);
+    workspace
+        .update(cx, |workspace, window, cx| {
+            workspace.focus_panel::<DebugPanel>(window, cx);
+        })
+        .unwrap();
+
+    let session = start_debug_session(&workspace, cx, |_| {}).unwrap();
+    let client = session.read_with(cx, |session, _| session.adapter_client().unwrap());
+
+    client.on_request::<StackTrace, _>(move |_, _| {
+        Ok(dap::StackTraceResponse {
+            stack_frames: Vec::default(),
+            total_frames: None,
+        })
+    });
+
+    for (category, output) in [
+        (None, "Console output"),
+        (Some(dap::OutputEventCategory::Stdout), "Stdout output"),
+        (Some(dap::OutputEventCategory::Stderr), "Stderr output"),
+        (
+            Some(dap::OutputEventCategory::Important),
+            "Important output",
+        ),
+    ] {
+        client
+            .fake_event(dap::messages::Events::Output(dap::OutputEvent {
+                category,
+                output: output.to_string(),
+                data: None,
+                variables_reference: None,
+                source: None,
+                line: None,
+                column: None,
+                group: None,
+                location_reference: None,
+            }))
+            .await;
+    }
+
+    client
+        .fake_event(dap::messages::Events::Stopped(dap::StoppedEvent {
+            reason: dap::StoppedEventReason::Pause,
+            description: None,
+            thread_id: Some(1),
+            preserve_focus_hint: None,
+            text: None,
+            all_threads_stopped: None,
+            hit_breakpoint_ids: None,
+        }))
+        .await;
+
+    cx.run_until_parked();
+
+    let console = active_debug_session_panel(workspace, cx).update_in(cx, |item, window, cx| {
+        cx.focus_self(window);
+        item.running_state().read(cx).console().clone()
+    });
+
+    cx.run_until_parked();
+
+    let console_text = |cx: &mut VisualTestContext| {
+        console.read_with(cx, |console, cx| console.editor().read(cx).text(cx))
+    };
+
+    assert_eq!(
+        "Console output\nStdout output\nStderr output\nImportant output\n",
+        console_text(cx)
+    );
+
+    console.update_in(cx, |console, window, cx| {
+        console.toggle_category(OutputCategoryFilter::Stdout, window, cx);
+    });
+    cx.run_until_parked();
+
+    assert_eq!(
+        "Console output\nStderr output\nImportant output\n",
+        console_text(cx)
+    );
+
+    console.update_in(cx, |console, window, cx| {
+        console.toggle_errors_only(window, cx);
+    });
+    cx.run_until_parked();
+
+    assert_eq!("Stderr output\nImportant output\n", console_text(cx));
+
+    console.update_in(cx, |console, window, cx| {
+        console.toggle_category(OutputCategoryFilter::Stdout, window, cx);
+        console.toggle_errors_only(window, cx);
+    });
+    cx.run_until_parked();
+
+    assert_eq!(
+        "Console output\nStdout output\nStderr output\nImportant output\n",
+        console_text(cx)
+    );
+}
+
 // #[gpui::test]
 // async fn test_grouped_output(executor: BackgroundExecutor, cx: &mut TestAppContext) {
 //     init_test(cx);
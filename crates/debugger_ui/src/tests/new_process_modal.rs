@@ -1,6 +1,7 @@
 use dap::DapRegistry;
 use editor::Editor;
 use gpui::{BackgroundExecutor, TestAppContext, VisualTestContext};
+use language::language_settings::LanguageDebugSettings;
 use project::{FakeFs, Fs as _, Project};
 use serde_json::json;
 use std::sync::Arc;
@@ -10,7 +11,7 @@ use text::Point;
 use util::path;
 
 use crate::NewProcessMode;
-use crate::new_process_modal::NewProcessModal;
+use crate::new_process_modal::{ConfigureMode, NewProcessModal};
 use crate::tests::{init_test, init_test_workspace};
 
 #[gpui::test]
@@ -44,6 +45,7 @@ async fn test_debug_session_substitutes_variables_and_relativizes_paths(
         cwd: None,
         task_variables: test_variables,
         project_env: Default::default(),
+        shell: None,
     };
 
     let home_dir = paths::home_dir();
@@ -276,6 +278,81 @@ async fn test_save_debug_scenario_to_file(executor: BackgroundExecutor, cx: &mut
     pretty_assertions::assert_eq!(expected_content, debug_json_content);
 }
 
+#[cfg(not(target_os = "windows"))]
+#[gpui::test]
+async fn test_language_debug_defaults(executor: BackgroundExecutor, cx: &mut TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(executor.clone());
+    fs.insert_tree(path!("/project"), json!({ "main.rs": "fn main() {}" }))
+        .await;
+
+    let project = Project::test(fs, [path!("/project").as_ref()], cx).await;
+    let workspace = init_test_workspace(&project, cx).await;
+    let cx = &mut VisualTestContext::from_window(*workspace, cx);
+
+    let configure_mode = cx.update(|window, cx| ConfigureMode::new(window, cx));
+    let defaults = LanguageDebugSettings {
+        program: Some("cargo run --bin app".to_owned()),
+        env: [
+            ("RUST_LOG".to_owned(), "debug".to_owned()),
+            ("MODE".to_owned(), "default".to_owned()),
+        ]
+        .into_iter()
+        .collect(),
+        cwd: Some("$ZED_WORKTREE_ROOT".to_owned()),
+    };
+    configure_mode.update_in(cx, |configure_mode, window, cx| {
+        configure_mode.load_language_defaults(&defaults, window, cx);
+    });
+
+    let request = cx.update(|_, cx| configure_mode.read(cx).debug_request(cx));
+    assert_eq!(request.program, "cargo");
+    assert_eq!(request.args, ["run", "--bin", "app"]);
+    assert_eq!(request.cwd, Some("$ZED_WORKTREE_ROOT".into()));
+    assert_eq!(
+        request.env.get("RUST_LOG").map(String::as_str),
+        Some("debug")
+    );
+    assert_eq!(request.env.get("MODE").map(String::as_str), Some("default"));
+
+    // Defaults don't replace a program or directory that was already entered.
+    configure_mode.update_in(cx, |configure_mode, window, cx| {
+        configure_mode.load_language_defaults(
+            &LanguageDebugSettings {
+                program: Some("./other".to_owned()),
+                cwd: Some("/other".to_owned()),
+                ..defaults.clone()
+            },
+            window,
+            cx,
+        );
+    });
+    let request = cx.update(|_, cx| configure_mode.read(cx).debug_request(cx));
+    assert_eq!(request.program, "cargo");
+    assert_eq!(request.cwd, Some("$ZED_WORKTREE_ROOT".into()));
+
+    // Variables given before the program take precedence over the default environment.
+    let configure_mode = cx.update(|window, cx| ConfigureMode::new(window, cx));
+    configure_mode.update_in(cx, |configure_mode, window, cx| {
+        configure_mode.load_language_defaults(
+            &LanguageDebugSettings {
+                program: Some("MODE=inline ./main".to_owned()),
+                ..defaults.clone()
+            },
+            window,
+            cx,
+        );
+    });
+    let request = cx.update(|_, cx| configure_mode.read(cx).debug_request(cx));
+    assert_eq!(request.program, "./main");
+    assert_eq!(
+        request.env.get("RUST_LOG").map(String::as_str),
+        Some("debug")
+    );
+    assert_eq!(request.env.get("MODE").map(String::as_str), Some("inline"));
+}
+
 #[gpui::test]
 async fn test_debug_modal_subtitles_with_multiple_worktrees(
     executor: BackgroundExecutor,
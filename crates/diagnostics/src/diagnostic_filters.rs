@@ -0,0 +1,121 @@
+use collections::BTreeSet;
+use language::Diagnostic;
+use lsp::DiagnosticSeverity;
+use serde::{Deserialize, Serialize};
+use workspace::Workspace;
+
+const DIAGNOSTIC_FILTERS_KEY: &str = "ProjectDiagnosticsFilters";
+
+/// How the files in the project diagnostics view are ordered.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) enum DiagnosticsGrouping {
+    /// By path, as in the project panel.
+    #[default]
+    File,
+    /// Files with errors first, then files with warnings, and so on.
+    Severity,
+    /// By the name of the language server or tool that reported the file's diagnostics.
+    Source,
+}
+
+impl DiagnosticsGrouping {
+    pub(crate) const ALL: [Self; 3] = [Self::File, Self::Severity, Self::Source];
+
+    pub(crate) fn label(self) -> &'static str {
+        match self {
+            Self::File => "File",
+            Self::Severity => "Severity",
+            Self::Source => "Source",
+        }
+    }
+}
+
+/// The grouping and filters chosen in the project diagnostics view, which are remembered for
+/// each workspace.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct DiagnosticFilters {
+    pub grouping: DiagnosticsGrouping,
+    /// The sources whose diagnostics are hidden, such as `eslint`.
+    pub hidden_sources: BTreeSet<String>,
+    /// Text that a diagnostic's message or source must contain for it to be shown.
+    pub query: String,
+}
+
+impl DiagnosticFilters {
+    pub(crate) fn is_active(&self) -> bool {
+        !self.hidden_sources.is_empty() || !self.query.trim().is_empty()
+    }
+
+    /// Whether a group of related diagnostics is shown. A group is judged by its primary
+    /// diagnostic's source, but matches the query if any of its messages do.
+    pub(crate) fn matches_group(&self, group: &[&Diagnostic]) -> bool {
+        let Some(primary) = group
+            .iter()
+            .find(|diagnostic| diagnostic.is_primary)
+            .or(group.first())
+        else {
+            return false;
+        };
+        if primary
+            .source
+            .as_ref()
+            .is_some_and(|source| self.hidden_sources.contains(source))
+        {
+            return false;
+        }
+
+        let query = self.query.trim().to_lowercase();
+        query.is_empty()
+            || group.iter().any(|diagnostic| {
+                diagnostic.message.to_lowercase().contains(&query)
+                    || diagnostic
+                        .source
+                        .as_ref()
+                        .is_some_and(|source| source.to_lowercase().contains(&query))
+            })
+    }
+
+    /// The position of a file's group among the others, given the primary diagnostics shown for
+    /// it, or `None` when files are grouped by path.
+    pub(crate) fn group_rank(&self, primary_diagnostics: &[&Diagnostic]) -> Option<u32> {
+        match self.grouping {
+            DiagnosticsGrouping::File => None,
+            DiagnosticsGrouping::Severity => primary_diagnostics
+                .iter()
+                .map(|diagnostic| severity_rank(diagnostic.severity))
+                .min(),
+            DiagnosticsGrouping::Source => primary_diagnostics
+                .iter()
+                .map(|diagnostic| diagnostic.source.as_deref().map_or(u32::MAX, source_rank))
+                .min(),
+        }
+    }
+}
+
+fn severity_rank(severity: DiagnosticSeverity) -> u32 {
+    match severity {
+        DiagnosticSeverity::ERROR => 0,
+        DiagnosticSeverity::WARNING => 1,
+        DiagnosticSeverity::INFORMATION => 2,
+        _ => 3,
+    }
+}
+
+/// Ranks sources alphabetically by their first four bytes, which is enough to tell the sources in
+/// a project apart while fitting beside the worktree in a path key's sort prefix.
+fn source_rank(source: &str) -> u32 {
+    let mut bytes = [0; 4];
+    for (byte, source_byte) in bytes.iter_mut().zip(source.to_ascii_lowercase().bytes()) {
+        *byte = source_byte;
+    }
+    u32::from_be_bytes(bytes).min(u32::MAX - 1)
+}
+
+pub(crate) fn serialization_key(workspace: &Workspace) -> Option<String> {
+    workspace
+        .database_id()
+        .map(|id| i64::from(id).to_string())
+        .or(workspace.session_id())
+        .map(|id| format!("{}-{:?}", DIAGNOSTIC_FILTERS_KEY, id))
+}
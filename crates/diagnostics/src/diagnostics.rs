@@ -2,6 +2,7 @@ pub mod items;
 mod toolbar_controls;
 
 mod buffer_diagnostics;
+mod diagnostic_filters;
 mod diagnostic_renderer;
 
 #[cfg(test)]
@@ -10,6 +11,8 @@ mod diagnostics_tests;
 use anyhow::Result;
 use buffer_diagnostics::BufferDiagnosticsEditor;
 use collections::{BTreeSet, HashMap};
+use db::kvp::KEY_VALUE_STORE;
+use diagnostic_filters::{DiagnosticFilters, DiagnosticsGrouping};
 use diagnostic_renderer::DiagnosticBlock;
 use editor::{
     Editor, EditorEvent, ExcerptRange, MultiBuffer, PathKey,
@@ -41,7 +44,7 @@ use text::{BufferId, OffsetRangeExt};
 use theme::ActiveTheme;
 use toolbar_controls::DiagnosticsToolbarEditor;
 pub use toolbar_controls::ToolbarControls;
-use ui::{Icon, IconName, Label, h_flex, prelude::*};
+use ui::{Checkbox, Icon, IconName, Label, ToggleState, Tooltip, h_flex, prelude::*};
 use util::ResultExt;
 use workspace::{
     ItemNavHistory, ToolbarItemLocation, Workspace,
@@ -82,9 +85,18 @@ pub(crate) struct ProjectDiagnosticsEditor {
     multibuffer: Entity<MultiBuffer>,
     paths_to_update: BTreeSet<ProjectPath>,
     include_warnings: bool,
+    filters: DiagnosticFilters,
+    filters_serialization_key: Option<String>,
+    /// Every source that has reported diagnostics while the view was open.
+    sources: BTreeSet<String>,
+    /// The path each buffer's excerpts were inserted under, which changes with its group.
+    path_keys: HashMap<BufferId, PathKey>,
+    query_editor: Entity<Editor>,
     update_excerpts_task: Option<Task<Result<()>>>,
     diagnostic_summary_update: Task<()>,
+    pending_serialization: Task<()>,
     _subscription: Subscription,
+    _query_subscription: Subscription,
 }
 
 impl EventEmitter<EditorEvent> for ProjectDiagnosticsEditor {}
@@ -100,6 +112,7 @@ impl Render for ProjectDiagnosticsEditor {
             0
         };
 
+        let has_problems = warning_count + self.summary.error_count > 0;
         let child =
             if warning_count + self.summary.error_count == 0 && self.editor.read(cx).is_empty(cx) {
                 let label = if self.summary.warning_count == 0 {
@@ -135,16 +148,24 @@ impl Render for ProjectDiagnosticsEditor {
                             ),
                         )
                     })
+            } else if self.filters.is_active() && self.multibuffer.read(cx).is_empty() {
+                v_flex()
+                    .flex_1()
+                    .justify_center()
+                    .items_center()
+                    .bg(cx.theme().colors().editor_background)
+                    .child(Label::new("No problems match the filters").color(Color::Muted))
             } else {
-                div().size_full().child(self.editor.clone())
+                v_flex().flex_1().min_h_0().child(self.editor.clone())
             };
 
-        div()
+        v_flex()
             .key_context("Diagnostics")
             .track_focus(&self.focus_handle(cx))
             .size_full()
             .on_action(cx.listener(Self::toggle_warnings))
             .on_action(cx.listener(Self::toggle_diagnostics_refresh))
+            .when(has_problems, |this| this.child(self.render_filter_bar(cx)))
             .child(child)
     }
 }
@@ -263,6 +284,55 @@ impl ProjectDiagnosticsEditor {
         })
         .detach();
 
+        let query_editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_placeholder_text("Filter diagnostics…", window, cx);
+            editor
+        });
+        let query_subscription = cx.subscribe_in(
+            &query_editor,
+            window,
+            |this, query_editor, event: &EditorEvent, window, cx| {
+                if let EditorEvent::BufferEdited = event {
+                    let filters = DiagnosticFilters {
+                        query: query_editor.read(cx).text(cx),
+                        ..this.filters.clone()
+                    };
+                    this.set_filters(filters, window, cx);
+                }
+            },
+        );
+
+        let workspace_handle = workspace.clone();
+        cx.spawn_in(window, async move |this, cx| {
+            let Some(serialization_key) = workspace_handle
+                .read_with(cx, |workspace, _| {
+                    diagnostic_filters::serialization_key(workspace)
+                })
+                .ok()
+                .flatten()
+            else {
+                return;
+            };
+            let serialized_filters = cx
+                .background_spawn({
+                    let serialization_key = serialization_key.clone();
+                    async move { KEY_VALUE_STORE.read_kvp(&serialization_key) }
+                })
+                .await
+                .log_err()
+                .flatten()
+                .and_then(|filters| serde_json::from_str::<DiagnosticFilters>(&filters).log_err());
+            this.update_in(cx, |this, window, cx| {
+                this.filters_serialization_key = Some(serialization_key);
+                if let Some(filters) = serialized_filters {
+                    this.set_filters(filters, window, cx);
+                }
+            })
+            .ok();
+        })
+        .detach();
+
         let project = project_handle.read(cx);
         let mut this = Self {
             project: project_handle.clone(),
@@ -270,6 +340,11 @@ impl ProjectDiagnosticsEditor {
             diagnostics: Default::default(),
             blocks: Default::default(),
             include_warnings,
+            filters: DiagnosticFilters::default(),
+            filters_serialization_key: None,
+            sources: BTreeSet::default(),
+            path_keys: HashMap::default(),
+            query_editor,
             workspace,
             multibuffer: excerpts,
             focus_handle,
@@ -277,7 +352,9 @@ impl ProjectDiagnosticsEditor {
             paths_to_update: Default::default(),
             update_excerpts_task: None,
             diagnostic_summary_update: Task::ready(()),
+            pending_serialization: Task::ready(()),
             _subscription: project_event_subscription,
+            _query_subscription: query_subscription,
         };
         this.update_all_excerpts(window, cx);
         this
@@ -358,6 +435,129 @@ impl ProjectDiagnosticsEditor {
         cx.set_global(IncludeWarnings(!self.include_warnings));
     }
 
+    fn set_filters(
+        &mut self,
+        filters: DiagnosticFilters,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if filters == self.filters {
+            return;
+        }
+        self.filters = filters;
+        if self.query_editor.read(cx).text(cx) != self.filters.query {
+            let query = self.filters.query.clone();
+            self.query_editor.update(cx, |editor, cx| {
+                editor.set_text(query, window, cx);
+            });
+        }
+        self.serialize_filters(cx);
+        self.diagnostics.clear();
+        self.update_all_excerpts(window, cx);
+        cx.notify();
+    }
+
+    fn set_grouping(
+        &mut self,
+        grouping: DiagnosticsGrouping,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let filters = DiagnosticFilters {
+            grouping,
+            ..self.filters.clone()
+        };
+        self.set_filters(filters, window, cx);
+    }
+
+    fn toggle_source(&mut self, source: &str, window: &mut Window, cx: &mut Context<Self>) {
+        let mut filters = self.filters.clone();
+        if !filters.hidden_sources.remove(source) {
+            filters.hidden_sources.insert(source.to_string());
+        }
+        self.set_filters(filters, window, cx);
+    }
+
+    fn serialize_filters(&mut self, cx: &mut Context<Self>) {
+        let Some(serialization_key) = self.filters_serialization_key.clone() else {
+            return;
+        };
+        let Some(filters) = serde_json::to_string(&self.filters).log_err() else {
+            return;
+        };
+        self.pending_serialization = cx.background_spawn(async move {
+            KEY_VALUE_STORE
+                .write_kvp(serialization_key, filters)
+                .await
+                .log_err();
+        });
+    }
+
+    fn render_filter_bar(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        h_flex()
+            .px_2()
+            .py_1()
+            .gap_3()
+            .flex_wrap()
+            .border_b_1()
+            .border_color(cx.theme().colors().border_variant)
+            .bg(cx.theme().colors().editor_background)
+            .child(
+                h_flex()
+                    .gap_0p5()
+                    .child(
+                        Label::new("Group by")
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    )
+                    .children(DiagnosticsGrouping::ALL.into_iter().map(|grouping| {
+                        Button::new(("grouping", grouping as usize), grouping.label())
+                            .label_size(LabelSize::Small)
+                            .toggle_state(self.filters.grouping == grouping)
+                            .on_click(cx.listener(move |this, _, window, cx| {
+                                this.set_grouping(grouping, window, cx)
+                            }))
+                    })),
+            )
+            .when(!self.sources.is_empty(), |this| {
+                this.child(
+                    h_flex()
+                        .gap_2()
+                        .children(self.sources.iter().enumerate().map(|(index, source)| {
+                            let is_shown = !self.filters.hidden_sources.contains(source);
+                            let source = source.clone();
+                            let this = cx.weak_entity();
+                            Checkbox::new(("source", index), ToggleState::from(is_shown))
+                                .label(source.clone())
+                                .tooltip(Tooltip::text(format!("Show diagnostics from {source}")))
+                                .on_click(move |_, window, cx| {
+                                    this.update(cx, |this, cx| {
+                                        this.toggle_source(&source, window, cx)
+                                    })
+                                    .ok();
+                                })
+                        })),
+                )
+            })
+            .child(
+                h_flex()
+                    .flex_1()
+                    .min_w_32()
+                    .px_1p5()
+                    .py_0p5()
+                    .gap_1()
+                    .rounded_sm()
+                    .border_1()
+                    .border_color(cx.theme().colors().border)
+                    .child(
+                        Icon::new(IconName::MagnifyingGlass)
+                            .size(IconSize::Small)
+                            .color(Color::Muted),
+                    )
+                    .child(self.query_editor.clone()),
+            )
+    }
+
     fn toggle_diagnostics_refresh(
         &mut self,
         _: &ToggleDiagnosticsRefresh,
@@ -443,6 +643,7 @@ impl ProjectDiagnosticsEditor {
         } else {
             lsp::DiagnosticSeverity::ERROR
         };
+        let filters = self.filters.clone();
 
         cx.spawn_in(window, async move |this, cx| {
             let diagnostics = buffer_snapshot
@@ -453,6 +654,11 @@ impl ProjectDiagnosticsEditor {
                 .collect::<Vec<_>>();
 
             let unchanged = this.update(cx, |this, _| {
+                this.sources.extend(
+                    diagnostics
+                        .iter()
+                        .filter_map(|entry| entry.diagnostic.source.clone()),
+                );
                 if this.diagnostics.get(&buffer_id).is_some_and(|existing| {
                     this.diagnostics_are_unchanged(existing, &diagnostics, &buffer_snapshot)
                 }) {
@@ -482,12 +688,27 @@ impl ProjectDiagnosticsEditor {
                     })
             }
             let mut blocks: Vec<DiagnosticBlock> = Vec::new();
+            let mut shown_primary_diagnostics = Vec::new();
 
             for (_, group) in grouped {
                 let group_severity = group.iter().map(|d| d.diagnostic.severity).min();
                 if group_severity.is_none_or(|s| s > max_severity) {
                     continue;
                 }
+                let group_diagnostics = group
+                    .iter()
+                    .map(|entry| entry.diagnostic)
+                    .collect::<Vec<_>>();
+                if !filters.matches_group(&group_diagnostics) {
+                    continue;
+                }
+                shown_primary_diagnostics.extend(
+                    group_diagnostics
+                        .iter()
+                        .find(|diagnostic| diagnostic.is_primary)
+                        .or(group_diagnostics.first())
+                        .copied(),
+                );
                 let more = cx.update(|_, cx| {
                     crate::diagnostic_renderer::DiagnosticRenderer::diagnostic_blocks_for_group(
                         group,
@@ -512,6 +733,8 @@ impl ProjectDiagnosticsEditor {
                 }
             }
 
+            let group_rank = filters.group_rank(&shown_primary_diagnostics);
+
             let mut excerpt_ranges: Vec<ExcerptRange<Point>> = Vec::new();
             let context_lines = cx.update(|_, cx| multibuffer_context_lines(cx))?;
             for b in blocks.iter() {
@@ -552,9 +775,24 @@ impl ProjectDiagnosticsEditor {
                         });
                     })
                 }
+                let path_key = match (group_rank, buffer.read(cx).file()) {
+                    // The worktree keeps files with the same path in different worktrees apart.
+                    (Some(group_rank), Some(file)) => PathKey::with_sort_prefix(
+                        (u64::from(group_rank) << 32)
+                            | (file.worktree_id(cx).to_proto() & u64::from(u32::MAX)),
+                        file.path().clone(),
+                    ),
+                    _ => PathKey::for_buffer(&buffer, cx),
+                };
+                let previous_path_key = this.path_keys.insert(buffer_id, path_key.clone());
                 let (anchor_ranges, _) = this.multibuffer.update(cx, |multi_buffer, cx| {
+                    if let Some(previous_path_key) = previous_path_key
+                        && previous_path_key != path_key
+                    {
+                        multi_buffer.remove_excerpts_for_path(previous_path_key, cx);
+                    }
                     multi_buffer.set_excerpt_ranges_for_path(
-                        PathKey::for_buffer(&buffer, cx),
+                        path_key,
                         buffer.clone(),
                         &buffer_snapshot,
                         excerpt_ranges,
@@ -1440,6 +1440,112 @@ async fn test_diagnostics_with_code(cx: &mut TestAppContext) {
     );
 }
 
+#[gpui::test]
+async fn test_diagnostics_grouping_and_filters(cx: &mut TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/root"),
+        json!({
+            "a.js": "let x = 1;\nlet y = 2;\n",
+            "b.js": "foo();\n",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs.clone(), [path!("/root").as_ref()], cx).await;
+    let lsp_store = project.read_with(cx, |project, _| project.lsp_store());
+    let window = cx.add_window(|window, cx| Workspace::test_new(project.clone(), window, cx));
+    let cx = &mut VisualTestContext::from_window(*window, cx);
+    let workspace = window.root(cx).unwrap();
+
+    lsp_store.update(cx, |lsp_store, cx| {
+        for (path, diagnostic) in [
+            (
+                path!("/root/a.js"),
+                lsp::Diagnostic {
+                    range: lsp::Range::new(lsp::Position::new(0, 4), lsp::Position::new(0, 5)),
+                    severity: Some(lsp::DiagnosticSeverity::WARNING),
+                    source: Some("eslint".to_string()),
+                    message: "'x' is never used".to_string(),
+                    ..Default::default()
+                },
+            ),
+            (
+                path!("/root/b.js"),
+                lsp::Diagnostic {
+                    range: lsp::Range::new(lsp::Position::new(0, 0), lsp::Position::new(0, 3)),
+                    severity: Some(lsp::DiagnosticSeverity::ERROR),
+                    source: Some("typescript".to_string()),
+                    message: "cannot find name 'foo'".to_string(),
+                    ..Default::default()
+                },
+            ),
+        ] {
+            lsp_store
+                .update_diagnostics(
+                    LanguageServerId(0),
+                    lsp::PublishDiagnosticsParams {
+                        uri: lsp::Uri::from_file_path(path).unwrap(),
+                        diagnostics: vec![diagnostic],
+                        version: None,
+                    },
+                    None,
+                    DiagnosticSourceKind::Pushed,
+                    &[],
+                    cx,
+                )
+                .unwrap();
+        }
+    });
+
+    let diagnostics = window.build_entity(cx, |window, cx| {
+        ProjectDiagnosticsEditor::new(true, project.clone(), workspace.downgrade(), window, cx)
+    });
+    let editor = diagnostics.update(cx, |diagnostics, _| diagnostics.editor.clone());
+    let file_headers = |cx: &mut VisualTestContext| {
+        cx.executor()
+            .advance_clock(DIAGNOSTICS_UPDATE_DELAY + Duration::from_millis(10));
+        cx.executor().run_until_parked();
+        editor_content_with_blocks(&editor, cx)
+            .lines()
+            .filter(|line| line.starts_with("§ ") && !line.starts_with("§ -"))
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(file_headers(cx), ["§ a.js", "§ b.js"]);
+    diagnostics.read_with(cx, |diagnostics, _| {
+        assert_eq!(
+            diagnostics.sources.iter().collect::<Vec<_>>(),
+            ["eslint", "typescript"]
+        );
+    });
+
+    // Files with errors come before files with only warnings.
+    diagnostics.update_in(cx, |diagnostics, window, cx| {
+        diagnostics.set_grouping(DiagnosticsGrouping::Severity, window, cx);
+    });
+    assert_eq!(file_headers(cx), ["§ b.js", "§ a.js"]);
+
+    diagnostics.update_in(cx, |diagnostics, window, cx| {
+        diagnostics.toggle_source("typescript", window, cx);
+    });
+    assert_eq!(file_headers(cx), ["§ a.js"]);
+
+    diagnostics.update_in(cx, |diagnostics, window, cx| {
+        diagnostics.toggle_source("typescript", window, cx);
+        diagnostics
+            .query_editor
+            .update(cx, |editor, cx| editor.set_text("FOO", window, cx));
+    });
+    assert_eq!(file_headers(cx), ["§ b.js"]);
+    diagnostics.read_with(cx, |diagnostics, _| {
+        assert_eq!(diagnostics.filters.query, "FOO");
+    });
+}
+
 #[gpui::test]
 async fn go_to_diagnostic_with_severity(cx: &mut TestAppContext) {
     init_test(cx);
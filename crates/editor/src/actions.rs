@@ -415,6 +415,9 @@ actions!(
         CopyPermalinkToLine,
         /// Cuts selected text to the clipboard.
         Cut,
+        /// Cycles soft wrap between off, the editor width and the preferred
+        /// line length.
+        CycleSoftWrap,
         /// Deletes the character after the cursor.
         Delete,
         /// Deletes the current line.
@@ -762,6 +765,8 @@ actions!(
         SwapSelectionEnds,
         /// Sets a mark at the current position.
         SetMark,
+        /// Toggles whether this editor is read-only.
+        ToggleReadOnly,
         /// Toggles relative line numbers display.
         ToggleRelativeLineNumbers,
         /// Toggles diff display for selected hunks.
@@ -769,10 +774,14 @@ actions!(
         ToggleSelectedDiffHunks,
         /// Toggles the selection menu.
         ToggleSelectionMenu,
+        /// Toggles snippet suggestions in completions for this editor.
+        ToggleSnippetSuggestions,
         /// Toggles soft wrap mode.
         ToggleSoftWrap,
         /// Toggles the tab bar display.
         ToggleTabBar,
+        /// Toggles word completions for this editor.
+        ToggleWordCompletions,
         /// Transposes characters around cursor.
         Transpose,
         /// Undoes the last edit.
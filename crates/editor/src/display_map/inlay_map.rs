@@ -1259,6 +1259,7 @@ mod tests {
                     padding_left: false,
                     padding_right: false,
                     tooltip: None,
+                    text_edits: Vec::new(),
                     kind: None,
                     resolve_state: ResolveState::Resolved,
                 },
@@ -1279,6 +1280,7 @@ mod tests {
                     padding_left: true,
                     padding_right: true,
                     tooltip: None,
+                    text_edits: Vec::new(),
                     kind: None,
                     resolve_state: ResolveState::Resolved,
                 },
@@ -1299,6 +1301,7 @@ mod tests {
                     padding_left: false,
                     padding_right: false,
                     tooltip: None,
+                    text_edits: Vec::new(),
                     kind: None,
                     resolve_state: ResolveState::Resolved,
                 },
@@ -1319,6 +1322,7 @@ mod tests {
                     padding_left: true,
                     padding_right: true,
                     tooltip: None,
+                    text_edits: Vec::new(),
                     kind: None,
                     resolve_state: ResolveState::Resolved,
                 },
@@ -1342,6 +1346,7 @@ mod tests {
                     padding_left: true,
                     padding_right: true,
                     tooltip: None,
+                    text_edits: Vec::new(),
                     kind: None,
                     resolve_state: ResolveState::Resolved,
                 },
@@ -34,6 +34,7 @@ mod mouse_context_menu;
 pub mod movement;
 mod persistence;
 mod proposed_changes_editor;
+mod rename_preview;
 mod rust_analyzer_ext;
 pub mod scroll;
 mod selections_collection;
@@ -68,6 +69,7 @@ pub use multi_buffer::{
 pub use proposed_changes_editor::{
     ProposedChangeLocation, ProposedChangesEditor, ProposedChangesEditorToolbar,
 };
+pub use rename_preview::RenamePreview;
 pub use text::Bias;
 
 use ::git::{
@@ -159,7 +161,10 @@ use selections_collection::{
     MutableSelectionsCollection, SelectionsCollection, resolve_selections,
 };
 use serde::{Deserialize, Serialize};
-use settings::{GitGutterSetting, Settings, SettingsLocation, SettingsStore, update_settings_file};
+use settings::{
+    GitGutterSetting, Settings, SettingsLocation, SettingsStore, SnippetSortOrder,
+    update_settings_file,
+};
 use smallvec::{SmallVec, smallvec};
 use snippet::Snippet;
 use std::{
@@ -929,6 +934,11 @@ pub struct Editor {
     inline_diagnostics_enabled: bool,
     diagnostics_enabled: bool,
     word_completions_enabled: bool,
+    /// Overrides `completions.words` for this editor, when toggled.
+    word_completions_override: Option<bool>,
+    /// Overrides whether `snippet_sort_order` hides snippets for this editor,
+    /// when toggled.
+    snippet_suggestions_override: Option<bool>,
     inline_diagnostics: Vec<(Anchor, InlineDiagnostic)>,
     soft_wrap_mode_override: Option<language_settings::SoftWrap>,
     hard_wrap: Option<usize>,
@@ -980,6 +990,9 @@ pub struct Editor {
     linked_editing_range_task: Option<Task<Option<()>>>,
     linked_edit_ranges: linked_editing_ranges::LinkedEditingRanges,
     pending_rename: Option<RenameState>,
+    /// The number of format and organize-imports requests that haven't
+    /// finished yet.
+    pending_formats: usize,
     searchable: bool,
     cursor_shape: CursorShape,
     current_line_highlight: Option<CurrentLineHighlight>,
@@ -2019,6 +2032,7 @@ impl Editor {
             document_highlights_task: None,
             linked_editing_range_task: None,
             pending_rename: None,
+            pending_formats: 0,
             searchable: !is_minimap,
             cursor_shape: EditorSettings::get_global(cx)
                 .cursor_shape
@@ -2042,6 +2056,8 @@ impl Editor {
             inline_diagnostics_enabled: full_mode,
             diagnostics_enabled: full_mode,
             word_completions_enabled: full_mode,
+            word_completions_override: None,
+            snippet_suggestions_override: None,
             inline_value_cache: InlineValueCache::new(inlay_hint_settings.show_value_hints),
             inlay_hint_cache: InlayHintCache::new(inlay_hint_settings),
             gutter_hovered: false,
@@ -2368,6 +2384,12 @@ impl Editor {
             key_context.add("multibuffer");
         }
 
+        let cursor = self.selections.newest_anchor().head();
+        let language_settings = self.buffer.read(cx).language_settings_at(cursor, cx);
+        for context in &language_settings.key_context {
+            key_context.add(context.clone());
+        }
+
         if self.selection_mark_mode {
             key_context.add("selection_mode");
         }
@@ -5347,6 +5369,10 @@ impl Editor {
         let completion_settings = language_settings(language.clone(), buffer_snapshot.file(), cx)
             .completions
             .clone();
+        let words_mode = Self::apply_word_completions_override(
+            completion_settings.words,
+            self.word_completions_override,
+        );
 
         let show_completion_documentation = buffer_snapshot
             .settings_at(buffer_position, cx)
@@ -5372,6 +5398,7 @@ impl Editor {
             .is_none_or(|query| !query.chars().any(|c| c.is_digit(10)));
 
         let omit_word_completions = !self.word_completions_enabled
+            || self.word_completions_override == Some(false)
             || (!ignore_word_threshold
                 && match &query {
                     Some(query) => query.chars().count() < completion_settings.words_min_length,
@@ -5389,7 +5416,7 @@ impl Editor {
                     cx,
                 );
 
-                let words = match (omit_word_completions, completion_settings.words) {
+                let words = match (omit_word_completions, words_mode) {
                     (true, _) | (_, WordsCompletionMode::Disabled) => {
                         Task::ready(BTreeMap::default())
                     }
@@ -5421,7 +5448,7 @@ impl Editor {
             }
         };
 
-        let snippet_sort_order = EditorSettings::get_global(cx).snippet_sort_order;
+        let snippet_sort_order = self.snippet_sort_order(cx);
 
         let id = post_inc(&mut self.next_completion_id);
         let task = cx.spawn_in(window, async move |editor, cx| {
@@ -5449,7 +5476,7 @@ impl Editor {
                         Some(options) => options.merge(&response.display_options),
                     }
                 }
-                if completion_settings.words == WordsCompletionMode::Fallback {
+                if words_mode == WordsCompletionMode::Fallback {
                     words = Task::ready(BTreeMap::default());
                 }
             }
@@ -10203,9 +10230,9 @@ impl Editor {
             };
 
             let wrap_column = self.hard_wrap.unwrap_or_else(|| {
-                buffer
-                    .language_settings_at(Point::new(start_row, 0), cx)
-                    .preferred_line_length as usize
+                let settings = buffer.language_settings_at(Point::new(start_row, 0), cx);
+                let comment_wrap_column = settings.comment_wrap_column.filter(|_| inside_comment);
+                comment_wrap_column.unwrap_or(settings.preferred_line_length) as usize
             });
 
             let subsequent_lines_prefix = if let Some(rewrap_prefix_str) = &rewrap_prefix {
@@ -15054,14 +15081,21 @@ impl Editor {
 
         Some(cx.spawn_in(window, async move |editor, cx| {
             let project_transaction = rename.await?;
-            Self::open_project_transaction(
-                &editor,
-                workspace,
-                project_transaction,
-                format!("Rename: {} → {}", old_name, new_name),
-                cx,
-            )
-            .await?;
+            let title = format!("Rename: {} → {}", old_name, new_name);
+            // Renames that touch several files open a preview where files can be left out
+            // before the rename is saved.
+            if project_transaction.0.len() > 1 {
+                workspace.update_in(cx, |workspace, window, cx| {
+                    let project = workspace.project().clone();
+                    let preview = cx.new(|cx| {
+                        RenamePreview::new(title, project_transaction, project, window, cx)
+                    });
+                    workspace.add_item_to_active_pane(Box::new(preview), None, true, window, cx);
+                })?;
+            } else {
+                Self::open_project_transaction(&editor, workspace, project_transaction, title, cx)
+                    .await?;
+            }
 
             editor.update(cx, |editor, cx| {
                 editor.refresh_document_highlights(cx);
@@ -15117,6 +15151,17 @@ impl Editor {
         self.pending_rename.as_ref()
     }
 
+    /// Whether the editor is waiting for a format or organize-imports request
+    /// to finish.
+    pub fn is_formatting(&self) -> bool {
+        self.pending_formats > 0
+    }
+
+    fn finish_pending_format(&mut self, cx: &mut Context<Self>) {
+        self.pending_formats = self.pending_formats.saturating_sub(1);
+        cx.notify();
+    }
+
     fn format(
         &mut self,
         _: &Format,
@@ -15216,10 +15261,32 @@ impl Editor {
             })
             .unwrap_or_else(|| self.selections.disjoint_anchors_arc());
 
-        let mut timeout = cx.background_executor().timer(FORMAT_TIMEOUT).fuse();
+        // When saving, each buffer's settings decide how long to wait, with 0
+        // meaning to wait for as long as formatting takes.
+        let timeout = match trigger {
+            FormatTrigger::Save => buffers
+                .iter()
+                .map(|buffer| {
+                    let buffer = buffer.read(cx);
+                    let timeout_ms =
+                        language_settings(buffer.language().map(|l| l.name()), buffer.file(), cx)
+                            .format_on_save_timeout_ms;
+                    (timeout_ms > 0).then(|| Duration::from_millis(timeout_ms))
+                })
+                .collect::<Option<Vec<_>>>()
+                .map(|timeouts| timeouts.into_iter().max().unwrap_or(FORMAT_TIMEOUT)),
+            FormatTrigger::Manual => Some(FORMAT_TIMEOUT),
+        };
+        let mut timeout = match timeout {
+            Some(timeout) => cx.background_executor().timer(timeout).left_future(),
+            None => future::pending().right_future(),
+        }
+        .fuse();
         let format = project.update(cx, |project, cx| {
             project.format(buffers, target, true, trigger, cx)
         });
+        self.pending_formats += 1;
+        cx.notify();
 
         cx.spawn_in(window, async move |editor, cx| {
             let transaction = futures::select_biased! {
@@ -15229,6 +15296,9 @@ impl Editor {
                     None
                 }
             };
+            editor
+                .update(cx, |editor, cx| editor.finish_pending_format(cx))
+                .ok();
 
             buffer
                 .update(cx, |buffer, cx| {
@@ -15290,7 +15360,9 @@ impl Editor {
         let apply_action = project.update(cx, |project, cx| {
             project.apply_code_action_kind(buffers, kind, true, cx)
         });
-        cx.spawn_in(window, async move |_, cx| {
+        self.pending_formats += 1;
+        cx.notify();
+        cx.spawn_in(window, async move |editor, cx| {
             let transaction = futures::select_biased! {
                 () = timeout => {
                     log::warn!("timed out waiting for executing code action");
@@ -15298,6 +15370,9 @@ impl Editor {
                 }
                 transaction = apply_action.log_err().fuse() => transaction,
             };
+            editor
+                .update(cx, |editor, cx| editor.finish_pending_format(cx))
+                .ok();
             buffer
                 .update(cx, |buffer, cx| {
                     // check if we need this
@@ -15487,6 +15562,66 @@ impl Editor {
         self.word_completions_enabled = false;
     }
 
+    /// Whether words from the buffer are suggested as completions, either
+    /// because of the `completions.words` setting or a toggle in this editor.
+    pub fn word_completions_enabled(&self, cx: &App) -> bool {
+        self.word_completions_enabled
+            && self.word_completions_mode(cx) != WordsCompletionMode::Disabled
+    }
+
+    fn word_completions_mode(&self, cx: &App) -> WordsCompletionMode {
+        let words = self.buffer.read(cx).language_settings(cx).completions.words;
+        Self::apply_word_completions_override(words, self.word_completions_override)
+    }
+
+    fn apply_word_completions_override(
+        words: WordsCompletionMode,
+        word_completions_override: Option<bool>,
+    ) -> WordsCompletionMode {
+        match word_completions_override {
+            Some(false) => WordsCompletionMode::Disabled,
+            Some(true) if words == WordsCompletionMode::Disabled => WordsCompletionMode::Enabled,
+            _ => words,
+        }
+    }
+
+    pub fn toggle_word_completions(
+        &mut self,
+        _: &ToggleWordCompletions,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.word_completions_override = Some(!self.word_completions_enabled(cx));
+        cx.notify();
+    }
+
+    /// Whether snippets are included in completions, either because of the
+    /// `snippet_sort_order` setting or a toggle in this editor.
+    pub fn snippet_suggestions_enabled(&self, cx: &App) -> bool {
+        self.snippet_sort_order(cx) != SnippetSortOrder::None
+    }
+
+    fn snippet_sort_order(&self, cx: &App) -> SnippetSortOrder {
+        let snippet_sort_order = EditorSettings::get_global(cx).snippet_sort_order;
+        match self.snippet_suggestions_override {
+            Some(false) => SnippetSortOrder::None,
+            Some(true) if snippet_sort_order == SnippetSortOrder::None => {
+                SnippetSortOrder::default()
+            }
+            _ => snippet_sort_order,
+        }
+    }
+
+    pub fn toggle_snippet_suggestions(
+        &mut self,
+        _: &ToggleSnippetSuggestions,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.snippet_suggestions_override = Some(!self.snippet_suggestions_enabled(cx));
+        cx.notify();
+    }
+
     pub fn diagnostics_enabled(&self) -> bool {
         self.diagnostics_enabled && self.mode.is_full()
     }
@@ -17191,6 +17326,52 @@ impl Editor {
             };
             self.soft_wrap_mode_override = Some(soft_wrap);
         }
+        self.serialize_soft_wrap(cx);
+        cx.notify();
+    }
+
+    pub fn cycle_soft_wrap(&mut self, _: &CycleSoftWrap, _: &mut Window, cx: &mut Context<Self>) {
+        let soft_wrap = match self.soft_wrap_mode(cx) {
+            SoftWrap::GitDiff => return,
+            SoftWrap::None => language_settings::SoftWrap::EditorWidth,
+            SoftWrap::EditorWidth | SoftWrap::Bounded(_) => {
+                language_settings::SoftWrap::PreferredLineLength
+            }
+            SoftWrap::Column(_) => language_settings::SoftWrap::None,
+        };
+        self.soft_wrap_mode_override = Some(soft_wrap);
+        self.serialize_soft_wrap(cx);
+        cx.notify();
+    }
+
+    /// Remembers the soft wrap chosen for this editor, so that it's restored
+    /// along with the rest of the editor's state.
+    fn serialize_soft_wrap(&self, cx: &mut Context<Self>) {
+        let Some(workspace_id) = self.workspace.as_ref().and_then(|workspace| workspace.1) else {
+            return;
+        };
+        let item_id = cx.entity().entity_id().as_u64() as ItemId;
+        let soft_wrap = self
+            .soft_wrap_mode_override
+            .and_then(|soft_wrap| serde_json::to_string(&soft_wrap).log_err());
+        cx.background_spawn(async move {
+            DB.save_soft_wrap(item_id, workspace_id, soft_wrap)
+                .await
+                .with_context(|| {
+                    format!("persisting soft wrap for editor {item_id}, workspace {workspace_id:?}")
+                })
+                .log_err();
+        })
+        .detach();
+    }
+
+    pub fn toggle_read_only(&mut self, _: &ToggleReadOnly, _: &mut Window, cx: &mut Context<Self>) {
+        // Buffers that can't be edited, like those of collaborators without
+        // write access, stay read-only regardless of the editor.
+        if self.buffer.read(cx).read_only() {
+            return;
+        }
+        self.read_only = !self.read_only;
         cx.notify();
     }
 
@@ -19772,6 +19953,15 @@ impl Editor {
                 });
                 self.selection_history.mode = SelectionHistoryMode::Normal;
             };
+
+            if let Some(soft_wrap) = DB
+                .get_soft_wrap(item_id, workspace_id)
+                .log_err()
+                .flatten()
+                .and_then(|soft_wrap| serde_json::from_str(&soft_wrap).log_err())
+            {
+                self.soft_wrap_mode_override = Some(soft_wrap);
+            }
         }
 
         self.read_scroll_position_from_db(item_id, workspace_id, window, cx);
@@ -21072,7 +21262,7 @@ fn inlay_hint_settings(
 ) -> InlayHintSettings {
     let file = snapshot.file_at(location);
     let language = snapshot.language_at(location).map(|l| l.name());
-    language_settings(language, file, cx).inlay_hints
+    language_settings(language, file, cx).inlay_hints.clone()
 }
 
 fn consume_contiguous_rows(
@@ -26,7 +26,8 @@ use language::{
     DiagnosticSourceKind, FakeLspAdapter, IndentGuideSettings, LanguageConfig,
     LanguageConfigOverride, LanguageMatcher, LanguageName, Override, Point,
     language_settings::{
-        CompletionSettingsContent, FormatterList, LanguageSettingsContent, LspInsertMode,
+        CodeActionOnFormat, CompletionSettingsContent, FormatterErrorPolicy, FormatterList,
+        FormatterStep, LanguageSettingsContent, LspInsertMode,
     },
     tree_sitter_python,
 };
@@ -6403,6 +6404,52 @@ async fn test_rewrap(cx: &mut TestAppContext) {
     }
 }
 
+#[gpui::test]
+async fn test_rewrap_comments_at_comment_wrap_column(cx: &mut TestAppContext) {
+    init_test(cx, |settings| {
+        settings.languages.0.extend([(
+            "C++".into(),
+            LanguageSettingsContent {
+                allow_rewrap: Some(language_settings::RewrapBehavior::Anywhere),
+                preferred_line_length: Some(40),
+                comment_wrap_column: Some(20),
+                ..Default::default()
+            },
+        )])
+    });
+
+    let mut cx = EditorTestContext::new(cx).await;
+    let cpp_language = Arc::new(Language::new(
+        LanguageConfig {
+            name: "C++".into(),
+            line_comments: vec!["// ".into()],
+            ..LanguageConfig::default()
+        },
+        None,
+    ));
+    cx.update_buffer(|buffer, cx| buffer.set_language(Some(cpp_language), cx));
+
+    // Comments are wrapped at the comment wrap column...
+    cx.set_state(indoc! {"
+        // ˇaaaa bbbb cccc dddd eeee ffff
+    "});
+    cx.update_editor(|editor, window, cx| editor.rewrap(&Rewrap, window, cx));
+    cx.assert_editor_state(indoc! {"
+        // ˇaaaa bbbb cccc
+        // dddd eeee ffff
+    "});
+
+    // ...while code is wrapped at the preferred line length.
+    cx.set_state(indoc! {"
+        ˇaaaa bbbb cccc dddd eeee ffff gggg hhhh iiii
+    "});
+    cx.update_editor(|editor, window, cx| editor.rewrap(&Rewrap, window, cx));
+    cx.assert_editor_state(indoc! {"
+        ˇaaaa bbbb cccc dddd eeee ffff gggg hhhh
+        iiii
+    "});
+}
+
 #[gpui::test]
 async fn test_rewrap_block_comments(cx: &mut TestAppContext) {
     init_test(cx, |settings| {
@@ -12197,6 +12244,237 @@ async fn test_multiple_formatters(cx: &mut TestAppContext) {
     });
 }
 
+#[gpui::test]
+async fn test_formatter_error_policy(cx: &mut TestAppContext) {
+    init_test(cx, |settings| {
+        settings.defaults.formatter = Some(FormatterList::Steps(vec![
+            FormatterStep::WithPolicy {
+                formatter: Formatter::LanguageServer(
+                    settings::LanguageServerFormatterSpecifier::Current,
+                ),
+                on_error: FormatterErrorPolicy::Skip,
+            },
+            FormatterStep::Formatter(Formatter::LanguageServer(
+                settings::LanguageServerFormatterSpecifier::Current,
+            )),
+        ]))
+    });
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_file(path!("/file.rs"), "one\n".into()).await;
+
+    let project = Project::test(fs, [path!("/").as_ref()], cx).await;
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(rust_lang());
+
+    let mut fake_servers = language_registry.register_fake_lsp(
+        "Rust",
+        FakeLspAdapter {
+            capabilities: lsp::ServerCapabilities {
+                document_formatting_provider: Some(lsp::OneOf::Left(true)),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    );
+
+    let buffer = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer(path!("/file.rs"), cx)
+        })
+        .await
+        .unwrap();
+
+    let buffer = cx.new(|cx| MultiBuffer::singleton(buffer, cx));
+    let (editor, cx) = cx.add_window_view(|window, cx| {
+        build_editor_with_project(project.clone(), buffer, window, cx)
+    });
+
+    cx.executor().start_waiting();
+
+    // Every other formatting request fails.
+    let request_count = Arc::new(AtomicUsize::new(0));
+    let fake_server = fake_servers.next().await.unwrap();
+    fake_server.set_request_handler::<lsp::request::Formatting, _, _>({
+        let request_count = request_count.clone();
+        move |_params, _| {
+            let request_index = request_count.fetch_add(1, atomic::Ordering::SeqCst);
+            async move {
+                if request_index % 2 == 0 {
+                    return Err(anyhow::anyhow!("formatting failed"));
+                }
+                Ok(Some(vec![lsp::TextEdit::new(
+                    lsp::Range::new(lsp::Position::new(0, 0), lsp::Position::new(0, 0)),
+                    "formatted\n".to_string(),
+                )]))
+            }
+        }
+    });
+
+    // The first formatter fails and is skipped, so the second one still runs.
+    editor
+        .update_in(cx, |editor, window, cx| {
+            editor.perform_format(
+                project.clone(),
+                FormatTrigger::Manual,
+                FormatTarget::Buffers(editor.buffer().read(cx).all_buffers()),
+                window,
+                cx,
+            )
+        })
+        .unwrap()
+        .await;
+    assert_eq!(request_count.load(atomic::Ordering::SeqCst), 2);
+    editor.update(cx, |editor, cx| {
+        assert_eq!(editor.text(cx), "formatted\none\n");
+    });
+
+    // When a formatter that aborts on error fails, the rest of the chain is not run.
+    update_test_language_settings(cx, |settings| {
+        settings.defaults.formatter = Some(FormatterList::Vec(vec![
+            Formatter::LanguageServer(settings::LanguageServerFormatterSpecifier::Current),
+            Formatter::LanguageServer(settings::LanguageServerFormatterSpecifier::Current),
+        ]))
+    });
+    editor
+        .update_in(cx, |editor, window, cx| {
+            editor.perform_format(
+                project.clone(),
+                FormatTrigger::Manual,
+                FormatTarget::Buffers(editor.buffer().read(cx).all_buffers()),
+                window,
+                cx,
+            )
+        })
+        .unwrap()
+        .await;
+    assert_eq!(request_count.load(atomic::Ordering::SeqCst), 3);
+    editor.update(cx, |editor, cx| {
+        assert_eq!(editor.text(cx), "formatted\none\n");
+    });
+}
+
+#[gpui::test]
+async fn test_code_actions_on_format_timeout(cx: &mut TestAppContext) {
+    init_test(cx, |settings| {
+        settings.defaults.formatter = Some(FormatterList::Vec(vec![Formatter::LanguageServer(
+            settings::LanguageServerFormatterSpecifier::Current,
+        )]));
+        settings.defaults.code_actions_on_format = Some(vec![
+            CodeActionOnFormat {
+                action: "slow-action".into(),
+                timeout_ms: Some(100),
+            },
+            CodeActionOnFormat::new("fast-action"),
+        ]);
+    });
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_file(path!("/file.rs"), "one\n".into()).await;
+
+    let project = Project::test(fs, [path!("/").as_ref()], cx).await;
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(rust_lang());
+
+    let mut fake_servers = language_registry.register_fake_lsp(
+        "Rust",
+        FakeLspAdapter {
+            capabilities: lsp::ServerCapabilities {
+                document_formatting_provider: Some(lsp::OneOf::Left(true)),
+                code_action_provider: Some(lsp::CodeActionProviderCapability::Simple(true)),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    );
+
+    let buffer = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer(path!("/file.rs"), cx)
+        })
+        .await
+        .unwrap();
+
+    let buffer = cx.new(|cx| MultiBuffer::singleton(buffer, cx));
+    let (editor, cx) = cx.add_window_view(|window, cx| {
+        build_editor_with_project(project.clone(), buffer, window, cx)
+    });
+
+    cx.executor().start_waiting();
+
+    let fake_server = fake_servers.next().await.unwrap();
+    fake_server.set_request_handler::<lsp::request::Formatting, _, _>(
+        move |_params, _| async move {
+            Ok(Some(vec![lsp::TextEdit::new(
+                lsp::Range::new(lsp::Position::new(0, 0), lsp::Position::new(0, 0)),
+                "formatted\n".to_string(),
+            )]))
+        },
+    );
+    // The slow code action doesn't respond until the lock is released.
+    let slow_action_lock = Arc::new(futures::lock::Mutex::new(()));
+    fake_server.set_request_handler::<lsp::request::CodeActionRequest, _, _>({
+        let slow_action_lock = slow_action_lock.clone();
+        move |params, _| {
+            let slow_action_lock = slow_action_lock.clone();
+            async move {
+                let requested_code_actions =
+                    params.context.only.expect("Expected code action request");
+                let action_kind = requested_code_actions[0].as_str().to_string();
+                if action_kind == "slow-action" {
+                    slow_action_lock.lock().await;
+                }
+                let uri = lsp::Uri::from_file_path(path!("/file.rs")).unwrap();
+                Ok(Some(vec![lsp::CodeActionOrCommand::CodeAction(
+                    lsp::CodeAction {
+                        kind: Some(action_kind.clone().into()),
+                        edit: Some(lsp::WorkspaceEdit::new(
+                            [(
+                                uri,
+                                vec![lsp::TextEdit::new(
+                                    lsp::Range::new(
+                                        lsp::Position::new(0, 0),
+                                        lsp::Position::new(0, 0),
+                                    ),
+                                    format!("applied-{action_kind}\n"),
+                                )],
+                            )]
+                            .into_iter()
+                            .collect(),
+                        )),
+                        ..Default::default()
+                    },
+                )]))
+            }
+        }
+    });
+    fake_server.set_request_handler::<lsp::request::CodeActionResolveRequest, _, _>({
+        move |params, _| async move { Ok(params) }
+    });
+
+    // The slow code action is skipped once it times out, and the rest of the
+    // formatting still runs, in order.
+    let lock_guard = slow_action_lock.lock().await;
+    let format = editor
+        .update_in(cx, |editor, window, cx| {
+            editor.perform_format(
+                project.clone(),
+                FormatTrigger::Manual,
+                FormatTarget::Buffers(editor.buffer().read(cx).all_buffers()),
+                window,
+                cx,
+            )
+        })
+        .unwrap();
+    cx.run_until_parked();
+    cx.executor().advance_clock(Duration::from_millis(100));
+    format.await;
+    editor.update(cx, |editor, cx| {
+        assert_eq!(editor.text(cx), "formatted\napplied-fast-action\none\n");
+    });
+    drop(lock_guard);
+}
+
 #[gpui::test]
 async fn test_organize_imports_manual_trigger(cx: &mut TestAppContext) {
     init_test(cx, |settings| {
@@ -12529,6 +12807,29 @@ async fn test_strip_whitespace_and_format_via_lsp(cx: &mut TestAppContext) {
     );
 }
 
+#[gpui::test]
+async fn test_language_key_context(cx: &mut TestAppContext) {
+    init_test(cx, |settings| {
+        settings.languages.0.insert(
+            "Rust".into(),
+            LanguageSettingsContent {
+                key_context: Some(vec!["rust_code".into()]),
+                ..Default::default()
+            },
+        );
+    });
+
+    let mut cx = EditorTestContext::new(cx).await;
+    cx.update_editor(|editor, window, cx| {
+        assert!(!editor.key_context(window, cx).contains("rust_code"));
+    });
+
+    cx.update_buffer(|buffer, cx| buffer.set_language(Some(rust_lang()), cx));
+    cx.update_editor(|editor, window, cx| {
+        assert!(editor.key_context(window, cx).contains("rust_code"));
+    });
+}
+
 #[gpui::test]
 async fn test_handle_input_for_show_signature_help_auto_signature_help_true(
     cx: &mut TestAppContext,
@@ -14720,6 +15021,104 @@ async fn test_word_completions_disabled(cx: &mut TestAppContext) {
     });
 }
 
+#[gpui::test]
+async fn test_cycle_soft_wrap(cx: &mut TestAppContext) {
+    init_test(cx, |settings| {
+        settings.defaults.soft_wrap = Some(language_settings::SoftWrap::None);
+        settings.defaults.preferred_line_length = Some(40);
+    });
+
+    let mut cx = EditorTestContext::new(cx).await;
+    cx.update_editor(|editor, window, cx| {
+        assert!(matches!(editor.soft_wrap_mode(cx), SoftWrap::None));
+        editor.cycle_soft_wrap(&CycleSoftWrap, window, cx);
+        assert!(matches!(editor.soft_wrap_mode(cx), SoftWrap::EditorWidth));
+        editor.cycle_soft_wrap(&CycleSoftWrap, window, cx);
+        assert!(matches!(editor.soft_wrap_mode(cx), SoftWrap::Column(40)));
+        editor.cycle_soft_wrap(&CycleSoftWrap, window, cx);
+        assert!(matches!(editor.soft_wrap_mode(cx), SoftWrap::None));
+    });
+}
+
+#[gpui::test]
+async fn test_toggle_read_only(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+    cx.set_state("abcˇ");
+    cx.update_editor(|editor, window, cx| {
+        editor.toggle_read_only(&ToggleReadOnly, window, cx);
+        assert!(editor.read_only(cx));
+    });
+    cx.simulate_input("d");
+    cx.assert_editor_state("abcˇ");
+
+    cx.update_editor(|editor, window, cx| {
+        editor.toggle_read_only(&ToggleReadOnly, window, cx);
+        assert!(!editor.read_only(cx));
+    });
+    cx.simulate_input("d");
+    cx.assert_editor_state("abcdˇ");
+}
+
+#[gpui::test]
+async fn test_toggle_word_completions(cx: &mut TestAppContext) {
+    init_test(cx, |language_settings| {
+        language_settings.defaults.completions = Some(CompletionSettingsContent {
+            words: Some(WordsCompletionMode::Enabled),
+            words_min_length: Some(0),
+            lsp_insert_mode: Some(LspInsertMode::Insert),
+            ..Default::default()
+        });
+    });
+
+    let mut cx = EditorLspTestContext::new_rust(lsp::ServerCapabilities::default(), cx).await;
+    cx.update_editor(|editor, window, cx| {
+        assert!(editor.word_completions_enabled(cx));
+        editor.toggle_word_completions(&ToggleWordCompletions, window, cx);
+        assert!(!editor.word_completions_enabled(cx));
+    });
+    cx.set_state(indoc! {"ˇ
+        wow
+        wowen
+        wowser
+    "});
+    cx.simulate_keystroke("w");
+    cx.executor().run_until_parked();
+    cx.update_editor(|editor, _, _| {
+        assert!(
+            editor.context_menu.borrow_mut().is_none(),
+            "expected completion menu to be hidden, as word completions were toggled off"
+        );
+    });
+
+    cx.update_editor(|editor, window, cx| {
+        editor.toggle_word_completions(&ToggleWordCompletions, window, cx);
+        assert!(editor.word_completions_enabled(cx));
+    });
+    cx.set_state(indoc! {"ˇ
+        wow
+        wowen
+        wowser
+    "});
+    cx.simulate_keystroke("w");
+    cx.executor().run_until_parked();
+    cx.condition(|editor, _| editor.context_menu_visible())
+        .await;
+    cx.update_editor(|editor, _, _| {
+        if let Some(CodeContextMenu::Completions(menu)) = editor.context_menu.borrow_mut().as_ref()
+        {
+            assert_eq!(
+                completion_menu_entries(menu),
+                &["wowser", "wowen", "wow"],
+                "word completions toggled back on should be shown"
+            );
+        } else {
+            panic!("expected completion menu to be open");
+        }
+    });
+}
+
 fn gen_text_edit(params: &CompletionParams, text: &str) -> Option<lsp::CompletionTextEdit> {
     let position = || lsp::Position {
         line: params.text_document_position.position.line,
@@ -22919,6 +23318,94 @@ async fn test_rename_with_duplicate_edits(cx: &mut TestAppContext) {
     "});
 }
 
+#[gpui::test]
+async fn test_rename_preview(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({
+            "a.rs": "fn foo() {}\n",
+            "b.rs": "fn bar() { foo() }\n",
+        }),
+    )
+    .await;
+    let project = Project::test(fs, [path!("/dir").as_ref()], cx).await;
+    let buffer_a = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer(path!("/dir/a.rs"), cx)
+        })
+        .await
+        .unwrap();
+    let buffer_b = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer(path!("/dir/b.rs"), cx)
+        })
+        .await
+        .unwrap();
+
+    let mut project_transaction = ProjectTransaction::default();
+    for (buffer, range) in [(&buffer_a, 3..6), (&buffer_b, 11..14)] {
+        let transaction = buffer.update(cx, |buffer, cx| {
+            buffer.start_transaction();
+            buffer.edit([(range, "renamed")], None, cx);
+            buffer.end_transaction(cx);
+            buffer.finalize_last_transaction().cloned().unwrap()
+        });
+        project_transaction.0.insert(buffer.clone(), transaction);
+    }
+
+    let (preview, cx) = cx.add_window_view(|window, cx| {
+        RenamePreview::new(
+            "Rename: foo → renamed",
+            project_transaction,
+            project.clone(),
+            window,
+            cx,
+        )
+    });
+    let texts = |cx: &mut VisualTestContext| {
+        (
+            buffer_a.read_with(cx, |buffer, _| buffer.text()),
+            buffer_b.read_with(cx, |buffer, _| buffer.text()),
+        )
+    };
+
+    // Leaving a file out of the rename undoes only that file's edits.
+    preview.update(cx, |preview, cx| preview.toggle_file(&buffer_b, cx));
+    assert_eq!(
+        texts(cx),
+        (
+            "fn renamed() {}\n".to_string(),
+            "fn bar() { foo() }\n".to_string()
+        )
+    );
+    preview.read_with(cx, |preview, _| {
+        assert!(preview.is_file_included(&buffer_a));
+        assert!(!preview.is_file_included(&buffer_b));
+    });
+
+    preview.update(cx, |preview, cx| preview.toggle_file(&buffer_b, cx));
+    assert_eq!(
+        texts(cx),
+        (
+            "fn renamed() {}\n".to_string(),
+            "fn bar() { renamed() }\n".to_string()
+        )
+    );
+
+    // Reverting undoes the rename in every file at once.
+    preview.update(cx, |preview, cx| preview.revert(cx));
+    assert_eq!(
+        texts(cx),
+        (
+            "fn foo() {}\n".to_string(),
+            "fn bar() { foo() }\n".to_string()
+        )
+    );
+}
+
 #[gpui::test]
 async fn test_rename_without_prepare(cx: &mut TestAppContext) {
     init_test(cx, |_| {});
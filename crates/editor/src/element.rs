@@ -21,6 +21,7 @@ use crate::{
         ScrollbarDiagnostics, ShowMinimap,
     },
     git::blame::{BlameRenderer, GitBlame, GlobalBlameRenderer},
+    hover_links,
     hover_popover::{
         self, HOVER_POPOVER_GAP, MIN_POPOVER_CHARACTER_WIDTH, MIN_POPOVER_LINE_HEIGHT,
         POPOVER_RIGHT_OFFSET, hover_at,
@@ -53,7 +54,10 @@ use gpui::{
     linear_gradient, outline, point, px, quad, relative, size, solid_background, transparent_black,
 };
 use itertools::Itertools;
-use language::{IndentGuideSettings, language_settings::ShowWhitespaceSetting};
+use language::{
+    IndentGuideSettings,
+    language_settings::{ShowWhitespaceSetting, WhitespaceClass, WhitespaceClasses},
+};
 use markdown::Markdown;
 use multi_buffer::{
     Anchor, ExcerptId, ExcerptInfo, ExpandExcerptDirection, ExpandInfo, MultiBufferPoint,
@@ -459,11 +463,15 @@ impl EditorElement {
         register_action(editor, window, Editor::open_excerpts_in_split);
         register_action(editor, window, Editor::open_proposed_changes_editor);
         register_action(editor, window, Editor::toggle_soft_wrap);
+        register_action(editor, window, Editor::cycle_soft_wrap);
+        register_action(editor, window, Editor::toggle_read_only);
         register_action(editor, window, Editor::toggle_tab_bar);
         register_action(editor, window, Editor::toggle_line_numbers);
         register_action(editor, window, Editor::toggle_relative_line_numbers);
         register_action(editor, window, Editor::toggle_indent_guides);
         register_action(editor, window, Editor::toggle_inlay_hints);
+        register_action(editor, window, Editor::toggle_snippet_suggestions);
+        register_action(editor, window, Editor::toggle_word_completions);
         if editor.read(cx).diagnostics_enabled() {
             register_action(editor, window, Editor::toggle_diagnostics);
         }
@@ -702,6 +710,20 @@ impl EditorElement {
             }
         }
 
+        if click_count == 2
+            && !modifiers.shift
+            && hover_links::apply_inlay_hint_text_edits(
+                &position_map.snapshot,
+                point_for_position,
+                editor,
+                window,
+                cx,
+            )
+        {
+            cx.stop_propagation();
+            return;
+        }
+
         let is_singleton = editor.buffer().read(cx).is_singleton();
 
         if click_count == 2 && !is_singleton {
@@ -7714,6 +7736,23 @@ impl LineWithInvisibles {
                             .shape_line(x, font_size, &[run], None)
                             .with_len(highlighted_chunk.text.len());
 
+                        // Non-breaking spaces and zero-width characters are replaced with a
+                        // placeholder, so their symbol has to be drawn on top of it.
+                        if editor_mode.is_full()
+                            && !highlighted_chunk.is_inlay
+                            && let Some(class) = highlighted_chunk
+                                .text
+                                .chars()
+                                .next()
+                                .and_then(WhitespaceClass::unusual)
+                        {
+                            invisibles.push(Invisible::Unusual {
+                                line_start_offset: len,
+                                line_end_offset: len + highlighted_chunk.text.len(),
+                                class,
+                            });
+                        }
+
                         width += line_layout.width;
                         len += highlighted_chunk.text.len();
                         fragments.push(LineFragment::Text(line_layout))
@@ -7784,11 +7823,14 @@ impl LineWithInvisibles {
                             // Line wrap pads its contents with fake whitespaces,
                             // avoid printing them
                             let is_soft_wrapped = is_row_soft_wrapped(row);
+                            // Offsets are relative to the whole line, including any
+                            // fragments shaped before a replaced chunk.
+                            let line_offset = len + line.len();
                             if highlighted_chunk.is_tab {
                                 if non_whitespace_added || !is_soft_wrapped {
                                     invisibles.push(Invisible::Tab {
-                                        line_start_offset: line.len(),
-                                        line_end_offset: line.len() + line_chunk.len(),
+                                        line_start_offset: line_offset,
+                                        line_end_offset: line_offset + line_chunk.len(),
                                     });
                                 }
                             } else {
@@ -7796,11 +7838,17 @@ impl LineWithInvisibles {
                                     |(index, c)| {
                                         let is_whitespace = c.is_whitespace();
                                         non_whitespace_added |= !is_whitespace;
-                                        if is_whitespace
+                                        if let Some(class) = WhitespaceClass::unusual(c) {
+                                            Some(Invisible::Unusual {
+                                                line_start_offset: line_offset + index,
+                                                line_end_offset: line_offset + index + c.len_utf8(),
+                                                class,
+                                            })
+                                        } else if is_whitespace
                                             && (non_whitespace_added || !is_soft_wrapped)
                                         {
                                             Some(Invisible::Whitespace {
-                                                line_offset: line.len() + index,
+                                                line_offset: line_offset + index,
                                             })
                                         } else {
                                             None
@@ -8028,6 +8076,15 @@ impl LineWithInvisibles {
                 Invisible::Whitespace { line_offset } => {
                     (*line_offset, line_offset + 1, &layout.space_invisible)
                 }
+                Invisible::Unusual {
+                    line_start_offset,
+                    line_end_offset,
+                    class,
+                } => (
+                    *line_start_offset,
+                    *line_end_offset,
+                    layout.whitespace_invisible(*class),
+                ),
             };
 
             let x_offset: ScrollPixelOffset = self.x_for_index(token_offset).into();
@@ -8052,10 +8109,18 @@ impl LineWithInvisibles {
             )
         };
 
-        let invisible_iter = self.invisibles.iter().map(extract_whitespace_info);
+        let invisibles = self
+            .invisibles
+            .iter()
+            .filter(|invisible| layout.whitespace_classes.contains(invisible.class()));
+        let invisible_iter = invisibles.clone().map(extract_whitespace_info);
         match whitespace_setting {
             ShowWhitespaceSetting::None => (),
             ShowWhitespaceSetting::All => invisible_iter.for_each(|(_, paint)| paint(window, cx)),
+            ShowWhitespaceSetting::Unusual => invisibles
+                .filter(|invisible| matches!(invisible, Invisible::Unusual { .. }))
+                .map(extract_whitespace_info)
+                .for_each(|(_, paint)| paint(window, cx)),
             ShowWhitespaceSetting::Selection => invisible_iter.for_each(|([start, _], paint)| {
                 let invisible_point = DisplayPoint::new(row, start as u32);
                 if !selection_ranges
@@ -8088,11 +8153,9 @@ impl LineWithInvisibles {
                 // the above cases.
                 // Note: We zip in the original `invisibles` to check for tab equality
                 let mut last_seen: Option<(bool, usize, Box<dyn Fn(&mut Window, &mut App)>)> = None;
-                for (([start, end], paint), invisible) in
-                    invisible_iter.zip_eq(self.invisibles.iter())
-                {
+                for (([start, end], paint), invisible) in invisible_iter.zip_eq(invisibles) {
                     let should_render = match (&last_seen, invisible) {
-                        (_, Invisible::Tab { .. }) => true,
+                        (_, Invisible::Tab { .. } | Invisible::Unusual { .. }) => true,
                         (Some((_, last_end, _)), _) => *last_end == start,
                         _ => false,
                     };
@@ -8223,6 +8286,23 @@ enum Invisible {
     Whitespace {
         line_offset: usize,
     },
+    /// A character that is easily mistaken for a regular space, like a non-breaking space or a
+    /// zero-width space
+    Unusual {
+        line_start_offset: usize,
+        line_end_offset: usize,
+        class: WhitespaceClass,
+    },
+}
+
+impl Invisible {
+    fn class(&self) -> WhitespaceClass {
+        match self {
+            Invisible::Tab { .. } => WhitespaceClass::Tab,
+            Invisible::Whitespace { .. } => WhitespaceClass::Space,
+            Invisible::Unusual { class, .. } => *class,
+        }
+    }
 }
 
 impl EditorElement {
@@ -9335,45 +9415,37 @@ impl Element for EditorElement {
                     });
 
                     let invisible_symbol_font_size = font_size / 2.;
-                    let whitespace_map = &self
-                        .editor
-                        .read(cx)
-                        .buffer
-                        .read(cx)
-                        .language_settings(cx)
-                        .whitespace_map;
-
-                    let tab_char = whitespace_map.tab.clone();
-                    let tab_len = tab_char.len();
-                    let tab_invisible = window.text_system().shape_line(
-                        tab_char,
-                        invisible_symbol_font_size,
-                        &[TextRun {
-                            len: tab_len,
-                            font: self.style.text.font(),
-                            color: cx.theme().colors().editor_invisible,
-                            background_color: None,
-                            underline: None,
-                            strikethrough: None,
-                        }],
-                        None,
-                    );
-
-                    let space_char = whitespace_map.space.clone();
-                    let space_len = space_char.len();
-                    let space_invisible = window.text_system().shape_line(
-                        space_char,
-                        invisible_symbol_font_size,
-                        &[TextRun {
-                            len: space_len,
-                            font: self.style.text.font(),
-                            color: cx.theme().colors().editor_invisible,
-                            background_color: None,
-                            underline: None,
-                            strikethrough: None,
-                        }],
-                        None,
-                    );
+                    let language_settings =
+                        self.editor.read(cx).buffer.read(cx).language_settings(cx);
+                    let whitespace_map = &language_settings.whitespace_map;
+                    let whitespace_classes = language_settings.whitespace_classes;
+
+                    let invisible_font = self.style.text.font();
+                    let invisible_color = cx.theme().colors().editor_invisible;
+                    let shape_invisible = |symbol: SharedString, window: &mut Window| {
+                        let len = symbol.len();
+                        window.text_system().shape_line(
+                            symbol,
+                            invisible_symbol_font_size,
+                            &[TextRun {
+                                len,
+                                font: invisible_font.clone(),
+                                color: invisible_color,
+                                background_color: None,
+                                underline: None,
+                                strikethrough: None,
+                            }],
+                            None,
+                        )
+                    };
+                    let tab_invisible = shape_invisible(whitespace_map.tab.clone(), window);
+                    let space_invisible = shape_invisible(whitespace_map.space.clone(), window);
+                    let ideographic_space_invisible =
+                        shape_invisible(whitespace_map.ideographic_space.clone(), window);
+                    let non_breaking_space_invisible =
+                        shape_invisible(whitespace_map.non_breaking_space.clone(), window);
+                    let zero_width_invisible =
+                        shape_invisible(whitespace_map.zero_width.clone(), window);
 
                     let mode = snapshot.mode.clone();
 
@@ -9455,6 +9527,10 @@ impl Element for EditorElement {
                         crease_trailers,
                         tab_invisible,
                         space_invisible,
+                        ideographic_space_invisible,
+                        non_breaking_space_invisible,
+                        zero_width_invisible,
+                        whitespace_classes,
                         sticky_buffer_header,
                         expand_toggles,
                     }
@@ -9631,11 +9707,25 @@ pub struct EditorLayout {
     mouse_context_menu: Option<AnyElement>,
     tab_invisible: ShapedLine,
     space_invisible: ShapedLine,
+    ideographic_space_invisible: ShapedLine,
+    non_breaking_space_invisible: ShapedLine,
+    zero_width_invisible: ShapedLine,
+    whitespace_classes: WhitespaceClasses,
     sticky_buffer_header: Option<AnyElement>,
     document_colors: Option<(DocumentColorsRenderMode, Vec<(Range<DisplayPoint>, Hsla)>)>,
 }
 
 impl EditorLayout {
+    fn whitespace_invisible(&self, class: WhitespaceClass) -> &ShapedLine {
+        match class {
+            WhitespaceClass::Space => &self.space_invisible,
+            WhitespaceClass::Tab => &self.tab_invisible,
+            WhitespaceClass::IdeographicSpace => &self.ideographic_space_invisible,
+            WhitespaceClass::NonBreakingSpace => &self.non_breaking_space_invisible,
+            WhitespaceClass::ZeroWidth => &self.zero_width_invisible,
+        }
+    }
+
     fn line_end_overshoot(&self) -> Pixels {
         0.15 * self.position_map.line_height
     }
@@ -10923,6 +11013,43 @@ mod tests {
         }
     }
 
+    #[gpui::test]
+    fn test_unusual_whitespace_invisibles(cx: &mut TestAppContext) {
+        init_test(cx, |s| {
+            s.defaults.show_whitespaces = Some(ShowWhitespaceSetting::Unusual);
+        });
+
+        let input_text = "a\u{a0}b\u{3000}c\u{200b}d e";
+        let expected_invisibles = vec![
+            Invisible::Unusual {
+                line_start_offset: 1,
+                line_end_offset: 3,
+                class: WhitespaceClass::NonBreakingSpace,
+            },
+            Invisible::Unusual {
+                line_start_offset: 4,
+                line_end_offset: 7,
+                class: WhitespaceClass::IdeographicSpace,
+            },
+            Invisible::Unusual {
+                line_start_offset: 8,
+                line_end_offset: 11,
+                class: WhitespaceClass::ZeroWidth,
+            },
+            Invisible::Whitespace { line_offset: 12 },
+        ];
+
+        let actual_invisibles = collect_invisibles_from_new_editor(
+            cx,
+            EditorMode::full(),
+            input_text,
+            px(500.0),
+            false,
+        );
+
+        assert_eq!(expected_invisibles, actual_invisibles);
+    }
+
     #[gpui::test]
     fn test_invisibles_dont_appear_in_certain_editors(cx: &mut TestAppContext) {
         init_test(cx, |s| {
@@ -2,6 +2,7 @@ use crate::{
     Anchor, Editor, EditorSettings, EditorSnapshot, FindAllReferences, GoToDefinition,
     GoToDefinitionSplit, GoToTypeDefinition, GoToTypeDefinitionSplit, GotoDefinitionKind, InlayId,
     Navigated, PointForPosition, SelectPhase,
+    display_map::InlayOffset,
     editor_settings::GoToDefinitionFallback,
     hover_popover::{self, InlayHover},
     scroll::ScrollAmount,
@@ -459,6 +460,78 @@ pub fn update_inlay_link_and_hover_points(
     }
 }
 
+/// Applies the text edits of the inlay hint at the given position, which make the hinted type or
+/// parameter name explicit in the code. Returns whether the hint had any edits to apply.
+///
+/// Language servers may only send the edits when a hint is resolved, which happens when the
+/// mouse hovers over it, so they are there by the time the hint is double-clicked.
+pub fn apply_inlay_hint_text_edits(
+    snapshot: &EditorSnapshot,
+    point_for_position: PointForPosition,
+    editor: &mut Editor,
+    window: &mut Window,
+    cx: &mut Context<Editor>,
+) -> bool {
+    if point_for_position.column_overshoot_after_line_end != 0 || editor.read_only(cx) {
+        return false;
+    }
+    let clicked_offset =
+        snapshot.display_point_to_inlay_offset(point_for_position.exact_unclipped, Bias::Left);
+    let buffer_snapshot = editor.buffer().read(cx).snapshot(cx);
+    let previous_valid_anchor =
+        buffer_snapshot.anchor_before(point_for_position.previous_valid.to_point(snapshot));
+    let next_valid_anchor =
+        buffer_snapshot.anchor_after(point_for_position.next_valid.to_point(snapshot));
+    let Some(clicked_hint_id) = editor
+        .visible_inlay_hints(cx)
+        .skip_while(|hint| {
+            hint.position
+                .cmp(&previous_valid_anchor, &buffer_snapshot)
+                .is_lt()
+        })
+        .take_while(|hint| {
+            hint.position
+                .cmp(&next_valid_anchor, &buffer_snapshot)
+                .is_le()
+        })
+        .find(|hint| {
+            let hint_start = snapshot.anchor_to_inlay_offset(hint.position);
+            let hint_end = InlayOffset(hint_start.0 + hint.text().len());
+            (hint_start..hint_end).contains(&clicked_offset)
+        })
+        .map(|hint| hint.id)
+    else {
+        return false;
+    };
+
+    let excerpt_id = previous_valid_anchor.excerpt_id;
+    let Some(clicked_hint) = editor
+        .inlay_hint_cache()
+        .hint_by_id(excerpt_id, clicked_hint_id)
+    else {
+        return false;
+    };
+    let edits = clicked_hint
+        .text_edits
+        .into_iter()
+        .filter_map(|(range, new_text)| {
+            let start = buffer_snapshot.anchor_in_excerpt(excerpt_id, range.start)?;
+            let end = buffer_snapshot.anchor_in_excerpt(excerpt_id, range.end)?;
+            Some((start..end, new_text))
+        })
+        .collect::<Vec<_>>();
+    if edits.is_empty() {
+        return false;
+    }
+
+    editor.transact(window, cx, |editor, _, cx| {
+        editor
+            .buffer()
+            .update(cx, |buffer, cx| buffer.edit(edits, None, cx));
+    });
+    true
+}
+
 pub fn show_link_definition(
     shift_held: bool,
     editor: &mut Editor,
@@ -920,7 +993,7 @@ mod tests {
         test::editor_lsp_test_context::EditorLspTestContext,
     };
     use futures::StreamExt;
-    use gpui::Modifiers;
+    use gpui::{Modifiers, MouseButton, MouseDownEvent, MouseUpEvent};
     use indoc::indoc;
     use lsp::request::{GotoDefinition, GotoTypeDefinition};
     use settings::InlayHintSettingsContent;
@@ -1281,6 +1354,9 @@ mod tests {
                 show_other_hints: Some(true),
                 show_background: Some(false),
                 toggle_on_modifiers_press: None,
+                max_hint_length: None,
+                excluded_kinds_per_server: None,
+                suppress_patterns: None,
             })
         });
 
@@ -1419,6 +1495,115 @@ mod tests {
             "});
     }
 
+    #[gpui::test]
+    async fn test_inlay_hint_text_edits_on_double_click(cx: &mut gpui::TestAppContext) {
+        init_test(cx, |settings| {
+            settings.defaults.inlay_hints = Some(InlayHintSettingsContent {
+                enabled: Some(true),
+                show_value_hints: Some(false),
+                edit_debounce_ms: Some(0),
+                scroll_debounce_ms: Some(0),
+                show_type_hints: Some(true),
+                show_parameter_hints: Some(true),
+                show_other_hints: Some(true),
+                show_background: Some(false),
+                toggle_on_modifiers_press: None,
+                max_hint_length: None,
+                excluded_kinds_per_server: None,
+                suppress_patterns: None,
+            })
+        });
+
+        let mut cx = EditorLspTestContext::new_rust(
+            lsp::ServerCapabilities {
+                inlay_hint_provider: Some(lsp::OneOf::Left(true)),
+                ..Default::default()
+            },
+            cx,
+        )
+        .await;
+        cx.set_state(indoc! {"
+                fn main() {
+                    let variableˇ = 1;
+                }
+            "});
+        let hint_start_offset = cx.ranges(indoc! {"
+                fn main() {
+                    let variableˇ = 1;
+                }
+            "})[0]
+            .start;
+        let hint_position = cx.to_lsp(hint_start_offset);
+        let hint_label = ": i32";
+        cx.lsp
+            .set_request_handler::<lsp::request::InlayHintRequest, _, _>(move |_, _| async move {
+                Ok(Some(vec![lsp::InlayHint {
+                    position: hint_position,
+                    label: lsp::InlayHintLabel::String(hint_label.to_string()),
+                    kind: Some(lsp::InlayHintKind::TYPE),
+                    text_edits: Some(vec![lsp::TextEdit {
+                        range: lsp::Range::new(hint_position, hint_position),
+                        new_text: hint_label.to_string(),
+                    }]),
+                    tooltip: None,
+                    padding_left: Some(false),
+                    padding_right: Some(false),
+                    data: None,
+                }]))
+            })
+            .next()
+            .await;
+        cx.background_executor.run_until_parked();
+        cx.update_editor(|editor, _window, cx| {
+            let expected_layers = vec![hint_label.to_string()];
+            assert_eq!(expected_layers, cached_hint_labels(editor));
+            assert_eq!(expected_layers, visible_hint_labels(editor, cx));
+        });
+
+        let hint_point = cx.update_editor(|editor, window, cx| {
+            let snapshot = editor.snapshot(window, cx);
+            let previous_valid = hint_start_offset.to_display_point(&snapshot);
+            DisplayPoint::new(
+                previous_valid.row(),
+                previous_valid.column() + (hint_label.len() / 2) as u32,
+            )
+        });
+        let hint_position = cx.pixel_position_for(hint_point);
+        cx.simulate_click(hint_position, Modifiers::none());
+        cx.background_executor.run_until_parked();
+        cx.assert_editor_state(indoc! {"
+                fn main() {
+                    let variableˇ = 1;
+                }
+            "});
+
+        cx.simulate_event(MouseDownEvent {
+            position: hint_position,
+            modifiers: Modifiers::none(),
+            button: MouseButton::Left,
+            click_count: 2,
+            first_mouse: false,
+        });
+        cx.simulate_event(MouseUpEvent {
+            position: hint_position,
+            modifiers: Modifiers::none(),
+            button: MouseButton::Left,
+            click_count: 2,
+        });
+        cx.background_executor.run_until_parked();
+        cx.update_editor(|editor, _window, cx| {
+            assert_eq!(
+                editor.text(cx),
+                indoc! {"
+                    fn main() {
+                        let variable: i32 = 1;
+                    }
+                "},
+                "Double-clicking the hint should insert its text edits"
+            );
+        });
+    }
+
     #[gpui::test]
     async fn test_urls(cx: &mut gpui::TestAppContext) {
         init_test(cx, |_| {});
@@ -1556,6 +1556,9 @@ mod tests {
                 show_other_hints: Some(true),
                 show_background: Some(false),
                 toggle_on_modifiers_press: None,
+                max_hint_length: None,
+                excluded_kinds_per_server: None,
+                suppress_patterns: None,
             })
         });
 
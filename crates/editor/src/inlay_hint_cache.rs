@@ -44,6 +44,8 @@ pub struct InlayHintCache {
     refresh_task: Task<()>,
     invalidate_debounce: Option<Duration>,
     append_debounce: Option<Duration>,
+    /// The settings the cached hints were filtered and truncated with.
+    hint_filter_settings: InlayHintSettings,
     lsp_request_limiter: Arc<Semaphore>,
 }
 
@@ -275,6 +277,7 @@ impl InlayHintCache {
             refresh_task: Task::ready(()),
             invalidate_debounce: debounce_value(inlay_hint_settings.edit_debounce_ms),
             append_debounce: debounce_value(inlay_hint_settings.scroll_debounce_ms),
+            hint_filter_settings: inlay_hint_settings,
             version: 0,
             lsp_request_limiter: Arc::new(Semaphore::new(MAX_CONCURRENT_LSP_REQUESTS)),
         }
@@ -305,13 +308,21 @@ impl InlayHintCache {
         self.invalidate_debounce = debounce_value(new_hint_settings.edit_debounce_ms);
         self.append_debounce = debounce_value(new_hint_settings.scroll_debounce_ms);
         let new_allowed_hint_kinds = new_hint_settings.enabled_inlay_hint_kinds();
+        let hint_filters_changed =
+            new_hint_settings.hint_filters_changed(&self.hint_filter_settings);
+        self.hint_filter_settings = new_hint_settings;
         match (old_enabled, self.enabled) {
             (false, false) => {
                 self.allowed_hint_kinds = new_allowed_hint_kinds;
                 ControlFlow::Break(None)
             }
             (true, true) => {
-                if new_allowed_hint_kinds == self.allowed_hint_kinds {
+                if hint_filters_changed {
+                    // Hints are filtered when they are fetched from the language server,
+                    // so the cached ones have to be queried again.
+                    self.allowed_hint_kinds = new_allowed_hint_kinds;
+                    ControlFlow::Continue(())
+                } else if new_allowed_hint_kinds == self.allowed_hint_kinds {
                     ControlFlow::Break(None)
                 } else {
                     let new_splice = self.new_allowed_hint_kinds_splice(
@@ -1332,6 +1343,9 @@ pub mod tests {
                 show_other_hints: Some(allowed_hint_kinds.contains(&None)),
                 show_background: Some(false),
                 toggle_on_modifiers_press: None,
+                max_hint_length: None,
+                excluded_kinds_per_server: None,
+                suppress_patterns: None,
             })
         });
         let (_, editor, fake_server) = prepare_test_objects(cx, |fake_server, file_with_hints| {
@@ -1442,6 +1456,9 @@ pub mod tests {
                 show_other_hints: Some(true),
                 show_background: Some(false),
                 toggle_on_modifiers_press: None,
+                max_hint_length: None,
+                excluded_kinds_per_server: None,
+                suppress_patterns: None,
             })
         });
 
@@ -1549,6 +1566,9 @@ pub mod tests {
                 show_other_hints: Some(true),
                 show_background: Some(false),
                 toggle_on_modifiers_press: None,
+                max_hint_length: None,
+                excluded_kinds_per_server: None,
+                suppress_patterns: None,
             })
         });
 
@@ -1781,6 +1801,9 @@ pub mod tests {
                 show_other_hints: Some(allowed_hint_kinds.contains(&None)),
                 show_background: Some(false),
                 toggle_on_modifiers_press: None,
+                max_hint_length: None,
+                excluded_kinds_per_server: None,
+                suppress_patterns: None,
             })
         });
 
@@ -1946,6 +1969,9 @@ pub mod tests {
                     show_other_hints: Some(new_allowed_hint_kinds.contains(&None)),
                     show_background: Some(false),
                     toggle_on_modifiers_press: None,
+                    max_hint_length: None,
+                    excluded_kinds_per_server: None,
+                    suppress_patterns: None,
                 })
             });
             cx.executor().run_until_parked();
@@ -1993,6 +2019,9 @@ pub mod tests {
                 show_other_hints: Some(another_allowed_hint_kinds.contains(&None)),
                 show_background: Some(false),
                 toggle_on_modifiers_press: None,
+                max_hint_length: None,
+                excluded_kinds_per_server: None,
+                suppress_patterns: None,
             })
         });
         cx.executor().run_until_parked();
@@ -2053,6 +2082,9 @@ pub mod tests {
                 show_other_hints: Some(final_allowed_hint_kinds.contains(&None)),
                 show_background: Some(false),
                 toggle_on_modifiers_press: None,
+                max_hint_length: None,
+                excluded_kinds_per_server: None,
+                suppress_patterns: None,
             })
         });
         cx.executor().run_until_parked();
@@ -2127,6 +2159,9 @@ pub mod tests {
                 show_other_hints: Some(true),
                 show_background: Some(false),
                 toggle_on_modifiers_press: None,
+                max_hint_length: None,
+                excluded_kinds_per_server: None,
+                suppress_patterns: None,
             })
         });
 
@@ -2264,6 +2299,9 @@ pub mod tests {
                 show_other_hints: Some(true),
                 show_background: Some(false),
                 toggle_on_modifiers_press: None,
+                max_hint_length: None,
+                excluded_kinds_per_server: None,
+                suppress_patterns: None,
             })
         });
 
@@ -2565,6 +2603,9 @@ pub mod tests {
                 show_other_hints: Some(true),
                 show_background: Some(false),
                 toggle_on_modifiers_press: None,
+                max_hint_length: None,
+                excluded_kinds_per_server: None,
+                suppress_patterns: None,
             })
         });
 
@@ -2889,6 +2930,9 @@ pub mod tests {
                 show_other_hints: Some(false),
                 show_background: Some(false),
                 toggle_on_modifiers_press: None,
+                max_hint_length: None,
+                excluded_kinds_per_server: None,
+                suppress_patterns: None,
             })
         });
 
@@ -3066,6 +3110,9 @@ pub mod tests {
                 show_other_hints: Some(true),
                 show_background: Some(false),
                 toggle_on_modifiers_press: None,
+                max_hint_length: None,
+                excluded_kinds_per_server: None,
+                suppress_patterns: None,
             })
         });
         cx.executor().run_until_parked();
@@ -3099,6 +3146,9 @@ pub mod tests {
                 show_other_hints: Some(true),
                 show_background: Some(false),
                 toggle_on_modifiers_press: None,
+                max_hint_length: None,
+                excluded_kinds_per_server: None,
+                suppress_patterns: None,
             })
         });
 
@@ -3192,6 +3242,9 @@ pub mod tests {
                 show_other_hints: Some(true),
                 show_background: Some(false),
                 toggle_on_modifiers_press: None,
+                max_hint_length: None,
+                excluded_kinds_per_server: None,
+                suppress_patterns: None,
             })
         });
 
@@ -3269,6 +3322,9 @@ pub mod tests {
                 show_other_hints: Some(true),
                 show_background: Some(false),
                 toggle_on_modifiers_press: None,
+                max_hint_length: None,
+                excluded_kinds_per_server: None,
+                suppress_patterns: None,
             })
         });
         cx.executor().run_until_parked();
@@ -3330,6 +3386,9 @@ pub mod tests {
                 show_other_hints: Some(true),
                 show_background: Some(false),
                 toggle_on_modifiers_press: None,
+                max_hint_length: None,
+                excluded_kinds_per_server: None,
+                suppress_patterns: None,
             })
         });
 
@@ -3471,6 +3530,88 @@ pub mod tests {
             .unwrap();
     }
 
+    #[gpui::test]
+    async fn test_hint_length_and_filtering_settings(cx: &mut gpui::TestAppContext) {
+        init_test(cx, |settings| {
+            settings.defaults.inlay_hints = Some(InlayHintSettingsContent {
+                enabled: Some(true),
+                edit_debounce_ms: Some(0),
+                scroll_debounce_ms: Some(0),
+                max_hint_length: Some(10),
+                excluded_kinds_per_server: Some(HashMap::from_iter([(
+                    "the-fake-language-server".to_string(),
+                    vec![InlayHintKind::Parameter],
+                )])),
+                suppress_patterns: Some(vec!["^impl Fn".to_string()]),
+                ..InlayHintSettingsContent::default()
+            })
+        });
+        let (_, editor, _fake_server) = prepare_test_objects(cx, |fake_server, _| {
+            fake_server.set_request_handler::<lsp::request::InlayHintRequest, _, _>(
+                move |_, _| async move {
+                    let hint = |character, label: &str, kind| lsp::InlayHint {
+                        position: lsp::Position::new(0, character),
+                        label: lsp::InlayHintLabel::String(label.to_string()),
+                        kind,
+                        text_edits: None,
+                        tooltip: None,
+                        padding_left: None,
+                        padding_right: None,
+                        data: None,
+                    };
+                    Ok(Some(vec![
+                        hint(1, "i32", Some(lsp::InlayHintKind::TYPE)),
+                        hint(2, "name", Some(lsp::InlayHintKind::PARAMETER)),
+                        hint(3, "impl Fn(usize) -> usize", None),
+                        hint(
+                            4,
+                            "Vec<HashMap<String, usize>>",
+                            Some(lsp::InlayHintKind::TYPE),
+                        ),
+                    ]))
+                },
+            );
+        })
+        .await;
+        cx.executor().run_until_parked();
+
+        editor
+            .update(cx, |editor, _window, cx| {
+                let expected_hints = vec!["i32".to_string(), "Vec<HashM…".to_string()];
+                assert_eq!(
+                    expected_hints,
+                    cached_hint_labels(editor),
+                    "Should hide excluded and suppressed hints, and truncate long ones"
+                );
+                assert_eq!(expected_hints, visible_hint_labels(editor, cx));
+            })
+            .unwrap();
+
+        update_test_language_settings(cx, |settings| {
+            let inlay_hints = settings.defaults.inlay_hints.get_or_insert_default();
+            inlay_hints.max_hint_length = Some(0);
+            inlay_hints.excluded_kinds_per_server = Some(HashMap::default());
+            inlay_hints.suppress_patterns = Some(Vec::new());
+        });
+        cx.executor().run_until_parked();
+        editor
+            .update(cx, |editor, _window, cx| {
+                let expected_hints = vec![
+                    "i32".to_string(),
+                    "name".to_string(),
+                    "impl Fn(usize) -> usize".to_string(),
+                    "Vec<HashMap<String, usize>>".to_string(),
+                ];
+                assert_eq!(
+                    expected_hints,
+                    cached_hint_labels(editor),
+                    "Should query hints again when the filtering settings change"
+                );
+                assert_eq!(expected_hints, visible_hint_labels(editor, cx));
+            })
+            .unwrap();
+    }
+
     pub(crate) fn init_test(cx: &mut TestAppContext, f: impl Fn(&mut AllLanguageSettingsContent)) {
         cx.update(|cx| {
             let settings_store = SettingsStore::test(cx);
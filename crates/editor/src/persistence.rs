@@ -104,6 +104,7 @@ impl Domain for EditorDb {
     //   language: Option<String>,
     //   mtime_seconds: Option<i64>,
     //   mtime_nanos: Option<i32>,
+    //   soft_wrap: Option<String>,
     // )
     //
     // editor_selections(
@@ -197,6 +198,9 @@ impl Domain for EditorDb {
                 ON DELETE CASCADE
             ) STRICT;
         ),
+        sql! (
+            ALTER TABLE editors ADD COLUMN soft_wrap TEXT;
+        ),
     ];
 }
 
@@ -259,6 +263,26 @@ impl EditorDb {
         }
     }
 
+    query! {
+        pub fn get_soft_wrap(item_id: ItemId, workspace_id: WorkspaceId) -> Result<Option<String>> {
+            SELECT soft_wrap
+            FROM editors
+            WHERE item_id = ? AND workspace_id = ? AND soft_wrap IS NOT NULL
+        }
+    }
+
+    query! {
+        pub async fn save_soft_wrap(
+            item_id: ItemId,
+            workspace_id: WorkspaceId,
+            soft_wrap: Option<String>
+        ) -> Result<()> {
+            UPDATE OR IGNORE editors
+            SET soft_wrap = ?3
+            WHERE item_id = ?1 AND workspace_id = ?2
+        }
+    }
+
     query! {
         pub fn get_editor_selections(
             editor_id: ItemId,
@@ -465,4 +489,33 @@ mod tests {
             .unwrap();
         assert_eq!(have, serialized_editor);
     }
+
+    #[gpui::test]
+    async fn test_save_and_get_soft_wrap() {
+        let workspace_id = workspace::WORKSPACE_DB.next_id().await.unwrap();
+        DB.save_serialized_editor(1234, workspace_id, SerializedEditor::default())
+            .await
+            .unwrap();
+        assert_eq!(DB.get_soft_wrap(1234, workspace_id).unwrap(), None);
+
+        DB.save_soft_wrap(1234, workspace_id, Some("editor_width".to_owned()))
+            .await
+            .unwrap();
+        assert_eq!(
+            DB.get_soft_wrap(1234, workspace_id).unwrap().as_deref(),
+            Some("editor_width")
+        );
+
+        // Saving the editor again keeps the soft wrap.
+        DB.save_serialized_editor(1234, workspace_id, SerializedEditor::default())
+            .await
+            .unwrap();
+        assert_eq!(
+            DB.get_soft_wrap(1234, workspace_id).unwrap().as_deref(),
+            Some("editor_width")
+        );
+
+        DB.save_soft_wrap(1234, workspace_id, None).await.unwrap();
+        assert_eq!(DB.get_soft_wrap(1234, workspace_id).unwrap(), None);
+    }
 }
@@ -0,0 +1,344 @@
+use crate::{Editor, EditorEvent, multibuffer_context_lines};
+use anyhow::Result;
+use collections::HashSet;
+use gpui::{App, Entity, EventEmitter, Focusable, Render, Subscription, Task};
+use language::{Buffer, Capability, Point};
+use multi_buffer::{MultiBuffer, PathKey};
+use project::{Project, ProjectTransaction};
+use std::any::TypeId;
+use text::TransactionId;
+use ui::{Checkbox, ToggleState, Tooltip, prelude::*};
+use workspace::{
+    Item,
+    item::{ItemEvent, SaveOptions},
+    searchable::SearchableItemHandle,
+};
+
+/// Shows the edits of a rename that touched several files, so that files can be left out of the
+/// rename before it is saved, or the whole rename can be reverted at once.
+///
+/// The language server's edits are already applied to the buffers when the preview opens, so
+/// leaving a file out undoes its edits and including it again redoes them.
+pub struct RenamePreview {
+    editor: Entity<Editor>,
+    multibuffer: Entity<MultiBuffer>,
+    project: Entity<Project>,
+    title: SharedString,
+    files: Vec<RenamePreviewFile>,
+    /// The multibuffer transaction that groups the edits of every file, so that a single undo
+    /// reverts the rename across all of them.
+    transaction_id: Option<TransactionId>,
+    _editor_subscription: Subscription,
+}
+
+struct RenamePreviewFile {
+    buffer: Entity<Buffer>,
+    transaction_id: TransactionId,
+    path: SharedString,
+    included: bool,
+}
+
+impl RenamePreview {
+    pub fn new(
+        title: impl Into<SharedString>,
+        transaction: ProjectTransaction,
+        project: Entity<Project>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let title = title.into();
+        let mut entries = transaction.0.into_iter().collect::<Vec<_>>();
+        entries.sort_unstable_by_key(|(buffer, _)| {
+            buffer.read(cx).file().map(|file| file.path().clone())
+        });
+
+        let mut ranges_to_highlight = Vec::new();
+        let multibuffer = cx.new(|cx| {
+            let mut multibuffer =
+                MultiBuffer::new(Capability::ReadWrite).with_title(title.to_string());
+            for (buffer, transaction) in &entries {
+                let edited_ranges = buffer
+                    .read(cx)
+                    .edited_ranges_for_transaction::<Point>(transaction)
+                    .collect::<Vec<_>>();
+                let (ranges, _) = multibuffer.set_excerpts_for_path(
+                    PathKey::for_buffer(buffer, cx),
+                    buffer.clone(),
+                    edited_ranges,
+                    multibuffer_context_lines(cx),
+                    cx,
+                );
+                ranges_to_highlight.extend(ranges);
+            }
+            multibuffer.push_transaction(
+                entries
+                    .iter()
+                    .map(|(buffer, transaction)| (buffer, transaction)),
+                cx,
+            );
+            multibuffer
+        });
+        let transaction_id = multibuffer.read(cx).last_transaction_id(cx);
+
+        let editor = cx.new(|cx| {
+            let mut editor =
+                Editor::for_multibuffer(multibuffer.clone(), Some(project.clone()), window, cx);
+            editor.highlight_background::<Self>(
+                &ranges_to_highlight,
+                |theme| theme.colors().editor_highlighted_line_background,
+                cx,
+            );
+            editor
+        });
+        let editor_subscription = cx.subscribe(&editor, |_, _, event: &EditorEvent, cx| {
+            Editor::to_item_events(event, |item_event| cx.emit(item_event))
+        });
+
+        let files = entries
+            .into_iter()
+            .map(|(buffer, transaction)| {
+                let path = buffer
+                    .read(cx)
+                    .file()
+                    .map(|file| file.path().display(file.path_style(cx)).into_owned())
+                    .unwrap_or_else(|| "untitled".to_string());
+                RenamePreviewFile {
+                    buffer,
+                    transaction_id: transaction.id,
+                    path: path.into(),
+                    included: true,
+                }
+            })
+            .collect();
+
+        Self {
+            editor,
+            multibuffer,
+            project,
+            title,
+            files,
+            transaction_id,
+            _editor_subscription: editor_subscription,
+        }
+    }
+
+    pub fn is_file_included(&self, buffer: &Entity<Buffer>) -> bool {
+        self.files
+            .iter()
+            .any(|file| &file.buffer == buffer && file.included)
+    }
+
+    /// Leaves a file out of the rename by undoing its edits, or includes it again by redoing them.
+    pub fn toggle_file(&mut self, buffer: &Entity<Buffer>, cx: &mut Context<Self>) {
+        let Some(file) = self.files.iter_mut().find(|file| &file.buffer == buffer) else {
+            return;
+        };
+        let transaction_id = file.transaction_id;
+        let included = file.included;
+        let changed = file.buffer.update(cx, |buffer, cx| {
+            if included {
+                buffer.undo_transaction(transaction_id, cx)
+            } else {
+                buffer.redo_to_transaction(transaction_id, cx)
+            }
+        });
+        // The edits can't be found in the buffer's history once it was edited in a way that
+        // discarded them, in which case the file is left as it is.
+        if changed {
+            file.included = !included;
+        }
+        cx.notify();
+    }
+
+    /// Saves the files that are part of the rename and closes the preview.
+    pub fn apply(&mut self, window: &mut Window, cx: &mut Context<Self>) -> Task<Result<()>> {
+        let buffers = self
+            .files
+            .iter()
+            .filter(|file| file.included)
+            .map(|file| file.buffer.clone())
+            .collect::<HashSet<_>>();
+        let save = self
+            .project
+            .update(cx, |project, cx| project.save_buffers(buffers, cx));
+        cx.spawn_in(window, async move |this, cx| {
+            save.await?;
+            this.update(cx, |_, cx| cx.emit(ItemEvent::CloseItem))
+        })
+    }
+
+    /// Reverts the edits of every file as a single undo and closes the preview.
+    pub fn revert(&mut self, cx: &mut Context<Self>) {
+        if let Some(transaction_id) = self.transaction_id.take() {
+            self.multibuffer.update(cx, |multibuffer, cx| {
+                multibuffer.undo_transaction(transaction_id, cx)
+            });
+        }
+        for file in &mut self.files {
+            file.included = false;
+        }
+        cx.emit(ItemEvent::CloseItem);
+    }
+
+    fn render_header(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let included_count = self.files.iter().filter(|file| file.included).count();
+        h_flex()
+            .px_2()
+            .py_1()
+            .gap_3()
+            .flex_wrap()
+            .border_b_1()
+            .border_color(cx.theme().colors().border_variant)
+            .bg(cx.theme().colors().editor_background)
+            .child(
+                Label::new(format!(
+                    "Renaming in {included_count} of {} files",
+                    self.files.len()
+                ))
+                .size(LabelSize::Small)
+                .color(Color::Muted),
+            )
+            .children(self.files.iter().enumerate().map(|(index, file)| {
+                let buffer = file.buffer.clone();
+                let this = cx.weak_entity();
+                Checkbox::new(("rename-file", index), ToggleState::from(file.included))
+                    .label(file.path.clone())
+                    .tooltip(Tooltip::text(format!("Rename in {}", file.path)))
+                    .on_click(move |_, _, cx| {
+                        this.update(cx, |this, cx| this.toggle_file(&buffer, cx))
+                            .ok();
+                    })
+            }))
+            .child(
+                h_flex()
+                    .ml_auto()
+                    .gap_1()
+                    .child(
+                        Button::new("revert-rename", "Revert")
+                            .label_size(LabelSize::Small)
+                            .tooltip(Tooltip::text("Undo the rename in every file"))
+                            .on_click(cx.listener(|this, _, _, cx| this.revert(cx))),
+                    )
+                    .child(
+                        Button::new("apply-rename", "Apply")
+                            .label_size(LabelSize::Small)
+                            .style(ButtonStyle::Filled)
+                            .disabled(included_count == 0)
+                            .tooltip(Tooltip::text("Save the renamed files"))
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.apply(window, cx).detach_and_log_err(cx)
+                            })),
+                    ),
+            )
+    }
+}
+
+impl Render for RenamePreview {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .size_full()
+            .key_context("RenamePreview")
+            .child(self.render_header(cx))
+            .child(self.editor.clone())
+    }
+}
+
+impl Focusable for RenamePreview {
+    fn focus_handle(&self, cx: &App) -> gpui::FocusHandle {
+        self.editor.focus_handle(cx)
+    }
+}
+
+impl EventEmitter<ItemEvent> for RenamePreview {}
+
+impl Item for RenamePreview {
+    type Event = ItemEvent;
+
+    fn to_item_events(event: &Self::Event, mut f: impl FnMut(ItemEvent)) {
+        f(*event)
+    }
+
+    fn tab_icon(&self, _window: &Window, _cx: &App) -> Option<Icon> {
+        Some(Icon::new(IconName::Pencil))
+    }
+
+    fn tab_content_text(&self, _detail: usize, _cx: &App) -> SharedString {
+        self.title.clone()
+    }
+
+    fn as_searchable(&self, _: &Entity<Self>) -> Option<Box<dyn SearchableItemHandle>> {
+        Some(Box::new(self.editor.clone()))
+    }
+
+    fn act_as_type<'a>(
+        &'a self,
+        type_id: TypeId,
+        self_handle: &'a Entity<Self>,
+        _: &'a App,
+    ) -> Option<gpui::AnyView> {
+        if type_id == TypeId::of::<Self>() {
+            Some(self_handle.to_any())
+        } else if type_id == TypeId::of::<Editor>() {
+            Some(self.editor.to_any())
+        } else {
+            None
+        }
+    }
+
+    fn added_to_workspace(
+        &mut self,
+        workspace: &mut workspace::Workspace,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.editor.update(cx, |editor, cx| {
+            Item::added_to_workspace(editor, workspace, window, cx)
+        });
+    }
+
+    fn deactivated(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.editor
+            .update(cx, |editor, cx| editor.deactivated(window, cx));
+    }
+
+    fn navigate(
+        &mut self,
+        data: Box<dyn std::any::Any>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        self.editor
+            .update(cx, |editor, cx| Item::navigate(editor, data, window, cx))
+    }
+
+    fn set_nav_history(
+        &mut self,
+        nav_history: workspace::ItemNavHistory,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.editor.update(cx, |editor, cx| {
+            Item::set_nav_history(editor, nav_history, window, cx)
+        });
+    }
+
+    fn is_dirty(&self, cx: &App) -> bool {
+        self.editor.read(cx).is_dirty(cx)
+    }
+
+    fn can_save(&self, cx: &App) -> bool {
+        self.editor.read(cx).can_save(cx)
+    }
+
+    fn save(
+        &mut self,
+        options: SaveOptions,
+        project: Entity<Project>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        self.editor.update(cx, |editor, cx| {
+            Item::save(editor, options, project, window, cx)
+        })
+    }
+}
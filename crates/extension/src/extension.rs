@@ -168,6 +168,14 @@ pub trait Extension: Send + Sync + 'static {
         locator_name: String,
         config: SpawnInTerminal,
     ) -> Result<DebugRequest>;
+
+    async fn panel_items(
+        &self,
+        panel_id: Arc<str>,
+        parent_item_id: Option<String>,
+    ) -> Result<Vec<PanelItem>>;
+
+    async fn handle_panel_event(&self, panel_id: Arc<str>, event: PanelEvent) -> Result<()>;
 }
 
 pub fn parse_wasm_extension_version(
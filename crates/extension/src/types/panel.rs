@@ -0,0 +1,36 @@
+/// An item in a panel contributed by an extension.
+#[derive(Debug, Clone)]
+pub struct PanelItem {
+    /// The ID of the item, unique within the panel.
+    pub id: String,
+    /// The label to display for the item.
+    pub label: String,
+    /// Secondary text to display next to the label.
+    pub detail: Option<String>,
+    /// The name of the icon to display before the label.
+    pub icon: Option<String>,
+    /// The absolute path of a file to open when the item is activated.
+    pub path: Option<String>,
+    /// Whether the item has children, which are requested when the item is expanded.
+    pub expandable: bool,
+    /// The commands that can be run on this item.
+    pub commands: Vec<PanelCommand>,
+}
+
+/// A command that can be run on a panel item.
+#[derive(Debug, Clone)]
+pub struct PanelCommand {
+    /// The ID of the command.
+    pub id: String,
+    /// The label to display for the command.
+    pub label: String,
+}
+
+/// An event that occurred in a panel contributed by an extension.
+#[derive(Debug, Clone)]
+pub enum PanelEvent {
+    /// The item with the given ID was activated.
+    ItemActivated(String),
+    /// The command with the given ID was run on the item with the given ID.
+    CommandRun { item_id: String, command_id: String },
+}
@@ -33,6 +33,9 @@ pub use wit::{
         node_binary_path, npm_install_package, npm_package_installed_version,
         npm_package_latest_version,
     },
+    zed::extension::panel::{
+        PanelCommand, PanelCommandInvocation, PanelEvent, PanelItem, refresh_panel,
+    },
     zed::extension::platform::{Architecture, Os, current_platform},
     zed::extension::slash_command::{
         SlashCommand, SlashCommandArgumentCompletion, SlashCommandOutput, SlashCommandOutputSection,
@@ -259,6 +262,24 @@ pub trait Extension: Send + Sync {
     ) -> Result<DebugRequest, String> {
         Err("`run_dap_locator` not implemented".to_string())
     }
+
+    /// Returns the items to display in the specified panel beneath the given parent item.
+    ///
+    /// The top-level items of the panel are requested without a parent item.
+    fn panel_items(
+        &mut self,
+        _panel_id: String,
+        _parent_item_id: Option<String>,
+    ) -> Result<Vec<PanelItem>, String> {
+        Err("`panel_items` not implemented".to_string())
+    }
+
+    /// Handles an event that occurred in the specified panel.
+    ///
+    /// Call [`refresh_panel`] to have Zed request the panel's items again after handling the event.
+    fn handle_panel_event(&mut self, _panel_id: String, _event: PanelEvent) -> Result<(), String> {
+        Ok(())
+    }
 }
 
 /// Registers the provided type as a Zed extension.
@@ -334,7 +355,7 @@ mod wit {
 
     wit_bindgen::generate!({
         skip: ["init-extension"],
-        path: "./wit/since_v0.6.0",
+        path: "./wit/since_v0.8.0",
     });
 }
 
@@ -518,6 +539,17 @@ impl wit::Guest for Component {
     ) -> Result<DebugRequest, String> {
         extension().run_dap_locator(locator_name, build_task)
     }
+
+    fn panel_items(
+        panel_id: String,
+        parent_item_id: Option<String>,
+    ) -> Result<Vec<PanelItem>, String> {
+        extension().panel_items(panel_id, parent_item_id)
+    }
+
+    fn handle_panel_event(panel_id: String, event: PanelEvent) -> Result<(), String> {
+        extension().handle_panel_event(panel_id, event)
+    }
 }
 
 /// The ID of a language server.
@@ -6,20 +6,33 @@ use url::Url;
 
 pub struct CapabilityGranter {
     granted_capabilities: Vec<ExtensionCapability>,
+    /// The capabilities the user has granted to this extension, which are empty until they
+    /// have been prompted.
+    extension_grants: Vec<ExtensionCapability>,
     manifest: Arc<ExtensionManifest>,
 }
 
 impl CapabilityGranter {
     pub fn new(
         granted_capabilities: Vec<ExtensionCapability>,
+        extension_grants: Vec<ExtensionCapability>,
         manifest: Arc<ExtensionManifest>,
     ) -> Self {
         Self {
             granted_capabilities,
+            extension_grants,
             manifest,
         }
     }
 
+    /// Returns whether a capability matching `allows` is granted both by the extension host
+    /// and by the user to this extension. The user is prompted for the capabilities that the
+    /// extension's manifest declares, so an extension that doesn't declare a capability can only
+    /// use it if the user grants it in their settings.
+    fn is_granted(&self, allows: impl Fn(&ExtensionCapability) -> bool) -> bool {
+        self.granted_capabilities.iter().any(&allows) && self.extension_grants.iter().any(&allows)
+    }
+
     pub fn grant_exec(
         &self,
         desired_command: &str,
@@ -27,15 +40,12 @@ impl CapabilityGranter {
     ) -> Result<()> {
         self.manifest.allow_exec(desired_command, desired_args)?;
 
-        let is_allowed = self
-            .granted_capabilities
-            .iter()
-            .any(|capability| match capability {
-                ExtensionCapability::ProcessExec(capability) => {
-                    capability.allows(desired_command, desired_args)
-                }
-                _ => false,
-            });
+        let is_allowed = self.is_granted(|capability| match capability {
+            ExtensionCapability::ProcessExec(capability) => {
+                capability.allows(desired_command, desired_args)
+            }
+            _ => false,
+        });
 
         if !is_allowed {
             bail!(
@@ -47,13 +57,10 @@ impl CapabilityGranter {
     }
 
     pub fn grant_download_file(&self, desired_url: &Url) -> Result<()> {
-        let is_allowed = self
-            .granted_capabilities
-            .iter()
-            .any(|capability| match capability {
-                ExtensionCapability::DownloadFile(capability) => capability.allows(desired_url),
-                _ => false,
-            });
+        let is_allowed = self.is_granted(|capability| match capability {
+            ExtensionCapability::DownloadFile(capability) => capability.allows(desired_url),
+            _ => false,
+        });
 
         if !is_allowed {
             bail!(
@@ -65,15 +72,10 @@ impl CapabilityGranter {
     }
 
     pub fn grant_npm_install_package(&self, package_name: &str) -> Result<()> {
-        let is_allowed = self
-            .granted_capabilities
-            .iter()
-            .any(|capability| match capability {
-                ExtensionCapability::NpmInstallPackage(capability) => {
-                    capability.allows(package_name)
-                }
-                _ => false,
-            });
+        let is_allowed = self.is_granted(|capability| match capability {
+            ExtensionCapability::NpmInstallPackage(capability) => capability.allows(package_name),
+            _ => false,
+        });
 
         if !is_allowed {
             bail!("capability for npm:install {package_name} is not granted by the extension host",);
@@ -87,7 +89,9 @@ impl CapabilityGranter {
 mod tests {
     use std::collections::BTreeMap;
 
-    use extension::{ProcessExecCapability, SchemaVersion};
+    use extension::{
+        DownloadFileCapability, NpmInstallPackageCapability, ProcessExecCapability, SchemaVersion,
+    };
 
     use super::*;
 
@@ -112,6 +116,7 @@ mod tests {
             capabilities: vec![],
             debug_adapters: Default::default(),
             debug_locators: Default::default(),
+            panels: Default::default(),
         }
     }
 
@@ -126,7 +131,8 @@ mod tests {
         });
 
         // It returns an error when the extension host has no granted capabilities.
-        let granter = CapabilityGranter::new(Vec::new(), manifest.clone());
+        let granter =
+            CapabilityGranter::new(Vec::new(), manifest.capabilities.clone(), manifest.clone());
         assert!(granter.grant_exec("ls", &["-la"]).is_err());
 
         // It succeeds when the extension host has the exact capability.
@@ -135,6 +141,7 @@ mod tests {
                 command: "ls".to_string(),
                 args: vec!["-la".to_string()],
             })],
+            manifest.capabilities.clone(),
             manifest.clone(),
         );
         assert!(granter.grant_exec("ls", &["-la"]).is_ok());
@@ -145,8 +152,100 @@ mod tests {
                 command: "*".to_string(),
                 args: vec!["**".to_string()],
             })],
+            manifest.capabilities.clone(),
+            manifest,
+        );
+        assert!(granter.grant_exec("ls", &["-la"]).is_ok());
+    }
+
+    #[test]
+    fn test_grant_exec_with_extension_grants() {
+        let ls_capability = ExtensionCapability::ProcessExec(ProcessExecCapability {
+            command: "ls".to_string(),
+            args: vec!["-la".to_string()],
+        });
+        let wildcard_capability = ExtensionCapability::ProcessExec(ProcessExecCapability {
+            command: "*".to_string(),
+            args: vec!["**".to_string()],
+        });
+        let manifest = Arc::new(ExtensionManifest {
+            capabilities: vec![ls_capability.clone()],
+            ..extension_manifest()
+        });
+
+        // It returns an error when the user has not granted the capability to the extension.
+        let granter = CapabilityGranter::new(
+            vec![wildcard_capability.clone()],
+            Vec::new(),
+            manifest.clone(),
+        );
+        assert!(granter.grant_exec("ls", &["-la"]).is_err());
+
+        // It succeeds when the user has granted the capability to the extension.
+        let granter = CapabilityGranter::new(
+            vec![wildcard_capability],
+            vec![ls_capability.clone()],
+            manifest.clone(),
+        );
+        assert!(granter.grant_exec("ls", &["-la"]).is_ok());
+
+        // It returns an error when the extension host does not grant the capability, even
+        // if the user has.
+        let granter = CapabilityGranter::new(Vec::new(), vec![ls_capability], manifest);
+        assert!(granter.grant_exec("ls", &["-la"]).is_err());
+    }
+
+    #[test]
+    fn test_ungranted_extension_is_denied() {
+        let exec_capability = ExtensionCapability::ProcessExec(ProcessExecCapability {
+            command: "*".to_string(),
+            args: vec!["**".to_string()],
+        });
+        let download_capability = ExtensionCapability::DownloadFile(DownloadFileCapability {
+            host: "*".to_string(),
+            path: vec!["**".to_string()],
+        });
+        let manifest = Arc::new(ExtensionManifest {
+            capabilities: vec![exec_capability.clone(), download_capability.clone()],
+            ..extension_manifest()
+        });
+        let url = Url::parse("https://example.com/server.tar.gz").unwrap();
+
+        // An extension the user hasn't been prompted about has no grants, so everything it
+        // declares is refused even though the extension host allows it.
+        let granter = CapabilityGranter::new(
+            vec![exec_capability.clone(), download_capability.clone()],
+            Vec::new(),
+            manifest.clone(),
+        );
+        assert!(granter.grant_exec("ls", &["-la"]).is_err());
+        assert!(granter.grant_download_file(&url).is_err());
+
+        // Kinds of capabilities the extension doesn't declare are refused too, unless the user
+        // grants them.
+        let npm_capability = ExtensionCapability::NpmInstallPackage(NpmInstallPackageCapability {
+            package: "*".to_string(),
+        });
+        let granter = CapabilityGranter::new(
+            vec![download_capability.clone(), npm_capability.clone()],
+            Vec::new(),
+            manifest.clone(),
+        );
+        assert!(granter.grant_npm_install_package("typescript").is_err());
+        let granter = CapabilityGranter::new(
+            vec![download_capability.clone(), npm_capability.clone()],
+            vec![npm_capability],
+            manifest.clone(),
+        );
+        assert!(granter.grant_npm_install_package("typescript").is_ok());
+
+        // Once the user grants the capabilities, they are allowed.
+        let granter = CapabilityGranter::new(
+            vec![exec_capability.clone(), download_capability.clone()],
+            vec![exec_capability, download_capability],
             manifest,
         );
         assert!(granter.grant_exec("ls", &["-la"]).is_ok());
+        assert!(granter.grant_download_file(&url).is_ok());
     }
 }
@@ -17,10 +17,10 @@ use extension::extension_builder::{CompileExtensionOptions, ExtensionBuilder};
 use extension::{
     ExtensionContextServerProxy, ExtensionDebugAdapterProviderProxy, ExtensionEvents,
     ExtensionGrammarProxy, ExtensionHostProxy, ExtensionLanguageProxy,
-    ExtensionLanguageServerProxy, ExtensionSlashCommandProxy, ExtensionSnippetProxy,
-    ExtensionThemeProxy,
+    ExtensionLanguageServerProxy, ExtensionPanelProxy, ExtensionSlashCommandProxy,
+    ExtensionSnippetProxy, ExtensionThemeProxy,
 };
-use fs::{Fs, RemoveOptions};
+use fs::{Fs, RemoveOptions, copy_recursive};
 use futures::future::join_all;
 use futures::{
     AsyncReadExt as _, Future, FutureExt as _, StreamExt as _,
@@ -32,8 +32,8 @@ use futures::{
     select_biased,
 };
 use gpui::{
-    App, AppContext as _, AsyncApp, Context, Entity, EventEmitter, Global, Task, WeakEntity,
-    actions,
+    App, AppContext as _, AsyncApp, Context, Entity, EventEmitter, Global, Subscription, Task,
+    WeakEntity, actions,
 };
 use http_client::{AsyncBody, HttpClient, HttpClientWithUrl};
 use language::{
@@ -46,13 +46,16 @@ use release_channel::ReleaseChannel;
 use remote::{RemoteClient, RemoteConnectionOptions};
 use semantic_version::SemanticVersion;
 use serde::{Deserialize, Serialize};
-use settings::Settings;
+use settings::{Settings, SettingsStore};
 use std::ops::RangeInclusive;
 use std::str::FromStr;
 use std::{
     cmp::Ordering,
     path::{self, Path, PathBuf},
-    sync::Arc,
+    sync::{
+        Arc,
+        atomic::{self, AtomicUsize},
+    },
     time::{Duration, Instant},
 };
 use url::Url;
@@ -124,6 +127,7 @@ pub struct ExtensionStore {
     pub tasks: Vec<Task<()>>,
     pub remote_clients: HashMap<RemoteConnectionOptions, WeakEntity<RemoteClient>>,
     pub ssh_registered_tx: UnboundedSender<()>,
+    _settings_subscription: Subscription,
 }
 
 #[derive(Clone, Copy)]
@@ -138,6 +142,7 @@ pub enum Event {
     ExtensionsUpdated,
     StartedReloading,
     ExtensionInstalled(Arc<str>),
+    ExtensionUpgraded(Arc<str>),
     ExtensionUninstalled(Arc<str>),
     ExtensionFailedToLoad(Arc<str>),
 }
@@ -274,6 +279,33 @@ impl ExtensionStore {
 
             remote_clients: HashMap::default(),
             ssh_registered_tx: connection_registered_tx,
+            _settings_subscription: cx.observe_global::<SettingsStore>({
+                let mut extension_grants =
+                    ExtensionSettings::get_global(cx).extension_grants.clone();
+                move |this, cx| {
+                    let new_extension_grants = &ExtensionSettings::get_global(cx).extension_grants;
+                    if *new_extension_grants == extension_grants {
+                        return;
+                    }
+
+                    // Reload the extensions whose grants changed, so that they take effect.
+                    let changed_extension_ids = new_extension_grants
+                        .keys()
+                        .chain(extension_grants.keys())
+                        .filter(|extension_id| {
+                            new_extension_grants.get(*extension_id)
+                                != extension_grants.get(*extension_id)
+                        })
+                        .cloned()
+                        .collect::<HashSet<_>>();
+                    this.wasm_host
+                        .set_extension_grants(new_extension_grants.clone());
+                    for extension_id in changed_extension_ids {
+                        this.reload_tx.unbounded_send(Some(extension_id)).ok();
+                    }
+                    extension_grants = new_extension_grants.clone();
+                }
+            }),
         };
 
         // The extensions store maintains an index file, which contains a complete
@@ -392,6 +424,7 @@ impl ExtensionStore {
                         if let Some(path::Component::Normal(extension_dir_name)) =
                             event_path.components().next()
                             && let Some(extension_id) = extension_dir_name.to_str()
+                            && !extension_id.starts_with('.')
                         {
                             reload_tx.unbounded_send(Some(extension_id.into())).ok();
                         }
@@ -656,13 +689,25 @@ impl ExtensionStore {
         anyhow::Ok(())
     }
 
+    /// Builds the URL of an extension registry endpoint, using the self-hosted registry
+    /// configured in the settings if there is one.
+    fn build_registry_url(&self, path: &str, query: &[(&str, &str)], cx: &App) -> Result<Url> {
+        match ExtensionSettings::get_global(cx).registry_url.as_deref() {
+            Some(registry_url) => Ok(Url::parse_with_params(
+                &format!("{}{path}", registry_url.trim_end_matches('/')),
+                query,
+            )?),
+            None => self.http_client.build_zed_api_url(path, query),
+        }
+    }
+
     fn fetch_extensions_from_api(
         &self,
         path: &str,
         query: &[(&str, &str)],
         cx: &mut Context<ExtensionStore>,
     ) -> Task<Result<Vec<ExtensionMetadata>>> {
-        let url = self.http_client.build_zed_api_url(path, query);
+        let url = self.build_registry_url(path, query, cx);
         let http_client = self.http_client.clone();
         cx.spawn(async move |_, _| {
             let mut response = http_client
@@ -781,6 +826,11 @@ impl ExtensionStore {
                         }
                 })
                 .ok();
+            } else {
+                this.update(cx, |_, cx| {
+                    cx.emit(Event::ExtensionUpgraded(extension_id.clone()));
+                })
+                .ok();
             }
 
             anyhow::Ok(())
@@ -794,8 +844,7 @@ impl ExtensionStore {
         let wasm_api_versions = wasm_api_version_range(ReleaseChannel::global(cx));
 
         let Some(url) = self
-            .http_client
-            .build_zed_api_url(
+            .build_registry_url(
                 &format!("/extensions/{extension_id}/download"),
                 &[
                     ("min_schema_version", &schema_versions.start().to_string()),
@@ -806,6 +855,7 @@ impl ExtensionStore {
                     ),
                     ("max_wasm_api_version", &wasm_api_versions.end().to_string()),
                 ],
+                cx,
             )
             .log_err()
         else {
@@ -839,10 +889,10 @@ impl ExtensionStore {
     ) -> Task<Result<()>> {
         log::info!("installing extension {extension_id} {version}");
         let Some(url) = self
-            .http_client
-            .build_zed_api_url(
+            .build_registry_url(
                 &format!("/extensions/{extension_id}/{version}/download"),
                 &[],
+                cx,
             )
             .log_err()
         else {
@@ -931,6 +981,270 @@ impl ExtensionStore {
         })
     }
 
+    /// Installs an already-built extension from the local file system, such as one copied
+    /// into an air-gapped environment.
+    ///
+    /// The path may be either an extension directory or a `.tar.gz` archive as served by the
+    /// extension registry. Unlike dev extensions, the extension is not compiled and continues
+    /// to receive updates from the configured registry.
+    pub fn install_extension_from_path(
+        &mut self,
+        path: PathBuf,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        let task = self.install_built_extension(path, false, cx);
+        cx.background_spawn(async move { task.await.map(|_| ()) })
+    }
+
+    /// Installs an already-built extension, returning its ID, or `None` if `only_if_newer` is set
+    /// and the installed version is at least as new.
+    fn install_built_extension(
+        &mut self,
+        path: PathBuf,
+        only_if_newer: bool,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Option<Arc<str>>>> {
+        static NEXT_STAGING_DIR_ID: AtomicUsize = AtomicUsize::new(0);
+
+        let fs = self.fs.clone();
+        let installed_dir = self.installed_dir.clone();
+        // Each install is staged in its own directory, so that concurrent installs don't
+        // overwrite each other's files.
+        let staging_dir = self.installed_dir.join(format!(
+            ".staging-{}-{}",
+            std::process::id(),
+            NEXT_STAGING_DIR_ID.fetch_add(1, atomic::Ordering::SeqCst)
+        ));
+
+        cx.spawn(async move |this, cx| {
+            let remove_options = RemoveOptions {
+                recursive: true,
+                ignore_if_not_exists: true,
+            };
+            let result = Self::install_staged_extension(
+                &this,
+                fs.clone(),
+                &path,
+                &staging_dir,
+                &installed_dir,
+                only_if_newer,
+                cx,
+            )
+            .await;
+            // The staging directory has been moved into place if the install succeeded.
+            fs.remove_dir(&staging_dir, remove_options).await.log_err();
+            result
+        })
+    }
+
+    async fn install_staged_extension(
+        this: &WeakEntity<Self>,
+        fs: Arc<dyn Fs>,
+        path: &Path,
+        staging_dir: &Path,
+        installed_dir: &Path,
+        only_if_newer: bool,
+        cx: &mut AsyncApp,
+    ) -> Result<Option<Arc<str>>> {
+        let remove_options = RemoveOptions {
+            recursive: true,
+            ignore_if_not_exists: true,
+        };
+        if fs.is_dir(path).await {
+            copy_recursive(fs.as_ref(), path, staging_dir, Default::default())
+                .await
+                .with_context(|| format!("copying extension from {path:?}"))?;
+        } else {
+            let tar_gz_bytes = fs
+                .load_bytes(path)
+                .await
+                .with_context(|| format!("reading extension archive {path:?}"))?;
+            let body = GzipDecoder::new(BufReader::new(tar_gz_bytes.as_slice()));
+            futures::pin_mut!(body);
+            fs.extract_tar_file(staging_dir, Archive::new(body))
+                .await
+                .with_context(|| format!("extracting extension archive {path:?}"))?;
+        }
+
+        let manifest = ExtensionManifest::load(fs.clone(), staging_dir)
+            .await
+            .with_context(|| format!("no extension manifest found in {path:?}"))?;
+        let extension_id = manifest.id.clone();
+        // The ID names the extension's directory, which must stay within the installed
+        // extensions directory.
+        if extension_id.is_empty()
+            || extension_id.starts_with('.')
+            || extension_id.contains(['/', '\\'])
+            || extension_id.contains("..")
+        {
+            bail!("invalid extension id {extension_id:?} in {path:?}");
+        }
+
+        // Claim the extension before anything else can be installed over it, and only then
+        // look at what's installed.
+        let installed_entry = this.update(cx, |this, cx| {
+            let installed_entry = this
+                .extension_index
+                .extensions
+                .get(extension_id.as_ref())
+                .cloned();
+            let operation = if installed_entry.is_some() {
+                ExtensionOperation::Upgrade
+            } else {
+                ExtensionOperation::Install
+            };
+            match this.outstanding_operations.entry(extension_id.clone()) {
+                btree_map::Entry::Occupied(_) => return None,
+                btree_map::Entry::Vacant(e) => e.insert(operation),
+            };
+            cx.notify();
+            Some(installed_entry)
+        })?;
+        let Some(installed_entry) = installed_entry else {
+            return Ok(None);
+        };
+        let _finish = cx.on_drop(this, {
+            let extension_id = extension_id.clone();
+            move |this, cx| {
+                this.outstanding_operations.remove(extension_id.as_ref());
+                cx.notify();
+            }
+        });
+
+        if installed_entry
+            .as_ref()
+            .is_some_and(|index_entry| index_entry.dev)
+        {
+            bail!("extension {extension_id} is installed as a dev extension");
+        }
+        if only_if_newer
+            && let Some(installed_entry) = &installed_entry
+            && !is_newer_version(&manifest.version, &installed_entry.manifest.version)
+        {
+            return Ok(None);
+        }
+
+        let extension_dir = installed_dir.join(extension_id.as_ref());
+        fs.remove_dir(&extension_dir, remove_options).await?;
+        fs.rename(staging_dir, &extension_dir, Default::default())
+            .await
+            .with_context(|| format!("installing extension {extension_id}"))?;
+
+        this.update(cx, |this, cx| this.reload(Some(extension_id.clone()), cx))?
+            .await;
+
+        this.update(cx, |this, cx| {
+            if installed_entry.is_some() {
+                cx.emit(Event::ExtensionUpgraded(extension_id.clone()));
+                return;
+            }
+            cx.emit(Event::ExtensionInstalled(extension_id.clone()));
+            if let Some(events) = ExtensionEvents::try_global(cx)
+                && let Some(manifest) = this.extension_manifest_for_id(&extension_id)
+            {
+                events.update(cx, |this, cx| {
+                    this.emit(extension::Event::ExtensionInstalled(manifest.clone()), cx)
+                });
+            }
+        })?;
+
+        Ok(Some(extension_id))
+    }
+
+    /// Copies every installed extension, other than dev extensions, into `bundle_dir`, so that
+    /// they can be installed on machines without access to the extension registry using
+    /// [`Self::install_extensions_from_bundle`].
+    ///
+    /// Each extension is copied into a subdirectory named after its ID. Returns the number of
+    /// extensions that were exported.
+    pub fn export_extensions_bundle(
+        &self,
+        bundle_dir: PathBuf,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<usize>> {
+        let fs = self.fs.clone();
+        let extension_dirs = self
+            .extension_index
+            .extensions
+            .iter()
+            .filter(|(_, entry)| !entry.dev)
+            .map(|(extension_id, _)| {
+                (
+                    self.installed_dir.join(extension_id.as_ref()),
+                    bundle_dir.join(extension_id.as_ref()),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        cx.background_spawn(async move {
+            fs.create_dir(&bundle_dir)
+                .await
+                .with_context(|| format!("creating extension bundle {bundle_dir:?}"))?;
+            for (extension_dir, bundled_dir) in &extension_dirs {
+                fs.remove_dir(
+                    bundled_dir,
+                    RemoveOptions {
+                        recursive: true,
+                        ignore_if_not_exists: true,
+                    },
+                )
+                .await?;
+                copy_recursive(fs.as_ref(), extension_dir, bundled_dir, Default::default())
+                    .await
+                    .with_context(|| format!("exporting extension from {extension_dir:?}"))?;
+            }
+            Ok(extension_dirs.len())
+        })
+    }
+
+    /// Installs the extensions in a bundle created by [`Self::export_extensions_bundle`], or in any
+    /// directory of extension directories and `.tar.gz` archives, such as a mirror of the
+    /// extension registry's downloads.
+    ///
+    /// Extensions that are already installed are upgraded when the bundle has a newer version.
+    /// Returns the IDs of the extensions that were installed or upgraded.
+    pub fn install_extensions_from_bundle(
+        &mut self,
+        bundle_dir: PathBuf,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<Arc<str>>>> {
+        let fs = self.fs.clone();
+        cx.spawn(async move |this, cx| {
+            let mut entries = fs
+                .read_dir(&bundle_dir)
+                .await
+                .with_context(|| format!("reading extension bundle {bundle_dir:?}"))?;
+            let mut paths = Vec::new();
+            while let Some(path) = entries.next().await {
+                let path = path?;
+                let is_archive = path
+                    .file_name()
+                    .and_then(|file_name| file_name.to_str())
+                    .is_some_and(|file_name| file_name.ends_with(".tar.gz"));
+                if is_archive || fs.is_file(&path.join("extension.toml")).await {
+                    paths.push(path);
+                }
+            }
+            paths.sort();
+
+            // Extensions are installed one at a time because they share a staging directory.
+            let mut installed_extension_ids = Vec::new();
+            for path in paths {
+                let task = this.update(cx, |this, cx| {
+                    this.install_built_extension(path.clone(), true, cx)
+                })?;
+                match task.await {
+                    Ok(Some(extension_id)) => installed_extension_ids.push(extension_id),
+                    Ok(None) => {}
+                    Err(error) => {
+                        log::error!("failed to install extension from bundle {path:?}: {error:#}")
+                    }
+                }
+            }
+            Ok(installed_extension_ids)
+        })
+    }
+
     pub fn install_dev_extension(
         &mut self,
         extension_source_path: PathBuf,
@@ -1204,6 +1518,10 @@ impl ExtensionStore {
             for command_name in extension.manifest.slash_commands.keys() {
                 self.proxy.unregister_slash_command(command_name.clone());
             }
+            for panel_id in extension.manifest.panels.keys() {
+                self.proxy
+                    .unregister_panel(extension.manifest.id.clone(), panel_id.clone(), cx);
+            }
         }
 
         self.wasm_extensions
@@ -1408,6 +1726,15 @@ impl ExtensionStore {
                             .register_context_server(extension.clone(), id.clone(), cx);
                     }
 
+                    for (panel_id, panel) in &manifest.panels {
+                        this.proxy.register_panel(
+                            extension.clone(),
+                            panel_id.clone(),
+                            panel.clone(),
+                            cx,
+                        );
+                    }
+
                     for (debug_adapter, meta) in &manifest.debug_adapters {
                         let mut path = root_dir.clone();
                         path.push(Path::new(manifest.id.as_ref()));
@@ -1466,9 +1793,11 @@ impl ExtensionStore {
                         continue;
                     };
 
+                    // Skips `.DS_Store` files, as well as the staging directory used while
+                    // installing extensions from the local file system.
                     if extension_dir
                         .file_name()
-                        .is_some_and(|file_name| file_name == ".DS_Store")
+                        .is_some_and(|file_name| file_name.to_string_lossy().starts_with('.'))
                     {
                         continue;
                     }
@@ -1820,6 +2149,18 @@ impl ExtensionStore {
     }
 }
 
+/// Returns whether `version` is newer than `installed_version`, treating versions that aren't
+/// semantic versions as newer whenever they differ.
+fn is_newer_version(version: &str, installed_version: &str) -> bool {
+    match (
+        SemanticVersion::from_str(version),
+        SemanticVersion::from_str(installed_version),
+    ) {
+        (Ok(version), Ok(installed_version)) => version > installed_version,
+        _ => version != installed_version,
+    }
+}
+
 fn load_plugin_queries(root_path: &Path) -> LanguageQueries {
     let mut result = LanguageQueries::default();
     if let Some(entries) = std::fs::read_dir(root_path).log_err() {
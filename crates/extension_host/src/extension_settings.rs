@@ -1,8 +1,9 @@
 use collections::HashMap;
 use extension::{
-    DownloadFileCapability, ExtensionCapability, NpmInstallPackageCapability, ProcessExecCapability,
+    DownloadFileCapability, ExtensionCapability, ExtensionManifest, NpmInstallPackageCapability,
+    ProcessExecCapability,
 };
-use settings::Settings;
+use settings::{ExtensionCapabilityContent, Settings};
 use std::sync::Arc;
 
 #[derive(Debug, Default, Clone)]
@@ -16,6 +17,10 @@ pub struct ExtensionSettings {
     pub auto_install_extensions: HashMap<Arc<str>, bool>,
     pub auto_update_extensions: HashMap<Arc<str>, bool>,
     pub granted_capabilities: Vec<ExtensionCapability>,
+    /// The capabilities the user has granted to individual extensions.
+    pub extension_grants: HashMap<Arc<str>, Vec<ExtensionCapability>>,
+    /// The URL of a self-hosted extension registry to use instead of Zed's.
+    pub registry_url: Option<String>,
 }
 
 impl ExtensionSettings {
@@ -33,6 +38,22 @@ impl ExtensionSettings {
             .copied()
             .unwrap_or(true)
     }
+
+    /// Returns the capabilities declared by the given extension that the user has not yet
+    /// granted to it.
+    pub fn ungranted_capabilities(&self, manifest: &ExtensionManifest) -> Vec<ExtensionCapability> {
+        let grants = self
+            .extension_grants
+            .get(&manifest.id)
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        manifest
+            .capabilities
+            .iter()
+            .filter(|capability| !grants.contains(capability))
+            .cloned()
+            .collect()
+    }
 }
 
 impl Settings for ExtensionSettings {
@@ -46,20 +67,55 @@ impl Settings for ExtensionSettings {
                 .clone()
                 .unwrap_or_default()
                 .into_iter()
-                .map(|capability| match capability {
-                    settings::ExtensionCapabilityContent::ProcessExec { command, args } => {
-                        ExtensionCapability::ProcessExec(ProcessExecCapability { command, args })
-                    }
-                    settings::ExtensionCapabilityContent::DownloadFile { host, path } => {
-                        ExtensionCapability::DownloadFile(DownloadFileCapability { host, path })
-                    }
-                    settings::ExtensionCapabilityContent::NpmInstallPackage { package } => {
-                        ExtensionCapability::NpmInstallPackage(NpmInstallPackageCapability {
-                            package,
-                        })
-                    }
+                .map(capability_from_content)
+                .collect(),
+            extension_grants: content
+                .extension
+                .extension_capability_grants
+                .iter()
+                .map(|(extension_id, capabilities)| {
+                    let capabilities = capabilities
+                        .iter()
+                        .cloned()
+                        .map(capability_from_content)
+                        .collect();
+                    (extension_id.clone(), capabilities)
                 })
                 .collect(),
+            registry_url: content
+                .extension
+                .extension_registry_url
+                .clone()
+                .filter(|url| !url.trim().is_empty()),
+        }
+    }
+}
+
+fn capability_from_content(capability: ExtensionCapabilityContent) -> ExtensionCapability {
+    match capability {
+        ExtensionCapabilityContent::ProcessExec { command, args } => {
+            ExtensionCapability::ProcessExec(ProcessExecCapability { command, args })
+        }
+        ExtensionCapabilityContent::DownloadFile { host, path } => {
+            ExtensionCapability::DownloadFile(DownloadFileCapability { host, path })
+        }
+        ExtensionCapabilityContent::NpmInstallPackage { package } => {
+            ExtensionCapability::NpmInstallPackage(NpmInstallPackageCapability { package })
+        }
+    }
+}
+
+/// Converts an [`ExtensionCapability`] into the form in which it is stored in the settings.
+pub fn capability_to_content(capability: &ExtensionCapability) -> ExtensionCapabilityContent {
+    match capability.clone() {
+        ExtensionCapability::ProcessExec(ProcessExecCapability { command, args }) => {
+            ExtensionCapabilityContent::ProcessExec { command, args }
+        }
+        ExtensionCapability::DownloadFile(DownloadFileCapability { host, path }) => {
+            ExtensionCapabilityContent::DownloadFile { host, path }
+        }
+        ExtensionCapability::NpmInstallPackage(NpmInstallPackageCapability { package }) => {
+            ExtensionCapabilityContent::NpmInstallPackage { package }
         }
     }
 }
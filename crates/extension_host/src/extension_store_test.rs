@@ -2,13 +2,14 @@ use crate::{
     Event, ExtensionIndex, ExtensionIndexEntry, ExtensionIndexLanguageEntry,
     ExtensionIndexThemeEntry, ExtensionManifest, ExtensionSettings, ExtensionStore,
     GrammarManifestEntry, RELOAD_DEBOUNCE_DURATION, SchemaVersion,
+    wasm_host::wit::is_supported_wasm_api_version,
 };
 use async_compression::futures::bufread::GzipEncoder;
 use collections::{BTreeMap, HashSet};
-use extension::ExtensionHostProxy;
+use extension::{Extension, ExtensionHostProxy, ExtensionPanelProxy, PanelManifestEntry};
 use fs::{FakeFs, Fs, RealFs};
 use futures::{AsyncReadExt, StreamExt, io::BufReader};
-use gpui::{AppContext as _, SemanticVersion, TestAppContext};
+use gpui::{AppContext as _, SemanticVersion, TestAppContext, UpdateGlobal as _};
 use http_client::{FakeHttpClient, Response};
 use language::{BinaryStatus, LanguageMatcher, LanguageName, LanguageRegistry};
 use language_extension::LspAccess;
@@ -16,10 +17,10 @@ use lsp::LanguageServerName;
 use node_runtime::NodeRuntime;
 use parking_lot::Mutex;
 use project::{DEFAULT_COMPLETION_CONTEXT, Project};
-use release_channel::AppVersion;
+use release_channel::{AppVersion, ReleaseChannel};
 use reqwest_client::ReqwestClient;
 use serde_json::json;
-use settings::{Settings as _, SettingsStore};
+use settings::{ExtensionCapabilityContent, Settings as _, SettingsStore};
 use std::{
     ffi::OsString,
     path::{Path, PathBuf},
@@ -164,6 +165,7 @@ async fn test_extension_store(cx: &mut TestAppContext) {
                         capabilities: Vec::new(),
                         debug_adapters: Default::default(),
                         debug_locators: Default::default(),
+                        panels: Default::default(),
                     }),
                     dev: false,
                 },
@@ -194,6 +196,7 @@ async fn test_extension_store(cx: &mut TestAppContext) {
                         capabilities: Vec::new(),
                         debug_adapters: Default::default(),
                         debug_locators: Default::default(),
+                        panels: Default::default(),
                     }),
                     dev: false,
                 },
@@ -372,6 +375,7 @@ async fn test_extension_store(cx: &mut TestAppContext) {
                 capabilities: Vec::new(),
                 debug_adapters: Default::default(),
                 debug_locators: Default::default(),
+                panels: Default::default(),
             }),
             dev: false,
         },
@@ -567,6 +571,35 @@ async fn test_extension_store_with_test_extension(cx: &mut TestAppContext) {
     );
     let node_runtime = NodeRuntime::unavailable();
 
+    #[derive(Clone, Default)]
+    struct FakePanelProxy(Arc<Mutex<BTreeMap<Arc<str>, Arc<dyn Extension>>>>);
+
+    impl ExtensionPanelProxy for FakePanelProxy {
+        fn register_panel(
+            &self,
+            extension: Arc<dyn Extension>,
+            panel_id: Arc<str>,
+            _panel: PanelManifestEntry,
+            _cx: &mut gpui::App,
+        ) {
+            self.0.lock().insert(panel_id, extension);
+        }
+
+        fn unregister_panel(
+            &self,
+            _extension_id: Arc<str>,
+            panel_id: Arc<str>,
+            _cx: &mut gpui::App,
+        ) {
+            self.0.lock().remove(&panel_id);
+        }
+
+        fn refresh_panel(&self, _: Arc<str>, _: Arc<str>, _: &mut gpui::App) {}
+    }
+
+    let panel_proxy = FakePanelProxy::default();
+    proxy.register_panel_proxy(panel_proxy.clone());
+
     let mut status_updates = language_registry.language_server_binary_statuses();
 
     struct FakeLanguageServerVersion {
@@ -647,6 +680,35 @@ async fn test_extension_store_with_test_extension(cx: &mut TestAppContext) {
             }
         }
     });
+    // The test extension runs a child process and downloads its language server, which it
+    // needs the user's grants for.
+    cx.update(|cx| {
+        SettingsStore::update_global(cx, |store, cx| {
+            store.update_user_settings(cx, |settings| {
+                settings.extension.extension_capability_grants.insert(
+                    test_extension_id.into(),
+                    vec![
+                        ExtensionCapabilityContent::ProcessExec {
+                            command: "echo".into(),
+                            args: vec!["hello from a child process!".into()],
+                        },
+                        ExtensionCapabilityContent::ProcessExec {
+                            command: "cmd".into(),
+                            args: vec![
+                                "/C".into(),
+                                "echo".into(),
+                                "hello from a child process!".into(),
+                            ],
+                        },
+                        ExtensionCapabilityContent::DownloadFile {
+                            host: "fake-download.example.com".into(),
+                            path: vec!["**".into()],
+                        },
+                    ],
+                );
+            });
+        });
+    });
     let user_agent = cx.update(|cx| {
         format!(
             "Zed/{} ({}; {})",
@@ -714,6 +776,25 @@ async fn test_extension_store_with_test_extension(cx: &mut TestAppContext) {
         .await
         .unwrap();
 
+    // The extension's panel is registered, and its items come from the extension.
+    let panel_extension = panel_proxy
+        .0
+        .lock()
+        .get("test-panel")
+        .cloned()
+        .expect("the panel should be registered");
+    let panel_items = panel_extension
+        .panel_items("test-panel".into(), None)
+        .await
+        .unwrap();
+    assert_eq!(
+        panel_items
+            .iter()
+            .map(|item| item.label.as_str())
+            .collect::<Vec<_>>(),
+        ["Hello from the test extension!"]
+    );
+
     let fake_server = fake_servers.next().await.unwrap();
     let work_dir = extensions_dir.join(format!("work/{test_extension_id}"));
     let expected_server_path = work_dir.join("gleam-v1.2.3/gleam");
@@ -853,6 +934,248 @@ async fn test_extension_store_with_test_extension(cx: &mut TestAppContext) {
 
     // The old language server directory has been cleaned up.
     assert!(fs.metadata(&expected_server_path).await.unwrap().is_none());
+
+    // Reloading the extension registered its panel again.
+    assert_eq!(
+        panel_proxy.0.lock().keys().cloned().collect::<Vec<_>>(),
+        [Arc::<str>::from("test-panel")]
+    );
+
+    // Uninstalling the extension unregisters its panel.
+    extension_store
+        .update(cx, |store, cx| {
+            store.uninstall_extension(test_extension_id.into(), cx)
+        })
+        .await
+        .unwrap();
+    cx.executor().run_until_parked();
+    assert!(panel_proxy.0.lock().is_empty());
+}
+
+#[gpui::test]
+async fn test_install_extension_from_path(cx: &mut TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    let http_client = FakeHttpClient::with_200_response();
+
+    fs.insert_tree(
+        "/the-extension-dir",
+        json!({
+            "installed": {}
+        }),
+    )
+    .await;
+    fs.insert_tree(
+        "/downloads/zed-monokai",
+        json!({
+            "extension.toml": r#"
+                id = "zed-monokai"
+                name = "Zed Monokai"
+                version = "2.0.0"
+                schema_version = 1
+            "#,
+            "themes": {
+                "monokai.json": r#"{
+                    "name": "Monokai",
+                    "author": "Someone",
+                    "themes": [
+                        {
+                            "name": "Monokai Dark",
+                            "appearance": "dark",
+                            "style": {}
+                        }
+                    ]
+                }"#,
+            }
+        }),
+    )
+    .await;
+
+    let proxy = Arc::new(ExtensionHostProxy::new());
+    let theme_registry = Arc::new(ThemeRegistry::new(Box::new(())));
+    theme_extension::init(proxy.clone(), theme_registry.clone(), cx.executor());
+    let language_registry = Arc::new(LanguageRegistry::test(cx.executor()));
+    language_extension::init(LspAccess::Noop, proxy.clone(), language_registry);
+
+    let store = cx.new(|cx| {
+        ExtensionStore::new(
+            PathBuf::from("/the-extension-dir"),
+            None,
+            proxy,
+            fs.clone(),
+            http_client.clone(),
+            http_client,
+            NodeRuntime::unavailable(),
+            cx,
+        )
+    });
+    cx.executor().advance_clock(RELOAD_DEBOUNCE_DURATION);
+    cx.executor().run_until_parked();
+
+    let install_task = store.update(cx, |store, cx| {
+        store.install_extension_from_path(PathBuf::from("/downloads/zed-monokai"), cx)
+    });
+    cx.executor().advance_clock(RELOAD_DEBOUNCE_DURATION);
+    install_task.await.unwrap();
+
+    store.read_with(cx, |store, _| {
+        let entry = store
+            .extension_index
+            .extensions
+            .get("zed-monokai")
+            .expect("extension should be installed");
+        assert!(!entry.dev);
+        assert_eq!(entry.manifest.version.as_ref(), "2.0.0");
+        assert!(store.extension_index.themes.contains_key("Monokai Dark"));
+    });
+    assert!(
+        fs.is_dir(Path::new("/the-extension-dir/installed/zed-monokai"))
+            .await
+    );
+    // No staging directory is left behind.
+    let installed_dirs = || {
+        fs.directories(false)
+            .into_iter()
+            .filter(|dir| dir.parent() == Some(Path::new("/the-extension-dir/installed")))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        installed_dirs(),
+        [PathBuf::from("/the-extension-dir/installed/zed-monokai")]
+    );
+
+    // An ID that names a directory outside of the installed extensions is rejected.
+    fs.insert_tree(
+        "/downloads/escaping",
+        json!({
+            "extension.toml": r#"
+                id = "../escaping"
+                name = "Escaping"
+                version = "1.0.0"
+                schema_version = 1
+            "#,
+        }),
+    )
+    .await;
+    let install_task = store.update(cx, |store, cx| {
+        store.install_extension_from_path(PathBuf::from("/downloads/escaping"), cx)
+    });
+    assert!(install_task.await.is_err());
+    assert!(!fs.is_dir(Path::new("/the-extension-dir/escaping")).await);
+    assert_eq!(
+        installed_dirs(),
+        [PathBuf::from("/the-extension-dir/installed/zed-monokai")]
+    );
+}
+
+#[gpui::test]
+async fn test_extensions_bundle(cx: &mut TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    let http_client = FakeHttpClient::with_200_response();
+
+    fs.insert_tree(
+        "/the-extension-dir",
+        json!({
+            "installed": {
+                "zed-monokai": {
+                    "extension.toml": r#"
+                        id = "zed-monokai"
+                        name = "Zed Monokai"
+                        version = "2.0.0"
+                        schema_version = 1
+                    "#,
+                }
+            }
+        }),
+    )
+    .await;
+    fs.insert_tree(
+        "/mirror",
+        json!({
+            "zed-monokai": {
+                "extension.toml": r#"
+                    id = "zed-monokai"
+                    name = "Zed Monokai"
+                    version = "2.1.0"
+                    schema_version = 1
+                "#,
+            },
+            "zed-ruby": {
+                "extension.toml": r#"
+                    id = "zed-ruby"
+                    name = "Zed Ruby"
+                    version = "0.1.0"
+                    schema_version = 1
+                "#,
+            },
+            "README.md": "Not an extension",
+        }),
+    )
+    .await;
+
+    let proxy = Arc::new(ExtensionHostProxy::new());
+    let store = cx.new(|cx| {
+        ExtensionStore::new(
+            PathBuf::from("/the-extension-dir"),
+            None,
+            proxy,
+            fs.clone(),
+            http_client.clone(),
+            http_client,
+            NodeRuntime::unavailable(),
+            cx,
+        )
+    });
+    cx.executor().advance_clock(RELOAD_DEBOUNCE_DURATION);
+    cx.executor().run_until_parked();
+
+    let export_task = store.update(cx, |store, cx| {
+        store.export_extensions_bundle(PathBuf::from("/bundle"), cx)
+    });
+    assert_eq!(export_task.await.unwrap(), 1);
+    assert!(
+        fs.is_file(Path::new("/bundle/zed-monokai/extension.toml"))
+            .await
+    );
+
+    // Newer versions in the bundle are installed, and other extensions are added.
+    let install_task = store.update(cx, |store, cx| {
+        store.install_extensions_from_bundle(PathBuf::from("/mirror"), cx)
+    });
+    cx.executor().advance_clock(RELOAD_DEBOUNCE_DURATION);
+    let installed_extension_ids = install_task.await.unwrap();
+    assert_eq!(
+        installed_extension_ids,
+        vec![Arc::<str>::from("zed-monokai"), Arc::from("zed-ruby")]
+    );
+    store.read_with(cx, |store, _| {
+        let versions = store
+            .extension_index
+            .extensions
+            .iter()
+            .map(|(id, entry)| (id.as_ref(), entry.manifest.version.as_ref()))
+            .collect::<Vec<_>>();
+        assert_eq!(versions, [("zed-monokai", "2.1.0"), ("zed-ruby", "0.1.0")]);
+    });
+
+    // Extensions that are already up to date are left alone.
+    let install_task = store.update(cx, |store, cx| {
+        store.install_extensions_from_bundle(PathBuf::from("/bundle"), cx)
+    });
+    cx.executor().advance_clock(RELOAD_DEBOUNCE_DURATION);
+    assert!(install_task.await.unwrap().is_empty());
+    store.read_with(cx, |store, _| {
+        assert_eq!(
+            store.extension_index.extensions["zed-monokai"]
+                .manifest
+                .version
+                .as_ref(),
+            "2.1.0"
+        );
+    });
 }
 
 fn init_test(cx: &mut TestAppContext) {
@@ -867,3 +1190,25 @@ fn init_test(cx: &mut TestAppContext) {
         language::init(cx);
     });
 }
+
+#[test]
+fn test_unreleased_wasm_api_version_gate() {
+    // Panels need version 0.8.0 of the extension API, which only development builds support.
+    let panel_api_version = SemanticVersion::new(0, 8, 0);
+    for release_channel in [ReleaseChannel::Dev, ReleaseChannel::Nightly] {
+        assert!(is_supported_wasm_api_version(
+            release_channel,
+            panel_api_version
+        ));
+    }
+    for release_channel in [ReleaseChannel::Stable, ReleaseChannel::Preview] {
+        assert!(!is_supported_wasm_api_version(
+            release_channel,
+            panel_api_version
+        ));
+        assert!(is_supported_wasm_api_version(
+            release_channel,
+            SemanticVersion::new(0, 7, 0)
+        ));
+    }
+}
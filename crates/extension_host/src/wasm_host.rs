@@ -4,12 +4,13 @@ use crate::capability_granter::CapabilityGranter;
 use crate::{ExtensionManifest, ExtensionSettings};
 use anyhow::{Context as _, Result, anyhow, bail};
 use async_trait::async_trait;
+use collections::HashMap;
 use dap::{DebugRequest, StartDebuggingRequestArgumentsRequest};
 use extension::{
     CodeLabel, Command, Completion, ContextServerConfiguration, DebugAdapterBinary,
     DebugTaskDefinition, ExtensionCapability, ExtensionHostProxy, KeyValueStoreDelegate,
-    ProjectDelegate, SlashCommand, SlashCommandArgumentCompletion, SlashCommandOutput, Symbol,
-    WorktreeDelegate,
+    PanelEvent, PanelItem, ProjectDelegate, SlashCommand, SlashCommandArgumentCompletion,
+    SlashCommandOutput, Symbol, WorktreeDelegate,
 };
 use fs::{Fs, normalize_path};
 use futures::future::LocalBoxFuture;
@@ -27,6 +28,7 @@ use language::LanguageName;
 use lsp::LanguageServerName;
 use moka::sync::Cache;
 use node_runtime::NodeRuntime;
+use parking_lot::RwLock;
 use release_channel::ReleaseChannel;
 use semantic_version::SemanticVersion;
 use settings::Settings;
@@ -56,6 +58,8 @@ pub struct WasmHost {
     pub work_dir: PathBuf,
     /// The capabilities granted to extensions running on the host.
     pub(crate) granted_capabilities: Vec<ExtensionCapability>,
+    /// The capabilities the user has granted to individual extensions.
+    extension_grants: RwLock<HashMap<Arc<str>, Vec<ExtensionCapability>>>,
     _main_thread_message_task: Task<()>,
     main_thread_message_tx: mpsc::UnboundedSender<MainThreadCall>,
 }
@@ -485,6 +489,38 @@ impl extension::Extension for WasmExtension {
         })
         .await?
     }
+
+    async fn panel_items(
+        &self,
+        panel_id: Arc<str>,
+        parent_item_id: Option<String>,
+    ) -> Result<Vec<PanelItem>> {
+        self.call(|extension, store| {
+            async move {
+                let items = extension
+                    .call_panel_items(store, &panel_id, parent_item_id.as_deref())
+                    .await?
+                    .map_err(|err| store.data().extension_error(err))?;
+
+                Ok(items.into_iter().map(Into::into).collect())
+            }
+            .boxed()
+        })
+        .await?
+    }
+
+    async fn handle_panel_event(&self, panel_id: Arc<str>, event: PanelEvent) -> Result<()> {
+        self.call(|extension, store| {
+            async move {
+                extension
+                    .call_handle_panel_event(store, &panel_id, event)
+                    .await?
+                    .map_err(|err| store.data().extension_error(err))
+            }
+            .boxed()
+        })
+        .await?
+    }
 }
 
 pub struct WasmState {
@@ -582,11 +618,21 @@ impl WasmHost {
             proxy,
             release_channel: ReleaseChannel::global(cx),
             granted_capabilities: extension_settings.granted_capabilities.clone(),
+            extension_grants: RwLock::new(extension_settings.extension_grants.clone()),
             _main_thread_message_task: task,
             main_thread_message_tx: tx,
         })
     }
 
+    /// Updates the capabilities granted to individual extensions, which take effect the next
+    /// time each extension is loaded.
+    pub(crate) fn set_extension_grants(
+        &self,
+        extension_grants: HashMap<Arc<str>, Vec<ExtensionCapability>>,
+    ) {
+        *self.extension_grants.write() = extension_grants;
+    }
+
     pub fn load_extension(
         self: &Arc<Self>,
         wasm_bytes: Vec<u8>,
@@ -609,6 +655,13 @@ impl WasmHost {
                     host: this.clone(),
                     capability_granter: CapabilityGranter::new(
                         this.granted_capabilities.clone(),
+                        // Extensions the user hasn't been asked about yet are denied
+                        // everything until they answer.
+                        this.extension_grants
+                            .read()
+                            .get(&manifest.id)
+                            .cloned()
+                            .unwrap_or_default(),
                         manifest.clone(),
                     ),
                 },
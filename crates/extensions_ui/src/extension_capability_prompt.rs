@@ -0,0 +1,189 @@
+use std::sync::Arc;
+
+use extension::ExtensionCapability;
+use extension_host::extension_settings::capability_to_content;
+use extension_host::{ExtensionSettings, ExtensionStore};
+use fs::Fs;
+use gpui::{App, Entity, Global, PromptLevel};
+use settings::Settings;
+use util::ResultExt as _;
+use workspace::Workspace;
+
+/// The extensions waiting to be asked about their capabilities.
+///
+/// Prompts are shown one at a time, and only once there is a window to show
+/// them in. Until the user answers, an extension has no grant entry and is
+/// denied all of the capabilities it declares.
+#[derive(Default)]
+struct PendingCapabilityPrompts {
+    extension_ids: Vec<Arc<str>>,
+    is_prompting: bool,
+}
+
+impl Global for PendingCapabilityPrompts {}
+
+/// Prompts the user to grant the capabilities declared by extensions when they are installed
+/// or upgraded, and for any installed extension that has never been granted capabilities.
+pub(crate) fn init(cx: &mut App) {
+    let Some(store) = ExtensionStore::try_global(cx) else {
+        return;
+    };
+
+    cx.set_global(PendingCapabilityPrompts::default());
+
+    cx.subscribe(&store, |store, event, cx| match event {
+        extension_host::Event::ExtensionInstalled(extension_id)
+        | extension_host::Event::ExtensionUpgraded(extension_id) => {
+            queue_prompt(extension_id.clone(), cx);
+            show_next_prompt(store, cx);
+        }
+        extension_host::Event::ExtensionsUpdated => {
+            queue_ungranted_extensions(&store, cx);
+            show_next_prompt(store, cx);
+        }
+        _ => {}
+    })
+    .detach();
+
+    // No prompt can be shown before the first window opens, so check again
+    // whenever a workspace is created.
+    cx.observe_new({
+        let store = store.clone();
+        move |_: &mut Workspace, _, cx| {
+            let store = store.clone();
+            cx.defer(move |cx| show_next_prompt(store, cx));
+        }
+    })
+    .detach();
+
+    queue_ungranted_extensions(&store, cx);
+    show_next_prompt(store, cx);
+}
+
+/// Queues the installed extensions that declare capabilities but have no grant entry, such as
+/// those installed before capabilities were granted per extension.
+fn queue_ungranted_extensions(store: &Entity<ExtensionStore>, cx: &mut App) {
+    let extension_grants = &ExtensionSettings::get_global(cx).extension_grants;
+    let extension_ids = store
+        .read(cx)
+        .installed_extensions()
+        .iter()
+        .filter(|(extension_id, entry)| {
+            !entry.manifest.capabilities.is_empty() && !extension_grants.contains_key(*extension_id)
+        })
+        .map(|(extension_id, _)| extension_id.clone())
+        .collect::<Vec<_>>();
+    for extension_id in extension_ids {
+        queue_prompt(extension_id, cx);
+    }
+}
+
+fn queue_prompt(extension_id: Arc<str>, cx: &mut App) {
+    let pending = cx.global_mut::<PendingCapabilityPrompts>();
+    if !pending.extension_ids.contains(&extension_id) {
+        pending.extension_ids.push(extension_id);
+    }
+}
+
+fn show_next_prompt(store: Entity<ExtensionStore>, cx: &mut App) {
+    if cx.global::<PendingCapabilityPrompts>().is_prompting {
+        return;
+    }
+    let Some(window) = cx.active_window() else {
+        return;
+    };
+
+    loop {
+        let pending = cx.global_mut::<PendingCapabilityPrompts>();
+        if pending.extension_ids.is_empty() {
+            return;
+        }
+        let extension_id = pending.extension_ids.remove(0);
+
+        let Some(manifest) = store
+            .read(cx)
+            .extension_manifest_for_id(&extension_id)
+            .cloned()
+        else {
+            continue;
+        };
+        let ungranted_capabilities =
+            ExtensionSettings::get_global(cx).ungranted_capabilities(&manifest);
+        if ungranted_capabilities.is_empty() {
+            continue;
+        }
+
+        let message = format!("Allow the {} extension to:", manifest.name);
+        let detail = ungranted_capabilities
+            .iter()
+            .map(|capability| format!("• {}", describe_capability(capability)))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let Some(answer) = window
+            .update(cx, |_, window, cx| {
+                window.prompt(
+                    PromptLevel::Warning,
+                    &message,
+                    Some(&detail),
+                    &["Allow", "Deny"],
+                    cx,
+                )
+            })
+            .log_err()
+        else {
+            // Try again once another window is available.
+            cx.global_mut::<PendingCapabilityPrompts>()
+                .extension_ids
+                .insert(0, extension_id);
+            return;
+        };
+        cx.global_mut::<PendingCapabilityPrompts>().is_prompting = true;
+
+        let fs = <dyn Fs>::global(cx);
+        cx.spawn(async move |cx| {
+            let allowed = answer.await.ok() == Some(0);
+            // Recording the capabilities that remain granted when denying ensures the extension
+            // is restricted to them, and isn't asked about them again.
+            let granted_capabilities = manifest
+                .capabilities
+                .iter()
+                .filter(|capability| allowed || !ungranted_capabilities.contains(capability))
+                .map(capability_to_content)
+                .collect::<Vec<_>>();
+            cx.update(|cx| {
+                settings::update_settings_file(fs, cx, move |settings, _| {
+                    settings
+                        .extension
+                        .extension_capability_grants
+                        .insert(extension_id, granted_capabilities);
+                });
+                cx.global_mut::<PendingCapabilityPrompts>().is_prompting = false;
+                show_next_prompt(store, cx);
+            })
+            .log_err();
+        })
+        .detach();
+        return;
+    }
+}
+
+fn describe_capability(capability: &ExtensionCapability) -> String {
+    match capability {
+        ExtensionCapability::ProcessExec(capability) => {
+            let mut command = capability.command.clone();
+            for arg in &capability.args {
+                command.push(' ');
+                command.push_str(arg);
+            }
+            format!("Run `{command}`")
+        }
+        ExtensionCapability::DownloadFile(capability) => format!(
+            "Download files from {}/{}",
+            capability.host,
+            capability.path.join("/")
+        ),
+        ExtensionCapability::NpmInstallPackage(capability) => {
+            format!("Install the npm package `{}`", capability.package)
+        }
+    }
+}
@@ -0,0 +1,566 @@
+use std::path::PathBuf;
+use std::str::FromStr as _;
+use std::sync::Arc;
+
+use collections::{BTreeMap, HashMap, HashSet};
+use extension::{
+    Extension, ExtensionHostProxy, ExtensionPanelProxy, PanelEvent, PanelItem, PanelManifestEntry,
+};
+use fs::Fs;
+use gpui::{
+    Action, App, AppContext as _, Context, Entity, EventEmitter, FocusHandle, Focusable, Global,
+    Pixels, Subscription, Task, WeakEntity, Window, actions,
+};
+use settings::Settings;
+use ui::{ListItem, ListItemSpacing, Tooltip, prelude::*};
+use util::ResultExt as _;
+use workspace::{
+    OpenOptions, Workspace,
+    dock::{DockPosition, Panel, PanelEvent as DockPanelEvent},
+};
+
+actions!(
+    extension_panel,
+    [
+        /// Toggles focus on the extension panel.
+        ToggleFocus,
+        /// Fetches the items of the active extension view again.
+        Refresh
+    ]
+);
+
+pub(crate) fn init(cx: &mut App) {
+    ExtensionPanelSettings::register(cx);
+
+    let registry = cx.new(|_| ExtensionPanelRegistry::default());
+    cx.set_global(GlobalExtensionPanelRegistry(registry));
+    ExtensionHostProxy::default_global(cx).register_panel_proxy(ExtensionPanelRegistryProxy);
+
+    cx.observe_new(|workspace: &mut Workspace, window, cx| {
+        let Some(window) = window else {
+            return;
+        };
+        workspace.register_action(|workspace, _: &ToggleFocus, window, cx| {
+            workspace.toggle_panel_focus::<ExtensionPanel>(window, cx);
+        });
+
+        let panel = cx.new(|cx| ExtensionPanel::new(workspace, cx));
+        workspace.add_panel(panel, window, cx);
+    })
+    .detach();
+}
+
+#[derive(Debug)]
+pub struct ExtensionPanelSettings {
+    pub button: bool,
+    pub dock: DockPosition,
+    pub default_width: Pixels,
+}
+
+impl Settings for ExtensionPanelSettings {
+    fn from_settings(content: &settings::SettingsContent) -> Self {
+        let panel = content.extension_panel.as_ref().unwrap();
+
+        Self {
+            button: panel.button.unwrap(),
+            dock: panel.dock.unwrap().into(),
+            default_width: panel.default_width.map(px).unwrap(),
+        }
+    }
+}
+
+struct GlobalExtensionPanelRegistry(Entity<ExtensionPanelRegistry>);
+
+impl Global for GlobalExtensionPanelRegistry {}
+
+/// The views that extensions contribute to the extension panel.
+#[derive(Default)]
+struct ExtensionPanelRegistry {
+    views: BTreeMap<ExtensionViewId, ExtensionView>,
+}
+
+/// Identifies a view by the extension that provides it as well as its panel ID, since different
+/// extensions may use the same panel IDs.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct ExtensionViewId {
+    extension_id: Arc<str>,
+    panel_id: Arc<str>,
+}
+
+#[derive(Clone)]
+struct ExtensionView {
+    extension: Arc<dyn Extension>,
+    title: SharedString,
+    icon: Option<IconName>,
+}
+
+enum ExtensionPanelRegistryEvent {
+    ViewsChanged,
+    RefreshRequested(ExtensionViewId),
+}
+
+impl EventEmitter<ExtensionPanelRegistryEvent> for ExtensionPanelRegistry {}
+
+impl ExtensionPanelRegistry {
+    fn try_global(cx: &App) -> Option<Entity<Self>> {
+        cx.try_global::<GlobalExtensionPanelRegistry>()
+            .map(|registry| registry.0.clone())
+    }
+}
+
+struct ExtensionPanelRegistryProxy;
+
+impl ExtensionPanelProxy for ExtensionPanelRegistryProxy {
+    fn register_panel(
+        &self,
+        extension: Arc<dyn Extension>,
+        panel_id: Arc<str>,
+        panel: PanelManifestEntry,
+        cx: &mut App,
+    ) {
+        let Some(registry) = ExtensionPanelRegistry::try_global(cx) else {
+            return;
+        };
+
+        let icon = panel
+            .icon
+            .as_deref()
+            .and_then(|icon| IconName::from_str(icon).ok());
+        let view_id = ExtensionViewId {
+            extension_id: extension.manifest().id.clone(),
+            panel_id,
+        };
+        registry.update(cx, |registry, cx| {
+            registry.views.insert(
+                view_id,
+                ExtensionView {
+                    extension,
+                    title: panel.title.into(),
+                    icon,
+                },
+            );
+            cx.emit(ExtensionPanelRegistryEvent::ViewsChanged);
+        });
+    }
+
+    fn unregister_panel(&self, extension_id: Arc<str>, panel_id: Arc<str>, cx: &mut App) {
+        let Some(registry) = ExtensionPanelRegistry::try_global(cx) else {
+            return;
+        };
+
+        let view_id = ExtensionViewId {
+            extension_id,
+            panel_id,
+        };
+        registry.update(cx, |registry, cx| {
+            if registry.views.remove(&view_id).is_some() {
+                cx.emit(ExtensionPanelRegistryEvent::ViewsChanged);
+            }
+        });
+    }
+
+    fn refresh_panel(&self, extension_id: Arc<str>, panel_id: Arc<str>, cx: &mut App) {
+        let Some(registry) = ExtensionPanelRegistry::try_global(cx) else {
+            return;
+        };
+
+        registry.update(cx, |_, cx| {
+            cx.emit(ExtensionPanelRegistryEvent::RefreshRequested(
+                ExtensionViewId {
+                    extension_id,
+                    panel_id,
+                },
+            ));
+        });
+    }
+}
+
+/// A dock panel that displays the tree and list views contributed by extensions.
+pub struct ExtensionPanel {
+    workspace: WeakEntity<Workspace>,
+    fs: Arc<dyn Fs>,
+    focus_handle: FocusHandle,
+    width: Option<Pixels>,
+    active_view_id: Option<ExtensionViewId>,
+    /// The items fetched for each parent item, keyed by the parent's ID, with the top-level
+    /// items stored under `None`.
+    items: HashMap<Option<String>, Vec<PanelItem>>,
+    expanded_item_ids: HashSet<String>,
+    error: Option<SharedString>,
+    fetch_tasks: HashMap<Option<String>, Task<()>>,
+    _registry_subscription: Option<Subscription>,
+}
+
+impl ExtensionPanel {
+    fn new(workspace: &Workspace, cx: &mut Context<Self>) -> Self {
+        let registry_subscription = ExtensionPanelRegistry::try_global(cx).map(|registry| {
+            cx.subscribe(&registry, |this, registry, event, cx| match event {
+                ExtensionPanelRegistryEvent::ViewsChanged => {
+                    let views = &registry.read(cx).views;
+                    let active_view_id = this
+                        .active_view_id
+                        .clone()
+                        .filter(|view_id| views.contains_key(view_id))
+                        .or_else(|| views.keys().next().cloned());
+                    if active_view_id != this.active_view_id {
+                        this.activate_view(active_view_id, cx);
+                    }
+                    cx.notify();
+                }
+                ExtensionPanelRegistryEvent::RefreshRequested(view_id) => {
+                    if this.active_view_id.as_ref() == Some(view_id) {
+                        this.refresh(cx);
+                    }
+                }
+            })
+        });
+
+        let mut this = Self {
+            workspace: workspace.weak_handle(),
+            fs: workspace.app_state().fs.clone(),
+            focus_handle: cx.focus_handle(),
+            width: None,
+            active_view_id: None,
+            items: HashMap::default(),
+            expanded_item_ids: HashSet::default(),
+            error: None,
+            fetch_tasks: HashMap::default(),
+            _registry_subscription: registry_subscription,
+        };
+        let first_view_id = ExtensionPanelRegistry::try_global(cx)
+            .and_then(|registry| registry.read(cx).views.keys().next().cloned());
+        this.activate_view(first_view_id, cx);
+        this
+    }
+
+    fn active_view(&self, cx: &App) -> Option<ExtensionView> {
+        let view_id = self.active_view_id.as_ref()?;
+        let registry = ExtensionPanelRegistry::try_global(cx)?;
+        registry.read(cx).views.get(view_id).cloned()
+    }
+
+    fn activate_view(&mut self, view_id: Option<ExtensionViewId>, cx: &mut Context<Self>) {
+        self.active_view_id = view_id;
+        self.items.clear();
+        self.expanded_item_ids.clear();
+        self.fetch_tasks.clear();
+        self.error = None;
+        self.fetch_items(None, cx);
+        cx.notify();
+    }
+
+    fn refresh(&mut self, cx: &mut Context<Self>) {
+        self.error = None;
+        self.fetch_items(None, cx);
+        for item_id in self.expanded_item_ids.clone() {
+            self.fetch_items(Some(item_id), cx);
+        }
+    }
+
+    fn fetch_items(&mut self, parent_item_id: Option<String>, cx: &mut Context<Self>) {
+        let (Some(view_id), Some(view)) = (self.active_view_id.clone(), self.active_view(cx))
+        else {
+            return;
+        };
+
+        let task = cx.spawn({
+            let parent_item_id = parent_item_id.clone();
+            async move |this, cx| {
+                let result = view
+                    .extension
+                    .panel_items(view_id.panel_id.clone(), parent_item_id.clone())
+                    .await;
+                this.update(cx, |this, cx| {
+                    if this.active_view_id.as_ref() != Some(&view_id) {
+                        return;
+                    }
+                    match result {
+                        Ok(items) => {
+                            this.items.insert(parent_item_id.clone(), items);
+                        }
+                        Err(error) => {
+                            log::error!(
+                                "failed to fetch items of view {} of extension {}: {error:?}",
+                                view_id.panel_id,
+                                view_id.extension_id,
+                            );
+                            if parent_item_id.is_none() {
+                                this.error = Some(error.to_string().into());
+                            }
+                        }
+                    }
+                    this.fetch_tasks.remove(&parent_item_id);
+                    cx.notify();
+                })
+                .ok();
+            }
+        });
+        self.fetch_tasks.insert(parent_item_id, task);
+    }
+
+    fn toggle_expanded(&mut self, item_id: String, cx: &mut Context<Self>) {
+        if !self.expanded_item_ids.remove(&item_id) {
+            self.expanded_item_ids.insert(item_id.clone());
+            self.fetch_items(Some(item_id), cx);
+        }
+        cx.notify();
+    }
+
+    fn activate_item(&mut self, item: &PanelItem, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(path) = item.path.clone() {
+            self.workspace
+                .update(cx, |workspace, cx| {
+                    workspace
+                        .open_abs_path(PathBuf::from(path), OpenOptions::default(), window, cx)
+                        .detach_and_log_err(cx);
+                })
+                .ok();
+        }
+        self.send_event(PanelEvent::ItemActivated(item.id.clone()), cx);
+    }
+
+    fn send_event(&mut self, event: PanelEvent, cx: &mut Context<Self>) {
+        let (Some(view_id), Some(view)) = (self.active_view_id.clone(), self.active_view(cx))
+        else {
+            return;
+        };
+
+        cx.background_spawn(async move {
+            view.extension
+                .handle_panel_event(view_id.panel_id, event)
+                .await
+                .log_err();
+        })
+        .detach();
+    }
+
+    fn visible_items(&self) -> Vec<(usize, &PanelItem)> {
+        fn push_items<'a>(
+            panel: &'a ExtensionPanel,
+            parent_item_id: Option<String>,
+            depth: usize,
+            entries: &mut Vec<(usize, &'a PanelItem)>,
+        ) {
+            for item in panel.items.get(&parent_item_id).into_iter().flatten() {
+                entries.push((depth, item));
+                if item.expandable && panel.expanded_item_ids.contains(&item.id) {
+                    push_items(panel, Some(item.id.clone()), depth + 1, entries);
+                }
+            }
+        }
+
+        let mut entries = Vec::new();
+        push_items(self, None, 0, &mut entries);
+        entries
+    }
+
+    fn render_view_tabs(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let views = ExtensionPanelRegistry::try_global(cx)
+            .map(|registry| registry.read(cx).views.clone())
+            .unwrap_or_default();
+
+        h_flex()
+            .h(Tab::container_height(cx))
+            .px_1()
+            .gap_1()
+            .justify_between()
+            .border_b_1()
+            .border_color(cx.theme().colors().border)
+            .child(
+                h_flex()
+                    .gap_1()
+                    .overflow_x_hidden()
+                    .children(views.into_iter().map(|(view_id, view)| {
+                        let is_active = self.active_view_id.as_ref() == Some(&view_id);
+                        let button_id =
+                            format!("view-{}-{}", view_id.extension_id, view_id.panel_id);
+                        Button::new(SharedString::from(button_id), view.title)
+                            .when_some(view.icon, |button, icon| {
+                                button.icon(icon).icon_position(IconPosition::Start)
+                            })
+                            .label_size(LabelSize::Small)
+                            .toggle_state(is_active)
+                            .on_click(cx.listener(move |this, _, _, cx| {
+                                if this.active_view_id.as_ref() != Some(&view_id) {
+                                    this.activate_view(Some(view_id.clone()), cx);
+                                }
+                            }))
+                    })),
+            )
+            .child(
+                IconButton::new("refresh-extension-view", IconName::RotateCw)
+                    .icon_size(IconSize::Small)
+                    .tooltip(Tooltip::for_action_title("Refresh", &Refresh))
+                    .on_click(cx.listener(|this, _, _, cx| this.refresh(cx))),
+            )
+    }
+
+    fn render_item(
+        &self,
+        depth: usize,
+        item: &PanelItem,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let is_expanded = self.expanded_item_ids.contains(&item.id);
+        let icon = item
+            .icon
+            .as_deref()
+            .and_then(|icon| IconName::from_str(icon).ok());
+        let item_id = item.id.clone();
+
+        ListItem::new(SharedString::from(format!("item-{}", item.id)))
+            .spacing(ListItemSpacing::Sparse)
+            .indent_level(depth)
+            .indent_step_size(px(12.))
+            .toggle(item.expandable.then_some(is_expanded))
+            .on_toggle(cx.listener({
+                let item_id = item_id.clone();
+                move |this, _, _, cx| this.toggle_expanded(item_id.clone(), cx)
+            }))
+            .when_some(icon, |list_item, icon| {
+                list_item.start_slot(Icon::new(icon).size(IconSize::Small).color(Color::Muted))
+            })
+            .child(
+                h_flex()
+                    .gap_2()
+                    .child(Label::new(item.label.clone()).truncate())
+                    .when_some(item.detail.clone(), |this, detail| {
+                        this.child(
+                            Label::new(detail)
+                                .size(LabelSize::Small)
+                                .color(Color::Muted)
+                                .truncate(),
+                        )
+                    }),
+            )
+            .when(!item.commands.is_empty(), |list_item| {
+                list_item.end_hover_slot(h_flex().gap_1().children(item.commands.iter().map(
+                    |command| {
+                        let event = PanelEvent::CommandRun {
+                            item_id: item_id.clone(),
+                            command_id: command.id.clone(),
+                        };
+                        Button::new(
+                            SharedString::from(format!("command-{item_id}-{}", command.id)),
+                            command.label.clone(),
+                        )
+                        .label_size(LabelSize::Small)
+                        .on_click(
+                            cx.listener(move |this, _, _, cx| this.send_event(event.clone(), cx)),
+                        )
+                    },
+                )))
+            })
+            .on_click(cx.listener({
+                let item = item.clone();
+                move |this, _, window, cx| {
+                    if item.expandable && item.path.is_none() {
+                        this.toggle_expanded(item.id.clone(), cx);
+                    }
+                    this.activate_item(&item, window, cx);
+                }
+            }))
+    }
+}
+
+impl Render for ExtensionPanel {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let content = if let Some(error) = self.error.clone() {
+            v_flex()
+                .p_2()
+                .child(Label::new(error).color(Color::Error))
+                .into_any_element()
+        } else if self.active_view_id.is_none() {
+            v_flex()
+                .p_2()
+                .child(Label::new("No installed extensions provide a view.").color(Color::Muted))
+                .into_any_element()
+        } else {
+            let items = self
+                .visible_items()
+                .into_iter()
+                .map(|(depth, item)| self.render_item(depth, item, cx).into_any_element())
+                .collect::<Vec<_>>();
+            v_flex()
+                .id("extension-view-items")
+                .flex_1()
+                .p_1()
+                .overflow_y_scroll()
+                .children(items)
+                .into_any_element()
+        };
+
+        v_flex()
+            .key_context("ExtensionPanel")
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(|this, _: &Refresh, _, cx| this.refresh(cx)))
+            .size_full()
+            .bg(cx.theme().colors().panel_background)
+            .child(self.render_view_tabs(cx))
+            .child(content)
+    }
+}
+
+impl Focusable for ExtensionPanel {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl EventEmitter<DockPanelEvent> for ExtensionPanel {}
+
+impl Panel for ExtensionPanel {
+    fn persistent_name() -> &'static str {
+        "ExtensionPanel"
+    }
+
+    fn position(&self, _: &Window, cx: &App) -> DockPosition {
+        ExtensionPanelSettings::get_global(cx).dock
+    }
+
+    fn position_is_valid(&self, position: DockPosition) -> bool {
+        matches!(position, DockPosition::Left | DockPosition::Right)
+    }
+
+    fn set_position(&mut self, position: DockPosition, _: &mut Window, cx: &mut Context<Self>) {
+        settings::update_settings_file(self.fs.clone(), cx, move |settings, _| {
+            settings.extension_panel.get_or_insert_default().dock = Some(position.into())
+        });
+    }
+
+    fn size(&self, _: &Window, cx: &App) -> Pixels {
+        self.width
+            .unwrap_or_else(|| ExtensionPanelSettings::get_global(cx).default_width)
+    }
+
+    fn set_size(&mut self, size: Option<Pixels>, _: &mut Window, cx: &mut Context<Self>) {
+        self.width = size;
+        cx.notify();
+    }
+
+    fn icon(&self, _: &Window, cx: &App) -> Option<IconName> {
+        let has_views = ExtensionPanelRegistry::try_global(cx)
+            .is_some_and(|registry| !registry.read(cx).views.is_empty());
+        if !has_views || !ExtensionPanelSettings::get_global(cx).button {
+            return None;
+        }
+
+        Some(
+            self.active_view(cx)
+                .and_then(|view| view.icon)
+                .unwrap_or(IconName::Blocks),
+        )
+    }
+
+    fn icon_tooltip(&self, _window: &Window, _cx: &App) -> Option<&'static str> {
+        Some("Extension Panel")
+    }
+
+    fn toggle_action(&self) -> Box<dyn Action> {
+        Box::new(ToggleFocus)
+    }
+
+    fn activation_priority(&self) -> u32 {
+        10
+    }
+}
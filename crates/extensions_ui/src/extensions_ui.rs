@@ -1,7 +1,10 @@
 mod components;
+mod extension_capability_prompt;
+mod extension_panel;
 mod extension_suggest;
 mod extension_version_selector;
 
+use std::path::PathBuf;
 use std::sync::OnceLock;
 use std::time::Duration;
 use std::{ops::Range, sync::Arc};
@@ -29,8 +32,9 @@ use ui::{
 };
 use vim_mode_setting::VimModeSetting;
 use workspace::{
-    Workspace,
+    Toast, Workspace,
     item::{Item, ItemEvent},
+    notifications::NotificationId,
 };
 use zed_actions::ExtensionCategoryFilter;
 
@@ -43,11 +47,24 @@ actions!(
     zed,
     [
         /// Installs an extension from a local directory for development.
-        InstallDevExtension
+        InstallDevExtension,
+        /// Installs an already-built extension from a local directory or `.tar.gz` archive.
+        InstallExtensionFromPath,
+        /// Installs or upgrades the extensions in a local bundle directory, such as one
+        /// exported on another machine.
+        InstallExtensionsFromBundle,
+        /// Exports the installed extensions to a bundle directory for use on machines without
+        /// network access.
+        ExportExtensionsBundle
     ]
 );
 
+pub use extension_panel::ExtensionPanel;
+
 pub fn init(cx: &mut App) {
+    extension_capability_prompt::init(cx);
+    extension_panel::init(cx);
+
     cx.observe_new(move |workspace: &mut Workspace, window, cx| {
         let Some(window) = window else {
             return;
@@ -169,6 +186,70 @@ pub fn init(cx: &mut App) {
                         Some(())
                     })
                     .detach();
+            })
+            .register_action(move |workspace, _: &InstallExtensionFromPath, window, cx| {
+                let store = ExtensionStore::global(cx);
+                let prompt = workspace.prompt_for_open_path(
+                    gpui::PathPromptOptions {
+                        files: true,
+                        directories: true,
+                        multiple: false,
+                        prompt: None,
+                    },
+                    DirectoryLister::Local(
+                        workspace.project().clone(),
+                        workspace.app_state().fs.clone(),
+                    ),
+                    window,
+                    cx,
+                );
+
+                let workspace_handle = cx.entity().downgrade();
+                window
+                    .spawn(cx, async move |cx| {
+                        let extension_path =
+                            match Flatten::flatten(prompt.await.map_err(|e| e.into())) {
+                                Ok(Some(mut paths)) => paths.pop()?,
+                                Ok(None) => return None,
+                                Err(err) => {
+                                    workspace_handle
+                                        .update(cx, |workspace, cx| {
+                                            workspace.show_portal_error(err.to_string(), cx);
+                                        })
+                                        .ok();
+                                    return None;
+                                }
+                            };
+
+                        let install_task = store
+                            .update(cx, |store, cx| {
+                                store.install_extension_from_path(extension_path, cx)
+                            })
+                            .ok()?;
+
+                        if let Err(err) = install_task.await {
+                            log::error!("Failed to install extension: {:?}", err);
+                            workspace_handle
+                                .update(cx, |workspace, cx| {
+                                    workspace.show_error(
+                                        &format!("Failed to install extension: {}", err),
+                                        cx,
+                                    );
+                                })
+                                .ok();
+                        }
+
+                        Some(())
+                    })
+                    .detach();
+            });
+
+        workspace
+            .register_action(|workspace, _: &InstallExtensionsFromBundle, window, cx| {
+                install_extensions_from_bundle(workspace, window, cx)
+            })
+            .register_action(|workspace, _: &ExportExtensionsBundle, window, cx| {
+                export_extensions_bundle(workspace, window, cx)
             });
 
         cx.subscribe_in(workspace.project(), window, |_, _, event, window, cx| {
@@ -181,6 +262,130 @@ pub fn init(cx: &mut App) {
     .detach();
 }
 
+fn install_extensions_from_bundle(
+    workspace: &mut Workspace,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let store = ExtensionStore::global(cx);
+    let bundle_dir = prompt_for_bundle_dir(workspace, window, cx);
+    cx.spawn(async move |workspace, cx| {
+        let Some(bundle_dir) = bundle_dir.await else {
+            return;
+        };
+        let Ok(install_task) = store.update(cx, |store, cx| {
+            store.install_extensions_from_bundle(bundle_dir, cx)
+        }) else {
+            return;
+        };
+        let result = install_task.await;
+        workspace
+            .update(cx, |workspace, cx| match result {
+                Ok(extension_ids) => {
+                    struct InstalledExtensionsFromBundle;
+                    let message = if extension_ids.is_empty() {
+                        "All extensions in the bundle are up to date".to_string()
+                    } else {
+                        format!(
+                            "Installed {} extensions from the bundle",
+                            extension_ids.len()
+                        )
+                    };
+                    workspace.show_toast(
+                        Toast::new(
+                            NotificationId::unique::<InstalledExtensionsFromBundle>(),
+                            message,
+                        ),
+                        cx,
+                    );
+                }
+                Err(err) => {
+                    log::error!("Failed to install extension bundle: {:?}", err);
+                    workspace
+                        .show_error(&format!("Failed to install extension bundle: {}", err), cx);
+                }
+            })
+            .ok();
+    })
+    .detach();
+}
+
+fn export_extensions_bundle(
+    workspace: &mut Workspace,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let store = ExtensionStore::global(cx);
+    let bundle_dir = prompt_for_bundle_dir(workspace, window, cx);
+    cx.spawn(async move |workspace, cx| {
+        let Some(bundle_dir) = bundle_dir.await else {
+            return;
+        };
+        let Ok(export_task) = store.update(cx, |store, cx| {
+            store.export_extensions_bundle(bundle_dir.clone(), cx)
+        }) else {
+            return;
+        };
+        let result = export_task.await;
+        workspace
+            .update(cx, |workspace, cx| match result {
+                Ok(count) => {
+                    struct ExportedExtensionsBundle;
+                    workspace.show_toast(
+                        Toast::new(
+                            NotificationId::unique::<ExportedExtensionsBundle>(),
+                            format!("Exported {count} extensions to {}", bundle_dir.display()),
+                        ),
+                        cx,
+                    );
+                }
+                Err(err) => {
+                    log::error!("Failed to export extension bundle: {:?}", err);
+                    workspace
+                        .show_error(&format!("Failed to export extension bundle: {}", err), cx);
+                }
+            })
+            .ok();
+    })
+    .detach();
+}
+
+/// Prompts for the directory of an extension bundle, reporting errors from the system prompt.
+fn prompt_for_bundle_dir(
+    workspace: &mut Workspace,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) -> Task<Option<PathBuf>> {
+    let prompt = workspace.prompt_for_open_path(
+        gpui::PathPromptOptions {
+            files: false,
+            directories: true,
+            multiple: false,
+            prompt: None,
+        },
+        DirectoryLister::Local(
+            workspace.project().clone(),
+            workspace.app_state().fs.clone(),
+        ),
+        window,
+        cx,
+    );
+    cx.spawn(async move |workspace, cx| {
+        match Flatten::flatten(prompt.await.map_err(|e| e.into())) {
+            Ok(Some(mut paths)) => paths.pop(),
+            Ok(None) => None,
+            Err(err) => {
+                workspace
+                    .update(cx, |workspace, cx| {
+                        workspace.show_portal_error(err.to_string(), cx);
+                    })
+                    .ok();
+                None
+            }
+        }
+    })
+}
+
 fn extension_provides_label(provides: ExtensionProvides) -> &'static str {
     match provides {
         ExtensionProvides::Themes => "Themes",
@@ -1384,12 +1589,55 @@ impl Render for ExtensionsPage {
                             .justify_between()
                             .child(Headline::new("Extensions").size(HeadlineSize::XLarge))
                             .child(
-                                Button::new("install-dev-extension", "Install Dev Extension")
-                                    .style(ButtonStyle::Filled)
-                                    .size(ButtonSize::Large)
-                                    .on_click(|_event, window, cx| {
-                                        window.dispatch_action(Box::new(InstallDevExtension), cx)
-                                    }),
+                                h_flex()
+                                    .gap_1()
+                                    .child(
+                                        Button::new(
+                                            "install-dev-extension",
+                                            "Install Dev Extension",
+                                        )
+                                        .style(ButtonStyle::Filled)
+                                        .size(ButtonSize::Large)
+                                        .on_click(
+                                            |_event, window, cx| {
+                                                window.dispatch_action(
+                                                    Box::new(InstallDevExtension),
+                                                    cx,
+                                                )
+                                            },
+                                        ),
+                                    )
+                                    .child(
+                                        PopoverMenu::new("offline-extensions-menu")
+                                            .trigger(
+                                                IconButton::new(
+                                                    "offline-extensions",
+                                                    IconName::Ellipsis,
+                                                )
+                                                .size(ButtonSize::Large),
+                                            )
+                                            .anchor(gpui::Corner::TopRight)
+                                            .menu(|window, cx| {
+                                                Some(ContextMenu::build(
+                                                    window,
+                                                    cx,
+                                                    |menu, _, _| {
+                                                        menu.action(
+                                                            "Install Extension from Path...",
+                                                            Box::new(InstallExtensionFromPath),
+                                                        )
+                                                        .action(
+                                                            "Install Extensions from Bundle...",
+                                                            Box::new(InstallExtensionsFromBundle),
+                                                        )
+                                                        .action(
+                                                            "Export Extensions Bundle...",
+                                                            Box::new(ExportExtensionsBundle),
+                                                        )
+                                                    },
+                                                ))
+                                            }),
+                                    ),
                             ),
                     )
                     .child(
@@ -1,22 +1,34 @@
-use crate::{FakeFs, FakeFsEntry, Fs};
+use crate::{FakeFs, FakeFsEntry, Fs, RemoveOptions};
 use anyhow::{Context as _, Result, bail};
 use collections::{HashMap, HashSet};
+use futures::channel::mpsc;
 use futures::future::{self, BoxFuture, join_all};
 use git::{
     Oid,
     blame::Blame,
+    lfs::{LfsFileStatus, LfsLock, is_lfs_pointer},
     repository::{
-        AskPassDelegate, Branch, CommitDetails, CommitOptions, FetchOptions, GitRepository,
-        GitRepositoryCheckpoint, PushOptions, Remote, RepoPath, ResetMode,
+        ApplyPatchOptions, AskPassDelegate, Branch, CleanOptions, CommitDetails, CommitGraph,
+        CommitGraphOptions, CommitOptions, ConflictSide, DiffType, FetchOptions, FileHistoryEntry,
+        GitRepository, GitRepositoryCheckpoint, IgnoreFile, LogOptions, MaintenanceCommand,
+        MergeOptions, PullOptions, PushOptions, PushedBranch, RebaseState, Remote, RemoteProgress,
+        RepoPath, RepositoryHealth, ResetMode, Tag, Upstream, UpstreamTracking,
+        UpstreamTrackingStatus, append_ignore_patterns,
+    },
+    status::{
+        FileStatus, GitStatus, StatusCode, TrackedStatus, UnmergedStatus, UnmergedStatusCode,
     },
-    status::{FileStatus, GitStatus, StatusCode, TrackedStatus, UnmergedStatus},
 };
 use gpui::{AsyncApp, BackgroundExecutor, SharedString, Task};
-use ignore::gitignore::GitignoreBuilder;
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
 use parking_lot::Mutex;
 use rope::Rope;
 use smol::future::FutureExt as _;
-use std::{path::PathBuf, sync::Arc};
+use std::{
+    collections::BTreeSet,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 use util::{paths::PathStyle, rel_path::RelPath};
 
 #[derive(Clone)]
@@ -40,6 +52,25 @@ pub struct FakeGitRepositoryState {
     pub branches: HashSet<String>,
     pub simulated_index_write_error_message: Option<String>,
     pub refs: HashMap<String, String>,
+    pub rebase_state: Option<RebaseState>,
+    pub merge_message: Option<String>,
+    pub commit_template: Option<String>,
+    /// The upstreams of local branches, such as `origin/main`, by branch name.
+    pub upstreams: HashMap<String, String>,
+    /// The commits reachable from `HEAD`, newest first.
+    pub commits: Vec<FakeCommit>,
+    /// The repository's tags, most recently created first.
+    pub tags: Vec<Tag>,
+    /// The paths that are stored by Git LFS.
+    pub lfs_paths: HashSet<RepoPath>,
+    pub lfs_locks: HashMap<RepoPath, LfsLock>,
+}
+
+/// A commit in a fake repository's history, along with the paths it changed.
+#[derive(Debug, Clone, Default)]
+pub struct FakeCommit {
+    pub details: CommitDetails,
+    pub changed_paths: Vec<RepoPath>,
 }
 
 impl FakeGitRepositoryState {
@@ -54,6 +85,14 @@ impl FakeGitRepositoryState {
             branches: Default::default(),
             simulated_index_write_error_message: Default::default(),
             refs: HashMap::from_iter([("HEAD".into(), "abc".into())]),
+            rebase_state: None,
+            merge_message: None,
+            commit_template: None,
+            upstreams: HashMap::default(),
+            commits: Vec::new(),
+            tags: Vec::new(),
+            lfs_paths: HashSet::default(),
+            lfs_locks: HashMap::default(),
         }
     }
 }
@@ -73,6 +112,38 @@ impl FakeGitRepository {
         }
         .boxed()
     }
+
+    /// Loads the `.gitignore` files of the working directory and its ancestors, followed by the
+    /// repository's `info/exclude`.
+    fn load_ignores(&self) -> Vec<Gitignore> {
+        let workdir_path = self.dot_git_path.parent().unwrap();
+        workdir_path
+            .ancestors()
+            .map(|dir| (dir, dir.join(".gitignore")))
+            .chain([(workdir_path, self.dot_git_path.join("info").join("exclude"))])
+            .filter_map(|(dir, ignore_path)| {
+                let content = self.fs.read_file_sync(ignore_path).ok()?;
+                let content = String::from_utf8(content).ok()?;
+                let mut builder = GitignoreBuilder::new(dir);
+                for line in content.lines() {
+                    builder.add_line(Some(dir.into()), line).ok()?;
+                }
+                builder.build().ok()
+            })
+            .collect()
+    }
+}
+
+fn is_path_ignored(ignores: &[Gitignore], abs_path: &Path) -> bool {
+    let mut is_ignored = false;
+    for ignore in ignores {
+        match ignore.matched_path_or_any_parents(abs_path, false) {
+            ignore::Match::None => {}
+            ignore::Match::Ignore(_) => is_ignored = true,
+            ignore::Match::Whitelist(_) => break,
+        }
+    }
+    is_ignored
 }
 
 impl GitRepository for FakeGitRepository {
@@ -108,6 +179,69 @@ impl GitRepository for FakeGitRepository {
         .boxed()
     }
 
+    fn load_committed_bytes(&self, path: RepoPath) -> BoxFuture<'_, Option<Vec<u8>>> {
+        async {
+            self.with_state_async(false, move |state| {
+                state
+                    .head_contents
+                    .get(&path)
+                    .context("not present in HEAD")
+                    .map(|contents| contents.clone().into_bytes())
+            })
+            .await
+            .ok()
+        }
+        .boxed()
+    }
+
+    fn log(&self, options: LogOptions) -> BoxFuture<'_, Result<Vec<CommitDetails>>> {
+        self.with_state_async(false, move |state| {
+            Ok(state
+                .commits
+                .iter()
+                .filter(|commit| {
+                    options
+                        .path
+                        .as_ref()
+                        .is_none_or(|path| commit.changed_paths.contains(path))
+                })
+                // Authors are matched by substring rather than by regular expression.
+                .filter(|commit| {
+                    options.author.as_deref().is_none_or(|author| {
+                        commit.details.author_name.contains(author)
+                            || commit.details.author_email.contains(author)
+                    })
+                })
+                .skip(options.skip)
+                .take(options.limit)
+                .map(|commit| commit.details.clone())
+                .collect())
+        })
+    }
+
+    fn commit_graph(&self, _options: CommitGraphOptions) -> BoxFuture<'_, Result<CommitGraph>> {
+        unimplemented!()
+    }
+
+    fn file_history(
+        &self,
+        path: RepoPath,
+        _follow_renames: bool,
+    ) -> BoxFuture<'_, Result<Vec<FileHistoryEntry>>> {
+        // Renames aren't simulated, so following them makes no difference.
+        self.with_state_async(false, move |state| {
+            Ok(state
+                .commits
+                .iter()
+                .filter(|commit| commit.changed_paths.contains(&path))
+                .map(|commit| FileHistoryEntry {
+                    commit: commit.details.clone(),
+                    path: path.clone(),
+                })
+                .collect())
+        })
+    }
+
     fn load_commit(
         &self,
         _commit: String,
@@ -166,6 +300,42 @@ impl GitRepository for FakeGitRepository {
         unimplemented!()
     }
 
+    fn paths_discarded_by_hard_reset(
+        &self,
+        commit: String,
+    ) -> BoxFuture<'_, Result<Vec<RepoPath>>> {
+        let workdir_path = self.dot_git_path.parent().unwrap();
+        let working_copy: HashMap<RepoPath, String> = self
+            .fs
+            .files()
+            .iter()
+            .filter_map(|path| {
+                let repo_path = path.strip_prefix(workdir_path).ok()?;
+                if repo_path.starts_with(".git") {
+                    return None;
+                }
+                let content = self.fs.read_file_sync(path).ok()?;
+                let repo_path = RelPath::new(repo_path, PathStyle::local()).ok()?;
+                Some((repo_path.into(), String::from_utf8(content).ok()?))
+            })
+            .collect();
+        self.with_state_async(false, move |state| {
+            // Only the contents of HEAD are simulated.
+            if commit != "HEAD" && state.current_branch_name.as_ref() != Some(&commit) {
+                bail!("unknown revision: {commit}");
+            }
+            Ok(working_copy
+                .into_iter()
+                .filter(|(path, content)| {
+                    let committed = state.head_contents.get(path);
+                    (committed.is_some() || state.index_contents.contains_key(path))
+                        && committed != Some(content)
+                })
+                .map(|(path, _)| path)
+                .collect())
+        })
+    }
+
     fn checkout_files(
         &self,
         _commit: String,
@@ -184,26 +354,145 @@ impl GitRepository for FakeGitRepository {
     }
 
     fn merge_message(&self) -> BoxFuture<'_, Option<String>> {
-        async move { None }.boxed()
+        let merge_message = self.with_state_async(false, |state| Ok(state.merge_message.clone()));
+        async move { merge_message.await.ok().flatten() }.boxed()
+    }
+
+    fn commit_template(&self) -> BoxFuture<'_, Option<String>> {
+        let commit_template =
+            self.with_state_async(false, |state| Ok(state.commit_template.clone()));
+        async move { commit_template.await.ok().flatten() }.boxed()
+    }
+
+    fn rebase_state(&self) -> BoxFuture<'_, Option<RebaseState>> {
+        let rebase_state = self.with_state_async(false, |state| Ok(state.rebase_state.clone()));
+        async move { rebase_state.await.ok().flatten() }.boxed()
+    }
+
+    fn rebase_start(
+        &self,
+        onto: String,
+        interactive: bool,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        self.with_state_async(true, move |state| {
+            if state.rebase_state.is_some() {
+                bail!("a rebase is already in progress");
+            }
+            // Non-interactive rebases are simulated as finishing without conflicts, while
+            // interactive ones stop before their first step.
+            if interactive {
+                state.rebase_state = Some(RebaseState {
+                    head_name: state.current_branch_name.clone().map(SharedString::from),
+                    onto: onto.into(),
+                    step: 0,
+                    total_steps: 1,
+                    interactive,
+                    stopped_at: None,
+                });
+            }
+            Ok(())
+        })
+    }
+
+    fn rebase_continue(&self, _env: Arc<HashMap<String, String>>) -> BoxFuture<'_, Result<()>> {
+        self.with_state_async(true, |state| {
+            state
+                .rebase_state
+                .take()
+                .context("no rebase in progress")
+                .map(|_| ())
+        })
+    }
+
+    fn rebase_abort(&self, _env: Arc<HashMap<String, String>>) -> BoxFuture<'_, Result<()>> {
+        self.with_state_async(true, |state| {
+            state
+                .rebase_state
+                .take()
+                .context("no rebase in progress")
+                .map(|_| ())
+        })
+    }
+
+    fn merge(
+        &self,
+        branch: String,
+        _options: MergeOptions,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        self.with_state_async(true, move |state| {
+            if !state.branches.contains(&branch) {
+                bail!("no such branch: {branch}");
+            }
+            if state.refs.contains_key("MERGE_HEAD") {
+                bail!("a merge is already in progress");
+            }
+            // Merges are simulated as stopping on conflicts in the unmerged paths, if there are
+            // any, and as finishing otherwise.
+            if !state.unmerged_paths.is_empty() {
+                let merge_head = state
+                    .refs
+                    .get(&branch)
+                    .cloned()
+                    .unwrap_or_else(|| branch.clone());
+                state.refs.insert("MERGE_HEAD".into(), merge_head);
+                state.merge_message = Some(format!("Merge branch '{branch}'\n"));
+            }
+            Ok(())
+        })
+    }
+
+    fn merge_abort(&self, _env: Arc<HashMap<String, String>>) -> BoxFuture<'_, Result<()>> {
+        self.with_state_async(true, |state| {
+            state
+                .refs
+                .remove("MERGE_HEAD")
+                .context("no merge in progress")?;
+            state.merge_message = None;
+            state.unmerged_paths.clear();
+            Ok(())
+        })
+    }
+
+    fn apply_patch(
+        &self,
+        _patch: String,
+        _options: ApplyPatchOptions,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        unimplemented!()
+    }
+
+    fn format_patch(&self, _range: String) -> BoxFuture<'_, Result<String>> {
+        unimplemented!()
+    }
+
+    fn maintenance(
+        &self,
+        _command: MaintenanceCommand,
+        _progress: mpsc::UnboundedSender<RemoteProgress>,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        unimplemented!()
+    }
+
+    fn gc(
+        &self,
+        _aggressive: bool,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        unimplemented!()
+    }
+
+    fn health(&self) -> BoxFuture<'_, Result<RepositoryHealth>> {
+        unimplemented!()
     }
 
     fn status(&self, path_prefixes: &[RepoPath]) -> Task<Result<GitStatus>> {
         let workdir_path = self.dot_git_path.parent().unwrap();
 
-        // Load gitignores
-        let ignores = workdir_path
-            .ancestors()
-            .filter_map(|dir| {
-                let ignore_path = dir.join(".gitignore");
-                let content = self.fs.read_file_sync(ignore_path).ok()?;
-                let content = String::from_utf8(content).ok()?;
-                let mut builder = GitignoreBuilder::new(dir);
-                for line in content.lines() {
-                    builder.add_line(Some(dir.into()), line).ok()?;
-                }
-                builder.build().ok()
-            })
-            .collect::<Vec<_>>();
+        let ignores = self.load_ignores();
 
         // Load working copy files.
         let git_files: HashMap<RepoPath, (String, bool)> = self
@@ -213,14 +502,7 @@ impl GitRepository for FakeGitRepository {
             .filter_map(|path| {
                 // TODO better simulate git status output in the case of submodules and worktrees
                 let repo_path = path.strip_prefix(workdir_path).ok()?;
-                let mut is_ignored = repo_path.starts_with(".git");
-                for ignore in &ignores {
-                    match ignore.matched_path_or_any_parents(path, false) {
-                        ignore::Match::None => {}
-                        ignore::Match::Ignore(_) => is_ignored = true,
-                        ignore::Match::Whitelist(_) => break,
-                    }
-                }
+                let is_ignored = repo_path.starts_with(".git") || is_path_ignored(&ignores, path);
                 let content = self
                     .fs
                     .read_file_sync(path)
@@ -336,7 +618,13 @@ impl GitRepository for FakeGitRepository {
                     is_head: Some(branch_name) == current_branch.as_ref(),
                     ref_name: branch_name.into(),
                     most_recent_commit: None,
-                    upstream: None,
+                    upstream: state.upstreams.get(branch_name).map(|upstream| Upstream {
+                        ref_name: format!("refs/remotes/{upstream}").into(),
+                        tracking: UpstreamTracking::Tracked(UpstreamTrackingStatus {
+                            ahead: 0,
+                            behind: 0,
+                        }),
+                    }),
                 })
                 .collect())
         })
@@ -369,6 +657,255 @@ impl GitRepository for FakeGitRepository {
         })
     }
 
+    fn delete_branch(&self, name: String, _force: bool) -> BoxFuture<'_, Result<()>> {
+        self.with_state_async(true, move |state| {
+            if state.current_branch_name.as_ref() == Some(&name) {
+                bail!("cannot delete the current branch: {name}");
+            }
+            if !state.branches.remove(&name) {
+                bail!("no such branch: {name}");
+            }
+            state.upstreams.remove(&name);
+            Ok(())
+        })
+    }
+
+    fn set_upstream(&self, branch: String, upstream: String) -> BoxFuture<'_, Result<()>> {
+        self.with_state_async(true, move |state| {
+            if !state.branches.contains(&branch) {
+                bail!("no such branch: {branch}");
+            }
+            state.upstreams.insert(branch, upstream);
+            Ok(())
+        })
+    }
+
+    fn ignore_paths(
+        &self,
+        paths: Vec<RepoPath>,
+        ignore_file: IgnoreFile,
+    ) -> BoxFuture<'_, Result<()>> {
+        let ignore_file_path = match ignore_file {
+            IgnoreFile::Gitignore => self.dot_git_path.parent().unwrap().join(".gitignore"),
+            IgnoreFile::InfoExclude => self.dot_git_path.join("info").join("exclude"),
+        };
+        let fs = self.fs.clone();
+        async move {
+            let content = fs.load(&ignore_file_path).await.unwrap_or_default();
+            if let Some(content) = append_ignore_patterns(&content, &paths) {
+                fs.write(&ignore_file_path, content.as_bytes()).await?;
+            }
+            Ok(())
+        }
+        .boxed()
+    }
+
+    fn is_ignored(&self, path: RepoPath) -> BoxFuture<'_, Result<bool>> {
+        let abs_path = self.dot_git_path.parent().unwrap().join(path.as_std_path());
+        future::ready(Ok(is_path_ignored(&self.load_ignores(), &abs_path))).boxed()
+    }
+
+    fn resolve_conflict(
+        &self,
+        path: RepoPath,
+        side: ConflictSide,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        let abs_path = self.dot_git_path.parent().unwrap().join(path.as_std_path());
+        async move {
+            // The fake doesn't store each side's version of a conflicted file, so the version in
+            // the working copy is taken as the one that's kept.
+            let content = self.fs.load(&abs_path).await.ok();
+            let deleted = self.fs.with_git_state(&self.dot_git_path, true, |state| {
+                let unmerged = state
+                    .unmerged_paths
+                    .remove(&path)
+                    .with_context(|| format!("{path:?} isn't conflicted"))?;
+                let side_status = match side {
+                    ConflictSide::Ours => unmerged.first_head,
+                    ConflictSide::Theirs => unmerged.second_head,
+                };
+                match content {
+                    Some(content) if side_status != UnmergedStatusCode::Deleted => {
+                        state.index_contents.insert(path, content);
+                        anyhow::Ok(false)
+                    }
+                    _ => {
+                        state.index_contents.remove(&path);
+                        anyhow::Ok(true)
+                    }
+                }
+            })??;
+            if deleted {
+                let options = RemoveOptions {
+                    recursive: false,
+                    ignore_if_not_exists: true,
+                };
+                self.fs.remove_file(&abs_path, options).await?;
+            }
+            Ok(())
+        }
+        .boxed()
+    }
+
+    fn clean(
+        &self,
+        paths: Vec<RepoPath>,
+        options: CleanOptions,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<Vec<RepoPath>>> {
+        let workdir_path = self.dot_git_path.parent().unwrap().to_path_buf();
+        let ignores = self.load_ignores();
+        let tracked_paths = self.fs.with_git_state(&self.dot_git_path, false, |state| {
+            state.index_contents.keys().cloned().collect::<HashSet<_>>()
+        });
+        let fs = self.fs.clone();
+        async move {
+            let tracked_paths = tracked_paths?;
+            let mut cleaned_paths = Vec::new();
+            for abs_path in fs.files() {
+                let Ok(relative_path) = abs_path.strip_prefix(&workdir_path) else {
+                    continue;
+                };
+                if relative_path.starts_with(".git") {
+                    continue;
+                }
+                let Ok(repo_path) = RepoPath::from_std_path(relative_path, PathStyle::local())
+                else {
+                    continue;
+                };
+                if tracked_paths.contains(&repo_path)
+                    || (!paths.is_empty() && !paths.iter().any(|path| repo_path.starts_with(path)))
+                    || (!options.ignored && is_path_ignored(&ignores, &abs_path))
+                {
+                    continue;
+                }
+                // Like git, directories without tracked files are deleted as a whole, if at all.
+                let untracked_directory = repo_path
+                    .ancestors()
+                    .skip(1)
+                    .filter(|ancestor| {
+                        !ancestor.is_empty()
+                            && !tracked_paths
+                                .iter()
+                                .any(|tracked_path| tracked_path.starts_with(ancestor))
+                    })
+                    .last();
+                let cleaned_path = match untracked_directory {
+                    Some(_) if !options.directories => continue,
+                    Some(directory) => RepoPath::from(directory),
+                    None => repo_path,
+                };
+                if !cleaned_paths.contains(&cleaned_path) {
+                    cleaned_paths.push(cleaned_path);
+                }
+            }
+            cleaned_paths.sort();
+
+            if !options.dry_run {
+                for path in &cleaned_paths {
+                    let abs_path = workdir_path.join(path.as_std_path());
+                    if fs.is_dir(&abs_path).await {
+                        let options = RemoveOptions {
+                            recursive: true,
+                            ignore_if_not_exists: false,
+                        };
+                        fs.remove_dir(&abs_path, options).await?;
+                    } else {
+                        fs.remove_file(&abs_path, RemoveOptions::default()).await?;
+                    }
+                }
+            }
+            Ok(cleaned_paths)
+        }
+        .boxed()
+    }
+
+    fn lfs_statuses(
+        &self,
+        paths: Vec<RepoPath>,
+    ) -> BoxFuture<'_, Result<HashMap<RepoPath, LfsFileStatus>>> {
+        let workdir_path = self.dot_git_path.parent().unwrap();
+        let lfs_paths = self.fs.with_git_state(&self.dot_git_path, false, |state| {
+            paths
+                .into_iter()
+                .filter(|path| state.lfs_paths.contains(path))
+                .map(|path| {
+                    let lock = state.lfs_locks.get(&path).cloned();
+                    (path, lock)
+                })
+                .collect::<Vec<_>>()
+        });
+        // The files are read once the git state is no longer borrowed, as that borrows the whole
+        // file system.
+        let statuses = lfs_paths.map(|lfs_paths| {
+            lfs_paths
+                .into_iter()
+                .map(|(path, lock)| {
+                    let content = self
+                        .fs
+                        .read_file_sync(workdir_path.join(path.as_std_path()))
+                        .unwrap_or_default();
+                    let status = LfsFileStatus {
+                        is_pointer: is_lfs_pointer(&content),
+                        lock,
+                    };
+                    (path, status)
+                })
+                .collect()
+        });
+        future::ready(statuses).boxed()
+    }
+
+    fn lfs_pull(
+        &self,
+        _askpass: AskPassDelegate,
+        _env: Arc<HashMap<String, String>>,
+        _cx: AsyncApp,
+    ) -> BoxFuture<'_, Result<git::repository::RemoteCommandOutput>> {
+        unimplemented!()
+    }
+
+    fn lfs_lock(
+        &self,
+        path: RepoPath,
+        _askpass: AskPassDelegate,
+        _env: Arc<HashMap<String, String>>,
+        _cx: AsyncApp,
+    ) -> BoxFuture<'_, Result<()>> {
+        self.with_state_async(true, move |state| {
+            if !state.lfs_paths.contains(&path) {
+                bail!("{} isn't stored by Git LFS", path.as_unix_str());
+            }
+            if state.lfs_locks.contains_key(&path) {
+                bail!("{} is already locked", path.as_unix_str());
+            }
+            let lock = LfsLock {
+                id: (state.lfs_locks.len() + 1).to_string(),
+                owner: None,
+                locked_at: None,
+            };
+            state.lfs_locks.insert(path, lock);
+            Ok(())
+        })
+    }
+
+    fn lfs_unlock(
+        &self,
+        path: RepoPath,
+        _force: bool,
+        _askpass: AskPassDelegate,
+        _env: Arc<HashMap<String, String>>,
+        _cx: AsyncApp,
+    ) -> BoxFuture<'_, Result<()>> {
+        self.with_state_async(true, move |state| {
+            if state.lfs_locks.remove(&path).is_none() {
+                bail!("{} isn't locked", path.as_unix_str());
+            }
+            Ok(())
+        })
+    }
+
     fn blame(&self, path: RepoPath, _content: Rope) -> BoxFuture<'_, Result<git::blame::Blame>> {
         self.with_state_async(false, move |state| {
             state
@@ -464,7 +1001,9 @@ impl GitRepository for FakeGitRepository {
         _message: gpui::SharedString,
         _name_and_email: Option<(gpui::SharedString, gpui::SharedString)>,
         _options: CommitOptions,
+        _askpass: AskPassDelegate,
         _env: Arc<HashMap<String, String>>,
+        _cx: AsyncApp,
     ) -> BoxFuture<'_, Result<()>> {
         unimplemented!()
     }
@@ -475,6 +1014,7 @@ impl GitRepository for FakeGitRepository {
         _remote: String,
         _options: Option<PushOptions>,
         _askpass: AskPassDelegate,
+        _progress: mpsc::UnboundedSender<RemoteProgress>,
         _env: Arc<HashMap<String, String>>,
         _cx: AsyncApp,
     ) -> BoxFuture<'_, Result<git::repository::RemoteCommandOutput>> {
@@ -485,7 +1025,9 @@ impl GitRepository for FakeGitRepository {
         &self,
         _branch: String,
         _remote: String,
+        _options: PullOptions,
         _askpass: AskPassDelegate,
+        _progress: mpsc::UnboundedSender<RemoteProgress>,
         _env: Arc<HashMap<String, String>>,
         _cx: AsyncApp,
     ) -> BoxFuture<'_, Result<git::repository::RemoteCommandOutput>> {
@@ -496,24 +1038,140 @@ impl GitRepository for FakeGitRepository {
         &self,
         _fetch_options: FetchOptions,
         _askpass: AskPassDelegate,
+        _progress: mpsc::UnboundedSender<RemoteProgress>,
         _env: Arc<HashMap<String, String>>,
         _cx: AsyncApp,
     ) -> BoxFuture<'_, Result<git::repository::RemoteCommandOutput>> {
         unimplemented!()
     }
 
-    fn get_remotes(&self, _branch: Option<String>) -> BoxFuture<'_, Result<Vec<Remote>>> {
+    fn get_remotes(&self, branch: Option<String>) -> BoxFuture<'_, Result<Vec<Remote>>> {
+        self.with_state_async(false, move |state| {
+            // Only the remotes that some branch tracks are known, by the upstreams' names.
+            let mut names = state
+                .upstreams
+                .iter()
+                .filter(|(branch_name, _)| {
+                    branch.as_ref().is_none_or(|branch| branch == *branch_name)
+                })
+                .filter_map(|(_, upstream)| Some(upstream.split_once('/')?.0.to_string()))
+                .collect::<Vec<_>>();
+            names.sort();
+            names.dedup();
+            Ok(names
+                .into_iter()
+                .map(|name| Remote { name: name.into() })
+                .collect())
+        })
+    }
+
+    fn tags(&self) -> BoxFuture<'_, Result<Vec<Tag>>> {
+        self.with_state_async(false, |state| Ok(state.tags.clone()))
+    }
+
+    fn create_tag(
+        &self,
+        name: String,
+        sha: String,
+        message: Option<String>,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        self.with_state_async(true, move |state| {
+            if state.tags.iter().any(|tag| tag.name.as_ref() == name) {
+                bail!("tag {name:?} already exists");
+            }
+            state.tags.insert(
+                0,
+                Tag {
+                    name: name.into(),
+                    sha: sha.into(),
+                    message: message.map(SharedString::from),
+                },
+            );
+            Ok(())
+        })
+    }
+
+    fn delete_tag(
+        &self,
+        name: String,
+        _env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        self.with_state_async(true, move |state| {
+            let index = state
+                .tags
+                .iter()
+                .position(|tag| tag.name.as_ref() == name)
+                .with_context(|| format!("tag {name:?} not found"))?;
+            state.tags.remove(index);
+            Ok(())
+        })
+    }
+
+    fn push_tag(
+        &self,
+        _name: String,
+        _remote: String,
+        _askpass: AskPassDelegate,
+        _env: Arc<HashMap<String, String>>,
+        _cx: AsyncApp,
+    ) -> BoxFuture<'_, Result<git::repository::RemoteCommandOutput>> {
         unimplemented!()
     }
 
-    fn check_for_pushed_commit(&self) -> BoxFuture<'_, Result<Vec<gpui::SharedString>>> {
+    fn check_for_pushed_commit(&self) -> BoxFuture<'_, Result<Vec<PushedBranch>>> {
         future::ready(Ok(Vec::new())).boxed()
     }
 
-    fn diff(&self, _diff: git::repository::DiffType) -> BoxFuture<'_, Result<String>> {
+    fn diff(&self, diff: DiffType) -> BoxFuture<'_, Result<String>> {
+        self.with_state_async(false, move |state| {
+            // Only staged changes are simulated, each one as a rewrite of the whole file.
+            let DiffType::HeadToIndex = diff else {
+                bail!("only HEAD to index diffs are simulated");
+            };
+            let paths = state
+                .head_contents
+                .keys()
+                .chain(state.index_contents.keys())
+                .collect::<BTreeSet<_>>();
+            let mut output = String::new();
+            for path in paths {
+                let head = state.head_contents.get(path);
+                let index = state.index_contents.get(path);
+                if head == index {
+                    continue;
+                }
+                output.push_str(&format!("--- a/{}\n", path.as_unix_str()));
+                output.push_str(&format!("+++ b/{}\n", path.as_unix_str()));
+                for line in head.into_iter().flat_map(|text| text.lines()) {
+                    output.push_str(&format!("-{line}\n"));
+                }
+                for line in index.into_iter().flat_map(|text| text.lines()) {
+                    output.push_str(&format!("+{line}\n"));
+                }
+            }
+            Ok(output)
+        })
+    }
+
+    fn diff_file(&self, _path: RepoPath, _base_rev: String) -> BoxFuture<'_, Result<String>> {
         unimplemented!()
     }
 
+    fn load_blob(&self, rev: String, path: RepoPath) -> BoxFuture<'_, Result<String>> {
+        self.with_state_async(false, move |state| {
+            // Only the contents of HEAD are simulated.
+            if rev != "HEAD" && state.current_branch_name.as_ref() != Some(&rev) {
+                bail!("unknown revision: {rev}");
+            }
+            state
+                .head_contents
+                .get(&path)
+                .cloned()
+                .with_context(|| format!("{path:?} doesn't exist at {rev}"))
+        })
+    }
+
     fn checkpoint(&self) -> BoxFuture<'static, Result<GitRepositoryCheckpoint>> {
         let executor = self.executor.clone();
         let fs = self.fs.clone();
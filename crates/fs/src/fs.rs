@@ -31,6 +31,7 @@ use serde::{Deserialize, Serialize};
 use smol::io::AsyncWriteExt;
 use std::{
     io::{self, Write},
+    ops::Range,
     path::{Component, Path, PathBuf},
     pin::Pin,
     sync::Arc,
@@ -44,6 +45,8 @@ mod fake_git_repo;
 #[cfg(any(test, feature = "test-support"))]
 use collections::{BTreeMap, btree_map};
 #[cfg(any(test, feature = "test-support"))]
+pub use fake_git_repo::FakeCommit;
+#[cfg(any(test, feature = "test-support"))]
 use fake_git_repo::FakeGitRepositoryState;
 #[cfg(any(test, feature = "test-support"))]
 use git::{
@@ -112,8 +115,27 @@ pub trait Fs: Send + Sync {
         Ok(String::from_utf8(self.load_bytes(path).await?)?)
     }
     async fn load_bytes(&self, path: &Path) -> Result<Vec<u8>>;
+    /// Loads the bytes within the given range of a file, which may be shorter than the range
+    /// when it extends past the end of the file.
+    async fn load_bytes_range(&self, path: &Path, range: Range<u64>) -> Result<Vec<u8>> {
+        let bytes = self.load_bytes(path).await?;
+        let len = bytes.len() as u64;
+        let start = range.start.min(len) as usize;
+        let end = range.end.clamp(range.start, len) as usize;
+        Ok(bytes[start..end].to_vec())
+    }
     async fn atomic_write(&self, path: PathBuf, text: String) -> Result<()>;
-    async fn save(&self, path: &Path, text: &Rope, line_ending: LineEnding) -> Result<()>;
+    async fn save(&self, path: &Path, text: &Rope, line_ending: LineEnding) -> Result<()> {
+        self.save_with_encoding(path, text, line_ending, Encoding::Utf8)
+            .await
+    }
+    async fn save_with_encoding(
+        &self,
+        path: &Path,
+        text: &Rope,
+        line_ending: LineEnding,
+        encoding: Encoding,
+    ) -> Result<()>;
     async fn write(&self, path: &Path, content: &[u8]) -> Result<()>;
     async fn canonicalize(&self, path: &Path) -> Result<PathBuf>;
     async fn is_file(&self, path: &Path) -> bool;
@@ -191,6 +213,91 @@ pub struct RemoveOptions {
     pub ignore_if_not_exists: bool,
 }
 
+/// The character encoding that text is written to disk with.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Encoding {
+    #[default]
+    Utf8,
+    /// UTF-8, preceded by a byte order mark.
+    Utf8Bom,
+    Utf16Le,
+    Utf16Be,
+    /// ISO-8859-1, which can only represent the first 256 code points.
+    Latin1,
+}
+
+impl Encoding {
+    const BYTE_ORDER_MARK: char = '\u{feff}';
+
+    /// Encodes the text, replacing its newlines with the given line ending.
+    pub fn encode(self, text: &Rope, line_ending: LineEnding) -> Result<Vec<u8>> {
+        let content = chunks(text, line_ending).collect::<String>();
+        // Buffers keep any byte order mark that the file was loaded with, so
+        // strip it to avoid writing it twice, or into encodings without one.
+        let content = content
+            .strip_prefix(Self::BYTE_ORDER_MARK)
+            .unwrap_or(&content);
+        let bytes = match self {
+            Encoding::Utf8 => content.as_bytes().to_vec(),
+            Encoding::Utf8Bom => {
+                let mut bytes = Vec::with_capacity(content.len() + 3);
+                bytes.extend_from_slice(Self::BYTE_ORDER_MARK.to_string().as_bytes());
+                bytes.extend_from_slice(content.as_bytes());
+                bytes
+            }
+            Encoding::Utf16Le => std::iter::once(Self::BYTE_ORDER_MARK as u16)
+                .chain(content.encode_utf16())
+                .flat_map(u16::to_le_bytes)
+                .collect(),
+            Encoding::Utf16Be => std::iter::once(Self::BYTE_ORDER_MARK as u16)
+                .chain(content.encode_utf16())
+                .flat_map(u16::to_be_bytes)
+                .collect(),
+            Encoding::Latin1 => content
+                .chars()
+                .map(|character| {
+                    u8::try_from(character)
+                        .map_err(|_| anyhow!("character {character:?} can't be encoded as latin1"))
+                })
+                .collect::<Result<_>>()?,
+        };
+        Ok(bytes)
+    }
+
+    /// Decodes bytes that were written with this encoding. A UTF-16 byte order
+    /// mark takes precedence, since it identifies the encoding unambiguously.
+    pub fn decode(self, bytes: Vec<u8>) -> Result<String> {
+        let encoding = match bytes.as_slice() {
+            [0xff, 0xfe, ..] => Encoding::Utf16Le,
+            [0xfe, 0xff, ..] => Encoding::Utf16Be,
+            _ => self,
+        };
+        match encoding {
+            Encoding::Utf8 | Encoding::Utf8Bom => Ok(String::from_utf8(bytes)?),
+            Encoding::Utf16Le | Encoding::Utf16Be => {
+                anyhow::ensure!(
+                    bytes.len() % 2 == 0,
+                    "UTF-16 content has an odd number of bytes"
+                );
+                let units = bytes.chunks_exact(2).map(|pair| {
+                    let pair = [pair[0], pair[1]];
+                    if encoding == Encoding::Utf16Le {
+                        u16::from_le_bytes(pair)
+                    } else {
+                        u16::from_be_bytes(pair)
+                    }
+                });
+                let content = char::decode_utf16(units).collect::<Result<String, _>>()?;
+                Ok(content
+                    .strip_prefix(Self::BYTE_ORDER_MARK)
+                    .map(ToString::to_string)
+                    .unwrap_or(content))
+            }
+            Encoding::Latin1 => Ok(bytes.into_iter().map(char::from).collect()),
+        }
+    }
+}
+
 #[derive(Copy, Clone, Debug)]
 pub struct Metadata {
     pub inode: u64,
@@ -571,6 +678,22 @@ impl Fs for RealFs {
         Ok(bytes)
     }
 
+    async fn load_bytes_range(&self, path: &Path, range: Range<u64>) -> Result<Vec<u8>> {
+        use std::io::{Read as _, Seek as _};
+
+        let path = path.to_path_buf();
+        let bytes = smol::unblock(move || -> io::Result<Vec<u8>> {
+            let mut file = std::fs::File::open(path)?;
+            file.seek(io::SeekFrom::Start(range.start))?;
+            let mut bytes = Vec::new();
+            file.take(range.end.saturating_sub(range.start))
+                .read_to_end(&mut bytes)?;
+            Ok(bytes)
+        })
+        .await?;
+        Ok(bytes)
+    }
+
     #[cfg(not(target_os = "windows"))]
     async fn atomic_write(&self, path: PathBuf, data: String) -> Result<()> {
         smol::unblock(move || {
@@ -617,15 +740,31 @@ impl Fs for RealFs {
         Ok(())
     }
 
-    async fn save(&self, path: &Path, text: &Rope, line_ending: LineEnding) -> Result<()> {
+    async fn save_with_encoding(
+        &self,
+        path: &Path,
+        text: &Rope,
+        line_ending: LineEnding,
+        encoding: Encoding,
+    ) -> Result<()> {
+        // Encode before creating the file, so that text the encoding can't
+        // represent doesn't truncate it.
+        let encoded = match encoding {
+            Encoding::Utf8 => None,
+            encoding => Some(encoding.encode(text, line_ending)?),
+        };
         let buffer_size = text.summary().len.min(10 * 1024);
         if let Some(path) = path.parent() {
             self.create_dir(path).await?;
         }
         let file = smol::fs::File::create(path).await?;
         let mut writer = smol::io::BufWriter::with_capacity(buffer_size, file);
-        for chunk in chunks(text, line_ending) {
-            writer.write_all(chunk.as_bytes()).await?;
+        if let Some(encoded) = encoded {
+            writer.write_all(&encoded).await?;
+        } else {
+            for chunk in chunks(text, line_ending) {
+                writer.write_all(chunk.as_bytes()).await?;
+            }
         }
         writer.flush().await?;
         Ok(())
@@ -1675,6 +1814,12 @@ impl FakeFs {
         .unwrap();
     }
 
+    /// Sets the commits reachable from `HEAD`, newest first.
+    pub fn set_commits_for_repo(&self, dot_git: &Path, commits: Vec<FakeCommit>) {
+        self.with_git_state(dot_git, true, |state| state.commits = commits)
+            .unwrap();
+    }
+
     pub fn set_blame_for_repo(&self, dot_git: &Path, blames: Vec<(RepoPath, git::blame::Blame)>) {
         self.with_git_state(dot_git, true, |state| {
             state.blames.clear();
@@ -2283,14 +2428,23 @@ impl Fs for FakeFs {
         Ok(())
     }
 
-    async fn save(&self, path: &Path, text: &Rope, line_ending: LineEnding) -> Result<()> {
+    async fn save_with_encoding(
+        &self,
+        path: &Path,
+        text: &Rope,
+        line_ending: LineEnding,
+        encoding: Encoding,
+    ) -> Result<()> {
         self.simulate_random_delay().await;
         let path = normalize_path(path);
-        let content = chunks(text, line_ending).collect::<String>();
+        let content = match encoding {
+            Encoding::Utf8 => chunks(text, line_ending).collect::<String>().into_bytes(),
+            encoding => encoding.encode(text, line_ending)?,
+        };
         if let Some(path) = path.parent() {
             self.create_dir(path).await?;
         }
-        self.write_file_internal(path, content.into_bytes(), false)?;
+        self.write_file_internal(path, content, false)?;
         Ok(())
     }
 
@@ -3129,4 +3283,77 @@ mod tests {
         let content = std::fs::read_to_string(&file_to_be_replaced).unwrap();
         assert_eq!(content, "Hello");
     }
+
+    #[gpui::test]
+    async fn test_realfs_load_bytes_range(executor: BackgroundExecutor) {
+        let fs = RealFs {
+            bundled_git_binary_path: None,
+            executor,
+        };
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("file.bin");
+        std::fs::write(&path, b"0123456789").unwrap();
+        assert_eq!(
+            smol::block_on(fs.load_bytes_range(&path, 2..5)).unwrap(),
+            b"234"
+        );
+        assert_eq!(
+            smol::block_on(fs.load_bytes_range(&path, 8..16)).unwrap(),
+            b"89"
+        );
+        assert_eq!(
+            smol::block_on(fs.load_bytes_range(&path, 12..16)).unwrap(),
+            b""
+        );
+    }
+
+    #[test]
+    fn test_encoding() {
+        let text = Rope::from("\u{feff}é\nb\n");
+        assert_eq!(
+            Encoding::Utf8.encode(&text, LineEnding::Windows).unwrap(),
+            "é\r\nb\r\n".as_bytes()
+        );
+        assert_eq!(
+            Encoding::Utf8Bom.encode(&text, LineEnding::Unix).unwrap(),
+            "\u{feff}é\nb\n".as_bytes()
+        );
+        assert_eq!(
+            Encoding::Utf16Le.encode(&text, LineEnding::Unix).unwrap(),
+            [0xff, 0xfe, 0xe9, 0x00, b'\n', 0x00, b'b', 0x00, b'\n', 0x00]
+        );
+        assert_eq!(
+            Encoding::Utf16Be.encode(&text, LineEnding::Unix).unwrap(),
+            [0xfe, 0xff, 0x00, 0xe9, 0x00, b'\n', 0x00, b'b', 0x00, b'\n']
+        );
+        assert_eq!(
+            Encoding::Latin1.encode(&text, LineEnding::Unix).unwrap(),
+            [0xe9, b'\n', b'b', b'\n']
+        );
+        assert!(
+            Encoding::Latin1
+                .encode(&Rope::from("€"), LineEnding::Unix)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_decoding() {
+        let text = Rope::from("é\nb\n");
+        for encoding in [
+            Encoding::Utf8,
+            Encoding::Utf16Le,
+            Encoding::Utf16Be,
+            Encoding::Latin1,
+        ] {
+            let bytes = encoding.encode(&text, LineEnding::Unix).unwrap();
+            assert_eq!(encoding.decode(bytes).unwrap(), "é\nb\n", "{encoding:?}");
+        }
+        assert_eq!(
+            Encoding::Utf8.decode(vec![0xff, 0xfe, b'a', 0x00]).unwrap(),
+            "a"
+        );
+        assert!(Encoding::Utf8.decode(vec![0xe9]).is_err());
+        assert!(Encoding::Utf16Le.decode(vec![0xff, 0xfe, b'a']).is_err());
+    }
 }
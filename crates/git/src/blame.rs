@@ -59,6 +59,39 @@ impl Blame {
             remote_url,
         })
     }
+
+    /// Returns the commit that last changed each of the given rows, for the rows that are
+    /// covered by the blame.
+    pub fn lines(&self, rows: Range<u32>) -> Vec<LineBlame> {
+        let mut lines = Vec::new();
+        for entry in &self.entries {
+            let start = entry.range.start.max(rows.start);
+            let end = entry.range.end.min(rows.end);
+            lines.extend((start..end).map(|row| LineBlame {
+                row,
+                sha: entry.sha,
+                author: entry.author.clone(),
+                author_mail: entry.author_mail.clone(),
+                author_time: entry.author_time,
+                author_tz: entry.author_tz.clone(),
+                summary: entry.summary.clone(),
+            }));
+        }
+        lines.sort_unstable_by_key(|line| line.row);
+        lines
+    }
+}
+
+/// The commit that last changed a single line of a file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LineBlame {
+    pub row: u32,
+    pub sha: Oid,
+    pub author: Option<String>,
+    pub author_mail: Option<String>,
+    pub author_time: Option<i64>,
+    pub author_tz: Option<String>,
+    pub summary: Option<String>,
 }
 
 const GIT_BLAME_NO_COMMIT_ERROR: &str = "fatal: no such ref: HEAD";
@@ -309,6 +342,7 @@ mod tests {
 
     use super::BlameEntry;
     use super::parse_git_blame;
+    use super::{Blame, LineBlame};
 
     fn read_test_data(filename: &str) -> String {
         let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
@@ -368,4 +402,39 @@ mod tests {
         let entries = parse_git_blame(&output).unwrap();
         assert_eq_golden(&entries, "blame_incremental_complex");
     }
+
+    #[test]
+    fn test_blame_lines() {
+        let output = read_test_data("blame_incremental_simple");
+        let blame = Blame {
+            entries: parse_git_blame(&output).unwrap(),
+            ..Default::default()
+        };
+        // Rows that no entry covers are skipped.
+        let lines = blame.lines(1..6);
+        assert_eq!(
+            lines.iter().map(|line| line.row).collect::<Vec<_>>(),
+            [1, 3, 4, 5]
+        );
+        for line in &lines {
+            let entry = blame
+                .entries
+                .iter()
+                .find(|entry| entry.range.contains(&line.row))
+                .unwrap();
+            assert_eq!(
+                *line,
+                LineBlame {
+                    row: line.row,
+                    sha: entry.sha,
+                    author: entry.author.clone(),
+                    author_mail: entry.author_mail.clone(),
+                    author_time: entry.author_time,
+                    author_tz: entry.author_tz.clone(),
+                    summary: entry.summary.clone(),
+                }
+            );
+        }
+        assert!(blame.lines(10_000..10_010).is_empty());
+    }
 }
@@ -1,7 +1,7 @@
 use crate::{Oid, status::StatusCode};
 use anyhow::{Context as _, Result};
 use collections::HashMap;
-use std::path::Path;
+use std::{fmt, path::Path};
 
 pub async fn get_messages(working_directory: &Path, shas: &[Oid]) -> Result<HashMap<Oid, String>> {
     if shas.is_empty() {
@@ -75,11 +75,223 @@ pub fn parse_git_diff_name_status(content: &str) -> impl Iterator<Item = (&str,
     })
 }
 
+/// A `Key: value` line in the last paragraph of a commit message, such as `Co-authored-by`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Trailer {
+    pub key: String,
+    pub value: String,
+}
+
+impl Trailer {
+    pub const CO_AUTHORED_BY: &str = "Co-authored-by";
+    pub const SIGNED_OFF_BY: &str = "Signed-off-by";
+
+    pub fn co_authored_by(name: &str, email: &str) -> Self {
+        Self {
+            key: Self::CO_AUTHORED_BY.to_string(),
+            value: format!("{name} <{email}>"),
+        }
+    }
+
+    pub fn signed_off_by(name: &str, email: &str) -> Self {
+        Self {
+            key: Self::SIGNED_OFF_BY.to_string(),
+            value: format!("{name} <{email}>"),
+        }
+    }
+
+    /// Whether the trailer's value is a person, as `Name <email>`.
+    fn names_person(&self) -> bool {
+        self.key.eq_ignore_ascii_case(Self::CO_AUTHORED_BY)
+            || self.key.eq_ignore_ascii_case(Self::SIGNED_OFF_BY)
+    }
+
+    /// The email address in a value of the form `Name <email>`.
+    pub fn email(&self) -> Option<&str> {
+        let (_, email) = self.value.trim_end().strip_suffix('>')?.rsplit_once('<')?;
+        Some(email.trim()).filter(|email| !email.is_empty())
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        anyhow::ensure!(
+            is_trailer_key(&self.key),
+            "invalid commit trailer key {:?}",
+            self.key
+        );
+        anyhow::ensure!(
+            !self.value.trim().is_empty() && !self.value.contains('\n'),
+            "the {} trailer must have a value on a single line",
+            self.key
+        );
+        if self.names_person() {
+            anyhow::ensure!(
+                self.email().is_some_and(|email| email.contains('@')),
+                "the {} trailer must be of the form `Name <email>`, not {:?}",
+                self.key,
+                self.value
+            );
+        }
+        Ok(())
+    }
+
+    /// Whether the trailers credit the same thing. People are told apart by their email
+    /// addresses, as their names may be spelled differently.
+    fn is_duplicate_of(&self, other: &Trailer) -> bool {
+        if !self.key.eq_ignore_ascii_case(&other.key) {
+            return false;
+        }
+        match (self.names_person(), self.email(), other.email()) {
+            (true, Some(email), Some(other_email)) => email.eq_ignore_ascii_case(other_email),
+            _ => self.value.trim() == other.value.trim(),
+        }
+    }
+}
+
+impl fmt::Display for Trailer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.key, self.value)
+    }
+}
+
+fn is_trailer_key(key: &str) -> bool {
+    !key.is_empty()
+        && key
+            .chars()
+            .all(|character| character.is_ascii_alphanumeric() || character == '-')
+}
+
+/// Parses the trailers in the last paragraph of a commit message. The paragraph only counts as
+/// trailers if every line in it is one, and the subject never does.
+pub fn parse_trailers(message: &str) -> Vec<Trailer> {
+    let lines = message
+        .lines()
+        .filter(|line| !line.starts_with('#'))
+        .map(str::trim_end)
+        .collect::<Vec<_>>();
+    let Some(last_line) = lines.iter().rposition(|line| !line.is_empty()) else {
+        return Vec::new();
+    };
+    let Some(blank_line) = lines[..last_line].iter().rposition(|line| line.is_empty()) else {
+        return Vec::new();
+    };
+    if lines[..blank_line].iter().all(|line| line.is_empty()) {
+        return Vec::new();
+    }
+
+    let mut trailers = Vec::<Trailer>::new();
+    for line in &lines[blank_line + 1..=last_line] {
+        if line.starts_with(char::is_whitespace) {
+            let Some(trailer) = trailers.last_mut() else {
+                return Vec::new();
+            };
+            trailer.value.push(' ');
+            trailer.value.push_str(line.trim());
+            continue;
+        }
+        let Some((key, value)) = line.split_once(':') else {
+            return Vec::new();
+        };
+        if !is_trailer_key(key) {
+            return Vec::new();
+        }
+        trailers.push(Trailer {
+            key: key.to_string(),
+            value: value.trim().to_string(),
+        });
+    }
+    trailers
+}
+
+/// Appends the trailers to the end of a commit message, leaving out the ones that it already has.
+pub fn append_trailers(message: &str, trailers: &[Trailer]) -> Result<String> {
+    for trailer in trailers {
+        trailer.validate()?;
+    }
+
+    let existing_trailers = parse_trailers(message);
+    let mut new_trailers = Vec::<&Trailer>::new();
+    for trailer in trailers {
+        let is_duplicate = existing_trailers
+            .iter()
+            .chain(new_trailers.iter().copied())
+            .any(|existing| trailer.is_duplicate_of(existing));
+        if !is_duplicate {
+            new_trailers.push(trailer);
+        }
+    }
+    if new_trailers.is_empty() {
+        return Ok(message.to_string());
+    }
+
+    let mut result = message.trim_end().to_string();
+    // Trailers have to be in a paragraph of their own to be recognized.
+    if existing_trailers.is_empty() {
+        result.push('\n');
+    }
+    for trailer in new_trailers {
+        result.push('\n');
+        result.push_str(&trailer.to_string());
+    }
+    result.push('\n');
+    Ok(result)
+}
+
 #[cfg(test)]
 mod tests {
 
     use super::*;
 
+    #[test]
+    fn test_parse_trailers() {
+        let message = "Fix the thing\n\nThe thing was broken: badly.\n\nCo-authored-by: Alice <alice@example.com>\nReviewed-by: Bob\n  Builder\n";
+        assert_eq!(
+            parse_trailers(message),
+            [
+                Trailer::co_authored_by("Alice", "alice@example.com"),
+                Trailer {
+                    key: "Reviewed-by".into(),
+                    value: "Bob Builder".into(),
+                },
+            ]
+        );
+
+        // The subject is never a trailer, and neither is a paragraph with other lines in it.
+        assert!(parse_trailers("fix: the thing").is_empty());
+        assert!(parse_trailers("Fix\n\nSigned-off-by: A <a@b.c>\nnot a trailer").is_empty());
+        assert!(parse_trailers("Fix\n\nFixes the: thing").is_empty());
+    }
+
+    #[test]
+    fn test_append_trailers() {
+        let alice = Trailer::co_authored_by("Alice", "alice@example.com");
+        let bob = Trailer::co_authored_by("Bob", "bob@example.com");
+
+        assert_eq!(
+            append_trailers("Fix the thing\n", &[alice.clone()]).unwrap(),
+            "Fix the thing\n\nCo-authored-by: Alice <alice@example.com>\n"
+        );
+
+        // Trailers are added to the existing ones, unless they credit the same person.
+        let message = "Fix the thing\n\nCo-authored-by: A. <ALICE@example.com>\n";
+        assert_eq!(
+            append_trailers(message, &[alice.clone(), bob.clone(), bob]).unwrap(),
+            "Fix the thing\n\nCo-authored-by: A. <ALICE@example.com>\nCo-authored-by: Bob <bob@example.com>\n"
+        );
+        assert_eq!(append_trailers(message, &[alice]).unwrap(), message);
+
+        assert!(append_trailers("Fix", &[Trailer::co_authored_by("Alice", "")]).is_err());
+        assert!(
+            append_trailers(
+                "Fix",
+                &[Trailer {
+                    key: "Not a key".into(),
+                    value: "value".into(),
+                }]
+            )
+            .is_err()
+        );
+    }
+
     #[test]
     fn test_parse_git_diff_name_status() {
         let input = concat!(
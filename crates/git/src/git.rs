@@ -1,6 +1,7 @@
 pub mod blame;
 pub mod commit;
 mod hosting_provider;
+pub mod lfs;
 mod remote;
 pub mod repository;
 pub mod stash;
@@ -96,6 +97,10 @@ actions!(
         Clone,
         /// Adds a file to .gitignore.
         AddToGitignore,
+        /// Resolves a conflicted file by keeping our version of it.
+        TakeOurs,
+        /// Resolves a conflicted file by keeping their version of it.
+        TakeTheirs,
     ]
 );
 
@@ -0,0 +1,123 @@
+use crate::repository::RepoPath;
+use anyhow::Result;
+use serde::Deserialize;
+use util::ResultExt as _;
+
+/// The first line of the pointer files that Git LFS stores in place of the files it tracks.
+pub const LFS_POINTER_PREFIX: &[u8] = b"version https://git-lfs.github.com/spec/v1\n";
+
+/// The Git LFS state of a file that's tracked by Git LFS.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LfsFileStatus {
+    /// Whether the working copy only contains the pointer to the file's content, because the
+    /// content wasn't downloaded yet.
+    pub is_pointer: bool,
+    /// The lock on the file, if anyone holds one.
+    pub lock: Option<LfsLock>,
+}
+
+/// A lock on a file tracked by Git LFS, which stops anyone but its owner from pushing changes to
+/// it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LfsLock {
+    pub id: String,
+    pub owner: Option<String>,
+    pub locked_at: Option<String>,
+}
+
+pub fn is_lfs_pointer(content: &[u8]) -> bool {
+    content.starts_with(LFS_POINTER_PREFIX)
+}
+
+/// Parses the output of `git check-attr -z filter`, returning the paths that are stored by Git
+/// LFS.
+pub fn parse_lfs_filter_attributes(output: &str) -> Vec<RepoPath> {
+    let mut fields = output.split('\0');
+    let mut paths = Vec::new();
+    while let (Some(path), Some(_attribute), Some(value)) =
+        (fields.next(), fields.next(), fields.next())
+    {
+        if value == "lfs"
+            && let Some(path) = RepoPath::new(path).log_err()
+        {
+            paths.push(path);
+        }
+    }
+    paths
+}
+
+#[derive(Deserialize)]
+struct LfsLockJson {
+    id: String,
+    path: String,
+    owner: Option<LfsLockOwnerJson>,
+    locked_at: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct LfsLockOwnerJson {
+    name: String,
+}
+
+/// Parses the output of `git lfs locks --json`.
+pub fn parse_lfs_locks(output: &str) -> Result<Vec<(RepoPath, LfsLock)>> {
+    let locks: Vec<LfsLockJson> = serde_json::from_str(output)?;
+    locks
+        .into_iter()
+        .map(|lock| {
+            Ok((
+                RepoPath::new(&lock.path)?,
+                LfsLock {
+                    id: lock.id,
+                    owner: lock.owner.map(|owner| owner.name),
+                    locked_at: lock.locked_at,
+                },
+            ))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repository::repo_path;
+
+    #[test]
+    fn test_parse_lfs_filter_attributes() {
+        let output = "a.psd\0filter\0lfs\0b.txt\0filter\0unspecified\0dir/c.bin\0filter\0lfs\0";
+        assert_eq!(
+            parse_lfs_filter_attributes(output),
+            [repo_path("a.psd"), repo_path("dir/c.bin")]
+        );
+    }
+
+    #[test]
+    fn test_parse_lfs_locks() {
+        let output = r#"[
+            {"id": "1", "path": "a.psd", "owner": {"name": "alice"}, "locked_at": "2016-05-17T15:49:06Z"},
+            {"id": "2", "path": "dir/c.bin"}
+        ]"#;
+        assert_eq!(
+            parse_lfs_locks(output).unwrap(),
+            [
+                (
+                    repo_path("a.psd"),
+                    LfsLock {
+                        id: "1".into(),
+                        owner: Some("alice".into()),
+                        locked_at: Some("2016-05-17T15:49:06Z".into()),
+                    }
+                ),
+                (
+                    repo_path("dir/c.bin"),
+                    LfsLock {
+                        id: "2".into(),
+                        owner: None,
+                        locked_at: None,
+                    }
+                ),
+            ]
+        );
+        assert!(parse_lfs_locks("[]").unwrap().is_empty());
+    }
+}
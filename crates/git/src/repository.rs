@@ -1,9 +1,13 @@
 use crate::commit::parse_git_diff_name_status;
+use crate::lfs::{
+    LFS_POINTER_PREFIX, LfsFileStatus, is_lfs_pointer, parse_lfs_filter_attributes, parse_lfs_locks,
+};
 use crate::stash::GitStash;
 use crate::status::{GitStatus, StatusCode};
 use crate::{Oid, SHORT_SHA_LENGTH};
 use anyhow::{Context as _, Result, anyhow, bail};
-use collections::HashMap;
+use collections::{HashMap, HashSet};
+use futures::channel::mpsc;
 use futures::future::BoxFuture;
 use futures::io::BufWriter;
 use futures::{AsyncWriteExt, FutureExt as _, select_biased};
@@ -22,6 +26,7 @@ use std::{
     future,
     path::{Path, PathBuf},
     sync::Arc,
+    time::Duration,
 };
 use sum_tree::MapSeekTarget;
 use thiserror::Error;
@@ -35,6 +40,10 @@ pub use askpass::{AskPassDelegate, AskPassResult, AskPassSession};
 
 pub const REMOTE_CANCELLED_BY_USER: &str = "Operation cancelled by user";
 
+/// The largest file that [`GitRepository::load_blob`] loads, since its content is loaded into
+/// memory as a whole and may be sent to guests.
+pub const MAX_BLOB_SIZE: usize = 10 * 1024 * 1024;
+
 #[derive(Clone, Debug, Hash, PartialEq, Eq)]
 pub struct Branch {
     pub is_head: bool,
@@ -98,6 +107,45 @@ impl Upstream {
 pub struct CommitOptions {
     pub amend: bool,
     pub signoff: bool,
+    /// Signs the commit even if `commit.gpgsign` isn't set.
+    pub sign: bool,
+}
+
+/// The kind of signature that commits are signed with, as configured by `gpg.format`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SigningFormat {
+    #[default]
+    OpenPgp,
+    X509,
+    Ssh,
+}
+
+impl SigningFormat {
+    pub fn from_config(format: Option<&str>) -> Result<Self> {
+        match format {
+            None | Some("openpgp") => Ok(Self::OpenPgp),
+            Some("x509") => Ok(Self::X509),
+            Some("ssh") => Ok(Self::Ssh),
+            Some(format) => bail!("unsupported gpg.format {format:?}"),
+        }
+    }
+}
+
+/// Returns the format that a commit will be signed with, or `None` if it won't be signed.
+fn commit_signing_format(config: &git2::Config, sign: bool) -> Result<Option<SigningFormat>> {
+    let sign = sign || config.get_bool("commit.gpgsign").unwrap_or(false);
+    if !sign {
+        return Ok(None);
+    }
+    let format = SigningFormat::from_config(config.get_string("gpg.format").ok().as_deref())?;
+    // Unlike gpg, ssh-keygen has no default key, so git would fail with a less helpful message.
+    if format == SigningFormat::Ssh
+        && config.get_string("user.signingkey").is_err()
+        && config.get_string("gpg.ssh.defaultKeyCommand").is_err()
+    {
+        bail!("Signing commits with SSH requires user.signingkey to be set");
+    }
+    Ok(Some(format))
 }
 
 #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
@@ -139,6 +187,111 @@ impl RemoteCommandOutput {
     }
 }
 
+/// A fetch, push or pull in a shared project that was given up on, because the host went too
+/// long without responding or reporting progress.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RemoteCommandTimedOut {
+    /// The git command, like "fetch".
+    pub command: &'static str,
+    /// How long the host was waited on each time.
+    pub timeout: Duration,
+    pub attempts: u32,
+}
+
+impl std::fmt::Display for RemoteCommandTimedOut {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            formatter,
+            "The host didn't respond to git {} within {} seconds",
+            self.command,
+            self.timeout.as_secs()
+        )?;
+        if self.attempts > 1 {
+            write!(formatter, " in any of {} attempts", self.attempts)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for RemoteCommandTimedOut {}
+
+/// A command that writes the index failed, because another git process held the index lock for
+/// as long as the command was retried.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IndexLocked {
+    pub lock_path: PathBuf,
+}
+
+impl IndexLocked {
+    const MESSAGE: &str = "Another git process is running";
+
+    /// Whether the error was caused by an [`IndexLocked`], including when it's a host's response
+    /// to a guest, which only carries the error's message.
+    pub fn is_cause_of(error: &anyhow::Error) -> bool {
+        error
+            .chain()
+            .any(|cause| cause.is::<IndexLocked>() || cause.to_string().contains(Self::MESSAGE))
+    }
+}
+
+impl std::fmt::Display for IndexLocked {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            formatter,
+            "{}: {} exists",
+            Self::MESSAGE,
+            self.lock_path.display()
+        )
+    }
+}
+
+impl std::error::Error for IndexLocked {}
+
+/// The progress of a fetch, push or pull through one of its phases, as reported by git.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RemoteProgress {
+    /// What git is doing, like "Receiving objects" or "Compressing objects".
+    pub phase: String,
+    /// How much of the phase is done, if git knows how long the phase is.
+    pub percent: Option<u8>,
+    pub completed: u64,
+    pub total: Option<u64>,
+}
+
+/// Parses a line of the progress that git writes to stderr, like
+/// "Receiving objects:  45% (450/1000), 1.20 MiB | 512.00 KiB/s" or
+/// "remote: Enumerating objects: 12, done.".
+pub fn parse_remote_progress(line: &str) -> Option<RemoteProgress> {
+    let line = line.trim();
+    let line = line.strip_prefix("remote:").map_or(line, str::trim_start);
+    let (phase, rest) = line.split_once(": ")?;
+    let rest = rest.trim_start();
+    if let Some((percent, rest)) = rest.split_once('%') {
+        let (completed, total) = rest
+            .trim_start()
+            .strip_prefix('(')?
+            .split_once(')')?
+            .0
+            .split_once('/')?;
+        Some(RemoteProgress {
+            phase: phase.to_string(),
+            percent: Some(percent.parse().ok()?),
+            completed: completed.parse().ok()?,
+            total: Some(total.parse().ok()?),
+        })
+    } else {
+        let count = rest
+            .split(|character: char| !character.is_ascii_digit())
+            .next()?;
+        Some(RemoteProgress {
+            phase: phase.to_string(),
+            percent: None,
+            completed: count.parse().ok()?,
+            total: None,
+        })
+    }
+}
+
 #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
 pub struct UpstreamTrackingStatus {
     pub ahead: u32,
@@ -182,11 +335,333 @@ impl CommitDetails {
     }
 }
 
+/// Selects a page of the commits reachable from `HEAD`, which are listed newest first.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LogOptions {
+    /// The maximum number of commits to list.
+    pub limit: usize,
+    /// The number of matching commits to skip, which are usually the ones loaded already.
+    pub skip: usize,
+    /// Only lists commits that changed this path.
+    pub path: Option<RepoPath>,
+    /// Only lists commits whose author's name or email matches this regular expression.
+    pub author: Option<String>,
+}
+
+/// Separates the commits in the output of `git log`, and the tags in the output of
+/// `git for-each-ref`, since their messages can contain any other separator that `--format` can
+/// print, apart from the null character, which separates their fields.
+const LOG_RECORD_SEPARATOR: char = '\x1e';
+const LOG_FORMAT: &str = "--format=%H%x00%B%x00%at%x00%ae%x00%an%x00%x1e";
+
+/// Like [`LOG_FORMAT`], but the separator starts each record, so that the changed paths that
+/// `--name-only` prints after a commit end up in the same record.
+const FILE_HISTORY_FORMAT: &str = "--format=%x1e%H%x00%B%x00%at%x00%ae%x00%an%x00";
+
+fn parse_log_output(output: &str) -> Result<Vec<CommitDetails>> {
+    output
+        .split(LOG_RECORD_SEPARATOR)
+        .map(|record| record.trim_start_matches('\n'))
+        .filter(|record| !record.is_empty())
+        .map(|record| Ok(parse_log_record(record)?.0))
+        .collect()
+}
+
+/// Parses a commit formatted by [`LOG_FORMAT`] or [`FILE_HISTORY_FORMAT`], along with whatever
+/// git printed after its fields.
+fn parse_log_record(record: &str) -> Result<(CommitDetails, &str)> {
+    let fields = record.split('\0').collect::<Vec<_>>();
+    if fields.len() != 6 {
+        bail!("unexpected git-log output: {record:?}")
+    }
+    let commit = CommitDetails {
+        sha: fields[0].to_string().into(),
+        message: fields[1].to_string().into(),
+        commit_timestamp: fields[2].parse()?,
+        author_email: fields[3].to_string().into(),
+        author_name: fields[4].to_string().into(),
+    };
+    Ok((commit, fields[5]))
+}
+
+fn parse_file_history_output(output: &str, path: &RepoPath) -> Result<Vec<FileHistoryEntry>> {
+    let mut entries = Vec::<FileHistoryEntry>::new();
+    for record in output.split(LOG_RECORD_SEPARATOR) {
+        if record.trim().is_empty() {
+            continue;
+        }
+        let (commit, changed_paths) = parse_log_record(record)?;
+        let path = match changed_paths.lines().find(|line| !line.is_empty()) {
+            Some(changed_path) => RepoPath::new(changed_path)?,
+            // Merges don't list the paths they changed, so the file keeps the name it has in
+            // the newer commits.
+            None => entries
+                .last()
+                .map_or_else(|| path.clone(), |entry| entry.path.clone()),
+        };
+        entries.push(FileHistoryEntry { commit, path });
+    }
+    Ok(entries)
+}
+
+/// Selects a page of the commits to draw in a branch graph, which are listed in topological
+/// order, so that no commit comes before any of its children.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CommitGraphOptions {
+    /// The revisions whose history is listed, like `main` or `main..feature`. Every branch, tag and
+    /// remote branch when unset.
+    pub range: Option<String>,
+    /// The maximum number of commits to list.
+    pub limit: usize,
+    /// The number of commits to skip, which are usually the ones loaded already.
+    pub skip: usize,
+}
+
+/// A commit in a branch graph. The graph's edges go from each commit to its parents.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CommitGraphNode {
+    pub sha: Oid,
+    /// The commit's parents, first parent first. Parents that aren't part of the selected range
+    /// are still listed, so that edges leaving the range can be drawn.
+    pub parents: Vec<Oid>,
+    /// The refs pointing at the commit, as git decorates them, like `HEAD -> main` or `tag: v1`.
+    pub refs: Vec<SharedString>,
+    pub commit_timestamp: i64,
+    pub author_name: SharedString,
+    pub subject: SharedString,
+}
+
+/// A page of a branch graph.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CommitGraph {
+    pub commits: Vec<CommitGraphNode>,
+    /// Whether there are more commits after this page.
+    pub has_more: bool,
+}
+
+const COMMIT_GRAPH_FORMAT: &str = "--format=%H%x00%P%x00%D%x00%at%x00%an%x00%s%x1e";
+
+fn parse_commit_graph_output(output: &str) -> Result<Vec<CommitGraphNode>> {
+    output
+        .split(LOG_RECORD_SEPARATOR)
+        .map(|record| record.trim_start_matches('\n'))
+        .filter(|record| !record.is_empty())
+        .map(|record| {
+            let fields = record.split('\0').collect::<Vec<_>>();
+            if fields.len() != 6 {
+                bail!("unexpected git-log output: {record:?}")
+            }
+            Ok(CommitGraphNode {
+                sha: fields[0].parse()?,
+                parents: fields[1]
+                    .split_whitespace()
+                    .map(str::parse)
+                    .collect::<Result<_>>()?,
+                refs: fields[2]
+                    .split(", ")
+                    .filter(|decoration| !decoration.is_empty())
+                    .map(|decoration| decoration.to_string().into())
+                    .collect(),
+                commit_timestamp: fields[3].parse()?,
+                author_name: fields[4].to_string().into(),
+                subject: fields[5].to_string().into(),
+            })
+        })
+        .collect()
+}
+
+/// A commit that changed a file, as listed by [`GitRepository::file_history`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FileHistoryEntry {
+    pub commit: CommitDetails,
+    /// The file's path as of the commit, which differs from its current path if it was renamed
+    /// since.
+    pub path: RepoPath,
+}
+
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
 pub struct Remote {
     pub name: SharedString,
 }
 
+/// A remote branch that already contains the `HEAD` commit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PushedBranch {
+    /// The branch's name, including its remote, such as `origin/main`.
+    pub name: SharedString,
+    pub remote_name: SharedString,
+    pub remote_url: Option<SharedString>,
+    /// The commit that the branch points to.
+    pub tip_sha: SharedString,
+    pub tip_subject: SharedString,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tag {
+    pub name: SharedString,
+    /// The commit that the tag points to.
+    pub sha: SharedString,
+    /// The message of an annotated tag. Lightweight tags don't have one.
+    pub message: Option<SharedString>,
+}
+
+const TAG_FORMAT: &str =
+    "--format=%(refname:strip=2)%00%(objecttype)%00%(objectname)%00%(*objectname)%00%(contents)%1e";
+
+fn parse_tag_output(output: &str) -> Result<Vec<Tag>> {
+    output
+        .split(LOG_RECORD_SEPARATOR)
+        .map(|record| record.trim_start_matches('\n'))
+        .filter(|record| !record.is_empty())
+        .map(|record| {
+            let fields = record.split('\0').collect::<Vec<_>>();
+            let [name, object_type, object_name, peeled_object_name, contents] = fields[..] else {
+                bail!("unexpected git-for-each-ref output: {record:?}")
+            };
+            // Annotated tags are objects of their own, which point to the tagged commit.
+            let annotated = object_type == "tag";
+            Ok(Tag {
+                name: name.to_string().into(),
+                sha: if annotated {
+                    peeled_object_name
+                } else {
+                    object_name
+                }
+                .to_string()
+                .into(),
+                message: annotated.then(|| contents.trim_end().to_string().into()),
+            })
+        })
+        .collect()
+}
+
+/// The file that [`GitRepository::ignore_paths`] adds patterns to.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum IgnoreFile {
+    /// The `.gitignore` at the root of the working directory, which is committed along with the
+    /// rest of the repository.
+    #[default]
+    Gitignore,
+    /// `.git/info/exclude`, which only applies to this clone of the repository.
+    InfoExclude,
+}
+
+/// Returns `content` with a pattern appended for each of the paths that it doesn't contain yet,
+/// or `None` if it contains all of them.
+pub fn append_ignore_patterns(content: &str, paths: &[RepoPath]) -> Option<String> {
+    let mut new_content = content.to_string();
+    for path in paths {
+        let pattern = ignore_pattern(path);
+        if new_content.lines().any(|line| line.trim_end() == pattern) {
+            continue;
+        }
+        if !new_content.is_empty() && !new_content.ends_with('\n') {
+            new_content.push('\n');
+        }
+        new_content.push_str(&pattern);
+        new_content.push('\n');
+    }
+    (new_content != content).then_some(new_content)
+}
+
+/// Parses the paths that `git clean` lists as removed, or as would be removed in a dry run.
+fn parse_clean_output(output: &str) -> Vec<RepoPath> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let path = line
+                .strip_prefix("Would remove ")
+                .or_else(|| line.strip_prefix("Removing "))?;
+            RepoPath::new(path.trim_end_matches('/')).log_err()
+        })
+        .collect()
+}
+
+/// Returns a pattern that only matches `path`. It's anchored to the root of the repository, as
+/// an unanchored file name would also match files of the same name in subdirectories.
+fn ignore_pattern(path: &RepoPath) -> String {
+    let mut pattern = String::from("/");
+    for character in path.display(PathStyle::Posix).chars() {
+        if matches!(character, '\\' | '*' | '?' | '[') {
+            pattern.push('\\');
+        }
+        pattern.push(character);
+    }
+    // Trailing spaces are ignored unless they're escaped.
+    if pattern.ends_with(' ') {
+        pattern.pop();
+        pattern.push_str("\\ ");
+    }
+    pattern
+}
+
+/// A `git rebase` that is in progress, because it stopped to resolve conflicts, to edit a
+/// commit, or at a `break` in an interactive rebase's todo list.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RebaseState {
+    /// The branch being rebased, or `None` if HEAD was detached when the rebase started.
+    pub head_name: Option<SharedString>,
+    /// The commit the branch is being rebased onto.
+    pub onto: SharedString,
+    /// The number of the current step of the todo list, which is 0 before the first step.
+    pub step: usize,
+    pub total_steps: usize,
+    pub interactive: bool,
+    /// The commit the rebase stopped at to resolve conflicts or edit it.
+    pub stopped_at: Option<SharedString>,
+}
+
+impl RebaseState {
+    /// Reads the state of the rebase in progress from a repository's git directory.
+    pub fn load(git_dir: &Path) -> Option<Self> {
+        let read = |path: PathBuf| {
+            std::fs::read_to_string(path)
+                .ok()
+                .map(|content| content.trim().to_string())
+        };
+        let read_number = |path: PathBuf| read(path).and_then(|number| number.parse().ok());
+
+        // The merge backend, which interactive rebases always use, keeps its state in
+        // `rebase-merge`, while the apply backend shares `rebase-apply` with `git am`.
+        let merge_dir = git_dir.join("rebase-merge");
+        let apply_dir = git_dir.join("rebase-apply");
+        let (dir, step_file, total_steps_file) = if merge_dir.is_dir() {
+            (merge_dir, "msgnum", "end")
+        } else if apply_dir.join("rebasing").exists() {
+            (apply_dir, "next", "last")
+        } else {
+            return None;
+        };
+
+        let head_name = read(dir.join("head-name"))
+            .filter(|head_name| head_name != "detached HEAD")
+            .map(|head_name| {
+                SharedString::from(
+                    head_name
+                        .strip_prefix("refs/heads/")
+                        .unwrap_or(&head_name)
+                        .to_string(),
+                )
+            });
+        Some(Self {
+            head_name,
+            onto: read(dir.join("onto"))?.into(),
+            step: read_number(dir.join(step_file)).unwrap_or(0),
+            total_steps: read_number(dir.join(total_steps_file)).unwrap_or(0),
+            interactive: dir.join("interactive").exists(),
+            stopped_at: read(dir.join("stopped-sha"))
+                .filter(|sha| !sha.is_empty())
+                .map(SharedString::from),
+        })
+    }
+}
+
+/// A sequence editor that adds a `break` before the first step of an interactive rebase, so that
+/// the rebase stops and its todo list can be edited before continuing. Git runs it through a shell
+/// with the todo list's path as its argument.
+const BREAK_BEFORE_FIRST_STEP_SEQUENCE_EDITOR: &str =
+    "f() { printf 'break\\n' | cat - \"$1\" > \"$1.tmp\" && mv \"$1.tmp\" \"$1\"; }; f";
+
 pub enum ResetMode {
     /// Reset the branch pointer, leave index and worktree unchanged (this will make it look like things that were
     /// committed are now staged).
@@ -194,6 +669,8 @@ pub enum ResetMode {
     /// Reset the branch pointer and index, leave worktree unchanged (this makes it look as though things that were
     /// committed are now unstaged).
     Mixed,
+    /// Reset the branch pointer, index and worktree, discarding all uncommitted changes to tracked files.
+    Hard,
 }
 
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
@@ -351,6 +828,12 @@ pub trait GitRepository: Send + Sync {
     /// Also returns `None` for symlinks.
     fn load_committed_text(&self, path: RepoPath) -> BoxFuture<'_, Option<String>>;
 
+    /// Returns the raw contents of an entry in the repository's HEAD, for files that may not be
+    /// valid UTF-8, or None if HEAD does not exist or has no entry for the given path.
+    ///
+    /// Also returns `None` for symlinks.
+    fn load_committed_bytes(&self, path: RepoPath) -> BoxFuture<'_, Option<Vec<u8>>>;
+
     fn set_index_text(
         &self,
         path: RepoPath,
@@ -378,6 +861,48 @@ pub trait GitRepository: Send + Sync {
 
     fn merge_message(&self) -> BoxFuture<'_, Option<String>>;
 
+    /// The contents of the file that the `commit.template` configuration points to.
+    fn commit_template(&self) -> BoxFuture<'_, Option<String>>;
+
+    fn rebase_state(&self) -> BoxFuture<'_, Option<RebaseState>>;
+
+    /// Starts rebasing the current branch onto `onto`. An interactive rebase stops before its
+    /// first step, so that its todo list can be edited.
+    fn rebase_start(
+        &self,
+        onto: String,
+        interactive: bool,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>>;
+
+    fn rebase_continue(&self, env: Arc<HashMap<String, String>>) -> BoxFuture<'_, Result<()>>;
+
+    fn rebase_abort(&self, env: Arc<HashMap<String, String>>) -> BoxFuture<'_, Result<()>>;
+
+    /// Merges `branch` into the current branch. A merge that stops on conflicts is still in
+    /// progress, until it's committed or aborted.
+    fn merge(
+        &self,
+        branch: String,
+        options: MergeOptions,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>>;
+
+    fn merge_abort(&self, env: Arc<HashMap<String, String>>) -> BoxFuture<'_, Result<()>>;
+
+    /// Applies a patch, such as the output of `git diff` or `git format-patch`, to the working
+    /// tree.
+    fn apply_patch(
+        &self,
+        patch: String,
+        options: ApplyPatchOptions,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>>;
+
+    /// Generates a patch in mailbox format for each commit in `range`, which is interpreted like
+    /// `git format-patch` does: a single revision stands for the commits since it.
+    fn format_patch(&self, range: String) -> BoxFuture<'_, Result<String>>;
+
     fn status(&self, path_prefixes: &[RepoPath]) -> Task<Result<GitStatus>>;
 
     fn stash_entries(&self) -> BoxFuture<'_, Result<GitStash>>;
@@ -387,6 +912,11 @@ pub trait GitRepository: Send + Sync {
     fn change_branch(&self, name: String) -> BoxFuture<'_, Result<()>>;
     fn create_branch(&self, name: String) -> BoxFuture<'_, Result<()>>;
     fn rename_branch(&self, branch: String, new_name: String) -> BoxFuture<'_, Result<()>>;
+    /// Deletes a local branch. Unless `force` is set, branches that aren't merged into their
+    /// upstream or `HEAD` are kept.
+    fn delete_branch(&self, name: String, force: bool) -> BoxFuture<'_, Result<()>>;
+    /// Sets the branch that `branch` tracks, such as `origin/main`.
+    fn set_upstream(&self, branch: String, upstream: String) -> BoxFuture<'_, Result<()>>;
 
     fn reset(
         &self,
@@ -402,11 +932,95 @@ pub trait GitRepository: Send + Sync {
         env: Arc<HashMap<String, String>>,
     ) -> BoxFuture<'_, Result<()>>;
 
+    /// Returns the paths whose working copy a hard reset to `commit` would overwrite or delete:
+    /// tracked files that differ from `commit`, and untracked files that `commit` tracks.
+    fn paths_discarded_by_hard_reset(&self, commit: String)
+    -> BoxFuture<'_, Result<Vec<RepoPath>>>;
+
     fn show(&self, commit: String) -> BoxFuture<'_, Result<CommitDetails>>;
 
+    /// Lists a page of the commits reachable from `HEAD`, newest first.
+    fn log(&self, options: LogOptions) -> BoxFuture<'_, Result<Vec<CommitDetails>>>;
+
+    /// Lists a page of commits with their parents and the refs pointing at them, for drawing a
+    /// branch graph.
+    fn commit_graph(&self, options: CommitGraphOptions) -> BoxFuture<'_, Result<CommitGraph>>;
+
+    /// Lists the commits reachable from `HEAD` that changed the file at `path`, newest first.
+    /// With `follow_renames`, the commits from before the file was renamed are listed too.
+    fn file_history(
+        &self,
+        path: RepoPath,
+        follow_renames: bool,
+    ) -> BoxFuture<'_, Result<Vec<FileHistoryEntry>>>;
+
     fn load_commit(&self, commit: String, cx: AsyncApp) -> BoxFuture<'_, Result<CommitDiff>>;
     fn blame(&self, path: RepoPath, content: Rope) -> BoxFuture<'_, Result<crate::blame::Blame>>;
 
+    /// Adds patterns for `paths` to an ignore file, unless it already has them.
+    fn ignore_paths(
+        &self,
+        paths: Vec<RepoPath>,
+        ignore_file: IgnoreFile,
+    ) -> BoxFuture<'_, Result<()>>;
+
+    /// Returns whether `path` is ignored by the repository's ignore files.
+    fn is_ignored(&self, path: RepoPath) -> BoxFuture<'_, Result<bool>>;
+
+    /// Resolves a conflicted file by checking out `side`'s version of it and staging it. If `side`
+    /// deleted the file, it's deleted.
+    fn resolve_conflict(
+        &self,
+        path: RepoPath,
+        side: ConflictSide,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>>;
+
+    /// Deletes the untracked files within `paths`, or the whole working directory if it's empty,
+    /// and returns the paths that were deleted. Directories are returned as a single path.
+    fn clean(
+        &self,
+        paths: Vec<RepoPath>,
+        options: CleanOptions,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<Vec<RepoPath>>>;
+
+    /// Returns the Git LFS state of those of `paths` that are stored by Git LFS.
+    fn lfs_statuses(
+        &self,
+        paths: Vec<RepoPath>,
+    ) -> BoxFuture<'_, Result<HashMap<RepoPath, LfsFileStatus>>>;
+
+    /// Downloads the content of the files stored by Git LFS, replacing their pointers.
+    fn lfs_pull(
+        &self,
+        askpass: AskPassDelegate,
+        env: Arc<HashMap<String, String>>,
+        // This method takes an AsyncApp to ensure it's invoked on the main thread,
+        // otherwise git-credentials-manager won't work.
+        cx: AsyncApp,
+    ) -> BoxFuture<'_, Result<RemoteCommandOutput>>;
+
+    /// Locks a file stored by Git LFS on the server, so that no one else can push changes to it.
+    fn lfs_lock(
+        &self,
+        path: RepoPath,
+        askpass: AskPassDelegate,
+        env: Arc<HashMap<String, String>>,
+        cx: AsyncApp,
+    ) -> BoxFuture<'_, Result<()>>;
+
+    /// Releases the lock on a file stored by Git LFS. Unless `force` is set, only locks held by
+    /// the current user are released.
+    fn lfs_unlock(
+        &self,
+        path: RepoPath,
+        force: bool,
+        askpass: AskPassDelegate,
+        env: Arc<HashMap<String, String>>,
+        cx: AsyncApp,
+    ) -> BoxFuture<'_, Result<()>>;
+
     /// Returns the absolute path to the repository. For worktrees, this will be the path to the
     /// worktree's gitdir within the main repository (typically `.git/worktrees/<name>`).
     fn path(&self) -> PathBuf;
@@ -435,7 +1049,9 @@ pub trait GitRepository: Send + Sync {
         message: SharedString,
         name_and_email: Option<(SharedString, SharedString)>,
         options: CommitOptions,
+        askpass: AskPassDelegate,
         env: Arc<HashMap<String, String>>,
+        cx: AsyncApp,
     ) -> BoxFuture<'_, Result<()>>;
 
     fn stash_paths(
@@ -468,6 +1084,7 @@ pub trait GitRepository: Send + Sync {
         upstream_name: String,
         options: Option<PushOptions>,
         askpass: AskPassDelegate,
+        progress: mpsc::UnboundedSender<RemoteProgress>,
         env: Arc<HashMap<String, String>>,
         // This method takes an AsyncApp to ensure it's invoked on the main thread,
         // otherwise git-credentials-manager won't work.
@@ -478,7 +1095,9 @@ pub trait GitRepository: Send + Sync {
         &self,
         branch_name: String,
         upstream_name: String,
+        options: PullOptions,
         askpass: AskPassDelegate,
+        progress: mpsc::UnboundedSender<RemoteProgress>,
         env: Arc<HashMap<String, String>>,
         // This method takes an AsyncApp to ensure it's invoked on the main thread,
         // otherwise git-credentials-manager won't work.
@@ -489,6 +1108,7 @@ pub trait GitRepository: Send + Sync {
         &self,
         fetch_options: FetchOptions,
         askpass: AskPassDelegate,
+        progress: mpsc::UnboundedSender<RemoteProgress>,
         env: Arc<HashMap<String, String>>,
         // This method takes an AsyncApp to ensure it's invoked on the main thread,
         // otherwise git-credentials-manager won't work.
@@ -497,12 +1117,68 @@ pub trait GitRepository: Send + Sync {
 
     fn get_remotes(&self, branch_name: Option<String>) -> BoxFuture<'_, Result<Vec<Remote>>>;
 
+    /// Lists the repository's tags, most recently created first.
+    fn tags(&self) -> BoxFuture<'_, Result<Vec<Tag>>>;
+
+    /// Tags the given commit, creating an annotated tag if a message is given and a lightweight
+    /// one otherwise.
+    fn create_tag(
+        &self,
+        name: String,
+        sha: String,
+        message: Option<String>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>>;
+
+    fn delete_tag(
+        &self,
+        name: String,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>>;
+
+    fn push_tag(
+        &self,
+        name: String,
+        remote_name: String,
+        askpass: AskPassDelegate,
+        env: Arc<HashMap<String, String>>,
+        // This method takes an AsyncApp to ensure it's invoked on the main thread,
+        // otherwise git-credentials-manager won't work.
+        cx: AsyncApp,
+    ) -> BoxFuture<'_, Result<RemoteCommandOutput>>;
+
     /// returns a list of remote branches that contain HEAD
-    fn check_for_pushed_commit(&self) -> BoxFuture<'_, Result<Vec<SharedString>>>;
+    fn check_for_pushed_commit(&self) -> BoxFuture<'_, Result<Vec<PushedBranch>>>;
 
     /// Run git diff
     fn diff(&self, diff: DiffType) -> BoxFuture<'_, Result<String>>;
 
+    /// Diffs the working copy of `path` against its content at `base_rev`, which can be any
+    /// revision, like a branch, tag or SHA.
+    fn diff_file(&self, path: RepoPath, base_rev: String) -> BoxFuture<'_, Result<String>>;
+
+    /// Returns the content of the file at `path` as of `rev`, which can be any revision, like a
+    /// branch, tag or SHA. Fails if the file didn't exist then, or if it isn't a text file of at
+    /// most [`MAX_BLOB_SIZE`] bytes.
+    fn load_blob(&self, rev: String, path: RepoPath) -> BoxFuture<'_, Result<String>>;
+
+    /// Runs the repository's maintenance tasks now, or starts or stops running them in the
+    /// background on a schedule. While running them, reports which task is running to
+    /// `progress`.
+    fn maintenance(
+        &self,
+        command: MaintenanceCommand,
+        progress: mpsc::UnboundedSender<RemoteProgress>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>>;
+
+    /// Packs loose objects and removes unreachable ones. An aggressive collection packs more
+    /// tightly, but takes much longer.
+    fn gc(&self, aggressive: bool, env: Arc<HashMap<String, String>>) -> BoxFuture<'_, Result<()>>;
+
+    /// Counts the repository's objects and how much space they take up.
+    fn health(&self) -> BoxFuture<'_, Result<RepositoryHealth>>;
+
     /// Creates a checkpoint for the repository.
     fn checkpoint(&self) -> BoxFuture<'static, Result<GitRepositoryCheckpoint>>;
 
@@ -529,6 +1205,11 @@ pub trait GitRepository: Send + Sync {
 pub enum DiffType {
     HeadToIndex,
     HeadToWorktree,
+    /// The changes from `base` to `head`, which can be any revisions.
+    Commits {
+        base: String,
+        head: String,
+    },
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, JsonSchema)]
@@ -537,6 +1218,118 @@ pub enum PushOptions {
     Force,
 }
 
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PullOptions {
+    /// Rebases the local commits onto the pulled ones, instead of merging them.
+    pub rebase: bool,
+    /// Fails instead of merging or rebasing when the local branch has diverged.
+    pub ff_only: bool,
+    /// The merge strategy to use, such as `ort` or `resolve`.
+    pub strategy: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MergeOptions {
+    /// Creates a merge commit even if the branch could be fast-forwarded.
+    pub no_ff: bool,
+    /// Fails instead of creating a merge commit when the branch can't be fast-forwarded.
+    pub ff_only: bool,
+    /// The merge strategy to use, such as `ort` or `resolve`.
+    pub strategy: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ApplyPatchOptions {
+    /// Falls back to a three-way merge when the patch doesn't apply cleanly, leaving conflicts
+    /// to resolve.
+    pub three_way: bool,
+    /// Applies the patch to the index as well as the working tree.
+    pub index: bool,
+}
+
+/// What `git maintenance` should do.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MaintenanceCommand {
+    /// Runs the maintenance tasks now.
+    Run,
+    /// Registers the repository to have its maintenance tasks run in the background on a
+    /// schedule.
+    Start,
+    /// Stops running the repository's maintenance tasks in the background.
+    Stop,
+}
+
+/// The tasks that [`MaintenanceCommand::Run`] runs, in order. These are the ones that
+/// `git maintenance start` schedules, except for `prefetch`, which would need credentials.
+const MAINTENANCE_TASKS: [&str; 4] = [
+    "commit-graph",
+    "loose-objects",
+    "incremental-repack",
+    "pack-refs",
+];
+
+/// How many objects the repository has and how much space they take up, as reported by
+/// `git count-objects`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RepositoryHealth {
+    /// The number of objects stored in their own files rather than in packs. Git slows down as
+    /// these pile up, until they're packed by a gc.
+    pub loose_objects: u64,
+    /// The disk space taken by loose objects, in bytes.
+    pub loose_objects_size: u64,
+    pub packs: u64,
+    /// The disk space taken by packs, in bytes.
+    pub packs_size: u64,
+}
+
+impl RepositoryHealth {
+    /// Parses the output of `git count-objects -v`, which gives sizes in KiB.
+    fn parse(output: &str) -> Result<Self> {
+        let mut health = Self::default();
+        for line in output.lines() {
+            let Some((key, value)) = line.split_once(": ") else {
+                continue;
+            };
+            let field = match key {
+                "count" => &mut health.loose_objects,
+                "size" => &mut health.loose_objects_size,
+                "packs" => &mut health.packs,
+                "size-pack" => &mut health.packs_size,
+                _ => continue,
+            };
+            let value = value
+                .parse::<u64>()
+                .with_context(|| format!("invalid {key} in count-objects output: {value}"))?;
+            *field = if key.starts_with("size") {
+                value * 1024
+            } else {
+                value
+            };
+        }
+        Ok(health)
+    }
+}
+
+/// The side of a conflicted merge whose version of a file is kept.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConflictSide {
+    /// The version of the branch that's being merged into, which is `HEAD`.
+    Ours,
+    /// The version of the branch that's being merged.
+    Theirs,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CleanOptions {
+    /// Only lists the files that would be deleted, without deleting them.
+    pub dry_run: bool,
+    /// Also deletes untracked directories, rather than only untracked files in directories that
+    /// have tracked files.
+    pub directories: bool,
+    /// Also deletes files that are ignored.
+    pub ignored: bool,
+}
+
 impl std::fmt::Debug for dyn GitRepository {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("dyn GitRepository<...>").finish()
@@ -575,6 +1368,54 @@ impl RealGitRepository {
             .context("failed to read git work directory")
             .map(Path::to_path_buf)
     }
+
+    fn run_rebase_command(
+        &self,
+        args: Vec<String>,
+        sequence_editor: Option<&'static str>,
+        may_stop: bool,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.any_git_binary_path.clone();
+        let git_dir = self.path();
+        self.executor
+            .spawn(async move {
+                let mut command = new_smol_command(git_binary_path);
+                command
+                    .current_dir(&working_directory?)
+                    .envs(env.iter())
+                    // Commit messages are kept as they are rather than opening an editor that
+                    // nothing would close.
+                    .env("GIT_EDITOR", "true")
+                    .args(&args);
+                if let Some(sequence_editor) = sequence_editor {
+                    command.env("GIT_SEQUENCE_EDITOR", sequence_editor);
+                }
+
+                let state_before = RebaseState::load(&git_dir);
+                let output = command.output().await?;
+
+                // A rebase that stops to resolve conflicts or at a `break` exits with an error,
+                // but is still in progress. It only got anywhere if it stopped somewhere else
+                // than before, since a `--continue` that's refused leaves the rebase as it was.
+                let stopped_further = may_stop
+                    && RebaseState::load(&git_dir).is_some_and(|state_after| {
+                        state_before.is_none_or(|state_before| {
+                            (state_before.step, state_before.stopped_at)
+                                != (state_after.step, state_after.stopped_at)
+                        })
+                    });
+                anyhow::ensure!(
+                    output.status.success() || stopped_further,
+                    "Failed to {}:\n{}",
+                    args.join(" "),
+                    String::from_utf8_lossy(&output.stderr)
+                );
+                Ok(())
+            })
+            .boxed()
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -681,15 +1522,117 @@ impl GitRepository for RealGitRepository {
             .boxed()
     }
 
-    fn load_commit(&self, commit: String, cx: AsyncApp) -> BoxFuture<'_, Result<CommitDiff>> {
-        let Some(working_directory) = self.repository.lock().workdir().map(ToOwned::to_owned)
-        else {
-            return future::ready(Err(anyhow!("no working directory"))).boxed();
-        };
+    fn log(&self, options: LogOptions) -> BoxFuture<'_, Result<Vec<CommitDetails>>> {
         let git_binary_path = self.any_git_binary_path.clone();
-        cx.background_spawn(async move {
-            let show_output = util::command::new_smol_command(&git_binary_path)
-                .current_dir(&working_directory)
+        let working_directory = self.working_directory();
+        self.executor
+            .spawn(async move {
+                let working_directory = working_directory?;
+                let mut command = new_smol_command(git_binary_path);
+                command
+                    .current_dir(&working_directory)
+                    .args(["--no-optional-locks", "log", LOG_FORMAT])
+                    .arg(format!("--max-count={}", options.limit))
+                    .arg(format!("--skip={}", options.skip));
+                if let Some(author) = &options.author {
+                    command.arg(format!("--author={author}"));
+                }
+                command.arg("HEAD");
+                if let Some(path) = &options.path {
+                    command.arg("--").arg(path.as_unix_str());
+                }
+                let output = command.output().await?;
+                if !output.status.success() {
+                    let stderr = String::from_utf8_lossy(&output.stderr);
+                    // A repository without commits has no history to list.
+                    if stderr.contains("ambiguous argument 'HEAD'") {
+                        return Ok(Vec::new());
+                    }
+                    bail!("git log failed: {stderr}");
+                }
+                parse_log_output(std::str::from_utf8(&output.stdout)?)
+            })
+            .boxed()
+    }
+
+    fn commit_graph(&self, options: CommitGraphOptions) -> BoxFuture<'_, Result<CommitGraph>> {
+        let git_binary_path = self.any_git_binary_path.clone();
+        let working_directory = self.working_directory();
+        self.executor
+            .spawn(async move {
+                let working_directory = working_directory?;
+                let mut command = new_smol_command(git_binary_path);
+                // One more commit than asked for is listed, to tell whether there are more pages.
+                command
+                    .current_dir(&working_directory)
+                    .args([
+                        "--no-optional-locks",
+                        "log",
+                        "--topo-order",
+                        COMMIT_GRAPH_FORMAT,
+                    ])
+                    .arg(format!("--max-count={}", options.limit + 1))
+                    .arg(format!("--skip={}", options.skip));
+                match &options.range {
+                    Some(range) => command.arg(range).arg("--"),
+                    None => command.arg("--all"),
+                };
+                let output = command.output().await?;
+                anyhow::ensure!(
+                    output.status.success(),
+                    "git log failed: {}",
+                    String::from_utf8_lossy(&output.stderr)
+                );
+                let mut commits = parse_commit_graph_output(std::str::from_utf8(&output.stdout)?)?;
+                let has_more = commits.len() > options.limit;
+                commits.truncate(options.limit);
+                Ok(CommitGraph { commits, has_more })
+            })
+            .boxed()
+    }
+
+    fn file_history(
+        &self,
+        path: RepoPath,
+        follow_renames: bool,
+    ) -> BoxFuture<'_, Result<Vec<FileHistoryEntry>>> {
+        let git_binary_path = self.any_git_binary_path.clone();
+        let working_directory = self.working_directory();
+        self.executor
+            .spawn(async move {
+                let working_directory = working_directory?;
+                let mut command = new_smol_command(git_binary_path);
+                command
+                    .current_dir(&working_directory)
+                    .args(["--no-optional-locks", "-c", "core.quotePath=false"])
+                    .args(["log", FILE_HISTORY_FORMAT, "--name-only"]);
+                if follow_renames {
+                    command.arg("--follow");
+                }
+                command.args(["HEAD", "--"]).arg(path.as_unix_str());
+                let output = command.output().await?;
+                if !output.status.success() {
+                    let stderr = String::from_utf8_lossy(&output.stderr);
+                    // A repository without commits has no history to list.
+                    if stderr.contains("ambiguous argument 'HEAD'") {
+                        return Ok(Vec::new());
+                    }
+                    bail!("git log failed: {stderr}");
+                }
+                parse_file_history_output(std::str::from_utf8(&output.stdout)?, &path)
+            })
+            .boxed()
+    }
+
+    fn load_commit(&self, commit: String, cx: AsyncApp) -> BoxFuture<'_, Result<CommitDiff>> {
+        let Some(working_directory) = self.repository.lock().workdir().map(ToOwned::to_owned)
+        else {
+            return future::ready(Err(anyhow!("no working directory"))).boxed();
+        };
+        let git_binary_path = self.any_git_binary_path.clone();
+        cx.background_spawn(async move {
+            let show_output = util::command::new_smol_command(&git_binary_path)
+                .current_dir(&working_directory)
                 .args([
                     "--no-optional-locks",
                     "show",
@@ -813,14 +1756,15 @@ impl GitRepository for RealGitRepository {
             let mode_flag = match mode {
                 ResetMode::Mixed => "--mixed",
                 ResetMode::Soft => "--soft",
+                ResetMode::Hard => "--hard",
             };
 
-            let output = new_smol_command(&self.any_git_binary_path)
+            let mut command = new_smol_command(&self.any_git_binary_path);
+            command
                 .envs(env.iter())
                 .current_dir(&working_directory?)
-                .args(["reset", mode_flag, &commit])
-                .output()
-                .await?;
+                .args(["reset", mode_flag, &commit]);
+            let output = output_retrying_index_lock(&mut command, &self.executor).await?;
             anyhow::ensure!(
                 output.status.success(),
                 "Failed to reset:\n{}",
@@ -831,6 +1775,59 @@ impl GitRepository for RealGitRepository {
         .boxed()
     }
 
+    fn paths_discarded_by_hard_reset(
+        &self,
+        commit: String,
+    ) -> BoxFuture<'_, Result<Vec<RepoPath>>> {
+        let git_binary_path = self.any_git_binary_path.clone();
+        let working_directory = self.working_directory();
+        let executor = self.executor.clone();
+        self.executor
+            .spawn(async move {
+                let working_directory = working_directory?;
+                let git = GitBinary::new(git_binary_path, working_directory.clone(), executor);
+                // Files missing from the working copy are skipped, since the reset restores them
+                // rather than discarding anything.
+                let changed = git
+                    .run([
+                        "diff",
+                        "--name-only",
+                        "--no-renames",
+                        "--diff-filter=d",
+                        "-z",
+                        "--end-of-options",
+                        &commit,
+                        "--",
+                    ])
+                    .await?;
+                let indexed = git.run(["ls-files", "-z"]).await?;
+                let committed = git
+                    .run([
+                        "ls-tree",
+                        "-r",
+                        "--name-only",
+                        "-z",
+                        "--end-of-options",
+                        &commit,
+                    ])
+                    .await?;
+
+                let indexed = indexed.split('\0').collect::<HashSet<_>>();
+                let overwritten = committed.split('\0').filter(|path| {
+                    !path.is_empty()
+                        && !indexed.contains(path)
+                        && working_directory.join(path).symlink_metadata().is_ok()
+                });
+                changed
+                    .split('\0')
+                    .filter(|path| !path.is_empty())
+                    .chain(overwritten)
+                    .map(RepoPath::new)
+                    .collect()
+            })
+            .boxed()
+    }
+
     fn checkout_files(
         &self,
         commit: String,
@@ -844,13 +1841,13 @@ impl GitRepository for RealGitRepository {
                 return Ok(());
             }
 
-            let output = new_smol_command(&git_binary_path)
+            let mut command = new_smol_command(&git_binary_path);
+            command
                 .current_dir(&working_directory?)
                 .envs(env.iter())
                 .args(["checkout", &commit, "--"])
-                .args(paths.iter().map(|path| path.as_unix_str()))
-                .output()
-                .await?;
+                .args(paths.iter().map(|path| path.as_unix_str()));
+            let output = output_retrying_index_lock(&mut command, &self.executor).await?;
             anyhow::ensure!(
                 output.status.success(),
                 "Failed to checkout files:\n{}",
@@ -908,6 +1905,21 @@ impl GitRepository for RealGitRepository {
             .boxed()
     }
 
+    fn load_committed_bytes(&self, path: RepoPath) -> BoxFuture<'_, Option<Vec<u8>>> {
+        let repo = self.repository.clone();
+        self.executor
+            .spawn(async move {
+                let repo = repo.lock();
+                let head = repo.head().ok()?.peel_to_tree().log_err()?;
+                let entry = head.get_path(path.as_std_path()).ok()?;
+                if entry.filemode() == i32::from(git2::FileMode::Link) {
+                    return None;
+                }
+                Some(repo.find_blob(entry.id()).log_err()?.content().to_owned())
+            })
+            .boxed()
+    }
+
     fn set_index_text(
         &self,
         path: RepoPath,
@@ -916,6 +1928,7 @@ impl GitRepository for RealGitRepository {
     ) -> BoxFuture<'_, anyhow::Result<()>> {
         let working_directory = self.working_directory();
         let git_binary_path = self.any_git_binary_path.clone();
+        let executor = self.executor.clone();
         self.executor
             .spawn(async move {
                 let working_directory = working_directory?;
@@ -936,13 +1949,13 @@ impl GitRepository for RealGitRepository {
 
                     log::debug!("indexing SHA: {sha}, path {path:?}");
 
-                    let output = new_smol_command(&git_binary_path)
+                    let mut command = new_smol_command(&git_binary_path);
+                    command
                         .current_dir(&working_directory)
                         .envs(env.iter())
                         .args(["update-index", "--add", "--cacheinfo", "100644", sha])
-                        .arg(path.as_unix_str())
-                        .output()
-                        .await?;
+                        .arg(path.as_unix_str());
+                    let output = output_retrying_index_lock(&mut command, &executor).await?;
 
                     anyhow::ensure!(
                         output.status.success(),
@@ -951,13 +1964,13 @@ impl GitRepository for RealGitRepository {
                     );
                 } else {
                     log::debug!("removing path {path:?} from the index");
-                    let output = new_smol_command(&git_binary_path)
+                    let mut command = new_smol_command(&git_binary_path);
+                    command
                         .current_dir(&working_directory)
                         .envs(env.iter())
                         .args(["update-index", "--force-remove"])
-                        .arg(path.as_unix_str())
-                        .output()
-                        .await?;
+                        .arg(path.as_unix_str());
+                    let output = output_retrying_index_lock(&mut command, &executor).await?;
                     anyhow::ensure!(
                         output.status.success(),
                         "Failed to unstage:\n{}",
@@ -1036,6 +2049,263 @@ impl GitRepository for RealGitRepository {
             .boxed()
     }
 
+    fn commit_template(&self) -> BoxFuture<'_, Option<String>> {
+        let repository = self.repository.clone();
+        let working_directory = self.working_directory();
+        self.executor
+            .spawn(async move {
+                let path = repository
+                    .lock()
+                    .config()
+                    .ok()?
+                    .get_path("commit.template")
+                    .ok()?;
+                // Like git, resolve a relative template path against the work directory.
+                let path = working_directory.ok()?.join(path);
+                std::fs::read_to_string(&path).log_err()
+            })
+            .boxed()
+    }
+
+    fn rebase_state(&self) -> BoxFuture<'_, Option<RebaseState>> {
+        let git_dir = self.path();
+        self.executor
+            .spawn(async move { RebaseState::load(&git_dir) })
+            .boxed()
+    }
+
+    fn rebase_start(
+        &self,
+        onto: String,
+        interactive: bool,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        if onto.starts_with('-') {
+            return future::ready(Err(anyhow!("Invalid commit to rebase onto: {onto}"))).boxed();
+        }
+        let mut args = vec!["rebase".to_string()];
+        let mut sequence_editor = None;
+        if interactive {
+            args.push("--interactive".to_string());
+            sequence_editor = Some(BREAK_BEFORE_FIRST_STEP_SEQUENCE_EDITOR);
+        }
+        args.push(onto);
+        let git_dir = self.path();
+        let executor = self.executor.clone();
+        async move {
+            let rebase_in_progress = executor
+                .spawn(async move { RebaseState::load(&git_dir).is_some() })
+                .await;
+            anyhow::ensure!(!rebase_in_progress, "A rebase is already in progress");
+            self.run_rebase_command(args, sequence_editor, true, env)
+                .await
+        }
+        .boxed()
+    }
+
+    fn rebase_continue(&self, env: Arc<HashMap<String, String>>) -> BoxFuture<'_, Result<()>> {
+        self.run_rebase_command(vec!["rebase".into(), "--continue".into()], None, true, env)
+    }
+
+    fn rebase_abort(&self, env: Arc<HashMap<String, String>>) -> BoxFuture<'_, Result<()>> {
+        self.run_rebase_command(vec!["rebase".into(), "--abort".into()], None, false, env)
+    }
+
+    fn merge(
+        &self,
+        branch: String,
+        options: MergeOptions,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        if branch.starts_with('-') {
+            return future::ready(Err(anyhow!("Invalid branch to merge: {branch}"))).boxed();
+        }
+        let working_directory = self.working_directory();
+        let git_binary_path = self.any_git_binary_path.clone();
+        let git_dir = self.path();
+        self.executor
+            .spawn(async move {
+                let mut command = new_smol_command(git_binary_path);
+                command
+                    .current_dir(&working_directory?)
+                    .envs(env.iter())
+                    .args(["merge", "--no-edit"]);
+                if options.no_ff {
+                    command.arg("--no-ff");
+                }
+                if options.ff_only {
+                    command.arg("--ff-only");
+                }
+                if let Some(strategy) = options.strategy {
+                    command.arg(format!("--strategy={strategy}"));
+                }
+                let output = command.arg(&branch).output().await?;
+
+                // A merge that stops to resolve conflicts exits with an error, but is still in
+                // progress.
+                anyhow::ensure!(
+                    output.status.success() || git_dir.join("MERGE_HEAD").exists(),
+                    "Failed to merge {branch}:\n{}",
+                    String::from_utf8_lossy(&output.stderr)
+                );
+                Ok(())
+            })
+            .boxed()
+    }
+
+    fn merge_abort(&self, env: Arc<HashMap<String, String>>) -> BoxFuture<'_, Result<()>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.any_git_binary_path.clone();
+        self.executor
+            .spawn(async move {
+                let output = new_smol_command(git_binary_path)
+                    .current_dir(&working_directory?)
+                    .envs(env.iter())
+                    .args(["merge", "--abort"])
+                    .output()
+                    .await?;
+
+                anyhow::ensure!(
+                    output.status.success(),
+                    "Failed to abort merge:\n{}",
+                    String::from_utf8_lossy(&output.stderr)
+                );
+                Ok(())
+            })
+            .boxed()
+    }
+
+    fn apply_patch(
+        &self,
+        patch: String,
+        options: ApplyPatchOptions,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.any_git_binary_path.clone();
+        self.executor
+            .spawn(async move {
+                let mut command = new_smol_command(git_binary_path);
+                command
+                    .current_dir(&working_directory?)
+                    .envs(env.iter())
+                    .arg("apply");
+                if options.three_way {
+                    command.arg("--3way");
+                }
+                if options.index {
+                    command.arg("--index");
+                }
+                let mut child = command
+                    .arg("-")
+                    .stdin(Stdio::piped())
+                    .stdout(Stdio::piped())
+                    .stderr(Stdio::piped())
+                    .spawn()?;
+                let mut stdin = child.stdin.take().context("failed to open stdin")?;
+                stdin.write_all(patch.as_bytes()).await?;
+                stdin.flush().await?;
+                drop(stdin);
+                let output = child.output().await?;
+
+                anyhow::ensure!(
+                    output.status.success(),
+                    "Failed to apply patch:\n{}",
+                    String::from_utf8_lossy(&output.stderr)
+                );
+                Ok(())
+            })
+            .boxed()
+    }
+
+    fn format_patch(&self, range: String) -> BoxFuture<'_, Result<String>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.any_git_binary_path.clone();
+        let executor = self.executor.clone();
+        self.executor
+            .spawn(async move {
+                GitBinary::new(git_binary_path, working_directory?, executor)
+                    .run_raw(["format-patch", "--stdout", "--end-of-options", &range])
+                    .await
+            })
+            .boxed()
+    }
+
+    fn maintenance(
+        &self,
+        command: MaintenanceCommand,
+        progress: mpsc::UnboundedSender<RemoteProgress>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.any_git_binary_path.clone();
+        let executor = self.executor.clone();
+        self.executor
+            .spawn(async move {
+                let git = GitBinary::new(git_binary_path, working_directory?, executor)
+                    .envs((*env).clone());
+                match command {
+                    MaintenanceCommand::Start => {
+                        git.run(["maintenance", "start"]).await?;
+                    }
+                    MaintenanceCommand::Stop => {
+                        git.run(["maintenance", "stop"]).await?;
+                    }
+                    MaintenanceCommand::Run => {
+                        // Git doesn't report the progress of maintenance when its output isn't a
+                        // terminal, so the tasks are run one at a time to report which is running.
+                        let total = MAINTENANCE_TASKS.len();
+                        for (index, task) in MAINTENANCE_TASKS.into_iter().enumerate() {
+                            progress
+                                .unbounded_send(RemoteProgress {
+                                    phase: format!("Running {task}"),
+                                    percent: Some((index * 100 / total) as u8),
+                                    completed: index as u64,
+                                    total: Some(total as u64),
+                                })
+                                .ok();
+                            git.run(["maintenance", "run", &format!("--task={task}")])
+                                .await?;
+                        }
+                    }
+                }
+                Ok(())
+            })
+            .boxed()
+    }
+
+    fn gc(&self, aggressive: bool, env: Arc<HashMap<String, String>>) -> BoxFuture<'_, Result<()>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.any_git_binary_path.clone();
+        let executor = self.executor.clone();
+        self.executor
+            .spawn(async move {
+                let git = GitBinary::new(git_binary_path, working_directory?, executor)
+                    .envs((*env).clone());
+                if aggressive {
+                    git.run(["gc", "--aggressive"]).await?;
+                } else {
+                    git.run(["gc"]).await?;
+                }
+                Ok(())
+            })
+            .boxed()
+    }
+
+    fn health(&self) -> BoxFuture<'_, Result<RepositoryHealth>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.any_git_binary_path.clone();
+        let executor = self.executor.clone();
+        self.executor
+            .spawn(async move {
+                let output = GitBinary::new(git_binary_path, working_directory?, executor)
+                    .run(["count-objects", "-v"])
+                    .await?;
+                RepositoryHealth::parse(&output)
+            })
+            .boxed()
+    }
+
     fn status(&self, path_prefixes: &[RepoPath]) -> Task<Result<GitStatus>> {
         let git_binary_path = self.any_git_binary_path.clone();
         let working_directory = match self.working_directory() {
@@ -1214,11 +2484,351 @@ impl GitRepository for RealGitRepository {
             .boxed()
     }
 
-    fn blame(&self, path: RepoPath, content: Rope) -> BoxFuture<'_, Result<crate::blame::Blame>> {
-        let working_directory = self.working_directory();
+    fn delete_branch(&self, name: String, force: bool) -> BoxFuture<'_, Result<()>> {
+        if name.starts_with('-') {
+            return future::ready(Err(anyhow!("Invalid branch name: {name}"))).boxed();
+        }
         let git_binary_path = self.any_git_binary_path.clone();
+        let working_directory = self.working_directory();
+        let executor = self.executor.clone();
 
-        let remote_url = self
+        self.executor
+            .spawn(async move {
+                let delete_flag = if force { "-D" } else { "-d" };
+                GitBinary::new(git_binary_path, working_directory?, executor)
+                    .run(&["branch", delete_flag, &name])
+                    .await?;
+                anyhow::Ok(())
+            })
+            .boxed()
+    }
+
+    fn set_upstream(&self, branch: String, upstream: String) -> BoxFuture<'_, Result<()>> {
+        if branch.starts_with('-') {
+            return future::ready(Err(anyhow!("Invalid branch name: {branch}"))).boxed();
+        }
+        let git_binary_path = self.any_git_binary_path.clone();
+        let working_directory = self.working_directory();
+        let executor = self.executor.clone();
+
+        self.executor
+            .spawn(async move {
+                GitBinary::new(git_binary_path, working_directory?, executor)
+                    .run(&["branch", &format!("--set-upstream-to={upstream}"), &branch])
+                    .await?;
+                anyhow::Ok(())
+            })
+            .boxed()
+    }
+
+    fn ignore_paths(
+        &self,
+        paths: Vec<RepoPath>,
+        ignore_file: IgnoreFile,
+    ) -> BoxFuture<'_, Result<()>> {
+        let ignore_file_path = match ignore_file {
+            IgnoreFile::Gitignore => self
+                .working_directory()
+                .map(|working_directory| working_directory.join(".gitignore")),
+            // Linked worktrees share the exclude file of the main repository.
+            IgnoreFile::InfoExclude => Ok(self
+                .repository
+                .lock()
+                .commondir()
+                .join("info")
+                .join("exclude")),
+        };
+        self.executor
+            .spawn(async move {
+                let ignore_file_path = ignore_file_path?;
+                let content = match smol::fs::read_to_string(&ignore_file_path).await {
+                    Ok(content) => content,
+                    Err(error) if error.kind() == std::io::ErrorKind::NotFound => String::new(),
+                    Err(error) => return Err(error.into()),
+                };
+                let Some(content) = append_ignore_patterns(&content, &paths) else {
+                    return Ok(());
+                };
+                if let Some(parent) = ignore_file_path.parent() {
+                    smol::fs::create_dir_all(parent).await?;
+                }
+                smol::fs::write(&ignore_file_path, content).await?;
+                Ok(())
+            })
+            .boxed()
+    }
+
+    fn is_ignored(&self, path: RepoPath) -> BoxFuture<'_, Result<bool>> {
+        let repo = self.repository.clone();
+        self.executor
+            .spawn(async move { Ok(repo.lock().is_path_ignored(path.as_std_path())?) })
+            .boxed()
+    }
+
+    fn resolve_conflict(
+        &self,
+        path: RepoPath,
+        side: ConflictSide,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        let git_binary_path = self.any_git_binary_path.clone();
+        let working_directory = self.working_directory();
+        let executor = self.executor.clone();
+        self.executor
+            .spawn(async move {
+                let git = GitBinary::new(git_binary_path, working_directory?, executor)
+                    .envs((*env).clone());
+                let path = path.as_unix_str();
+                // The index has an entry for each side that has a version of the file, at stage 2
+                // for ours and stage 3 for theirs.
+                let (stage, side_flag) = match side {
+                    ConflictSide::Ours => ("2", "--ours"),
+                    ConflictSide::Theirs => ("3", "--theirs"),
+                };
+                let unmerged_entries = git.run(["ls-files", "-u", "-z", "--", path]).await?;
+                anyhow::ensure!(!unmerged_entries.is_empty(), "{path} isn't conflicted");
+                let side_has_file = unmerged_entries.split('\0').any(|entry| {
+                    entry
+                        .split_once('\t')
+                        .and_then(|(info, _)| info.split(' ').nth(2))
+                        == Some(stage)
+                });
+
+                if side_has_file {
+                    git.run(["checkout", side_flag, "--", path]).await?;
+                    git.run(["add", "--", path]).await?;
+                } else {
+                    git.run(["rm", "--force", "--quiet", "--", path]).await?;
+                }
+                Ok(())
+            })
+            .boxed()
+    }
+
+    fn clean(
+        &self,
+        paths: Vec<RepoPath>,
+        options: CleanOptions,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<Vec<RepoPath>>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.any_git_binary_path.clone();
+        self.executor
+            .spawn(async move {
+                let mut command = new_smol_command(git_binary_path);
+                // Unquoted paths can be read back from the output as they are, and the C
+                // locale keeps the messages that they're listed in from being translated.
+                command
+                    .current_dir(&working_directory?)
+                    .envs(env.iter())
+                    .env("LC_ALL", "C")
+                    .env("LANGUAGE", "C")
+                    .args(["-c", "core.quotePath=false", "clean", "--force"]);
+                if options.dry_run {
+                    command.arg("--dry-run");
+                }
+                if options.directories {
+                    command.arg("-d");
+                }
+                if options.ignored {
+                    command.arg("-x");
+                }
+                let output = command
+                    .arg("--")
+                    .args(paths.iter().map(|path| path.as_unix_str()))
+                    .output()
+                    .await?;
+                anyhow::ensure!(
+                    output.status.success(),
+                    "Failed to clean untracked files:\n{}",
+                    String::from_utf8_lossy(&output.stderr)
+                );
+                Ok(parse_clean_output(&String::from_utf8_lossy(&output.stdout)))
+            })
+            .boxed()
+    }
+
+    fn lfs_statuses(
+        &self,
+        paths: Vec<RepoPath>,
+    ) -> BoxFuture<'_, Result<HashMap<RepoPath, LfsFileStatus>>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.any_git_binary_path.clone();
+        self.executor
+            .spawn(async move {
+                let working_directory = working_directory?;
+                if paths.is_empty() {
+                    return Ok(HashMap::default());
+                }
+
+                let mut check_attr_process = new_smol_command(&git_binary_path)
+                    .current_dir(&working_directory)
+                    .args(["check-attr", "-z", "--stdin", "filter"])
+                    .stdin(Stdio::piped())
+                    .stdout(Stdio::piped())
+                    .stderr(Stdio::piped())
+                    .spawn()
+                    .context("starting git check-attr process")?;
+                let mut stdin = BufWriter::new(
+                    check_attr_process
+                        .stdin
+                        .take()
+                        .context("no stdin for git check-attr process")?,
+                );
+                // The paths are written while the output is read, so that neither pipe fills up.
+                let write_paths = async move {
+                    for path in &paths {
+                        stdin.write_all(path.as_unix_str().as_bytes()).await?;
+                        stdin.write_all(b"\0").await?;
+                    }
+                    stdin.close().await?;
+                    anyhow::Ok(())
+                };
+                let (write_result, output) =
+                    futures::future::join(write_paths, check_attr_process.output()).await;
+                write_result?;
+                let output = output?;
+                anyhow::ensure!(
+                    output.status.success(),
+                    "Failed to check Git LFS attributes:\n{}",
+                    String::from_utf8_lossy(&output.stderr)
+                );
+                let lfs_paths =
+                    parse_lfs_filter_attributes(&String::from_utf8_lossy(&output.stdout));
+                if lfs_paths.is_empty() {
+                    return Ok(HashMap::default());
+                }
+
+                // Only the locks that this clone already knows about are listed, so that the
+                // server isn't contacted on every scan. The command fails when Git LFS isn't
+                // installed, in which case there are no known locks.
+                let mut locks = new_smol_command(&git_binary_path)
+                    .current_dir(&working_directory)
+                    .args(["lfs", "locks", "--local", "--json"])
+                    .output()
+                    .await
+                    .ok()
+                    .filter(|output| output.status.success())
+                    .and_then(|output| {
+                        parse_lfs_locks(&String::from_utf8_lossy(&output.stdout)).log_err()
+                    })
+                    .unwrap_or_default()
+                    .into_iter()
+                    .collect::<HashMap<_, _>>();
+
+                let mut statuses = HashMap::default();
+                for path in lfs_paths {
+                    let mut prefix = Vec::with_capacity(LFS_POINTER_PREFIX.len());
+                    // Deleted files have no content to download.
+                    if let Ok(file) =
+                        smol::fs::File::open(working_directory.join(path.as_std_path())).await
+                    {
+                        file.take(LFS_POINTER_PREFIX.len() as u64)
+                            .read_to_end(&mut prefix)
+                            .await?;
+                    }
+                    let status = LfsFileStatus {
+                        is_pointer: is_lfs_pointer(&prefix),
+                        lock: locks.remove(&path),
+                    };
+                    statuses.insert(path, status);
+                }
+                Ok(statuses)
+            })
+            .boxed()
+    }
+
+    fn lfs_pull(
+        &self,
+        ask_pass: AskPassDelegate,
+        env: Arc<HashMap<String, String>>,
+        cx: AsyncApp,
+    ) -> BoxFuture<'_, Result<RemoteCommandOutput>> {
+        let working_directory = self.working_directory();
+        let executor = cx.background_executor().clone();
+        let git_binary_path = self.system_git_binary_path.clone();
+        async move {
+            let git_binary_path = git_binary_path.context("git not found on $PATH, can't pull")?;
+            let mut command = new_smol_command(git_binary_path);
+            command
+                .envs(env.iter())
+                .current_dir(&working_directory?)
+                .args(["lfs", "pull"])
+                .stdin(smol::process::Stdio::null())
+                .stdout(smol::process::Stdio::piped())
+                .stderr(smol::process::Stdio::piped());
+
+            run_git_command(env, ask_pass, command, &executor).await
+        }
+        .boxed()
+    }
+
+    fn lfs_lock(
+        &self,
+        path: RepoPath,
+        ask_pass: AskPassDelegate,
+        env: Arc<HashMap<String, String>>,
+        cx: AsyncApp,
+    ) -> BoxFuture<'_, Result<()>> {
+        let working_directory = self.working_directory();
+        let executor = cx.background_executor().clone();
+        let git_binary_path = self.system_git_binary_path.clone();
+        async move {
+            let git_binary_path = git_binary_path.context("git not found on $PATH, can't lock")?;
+            let mut command = new_smol_command(git_binary_path);
+            command
+                .envs(env.iter())
+                .current_dir(&working_directory?)
+                .args(["lfs", "lock", "--", path.as_unix_str()])
+                .stdin(smol::process::Stdio::null())
+                .stdout(smol::process::Stdio::piped())
+                .stderr(smol::process::Stdio::piped());
+
+            run_git_command(env, ask_pass, command, &executor).await?;
+            Ok(())
+        }
+        .boxed()
+    }
+
+    fn lfs_unlock(
+        &self,
+        path: RepoPath,
+        force: bool,
+        ask_pass: AskPassDelegate,
+        env: Arc<HashMap<String, String>>,
+        cx: AsyncApp,
+    ) -> BoxFuture<'_, Result<()>> {
+        let working_directory = self.working_directory();
+        let executor = cx.background_executor().clone();
+        let git_binary_path = self.system_git_binary_path.clone();
+        async move {
+            let git_binary_path =
+                git_binary_path.context("git not found on $PATH, can't unlock")?;
+            let mut command = new_smol_command(git_binary_path);
+            command
+                .envs(env.iter())
+                .current_dir(&working_directory?)
+                .args(["lfs", "unlock"]);
+            if force {
+                command.arg("--force");
+            }
+            command
+                .args(["--", path.as_unix_str()])
+                .stdin(smol::process::Stdio::null())
+                .stdout(smol::process::Stdio::piped())
+                .stderr(smol::process::Stdio::piped());
+
+            run_git_command(env, ask_pass, command, &executor).await?;
+            Ok(())
+        }
+        .boxed()
+    }
+
+    fn blame(&self, path: RepoPath, content: Rope) -> BoxFuture<'_, Result<crate::blame::Blame>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.any_git_binary_path.clone();
+
+        let remote_url = self
             .remote_url("upstream")
             .or_else(|| self.remote_url("origin"));
 
@@ -1242,8 +2852,12 @@ impl GitRepository for RealGitRepository {
         self.executor
             .spawn(async move {
                 let args = match diff {
-                    DiffType::HeadToIndex => Some("--staged"),
-                    DiffType::HeadToWorktree => None,
+                    DiffType::HeadToIndex => vec!["--staged".to_string()],
+                    DiffType::HeadToWorktree => Vec::new(),
+                    // Revisions can come from guests, so they mustn't be taken as options.
+                    DiffType::Commits { base, head } => {
+                        vec!["--end-of-options".to_string(), base, head]
+                    }
                 };
 
                 let output = new_smol_command(&git_binary_path)
@@ -1263,6 +2877,49 @@ impl GitRepository for RealGitRepository {
             .boxed()
     }
 
+    fn diff_file(&self, path: RepoPath, base_rev: String) -> BoxFuture<'_, Result<String>> {
+        let git_binary_path = self.any_git_binary_path.clone();
+        let working_directory = self.working_directory();
+        let executor = self.executor.clone();
+        self.executor
+            .spawn(async move {
+                GitBinary::new(git_binary_path, working_directory?, executor)
+                    .run_raw([
+                        "diff",
+                        "--end-of-options",
+                        &base_rev,
+                        "--",
+                        path.as_unix_str(),
+                    ])
+                    .await
+            })
+            .boxed()
+    }
+
+    fn load_blob(&self, rev: String, path: RepoPath) -> BoxFuture<'_, Result<String>> {
+        let repo = self.repository.clone();
+        self.executor
+            .spawn(async move {
+                let repo = repo.lock();
+                let tree = repo.revparse_single(&rev)?.peel_to_tree()?;
+                let entry = tree
+                    .get_path(path.as_std_path())
+                    .with_context(|| format!("{path:?} doesn't exist at {rev}"))?;
+                anyhow::ensure!(
+                    entry.filemode() != i32::from(git2::FileMode::Link),
+                    "{path:?} is a symlink at {rev}"
+                );
+                let blob = repo.find_blob(entry.id())?;
+                anyhow::ensure!(
+                    blob.size() <= MAX_BLOB_SIZE,
+                    "{path:?} is too large to load at {rev}"
+                );
+                String::from_utf8(blob.content().to_owned())
+                    .with_context(|| format!("{path:?} isn't a text file at {rev}"))
+            })
+            .boxed()
+    }
+
     fn stage_paths(
         &self,
         paths: Vec<RepoPath>,
@@ -1270,16 +2927,17 @@ impl GitRepository for RealGitRepository {
     ) -> BoxFuture<'_, Result<()>> {
         let working_directory = self.working_directory();
         let git_binary_path = self.any_git_binary_path.clone();
+        let executor = self.executor.clone();
         self.executor
             .spawn(async move {
                 if !paths.is_empty() {
-                    let output = new_smol_command(&git_binary_path)
+                    let mut command = new_smol_command(&git_binary_path);
+                    command
                         .current_dir(&working_directory?)
                         .envs(env.iter())
                         .args(["update-index", "--add", "--remove", "--"])
-                        .args(paths.iter().map(|p| p.as_unix_str()))
-                        .output()
-                        .await?;
+                        .args(paths.iter().map(|p| p.as_unix_str()));
+                    let output = output_retrying_index_lock(&mut command, &executor).await?;
                     anyhow::ensure!(
                         output.status.success(),
                         "Failed to stage paths:\n{}",
@@ -1298,17 +2956,18 @@ impl GitRepository for RealGitRepository {
     ) -> BoxFuture<'_, Result<()>> {
         let working_directory = self.working_directory();
         let git_binary_path = self.any_git_binary_path.clone();
+        let executor = self.executor.clone();
 
         self.executor
             .spawn(async move {
                 if !paths.is_empty() {
-                    let output = new_smol_command(&git_binary_path)
+                    let mut command = new_smol_command(&git_binary_path);
+                    command
                         .current_dir(&working_directory?)
                         .envs(env.iter())
                         .args(["reset", "--quiet", "--"])
-                        .args(paths.iter().map(|p| p.as_std_path()))
-                        .output()
-                        .await?;
+                        .args(paths.iter().map(|p| p.as_std_path()));
+                    let output = output_retrying_index_lock(&mut command, &executor).await?;
 
                     anyhow::ensure!(
                         output.status.success(),
@@ -1445,18 +3104,63 @@ impl GitRepository for RealGitRepository {
         message: SharedString,
         name_and_email: Option<(SharedString, SharedString)>,
         options: CommitOptions,
+        ask_pass: AskPassDelegate,
         env: Arc<HashMap<String, String>>,
+        cx: AsyncApp,
     ) -> BoxFuture<'_, Result<()>> {
         let working_directory = self.working_directory();
+        let git_dir = self.path();
         let git_binary_path = self.any_git_binary_path.clone();
+        let repository = self.repository.clone();
+        let executor = cx.background_executor().clone();
         self.executor
             .spawn(async move {
+                let signing_format =
+                    commit_signing_format(&repository.lock().config()?, options.sign)?;
+                let working_directory = working_directory?;
+
+                // The hooks are run ahead of the commit rather than by it, so that their failures
+                // can be told apart from git's own. Git versions that can't run hooks on their
+                // own are left to run them as part of the commit.
+                let hooks_supported = run_commit_hook(
+                    &git_binary_path,
+                    &working_directory,
+                    &env,
+                    CommitHook::PreCommit,
+                    None,
+                )
+                .await?;
+                let message = if hooks_supported {
+                    let message_path = git_dir.join("COMMIT_EDITMSG");
+                    std::fs::write(&message_path, message.as_bytes())
+                        .context("writing the commit message")?;
+                    run_commit_hook(
+                        &git_binary_path,
+                        &working_directory,
+                        &env,
+                        CommitHook::CommitMsg,
+                        Some(&message_path),
+                    )
+                    .await?;
+                    // The commit-msg hook is allowed to rewrite the message.
+                    std::fs::read_to_string(&message_path).context("reading the commit message")?
+                } else {
+                    message.to_string()
+                };
+
                 let mut cmd = new_smol_command(git_binary_path);
-                cmd.current_dir(&working_directory?)
+                cmd.current_dir(&working_directory)
                     .envs(env.iter())
-                    .args(["commit", "--quiet", "-m"])
-                    .arg(&message.to_string())
-                    .arg("--cleanup=strip");
+                    .args(["commit", "--quiet"]);
+                if hooks_supported {
+                    cmd.arg("--no-verify");
+                }
+                cmd.arg("-m")
+                    .arg(&message)
+                    .arg("--cleanup=strip")
+                    .stdin(smol::process::Stdio::null())
+                    .stdout(smol::process::Stdio::piped())
+                    .stderr(smol::process::Stdio::piped());
 
                 if options.amend {
                     cmd.arg("--amend");
@@ -1466,11 +3170,24 @@ impl GitRepository for RealGitRepository {
                     cmd.arg("--signoff");
                 }
 
+                if options.sign {
+                    cmd.arg("--gpg-sign");
+                }
+
                 if let Some((name, email)) = name_and_email {
                     cmd.arg("--author").arg(&format!("{name} <{email}>"));
                 }
 
-                let output = cmd.output().await?;
+                // The signer is spawned by git, and ssh-keygen asks for the passphrase of an
+                // encrypted key through SSH_ASKPASS. gpg uses its own pinentry instead.
+                if signing_format == Some(SigningFormat::Ssh) {
+                    run_git_command(env, ask_pass, cmd, &executor)
+                        .await
+                        .context("Failed to commit")?;
+                    return Ok(());
+                }
+
+                let output = output_retrying_index_lock(&mut cmd, &executor).await?;
 
                 anyhow::ensure!(
                     output.status.success(),
@@ -1488,6 +3205,7 @@ impl GitRepository for RealGitRepository {
         remote_name: String,
         options: Option<PushOptions>,
         ask_pass: AskPassDelegate,
+        progress: mpsc::UnboundedSender<RemoteProgress>,
         env: Arc<HashMap<String, String>>,
         cx: AsyncApp,
     ) -> BoxFuture<'_, Result<RemoteCommandOutput>> {
@@ -1501,7 +3219,7 @@ impl GitRepository for RealGitRepository {
             command
                 .envs(env.iter())
                 .current_dir(&working_directory)
-                .args(["push"])
+                .args(["push", "--progress"])
                 .args(options.map(|option| match option {
                     PushOptions::SetUpstream => "--set-upstream",
                     PushOptions::Force => "--force-with-lease",
@@ -1512,7 +3230,7 @@ impl GitRepository for RealGitRepository {
                 .stdout(smol::process::Stdio::piped())
                 .stderr(smol::process::Stdio::piped());
 
-            run_git_command(env, ask_pass, command, &executor).await
+            run_git_command_with_progress(env, ask_pass, command, Some(progress), &executor).await
         }
         .boxed()
     }
@@ -1521,7 +3239,9 @@ impl GitRepository for RealGitRepository {
         &self,
         branch_name: String,
         remote_name: String,
+        options: PullOptions,
         ask_pass: AskPassDelegate,
+        progress: mpsc::UnboundedSender<RemoteProgress>,
         env: Arc<HashMap<String, String>>,
         cx: AsyncApp,
     ) -> BoxFuture<'_, Result<RemoteCommandOutput>> {
@@ -1534,13 +3254,23 @@ impl GitRepository for RealGitRepository {
             command
                 .envs(env.iter())
                 .current_dir(&working_directory?)
-                .args(["pull"])
+                .args(["pull", "--progress"]);
+            if options.rebase {
+                command.arg("--rebase");
+            }
+            if options.ff_only {
+                command.arg("--ff-only");
+            }
+            if let Some(strategy) = options.strategy {
+                command.arg(format!("--strategy={strategy}"));
+            }
+            command
                 .arg(remote_name)
                 .arg(branch_name)
                 .stdout(smol::process::Stdio::piped())
                 .stderr(smol::process::Stdio::piped());
 
-            run_git_command(env, ask_pass, command, &executor).await
+            run_git_command_with_progress(env, ask_pass, command, Some(progress), &executor).await
         }
         .boxed()
     }
@@ -1549,6 +3279,7 @@ impl GitRepository for RealGitRepository {
         &self,
         fetch_options: FetchOptions,
         ask_pass: AskPassDelegate,
+        progress: mpsc::UnboundedSender<RemoteProgress>,
         env: Arc<HashMap<String, String>>,
         cx: AsyncApp,
     ) -> BoxFuture<'_, Result<RemoteCommandOutput>> {
@@ -1562,22 +3293,138 @@ impl GitRepository for RealGitRepository {
             command
                 .envs(env.iter())
                 .current_dir(&working_directory?)
-                .args(["fetch", &remote_name])
+                .args(["fetch", "--progress", &remote_name])
                 .stdout(smol::process::Stdio::piped())
                 .stderr(smol::process::Stdio::piped());
 
-            run_git_command(env, ask_pass, command, &executor).await
+            run_git_command_with_progress(env, ask_pass, command, Some(progress), &executor).await
         }
         .boxed()
     }
 
-    fn get_remotes(&self, branch_name: Option<String>) -> BoxFuture<'_, Result<Vec<Remote>>> {
+    fn tags(&self) -> BoxFuture<'_, Result<Vec<Tag>>> {
         let working_directory = self.working_directory();
         let git_binary_path = self.any_git_binary_path.clone();
         self.executor
             .spawn(async move {
-                let working_directory = working_directory?;
-                if let Some(branch_name) = branch_name {
+                let output = new_smol_command(&git_binary_path)
+                    .current_dir(&working_directory?)
+                    .args([
+                        "for-each-ref",
+                        "refs/tags",
+                        "--sort=-creatordate",
+                        TAG_FORMAT,
+                    ])
+                    .output()
+                    .await?;
+                anyhow::ensure!(
+                    output.status.success(),
+                    "Failed to list tags:\n{}",
+                    String::from_utf8_lossy(&output.stderr)
+                );
+                parse_tag_output(&String::from_utf8_lossy(&output.stdout))
+            })
+            .boxed()
+    }
+
+    fn create_tag(
+        &self,
+        name: String,
+        sha: String,
+        message: Option<String>,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.any_git_binary_path.clone();
+        self.executor
+            .spawn(async move {
+                // Neither argument is a valid tag or commit if it starts with a dash, so reject
+                // them rather than let them be parsed as options.
+                anyhow::ensure!(
+                    !name.starts_with('-') && !sha.starts_with('-'),
+                    "invalid tag {name:?} of commit {sha:?}"
+                );
+                let mut command = new_smol_command(&git_binary_path);
+                command
+                    .current_dir(&working_directory?)
+                    .envs(env.iter())
+                    .arg("tag");
+                if let Some(message) = &message {
+                    command.args(["--annotate", "--message", message]);
+                }
+                let output = command.arg(&name).arg(&sha).output().await?;
+                anyhow::ensure!(
+                    output.status.success(),
+                    "Failed to create tag:\n{}",
+                    String::from_utf8_lossy(&output.stderr)
+                );
+                Ok(())
+            })
+            .boxed()
+    }
+
+    fn delete_tag(
+        &self,
+        name: String,
+        env: Arc<HashMap<String, String>>,
+    ) -> BoxFuture<'_, Result<()>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.any_git_binary_path.clone();
+        self.executor
+            .spawn(async move {
+                let output = new_smol_command(&git_binary_path)
+                    .current_dir(&working_directory?)
+                    .envs(env.iter())
+                    .args(["tag", "--delete", "--"])
+                    .arg(&name)
+                    .output()
+                    .await?;
+                anyhow::ensure!(
+                    output.status.success(),
+                    "Failed to delete tag:\n{}",
+                    String::from_utf8_lossy(&output.stderr)
+                );
+                Ok(())
+            })
+            .boxed()
+    }
+
+    fn push_tag(
+        &self,
+        name: String,
+        remote_name: String,
+        ask_pass: AskPassDelegate,
+        env: Arc<HashMap<String, String>>,
+        cx: AsyncApp,
+    ) -> BoxFuture<'_, Result<RemoteCommandOutput>> {
+        let working_directory = self.working_directory();
+        let executor = cx.background_executor().clone();
+        let git_binary_path = self.system_git_binary_path.clone();
+        async move {
+            let git_binary_path = git_binary_path.context("git not found on $PATH, can't push")?;
+            let mut command = new_smol_command(git_binary_path);
+            command
+                .envs(env.iter())
+                .current_dir(&working_directory?)
+                .args(["push"])
+                .arg(remote_name)
+                .arg(format!("refs/tags/{name}"))
+                .stdin(smol::process::Stdio::null())
+                .stdout(smol::process::Stdio::piped())
+                .stderr(smol::process::Stdio::piped());
+
+            run_git_command(env, ask_pass, command, &executor).await
+        }
+        .boxed()
+    }
+
+    fn get_remotes(&self, branch_name: Option<String>) -> BoxFuture<'_, Result<Vec<Remote>>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.any_git_binary_path.clone();
+        self.executor
+            .spawn(async move {
+                let working_directory = working_directory?;
+                if let Some(branch_name) = branch_name {
                     let output = new_smol_command(&git_binary_path)
                         .current_dir(&working_directory)
                         .args(["config", "--get"])
@@ -1617,7 +3464,7 @@ impl GitRepository for RealGitRepository {
             .boxed()
     }
 
-    fn check_for_pushed_commit(&self) -> BoxFuture<'_, Result<Vec<SharedString>>> {
+    fn check_for_pushed_commit(&self) -> BoxFuture<'_, Result<Vec<PushedBranch>>> {
         let working_directory = self.working_directory();
         let git_binary_path = self.any_git_binary_path.clone();
         self.executor
@@ -1642,20 +3489,55 @@ impl GitRepository for RealGitRepository {
                     .trim()
                     .to_owned();
 
-                let mut remote_branches = vec![];
+                let remotes = git_cmd(&["remote"])
+                    .await
+                    .context("Failed to get remotes")?;
+                let mut remote_branches = Vec::<PushedBranch>::new();
                 let mut add_if_matching = async |remote_head: &str| {
-                    if let Ok(merge_base) = git_cmd(&["merge-base", &head, remote_head]).await
-                        && merge_base.trim() == head
-                        && let Some(s) = remote_head.strip_prefix("refs/remotes/")
-                    {
-                        remote_branches.push(s.to_owned().into());
+                    let Some(name) = remote_head.strip_prefix("refs/remotes/") else {
+                        return;
+                    };
+                    if remote_branches.iter().any(|branch| branch.name == *name) {
+                        return;
+                    }
+                    let Ok(merge_base) = git_cmd(&["merge-base", &head, remote_head]).await else {
+                        return;
+                    };
+                    if merge_base.trim() != head {
+                        return;
                     }
+                    // Remote names can contain slashes, so find the remote that the branch
+                    // belongs to among the known ones rather than splitting its name.
+                    let Some(remote_name) = remotes
+                        .lines()
+                        .filter(|remote| {
+                            name.strip_prefix(remote)
+                                .is_some_and(|branch| branch.starts_with('/'))
+                        })
+                        .max_by_key(|remote| remote.len())
+                    else {
+                        return;
+                    };
+                    let remote_url = git_cmd(&["remote", "get-url", remote_name])
+                        .await
+                        .ok()
+                        .map(|url| url.trim().to_owned().into());
+                    let Ok(tip) = git_cmd(&["log", "-1", "--format=%H%x00%s", remote_head]).await
+                    else {
+                        return;
+                    };
+                    let tip = tip.trim_end();
+                    let (tip_sha, tip_subject) = tip.split_once('\0').unwrap_or((tip, ""));
+                    remote_branches.push(PushedBranch {
+                        name: name.to_owned().into(),
+                        remote_name: remote_name.to_owned().into(),
+                        remote_url,
+                        tip_sha: tip_sha.to_owned().into(),
+                        tip_subject: tip_subject.to_owned().into(),
+                    });
                 };
 
                 // check the main branch of each remote
-                let remotes = git_cmd(&["remote"])
-                    .await
-                    .context("Failed to get remotes")?;
                 for remote in remotes.lines() {
                     if let Ok(remote_head) =
                         git_cmd(&["symbolic-ref", &format!("refs/remotes/{remote}/HEAD")]).await
@@ -2035,6 +3917,31 @@ impl GitBinary {
     }
 }
 
+/// A hook that runs when committing, and can reject the commit.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CommitHook {
+    PreCommit,
+    CommitMsg,
+}
+
+impl CommitHook {
+    pub fn name(&self) -> &'static str {
+        match self {
+            CommitHook::PreCommit => "pre-commit",
+            CommitHook::CommitMsg => "commit-msg",
+        }
+    }
+}
+
+/// A commit was rejected by one of its hooks. `output` is what the hook printed, which usually
+/// explains why.
+#[derive(Error, Debug)]
+#[error("The {} hook failed:\n{output}", hook.name())]
+pub struct CommitHookError {
+    pub hook: CommitHook,
+    pub output: String,
+}
+
 #[derive(Error, Debug)]
 #[error("Git command failed:\n{stdout}{stderr}\n")]
 struct GitBinaryCommandError {
@@ -2043,15 +3950,111 @@ struct GitBinaryCommandError {
     status: ExitStatus,
 }
 
+/// How many times a command is retried when another git process holds the index lock.
+const INDEX_LOCK_RETRIES: u32 = 3;
+
+/// How long to wait before retrying a command that found the index locked, which doubles with
+/// each retry.
+const INDEX_LOCK_RETRY_DELAY: Duration = Duration::from_millis(100);
+
+/// Runs a command that writes the index, retrying it while another git process, like an editor
+/// integration or a `git gc --auto`, holds the index lock. Git doesn't change anything when it
+/// can't take the lock, so the command is safe to run again.
+///
+/// The command runs in the C locale, as the lock is recognized by git's untranslated message.
+async fn output_retrying_index_lock(
+    command: &mut smol::process::Command,
+    executor: &BackgroundExecutor,
+) -> Result<std::process::Output> {
+    command.env("LC_ALL", "C").env("LANGUAGE", "C");
+    let mut delay = INDEX_LOCK_RETRY_DELAY;
+    let mut retries = 0;
+    loop {
+        let output = command.output().await?;
+        if output.status.success() {
+            return Ok(output);
+        }
+        let Some(lock_path) = index_lock_path(&String::from_utf8_lossy(&output.stderr)) else {
+            return Ok(output);
+        };
+        if retries == INDEX_LOCK_RETRIES {
+            return Err(IndexLocked { lock_path }.into());
+        }
+        log::debug!("{lock_path:?} exists, retrying in {delay:?}");
+        executor.timer(delay).await;
+        delay *= 2;
+        retries += 1;
+    }
+}
+
+/// The lock file that git names when it fails because another git process holds the index lock.
+fn index_lock_path(stderr: &str) -> Option<PathBuf> {
+    let (_, rest) = stderr.split_once("Unable to create '")?;
+    let (path, _) = rest.split_once("': File exists")?;
+    path.ends_with("index.lock").then(|| PathBuf::from(path))
+}
+
+/// Runs one of the repository's commit hooks, if it has one.
+///
+/// Returns `false` if this version of git can't run hooks on its own, as `git hook run` was only
+/// added in git 2.36, in which case the hook hasn't been run.
+async fn run_commit_hook(
+    git_binary_path: &Path,
+    working_directory: &Path,
+    env: &HashMap<String, String>,
+    hook: CommitHook,
+    message_path: Option<&Path>,
+) -> Result<bool> {
+    let output = new_smol_command(git_binary_path)
+        .current_dir(working_directory)
+        .envs(env.iter())
+        .args(["hook", "run", "--ignore-missing", hook.name(), "--"])
+        .args(message_path)
+        .stdin(smol::process::Stdio::null())
+        .output()
+        .await
+        .with_context(|| format!("running the {} hook", hook.name()))?;
+    if output.status.success() {
+        return Ok(true);
+    }
+    if is_hook_command_unsupported(&String::from_utf8_lossy(&output.stderr)) {
+        return Ok(false);
+    }
+    let mut hook_output = String::from_utf8_lossy(&output.stdout).into_owned();
+    hook_output.push_str(&String::from_utf8_lossy(&output.stderr));
+    Err(CommitHookError {
+        hook,
+        output: hook_output.trim().to_string(),
+    }
+    .into())
+}
+
+/// Whether git failed because it doesn't have the `hook` command.
+fn is_hook_command_unsupported(stderr: &str) -> bool {
+    stderr.contains("'hook' is not a git command")
+}
+
 async fn run_git_command(
+    env: Arc<HashMap<String, String>>,
+    ask_pass: AskPassDelegate,
+    command: smol::process::Command,
+    executor: &BackgroundExecutor,
+) -> Result<RemoteCommandOutput> {
+    run_git_command_with_progress(env, ask_pass, command, None, executor).await
+}
+
+/// Runs a command that talks to a remote, sending the progress that it writes to stderr to
+/// `progress`, if given, rather than including it in the output.
+async fn run_git_command_with_progress(
     env: Arc<HashMap<String, String>>,
     ask_pass: AskPassDelegate,
     mut command: smol::process::Command,
+    progress: Option<mpsc::UnboundedSender<RemoteProgress>>,
     executor: &BackgroundExecutor,
 ) -> Result<RemoteCommandOutput> {
     if env.contains_key("GIT_ASKPASS") {
         let git_process = command.spawn()?;
-        let output = git_process.output().await?;
+        let output = git_process_output(git_process, progress).await?;
         anyhow::ensure!(
             output.status.success(),
             "{}",
@@ -2069,13 +4072,14 @@ async fn run_git_command(
             .env("SSH_ASKPASS_REQUIRE", "force");
         let git_process = command.spawn()?;
 
-        run_askpass_command(ask_pass, git_process).await
+        run_askpass_command(ask_pass, git_process, progress).await
     }
 }
 
 async fn run_askpass_command(
     mut ask_pass: AskPassSession,
     git_process: smol::process::Child,
+    progress: Option<mpsc::UnboundedSender<RemoteProgress>>,
 ) -> anyhow::Result<RemoteCommandOutput> {
     select_biased! {
         result = ask_pass.run().fuse() => {
@@ -2088,7 +4092,7 @@ async fn run_askpass_command(
                 }
             }
         }
-        output = git_process.output().fuse() => {
+        output = git_process_output(git_process, progress).fuse() => {
             let output = output?;
             anyhow::ensure!(
                 output.status.success(),
@@ -2103,6 +4107,72 @@ async fn run_askpass_command(
     }
 }
 
+/// Waits for a process to exit and collects its output, like [`smol::process::Child::output`].
+/// If `progress` is given, the progress lines that git writes to stderr are sent to it as they're
+/// written, and left out of the output.
+async fn git_process_output(
+    mut git_process: smol::process::Child,
+    progress: Option<mpsc::UnboundedSender<RemoteProgress>>,
+) -> Result<std::process::Output> {
+    let Some(progress) = progress else {
+        return Ok(git_process.output().await?);
+    };
+    let mut stdout = git_process
+        .stdout
+        .take()
+        .context("git's stdout isn't piped")?;
+    let mut stderr = git_process
+        .stderr
+        .take()
+        .context("git's stderr isn't piped")?;
+
+    let read_stdout = async move {
+        let mut output = Vec::new();
+        stdout.read_to_end(&mut output).await?;
+        anyhow::Ok(output)
+    };
+    let read_stderr = async move {
+        let mut unread = Vec::new();
+        let mut output = Vec::new();
+        let mut chunk = [0; 4096];
+        loop {
+            let chunk_len = stderr.read(&mut chunk).await?;
+            if chunk_len == 0 {
+                break;
+            }
+            unread.extend_from_slice(&chunk[..chunk_len]);
+            // Progress lines end with a carriage return, so that terminals overwrite them with
+            // the next update.
+            while let Some(line_len) = unread
+                .iter()
+                .position(|byte| *byte == b'\r' || *byte == b'\n')
+            {
+                let line = String::from_utf8_lossy(&unread[..line_len]);
+                match parse_remote_progress(&line) {
+                    Some(update) => {
+                        progress.unbounded_send(update).ok();
+                    }
+                    None if !line.is_empty() => {
+                        output.extend_from_slice(&unread[..line_len]);
+                        output.push(b'\n');
+                    }
+                    None => {}
+                }
+                unread.drain(..=line_len);
+            }
+        }
+        output.extend_from_slice(&unread);
+        anyhow::Ok(output)
+    };
+    let (stdout, stderr) = futures::future::try_join(read_stdout, read_stderr).await?;
+    let status = git_process.status().await?;
+    Ok(std::process::Output {
+        status,
+        stdout,
+        stderr,
+    })
+}
+
 #[derive(Clone, Debug, Ord, Hash, PartialOrd, Eq, PartialEq)]
 pub struct RepoPath(pub Arc<RelPath>);
 
@@ -2265,18 +4335,71 @@ fn checkpoint_author_envs() -> HashMap<String, String> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use futures::StreamExt as _;
     use gpui::TestAppContext;
 
+    #[test]
+    fn test_load_rebase_state() {
+        let git_dir = tempfile::tempdir().unwrap();
+        assert_eq!(RebaseState::load(git_dir.path()), None);
+
+        let rebase_dir = git_dir.path().join("rebase-merge");
+        std::fs::create_dir(&rebase_dir).unwrap();
+        for (name, content) in [
+            ("head-name", "refs/heads/feature\n"),
+            ("onto", "1234abcd\n"),
+            ("msgnum", "2\n"),
+            ("end", "5\n"),
+            ("interactive", ""),
+            ("stopped-sha", "5678efgh\n"),
+        ] {
+            std::fs::write(rebase_dir.join(name), content).unwrap();
+        }
+        assert_eq!(
+            RebaseState::load(git_dir.path()),
+            Some(RebaseState {
+                head_name: Some("feature".into()),
+                onto: "1234abcd".into(),
+                step: 2,
+                total_steps: 5,
+                interactive: true,
+                stopped_at: Some("5678efgh".into()),
+            })
+        );
+
+        // `rebase-apply` is only a rebase's if it's marked as one, since `git am` uses it too.
+        std::fs::remove_dir_all(&rebase_dir).unwrap();
+        let apply_dir = git_dir.path().join("rebase-apply");
+        std::fs::create_dir(&apply_dir).unwrap();
+        for (name, content) in [
+            ("head-name", "detached HEAD\n"),
+            ("onto", "1234abcd\n"),
+            ("next", "1\n"),
+            ("last", "3\n"),
+        ] {
+            std::fs::write(apply_dir.join(name), content).unwrap();
+        }
+        assert_eq!(RebaseState::load(git_dir.path()), None);
+        std::fs::write(apply_dir.join("rebasing"), "").unwrap();
+        assert_eq!(
+            RebaseState::load(git_dir.path()),
+            Some(RebaseState {
+                head_name: None,
+                onto: "1234abcd".into(),
+                step: 1,
+                total_steps: 3,
+                interactive: false,
+                stopped_at: None,
+            })
+        );
+    }
+
     #[gpui::test]
-    async fn test_checkpoint_basic(cx: &mut TestAppContext) {
+    async fn test_log(cx: &mut TestAppContext) {
         cx.executor().allow_parking();
 
         let repo_dir = tempfile::tempdir().unwrap();
-
         git2::Repository::init(repo_dir.path()).unwrap();
-        let file_path = repo_dir.path().join("file");
-        smol::fs::write(&file_path, "initial").await.unwrap();
-
         let repo = RealGitRepository::new(
             &repo_dir.path().join(".git"),
             None,
@@ -2284,76 +4407,98 @@ mod tests {
             cx.executor(),
         )
         .unwrap();
-        repo.stage_paths(vec![repo_path("file")], Arc::new(HashMap::default()))
-            .await
-            .unwrap();
-        repo.commit(
-            "Initial commit".into(),
-            None,
-            CommitOptions::default(),
-            Arc::new(checkpoint_author_envs()),
-        )
-        .await
-        .unwrap();
-
-        smol::fs::write(&file_path, "modified before checkpoint")
-            .await
-            .unwrap();
-        smol::fs::write(repo_dir.path().join("new_file_before_checkpoint"), "1")
-            .await
-            .unwrap();
-        let checkpoint = repo.checkpoint().await.unwrap();
-
-        // Ensure the user can't see any branches after creating a checkpoint.
-        assert_eq!(repo.branches().await.unwrap().len(), 1);
-
-        smol::fs::write(&file_path, "modified after checkpoint")
-            .await
-            .unwrap();
-        repo.stage_paths(vec![repo_path("file")], Arc::new(HashMap::default()))
+        // A repository without commits has an empty history.
+        assert_eq!(
+            repo.log(LogOptions {
+                limit: 10,
+                ..Default::default()
+            })
             .await
-            .unwrap();
-        repo.commit(
-            "Commit after checkpoint".into(),
-            None,
-            CommitOptions::default(),
-            Arc::new(checkpoint_author_envs()),
-        )
-        .await
-        .unwrap();
+            .unwrap(),
+            Vec::<CommitDetails>::new()
+        );
 
-        smol::fs::remove_file(repo_dir.path().join("new_file_before_checkpoint"))
-            .await
-            .unwrap();
-        smol::fs::write(repo_dir.path().join("new_file_after_checkpoint"), "2")
+        for (file, message) in [
+            ("a", "Add a"),
+            ("b", "Add b\n\nWith a body."),
+            ("a", "Change a"),
+        ] {
+            smol::fs::write(repo_dir.path().join(file), message)
+                .await
+                .unwrap();
+            repo.stage_paths(vec![repo_path(file)], Arc::new(HashMap::default()))
+                .await
+                .unwrap();
+            repo.commit(
+                message.into(),
+                None,
+                CommitOptions::default(),
+                test_askpass(cx),
+                Arc::new(checkpoint_author_envs()),
+                cx.to_async(),
+            )
             .await
             .unwrap();
+        }
 
-        // Ensure checkpoint stays alive even after a Git GC.
-        repo.gc().await.unwrap();
-        repo.restore_checkpoint(checkpoint.clone()).await.unwrap();
-
+        let messages = |commits: Vec<CommitDetails>| {
+            commits
+                .into_iter()
+                .map(|commit| {
+                    assert_eq!(commit.author_name.as_ref(), "Zed");
+                    commit.message.trim_end().to_string()
+                })
+                .collect::<Vec<_>>()
+        };
         assert_eq!(
-            smol::fs::read_to_string(&file_path).await.unwrap(),
-            "modified before checkpoint"
+            messages(
+                repo.log(LogOptions {
+                    limit: 2,
+                    ..Default::default()
+                })
+                .await
+                .unwrap()
+            ),
+            ["Change a", "Add b\n\nWith a body."]
         );
         assert_eq!(
-            smol::fs::read_to_string(repo_dir.path().join("new_file_before_checkpoint"))
+            messages(
+                repo.log(LogOptions {
+                    limit: 2,
+                    skip: 2,
+                    ..Default::default()
+                })
                 .await
-                .unwrap(),
-            "1"
+                .unwrap()
+            ),
+            ["Add a"]
+        );
+        assert_eq!(
+            messages(
+                repo.log(LogOptions {
+                    limit: 10,
+                    path: Some(repo_path("a")),
+                    ..Default::default()
+                })
+                .await
+                .unwrap()
+            ),
+            ["Change a", "Add a"]
+        );
+        assert_eq!(
+            repo.log(LogOptions {
+                limit: 10,
+                author: Some("someone-else@example.com".into()),
+                ..Default::default()
+            })
+            .await
+            .unwrap(),
+            Vec::<CommitDetails>::new()
         );
-        // See TODO above
-        // assert_eq!(
-        //     smol::fs::read_to_string(repo_dir.path().join("new_file_after_checkpoint"))
-        //         .await
-        //         .ok(),
-        //     None
-        // );
     }
 
     #[gpui::test]
-    async fn test_checkpoint_empty_repo(cx: &mut TestAppContext) {
+    async fn test_commit_graph(cx: &mut TestAppContext) {
         cx.executor().allow_parking();
 
         let repo_dir = tempfile::tempdir().unwrap();
@@ -2365,16 +4510,1692 @@ mod tests {
             cx.executor(),
         )
         .unwrap();
-
-        smol::fs::write(repo_dir.path().join("foo"), "foo")
+        let env = Arc::new(checkpoint_author_envs());
+        let commit_file = async |file: &str, cx: &mut TestAppContext| {
+            smol::fs::write(repo_dir.path().join(file), file)
+                .await
+                .unwrap();
+            repo.stage_paths(vec![repo_path(file)], env.clone())
+                .await
+                .unwrap();
+            repo.commit(
+                format!("Add {file}").into(),
+                None,
+                CommitOptions::default(),
+                test_askpass(cx),
+                env.clone(),
+                cx.to_async(),
+            )
             .await
             .unwrap();
-        let checkpoint_sha = repo.checkpoint().await.unwrap();
-
-        // Ensure the user can't see any branches after creating a checkpoint.
-        assert_eq!(repo.branches().await.unwrap().len(), 1);
+        };
 
-        smol::fs::write(repo_dir.path().join("foo"), "bar")
+        commit_file("base", cx).await;
+        let main_branch = repo
+            .branches()
+            .await
+            .unwrap()
+            .into_iter()
+            .find(|branch| branch.is_head)
+            .unwrap()
+            .name()
+            .to_string();
+        repo.create_branch("feature".into()).await.unwrap();
+        repo.change_branch("feature".into()).await.unwrap();
+        commit_file("feature", cx).await;
+        repo.change_branch(main_branch.clone()).await.unwrap();
+        commit_file("main", cx).await;
+
+        let first_page = repo
+            .commit_graph(CommitGraphOptions {
+                limit: 2,
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(first_page.commits.len(), 2);
+        assert!(first_page.has_more);
+        let second_page = repo
+            .commit_graph(CommitGraphOptions {
+                limit: 2,
+                skip: 2,
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert!(!second_page.has_more);
+
+        // Both branches' commits come before the commit they were branched from.
+        let commits = first_page
+            .commits
+            .into_iter()
+            .chain(second_page.commits)
+            .collect::<Vec<_>>();
+        assert_eq!(commits.len(), 3);
+        let base = &commits[2];
+        assert_eq!(base.subject.as_ref(), "Add base");
+        assert!(base.parents.is_empty());
+        let head_ref = format!("HEAD -> {main_branch}");
+        for commit in &commits[..2] {
+            assert_eq!(commit.parents, [base.sha]);
+            let expected_ref = match commit.subject.as_ref() {
+                "Add main" => head_ref.as_str(),
+                "Add feature" => "feature",
+                subject => panic!("unexpected commit {subject:?}"),
+            };
+            assert_eq!(
+                commit.refs.iter().map(AsRef::as_ref).collect::<Vec<&str>>(),
+                [expected_ref]
+            );
+        }
+
+        let feature_only = repo
+            .commit_graph(CommitGraphOptions {
+                range: Some(format!("{main_branch}..feature")),
+                limit: 10,
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(
+            feature_only
+                .commits
+                .iter()
+                .map(|commit| commit.subject.as_ref())
+                .collect::<Vec<_>>(),
+            ["Add feature"]
+        );
+    }
+
+    #[gpui::test]
+    async fn test_file_history(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+
+        let repo_dir = tempfile::tempdir().unwrap();
+        git2::Repository::init(repo_dir.path()).unwrap();
+        let repo = RealGitRepository::new(
+            &repo_dir.path().join(".git"),
+            None,
+            Some("git".into()),
+            cx.executor(),
+        )
+        .unwrap();
+        assert_eq!(
+            repo.file_history(repo_path("new"), true).await.unwrap(),
+            Vec::new()
+        );
+
+        let env = Arc::new(checkpoint_author_envs());
+        let commit = async |message: &str| {
+            repo.commit(
+                message.to_string().into(),
+                None,
+                CommitOptions::default(),
+                test_askpass(cx),
+                env.clone(),
+                cx.to_async(),
+            )
+            .await
+            .unwrap();
+        };
+        let contents = "one\ntwo\nthree\nfour\nfive\n";
+        smol::fs::write(repo_dir.path().join("old"), contents)
+            .await
+            .unwrap();
+        repo.stage_paths(vec![repo_path("old")], env.clone())
+            .await
+            .unwrap();
+        commit("Add old").await;
+        smol::fs::rename(repo_dir.path().join("old"), repo_dir.path().join("new"))
+            .await
+            .unwrap();
+        repo.stage_paths(vec![repo_path("old"), repo_path("new")], env.clone())
+            .await
+            .unwrap();
+        commit("Rename old to new").await;
+        smol::fs::write(repo_dir.path().join("new"), format!("{contents}six\n"))
+            .await
+            .unwrap();
+        repo.stage_paths(vec![repo_path("new")], env.clone())
+            .await
+            .unwrap();
+        commit("Change new").await;
+
+        let history = |entries: Vec<FileHistoryEntry>| {
+            entries
+                .into_iter()
+                .map(|entry| {
+                    (
+                        entry.commit.message.trim_end().to_string(),
+                        entry.path.as_unix_str().to_string(),
+                    )
+                })
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            history(repo.file_history(repo_path("new"), true).await.unwrap()),
+            [
+                ("Change new".to_string(), "new".to_string()),
+                ("Rename old to new".to_string(), "new".to_string()),
+                ("Add old".to_string(), "old".to_string()),
+            ]
+        );
+        assert_eq!(
+            history(repo.file_history(repo_path("new"), false).await.unwrap()),
+            [
+                ("Change new".to_string(), "new".to_string()),
+                ("Rename old to new".to_string(), "new".to_string()),
+            ]
+        );
+    }
+
+    #[gpui::test]
+    async fn test_tags(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+
+        let repo_dir = tempfile::tempdir().unwrap();
+        git2::Repository::init(repo_dir.path()).unwrap();
+        let repo = RealGitRepository::new(
+            &repo_dir.path().join(".git"),
+            None,
+            Some("git".into()),
+            cx.executor(),
+        )
+        .unwrap();
+        smol::fs::write(repo_dir.path().join("file"), "initial")
+            .await
+            .unwrap();
+        repo.stage_paths(vec![repo_path("file")], Arc::new(HashMap::default()))
+            .await
+            .unwrap();
+        repo.commit(
+            "Initial commit".into(),
+            None,
+            CommitOptions::default(),
+            test_askpass(cx),
+            Arc::new(checkpoint_author_envs()),
+            cx.to_async(),
+        )
+        .await
+        .unwrap();
+        let head_sha = repo.head_sha().await.unwrap();
+        let env = Arc::new(checkpoint_author_envs());
+
+        repo.create_tag("v1".into(), head_sha.clone(), None, env.clone())
+            .await
+            .unwrap();
+        repo.create_tag(
+            "v2".into(),
+            head_sha.clone(),
+            Some("Release 2\n\nWith notes.".into()),
+            env.clone(),
+        )
+        .await
+        .unwrap();
+        assert!(
+            repo.create_tag("--force".into(), head_sha.clone(), None, env.clone())
+                .await
+                .is_err()
+        );
+
+        let mut tags = repo.tags().await.unwrap();
+        tags.sort_by(|tag, other| tag.name.cmp(&other.name));
+        assert_eq!(
+            tags,
+            [
+                Tag {
+                    name: "v1".into(),
+                    sha: head_sha.clone().into(),
+                    message: None,
+                },
+                Tag {
+                    name: "v2".into(),
+                    sha: head_sha.clone().into(),
+                    message: Some("Release 2\n\nWith notes.".into()),
+                },
+            ]
+        );
+
+        repo.delete_tag("v1".into(), env).await.unwrap();
+        let tags = repo.tags().await.unwrap();
+        assert_eq!(
+            tags.iter().map(|tag| tag.name.as_ref()).collect::<Vec<_>>(),
+            ["v2"]
+        );
+    }
+
+    #[gpui::test]
+    async fn test_pull_options(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+
+        let origin_dir = tempfile::tempdir().unwrap();
+        git2::Repository::init(origin_dir.path()).unwrap();
+        let origin = RealGitRepository::new(
+            &origin_dir.path().join(".git"),
+            None,
+            Some("git".into()),
+            cx.executor(),
+        )
+        .unwrap();
+        let env = Arc::new(checkpoint_author_envs());
+        smol::fs::write(origin_dir.path().join("file"), "base")
+            .await
+            .unwrap();
+        origin
+            .stage_paths(vec![repo_path("file")], env.clone())
+            .await
+            .unwrap();
+        origin
+            .commit(
+                "Initial commit".into(),
+                None,
+                CommitOptions::default(),
+                test_askpass(cx),
+                env.clone(),
+                cx.to_async(),
+            )
+            .await
+            .unwrap();
+        let branches = origin.branches().await.unwrap();
+        let branch_name = branches
+            .iter()
+            .find(|branch| branch.is_head)
+            .unwrap()
+            .name()
+            .to_string();
+
+        let local_dir = tempfile::tempdir().unwrap();
+        let output = new_smol_command("git")
+            .arg("clone")
+            .arg(origin_dir.path())
+            .arg(local_dir.path())
+            .output()
+            .await
+            .unwrap();
+        assert!(output.status.success());
+        let local = RealGitRepository::new(
+            &local_dir.path().join(".git"),
+            None,
+            Some("git".into()),
+            cx.executor(),
+        )
+        .unwrap();
+
+        // Diverge from the remote by changing the same line on both sides.
+        for (repo, dir, content) in [
+            (&origin, origin_dir.path(), "upstream"),
+            (&local, local_dir.path(), "local"),
+        ] {
+            smol::fs::write(dir.join("file"), content).await.unwrap();
+            repo.stage_paths(vec![repo_path("file")], env.clone())
+                .await
+                .unwrap();
+            repo.commit(
+                content.to_string().into(),
+                None,
+                CommitOptions::default(),
+                test_askpass(cx),
+                env.clone(),
+                cx.to_async(),
+            )
+            .await
+            .unwrap();
+        }
+
+        let (progress_tx, mut progress_rx) = mpsc::unbounded();
+        let pull = |options: PullOptions, cx: &mut TestAppContext| {
+            local.pull(
+                branch_name.clone(),
+                "origin".into(),
+                options,
+                test_askpass(cx),
+                progress_tx.clone(),
+                env.clone(),
+                cx.to_async(),
+            )
+        };
+        pull(
+            PullOptions {
+                ff_only: true,
+                ..Default::default()
+            },
+            cx,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(local.rebase_state().await, None);
+        // Fetching the upstream commit is reported as it happens.
+        assert!(progress_rx.try_next().unwrap().is_some());
+
+        // The conflicting change stops the rebase, leaving it in progress.
+        pull(
+            PullOptions {
+                rebase: true,
+                ..Default::default()
+            },
+            cx,
+        )
+        .await
+        .unwrap_err();
+        assert!(local.rebase_state().await.is_some());
+    }
+
+    #[test]
+    fn test_parse_remote_progress() {
+        assert_eq!(
+            parse_remote_progress("Receiving objects:  45% (450/1000), 1.20 MiB | 512.00 KiB/s"),
+            Some(RemoteProgress {
+                phase: "Receiving objects".into(),
+                percent: Some(45),
+                completed: 450,
+                total: Some(1000),
+            })
+        );
+        assert_eq!(
+            parse_remote_progress("remote: Enumerating objects: 12, done.        "),
+            Some(RemoteProgress {
+                phase: "Enumerating objects".into(),
+                percent: None,
+                completed: 12,
+                total: None,
+            })
+        );
+        assert_eq!(
+            parse_remote_progress("remote: Total 3 (delta 0), reused 0 (delta 0)"),
+            None
+        );
+        assert_eq!(
+            parse_remote_progress("error: failed to push some refs to 'origin'"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_index_lock_path() {
+        assert_eq!(
+            index_lock_path(
+                "fatal: Unable to create '/code/.git/index.lock': File exists.\n\nAnother git process seems to be running in this repository"
+            ),
+            Some(PathBuf::from("/code/.git/index.lock"))
+        );
+        assert_eq!(
+            index_lock_path("error: pathspec 'file' did not match any file(s) known to git"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_is_hook_command_unsupported() {
+        assert!(is_hook_command_unsupported(
+            "git: 'hook' is not a git command. See 'git --help'."
+        ));
+        assert!(!is_hook_command_unsupported("lint failed"));
+    }
+
+    #[gpui::test]
+    async fn test_merge(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+
+        let repo_dir = tempfile::tempdir().unwrap();
+        git2::Repository::init(repo_dir.path()).unwrap();
+        let repo = RealGitRepository::new(
+            &repo_dir.path().join(".git"),
+            None,
+            Some("git".into()),
+            cx.executor(),
+        )
+        .unwrap();
+        let env = Arc::new(checkpoint_author_envs());
+        let commit_file = async |content: &str, cx: &mut TestAppContext| {
+            smol::fs::write(repo_dir.path().join("file"), content)
+                .await
+                .unwrap();
+            repo.stage_paths(vec![repo_path("file")], env.clone())
+                .await
+                .unwrap();
+            repo.commit(
+                content.to_string().into(),
+                None,
+                CommitOptions::default(),
+                test_askpass(cx),
+                env.clone(),
+                cx.to_async(),
+            )
+            .await
+            .unwrap();
+        };
+
+        commit_file("base", cx).await;
+        let branches = repo.branches().await.unwrap();
+        let main_branch = branches
+            .iter()
+            .find(|branch| branch.is_head)
+            .unwrap()
+            .name()
+            .to_string();
+        repo.create_branch("feature".into()).await.unwrap();
+        repo.change_branch("feature".into()).await.unwrap();
+        commit_file("feature", cx).await;
+        repo.change_branch(main_branch).await.unwrap();
+        commit_file("main", cx).await;
+
+        assert!(
+            repo.merge(
+                "feature".into(),
+                MergeOptions {
+                    ff_only: true,
+                    ..Default::default()
+                },
+                env.clone(),
+            )
+            .await
+            .is_err()
+        );
+        assert_eq!(repo.merge_message().await, None);
+
+        // The conflicting change stops the merge, leaving it in progress.
+        repo.merge("feature".into(), MergeOptions::default(), env.clone())
+            .await
+            .unwrap();
+        assert!(
+            repo.merge_message()
+                .await
+                .unwrap()
+                .starts_with("Merge branch 'feature'")
+        );
+        let status = repo.status(&[RelPath::empty().into()]).await.unwrap();
+        assert!(
+            status
+                .entries
+                .iter()
+                .any(|(path, status)| *path == repo_path("file") && status.is_conflicted())
+        );
+
+        repo.merge_abort(env).await.unwrap();
+        assert_eq!(repo.merge_message().await, None);
+        assert_eq!(
+            smol::fs::read_to_string(repo_dir.path().join("file"))
+                .await
+                .unwrap(),
+            "main"
+        );
+    }
+
+    #[gpui::test]
+    async fn test_paths_discarded_by_hard_reset(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+
+        let repo_dir = tempfile::tempdir().unwrap();
+        git2::Repository::init(repo_dir.path()).unwrap();
+        let repo = RealGitRepository::new(
+            &repo_dir.path().join(".git"),
+            None,
+            Some("git".into()),
+            cx.executor(),
+        )
+        .unwrap();
+        let env = Arc::new(checkpoint_author_envs());
+        for name in ["modified", "unchanged", "untracked", "deleted"] {
+            smol::fs::write(repo_dir.path().join(name), name)
+                .await
+                .unwrap();
+        }
+        repo.stage_paths(
+            vec![
+                repo_path("modified"),
+                repo_path("unchanged"),
+                repo_path("untracked"),
+                repo_path("deleted"),
+            ],
+            env.clone(),
+        )
+        .await
+        .unwrap();
+        repo.commit(
+            "Initial commit".into(),
+            None,
+            CommitOptions::default(),
+            test_askpass(cx),
+            env.clone(),
+            cx.to_async(),
+        )
+        .await
+        .unwrap();
+
+        smol::fs::write(repo_dir.path().join("modified"), "changed")
+            .await
+            .unwrap();
+        smol::fs::remove_file(repo_dir.path().join("deleted"))
+            .await
+            .unwrap();
+        smol::fs::write(repo_dir.path().join("new"), "new")
+            .await
+            .unwrap();
+        // A file that was committed but is untracked now gets overwritten by the reset.
+        let mut index = git2::Repository::open(repo_dir.path())
+            .unwrap()
+            .index()
+            .unwrap();
+        index.remove_path(Path::new("untracked")).unwrap();
+        index.write().unwrap();
+
+        let mut paths = repo
+            .paths_discarded_by_hard_reset("HEAD".into())
+            .await
+            .unwrap();
+        paths.sort();
+        assert_eq!(paths, [repo_path("modified"), repo_path("untracked")]);
+    }
+
+    #[gpui::test]
+    async fn test_rebase(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+
+        let repo_dir = tempfile::tempdir().unwrap();
+        git2::Repository::init(repo_dir.path()).unwrap();
+        let repo = RealGitRepository::new(
+            &repo_dir.path().join(".git"),
+            None,
+            Some("git".into()),
+            cx.executor(),
+        )
+        .unwrap();
+        let env = Arc::new(checkpoint_author_envs());
+        let commit_file = async |content: &str, cx: &mut TestAppContext| {
+            smol::fs::write(repo_dir.path().join("file"), content)
+                .await
+                .unwrap();
+            repo.stage_paths(vec![repo_path("file")], env.clone())
+                .await
+                .unwrap();
+            repo.commit(
+                content.to_string().into(),
+                None,
+                CommitOptions::default(),
+                test_askpass(cx),
+                env.clone(),
+                cx.to_async(),
+            )
+            .await
+            .unwrap();
+        };
+
+        commit_file("base", cx).await;
+        let branches = repo.branches().await.unwrap();
+        let main_branch = branches
+            .iter()
+            .find(|branch| branch.is_head)
+            .unwrap()
+            .name()
+            .to_string();
+        commit_file("main", cx).await;
+        repo.create_branch("feature".into()).await.unwrap();
+        repo.change_branch("feature".into()).await.unwrap();
+        repo.reset("HEAD~1".into(), ResetMode::Hard, env.clone())
+            .await
+            .unwrap();
+        commit_file("feature", cx).await;
+
+        // The conflicting change stops the rebase, leaving it in progress.
+        repo.rebase_start(main_branch.clone(), false, env.clone())
+            .await
+            .unwrap();
+        let state = repo.rebase_state().await.unwrap();
+        assert_eq!(state.head_name, Some("feature".into()));
+        assert!(state.stopped_at.is_some());
+
+        // Neither starting another rebase nor continuing with the conflict unresolved gets anywhere.
+        assert!(
+            repo.rebase_start(main_branch, false, env.clone())
+                .await
+                .is_err()
+        );
+        assert!(repo.rebase_continue(env.clone()).await.is_err());
+        assert_eq!(repo.rebase_state().await, Some(state));
+
+        repo.rebase_abort(env).await.unwrap();
+        assert_eq!(repo.rebase_state().await, None);
+        assert_eq!(
+            smol::fs::read_to_string(repo_dir.path().join("file"))
+                .await
+                .unwrap(),
+            "feature"
+        );
+    }
+
+    #[cfg(unix)]
+    #[gpui::test]
+    async fn test_commit_hooks_and_template(cx: &mut TestAppContext) {
+        use std::os::unix::fs::PermissionsExt as _;
+
+        cx.executor().allow_parking();
+
+        let repo_dir = tempfile::tempdir().unwrap();
+        git2::Repository::init(repo_dir.path()).unwrap();
+        let repo = RealGitRepository::new(
+            &repo_dir.path().join(".git"),
+            None,
+            Some("git".into()),
+            cx.executor(),
+        )
+        .unwrap();
+        let env = Arc::new(checkpoint_author_envs());
+        let hooks_dir = repo_dir.path().join(".git/hooks");
+        smol::fs::create_dir_all(&hooks_dir).await.unwrap();
+        let write_hook = async |name: &str, script: &str| {
+            let path = hooks_dir.join(name);
+            smol::fs::write(&path, script).await.unwrap();
+            smol::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755))
+                .await
+                .unwrap();
+        };
+
+        assert_eq!(repo.commit_template().await, None);
+        smol::fs::write(repo_dir.path().join("template.txt"), "Summary\n")
+            .await
+            .unwrap();
+        git2::Repository::open(repo_dir.path())
+            .unwrap()
+            .config()
+            .unwrap()
+            .set_str("commit.template", "template.txt")
+            .unwrap();
+        assert_eq!(repo.commit_template().await.as_deref(), Some("Summary\n"));
+
+        smol::fs::write(repo_dir.path().join("file"), "content")
+            .await
+            .unwrap();
+        repo.stage_paths(vec![repo_path("file")], env.clone())
+            .await
+            .unwrap();
+
+        write_hook("pre-commit", "#!/bin/sh\necho 'lint failed'\nexit 1\n").await;
+        let error = repo
+            .commit(
+                "message".into(),
+                None,
+                CommitOptions::default(),
+                test_askpass(cx),
+                env.clone(),
+                cx.to_async(),
+            )
+            .await
+            .unwrap_err();
+        let hook_error = error.downcast_ref::<CommitHookError>().unwrap();
+        assert_eq!(hook_error.hook, CommitHook::PreCommit);
+        assert_eq!(hook_error.output, "lint failed");
+
+        write_hook("pre-commit", "#!/bin/sh\nexit 0\n").await;
+        write_hook(
+            "commit-msg",
+            "#!/bin/sh\nprintf '\\n\\nSigned-off-by: hook' >> \"$1\"\n",
+        )
+        .await;
+        repo.commit(
+            "message".into(),
+            None,
+            CommitOptions::default(),
+            test_askpass(cx),
+            env.clone(),
+            cx.to_async(),
+        )
+        .await
+        .unwrap();
+        let head_message = git2::Repository::open(repo_dir.path())
+            .unwrap()
+            .head()
+            .unwrap()
+            .peel_to_commit()
+            .unwrap()
+            .message()
+            .unwrap()
+            .to_string();
+        assert_eq!(head_message, "message\n\nSigned-off-by: hook\n");
+    }
+
+    #[gpui::test]
+    async fn test_check_for_pushed_commit(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+
+        let repo_dir = tempfile::tempdir().unwrap();
+        git2::Repository::init(repo_dir.path()).unwrap();
+        let repo = RealGitRepository::new(
+            &repo_dir.path().join(".git"),
+            None,
+            Some("git".into()),
+            cx.executor(),
+        )
+        .unwrap();
+        let env = Arc::new(checkpoint_author_envs());
+        let commit = async |message: &str, cx: &mut TestAppContext| {
+            smol::fs::write(repo_dir.path().join("file"), message)
+                .await
+                .unwrap();
+            repo.stage_paths(vec![repo_path("file")], env.clone())
+                .await
+                .unwrap();
+            repo.commit(
+                message.to_string().into(),
+                None,
+                CommitOptions::default(),
+                test_askpass(cx),
+                env.clone(),
+                cx.to_async(),
+            )
+            .await
+            .unwrap();
+        };
+
+        commit("Pushed commit", cx).await;
+        let head_sha = repo.head_sha().await.unwrap();
+        for args in [
+            vec![
+                "remote",
+                "add",
+                "team/origin",
+                "https://example.com/repo.git",
+            ],
+            vec!["update-ref", "refs/remotes/team/origin/main", "HEAD"],
+            vec![
+                "symbolic-ref",
+                "refs/remotes/team/origin/HEAD",
+                "refs/remotes/team/origin/main",
+            ],
+        ] {
+            let output = new_smol_command("git")
+                .current_dir(repo_dir.path())
+                .args(args)
+                .output()
+                .await
+                .unwrap();
+            assert!(output.status.success());
+        }
+
+        assert_eq!(
+            repo.check_for_pushed_commit().await.unwrap(),
+            [PushedBranch {
+                name: "team/origin/main".into(),
+                remote_name: "team/origin".into(),
+                remote_url: Some("https://example.com/repo.git".into()),
+                tip_sha: head_sha.into(),
+                tip_subject: "Pushed commit".into(),
+            }]
+        );
+
+        commit("Local commit", cx).await;
+        assert!(repo.check_for_pushed_commit().await.unwrap().is_empty());
+    }
+
+    #[gpui::test]
+    async fn test_format_and_apply_patch(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+
+        let repo_dir = tempfile::tempdir().unwrap();
+        git2::Repository::init(repo_dir.path()).unwrap();
+        let repo = RealGitRepository::new(
+            &repo_dir.path().join(".git"),
+            None,
+            Some("git".into()),
+            cx.executor(),
+        )
+        .unwrap();
+        let env = Arc::new(checkpoint_author_envs());
+        for (content, message) in [("base\n", "Add file"), ("changed\n", "Change file")] {
+            smol::fs::write(repo_dir.path().join("file"), content)
+                .await
+                .unwrap();
+            repo.stage_paths(vec![repo_path("file")], env.clone())
+                .await
+                .unwrap();
+            repo.commit(
+                message.into(),
+                None,
+                CommitOptions::default(),
+                test_askpass(cx),
+                env.clone(),
+                cx.to_async(),
+            )
+            .await
+            .unwrap();
+        }
+
+        let patch = repo.format_patch("HEAD~1".into()).await.unwrap();
+        assert!(patch.contains("Subject: [PATCH] Change file"), "{patch}");
+        assert!(patch.contains("-base\n+changed\n"), "{patch}");
+
+        repo.reset("HEAD~1".into(), ResetMode::Mixed, env.clone())
+            .await
+            .unwrap();
+        repo.checkout_files("HEAD".into(), vec![repo_path("file")], env.clone())
+            .await
+            .unwrap();
+        repo.apply_patch(
+            patch.clone(),
+            ApplyPatchOptions {
+                index: true,
+                ..Default::default()
+            },
+            env.clone(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            smol::fs::read_to_string(repo_dir.path().join("file"))
+                .await
+                .unwrap(),
+            "changed\n"
+        );
+        assert_eq!(
+            repo.load_index_text(repo_path("file")).await.as_deref(),
+            Some("changed\n")
+        );
+
+        // The patch no longer applies once its changes are in.
+        assert!(
+            repo.apply_patch(patch, ApplyPatchOptions::default(), env)
+                .await
+                .is_err()
+        );
+    }
+
+    #[gpui::test]
+    async fn test_maintenance_and_health(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+
+        let repo_dir = tempfile::tempdir().unwrap();
+        git2::Repository::init(repo_dir.path()).unwrap();
+        let repo = RealGitRepository::new(
+            &repo_dir.path().join(".git"),
+            None,
+            Some("git".into()),
+            cx.executor(),
+        )
+        .unwrap();
+        let env = Arc::new(checkpoint_author_envs());
+        smol::fs::write(repo_dir.path().join("file"), "content")
+            .await
+            .unwrap();
+        repo.stage_paths(vec![repo_path("file")], env.clone())
+            .await
+            .unwrap();
+        repo.commit(
+            "Initial commit".into(),
+            None,
+            CommitOptions::default(),
+            test_askpass(cx),
+            env.clone(),
+            cx.to_async(),
+        )
+        .await
+        .unwrap();
+
+        // A blob, a tree and a commit.
+        let health = repo.health().await.unwrap();
+        assert_eq!(health.loose_objects, 3);
+        assert!(health.loose_objects_size > 0);
+        assert_eq!(health.packs, 0);
+
+        repo.gc(false, env.clone()).await.unwrap();
+        let health = repo.health().await.unwrap();
+        assert_eq!(health.loose_objects, 0);
+        assert_eq!(health.packs, 1);
+        assert!(health.packs_size > 0);
+
+        let (progress_tx, progress_rx) = mpsc::unbounded();
+        repo.maintenance(MaintenanceCommand::Run, progress_tx, env)
+            .await
+            .unwrap();
+        let progress = progress_rx.collect::<Vec<_>>().await;
+        assert_eq!(
+            progress
+                .iter()
+                .map(|progress| (progress.phase.as_str(), progress.percent))
+                .collect::<Vec<_>>(),
+            [
+                ("Running commit-graph", Some(0)),
+                ("Running loose-objects", Some(25)),
+                ("Running incremental-repack", Some(50)),
+                ("Running pack-refs", Some(75)),
+            ]
+        );
+    }
+
+    #[gpui::test]
+    async fn test_index_locked(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+
+        let repo_dir = tempfile::tempdir().unwrap();
+        git2::Repository::init(repo_dir.path()).unwrap();
+        let repo = RealGitRepository::new(
+            &repo_dir.path().join(".git"),
+            None,
+            Some("git".into()),
+            cx.executor(),
+        )
+        .unwrap();
+        // The lock is still recognized when the user's locale translates git's messages.
+        let mut env = checkpoint_author_envs();
+        env.insert("LC_ALL".into(), "de_DE.UTF-8".into());
+        env.insert("LANGUAGE".into(), "de".into());
+        let env = Arc::new(env);
+        smol::fs::write(repo_dir.path().join("file"), "content")
+            .await
+            .unwrap();
+
+        // Another git process holds the lock for longer than staging retries for. The clock is
+        // moved past each retry's delay while git runs.
+        let lock_path = repo_dir.path().join(".git/index.lock");
+        smol::fs::write(&lock_path, "").await.unwrap();
+        let stage = repo.stage_paths(vec![repo_path("file")], env.clone());
+        let advance_clock = async {
+            loop {
+                cx.executor().advance_clock(INDEX_LOCK_RETRY_DELAY * 8);
+                smol::future::yield_now().await;
+            }
+        };
+        let result = match futures::future::select(stage, std::pin::pin!(advance_clock)).await {
+            futures::future::Either::Left((result, _)) => result,
+            futures::future::Either::Right(_) => unreachable!(),
+        };
+        let error = result.unwrap_err();
+        assert!(IndexLocked::is_cause_of(&error), "{error:?}");
+        let index_locked = error.downcast_ref::<IndexLocked>().unwrap();
+        assert!(index_locked.lock_path.ends_with(".git/index.lock"));
+
+        // Guests only receive the host's error message.
+        assert!(IndexLocked::is_cause_of(&anyhow!("{error}")));
+        assert!(!IndexLocked::is_cause_of(&anyhow!("Failed to stage paths")));
+
+        smol::fs::remove_file(&lock_path).await.unwrap();
+        repo.stage_paths(vec![repo_path("file")], env)
+            .await
+            .unwrap();
+    }
+
+    #[gpui::test]
+    async fn test_resolve_conflict(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+
+        let repo_dir = tempfile::tempdir().unwrap();
+        git2::Repository::init(repo_dir.path()).unwrap();
+        let repo = RealGitRepository::new(
+            &repo_dir.path().join(".git"),
+            None,
+            Some("git".into()),
+            cx.executor(),
+        )
+        .unwrap();
+        let env = Arc::new(checkpoint_author_envs());
+        let commit_files = async |files: &[(&str, Option<&str>)], cx: &mut TestAppContext| {
+            for (file, content) in files {
+                let path = repo_dir.path().join(file);
+                match content {
+                    Some(content) => smol::fs::write(path, content).await.unwrap(),
+                    None => smol::fs::remove_file(path).await.unwrap(),
+                }
+            }
+            repo.stage_paths(
+                files.iter().map(|(file, _)| repo_path(file)).collect(),
+                env.clone(),
+            )
+            .await
+            .unwrap();
+            repo.commit(
+                "commit".into(),
+                None,
+                CommitOptions::default(),
+                test_askpass(cx),
+                env.clone(),
+                cx.to_async(),
+            )
+            .await
+            .unwrap();
+        };
+
+        commit_files(&[("a", Some("base")), ("b", Some("base"))], cx).await;
+        let branches = repo.branches().await.unwrap();
+        let main_branch = branches
+            .iter()
+            .find(|branch| branch.is_head)
+            .unwrap()
+            .name()
+            .to_string();
+        repo.create_branch("feature".into()).await.unwrap();
+        repo.change_branch("feature".into()).await.unwrap();
+        commit_files(&[("a", Some("feature")), ("b", None)], cx).await;
+        repo.change_branch(main_branch).await.unwrap();
+        commit_files(&[("a", Some("main")), ("b", Some("main"))], cx).await;
+        repo.merge("feature".into(), MergeOptions::default(), env.clone())
+            .await
+            .unwrap();
+
+        repo.resolve_conflict(repo_path("a"), ConflictSide::Theirs, env.clone())
+            .await
+            .unwrap();
+        assert_eq!(
+            smol::fs::read_to_string(repo_dir.path().join("a"))
+                .await
+                .unwrap(),
+            "feature"
+        );
+        // Taking the side that deleted the file deletes it.
+        repo.resolve_conflict(repo_path("b"), ConflictSide::Theirs, env.clone())
+            .await
+            .unwrap();
+        assert!(!repo_dir.path().join("b").exists());
+
+        let status = repo.status(&[RelPath::empty().into()]).await.unwrap();
+        assert!(
+            status
+                .entries
+                .iter()
+                .all(|(_, status)| !status.is_conflicted())
+        );
+        assert!(
+            repo.resolve_conflict(repo_path("a"), ConflictSide::Ours, env)
+                .await
+                .is_err()
+        );
+    }
+
+    #[gpui::test]
+    async fn test_diff_against_revisions(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+
+        let repo_dir = tempfile::tempdir().unwrap();
+        git2::Repository::init(repo_dir.path()).unwrap();
+        let repo = RealGitRepository::new(
+            &repo_dir.path().join(".git"),
+            None,
+            Some("git".into()),
+            cx.executor(),
+        )
+        .unwrap();
+        let env = Arc::new(checkpoint_author_envs());
+        for content in ["one\n", "two\n"] {
+            smol::fs::write(repo_dir.path().join("file"), content)
+                .await
+                .unwrap();
+            repo.stage_paths(vec![repo_path("file")], env.clone())
+                .await
+                .unwrap();
+            repo.commit(
+                content.into(),
+                None,
+                CommitOptions::default(),
+                test_askpass(cx),
+                env.clone(),
+                cx.to_async(),
+            )
+            .await
+            .unwrap();
+        }
+        smol::fs::write(repo_dir.path().join("file"), "three\n")
+            .await
+            .unwrap();
+
+        let diff = repo
+            .diff(DiffType::Commits {
+                base: "HEAD~1".into(),
+                head: "HEAD".into(),
+            })
+            .await
+            .unwrap();
+        assert!(diff.contains("-one\n+two\n"), "{diff}");
+
+        let diff = repo
+            .diff_file(repo_path("file"), "HEAD~1".into())
+            .await
+            .unwrap();
+        assert!(diff.contains("-one\n+three\n"), "{diff}");
+
+        // Revisions aren't taken as options.
+        assert!(
+            repo.diff_file(repo_path("file"), "--output=out".into())
+                .await
+                .is_err()
+        );
+        assert!(!repo_dir.path().join("out").exists());
+    }
+
+    #[gpui::test]
+    async fn test_load_blob(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+
+        let repo_dir = tempfile::tempdir().unwrap();
+        git2::Repository::init(repo_dir.path()).unwrap();
+        let repo = RealGitRepository::new(
+            &repo_dir.path().join(".git"),
+            None,
+            Some("git".into()),
+            cx.executor(),
+        )
+        .unwrap();
+        let env = Arc::new(checkpoint_author_envs());
+        for content in ["one", "two"] {
+            smol::fs::write(repo_dir.path().join("file"), content)
+                .await
+                .unwrap();
+            repo.stage_paths(vec![repo_path("file")], env.clone())
+                .await
+                .unwrap();
+            repo.commit(
+                content.into(),
+                None,
+                CommitOptions::default(),
+                test_askpass(cx),
+                env.clone(),
+                cx.to_async(),
+            )
+            .await
+            .unwrap();
+        }
+
+        assert_eq!(
+            repo.load_blob("HEAD~1".into(), repo_path("file"))
+                .await
+                .unwrap(),
+            "one"
+        );
+        assert_eq!(
+            repo.load_blob("HEAD".into(), repo_path("file"))
+                .await
+                .unwrap(),
+            "two"
+        );
+        assert!(
+            repo.load_blob("HEAD".into(), repo_path("missing"))
+                .await
+                .is_err()
+        );
+        assert!(
+            repo.load_blob("no-such-branch".into(), repo_path("file"))
+                .await
+                .is_err()
+        );
+    }
+
+    #[gpui::test]
+    async fn test_delete_branch_and_set_upstream(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+
+        let repo_dir = tempfile::tempdir().unwrap();
+        git2::Repository::init(repo_dir.path()).unwrap();
+        let repo = RealGitRepository::new(
+            &repo_dir.path().join(".git"),
+            None,
+            Some("git".into()),
+            cx.executor(),
+        )
+        .unwrap();
+        let env = Arc::new(checkpoint_author_envs());
+        let commit_file = async |content: &str, cx: &mut TestAppContext| {
+            smol::fs::write(repo_dir.path().join("file"), content)
+                .await
+                .unwrap();
+            repo.stage_paths(vec![repo_path("file")], env.clone())
+                .await
+                .unwrap();
+            repo.commit(
+                content.to_string().into(),
+                None,
+                CommitOptions::default(),
+                test_askpass(cx),
+                env.clone(),
+                cx.to_async(),
+            )
+            .await
+            .unwrap();
+        };
+
+        commit_file("base", cx).await;
+        let branches = repo.branches().await.unwrap();
+        let main_branch = branches
+            .iter()
+            .find(|branch| branch.is_head)
+            .unwrap()
+            .name()
+            .to_string();
+
+        // Upstreams are remote-tracking branches, which need a remote to belong to.
+        for args in [
+            vec!["remote", "add", "origin", "https://example.com/repo.git"],
+            vec!["update-ref", "refs/remotes/origin/main", "HEAD"],
+        ] {
+            let output = new_smol_command("git")
+                .current_dir(repo_dir.path())
+                .args(args)
+                .output()
+                .await
+                .unwrap();
+            assert!(output.status.success());
+        }
+        repo.set_upstream(main_branch.clone(), "origin/main".into())
+            .await
+            .unwrap();
+        let branches = repo.branches().await.unwrap();
+        let head = branches.iter().find(|branch| branch.is_head).unwrap();
+        assert_eq!(
+            head.upstream
+                .as_ref()
+                .map(|upstream| upstream.ref_name.as_ref()),
+            Some("refs/remotes/origin/main")
+        );
+        assert!(
+            repo.set_upstream(main_branch.clone(), "origin/missing".into())
+                .await
+                .is_err()
+        );
+
+        repo.create_branch("feature".into()).await.unwrap();
+        repo.change_branch("feature".into()).await.unwrap();
+        commit_file("feature", cx).await;
+        repo.change_branch(main_branch).await.unwrap();
+
+        // The unmerged branch is only deleted when forced.
+        assert!(repo.delete_branch("feature".into(), false).await.is_err());
+        repo.delete_branch("feature".into(), true).await.unwrap();
+        let branches = repo.branches().await.unwrap();
+        assert!(!branches.iter().any(|branch| branch.name() == "feature"));
+    }
+
+    #[test]
+    fn test_append_ignore_patterns() {
+        assert_eq!(
+            append_ignore_patterns("target\n", &[repo_path("a.txt"), repo_path("b/c*.txt")])
+                .as_deref(),
+            Some("target\n/a.txt\n/b/c\\*.txt\n")
+        );
+        assert_eq!(
+            append_ignore_patterns("target", &[repo_path("a.txt")]).as_deref(),
+            Some("target\n/a.txt\n")
+        );
+        assert_eq!(
+            append_ignore_patterns("", &[repo_path("a.txt"), repo_path("a.txt")]).as_deref(),
+            Some("/a.txt\n")
+        );
+        assert_eq!(
+            append_ignore_patterns("/a.txt\n", &[repo_path("a.txt")]),
+            None
+        );
+    }
+
+    #[gpui::test]
+    async fn test_ignore_paths(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+
+        let repo_dir = tempfile::tempdir().unwrap();
+        git2::Repository::init(repo_dir.path()).unwrap();
+        let repo = RealGitRepository::new(
+            &repo_dir.path().join(".git"),
+            None,
+            Some("git".into()),
+            cx.executor(),
+        )
+        .unwrap();
+        for file in ["a.txt", "b.txt", "sub/a.txt"] {
+            let path = repo_dir.path().join(file);
+            smol::fs::create_dir_all(path.parent().unwrap())
+                .await
+                .unwrap();
+            smol::fs::write(path, "content").await.unwrap();
+        }
+
+        repo.ignore_paths(vec![repo_path("a.txt")], IgnoreFile::Gitignore)
+            .await
+            .unwrap();
+        repo.ignore_paths(vec![repo_path("b.txt")], IgnoreFile::InfoExclude)
+            .await
+            .unwrap();
+        assert_eq!(
+            smol::fs::read_to_string(repo_dir.path().join(".gitignore"))
+                .await
+                .unwrap(),
+            "/a.txt\n"
+        );
+        assert!(repo.is_ignored(repo_path("a.txt")).await.unwrap());
+        assert!(repo.is_ignored(repo_path("b.txt")).await.unwrap());
+        // Patterns are anchored to the root of the repository.
+        assert!(!repo.is_ignored(repo_path("sub/a.txt")).await.unwrap());
+    }
+
+    #[gpui::test]
+    async fn test_clean(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+
+        let repo_dir = tempfile::tempdir().unwrap();
+        git2::Repository::init(repo_dir.path()).unwrap();
+        let repo = RealGitRepository::new(
+            &repo_dir.path().join(".git"),
+            None,
+            Some("git".into()),
+            cx.executor(),
+        )
+        .unwrap();
+        for (file, content) in [
+            (".gitignore", "*.log"),
+            ("tracked.txt", "tracked"),
+            ("untracked.txt", "untracked"),
+            ("ignored.log", "ignored"),
+            ("new_dir/file.txt", "untracked"),
+        ] {
+            let path = repo_dir.path().join(file);
+            smol::fs::create_dir_all(path.parent().unwrap())
+                .await
+                .unwrap();
+            smol::fs::write(path, content).await.unwrap();
+        }
+        // The removed paths are still found when the user's locale translates git's messages.
+        let env = Arc::new(HashMap::from_iter([
+            ("LC_ALL".to_string(), "de_DE.UTF-8".to_string()),
+            ("LANGUAGE".to_string(), "de".to_string()),
+        ]));
+        repo.stage_paths(
+            vec![repo_path(".gitignore"), repo_path("tracked.txt")],
+            env.clone(),
+        )
+        .await
+        .unwrap();
+
+        let dry_run = CleanOptions {
+            dry_run: true,
+            ..CleanOptions::default()
+        };
+        // Untracked directories are skipped unless they're asked for.
+        assert_eq!(
+            repo.clean(Vec::new(), dry_run, env.clone()).await.unwrap(),
+            [repo_path("untracked.txt")]
+        );
+        assert_eq!(
+            repo.clean(
+                Vec::new(),
+                CleanOptions {
+                    directories: true,
+                    ..dry_run
+                },
+                env.clone()
+            )
+            .await
+            .unwrap(),
+            [repo_path("new_dir"), repo_path("untracked.txt")]
+        );
+        assert!(repo_dir.path().join("untracked.txt").exists());
+
+        assert_eq!(
+            repo.clean(
+                vec![repo_path("new_dir")],
+                CleanOptions {
+                    directories: true,
+                    ..CleanOptions::default()
+                },
+                env.clone()
+            )
+            .await
+            .unwrap(),
+            [repo_path("new_dir")]
+        );
+        assert!(!repo_dir.path().join("new_dir").exists());
+        assert!(repo_dir.path().join("untracked.txt").exists());
+
+        assert_eq!(
+            repo.clean(
+                Vec::new(),
+                CleanOptions {
+                    ignored: true,
+                    ..CleanOptions::default()
+                },
+                env.clone()
+            )
+            .await
+            .unwrap(),
+            [repo_path("ignored.log"), repo_path("untracked.txt")]
+        );
+        assert!(!repo_dir.path().join("ignored.log").exists());
+        assert!(repo_dir.path().join("tracked.txt").exists());
+    }
+
+    #[gpui::test]
+    async fn test_lfs_statuses(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+
+        let repo_dir = tempfile::tempdir().unwrap();
+        git2::Repository::init(repo_dir.path()).unwrap();
+        let repo = RealGitRepository::new(
+            &repo_dir.path().join(".git"),
+            None,
+            Some("git".into()),
+            cx.executor(),
+        )
+        .unwrap();
+        let pointer = "version https://git-lfs.github.com/spec/v1\noid sha256:4d7a214614ab2935c943f9e0ff69d22eadbb8f32b1258daaa5e2ca24d17e2393\nsize 12345\n";
+        for (file, content) in [
+            (
+                ".gitattributes",
+                "*.bin filter=lfs diff=lfs merge=lfs -text\n",
+            ),
+            ("pointer.bin", pointer),
+            ("downloaded.bin", "binary content"),
+            ("text.txt", "text"),
+        ] {
+            smol::fs::write(repo_dir.path().join(file), content)
+                .await
+                .unwrap();
+        }
+
+        let statuses = repo
+            .lfs_statuses(vec![
+                repo_path("pointer.bin"),
+                repo_path("downloaded.bin"),
+                repo_path("deleted.bin"),
+                repo_path("text.txt"),
+            ])
+            .await
+            .unwrap();
+        assert_eq!(statuses.len(), 3);
+        assert!(statuses[&repo_path("pointer.bin")].is_pointer);
+        assert!(!statuses[&repo_path("downloaded.bin")].is_pointer);
+        assert!(!statuses[&repo_path("deleted.bin")].is_pointer);
+        assert!(!statuses.contains_key(&repo_path("text.txt")));
+    }
+
+    #[test]
+    fn test_commit_signing_format() {
+        let repo_dir = tempfile::tempdir().unwrap();
+        let repo = git2::Repository::init(repo_dir.path()).unwrap();
+        // Only read the repository's own config, so that the user's doesn't affect the result.
+        let mut config = repo
+            .config()
+            .unwrap()
+            .open_level(git2::ConfigLevel::Local)
+            .unwrap();
+
+        assert_eq!(commit_signing_format(&config, false).unwrap(), None);
+        assert_eq!(
+            commit_signing_format(&config, true).unwrap(),
+            Some(SigningFormat::OpenPgp)
+        );
+
+        config.set_bool("commit.gpgsign", true).unwrap();
+        config.set_str("gpg.format", "x509").unwrap();
+        assert_eq!(
+            commit_signing_format(&config, false).unwrap(),
+            Some(SigningFormat::X509)
+        );
+
+        config.set_str("gpg.format", "ssh").unwrap();
+        assert!(commit_signing_format(&config, false).is_err());
+        config
+            .set_str("user.signingkey", "~/.ssh/id_ed25519.pub")
+            .unwrap();
+        assert_eq!(
+            commit_signing_format(&config, false).unwrap(),
+            Some(SigningFormat::Ssh)
+        );
+
+        config.set_str("gpg.format", "smime").unwrap();
+        assert!(commit_signing_format(&config, false).is_err());
+    }
+
+    #[gpui::test]
+    async fn test_hard_reset(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+
+        let repo_dir = tempfile::tempdir().unwrap();
+        git2::Repository::init(repo_dir.path()).unwrap();
+        let repo = RealGitRepository::new(
+            &repo_dir.path().join(".git"),
+            None,
+            Some("git".into()),
+            cx.executor(),
+        )
+        .unwrap();
+        let file_path = repo_dir.path().join("file");
+        smol::fs::write(&file_path, "committed").await.unwrap();
+        repo.stage_paths(vec![repo_path("file")], Arc::new(HashMap::default()))
+            .await
+            .unwrap();
+        repo.commit(
+            "Initial commit".into(),
+            None,
+            CommitOptions::default(),
+            test_askpass(cx),
+            Arc::new(checkpoint_author_envs()),
+            cx.to_async(),
+        )
+        .await
+        .unwrap();
+
+        smol::fs::write(&file_path, "modified").await.unwrap();
+        smol::fs::write(repo_dir.path().join("untracked"), "untracked")
+            .await
+            .unwrap();
+        repo.reset("HEAD".into(), ResetMode::Hard, Arc::new(HashMap::default()))
+            .await
+            .unwrap();
+        assert_eq!(
+            smol::fs::read_to_string(&file_path).await.unwrap(),
+            "committed"
+        );
+        assert_eq!(
+            smol::fs::read_to_string(repo_dir.path().join("untracked"))
+                .await
+                .unwrap(),
+            "untracked"
+        );
+    }
+
+    #[gpui::test]
+    async fn test_checkpoint_basic(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+
+        let repo_dir = tempfile::tempdir().unwrap();
+
+        git2::Repository::init(repo_dir.path()).unwrap();
+        let file_path = repo_dir.path().join("file");
+        smol::fs::write(&file_path, "initial").await.unwrap();
+
+        let repo = RealGitRepository::new(
+            &repo_dir.path().join(".git"),
+            None,
+            Some("git".into()),
+            cx.executor(),
+        )
+        .unwrap();
+        repo.stage_paths(vec![repo_path("file")], Arc::new(HashMap::default()))
+            .await
+            .unwrap();
+        repo.commit(
+            "Initial commit".into(),
+            None,
+            CommitOptions::default(),
+            test_askpass(cx),
+            Arc::new(checkpoint_author_envs()),
+            cx.to_async(),
+        )
+        .await
+        .unwrap();
+
+        smol::fs::write(&file_path, "modified before checkpoint")
+            .await
+            .unwrap();
+        smol::fs::write(repo_dir.path().join("new_file_before_checkpoint"), "1")
+            .await
+            .unwrap();
+        let checkpoint = repo.checkpoint().await.unwrap();
+
+        // Ensure the user can't see any branches after creating a checkpoint.
+        assert_eq!(repo.branches().await.unwrap().len(), 1);
+
+        smol::fs::write(&file_path, "modified after checkpoint")
+            .await
+            .unwrap();
+        repo.stage_paths(vec![repo_path("file")], Arc::new(HashMap::default()))
+            .await
+            .unwrap();
+        repo.commit(
+            "Commit after checkpoint".into(),
+            None,
+            CommitOptions::default(),
+            test_askpass(cx),
+            Arc::new(checkpoint_author_envs()),
+            cx.to_async(),
+        )
+        .await
+        .unwrap();
+
+        smol::fs::remove_file(repo_dir.path().join("new_file_before_checkpoint"))
+            .await
+            .unwrap();
+        smol::fs::write(repo_dir.path().join("new_file_after_checkpoint"), "2")
+            .await
+            .unwrap();
+
+        // Ensure checkpoint stays alive even after a Git GC.
+        repo.gc().await.unwrap();
+        repo.restore_checkpoint(checkpoint.clone()).await.unwrap();
+
+        assert_eq!(
+            smol::fs::read_to_string(&file_path).await.unwrap(),
+            "modified before checkpoint"
+        );
+        assert_eq!(
+            smol::fs::read_to_string(repo_dir.path().join("new_file_before_checkpoint"))
+                .await
+                .unwrap(),
+            "1"
+        );
+        // See TODO above
+        // assert_eq!(
+        //     smol::fs::read_to_string(repo_dir.path().join("new_file_after_checkpoint"))
+        //         .await
+        //         .ok(),
+        //     None
+        // );
+    }
+
+    #[gpui::test]
+    async fn test_checkpoint_empty_repo(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+
+        let repo_dir = tempfile::tempdir().unwrap();
+        git2::Repository::init(repo_dir.path()).unwrap();
+        let repo = RealGitRepository::new(
+            &repo_dir.path().join(".git"),
+            None,
+            Some("git".into()),
+            cx.executor(),
+        )
+        .unwrap();
+
+        smol::fs::write(repo_dir.path().join("foo"), "foo")
+            .await
+            .unwrap();
+        let checkpoint_sha = repo.checkpoint().await.unwrap();
+
+        // Ensure the user can't see any branches after creating a checkpoint.
+        assert_eq!(repo.branches().await.unwrap().len(), 1);
+
+        smol::fs::write(repo_dir.path().join("foo"), "bar")
             .await
             .unwrap();
         smol::fs::write(repo_dir.path().join("baz"), "qux")
@@ -2467,7 +6288,9 @@ mod tests {
             "Initial commit".into(),
             None,
             CommitOptions::default(),
+            test_askpass(cx),
             Arc::new(checkpoint_author_envs()),
+            cx.to_async(),
         )
         .await
         .unwrap();
@@ -2524,6 +6347,10 @@ mod tests {
         )
     }
 
+    fn test_askpass(cx: &mut TestAppContext) -> AskPassDelegate {
+        AskPassDelegate::new(&mut cx.to_async(), |_, _, _| {})
+    }
+
     impl RealGitRepository {
         /// Force a Git garbage collection on the repository.
         fn gc(&self) -> BoxFuture<'_, Result<()>> {
@@ -107,6 +107,21 @@ impl GitHostingProvider for Bitbucket {
         permalink
     }
 
+    fn build_create_pull_request_url(
+        &self,
+        remote: &ParsedGitRemote,
+        source_branch: &str,
+    ) -> Option<Url> {
+        let ParsedGitRemote { owner, repo } = remote;
+
+        let mut url = self
+            .base_url()
+            .join(&format!("{owner}/{repo}/pull-requests/new"))
+            .ok()?;
+        url.query_pairs_mut().append_pair("source", source_branch);
+        Some(url)
+    }
+
     fn extract_pull_request(&self, remote: &ParsedGitRemote, message: &str) -> Option<PullRequest> {
         // Check first line of commit message for PR references
         let first_line = message.lines().next()?;
@@ -176,6 +191,21 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_build_bitbucket_create_pull_request_url() {
+        let url = Bitbucket::public_instance().build_create_pull_request_url(
+            &ParsedGitRemote {
+                owner: "zed-industries".into(),
+                repo: "zed".into(),
+            },
+            "feature/x",
+        );
+
+        let expected_url =
+            "https://bitbucket.org/zed-industries/zed/pull-requests/new?source=feature%2Fx";
+        assert_eq!(url.unwrap().to_string(), expected_url.to_string())
+    }
+
     #[test]
     fn test_build_bitbucket_permalink() {
         let permalink = Bitbucket::public_instance().build_permalink(
@@ -185,6 +185,25 @@ impl GitHostingProvider for Codeberg {
         permalink
     }
 
+    fn build_create_pull_request_url(
+        &self,
+        remote: &ParsedGitRemote,
+        source_branch: &str,
+    ) -> Option<Url> {
+        let ParsedGitRemote { owner, repo } = remote;
+
+        // Without a base branch, the source branch is compared with the default branch.
+        let mut url = self
+            .base_url()
+            .join(&format!("{owner}/{repo}/compare/"))
+            .ok()?;
+        url.path_segments_mut()
+            .ok()?
+            .pop_if_empty()
+            .extend(source_branch.split('/'));
+        Some(url)
+    }
+
     async fn commit_author_avatar_url(
         &self,
         repo_owner: &str,
@@ -239,6 +258,20 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_build_codeberg_create_pull_request_url() {
+        let url = Codeberg.build_create_pull_request_url(
+            &ParsedGitRemote {
+                owner: "zed-industries".into(),
+                repo: "zed".into(),
+            },
+            "feature/x",
+        );
+
+        let expected_url = "https://codeberg.org/zed-industries/zed/compare/feature/x";
+        assert_eq!(url.unwrap().to_string(), expected_url.to_string())
+    }
+
     #[test]
     fn test_build_codeberg_permalink() {
         let permalink = Codeberg.build_permalink(
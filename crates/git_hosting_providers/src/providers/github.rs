@@ -224,6 +224,25 @@ impl GitHostingProvider for Github {
         permalink
     }
 
+    fn build_create_pull_request_url(
+        &self,
+        remote: &ParsedGitRemote,
+        source_branch: &str,
+    ) -> Option<Url> {
+        let ParsedGitRemote { owner, repo } = remote;
+
+        let mut url = self
+            .base_url()
+            .join(&format!("{owner}/{repo}/compare/"))
+            .ok()?;
+        url.path_segments_mut()
+            .ok()?
+            .pop_if_empty()
+            .extend(source_branch.split('/'));
+        url.set_query(Some("expand=1"));
+        Some(url)
+    }
+
     fn extract_pull_request(&self, remote: &ParsedGitRemote, message: &str) -> Option<PullRequest> {
         let line = message.lines().next()?;
         let capture = pull_request_number_regex().captures(line)?;
@@ -412,6 +431,20 @@ mod tests {
         assert_eq!(permalink.to_string(), expected_url.to_string())
     }
 
+    #[test]
+    fn test_build_github_create_pull_request_url() {
+        let url = Github::public_instance().build_create_pull_request_url(
+            &ParsedGitRemote {
+                owner: "zed-industries".into(),
+                repo: "zed".into(),
+            },
+            "user/fix-#123",
+        );
+
+        let expected_url = "https://github.com/zed-industries/zed/compare/user/fix-%23123?expand=1";
+        assert_eq!(url.unwrap().to_string(), expected_url.to_string())
+    }
+
     #[test]
     fn test_build_github_permalink() {
         let permalink = Github::public_instance().build_permalink(
@@ -122,6 +122,22 @@ impl GitHostingProvider for Gitlab {
         );
         permalink
     }
+
+    fn build_create_pull_request_url(
+        &self,
+        remote: &ParsedGitRemote,
+        source_branch: &str,
+    ) -> Option<Url> {
+        let ParsedGitRemote { owner, repo } = remote;
+
+        let mut url = self
+            .base_url()
+            .join(&format!("{owner}/{repo}/-/merge_requests/new"))
+            .ok()?;
+        url.query_pairs_mut()
+            .append_pair("merge_request[source_branch]", source_branch);
+        Some(url)
+    }
 }
 
 #[cfg(test)]
@@ -203,6 +219,20 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_build_gitlab_create_pull_request_url() {
+        let url = Gitlab::public_instance().build_create_pull_request_url(
+            &ParsedGitRemote {
+                owner: "group/subgroup".into(),
+                repo: "zed".into(),
+            },
+            "feature/x",
+        );
+
+        let expected_url = "https://gitlab.com/group/subgroup/zed/-/merge_requests/new?merge_request%5Bsource_branch%5D=feature%2Fx";
+        assert_eq!(url.unwrap().to_string(), expected_url.to_string())
+    }
+
     #[test]
     fn test_build_gitlab_permalink() {
         let permalink = Gitlab::public_instance().build_permalink(
@@ -435,6 +435,7 @@ impl CommitModal {
                                     CommitOptions {
                                         amend: is_amend_pending,
                                         signoff: is_signoff_enabled,
+                                        sign: false,
                                     },
                                     window,
                                     cx,
@@ -495,6 +496,7 @@ impl CommitModal {
                 CommitOptions {
                     amend: false,
                     signoff: git_panel.signoff_enabled(),
+                    sign: false,
                 },
                 window,
                 cx,
@@ -526,6 +528,7 @@ impl CommitModal {
                     CommitOptions {
                         amend: true,
                         signoff: git_panel.signoff_enabled(),
+                        sign: false,
                     },
                     window,
                     cx,
@@ -13,9 +13,12 @@ use askpass::AskPassDelegate;
 use db::kvp::KEY_VALUE_STORE;
 use editor::{Editor, EditorElement, EditorMode, MultiBuffer};
 use git::blame::ParsedCommitMessage;
+use git::commit::Trailer;
+use git::lfs::LfsFileStatus;
 use git::repository::{
-    Branch, CommitDetails, CommitOptions, CommitSummary, FetchOptions, GitCommitter, PushOptions,
-    Remote, RemoteCommandOutput, ResetMode, Upstream, UpstreamTracking, UpstreamTrackingStatus,
+    Branch, CommitDetails, CommitHookError, CommitOptions, CommitSummary, ConflictSide,
+    FetchOptions, GitCommitter, PullOptions, PushOptions, PushedBranch, Remote,
+    RemoteCommandOutput, ResetMode, Upstream, UpstreamTracking, UpstreamTrackingStatus,
     get_git_committer,
 };
 use git::stash::GitStash;
@@ -48,8 +51,8 @@ use serde::{Deserialize, Serialize};
 use settings::{Settings, SettingsStore, StatusStyle};
 use std::future::Future;
 use std::ops::Range;
-use std::path::Path;
-use std::{collections::HashSet, sync::Arc, time::Duration, usize};
+use std::path::{Path, PathBuf};
+use std::{sync::Arc, time::Duration, usize};
 use strum::{IntoEnumIterator, VariantNames};
 use time::OffsetDateTime;
 use ui::{
@@ -193,6 +196,9 @@ struct SerializedGitPanel {
     amend_pending: bool,
     #[serde(default)]
     signoff_enabled: bool,
+    /// The work directory of the active repository.
+    #[serde(default)]
+    active_repository: Option<PathBuf>,
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
@@ -358,6 +364,42 @@ pub(crate) fn commit_message_editor(
     commit_editor
 }
 
+fn render_lfs_badge(ix: usize, lfs_status: LfsFileStatus) -> impl IntoElement {
+    let mut tooltip = String::from("Stored by Git LFS");
+    if lfs_status.is_pointer {
+        // Pointers show up as modified, which would otherwise be confusing.
+        tooltip.push_str(", but only its pointer was downloaded");
+    }
+    if let Some(lock) = &lfs_status.lock {
+        match &lock.owner {
+            Some(owner) => tooltip.push_str(&format!("\nLocked by {owner}")),
+            None => tooltip.push_str("\nLocked"),
+        }
+    }
+
+    h_flex()
+        .id(("lfs-badge", ix))
+        .flex_none()
+        .gap_0p5()
+        .when(lfs_status.lock.is_some(), |this| {
+            this.child(
+                Icon::new(IconName::LockOutlined)
+                    .size(IconSize::XSmall)
+                    .color(Color::Muted),
+            )
+        })
+        .child(
+            Label::new("LFS")
+                .size(LabelSize::XSmall)
+                .color(if lfs_status.is_pointer {
+                    Color::Warning
+                } else {
+                    Color::Muted
+                }),
+        )
+        .tooltip(Tooltip::text(tooltip))
+}
+
 impl GitPanel {
     fn new(
         workspace: &mut Workspace,
@@ -405,6 +447,7 @@ impl GitPanel {
                     GitStoreEvent::ActiveRepositoryChanged(_) => {
                         this.active_repository = this.project.read(cx).active_repository(cx);
                         this.schedule_update(true, window, cx);
+                        this.serialize(cx);
                     }
                     GitStoreEvent::RepositoryUpdated(
                         _,
@@ -424,8 +467,12 @@ impl GitPanel {
                             })
                             .ok();
                     }
+                    GitStoreEvent::IndexLocked(_) => this.show_index_locked_toast(cx),
                     GitStoreEvent::RepositoryUpdated(_, _, _) => {}
-                    GitStoreEvent::JobsUpdated | GitStoreEvent::ConflictsUpdated => {}
+                    GitStoreEvent::JobsUpdated
+                    | GitStoreEvent::ConflictsUpdated
+                    | GitStoreEvent::BatchOperationCompleted(_)
+                    | GitStoreEvent::SlowJob(_) => {}
                 },
             )
             .detach();
@@ -552,6 +599,10 @@ impl GitPanel {
         let width = self.width;
         let amend_pending = self.amend_pending;
         let signoff_enabled = self.signoff_enabled;
+        let active_repository = self
+            .active_repository
+            .as_ref()
+            .map(|repository| repository.read(cx).work_directory_abs_path.to_path_buf());
 
         self.pending_serialization = cx.spawn(async move |git_panel, cx| {
             cx.background_executor()
@@ -579,6 +630,7 @@ impl GitPanel {
                                 width,
                                 amend_pending,
                                 signoff_enabled,
+                                active_repository,
                             })?,
                         )
                         .await?;
@@ -922,6 +974,44 @@ impl GitPanel {
         });
     }
 
+    fn take_ours(&mut self, _: &git::TakeOurs, _window: &mut Window, cx: &mut Context<Self>) {
+        self.resolve_selected_conflict(ConflictSide::Ours, cx);
+    }
+
+    fn take_theirs(&mut self, _: &git::TakeTheirs, _window: &mut Window, cx: &mut Context<Self>) {
+        self.resolve_selected_conflict(ConflictSide::Theirs, cx);
+    }
+
+    fn resolve_selected_conflict(&mut self, side: ConflictSide, cx: &mut Context<Self>) {
+        let Some(active_repository) = self.active_repository.clone() else {
+            return;
+        };
+        let Some(entry) = self
+            .selected_entry
+            .and_then(|ix| self.entries.get(ix))
+            .and_then(|entry| entry.status_entry())
+            .filter(|entry| entry.status.is_conflicted())
+        else {
+            return;
+        };
+        let repo_path = entry.repo_path.clone();
+
+        cx.spawn(async move |this, cx| {
+            let result = active_repository
+                .update(cx, |repository, cx| {
+                    repository.resolve_conflict(repo_path, side, cx)
+                })?
+                .await?;
+            this.update(cx, |this, cx| {
+                if let Err(error) = result {
+                    this.show_error_toast("checkout", error, cx);
+                }
+                cx.notify();
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
     fn revert_entry(
         &mut self,
         entry: &GitStatusEntry,
@@ -1302,7 +1392,10 @@ impl GitPanel {
                             pending.finished = true
                         }
                     }
+                    // Paths that were staged are kept even when others failed, so only the failures
+                    // are reported.
                     result
+                        .and_then(|report| report.ensure_succeeded())
                         .map_err(|e| {
                             this.show_error_toast(if stage { "add" } else { "reset" }, e, cx);
                         })
@@ -1456,6 +1549,7 @@ impl GitPanel {
                 CommitOptions {
                     amend: false,
                     signoff: self.signoff_enabled,
+                    sign: false,
                 },
                 window,
                 cx,
@@ -1480,6 +1574,7 @@ impl GitPanel {
                         CommitOptions {
                             amend: true,
                             signoff: self.signoff_enabled,
+                            sign: false,
                         },
                         window,
                         cx,
@@ -1593,19 +1688,23 @@ impl GitPanel {
 
         let commit_message = self.custom_or_suggested_commit_message(window, cx);
 
-        let Some(mut message) = commit_message else {
+        let Some(message) = commit_message else {
             self.commit_editor.read(cx).focus_handle(cx).focus(window);
             return;
         };
 
-        if self.add_coauthors {
-            self.fill_co_authors(&mut message, cx);
-        }
+        let trailers = if self.add_coauthors {
+            self.co_author_trailers(cx)
+        } else {
+            Vec::new()
+        };
 
+        // Signing keys may be protected by a passphrase.
+        let askpass = self.askpass_delegate("git commit", window, cx);
         let task = if self.has_staged_changes() {
             // Repository serializes all git operations, so we can just send a commit immediately
             let commit_task = active_repository.update(cx, |repo, cx| {
-                repo.commit(message.into(), None, options, cx)
+                repo.commit(message.into(), None, options, trailers, askpass, cx)
             });
             cx.background_spawn(async move { commit_task.await? })
         } else {
@@ -1625,9 +1724,9 @@ impl GitPanel {
             let stage_task =
                 active_repository.update(cx, |repo, cx| repo.stage_entries(changed_files, cx));
             cx.spawn(async move |_, cx| {
-                stage_task.await?;
+                stage_task.await?.ensure_succeeded()?;
                 let commit_task = active_repository.update(cx, |repo, cx| {
-                    repo.commit(message.into(), None, options, cx)
+                    repo.commit(message.into(), None, options, trailers, askpass, cx)
                 })?;
                 commit_task.await?
             })
@@ -1642,7 +1741,13 @@ impl GitPanel {
                             .update(cx, |editor, cx| editor.clear(window, cx));
                         this.original_commit_message = None;
                     }
-                    Err(e) => this.show_error_toast("commit", e, cx),
+                    Err(e) => {
+                        let action = match e.downcast_ref::<CommitHookError>() {
+                            Some(hook_error) => format!("{} hook", hook_error.hook.name()),
+                            None => "commit".to_string(),
+                        };
+                        this.show_error_toast(action, e, cx)
+                    }
                 }
             })
             .ok();
@@ -1708,7 +1813,7 @@ impl GitPanel {
         async move {
             let repo = repo.context("No active repository")?;
 
-            let pushed_to: Vec<SharedString> = repo
+            let pushed_to: Vec<PushedBranch> = repo
                 .update(&mut cx, |repo, _| repo.check_for_pushed_commits())?
                 .await??;
 
@@ -1722,8 +1827,22 @@ impl GitPanel {
                     Cancel,
                 }
                 let detail = format!(
-                    "This commit was already pushed to {}.",
-                    pushed_to.into_iter().join(", ")
+                    "This commit was already pushed to:\n{}",
+                    pushed_to
+                        .iter()
+                        .map(|branch| {
+                            let location = match &branch.remote_url {
+                                Some(remote_url) => format!("{} ({remote_url})", branch.name),
+                                None => branch.name.to_string(),
+                            };
+                            let tip_sha = branch
+                                .tip_sha
+                                .chars()
+                                .take(git::SHORT_SHA_LENGTH)
+                                .collect::<String>();
+                            format!("{location}, at {tip_sha} \"{}\"", branch.tip_subject)
+                        })
+                        .join("\n")
                 );
                 let result = cx
                     .update(|window, cx| prompt("Are you sure?", Some(&detail), window, cx))?
@@ -1841,7 +1960,7 @@ impl GitPanel {
                     return Ok(());
                 };
                 let fetch = repo.update(cx, |repo, cx| {
-                    repo.fetch(fetch_options.clone(), askpass, cx)
+                    repo.fetch(fetch_options.clone(), askpass, None, cx)
                 })?;
 
                 let remote_message = fetch.await?;
@@ -2072,7 +2191,9 @@ impl GitPanel {
                 repo.pull(
                     branch.name().to_owned().into(),
                     remote.name.clone(),
+                    PullOptions::default(),
                     askpass,
+                    None,
                     cx,
                 )
             })?;
@@ -2150,6 +2271,7 @@ impl GitPanel {
                     remote.name.clone(),
                     options,
                     askpass_delegate,
+                    None,
                     cx,
                 )
             })?;
@@ -2341,52 +2463,14 @@ impl GitPanel {
         }
     }
 
-    fn fill_co_authors(&mut self, message: &mut String, cx: &mut Context<Self>) {
-        const CO_AUTHOR_PREFIX: &str = "Co-authored-by: ";
-
-        let existing_text = message.to_ascii_lowercase();
-        let lowercase_co_author_prefix = CO_AUTHOR_PREFIX.to_lowercase();
-        let mut ends_with_co_authors = false;
-        let existing_co_authors = existing_text
-            .lines()
-            .filter_map(|line| {
-                let line = line.trim();
-                if line.starts_with(&lowercase_co_author_prefix) {
-                    ends_with_co_authors = true;
-                    Some(line)
-                } else {
-                    ends_with_co_authors = false;
-                    None
-                }
-            })
-            .collect::<HashSet<_>>();
-
-        let new_co_authors = self
-            .potential_co_authors(cx)
+    /// Credits the collaborators as co-authors. The ones whose names can't go in a trailer are
+    /// left out, rather than keeping the commit from being made.
+    fn co_author_trailers(&self, cx: &App) -> Vec<Trailer> {
+        self.potential_co_authors(cx)
             .into_iter()
-            .filter(|(_, email)| {
-                !existing_co_authors
-                    .iter()
-                    .any(|existing| existing.contains(email.as_str()))
-            })
-            .collect::<Vec<_>>();
-
-        if new_co_authors.is_empty() {
-            return;
-        }
-
-        if !ends_with_co_authors {
-            message.push('\n');
-        }
-        for (name, email) in new_co_authors {
-            message.push('\n');
-            message.push_str(CO_AUTHOR_PREFIX);
-            message.push_str(&name);
-            message.push_str(" <");
-            message.push_str(&email);
-            message.push('>');
-        }
-        message.push('\n');
+            .map(|(name, email)| Trailer::co_authored_by(&name, &email))
+            .filter(|trailer| trailer.validate().is_ok())
+            .collect()
     }
 
     fn schedule_update(
@@ -2737,6 +2821,8 @@ impl GitPanel {
             .next()
             .is_some()
         { // Hide the cancelled by user message
+        } else if git::repository::IndexLocked::is_cause_of(&e) {
+            // Already reported by `GitStoreEvent::IndexLocked`.
         } else {
             workspace.update(cx, |workspace, cx| {
                 let workspace_weak = cx.weak_entity();
@@ -2757,6 +2843,23 @@ impl GitPanel {
         }
     }
 
+    fn show_index_locked_toast(&self, cx: &mut App) {
+        let Some(workspace) = self.workspace.upgrade() else {
+            return;
+        };
+        workspace.update(cx, |workspace, cx| {
+            let toast = StatusToast::new(
+                "Another git process is running in this repository. Try again once it has finished.",
+                cx,
+                |this, _| {
+                    this.icon(ToastIcon::new(IconName::Warning).color(Color::Warning))
+                        .dismiss_button(true)
+                },
+            );
+            workspace.toggle_status_toast(toast, cx)
+        });
+    }
+
     fn show_remote_output(&self, action: RemoteAction, info: RemoteCommandOutput, cx: &mut App) {
         let Some(workspace) = self.workspace.upgrade() else {
             return;
@@ -3264,7 +3367,11 @@ impl GitPanel {
                         git_panel
                             .update(cx, |git_panel, cx| {
                                 git_panel.commit_changes(
-                                    CommitOptions { amend, signoff },
+                                    CommitOptions {
+                                        amend,
+                                        signoff,
+                                        sign: false,
+                                    },
                                     window,
                                     cx,
                                 );
@@ -3647,6 +3754,12 @@ impl GitPanel {
                     context_menu.action("Add to .gitignore", git::AddToGitignore.boxed_clone());
             }
 
+            if entry.status.is_conflicted() {
+                context_menu = context_menu
+                    .action("Take Ours", git::TakeOurs.boxed_clone())
+                    .action("Take Theirs", git::TakeTheirs.boxed_clone());
+            }
+
             context_menu
                 .separator()
                 .action("Open Diff", Confirm.boxed_clone())
@@ -3743,6 +3856,13 @@ impl GitPanel {
             Color::Muted
         };
 
+        let lfs_status = self.active_repository.as_ref().and_then(|repository| {
+            repository
+                .read(cx)
+                .lfs_status_for_path(&entry.repo_path)
+                .cloned()
+        });
+
         let id: ElementId = ElementId::Name(format!("entry_{}_{}", display_name, ix).into());
         let checkbox_wrapper_id: ElementId =
             ElementId::Name(format!("entry_{}_{}_checkbox_wrapper", display_name, ix).into());
@@ -3902,6 +4022,9 @@ impl GitPanel {
                             .when(status.is_deleted(), |this| this.strikethrough()),
                     ),
             )
+            .when_some(lfs_status, |this, lfs_status| {
+                this.child(render_lfs_badge(ix, lfs_status))
+            })
             .into_any_element()
     }
 
@@ -3985,7 +4108,13 @@ impl GitPanel {
                     panel.amend_pending = serialized_panel.amend_pending;
                     panel.signoff_enabled = serialized_panel.signoff_enabled;
                     cx.notify();
-                })
+                });
+                if let Some(active_repository) = serialized_panel.active_repository {
+                    let git_store = workspace.project().read(cx).git_store().clone();
+                    git_store.update(cx, |git_store, cx| {
+                        git_store.set_preferred_active_repository(active_repository.into(), cx)
+                    });
+                }
             }
 
             panel
@@ -4071,6 +4200,8 @@ impl Render for GitPanel {
                     .on_action(cx.listener(Self::restore_tracked_files))
                     .on_action(cx.listener(Self::revert_selected))
                     .on_action(cx.listener(Self::add_to_gitignore))
+                    .on_action(cx.listener(Self::take_ours))
+                    .on_action(cx.listener(Self::take_theirs))
                     .on_action(cx.listener(Self::clean_all))
                     .on_action(cx.listener(Self::stash_all))
                     .on_action(cx.listener(Self::stash_pop))
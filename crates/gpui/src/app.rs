@@ -1943,6 +1943,11 @@ impl App {
         self.active_drag.is_some()
     }
 
+    /// Returns the value being dragged by the active drag operation, if it is of the given type.
+    pub fn active_drag_value<T: 'static>(&self) -> Option<&T> {
+        self.active_drag.as_ref()?.value.downcast_ref::<T>()
+    }
+
     /// Gets the cursor style of the currently active drag operation.
     pub fn active_drag_cursor_style(&self) -> Option<CursorStyle> {
         self.active_drag.as_ref().and_then(|drag| drag.cursor_style)
@@ -2003,6 +2008,11 @@ impl App {
         self.loading_assets.remove(&asset_id);
     }
 
+    /// Remove every asset from GPUI's cache, so that they are loaded again when next used
+    pub fn clear_asset_cache(&mut self) {
+        self.loading_assets.clear();
+    }
+
     /// Asynchronously load an asset, if the asset hasn't finished loading this will return None.
     ///
     /// Note that the multiple calls to this method will only result in one `Asset::load` call at a
@@ -308,4 +308,10 @@ pub struct GpuSpecs {
     pub driver_name: String,
     /// Further information about the driver, as reported by Vulkan.
     pub driver_info: String,
+    /// The graphics API used for rendering, such as Vulkan or DirectX 11.
+    pub backend: String,
+    /// Why rendering fell back to a software rasterizer, if the GPU failed to initialize.
+    ///
+    /// Only Linux has a fallback, to Mesa's lavapipe, so this is always `None` elsewhere.
+    pub fallback_reason: Option<String>,
 }
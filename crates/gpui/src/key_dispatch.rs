@@ -431,6 +431,17 @@ impl DispatchTree {
             .cloned()
     }
 
+    /// Returns the bindings that could be completed by typing more keystrokes after the input.
+    pub fn pending_bindings_for_input(
+        &self,
+        input: &[Keystroke],
+        context_stack: &[KeyContext],
+    ) -> Vec<KeyBinding> {
+        self.keymap
+            .borrow()
+            .pending_bindings_for_input(input, context_stack)
+    }
+
     fn binding_matches_predicate_and_not_shadowed(
         keymap: &Keymap,
         binding: &KeyBinding,
@@ -20,6 +20,7 @@ pub struct Keymap {
     bindings: Vec<KeyBinding>,
     binding_indices_by_action_id: HashMap<TypeId, SmallVec<[usize; 3]>>,
     no_action_binding_indices: Vec<usize>,
+    user_defined_metas: Option<SmallVec<[KeyBindingMetaIndex; 2]>>,
     version: KeymapVersion,
 }
 
@@ -58,6 +59,24 @@ impl Keymap {
         self.version.0 += 1;
     }
 
+    /// Set which binding metadata marks a binding as written by the user. A user-defined
+    /// `NoAction` binding disables its keystrokes outright, while other `NoAction` bindings
+    /// can still be overridden by user bindings in less specific contexts. When this is not
+    /// set, bindings with `KeyBindingMetaIndex(0)` or no metadata are user-defined.
+    pub fn set_user_defined_metas(&mut self, metas: impl IntoIterator<Item = KeyBindingMetaIndex>) {
+        self.user_defined_metas = Some(metas.into_iter().collect());
+    }
+
+    fn is_user_defined(&self, binding: &KeyBinding) -> bool {
+        let Some(meta) = binding.meta else {
+            return true;
+        };
+        match &self.user_defined_metas {
+            Some(metas) => metas.contains(&meta),
+            None => meta.0 == 0,
+        }
+    }
+
     /// Reset this keymap to its initial state.
     pub fn clear(&mut self) {
         self.bindings.clear();
@@ -173,12 +192,7 @@ impl Keymap {
             if is_no_action(&*binding.action) {
                 // Only break if this is a user-defined NoAction binding
                 // This allows user keymaps to override base keymap NoAction bindings
-                if let Some(meta) = binding.meta {
-                    if meta.0 == 0 {
-                        break;
-                    }
-                } else {
-                    // If no meta is set, assume it's a user binding for safety
+                if self.is_user_defined(binding) {
                     break;
                 }
                 // For non-user NoAction bindings, continue searching for user overrides
@@ -204,6 +218,38 @@ impl Keymap {
 
         (bindings, !pending.is_empty())
     }
+
+    /// Returns the bindings that could still complete if more keystrokes were typed after the
+    /// given input, in precedence order. For each sequence of keystrokes only the highest
+    /// precedence binding is returned, and sequences disabled with `null` are omitted.
+    pub fn pending_bindings_for_input(
+        &self,
+        input: &[impl AsKeystroke],
+        context_stack: &[KeyContext],
+    ) -> Vec<KeyBinding> {
+        let mut pending_bindings = Vec::new();
+        for (ix, binding) in self.bindings().enumerate().rev() {
+            let Some(depth) = self.binding_enabled(binding, context_stack) else {
+                continue;
+            };
+            if binding.match_keystrokes(input) == Some(true) {
+                pending_bindings.push((depth, BindingIndex(ix), binding));
+            }
+        }
+
+        pending_bindings.sort_by(|(depth_a, ix_a, _), (depth_b, ix_b, _)| {
+            depth_b.cmp(depth_a).then(ix_b.cmp(ix_a))
+        });
+
+        let mut seen_keystrokes = HashSet::default();
+        pending_bindings
+            .into_iter()
+            .filter(|(_, _, binding)| seen_keystrokes.insert(&binding.keystrokes))
+            .filter(|(_, _, binding)| !is_no_action(&*binding.action))
+            .map(|(_, _, binding)| binding.clone())
+            .collect()
+    }
+
     /// Check if the given binding is enabled, given a certain key context.
     /// Returns the deepest depth at which the binding matches, or None if it doesn't match.
     fn binding_enabled(&self, binding: &KeyBinding, contexts: &[KeyContext]) -> Option<usize> {
@@ -291,6 +337,34 @@ mod tests {
         assert!(result[1].action.partial_eq(&ActionBeta {}));
     }
 
+    #[test]
+    fn test_pending_bindings_for_input() {
+        let bindings = [
+            KeyBinding::new("g a", ActionAlpha {}, Some("pane")),
+            KeyBinding::new("g a", ActionBeta {}, Some("editor")),
+            KeyBinding::new("g b", ActionGamma {}, Some("editor")),
+            KeyBinding::new("g c", ActionDelta {}, Some("editor")),
+            KeyBinding::new("g c", NoAction {}, Some("editor")),
+            KeyBinding::new("g", ActionAlpha {}, Some("editor")),
+            KeyBinding::new("h a", ActionAlpha {}, Some("editor")),
+        ];
+
+        let mut keymap = Keymap::default();
+        keymap.add_bindings(bindings);
+
+        let result = keymap.pending_bindings_for_input(
+            &[Keystroke::parse("g").unwrap()],
+            &[
+                KeyContext::parse("pane").unwrap(),
+                KeyContext::parse("editor").unwrap(),
+            ],
+        );
+
+        assert_eq!(result.len(), 2);
+        assert!(result[0].action.partial_eq(&ActionGamma {}));
+        assert!(result[1].action.partial_eq(&ActionBeta {}));
+    }
+
     #[test]
     fn test_keymap_disabled() {
         let bindings = [
@@ -710,4 +784,30 @@ mod tests {
         assert!(result[0].action.partial_eq(&ActionBeta {}));
         assert!(result[1].action.partial_eq(&ActionAlpha {}));
     }
+
+    #[test]
+    fn test_user_defined_metas() {
+        let mut keymap = Keymap::default();
+
+        let mut default_binding = KeyBinding::new("ctrl-x", ActionAlpha {}, Some("Workspace"));
+        default_binding.set_meta(KeyBindingMetaIndex(3));
+        let mut no_action_binding = KeyBinding::new("ctrl-x", NoAction {}, Some("Editor"));
+        no_action_binding.set_meta(KeyBindingMetaIndex(5));
+        keymap.add_bindings([default_binding, no_action_binding]);
+
+        let context_stack = [
+            KeyContext::parse("Workspace").unwrap(),
+            KeyContext::parse("Editor").unwrap(),
+        ];
+        let keystrokes = [Keystroke::parse("ctrl-x").unwrap()];
+
+        // Only index 0 is user-defined by default, so the NoAction doesn't mask the binding
+        let (result, _) = keymap.bindings_for_input(&keystrokes, &context_stack);
+        assert_eq!(result.len(), 1);
+        assert!(result[0].action.partial_eq(&ActionAlpha {}));
+
+        keymap.set_user_defined_metas([KeyBindingMetaIndex(0), KeyBindingMetaIndex(5)]);
+        let (result, _) = keymap.bindings_for_input(&keystrokes, &context_stack);
+        assert!(result.is_empty());
+    }
 }
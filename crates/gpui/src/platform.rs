@@ -768,6 +768,8 @@ pub(crate) trait PlatformAtlas: Send + Sync {
         build: &mut dyn FnMut() -> Result<Option<(Size<DevicePixels>, Cow<'a, [u8]>)>>,
     ) -> Result<Option<AtlasTile>>;
     fn remove(&self, key: &AtlasKey);
+    /// The number of bytes of GPU memory held by the atlas' textures.
+    fn texture_memory(&self) -> usize;
 }
 
 struct AtlasTextureList<T> {
@@ -799,6 +801,11 @@ impl<T> AtlasTextureList<T> {
         self.textures.drain(..)
     }
 
+    #[allow(dead_code)]
+    fn iter(&self) -> impl DoubleEndedIterator<Item = &T> {
+        self.textures.iter().flatten()
+    }
+
     #[allow(dead_code)]
     fn iter_mut(&mut self) -> impl DoubleEndedIterator<Item = &mut T> {
         self.textures.iter_mut().flatten()
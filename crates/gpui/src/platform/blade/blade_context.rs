@@ -6,10 +6,37 @@ use util::ResultExt;
 #[cfg_attr(target_os = "macos", derive(Clone))]
 pub struct BladeContext {
     pub(super) gpu: Arc<gpu::Context>,
+    /// Why the GPU couldn't be used, when rendering fell back to a software rasterizer.
+    pub(super) fallback_reason: Option<String>,
 }
 
 impl BladeContext {
+    /// Initializes the GPU, falling back to lavapipe on Linux, since old or missing drivers
+    /// otherwise leave Zed unable to open any windows. Other platforms have no fallback.
+    ///
+    /// On Linux, this must be called before the platform spawns any threads.
     pub fn new() -> anyhow::Result<Self> {
+        match Self::init_gpu() {
+            Ok(gpu) => Ok(Self {
+                gpu,
+                fallback_reason: None,
+            }),
+            Err(error) => {
+                log::error!(
+                    "Failed to initialize GPU, falling back to software rendering: {error}"
+                );
+                let gpu = init_software_gpu().with_context(|| {
+                    format!("Failed to initialize GPU ({error}) and software renderer")
+                })?;
+                Ok(Self {
+                    gpu,
+                    fallback_reason: Some(error.to_string()),
+                })
+            }
+        }
+    }
+
+    fn init_gpu() -> anyhow::Result<Arc<gpu::Context>> {
         let device_id_forced = match std::env::var("ZED_DEVICE_ID") {
             Ok(val) => parse_pci_id(&val)
                 .context("Failed to parse device ID from `ZED_DEVICE_ID` environment variable")
@@ -32,10 +59,40 @@ impl BladeContext {
             }
             .map_err(|e| anyhow::anyhow!("{e:?}"))?,
         );
-        Ok(Self { gpu })
+        Ok(gpu)
     }
 }
 
+/// Initializes Mesa's lavapipe, a Vulkan driver that rasterizes on the CPU and is installed
+/// alongside Mesa's hardware drivers on most distributions.
+#[cfg(any(target_os = "linux", target_os = "freebsd"))]
+fn init_software_gpu() -> anyhow::Result<Arc<gpu::Context>> {
+    // Lavapipe reports no PCI device ID, so it can't be picked with `ContextDesc::device_id`.
+    // Instead, the Vulkan loader is restricted to it while the instance is created, which is
+    // when the loader reads this variable.
+    const DRIVERS_SELECT: &str = "VK_LOADER_DRIVERS_SELECT";
+    // SAFETY: the Linux platforms create the GPU context before `LinuxCommon` spawns the
+    // dispatcher and timer threads, so nothing reads the environment concurrently.
+    unsafe { std::env::set_var(DRIVERS_SELECT, "*lvp*") };
+    let gpu = unsafe {
+        gpu::Context::init(gpu::ContextDesc {
+            presentation: true,
+            validation: false,
+            ..Default::default()
+        })
+    };
+    // Removed straight away, so that the terminals, tasks and language servers that Zed spawns
+    // don't inherit it and get forced onto lavapipe too.
+    // SAFETY: see above.
+    unsafe { std::env::remove_var(DRIVERS_SELECT) };
+    Ok(Arc::new(gpu.map_err(|e| anyhow::anyhow!("{e:?}"))?))
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "freebsd")))]
+fn init_software_gpu() -> anyhow::Result<Arc<gpu::Context>> {
+    anyhow::bail!("no software renderer is available on this platform")
+}
+
 fn parse_pci_id(id: &str) -> anyhow::Result<u32> {
     let mut id = id.trim();
 
@@ -341,6 +341,7 @@ pub struct BladeRenderer {
     path_intermediate_msaa_texture: Option<gpu::Texture>,
     path_intermediate_msaa_texture_view: Option<gpu::TextureView>,
     rendering_parameters: RenderingParameters,
+    fallback_reason: Option<String>,
 }
 
 impl BladeRenderer {
@@ -427,6 +428,7 @@ impl BladeRenderer {
             path_intermediate_msaa_texture,
             path_intermediate_msaa_texture_view,
             rendering_parameters,
+            fallback_reason: context.fallback_reason.clone(),
         })
     }
 
@@ -547,6 +549,13 @@ impl BladeRenderer {
             device_name: info.device_name.clone(),
             driver_name: info.driver_name.clone(),
             driver_info: info.driver_info.clone(),
+            backend: if cfg!(target_os = "macos") {
+                "Metal"
+            } else {
+                "Vulkan"
+            }
+            .to_string(),
+            fallback_reason: self.fallback_reason.clone(),
         }
     }
 
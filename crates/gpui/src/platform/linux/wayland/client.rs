@@ -482,6 +482,10 @@ impl WaylandClient {
 
         let event_loop = EventLoop::<WaylandClientStatePtr>::try_new().unwrap();
 
+        // The GPU is initialized before `LinuxCommon` spawns the dispatcher threads, as the
+        // software fallback changes the environment.
+        let gpu_context = BladeContext::new().expect("Unable to init GPU context");
+
         let (common, main_receiver) = LinuxCommon::new(event_loop.get_signal());
 
         let handle = event_loop.handle();
@@ -498,8 +502,6 @@ impl WaylandClient {
             })
             .unwrap();
 
-        let gpu_context = BladeContext::new().expect("Unable to init GPU context");
-
         let seat = seat.unwrap();
         let globals = Globals::new(
             globals,
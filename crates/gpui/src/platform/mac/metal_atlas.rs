@@ -87,6 +87,15 @@ impl PlatformAtlas for MetalAtlas {
             }
         }
     }
+
+    fn texture_memory(&self) -> usize {
+        let lock = self.0.lock();
+        lock.monochrome_textures
+            .iter()
+            .chain(lock.polychrome_textures.iter())
+            .map(MetalAtlasTexture::memory_size)
+            .sum()
+    }
 }
 
 impl MetalAtlasState {
@@ -233,6 +242,11 @@ impl MetalAtlasTexture {
         }
     }
 
+    fn memory_size(&self) -> usize {
+        let size = self.allocator.size();
+        size.width as usize * size.height as usize * self.bytes_per_pixel() as usize
+    }
+
     fn decrement_ref_count(&mut self) {
         self.live_atlas_keys -= 1;
     }
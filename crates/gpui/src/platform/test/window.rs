@@ -359,4 +359,14 @@ impl PlatformAtlas for TestAtlas {
         let mut state = self.0.lock();
         state.tiles.remove(key);
     }
+
+    fn texture_memory(&self) -> usize {
+        // Every tile gets a texture of its own, with one byte per pixel.
+        self.0
+            .lock()
+            .tiles
+            .values()
+            .map(|tile| tile.bounds.size.width.0 as usize * tile.bounds.size.height.0 as usize)
+            .sum()
+    }
 }
@@ -602,6 +602,8 @@ impl DirectXRenderer {
             device_name,
             driver_name,
             driver_info: driver_version,
+            backend: "DirectX 11".to_string(),
+            fallback_reason: None,
         })
     }
 
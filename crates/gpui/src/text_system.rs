@@ -98,6 +98,13 @@ impl TextSystem {
         names
     }
 
+    /// Drop the cached glyph bounds and line wrappers, which are computed again when next needed.
+    pub fn clear_caches(&self) {
+        self.raster_bounds.write().clear();
+        self.wrapper_pool.lock().clear();
+        self.font_runs_pool.lock().clear();
+    }
+
     /// Add a font's data to the text system.
     pub fn add_fonts(&self, fonts: Vec<Cow<'static, [u8]>>) -> Result<()> {
         self.platform_text_system.add_fonts(fonts)
@@ -3942,6 +3942,18 @@ impl Window {
             .map(|pending_input| pending_input.keystrokes.as_slice())
     }
 
+    /// Returns the key bindings that could be completed by typing more keystrokes after the
+    /// currently pending input, in precedence order.
+    pub fn pending_input_bindings(&self) -> Vec<KeyBinding> {
+        let Some(keystrokes) = self.pending_input_keystrokes() else {
+            return Vec::new();
+        };
+        let context_stack = self.context_stack();
+        self.rendered_frame
+            .dispatch_tree
+            .pending_bindings_for_input(keystrokes, &context_stack)
+    }
+
     fn replay_pending_input(&mut self, replays: SmallVec<[Replay; 1]>, cx: &mut App) {
         let node_id = self.focus_node_id_in_rendered_frame(self.focus);
         let dispatch_path = self.rendered_frame.dispatch_tree.dispatch_path(node_id);
@@ -4378,6 +4390,12 @@ impl Window {
         self.platform_window.gpu_specs()
     }
 
+    /// The number of bytes of GPU memory held by the textures of this window's sprite atlas,
+    /// which caches rasterized glyphs, icons and images.
+    pub fn sprite_atlas_memory(&self) -> usize {
+        self.sprite_atlas.texture_memory()
+    }
+
     /// Perform titlebar double-click action.
     /// This is macOS specific.
     pub fn titlebar_double_click(&self) {
@@ -0,0 +1,207 @@
+use std::{ops::Range, sync::Arc};
+
+use collections::{BTreeMap, HashMap};
+
+/// The number of bytes loaded from disk at a time.
+pub const PAGE_SIZE: u64 = 64 * 1024;
+
+/// The number of pages kept in memory, so that scrolling through a large file doesn't load all
+/// of it.
+const MAX_LOADED_PAGES: usize = 16;
+
+/// The contents of a file being edited in the hex editor.
+///
+/// The file is loaded lazily in pages, and edits are kept separately from the pages, so that
+/// pages can be evicted without losing them. Edits only ever replace bytes, so the length of the
+/// file never changes.
+pub struct HexBuffer {
+    len: u64,
+    pages: HashMap<u64, Arc<[u8]>>,
+    edits: BTreeMap<u64, u8>,
+}
+
+impl HexBuffer {
+    pub fn new(len: u64) -> Self {
+        Self {
+            len,
+            pages: HashMap::default(),
+            edits: BTreeMap::default(),
+        }
+    }
+
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn page_for_offset(offset: u64) -> u64 {
+        offset / PAGE_SIZE
+    }
+
+    /// The byte range of the given page, clipped to the end of the file.
+    pub fn page_range(&self, page: u64) -> Range<u64> {
+        let start = (page * PAGE_SIZE).min(self.len);
+        let end = (start + PAGE_SIZE).min(self.len);
+        start..end
+    }
+
+    /// The pages that need to be loaded to display the given byte range.
+    pub fn missing_pages(&self, range: Range<u64>) -> Vec<u64> {
+        if range.is_empty() {
+            return Vec::new();
+        }
+        let first_page = Self::page_for_offset(range.start);
+        let last_page = Self::page_for_offset(range.end.min(self.len).saturating_sub(1));
+        (first_page..=last_page)
+            .filter(|page| !self.pages.contains_key(page))
+            .collect()
+    }
+
+    /// Stores a loaded page, evicting the pages furthest from it when too many are loaded.
+    pub fn insert_page(&mut self, page: u64, bytes: Arc<[u8]>) {
+        self.pages.insert(page, bytes);
+        while self.pages.len() > MAX_LOADED_PAGES {
+            let Some(furthest_page) = self
+                .pages
+                .keys()
+                .copied()
+                .max_by_key(|loaded_page| loaded_page.abs_diff(page))
+            else {
+                break;
+            };
+            self.pages.remove(&furthest_page);
+        }
+    }
+
+    /// The byte on disk at the given offset, if its page is loaded.
+    pub fn original_byte(&self, offset: u64) -> Option<u8> {
+        let page = self.pages.get(&Self::page_for_offset(offset))?;
+        page.get((offset % PAGE_SIZE) as usize).copied()
+    }
+
+    /// The byte at the given offset, including any edits.
+    pub fn byte(&self, offset: u64) -> Option<u8> {
+        if offset >= self.len {
+            return None;
+        }
+        self.edits
+            .get(&offset)
+            .copied()
+            .or_else(|| self.original_byte(offset))
+    }
+
+    /// Replaces the byte at the given offset, returning whether the offset was within the file.
+    pub fn set_byte(&mut self, offset: u64, value: u8) -> bool {
+        if offset >= self.len {
+            return false;
+        }
+        if self.original_byte(offset) == Some(value) {
+            self.edits.remove(&offset);
+        } else {
+            self.edits.insert(offset, value);
+        }
+        true
+    }
+
+    pub fn is_modified(&self, offset: u64) -> bool {
+        self.edits.contains_key(&offset)
+    }
+
+    pub fn is_dirty(&self) -> bool {
+        !self.edits.is_empty()
+    }
+
+    pub fn edits(&self) -> &BTreeMap<u64, u8> {
+        &self.edits
+    }
+
+    /// Discards the loaded pages and the given edits once they have been written to disk,
+    /// keeping any edits made while saving.
+    pub fn mark_saved(&mut self, saved_edits: &BTreeMap<u64, u8>) {
+        self.pages.clear();
+        self.edits
+            .retain(|offset, value| saved_edits.get(offset) != Some(value));
+    }
+
+    /// Discards the edits and loaded pages, e.g. when the file is reloaded from disk.
+    pub fn reset(&mut self, len: u64) {
+        self.len = len;
+        self.pages.clear();
+        self.edits.clear();
+    }
+}
+
+/// Writes edits into the contents of a file.
+pub fn apply_edits(edits: &BTreeMap<u64, u8>, bytes: &mut [u8]) {
+    for (&offset, &value) in edits {
+        if let Some(byte) = bytes.get_mut(offset as usize) {
+            *byte = value;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn page(bytes: &[u8]) -> Arc<[u8]> {
+        Arc::from(bytes)
+    }
+
+    #[test]
+    fn test_paging() {
+        let mut buffer = HexBuffer::new(PAGE_SIZE * 2 + 10);
+        assert_eq!(buffer.page_range(2), PAGE_SIZE * 2..PAGE_SIZE * 2 + 10);
+        assert_eq!(buffer.missing_pages(0..PAGE_SIZE + 1), vec![0, 1]);
+        assert_eq!(buffer.missing_pages(PAGE_SIZE * 2..PAGE_SIZE * 4), vec![2]);
+
+        buffer.insert_page(1, page(&[7; PAGE_SIZE as usize]));
+        assert_eq!(buffer.missing_pages(0..PAGE_SIZE * 3), vec![0, 2]);
+        assert_eq!(buffer.byte(0), None);
+        assert_eq!(buffer.byte(PAGE_SIZE + 3), Some(7));
+        assert_eq!(buffer.byte(PAGE_SIZE * 3), None);
+
+        for loaded_page in 2..2 + MAX_LOADED_PAGES as u64 {
+            buffer.insert_page(loaded_page, page(&[0]));
+        }
+        assert_eq!(buffer.missing_pages(PAGE_SIZE..PAGE_SIZE + 1), vec![1]);
+    }
+
+    #[test]
+    fn test_edits() {
+        let mut buffer = HexBuffer::new(4);
+        buffer.insert_page(0, page(&[1, 2, 3, 4]));
+        assert!(!buffer.is_dirty());
+
+        assert!(buffer.set_byte(1, 0xff));
+        assert!(!buffer.set_byte(4, 0xff));
+        assert!(buffer.is_modified(1));
+        assert_eq!(buffer.byte(1), Some(0xff));
+
+        // Edits survive their page being evicted.
+        buffer.pages.clear();
+        assert_eq!(buffer.byte(1), Some(0xff));
+        assert_eq!(buffer.byte(2), None);
+
+        let mut bytes = vec![1, 2, 3, 4];
+        apply_edits(buffer.edits(), &mut bytes);
+        assert_eq!(bytes, [1, 0xff, 3, 4]);
+
+        // Restoring the original value clears the edit.
+        buffer.insert_page(0, page(&[1, 2, 3, 4]));
+        buffer.set_byte(1, 2);
+        assert!(!buffer.is_dirty());
+
+        // Edits made while saving are kept.
+        buffer.set_byte(0, 0xaa);
+        let saved_edits = buffer.edits().clone();
+        buffer.set_byte(3, 0xbb);
+        buffer.mark_saved(&saved_edits);
+        assert!(!buffer.is_modified(0));
+        assert!(buffer.is_modified(3));
+        assert_eq!(buffer.byte(0), None);
+    }
+}
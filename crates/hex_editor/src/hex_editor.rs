@@ -0,0 +1,586 @@
+mod hex_buffer;
+
+use std::{ops::Range, path::Path, sync::Arc};
+
+use anyhow::Result;
+use collections::HashSet;
+use file_icons::FileIcons;
+use fs::Fs;
+use gpui::{
+    AnyElement, App, Context, Entity, EventEmitter, FocusHandle, Focusable, KeyDownEvent,
+    ScrollStrategy, Task, UniformListScrollHandle, Window, actions, uniform_list,
+};
+use project::Project;
+use ui::prelude::*;
+use util::paths::PathExt;
+use workspace::{
+    ItemSettings, Workspace,
+    invalid_buffer_view::InvalidBufferView,
+    item::{Item, ItemEvent, SaveOptions},
+};
+use zed_actions::hex_editor::ReopenAsHex;
+
+use crate::hex_buffer::{HexBuffer, apply_edits};
+
+actions!(
+    hex_editor,
+    [
+        /// Moves the cursor to the previous byte.
+        MoveLeft,
+        /// Moves the cursor to the next byte.
+        MoveRight,
+        /// Moves the cursor to the previous row.
+        MoveUp,
+        /// Moves the cursor to the next row.
+        MoveDown,
+        /// Moves the cursor up by a page.
+        MovePageUp,
+        /// Moves the cursor down by a page.
+        MovePageDown,
+        /// Moves the cursor to the first byte of the file.
+        MoveToBeginning,
+        /// Moves the cursor to the last byte of the file.
+        MoveToEnd,
+        /// Switches between editing the hex and the ASCII column.
+        SwitchColumn,
+        /// Restores the original value of the byte under the cursor.
+        RevertByte,
+    ]
+);
+
+const BYTES_PER_ROW: u64 = 16;
+
+pub fn init(cx: &mut App) {
+    cx.observe_new(|workspace: &mut Workspace, _, _| {
+        workspace.register_action(reopen_as_hex);
+    })
+    .detach();
+}
+
+fn reopen_as_hex(
+    workspace: &mut Workspace,
+    _: &ReopenAsHex,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let Some(active_item) = workspace.active_item(cx) else {
+        return;
+    };
+    let abs_path = if let Some(invalid_buffer_view) = active_item.downcast::<InvalidBufferView>() {
+        Some(invalid_buffer_view.read(cx).abs_path.to_path_buf())
+    } else {
+        active_item.project_path(cx).and_then(|project_path| {
+            workspace
+                .project()
+                .read(cx)
+                .absolute_path(&project_path, cx)
+        })
+    };
+    let Some(abs_path) = abs_path else {
+        return;
+    };
+    if !workspace.project().read(cx).is_local() {
+        workspace.show_error(&"The hex editor is only available for local files", cx);
+        return;
+    }
+
+    let fs = workspace.app_state().fs.clone();
+    workspace.active_pane().update(cx, |pane, cx| {
+        let existing_item_index = pane.items().position(|item| {
+            item.downcast::<HexEditor>()
+                .is_some_and(|hex_editor| hex_editor.read(cx).abs_path.as_ref() == abs_path)
+        });
+        if let Some(existing_item_index) = existing_item_index {
+            pane.activate_item(existing_item_index, true, true, window, cx);
+        } else {
+            let hex_editor = cx.new(|cx| HexEditor::new(abs_path.into(), fs, window, cx));
+            pane.add_item(Box::new(hex_editor), true, true, None, window, cx);
+        }
+    });
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum HexColumn {
+    Hex,
+    Ascii,
+}
+
+pub enum HexEditorEvent {
+    TitleChanged,
+}
+
+/// An editor for the raw bytes of a file, showing them as hex and ASCII.
+pub struct HexEditor {
+    abs_path: Arc<Path>,
+    fs: Arc<dyn Fs>,
+    buffer: HexBuffer,
+    /// Incremented whenever the buffer is reloaded, so that pages loaded for an older version of
+    /// the file are discarded.
+    version: usize,
+    loading_pages: HashSet<u64>,
+    error: Option<SharedString>,
+    cursor: u64,
+    column: HexColumn,
+    /// The high nibble typed into the hex column, while waiting for the low one.
+    pending_nibble: Option<u8>,
+    visible_row_count: usize,
+    scroll_handle: UniformListScrollHandle,
+    focus_handle: FocusHandle,
+    _reload_task: Task<Result<()>>,
+}
+
+impl HexEditor {
+    pub fn new(
+        abs_path: Arc<Path>,
+        fs: Arc<dyn Fs>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let mut this = Self {
+            abs_path,
+            fs,
+            buffer: HexBuffer::new(0),
+            version: 0,
+            loading_pages: HashSet::default(),
+            error: None,
+            cursor: 0,
+            column: HexColumn::Hex,
+            pending_nibble: None,
+            visible_row_count: 0,
+            scroll_handle: UniformListScrollHandle::new(),
+            focus_handle: cx.focus_handle(),
+            _reload_task: Task::ready(Ok(())),
+        };
+        this._reload_task = this.reload_from_disk(window, cx);
+        this
+    }
+
+    fn reload_from_disk(&mut self, _: &mut Window, cx: &mut Context<Self>) -> Task<Result<()>> {
+        let fs = self.fs.clone();
+        let abs_path = self.abs_path.clone();
+        cx.spawn(async move |this, cx| {
+            let metadata = fs.metadata(&abs_path).await;
+            this.update(cx, |this, cx| {
+                this.version += 1;
+                this.loading_pages.clear();
+                this.pending_nibble = None;
+                match &metadata {
+                    Ok(Some(metadata)) => {
+                        this.error = None;
+                        this.buffer.reset(metadata.len);
+                        this.cursor = this.cursor.min(metadata.len.saturating_sub(1));
+                    }
+                    Ok(None) => {
+                        this.error = Some("File not found".into());
+                        this.buffer.reset(0);
+                    }
+                    Err(error) => {
+                        this.error = Some(format!("{error:#}").into());
+                        this.buffer.reset(0);
+                    }
+                }
+                cx.emit(HexEditorEvent::TitleChanged);
+                cx.notify();
+            })?;
+            metadata?;
+            Ok(())
+        })
+    }
+
+    /// Starts loading the pages needed to display the given bytes, if they aren't loaded yet.
+    fn load_pages(&mut self, range: Range<u64>, cx: &mut Context<Self>) {
+        if self.error.is_some() {
+            return;
+        }
+        for page in self.buffer.missing_pages(range) {
+            if !self.loading_pages.insert(page) {
+                continue;
+            }
+            let fs = self.fs.clone();
+            let abs_path = self.abs_path.clone();
+            let page_range = self.buffer.page_range(page);
+            let version = self.version;
+            cx.spawn(async move |this, cx| {
+                let bytes = fs.load_bytes_range(&abs_path, page_range).await;
+                this.update(cx, |this, cx| {
+                    if this.version != version {
+                        return;
+                    }
+                    this.loading_pages.remove(&page);
+                    match bytes {
+                        Ok(bytes) => this.buffer.insert_page(page, bytes.into()),
+                        Err(error) => this.error = Some(format!("{error:#}").into()),
+                    }
+                    cx.notify();
+                })
+                .ok();
+            })
+            .detach();
+        }
+    }
+
+    fn row_count(&self) -> u64 {
+        self.buffer.len().div_ceil(BYTES_PER_ROW)
+    }
+
+    fn set_cursor(&mut self, offset: u64, cx: &mut Context<Self>) {
+        if self.buffer.is_empty() {
+            return;
+        }
+        self.cursor = offset.min(self.buffer.len() - 1);
+        self.pending_nibble = None;
+        self.scroll_handle
+            .scroll_to_item((self.cursor / BYTES_PER_ROW) as usize, ScrollStrategy::Top);
+        cx.notify();
+    }
+
+    fn move_left(&mut self, _: &MoveLeft, _: &mut Window, cx: &mut Context<Self>) {
+        self.set_cursor(self.cursor.saturating_sub(1), cx);
+    }
+
+    fn move_right(&mut self, _: &MoveRight, _: &mut Window, cx: &mut Context<Self>) {
+        self.set_cursor(self.cursor + 1, cx);
+    }
+
+    fn move_up(&mut self, _: &MoveUp, _: &mut Window, cx: &mut Context<Self>) {
+        if self.cursor >= BYTES_PER_ROW {
+            self.set_cursor(self.cursor - BYTES_PER_ROW, cx);
+        }
+    }
+
+    fn move_down(&mut self, _: &MoveDown, _: &mut Window, cx: &mut Context<Self>) {
+        if self.cursor + BYTES_PER_ROW < self.buffer.len() {
+            self.set_cursor(self.cursor + BYTES_PER_ROW, cx);
+        }
+    }
+
+    fn move_page_up(&mut self, _: &MovePageUp, _: &mut Window, cx: &mut Context<Self>) {
+        let page_bytes = self.visible_row_count.max(1) as u64 * BYTES_PER_ROW;
+        self.set_cursor(self.cursor.saturating_sub(page_bytes), cx);
+    }
+
+    fn move_page_down(&mut self, _: &MovePageDown, _: &mut Window, cx: &mut Context<Self>) {
+        let page_bytes = self.visible_row_count.max(1) as u64 * BYTES_PER_ROW;
+        self.set_cursor(self.cursor.saturating_add(page_bytes), cx);
+    }
+
+    fn move_to_beginning(&mut self, _: &MoveToBeginning, _: &mut Window, cx: &mut Context<Self>) {
+        self.set_cursor(0, cx);
+    }
+
+    fn move_to_end(&mut self, _: &MoveToEnd, _: &mut Window, cx: &mut Context<Self>) {
+        self.set_cursor(u64::MAX, cx);
+    }
+
+    fn switch_column(&mut self, _: &SwitchColumn, _: &mut Window, cx: &mut Context<Self>) {
+        self.column = match self.column {
+            HexColumn::Hex => HexColumn::Ascii,
+            HexColumn::Ascii => HexColumn::Hex,
+        };
+        self.pending_nibble = None;
+        cx.notify();
+    }
+
+    fn revert_byte(&mut self, _: &RevertByte, _: &mut Window, cx: &mut Context<Self>) {
+        if let Some(original_byte) = self.buffer.original_byte(self.cursor) {
+            self.write_byte(original_byte, cx);
+        }
+    }
+
+    fn write_byte(&mut self, value: u8, cx: &mut Context<Self>) {
+        let was_dirty = self.buffer.is_dirty();
+        if self.buffer.set_byte(self.cursor, value) {
+            if was_dirty != self.buffer.is_dirty() {
+                cx.emit(HexEditorEvent::TitleChanged);
+            }
+            cx.notify();
+        }
+    }
+
+    fn key_down(&mut self, event: &KeyDownEvent, _: &mut Window, cx: &mut Context<Self>) {
+        let keystroke = &event.keystroke;
+        if keystroke.modifiers.control || keystroke.modifiers.alt || keystroke.modifiers.platform {
+            return;
+        }
+        let mut characters = keystroke
+            .key_char
+            .iter()
+            .flat_map(|key_char| key_char.chars());
+        let (Some(character), None) = (characters.next(), characters.next()) else {
+            return;
+        };
+        // Bytes whose page hasn't loaded yet can't be edited, as typing a single nibble keeps
+        // the other one.
+        let Some(current_byte) = self.buffer.byte(self.cursor) else {
+            return;
+        };
+
+        match self.column {
+            HexColumn::Hex => {
+                let Some(nibble) = character.to_digit(16).map(|digit| digit as u8) else {
+                    return;
+                };
+                if let Some(high_nibble) = self.pending_nibble.take() {
+                    self.write_byte((high_nibble << 4) | nibble, cx);
+                    self.set_cursor(self.cursor + 1, cx);
+                } else {
+                    self.write_byte((nibble << 4) | (current_byte & 0x0f), cx);
+                    self.pending_nibble = Some(nibble);
+                }
+            }
+            HexColumn::Ascii => {
+                if !character.is_ascii() || character.is_ascii_control() {
+                    return;
+                }
+                self.write_byte(character as u8, cx);
+                self.set_cursor(self.cursor + 1, cx);
+            }
+        }
+        cx.stop_propagation();
+    }
+
+    fn render_row(&self, row: u64, focused: bool, cx: &mut Context<Self>) -> AnyElement {
+        let row_start = row * BYTES_PER_ROW;
+        let row_end = (row_start + BYTES_PER_ROW).min(self.buffer.len());
+        let cursor_background = |column: HexColumn| {
+            if focused && column == self.column {
+                cx.theme().colors().element_selected
+            } else {
+                cx.theme().colors().element_hover
+            }
+        };
+
+        let hex_cells = (row_start..row_end).map(|offset| {
+            let text = match (self.buffer.byte(offset), self.pending_nibble) {
+                (_, Some(high_nibble)) if offset == self.cursor => format!("{high_nibble:X}_"),
+                (Some(byte), _) => format!("{byte:02X}"),
+                (None, _) => "··".to_string(),
+            };
+            div()
+                .id(("hex-byte", offset))
+                .px_0p5()
+                .rounded_xs()
+                .when(offset % 8 == 0 && offset != row_start, |cell| cell.ml_1p5())
+                .when(offset == self.cursor, |cell| {
+                    cell.bg(cursor_background(HexColumn::Hex))
+                })
+                .child(self.render_byte_label(offset, text, cx))
+                .on_click(cx.listener(move |this, _, window, cx| {
+                    this.column = HexColumn::Hex;
+                    this.set_cursor(offset, cx);
+                    window.focus(&this.focus_handle);
+                }))
+        });
+
+        let ascii_cells = (row_start..row_end).map(|offset| {
+            let character = self
+                .buffer
+                .byte(offset)
+                .map(char::from)
+                .filter(|character| character.is_ascii_graphic() || *character == ' ')
+                .unwrap_or('·');
+            div()
+                .id(("ascii-byte", offset))
+                .rounded_xs()
+                .when(offset == self.cursor, |cell| {
+                    cell.bg(cursor_background(HexColumn::Ascii))
+                })
+                .child(self.render_byte_label(offset, character.to_string(), cx))
+                .on_click(cx.listener(move |this, _, window, cx| {
+                    this.column = HexColumn::Ascii;
+                    this.set_cursor(offset, cx);
+                    window.focus(&this.focus_handle);
+                }))
+        });
+
+        h_flex()
+            .id(("hex-row", row))
+            .gap_x_4()
+            .px_2()
+            .child(
+                Label::new(format!("{row_start:08X}"))
+                    .buffer_font(cx)
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+            )
+            .child(h_flex().gap_x_1().children(hex_cells))
+            .child(h_flex().children(ascii_cells))
+            .into_any_element()
+    }
+
+    fn render_byte_label(&self, offset: u64, text: String, cx: &App) -> Label {
+        let color = if self.buffer.is_modified(offset) {
+            Color::Modified
+        } else if self.buffer.byte(offset).is_none() {
+            Color::Muted
+        } else {
+            Color::Default
+        };
+        Label::new(text)
+            .buffer_font(cx)
+            .size(LabelSize::Small)
+            .color(color)
+    }
+}
+
+impl Item for HexEditor {
+    type Event = HexEditorEvent;
+
+    fn to_item_events(event: &Self::Event, mut f: impl FnMut(ItemEvent)) {
+        match event {
+            HexEditorEvent::TitleChanged => f(ItemEvent::UpdateTab),
+        }
+    }
+
+    fn tab_content_text(&self, _: usize, _: &App) -> SharedString {
+        self.abs_path
+            .file_name()
+            .unwrap_or(self.abs_path.as_os_str())
+            .to_string_lossy()
+            .into_owned()
+            .into()
+    }
+
+    fn tab_tooltip_text(&self, _: &App) -> Option<SharedString> {
+        Some(
+            self.abs_path
+                .compact()
+                .to_string_lossy()
+                .into_owned()
+                .into(),
+        )
+    }
+
+    fn tab_icon(&self, _: &Window, cx: &App) -> Option<Icon> {
+        ItemSettings::get_global(cx)
+            .file_icons
+            .then(|| FileIcons::get_icon(&self.abs_path, cx))
+            .flatten()
+            .map(Icon::from_path)
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        Some("Hex Editor Opened")
+    }
+
+    fn is_dirty(&self, _: &App) -> bool {
+        self.buffer.is_dirty()
+    }
+
+    fn can_save(&self, _: &App) -> bool {
+        true
+    }
+
+    fn save(
+        &mut self,
+        _: SaveOptions,
+        _: Entity<Project>,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        let fs = self.fs.clone();
+        let abs_path = self.abs_path.clone();
+        let edits = self.buffer.edits().clone();
+        cx.spawn(async move |this, cx| {
+            // Edits only replace bytes in place, but the file system has no way to write a range
+            // of a file, so the whole file is rewritten.
+            let mut bytes = fs.load_bytes(&abs_path).await?;
+            apply_edits(&edits, &mut bytes);
+            fs.write(&abs_path, &bytes).await?;
+            this.update(cx, |this, cx| {
+                this.version += 1;
+                this.loading_pages.clear();
+                this.buffer.mark_saved(&edits);
+                cx.emit(HexEditorEvent::TitleChanged);
+                cx.notify();
+            })
+        })
+    }
+
+    fn reload(
+        &mut self,
+        _: Entity<Project>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        self.reload_from_disk(window, cx)
+    }
+}
+
+impl EventEmitter<HexEditorEvent> for HexEditor {}
+
+impl Focusable for HexEditor {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for HexEditor {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let focused = self.focus_handle.is_focused(window);
+
+        v_flex()
+            .key_context("HexEditor")
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::move_left))
+            .on_action(cx.listener(Self::move_right))
+            .on_action(cx.listener(Self::move_up))
+            .on_action(cx.listener(Self::move_down))
+            .on_action(cx.listener(Self::move_page_up))
+            .on_action(cx.listener(Self::move_page_down))
+            .on_action(cx.listener(Self::move_to_beginning))
+            .on_action(cx.listener(Self::move_to_end))
+            .on_action(cx.listener(Self::switch_column))
+            .on_action(cx.listener(Self::revert_byte))
+            .on_key_down(cx.listener(Self::key_down))
+            .size_full()
+            .py_1()
+            .bg(cx.theme().colors().editor_background)
+            .child(
+                uniform_list(
+                    "hex-editor-rows",
+                    self.row_count() as usize,
+                    cx.processor(move |this, range: Range<usize>, _, cx| {
+                        this.visible_row_count = range.len();
+                        this.load_pages(
+                            range.start as u64 * BYTES_PER_ROW..range.end as u64 * BYTES_PER_ROW,
+                            cx,
+                        );
+                        range
+                            .map(|row| this.render_row(row as u64, focused, cx))
+                            .collect::<Vec<_>>()
+                    }),
+                )
+                .track_scroll(self.scroll_handle.clone())
+                .flex_1(),
+            )
+            .when_some(self.error.clone(), |this, error| {
+                this.child(
+                    h_flex()
+                        .px_2()
+                        .child(Label::new(error).size(LabelSize::Small).color(Color::Error)),
+                )
+            })
+            .child(
+                h_flex()
+                    .px_2()
+                    .gap_2()
+                    .child(
+                        Label::new(format!(
+                            "Offset {:#X} of {:#X}",
+                            self.cursor,
+                            self.buffer.len()
+                        ))
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                    )
+                    .when(self.buffer.is_dirty(), |this| {
+                        this.child(
+                            Label::new(format!("{} modified bytes", self.buffer.edits().len()))
+                                .size(LabelSize::Small)
+                                .color(Color::Modified),
+                        )
+                    }),
+            )
+    }
+}
@@ -16,7 +16,7 @@ use http::request::Builder;
 use parking_lot::Mutex;
 #[cfg(feature = "test-support")]
 use std::fmt;
-use std::{any::type_name, sync::Arc};
+use std::{any::type_name, net::IpAddr, sync::Arc};
 pub use url::Url;
 
 #[derive(Default, Debug, Clone, PartialEq, Eq, Hash)]
@@ -344,6 +344,68 @@ pub fn read_no_proxy_from_env() -> Option<String> {
     ENV_VARS.iter().find_map(|var| std::env::var(var).ok())
 }
 
+/// Returns whether requests to `host` should bypass the proxy, given a comma-separated list of
+/// bypass rules in the format of the `NO_PROXY` environment variable.
+///
+/// A rule is either `*`, which matches every host, a domain, which matches the domain and its
+/// subdomains, an IP address, or an IP range in CIDR notation.
+pub fn is_proxy_bypassed(host: &str, no_proxy: &str) -> bool {
+    let host = host
+        .trim_start_matches('[')
+        .trim_end_matches(']')
+        .to_ascii_lowercase();
+    let host_ip = host.parse::<IpAddr>().ok();
+    no_proxy
+        .split(',')
+        .map(str::trim)
+        .filter(|rule| !rule.is_empty())
+        .any(|rule| {
+            if rule == "*" {
+                return true;
+            }
+            if let Some(host_ip) = host_ip {
+                return match rule.split_once('/') {
+                    Some((network, prefix_len)) => network
+                        .parse::<IpAddr>()
+                        .ok()
+                        .zip(prefix_len.parse::<u32>().ok())
+                        .is_some_and(|(network, prefix_len)| {
+                            ip_in_network(host_ip, network, prefix_len)
+                        }),
+                    None => rule.parse::<IpAddr>().is_ok_and(|ip| ip == host_ip),
+                };
+            }
+            let domain = rule
+                .trim_start_matches("*.")
+                .trim_start_matches('.')
+                .to_ascii_lowercase();
+            host == domain || host.ends_with(&format!(".{domain}"))
+        })
+}
+
+fn ip_in_network(ip: IpAddr, network: IpAddr, prefix_len: u32) -> bool {
+    fn matches_prefix(ip: u128, network: u128, prefix_len: u32, bits: u32) -> bool {
+        if prefix_len > bits {
+            return false;
+        }
+        let shift = bits - prefix_len;
+        shift >= bits || ip >> shift == network >> shift
+    }
+
+    match (ip, network) {
+        (IpAddr::V4(ip), IpAddr::V4(network)) => matches_prefix(
+            u32::from(ip).into(),
+            u32::from(network).into(),
+            prefix_len,
+            32,
+        ),
+        (IpAddr::V6(ip), IpAddr::V6(network)) => {
+            matches_prefix(ip.into(), network.into(), prefix_len, 128)
+        }
+        _ => false,
+    }
+}
+
 pub struct BlockedHttpClient;
 
 impl BlockedHttpClient {
@@ -480,3 +542,30 @@ impl HttpClient for FakeHttpClient {
         self
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_proxy_bypassed() {
+        let no_proxy = "localhost, .internal.example.com,*.corp, 10.0.0.0/8, ::1, 192.168.1.5";
+
+        assert!(is_proxy_bypassed("localhost", no_proxy));
+        assert!(is_proxy_bypassed("internal.example.com", no_proxy));
+        assert!(is_proxy_bypassed("api.internal.example.com", no_proxy));
+        assert!(is_proxy_bypassed("GIT.CORP", no_proxy));
+        assert!(is_proxy_bypassed("10.20.30.40", no_proxy));
+        assert!(is_proxy_bypassed("[::1]", no_proxy));
+        assert!(is_proxy_bypassed("192.168.1.5", no_proxy));
+
+        assert!(!is_proxy_bypassed("example.com", no_proxy));
+        assert!(!is_proxy_bypassed("notlocalhost", no_proxy));
+        assert!(!is_proxy_bypassed("11.0.0.1", no_proxy));
+        assert!(!is_proxy_bypassed("192.168.1.6", no_proxy));
+
+        assert!(is_proxy_bypassed("example.com", "*"));
+        assert!(is_proxy_bypassed("1.2.3.4", "0.0.0.0/0"));
+        assert!(!is_proxy_bypassed("example.com", ""));
+    }
+}
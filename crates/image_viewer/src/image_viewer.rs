@@ -5,17 +5,24 @@ use anyhow::Context as _;
 use editor::{EditorSettings, items::entry_git_aware_label_color};
 use file_icons::FileIcons;
 use gpui::{
-    AnyElement, App, Bounds, Context, Entity, EventEmitter, FocusHandle, Focusable,
-    InteractiveElement, IntoElement, ObjectFit, ParentElement, Render, Styled, Task, WeakEntity,
-    Window, canvas, div, fill, img, opaque_grey, point, size,
+    AnyElement, App, Bounds, Context, CursorStyle, Entity, EventEmitter, FocusHandle, Focusable,
+    InteractiveElement, IntoElement, MouseButton, MouseDownEvent, MouseMoveEvent, MouseUpEvent,
+    ObjectFit, ParentElement, Point, Render, ScrollWheelEvent, Size, Styled, Subscription, Task,
+    WeakEntity, Window, actions, canvas, div, fill, img, opaque_grey, point, size,
 };
+use image::RgbaImage;
 use language::{DiskState, File as _};
 use persistence::IMAGE_VIEWER;
-use project::{ImageItem, Project, ProjectPath, image_store::ImageItemEvent};
+use project::{
+    ImageItem, Project, ProjectPath,
+    git_store::{GitStoreEvent, RepositoryEvent},
+    image_store::ImageItemEvent,
+};
 use settings::Settings;
+use std::sync::Arc;
 use theme::Theme;
-use ui::prelude::*;
-use util::paths::PathExt;
+use ui::{IconButton, Tooltip, prelude::*};
+use util::{ResultExt as _, paths::PathExt};
 use workspace::{
     ItemId, ItemSettings, Pane, ToolbarItemLocation, Workspace, WorkspaceId, delete_unloaded_items,
     item::{BreadcrumbText, Item, ProjectItem, SerializableItem, TabContentParams},
@@ -24,10 +31,55 @@ use workspace::{
 pub use crate::image_info::*;
 pub use crate::image_viewer_settings::*;
 
+actions!(
+    image_viewer,
+    [
+        /// Zooms into the image.
+        ZoomIn,
+        /// Zooms out of the image.
+        ZoomOut,
+        /// Scales the image to fit within the view.
+        ZoomToFit,
+        /// Displays the image at its actual size.
+        ZoomToActualSize,
+        /// Toggles showing the image side by side with its version at git HEAD.
+        ToggleSideBySideDiff,
+        /// Toggles overlaying the image on its version at git HEAD.
+        ToggleOnionSkinDiff,
+    ]
+);
+
+const ZOOM_STEP: f32 = 1.25;
+const MIN_ZOOM: f32 = 0.05;
+const MAX_ZOOM: f32 = 64.;
+const ONION_SKIN_OPACITY_STEP: f32 = 0.1;
+
+/// How the image is compared with its version at git HEAD.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ImageDiffMode {
+    SideBySide,
+    OnionSkin,
+}
+
 pub struct ImageView {
     image_item: Entity<ImageItem>,
     project: Entity<Project>,
     focus_handle: FocusHandle,
+    /// The scale at which the image is displayed, or `None` to fit it within the view.
+    zoom: Option<f32>,
+    pan_offset: Point<Pixels>,
+    /// Where the mouse was pressed to pan the image, relative to the pan offset at that time.
+    pan_drag_origin: Option<Point<Pixels>>,
+    viewport_bounds: Bounds<Pixels>,
+    image_bounds: Bounds<Pixels>,
+    pixels: Option<Arc<RgbaImage>>,
+    hovered_pixel: Option<Point<u32>>,
+    head_image: Option<Arc<gpui::Image>>,
+    diff_mode: Option<ImageDiffMode>,
+    onion_skin_opacity: f32,
+    decode_pixels_task: Task<()>,
+    load_head_image_task: Task<()>,
+    _subscriptions: Vec<Subscription>,
 }
 
 impl ImageView {
@@ -37,21 +89,53 @@ impl ImageView {
         window: &mut Window,
         cx: &mut Context<Self>,
     ) -> Self {
-        cx.subscribe(&image_item, Self::on_image_event).detach();
         cx.on_release_in(window, |this, window, cx| {
             let image_data = this.image_item.read(cx).image.clone();
             if let Some(image) = image_data.clone().get_render_image(window, cx) {
                 cx.drop_image(image, None);
             }
             image_data.remove_asset(cx);
+            if let Some(head_image) = this.head_image.take() {
+                if let Some(image) = head_image.clone().get_render_image(window, cx) {
+                    cx.drop_image(image, None);
+                }
+                head_image.remove_asset(cx);
+            }
         })
         .detach();
 
-        Self {
+        let subscriptions = vec![
+            cx.subscribe(&image_item, Self::on_image_event),
+            cx.subscribe(project.read(cx).git_store(), |this, _, event, cx| {
+                if let GitStoreEvent::RepositoryUpdated(_, RepositoryEvent::Updated { .. }, _) =
+                    event
+                {
+                    this.load_head_image(cx);
+                }
+            }),
+        ];
+
+        let mut this = Self {
             image_item,
             project,
             focus_handle: cx.focus_handle(),
-        }
+            zoom: None,
+            pan_offset: Point::default(),
+            pan_drag_origin: None,
+            viewport_bounds: Bounds::default(),
+            image_bounds: Bounds::default(),
+            pixels: None,
+            hovered_pixel: None,
+            head_image: None,
+            diff_mode: None,
+            onion_skin_opacity: 0.5,
+            decode_pixels_task: Task::ready(()),
+            load_head_image_task: Task::ready(()),
+            _subscriptions: subscriptions,
+        };
+        this.decode_pixels(cx);
+        this.load_head_image(cx);
+        this
     }
 
     fn on_image_event(
@@ -64,12 +148,227 @@ impl ImageView {
             ImageItemEvent::MetadataUpdated
             | ImageItemEvent::FileHandleChanged
             | ImageItemEvent::Reloaded => {
+                if matches!(event, ImageItemEvent::Reloaded) {
+                    self.decode_pixels(cx);
+                    self.load_head_image(cx);
+                }
                 cx.emit(ImageViewEvent::TitleChanged);
                 cx.notify();
             }
             ImageItemEvent::ReloadNeeded => {}
         }
     }
+
+    /// Decodes the image into pixels, so that the color under the mouse can be shown.
+    fn decode_pixels(&mut self, cx: &mut Context<Self>) {
+        let image = self.image_item.read(cx).image.clone();
+        self.decode_pixels_task = cx.spawn(async move |this, cx| {
+            let pixels = cx
+                .background_spawn(async move {
+                    if image.format == gpui::ImageFormat::Svg {
+                        return None;
+                    }
+                    image::load_from_memory(&image.bytes)
+                        .log_err()
+                        .map(|decoded| Arc::new(decoded.into_rgba8()))
+                })
+                .await;
+            this.update(cx, |this, cx| {
+                this.pixels = pixels;
+                cx.notify();
+            })
+            .ok();
+        });
+    }
+
+    /// Loads the version of the image at git HEAD, if it differs from the current one.
+    fn load_head_image(&mut self, cx: &mut Context<Self>) {
+        let image_item = self.image_item.read(cx);
+        let image = image_item.image.clone();
+        let project_path = image_item.project_path(cx);
+        let Some((repository, repo_path)) = self
+            .project
+            .read(cx)
+            .git_store()
+            .read(cx)
+            .repository_and_path_for_project_path(&project_path, cx)
+        else {
+            return;
+        };
+        let committed_bytes = repository.update(cx, |repository, _| {
+            repository.load_committed_bytes(repo_path)
+        });
+
+        self.load_head_image_task = cx.spawn(async move |this, cx| {
+            let head_image = match committed_bytes.await {
+                Ok(Ok(Some(bytes))) if bytes != image.bytes => {
+                    Some(Arc::new(gpui::Image::from_bytes(image.format, bytes)))
+                }
+                Ok(Ok(_)) => None,
+                Ok(Err(error)) => {
+                    log::error!("failed to load image at HEAD: {error:#}");
+                    None
+                }
+                Err(_) => None,
+            };
+            this.update(cx, |this, cx| {
+                if head_image.is_none() {
+                    this.diff_mode = None;
+                }
+                this.head_image = head_image;
+                cx.notify();
+            })
+            .ok();
+        });
+    }
+
+    fn image_size(&self, cx: &App) -> Option<Size<u32>> {
+        if let Some(metadata) = &self.image_item.read(cx).image_metadata {
+            return Some(size(metadata.width, metadata.height));
+        }
+        let pixels = self.pixels.as_ref()?;
+        Some(size(pixels.width(), pixels.height()))
+    }
+
+    fn fit_zoom(&self, cx: &App) -> Option<f32> {
+        fit_zoom(self.image_size(cx)?, self.viewport_bounds.size)
+    }
+
+    fn current_zoom(&self, cx: &App) -> Option<f32> {
+        self.zoom.or_else(|| self.fit_zoom(cx))
+    }
+
+    /// Changes the zoom, keeping the point of the image under `anchor` in place.
+    fn set_zoom(&mut self, zoom: f32, anchor: Option<Point<Pixels>>, cx: &mut Context<Self>) {
+        let Some(previous_zoom) = self.current_zoom(cx) else {
+            return;
+        };
+        let zoom = zoom.clamp(MIN_ZOOM, MAX_ZOOM);
+        let anchor = anchor
+            .map(|anchor| anchor - self.viewport_bounds.center())
+            .unwrap_or_default();
+        self.pan_offset = pan_offset_for_zoom(self.pan_offset, anchor, previous_zoom, zoom);
+        self.zoom = Some(zoom);
+        cx.notify();
+    }
+
+    fn zoom_in(&mut self, _: &ZoomIn, _: &mut Window, cx: &mut Context<Self>) {
+        if let Some(zoom) = self.current_zoom(cx) {
+            self.set_zoom(zoom * ZOOM_STEP, None, cx);
+        }
+    }
+
+    fn zoom_out(&mut self, _: &ZoomOut, _: &mut Window, cx: &mut Context<Self>) {
+        if let Some(zoom) = self.current_zoom(cx) {
+            self.set_zoom(zoom / ZOOM_STEP, None, cx);
+        }
+    }
+
+    fn zoom_to_fit(&mut self, _: &ZoomToFit, _: &mut Window, cx: &mut Context<Self>) {
+        self.zoom = None;
+        self.pan_offset = Point::default();
+        cx.notify();
+    }
+
+    fn zoom_to_actual_size(
+        &mut self,
+        _: &ZoomToActualSize,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.set_zoom(1., None, cx);
+    }
+
+    fn toggle_side_by_side_diff(
+        &mut self,
+        _: &ToggleSideBySideDiff,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.toggle_diff_mode(ImageDiffMode::SideBySide, cx);
+    }
+
+    fn toggle_onion_skin_diff(
+        &mut self,
+        _: &ToggleOnionSkinDiff,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.toggle_diff_mode(ImageDiffMode::OnionSkin, cx);
+    }
+
+    fn toggle_diff_mode(&mut self, mode: ImageDiffMode, cx: &mut Context<Self>) {
+        if self.head_image.is_none() {
+            return;
+        }
+        self.diff_mode = if self.diff_mode == Some(mode) {
+            None
+        } else {
+            Some(mode)
+        };
+        cx.notify();
+    }
+
+    fn adjust_onion_skin_opacity(&mut self, delta: f32, cx: &mut Context<Self>) {
+        self.onion_skin_opacity = (self.onion_skin_opacity + delta).clamp(0., 1.);
+        cx.notify();
+    }
+
+    fn on_scroll_wheel(
+        &mut self,
+        event: &ScrollWheelEvent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let delta = event.delta.pixel_delta(window.line_height());
+        if event.modifiers.secondary() {
+            if let Some(zoom) = self.current_zoom(cx)
+                && delta.y != px(0.)
+            {
+                let factor = if delta.y > px(0.) {
+                    ZOOM_STEP
+                } else {
+                    1. / ZOOM_STEP
+                };
+                self.set_zoom(zoom * factor, Some(event.position), cx);
+            }
+        } else if self.zoom.is_some() {
+            self.pan_offset += delta;
+            cx.notify();
+        }
+    }
+
+    fn on_mouse_down(&mut self, event: &MouseDownEvent, _: &mut Window, cx: &mut Context<Self>) {
+        if self.zoom.is_some() {
+            self.pan_drag_origin = Some(event.position - self.pan_offset);
+            cx.notify();
+        }
+    }
+
+    fn on_mouse_up(&mut self, _: &MouseUpEvent, _: &mut Window, cx: &mut Context<Self>) {
+        if self.pan_drag_origin.take().is_some() {
+            cx.notify();
+        }
+    }
+
+    fn on_mouse_move(&mut self, event: &MouseMoveEvent, _: &mut Window, cx: &mut Context<Self>) {
+        if let Some(origin) = self.pan_drag_origin {
+            if event.pressed_button == Some(MouseButton::Left) {
+                self.pan_offset = event.position - origin;
+                cx.notify();
+            } else {
+                self.pan_drag_origin = None;
+            }
+        }
+
+        let hovered_pixel = self
+            .image_size(cx)
+            .and_then(|image_size| pixel_at(event.position, self.image_bounds, image_size));
+        if hovered_pixel != self.hovered_pixel {
+            self.hovered_pixel = hovered_pixel;
+            cx.notify();
+        }
+    }
 }
 
 pub enum ImageViewEvent {
@@ -172,17 +471,15 @@ impl Item for ImageView {
     fn clone_on_split(
         &self,
         _workspace_id: Option<WorkspaceId>,
-        _: &mut Window,
+        window: &mut Window,
         cx: &mut Context<Self>,
     ) -> Option<Entity<Self>>
     where
         Self: Sized,
     {
-        Some(cx.new(|cx| Self {
-            image_item: self.image_item.clone(),
-            project: self.project.clone(),
-            focus_handle: cx.focus_handle(),
-        }))
+        let image_item = self.image_item.clone();
+        let project = self.project.clone();
+        Some(cx.new(|cx| Self::new(image_item, project, window, cx)))
     }
 
     fn has_deleted_file(&self, cx: &App) -> bool {
@@ -293,82 +590,380 @@ impl Focusable for ImageView {
     }
 }
 
+impl ImageView {
+    /// Renders one pane of the view, stacking the given images with their opacities.
+    ///
+    /// Only the primary pane records its bounds, which drive zooming and pixel inspection.
+    fn render_image_pane(
+        &self,
+        id: &'static str,
+        layers: Vec<(Arc<gpui::Image>, f32)>,
+        label: Option<&'static str>,
+        is_primary: bool,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let this = cx.entity();
+        let zoom = self.current_zoom(cx);
+        let frame = match zoom.zip(self.image_size(cx)) {
+            Some((zoom, image_size)) => div()
+                .relative()
+                .flex_none()
+                .w(px(image_size.width as f32 * zoom))
+                .h(px(image_size.height as f32 * zoom))
+                .left(self.pan_offset.x)
+                .top(self.pan_offset.y)
+                .children(layers.into_iter().map(|(image, opacity)| {
+                    img(image)
+                        .object_fit(ObjectFit::Contain)
+                        .absolute()
+                        .top_0()
+                        .left_0()
+                        .size_full()
+                        .opacity(opacity)
+                })),
+            // Until the image has been measured, it is shown scaled down to fit, as the size of
+            // the frame can't be determined.
+            None => div().relative().max_w_full().max_h_full().children(
+                layers
+                    .into_iter()
+                    .enumerate()
+                    .map(|(ix, (image, opacity))| {
+                        img(image)
+                            .object_fit(ObjectFit::ScaleDown)
+                            .max_w_full()
+                            .max_h_full()
+                            .opacity(opacity)
+                            .when(ix > 0, |image| {
+                                image.absolute().top_0().left_0().size_full()
+                            })
+                    }),
+            ),
+        };
+
+        div()
+            .id(id)
+            .relative()
+            .flex()
+            .justify_center()
+            .items_center()
+            .size_full()
+            .overflow_hidden()
+            .when(is_primary, |pane| {
+                let this = this.clone();
+                pane.child(
+                    canvas(
+                        move |bounds, _, cx| {
+                            this.update(cx, |this, _| this.viewport_bounds = bounds);
+                        },
+                        |_, _, _, _| {},
+                    )
+                    .absolute()
+                    .size_full(),
+                )
+            })
+            .child(
+                frame.child(
+                    canvas(
+                        move |bounds, _, cx| {
+                            if is_primary {
+                                this.update(cx, |this, _| this.image_bounds = bounds);
+                            }
+                        },
+                        |bounds, _, window, _| paint_checkered_background(bounds, window),
+                    )
+                    .border_2()
+                    .border_color(cx.theme().styles.colors.border)
+                    .size_full()
+                    .absolute()
+                    .top_0()
+                    .left_0(),
+                ),
+            )
+            .when_some(label, |pane, label| {
+                pane.child(
+                    div()
+                        .absolute()
+                        .top_2()
+                        .left_2()
+                        .px_1p5()
+                        .rounded_sm()
+                        .elevation_1(cx)
+                        .child(Label::new(label).size(LabelSize::Small)),
+                )
+            })
+    }
+
+    fn render_status(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let zoom = self.current_zoom(cx);
+        let hovered_color = self.hovered_pixel.and_then(|position| {
+            let pixel = self
+                .pixels
+                .as_ref()?
+                .get_pixel_checked(position.x, position.y)?;
+            Some(pixel.0)
+        });
+
+        h_flex()
+            .absolute()
+            .bottom_2()
+            .left_2()
+            .gap_2()
+            .px_1p5()
+            .rounded_sm()
+            .elevation_1(cx)
+            .when_some(zoom, |status, zoom| {
+                status.child(
+                    Label::new(format!("{:.0}%", zoom * 100.))
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                )
+            })
+            .when_some(self.hovered_pixel, |status, position| {
+                status.child(
+                    Label::new(format!("{}, {}", position.x, position.y))
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                )
+            })
+            .when_some(hovered_color, |status, [red, green, blue, alpha]| {
+                status
+                    .child(
+                        div()
+                            .size_3()
+                            .border_1()
+                            .border_color(cx.theme().colors().border)
+                            .bg(gpui::Rgba {
+                                r: red as f32 / 255.,
+                                g: green as f32 / 255.,
+                                b: blue as f32 / 255.,
+                                a: alpha as f32 / 255.,
+                            }),
+                    )
+                    .child(
+                        Label::new(format!("#{red:02X}{green:02X}{blue:02X}{alpha:02X}"))
+                            .size(LabelSize::Small)
+                            .buffer_font(cx),
+                    )
+            })
+    }
+
+    fn render_diff_controls(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        h_flex()
+            .absolute()
+            .top_2()
+            .right_2()
+            .gap_1()
+            .p_0p5()
+            .rounded_sm()
+            .elevation_1(cx)
+            .child(
+                Button::new("side-by-side-diff", "Side by Side")
+                    .label_size(LabelSize::Small)
+                    .toggle_state(self.diff_mode == Some(ImageDiffMode::SideBySide))
+                    .tooltip(Tooltip::text("Compare with the version at HEAD"))
+                    .on_click(cx.listener(|this, _, _, cx| {
+                        this.toggle_diff_mode(ImageDiffMode::SideBySide, cx)
+                    })),
+            )
+            .child(
+                Button::new("onion-skin-diff", "Onion Skin")
+                    .label_size(LabelSize::Small)
+                    .toggle_state(self.diff_mode == Some(ImageDiffMode::OnionSkin))
+                    .tooltip(Tooltip::text("Overlay on the version at HEAD"))
+                    .on_click(cx.listener(|this, _, _, cx| {
+                        this.toggle_diff_mode(ImageDiffMode::OnionSkin, cx)
+                    })),
+            )
+            .when(
+                self.diff_mode == Some(ImageDiffMode::OnionSkin),
+                |controls| {
+                    controls
+                        .child(
+                            IconButton::new("decrease-onion-skin-opacity", IconName::Dash)
+                                .icon_size(IconSize::Small)
+                                .on_click(cx.listener(|this, _, _, cx| {
+                                    this.adjust_onion_skin_opacity(-ONION_SKIN_OPACITY_STEP, cx)
+                                })),
+                        )
+                        .child(
+                            Label::new(format!("{:.0}%", self.onion_skin_opacity * 100.))
+                                .size(LabelSize::Small),
+                        )
+                        .child(
+                            IconButton::new("increase-onion-skin-opacity", IconName::Plus)
+                                .icon_size(IconSize::Small)
+                                .on_click(cx.listener(|this, _, _, cx| {
+                                    this.adjust_onion_skin_opacity(ONION_SKIN_OPACITY_STEP, cx)
+                                })),
+                        )
+                },
+            )
+    }
+}
+
+/// The scale at which an image fits within the view, without being enlarged.
+fn fit_zoom(image_size: Size<u32>, viewport_size: Size<Pixels>) -> Option<f32> {
+    if image_size.width == 0
+        || image_size.height == 0
+        || viewport_size.width <= px(0.)
+        || viewport_size.height <= px(0.)
+    {
+        return None;
+    }
+    Some(
+        (f32::from(viewport_size.width) / image_size.width as f32)
+            .min(f32::from(viewport_size.height) / image_size.height as f32)
+            .min(1.),
+    )
+}
+
+/// The pan offset that keeps the point of the image under `anchor` in place when zooming. Both
+/// `anchor` and the pan offset are relative to the center of the view.
+fn pan_offset_for_zoom(
+    pan_offset: Point<Pixels>,
+    anchor: Point<Pixels>,
+    previous_zoom: f32,
+    zoom: f32,
+) -> Point<Pixels> {
+    anchor - (anchor - pan_offset) * (zoom / previous_zoom)
+}
+
+/// The pixel of an image displayed within `image_bounds` that lies under `position`.
+fn pixel_at(
+    position: Point<Pixels>,
+    image_bounds: Bounds<Pixels>,
+    image_size: Size<u32>,
+) -> Option<Point<u32>> {
+    if !image_bounds.contains(&position)
+        || image_bounds.size.width <= px(0.)
+        || image_bounds.size.height <= px(0.)
+    {
+        return None;
+    }
+    let x =
+        (position.x - image_bounds.origin.x) / image_bounds.size.width * image_size.width as f32;
+    let y =
+        (position.y - image_bounds.origin.y) / image_bounds.size.height * image_size.height as f32;
+    Some(point(
+        (x as u32).min(image_size.width.saturating_sub(1)),
+        (y as u32).min(image_size.height.saturating_sub(1)),
+    ))
+}
+
+fn paint_checkered_background(bounds: Bounds<Pixels>, window: &mut Window) {
+    let square_size: f32 = 32.0;
+
+    let start_y = bounds.origin.y.into();
+    let height: f32 = bounds.size.height.into();
+    let start_x = bounds.origin.x.into();
+    let width: f32 = bounds.size.width.into();
+
+    let mut y = start_y;
+    let mut x = start_x;
+    let mut color_swapper = true;
+    // draw checkerboard pattern
+    while y < start_y + height {
+        // Keeping track of the grid in order to be resilient to resizing
+        let start_swap = color_swapper;
+        while x < start_x + width {
+            // Clamp square dimensions to not exceed bounds
+            let square_width = square_size.min(start_x + width - x);
+            let square_height = square_size.min(start_y + height - y);
+
+            let rect = Bounds::new(
+                point(px(x), px(y)),
+                size(px(square_width), px(square_height)),
+            );
+
+            let color = if color_swapper {
+                opaque_grey(0.6, 0.4)
+            } else {
+                opaque_grey(0.7, 0.4)
+            };
+
+            window.paint_quad(fill(rect, color));
+            color_swapper = !color_swapper;
+            x += square_size;
+        }
+        x = start_x;
+        color_swapper = !start_swap;
+        y += square_size;
+    }
+}
+
 impl Render for ImageView {
     fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
         let image = self.image_item.read(cx).image.clone();
-        let checkered_background =
-            |bounds: Bounds<Pixels>, _, window: &mut Window, _cx: &mut App| {
-                let square_size: f32 = 32.0;
-
-                let start_y = bounds.origin.y.into();
-                let height: f32 = bounds.size.height.into();
-                let start_x = bounds.origin.x.into();
-                let width: f32 = bounds.size.width.into();
-
-                let mut y = start_y;
-                let mut x = start_x;
-                let mut color_swapper = true;
-                // draw checkerboard pattern
-                while y < start_y + height {
-                    // Keeping track of the grid in order to be resilient to resizing
-                    let start_swap = color_swapper;
-                    while x < start_x + width {
-                        // Clamp square dimensions to not exceed bounds
-                        let square_width = square_size.min(start_x + width - x);
-                        let square_height = square_size.min(start_y + height - y);
-
-                        let rect = Bounds::new(
-                            point(px(x), px(y)),
-                            size(px(square_width), px(square_height)),
-                        );
-
-                        let color = if color_swapper {
-                            opaque_grey(0.6, 0.4)
-                        } else {
-                            opaque_grey(0.7, 0.4)
-                        };
-
-                        window.paint_quad(fill(rect, color));
-                        color_swapper = !color_swapper;
-                        x += square_size;
-                    }
-                    x = start_x;
-                    color_swapper = !start_swap;
-                    y += square_size;
-                }
-            };
+        let head_image = self.head_image.clone().filter(|_| self.diff_mode.is_some());
 
-        div().track_focus(&self.focus_handle(cx)).size_full().child(
-            div()
-                .flex()
-                .justify_center()
-                .items_center()
-                .w_full()
-                // TODO: In browser based Tailwind & Flex this would be h-screen and we'd use w-full
-                .h_full()
+        let content = match (self.diff_mode, head_image) {
+            (Some(ImageDiffMode::SideBySide), Some(head_image)) => h_flex()
+                .size_full()
+                .gap_px()
+                .bg(cx.theme().colors().border)
                 .child(
                     div()
-                        .relative()
-                        .max_w_full()
-                        .max_h_full()
-                        .child(
-                            canvas(|_, _, _| (), checkered_background)
-                                .border_2()
-                                .border_color(cx.theme().styles.colors.border)
-                                .size_full()
-                                .absolute()
-                                .top_0()
-                                .left_0(),
-                        )
-                        .child(
-                            img(image)
-                                .object_fit(ObjectFit::ScaleDown)
-                                .max_w_full()
-                                .max_h_full()
-                                .id("img"),
-                        ),
-                ),
-        )
+                        .size_full()
+                        .bg(cx.theme().colors().editor_background)
+                        .child(self.render_image_pane(
+                            "head-image",
+                            vec![(head_image, 1.)],
+                            Some("HEAD"),
+                            false,
+                            cx,
+                        )),
+                )
+                .child(
+                    div()
+                        .size_full()
+                        .bg(cx.theme().colors().editor_background)
+                        .child(self.render_image_pane(
+                            "image",
+                            vec![(image, 1.)],
+                            Some("Working Tree"),
+                            true,
+                            cx,
+                        )),
+                )
+                .into_any_element(),
+            (Some(ImageDiffMode::OnionSkin), Some(head_image)) => self
+                .render_image_pane(
+                    "image",
+                    vec![(head_image, 1.), (image, self.onion_skin_opacity)],
+                    None,
+                    true,
+                    cx,
+                )
+                .into_any_element(),
+            _ => self
+                .render_image_pane("image", vec![(image, 1.)], None, true, cx)
+                .into_any_element(),
+        };
+
+        div()
+            .key_context("ImageViewer")
+            .track_focus(&self.focus_handle(cx))
+            .on_action(cx.listener(Self::zoom_in))
+            .on_action(cx.listener(Self::zoom_out))
+            .on_action(cx.listener(Self::zoom_to_fit))
+            .on_action(cx.listener(Self::zoom_to_actual_size))
+            .on_action(cx.listener(Self::toggle_side_by_side_diff))
+            .on_action(cx.listener(Self::toggle_onion_skin_diff))
+            .on_scroll_wheel(cx.listener(Self::on_scroll_wheel))
+            .on_mouse_down(MouseButton::Left, cx.listener(Self::on_mouse_down))
+            .on_mouse_up(MouseButton::Left, cx.listener(Self::on_mouse_up))
+            .on_mouse_up_out(MouseButton::Left, cx.listener(Self::on_mouse_up))
+            .on_mouse_move(cx.listener(Self::on_mouse_move))
+            .relative()
+            .size_full()
+            .when(self.pan_drag_origin.is_some(), |this| {
+                this.cursor(CursorStyle::ClosedHand)
+            })
+            .child(content)
+            .child(self.render_status(cx))
+            .when(self.head_image.is_some(), |this| {
+                this.child(self.render_diff_controls(cx))
+            })
     }
 }
 
@@ -447,3 +1042,63 @@ mod persistence {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fit_zoom() {
+        // Large images are scaled down along their most constrained dimension.
+        assert_eq!(
+            fit_zoom(size(400, 100), size(px(200.), px(200.))),
+            Some(0.5)
+        );
+        assert_eq!(
+            fit_zoom(size(100, 800), size(px(200.), px(200.))),
+            Some(0.25)
+        );
+        // Small images are never enlarged.
+        assert_eq!(fit_zoom(size(50, 50), size(px(200.), px(200.))), Some(1.));
+        assert_eq!(fit_zoom(size(0, 50), size(px(200.), px(200.))), None);
+        assert_eq!(fit_zoom(size(50, 50), size(px(0.), px(200.))), None);
+    }
+
+    #[test]
+    fn test_pan_offset_for_zoom() {
+        // Zooming around the center of the view scales the existing pan offset.
+        assert_eq!(
+            pan_offset_for_zoom(point(px(10.), px(-20.)), Point::default(), 1., 2.),
+            point(px(20.), px(-40.))
+        );
+        // The point of the image under the anchor stays under it.
+        let pan_offset = point(px(0.), px(0.));
+        let anchor = point(px(100.), px(50.));
+        let new_offset = pan_offset_for_zoom(pan_offset, anchor, 1., 4.);
+        assert_eq!(new_offset, point(px(-300.), px(-150.)));
+        assert_eq!((anchor - new_offset) * (1. / 4.), anchor - pan_offset);
+    }
+
+    #[test]
+    fn test_pixel_at() {
+        let bounds = Bounds::new(point(px(100.), px(100.)), size(px(200.), px(100.)));
+        let image_size = size(20, 10);
+        assert_eq!(
+            pixel_at(point(px(100.), px(100.)), bounds, image_size),
+            Some(point(0, 0))
+        );
+        assert_eq!(
+            pixel_at(point(px(215.), px(155.)), bounds, image_size),
+            Some(point(11, 5))
+        );
+        assert_eq!(
+            pixel_at(point(px(299.9), px(199.9)), bounds, image_size),
+            Some(point(19, 9))
+        );
+        assert_eq!(pixel_at(point(px(99.), px(150.)), bounds, image_size), None);
+        assert_eq!(
+            pixel_at(point(px(150.), px(150.)), Bounds::default(), image_size),
+            None
+        );
+    }
+}
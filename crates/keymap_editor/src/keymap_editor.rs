@@ -228,6 +228,9 @@ impl ConflictOrigin {
 #[derive(Default)]
 struct ConflictState {
     conflicts: Vec<Option<ConflictOrigin>>,
+    /// For each binding, the index of a binding with the same keystrokes in a narrower context
+    /// that takes precedence over it wherever that narrower context applies.
+    shadows: Vec<Option<usize>>,
     keybind_mapping: ConflictKeybindMapping,
     has_user_conflicts: bool,
 }
@@ -294,8 +297,42 @@ impl ConflictState {
             }
         }
 
+        let mut shadows = vec![None; largest_index + 1];
+        for entries in action_keybind_mapping.values() {
+            for (broader_predicate, broader_origins) in entries {
+                let Some(broader_predicate) = broader_predicate else {
+                    continue;
+                };
+                for (narrower_predicate, narrower_origins) in entries {
+                    let Some(narrower_predicate) = narrower_predicate else {
+                        continue;
+                    };
+                    let Some(shadowing_origin) = narrower_origins.first() else {
+                        continue;
+                    };
+                    if !context_shadows(broader_predicate, narrower_predicate) {
+                        continue;
+                    }
+                    // Only a user binding that's shadowed by one for a different action is
+                    // reported. The default keymaps deliberately refine broad bindings in
+                    // narrower contexts, as users do when they override a default binding.
+                    let shadowing_action = key_bindings[shadowing_origin.index].action();
+                    for origin in broader_origins {
+                        if origin.override_source == KeybindSource::User
+                            && !key_bindings[origin.index]
+                                .action()
+                                .is_same_action(shadowing_action)
+                        {
+                            shadows[origin.index].get_or_insert(shadowing_origin.index);
+                        }
+                    }
+                }
+            }
+        }
+
         Self {
             conflicts,
+            shadows,
             keybind_mapping: action_keybind_mapping,
             has_user_conflicts,
         }
@@ -345,8 +382,12 @@ impl ConflictState {
             .is_some_and(|conflict| conflict.is_user_keybind_conflict())
     }
 
+    fn shadowing_binding_for_idx(&self, idx: usize) -> Option<usize> {
+        self.shadows.get(idx).copied().flatten()
+    }
+
     fn any_user_binding_conflicts(&self) -> bool {
-        self.has_user_conflicts
+        self.has_user_conflicts || self.shadows.iter().any(Option::is_some)
     }
 }
 
@@ -562,8 +603,11 @@ impl KeymapEditor {
             match this.filter_state {
                 FilterState::Conflicts => {
                     matches.retain(|candidate| {
-                        this.keybinding_conflict_state
-                            .has_user_conflict(candidate.candidate_id)
+                        let conflict_state = &this.keybinding_conflict_state;
+                        conflict_state.has_user_conflict(candidate.candidate_id)
+                            || conflict_state
+                                .shadowing_binding_for_idx(candidate.candidate_id)
+                                .is_some()
                     });
                 }
                 FilterState::All => {}
@@ -639,6 +683,18 @@ impl KeymapEditor {
         })
     }
 
+    fn get_shadowing_context(&self, row_index: usize) -> Option<SharedString> {
+        let candidate = self.matches.get(row_index)?;
+        let shadowing_index = self
+            .keybinding_conflict_state
+            .shadowing_binding_for_idx(candidate.candidate_id)?;
+        self.keybindings
+            .get(shadowing_index)?
+            .context()?
+            .local()
+            .cloned()
+    }
+
     fn process_bindings(
         json_language: Arc<Language>,
         zed_keybind_context_language: Arc<Language>,
@@ -949,6 +1005,7 @@ impl KeymapEditor {
         &self,
         index: usize,
         conflict: Option<ConflictOrigin>,
+        shadowing_context: Option<SharedString>,
         cx: &mut Context<Self>,
     ) -> IconButton {
         if self.filter_state != FilterState::Conflicts
@@ -1012,6 +1069,33 @@ impl KeymapEditor {
                         }
                     }))
             }
+        } else if self.filter_state != FilterState::Conflicts
+            && let Some(shadowing_context) = shadowing_context
+        {
+            let meta: SharedString = format!(
+                "This binding is shadowed by a binding in the narrower context `{shadowing_context}`.\nUse alt+click to edit this binding"
+            )
+            .into();
+            base_button_style(index, IconName::Warning)
+                .icon_color(Color::Warning)
+                .tooltip(move |window, cx| {
+                    Tooltip::with_meta(
+                        "Show matching keybinds",
+                        Some(&ShowMatchingKeybinds),
+                        meta.clone(),
+                        window,
+                        cx,
+                    )
+                })
+                .on_click(cx.listener(move |this, click: &ClickEvent, window, cx| {
+                    this.select_index(index, None, window, cx);
+                    if click.modifiers().alt {
+                        this.open_edit_keybinding_modal(false, window, cx);
+                    } else {
+                        this.show_matching_keystrokes(&Default::default(), window, cx);
+                    }
+                    cx.stop_propagation();
+                }))
         } else {
             base_button_style(index, IconName::Pencil)
                 .visible_on_hover(if self.selected_index == Some(index) {
@@ -1335,6 +1419,13 @@ struct ActionInformation {
 }
 
 impl ActionInformation {
+    /// Whether both run the same action with the same arguments.
+    fn is_same_action(&self, other: &Self) -> bool {
+        self.name == other.name
+            && self.arguments.as_ref().map(|arguments| &arguments.text)
+                == other.arguments.as_ref().map(|arguments| &arguments.text)
+    }
+
     fn new(
         action_name: &'static str,
         action_arguments: Option<SyntaxHighlightedText>,
@@ -1775,7 +1866,13 @@ impl Render for KeymapEditor {
                                         !conflict.is_user_keybind_conflict()
                                     });
 
-                                    let icon = this.create_row_button(index, conflict, cx);
+                                    let shadowing_context = this.get_shadowing_context(index);
+                                    let icon = this.create_row_button(
+                                        index,
+                                        conflict,
+                                        shadowing_context,
+                                        cx,
+                                    );
 
                                     let action = div()
                                         .id(("keymap action", index))
@@ -1939,6 +2036,7 @@ impl Render for KeymapEditor {
                                                 let context = overriding_binding.and_then(|binding| {
                                                     match conflict.override_source {
                                                         KeybindSource::User  => Some("your keymap"),
+                                                        KeybindSource::Project => Some("the project keymap"),
                                                         KeybindSource::Vim => Some("the vim keymap"),
                                                         KeybindSource::Base => Some("your base keymap"),
                                                         _ => {
@@ -3148,6 +3246,15 @@ fn collect_contexts_from_assets() -> Vec<SharedString> {
     contexts
 }
 
+/// Whether a binding in the `narrower` context takes precedence over one with the same
+/// keystrokes in the `broader` context wherever the narrower context applies.
+fn context_shadows(
+    broader: &gpui::KeyBindingContextPredicate,
+    narrower: &gpui::KeyBindingContextPredicate,
+) -> bool {
+    broader.is_superset(narrower) && !normalized_ctx_eq(broader, narrower)
+}
+
 fn normalized_ctx_eq(
     a: &gpui::KeyBindingContextPredicate,
     b: &gpui::KeyBindingContextPredicate,
@@ -3381,6 +3488,27 @@ mod persistence {
 mod tests {
     use super::*;
 
+    #[test]
+    fn context_shadowing() {
+        #[track_caller]
+        fn shadows(broader: &str, narrower: &str) -> bool {
+            let broader = gpui::KeyBindingContextPredicate::parse(broader)
+                .expect("Failed to parse broader keybinding context");
+            let narrower = gpui::KeyBindingContextPredicate::parse(narrower)
+                .expect("Failed to parse narrower keybinding context");
+            context_shadows(&broader, &narrower)
+        }
+
+        assert!(shadows("Editor", "Editor && vim_mode == normal"));
+        assert!(shadows("Editor", "Workspace > Editor"));
+        assert!(shadows("Editor || Terminal", "Terminal"));
+
+        assert!(!shadows("Editor", "Editor"));
+        assert!(!shadows("a && b", "b && a"));
+        assert!(!shadows("Editor && vim_mode == normal", "Editor"));
+        assert!(!shadows("Editor", "Terminal"));
+    }
+
     #[test]
     fn normalized_ctx_cmp() {
         #[track_caller]
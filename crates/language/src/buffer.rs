@@ -371,6 +371,11 @@ pub trait File: Send + Sync + Any {
 
     /// Return whether Zed considers this to be a private file.
     fn is_private(&self) -> bool;
+
+    /// Returns the size of the file on disk in bytes, if it is known.
+    fn disk_size(&self, _cx: &App) -> Option<u64> {
+        None
+    }
 }
 
 /// The file's storage status - whether it's stored (`Present`), and if so when it was last
@@ -1327,16 +1332,25 @@ impl Buffer {
         let (tx, rx) = futures::channel::oneshot::channel();
         let prev_version = self.text.version();
         self.reload_task = Some(cx.spawn(async move |this, cx| {
-            let Some((new_mtime, new_text)) = this.update(cx, |this, cx| {
+            let Some((new_mtime, new_bytes, encoding)) = this.update(cx, |this, cx| {
                 let file = this.file.as_ref()?.as_local()?;
+                let encoding = language_settings(
+                    this.language().map(|language| language.name()),
+                    this.file.as_ref(),
+                    cx,
+                )
+                .encoding;
 
-                Some((file.disk_state().mtime(), file.load(cx)))
+                Some((file.disk_state().mtime(), file.load_bytes(cx), encoding))
             })?
             else {
                 return Ok(());
             };
 
-            let new_text = new_text.await?;
+            let new_bytes = new_bytes.await?;
+            let new_text = cx
+                .background_spawn(async move { encoding.decode(new_bytes) })
+                .await?;
             let diff = this
                 .update(cx, |this, cx| this.diff(new_text.clone(), cx))?
                 .await;
@@ -379,6 +379,55 @@ async fn test_language_for_file_with_custom_file_types(cx: &mut TestAppContext)
     assert_eq!(language.name(), "Dockerfile".into());
 }
 
+#[gpui::test]
+async fn test_language_for_file_with_excluded_file_types(cx: &mut TestAppContext) {
+    cx.update(|cx| {
+        init_settings(cx, |settings| {
+            settings.file_types.get_or_insert_default().extend([(
+                "C++".into(),
+                vec![
+                    "**/*.h".into(),
+                    "!**/legacy/**".into(),
+                    "**/legacy/modern/**".into(),
+                ]
+                .into(),
+            )]);
+        })
+    });
+
+    let languages = Arc::new(LanguageRegistry::test(cx.executor()));
+    for (name, path_suffix) in [("C", "h"), ("C++", "cpp")] {
+        languages.add(Arc::new(Language::new(
+            LanguageConfig {
+                name: name.into(),
+                matcher: LanguageMatcher {
+                    path_suffixes: vec![path_suffix.to_string()],
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            None,
+        )));
+    }
+
+    let language = cx
+        .read(|cx| languages.language_for_file(&file("src/foo.h"), None, cx))
+        .unwrap();
+    assert_eq!(language.name(), "C++".into());
+
+    // excluded files fall back to the system-provided extension
+    let language = cx
+        .read(|cx| languages.language_for_file(&file("src/legacy/foo.h"), None, cx))
+        .unwrap();
+    assert_eq!(language.name(), "C".into());
+
+    // later patterns take precedence over earlier exclusions
+    let language = cx
+        .read(|cx| languages.language_for_file(&file("src/legacy/modern/foo.h"), None, cx))
+        .unwrap();
+    assert_eq!(language.name(), "C++".into());
+}
+
 fn file(path: &str) -> Arc<dyn File> {
     Arc::new(TestFile {
         path: Arc::from(rel_path(path)),
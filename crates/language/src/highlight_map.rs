@@ -1,6 +1,6 @@
 use gpui::HighlightStyle;
 use std::sync::Arc;
-use theme::SyntaxTheme;
+use theme::{LANGUAGE_KEY_SEPARATOR, SyntaxTheme};
 
 #[derive(Clone, Debug)]
 pub struct HighlightMap(Arc<[HighlightId]>);
@@ -11,10 +11,12 @@ pub struct HighlightId(pub u32);
 const DEFAULT_SYNTAX_HIGHLIGHT_ID: HighlightId = HighlightId(u32::MAX);
 
 impl HighlightMap {
-    pub(crate) fn new(capture_names: &[&str], theme: &SyntaxTheme) -> Self {
+    pub(crate) fn new(capture_names: &[&str], theme: &SyntaxTheme, language_name: &str) -> Self {
         // For each capture name in the highlight query, find the longest
         // key in the theme's syntax styles that matches all of the
-        // dot-separated components of the capture name.
+        // dot-separated components of the capture name. Keys specific to
+        // this language win over generic keys of the same length, and keys
+        // specific to other languages are ignored.
         HighlightMap(
             capture_names
                 .iter()
@@ -24,6 +26,14 @@ impl HighlightMap {
                         .iter()
                         .enumerate()
                         .filter_map(|(i, (key, _))| {
+                            let (key, is_language_specific) =
+                                match key.split_once(LANGUAGE_KEY_SEPARATOR) {
+                                    Some((key_language, key)) if key_language == language_name => {
+                                        (key, true)
+                                    }
+                                    Some(_) => return None,
+                                    None => (key.as_str(), false),
+                                };
                             let mut len = 0;
                             let capture_parts = capture_name.split('.');
                             for key_part in key.split('.') {
@@ -33,9 +43,9 @@ impl HighlightMap {
                                     return None;
                                 }
                             }
-                            Some((i, len))
+                            Some((i, (len, is_language_specific)))
                         })
-                        .max_by_key(|(_, len)| *len)
+                        .max_by_key(|(_, precedence)| *precedence)
                         .map_or(DEFAULT_SYNTAX_HIGHLIGHT_ID, |(i, _)| HighlightId(i as u32))
                 })
                 .collect(),
@@ -103,9 +113,36 @@ mod tests {
             "variable.builtin.self",
         ];
 
-        let map = HighlightMap::new(capture_names, &theme);
+        let map = HighlightMap::new(capture_names, &theme, "Rust");
         assert_eq!(map.get(0).name(&theme), Some("function"));
         assert_eq!(map.get(1).name(&theme), Some("function.async"));
         assert_eq!(map.get(2).name(&theme), Some("variable.builtin"));
     }
+
+    #[test]
+    fn test_highlight_map_language_specific_keys() {
+        let theme = SyntaxTheme {
+            highlights: [
+                ("function", rgba(0x100000ff)),
+                ("Rust/function", rgba(0x200000ff)),
+                ("Go/function", rgba(0x300000ff)),
+                ("function.method", rgba(0x400000ff)),
+                ("Go/keyword", rgba(0x500000ff)),
+            ]
+            .iter()
+            .map(|(name, color)| (name.to_string(), (*color).into()))
+            .collect(),
+        };
+
+        let capture_names = &["function", "function.method", "keyword"];
+
+        let map = HighlightMap::new(capture_names, &theme, "Rust");
+        assert_eq!(map.get(0).name(&theme), Some("Rust/function"));
+        assert_eq!(map.get(1).name(&theme), Some("function.method"));
+        assert!(map.get(2).is_default());
+
+        let map = HighlightMap::new(capture_names, &theme, "Go");
+        assert_eq!(map.get(0).name(&theme), Some("Go/function"));
+        assert_eq!(map.get(2).name(&theme), Some("Go/keyword"));
+    }
 }
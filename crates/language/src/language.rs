@@ -1982,8 +1982,11 @@ impl Language {
         if let Some(grammar) = self.grammar.as_ref()
             && let Some(highlights_config) = &grammar.highlights_config
         {
-            *grammar.highlight_map.lock() =
-                HighlightMap::new(highlights_config.query.capture_names(), theme);
+            *grammar.highlight_map.lock() = HighlightMap::new(
+                highlights_config.query.capture_names(),
+                theme,
+                self.config.name.as_ref(),
+            );
         }
     }
 
@@ -2218,6 +2221,10 @@ impl Grammar {
         self.debug_variables_config.as_ref()
     }
 
+    pub fn has_runnables(&self) -> bool {
+        self.runnable_config.is_some()
+    }
+
     pub fn imports_config(&self) -> Option<&ImportsConfig> {
         self.imports_config.as_ref()
     }
@@ -3,20 +3,31 @@
 use crate::{File, LanguageName, LanguageServerName};
 use collections::{FxHashMap, HashMap, HashSet};
 use ec4rs::{
-    property::{FinalNewline, IndentSize, IndentStyle, MaxLineLen, TabWidth, TrimTrailingWs},
+    property::{
+        Charset, EndOfLine, FinalNewline, IndentSize, IndentStyle, MaxLineLen, TabWidth,
+        TrimTrailingWs,
+    },
     Properties as EditorconfigProperties,
 };
-use globset::{Glob, GlobSet, GlobSetBuilder};
+use fs::Encoding;
+use globset::{Candidate, Glob, GlobSet, GlobSetBuilder};
 use gpui::{App, Modifiers, SharedString};
 use itertools::{Either, Itertools};
+use regex::Regex;
 
 pub use settings::{
-    CompletionSettingsContent, FormatOnSave, Formatter, FormatterList, InlayHintKind,
-    LanguageSettingsContent, LspInsertMode, RewrapBehavior, ShowWhitespaceSetting, SoftWrap,
+    CodeActionOnFormat, CompletionSettingsContent, FormatOnSave, Formatter, FormatterErrorPolicy,
+    FormatterList, FormatterStep, InlayHintKind, LanguageSettingsContent, LspInsertMode,
+    RewrapBehavior, ShowWhitespaceSetting, SoftWrap, SpellcheckTarget, TaskCwdStrategy,
     WordsCompletionMode,
 };
-use settings::{ExtendingVec, Settings, SettingsContent, SettingsLocation, SettingsStore};
-use std::{borrow::Cow, num::NonZeroU32, sync::Arc};
+use settings::{
+    DefaultLineEnding, ExtendingVec, FileEncoding, LanguageServerFormatterSpecifier, Settings,
+    SettingsContent, SettingsFile, SettingsLocation, SettingsStore,
+};
+use std::{borrow::Cow, collections::BTreeMap, num::NonZeroU32, sync::Arc};
+use text::LineEnding;
+use util::{ResultExt as _, rel_path::RelPath};
 
 /// Initializes the language settings.
 pub fn init(cx: &mut App) {
@@ -33,7 +44,14 @@ pub fn language_settings<'a>(
         worktree_id: f.worktree_id(cx),
         path: f.path().as_ref(),
     });
-    AllLanguageSettings::get(location, cx).language(location, language.as_ref(), cx)
+    let all_language_settings = AllLanguageSettings::get(location, cx);
+    let mut settings = all_language_settings.language(location, language.as_ref(), cx);
+    if let Some(file_size) = file.and_then(|file| file.disk_size(cx)) {
+        all_language_settings
+            .large_file_thresholds
+            .degrade(file_size, &mut settings);
+    }
+    settings
 }
 
 /// Returns the settings for all languages from the provided file.
@@ -54,13 +72,193 @@ pub struct AllLanguageSettings {
     /// The edit prediction settings.
     pub defaults: LanguageSettings,
     languages: HashMap<LanguageName, LanguageSettings>,
-    pub(crate) file_types: FxHashMap<Arc<str>, GlobSet>,
+    /// Glob-scoped overrides, keyed by the language whose settings they
+    /// extend (`None` for the defaults).
+    glob_overrides: HashMap<Option<LanguageName>, Vec<GlobOverride>>,
+    pub(crate) file_types: FxHashMap<Arc<str>, FileTypeGlobs>,
+    /// File sizes above which features are disabled.
+    pub large_file_thresholds: LargeFileThresholds,
+}
+
+/// Language settings that apply to files whose paths match a set of globs.
+#[derive(Debug, Clone)]
+struct GlobOverride {
+    globs: GlobSet,
+    settings: LanguageSettings,
+}
+
+/// The globs from the `file_types` setting that associate files with a
+/// language.
+///
+/// Patterns starting with `!` exclude files, and later patterns take precedence
+/// over earlier ones, so `["**/*.h", "!**/legacy/**"]` matches headers outside
+/// of `legacy` directories.
+#[derive(Debug, Clone)]
+pub struct FileTypeGlobs {
+    globs: GlobSet,
+    /// Whether the pattern at each index of `globs` is an exclusion.
+    excluded: Vec<bool>,
+}
+
+impl FileTypeGlobs {
+    pub fn new(patterns: &[String]) -> anyhow::Result<Self> {
+        let mut builder = GlobSetBuilder::new();
+        let mut excluded = Vec::with_capacity(patterns.len());
+        for pattern in patterns {
+            let (pattern, is_excluded) = match pattern.strip_prefix('!') {
+                Some(pattern) => (pattern, true),
+                None => (pattern.as_str(), false),
+            };
+            builder.add(Glob::new(pattern)?);
+            excluded.push(is_excluded);
+        }
+        Ok(Self {
+            globs: builder.build()?,
+            excluded,
+        })
+    }
+
+    /// Given the suffixes of a file's path (e.g. its extension, file name, and
+    /// full path), returns the length of the first suffix that the globs
+    /// associate with the language, if the file isn't excluded.
+    ///
+    /// A file is excluded when the last pattern that matches any of its
+    /// suffixes is an exclusion.
+    pub fn matching_suffix_len(&self, path_suffixes: &[(&str, Candidate)]) -> Option<usize> {
+        let mut last_matching_pattern = None;
+        let mut suffix_len = None;
+        for (suffix, candidate) in path_suffixes {
+            let matching_patterns = self.globs.matches_candidate(candidate);
+            let matches_included_pattern =
+                matching_patterns.iter().any(|index| !self.excluded[*index]);
+            if suffix_len.is_none() && matches_included_pattern {
+                suffix_len = Some(suffix.len());
+            }
+            last_matching_pattern = last_matching_pattern.max(matching_patterns.into_iter().max());
+        }
+        if self.excluded[last_matching_pattern?] {
+            None
+        } else {
+            suffix_len
+        }
+    }
+}
+
+/// Where the effective value of a language setting came from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LanguageSettingSource {
+    /// Zed's default settings.
+    Default,
+    /// The user's settings file.
+    User,
+    /// The user's settings file, with the value written by a VS Code settings
+    /// import in this session.
+    VsCodeImport,
+    /// Settings provided by the remote server.
+    Server,
+    /// The project settings file in the given directory.
+    Project(Arc<RelPath>),
+    /// An `.editorconfig` file.
+    Editorconfig,
+}
+
+/// File sizes, in bytes, above which features are disabled for a file. A
+/// threshold of 0 never disables the feature.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LargeFileThresholds {
+    /// The size above which inlay hints are disabled.
+    pub inlay_hints: u64,
+    /// The size above which words from the buffer aren't completed.
+    pub word_completions: u64,
+    /// The size above which indent guides are hidden.
+    pub indent_guides: u64,
+}
+
+impl LargeFileThresholds {
+    /// Disables the features that a file of the given size is too large for,
+    /// only cloning the settings when something changes.
+    pub fn degrade(&self, file_size: u64, settings: &mut Cow<'_, LanguageSettings>) {
+        let exceeds = |threshold: u64| threshold > 0 && file_size > threshold;
+        if exceeds(self.inlay_hints) && settings.inlay_hints.enabled {
+            settings.to_mut().inlay_hints.enabled = false;
+        }
+        if exceeds(self.word_completions)
+            && settings.completions.words != WordsCompletionMode::Disabled
+        {
+            settings.to_mut().completions.words = WordsCompletionMode::Disabled;
+        }
+        if exceeds(self.indent_guides) && settings.indent_guides.enabled {
+            settings.to_mut().indent_guides.enabled = false;
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct WhitespaceMap {
     pub space: SharedString,
     pub tab: SharedString,
+    pub ideographic_space: SharedString,
+    pub non_breaking_space: SharedString,
+    pub zero_width: SharedString,
+}
+
+impl WhitespaceMap {
+    /// The symbol used to render whitespace of the given class.
+    pub fn symbol(&self, class: WhitespaceClass) -> &SharedString {
+        match class {
+            WhitespaceClass::Space => &self.space,
+            WhitespaceClass::Tab => &self.tab,
+            WhitespaceClass::IdeographicSpace => &self.ideographic_space,
+            WhitespaceClass::NonBreakingSpace => &self.non_breaking_space,
+            WhitespaceClass::ZeroWidth => &self.zero_width,
+        }
+    }
+}
+
+/// A kind of whitespace that is rendered with its own symbol.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WhitespaceClass {
+    Space,
+    Tab,
+    IdeographicSpace,
+    NonBreakingSpace,
+    ZeroWidth,
+}
+
+impl WhitespaceClass {
+    /// The class of a character that is easily mistaken for a regular space,
+    /// or `None` for regular spaces, tabs and any other character.
+    pub fn unusual(character: char) -> Option<Self> {
+        match character {
+            '\u{3000}' => Some(Self::IdeographicSpace),
+            '\u{a0}' | '\u{2007}' | '\u{202f}' => Some(Self::NonBreakingSpace),
+            // U+200D is left out, as it's expected between the parts of emoji sequences.
+            '\u{200b}' | '\u{200c}' | '\u{2060}' | '\u{feff}' => Some(Self::ZeroWidth),
+            _ => None,
+        }
+    }
+}
+
+/// Which classes of whitespace are rendered when show_whitespaces is enabled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WhitespaceClasses {
+    pub space: bool,
+    pub tab: bool,
+    pub ideographic_space: bool,
+    pub non_breaking_space: bool,
+    pub zero_width: bool,
+}
+
+impl WhitespaceClasses {
+    pub fn contains(&self, class: WhitespaceClass) -> bool {
+        match class {
+            WhitespaceClass::Space => self.space,
+            WhitespaceClass::Tab => self.tab,
+            WhitespaceClass::IdeographicSpace => self.ideographic_space,
+            WhitespaceClass::NonBreakingSpace => self.non_breaking_space,
+            WhitespaceClass::ZeroWidth => self.zero_width,
+        }
+    }
 }
 
 /// The settings for a particular language.
@@ -76,6 +274,9 @@ pub struct LanguageSettings {
     /// The column at which to soft-wrap lines, for buffers where soft-wrap
     /// is enabled.
     pub preferred_line_length: u32,
+    /// The column at which to rewrap comments, if it differs from
+    /// 'preferred_line_length'.
+    pub comment_wrap_column: Option<u32>,
     /// Whether to show wrap guides (vertical rulers) in the editor.
     /// Setting this to true will show a guide at the 'preferred_line_length' value
     /// if softwrap is set to 'preferred_line_length', and will show any
@@ -87,12 +288,19 @@ pub struct LanguageSettings {
     pub indent_guides: IndentGuideSettings,
     /// Whether or not to perform a buffer format before saving.
     pub format_on_save: FormatOnSave,
+    /// How long to wait for formatting when saving, in milliseconds, where 0
+    /// means to wait indefinitely.
+    pub format_on_save_timeout_ms: u64,
     /// Whether or not to remove any trailing whitespace from lines of a buffer
     /// before saving it.
     pub remove_trailing_whitespace_on_save: bool,
     /// Whether or not to ensure there's a single newline at the end of a buffer
     /// when saving it.
     pub ensure_final_newline_on_save: bool,
+    /// The encoding to save new files with.
+    pub default_encoding: Encoding,
+    /// The line ending to save new files with.
+    pub default_line_ending: LineEnding,
     /// How to perform a buffer format.
     pub formatter: settings::FormatterList,
     /// Zed's Prettier integration settings.
@@ -117,6 +325,8 @@ pub struct LanguageSettings {
     pub show_whitespaces: settings::ShowWhitespaceSetting,
     /// Visible characters used to render whitespace when show_whitespaces is enabled.
     pub whitespace_map: WhitespaceMap,
+    /// Which classes of whitespace to render when show_whitespaces is enabled.
+    pub whitespace_classes: WhitespaceClasses,
     /// Whether to start a new line with a comment when a previous line is a comment as well.
     pub extend_comment_on_newline: bool,
     /// Inlay hint related settings.
@@ -134,8 +344,8 @@ pub struct LanguageSettings {
     pub auto_indent_on_paste: bool,
     /// Controls how the editor handles the autoclosed characters.
     pub always_treat_brackets_as_autoclosed: bool,
-    /// Which code actions to run on save
-    pub code_actions_on_format: HashMap<String, bool>,
+    /// Which code actions to run on save, in order.
+    pub code_actions_on_format: Vec<CodeActionOnFormat>,
     /// Whether to perform linked edits
     pub linked_edits: bool,
     /// Task configuration for this language.
@@ -148,8 +358,54 @@ pub struct LanguageSettings {
     pub show_completion_documentation: bool,
     /// Completion settings for this language.
     pub completions: CompletionSettings,
+    /// Spell-checking settings for this language.
+    pub spellcheck: SpellcheckSettings,
     /// Preferred debuggers for this language.
     pub debuggers: Vec<String>,
+    /// Defaults for debug sessions started without a `debug.json` scenario.
+    pub debug: LanguageDebugSettings,
+    /// Preferences for the REPL's kernel.
+    pub repl: LanguageReplSettings,
+    /// Extra keymap contexts for editors in this language.
+    pub key_context: Vec<String>,
+    /// The encoding to save files with, which can only be set by an
+    /// `.editorconfig` file's `charset` property.
+    pub encoding: Encoding,
+    /// Which of these settings were set by an `.editorconfig` file.
+    pub editorconfig: EditorconfigProvenance,
+}
+
+/// Tracks which [`LanguageSettings`] were overridden by an `.editorconfig` file,
+/// so that they can be attributed to it rather than to the user's settings.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EditorconfigProvenance {
+    /// Set by `indent_size` or `tab_width`.
+    pub tab_size: bool,
+    /// Set by `indent_style`.
+    pub hard_tabs: bool,
+    /// Set by `max_line_length`.
+    pub preferred_line_length: bool,
+    /// Set by `trim_trailing_whitespace`.
+    pub remove_trailing_whitespace_on_save: bool,
+    /// Set by `insert_final_newline`.
+    pub ensure_final_newline_on_save: bool,
+    /// Set by `end_of_line`.
+    pub default_line_ending: bool,
+    /// Set by `charset`.
+    pub encoding: bool,
+}
+
+impl EditorconfigProvenance {
+    /// Returns whether any setting was set by an `.editorconfig` file.
+    pub fn any(&self) -> bool {
+        self.tab_size
+            || self.hard_tabs
+            || self.preferred_line_length
+            || self.remove_trailing_whitespace_on_save
+            || self.ensure_final_newline_on_save
+            || self.default_line_ending
+            || self.encoding
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -179,6 +435,30 @@ pub struct CompletionSettings {
     pub lsp_insert_mode: LspInsertMode,
 }
 
+/// The spell-checking settings for a particular language.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpellcheckSettings {
+    /// Whether to check spelling.
+    ///
+    /// Default: false
+    pub enabled: bool,
+    /// The dictionaries to check words against, by locale.
+    ///
+    /// Default: ["en_US"]
+    pub dictionaries: Vec<String>,
+    /// Which parts of the code to check.
+    ///
+    /// Default: ["comments", "strings"]
+    pub targets: Vec<SpellcheckTarget>,
+}
+
+impl SpellcheckSettings {
+    /// Returns whether the given part of the code should be spell-checked.
+    pub fn checks(&self, target: SpellcheckTarget) -> bool {
+        self.enabled && self.targets.contains(&target)
+    }
+}
+
 /// The settings for indent guides.
 #[derive(Debug, Clone, PartialEq)]
 pub struct IndentGuideSettings {
@@ -217,6 +497,36 @@ pub struct LanguageTaskSettings {
     /// * Zed task from either of the task config file
     /// * Zed task from history (e.g. one-off task was spawned before)
     pub prefer_lsp: bool,
+    /// The shell to run tasks in, for tasks that don't specify their own.
+    pub shell: Option<task::Shell>,
+    /// Dotenv files to load into the environment of tasks.
+    pub env_files: Vec<String>,
+    /// The directory to run tasks in, for tasks that don't specify their own.
+    pub cwd_strategy: TaskCwdStrategy,
+}
+
+/// The defaults for debug sessions of a particular language.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LanguageDebugSettings {
+    /// The program to launch, along with its arguments, which may contain task
+    /// variables.
+    pub program: Option<String>,
+    /// Environment variables to launch the program with.
+    pub env: HashMap<String, String>,
+    /// The directory to launch the program in, which may contain task
+    /// variables.
+    pub cwd: Option<String>,
+}
+
+/// The REPL preferences of a particular language.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LanguageReplSettings {
+    /// The name of the preferred kernel.
+    pub kernel: Option<String>,
+    /// Code to run silently when a kernel starts.
+    pub startup_code: Option<String>,
+    /// Whether to start a kernel when an editor is focused.
+    pub auto_attach: bool,
 }
 
 /// Allows to enable/disable formatting with Prettier
@@ -286,10 +596,26 @@ impl LanguageSettings {
             })
             .collect::<Vec<_>>()
     }
+
+    /// Returns whether any of the configured formatters can run, given
+    /// whether language servers and Prettier are enabled for the language.
+    pub fn has_formatter(&self) -> bool {
+        self.formatter
+            .steps()
+            .into_iter()
+            .any(|(formatter, _)| match formatter {
+                Formatter::Auto => self.enable_language_server || self.prettier.allowed,
+                Formatter::Prettier => self.prettier.allowed,
+                Formatter::External { .. } => true,
+                Formatter::CodeAction(_) | Formatter::LanguageServer(_) => {
+                    self.enable_language_server
+                }
+            })
+    }
 }
 
 // The settings for inlay hints.
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug)]
 pub struct InlayHintSettings {
     /// Global switch to toggle hints on and off.
     ///
@@ -336,9 +662,73 @@ pub struct InlayHintSettings {
     ///
     /// Default: None
     pub toggle_on_modifiers_press: Option<Modifiers>,
+    /// The maximum number of characters to show for a hint, longer hints are
+    /// truncated with an ellipsis.
+    ///
+    /// Set to 0 to show hints in full.
+    ///
+    /// Default: 0
+    pub max_hint_length: usize,
+    /// Kinds of hints to hide from specific language servers.
+    ///
+    /// Default: {}
+    pub excluded_kinds_per_server: HashMap<LanguageServerName, HashSet<InlayHintKind>>,
+    /// Hints whose text matches any of these patterns are hidden.
+    ///
+    /// Default: []
+    pub suppress_patterns: Vec<Regex>,
+}
+
+impl Eq for InlayHintSettings {}
+
+impl PartialEq for InlayHintSettings {
+    fn eq(&self, other: &Self) -> bool {
+        self.enabled == other.enabled
+            && self.show_value_hints == other.show_value_hints
+            && self.show_type_hints == other.show_type_hints
+            && self.show_parameter_hints == other.show_parameter_hints
+            && self.show_other_hints == other.show_other_hints
+            && self.show_background == other.show_background
+            && self.edit_debounce_ms == other.edit_debounce_ms
+            && self.scroll_debounce_ms == other.scroll_debounce_ms
+            && self.toggle_on_modifiers_press == other.toggle_on_modifiers_press
+            && !self.hint_filters_changed(other)
+    }
 }
 
 impl InlayHintSettings {
+    /// Returns whether a hint from the given language server should be hidden,
+    /// based on its kind and text.
+    pub fn is_hint_suppressed(
+        &self,
+        server_name: &LanguageServerName,
+        kind: Option<InlayHintKind>,
+        text: &str,
+    ) -> bool {
+        let kind_excluded = kind.is_some_and(|kind| {
+            self.excluded_kinds_per_server
+                .get(server_name)
+                .is_some_and(|kinds| kinds.contains(&kind))
+        });
+        kind_excluded
+            || self
+                .suppress_patterns
+                .iter()
+                .any(|pattern| pattern.is_match(text))
+    }
+
+    /// Returns whether hints that were already fetched need to be queried
+    /// again, because the filtering applied to them has changed.
+    pub fn hint_filters_changed(&self, other: &Self) -> bool {
+        self.max_hint_length != other.max_hint_length
+            || self.excluded_kinds_per_server != other.excluded_kinds_per_server
+            || !self
+                .suppress_patterns
+                .iter()
+                .map(Regex::as_str)
+                .eq(other.suppress_patterns.iter().map(Regex::as_str))
+    }
+
     /// Returns the kinds of inlay hints that are enabled based on the settings.
     pub fn enabled_inlay_hint_kinds(&self) -> HashSet<Option<InlayHintKind>> {
         let mut kinds = HashSet::default();
@@ -363,9 +753,20 @@ impl AllLanguageSettings {
         language_name: Option<&LanguageName>,
         cx: &'a App,
     ) -> Cow<'a, LanguageSettings> {
-        let settings = language_name
+        // Languages without settings of their own use the defaults, including
+        // the default glob overrides.
+        let language_name = language_name.filter(|name| self.languages.contains_key(*name));
+        let mut settings = language_name
             .and_then(|name| self.languages.get(name))
             .unwrap_or(&self.defaults);
+        if let Some(location) = location
+            && let Some(glob_overrides) = self.glob_overrides.get(&language_name.cloned())
+            && let Some(glob_override) = glob_overrides
+                .iter()
+                .find(|glob_override| glob_override.globs.is_match(location.path.as_std_path()))
+        {
+            settings = &glob_override.settings;
+        }
 
         let editorconfig_properties = location.and_then(|location| {
             cx.global::<SettingsStore>()
@@ -379,9 +780,164 @@ impl AllLanguageSettings {
             Cow::Borrowed(settings)
         }
     }
+
+    /// Returns the source that supplied the effective value of each of the
+    /// language's settings at the given location, keyed by setting name.
+    ///
+    /// For settings whose values are objects, this is the highest-precedence
+    /// source that sets any part of the object.
+    pub fn explain(
+        &self,
+        location: Option<SettingsLocation>,
+        language_name: Option<&LanguageName>,
+        cx: &App,
+    ) -> BTreeMap<String, LanguageSettingSource> {
+        let store = cx.global::<SettingsStore>();
+        let mut sources = BTreeMap::default();
+
+        // `.editorconfig` properties are applied on top of all other settings.
+        let editorconfig = self.language(location, language_name, cx).editorconfig;
+        for (is_set, name) in [
+            (editorconfig.tab_size, "tab_size"),
+            (editorconfig.hard_tabs, "hard_tabs"),
+            (editorconfig.preferred_line_length, "preferred_line_length"),
+            (
+                editorconfig.remove_trailing_whitespace_on_save,
+                "remove_trailing_whitespace_on_save",
+            ),
+            (
+                editorconfig.ensure_final_newline_on_save,
+                "ensure_final_newline_on_save",
+            ),
+            (editorconfig.default_line_ending, "default_line_ending"),
+            // `charset` takes precedence over `default_encoding` when saving.
+            (editorconfig.encoding, "default_encoding"),
+        ] {
+            if is_set {
+                sources.insert(name.to_string(), LanguageSettingSource::Editorconfig);
+            }
+        }
+
+        // Settings files that apply to the location, from highest to lowest
+        // precedence.
+        let files = store
+            .get_all_files()
+            .into_iter()
+            .filter(|file| match file {
+                SettingsFile::Project((worktree_id, path)) => location.is_some_and(|location| {
+                    location.worktree_id == *worktree_id && location.path.starts_with(path)
+                }),
+                _ => true,
+            })
+            .filter_map(|file| Some((store.get_content_for_file(file.clone())?, file)))
+            .collect::<Vec<_>>();
+        let vscode_imported_settings = store.vscode_imported_settings();
+
+        // Language-specific settings from any file take precedence over the
+        // defaults from any file.
+        let language_layers = files.iter().filter_map(|(content, file)| {
+            let language_name = language_name?;
+            let settings = content
+                .project
+                .all_languages
+                .languages
+                .0
+                .get(&language_name.0)?;
+            let imported = vscode_imported_settings.and_then(|imported| {
+                imported
+                    .project
+                    .all_languages
+                    .languages
+                    .0
+                    .get(&language_name.0)
+            });
+            Some((settings, file, imported))
+        });
+        let default_layers = files.iter().map(|(content, file)| {
+            let imported =
+                vscode_imported_settings.map(|imported| &imported.project.all_languages.defaults);
+            (&content.project.all_languages.defaults, file, imported)
+        });
+        let layers = language_layers.chain(default_layers).collect::<Vec<_>>();
+
+        if let Some(location) = location
+            && let Some((overrides, file)) = layers
+                .iter()
+                .find_map(|(settings, file, _)| Some((settings.overrides.as_ref()?, *file)))
+            && let Some(entry) = overrides.iter().find(|entry| {
+                build_glob_set(&entry.globs)
+                    .is_some_and(|globs| globs.is_match(location.path.as_std_path()))
+            })
+        {
+            // Nested overrides are ignored, so they don't supply any value.
+            let mut override_settings = entry.settings.as_ref().clone();
+            override_settings.overrides = None;
+            record_sources(&mut sources, &override_settings, file, None);
+        }
+
+        for (settings, file, imported) in layers {
+            record_sources(&mut sources, settings, file, imported);
+        }
+
+        sources
+    }
+}
+
+/// Records `file` as the source of each setting in `settings` that doesn't
+/// have a source yet.
+fn record_sources(
+    sources: &mut BTreeMap<String, LanguageSettingSource>,
+    settings: &LanguageSettingsContent,
+    file: &SettingsFile,
+    vscode_imported_settings: Option<&LanguageSettingsContent>,
+) {
+    let Some(serde_json::Value::Object(values)) = serde_json::to_value(settings).log_err() else {
+        return;
+    };
+    let imported_values = vscode_imported_settings
+        .and_then(|imported| serde_json::to_value(imported).log_err())
+        .unwrap_or_default();
+    for (name, value) in values {
+        let source = match file {
+            SettingsFile::Default => LanguageSettingSource::Default,
+            SettingsFile::User if imported_values.get(&name) == Some(&value) => {
+                LanguageSettingSource::VsCodeImport
+            }
+            SettingsFile::User => LanguageSettingSource::User,
+            SettingsFile::Server => LanguageSettingSource::Server,
+            SettingsFile::Project((_, path)) => LanguageSettingSource::Project(path.clone()),
+        };
+        sources.entry(name).or_insert(source);
+    }
+}
+
+fn build_glob_set(globs: &[String]) -> Option<GlobSet> {
+    let mut builder = GlobSetBuilder::new();
+    for glob in globs {
+        builder.add(Glob::new(glob).log_err()?);
+    }
+    builder.build().log_err()
+}
+
+fn task_shell_from_settings(shell: settings::Shell) -> task::Shell {
+    match shell {
+        settings::Shell::System => task::Shell::System,
+        settings::Shell::Program(program) => task::Shell::Program(program),
+        settings::Shell::WithArguments {
+            program,
+            args,
+            title_override,
+        } => task::Shell::WithArguments {
+            program,
+            args,
+            title_override,
+        },
+    }
 }
 
 fn merge_with_editorconfig(settings: &mut LanguageSettings, cfg: &EditorconfigProperties) {
+    // `off` defers to the user's settings, while still overriding any length
+    // from an outer `.editorconfig` section.
     let preferred_line_length = cfg.get::<MaxLineLen>().ok().and_then(|v| match v {
         MaxLineLen::Value(u) => Some(u as u32),
         MaxLineLen::Off => None,
@@ -408,22 +964,62 @@ fn merge_with_editorconfig(settings: &mut LanguageSettings, cfg: &EditorconfigPr
             TrimTrailingWs::Value(b) => b,
         })
         .ok();
-    fn merge<T>(target: &mut T, value: Option<T>) {
+    // Old Mac OS line endings aren't supported, so `cr` is ignored.
+    let default_line_ending = cfg.get::<EndOfLine>().ok().and_then(|v| match v {
+        EndOfLine::Lf => Some(LineEnding::Unix),
+        EndOfLine::CrLf => Some(LineEnding::Windows),
+        EndOfLine::Cr => None,
+    });
+    let encoding = cfg.get::<Charset>().ok().map(|v| match v {
+        Charset::Utf8 => Encoding::Utf8,
+        Charset::Utf8Bom => Encoding::Utf8Bom,
+        Charset::Utf16Le => Encoding::Utf16Le,
+        Charset::Utf16Be => Encoding::Utf16Be,
+        Charset::Latin1 => Encoding::Latin1,
+    });
+    fn merge<T>(target: &mut T, from_editorconfig: &mut bool, value: Option<T>) {
         if let Some(value) = value {
             *target = value;
+            *from_editorconfig = true;
         }
     }
-    merge(&mut settings.preferred_line_length, preferred_line_length);
-    merge(&mut settings.tab_size, tab_size);
-    merge(&mut settings.hard_tabs, hard_tabs);
+    let provenance = &mut settings.editorconfig;
+    merge(
+        &mut settings.preferred_line_length,
+        &mut provenance.preferred_line_length,
+        preferred_line_length,
+    );
+    // EditorConfig has no property for comments, but they shouldn't go past
+    // the maximum line length that it sets.
+    if let Some((comment_wrap_column, max_line_length)) = settings
+        .comment_wrap_column
+        .as_mut()
+        .zip(preferred_line_length)
+    {
+        *comment_wrap_column = (*comment_wrap_column).min(max_line_length);
+    }
+    merge(&mut settings.tab_size, &mut provenance.tab_size, tab_size);
+    merge(
+        &mut settings.hard_tabs,
+        &mut provenance.hard_tabs,
+        hard_tabs,
+    );
     merge(
         &mut settings.remove_trailing_whitespace_on_save,
+        &mut provenance.remove_trailing_whitespace_on_save,
         remove_trailing_whitespace_on_save,
     );
     merge(
         &mut settings.ensure_final_newline_on_save,
+        &mut provenance.ensure_final_newline_on_save,
         ensure_final_newline_on_save,
     );
+    merge(
+        &mut settings.default_line_ending,
+        &mut provenance.default_line_ending,
+        default_line_ending,
+    );
+    merge(&mut settings.encoding, &mut provenance.encoding, encoding);
 }
 
 impl settings::Settings for AllLanguageSettings {
@@ -433,16 +1029,21 @@ impl settings::Settings for AllLanguageSettings {
         fn load_from_content(settings: LanguageSettingsContent) -> LanguageSettings {
             let inlay_hints = settings.inlay_hints.unwrap();
             let completions = settings.completions.unwrap();
+            let spellcheck = settings.spellcheck.unwrap();
             let prettier = settings.prettier.unwrap();
             let indent_guides = settings.indent_guides.unwrap();
             let tasks = settings.tasks.unwrap();
+            let debug = settings.debug.unwrap();
+            let repl = settings.repl.unwrap();
             let whitespace_map = settings.whitespace_map.unwrap();
+            let whitespace_classes = settings.whitespace_classes.unwrap();
 
             LanguageSettings {
                 tab_size: settings.tab_size.unwrap(),
                 hard_tabs: settings.hard_tabs.unwrap(),
                 soft_wrap: settings.soft_wrap.unwrap(),
                 preferred_line_length: settings.preferred_line_length.unwrap(),
+                comment_wrap_column: settings.comment_wrap_column,
                 show_wrap_guides: settings.show_wrap_guides.unwrap(),
                 wrap_guides: settings.wrap_guides.unwrap(),
                 indent_guides: IndentGuideSettings {
@@ -453,10 +1054,23 @@ impl settings::Settings for AllLanguageSettings {
                     background_coloring: indent_guides.background_coloring.unwrap(),
                 },
                 format_on_save: settings.format_on_save.unwrap(),
+                format_on_save_timeout_ms: settings.format_on_save_timeout_ms.unwrap(),
                 remove_trailing_whitespace_on_save: settings
                     .remove_trailing_whitespace_on_save
                     .unwrap(),
                 ensure_final_newline_on_save: settings.ensure_final_newline_on_save.unwrap(),
+                default_encoding: match settings.default_encoding.unwrap() {
+                    FileEncoding::Utf8 => Encoding::Utf8,
+                    FileEncoding::Utf8Bom => Encoding::Utf8Bom,
+                    FileEncoding::Utf16Le => Encoding::Utf16Le,
+                    FileEncoding::Utf16Be => Encoding::Utf16Be,
+                    FileEncoding::Latin1 => Encoding::Latin1,
+                },
+                default_line_ending: match settings.default_line_ending.unwrap() {
+                    DefaultLineEnding::Lf => LineEnding::Unix,
+                    DefaultLineEnding::Crlf => LineEnding::Windows,
+                    DefaultLineEnding::Platform => LineEnding::default(),
+                },
                 formatter: settings.formatter.unwrap(),
                 prettier: PrettierSettings {
                     allowed: prettier.allowed.unwrap(),
@@ -472,6 +1086,20 @@ impl settings::Settings for AllLanguageSettings {
                 whitespace_map: WhitespaceMap {
                     space: SharedString::new(whitespace_map.space.unwrap().to_string()),
                     tab: SharedString::new(whitespace_map.tab.unwrap().to_string()),
+                    ideographic_space: SharedString::new(
+                        whitespace_map.ideographic_space.unwrap().to_string(),
+                    ),
+                    non_breaking_space: SharedString::new(
+                        whitespace_map.non_breaking_space.unwrap().to_string(),
+                    ),
+                    zero_width: SharedString::new(whitespace_map.zero_width.unwrap().to_string()),
+                },
+                whitespace_classes: WhitespaceClasses {
+                    space: whitespace_classes.space.unwrap(),
+                    tab: whitespace_classes.tab.unwrap(),
+                    ideographic_space: whitespace_classes.ideographic_space.unwrap(),
+                    non_breaking_space: whitespace_classes.non_breaking_space.unwrap(),
+                    zero_width: whitespace_classes.zero_width.unwrap(),
                 },
                 extend_comment_on_newline: settings.extend_comment_on_newline.unwrap(),
                 inlay_hints: InlayHintSettings {
@@ -484,6 +1112,24 @@ impl settings::Settings for AllLanguageSettings {
                     edit_debounce_ms: inlay_hints.edit_debounce_ms.unwrap(),
                     scroll_debounce_ms: inlay_hints.scroll_debounce_ms.unwrap(),
                     toggle_on_modifiers_press: inlay_hints.toggle_on_modifiers_press,
+                    max_hint_length: inlay_hints.max_hint_length.unwrap(),
+                    excluded_kinds_per_server: inlay_hints
+                        .excluded_kinds_per_server
+                        .unwrap()
+                        .into_iter()
+                        .map(|(server_name, kinds)| {
+                            (
+                                LanguageServerName(server_name.into()),
+                                kinds.into_iter().collect(),
+                            )
+                        })
+                        .collect(),
+                    suppress_patterns: inlay_hints
+                        .suppress_patterns
+                        .unwrap()
+                        .iter()
+                        .filter_map(|pattern| Regex::new(pattern).log_err())
+                        .collect(),
                 },
                 use_autoclose: settings.use_autoclose.unwrap(),
                 use_auto_surround: settings.use_auto_surround.unwrap(),
@@ -499,6 +1145,9 @@ impl settings::Settings for AllLanguageSettings {
                     variables: tasks.variables.unwrap_or_default(),
                     enabled: tasks.enabled.unwrap(),
                     prefer_lsp: tasks.prefer_lsp.unwrap(),
+                    shell: tasks.shell.map(task_shell_from_settings),
+                    env_files: tasks.env_files.unwrap(),
+                    cwd_strategy: tasks.cwd_strategy.unwrap(),
                 },
                 show_completions_on_input: settings.show_completions_on_input.unwrap(),
                 show_completion_documentation: settings.show_completion_documentation.unwrap(),
@@ -509,123 +1158,106 @@ impl settings::Settings for AllLanguageSettings {
                     lsp_fetch_timeout_ms: completions.lsp_fetch_timeout_ms.unwrap(),
                     lsp_insert_mode: completions.lsp_insert_mode.unwrap(),
                 },
+                spellcheck: SpellcheckSettings {
+                    enabled: spellcheck.enabled.unwrap(),
+                    dictionaries: spellcheck.dictionaries.unwrap(),
+                    targets: spellcheck.targets.unwrap(),
+                },
                 debuggers: settings.debuggers.unwrap(),
+                debug: LanguageDebugSettings {
+                    program: debug.program.filter(|program| !program.is_empty()),
+                    env: debug.env.unwrap_or_default(),
+                    cwd: debug.cwd.filter(|cwd| !cwd.is_empty()),
+                },
+                repl: LanguageReplSettings {
+                    kernel: repl.kernel.filter(|kernel| !kernel.is_empty()),
+                    startup_code: repl.startup_code.filter(|code| !code.trim().is_empty()),
+                    auto_attach: repl.auto_attach.unwrap(),
+                },
+                key_context: settings.key_context.unwrap(),
+                encoding: Encoding::default(),
+                editorconfig: EditorconfigProvenance::default(),
             }
         }
 
+        fn load_glob_overrides(settings: &LanguageSettingsContent) -> Vec<GlobOverride> {
+            settings
+                .overrides
+                .iter()
+                .flatten()
+                .filter_map(|entry| {
+                    let globs = build_glob_set(&entry.globs)?;
+                    let mut override_settings = settings.clone();
+                    settings::merge_from::MergeFrom::merge_from(
+                        &mut override_settings,
+                        entry.settings.as_ref(),
+                    );
+                    Some(GlobOverride {
+                        globs,
+                        settings: load_from_content(override_settings),
+                    })
+                })
+                .collect()
+        }
+
         let default_language_settings = load_from_content(all_languages.defaults.clone());
 
         let mut languages = HashMap::default();
+        let mut glob_overrides = HashMap::default();
+        glob_overrides.insert(None, load_glob_overrides(&all_languages.defaults));
         for (language_name, settings) in &all_languages.languages.0 {
             let mut language_settings = all_languages.defaults.clone();
             settings::merge_from::MergeFrom::merge_from(&mut language_settings, settings);
+            glob_overrides.insert(
+                Some(LanguageName(language_name.clone())),
+                load_glob_overrides(&language_settings),
+            );
             languages.insert(
                 LanguageName(language_name.clone()),
                 load_from_content(language_settings),
             );
         }
 
-        let mut file_types: FxHashMap<Arc<str>, GlobSet> = FxHashMap::default();
+        let mut file_types: FxHashMap<Arc<str>, FileTypeGlobs> = FxHashMap::default();
 
         for (language, patterns) in all_languages.file_types.iter().flatten() {
-            let mut builder = GlobSetBuilder::new();
-
-            for pattern in &patterns.0 {
-                builder.add(Glob::new(pattern).unwrap());
+            if let Some(globs) = FileTypeGlobs::new(&patterns.0).log_err() {
+                file_types.insert(language.clone(), globs);
             }
-
-            file_types.insert(language.clone(), builder.build().unwrap());
         }
 
+        let large_file_thresholds = all_languages.large_file_thresholds.clone().unwrap();
+
         Self {
             defaults: default_language_settings,
             languages,
+            glob_overrides,
             file_types,
+            large_file_thresholds: LargeFileThresholds {
+                inlay_hints: large_file_thresholds.inlay_hints.unwrap(),
+                word_completions: large_file_thresholds.word_completions.unwrap(),
+                indent_guides: large_file_thresholds.indent_guides.unwrap(),
+            },
         }
     }
 
     fn import_from_vscode(vscode: &settings::VsCodeSettings, current: &mut SettingsContent) {
-        let d = &mut current.project.all_languages.defaults;
-        if let Some(size) = vscode
-            .read_value("editor.tabSize")
-            .and_then(|v| v.as_u64())
-            .and_then(|n| NonZeroU32::new(n as u32))
-        {
-            d.tab_size = Some(size);
-        }
-        if let Some(v) = vscode.read_bool("editor.insertSpaces") {
-            d.hard_tabs = Some(!v);
-        }
-
-        vscode.enum_setting("editor.wordWrap", &mut d.soft_wrap, |s| match s {
-            "on" => Some(SoftWrap::EditorWidth),
-            "wordWrapColumn" => Some(SoftWrap::PreferLine),
-            "bounded" => Some(SoftWrap::Bounded),
-            "off" => Some(SoftWrap::None),
-            _ => None,
-        });
-        vscode.u32_setting("editor.wordWrapColumn", &mut d.preferred_line_length);
-
-        if let Some(arr) = vscode
-            .read_value("editor.rulers")
-            .and_then(|v| v.as_array())
-            .map(|v| v.iter().map(|n| n.as_u64().map(|n| n as usize)).collect())
-        {
-            d.wrap_guides = arr;
-        }
-        if let Some(b) = vscode.read_bool("editor.guides.indentation") {
-            d.indent_guides.get_or_insert_default().enabled = Some(b);
-        }
-
-        if let Some(b) = vscode.read_bool("editor.guides.formatOnSave") {
-            d.format_on_save = Some(if b {
-                FormatOnSave::On
-            } else {
-                FormatOnSave::Off
-            });
-        }
-        vscode.bool_setting(
-            "editor.trimAutoWhitespace",
-            &mut d.remove_trailing_whitespace_on_save,
-        );
-        vscode.bool_setting(
-            "files.insertFinalNewline",
-            &mut d.ensure_final_newline_on_save,
-        );
-        vscode.enum_setting("editor.renderWhitespace", &mut d.show_whitespaces, |s| {
-            Some(match s {
-                "boundary" => ShowWhitespaceSetting::Boundary,
-                "trailing" => ShowWhitespaceSetting::Trailing,
-                "selection" => ShowWhitespaceSetting::Selection,
-                "all" => ShowWhitespaceSetting::All,
-                _ => ShowWhitespaceSetting::None,
-            })
-        });
-        vscode.enum_setting(
-            "editor.autoSurround",
-            &mut d.use_auto_surround,
-            |s| match s {
-                "languageDefined" | "quotes" | "brackets" => Some(true),
-                "never" => Some(false),
-                _ => None,
-            },
-        );
-        vscode.bool_setting("editor.formatOnType", &mut d.use_on_type_format);
-        vscode.bool_setting("editor.linkedEditing", &mut d.linked_edits);
-        vscode.bool_setting("editor.formatOnPaste", &mut d.auto_indent_on_paste);
-        vscode.bool_setting(
-            "editor.suggestOnTriggerCharacters",
-            &mut d.show_completions_on_input,
-        );
-        if let Some(b) = vscode.read_bool("editor.suggest.showWords") {
-            let mode = if b {
-                WordsCompletionMode::Enabled
-            } else {
-                WordsCompletionMode::Disabled
-            };
-            d.completions.get_or_insert_default().words = Some(mode);
+        import_language_settings_from_vscode(vscode, &mut current.project.all_languages.defaults);
+        for (language_ids, language_vscode) in vscode.language_scoped_settings() {
+            for language_id in language_ids {
+                let Some(language_name) = language_name_for_vscode_id(language_id) else {
+                    continue;
+                };
+                let settings = current
+                    .project
+                    .all_languages
+                    .languages
+                    .0
+                    .entry(SharedString::new_static(language_name))
+                    .or_default();
+                import_language_settings_from_vscode(&language_vscode, settings);
+            }
         }
-        // TODO: pull ^ out into helper and reuse for per-language settings
 
         // vscodes file association map is inverted from ours, so we flip the mapping before merging
         let mut associations: HashMap<Arc<str>, ExtendingVec<String>> = HashMap::default();
@@ -647,6 +1279,241 @@ impl settings::Settings for AllLanguageSettings {
             .get_or_insert_default()
             .extend(associations);
     }
+
+    fn import_from_jetbrains(
+        jetbrains: &settings::JetBrainsCodeStyle,
+        current: &mut SettingsContent,
+    ) {
+        import_language_settings_from_jetbrains(
+            jetbrains.general_options(),
+            &mut current.project.all_languages.defaults,
+        );
+        for (language_id, options) in jetbrains.language_options() {
+            for language_name in language_names_for_jetbrains_id(language_id) {
+                let settings = current
+                    .project
+                    .all_languages
+                    .languages
+                    .0
+                    .entry(SharedString::new_static(language_name))
+                    .or_default();
+                import_language_settings_from_jetbrains(options, settings);
+            }
+        }
+    }
+}
+
+/// Imports the VS Code editor settings that Zed can apply per language, either
+/// globally or from a language-specific section like `"[rust]": { ... }`.
+fn import_language_settings_from_vscode(
+    vscode: &settings::VsCodeSettings,
+    d: &mut LanguageSettingsContent,
+) {
+    if let Some(size) = vscode
+        .read_value("editor.tabSize")
+        .and_then(|v| v.as_u64())
+        .and_then(|n| NonZeroU32::new(n as u32))
+    {
+        d.tab_size = Some(size);
+    }
+    if let Some(v) = vscode.read_bool("editor.insertSpaces") {
+        d.hard_tabs = Some(!v);
+    }
+
+    vscode.enum_setting("editor.wordWrap", &mut d.soft_wrap, |s| match s {
+        "on" => Some(SoftWrap::EditorWidth),
+        "wordWrapColumn" => Some(SoftWrap::PreferLine),
+        "bounded" => Some(SoftWrap::Bounded),
+        "off" => Some(SoftWrap::None),
+        _ => None,
+    });
+    vscode.u32_setting("editor.wordWrapColumn", &mut d.preferred_line_length);
+
+    if let Some(arr) = vscode
+        .read_value("editor.rulers")
+        .and_then(|v| v.as_array())
+        .map(|v| v.iter().map(|n| n.as_u64().map(|n| n as usize)).collect())
+    {
+        d.wrap_guides = arr;
+    }
+    if let Some(b) = vscode.read_bool("editor.guides.indentation") {
+        d.indent_guides.get_or_insert_default().enabled = Some(b);
+    }
+
+    if let Some(b) = vscode.read_bool("editor.guides.formatOnSave") {
+        d.format_on_save = Some(if b {
+            FormatOnSave::On
+        } else {
+            FormatOnSave::Off
+        });
+    }
+    vscode.bool_setting(
+        "editor.trimAutoWhitespace",
+        &mut d.remove_trailing_whitespace_on_save,
+    );
+    vscode.bool_setting(
+        "files.insertFinalNewline",
+        &mut d.ensure_final_newline_on_save,
+    );
+    vscode.enum_setting("editor.renderWhitespace", &mut d.show_whitespaces, |s| {
+        Some(match s {
+            "boundary" => ShowWhitespaceSetting::Boundary,
+            "trailing" => ShowWhitespaceSetting::Trailing,
+            "selection" => ShowWhitespaceSetting::Selection,
+            "all" => ShowWhitespaceSetting::All,
+            _ => ShowWhitespaceSetting::None,
+        })
+    });
+    vscode.enum_setting(
+        "editor.autoSurround",
+        &mut d.use_auto_surround,
+        |s| match s {
+            "languageDefined" | "quotes" | "brackets" => Some(true),
+            "never" => Some(false),
+            _ => None,
+        },
+    );
+    vscode.bool_setting("editor.formatOnType", &mut d.use_on_type_format);
+    vscode.bool_setting("editor.linkedEditing", &mut d.linked_edits);
+    vscode.bool_setting("editor.formatOnPaste", &mut d.auto_indent_on_paste);
+    vscode.bool_setting(
+        "editor.suggestOnTriggerCharacters",
+        &mut d.show_completions_on_input,
+    );
+    if let Some(b) = vscode.read_bool("editor.suggest.showWords") {
+        let mode = if b {
+            WordsCompletionMode::Enabled
+        } else {
+            WordsCompletionMode::Disabled
+        };
+        d.completions.get_or_insert_default().words = Some(mode);
+    }
+    if let Some(enabled) = vscode.read_bool("cSpell.enabled") {
+        d.spellcheck.get_or_insert_default().enabled = Some(enabled);
+    }
+    // cSpell lists its locales in a single comma-separated string, like "en,de".
+    if let Some(locales) = vscode.read_string("cSpell.language") {
+        let dictionaries = locales
+            .split(',')
+            .map(|locale| locale.trim().replace('-', "_"))
+            .filter(|locale| !locale.is_empty())
+            .collect();
+        d.spellcheck.get_or_insert_default().dictionaries = Some(dictionaries);
+    }
+    if let Some(formatter) = vscode.read_string("editor.defaultFormatter") {
+        let formatter = match formatter {
+            "esbenp.prettier-vscode" => Formatter::Prettier,
+            "biomejs.biome" => {
+                Formatter::LanguageServer(LanguageServerFormatterSpecifier::Specific {
+                    name: "biome".to_string(),
+                })
+            }
+            "charliermarsh.ruff" => {
+                Formatter::LanguageServer(LanguageServerFormatterSpecifier::Specific {
+                    name: "ruff".to_string(),
+                })
+            }
+            // Other formatters are usually the extension that provides the
+            // language's server, which Zed already runs.
+            _ => Formatter::LanguageServer(LanguageServerFormatterSpecifier::Current),
+        };
+        d.formatter = Some(FormatterList::Single(formatter));
+    }
+}
+
+/// Maps VS Code's identifier for a language to the name of the equivalent
+/// built-in Zed language.
+fn language_name_for_vscode_id(vscode_id: &str) -> Option<&'static str> {
+    Some(match vscode_id {
+        "c" => "C",
+        "cpp" => "C++",
+        "css" => "CSS",
+        "diff" => "Diff",
+        "git-commit" => "Git Commit",
+        "go" => "Go",
+        "go.mod" => "Go Mod",
+        "go.work" => "Go Work",
+        "html" => "HTML",
+        "javascript" | "javascriptreact" => "JavaScript",
+        "json" => "JSON",
+        "jsonc" => "JSONC",
+        "markdown" => "Markdown",
+        "python" => "Python",
+        "rust" => "Rust",
+        "shellscript" => "Shell Script",
+        "typescript" => "TypeScript",
+        "typescriptreact" => "TSX",
+        "yaml" => "YAML",
+        _ => return None,
+    })
+}
+
+/// Imports the indent, wrap, and import-ordering options of a JetBrains code
+/// style, either for all languages or for a specific one.
+fn import_language_settings_from_jetbrains(
+    options: &settings::JetBrainsOptions,
+    settings: &mut LanguageSettingsContent,
+) {
+    let use_tabs = options.read_bool("USE_TAB_CHARACTER");
+    if let Some(use_tabs) = use_tabs {
+        settings.hard_tabs = Some(use_tabs);
+    }
+    // Zed uses a single width for both indents and tabs, so take the width of
+    // whichever the indent is made of.
+    let width = if use_tabs == Some(true) {
+        options.read_u32("TAB_SIZE")
+    } else {
+        options.read_u32("INDENT_SIZE")
+    };
+    if let Some(width) = width.and_then(NonZeroU32::new) {
+        settings.tab_size = Some(width);
+    }
+
+    if let Some(right_margin) = options.read_u32("RIGHT_MARGIN") {
+        settings.preferred_line_length = Some(right_margin);
+    }
+    // Soft margins are the IDE's visual guides, drawn like Zed's wrap guides.
+    if let Some(soft_margins) = options.read_string("SOFT_MARGINS") {
+        settings.wrap_guides = Some(
+            soft_margins
+                .split(',')
+                .filter_map(|margin| margin.trim().parse().ok())
+                .collect(),
+        );
+    }
+
+    // Zed orders imports with the language server's organize imports action,
+    // which it can run when formatting.
+    if let Some(sort_imports) = options
+        .read_bool("OPTIMIZE_IMPORTS_SORT_IMPORTS")
+        .or_else(|| options.read_bool("IMPORT_SORT_MODULE_NAME"))
+    {
+        let code_actions = settings.code_actions_on_format.get_or_insert_default();
+        code_actions.retain(|code_action| code_action.action != "source.organizeImports");
+        if sort_imports {
+            code_actions.push(CodeActionOnFormat::new("source.organizeImports"));
+        }
+    }
+}
+
+/// Maps a JetBrains IDE's identifier for a language to the names of the
+/// equivalent built-in Zed languages.
+fn language_names_for_jetbrains_id(jetbrains_id: &str) -> &'static [&'static str] {
+    match jetbrains_id {
+        "CSS" => &["CSS"],
+        "go" => &["Go"],
+        "HTML" => &["HTML"],
+        "JavaScript" => &["JavaScript"],
+        "JSON" => &["JSON", "JSONC"],
+        "Markdown" => &["Markdown"],
+        "ObjectiveC" => &["C", "C++"],
+        "Python" => &["Python"],
+        "Rust" => &["Rust"],
+        "Shell Script" => &["Shell Script"],
+        "TypeScript" => &["TypeScript", "TSX"],
+        "yaml" => &["YAML"],
+        _ => &[],
+    }
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Eq)]
@@ -738,4 +1605,197 @@ mod tests {
             ])
         );
     }
+
+    #[gpui::test]
+    fn test_has_formatter(cx: &mut App) {
+        let settings_store = SettingsStore::test(cx);
+        cx.set_global(settings_store);
+        AllLanguageSettings::register(cx);
+
+        let has_formatter = |cx: &mut App, update: fn(&mut LanguageSettingsContent)| {
+            cx.update_global::<SettingsStore, _>(|settings, cx| {
+                settings.update_user_settings(cx, |content| {
+                    let mut defaults = LanguageSettingsContent::default();
+                    update(&mut defaults);
+                    content.project.all_languages.defaults = defaults;
+                });
+            });
+            language_settings(None, None, cx).has_formatter()
+        };
+
+        assert!(has_formatter(cx, |_| {}));
+        assert!(!has_formatter(cx, |settings| {
+            settings.formatter = Some(FormatterList::Vec(Vec::new()));
+        }));
+        assert!(!has_formatter(cx, |settings| {
+            settings.enable_language_server = Some(false);
+        }));
+        assert!(has_formatter(cx, |settings| {
+            settings.enable_language_server = Some(false);
+            settings.formatter = Some(FormatterList::Single(Formatter::External {
+                command: "rustfmt".into(),
+                arguments: None,
+            }));
+        }));
+    }
+
+    #[test]
+    fn test_import_spellcheck_from_vscode() {
+        let vscode = settings::VsCodeSettings::from_str(
+            r#"{ "cSpell.enabled": true, "cSpell.language": "en-GB, de" }"#,
+            settings::VsCodeSettingsSource::VsCode,
+        )
+        .unwrap();
+        let mut content = SettingsContent::default();
+        AllLanguageSettings::import_from_vscode(&vscode, &mut content);
+
+        let spellcheck = content.project.all_languages.defaults.spellcheck.unwrap();
+        assert_eq!(spellcheck.enabled, Some(true));
+        assert_eq!(
+            spellcheck.dictionaries,
+            Some(vec!["en_GB".to_string(), "de".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_import_language_scoped_settings_from_vscode() {
+        let vscode = settings::VsCodeSettings::from_str(
+            r#"{
+                "editor.tabSize": 4,
+                "[rust]": {
+                    "editor.tabSize": 2,
+                    "editor.rulers": [100],
+                    "editor.defaultFormatter": "rust-lang.rust-analyzer"
+                },
+                "[javascript][typescript]": {
+                    "editor.defaultFormatter": "esbenp.prettier-vscode"
+                },
+                "[unknown-language]": {
+                    "editor.tabSize": 8
+                }
+            }"#,
+            settings::VsCodeSettingsSource::VsCode,
+        )
+        .unwrap();
+        let mut content = SettingsContent::default();
+        AllLanguageSettings::import_from_vscode(&vscode, &mut content);
+
+        let all_languages = &content.project.all_languages;
+        assert_eq!(all_languages.defaults.tab_size, NonZeroU32::new(4));
+        assert_eq!(all_languages.languages.0.len(), 3);
+
+        let rust = &all_languages.languages.0["Rust"];
+        assert_eq!(rust.tab_size, NonZeroU32::new(2));
+        assert_eq!(rust.wrap_guides, Some(vec![100]));
+        assert_eq!(
+            rust.formatter,
+            Some(FormatterList::Single(Formatter::LanguageServer(
+                LanguageServerFormatterSpecifier::Current
+            )))
+        );
+
+        for language_name in ["JavaScript", "TypeScript"] {
+            let settings = &all_languages.languages.0[language_name];
+            assert_eq!(settings.tab_size, None);
+            assert_eq!(
+                settings.formatter,
+                Some(FormatterList::Single(Formatter::Prettier))
+            );
+        }
+    }
+
+    #[test]
+    fn test_import_from_jetbrains() {
+        let code_style = settings::JetBrainsCodeStyle::from_xml(
+            r#"<code_scheme name="Project" version="173">
+              <option name="RIGHT_MARGIN" value="100" />
+              <option name="SOFT_MARGINS" value="80, 120" />
+              <option name="OTHER_INDENT_OPTIONS">
+                <value>
+                  <option name="INDENT_SIZE" value="4" />
+                  <option name="TAB_SIZE" value="8" />
+                </value>
+              </option>
+              <TypeScriptCodeStyleSettings>
+                <option name="IMPORT_SORT_MODULE_NAME" value="true" />
+              </TypeScriptCodeStyleSettings>
+              <codeStyleSettings language="ObjectiveC">
+                <indentOptions>
+                  <option name="TAB_SIZE" value="2" />
+                  <option name="USE_TAB_CHARACTER" value="true" />
+                </indentOptions>
+              </codeStyleSettings>
+              <codeStyleSettings language="JAVA">
+                <option name="RIGHT_MARGIN" value="80" />
+              </codeStyleSettings>
+            </code_scheme>"#,
+        )
+        .unwrap();
+        let mut content = SettingsContent::default();
+        AllLanguageSettings::import_from_jetbrains(&code_style, &mut content);
+
+        let all_languages = &content.project.all_languages;
+        assert_eq!(all_languages.defaults.tab_size, NonZeroU32::new(4));
+        assert_eq!(all_languages.defaults.hard_tabs, None);
+        assert_eq!(all_languages.defaults.preferred_line_length, Some(100));
+        assert_eq!(all_languages.defaults.wrap_guides, Some(vec![80, 120]));
+
+        let mut language_names = all_languages
+            .languages
+            .0
+            .keys()
+            .map(|name| name.to_string())
+            .collect::<Vec<_>>();
+        language_names.sort();
+        assert_eq!(language_names, ["C", "C++", "TSX", "TypeScript"]);
+
+        for language_name in ["C", "C++"] {
+            let settings = &all_languages.languages.0[language_name];
+            assert_eq!(settings.tab_size, NonZeroU32::new(2));
+            assert_eq!(settings.hard_tabs, Some(true));
+        }
+        for language_name in ["TypeScript", "TSX"] {
+            let settings = &all_languages.languages.0[language_name];
+            assert_eq!(
+                settings.code_actions_on_format,
+                Some(vec![CodeActionOnFormat::new("source.organizeImports")])
+            );
+        }
+    }
+
+    #[test]
+    fn test_file_type_globs() {
+        fn matching_suffix_len(globs: &FileTypeGlobs, path: &str) -> Option<usize> {
+            let path = std::path::Path::new(path);
+            let file_name = path.file_name().unwrap().to_str().unwrap();
+            let path = path.to_str().unwrap();
+            globs.matching_suffix_len(&[
+                (file_name, Candidate::new(file_name)),
+                (path, Candidate::new(path)),
+            ])
+        }
+
+        let globs = FileTypeGlobs::new(&[
+            "*.h".to_string(),
+            "!**/legacy/**".to_string(),
+            "**/legacy/modern/*.h".to_string(),
+        ])
+        .unwrap();
+        assert_eq!(matching_suffix_len(&globs, "src/a.h"), Some(3));
+        assert_eq!(matching_suffix_len(&globs, "src/a.c"), None);
+        assert_eq!(matching_suffix_len(&globs, "src/legacy/a.h"), None);
+        assert_eq!(
+            matching_suffix_len(&globs, "src/legacy/modern/a.h"),
+            Some(3)
+        );
+
+        // Exclusions only remove files matched by earlier patterns.
+        let globs = FileTypeGlobs::new(&["!**/legacy/**".to_string(), "*.h".to_string()]).unwrap();
+        assert_eq!(matching_suffix_len(&globs, "src/legacy/a.h"), Some(3));
+
+        let globs = FileTypeGlobs::new(&["!**/legacy/**".to_string()]).unwrap();
+        assert_eq!(matching_suffix_len(&globs, "src/legacy/a.h"), None);
+
+        assert!(FileTypeGlobs::new(&["!a[".to_string()]).is_err());
+    }
 }
@@ -0,0 +1,302 @@
+use collections::HashMap;
+use editor::{Editor, EditorEvent, SoftWrap};
+use futures::future::Shared;
+use gpui::{
+    App, AppContext as _, Entity, EventEmitter, FocusHandle, Focusable, Render, Subscription, Task,
+    actions,
+};
+use project::{Project, ProjectEnvironmentEvent, Worktree};
+use settings::SettingsStore;
+use std::{fmt::Write as _, path::Path, sync::Arc};
+use task::Shell;
+use ui::prelude::*;
+use workspace::{
+    Item, SplitDirection, Workspace, WorkspaceId, item::ItemEvent, searchable::SearchableItemHandle,
+};
+
+use crate::get_or_create_tool;
+
+actions!(
+    dev,
+    [
+        /// Opens a view of the environment variables that language servers, tasks and Git are
+        /// started with in each worktree.
+        OpenProjectEnvironment
+    ]
+);
+
+pub fn init(cx: &mut App) {
+    cx.observe_new(|workspace: &mut Workspace, _, _| {
+        workspace.register_action(|workspace, _: &OpenProjectEnvironment, window, cx| {
+            let project = workspace.project().clone();
+            get_or_create_tool(
+                workspace,
+                SplitDirection::Right,
+                window,
+                cx,
+                move |window, cx| EnvironmentView::new(project, window, cx),
+            );
+        });
+    })
+    .detach();
+}
+
+/// Shows the environment the project resolved for each worktree, and which variables of it come
+/// from the `env` setting.
+pub struct EnvironmentView {
+    project: Entity<Project>,
+    editor: Entity<Editor>,
+    refresh_task: Task<()>,
+    _subscriptions: Vec<Subscription>,
+}
+
+struct WorktreeEnvironment {
+    name: SharedString,
+    abs_path: Arc<Path>,
+    environment: Option<HashMap<String, String>>,
+    overrides: HashMap<String, String>,
+}
+
+impl EnvironmentView {
+    pub fn new(project: Entity<Project>, window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let editor = cx.new(|cx| {
+            let mut editor = Editor::multi_line(window, cx);
+            editor.hide_minimap_by_default(window, cx);
+            editor.set_show_git_diff_gutter(false, cx);
+            editor.set_show_runnables(false, cx);
+            editor.set_show_breakpoints(false, cx);
+            editor.set_read_only(true);
+            editor.set_soft_wrap_mode(SoftWrap::EditorWidth, cx);
+            editor
+        });
+        let subscriptions = vec![
+            cx.subscribe(&editor, |_, _, event: &EditorEvent, cx| {
+                cx.emit(event.clone())
+            }),
+            // Settings can change the overridden variables, and the environment of worktrees
+            // whose settings changed is loaded again.
+            cx.observe_global_in::<SettingsStore>(window, |this, window, cx| {
+                this.refresh(window, cx)
+            }),
+            cx.subscribe_in(
+                project.read(cx).environment(),
+                window,
+                |this, _, event, window, cx| match event {
+                    ProjectEnvironmentEvent::ErrorsUpdated
+                    | ProjectEnvironmentEvent::OverridesChanged(_) => this.refresh(window, cx),
+                },
+            ),
+        ];
+        let mut this = Self {
+            project,
+            editor,
+            refresh_task: Task::ready(()),
+            _subscriptions: subscriptions,
+        };
+        this.refresh(window, cx);
+        this
+    }
+
+    fn refresh(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let worktrees = self.project.read(cx).worktrees(cx).collect::<Vec<_>>();
+        let environments = worktrees
+            .into_iter()
+            .map(|worktree| self.worktree_environment(worktree, cx))
+            .collect::<Vec<_>>();
+        self.refresh_task = cx.spawn_in(window, async move |this, cx| {
+            let mut worktree_environments = Vec::with_capacity(environments.len());
+            for (mut worktree_environment, environment) in environments {
+                worktree_environment.environment = environment.await;
+                worktree_environments.push(worktree_environment);
+            }
+            let text = describe_environments(&worktree_environments);
+            this.update_in(cx, |this, window, cx| {
+                this.editor
+                    .update(cx, |editor, cx| editor.set_text(text, window, cx));
+            })
+            .ok();
+        });
+    }
+
+    fn worktree_environment(
+        &self,
+        worktree: Entity<Worktree>,
+        cx: &mut Context<Self>,
+    ) -> (
+        WorktreeEnvironment,
+        Shared<Task<Option<HashMap<String, String>>>>,
+    ) {
+        let name = SharedString::from(worktree.read(cx).root_name_str().to_string());
+        let abs_path = worktree.read(cx).abs_path();
+        let project_environment = self.project.read(cx).environment().clone();
+        let overrides = project_environment
+            .read(cx)
+            .environment_overrides(&abs_path, cx);
+        // Remote projects load the environment on the remote machine, which applies the
+        // overrides there.
+        let environment = if self.project.read(cx).is_local() {
+            project_environment.update(cx, |environment, cx| {
+                environment.get_worktree_environment(worktree, cx)
+            })
+        } else {
+            self.project.update(cx, |project, cx| {
+                project.directory_environment(&Shell::System, abs_path.clone(), cx)
+            })
+        };
+        let worktree_environment = WorktreeEnvironment {
+            name,
+            abs_path,
+            environment: None,
+            overrides,
+        };
+        (worktree_environment, environment)
+    }
+}
+
+fn describe_environments(worktree_environments: &[WorktreeEnvironment]) -> String {
+    if worktree_environments.is_empty() {
+        return "This project has no worktrees.\n".to_string();
+    }
+
+    let mut text = String::new();
+    for worktree in worktree_environments {
+        writeln!(
+            text,
+            "# {} ({})\n",
+            worktree.name,
+            worktree.abs_path.display()
+        )
+        .ok();
+        let Some(environment) = &worktree.environment else {
+            writeln!(
+                text,
+                "The environment couldn't be loaded, so language servers and tasks inherit Zed's own environment.\n"
+            )
+            .ok();
+            continue;
+        };
+
+        let mut names = worktree.overrides.keys().collect::<Vec<_>>();
+        names.sort();
+        if names.is_empty() {
+            writeln!(text, "No variables are overridden by the `env` setting.\n").ok();
+        } else {
+            writeln!(
+                text,
+                "Overridden by the `env` setting: {}\n",
+                names
+                    .into_iter()
+                    .map(String::as_str)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )
+            .ok();
+        }
+
+        if let Some(path) = environment.get("PATH") {
+            writeln!(text, "PATH:").ok();
+            for directory in std::env::split_paths(path) {
+                writeln!(text, "  {}", directory.display()).ok();
+            }
+            writeln!(text).ok();
+        }
+
+        let mut variables = environment.iter().collect::<Vec<_>>();
+        variables.sort();
+        for (name, value) in variables {
+            writeln!(text, "{name}={value}").ok();
+        }
+        writeln!(text).ok();
+    }
+    text
+}
+
+impl EventEmitter<EditorEvent> for EnvironmentView {}
+
+impl Focusable for EnvironmentView {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.editor.focus_handle(cx)
+    }
+}
+
+impl Render for EnvironmentView {
+    fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+        self.editor.clone()
+    }
+}
+
+impl Item for EnvironmentView {
+    type Event = EditorEvent;
+
+    fn to_item_events(event: &Self::Event, f: impl FnMut(ItemEvent)) {
+        Editor::to_item_events(event, f)
+    }
+
+    fn tab_content_text(&self, _detail: usize, _cx: &App) -> SharedString {
+        "Project Environment".into()
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        None
+    }
+
+    fn as_searchable(&self, _: &Entity<Self>) -> Option<Box<dyn SearchableItemHandle>> {
+        Some(Box::new(self.editor.clone()))
+    }
+
+    fn clone_on_split(
+        &self,
+        _workspace_id: Option<WorkspaceId>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Option<Entity<Self>>
+    where
+        Self: Sized,
+    {
+        Some(cx.new(|cx| Self::new(self.project.clone(), window, cx)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_describe_environments() {
+        let path = std::env::join_paths(["/usr/local/bin", "/usr/bin"])
+            .unwrap()
+            .into_string()
+            .unwrap();
+        let environment = [
+            ("PATH", path.as_str()),
+            ("RUST_LOG", "info"),
+            ("HOME", "/home/user"),
+        ]
+        .into_iter()
+        .map(|(name, value)| (name.to_string(), value.to_string()))
+        .collect();
+        let overrides = [("RUST_LOG".to_string(), "info".to_string())]
+            .into_iter()
+            .collect();
+        let text = describe_environments(&[
+            WorktreeEnvironment {
+                name: "zed".into(),
+                abs_path: Path::new("/home/user/zed").into(),
+                environment: Some(environment),
+                overrides,
+            },
+            WorktreeEnvironment {
+                name: "notes".into(),
+                abs_path: Path::new("/home/user/notes").into(),
+                environment: None,
+                overrides: HashMap::default(),
+            },
+        ]);
+
+        assert!(text.starts_with("# zed (/home/user/zed)\n\n"));
+        assert!(text.contains("Overridden by the `env` setting: RUST_LOG\n"));
+        assert!(text.contains("PATH:\n  /usr/local/bin\n  /usr/bin\n"));
+        assert!(text.contains(&format!("HOME=/home/user\nPATH={path}\nRUST_LOG=info\n")));
+        assert!(text.contains("# notes (/home/user/notes)\n\nThe environment couldn't be loaded"));
+    }
+}
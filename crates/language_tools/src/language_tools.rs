@@ -1,3 +1,4 @@
+mod environment_view;
 mod key_context_view;
 pub mod lsp_button;
 pub mod lsp_log_view;
@@ -17,6 +18,7 @@ pub fn init(cx: &mut App) {
     lsp_log_view::init(false, cx);
     syntax_tree_view::init(cx);
     key_context_view::init(cx);
+    environment_view::init(cx);
 }
 
 fn get_or_create_tool<T>(
@@ -0,0 +1,208 @@
+use anyhow::Result;
+use fs::{Fs, RemoveOptions};
+use futures::StreamExt as _;
+use parking_lot::Mutex;
+use sha2::{Digest as _, Sha256};
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+/// Stores snapshots of files on disk, in a directory per file that is named after the hash of
+/// the file's absolute path. Each snapshot is a copy of the file's text, named after the time it
+/// was taken in milliseconds since the Unix epoch.
+#[derive(Clone)]
+pub struct HistoryStore {
+    fs: Arc<dyn Fs>,
+    dir: PathBuf,
+    /// The total size of the snapshots, which is only known once the directory has been scanned
+    /// and is then kept up to date as snapshots are written, so that pruning doesn't need to
+    /// scan the directory until the budget is exceeded.
+    disk_usage: Arc<Mutex<Option<u64>>>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Snapshot {
+    pub path: PathBuf,
+    pub timestamp_ms: u64,
+    pub len: u64,
+}
+
+impl HistoryStore {
+    pub fn new(fs: Arc<dyn Fs>, dir: PathBuf) -> Self {
+        Self {
+            fs,
+            dir,
+            disk_usage: Arc::default(),
+        }
+    }
+
+    fn file_dir(&self, abs_path: &Path) -> PathBuf {
+        let digest = Sha256::digest(abs_path.to_string_lossy().as_bytes());
+        self.dir.join(format!("{digest:x}"))
+    }
+
+    /// Records a snapshot of the file's text, unless it's identical to the latest snapshot.
+    /// Returns whether a snapshot was written.
+    pub async fn record(&self, abs_path: &Path, text: &str, timestamp_ms: u64) -> Result<bool> {
+        let file_dir = self.file_dir(abs_path);
+        let snapshots = self.snapshots_in(&file_dir).await?;
+        if let Some(latest) = snapshots.first() {
+            if latest.timestamp_ms >= timestamp_ms {
+                return Ok(false);
+            }
+            if latest.len == text.len() as u64 && self.fs.load(&latest.path).await? == text {
+                return Ok(false);
+            }
+        }
+        self.fs
+            .write(&file_dir.join(timestamp_ms.to_string()), text.as_bytes())
+            .await?;
+        if let Some(disk_usage) = self.disk_usage.lock().as_mut() {
+            *disk_usage += text.len() as u64;
+        }
+        Ok(true)
+    }
+
+    /// Returns the snapshots of the file, newest first.
+    pub async fn snapshots(&self, abs_path: &Path) -> Result<Vec<Snapshot>> {
+        self.snapshots_in(&self.file_dir(abs_path)).await
+    }
+
+    pub async fn load(&self, snapshot: &Snapshot) -> Result<String> {
+        self.fs.load(&snapshot.path).await
+    }
+
+    /// Deletes the oldest snapshots of all files until the remaining ones fit in `max_bytes`.
+    pub async fn prune(&self, max_bytes: u64) -> Result<()> {
+        if self
+            .disk_usage
+            .lock()
+            .is_some_and(|disk_usage| disk_usage <= max_bytes)
+        {
+            return Ok(());
+        }
+
+        let mut file_dirs = Vec::new();
+        let mut snapshots = Vec::new();
+        if self.fs.is_dir(&self.dir).await {
+            let mut entries = self.fs.read_dir(&self.dir).await?;
+            while let Some(entry) = entries.next().await {
+                let file_dir = entry?;
+                if self.fs.is_dir(&file_dir).await {
+                    snapshots.extend(self.snapshots_in(&file_dir).await?);
+                    file_dirs.push(file_dir);
+                }
+            }
+        }
+
+        let mut total_bytes = snapshots.iter().map(|snapshot| snapshot.len).sum::<u64>();
+        if total_bytes <= max_bytes {
+            *self.disk_usage.lock() = Some(total_bytes);
+            return Ok(());
+        }
+        snapshots.sort_by_key(|snapshot| snapshot.timestamp_ms);
+        for snapshot in snapshots {
+            if total_bytes <= max_bytes {
+                break;
+            }
+            self.fs
+                .remove_file(&snapshot.path, RemoveOptions::default())
+                .await?;
+            total_bytes -= snapshot.len;
+        }
+        *self.disk_usage.lock() = Some(total_bytes);
+
+        for file_dir in file_dirs {
+            let mut entries = self.fs.read_dir(&file_dir).await?;
+            if entries.next().await.is_none() {
+                self.fs
+                    .remove_dir(&file_dir, RemoveOptions::default())
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+
+    async fn snapshots_in(&self, file_dir: &Path) -> Result<Vec<Snapshot>> {
+        let mut snapshots = Vec::new();
+        if !self.fs.is_dir(file_dir).await {
+            return Ok(snapshots);
+        }
+        let mut entries = self.fs.read_dir(file_dir).await?;
+        while let Some(entry) = entries.next().await {
+            let path = entry?;
+            let Some(timestamp_ms) = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .and_then(|name| name.parse::<u64>().ok())
+            else {
+                continue;
+            };
+            let Some(metadata) = self.fs.metadata(&path).await? else {
+                continue;
+            };
+            snapshots.push(Snapshot {
+                path,
+                timestamp_ms,
+                len: metadata.len,
+            });
+        }
+        snapshots.sort_by_key(|snapshot| std::cmp::Reverse(snapshot.timestamp_ms));
+        Ok(snapshots)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fs::FakeFs;
+    use gpui::TestAppContext;
+
+    #[gpui::test]
+    async fn test_record_and_prune(cx: &mut TestAppContext) {
+        let fs = FakeFs::new(cx.executor());
+        let store = HistoryStore::new(fs.clone(), PathBuf::from("/history"));
+        let main_path = Path::new("/project/src/main.rs");
+        let lib_path = Path::new("/project/src/lib.rs");
+
+        assert!(store.record(main_path, "fn main() {}", 1000).await.unwrap());
+        // Unchanged text isn't recorded again.
+        assert!(!store.record(main_path, "fn main() {}", 2000).await.unwrap());
+        assert!(store.record(lib_path, "mod a;", 3000).await.unwrap());
+        assert!(
+            store
+                .record(main_path, "fn main() { }", 4000)
+                .await
+                .unwrap()
+        );
+
+        let snapshots = store.snapshots(main_path).await.unwrap();
+        assert_eq!(
+            snapshots
+                .iter()
+                .map(|snapshot| snapshot.timestamp_ms)
+                .collect::<Vec<_>>(),
+            [4000, 1000]
+        );
+        assert_eq!(store.load(&snapshots[1]).await.unwrap(), "fn main() {}");
+
+        // The oldest snapshots are deleted first, regardless of which file they belong to.
+        store.prune(20).await.unwrap();
+        let snapshots = store.snapshots(main_path).await.unwrap();
+        assert_eq!(snapshots.len(), 1);
+        assert_eq!(snapshots[0].timestamp_ms, 4000);
+        assert_eq!(store.snapshots(lib_path).await.unwrap().len(), 1);
+
+        store.prune(13).await.unwrap();
+        assert!(store.snapshots(lib_path).await.unwrap().is_empty());
+        assert_eq!(store.snapshots(main_path).await.unwrap().len(), 1);
+        assert!(!fs.is_dir(&store.file_dir(lib_path)).await);
+
+        // Snapshots written after a scan count towards the budget without rescanning.
+        assert!(store.record(lib_path, "mod b;", 5000).await.unwrap());
+        store.prune(13).await.unwrap();
+        assert!(store.snapshots(main_path).await.unwrap().is_empty());
+        assert_eq!(store.snapshots(lib_path).await.unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,245 @@
+mod history_store;
+mod local_history_view;
+
+use anyhow::Result;
+use collections::HashMap;
+use fs::Fs;
+use gpui::{
+    App, AppContext as _, Context, Entity, EntityId, EventEmitter, Global, Subscription, Task,
+    WeakEntity,
+};
+use language::{Buffer, BufferEvent, Rope};
+use project::{Project, buffer_store::BufferStoreEvent};
+use settings::Settings;
+use std::{path::PathBuf, sync::Arc, time::Duration};
+
+pub use history_store::{HistoryStore, Snapshot};
+pub use local_history_view::{LocalHistoryView, ShowLocalHistory};
+
+/// Files larger than this fraction of the disk budget aren't snapshotted, because a few
+/// snapshots of them would evict the history of every other file.
+const MAX_FILE_SIZE_DIVISOR: u64 = 16;
+
+#[derive(Clone, Debug)]
+pub struct LocalHistorySettings {
+    pub enabled: bool,
+    pub snapshot_interval: Duration,
+    pub max_disk_usage: u64,
+}
+
+impl Settings for LocalHistorySettings {
+    fn from_settings(content: &settings::SettingsContent) -> Self {
+        let local_history = content.local_history.clone().unwrap();
+        Self {
+            enabled: local_history.enabled.unwrap(),
+            snapshot_interval: Duration::from_secs(
+                local_history.snapshot_interval_seconds.unwrap().max(1),
+            ),
+            max_disk_usage: local_history.max_disk_usage_mb.unwrap() * 1024 * 1024,
+        }
+    }
+}
+
+pub fn init(fs: Arc<dyn Fs>, cx: &mut App) {
+    LocalHistorySettings::register(cx);
+
+    let store = HistoryStore::new(fs, paths::local_history_dir().clone());
+    let local_history = cx.new(|cx| LocalHistory::new(store, cx));
+    cx.set_global(GlobalLocalHistory(local_history));
+
+    cx.observe_new(|project: &mut Project, _, cx| {
+        // Remote buffers are snapshotted on the machine that edits them, if at all, since their
+        // paths don't refer to files on this one.
+        if !project.is_local() {
+            return;
+        }
+        let buffer_store = project.buffer_store().clone();
+        for buffer in buffer_store.read(cx).buffers() {
+            LocalHistory::global(cx)
+                .update(cx, |local_history, cx| local_history.track(buffer, cx));
+        }
+        cx.subscribe(&buffer_store, |_, _, event, cx| {
+            if let BufferStoreEvent::BufferAdded(buffer) = event {
+                LocalHistory::global(cx).update(cx, |local_history, cx| {
+                    local_history.track(buffer.clone(), cx)
+                });
+            }
+        })
+        .detach();
+    })
+    .detach();
+
+    local_history_view::init(cx);
+}
+
+struct GlobalLocalHistory(Entity<LocalHistory>);
+
+impl Global for GlobalLocalHistory {}
+
+pub enum LocalHistoryEvent {
+    SnapshotTaken(PathBuf),
+}
+
+/// Snapshots the files of local projects when they're saved or reloaded, and periodically while
+/// they have unsaved edits.
+pub struct LocalHistory {
+    store: HistoryStore,
+    buffers: HashMap<EntityId, TrackedBuffer>,
+    _snapshot_task: Task<()>,
+}
+
+struct TrackedBuffer {
+    buffer: WeakEntity<Buffer>,
+    /// The text the buffer was opened with, which is recorded once it's first edited so that the
+    /// edits can be reverted even if the file was never snapshotted before.
+    unrecorded_base_text: Option<Rope>,
+    recorded_version: Option<clock::Global>,
+    _subscriptions: [Subscription; 2],
+}
+
+impl EventEmitter<LocalHistoryEvent> for LocalHistory {}
+
+impl LocalHistory {
+    pub fn global(cx: &App) -> Entity<Self> {
+        cx.global::<GlobalLocalHistory>().0.clone()
+    }
+
+    fn new(store: HistoryStore, cx: &mut Context<Self>) -> Self {
+        let snapshot_task = cx.spawn(async move |this, cx| {
+            loop {
+                let Ok(interval) =
+                    cx.update(|cx| LocalHistorySettings::get_global(cx).snapshot_interval)
+                else {
+                    break;
+                };
+                cx.background_executor().timer(interval).await;
+                if this
+                    .update(cx, |this, cx| this.snapshot_dirty_buffers(cx))
+                    .is_err()
+                {
+                    break;
+                }
+            }
+        });
+        Self {
+            store,
+            buffers: HashMap::default(),
+            _snapshot_task: snapshot_task,
+        }
+    }
+
+    pub fn store(&self) -> &HistoryStore {
+        &self.store
+    }
+
+    fn track(&mut self, buffer: Entity<Buffer>, cx: &mut Context<Self>) {
+        let buffer_id = buffer.entity_id();
+        if self.buffers.contains_key(&buffer_id) {
+            return;
+        }
+        let subscriptions = [
+            cx.subscribe(&buffer, |this, buffer, event, cx| match event {
+                BufferEvent::Edited => this.record_base_text(&buffer, cx),
+                BufferEvent::Saved | BufferEvent::Reloaded => {
+                    this.snapshot(&buffer, cx).detach_and_log_err(cx)
+                }
+                _ => {}
+            }),
+            cx.observe_release(&buffer, move |this, _, _| {
+                this.buffers.remove(&buffer_id);
+            }),
+        ];
+        self.buffers.insert(
+            buffer_id,
+            TrackedBuffer {
+                buffer: buffer.downgrade(),
+                unrecorded_base_text: Some(buffer.read(cx).as_rope().clone()),
+                recorded_version: None,
+                _subscriptions: subscriptions,
+            },
+        );
+    }
+
+    fn record_base_text(&mut self, buffer: &Entity<Buffer>, cx: &mut Context<Self>) {
+        let Some(base_text) = self
+            .buffers
+            .get_mut(&buffer.entity_id())
+            .and_then(|tracked| tracked.unrecorded_base_text.take())
+        else {
+            return;
+        };
+        self.record(buffer, base_text, cx).detach_and_log_err(cx);
+    }
+
+    fn snapshot_dirty_buffers(&mut self, cx: &mut Context<Self>) {
+        let dirty_buffers = self
+            .buffers
+            .values()
+            .filter_map(|tracked| tracked.buffer.upgrade())
+            .filter(|buffer| buffer.read(cx).is_dirty())
+            .collect::<Vec<_>>();
+        for buffer in dirty_buffers {
+            self.snapshot(&buffer, cx).detach_and_log_err(cx);
+        }
+    }
+
+    /// Snapshots the buffer's current text, unless it hasn't changed since it was last
+    /// snapshotted.
+    pub fn snapshot(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        let Some(tracked) = self.buffers.get_mut(&buffer.entity_id()) else {
+            return Task::ready(Ok(()));
+        };
+        let version = buffer.read(cx).version();
+        if tracked.recorded_version.as_ref() == Some(&version) {
+            return Task::ready(Ok(()));
+        }
+        tracked.recorded_version = Some(version);
+        // The base text only needs recording separately if the buffer is snapshotted before
+        // it's edited.
+        tracked.unrecorded_base_text = None;
+        let text = buffer.read(cx).as_rope().clone();
+        self.record(buffer, text, cx)
+    }
+
+    fn record(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        text: Rope,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        let settings = LocalHistorySettings::get_global(cx);
+        let max_disk_usage = settings.max_disk_usage;
+        if !settings.enabled || text.len() as u64 > max_disk_usage / MAX_FILE_SIZE_DIVISOR {
+            return Task::ready(Ok(()));
+        }
+        // Private files, such as `.env` files and keys, are never copied into the history.
+        let Some(abs_path) = buffer
+            .read(cx)
+            .file()
+            .filter(|file| !file.is_private())
+            .and_then(|file| file.as_local())
+            .map(|file| file.abs_path(cx))
+        else {
+            return Task::ready(Ok(()));
+        };
+
+        let store = self.store.clone();
+        let timestamp_ms =
+            (time::OffsetDateTime::now_utc().unix_timestamp_nanos() / 1_000_000) as u64;
+        cx.spawn(async move |this, cx| {
+            let text = cx.background_spawn(async move { text.to_string() }).await;
+            let recorded = store.record(&abs_path, &text, timestamp_ms).await?;
+            store.prune(max_disk_usage).await?;
+            if recorded {
+                this.update(cx, |_, cx| {
+                    cx.emit(LocalHistoryEvent::SnapshotTaken(abs_path))
+                })?;
+            }
+            Ok(())
+        })
+    }
+}
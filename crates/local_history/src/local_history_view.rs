@@ -0,0 +1,325 @@
+use crate::{LocalHistory, LocalHistoryEvent, Snapshot};
+use editor::Editor;
+use gpui::{
+    App, AppContext as _, Entity, EventEmitter, FocusHandle, Focusable, Subscription, Task, actions,
+};
+use language::Buffer;
+use std::path::PathBuf;
+use time::OffsetDateTime;
+use time_format::TimestampFormat;
+use ui::{ListItem, ListItemSpacing, Tooltip, prelude::*};
+use util::{ResultExt as _, size::format_file_size};
+use workspace::{
+    Workspace,
+    item::{Item, ItemEvent},
+};
+
+actions!(
+    local_history,
+    [
+        /// Shows the snapshots of the active file that were kept independently of version
+        /// control, and allows restoring them.
+        ShowLocalHistory
+    ]
+);
+
+pub(crate) fn init(cx: &mut App) {
+    cx.observe_new(|workspace: &mut Workspace, _, _| {
+        workspace.register_action(|workspace, _: &ShowLocalHistory, window, cx| {
+            let Some(editor) = workspace.active_item_as::<Editor>(cx) else {
+                return;
+            };
+            let Some(buffer) = editor.read(cx).buffer().read(cx).as_singleton() else {
+                return;
+            };
+            let Some(abs_path) = buffer
+                .read(cx)
+                .file()
+                .and_then(|file| file.as_local())
+                .map(|file| file.abs_path(cx))
+            else {
+                return;
+            };
+
+            let existing = workspace
+                .active_pane()
+                .read(cx)
+                .items_of_type::<LocalHistoryView>()
+                .find(|view| view.read(cx).buffer == buffer);
+            if let Some(existing) = existing {
+                workspace.activate_item(&existing, true, true, window, cx);
+                return;
+            }
+            let view = cx.new(|cx| LocalHistoryView::new(buffer, abs_path, window, cx));
+            workspace.add_item_to_active_pane(Box::new(view), None, true, window, cx);
+        });
+    })
+    .detach();
+}
+
+/// Lists the snapshots of a file, newest first, and previews the selected one.
+pub struct LocalHistoryView {
+    buffer: Entity<Buffer>,
+    abs_path: PathBuf,
+    snapshots: Vec<Snapshot>,
+    selected_index: Option<usize>,
+    preview_buffer: Entity<Buffer>,
+    preview: Entity<Editor>,
+    focus_handle: FocusHandle,
+    refresh_task: Task<()>,
+    load_task: Task<()>,
+    _subscription: Subscription,
+}
+
+impl LocalHistoryView {
+    fn new(
+        buffer: Entity<Buffer>,
+        abs_path: PathBuf,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let language = buffer.read(cx).language().cloned();
+        let preview_buffer = cx.new(|cx| {
+            let mut preview_buffer = Buffer::local("", cx);
+            preview_buffer.set_language(language, cx);
+            preview_buffer
+        });
+        let preview = cx.new(|cx| {
+            let mut editor = Editor::for_buffer(preview_buffer.clone(), None, window, cx);
+            editor.set_read_only(true);
+            editor.set_show_git_diff_gutter(false, cx);
+            editor.set_show_breakpoints(false, cx);
+            editor.set_show_runnables(false, cx);
+            editor
+        });
+        let subscription = cx.subscribe_in(
+            &LocalHistory::global(cx),
+            window,
+            |this, _, event, window, cx| match event {
+                LocalHistoryEvent::SnapshotTaken(abs_path) => {
+                    if *abs_path == this.abs_path {
+                        this.refresh(window, cx);
+                    }
+                }
+            },
+        );
+        let mut this = Self {
+            buffer,
+            abs_path,
+            snapshots: Vec::new(),
+            selected_index: None,
+            preview_buffer,
+            preview,
+            focus_handle: cx.focus_handle(),
+            refresh_task: Task::ready(()),
+            load_task: Task::ready(()),
+            _subscription: subscription,
+        };
+        this.refresh(window, cx);
+        this
+    }
+
+    fn refresh(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let store = LocalHistory::global(cx).read(cx).store().clone();
+        let abs_path = self.abs_path.clone();
+        self.refresh_task = cx.spawn_in(window, async move |this, cx| {
+            let Some(snapshots) = store.snapshots(&abs_path).await.log_err() else {
+                return;
+            };
+            this.update_in(cx, |this, window, cx| {
+                // Keep the same snapshot selected when newer ones are added in front of it.
+                let selected_timestamp = this
+                    .selected_index
+                    .and_then(|index| this.snapshots.get(index))
+                    .map(|snapshot| snapshot.timestamp_ms);
+                this.snapshots = snapshots;
+                let selected_index = selected_timestamp
+                    .and_then(|timestamp_ms| {
+                        this.snapshots
+                            .iter()
+                            .position(|snapshot| snapshot.timestamp_ms == timestamp_ms)
+                    })
+                    .or_else(|| (!this.snapshots.is_empty()).then_some(0));
+                match selected_index {
+                    Some(index) => this.select(index, window, cx),
+                    None => {
+                        this.selected_index = None;
+                        cx.notify();
+                    }
+                }
+            })
+            .ok();
+        });
+    }
+
+    fn select(&mut self, index: usize, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(snapshot) = self.snapshots.get(index).cloned() else {
+            return;
+        };
+        self.selected_index = Some(index);
+        cx.notify();
+        let store = LocalHistory::global(cx).read(cx).store().clone();
+        self.load_task = cx.spawn_in(window, async move |this, cx| {
+            let Some(text) = store.load(&snapshot).await.log_err() else {
+                return;
+            };
+            this.update(cx, |this, cx| {
+                this.preview_buffer
+                    .update(cx, |preview_buffer, cx| preview_buffer.set_text(text, cx));
+            })
+            .ok();
+        });
+    }
+
+    fn restore_selected(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(snapshot) = self
+            .selected_index
+            .and_then(|index| self.snapshots.get(index))
+            .cloned()
+        else {
+            return;
+        };
+        let local_history = LocalHistory::global(cx);
+        let store = local_history.read(cx).store().clone();
+        let buffer = self.buffer.clone();
+        cx.spawn_in(window, async move |_, cx| {
+            let text = store.load(&snapshot).await?;
+            // Snapshot the current text first, so that it can be restored in turn.
+            local_history
+                .update(cx, |local_history, cx| local_history.snapshot(&buffer, cx))?
+                .await?;
+            buffer.update(cx, |buffer, cx| buffer.set_text(text, cx))?;
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn render_snapshot(
+        &self,
+        index: usize,
+        snapshot: &Snapshot,
+        now: OffsetDateTime,
+        cx: &Context<Self>,
+    ) -> impl IntoElement {
+        let timestamp =
+            OffsetDateTime::from_unix_timestamp_nanos(snapshot.timestamp_ms as i128 * 1_000_000)
+                .map(|timestamp| {
+                    time_format::format_local_timestamp(
+                        timestamp,
+                        now,
+                        TimestampFormat::EnhancedAbsolute,
+                    )
+                })
+                .unwrap_or_default();
+        ListItem::new(("snapshot", index))
+            .inset(true)
+            .spacing(ListItemSpacing::Sparse)
+            .toggle_state(self.selected_index == Some(index))
+            .child(
+                h_flex()
+                    .w_full()
+                    .justify_between()
+                    .gap_2()
+                    .child(Label::new(timestamp))
+                    .child(
+                        Label::new(format_file_size(snapshot.len, false))
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    ),
+            )
+            .on_click(cx.listener(move |this, _, window, cx| this.select(index, window, cx)))
+    }
+}
+
+impl Render for LocalHistoryView {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let now = OffsetDateTime::now_utc();
+        let timeline = if self.snapshots.is_empty() {
+            div()
+                .p_2()
+                .child(
+                    Label::new("No snapshots of this file have been taken yet.")
+                        .color(Color::Muted),
+                )
+                .into_any_element()
+        } else {
+            v_flex()
+                .children(
+                    self.snapshots
+                        .iter()
+                        .enumerate()
+                        .map(|(index, snapshot)| self.render_snapshot(index, snapshot, now, cx)),
+                )
+                .into_any_element()
+        };
+
+        h_flex()
+            .track_focus(&self.focus_handle)
+            .size_full()
+            .bg(cx.theme().colors().editor_background)
+            .child(
+                v_flex()
+                    .w_64()
+                    .h_full()
+                    .flex_none()
+                    .border_r_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .child(
+                        h_flex().p_2().child(
+                            Button::new("restore-snapshot", "Restore")
+                                .icon(IconName::RotateCcw)
+                                .icon_size(IconSize::Small)
+                                .icon_position(IconPosition::Start)
+                                .disabled(self.selected_index.is_none())
+                                .tooltip(Tooltip::text(
+                                    "Replace the file's text with the selected snapshot, which can be undone",
+                                ))
+                                .on_click(cx.listener(|this, _, window, cx| {
+                                    this.restore_selected(window, cx)
+                                })),
+                        ),
+                    )
+                    .child(
+                        div()
+                            .id("local-history-timeline")
+                            .flex_1()
+                            .overflow_y_scroll()
+                            .child(timeline),
+                    ),
+            )
+            .child(div().flex_1().h_full().child(self.preview.clone()))
+    }
+}
+
+impl Focusable for LocalHistoryView {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl EventEmitter<ItemEvent> for LocalHistoryView {}
+
+impl Item for LocalHistoryView {
+    type Event = ItemEvent;
+
+    fn tab_content_text(&self, _detail: usize, _cx: &App) -> SharedString {
+        let file_name = self
+            .abs_path
+            .file_name()
+            .map(|file_name| file_name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        format!("Local History: {file_name}").into()
+    }
+
+    fn tab_tooltip_text(&self, _cx: &App) -> Option<SharedString> {
+        Some(self.abs_path.to_string_lossy().into_owned().into())
+    }
+
+    fn show_toolbar(&self) -> bool {
+        false
+    }
+
+    fn to_item_events(event: &Self::Event, mut f: impl FnMut(ItemEvent)) {
+        f(*event)
+    }
+}
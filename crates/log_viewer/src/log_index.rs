@@ -0,0 +1,203 @@
+use std::ops::Range;
+
+/// The number of bytes at the start of a line that are searched for its level.
+const LEVEL_SEARCH_LEN: usize = 256;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum LogLevel {
+    Trace,
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+impl LogLevel {
+    pub const ALL: [LogLevel; 5] = [
+        LogLevel::Trace,
+        LogLevel::Debug,
+        LogLevel::Info,
+        LogLevel::Warn,
+        LogLevel::Error,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            LogLevel::Trace => "Trace",
+            LogLevel::Debug => "Debug",
+            LogLevel::Info => "Info",
+            LogLevel::Warn => "Warn",
+            LogLevel::Error => "Error",
+        }
+    }
+}
+
+/// Finds the level of a log line, from the first level name that appears as a whole word near
+/// its start.
+pub fn detect_level(line: &[u8]) -> Option<LogLevel> {
+    let line = &line[..line.len().min(LEVEL_SEARCH_LEN)];
+    line.split(|byte| !byte.is_ascii_alphabetic())
+        .find_map(|word| match word.to_ascii_uppercase().as_slice() {
+            b"TRACE" | b"TRC" => Some(LogLevel::Trace),
+            b"DEBUG" | b"DBG" => Some(LogLevel::Debug),
+            b"INFO" | b"INF" => Some(LogLevel::Info),
+            b"WARN" | b"WARNING" | b"WRN" => Some(LogLevel::Warn),
+            b"ERROR" | b"ERR" | b"FATAL" | b"CRITICAL" | b"PANIC" => Some(LogLevel::Error),
+            _ => None,
+        })
+}
+
+/// The offsets and levels of the lines of a log file, built incrementally as the file is read so
+/// that the file itself never needs to be held in memory.
+#[derive(Clone, Default)]
+pub struct LineIndex {
+    line_starts: Vec<u64>,
+    /// The level of each line. Lines without a level, such as the continuation of a stack
+    /// trace, take the level of the line before them.
+    levels: Vec<Option<LogLevel>>,
+    indexed_len: u64,
+    /// The start of the last line, if it hasn't been terminated yet, kept to detect its level
+    /// once more of it has been read.
+    last_line_prefix: Vec<u8>,
+}
+
+impl LineIndex {
+    pub fn line_count(&self) -> usize {
+        self.line_starts.len()
+    }
+
+    /// The number of bytes of the file that have been indexed.
+    pub fn indexed_len(&self) -> u64 {
+        self.indexed_len
+    }
+
+    pub fn level(&self, line: usize) -> Option<LogLevel> {
+        self.levels.get(line).copied().flatten()
+    }
+
+    /// The byte range of the given line, including its line ending.
+    pub fn line_range(&self, line: usize) -> Range<u64> {
+        let start = self.line_starts[line];
+        let end = self
+            .line_starts
+            .get(line + 1)
+            .copied()
+            .unwrap_or(self.indexed_len);
+        start..end
+    }
+
+    /// Indexes the next chunk of the file, which must start where the previous one ended.
+    pub fn append(&mut self, chunk: &[u8]) {
+        let chunk_start = self.indexed_len;
+        let mut line_start = 0;
+        while line_start < chunk.len() {
+            let line_end = chunk[line_start..]
+                .iter()
+                .position(|byte| *byte == b'\n')
+                .map(|newline| line_start + newline + 1);
+            let line = &chunk[line_start..line_end.unwrap_or(chunk.len())];
+
+            let continues_last_line = line_start == 0 && !self.last_line_prefix.is_empty();
+            if continues_last_line {
+                let remaining_len = LEVEL_SEARCH_LEN.saturating_sub(self.last_line_prefix.len());
+                self.last_line_prefix
+                    .extend_from_slice(&line[..line.len().min(remaining_len)]);
+                self.levels.pop();
+            } else {
+                self.line_starts.push(chunk_start + line_start as u64);
+                self.last_line_prefix.clear();
+                self.last_line_prefix
+                    .extend_from_slice(&line[..line.len().min(LEVEL_SEARCH_LEN)]);
+            }
+            let previous_level = self.levels.last().copied().flatten();
+            self.levels
+                .push(detect_level(&self.last_line_prefix).or(previous_level));
+
+            match line_end {
+                Some(line_end) => {
+                    self.last_line_prefix.clear();
+                    line_start = line_end;
+                }
+                None => break,
+            }
+        }
+        self.indexed_len += chunk.len() as u64;
+    }
+}
+
+/// Splits a chunk of a file into the text of the given lines, with their line endings removed.
+pub fn split_lines(
+    index: &LineIndex,
+    lines: Range<usize>,
+    chunk_start: u64,
+    chunk: &[u8],
+) -> Vec<String> {
+    lines
+        .map(|line| {
+            let range = index.line_range(line);
+            let start = (range.start.saturating_sub(chunk_start) as usize).min(chunk.len());
+            let end = (range.end.saturating_sub(chunk_start) as usize).min(chunk.len());
+            let text = &chunk[start..end];
+            let text = text.strip_suffix(b"\n").unwrap_or(text);
+            let text = text.strip_suffix(b"\r").unwrap_or(text);
+            String::from_utf8_lossy(text).into_owned()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_level() {
+        assert_eq!(
+            detect_level(b"2024-01-01T00:00:00Z ERROR [db] connection lost"),
+            Some(LogLevel::Error)
+        );
+        assert_eq!(
+            detect_level(b"[warning] disk almost full"),
+            Some(LogLevel::Warn)
+        );
+        assert_eq!(detect_level(b"level=debug msg=hi"), Some(LogLevel::Debug));
+        assert_eq!(detect_level(b"INFORMATION about errors"), None);
+        assert_eq!(detect_level(b"    at main.rs:10"), None);
+    }
+
+    #[test]
+    fn test_line_index() {
+        let text = b"INFO start\n  detail\nERROR failed\r\nWARN partial line";
+        let mut index = LineIndex::default();
+        // Split the text in the middle of lines, to check that lines spanning chunks are joined.
+        index.append(&text[..3]);
+        index.append(&text[3..25]);
+        index.append(&text[25..]);
+
+        assert_eq!(index.line_count(), 4);
+        assert_eq!(index.indexed_len(), text.len() as u64);
+        assert_eq!(
+            (0..4).map(|line| index.level(line)).collect::<Vec<_>>(),
+            [
+                Some(LogLevel::Info),
+                Some(LogLevel::Info),
+                Some(LogLevel::Error),
+                Some(LogLevel::Warn)
+            ]
+        );
+        assert_eq!(
+            split_lines(&index, 0..4, 0, text),
+            [
+                "INFO start",
+                "  detail",
+                "ERROR failed",
+                "WARN partial line"
+            ]
+        );
+
+        // The unterminated last line is extended by the next chunk.
+        index.append(b" continued\nDEBUG next\n");
+        assert_eq!(index.line_count(), 5);
+        assert_eq!(index.line_range(3), 34..62);
+        assert_eq!(index.level(4), Some(LogLevel::Debug));
+    }
+}
@@ -0,0 +1,66 @@
+mod log_index;
+mod log_viewer_view;
+
+use gpui::{App, Context, Window, actions};
+use workspace::{Workspace, invalid_buffer_view::InvalidBufferView};
+
+pub use crate::log_viewer_view::LogViewer;
+
+actions!(
+    log_viewer,
+    [
+        /// Opens the active file in the log viewer.
+        OpenLogViewer,
+        /// Toggles keeping the end of the log file in view as it grows.
+        ToggleFollow,
+    ]
+);
+
+pub fn init(cx: &mut App) {
+    cx.observe_new(|workspace: &mut Workspace, _, _| {
+        workspace.register_action(open_log_viewer);
+    })
+    .detach();
+}
+
+fn open_log_viewer(
+    workspace: &mut Workspace,
+    _: &OpenLogViewer,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let Some(active_item) = workspace.active_item(cx) else {
+        return;
+    };
+    let abs_path = if let Some(invalid_buffer_view) = active_item.downcast::<InvalidBufferView>() {
+        Some(invalid_buffer_view.read(cx).abs_path.to_path_buf())
+    } else {
+        active_item.project_path(cx).and_then(|project_path| {
+            workspace
+                .project()
+                .read(cx)
+                .absolute_path(&project_path, cx)
+        })
+    };
+    let Some(abs_path) = abs_path else {
+        return;
+    };
+    if !workspace.project().read(cx).is_local() {
+        workspace.show_error(&"The log viewer is only available for local files", cx);
+        return;
+    }
+
+    let fs = workspace.app_state().fs.clone();
+    workspace.active_pane().update(cx, |pane, cx| {
+        let existing_item_index = pane.items().position(|item| {
+            item.downcast::<LogViewer>()
+                .is_some_and(|log_viewer| log_viewer.read(cx).abs_path().as_ref() == abs_path)
+        });
+        if let Some(existing_item_index) = existing_item_index {
+            pane.activate_item(existing_item_index, true, true, window, cx);
+        } else {
+            let log_viewer = cx.new(|cx| LogViewer::new(abs_path.into(), fs, window, cx));
+            pane.add_item(Box::new(log_viewer), true, true, None, window, cx);
+        }
+    });
+}
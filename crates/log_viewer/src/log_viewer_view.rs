@@ -0,0 +1,694 @@
+use std::{ops::Range, path::Path, sync::Arc, time::Duration};
+
+use anyhow::Result;
+use collections::{HashMap, HashSet};
+use editor::{Editor, EditorEvent};
+use file_icons::FileIcons;
+use fs::Fs;
+use futures::StreamExt as _;
+use gpui::{
+    App, AsyncApp, Context, Entity, EventEmitter, FocusHandle, Focusable, ScrollStrategy,
+    Subscription, Task, UniformListScrollHandle, WeakEntity, Window, uniform_list,
+};
+use regex::{Regex, RegexBuilder};
+use ui::{IconButton, Tooltip, prelude::*};
+use util::paths::PathExt;
+use workspace::{
+    ItemSettings,
+    item::{Item, ItemEvent},
+};
+
+use crate::ToggleFollow;
+use crate::log_index::{LineIndex, LogLevel, split_lines};
+
+/// The number of bytes read from the file at a time, when indexing or filtering it.
+const CHUNK_SIZE: u64 = 1024 * 1024;
+/// The number of lines whose text is kept in memory for display.
+const MAX_CACHED_LINES: usize = 10_000;
+/// Lines longer than this are cut off, to keep rendering fast.
+const MAX_LINE_CHARS: usize = 4096;
+const FILTER_DEBOUNCE: Duration = Duration::from_millis(200);
+const WATCH_LATENCY: Duration = Duration::from_millis(100);
+
+/// A read-only view of a log file, which colors lines by their level, filters them by level and
+/// regex, and follows the file as it grows.
+///
+/// The file is never held in memory: it is indexed in chunks, and only the lines being displayed
+/// are read.
+pub struct LogViewer {
+    abs_path: Arc<Path>,
+    fs: Arc<dyn Fs>,
+    index: LineIndex,
+    /// Incremented whenever the file is indexed from the start, so that results computed for an
+    /// older index are discarded.
+    index_version: usize,
+    is_indexing: bool,
+    min_level: Option<LogLevel>,
+    query_editor: Entity<Editor>,
+    query: Option<Regex>,
+    query_error: Option<SharedString>,
+    /// The lines matching the filters, or `None` when no filter is set and all lines are shown.
+    filtered_lines: Option<Vec<usize>>,
+    /// Incremented whenever the filters change, so that lines filtered with older filters are
+    /// discarded.
+    filter_version: usize,
+    is_filtering: bool,
+    line_cache: HashMap<usize, SharedString>,
+    loading_lines: HashSet<usize>,
+    selected_line: Option<usize>,
+    /// Whether to keep the end of the file in view as it grows.
+    follow: bool,
+    error: Option<SharedString>,
+    scroll_handle: UniformListScrollHandle,
+    focus_handle: FocusHandle,
+    index_task: Task<()>,
+    filter_task: Task<()>,
+    _query_subscription: Subscription,
+}
+
+impl LogViewer {
+    pub fn new(
+        abs_path: Arc<Path>,
+        fs: Arc<dyn Fs>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let query_editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_placeholder_text("Filter by regex…", window, cx);
+            editor
+        });
+        let query_subscription = cx.subscribe(&query_editor, |this, _, event: &EditorEvent, cx| {
+            if let EditorEvent::BufferEdited = event {
+                this.update_query(cx);
+            }
+        });
+
+        let mut this = Self {
+            abs_path,
+            fs,
+            index: LineIndex::default(),
+            index_version: 0,
+            is_indexing: false,
+            min_level: None,
+            query_editor,
+            query: None,
+            query_error: None,
+            filtered_lines: None,
+            filter_version: 0,
+            is_filtering: false,
+            line_cache: HashMap::default(),
+            loading_lines: HashSet::default(),
+            selected_line: None,
+            follow: true,
+            error: None,
+            scroll_handle: UniformListScrollHandle::new(),
+            focus_handle: cx.focus_handle(),
+            index_task: Task::ready(()),
+            filter_task: Task::ready(()),
+            _query_subscription: query_subscription,
+        };
+        this.index_file(cx);
+        this
+    }
+
+    pub fn abs_path(&self) -> &Arc<Path> {
+        &self.abs_path
+    }
+
+    /// Indexes the file from the start, then indexes what is appended to it as it changes.
+    fn index_file(&mut self, cx: &mut Context<Self>) {
+        self.index = LineIndex::default();
+        self.index_version += 1;
+        self.is_indexing = true;
+        self.line_cache.clear();
+        self.loading_lines.clear();
+        self.error = None;
+        if self.min_level.is_some() || self.query.is_some() {
+            self.filtered_lines = Some(Vec::new());
+        }
+
+        let fs = self.fs.clone();
+        let abs_path = self.abs_path.clone();
+        self.index_task = cx.spawn(async move |this, cx| {
+            let (mut events, _watcher) = fs.watch(&abs_path, WATCH_LATENCY).await;
+            loop {
+                let result = Self::index_appended_lines(&this, &fs, &abs_path, cx).await;
+                let Ok(should_reindex) = this.update(cx, |this, cx| {
+                    this.is_indexing = false;
+                    cx.notify();
+                    match result {
+                        Ok(should_reindex) => should_reindex,
+                        Err(error) => {
+                            this.error = Some(format!("{error:#}").into());
+                            false
+                        }
+                    }
+                }) else {
+                    return;
+                };
+                if should_reindex {
+                    this.update(cx, |this, cx| this.index_file(cx)).ok();
+                    return;
+                }
+                if events.next().await.is_none() {
+                    return;
+                }
+            }
+        });
+    }
+
+    /// Indexes the part of the file that hasn't been indexed yet, returning whether the file
+    /// needs to be indexed from the start because it has been truncated.
+    async fn index_appended_lines(
+        this: &WeakEntity<Self>,
+        fs: &Arc<dyn Fs>,
+        abs_path: &Path,
+        cx: &mut AsyncApp,
+    ) -> Result<bool> {
+        let Some(metadata) = fs.metadata(abs_path).await? else {
+            anyhow::bail!("file not found");
+        };
+        let (start, start_line_count) = this.update(cx, |this, _| {
+            (this.index.indexed_len(), this.index.line_count())
+        })?;
+        if metadata.len < start {
+            return Ok(true);
+        }
+
+        let mut position = start;
+        while position < metadata.len {
+            let end = (position + CHUNK_SIZE).min(metadata.len);
+            let chunk = fs.load_bytes_range(abs_path, position..end).await?;
+            if chunk.is_empty() {
+                break;
+            }
+            position += chunk.len() as u64;
+            this.update(cx, |this, cx| {
+                this.index.append(&chunk);
+                cx.notify();
+            })?;
+        }
+
+        this.update(cx, |this, cx| {
+            if position > start {
+                // The last line may have been extended, so its text needs to be read again.
+                if let Some(last_line) = start_line_count.checked_sub(1) {
+                    this.line_cache.remove(&last_line);
+                }
+                this.filter_appended_lines(start_line_count.saturating_sub(1), cx);
+                if this.follow {
+                    this.scroll_to_end();
+                }
+            }
+        })?;
+        Ok(false)
+    }
+
+    fn update_query(&mut self, cx: &mut Context<Self>) {
+        let query = self.query_editor.read(cx).text(cx);
+        self.query_error = None;
+        self.query = if query.is_empty() {
+            None
+        } else {
+            // Like search, the query is case-insensitive unless it contains uppercase letters.
+            match RegexBuilder::new(&query)
+                .case_insensitive(!query.chars().any(char::is_uppercase))
+                .build()
+            {
+                Ok(query) => Some(query),
+                Err(error) => {
+                    self.query_error = Some(error.to_string().into());
+                    cx.notify();
+                    return;
+                }
+            }
+        };
+        self.filter_lines(true, cx);
+    }
+
+    fn set_min_level(&mut self, min_level: Option<LogLevel>, cx: &mut Context<Self>) {
+        self.min_level = min_level;
+        self.filter_lines(false, cx);
+    }
+
+    /// Recomputes the lines matching the filters.
+    fn filter_lines(&mut self, wait_for_debounce: bool, cx: &mut Context<Self>) {
+        self.filter_version += 1;
+        if self.min_level.is_none() && self.query.is_none() {
+            self.filtered_lines = None;
+            self.is_filtering = false;
+            self.filter_task = Task::ready(());
+            self.scroll_to_selected_line();
+            cx.notify();
+            return;
+        }
+
+        let index = self.index.clone();
+        let index_version = self.index_version;
+        let filter = LineFilter {
+            min_level: self.min_level,
+            query: self.query.clone(),
+        };
+        let fs = self.fs.clone();
+        let abs_path = self.abs_path.clone();
+        self.is_filtering = true;
+        cx.notify();
+        self.filter_task = cx.spawn(async move |this, cx| {
+            if wait_for_debounce {
+                cx.background_executor().timer(FILTER_DEBOUNCE).await;
+            }
+            let lines = 0..index.line_count();
+            let result = filter.apply(&fs, &abs_path, &index, lines).await;
+            this.update(cx, |this, cx| {
+                if this.index_version != index_version {
+                    return;
+                }
+                this.is_filtering = false;
+                match result {
+                    Ok(filtered_lines) => {
+                        this.filtered_lines = Some(filtered_lines);
+                        // Lines appended while filtering are filtered separately.
+                        if this.index.indexed_len() > index.indexed_len() {
+                            this.filter_appended_lines(index.line_count().saturating_sub(1), cx);
+                        }
+                        this.scroll_to_selected_line();
+                    }
+                    Err(error) => this.error = Some(format!("{error:#}").into()),
+                }
+                cx.notify();
+            })
+            .ok();
+        });
+    }
+
+    /// Adds the lines from `first_line` onwards that match the filters to the filtered lines.
+    fn filter_appended_lines(&mut self, first_line: usize, cx: &mut Context<Self>) {
+        if self.filtered_lines.is_none() || self.is_filtering {
+            return;
+        }
+        let lines = first_line..self.index.line_count();
+        let filter = LineFilter {
+            min_level: self.min_level,
+            query: self.query.clone(),
+        };
+        let index = self.index.clone();
+        let index_version = self.index_version;
+        let filter_version = self.filter_version;
+        let fs = self.fs.clone();
+        let abs_path = self.abs_path.clone();
+        cx.spawn(async move |this, cx| {
+            let result = filter.apply(&fs, &abs_path, &index, lines.clone()).await;
+            this.update(cx, |this, cx| {
+                if this.index_version != index_version || this.filter_version != filter_version {
+                    return;
+                }
+                let (Ok(new_lines), Some(filtered_lines)) = (result, &mut this.filtered_lines)
+                else {
+                    return;
+                };
+                // The first line may have been filtered before, when it was shorter, and the
+                // lines appended by consecutive writes may finish filtering out of order.
+                filtered_lines.retain(|line| !lines.contains(line));
+                filtered_lines.extend(new_lines);
+                filtered_lines.sort_unstable();
+                if this.follow {
+                    this.scroll_to_end();
+                }
+                cx.notify();
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    fn displayed_line_count(&self) -> usize {
+        match &self.filtered_lines {
+            Some(filtered_lines) => filtered_lines.len(),
+            None => self.index.line_count(),
+        }
+    }
+
+    fn displayed_line(&self, ix: usize) -> Option<usize> {
+        match &self.filtered_lines {
+            Some(filtered_lines) => filtered_lines.get(ix).copied(),
+            None => (ix < self.index.line_count()).then_some(ix),
+        }
+    }
+
+    fn scroll_to_end(&mut self) {
+        if let Some(last_ix) = self.displayed_line_count().checked_sub(1) {
+            self.scroll_handle
+                .scroll_to_item(last_ix, ScrollStrategy::Bottom);
+        }
+    }
+
+    /// Keeps the selected line in view when the filters change, so that the context around a
+    /// filtered line can be found by clearing the filters.
+    fn scroll_to_selected_line(&mut self) {
+        let Some(selected_line) = self.selected_line else {
+            return;
+        };
+        let ix = match &self.filtered_lines {
+            Some(filtered_lines) => filtered_lines
+                .binary_search(&selected_line)
+                .unwrap_or_else(|ix| ix),
+            None => selected_line,
+        };
+        self.follow = false;
+        self.scroll_handle
+            .scroll_to_item(ix, ScrollStrategy::Center);
+    }
+
+    fn toggle_follow(&mut self, _: &ToggleFollow, _: &mut Window, cx: &mut Context<Self>) {
+        self.follow = !self.follow;
+        if self.follow {
+            self.scroll_to_end();
+        }
+        cx.notify();
+    }
+
+    /// Starts reading the text of the given lines, if it isn't cached yet.
+    fn load_lines(&mut self, lines: Vec<usize>, cx: &mut Context<Self>) {
+        let missing_lines = lines
+            .into_iter()
+            .filter(|line| {
+                !self.line_cache.contains_key(line) && !self.loading_lines.contains(line)
+            })
+            .collect::<Vec<_>>();
+        if missing_lines.is_empty() {
+            return;
+        }
+        if self.line_cache.len() + missing_lines.len() > MAX_CACHED_LINES {
+            self.line_cache.clear();
+        }
+        self.loading_lines.extend(missing_lines.iter().copied());
+
+        let index = self.index.clone();
+        let index_version = self.index_version;
+        let fs = self.fs.clone();
+        let abs_path = self.abs_path.clone();
+        cx.spawn(async move |this, cx| {
+            for run in consecutive_runs(&missing_lines) {
+                let byte_range =
+                    index.line_range(run.start).start..index.line_range(run.end - 1).end;
+                let chunk = fs.load_bytes_range(&abs_path, byte_range.clone()).await;
+                let texts =
+                    chunk.map(|chunk| split_lines(&index, run.clone(), byte_range.start, &chunk));
+                let should_continue = this.update(cx, |this, cx| {
+                    if this.index_version != index_version {
+                        return false;
+                    }
+                    for line in run.clone() {
+                        this.loading_lines.remove(&line);
+                    }
+                    match texts {
+                        Ok(texts) => {
+                            for (line, mut text) in run.zip(texts) {
+                                if let Some((cutoff, _)) = text.char_indices().nth(MAX_LINE_CHARS) {
+                                    text.truncate(cutoff);
+                                    text.push('…');
+                                }
+                                this.line_cache.insert(line, text.into());
+                            }
+                        }
+                        Err(error) => this.error = Some(format!("{error:#}").into()),
+                    }
+                    cx.notify();
+                    true
+                });
+                if !should_continue.unwrap_or(false) {
+                    return;
+                }
+            }
+        })
+        .detach();
+    }
+
+    fn render_line(&self, ix: usize, line: usize, cx: &mut Context<Self>) -> AnyElement {
+        let level = self.index.level(line);
+        let color = match level {
+            Some(LogLevel::Error) => Color::Error,
+            Some(LogLevel::Warn) => Color::Warning,
+            Some(LogLevel::Info) | None => Color::Default,
+            Some(LogLevel::Debug) => Color::Muted,
+            Some(LogLevel::Trace) => Color::Disabled,
+        };
+        let line_number_width = self.index.line_count().max(1).ilog10() as usize + 1;
+
+        h_flex()
+            .id(("log-line", ix))
+            .px_2()
+            .gap_3()
+            .when(self.selected_line == Some(line), |this| {
+                this.bg(cx.theme().colors().element_selected)
+            })
+            .child(
+                Label::new(format!("{:>line_number_width$}", line + 1))
+                    .buffer_font(cx)
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+            )
+            .child(
+                Label::new(self.line_cache.get(&line).cloned().unwrap_or_default())
+                    .buffer_font(cx)
+                    .size(LabelSize::Small)
+                    .color(color)
+                    .single_line(),
+            )
+            .on_click(cx.listener(move |this, _, window, cx| {
+                this.selected_line = Some(line);
+                window.focus(&this.focus_handle);
+                cx.notify();
+            }))
+            .into_any_element()
+    }
+
+    fn render_toolbar(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let status = if self.is_indexing || self.is_filtering {
+            "Loading…".to_string()
+        } else if self.filtered_lines.is_some() {
+            format!(
+                "{} of {} lines",
+                self.displayed_line_count(),
+                self.index.line_count()
+            )
+        } else {
+            format!("{} lines", self.index.line_count())
+        };
+
+        h_flex()
+            .px_2()
+            .py_1()
+            .gap_2()
+            .border_b_1()
+            .border_color(cx.theme().colors().border_variant)
+            .child(
+                h_flex()
+                    .gap_0p5()
+                    .child(
+                        Button::new("level-all", "All")
+                            .label_size(LabelSize::Small)
+                            .toggle_state(self.min_level.is_none())
+                            .on_click(cx.listener(|this, _, _, cx| this.set_min_level(None, cx))),
+                    )
+                    .children(LogLevel::ALL.into_iter().map(|level| {
+                        Button::new(("level", level as usize), level.label())
+                            .label_size(LabelSize::Small)
+                            .toggle_state(self.min_level == Some(level))
+                            .tooltip(Tooltip::text(format!(
+                                "Show {} and more severe lines",
+                                level.label()
+                            )))
+                            .on_click(cx.listener(move |this, _, _, cx| {
+                                this.set_min_level(Some(level), cx)
+                            }))
+                    })),
+            )
+            .child(
+                h_flex()
+                    .flex_1()
+                    .px_1p5()
+                    .py_0p5()
+                    .gap_1()
+                    .rounded_sm()
+                    .border_1()
+                    .border_color(if self.query_error.is_some() {
+                        Color::Error.color(cx)
+                    } else {
+                        cx.theme().colors().border
+                    })
+                    .child(
+                        Icon::new(IconName::Regex)
+                            .size(IconSize::Small)
+                            .color(Color::Muted),
+                    )
+                    .child(self.query_editor.clone()),
+            )
+            .child(
+                Label::new(status)
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+            )
+            .child(
+                IconButton::new("follow", IconName::ArrowDown)
+                    .icon_size(IconSize::Small)
+                    .toggle_state(self.follow)
+                    .tooltip(Tooltip::text("Follow the End of the File"))
+                    .on_click(cx.listener(|this, _, window, cx| {
+                        this.toggle_follow(&ToggleFollow, window, cx)
+                    })),
+            )
+    }
+}
+
+/// Groups sorted line numbers into ranges of consecutive lines.
+fn consecutive_runs(lines: &[usize]) -> Vec<Range<usize>> {
+    let mut runs: Vec<Range<usize>> = Vec::new();
+    for &line in lines {
+        match runs.last_mut() {
+            Some(run) if run.end == line => run.end += 1,
+            _ => runs.push(line..line + 1),
+        }
+    }
+    runs
+}
+
+struct LineFilter {
+    min_level: Option<LogLevel>,
+    query: Option<Regex>,
+}
+
+impl LineFilter {
+    fn matches_level(&self, index: &LineIndex, line: usize) -> bool {
+        self.min_level
+            .is_none_or(|min_level| index.level(line).is_some_and(|level| level >= min_level))
+    }
+
+    /// Returns the given lines that match the filter, reading them from disk in chunks when
+    /// their text needs to be matched.
+    async fn apply(
+        &self,
+        fs: &Arc<dyn Fs>,
+        abs_path: &Path,
+        index: &LineIndex,
+        lines: Range<usize>,
+    ) -> Result<Vec<usize>> {
+        let Some(query) = &self.query else {
+            return Ok(lines
+                .filter(|line| self.matches_level(index, *line))
+                .collect());
+        };
+
+        let mut matching_lines = Vec::new();
+        let mut batch_start = lines.start;
+        while batch_start < lines.end {
+            let batch_byte_start = index.line_range(batch_start).start;
+            let mut batch_end = batch_start + 1;
+            while batch_end < lines.end
+                && index.line_range(batch_end).end - batch_byte_start <= CHUNK_SIZE
+            {
+                batch_end += 1;
+            }
+            let batch_lines = batch_start..batch_end;
+            let byte_range = batch_byte_start..index.line_range(batch_end - 1).end;
+            let chunk = fs.load_bytes_range(abs_path, byte_range).await?;
+            let texts = split_lines(index, batch_lines.clone(), batch_byte_start, &chunk);
+            matching_lines.extend(
+                batch_lines
+                    .zip(texts)
+                    .filter(|(line, text)| self.matches_level(index, *line) && query.is_match(text))
+                    .map(|(line, _)| line),
+            );
+            batch_start = batch_end;
+        }
+        Ok(matching_lines)
+    }
+}
+
+impl Item for LogViewer {
+    type Event = ();
+
+    fn to_item_events(_event: &Self::Event, _f: impl FnMut(ItemEvent)) {}
+
+    fn tab_content_text(&self, _: usize, _: &App) -> SharedString {
+        self.abs_path
+            .file_name()
+            .unwrap_or(self.abs_path.as_os_str())
+            .to_string_lossy()
+            .into_owned()
+            .into()
+    }
+
+    fn tab_tooltip_text(&self, _: &App) -> Option<SharedString> {
+        Some(
+            self.abs_path
+                .compact()
+                .to_string_lossy()
+                .into_owned()
+                .into(),
+        )
+    }
+
+    fn tab_icon(&self, _: &Window, cx: &App) -> Option<Icon> {
+        ItemSettings::get_global(cx)
+            .file_icons
+            .then(|| FileIcons::get_icon(&self.abs_path, cx))
+            .flatten()
+            .map(Icon::from_path)
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        Some("Log Viewer Opened")
+    }
+}
+
+impl EventEmitter<()> for LogViewer {}
+
+impl Focusable for LogViewer {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for LogViewer {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .key_context("LogViewer")
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::toggle_follow))
+            .size_full()
+            .bg(cx.theme().colors().editor_background)
+            .child(self.render_toolbar(cx))
+            .when_some(
+                self.error.clone().or_else(|| self.query_error.clone()),
+                |this, error| {
+                    this.child(
+                        h_flex()
+                            .px_2()
+                            .child(Label::new(error).size(LabelSize::Small).color(Color::Error)),
+                    )
+                },
+            )
+            .child(
+                uniform_list(
+                    "log-lines",
+                    self.displayed_line_count(),
+                    cx.processor(|this, range: Range<usize>, _, cx| {
+                        let lines = range
+                            .clone()
+                            .filter_map(|ix| this.displayed_line(ix))
+                            .collect::<Vec<_>>();
+                        this.load_lines(lines, cx);
+                        range
+                            .filter_map(|ix| {
+                                Some(this.render_line(ix, this.displayed_line(ix)?, cx))
+                            })
+                            .collect::<Vec<_>>()
+                    }),
+                )
+                .track_scroll(self.scroll_handle.clone())
+                .flex_1(),
+            )
+    }
+}
@@ -861,6 +861,9 @@ impl LanguageServer {
                         dynamic_registration: Some(true),
                         ..DocumentSymbolClientCapabilities::default()
                     }),
+                    type_hierarchy: Some(TypeHierarchyClientCapabilities {
+                        dynamic_registration: None,
+                    }),
                     diagnostic: Some(DiagnosticClientCapabilities {
                         dynamic_registration: Some(true),
                         related_document_support: Some(true),
@@ -1153,6 +1156,11 @@ impl LanguageServer {
         &self.process_name
     }
 
+    /// Get the operating system's id for the language server's process, while it is running.
+    pub fn process_id(&self) -> Option<u32> {
+        self.server.lock().as_ref().map(|server| server.id())
+    }
+
     /// Get the reported capabilities of the running language server.
     pub fn capabilities(&self) -> ServerCapabilities {
         self.capabilities.read().clone()
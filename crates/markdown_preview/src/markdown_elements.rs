@@ -1,3 +1,4 @@
+use crate::mermaid::MermaidDiagram;
 use gpui::{
     DefiniteLength, FontStyle, FontWeight, HighlightStyle, Hsla, SharedString, StrikethroughStyle,
     UnderlineStyle, px,
@@ -30,6 +31,7 @@ impl ParsedMarkdownElement {
             Self::Paragraph(text) => match text.get(0)? {
                 MarkdownParagraphChunk::Text(t) => t.source_range.clone(),
                 MarkdownParagraphChunk::Image(image) => image.source_range.clone(),
+                MarkdownParagraphChunk::Math(math) => math.source_range.clone(),
             },
             Self::HorizontalRule(range) => range.clone(),
             Self::Image(image) => image.source_range.clone(),
@@ -48,6 +50,15 @@ pub type MarkdownParagraph = Vec<MarkdownParagraphChunk>;
 pub enum MarkdownParagraphChunk {
     Text(ParsedMarkdownText),
     Image(Image),
+    /// Math displayed on its own line, converted from TeX to plain text.
+    Math(ParsedMarkdownMath),
+}
+
+#[derive(Debug)]
+#[cfg_attr(test, derive(PartialEq))]
+pub struct ParsedMarkdownMath {
+    pub source_range: Range<usize>,
+    pub contents: SharedString,
 }
 
 #[derive(Debug)]
@@ -81,6 +92,8 @@ pub struct ParsedMarkdownCodeBlock {
     pub language: Option<String>,
     pub contents: SharedString,
     pub highlights: Option<Vec<(Range<usize>, HighlightId)>>,
+    /// The diagram described by a `mermaid` code block, if it could be parsed.
+    pub mermaid_diagram: Option<MermaidDiagram>,
 }
 
 #[derive(Debug)]
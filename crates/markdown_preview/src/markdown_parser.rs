@@ -1,6 +1,8 @@
 use crate::{
     markdown_elements::*,
     markdown_minifier::{Minifier, MinifierOptions},
+    math::tex_to_unicode,
+    mermaid::MermaidDiagram,
 };
 use async_recursion::async_recursion;
 use collections::FxHashMap;
@@ -17,9 +19,13 @@ pub async fn parse_markdown(
     markdown_input: &str,
     file_location_directory: Option<PathBuf>,
     language_registry: Option<Arc<LanguageRegistry>>,
+    render_math: bool,
 ) -> ParsedMarkdown {
     let mut options = Options::all();
     options.remove(pulldown_cmark::Options::ENABLE_DEFINITION_LIST);
+    if !render_math {
+        options.remove(pulldown_cmark::Options::ENABLE_MATH);
+    }
 
     let parser = Parser::new_ext(markdown_input, options);
     let parser = MarkdownParser::new(
@@ -127,6 +133,8 @@ impl<'a> MarkdownParser<'a> {
             | Event::Html(_)
             | Event::InlineHtml(_)
             | Event::FootnoteReference(_)
+            | Event::InlineMath(_)
+            | Event::DisplayMath(_)
             | Event::Start(Tag::Link { .. })
             | Event::Start(Tag::Emphasis)
             | Event::Start(Tag::Strong)
@@ -244,7 +252,7 @@ impl<'a> MarkdownParser<'a> {
                 break;
             }
 
-            let (current, _) = self.current().unwrap();
+            let (current, current_source_range) = self.current().unwrap();
             let prev_len = text.len();
             match current {
                 Event::SoftBreak => {
@@ -344,6 +352,31 @@ impl<'a> MarkdownParser<'a> {
                         }
                     }
                 }
+                Event::InlineMath(tex) => {
+                    text.push_str(&tex_to_unicode(tex));
+                    highlights.push((
+                        prev_len..text.len(),
+                        MarkdownHighlight::Style(MarkdownHighlightStyle {
+                            italic: true,
+                            ..Default::default()
+                        }),
+                    ));
+                }
+                Event::DisplayMath(tex) => {
+                    if !text.is_empty() {
+                        markdown_text_like.push(MarkdownParagraphChunk::Text(ParsedMarkdownText {
+                            source_range: source_range.clone(),
+                            contents: mem::take(&mut text).into(),
+                            highlights: mem::take(&mut highlights),
+                            region_ranges: mem::take(&mut region_ranges),
+                            regions: mem::take(&mut regions),
+                        }));
+                    }
+                    markdown_text_like.push(MarkdownParagraphChunk::Math(ParsedMarkdownMath {
+                        source_range: current_source_range.clone(),
+                        contents: tex_to_unicode(tex).into(),
+                    }));
+                }
                 Event::Code(t) => {
                     text.push_str(t.as_ref());
                     region_ranges.push(prev_len..text.len());
@@ -776,11 +809,18 @@ impl<'a> MarkdownParser<'a> {
             None
         };
 
+        let mermaid_diagram = if language.as_deref() == Some("mermaid") {
+            MermaidDiagram::parse(&code).ok()
+        } else {
+            None
+        };
+
         Some(ParsedMarkdownCodeBlock {
             source_range,
             contents: code.into(),
             language,
             highlights,
+            mermaid_diagram,
         })
     }
 
@@ -1119,7 +1159,7 @@ mod tests {
     use pretty_assertions::assert_eq;
 
     async fn parse(input: &str) -> ParsedMarkdown {
-        parse_markdown(input, None, None).await
+        parse_markdown(input, None, None, true).await
     }
 
     #[gpui::test]
@@ -1258,6 +1298,21 @@ mod tests {
         );
     }
 
+    #[gpui::test]
+    async fn test_math() {
+        let parsed = parse(r"Euler: $$e^{i\pi} + 1 = 0$$").await;
+        let ParsedMarkdownElement::Paragraph(paragraph) = &parsed.children[0] else {
+            panic!("Expected a paragraph");
+        };
+        let MarkdownParagraphChunk::Math(math) = &paragraph[1] else {
+            panic!("Expected display math");
+        };
+        assert_eq!(math.contents.as_ref(), "e^(iπ) + 1 = 0");
+
+        let parsed = parse_markdown("Area: $\\pi r^2$", None, None, false).await;
+        assert_eq!(parsed.children, vec![p("Area: $\\pi r^2$", 0..15)]);
+    }
+
     #[gpui::test]
     async fn test_empty_image() {
         let parsed = parse("![]()").await;
@@ -2300,6 +2355,7 @@ fn main() {
             language,
             contents: code.to_string().into(),
             highlights,
+            mermaid_diagram: None,
         })
     }
 
@@ -1,11 +1,15 @@
 use gpui::{App, actions};
+use settings::Settings;
 use workspace::Workspace;
 
 pub mod markdown_elements;
 mod markdown_minifier;
 pub mod markdown_parser;
+pub mod markdown_preview_settings;
 pub mod markdown_preview_view;
 pub mod markdown_renderer;
+pub mod math;
+pub mod mermaid;
 
 actions!(
     markdown,
@@ -24,6 +28,7 @@ actions!(
 );
 
 pub fn init(cx: &mut App) {
+    markdown_preview_settings::MarkdownPreviewSettings::register(cx);
     cx.observe_new(|workspace: &mut Workspace, window, cx| {
         let Some(window) = window else {
             return;
@@ -0,0 +1,24 @@
+use settings::Settings;
+
+/// The settings for the Markdown preview.
+#[derive(Clone, Debug)]
+pub struct MarkdownPreviewSettings {
+    /// Whether to render `$...$` and `$$...$$` as math, rather than as plain text.
+    ///
+    /// Default: true
+    pub math: bool,
+}
+
+impl Settings for MarkdownPreviewSettings {
+    fn from_settings(content: &settings::SettingsContent) -> Self {
+        Self {
+            math: content
+                .project
+                .markdown_preview
+                .clone()
+                .unwrap()
+                .math
+                .unwrap(),
+        }
+    }
+}
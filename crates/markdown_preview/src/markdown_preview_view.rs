@@ -11,13 +11,14 @@ use gpui::{
     Subscription, Task, WeakEntity, Window, list,
 };
 use language::LanguageRegistry;
-use settings::Settings;
+use settings::{Settings, SettingsLocation};
 use theme::ThemeSettings;
 use ui::{WithScrollbar, prelude::*};
 use workspace::item::{Item, ItemHandle};
 use workspace::{Pane, Workspace};
 
 use crate::markdown_elements::ParsedMarkdownElement;
+use crate::markdown_preview_settings::MarkdownPreviewSettings;
 use crate::markdown_renderer::CheckboxClickedEvent;
 use crate::{
     MovePageDown, MovePageUp, OpenFollowingPreview, OpenPreview, OpenPreviewToTheSide,
@@ -328,15 +329,27 @@ impl MarkdownPreviewView {
                 cx.background_executor().timer(REPARSE_DEBOUNCE).await;
             }
 
-            let (contents, file_location) = view.update(cx, |_, cx| {
+            let (contents, file_location, render_math) = view.update(cx, |_, cx| {
                 let editor = editor.read(cx);
                 let contents = editor.buffer().read(cx).snapshot(cx).text();
                 let file_location = MarkdownPreviewView::get_folder_for_active_editor(editor, cx);
-                (contents, file_location)
+                let file = editor.file_at(0, cx);
+                let settings_location = file.as_ref().map(|file| SettingsLocation {
+                    worktree_id: file.worktree_id(cx),
+                    path: file.path().as_ref(),
+                });
+                let render_math = MarkdownPreviewSettings::get(settings_location, cx).math;
+                (contents, file_location, render_math)
             })?;
 
             let parsing_task = cx.background_spawn(async move {
-                parse_markdown(&contents, file_location, Some(language_registry)).await
+                parse_markdown(
+                    &contents,
+                    file_location,
+                    Some(language_registry),
+                    render_math,
+                )
+                .await
             });
             let contents = parsing_task.await;
             view.update(cx, move |view, cx| {
@@ -4,12 +4,13 @@ use crate::markdown_elements::{
     ParsedMarkdownHeading, ParsedMarkdownListItem, ParsedMarkdownListItemType, ParsedMarkdownTable,
     ParsedMarkdownTableAlignment, ParsedMarkdownTableRow,
 };
+use crate::mermaid::{MermaidColors, MermaidDiagram};
 use fs::normalize_path;
 use gpui::{
     AbsoluteLength, AnyElement, App, AppContext as _, ClipboardItem, Context, DefiniteLength, Div,
-    Element, ElementId, Entity, HighlightStyle, Hsla, ImageSource, InteractiveText, IntoElement,
-    Keystroke, Length, Modifiers, ParentElement, Render, Resource, SharedString, Styled,
-    StyledText, TextStyle, WeakEntity, Window, div, img, rems,
+    Element, ElementId, Entity, FontStyle, HighlightStyle, Hsla, ImageFormat, ImageSource,
+    InteractiveText, IntoElement, Keystroke, Length, Modifiers, ParentElement, Render, Resource,
+    SharedString, Styled, StyledText, TextStyle, WeakEntity, Window, div, img, rems,
 };
 use settings::Settings;
 use std::{
@@ -459,6 +460,7 @@ fn paragraph_len(paragraphs: &MarkdownParagraph) -> usize {
             MarkdownParagraphChunk::Text(text) => text.contents.len(),
             // TODO: Scale column width based on image size
             MarkdownParagraphChunk::Image(_) => 1,
+            MarkdownParagraphChunk::Math(math) => math.contents.len(),
         })
         .sum()
 }
@@ -605,6 +607,10 @@ fn render_markdown_code_block(
     parsed: &ParsedMarkdownCodeBlock,
     cx: &mut RenderContext,
 ) -> AnyElement {
+    if let Some(diagram) = &parsed.mermaid_diagram {
+        return render_mermaid_diagram(parsed, diagram, cx);
+    }
+
     let body = if let Some(highlights) = parsed.highlights.as_ref() {
         StyledText::new(parsed.contents.clone()).with_default_highlights(
             &cx.buffer_text_style,
@@ -647,6 +653,50 @@ fn render_markdown_code_block(
         .into_any()
 }
 
+fn render_mermaid_diagram(
+    parsed: &ParsedMarkdownCodeBlock,
+    diagram: &MermaidDiagram,
+    cx: &mut RenderContext,
+) -> AnyElement {
+    /// Rendering the diagram at a higher resolution than it is displayed at keeps it crisp on
+    /// high density displays.
+    const SVG_SCALE: f32 = 2.;
+
+    let colors = MermaidColors {
+        node_background: cx.element_background_color,
+        node_border: cx.border_color,
+        edge: cx.text_muted_color,
+        text: cx.text_color,
+        label_background: cx.code_block_background_color,
+    };
+    let svg = diagram.to_svg(&colors, SVG_SCALE);
+    let image = Arc::new(gpui::Image::from_bytes(ImageFormat::Svg, svg.into_bytes()));
+    let diagram_size = diagram.size();
+
+    cx.with_common_p(div())
+        .px_3()
+        .py_3()
+        .bg(cx.code_block_background_color)
+        .rounded_sm()
+        .child(
+            img(ImageSource::Image(image))
+                .w(diagram_size.width)
+                .h(diagram_size.height)
+                .max_w_full()
+                .with_fallback({
+                    let contents = parsed.contents.clone();
+                    let buffer_font_family = cx.buffer_font_family.clone();
+                    move || {
+                        div()
+                            .font_family(buffer_font_family.clone())
+                            .child(contents.clone())
+                            .into_any_element()
+                    }
+                }),
+        )
+        .into_any()
+}
+
 fn render_markdown_paragraph(parsed: &MarkdownParagraph, cx: &mut RenderContext) -> AnyElement {
     cx.with_common_p(div())
         .children(render_markdown_text(parsed, cx))
@@ -750,6 +800,28 @@ fn render_markdown_text(parsed_new: &MarkdownParagraph, cx: &mut RenderContext)
             MarkdownParagraphChunk::Image(image) => {
                 any_element.push(render_markdown_image(image, cx));
             }
+
+            MarkdownParagraphChunk::Math(math) => {
+                any_element.push(
+                    h_flex()
+                        .w_full()
+                        .justify_center()
+                        .py(cx.scaled_rems(0.5))
+                        .child(
+                            StyledText::new(math.contents.clone()).with_default_highlights(
+                                &text_style,
+                                [(
+                                    0..math.contents.len(),
+                                    HighlightStyle {
+                                        font_style: Some(FontStyle::Italic),
+                                        ..Default::default()
+                                    },
+                                )],
+                            ),
+                        )
+                        .into_any(),
+                );
+            }
         }
     }
 
@@ -0,0 +1,420 @@
+use std::{iter::Peekable, str::Chars};
+
+/// Converts TeX math into plain text, using the Unicode forms of symbols, Greek letters and
+/// simple superscripts and subscripts, so that it can be laid out like any other text.
+///
+/// Constructs without a Unicode equivalent are kept close to their TeX source, such as
+/// fractions becoming `(a)/(b)`, which keeps the converted text readable.
+pub fn tex_to_unicode(tex: &str) -> String {
+    let mut converter = TexConverter {
+        chars: tex.chars().peekable(),
+    };
+    let converted = converter.convert_until(None);
+    converted
+        .lines()
+        .map(|line| line.split_whitespace().collect::<Vec<_>>().join(" "))
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+struct TexConverter<'a> {
+    chars: Peekable<Chars<'a>>,
+}
+
+impl TexConverter<'_> {
+    fn convert_until(&mut self, end: Option<char>) -> String {
+        let mut output = String::new();
+        while let Some(character) = self.chars.next() {
+            match character {
+                _ if Some(character) == end => break,
+                '{' => output.push_str(&self.convert_until(Some('}'))),
+                '\\' => output.push_str(&self.convert_command()),
+                '^' => output.push_str(&script(&self.convert_argument(), superscript, '^')),
+                '_' => output.push_str(&script(&self.convert_argument(), subscript, '_')),
+                '&' | '~' => output.push(' '),
+                '\'' => output.push('′'),
+                _ => output.push(character),
+            }
+        }
+        output
+    }
+
+    /// Converts the next argument of a command or script, which is either a group or a single
+    /// character or command.
+    fn convert_argument(&mut self) -> String {
+        while self
+            .chars
+            .next_if(|character| character.is_whitespace())
+            .is_some()
+        {}
+        match self.chars.next() {
+            Some('{') => self.convert_until(Some('}')),
+            Some('\\') => self.convert_command(),
+            Some(character) => character.to_string(),
+            None => String::new(),
+        }
+    }
+
+    fn convert_command(&mut self) -> String {
+        let mut name = String::new();
+        while let Some(character) = self.chars.next_if(|character| character.is_alphabetic()) {
+            name.push(character);
+        }
+        if name.is_empty() {
+            return match self.chars.next() {
+                Some('\\') => "\n".to_string(),
+                Some(',' | ':' | '!') | None => String::new(),
+                Some(';' | ' ') => " ".to_string(),
+                Some(character) => character.to_string(),
+            };
+        }
+
+        match name.as_str() {
+            "frac" | "dfrac" | "tfrac" => {
+                let numerator = self.convert_argument();
+                let denominator = self.convert_argument();
+                format!(
+                    "{}/{}",
+                    parenthesize(&numerator),
+                    parenthesize(&denominator)
+                )
+            }
+            "sqrt" => {
+                let mut index = String::new();
+                if self.chars.next_if_eq(&'[').is_some() {
+                    index = self.convert_until(Some(']'));
+                }
+                let radical = match index.trim() {
+                    "" | "2" => "√",
+                    "3" => "∛",
+                    "4" => "∜",
+                    index => return format!("{index}√{}", parenthesize(&self.convert_argument())),
+                };
+                format!("{radical}{}", parenthesize(&self.convert_argument()))
+            }
+            "mathbb" => self.convert_argument().chars().map(double_struck).collect(),
+            "text" | "textrm" | "textbf" | "textit" | "mathrm" | "mathbf" | "mathit" | "mathsf"
+            | "mathtt" | "mathcal" | "operatorname" | "boldsymbol" => self.convert_argument(),
+            "left" | "right" | "big" | "Big" | "bigg" | "Bigg" | "bigl" | "bigr" | "Bigl"
+            | "Bigr" | "displaystyle" | "textstyle" | "limits" | "nolimits" => {
+                // `\left.` and `\right.` stand for an invisible delimiter.
+                self.chars.next_if_eq(&'.');
+                String::new()
+            }
+            "begin" | "end" => {
+                // Environments such as `aligned` and `cases` only affect alignment, and their
+                // rows are already separated by `\\`.
+                self.convert_argument();
+                String::new()
+            }
+            "quad" => "  ".to_string(),
+            "qquad" => "    ".to_string(),
+            name => match symbol(name) {
+                Some(symbol) => symbol.to_string(),
+                None => format!("\\{name}"),
+            },
+        }
+    }
+}
+
+fn parenthesize(text: &str) -> String {
+    let text = text.trim();
+    if text.chars().count() <= 1 || text.chars().all(char::is_alphanumeric) {
+        text.to_string()
+    } else {
+        format!("({text})")
+    }
+}
+
+fn script(text: &str, map: fn(char) -> Option<char>, marker: char) -> String {
+    let text = text.trim();
+    let mapped = text
+        .chars()
+        .filter(|character| !character.is_whitespace())
+        .map(map)
+        .collect::<Option<String>>();
+    match mapped {
+        Some(mapped) if !mapped.is_empty() => mapped,
+        _ if text.chars().count() == 1 => format!("{marker}{text}"),
+        _ => format!("{marker}({text})"),
+    }
+}
+
+fn superscript(character: char) -> Option<char> {
+    Some(match character {
+        '0' => '⁰',
+        '1' => '¹',
+        '2' => '²',
+        '3' => '³',
+        '4' => '⁴',
+        '5' => '⁵',
+        '6' => '⁶',
+        '7' => '⁷',
+        '8' => '⁸',
+        '9' => '⁹',
+        '+' => '⁺',
+        '-' | '−' => '⁻',
+        '=' => '⁼',
+        '(' => '⁽',
+        ')' => '⁾',
+        'a' => 'ᵃ',
+        'b' => 'ᵇ',
+        'c' => 'ᶜ',
+        'd' => 'ᵈ',
+        'e' => 'ᵉ',
+        'f' => 'ᶠ',
+        'g' => 'ᵍ',
+        'h' => 'ʰ',
+        'i' => 'ⁱ',
+        'j' => 'ʲ',
+        'k' => 'ᵏ',
+        'l' => 'ˡ',
+        'm' => 'ᵐ',
+        'n' => 'ⁿ',
+        'o' => 'ᵒ',
+        'p' => 'ᵖ',
+        'r' => 'ʳ',
+        's' => 'ˢ',
+        't' => 'ᵗ',
+        'u' => 'ᵘ',
+        'v' => 'ᵛ',
+        'w' => 'ʷ',
+        'x' => 'ˣ',
+        'y' => 'ʸ',
+        'z' => 'ᶻ',
+        'T' => 'ᵀ',
+        '′' => '′',
+        '∗' | '*' => '*',
+        _ => return None,
+    })
+}
+
+fn subscript(character: char) -> Option<char> {
+    Some(match character {
+        '0' => '₀',
+        '1' => '₁',
+        '2' => '₂',
+        '3' => '₃',
+        '4' => '₄',
+        '5' => '₅',
+        '6' => '₆',
+        '7' => '₇',
+        '8' => '₈',
+        '9' => '₉',
+        '+' => '₊',
+        '-' | '−' => '₋',
+        '=' => '₌',
+        '(' => '₍',
+        ')' => '₎',
+        'a' => 'ₐ',
+        'e' => 'ₑ',
+        'h' => 'ₕ',
+        'i' => 'ᵢ',
+        'j' => 'ⱼ',
+        'k' => 'ₖ',
+        'l' => 'ₗ',
+        'm' => 'ₘ',
+        'n' => 'ₙ',
+        'o' => 'ₒ',
+        'p' => 'ₚ',
+        'r' => 'ᵣ',
+        's' => 'ₛ',
+        't' => 'ₜ',
+        'u' => 'ᵤ',
+        'v' => 'ᵥ',
+        'x' => 'ₓ',
+        _ => return None,
+    })
+}
+
+fn double_struck(character: char) -> char {
+    match character {
+        'C' => 'ℂ',
+        'H' => 'ℍ',
+        'N' => 'ℕ',
+        'P' => 'ℙ',
+        'Q' => 'ℚ',
+        'R' => 'ℝ',
+        'Z' => 'ℤ',
+        character => character,
+    }
+}
+
+fn symbol(name: &str) -> Option<&'static str> {
+    Some(match name {
+        "alpha" => "α",
+        "beta" => "β",
+        "gamma" => "γ",
+        "delta" => "δ",
+        "epsilon" => "ϵ",
+        "varepsilon" => "ε",
+        "zeta" => "ζ",
+        "eta" => "η",
+        "theta" => "θ",
+        "vartheta" => "ϑ",
+        "iota" => "ι",
+        "kappa" => "κ",
+        "lambda" => "λ",
+        "mu" => "μ",
+        "nu" => "ν",
+        "xi" => "ξ",
+        "pi" => "π",
+        "varpi" => "ϖ",
+        "rho" => "ρ",
+        "varrho" => "ϱ",
+        "sigma" => "σ",
+        "varsigma" => "ς",
+        "tau" => "τ",
+        "upsilon" => "υ",
+        "phi" => "ϕ",
+        "varphi" => "φ",
+        "chi" => "χ",
+        "psi" => "ψ",
+        "omega" => "ω",
+        "Gamma" => "Γ",
+        "Delta" => "Δ",
+        "Theta" => "Θ",
+        "Lambda" => "Λ",
+        "Xi" => "Ξ",
+        "Pi" => "Π",
+        "Sigma" => "Σ",
+        "Upsilon" => "Υ",
+        "Phi" => "Φ",
+        "Psi" => "Ψ",
+        "Omega" => "Ω",
+        "sum" => "∑",
+        "prod" => "∏",
+        "coprod" => "∐",
+        "int" => "∫",
+        "iint" => "∬",
+        "iiint" => "∭",
+        "oint" => "∮",
+        "infty" => "∞",
+        "partial" => "∂",
+        "nabla" => "∇",
+        "pm" => "±",
+        "mp" => "∓",
+        "times" => "×",
+        "div" => "÷",
+        "cdot" => "⋅",
+        "ast" => "∗",
+        "star" => "⋆",
+        "circ" => "∘",
+        "bullet" => "•",
+        "leq" | "le" => "≤",
+        "geq" | "ge" => "≥",
+        "neq" | "ne" => "≠",
+        "approx" => "≈",
+        "equiv" => "≡",
+        "sim" => "∼",
+        "simeq" => "≃",
+        "cong" => "≅",
+        "propto" => "∝",
+        "ll" => "≪",
+        "gg" => "≫",
+        "in" => "∈",
+        "notin" => "∉",
+        "ni" => "∋",
+        "subset" => "⊂",
+        "subseteq" => "⊆",
+        "supset" => "⊃",
+        "supseteq" => "⊇",
+        "cup" => "∪",
+        "cap" => "∩",
+        "setminus" => "∖",
+        "emptyset" | "varnothing" => "∅",
+        "forall" => "∀",
+        "exists" => "∃",
+        "nexists" => "∄",
+        "neg" | "lnot" => "¬",
+        "land" | "wedge" => "∧",
+        "lor" | "vee" => "∨",
+        "oplus" => "⊕",
+        "otimes" => "⊗",
+        "to" | "rightarrow" => "→",
+        "gets" | "leftarrow" => "←",
+        "leftrightarrow" => "↔",
+        "Rightarrow" => "⇒",
+        "Leftarrow" => "⇐",
+        "Leftrightarrow" => "⇔",
+        "implies" => "⟹",
+        "iff" => "⟺",
+        "mapsto" => "↦",
+        "uparrow" => "↑",
+        "downarrow" => "↓",
+        "ldots" | "dots" => "…",
+        "cdots" => "⋯",
+        "vdots" => "⋮",
+        "ddots" => "⋱",
+        "prime" => "′",
+        "angle" => "∠",
+        "perp" => "⊥",
+        "parallel" => "∥",
+        "mid" => "∣",
+        "langle" => "⟨",
+        "rangle" => "⟩",
+        "lceil" => "⌈",
+        "rceil" => "⌉",
+        "lfloor" => "⌊",
+        "rfloor" => "⌋",
+        "lbrace" => "{",
+        "rbrace" => "}",
+        "vert" => "|",
+        "Vert" => "‖",
+        "hbar" => "ℏ",
+        "ell" => "ℓ",
+        "Re" => "ℜ",
+        "Im" => "ℑ",
+        "aleph" => "ℵ",
+        "degree" => "°",
+        "sin" => "sin",
+        "cos" => "cos",
+        "tan" => "tan",
+        "cot" => "cot",
+        "sec" => "sec",
+        "csc" => "csc",
+        "arcsin" => "arcsin",
+        "arccos" => "arccos",
+        "arctan" => "arctan",
+        "sinh" => "sinh",
+        "cosh" => "cosh",
+        "tanh" => "tanh",
+        "log" => "log",
+        "ln" => "ln",
+        "exp" => "exp",
+        "lim" => "lim",
+        "max" => "max",
+        "min" => "min",
+        "sup" => "sup",
+        "inf" => "inf",
+        "det" => "det",
+        "gcd" => "gcd",
+        "mod" | "bmod" => "mod",
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tex_to_unicode() {
+        assert_eq!(tex_to_unicode(r"E = mc^2"), "E = mc²");
+        assert_eq!(tex_to_unicode(r"x_{i+1} \leq \alpha x_i"), "xᵢ₊₁ ≤ α xᵢ");
+        assert_eq!(
+            tex_to_unicode(r"\sum_{n=1}^{\infty} \frac{1}{n^2} = \frac{\pi^2}{6}"),
+            "∑ₙ₌₁^∞ 1/n² = π²/6"
+        );
+        assert_eq!(tex_to_unicode(r"\sqrt{a^2 + b^2}"), "√(a² + b²)");
+        assert_eq!(tex_to_unicode(r"\forall x \in \mathbb{R}"), "∀ x ∈ ℝ");
+        assert_eq!(tex_to_unicode(r"\text{if } x > 0"), "if x > 0");
+    }
+
+    #[test]
+    fn test_tex_to_unicode_keeps_unknown_commands() {
+        assert_eq!(tex_to_unicode(r"\unknown{x}"), r"\unknownx");
+        assert_eq!(tex_to_unicode("a &= b \\\\\n c &= d"), "a = b\nc = d");
+    }
+}
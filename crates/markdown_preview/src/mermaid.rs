@@ -0,0 +1,766 @@
+use std::fmt::Write as _;
+
+use anyhow::{Context as _, Result, bail};
+use collections::{HashMap, HashSet};
+use gpui::{Hsla, Pixels, Rgba, Size, px, size};
+
+const FONT_SIZE: f32 = 14.;
+/// An estimate of the average glyph width, as the diagram is laid out before any text is shaped.
+const CHAR_WIDTH: f32 = 8.;
+const LINE_HEIGHT: f32 = 18.;
+const NODE_PADDING_X: f32 = 16.;
+const NODE_PADDING_Y: f32 = 10.;
+const NODE_GAP: f32 = 32.;
+const RANK_GAP: f32 = 56.;
+const MARGIN: f32 = 8.;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Direction {
+    TopToBottom,
+    BottomToTop,
+    LeftToRight,
+    RightToLeft,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum NodeShape {
+    Rectangle,
+    Rounded,
+    Circle,
+    Diamond,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum EdgeStyle {
+    Solid,
+    Dotted,
+    Thick,
+}
+
+#[derive(Debug, PartialEq)]
+struct Node {
+    id: String,
+    lines: Vec<String>,
+    shape: NodeShape,
+    center: (f32, f32),
+    size: (f32, f32),
+}
+
+#[derive(Debug, PartialEq)]
+struct Edge {
+    from: usize,
+    to: usize,
+    label: Option<String>,
+    style: EdgeStyle,
+    arrow: bool,
+}
+
+/// The colors used to draw a [`MermaidDiagram`].
+pub struct MermaidColors {
+    pub node_background: Hsla,
+    pub node_border: Hsla,
+    pub edge: Hsla,
+    pub text: Hsla,
+    pub label_background: Hsla,
+}
+
+/// A laid out Mermaid flowchart.
+///
+/// Only the `graph` and `flowchart` diagram types are supported, which is enough to render most
+/// diagrams found in READMEs and design documents.
+#[derive(Debug, PartialEq)]
+pub struct MermaidDiagram {
+    nodes: Vec<Node>,
+    edges: Vec<Edge>,
+    width: f32,
+    height: f32,
+}
+
+impl MermaidDiagram {
+    pub fn parse(source: &str) -> Result<Self> {
+        let mut statements = source
+            .lines()
+            .flat_map(|line| line.split(';'))
+            .map(str::trim)
+            .filter(|statement| !statement.is_empty() && !statement.starts_with("%%"));
+
+        let header = statements.next().context("empty diagram")?;
+        let mut header_words = header.split_whitespace();
+        if !matches!(header_words.next(), Some("graph" | "flowchart")) {
+            bail!("unsupported diagram type: {header}");
+        }
+        let direction = match header_words.next() {
+            None | Some("TB" | "TD") => Direction::TopToBottom,
+            Some("BT") => Direction::BottomToTop,
+            Some("LR") => Direction::LeftToRight,
+            Some("RL") => Direction::RightToLeft,
+            Some(direction) => bail!("unknown flowchart direction: {direction}"),
+        };
+
+        let mut builder = DiagramBuilder::default();
+        for statement in statements {
+            // Subgraphs are drawn as if their nodes were declared at the top level, and styling
+            // is left to the theme.
+            let keyword = statement.split_whitespace().next().unwrap_or_default();
+            if matches!(
+                keyword,
+                "subgraph"
+                    | "end"
+                    | "direction"
+                    | "style"
+                    | "classDef"
+                    | "class"
+                    | "linkStyle"
+                    | "click"
+            ) {
+                continue;
+            }
+            builder.parse_statement(statement)?;
+        }
+        if builder.nodes.is_empty() {
+            bail!("diagram has no nodes");
+        }
+        Ok(builder.layout(direction))
+    }
+
+    /// The size at which the diagram is meant to be displayed.
+    pub fn size(&self) -> Size<Pixels> {
+        size(px(self.width), px(self.height))
+    }
+
+    /// Renders the diagram as an SVG document, with its pixel dimensions multiplied by `scale`
+    /// so that it stays crisp on high density displays.
+    pub fn to_svg(&self, colors: &MermaidColors, scale: f32) -> String {
+        let mut svg = String::new();
+        write!(
+            svg,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" viewBox="0 0 {} {}">"#,
+            self.width * scale,
+            self.height * scale,
+            self.width,
+            self.height,
+        )
+        .ok();
+        write!(
+            svg,
+            r#"<defs><marker id="arrow" viewBox="0 0 10 10" refX="10" refY="5" markerWidth="8" markerHeight="8" orient="auto"><path d="M0,0 L10,5 L0,10 z" {}/></marker></defs>"#,
+            paint("fill", colors.edge),
+        )
+        .ok();
+
+        for edge in &self.edges {
+            let from = &self.nodes[edge.from];
+            let to = &self.nodes[edge.to];
+            let path = if edge.from == edge.to {
+                let (x, y) = (from.center.0 + from.size.0 / 2., from.center.1);
+                format!(
+                    "M{},{} C{},{} {},{} {},{}",
+                    x,
+                    y - 6.,
+                    x + 32.,
+                    y - 32.,
+                    x + 32.,
+                    y + 32.,
+                    x,
+                    y + 6.
+                )
+            } else {
+                let start = boundary_point(from, to.center);
+                let end = boundary_point(to, from.center);
+                format!("M{},{} L{},{}", start.0, start.1, end.0, end.1)
+            };
+            let (stroke_width, dash) = match edge.style {
+                EdgeStyle::Solid => (1.5, ""),
+                EdgeStyle::Dotted => (1.5, r#" stroke-dasharray="3 3""#),
+                EdgeStyle::Thick => (3., ""),
+            };
+            write!(
+                svg,
+                r#"<path d="{path}" fill="none" {} stroke-width="{stroke_width}"{dash}{}/>"#,
+                paint("stroke", colors.edge),
+                if edge.arrow {
+                    r#" marker-end="url(#arrow)""#
+                } else {
+                    ""
+                },
+            )
+            .ok();
+        }
+
+        for node in &self.nodes {
+            let (x, y) = node.center;
+            let (width, height) = node.size;
+            let fill = paint("fill", colors.node_background);
+            let stroke = paint("stroke", colors.node_border);
+            match node.shape {
+                NodeShape::Rectangle | NodeShape::Rounded => {
+                    let radius = if node.shape == NodeShape::Rounded {
+                        height / 2.
+                    } else {
+                        2.
+                    };
+                    write!(
+                        svg,
+                        r#"<rect x="{}" y="{}" width="{width}" height="{height}" rx="{radius}" {fill} {stroke}/>"#,
+                        x - width / 2.,
+                        y - height / 2.,
+                    )
+                    .ok();
+                }
+                NodeShape::Circle => {
+                    write!(
+                        svg,
+                        r#"<circle cx="{x}" cy="{y}" r="{}" {fill} {stroke}/>"#,
+                        width / 2.
+                    )
+                    .ok();
+                }
+                NodeShape::Diamond => {
+                    write!(
+                        svg,
+                        r#"<polygon points="{},{y} {x},{} {},{y} {x},{}" {fill} {stroke}/>"#,
+                        x - width / 2.,
+                        y - height / 2.,
+                        x + width / 2.,
+                        y + height / 2.,
+                    )
+                    .ok();
+                }
+            }
+            write_text(&mut svg, &node.lines, node.center, colors.text);
+        }
+
+        for edge in &self.edges {
+            let Some(label) = &edge.label else {
+                continue;
+            };
+            let from = self.nodes[edge.from].center;
+            let to = self.nodes[edge.to].center;
+            let center = if edge.from == edge.to {
+                let node = &self.nodes[edge.from];
+                (from.0 + node.size.0 / 2. + 32., from.1)
+            } else {
+                ((from.0 + to.0) / 2., (from.1 + to.1) / 2.)
+            };
+            let lines = label_lines(label);
+            let (width, height) = text_size(&lines);
+            write!(
+                svg,
+                r#"<rect x="{}" y="{}" width="{}" height="{}" {}/>"#,
+                center.0 - width / 2. - 4.,
+                center.1 - height / 2.,
+                width + 8.,
+                height,
+                paint("fill", colors.label_background),
+            )
+            .ok();
+            write_text(&mut svg, &lines, center, colors.text);
+        }
+
+        svg.push_str("</svg>");
+        svg
+    }
+}
+
+#[derive(Default)]
+struct DiagramBuilder {
+    nodes: Vec<(String, Option<(NodeShape, String)>)>,
+    node_indices: HashMap<String, usize>,
+    edges: Vec<(usize, usize, Link)>,
+}
+
+struct Link {
+    label: Option<String>,
+    style: EdgeStyle,
+    arrow: bool,
+}
+
+impl DiagramBuilder {
+    fn parse_statement(&mut self, statement: &str) -> Result<()> {
+        let mut rest = statement;
+        let mut previous = self.parse_node(&mut rest)?;
+        loop {
+            rest = rest.trim_start();
+            if rest.is_empty() {
+                return Ok(());
+            }
+            let link = parse_link(&mut rest)?;
+            let next = self.parse_node(&mut rest)?;
+            self.edges.push((previous, next, link));
+            previous = next;
+        }
+    }
+
+    fn parse_node(&mut self, rest: &mut &str) -> Result<usize> {
+        let source = rest.trim_start();
+        let id_len = source
+            .find(|character: char| !(character.is_alphanumeric() || character == '_'))
+            .unwrap_or(source.len());
+        if id_len == 0 {
+            bail!("expected a node at {source:?}");
+        }
+        let (id, mut after_id) = source.split_at(id_len);
+        let shape = parse_shape(&mut after_id)?;
+        if let Some(class) = after_id.strip_prefix(":::") {
+            let class_len = class
+                .find(|character: char| !(character.is_alphanumeric() || character == '_'))
+                .unwrap_or(class.len());
+            after_id = &class[class_len..];
+        }
+        *rest = after_id;
+
+        let index = match self.node_indices.get(id) {
+            Some(index) => *index,
+            None => {
+                self.nodes.push((id.to_string(), None));
+                self.node_indices
+                    .insert(id.to_string(), self.nodes.len() - 1);
+                self.nodes.len() - 1
+            }
+        };
+        if shape.is_some() {
+            self.nodes[index].1 = shape;
+        }
+        Ok(index)
+    }
+
+    fn layout(self, direction: Direction) -> MermaidDiagram {
+        let mut nodes = self
+            .nodes
+            .into_iter()
+            .map(|(id, shape)| {
+                let (shape, label) = shape.unwrap_or_else(|| (NodeShape::Rectangle, id.clone()));
+                let lines = label_lines(&label);
+                let (text_width, text_height) = text_size(&lines);
+                let (width, height) = (
+                    text_width + 2. * NODE_PADDING_X,
+                    text_height + 2. * NODE_PADDING_Y,
+                );
+                let size = match shape {
+                    NodeShape::Rectangle | NodeShape::Rounded => (width, height),
+                    NodeShape::Circle => (width.max(height), width.max(height)),
+                    NodeShape::Diamond => (width * 1.5, height * 1.5),
+                };
+                Node {
+                    id,
+                    lines,
+                    shape,
+                    center: (0., 0.),
+                    size,
+                }
+            })
+            .collect::<Vec<_>>();
+        let edges = self
+            .edges
+            .into_iter()
+            .map(|(from, to, link)| Edge {
+                from,
+                to,
+                label: link.label,
+                style: link.style,
+                arrow: link.arrow,
+            })
+            .collect::<Vec<_>>();
+
+        let ranks = rank_nodes(nodes.len(), &edges);
+        let vertical = matches!(direction, Direction::TopToBottom | Direction::BottomToTop);
+        // Sizes along the axis that ranks follow each other on, and the one nodes within a rank
+        // are placed along.
+        let main_size = |node: &Node| if vertical { node.size.1 } else { node.size.0 };
+        let cross_size = |node: &Node| if vertical { node.size.0 } else { node.size.1 };
+
+        let rank_cross_extents = ranks
+            .iter()
+            .map(|rank| {
+                rank.iter().map(|ix| cross_size(&nodes[*ix])).sum::<f32>()
+                    + NODE_GAP * rank.len().saturating_sub(1) as f32
+            })
+            .collect::<Vec<_>>();
+        let total_cross = rank_cross_extents.iter().copied().fold(0., f32::max);
+        let mut main_start = 0.;
+        for (rank, cross_extent) in ranks.iter().zip(&rank_cross_extents) {
+            let main_extent = rank
+                .iter()
+                .map(|ix| main_size(&nodes[*ix]))
+                .fold(0., f32::max);
+            let mut cross_cursor = (total_cross - cross_extent) / 2.;
+            for ix in rank {
+                let node_cross_size = cross_size(&nodes[*ix]);
+                let main = main_start + main_extent / 2.;
+                let cross = cross_cursor + node_cross_size / 2.;
+                nodes[*ix].center = if vertical {
+                    (cross, main)
+                } else {
+                    (main, cross)
+                };
+                cross_cursor += node_cross_size + NODE_GAP;
+            }
+            main_start += main_extent + RANK_GAP;
+        }
+        let total_main = (main_start - RANK_GAP).max(0.);
+
+        for node in &mut nodes {
+            match direction {
+                Direction::BottomToTop => node.center.1 = total_main - node.center.1,
+                Direction::RightToLeft => node.center.0 = total_main - node.center.0,
+                Direction::TopToBottom | Direction::LeftToRight => {}
+            }
+            node.center.0 += MARGIN;
+            node.center.1 += MARGIN;
+        }
+
+        let (width, height) = if vertical {
+            (total_cross, total_main)
+        } else {
+            (total_main, total_cross)
+        };
+        MermaidDiagram {
+            nodes,
+            edges,
+            width: width + 2. * MARGIN,
+            height: height + 2. * MARGIN,
+        }
+    }
+}
+
+fn parse_shape(rest: &mut &str) -> Result<Option<(NodeShape, String)>> {
+    const DELIMITERS: &[(&str, &str, NodeShape)] = &[
+        ("((", "))", NodeShape::Circle),
+        ("([", "])", NodeShape::Rounded),
+        ("[[", "]]", NodeShape::Rectangle),
+        ("[(", ")]", NodeShape::Rectangle),
+        ("{{", "}}", NodeShape::Rectangle),
+        ("[", "]", NodeShape::Rectangle),
+        ("(", ")", NodeShape::Rounded),
+        ("{", "}", NodeShape::Diamond),
+        (">", "]", NodeShape::Rectangle),
+    ];
+
+    for (open, close, shape) in DELIMITERS {
+        let Some(after_open) = rest.strip_prefix(open) else {
+            continue;
+        };
+        let trimmed = after_open.trim_start();
+        let (label, after_label) = if let Some(quoted) = trimmed.strip_prefix('"') {
+            let quote_end = quoted.find('"').context("unterminated node label")?;
+            (&quoted[..quote_end], quoted[quote_end + 1..].trim_start())
+        } else {
+            let close_start = after_open.find(close).context("unterminated node shape")?;
+            (after_open[..close_start].trim(), &after_open[close_start..])
+        };
+        let after_close = after_label
+            .strip_prefix(close)
+            .with_context(|| format!("expected {close:?} after node label"))?;
+        *rest = after_close;
+        return Ok(Some((*shape, label.to_string())));
+    }
+    Ok(None)
+}
+
+fn parse_link(rest: &mut &str) -> Result<Link> {
+    fn link_len(source: &str) -> usize {
+        source
+            .find(|character| !matches!(character, '-' | '.' | '=' | '>'))
+            .unwrap_or(source.len())
+    }
+
+    fn style(link: &str) -> EdgeStyle {
+        if link.contains('=') {
+            EdgeStyle::Thick
+        } else if link.contains('.') {
+            EdgeStyle::Dotted
+        } else {
+            EdgeStyle::Solid
+        }
+    }
+
+    let source = rest.strip_prefix('<').unwrap_or(rest);
+    let opening_len = link_len(source);
+    let opening = &source[..opening_len];
+    if opening_len < 2 {
+        bail!("expected a link at {source:?}");
+    }
+    let after_opening = &source[opening_len..];
+
+    // Links can carry their label inline, as in `A -- label --> B`.
+    if matches!(opening, "--" | "==" | "-.") {
+        let label_len = ["--", "==", ".-"]
+            .iter()
+            .filter_map(|closing| after_opening.find(closing))
+            .min()
+            .context("unterminated link label")?;
+        let label = after_opening[..label_len].trim();
+        let after_label = &after_opening[label_len..];
+        let closing_len = link_len(after_label);
+        let closing = &after_label[..closing_len];
+        *rest = &after_label[closing_len..];
+        return Ok(Link {
+            label: (!label.is_empty()).then(|| label.to_string()),
+            style: style(opening),
+            arrow: closing.ends_with('>'),
+        });
+    }
+
+    let mut label = None;
+    let mut after_link = after_opening;
+    if let Some(piped) = after_opening.trim_start().strip_prefix('|') {
+        let label_end = piped.find('|').context("unterminated link label")?;
+        label = Some(piped[..label_end].trim().to_string());
+        after_link = &piped[label_end + 1..];
+    }
+    *rest = after_link;
+    Ok(Link {
+        label,
+        style: style(opening),
+        arrow: opening.ends_with('>'),
+    })
+}
+
+/// Assigns each node to a rank, which is the length of the longest path reaching it, and orders
+/// the nodes within each rank to reduce edge crossings.
+fn rank_nodes(node_count: usize, edges: &[Edge]) -> Vec<Vec<usize>> {
+    fn find_back_edges(
+        node: usize,
+        successors: &[Vec<usize>],
+        visit_states: &mut [u8],
+        back_edges: &mut HashSet<(usize, usize)>,
+    ) {
+        const VISITING: u8 = 1;
+        const VISITED: u8 = 2;
+
+        visit_states[node] = VISITING;
+        for successor in &successors[node] {
+            match visit_states[*successor] {
+                VISITING => {
+                    back_edges.insert((node, *successor));
+                }
+                VISITED => {}
+                _ => find_back_edges(*successor, successors, visit_states, back_edges),
+            }
+        }
+        visit_states[node] = VISITED;
+    }
+
+    let mut successors = vec![Vec::new(); node_count];
+    for edge in edges {
+        successors[edge.from].push(edge.to);
+    }
+
+    // Edges closing a cycle are left out of the ranking, so that every cycle still has a start.
+    let mut visit_states = vec![0; node_count];
+    let mut back_edges = HashSet::default();
+    for node in 0..node_count {
+        if visit_states[node] == 0 {
+            find_back_edges(node, &successors, &mut visit_states, &mut back_edges);
+        }
+    }
+    let forward_edges = edges
+        .iter()
+        .map(|edge| (edge.from, edge.to))
+        .filter(|edge| !back_edges.contains(edge))
+        .collect::<Vec<_>>();
+
+    let mut incoming_counts = vec![0; node_count];
+    for (_, to) in &forward_edges {
+        incoming_counts[*to] += 1;
+    }
+    let mut node_ranks = vec![0; node_count];
+    let mut ready = (0..node_count)
+        .filter(|node| incoming_counts[*node] == 0)
+        .collect::<Vec<_>>();
+    while let Some(node) = ready.pop() {
+        for (from, to) in &forward_edges {
+            if *from != node {
+                continue;
+            }
+            node_ranks[*to] = node_ranks[*to].max(node_ranks[node] + 1);
+            incoming_counts[*to] -= 1;
+            if incoming_counts[*to] == 0 {
+                ready.push(*to);
+            }
+        }
+    }
+
+    let rank_count = node_ranks.iter().max().map_or(0, |rank| rank + 1);
+    let mut ranks = vec![Vec::new(); rank_count];
+    for (node, rank) in node_ranks.iter().enumerate() {
+        ranks[*rank].push(node);
+    }
+
+    let mut positions = vec![0.; node_count];
+    for rank_ix in 0..ranks.len() {
+        let barycenter = |node: usize| {
+            let predecessor_positions = forward_edges
+                .iter()
+                .filter(|(from, to)| *to == node && node_ranks[*from] < rank_ix)
+                .map(|(from, _)| positions[*from])
+                .collect::<Vec<f32>>();
+            if predecessor_positions.is_empty() {
+                None
+            } else {
+                Some(predecessor_positions.iter().sum::<f32>() / predecessor_positions.len() as f32)
+            }
+        };
+        let mut keyed_rank = ranks[rank_ix]
+            .iter()
+            .enumerate()
+            .map(|(ix, node)| (barycenter(*node).unwrap_or(ix as f32), *node))
+            .collect::<Vec<_>>();
+        keyed_rank.sort_by(|(a, _), (b, _)| a.total_cmp(b));
+        ranks[rank_ix] = keyed_rank.into_iter().map(|(_, node)| node).collect();
+        for (ix, node) in ranks[rank_ix].iter().enumerate() {
+            positions[*node] = ix as f32;
+        }
+    }
+    ranks
+}
+
+/// Returns where the line from the center of `node` towards `target` leaves the node's outline.
+fn boundary_point(node: &Node, target: (f32, f32)) -> (f32, f32) {
+    let (x, y) = node.center;
+    let (dx, dy) = (target.0 - x, target.1 - y);
+    if dx == 0. && dy == 0. {
+        return node.center;
+    }
+    let (half_width, half_height) = (node.size.0 / 2., node.size.1 / 2.);
+    let t = match node.shape {
+        NodeShape::Rectangle | NodeShape::Rounded => {
+            (half_width / dx.abs()).min(half_height / dy.abs())
+        }
+        NodeShape::Circle => half_width / (dx * dx + dy * dy).sqrt(),
+        NodeShape::Diamond => 1. / (dx.abs() / half_width + dy.abs() / half_height),
+    };
+    (x + dx * t, y + dy * t)
+}
+
+fn label_lines(label: &str) -> Vec<String> {
+    label
+        .replace("<br/>", "<br>")
+        .replace("<br />", "<br>")
+        .split("<br>")
+        .map(|line| line.trim().to_string())
+        .collect()
+}
+
+fn text_size(lines: &[String]) -> (f32, f32) {
+    let longest_line = lines
+        .iter()
+        .map(|line| line.chars().count())
+        .max()
+        .unwrap_or(0);
+    (
+        longest_line as f32 * CHAR_WIDTH,
+        lines.len() as f32 * LINE_HEIGHT,
+    )
+}
+
+fn write_text(svg: &mut String, lines: &[String], center: (f32, f32), color: Hsla) {
+    let top = center.1 - lines.len() as f32 * LINE_HEIGHT / 2.;
+    for (ix, line) in lines.iter().enumerate() {
+        // SVG positions text by its baseline, which sits roughly a third of the font size below
+        // the middle of the line.
+        let baseline = top + (ix as f32 + 0.5) * LINE_HEIGHT + FONT_SIZE * 0.35;
+        write!(
+            svg,
+            r#"<text x="{}" y="{baseline}" text-anchor="middle" font-family="sans-serif" font-size="{FONT_SIZE}" {}>{}</text>"#,
+            center.0,
+            paint("fill", color),
+            escape_xml(line),
+        )
+        .ok();
+    }
+}
+
+fn paint(attribute: &str, color: Hsla) -> String {
+    let color = Rgba::from(color);
+    format!(
+        r##"{attribute}="#{:02x}{:02x}{:02x}" {attribute}-opacity="{}""##,
+        (color.r * 255.).round() as u8,
+        (color.g * 255.).round() as u8,
+        (color.b * 255.).round() as u8,
+        color.a,
+    )
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node_ids(diagram: &MermaidDiagram) -> Vec<&str> {
+        diagram.nodes.iter().map(|node| node.id.as_str()).collect()
+    }
+
+    #[test]
+    fn test_parse_flowchart() {
+        let diagram = MermaidDiagram::parse(
+            "flowchart LR\n    A[Start] --> B{Is it?}\n    B -->|Yes| C(OK)\n    B -- No --> D((Stop))\n    C -.-> A",
+        )
+        .expect("diagram should parse");
+
+        assert_eq!(node_ids(&diagram), ["A", "B", "C", "D"]);
+        assert_eq!(diagram.nodes[0].lines, ["Start"]);
+        assert_eq!(diagram.nodes[1].shape, NodeShape::Diamond);
+        assert_eq!(diagram.nodes[2].shape, NodeShape::Rounded);
+        assert_eq!(diagram.nodes[3].shape, NodeShape::Circle);
+
+        let edges = diagram
+            .edges
+            .iter()
+            .map(|edge| (edge.from, edge.to, edge.label.as_deref(), edge.style))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            edges,
+            [
+                (0, 1, None, EdgeStyle::Solid),
+                (1, 2, Some("Yes"), EdgeStyle::Solid),
+                (1, 3, Some("No"), EdgeStyle::Solid),
+                (2, 0, None, EdgeStyle::Dotted),
+            ]
+        );
+
+        // Ranks follow each other from left to right, even though `C` links back to `A`.
+        assert!(diagram.nodes[0].center.0 < diagram.nodes[1].center.0);
+        assert!(diagram.nodes[1].center.0 < diagram.nodes[2].center.0);
+        assert_eq!(diagram.nodes[2].center.0, diagram.nodes[3].center.0);
+    }
+
+    #[test]
+    fn test_parse_chained_statements() {
+        let diagram = MermaidDiagram::parse("graph TD; a --> b --> c; b === d")
+            .expect("diagram should parse");
+        assert_eq!(node_ids(&diagram), ["a", "b", "c", "d"]);
+        assert_eq!(diagram.edges.len(), 3);
+        assert_eq!(diagram.edges[2].style, EdgeStyle::Thick);
+        assert!(!diagram.edges[2].arrow);
+        assert!(diagram.nodes[0].center.1 < diagram.nodes[1].center.1);
+    }
+
+    #[test]
+    fn test_unsupported_diagrams() {
+        assert!(MermaidDiagram::parse("sequenceDiagram\n    Alice->>Bob: Hi").is_err());
+        assert!(MermaidDiagram::parse("graph TD\n    A[Unterminated --> B").is_err());
+        assert!(MermaidDiagram::parse("graph TD").is_err());
+    }
+
+    #[test]
+    fn test_svg_escapes_labels() {
+        let diagram =
+            MermaidDiagram::parse(r#"graph TD; A["a < b & c"]"#).expect("diagram should parse");
+        let colors = MermaidColors {
+            node_background: gpui::white(),
+            node_border: gpui::black(),
+            edge: gpui::black(),
+            text: gpui::black(),
+            label_background: gpui::white(),
+        };
+        let svg = diagram.to_svg(&colors, 2.);
+        assert!(svg.contains("a &lt; b &amp; c"));
+    }
+}
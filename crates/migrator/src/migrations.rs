@@ -123,3 +123,9 @@ pub(crate) mod m_2025_10_16 {
 
     pub(crate) use settings::restore_code_actions_on_format;
 }
+
+pub(crate) mod m_2025_10_21 {
+    mod settings;
+
+    pub(crate) use settings::code_actions_on_format_to_list;
+}
@@ -15,6 +15,11 @@ fn restore_code_actions_on_format_inner(value: &mut Value, path: &[&str]) -> Res
         .get("code_actions_on_format")
         .cloned()
         .unwrap_or_else(|| Value::Object(Default::default()));
+    // The setting has since become a list, which a later migration converts
+    // the map produced here into.
+    if code_actions_on_format.is_array() {
+        return Ok(());
+    }
 
     fn fmt_path(path: &[&str], key: &str) -> String {
         let mut path = path.to_vec();
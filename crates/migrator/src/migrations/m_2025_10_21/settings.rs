@@ -0,0 +1,39 @@
+use anyhow::Result;
+use serde_json::Value;
+
+use crate::patterns::migrate_language_setting;
+
+pub fn code_actions_on_format_to_list(value: &mut Value) -> Result<()> {
+    migrate_language_setting(value, code_actions_on_format_to_list_inner)
+}
+
+fn code_actions_on_format_to_list_inner(value: &mut Value, path: &[&str]) -> Result<()> {
+    let Some(obj) = value.as_object_mut() else {
+        return Ok(());
+    };
+    let Some(code_actions_on_format) = obj.get("code_actions_on_format") else {
+        return Ok(());
+    };
+    if code_actions_on_format.is_array() {
+        return Ok(());
+    }
+    let Some(code_actions_map) = code_actions_on_format.as_object() else {
+        let mut path = path.to_vec();
+        path.push("code_actions_on_format");
+        anyhow::bail!(
+            r#"The `code_actions_on_format` is in an invalid state and cannot be migrated at {}. Please ensure the code_actions_on_format setting is a Map<String, bool>"#,
+            path.join("."),
+        );
+    };
+
+    // Disabled actions are dropped, as the list replaces any inherited one
+    // rather than being merged with it.
+    let code_actions = code_actions_map
+        .iter()
+        .filter(|(_, enabled)| enabled.as_bool().unwrap_or(false))
+        .map(|(action, _)| serde_json::json!({ "action": action }))
+        .collect();
+    obj.insert("code_actions_on_format".into(), Value::Array(code_actions));
+
+    Ok(())
+}
@@ -214,6 +214,7 @@ pub fn migrate_settings(text: &str) -> Result<Option<String>> {
             &SETTINGS_QUERY_2025_10_03,
         ),
         MigrationType::Json(migrations::m_2025_10_16::restore_code_actions_on_format),
+        MigrationType::Json(migrations::m_2025_10_21::code_actions_on_format_to_list),
     ];
     run_migrations(text, migrations)
 }
@@ -2019,4 +2020,43 @@ mod tests {
             ),
         );
     }
+
+    #[test]
+    fn test_code_actions_on_format_to_list() {
+        assert_migrate_settings_with_migrations(
+            &[MigrationType::Json(
+                migrations::m_2025_10_21::code_actions_on_format_to_list,
+            )],
+            &r#"{
+                "code_actions_on_format": {
+                    "source.organizeImports": true,
+                    "source.fixAll": false
+                }
+            }"#
+            .unindent(),
+            Some(
+                &r#"{
+                    "code_actions_on_format": [
+                        {
+                            "action": "source.organizeImports"
+                        }
+                    ]
+                }"#
+                .unindent(),
+            ),
+        );
+
+        assert_migrate_settings_with_migrations(
+            &[MigrationType::Json(
+                migrations::m_2025_10_21::code_actions_on_format_to_list,
+            )],
+            &r#"{
+                "code_actions_on_format": [
+                    { "action": "source.organizeImports", "timeout_ms": 500 }
+                ]
+            }"#
+            .unindent(),
+            None,
+        );
+    }
 }
@@ -3,10 +3,7 @@ use std::sync::Arc;
 use fs::Fs;
 use gpui::{Action, App, IntoElement};
 use settings::{BaseKeymap, Settings, update_settings_file};
-use theme::{
-    Appearance, SystemAppearance, ThemeMode, ThemeName, ThemeRegistry, ThemeSelection,
-    ThemeSettings,
-};
+use theme::{Appearance, ThemeMode, ThemeName, ThemeRegistry, ThemeSelection, ThemeSettings};
 use ui::{
     Divider, ParentElement as _, StatefulInteractiveElement, SwitchField, TintColor,
     ToggleButtonGroup, ToggleButtonGroupSize, ToggleButtonSimple, ToggleButtonWithIcon, prelude::*,
@@ -36,16 +33,29 @@ fn get_theme_family_themes(theme_name: &str) -> Option<(&'static str, &'static s
     None
 }
 
-fn render_theme_section(tab_index: &mut isize, cx: &mut App) -> impl IntoElement {
-    let theme_selection = ThemeSettings::get_global(cx).theme.clone();
-    let system_appearance = theme::SystemAppearance::global(cx);
-
-    let theme_mode = theme_selection
+/// Returns the mode to show as selected, which for a static theme is the one matching the
+/// system appearance.
+fn selected_theme_mode(theme_selection: &ThemeSelection, cx: &App) -> ThemeMode {
+    theme_selection
         .mode()
-        .unwrap_or_else(|| match *system_appearance {
+        .unwrap_or_else(|| match theme::appearance_for_mode(None, cx) {
             Appearance::Light => ThemeMode::Light,
             Appearance::Dark => ThemeMode::Dark,
-        });
+        })
+}
+
+fn appearance_for_mode(theme_mode: ThemeMode, cx: &App) -> Appearance {
+    match theme_mode {
+        ThemeMode::Light => Appearance::Light,
+        ThemeMode::Dark => Appearance::Dark,
+        ThemeMode::System | ThemeMode::Schedule => theme::appearance_for_mode(Some(theme_mode), cx),
+    }
+}
+
+fn render_theme_section(tab_index: &mut isize, cx: &mut App) -> impl IntoElement {
+    let theme_selection = ThemeSettings::get_global(cx).theme.clone();
+
+    let theme_mode = selected_theme_mode(&theme_selection, cx);
 
     return v_flex()
         .gap_2()
@@ -86,21 +96,11 @@ fn render_theme_section(tab_index: &mut isize, cx: &mut App) -> impl IntoElement
         theme_selection: &ThemeSelection,
         cx: &mut App,
     ) -> [impl IntoElement; 3] {
-        let system_appearance = SystemAppearance::global(cx);
         let theme_registry = ThemeRegistry::global(cx);
 
         let theme_seed = 0xBEEF as f32;
-        let theme_mode = theme_selection
-            .mode()
-            .unwrap_or_else(|| match *system_appearance {
-                Appearance::Light => ThemeMode::Light,
-                Appearance::Dark => ThemeMode::Dark,
-            });
-        let appearance = match theme_mode {
-            ThemeMode::Light => Appearance::Light,
-            ThemeMode::Dark => Appearance::Dark,
-            ThemeMode::System => *system_appearance,
-        };
+        let theme_mode = selected_theme_mode(theme_selection, cx);
+        let appearance = appearance_for_mode(theme_mode, cx);
         let current_theme_name: SharedString = theme_selection.name(appearance).0.into();
 
         let theme_names = match appearance {
@@ -151,7 +151,7 @@ fn render_theme_section(tab_index: &mut isize, cx: &mut App) -> impl IntoElement
                             }
                         })
                         .map(|this| {
-                            if theme_mode == ThemeMode::System {
+                            if matches!(theme_mode, ThemeMode::System | ThemeMode::Schedule) {
                                 let (light, dark) = (
                                     theme_registry.get(LIGHT_THEMES[index]).unwrap(),
                                     theme_registry.get(DARK_THEMES[index]).unwrap(),
@@ -187,17 +187,17 @@ fn render_theme_section(tab_index: &mut isize, cx: &mut App) -> impl IntoElement
         let fs = <dyn Fs>::global(cx);
         let theme = theme.into();
         update_settings_file(fs, cx, move |settings, cx| {
-            if theme_mode == ThemeMode::System {
+            if matches!(theme_mode, ThemeMode::System | ThemeMode::Schedule) {
                 let (light_theme, dark_theme) =
                     get_theme_family_themes(&theme).unwrap_or((theme.as_ref(), theme.as_ref()));
 
                 settings.theme.theme = Some(settings::ThemeSelection::Dynamic {
-                    mode: ThemeMode::System,
+                    mode: theme_mode,
                     light: ThemeName(light_theme.into()),
                     dark: ThemeName(dark_theme.into()),
                 });
             } else {
-                let appearance = *SystemAppearance::global(cx);
+                let appearance = appearance_for_mode(theme_mode, cx);
                 theme::set_theme(settings, theme, appearance);
             }
         });
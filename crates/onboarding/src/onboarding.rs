@@ -477,9 +477,9 @@ pub async fn handle_import_vscode_settings(
     let Ok(result_channel) = cx.update(|_, cx| {
         let source = vscode_settings.source;
         let path = vscode_settings.path.clone();
-        let result_channel = cx
-            .global::<SettingsStore>()
-            .import_vscode_settings(fs, vscode_settings);
+        let result_channel = cx.update_global(|store: &mut SettingsStore, _| {
+            store.import_vscode_settings(fs, vscode_settings)
+        });
         zlog::info!("Imported {source} settings from {}", path.display());
         result_channel
     }) else {
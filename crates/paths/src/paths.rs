@@ -80,6 +80,46 @@ pub fn set_custom_data_dir(dir: &str) -> &'static PathBuf {
     })
 }
 
+/// The environment variable that puts Zed in portable mode, storing all user data in the
+/// directory it names.
+pub const PORTABLE_DATA_DIR_ENV_VAR: &str = "ZED_PORTABLE_DATA_DIR";
+
+/// The name of the directory next to Zed's executable that puts Zed in portable mode.
+pub const PORTABLE_DATA_DIR_NAME: &str = "zed-portable-data";
+
+/// Returns the directory that all user data should be stored in when Zed runs in portable mode,
+/// such as from a USB stick, or `None` when it should use the platform's default directories.
+///
+/// Zed runs in portable mode when the `ZED_PORTABLE_DATA_DIR` environment variable is set, or
+/// when a `zed-portable-data` directory exists next to the executable or next to the directory
+/// that contains it.
+pub fn portable_data_dir() -> Option<PathBuf> {
+    if let Some(dir) = env::var_os(PORTABLE_DATA_DIR_ENV_VAR).filter(|dir| !dir.is_empty()) {
+        return Some(PathBuf::from(dir));
+    }
+
+    portable_data_dir_near(&env::current_exe().ok()?)
+}
+
+/// Returns the `zed-portable-data` directory next to the given executable or next to the
+/// directory that contains it, if one exists.
+fn portable_data_dir_near(executable: &Path) -> Option<PathBuf> {
+    let executable_dir = executable.parent()?;
+    // The CLI and the app are in different directories of some bundles, such as `bin` and
+    // `libexec` on Linux, and on macOS the app bundle must not be modified, so the directory is
+    // also looked for next to the bundle.
+    let bundle_dir = if cfg!(target_os = "macos") {
+        executable_dir.ancestors().nth(3)
+    } else {
+        executable_dir.parent()
+    };
+    [Some(executable_dir), bundle_dir]
+        .into_iter()
+        .flatten()
+        .map(|dir| dir.join(PORTABLE_DATA_DIR_NAME))
+        .find(|dir| dir.is_dir())
+}
+
 /// Returns the path to the configuration directory used by Zed.
 pub fn config_dir() -> &'static PathBuf {
     CONFIG_DIR.get_or_init(|| {
@@ -130,6 +170,10 @@ pub fn data_dir() -> &'static PathBuf {
 pub fn temp_dir() -> &'static PathBuf {
     static TEMP_DIR: OnceLock<PathBuf> = OnceLock::new();
     TEMP_DIR.get_or_init(|| {
+        if let Some(custom_dir) = CUSTOM_DATA_DIR.get() {
+            return custom_dir.join("cache");
+        }
+
         if cfg!(target_os = "macos") {
             return dirs::cache_dir()
                 .expect("failed to determine cachesDirectory directory")
@@ -159,7 +203,7 @@ pub fn temp_dir() -> &'static PathBuf {
 pub fn logs_dir() -> &'static PathBuf {
     static LOGS_DIR: OnceLock<PathBuf> = OnceLock::new();
     LOGS_DIR.get_or_init(|| {
-        if cfg!(target_os = "macos") {
+        if cfg!(target_os = "macos") && CUSTOM_DATA_DIR.get().is_none() {
             home_dir().join("Library/Logs/Zed")
         } else {
             data_dir().join("logs")
@@ -191,6 +235,12 @@ pub fn database_dir() -> &'static PathBuf {
     DATABASE_DIR.get_or_init(|| data_dir().join("db"))
 }
 
+/// Returns the path to the directory where snapshots of edited files are kept.
+pub fn local_history_dir() -> &'static PathBuf {
+    static LOCAL_HISTORY_DIR: OnceLock<PathBuf> = OnceLock::new();
+    LOCAL_HISTORY_DIR.get_or_init(|| data_dir().join("local_history"))
+}
+
 /// Returns the path to the crashes directory, if it exists for the current platform.
 pub fn crashes_dir() -> &'static Option<PathBuf> {
     static CRASHES_DIR: OnceLock<Option<PathBuf>> = OnceLock::new();
@@ -419,6 +469,13 @@ pub fn local_settings_file_relative_path() -> &'static RelPath {
     *CACHED
 }
 
+/// Returns the relative path to a `keymap.json` file within a project.
+pub fn local_keymap_file_relative_path() -> &'static RelPath {
+    static CACHED: LazyLock<&'static RelPath> =
+        LazyLock::new(|| RelPath::unix(".zed/keymap.json").unwrap());
+    *CACHED
+}
+
 /// Returns the relative path to a `tasks.json` file within a project.
 pub fn local_tasks_file_relative_path() -> &'static RelPath {
     static CACHED: LazyLock<&'static RelPath> =
@@ -548,3 +605,45 @@ pub fn global_gitignore_path() -> Option<PathBuf> {
         .get_or_init(::ignore::gitignore::gitconfig_excludes_path)
         .clone()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_portable_data_dir_near() {
+        let root = tempfile::tempdir().unwrap();
+        let executable_dir = root.path().join("bundle/Contents/MacOS");
+        std::fs::create_dir_all(&executable_dir).unwrap();
+        let executable = executable_dir.join("zed");
+
+        assert_eq!(portable_data_dir_near(&executable), None);
+
+        let bundle_dir = if cfg!(target_os = "macos") {
+            root.path().to_path_buf()
+        } else {
+            root.path().join("bundle/Contents")
+        };
+        std::fs::create_dir(bundle_dir.join(PORTABLE_DATA_DIR_NAME)).unwrap();
+        assert_eq!(
+            portable_data_dir_near(&executable),
+            Some(bundle_dir.join(PORTABLE_DATA_DIR_NAME))
+        );
+
+        // The directory next to the executable takes precedence.
+        std::fs::create_dir(executable_dir.join(PORTABLE_DATA_DIR_NAME)).unwrap();
+        assert_eq!(
+            portable_data_dir_near(&executable),
+            Some(executable_dir.join(PORTABLE_DATA_DIR_NAME))
+        );
+
+        // A file with the same name doesn't enable portable mode.
+        let other_executable_dir = root.path().join("bin");
+        std::fs::create_dir(&other_executable_dir).unwrap();
+        std::fs::write(other_executable_dir.join(PORTABLE_DATA_DIR_NAME), "").unwrap();
+        assert_eq!(
+            portable_data_dir_near(&other_executable_dir.join("zed")),
+            None
+        );
+    }
+}
@@ -14,6 +14,7 @@ use gpui::{
 };
 use language::{
     Buffer, BufferEvent, Capability, DiskState, File as _, Language, Operation,
+    language_settings::AllLanguageSettings,
     proto::{
         deserialize_line_ending, deserialize_version, serialize_line_ending, serialize_version,
         split_operations,
@@ -23,6 +24,7 @@ use rpc::{
     AnyProtoClient, ErrorCode, ErrorExt as _, TypedEnvelope,
     proto::{self},
 };
+use settings::{Settings as _, SettingsLocation};
 use smol::channel::Receiver;
 use std::{io, pin::pin, sync::Arc, time::Instant};
 use text::BufferId;
@@ -376,6 +378,34 @@ impl LocalBufferStore {
     ) -> Task<Result<()>> {
         let buffer = buffer_handle.read(cx);
 
+        // Resolve settings for the path being written to rather than the buffer's
+        // file, which differs when saving to a new path.
+        let location = SettingsLocation {
+            worktree_id: worktree.read(cx).id(),
+            path: &path,
+        };
+        let language_name = buffer.language().map(|language| language.name());
+        let settings = AllLanguageSettings::get(Some(location), cx).language(
+            Some(location),
+            language_name.as_ref(),
+            cx,
+        );
+        // New files have no encoding or line ending of their own yet, so they
+        // take the defaults for their language.
+        let is_new_file = buffer
+            .file()
+            .is_none_or(|file| file.disk_state() == DiskState::New);
+        let encoding = if is_new_file && !settings.editorconfig.encoding {
+            settings.default_encoding
+        } else {
+            settings.encoding
+        };
+        let default_line_ending = is_new_file.then_some(settings.default_line_ending);
+        if let Some(line_ending) = default_line_ending {
+            buffer_handle.update(cx, |buffer, cx| buffer.set_line_ending(line_ending, cx));
+        }
+
+        let buffer = buffer_handle.read(cx);
         let text = buffer.as_rope().clone();
         let line_ending = buffer.line_ending();
         let version = buffer.version();
@@ -389,7 +419,7 @@ impl LocalBufferStore {
         }
 
         let save = worktree.update(cx, |worktree, cx| {
-            worktree.write_file(path, text, line_ending, cx)
+            worktree.write_file(path, text, line_ending, encoding, cx)
         });
 
         cx.spawn(async move |this, cx| {
@@ -619,8 +649,17 @@ impl LocalBufferStore {
         worktree: Entity<Worktree>,
         cx: &mut Context<BufferStore>,
     ) -> Task<Result<Entity<Buffer>>> {
+        // Decode the file with the charset that it would be saved with, so that
+        // files written in a non-UTF-8 encoding can be opened again.
+        let location = SettingsLocation {
+            worktree_id: worktree.read(cx).id(),
+            path: &path,
+        };
+        let encoding = AllLanguageSettings::get(Some(location), cx)
+            .language(Some(location), None, cx)
+            .encoding;
         let load_buffer = worktree.update(cx, |worktree, cx| {
-            let load_file = worktree.load_file(path.as_ref(), cx);
+            let load_file = worktree.load_file(path.as_ref(), encoding, cx);
             let reservation = cx.reserve_entity();
             let buffer_id = BufferId::from(reservation.entity_id().as_non_zero_u64());
             cx.spawn(async move |_, cx| {
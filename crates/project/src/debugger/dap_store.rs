@@ -680,6 +680,7 @@ impl DapStore {
                             padding_left: false,
                             padding_right: false,
                             tooltip: None,
+                            text_edits: Vec::new(),
                             resolve_state: ResolveState::Resolved,
                         });
                     }
@@ -703,6 +704,7 @@ impl DapStore {
                                 padding_left: false,
                                 padding_right: false,
                                 tooltip: None,
+                                text_edits: Vec::new(),
                                 resolve_state: ResolveState::Resolved,
                             });
                         };
@@ -4,15 +4,15 @@ use remote::RemoteClient;
 use rpc::proto::{self, REMOTE_SERVER_PROJECT_ID};
 use std::{collections::VecDeque, path::Path, sync::Arc};
 use task::Shell;
-use util::ResultExt;
-use worktree::Worktree;
+use util::{ResultExt, rel_path::RelPath};
+use worktree::{Worktree, WorktreeId};
 
 use collections::HashMap;
-use gpui::{AppContext as _, Context, Entity, EventEmitter, Task};
-use settings::Settings as _;
+use gpui::{App, AppContext as _, Context, Entity, EventEmitter, Subscription, Task, WeakEntity};
+use settings::{Settings as _, SettingsLocation, SettingsStore};
 
 use crate::{
-    project_settings::{DirenvSettings, ProjectSettings},
+    project_settings::{DirenvSettings, GitSettings, ProjectSettings},
     worktree_store::WorktreeStore,
 };
 
@@ -21,21 +21,35 @@ pub struct ProjectEnvironment {
     local_environments: HashMap<(Shell, Arc<Path>), Shared<Task<Option<HashMap<String, String>>>>>,
     remote_environments: HashMap<(Shell, Arc<Path>), Shared<Task<Option<HashMap<String, String>>>>>,
     environment_error_messages: VecDeque<EnvironmentErrorMessage>,
+    worktree_store: WeakEntity<WorktreeStore>,
+    /// The variables each worktree's settings override, to tell which worktrees' language servers
+    /// were started with an environment that is out of date.
+    overrides_by_worktree: HashMap<WorktreeId, HashMap<String, String>>,
+    _settings_subscription: Subscription,
 }
 
 pub enum ProjectEnvironmentEvent {
     ErrorsUpdated,
+    /// The environment variables that settings override changed for these worktrees.
+    OverridesChanged(Vec<WorktreeId>),
 }
 
 impl EventEmitter<ProjectEnvironmentEvent> for ProjectEnvironment {}
 
 impl ProjectEnvironment {
-    pub fn new(cli_environment: Option<HashMap<String, String>>) -> Self {
+    pub fn new(
+        cli_environment: Option<HashMap<String, String>>,
+        worktree_store: &Entity<WorktreeStore>,
+        cx: &mut Context<Self>,
+    ) -> Self {
         Self {
             cli_environment,
             local_environments: Default::default(),
             remote_environments: Default::default(),
             environment_error_messages: Default::default(),
+            worktree_store: worktree_store.downgrade(),
+            overrides_by_worktree: Default::default(),
+            _settings_subscription: cx.observe_global::<SettingsStore>(Self::check_overrides),
         }
     }
 
@@ -55,6 +69,15 @@ impl ProjectEnvironment {
         worktree_store: &Entity<WorktreeStore>,
         cx: &mut Context<Self>,
     ) -> Shared<Task<Option<HashMap<String, String>>>> {
+        let worktree = buffer
+            .read(cx)
+            .file()
+            .map(|f| f.worktree_id(cx))
+            .and_then(|worktree_id| worktree_store.read(cx).worktree_for_id(worktree_id, cx));
+        if let Some(worktree) = worktree {
+            return self.get_worktree_environment(worktree, cx);
+        }
+
         if cfg!(any(test, feature = "test-support")) {
             return Task::ready(Some(HashMap::default())).shared();
         }
@@ -64,16 +87,7 @@ impl ProjectEnvironment {
             return Task::ready(Some(cli_environment)).shared();
         }
 
-        let Some(worktree) = buffer
-            .read(cx)
-            .file()
-            .map(|f| f.worktree_id(cx))
-            .and_then(|worktree_id| worktree_store.read(cx).worktree_for_id(worktree_id, cx))
-        else {
-            return Task::ready(None).shared();
-        };
-
-        self.get_worktree_environment(worktree, cx)
+        Task::ready(None).shared()
     }
 
     pub fn get_worktree_environment(
@@ -81,16 +95,23 @@ impl ProjectEnvironment {
         worktree: Entity<Worktree>,
         cx: &mut Context<Self>,
     ) -> Shared<Task<Option<HashMap<String, String>>>> {
+        let worktree_id = worktree.read(cx).id();
+        self.overrides_by_worktree
+            .entry(worktree_id)
+            .or_insert_with(|| worktree_overrides(worktree_id, cx));
+
+        let mut abs_path = worktree.read(cx).abs_path();
         if cfg!(any(test, feature = "test-support")) {
-            return Task::ready(Some(HashMap::default())).shared();
+            let environment = Task::ready(Some(HashMap::default())).shared();
+            return self.with_overrides(environment, &abs_path, cx);
         }
 
         if let Some(cli_environment) = self.get_cli_environment() {
             log::debug!("using project environment variables from CLI");
-            return Task::ready(Some(cli_environment)).shared();
+            let environment = Task::ready(Some(cli_environment)).shared();
+            return self.with_overrides(environment, &abs_path, cx);
         }
 
-        let mut abs_path = worktree.read(cx).abs_path();
         if !worktree.read(cx).is_local() {
             log::error!(
                 "attempted to get project environment for a non-local worktree at {abs_path:?}"
@@ -110,27 +131,97 @@ impl ProjectEnvironment {
     /// If the project was opened from the CLI, then the inherited CLI environment is returned.
     /// If it wasn't opened from the CLI, and an absolute path is given, then a shell is spawned in
     /// that directory, to get environment variables as if the user has `cd`'d there.
+    /// Either way, the variables set in the `env` setting take precedence.
     pub fn get_local_directory_environment(
         &mut self,
         shell: &Shell,
         abs_path: Arc<Path>,
         cx: &mut Context<Self>,
     ) -> Shared<Task<Option<HashMap<String, String>>>> {
-        if cfg!(any(test, feature = "test-support")) {
-            return Task::ready(Some(HashMap::default())).shared();
+        let environment = if cfg!(any(test, feature = "test-support")) {
+            Task::ready(Some(HashMap::default())).shared()
+        } else if let Some(cli_environment) = self.get_cli_environment() {
+            log::debug!("using project environment variables from CLI");
+            Task::ready(Some(cli_environment)).shared()
+        } else {
+            self.local_environments
+                .entry((shell.clone(), abs_path.clone()))
+                .or_insert_with(|| {
+                    get_local_directory_environment_impl(shell, abs_path.clone(), cx).shared()
+                })
+                .clone()
+        };
+        self.with_overrides(environment, &abs_path, cx)
+    }
+
+    /// Returns the environment variables that the `env` setting overrides in the given directory.
+    pub fn environment_overrides(&self, abs_path: &Path, cx: &App) -> HashMap<String, String> {
+        self.settings_in(abs_path, cx).env.clone()
+    }
+
+    /// Returns the `git` settings that apply to the repository in the given directory.
+    pub fn git_settings(&self, abs_path: &Path, cx: &App) -> GitSettings {
+        self.settings_in(abs_path, cx).git.clone()
+    }
+
+    fn settings_in<'a>(&self, abs_path: &Path, cx: &'a App) -> &'a ProjectSettings {
+        let location = self
+            .worktree_store
+            .upgrade()
+            .and_then(|worktree_store| worktree_store.read(cx).find_worktree(abs_path, cx));
+        match location {
+            Some((worktree, path)) => ProjectSettings::get(
+                Some(SettingsLocation {
+                    worktree_id: worktree.read(cx).id(),
+                    path: &path,
+                }),
+                cx,
+            ),
+            None => ProjectSettings::get_global(cx),
         }
+    }
 
-        if let Some(cli_environment) = self.get_cli_environment() {
-            log::debug!("using project environment variables from CLI");
-            return Task::ready(Some(cli_environment)).shared();
+    fn with_overrides(
+        &self,
+        environment: Shared<Task<Option<HashMap<String, String>>>>,
+        abs_path: &Path,
+        cx: &Context<Self>,
+    ) -> Shared<Task<Option<HashMap<String, String>>>> {
+        let overrides = self.environment_overrides(abs_path, cx);
+        if overrides.is_empty() {
+            return environment;
         }
+        cx.background_spawn(async move {
+            let mut environment = environment.await?;
+            environment.extend(overrides);
+            Some(environment)
+        })
+        .shared()
+    }
 
-        self.local_environments
-            .entry((shell.clone(), abs_path.clone()))
-            .or_insert_with(|| {
-                get_local_directory_environment_impl(shell, abs_path.clone(), cx).shared()
-            })
-            .clone()
+    /// Reports the worktrees whose overridden variables changed since their environment was
+    /// handed out, as their language servers have to be restarted to see them.
+    fn check_overrides(&mut self, cx: &mut Context<Self>) {
+        let Some(worktree_store) = self.worktree_store.upgrade() else {
+            return;
+        };
+        self.overrides_by_worktree.retain(|worktree_id, _| {
+            worktree_store
+                .read(cx)
+                .worktree_for_id(*worktree_id, cx)
+                .is_some()
+        });
+        let mut changed_worktrees = Vec::new();
+        for (worktree_id, overrides) in &mut self.overrides_by_worktree {
+            let new_overrides = worktree_overrides(*worktree_id, cx);
+            if *overrides != new_overrides {
+                *overrides = new_overrides;
+                changed_worktrees.push(*worktree_id);
+            }
+        }
+        if !changed_worktrees.is_empty() {
+            cx.emit(ProjectEnvironmentEvent::OverridesChanged(changed_worktrees));
+        }
     }
 
     pub fn get_remote_directory_environment(
@@ -174,6 +265,18 @@ impl ProjectEnvironment {
     }
 }
 
+fn worktree_overrides(worktree_id: WorktreeId, cx: &App) -> HashMap<String, String> {
+    ProjectSettings::get(
+        Some(SettingsLocation {
+            worktree_id,
+            path: RelPath::empty(),
+        }),
+        cx,
+    )
+    .env
+    .clone()
+}
+
 fn set_origin_marker(env: &mut HashMap<String, String>, origin: EnvironmentOrigin) {
     env.insert(ZED_ENVIRONMENT_ORIGIN_MARKER.to_string(), origin.into());
 }
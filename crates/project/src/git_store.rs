@@ -4,6 +4,7 @@ pub mod git_traversal;
 use crate::{
     ProjectEnvironment, ProjectItem, ProjectPath,
     buffer_store::{BufferStore, BufferStoreEvent},
+    project_settings::ProjectSettings,
     worktree_store::{WorktreeStore, WorktreeStoreEvent},
 };
 use anyhow::{Context as _, Result, anyhow, bail};
@@ -20,13 +21,18 @@ use futures::{
     stream::FuturesOrdered,
 };
 use git::{
-    BuildPermalinkParams, GitHostingProviderRegistry, Oid,
-    blame::Blame,
+    BuildPermalinkParams, GitHostingProvider, GitHostingProviderRegistry, Oid, ParsedGitRemote,
+    blame::{Blame, LineBlame},
+    commit::{Trailer, append_trailers, parse_trailers},
+    lfs::{LfsFileStatus, LfsLock},
     parse_git_remote_url,
     repository::{
-        Branch, CommitDetails, CommitDiff, CommitFile, CommitOptions, DiffType, FetchOptions,
-        GitRepository, GitRepositoryCheckpoint, PushOptions, Remote, RemoteCommandOutput, RepoPath,
-        ResetMode, UpstreamTrackingStatus,
+        ApplyPatchOptions, Branch, CleanOptions, CommitDetails, CommitDiff, CommitFile,
+        CommitGraph, CommitGraphNode, CommitGraphOptions, CommitOptions, ConflictSide, DiffType,
+        FetchOptions, FileHistoryEntry, GitRepository, GitRepositoryCheckpoint, IgnoreFile,
+        LogOptions, MaintenanceCommand, MergeOptions, PullOptions, PushOptions, PushedBranch,
+        RebaseState, Remote, RemoteCommandOutput, RemoteCommandTimedOut, RemoteProgress, RepoPath,
+        RepositoryHealth, ResetMode, Tag, UpstreamTrackingStatus,
     },
     stash::{GitStash, StashEntry},
     status::{
@@ -45,23 +51,28 @@ use parking_lot::Mutex;
 use postage::stream::Stream as _;
 use rpc::{
     AnyProtoClient, TypedEnvelope,
-    proto::{self, git_reset, split_repository_update},
+    proto::{
+        self, git_ignore_paths, git_maintenance, git_reset, git_resolve_conflict,
+        split_repository_update,
+    },
 };
 use serde::Deserialize;
+use settings::Settings as _;
 use std::{
     cmp::Ordering,
-    collections::{BTreeSet, VecDeque},
+    collections::{BTreeMap, BTreeSet, VecDeque},
     future::Future,
     mem,
     ops::Range,
     path::{Path, PathBuf},
+    pin::pin,
     sync::{
         Arc,
         atomic::{self, AtomicU64},
     },
-    time::Instant,
+    time::{Duration, Instant},
 };
-use sum_tree::{Edit, SumTree, TreeSet};
+use sum_tree::{Edit, SumTree, TreeMap, TreeSet};
 use task::Shell;
 use text::{Bias, BufferId};
 use util::{
@@ -82,14 +93,27 @@ pub struct GitStore {
     worktree_store: Entity<WorktreeStore>,
     repositories: HashMap<RepositoryId, Entity<Repository>>,
     active_repo_id: Option<RepositoryId>,
+    /// The work directory of the repository that was last chosen to be active, which is made
+    /// active again whenever it's opened, as repositories come and go with their worktrees.
+    preferred_active_work_directory: Option<Arc<Path>>,
     #[allow(clippy::type_complexity)]
     loading_diffs:
         HashMap<(BufferId, DiffKind), Shared<Task<Result<Entity<BufferDiff>, Arc<anyhow::Error>>>>>,
     diffs: HashMap<BufferId, Entity<BufferGitState>>,
     shared_diffs: HashMap<proto::PeerId, HashMap<BufferId, SharedDiffs>>,
+    /// Diffs too large for a single response, kept until the peer that asked for them has
+    /// fetched their remaining chunks, or stops fetching them.
+    diff_streams: HashMap<proto::PeerId, BTreeMap<u64, DiffStream>>,
+    next_diff_stream_id: u64,
     _subscriptions: Vec<Subscription>,
 }
 
+struct DiffStream {
+    diff: String,
+    /// Drops the stream once the peer hasn't fetched a chunk of it for a while.
+    _expire: Task<()>,
+}
+
 #[derive(Default)]
 struct SharedDiffs {
     unstaged: Option<Entity<BufferDiff>>,
@@ -249,11 +273,29 @@ pub struct RepositorySnapshot {
     pub path_style: PathStyle,
     pub branch: Option<Branch>,
     pub head_commit: Option<CommitDetails>,
+    /// What `HEAD` points to, which is unknown until the repository has been scanned.
+    pub head_state: Option<HeadState>,
     pub scan_id: u64,
     pub merge: MergeDetails,
     pub remote_origin_url: Option<String>,
     pub remote_upstream_url: Option<String>,
     pub stash_entries: GitStash,
+    /// The rebase in progress, if any.
+    pub rebase: Option<RebaseState>,
+    /// The Git LFS state of those of the files with statuses that are stored by Git LFS.
+    pub lfs_statuses: TreeMap<RepoPath, LfsFileStatus>,
+}
+
+/// What a repository's `HEAD` points to.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum HeadState {
+    /// `HEAD` is a branch, which has at least one commit.
+    Branch(SharedString),
+    /// `HEAD` is the commit with this SHA rather than a branch, like after checking out a tag or
+    /// while rebasing.
+    Detached(SharedString),
+    /// `HEAD` is a branch without any commits, as in a new repository.
+    Unborn,
 }
 
 type JobId = u64;
@@ -264,6 +306,29 @@ pub struct JobInfo {
     pub message: SharedString,
 }
 
+/// A job that has been sent to a repository's worker and hasn't finished yet.
+struct PendingJob {
+    key: Option<GitJobKey>,
+    message: Option<SharedString>,
+    queued_at: Instant,
+    started_at: Option<Instant>,
+}
+
+/// A job that is queued or running in a repository, as reported by
+/// [`GitStore::job_queue_status`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GitJobStatus {
+    pub repository_id: RepositoryId,
+    /// What the job is shown as while it runs, if it's shown.
+    pub message: Option<SharedString>,
+    /// Identifies the jobs that replace each other, so that only the last of them queued runs.
+    pub key: Option<GitJobKey>,
+    /// How long the job waited to start running, or has waited so far if it hasn't started.
+    pub queued_for: Duration,
+    /// How long the job has been running, if it has started.
+    pub running_for: Option<Duration>,
+}
+
 pub struct Repository {
     this: WeakEntity<Self>,
     snapshot: RepositorySnapshot,
@@ -273,10 +338,23 @@ pub struct Repository {
     // and that should be examined during the next status scan.
     paths_needing_status_update: BTreeSet<RepoPath>,
     job_sender: mpsc::UnboundedSender<GitJob>,
-    active_jobs: HashMap<JobId, JobInfo>,
+    /// The jobs that are queued or running, in the order that they were queued.
+    jobs: BTreeMap<JobId, PendingJob>,
     job_id: JobId,
     askpass_delegates: Arc<Mutex<HashMap<u64, AskPassDelegate>>>,
     latest_askpass_id: u64,
+    /// Where the progress of the remote operations that are running on the host is sent, by their
+    /// askpass ID.
+    remote_progress_senders: Arc<Mutex<HashMap<u64, mpsc::UnboundedSender<RemoteProgress>>>>,
+    /// The latest progress of the running fetch, push or pull, along with its askpass ID.
+    remote_progress: Option<(u64, RemoteProgress)>,
+    /// The branches and remotes listed ahead of time, when `git.prefetch_branches` is enabled.
+    /// They're cleared whenever the repository's git state changes.
+    cached_branches: Option<Vec<Branch>>,
+    cached_remotes: Option<Vec<Remote>>,
+    /// Incremented when the cached branches and remotes are cleared, so that a prefetch that was
+    /// queued before then doesn't fill them in with what it listed.
+    branch_cache_generation: u64,
 }
 
 impl std::ops::Deref for Repository {
@@ -301,14 +379,31 @@ pub enum RepositoryState {
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum RepositoryEvent {
-    Updated { full_scan: bool, new_instance: bool },
+    Updated {
+        full_scan: bool,
+        new_instance: bool,
+    },
     MergeHeadsChanged,
     PathsChanged,
+    RebaseStateChanged,
+    /// The statuses of these paths changed. Emitted alongside [`RepositoryEvent::Updated`] when
+    /// the changed paths are known, so that consumers don't have to recheck every path.
+    StatusesChanged(Arc<[RepoPath]>),
 }
 
 #[derive(Clone, Debug)]
 pub struct JobsUpdated;
 
+/// Emitted when a job has been running for longer than the `git.slow_operation_threshold_ms`
+/// setting.
+#[derive(Clone, Debug)]
+pub struct SlowJob(pub GitJobStatus);
+
+/// Emitted when writing the index failed, because another git process kept the index locked for
+/// longer than the write was retried for.
+#[derive(Clone, Debug)]
+pub struct IndexLocked;
+
 #[derive(Debug)]
 pub enum GitStoreEvent {
     ActiveRepositoryChanged(Option<RepositoryId>),
@@ -318,23 +413,114 @@ pub enum GitStoreEvent {
     IndexWriteError(anyhow::Error),
     JobsUpdated,
     ConflictsUpdated,
+    BatchOperationCompleted(BatchOperationReport),
+    SlowJob(GitJobStatus),
+    IndexLocked(RepositoryId),
+}
+
+/// An operation run across every repository in the project at once.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BatchOperation {
+    Fetch,
+    /// Reloads each repository's whole snapshot.
+    StatusRefresh,
+    /// Rescans only the statuses of each repository.
+    RefreshStatuses,
+}
+
+/// The outcome of a [`BatchOperation`], per repository.
+#[derive(Clone, Debug)]
+pub struct BatchOperationReport {
+    pub operation: BatchOperation,
+    pub succeeded: Vec<RepositoryId>,
+    pub failed: Vec<(RepositoryId, SharedString)>,
+}
+
+/// The outcome of staging or unstaging a set of paths, per path.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct StagingReport {
+    pub succeeded: Vec<RepoPath>,
+    /// Untracked paths that are ignored, which were left alone.
+    pub skipped_ignored: Vec<RepoPath>,
+    pub failed: Vec<(RepoPath, SharedString)>,
+}
+
+impl StagingReport {
+    /// Returns an error listing the paths that couldn't be staged or unstaged, if there were any.
+    pub fn ensure_succeeded(&self) -> Result<()> {
+        if self.failed.is_empty() {
+            return Ok(());
+        }
+        let total = self.succeeded.len() + self.failed.len();
+        let mut message = format!("{} of {total} paths failed:", self.failed.len());
+        for (path, error) in &self.failed {
+            message.push_str(&format!("\n{}: {error}", path.as_unix_str()));
+        }
+        Err(anyhow!(message))
+    }
+
+    fn to_proto(&self) -> proto::StagingResponse {
+        proto::StagingResponse {
+            succeeded: self.succeeded.iter().map(|path| path.to_proto()).collect(),
+            skipped_ignored: self
+                .skipped_ignored
+                .iter()
+                .map(|path| path.to_proto())
+                .collect(),
+            failed: self
+                .failed
+                .iter()
+                .map(|(path, error)| proto::StagingFailure {
+                    path: path.to_proto(),
+                    error: error.to_string(),
+                })
+                .collect(),
+        }
+    }
+
+    fn from_proto(response: proto::StagingResponse) -> Result<Self> {
+        Ok(Self {
+            succeeded: response
+                .succeeded
+                .iter()
+                .map(|path| RepoPath::from_proto(path))
+                .collect::<Result<_>>()?,
+            skipped_ignored: response
+                .skipped_ignored
+                .iter()
+                .map(|path| RepoPath::from_proto(path))
+                .collect::<Result<_>>()?,
+            failed: response
+                .failed
+                .into_iter()
+                .map(|failure| Ok((RepoPath::from_proto(&failure.path)?, failure.error.into())))
+                .collect::<Result<_>>()?,
+        })
+    }
 }
 
 impl EventEmitter<RepositoryEvent> for Repository {}
 impl EventEmitter<JobsUpdated> for Repository {}
+impl EventEmitter<SlowJob> for Repository {}
+impl EventEmitter<IndexLocked> for Repository {}
 impl EventEmitter<GitStoreEvent> for GitStore {}
 
 pub struct GitJob {
+    id: JobId,
     job: Box<dyn FnOnce(RepositoryState, &mut AsyncApp) -> Task<()>>,
     key: Option<GitJobKey>,
 }
 
-#[derive(PartialEq, Eq)]
-enum GitJobKey {
+/// Identifies the jobs that make each other redundant. A job is skipped when another job with
+/// the same key is queued after it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum GitJobKey {
     WriteIndex(RepoPath),
     ReloadBufferDiffBases,
     RefreshStatuses,
     ReloadGitState,
+    PrefetchBranches,
+    ResolveConflict(RepoPath),
 }
 
 impl GitStore {
@@ -394,9 +580,12 @@ impl GitStore {
             worktree_store,
             repositories: HashMap::default(),
             active_repo_id: None,
+            preferred_active_work_directory: None,
             _subscriptions,
             loading_diffs: HashMap::default(),
             shared_diffs: HashMap::default(),
+            diff_streams: HashMap::default(),
+            next_diff_stream_id: 0,
             diffs: HashMap::default(),
         }
     }
@@ -408,8 +597,21 @@ impl GitStore {
         client.add_entity_request_handler(Self::handle_change_branch);
         client.add_entity_request_handler(Self::handle_create_branch);
         client.add_entity_request_handler(Self::handle_rename_branch);
+        client.add_entity_request_handler(Self::handle_delete_branch);
+        client.add_entity_request_handler(Self::handle_set_upstream);
+        client.add_entity_request_handler(Self::handle_ignore_paths);
+        client.add_entity_request_handler(Self::handle_is_ignored);
+        client.add_entity_request_handler(Self::handle_clean);
+        client.add_entity_request_handler(Self::handle_resolve_conflict);
+        client.add_entity_request_handler(Self::handle_lfs_pull);
+        client.add_entity_request_handler(Self::handle_lfs_lock);
+        client.add_entity_request_handler(Self::handle_lfs_unlock);
         client.add_entity_request_handler(Self::handle_git_init);
         client.add_entity_request_handler(Self::handle_push);
+        client.add_entity_request_handler(Self::handle_get_tags);
+        client.add_entity_request_handler(Self::handle_create_tag);
+        client.add_entity_request_handler(Self::handle_delete_tag);
+        client.add_entity_request_handler(Self::handle_push_tag);
         client.add_entity_request_handler(Self::handle_pull);
         client.add_entity_request_handler(Self::handle_fetch);
         client.add_entity_request_handler(Self::handle_stage);
@@ -418,21 +620,42 @@ impl GitStore {
         client.add_entity_request_handler(Self::handle_stash_pop);
         client.add_entity_request_handler(Self::handle_stash_apply);
         client.add_entity_request_handler(Self::handle_stash_drop);
+        client.add_entity_request_handler(Self::handle_rebase_start);
+        client.add_entity_request_handler(Self::handle_rebase_continue);
+        client.add_entity_request_handler(Self::handle_rebase_abort);
+        client.add_entity_request_handler(Self::handle_merge);
+        client.add_entity_request_handler(Self::handle_merge_abort);
         client.add_entity_request_handler(Self::handle_commit);
         client.add_entity_request_handler(Self::handle_reset);
+        client.add_entity_request_handler(Self::handle_paths_discarded_by_hard_reset);
         client.add_entity_request_handler(Self::handle_show);
+        client.add_entity_request_handler(Self::handle_log);
+        client.add_entity_request_handler(Self::handle_file_history);
+        client.add_entity_request_handler(Self::handle_commit_graph);
+        client.add_entity_request_handler(Self::handle_permalink);
+        client.add_entity_request_handler(Self::handle_open_pull_request_url);
         client.add_entity_request_handler(Self::handle_load_commit_diff);
         client.add_entity_request_handler(Self::handle_checkout_files);
         client.add_entity_request_handler(Self::handle_open_commit_message_buffer);
         client.add_entity_request_handler(Self::handle_set_index_text);
         client.add_entity_request_handler(Self::handle_askpass);
+        client.add_entity_message_handler(Self::handle_remote_progress);
         client.add_entity_request_handler(Self::handle_check_for_pushed_commits);
         client.add_entity_request_handler(Self::handle_git_diff);
+        client.add_entity_request_handler(Self::handle_git_diff_file);
+        client.add_entity_request_handler(Self::handle_git_diff_chunk);
+        client.add_entity_request_handler(Self::handle_load_blob);
+        client.add_entity_request_handler(Self::handle_apply_patch);
+        client.add_entity_request_handler(Self::handle_format_patch);
+        client.add_entity_request_handler(Self::handle_maintenance);
+        client.add_entity_request_handler(Self::handle_gc);
+        client.add_entity_request_handler(Self::handle_repository_health);
         client.add_entity_request_handler(Self::handle_open_unstaged_diff);
         client.add_entity_request_handler(Self::handle_open_uncommitted_diff);
         client.add_entity_message_handler(Self::handle_update_diff_bases);
         client.add_entity_request_handler(Self::handle_get_permalink_to_line);
         client.add_entity_request_handler(Self::handle_blame_buffer);
+        client.add_entity_request_handler(Self::handle_git_blame);
         client.add_entity_message_handler(Self::handle_update_repository);
         client.add_entity_message_handler(Self::handle_remove_repository);
         client.add_entity_request_handler(Self::handle_git_clone);
@@ -444,10 +667,54 @@ impl GitStore {
     pub fn set_active_repo_for_path(&mut self, project_path: &ProjectPath, cx: &mut Context<Self>) {
         if let Some((repo, _)) = self.repository_and_path_for_project_path(project_path, cx) {
             let id = repo.read(cx).id;
-            if self.active_repo_id != Some(id) {
-                self.active_repo_id = Some(id);
-                cx.emit(GitStoreEvent::ActiveRepositoryChanged(Some(id)));
-            }
+            self.choose_active_repository(id, cx);
+        }
+    }
+
+    /// Makes the repository with the given work directory the active one, now if it's open, or
+    /// as soon as it's opened otherwise, such as when restoring a workspace.
+    pub fn set_preferred_active_repository(
+        &mut self,
+        work_directory_abs_path: Arc<Path>,
+        cx: &mut Context<Self>,
+    ) {
+        self.preferred_active_work_directory = Some(work_directory_abs_path);
+        self.update_active_repository(cx);
+    }
+
+    fn choose_active_repository(&mut self, id: RepositoryId, cx: &mut Context<Self>) {
+        let Some(repository) = self.repositories.get(&id) else {
+            return;
+        };
+        self.preferred_active_work_directory =
+            Some(repository.read(cx).work_directory_abs_path.clone());
+        self.set_active_repo_id(Some(id), cx);
+    }
+
+    /// Picks the active repository after repositories were added or removed: the preferred one
+    /// if it's open, otherwise the current one if it's still open, otherwise any.
+    fn update_active_repository(&mut self, cx: &mut Context<Self>) {
+        let preferred_id = self
+            .preferred_active_work_directory
+            .as_ref()
+            .and_then(|path| {
+                self.repositories
+                    .iter()
+                    .find(|(_, repository)| repository.read(cx).work_directory_abs_path == *path)
+                    .map(|(id, _)| *id)
+            });
+        let id = preferred_id
+            .or(self
+                .active_repo_id
+                .filter(|id| self.repositories.contains_key(id)))
+            .or_else(|| self.repositories.keys().min().copied());
+        self.set_active_repo_id(id, cx);
+    }
+
+    fn set_active_repo_id(&mut self, id: Option<RepositoryId>, cx: &mut Context<Self>) {
+        if self.active_repo_id != id {
+            self.active_repo_id = id;
+            cx.emit(GitStoreEvent::ActiveRepositoryChanged(id));
         }
     }
 
@@ -548,10 +815,12 @@ impl GitStore {
             }
         }
         self.shared_diffs.clear();
+        self.diff_streams.clear();
     }
 
     pub(crate) fn forget_shared_diffs_for(&mut self, peer_id: &proto::PeerId) {
         self.shared_diffs.remove(peer_id);
+        self.diff_streams.remove(peer_id);
     }
 
     pub fn active_repository(&self) -> Option<Entity<Repository>> {
@@ -1127,6 +1396,11 @@ impl GitStore {
                 self.repository_and_path_for_buffer_id(*buffer_id, cx)
                 && buffer_repo == repo
             {
+                if let RepositoryEvent::StatusesChanged(paths) = event
+                    && !paths.contains(&repo_path)
+                {
+                    continue;
+                }
                 diff.update(cx, |diff, cx| {
                     if let Some(conflict_set) = &diff.conflict_set {
                         let conflict_status_changed =
@@ -1158,6 +1432,28 @@ impl GitStore {
         cx.emit(GitStoreEvent::JobsUpdated)
     }
 
+    fn on_slow_job(&mut self, _: Entity<Repository>, event: &SlowJob, cx: &mut Context<Self>) {
+        cx.emit(GitStoreEvent::SlowJob(event.0.clone()))
+    }
+
+    fn on_index_locked(
+        &mut self,
+        repo: Entity<Repository>,
+        _: &IndexLocked,
+        cx: &mut Context<Self>,
+    ) {
+        cx.emit(GitStoreEvent::IndexLocked(repo.read(cx).id))
+    }
+
+    /// The jobs that are queued or running in every repository, so that slow git operations can
+    /// be diagnosed.
+    pub fn job_queue_status(&self, cx: &App) -> Vec<GitJobStatus> {
+        self.repositories
+            .values()
+            .flat_map(|repository| repository.read(cx).job_queue_status())
+            .collect()
+    }
+
     /// Update our list of repositories and schedule git scans in response to a notification from a worktree,
     fn update_repositories_from_worktree(
         &mut self,
@@ -1217,20 +1513,16 @@ impl GitStore {
                     .push(cx.subscribe(&repo, Self::on_repository_event));
                 self._subscriptions
                     .push(cx.subscribe(&repo, Self::on_jobs_updated));
+                self._subscriptions
+                    .push(cx.subscribe(&repo, Self::on_slow_job));
+                self._subscriptions
+                    .push(cx.subscribe(&repo, Self::on_index_locked));
                 self.repositories.insert(id, repo);
                 cx.emit(GitStoreEvent::RepositoryAdded(id));
-                self.active_repo_id.get_or_insert_with(|| {
-                    cx.emit(GitStoreEvent::ActiveRepositoryChanged(Some(id)));
-                    id
-                });
             }
         }
 
         for id in removed_ids {
-            if self.active_repo_id == Some(id) {
-                self.active_repo_id = None;
-                cx.emit(GitStoreEvent::ActiveRepositoryChanged(None));
-            }
             self.repositories.remove(&id);
             if let Some(updates_tx) = updates_tx.as_ref() {
                 updates_tx
@@ -1238,6 +1530,7 @@ impl GitStore {
                     .ok();
             }
         }
+        self.update_active_repository(cx);
     }
 
     fn on_buffer_store_event(
@@ -1368,6 +1661,90 @@ impl GitStore {
         &self.repositories
     }
 
+    /// Runs `run` against every repository concurrently, then emits
+    /// [`GitStoreEvent::BatchOperationCompleted`] with the outcome for each of them.
+    pub fn for_each_repository<T, F>(
+        &self,
+        operation: BatchOperation,
+        mut run: F,
+        cx: &mut Context<Self>,
+    ) -> Task<BatchOperationReport>
+    where
+        T: 'static,
+        F: FnMut(&mut Repository, &mut Context<Repository>) -> oneshot::Receiver<Result<T>>,
+    {
+        let mut repositories = self.repositories.iter().collect::<Vec<_>>();
+        repositories.sort_by_key(|(id, _)| **id);
+        let jobs = repositories
+            .into_iter()
+            .map(|(id, repository)| {
+                let job = repository.update(cx, |repository, cx| run(repository, cx));
+                async move {
+                    let result = match job.await {
+                        Ok(result) => result.map(drop),
+                        Err(canceled) => Err(anyhow!(canceled)),
+                    };
+                    (*id, result)
+                }
+            })
+            .collect::<Vec<_>>();
+
+        cx.spawn(async move |this, cx| {
+            let mut report = BatchOperationReport {
+                operation,
+                succeeded: Vec::new(),
+                failed: Vec::new(),
+            };
+            for (id, result) in future::join_all(jobs).await {
+                match result {
+                    Ok(()) => report.succeeded.push(id),
+                    Err(error) => report.failed.push((id, format!("{error:#}").into())),
+                }
+            }
+            this.update(cx, |_, cx| {
+                cx.emit(GitStoreEvent::BatchOperationCompleted(report.clone()))
+            })
+            .ok();
+            report
+        })
+    }
+
+    /// Fetches from every remote of every repository in the project.
+    pub fn fetch_all(
+        &self,
+        mut askpass: impl FnMut(RepositoryId, &mut App) -> AskPassDelegate,
+        cx: &mut Context<Self>,
+    ) -> Task<BatchOperationReport> {
+        self.for_each_repository(
+            BatchOperation::Fetch,
+            |repository, cx| {
+                let askpass = askpass(repository.id, cx);
+                repository.fetch(FetchOptions::All, askpass, None, cx)
+            },
+            cx,
+        )
+    }
+
+    /// Recomputes the status of every repository in the project, without waiting for a
+    /// file system event to trigger it.
+    pub fn status_refresh_all(&self, cx: &mut Context<Self>) -> Task<BatchOperationReport> {
+        self.for_each_repository(
+            BatchOperation::StatusRefresh,
+            |repository, cx| repository.refresh_status(cx),
+            cx,
+        )
+    }
+
+    /// Rescans the statuses of every repository in the project, for when they were changed by
+    /// something the file system watcher didn't see.
+    pub fn refresh_statuses(&self, cx: &mut Context<Self>) -> Task<BatchOperationReport> {
+        self.for_each_repository(
+            BatchOperation::RefreshStatuses,
+            |repository, cx| repository.refresh_statuses(cx),
+            cx,
+        )
+    }
+
     pub fn status_for_buffer_id(&self, buffer_id: BufferId, cx: &App) -> Option<FileStatus> {
         let (repo, path) = self.repository_and_path_for_buffer_id(buffer_id, cx)?;
         let status = repo.read(cx).snapshot.status_for_path(&path)?;
@@ -1503,7 +1880,11 @@ impl GitStore {
             });
             if is_new {
                 this._subscriptions
-                    .push(cx.subscribe(repo, Self::on_repository_event))
+                    .push(cx.subscribe(repo, Self::on_repository_event));
+                this._subscriptions
+                    .push(cx.subscribe(repo, Self::on_slow_job));
+                this._subscriptions
+                    .push(cx.subscribe(repo, Self::on_index_locked));
             }
 
             repo.update(cx, {
@@ -1511,10 +1892,7 @@ impl GitStore {
                 |repo, cx| repo.apply_remote_update(update, is_new, cx)
             })?;
 
-            this.active_repo_id.get_or_insert_with(|| {
-                cx.emit(GitStoreEvent::ActiveRepositoryChanged(Some(id)));
-                id
-            });
+            this.update_active_repository(cx);
 
             if let Some((client, project_id)) = this.downstream_client() {
                 update.project_id = project_id.to_proto();
@@ -1537,10 +1915,7 @@ impl GitStore {
                 update.project_id = project_id.to_proto();
                 client.send(update).log_err();
             }
-            if this.active_repo_id == Some(id) {
-                this.active_repo_id = None;
-                cx.emit(GitStoreEvent::ActiveRepositoryChanged(None));
-            }
+            this.update_active_repository(cx);
             cx.emit(GitStoreEvent::RepositoryRemoved(id));
         })
     }
@@ -1584,6 +1959,13 @@ impl GitStore {
         let fetch_options = FetchOptions::from_proto(envelope.payload.remote);
         let askpass_id = envelope.payload.askpass_id;
 
+        let progress = make_remote_progress_sender(
+            this.clone(),
+            envelope.payload.project_id,
+            repository_id,
+            askpass_id,
+            &mut cx,
+        );
         let askpass = make_remote_delegate(
             this,
             envelope.payload.project_id,
@@ -1594,7 +1976,7 @@ impl GitStore {
 
         let remote_output = repository_handle
             .update(&mut cx, |repository_handle, cx| {
-                repository_handle.fetch(fetch_options, askpass, cx)
+                repository_handle.fetch(fetch_options, askpass, Some(progress), cx)
             })?
             .await??;
 
@@ -1613,6 +1995,13 @@ impl GitStore {
         let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
 
         let askpass_id = envelope.payload.askpass_id;
+        let progress = make_remote_progress_sender(
+            this.clone(),
+            envelope.payload.project_id,
+            repository_id,
+            askpass_id,
+            &mut cx,
+        );
         let askpass = make_remote_delegate(
             this,
             envelope.payload.project_id,
@@ -1635,7 +2024,95 @@ impl GitStore {
 
         let remote_output = repository_handle
             .update(&mut cx, |repository_handle, cx| {
-                repository_handle.push(branch_name, remote_name, options, askpass, cx)
+                repository_handle.push(
+                    branch_name,
+                    remote_name,
+                    options,
+                    askpass,
+                    Some(progress),
+                    cx,
+                )
+            })?
+            .await??;
+        Ok(proto::RemoteMessageResponse {
+            stdout: remote_output.stdout,
+            stderr: remote_output.stderr,
+        })
+    }
+
+    async fn handle_get_tags(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitGetTags>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GitTagsResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        let tags = repository_handle
+            .update(&mut cx, |repository_handle, _| repository_handle.tags())?
+            .await??;
+        Ok(proto::GitTagsResponse {
+            tags: tags.iter().map(tag_to_proto).collect(),
+        })
+    }
+
+    async fn handle_create_tag(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitCreateTag>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.create_tag(
+                    envelope.payload.name,
+                    envelope.payload.sha,
+                    envelope.payload.message,
+                )
+            })?
+            .await??;
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_delete_tag(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitDeleteTag>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.delete_tag(envelope.payload.name)
+            })?
+            .await??;
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_push_tag(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitPushTag>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::RemoteMessageResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+        let askpass_id = envelope.payload.askpass_id;
+        let askpass = make_remote_delegate(
+            this,
+            envelope.payload.project_id,
+            repository_id,
+            askpass_id,
+            &mut cx,
+        );
+
+        let tag_name = envelope.payload.name.into();
+        let remote_name = envelope.payload.remote_name.into();
+        let remote_output = repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.push_tag(tag_name, remote_name, askpass)
             })?
             .await??;
         Ok(proto::RemoteMessageResponse {
@@ -1652,6 +2129,13 @@ impl GitStore {
         let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
         let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
         let askpass_id = envelope.payload.askpass_id;
+        let progress = make_remote_progress_sender(
+            this.clone(),
+            envelope.payload.project_id,
+            repository_id,
+            askpass_id,
+            &mut cx,
+        );
         let askpass = make_remote_delegate(
             this,
             envelope.payload.project_id,
@@ -1662,10 +2146,23 @@ impl GitStore {
 
         let branch_name = envelope.payload.branch_name.into();
         let remote_name = envelope.payload.remote_name.into();
+        let options = envelope.payload.options.unwrap_or_default();
+        let options = PullOptions {
+            rebase: options.rebase,
+            ff_only: options.ff_only,
+            strategy: options.strategy,
+        };
 
         let remote_message = repository_handle
             .update(&mut cx, |repository_handle, cx| {
-                repository_handle.pull(branch_name, remote_name, askpass, cx)
+                repository_handle.pull(
+                    branch_name,
+                    remote_name,
+                    options,
+                    askpass,
+                    Some(progress),
+                    cx,
+                )
             })?
             .await??;
 
@@ -1679,7 +2176,7 @@ impl GitStore {
         this: Entity<Self>,
         envelope: TypedEnvelope<proto::Stage>,
         mut cx: AsyncApp,
-    ) -> Result<proto::Ack> {
+    ) -> Result<proto::StagingResponse> {
         let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
         let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
 
@@ -1690,19 +2187,19 @@ impl GitStore {
             .map(|path| RepoPath::new(&path))
             .collect::<Result<Vec<_>>>()?;
 
-        repository_handle
+        let report = repository_handle
             .update(&mut cx, |repository_handle, cx| {
                 repository_handle.stage_entries(entries, cx)
             })?
             .await?;
-        Ok(proto::Ack {})
+        Ok(report.to_proto())
     }
 
     async fn handle_unstage(
         this: Entity<Self>,
         envelope: TypedEnvelope<proto::Unstage>,
         mut cx: AsyncApp,
-    ) -> Result<proto::Ack> {
+    ) -> Result<proto::StagingResponse> {
         let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
         let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
 
@@ -1713,13 +2210,13 @@ impl GitStore {
             .map(|path| RepoPath::new(&path))
             .collect::<Result<Vec<_>>>()?;
 
-        repository_handle
+        let report = repository_handle
             .update(&mut cx, |repository_handle, cx| {
                 repository_handle.unstage_entries(entries, cx)
             })?
             .await?;
 
-        Ok(proto::Ack {})
+        Ok(report.to_proto())
     }
 
     async fn handle_stash(
@@ -1782,84 +2279,209 @@ impl GitStore {
         Ok(proto::Ack {})
     }
 
-    async fn handle_stash_drop(
+    async fn handle_rebase_start(
         this: Entity<Self>,
-        envelope: TypedEnvelope<proto::StashDrop>,
+        envelope: TypedEnvelope<proto::GitRebaseStart>,
         mut cx: AsyncApp,
     ) -> Result<proto::Ack> {
         let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
         let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
-        let stash_index = envelope.payload.stash_index.map(|i| i as usize);
 
         repository_handle
             .update(&mut cx, |repository_handle, cx| {
-                repository_handle.stash_drop(stash_index, cx)
+                repository_handle.rebase_start(
+                    envelope.payload.onto,
+                    envelope.payload.interactive,
+                    cx,
+                )
             })?
-            .await??;
+            .await?;
 
         Ok(proto::Ack {})
     }
 
-    async fn handle_set_index_text(
+    async fn handle_rebase_continue(
         this: Entity<Self>,
-        envelope: TypedEnvelope<proto::SetIndexText>,
+        envelope: TypedEnvelope<proto::GitRebaseContinue>,
         mut cx: AsyncApp,
     ) -> Result<proto::Ack> {
         let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
         let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
-        let repo_path = RepoPath::from_proto(&envelope.payload.path)?;
 
         repository_handle
             .update(&mut cx, |repository_handle, cx| {
-                repository_handle.spawn_set_index_text_job(
-                    repo_path,
-                    envelope.payload.text,
-                    None,
-                    cx,
-                )
+                repository_handle.rebase_continue(cx)
             })?
-            .await??;
+            .await?;
+
         Ok(proto::Ack {})
     }
 
-    async fn handle_commit(
+    async fn handle_rebase_abort(
         this: Entity<Self>,
-        envelope: TypedEnvelope<proto::Commit>,
+        envelope: TypedEnvelope<proto::GitRebaseAbort>,
         mut cx: AsyncApp,
     ) -> Result<proto::Ack> {
         let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
         let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
 
-        let message = SharedString::from(envelope.payload.message);
-        let name = envelope.payload.name.map(SharedString::from);
-        let email = envelope.payload.email.map(SharedString::from);
-        let options = envelope.payload.options.unwrap_or_default();
-
         repository_handle
             .update(&mut cx, |repository_handle, cx| {
-                repository_handle.commit(
-                    message,
-                    name.zip(email),
-                    CommitOptions {
-                        amend: options.amend,
-                        signoff: options.signoff,
-                    },
-                    cx,
-                )
+                repository_handle.rebase_abort(cx)
             })?
-            .await??;
+            .await?;
+
         Ok(proto::Ack {})
     }
 
-    async fn handle_get_remotes(
+    async fn handle_merge(
         this: Entity<Self>,
-        envelope: TypedEnvelope<proto::GetRemotes>,
+        envelope: TypedEnvelope<proto::GitMerge>,
         mut cx: AsyncApp,
-    ) -> Result<proto::GetRemotesResponse> {
+    ) -> Result<proto::Ack> {
         let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
         let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+        let options = MergeOptions {
+            no_ff: envelope.payload.no_ff,
+            ff_only: envelope.payload.ff_only,
+            strategy: envelope.payload.strategy,
+        };
 
-        let branch_name = envelope.payload.branch_name;
+        repository_handle
+            .update(&mut cx, |repository_handle, cx| {
+                repository_handle.merge(envelope.payload.branch, options, cx)
+            })?
+            .await??;
+
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_merge_abort(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitMergeAbort>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        repository_handle
+            .update(&mut cx, |repository_handle, cx| {
+                repository_handle.merge_abort(cx)
+            })?
+            .await??;
+
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_stash_drop(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::StashDrop>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+        let stash_index = envelope.payload.stash_index.map(|i| i as usize);
+
+        repository_handle
+            .update(&mut cx, |repository_handle, cx| {
+                repository_handle.stash_drop(stash_index, cx)
+            })?
+            .await??;
+
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_set_index_text(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::SetIndexText>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+        let repo_path = RepoPath::from_proto(&envelope.payload.path)?;
+
+        repository_handle
+            .update(&mut cx, |repository_handle, cx| {
+                repository_handle.spawn_set_index_text_job(
+                    repo_path,
+                    envelope.payload.text,
+                    None,
+                    cx,
+                )
+            })?
+            .await??;
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_git_blame(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitBlame>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GitBlameResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+        let repo_path = RepoPath::from_proto(&envelope.payload.path)?;
+        let rows = envelope.payload.start_row..envelope.payload.end_row;
+
+        let lines = repository_handle
+            .update(&mut cx, |repository_handle, cx| {
+                repository_handle.blame(repo_path, rows, cx)
+            })?
+            .await?;
+        Ok(proto::GitBlameResponse {
+            lines: lines.iter().map(line_blame_to_proto).collect(),
+        })
+    }
+
+    async fn handle_commit(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::Commit>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        let message = SharedString::from(envelope.payload.message);
+        let name = envelope.payload.name.map(SharedString::from);
+        let email = envelope.payload.email.map(SharedString::from);
+        let options = envelope.payload.options.unwrap_or_default();
+        let askpass = make_remote_delegate(
+            this,
+            envelope.payload.project_id,
+            repository_id,
+            envelope.payload.askpass_id,
+            &mut cx,
+        );
+
+        repository_handle
+            .update(&mut cx, |repository_handle, cx| {
+                repository_handle.commit(
+                    message,
+                    name.zip(email),
+                    CommitOptions {
+                        amend: options.amend,
+                        signoff: options.signoff,
+                        sign: options.sign,
+                    },
+                    // The guest appends its trailers to the message.
+                    Vec::new(),
+                    askpass,
+                    cx,
+                )
+            })?
+            .await??;
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_get_remotes(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GetRemotes>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GetRemotesResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        let branch_name = envelope.payload.branch_name;
 
         let remotes = repository_handle
             .update(&mut cx, |repository_handle, _| {
@@ -1968,1197 +2590,2158 @@ impl GitStore {
         Ok(proto::Ack {})
     }
 
-    async fn handle_show(
+    async fn handle_delete_branch(
         this: Entity<Self>,
-        envelope: TypedEnvelope<proto::GitShow>,
+        envelope: TypedEnvelope<proto::GitDeleteBranch>,
         mut cx: AsyncApp,
-    ) -> Result<proto::GitCommitDetails> {
+    ) -> Result<proto::Ack> {
         let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
         let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+        let branch_name = envelope.payload.branch_name;
+        let force = envelope.payload.force;
 
-        let commit = repository_handle
+        repository_handle
             .update(&mut cx, |repository_handle, _| {
-                repository_handle.show(envelope.payload.commit)
+                repository_handle.delete_branch(branch_name, force)
             })?
             .await??;
-        Ok(proto::GitCommitDetails {
-            sha: commit.sha.into(),
-            message: commit.message.into(),
-            commit_timestamp: commit.commit_timestamp,
-            author_email: commit.author_email.into(),
-            author_name: commit.author_name.into(),
-        })
-    }
-
-    async fn handle_load_commit_diff(
-        this: Entity<Self>,
-        envelope: TypedEnvelope<proto::LoadCommitDiff>,
-        mut cx: AsyncApp,
-    ) -> Result<proto::LoadCommitDiffResponse> {
-        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
-        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
 
-        let commit_diff = repository_handle
-            .update(&mut cx, |repository_handle, _| {
-                repository_handle.load_commit_diff(envelope.payload.commit)
-            })?
-            .await??;
-        Ok(proto::LoadCommitDiffResponse {
-            files: commit_diff
-                .files
-                .into_iter()
-                .map(|file| proto::CommitFile {
-                    path: file.path.to_proto(),
-                    old_text: file.old_text,
-                    new_text: file.new_text,
-                })
-                .collect(),
-        })
+        Ok(proto::Ack {})
     }
 
-    async fn handle_reset(
+    async fn handle_set_upstream(
         this: Entity<Self>,
-        envelope: TypedEnvelope<proto::GitReset>,
+        envelope: TypedEnvelope<proto::GitSetUpstream>,
         mut cx: AsyncApp,
     ) -> Result<proto::Ack> {
         let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
         let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
-
-        let mode = match envelope.payload.mode() {
-            git_reset::ResetMode::Soft => ResetMode::Soft,
-            git_reset::ResetMode::Mixed => ResetMode::Mixed,
-        };
+        let branch_name = envelope.payload.branch_name;
+        let upstream = envelope.payload.upstream;
 
         repository_handle
-            .update(&mut cx, |repository_handle, cx| {
-                repository_handle.reset(envelope.payload.commit, mode, cx)
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.set_upstream(branch_name, upstream)
             })?
             .await??;
+
         Ok(proto::Ack {})
     }
 
-    async fn handle_checkout_files(
+    async fn handle_ignore_paths(
         this: Entity<Self>,
-        envelope: TypedEnvelope<proto::GitCheckoutFiles>,
+        envelope: TypedEnvelope<proto::GitIgnorePaths>,
         mut cx: AsyncApp,
     ) -> Result<proto::Ack> {
         let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
         let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+        let ignore_file = match envelope.payload.ignore_file() {
+            git_ignore_paths::IgnoreFile::Gitignore => IgnoreFile::Gitignore,
+            git_ignore_paths::IgnoreFile::InfoExclude => IgnoreFile::InfoExclude,
+        };
         let paths = envelope
             .payload
             .paths
             .iter()
-            .map(|s| RepoPath::from_proto(s))
+            .map(|path| RepoPath::from_proto(path))
             .collect::<Result<Vec<_>>>()?;
 
         repository_handle
             .update(&mut cx, |repository_handle, cx| {
-                repository_handle.checkout_files(&envelope.payload.commit, paths, cx)
+                repository_handle.ignore_paths(paths, ignore_file, cx)
             })?
             .await??;
+
         Ok(proto::Ack {})
     }
 
-    async fn handle_open_commit_message_buffer(
+    async fn handle_is_ignored(
         this: Entity<Self>,
-        envelope: TypedEnvelope<proto::OpenCommitMessageBuffer>,
+        envelope: TypedEnvelope<proto::GitIsIgnored>,
         mut cx: AsyncApp,
-    ) -> Result<proto::OpenBufferResponse> {
+    ) -> Result<proto::GitIsIgnoredResponse> {
         let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
-        let repository = Self::repository_for_request(&this, repository_id, &mut cx)?;
-        let buffer = repository
-            .update(&mut cx, |repository, cx| {
-                repository.open_commit_buffer(None, this.read(cx).buffer_store.clone(), cx)
-            })?
-            .await?;
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+        let path = RepoPath::from_proto(&envelope.payload.path)?;
 
-        let buffer_id = buffer.read_with(&cx, |buffer, _| buffer.remote_id())?;
-        this.update(&mut cx, |this, cx| {
-            this.buffer_store.update(cx, |buffer_store, cx| {
-                buffer_store
-                    .create_buffer_for_peer(
-                        &buffer,
-                        envelope.original_sender_id.unwrap_or(envelope.sender_id),
-                        cx,
-                    )
-                    .detach_and_log_err(cx);
-            })
-        })?;
+        let ignored = repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.is_ignored(path)
+            })?
+            .await??;
 
-        Ok(proto::OpenBufferResponse {
-            buffer_id: buffer_id.to_proto(),
-        })
+        Ok(proto::GitIsIgnoredResponse { ignored })
     }
 
-    async fn handle_askpass(
+    async fn handle_clean(
         this: Entity<Self>,
-        envelope: TypedEnvelope<proto::AskPassRequest>,
+        envelope: TypedEnvelope<proto::GitClean>,
         mut cx: AsyncApp,
-    ) -> Result<proto::AskPassResponse> {
+    ) -> Result<proto::GitCleanResponse> {
         let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
-        let repository = Self::repository_for_request(&this, repository_id, &mut cx)?;
-
-        let delegates = cx.update(|cx| repository.read(cx).askpass_delegates.clone())?;
-        let Some(mut askpass) = delegates.lock().remove(&envelope.payload.askpass_id) else {
-            debug_panic!("no askpass found");
-            anyhow::bail!("no askpass found");
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+        let paths = envelope
+            .payload
+            .paths
+            .iter()
+            .map(|path| RepoPath::from_proto(path))
+            .collect::<Result<Vec<_>>>()?;
+        let options = CleanOptions {
+            dry_run: envelope.payload.dry_run,
+            directories: envelope.payload.directories,
+            ignored: envelope.payload.ignored,
         };
 
-        let response = askpass
-            .ask_password(envelope.payload.prompt)
-            .await
-            .ok_or_else(|| anyhow::anyhow!("askpass cancelled"))?;
-
-        delegates
-            .lock()
-            .insert(envelope.payload.askpass_id, askpass);
+        let cleaned_paths = repository_handle
+            .update(&mut cx, |repository_handle, cx| {
+                repository_handle.clean(paths, options, cx)
+            })?
+            .await??;
 
-        // In fact, we don't quite know what we're doing here, as we're sending askpass password unencrypted, but..
-        Ok(proto::AskPassResponse {
-            response: response.decrypt(IKnowWhatIAmDoingAndIHaveReadTheDocs)?,
+        Ok(proto::GitCleanResponse {
+            paths: cleaned_paths.iter().map(|path| path.to_proto()).collect(),
         })
     }
 
-    async fn handle_check_for_pushed_commits(
+    async fn handle_resolve_conflict(
         this: Entity<Self>,
-        envelope: TypedEnvelope<proto::CheckForPushedCommits>,
+        envelope: TypedEnvelope<proto::GitResolveConflict>,
         mut cx: AsyncApp,
-    ) -> Result<proto::CheckForPushedCommitsResponse> {
+    ) -> Result<proto::Ack> {
         let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
         let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+        let path = RepoPath::from_proto(&envelope.payload.path)?;
+        let side = match envelope.payload.side() {
+            git_resolve_conflict::ConflictSide::Ours => ConflictSide::Ours,
+            git_resolve_conflict::ConflictSide::Theirs => ConflictSide::Theirs,
+        };
 
-        let branches = repository_handle
-            .update(&mut cx, |repository_handle, _| {
-                repository_handle.check_for_pushed_commits()
+        repository_handle
+            .update(&mut cx, |repository_handle, cx| {
+                repository_handle.resolve_conflict(path, side, cx)
             })?
             .await??;
-        Ok(proto::CheckForPushedCommitsResponse {
-            pushed_to: branches
-                .into_iter()
-                .map(|commit| commit.to_string())
-                .collect(),
-        })
+        Ok(proto::Ack {})
     }
 
-    async fn handle_git_diff(
+    async fn handle_lfs_pull(
         this: Entity<Self>,
-        envelope: TypedEnvelope<proto::GitDiff>,
+        envelope: TypedEnvelope<proto::GitLfsPull>,
         mut cx: AsyncApp,
-    ) -> Result<proto::GitDiffResponse> {
+    ) -> Result<proto::RemoteMessageResponse> {
         let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
         let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
-        let diff_type = match envelope.payload.diff_type() {
-            proto::git_diff::DiffType::HeadToIndex => DiffType::HeadToIndex,
-            proto::git_diff::DiffType::HeadToWorktree => DiffType::HeadToWorktree,
-        };
+        let askpass_id = envelope.payload.askpass_id;
+        let askpass = make_remote_delegate(
+            this,
+            envelope.payload.project_id,
+            repository_id,
+            askpass_id,
+            &mut cx,
+        );
 
-        let mut diff = repository_handle
+        let remote_output = repository_handle
             .update(&mut cx, |repository_handle, cx| {
-                repository_handle.diff(diff_type, cx)
+                repository_handle.lfs_pull(askpass, cx)
             })?
             .await??;
-        const ONE_MB: usize = 1_000_000;
-        if diff.len() > ONE_MB {
-            diff = diff.chars().take(ONE_MB).collect()
-        }
-
-        Ok(proto::GitDiffResponse { diff })
+        Ok(proto::RemoteMessageResponse {
+            stdout: remote_output.stdout,
+            stderr: remote_output.stderr,
+        })
     }
 
-    async fn handle_open_unstaged_diff(
+    async fn handle_lfs_lock(
         this: Entity<Self>,
-        request: TypedEnvelope<proto::OpenUnstagedDiff>,
+        envelope: TypedEnvelope<proto::GitLfsLock>,
         mut cx: AsyncApp,
-    ) -> Result<proto::OpenUnstagedDiffResponse> {
-        let buffer_id = BufferId::new(request.payload.buffer_id)?;
-        let diff = this
-            .update(&mut cx, |this, cx| {
-                let buffer = this.buffer_store.read(cx).get(buffer_id)?;
-                Some(this.open_unstaged_diff(buffer, cx))
+    ) -> Result<proto::Ack> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+        let askpass_id = envelope.payload.askpass_id;
+        let askpass = make_remote_delegate(
+            this,
+            envelope.payload.project_id,
+            repository_id,
+            askpass_id,
+            &mut cx,
+        );
+        let path = RepoPath::from_proto(&envelope.payload.path)?;
+
+        repository_handle
+            .update(&mut cx, |repository_handle, cx| {
+                repository_handle.lfs_lock(path, askpass, cx)
             })?
-            .context("missing buffer")?
-            .await?;
-        this.update(&mut cx, |this, _| {
-            let shared_diffs = this
-                .shared_diffs
-                .entry(request.original_sender_id.unwrap_or(request.sender_id))
-                .or_default();
-            shared_diffs.entry(buffer_id).or_default().unstaged = Some(diff.clone());
-        })?;
-        let staged_text = diff.read_with(&cx, |diff, _| diff.base_text_string())?;
-        Ok(proto::OpenUnstagedDiffResponse { staged_text })
+            .await??;
+        Ok(proto::Ack {})
     }
 
-    async fn handle_open_uncommitted_diff(
+    async fn handle_lfs_unlock(
         this: Entity<Self>,
-        request: TypedEnvelope<proto::OpenUncommittedDiff>,
+        envelope: TypedEnvelope<proto::GitLfsUnlock>,
         mut cx: AsyncApp,
-    ) -> Result<proto::OpenUncommittedDiffResponse> {
-        let buffer_id = BufferId::new(request.payload.buffer_id)?;
-        let diff = this
-            .update(&mut cx, |this, cx| {
-                let buffer = this.buffer_store.read(cx).get(buffer_id)?;
-                Some(this.open_uncommitted_diff(buffer, cx))
-            })?
-            .context("missing buffer")?
-            .await?;
-        this.update(&mut cx, |this, _| {
-            let shared_diffs = this
-                .shared_diffs
-                .entry(request.original_sender_id.unwrap_or(request.sender_id))
-                .or_default();
-            shared_diffs.entry(buffer_id).or_default().uncommitted = Some(diff.clone());
-        })?;
-        diff.read_with(&cx, |diff, cx| {
-            use proto::open_uncommitted_diff_response::Mode;
+    ) -> Result<proto::Ack> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+        let askpass_id = envelope.payload.askpass_id;
+        let askpass = make_remote_delegate(
+            this,
+            envelope.payload.project_id,
+            repository_id,
+            askpass_id,
+            &mut cx,
+        );
+        let path = RepoPath::from_proto(&envelope.payload.path)?;
 
-            let unstaged_diff = diff.secondary_diff();
-            let index_snapshot = unstaged_diff.and_then(|diff| {
-                let diff = diff.read(cx);
-                diff.base_text_exists().then(|| diff.base_text())
-            });
+        repository_handle
+            .update(&mut cx, |repository_handle, cx| {
+                repository_handle.lfs_unlock(path, envelope.payload.force, askpass, cx)
+            })?
+            .await??;
+        Ok(proto::Ack {})
+    }
 
-            let mode;
-            let staged_text;
-            let committed_text;
-            if diff.base_text_exists() {
-                let committed_snapshot = diff.base_text();
-                committed_text = Some(committed_snapshot.text());
-                if let Some(index_text) = index_snapshot {
-                    if index_text.remote_id() == committed_snapshot.remote_id() {
-                        mode = Mode::IndexMatchesHead;
-                        staged_text = None;
-                    } else {
-                        mode = Mode::IndexAndHead;
-                        staged_text = Some(index_text.text());
-                    }
-                } else {
-                    mode = Mode::IndexAndHead;
-                    staged_text = None;
-                }
-            } else {
-                mode = Mode::IndexAndHead;
-                committed_text = None;
-                staged_text = index_snapshot.as_ref().map(|buffer| buffer.text());
-            }
+    async fn handle_show(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitShow>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GitCommitDetails> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
 
-            proto::OpenUncommittedDiffResponse {
-                committed_text,
-                staged_text,
-                mode: mode.into(),
-            }
+        let commit = repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.show(envelope.payload.commit)
+            })?
+            .await??;
+        Ok(proto::GitCommitDetails {
+            sha: commit.sha.into(),
+            message: commit.message.into(),
+            commit_timestamp: commit.commit_timestamp,
+            author_email: commit.author_email.into(),
+            author_name: commit.author_name.into(),
         })
     }
 
-    async fn handle_update_diff_bases(
+    async fn handle_log(
         this: Entity<Self>,
-        request: TypedEnvelope<proto::UpdateDiffBases>,
+        envelope: TypedEnvelope<proto::GitLog>,
         mut cx: AsyncApp,
-    ) -> Result<()> {
-        let buffer_id = BufferId::new(request.payload.buffer_id)?;
-        this.update(&mut cx, |this, cx| {
-            if let Some(diff_state) = this.diffs.get_mut(&buffer_id)
-                && let Some(buffer) = this.buffer_store.read(cx).get(buffer_id)
-            {
-                let buffer = buffer.read(cx).text_snapshot();
-                diff_state.update(cx, |diff_state, cx| {
-                    diff_state.handle_base_texts_updated(buffer, request.payload, cx);
+    ) -> Result<proto::GitLogResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+        let options = LogOptions {
+            limit: envelope.payload.limit as usize,
+            skip: envelope.payload.skip as usize,
+            path: envelope
+                .payload
+                .path
+                .as_deref()
+                .map(RepoPath::from_proto)
+                .transpose()?,
+            author: envelope.payload.author,
+        };
+
+        let commits = repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.log(options)
+            })?
+            .await??;
+        Ok(proto::GitLogResponse {
+            commits: commits
+                .into_iter()
+                .map(|commit| proto::GitCommitDetails {
+                    sha: commit.sha.into(),
+                    message: commit.message.into(),
+                    commit_timestamp: commit.commit_timestamp,
+                    author_email: commit.author_email.into(),
+                    author_name: commit.author_name.into(),
                 })
-            }
+                .collect(),
         })
     }
 
-    async fn handle_blame_buffer(
+    async fn handle_file_history(
         this: Entity<Self>,
-        envelope: TypedEnvelope<proto::BlameBuffer>,
+        envelope: TypedEnvelope<proto::GitFileHistory>,
         mut cx: AsyncApp,
-    ) -> Result<proto::BlameBufferResponse> {
-        let buffer_id = BufferId::new(envelope.payload.buffer_id)?;
-        let version = deserialize_version(&envelope.payload.version);
-        let buffer = this.read_with(&cx, |this, cx| {
-            this.buffer_store.read(cx).get_existing(buffer_id)
-        })??;
-        buffer
-            .update(&mut cx, |buffer, _| {
-                buffer.wait_for_version(version.clone())
-            })?
-            .await?;
-        let blame = this
-            .update(&mut cx, |this, cx| {
-                this.blame_buffer(&buffer, Some(version), cx)
+    ) -> Result<proto::GitFileHistoryResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+        let path = RepoPath::from_proto(&envelope.payload.path)?;
+
+        let entries = repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.file_history(path, envelope.payload.follow_renames)
             })?
-            .await?;
-        Ok(serialize_blame_buffer_response(blame))
+            .await??;
+        Ok(proto::GitFileHistoryResponse {
+            entries: entries
+                .iter()
+                .map(|entry| proto::GitFileHistoryEntry {
+                    commit: Some(commit_details_to_proto(&entry.commit)),
+                    path: entry.path.to_proto(),
+                })
+                .collect(),
+        })
     }
 
-    async fn handle_get_permalink_to_line(
+    async fn handle_commit_graph(
         this: Entity<Self>,
-        envelope: TypedEnvelope<proto::GetPermalinkToLine>,
+        envelope: TypedEnvelope<proto::GitCommitGraph>,
         mut cx: AsyncApp,
-    ) -> Result<proto::GetPermalinkToLineResponse> {
-        let buffer_id = BufferId::new(envelope.payload.buffer_id)?;
-        // let version = deserialize_version(&envelope.payload.version);
-        let selection = {
-            let proto_selection = envelope
-                .payload
-                .selection
-                .context("no selection to get permalink for defined")?;
-            proto_selection.start as u32..proto_selection.end as u32
+    ) -> Result<proto::GitCommitGraphResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+        let options = CommitGraphOptions {
+            range: envelope.payload.range,
+            limit: envelope.payload.limit as usize,
+            skip: envelope.payload.skip as usize,
         };
-        let buffer = this.read_with(&cx, |this, cx| {
-            this.buffer_store.read(cx).get_existing(buffer_id)
-        })??;
-        let permalink = this
-            .update(&mut cx, |this, cx| {
-                this.get_permalink_to_line(&buffer, selection, cx)
+
+        let graph = repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.commit_graph(options)
             })?
-            .await?;
-        Ok(proto::GetPermalinkToLineResponse {
-            permalink: permalink.to_string(),
+            .await??;
+        Ok(proto::GitCommitGraphResponse {
+            commits: graph
+                .commits
+                .into_iter()
+                .map(|commit| proto::GitCommitGraphNode {
+                    sha: commit.sha.to_string(),
+                    parents: commit
+                        .parents
+                        .iter()
+                        .map(|parent| parent.to_string())
+                        .collect(),
+                    refs: commit.refs.into_iter().map(String::from).collect(),
+                    commit_timestamp: commit.commit_timestamp,
+                    author_name: commit.author_name.into(),
+                    subject: commit.subject.into(),
+                })
+                .collect(),
+            has_more: graph.has_more,
         })
     }
 
-    fn repository_for_request(
-        this: &Entity<Self>,
-        id: RepositoryId,
-        cx: &mut AsyncApp,
-    ) -> Result<Entity<Repository>> {
-        this.read_with(cx, |this, _| {
-            this.repositories
-                .get(&id)
-                .context("missing repository handle")
-                .cloned()
-        })?
+    async fn handle_permalink(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitPermalink>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GitUrlResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+        let path = RepoPath::from_proto(&envelope.payload.path)?;
+        let selection = envelope
+            .payload
+            .selection
+            .map(|selection| selection.start as u32..selection.end as u32);
+
+        let url = repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.permalink(path, selection, envelope.payload.rev)
+            })?
+            .await??;
+        Ok(proto::GitUrlResponse {
+            url: url.to_string(),
+        })
     }
 
-    pub fn repo_snapshots(&self, cx: &App) -> HashMap<RepositoryId, RepositorySnapshot> {
-        self.repositories
-            .iter()
-            .map(|(id, repo)| (*id, repo.read(cx).snapshot.clone()))
-            .collect()
+    async fn handle_open_pull_request_url(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitOpenPullRequestUrl>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GitUrlResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        let url = repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.open_pull_request_url(envelope.payload.branch)
+            })?
+            .await??;
+        Ok(proto::GitUrlResponse {
+            url: url.to_string(),
+        })
     }
 
-    fn process_updated_entries(
-        &self,
-        worktree: &Entity<Worktree>,
-        updated_entries: &[(Arc<RelPath>, ProjectEntryId, PathChange)],
-        cx: &mut App,
-    ) -> Task<HashMap<Entity<Repository>, Vec<RepoPath>>> {
-        let path_style = worktree.read(cx).path_style();
-        let mut repo_paths = self
-            .repositories
-            .values()
-            .map(|repo| (repo.read(cx).work_directory_abs_path.clone(), repo.clone()))
-            .collect::<Vec<_>>();
-        let mut entries: Vec<_> = updated_entries
-            .iter()
-            .map(|(path, _, _)| path.clone())
-            .collect();
-        entries.sort();
-        let worktree = worktree.read(cx);
+    async fn handle_load_commit_diff(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::LoadCommitDiff>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::LoadCommitDiffResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
 
-        let entries = entries
-            .into_iter()
-            .map(|path| worktree.absolutize(&path))
-            .collect::<Arc<[_]>>();
+        let commit_diff = repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.load_commit_diff(envelope.payload.commit)
+            })?
+            .await??;
+        Ok(proto::LoadCommitDiffResponse {
+            files: commit_diff
+                .files
+                .into_iter()
+                .map(|file| proto::CommitFile {
+                    path: file.path.to_proto(),
+                    old_text: file.old_text,
+                    new_text: file.new_text,
+                })
+                .collect(),
+        })
+    }
 
-        let executor = cx.background_executor().clone();
-        cx.background_executor().spawn(async move {
-            repo_paths.sort_by(|lhs, rhs| lhs.0.cmp(&rhs.0));
-            let mut paths_by_git_repo = HashMap::<_, Vec<_>>::default();
-            let mut tasks = FuturesOrdered::new();
-            for (repo_path, repo) in repo_paths.into_iter().rev() {
-                let entries = entries.clone();
-                let task = executor.spawn(async move {
-                    // Find all repository paths that belong to this repo
-                    let mut ix = entries.partition_point(|path| path < &*repo_path);
-                    if ix == entries.len() {
-                        return None;
-                    };
+    async fn handle_reset(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitReset>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
 
-                    let mut paths = Vec::new();
-                    // All paths prefixed by a given repo will constitute a continuous range.
-                    while let Some(path) = entries.get(ix)
-                        && let Some(repo_path) = RepositorySnapshot::abs_path_to_repo_path_inner(
-                            &repo_path, path, path_style,
-                        )
-                    {
-                        paths.push((repo_path, ix));
-                        ix += 1;
-                    }
-                    if paths.is_empty() {
-                        None
-                    } else {
-                        Some((repo, paths))
-                    }
-                });
-                tasks.push_back(task);
-            }
+        let mode = match envelope.payload.mode() {
+            git_reset::ResetMode::Soft => ResetMode::Soft,
+            git_reset::ResetMode::Mixed => ResetMode::Mixed,
+            git_reset::ResetMode::Hard => ResetMode::Hard,
+        };
 
-            // Now, let's filter out the "duplicate" entries that were processed by multiple distinct repos.
-            let mut path_was_used = vec![false; entries.len()];
-            let tasks = tasks.collect::<Vec<_>>().await;
-            // Process tasks from the back: iterating backwards allows us to see more-specific paths first.
-            // We always want to assign a path to it's innermost repository.
-            for t in tasks {
-                let Some((repo, paths)) = t else {
-                    continue;
-                };
-                let entry = paths_by_git_repo.entry(repo).or_default();
-                for (repo_path, ix) in paths {
-                    if path_was_used[ix] {
-                        continue;
-                    }
-                    path_was_used[ix] = true;
-                    entry.push(repo_path);
-                }
-            }
+        repository_handle
+            .update(&mut cx, |repository_handle, cx| {
+                repository_handle.reset(envelope.payload.commit, mode, cx)
+            })?
+            .await??;
+        Ok(proto::Ack {})
+    }
 
-            paths_by_git_repo
+    async fn handle_paths_discarded_by_hard_reset(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitPathsDiscardedByHardReset>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GitPathsDiscardedByHardResetResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+
+        let paths = repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.paths_discarded_by_hard_reset(envelope.payload.commit)
+            })?
+            .await??;
+        Ok(proto::GitPathsDiscardedByHardResetResponse {
+            paths: paths.iter().map(|path| path.to_proto()).collect(),
         })
     }
-}
 
-impl BufferGitState {
-    fn new(_git_store: WeakEntity<GitStore>) -> Self {
-        Self {
-            unstaged_diff: Default::default(),
-            uncommitted_diff: Default::default(),
-            recalculate_diff_task: Default::default(),
-            language: Default::default(),
-            language_registry: Default::default(),
-            recalculating_tx: postage::watch::channel_with(false).0,
-            hunk_staging_operation_count: 0,
-            hunk_staging_operation_count_as_of_write: 0,
-            head_text: Default::default(),
-            index_text: Default::default(),
-            head_changed: Default::default(),
-            index_changed: Default::default(),
-            language_changed: Default::default(),
-            conflict_updated_futures: Default::default(),
-            conflict_set: Default::default(),
-            reparse_conflict_markers_task: Default::default(),
-        }
-    }
+    async fn handle_checkout_files(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitCheckoutFiles>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+        let paths = envelope
+            .payload
+            .paths
+            .iter()
+            .map(|s| RepoPath::from_proto(s))
+            .collect::<Result<Vec<_>>>()?;
 
-    fn buffer_language_changed(&mut self, buffer: Entity<Buffer>, cx: &mut Context<Self>) {
-        self.language = buffer.read(cx).language().cloned();
-        self.language_changed = true;
-        let _ = self.recalculate_diffs(buffer.read(cx).text_snapshot(), cx);
+        repository_handle
+            .update(&mut cx, |repository_handle, cx| {
+                repository_handle.checkout_files(&envelope.payload.commit, paths, cx)
+            })?
+            .await??;
+        Ok(proto::Ack {})
     }
 
-    fn reparse_conflict_markers(
-        &mut self,
-        buffer: text::BufferSnapshot,
-        cx: &mut Context<Self>,
-    ) -> oneshot::Receiver<()> {
-        let (tx, rx) = oneshot::channel();
+    async fn handle_open_commit_message_buffer(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::OpenCommitMessageBuffer>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::OpenBufferResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository = Self::repository_for_request(&this, repository_id, &mut cx)?;
+        let buffer = repository
+            .update(&mut cx, |repository, cx| {
+                repository.open_commit_buffer(None, this.read(cx).buffer_store.clone(), cx)
+            })?
+            .await?;
 
-        let Some(conflict_set) = self
-            .conflict_set
-            .as_ref()
-            .and_then(|conflict_set| conflict_set.upgrade())
-        else {
-            return rx;
-        };
+        let buffer_id = buffer.read_with(&cx, |buffer, _| buffer.remote_id())?;
+        this.update(&mut cx, |this, cx| {
+            this.buffer_store.update(cx, |buffer_store, cx| {
+                buffer_store
+                    .create_buffer_for_peer(
+                        &buffer,
+                        envelope.original_sender_id.unwrap_or(envelope.sender_id),
+                        cx,
+                    )
+                    .detach_and_log_err(cx);
+            })
+        })?;
 
-        let old_snapshot = conflict_set.read_with(cx, |conflict_set, _| {
-            if conflict_set.has_conflict {
-                Some(conflict_set.snapshot())
-            } else {
-                None
-            }
-        });
+        Ok(proto::OpenBufferResponse {
+            buffer_id: buffer_id.to_proto(),
+        })
+    }
 
-        if let Some(old_snapshot) = old_snapshot {
-            self.conflict_updated_futures.push(tx);
-            self.reparse_conflict_markers_task = Some(cx.spawn(async move |this, cx| {
-                let (snapshot, changed_range) = cx
-                    .background_spawn(async move {
-                        let new_snapshot = ConflictSet::parse(&buffer);
-                        let changed_range = old_snapshot.compare(&new_snapshot, &buffer);
-                        (new_snapshot, changed_range)
-                    })
-                    .await;
-                this.update(cx, |this, cx| {
-                    if let Some(conflict_set) = &this.conflict_set {
-                        conflict_set
-                            .update(cx, |conflict_set, cx| {
-                                conflict_set.set_snapshot(snapshot, changed_range, cx);
-                            })
-                            .ok();
-                    }
-                    let futures = std::mem::take(&mut this.conflict_updated_futures);
-                    for tx in futures {
-                        tx.send(()).ok();
-                    }
+    async fn handle_remote_progress(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::RemoteProgress>,
+        mut cx: AsyncApp,
+    ) -> Result<()> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository = Self::repository_for_request(&this, repository_id, &mut cx)?;
+        let progress_tx = repository.read_with(&cx, |repository, _| {
+            repository
+                .remote_progress_senders
+                .lock()
+                .get(&envelope.payload.askpass_id)
+                .cloned()
+        })?;
+        // Progress that arrives after the operation finished is dropped.
+        if let Some(progress_tx) = progress_tx {
+            progress_tx
+                .unbounded_send(RemoteProgress {
+                    phase: envelope.payload.phase,
+                    percent: envelope
+                        .payload
+                        .percent
+                        .and_then(|percent| u8::try_from(percent).ok()),
+                    completed: envelope.payload.completed,
+                    total: envelope.payload.total,
                 })
-            }))
+                .ok();
         }
-
-        rx
+        Ok(())
     }
 
-    fn unstaged_diff(&self) -> Option<Entity<BufferDiff>> {
-        self.unstaged_diff.as_ref().and_then(|set| set.upgrade())
-    }
+    async fn handle_askpass(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::AskPassRequest>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::AskPassResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository = Self::repository_for_request(&this, repository_id, &mut cx)?;
 
-    fn uncommitted_diff(&self) -> Option<Entity<BufferDiff>> {
-        self.uncommitted_diff.as_ref().and_then(|set| set.upgrade())
+        let delegates = cx.update(|cx| repository.read(cx).askpass_delegates.clone())?;
+        let Some(mut askpass) = delegates.lock().remove(&envelope.payload.askpass_id) else {
+            debug_panic!("no askpass found");
+            anyhow::bail!("no askpass found");
+        };
+
+        let response = askpass
+            .ask_password(envelope.payload.prompt)
+            .await
+            .ok_or_else(|| anyhow::anyhow!("askpass cancelled"))?;
+
+        delegates
+            .lock()
+            .insert(envelope.payload.askpass_id, askpass);
+
+        // In fact, we don't quite know what we're doing here, as we're sending askpass password unencrypted, but..
+        Ok(proto::AskPassResponse {
+            response: response.decrypt(IKnowWhatIAmDoingAndIHaveReadTheDocs)?,
+        })
     }
 
-    fn handle_base_texts_updated(
-        &mut self,
-        buffer: text::BufferSnapshot,
-        message: proto::UpdateDiffBases,
-        cx: &mut Context<Self>,
-    ) {
-        use proto::update_diff_bases::Mode;
+    async fn handle_check_for_pushed_commits(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::CheckForPushedCommits>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::CheckForPushedCommitsResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
 
-        let Some(mode) = Mode::from_i32(message.mode) else {
-            return;
-        };
+        let branches = repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.check_for_pushed_commits()
+            })?
+            .await??;
+        Ok(proto::CheckForPushedCommitsResponse {
+            pushed_to: branches.iter().map(pushed_branch_to_proto).collect(),
+        })
+    }
 
-        let diff_bases_change = match mode {
-            Mode::HeadOnly => DiffBasesChange::SetHead(message.committed_text),
-            Mode::IndexOnly => DiffBasesChange::SetIndex(message.staged_text),
-            Mode::IndexMatchesHead => DiffBasesChange::SetBoth(message.committed_text),
-            Mode::IndexAndHead => DiffBasesChange::SetEach {
-                index: message.staged_text,
-                head: message.committed_text,
+    async fn handle_git_diff(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitDiff>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GitDiffResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+        let peer_id = envelope.original_sender_id.unwrap_or(envelope.sender_id);
+        let diff_type = match envelope.payload.diff_type() {
+            proto::git_diff::DiffType::HeadToIndex => DiffType::HeadToIndex,
+            proto::git_diff::DiffType::HeadToWorktree => DiffType::HeadToWorktree,
+            proto::git_diff::DiffType::Commits => DiffType::Commits {
+                base: envelope.payload.base.context("missing base revision")?,
+                head: envelope.payload.head.context("missing head revision")?,
             },
         };
 
-        self.diff_bases_changed(buffer, Some(diff_bases_change), cx);
-    }
+        let diff = repository_handle
+            .update(&mut cx, |repository_handle, cx| {
+                repository_handle.diff(diff_type, cx)
+            })?
+            .await??;
 
-    pub fn wait_for_recalculation(&mut self) -> Option<impl Future<Output = ()> + use<>> {
-        if *self.recalculating_tx.borrow() {
-            let mut rx = self.recalculating_tx.subscribe();
-            Some(async move {
-                loop {
-                    let is_recalculating = rx.recv().await;
-                    if is_recalculating != Some(true) {
-                        break;
-                    }
-                }
-            })
-        } else {
-            None
-        }
+        this.update(&mut cx, |this, cx| this.diff_response(peer_id, diff, cx))
     }
 
-    fn diff_bases_changed(
-        &mut self,
-        buffer: text::BufferSnapshot,
-        diff_bases_change: Option<DiffBasesChange>,
-        cx: &mut Context<Self>,
-    ) {
-        match diff_bases_change {
-            Some(DiffBasesChange::SetIndex(index)) => {
-                self.index_text = index.map(|mut index| {
-                    text::LineEnding::normalize(&mut index);
-                    Arc::new(index)
-                });
-                self.index_changed = true;
-            }
-            Some(DiffBasesChange::SetHead(head)) => {
-                self.head_text = head.map(|mut head| {
-                    text::LineEnding::normalize(&mut head);
-                    Arc::new(head)
-                });
-                self.head_changed = true;
-            }
-            Some(DiffBasesChange::SetBoth(text)) => {
-                let text = text.map(|mut text| {
-                    text::LineEnding::normalize(&mut text);
-                    Arc::new(text)
-                });
-                self.head_text = text.clone();
-                self.index_text = text;
-                self.head_changed = true;
-                self.index_changed = true;
-            }
-            Some(DiffBasesChange::SetEach { index, head }) => {
-                self.index_text = index.map(|mut index| {
-                    text::LineEnding::normalize(&mut index);
-                    Arc::new(index)
-                });
-                self.index_changed = true;
-                self.head_text = head.map(|mut head| {
-                    text::LineEnding::normalize(&mut head);
-                    Arc::new(head)
-                });
-                self.head_changed = true;
-            }
-            None => {}
-        }
+    async fn handle_load_blob(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitLoadBlob>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GitLoadBlobResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+        let path = RepoPath::from_proto(&envelope.payload.path)?;
 
-        self.recalculate_diffs(buffer, cx)
-    }
+        let content = repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.load_blob(envelope.payload.rev, path)
+            })?
+            .await??;
 
-    fn recalculate_diffs(&mut self, buffer: text::BufferSnapshot, cx: &mut Context<Self>) {
-        *self.recalculating_tx.borrow_mut() = true;
+        Ok(proto::GitLoadBlobResponse { content })
+    }
 
-        let language = self.language.clone();
-        let language_registry = self.language_registry.clone();
-        let unstaged_diff = self.unstaged_diff();
-        let uncommitted_diff = self.uncommitted_diff();
-        let head = self.head_text.clone();
-        let index = self.index_text.clone();
-        let index_changed = self.index_changed;
-        let head_changed = self.head_changed;
-        let language_changed = self.language_changed;
-        let prev_hunk_staging_operation_count = self.hunk_staging_operation_count_as_of_write;
-        let index_matches_head = match (self.index_text.as_ref(), self.head_text.as_ref()) {
-            (Some(index), Some(head)) => Arc::ptr_eq(index, head),
-            (None, None) => true,
-            _ => false,
+    async fn handle_apply_patch(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitApplyPatch>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+        let options = ApplyPatchOptions {
+            three_way: envelope.payload.three_way,
+            index: envelope.payload.index,
         };
-        self.recalculate_diff_task = Some(cx.spawn(async move |this, cx| {
-            log::debug!(
-                "start recalculating diffs for buffer {}",
-                buffer.remote_id()
-            );
 
-            let mut new_unstaged_diff = None;
-            if let Some(unstaged_diff) = &unstaged_diff {
-                new_unstaged_diff = Some(
-                    BufferDiff::update_diff(
-                        unstaged_diff.clone(),
-                        buffer.clone(),
-                        index,
-                        index_changed,
-                        language_changed,
-                        language.clone(),
-                        language_registry.clone(),
-                        cx,
-                    )
-                    .await?,
-                );
-            }
+        repository_handle
+            .update(&mut cx, |repository_handle, cx| {
+                repository_handle.apply_patch(envelope.payload.patch, options, cx)
+            })?
+            .await??;
 
-            let mut new_uncommitted_diff = None;
-            if let Some(uncommitted_diff) = &uncommitted_diff {
-                new_uncommitted_diff = if index_matches_head {
-                    new_unstaged_diff.clone()
-                } else {
-                    Some(
-                        BufferDiff::update_diff(
-                            uncommitted_diff.clone(),
-                            buffer.clone(),
-                            head,
-                            head_changed,
-                            language_changed,
-                            language.clone(),
-                            language_registry.clone(),
-                            cx,
-                        )
-                        .await?,
-                    )
-                }
-            }
+        Ok(proto::Ack {})
+    }
 
-            let cancel = this.update(cx, |this, _| {
-                // This checks whether all pending stage/unstage operations
-                // have quiesced (i.e. both the corresponding write and the
-                // read of that write have completed). If not, then we cancel
-                // this recalculation attempt to avoid invalidating pending
-                // state too quickly; another recalculation will come along
-                // later and clear the pending state once the state of the index has settled.
-                if this.hunk_staging_operation_count > prev_hunk_staging_operation_count {
-                    *this.recalculating_tx.borrow_mut() = false;
-                    true
-                } else {
-                    false
-                }
-            })?;
-            if cancel {
-                log::debug!(
-                    concat!(
-                        "aborting recalculating diffs for buffer {}",
-                        "due to subsequent hunk operations",
-                    ),
-                    buffer.remote_id()
-                );
-                return Ok(());
-            }
+    async fn handle_format_patch(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitFormatPatch>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GitFormatPatchResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
 
-            let unstaged_changed_range = if let Some((unstaged_diff, new_unstaged_diff)) =
-                unstaged_diff.as_ref().zip(new_unstaged_diff.clone())
-            {
-                unstaged_diff.update(cx, |diff, cx| {
-                    if language_changed {
-                        diff.language_changed(cx);
-                    }
-                    diff.set_snapshot(new_unstaged_diff, &buffer, cx)
-                })?
-            } else {
-                None
-            };
+        let patch = repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.format_patch(envelope.payload.range)
+            })?
+            .await??;
 
-            if let Some((uncommitted_diff, new_uncommitted_diff)) =
-                uncommitted_diff.as_ref().zip(new_uncommitted_diff.clone())
-            {
-                uncommitted_diff.update(cx, |diff, cx| {
-                    if language_changed {
-                        diff.language_changed(cx);
-                    }
-                    diff.set_snapshot_with_secondary(
-                        new_uncommitted_diff,
-                        &buffer,
-                        unstaged_changed_range,
-                        true,
-                        cx,
-                    );
-                })?;
-            }
+        Ok(proto::GitFormatPatchResponse { patch })
+    }
 
-            log::debug!(
-                "finished recalculating diffs for buffer {}",
-                buffer.remote_id()
-            );
+    async fn handle_maintenance(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitMaintenance>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+        let command = match envelope.payload.command() {
+            git_maintenance::Command::Run => MaintenanceCommand::Run,
+            git_maintenance::Command::Start => MaintenanceCommand::Start,
+            git_maintenance::Command::Stop => MaintenanceCommand::Stop,
+        };
+        let progress = make_remote_progress_sender(
+            this,
+            envelope.payload.project_id,
+            repository_id,
+            envelope.payload.askpass_id,
+            &mut cx,
+        );
 
-            if let Some(this) = this.upgrade() {
-                this.update(cx, |this, _| {
-                    this.index_changed = false;
-                    this.head_changed = false;
-                    this.language_changed = false;
-                    *this.recalculating_tx.borrow_mut() = false;
-                })?;
-            }
+        repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.maintenance(command, Some(progress))
+            })?
+            .await??;
 
-            Ok(())
-        }));
+        Ok(proto::Ack {})
     }
-}
 
-fn make_remote_delegate(
-    this: Entity<GitStore>,
-    project_id: u64,
-    repository_id: RepositoryId,
-    askpass_id: u64,
-    cx: &mut AsyncApp,
-) -> AskPassDelegate {
-    AskPassDelegate::new(cx, move |prompt, tx, cx| {
-        this.update(cx, |this, cx| {
-            let Some((client, _)) = this.downstream_client() else {
-                return;
-            };
-            let response = client.request(proto::AskPassRequest {
-                project_id,
-                repository_id: repository_id.to_proto(),
-                askpass_id,
-                prompt,
-            });
-            cx.spawn(async move |_, _| {
-                let mut response = response.await?.response;
-                tx.send(EncryptedPassword::try_from(response.as_ref())?)
-                    .ok();
-                response.zeroize();
-                anyhow::Ok(())
-            })
-            .detach_and_log_err(cx);
-        })
-        .log_err();
-    })
-}
+    async fn handle_gc(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitGc>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
 
-impl RepositoryId {
-    pub fn to_proto(self) -> u64 {
-        self.0
-    }
+        repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.gc(envelope.payload.aggressive)
+            })?
+            .await??;
 
-    pub fn from_proto(id: u64) -> Self {
-        RepositoryId(id)
+        Ok(proto::Ack {})
     }
-}
 
-impl RepositorySnapshot {
-    fn empty(id: RepositoryId, work_directory_abs_path: Arc<Path>, path_style: PathStyle) -> Self {
-        Self {
-            id,
-            statuses_by_path: Default::default(),
-            work_directory_abs_path,
-            branch: None,
-            head_commit: None,
-            scan_id: 0,
-            merge: Default::default(),
-            remote_origin_url: None,
-            remote_upstream_url: None,
-            stash_entries: Default::default(),
-            path_style,
-        }
-    }
+    async fn handle_repository_health(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitRepositoryHealth>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GitRepositoryHealthResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
 
-    fn initial_update(&self, project_id: u64) -> proto::UpdateRepository {
-        proto::UpdateRepository {
-            branch_summary: self.branch.as_ref().map(branch_to_proto),
-            head_commit_details: self.head_commit.as_ref().map(commit_details_to_proto),
-            updated_statuses: self
-                .statuses_by_path
-                .iter()
-                .map(|entry| entry.to_proto())
-                .collect(),
-            removed_statuses: Default::default(),
-            current_merge_conflicts: self
-                .merge
-                .conflicted_paths
-                .iter()
-                .map(|repo_path| repo_path.to_proto())
-                .collect(),
-            merge_message: self.merge.message.as_ref().map(|msg| msg.to_string()),
-            project_id,
-            id: self.id.to_proto(),
-            abs_path: self.work_directory_abs_path.to_string_lossy().into_owned(),
-            entry_ids: vec![self.id.to_proto()],
-            scan_id: self.scan_id,
-            is_last_update: true,
-            stash_entries: self
-                .stash_entries
-                .entries
-                .iter()
-                .map(stash_to_proto)
-                .collect(),
-        }
-    }
+        let health = repository_handle
+            .update(&mut cx, |repository_handle, _| repository_handle.health())?
+            .await??;
 
-    fn build_update(&self, old: &Self, project_id: u64) -> proto::UpdateRepository {
-        let mut updated_statuses: Vec<proto::StatusEntry> = Vec::new();
-        let mut removed_statuses: Vec<String> = Vec::new();
+        Ok(proto::GitRepositoryHealthResponse {
+            loose_objects: health.loose_objects,
+            loose_objects_size: health.loose_objects_size,
+            packs: health.packs,
+            packs_size: health.packs_size,
+        })
+    }
 
-        let mut new_statuses = self.statuses_by_path.iter().peekable();
-        let mut old_statuses = old.statuses_by_path.iter().peekable();
+    async fn handle_git_diff_file(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitDiffFile>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GitDiffResponse> {
+        let repository_id = RepositoryId::from_proto(envelope.payload.repository_id);
+        let repository_handle = Self::repository_for_request(&this, repository_id, &mut cx)?;
+        let path = RepoPath::from_proto(&envelope.payload.path)?;
+        let peer_id = envelope.original_sender_id.unwrap_or(envelope.sender_id);
 
-        let mut current_new_entry = new_statuses.next();
-        let mut current_old_entry = old_statuses.next();
-        loop {
-            match (current_new_entry, current_old_entry) {
-                (Some(new_entry), Some(old_entry)) => {
-                    match new_entry.repo_path.cmp(&old_entry.repo_path) {
-                        Ordering::Less => {
-                            updated_statuses.push(new_entry.to_proto());
-                            current_new_entry = new_statuses.next();
-                        }
-                        Ordering::Equal => {
-                            if new_entry.status != old_entry.status {
-                                updated_statuses.push(new_entry.to_proto());
-                            }
-                            current_old_entry = old_statuses.next();
-                            current_new_entry = new_statuses.next();
-                        }
-                        Ordering::Greater => {
-                            removed_statuses.push(old_entry.repo_path.to_proto());
-                            current_old_entry = old_statuses.next();
-                        }
-                    }
-                }
-                (None, Some(old_entry)) => {
-                    removed_statuses.push(old_entry.repo_path.to_proto());
-                    current_old_entry = old_statuses.next();
-                }
-                (Some(new_entry), None) => {
-                    updated_statuses.push(new_entry.to_proto());
-                    current_new_entry = new_statuses.next();
-                }
-                (None, None) => break,
-            }
-        }
+        let diff = repository_handle
+            .update(&mut cx, |repository_handle, _| {
+                repository_handle.diff_file(path, envelope.payload.base_rev)
+            })?
+            .await??;
 
-        proto::UpdateRepository {
-            branch_summary: self.branch.as_ref().map(branch_to_proto),
-            head_commit_details: self.head_commit.as_ref().map(commit_details_to_proto),
-            updated_statuses,
-            removed_statuses,
-            current_merge_conflicts: self
-                .merge
-                .conflicted_paths
-                .iter()
-                .map(|path| path.to_proto())
-                .collect(),
-            merge_message: self.merge.message.as_ref().map(|msg| msg.to_string()),
-            project_id,
-            id: self.id.to_proto(),
-            abs_path: self.work_directory_abs_path.to_string_lossy().into_owned(),
-            entry_ids: vec![],
-            scan_id: self.scan_id,
-            is_last_update: true,
-            stash_entries: self
-                .stash_entries
-                .entries
-                .iter()
-                .map(stash_to_proto)
-                .collect(),
-        }
+        this.update(&mut cx, |this, cx| this.diff_response(peer_id, diff, cx))
     }
 
-    pub fn status(&self) -> impl Iterator<Item = StatusEntry> + '_ {
-        self.statuses_by_path.iter().cloned()
-    }
+    async fn handle_git_diff_chunk(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GitDiffChunk>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GitDiffChunkResponse> {
+        let peer_id = envelope.original_sender_id.unwrap_or(envelope.sender_id);
+        let stream_id = envelope.payload.stream_id;
+        let offset = envelope.payload.offset as usize;
 
-    pub fn status_summary(&self) -> GitSummary {
-        self.statuses_by_path.summary().item_summary
+        this.update(&mut cx, |this, cx| {
+            let stream = this
+                .diff_streams
+                .get_mut(&peer_id)
+                .and_then(|streams| streams.get_mut(&stream_id))
+                .context("unknown diff stream")?;
+            anyhow::ensure!(
+                stream.diff.is_char_boundary(offset),
+                "invalid offset {offset} for diff stream"
+            );
+            let end = diff_chunk_end(&stream.diff, offset);
+            let chunk = stream.diff[offset..end].to_string();
+            if end == stream.diff.len() {
+                this.remove_diff_stream(peer_id, stream_id);
+            } else {
+                stream._expire = Self::expire_diff_stream(peer_id, stream_id, cx);
+            }
+            Ok(proto::GitDiffChunkResponse { chunk })
+        })?
     }
 
-    pub fn status_for_path(&self, path: &RepoPath) -> Option<StatusEntry> {
-        self.statuses_by_path
-            .get(&PathKey(path.0.clone()), ())
-            .cloned()
+    fn expire_diff_stream(peer_id: proto::PeerId, stream_id: u64, cx: &Context<Self>) -> Task<()> {
+        cx.spawn(async move |this, cx| {
+            cx.background_executor().timer(DIFF_STREAM_TIMEOUT).await;
+            this.update(cx, |this, _| this.remove_diff_stream(peer_id, stream_id))
+                .ok();
+        })
     }
 
-    pub fn abs_path_to_repo_path(&self, abs_path: &Path) -> Option<RepoPath> {
-        Self::abs_path_to_repo_path_inner(&self.work_directory_abs_path, abs_path, self.path_style)
+    fn remove_diff_stream(&mut self, peer_id: proto::PeerId, stream_id: u64) {
+        if let Some(streams) = self.diff_streams.get_mut(&peer_id) {
+            streams.remove(&stream_id);
+            if streams.is_empty() {
+                self.diff_streams.remove(&peer_id);
+            }
+        }
     }
 
-    #[inline]
-    fn abs_path_to_repo_path_inner(
-        work_directory_abs_path: &Path,
-        abs_path: &Path,
-        path_style: PathStyle,
-    ) -> Option<RepoPath> {
-        abs_path
-            .strip_prefix(&work_directory_abs_path)
-            .ok()
-            .and_then(|path| RepoPath::from_std_path(path, path_style).ok())
-    }
+    /// Responds with the diff in one message if it's small enough, and otherwise with its first
+    /// chunk, keeping the rest for the peer to fetch one chunk at a time.
+    fn diff_response(
+        &mut self,
+        peer_id: proto::PeerId,
+        mut diff: String,
+        cx: &mut Context<Self>,
+    ) -> proto::GitDiffResponse {
+        let truncated = diff.len() > MAX_DIFF_LEN;
+        if truncated {
+            let mut end = MAX_DIFF_LEN;
+            while !diff.is_char_boundary(end) {
+                end -= 1;
+            }
+            diff.truncate(end);
+        }
+        let total_len = diff.len() as u64;
+        let first_chunk_end = diff_chunk_end(&diff, 0);
+        if first_chunk_end == diff.len() {
+            return proto::GitDiffResponse {
+                diff,
+                stream_id: None,
+                total_len,
+                truncated,
+            };
+        }
 
-    pub fn had_conflict_on_last_merge_head_change(&self, repo_path: &RepoPath) -> bool {
-        self.merge.conflicted_paths.contains(repo_path)
-    }
-
-    pub fn has_conflict(&self, repo_path: &RepoPath) -> bool {
-        let had_conflict_on_last_merge_head_change =
-            self.merge.conflicted_paths.contains(repo_path);
-        let has_conflict_currently = self
-            .status_for_path(repo_path)
-            .is_some_and(|entry| entry.status.is_conflicted());
-        had_conflict_on_last_merge_head_change || has_conflict_currently
+        let stream_id = post_inc(&mut self.next_diff_stream_id);
+        let first_chunk = diff[..first_chunk_end].to_string();
+        let streams = self.diff_streams.entry(peer_id).or_default();
+        // The peer's oldest streams make way for new ones, so that a peer can't hold on to an
+        // unbounded number of diffs.
+        while streams.len() >= MAX_DIFF_STREAMS_PER_PEER {
+            streams.pop_first();
+        }
+        streams.insert(
+            stream_id,
+            DiffStream {
+                diff,
+                _expire: Self::expire_diff_stream(peer_id, stream_id, cx),
+            },
+        );
+        proto::GitDiffResponse {
+            diff: first_chunk,
+            stream_id: Some(stream_id),
+            total_len,
+            truncated,
+        }
     }
 
-    /// This is the name that will be displayed in the repository selector for this repository.
-    pub fn display_name(&self) -> SharedString {
-        self.work_directory_abs_path
-            .file_name()
-            .unwrap_or_default()
-            .to_string_lossy()
-            .to_string()
-            .into()
+    async fn handle_open_unstaged_diff(
+        this: Entity<Self>,
+        request: TypedEnvelope<proto::OpenUnstagedDiff>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::OpenUnstagedDiffResponse> {
+        let buffer_id = BufferId::new(request.payload.buffer_id)?;
+        let diff = this
+            .update(&mut cx, |this, cx| {
+                let buffer = this.buffer_store.read(cx).get(buffer_id)?;
+                Some(this.open_unstaged_diff(buffer, cx))
+            })?
+            .context("missing buffer")?
+            .await?;
+        this.update(&mut cx, |this, _| {
+            let shared_diffs = this
+                .shared_diffs
+                .entry(request.original_sender_id.unwrap_or(request.sender_id))
+                .or_default();
+            shared_diffs.entry(buffer_id).or_default().unstaged = Some(diff.clone());
+        })?;
+        let staged_text = diff.read_with(&cx, |diff, _| diff.base_text_string())?;
+        Ok(proto::OpenUnstagedDiffResponse { staged_text })
     }
-}
 
-pub fn stash_to_proto(entry: &StashEntry) -> proto::StashEntry {
-    proto::StashEntry {
-        oid: entry.oid.as_bytes().to_vec(),
-        message: entry.message.clone(),
-        branch: entry.branch.clone(),
-        index: entry.index as u64,
-        timestamp: entry.timestamp,
-    }
-}
+    async fn handle_open_uncommitted_diff(
+        this: Entity<Self>,
+        request: TypedEnvelope<proto::OpenUncommittedDiff>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::OpenUncommittedDiffResponse> {
+        let buffer_id = BufferId::new(request.payload.buffer_id)?;
+        let diff = this
+            .update(&mut cx, |this, cx| {
+                let buffer = this.buffer_store.read(cx).get(buffer_id)?;
+                Some(this.open_uncommitted_diff(buffer, cx))
+            })?
+            .context("missing buffer")?
+            .await?;
+        this.update(&mut cx, |this, _| {
+            let shared_diffs = this
+                .shared_diffs
+                .entry(request.original_sender_id.unwrap_or(request.sender_id))
+                .or_default();
+            shared_diffs.entry(buffer_id).or_default().uncommitted = Some(diff.clone());
+        })?;
+        diff.read_with(&cx, |diff, cx| {
+            use proto::open_uncommitted_diff_response::Mode;
 
-pub fn proto_to_stash(entry: &proto::StashEntry) -> Result<StashEntry> {
-    Ok(StashEntry {
-        oid: Oid::from_bytes(&entry.oid)?,
-        message: entry.message.clone(),
-        index: entry.index as usize,
-        branch: entry.branch.clone(),
-        timestamp: entry.timestamp,
-    })
-}
+            let unstaged_diff = diff.secondary_diff();
+            let index_snapshot = unstaged_diff.and_then(|diff| {
+                let diff = diff.read(cx);
+                diff.base_text_exists().then(|| diff.base_text())
+            });
 
-impl MergeDetails {
-    async fn load(
-        backend: &Arc<dyn GitRepository>,
-        status: &SumTree<StatusEntry>,
-        prev_snapshot: &RepositorySnapshot,
-    ) -> Result<(MergeDetails, bool)> {
-        log::debug!("load merge details");
-        let message = backend.merge_message().await;
-        let heads = backend
-            .revparse_batch(vec![
-                "MERGE_HEAD".into(),
-                "CHERRY_PICK_HEAD".into(),
-                "REBASE_HEAD".into(),
-                "REVERT_HEAD".into(),
-                "APPLY_HEAD".into(),
-            ])
-            .await
-            .log_err()
-            .unwrap_or_default()
-            .into_iter()
-            .map(|opt| opt.map(SharedString::from))
-            .collect::<Vec<_>>();
-        let merge_heads_changed = heads != prev_snapshot.merge.heads;
-        let conflicted_paths = if merge_heads_changed {
-            let current_conflicted_paths = TreeSet::from_ordered_entries(
-                status
-                    .iter()
-                    .filter(|entry| entry.status.is_conflicted())
-                    .map(|entry| entry.repo_path.clone()),
-            );
+            let mode;
+            let staged_text;
+            let committed_text;
+            if diff.base_text_exists() {
+                let committed_snapshot = diff.base_text();
+                committed_text = Some(committed_snapshot.text());
+                if let Some(index_text) = index_snapshot {
+                    if index_text.remote_id() == committed_snapshot.remote_id() {
+                        mode = Mode::IndexMatchesHead;
+                        staged_text = None;
+                    } else {
+                        mode = Mode::IndexAndHead;
+                        staged_text = Some(index_text.text());
+                    }
+                } else {
+                    mode = Mode::IndexAndHead;
+                    staged_text = None;
+                }
+            } else {
+                mode = Mode::IndexAndHead;
+                committed_text = None;
+                staged_text = index_snapshot.as_ref().map(|buffer| buffer.text());
+            }
 
-            // It can happen that we run a scan while a lengthy merge is in progress
-            // that will eventually result in conflicts, but before those conflicts
-            // are reported by `git status`. Since for the moment we only care about
-            // the merge heads state for the purposes of tracking conflicts, don't update
-            // this state until we see some conflicts.
-            if heads.iter().any(Option::is_some)
-                && !prev_snapshot.merge.heads.iter().any(Option::is_some)
-                && current_conflicted_paths.is_empty()
+            proto::OpenUncommittedDiffResponse {
+                committed_text,
+                staged_text,
+                mode: mode.into(),
+            }
+        })
+    }
+
+    async fn handle_update_diff_bases(
+        this: Entity<Self>,
+        request: TypedEnvelope<proto::UpdateDiffBases>,
+        mut cx: AsyncApp,
+    ) -> Result<()> {
+        let buffer_id = BufferId::new(request.payload.buffer_id)?;
+        this.update(&mut cx, |this, cx| {
+            if let Some(diff_state) = this.diffs.get_mut(&buffer_id)
+                && let Some(buffer) = this.buffer_store.read(cx).get(buffer_id)
             {
-                log::debug!("not updating merge heads because no conflicts found");
-                return Ok((
-                    MergeDetails {
-                        message: message.map(SharedString::from),
-                        ..prev_snapshot.merge.clone()
-                    },
-                    false,
-                ));
+                let buffer = buffer.read(cx).text_snapshot();
+                diff_state.update(cx, |diff_state, cx| {
+                    diff_state.handle_base_texts_updated(buffer, request.payload, cx);
+                })
             }
+        })
+    }
 
-            current_conflicted_paths
-        } else {
-            prev_snapshot.merge.conflicted_paths.clone()
-        };
-        let details = MergeDetails {
-            conflicted_paths,
-            message: message.map(SharedString::from),
-            heads,
-        };
-        Ok((details, merge_heads_changed))
+    async fn handle_blame_buffer(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::BlameBuffer>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::BlameBufferResponse> {
+        let buffer_id = BufferId::new(envelope.payload.buffer_id)?;
+        let version = deserialize_version(&envelope.payload.version);
+        let buffer = this.read_with(&cx, |this, cx| {
+            this.buffer_store.read(cx).get_existing(buffer_id)
+        })??;
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_version(version.clone())
+            })?
+            .await?;
+        let blame = this
+            .update(&mut cx, |this, cx| {
+                this.blame_buffer(&buffer, Some(version), cx)
+            })?
+            .await?;
+        Ok(serialize_blame_buffer_response(blame))
     }
-}
 
-impl Repository {
-    pub fn snapshot(&self) -> RepositorySnapshot {
-        self.snapshot.clone()
+    async fn handle_get_permalink_to_line(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GetPermalinkToLine>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::GetPermalinkToLineResponse> {
+        let buffer_id = BufferId::new(envelope.payload.buffer_id)?;
+        // let version = deserialize_version(&envelope.payload.version);
+        let selection = {
+            let proto_selection = envelope
+                .payload
+                .selection
+                .context("no selection to get permalink for defined")?;
+            proto_selection.start as u32..proto_selection.end as u32
+        };
+        let buffer = this.read_with(&cx, |this, cx| {
+            this.buffer_store.read(cx).get_existing(buffer_id)
+        })??;
+        let permalink = this
+            .update(&mut cx, |this, cx| {
+                this.get_permalink_to_line(&buffer, selection, cx)
+            })?
+            .await?;
+        Ok(proto::GetPermalinkToLineResponse {
+            permalink: permalink.to_string(),
+        })
     }
 
-    fn local(
+    fn repository_for_request(
+        this: &Entity<Self>,
         id: RepositoryId,
-        work_directory_abs_path: Arc<Path>,
-        dot_git_abs_path: Arc<Path>,
-        repository_dir_abs_path: Arc<Path>,
-        common_dir_abs_path: Arc<Path>,
-        project_environment: WeakEntity<ProjectEnvironment>,
-        fs: Arc<dyn Fs>,
-        git_store: WeakEntity<GitStore>,
-        cx: &mut Context<Self>,
-    ) -> Self {
-        let snapshot =
-            RepositorySnapshot::empty(id, work_directory_abs_path.clone(), PathStyle::local());
-        Repository {
-            this: cx.weak_entity(),
-            git_store,
-            snapshot,
-            commit_message_buffer: None,
-            askpass_delegates: Default::default(),
-            paths_needing_status_update: Default::default(),
-            latest_askpass_id: 0,
-            job_sender: Repository::spawn_local_git_worker(
-                work_directory_abs_path,
-                dot_git_abs_path,
-                repository_dir_abs_path,
-                common_dir_abs_path,
-                project_environment,
-                fs,
-                cx,
-            ),
-            job_id: 0,
-            active_jobs: Default::default(),
-        }
+        cx: &mut AsyncApp,
+    ) -> Result<Entity<Repository>> {
+        this.read_with(cx, |this, _| {
+            this.repositories
+                .get(&id)
+                .context("missing repository handle")
+                .cloned()
+        })?
     }
 
-    fn remote(
-        id: RepositoryId,
-        work_directory_abs_path: Arc<Path>,
-        path_style: PathStyle,
-        project_id: ProjectId,
-        client: AnyProtoClient,
-        git_store: WeakEntity<GitStore>,
-        cx: &mut Context<Self>,
-    ) -> Self {
-        let snapshot = RepositorySnapshot::empty(id, work_directory_abs_path, path_style);
+    pub fn repo_snapshots(&self, cx: &App) -> HashMap<RepositoryId, RepositorySnapshot> {
+        self.repositories
+            .iter()
+            .map(|(id, repo)| (*id, repo.read(cx).snapshot.clone()))
+            .collect()
+    }
+
+    fn process_updated_entries(
+        &self,
+        worktree: &Entity<Worktree>,
+        updated_entries: &[(Arc<RelPath>, ProjectEntryId, PathChange)],
+        cx: &mut App,
+    ) -> Task<HashMap<Entity<Repository>, Vec<RepoPath>>> {
+        let path_style = worktree.read(cx).path_style();
+        let mut repo_paths = self
+            .repositories
+            .values()
+            .map(|repo| (repo.read(cx).work_directory_abs_path.clone(), repo.clone()))
+            .collect::<Vec<_>>();
+        let mut entries: Vec<_> = updated_entries
+            .iter()
+            .map(|(path, _, _)| path.clone())
+            .collect();
+        entries.sort();
+        let worktree = worktree.read(cx);
+
+        let entries = entries
+            .into_iter()
+            .map(|path| worktree.absolutize(&path))
+            .collect::<Arc<[_]>>();
+
+        let executor = cx.background_executor().clone();
+        cx.background_executor().spawn(async move {
+            repo_paths.sort_by(|lhs, rhs| lhs.0.cmp(&rhs.0));
+            let mut paths_by_git_repo = HashMap::<_, Vec<_>>::default();
+            let mut tasks = FuturesOrdered::new();
+            for (repo_path, repo) in repo_paths.into_iter().rev() {
+                let entries = entries.clone();
+                let task = executor.spawn(async move {
+                    // Find all repository paths that belong to this repo
+                    let mut ix = entries.partition_point(|path| path < &*repo_path);
+                    if ix == entries.len() {
+                        return None;
+                    };
+
+                    let mut paths = Vec::new();
+                    // All paths prefixed by a given repo will constitute a continuous range.
+                    while let Some(path) = entries.get(ix)
+                        && let Some(repo_path) = RepositorySnapshot::abs_path_to_repo_path_inner(
+                            &repo_path, path, path_style,
+                        )
+                    {
+                        paths.push((repo_path, ix));
+                        ix += 1;
+                    }
+                    if paths.is_empty() {
+                        None
+                    } else {
+                        Some((repo, paths))
+                    }
+                });
+                tasks.push_back(task);
+            }
+
+            // Now, let's filter out the "duplicate" entries that were processed by multiple distinct repos.
+            let mut path_was_used = vec![false; entries.len()];
+            let tasks = tasks.collect::<Vec<_>>().await;
+            // Process tasks from the back: iterating backwards allows us to see more-specific paths first.
+            // We always want to assign a path to it's innermost repository.
+            for t in tasks {
+                let Some((repo, paths)) = t else {
+                    continue;
+                };
+                let entry = paths_by_git_repo.entry(repo).or_default();
+                for (repo_path, ix) in paths {
+                    if path_was_used[ix] {
+                        continue;
+                    }
+                    path_was_used[ix] = true;
+                    entry.push(repo_path);
+                }
+            }
+
+            paths_by_git_repo
+        })
+    }
+}
+
+impl BufferGitState {
+    fn new(_git_store: WeakEntity<GitStore>) -> Self {
         Self {
-            this: cx.weak_entity(),
-            snapshot,
-            commit_message_buffer: None,
-            git_store,
-            paths_needing_status_update: Default::default(),
-            job_sender: Self::spawn_remote_git_worker(project_id, client, cx),
-            askpass_delegates: Default::default(),
-            latest_askpass_id: 0,
-            active_jobs: Default::default(),
-            job_id: 0,
+            unstaged_diff: Default::default(),
+            uncommitted_diff: Default::default(),
+            recalculate_diff_task: Default::default(),
+            language: Default::default(),
+            language_registry: Default::default(),
+            recalculating_tx: postage::watch::channel_with(false).0,
+            hunk_staging_operation_count: 0,
+            hunk_staging_operation_count_as_of_write: 0,
+            head_text: Default::default(),
+            index_text: Default::default(),
+            head_changed: Default::default(),
+            index_changed: Default::default(),
+            language_changed: Default::default(),
+            conflict_updated_futures: Default::default(),
+            conflict_set: Default::default(),
+            reparse_conflict_markers_task: Default::default(),
         }
     }
 
-    pub fn git_store(&self) -> Option<Entity<GitStore>> {
-        self.git_store.upgrade()
+    fn buffer_language_changed(&mut self, buffer: Entity<Buffer>, cx: &mut Context<Self>) {
+        self.language = buffer.read(cx).language().cloned();
+        self.language_changed = true;
+        let _ = self.recalculate_diffs(buffer.read(cx).text_snapshot(), cx);
     }
 
-    fn reload_buffer_diff_bases(&mut self, cx: &mut Context<Self>) {
-        let this = cx.weak_entity();
-        let git_store = self.git_store.clone();
-        let _ = self.send_keyed_job(
-            Some(GitJobKey::ReloadBufferDiffBases),
-            None,
-            |state, mut cx| async move {
-                let RepositoryState::Local { backend, .. } = state else {
-                    log::error!("tried to recompute diffs for a non-local repository");
-                    return Ok(());
-                };
+    fn reparse_conflict_markers(
+        &mut self,
+        buffer: text::BufferSnapshot,
+        cx: &mut Context<Self>,
+    ) -> oneshot::Receiver<()> {
+        let (tx, rx) = oneshot::channel();
 
-                let Some(this) = this.upgrade() else {
-                    return Ok(());
-                };
+        let Some(conflict_set) = self
+            .conflict_set
+            .as_ref()
+            .and_then(|conflict_set| conflict_set.upgrade())
+        else {
+            return rx;
+        };
 
-                let repo_diff_state_updates = this.update(&mut cx, |this, cx| {
-                    git_store.update(cx, |git_store, cx| {
-                        git_store
-                            .diffs
-                            .iter()
-                            .filter_map(|(buffer_id, diff_state)| {
-                                let buffer_store = git_store.buffer_store.read(cx);
-                                let buffer = buffer_store.get(*buffer_id)?;
-                                let file = File::from_dyn(buffer.read(cx).file())?;
-                                let abs_path = file.worktree.read(cx).absolutize(&file.path);
-                                let repo_path = this.abs_path_to_repo_path(&abs_path)?;
-                                log::debug!(
-                                    "start reload diff bases for repo path {}",
-                                    repo_path.as_unix_str()
-                                );
-                                diff_state.update(cx, |diff_state, _| {
-                                    let has_unstaged_diff = diff_state
-                                        .unstaged_diff
-                                        .as_ref()
-                                        .is_some_and(|diff| diff.is_upgradable());
-                                    let has_uncommitted_diff = diff_state
-                                        .uncommitted_diff
-                                        .as_ref()
-                                        .is_some_and(|set| set.is_upgradable());
+        let old_snapshot = conflict_set.read_with(cx, |conflict_set, _| {
+            if conflict_set.has_conflict {
+                Some(conflict_set.snapshot())
+            } else {
+                None
+            }
+        });
 
-                                    Some((
-                                        buffer,
-                                        repo_path,
-                                        has_unstaged_diff.then(|| diff_state.index_text.clone()),
-                                        has_uncommitted_diff.then(|| diff_state.head_text.clone()),
-                                    ))
-                                })
-                            })
-                            .collect::<Vec<_>>()
+        if let Some(old_snapshot) = old_snapshot {
+            self.conflict_updated_futures.push(tx);
+            self.reparse_conflict_markers_task = Some(cx.spawn(async move |this, cx| {
+                let (snapshot, changed_range) = cx
+                    .background_spawn(async move {
+                        let new_snapshot = ConflictSet::parse(&buffer);
+                        let changed_range = old_snapshot.compare(&new_snapshot, &buffer);
+                        (new_snapshot, changed_range)
                     })
-                })??;
+                    .await;
+                this.update(cx, |this, cx| {
+                    if let Some(conflict_set) = &this.conflict_set {
+                        conflict_set
+                            .update(cx, |conflict_set, cx| {
+                                conflict_set.set_snapshot(snapshot, changed_range, cx);
+                            })
+                            .ok();
+                    }
+                    let futures = std::mem::take(&mut this.conflict_updated_futures);
+                    for tx in futures {
+                        tx.send(()).ok();
+                    }
+                })
+            }))
+        }
 
-                let buffer_diff_base_changes = cx
-                    .background_spawn(async move {
-                        let mut changes = Vec::new();
-                        for (buffer, repo_path, current_index_text, current_head_text) in
-                            &repo_diff_state_updates
-                        {
-                            let index_text = if current_index_text.is_some() {
-                                backend.load_index_text(repo_path.clone()).await
-                            } else {
+        rx
+    }
+
+    fn unstaged_diff(&self) -> Option<Entity<BufferDiff>> {
+        self.unstaged_diff.as_ref().and_then(|set| set.upgrade())
+    }
+
+    fn uncommitted_diff(&self) -> Option<Entity<BufferDiff>> {
+        self.uncommitted_diff.as_ref().and_then(|set| set.upgrade())
+    }
+
+    fn handle_base_texts_updated(
+        &mut self,
+        buffer: text::BufferSnapshot,
+        message: proto::UpdateDiffBases,
+        cx: &mut Context<Self>,
+    ) {
+        use proto::update_diff_bases::Mode;
+
+        let Some(mode) = Mode::from_i32(message.mode) else {
+            return;
+        };
+
+        let diff_bases_change = match mode {
+            Mode::HeadOnly => DiffBasesChange::SetHead(message.committed_text),
+            Mode::IndexOnly => DiffBasesChange::SetIndex(message.staged_text),
+            Mode::IndexMatchesHead => DiffBasesChange::SetBoth(message.committed_text),
+            Mode::IndexAndHead => DiffBasesChange::SetEach {
+                index: message.staged_text,
+                head: message.committed_text,
+            },
+        };
+
+        self.diff_bases_changed(buffer, Some(diff_bases_change), cx);
+    }
+
+    pub fn wait_for_recalculation(&mut self) -> Option<impl Future<Output = ()> + use<>> {
+        if *self.recalculating_tx.borrow() {
+            let mut rx = self.recalculating_tx.subscribe();
+            Some(async move {
+                loop {
+                    let is_recalculating = rx.recv().await;
+                    if is_recalculating != Some(true) {
+                        break;
+                    }
+                }
+            })
+        } else {
+            None
+        }
+    }
+
+    fn diff_bases_changed(
+        &mut self,
+        buffer: text::BufferSnapshot,
+        diff_bases_change: Option<DiffBasesChange>,
+        cx: &mut Context<Self>,
+    ) {
+        match diff_bases_change {
+            Some(DiffBasesChange::SetIndex(index)) => {
+                self.index_text = index.map(|mut index| {
+                    text::LineEnding::normalize(&mut index);
+                    Arc::new(index)
+                });
+                self.index_changed = true;
+            }
+            Some(DiffBasesChange::SetHead(head)) => {
+                self.head_text = head.map(|mut head| {
+                    text::LineEnding::normalize(&mut head);
+                    Arc::new(head)
+                });
+                self.head_changed = true;
+            }
+            Some(DiffBasesChange::SetBoth(text)) => {
+                let text = text.map(|mut text| {
+                    text::LineEnding::normalize(&mut text);
+                    Arc::new(text)
+                });
+                self.head_text = text.clone();
+                self.index_text = text;
+                self.head_changed = true;
+                self.index_changed = true;
+            }
+            Some(DiffBasesChange::SetEach { index, head }) => {
+                self.index_text = index.map(|mut index| {
+                    text::LineEnding::normalize(&mut index);
+                    Arc::new(index)
+                });
+                self.index_changed = true;
+                self.head_text = head.map(|mut head| {
+                    text::LineEnding::normalize(&mut head);
+                    Arc::new(head)
+                });
+                self.head_changed = true;
+            }
+            None => {}
+        }
+
+        self.recalculate_diffs(buffer, cx)
+    }
+
+    fn recalculate_diffs(&mut self, buffer: text::BufferSnapshot, cx: &mut Context<Self>) {
+        *self.recalculating_tx.borrow_mut() = true;
+
+        let language = self.language.clone();
+        let language_registry = self.language_registry.clone();
+        let unstaged_diff = self.unstaged_diff();
+        let uncommitted_diff = self.uncommitted_diff();
+        let head = self.head_text.clone();
+        let index = self.index_text.clone();
+        let index_changed = self.index_changed;
+        let head_changed = self.head_changed;
+        let language_changed = self.language_changed;
+        let prev_hunk_staging_operation_count = self.hunk_staging_operation_count_as_of_write;
+        let index_matches_head = match (self.index_text.as_ref(), self.head_text.as_ref()) {
+            (Some(index), Some(head)) => Arc::ptr_eq(index, head),
+            (None, None) => true,
+            _ => false,
+        };
+        self.recalculate_diff_task = Some(cx.spawn(async move |this, cx| {
+            log::debug!(
+                "start recalculating diffs for buffer {}",
+                buffer.remote_id()
+            );
+
+            let mut new_unstaged_diff = None;
+            if let Some(unstaged_diff) = &unstaged_diff {
+                new_unstaged_diff = Some(
+                    BufferDiff::update_diff(
+                        unstaged_diff.clone(),
+                        buffer.clone(),
+                        index,
+                        index_changed,
+                        language_changed,
+                        language.clone(),
+                        language_registry.clone(),
+                        cx,
+                    )
+                    .await?,
+                );
+            }
+
+            let mut new_uncommitted_diff = None;
+            if let Some(uncommitted_diff) = &uncommitted_diff {
+                new_uncommitted_diff = if index_matches_head {
+                    new_unstaged_diff.clone()
+                } else {
+                    Some(
+                        BufferDiff::update_diff(
+                            uncommitted_diff.clone(),
+                            buffer.clone(),
+                            head,
+                            head_changed,
+                            language_changed,
+                            language.clone(),
+                            language_registry.clone(),
+                            cx,
+                        )
+                        .await?,
+                    )
+                }
+            }
+
+            let cancel = this.update(cx, |this, _| {
+                // This checks whether all pending stage/unstage operations
+                // have quiesced (i.e. both the corresponding write and the
+                // read of that write have completed). If not, then we cancel
+                // this recalculation attempt to avoid invalidating pending
+                // state too quickly; another recalculation will come along
+                // later and clear the pending state once the state of the index has settled.
+                if this.hunk_staging_operation_count > prev_hunk_staging_operation_count {
+                    *this.recalculating_tx.borrow_mut() = false;
+                    true
+                } else {
+                    false
+                }
+            })?;
+            if cancel {
+                log::debug!(
+                    concat!(
+                        "aborting recalculating diffs for buffer {}",
+                        "due to subsequent hunk operations",
+                    ),
+                    buffer.remote_id()
+                );
+                return Ok(());
+            }
+
+            let unstaged_changed_range = if let Some((unstaged_diff, new_unstaged_diff)) =
+                unstaged_diff.as_ref().zip(new_unstaged_diff.clone())
+            {
+                unstaged_diff.update(cx, |diff, cx| {
+                    if language_changed {
+                        diff.language_changed(cx);
+                    }
+                    diff.set_snapshot(new_unstaged_diff, &buffer, cx)
+                })?
+            } else {
+                None
+            };
+
+            if let Some((uncommitted_diff, new_uncommitted_diff)) =
+                uncommitted_diff.as_ref().zip(new_uncommitted_diff.clone())
+            {
+                uncommitted_diff.update(cx, |diff, cx| {
+                    if language_changed {
+                        diff.language_changed(cx);
+                    }
+                    diff.set_snapshot_with_secondary(
+                        new_uncommitted_diff,
+                        &buffer,
+                        unstaged_changed_range,
+                        true,
+                        cx,
+                    );
+                })?;
+            }
+
+            log::debug!(
+                "finished recalculating diffs for buffer {}",
+                buffer.remote_id()
+            );
+
+            if let Some(this) = this.upgrade() {
+                this.update(cx, |this, _| {
+                    this.index_changed = false;
+                    this.head_changed = false;
+                    this.language_changed = false;
+                    *this.recalculating_tx.borrow_mut() = false;
+                })?;
+            }
+
+            Ok(())
+        }));
+    }
+}
+
+/// The largest part of a diff that's sent to a peer in a single message.
+const DIFF_CHUNK_LEN: usize = 1_000_000;
+
+/// How long a streamed diff is kept after the peer last fetched a chunk of it.
+const DIFF_STREAM_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// How many streamed diffs are kept for each peer at once.
+const MAX_DIFF_STREAMS_PER_PEER: usize = 4;
+
+/// Diffs beyond this length are truncated rather than sent to peers, since even streamed they'd be
+/// held in memory whole on both ends.
+const MAX_DIFF_LEN: usize = 256_000_000;
+
+/// The end of the chunk of `diff` that starts at `offset`, which never splits a character.
+fn diff_chunk_end(diff: &str, offset: usize) -> usize {
+    let mut end = (offset + DIFF_CHUNK_LEN).min(diff.len());
+    while !diff.is_char_boundary(end) {
+        end -= 1;
+    }
+    end
+}
+
+/// Assembles a diff that the host sent in chunks. Each chunk is only requested once the previous
+/// one has arrived, so the host never has more than one chunk in flight.
+async fn receive_diff(
+    client: &AnyProtoClient,
+    project_id: ProjectId,
+    repository_id: RepositoryId,
+    response: proto::GitDiffResponse,
+) -> Result<String> {
+    let mut diff = response.diff;
+    if let Some(stream_id) = response.stream_id {
+        diff.reserve((response.total_len as usize).saturating_sub(diff.len()));
+        while (diff.len() as u64) < response.total_len {
+            let response = client
+                .request(proto::GitDiffChunk {
+                    project_id: project_id.0,
+                    repository_id: repository_id.to_proto(),
+                    stream_id,
+                    offset: diff.len() as u64,
+                })
+                .await?;
+            anyhow::ensure!(!response.chunk.is_empty(), "diff stream ended early");
+            diff.push_str(&response.chunk);
+        }
+    }
+    if response.truncated {
+        log::warn!(
+            "diff was truncated by the host to {} bytes",
+            response.total_len
+        );
+    }
+    Ok(diff)
+}
+
+/// How long to pause before retrying a fetch that the host didn't respond to, which doubles with
+/// each retry.
+const REMOTE_REQUEST_RETRY_BACKOFF: Duration = Duration::from_secs(1);
+
+/// A fetch, push or pull that a guest asked the host for. While it's in flight, the host's
+/// credential prompts are answered by its askpass delegate, and the host's progress is forwarded
+/// to the repository.
+struct RemoteCommandRequest {
+    askpass_id: u64,
+    askpass_delegates: Arc<Mutex<HashMap<u64, AskPassDelegate>>>,
+    remote_progress_senders: Arc<Mutex<HashMap<u64, mpsc::UnboundedSender<RemoteProgress>>>>,
+    host_progress_rx: mpsc::UnboundedReceiver<RemoteProgress>,
+    progress_tx: mpsc::UnboundedSender<RemoteProgress>,
+}
+
+impl RemoteCommandRequest {
+    fn new(
+        askpass_id: u64,
+        askpass: AskPassDelegate,
+        progress_tx: mpsc::UnboundedSender<RemoteProgress>,
+        askpass_delegates: Arc<Mutex<HashMap<u64, AskPassDelegate>>>,
+        remote_progress_senders: Arc<Mutex<HashMap<u64, mpsc::UnboundedSender<RemoteProgress>>>>,
+    ) -> Self {
+        askpass_delegates.lock().insert(askpass_id, askpass);
+        // The host's progress passes through here, as it shows that the host is still working.
+        let (host_progress_tx, host_progress_rx) = mpsc::unbounded();
+        remote_progress_senders
+            .lock()
+            .insert(askpass_id, host_progress_tx);
+        Self {
+            askpass_id,
+            askpass_delegates,
+            remote_progress_senders,
+            host_progress_rx,
+            progress_tx,
+        }
+    }
+
+    /// Sends `request` to the host and waits for its response. If the host goes longer than the
+    /// `git.remote_request_timeout_ms` setting without responding or reporting progress, the
+    /// request is given up on with a [`RemoteCommandTimedOut`] error. Idempotent requests are
+    /// sent again first, up to `git.remote_request_retries` times.
+    async fn send<T>(
+        &mut self,
+        client: &AnyProtoClient,
+        request: T,
+        command: &'static str,
+        idempotent: bool,
+        cx: &AsyncApp,
+    ) -> Result<T::Response>
+    where
+        T: proto::RequestMessage + Clone,
+    {
+        let (timeout, retries) = cx.update(|cx| {
+            let git_settings = &ProjectSettings::get_global(cx).git;
+            let retries = if idempotent {
+                git_settings.remote_request_retries
+            } else {
+                0
+            };
+            (git_settings.remote_request_timeout, retries)
+        })?;
+        let Some(timeout) = timeout else {
+            // Without a timeout, the host is waited on for as long as it takes.
+            return self
+                .wait_for_response(client.request(request), None, cx)
+                .await
+                .context("the host stopped responding")?;
+        };
+
+        let mut attempts = 0;
+        loop {
+            attempts += 1;
+            let response = client.request(request.clone());
+            if let Some(response) = self.wait_for_response(response, Some(timeout), cx).await {
+                return response;
+            }
+            if attempts > retries {
+                return Err(RemoteCommandTimedOut {
+                    command,
+                    timeout,
+                    attempts,
+                }
+                .into());
+            }
+            log::warn!("the host didn't respond to git {command}, retrying");
+            cx.background_executor()
+                .timer(REMOTE_REQUEST_RETRY_BACKOFF * 2u32.pow(attempts - 1))
+                .await;
+        }
+    }
+
+    /// Waits for `response`, forwarding the host's progress in the meantime. Returns `None` if
+    /// the host goes `timeout` without responding or reporting progress.
+    async fn wait_for_response<R>(
+        &mut self,
+        response: impl Future<Output = Result<R>>,
+        timeout: Option<Duration>,
+        cx: &AsyncApp,
+    ) -> Option<Result<R>> {
+        let mut response = pin!(response.fuse());
+        loop {
+            let mut timer = match timeout {
+                Some(timeout) => cx.background_executor().timer(timeout).boxed_local(),
+                None => future::pending().boxed_local(),
+            }
+            .fuse();
+            futures::select_biased! {
+                response = response => return Some(response),
+                progress = self.host_progress_rx.select_next_some() => {
+                    self.progress_tx.unbounded_send(progress).ok();
+                }
+                _ = timer => {
+                    // The host's delegate is taken out while the user answers one of its
+                    // prompts, during which the host is waiting on the user, not stalled.
+                    if self.askpass_delegates.lock().contains_key(&self.askpass_id) {
+                        return None;
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl Drop for RemoteCommandRequest {
+    fn drop(&mut self) {
+        let askpass_delegate = self.askpass_delegates.lock().remove(&self.askpass_id);
+        debug_assert!(askpass_delegate.is_some());
+        self.remote_progress_senders.lock().remove(&self.askpass_id);
+    }
+}
+
+fn make_remote_progress_sender(
+    this: Entity<GitStore>,
+    project_id: u64,
+    repository_id: RepositoryId,
+    askpass_id: u64,
+    cx: &mut AsyncApp,
+) -> mpsc::UnboundedSender<RemoteProgress> {
+    let (progress_tx, mut progress_rx) = mpsc::unbounded::<RemoteProgress>();
+    cx.spawn(async move |cx| {
+        while let Some(progress) = progress_rx.next().await {
+            let sent = this.update(cx, |this, _| {
+                let Some((client, _)) = this.downstream_client() else {
+                    return;
+                };
+                client
+                    .send(proto::RemoteProgress {
+                        project_id,
+                        repository_id: repository_id.to_proto(),
+                        askpass_id,
+                        phase: progress.phase,
+                        percent: progress.percent.map(u32::from),
+                        completed: progress.completed,
+                        total: progress.total,
+                    })
+                    .log_err();
+            });
+            if sent.is_err() {
+                return;
+            }
+        }
+    })
+    .detach();
+    progress_tx
+}
+
+fn make_remote_delegate(
+    this: Entity<GitStore>,
+    project_id: u64,
+    repository_id: RepositoryId,
+    askpass_id: u64,
+    cx: &mut AsyncApp,
+) -> AskPassDelegate {
+    AskPassDelegate::new(cx, move |prompt, tx, cx| {
+        this.update(cx, |this, cx| {
+            let Some((client, _)) = this.downstream_client() else {
+                return;
+            };
+            let response = client.request(proto::AskPassRequest {
+                project_id,
+                repository_id: repository_id.to_proto(),
+                askpass_id,
+                prompt,
+            });
+            cx.spawn(async move |_, _| {
+                let mut response = response.await?.response;
+                tx.send(EncryptedPassword::try_from(response.as_ref())?)
+                    .ok();
+                response.zeroize();
+                anyhow::Ok(())
+            })
+            .detach_and_log_err(cx);
+        })
+        .log_err();
+    })
+}
+
+impl RepositoryId {
+    pub fn to_proto(self) -> u64 {
+        self.0
+    }
+
+    pub fn from_proto(id: u64) -> Self {
+        RepositoryId(id)
+    }
+}
+
+impl RepositorySnapshot {
+    fn empty(id: RepositoryId, work_directory_abs_path: Arc<Path>, path_style: PathStyle) -> Self {
+        Self {
+            id,
+            statuses_by_path: Default::default(),
+            work_directory_abs_path,
+            branch: None,
+            head_commit: None,
+            head_state: None,
+            scan_id: 0,
+            merge: Default::default(),
+            remote_origin_url: None,
+            remote_upstream_url: None,
+            stash_entries: Default::default(),
+            rebase: None,
+            lfs_statuses: Default::default(),
+            path_style,
+        }
+    }
+
+    fn initial_update(&self, project_id: u64) -> proto::UpdateRepository {
+        proto::UpdateRepository {
+            branch_summary: self.branch.as_ref().map(branch_to_proto),
+            head_commit_details: self.head_commit.as_ref().map(commit_details_to_proto),
+            head_state: self.head_state.as_ref().map(head_state_to_proto),
+            updated_statuses: self
+                .statuses_by_path
+                .iter()
+                .map(|entry| entry.to_proto())
+                .collect(),
+            removed_statuses: Default::default(),
+            current_merge_conflicts: self
+                .merge
+                .conflicted_paths
+                .iter()
+                .map(|repo_path| repo_path.to_proto())
+                .collect(),
+            merge_message: self.merge.message.as_ref().map(|msg| msg.to_string()),
+            project_id,
+            id: self.id.to_proto(),
+            abs_path: self.work_directory_abs_path.to_string_lossy().into_owned(),
+            entry_ids: vec![self.id.to_proto()],
+            scan_id: self.scan_id,
+            is_last_update: true,
+            stash_entries: self
+                .stash_entries
+                .entries
+                .iter()
+                .map(stash_to_proto)
+                .collect(),
+            rebase_state: self.rebase.as_ref().map(rebase_state_to_proto),
+            lfs_statuses: self
+                .lfs_statuses
+                .iter()
+                .map(|(path, status)| lfs_file_status_to_proto(path, status))
+                .collect(),
+        }
+    }
+
+    fn build_update(&self, old: &Self, project_id: u64) -> proto::UpdateRepository {
+        let mut updated_statuses: Vec<proto::StatusEntry> = Vec::new();
+        let mut removed_statuses: Vec<String> = Vec::new();
+
+        let mut new_statuses = self.statuses_by_path.iter().peekable();
+        let mut old_statuses = old.statuses_by_path.iter().peekable();
+
+        let mut current_new_entry = new_statuses.next();
+        let mut current_old_entry = old_statuses.next();
+        loop {
+            match (current_new_entry, current_old_entry) {
+                (Some(new_entry), Some(old_entry)) => {
+                    match new_entry.repo_path.cmp(&old_entry.repo_path) {
+                        Ordering::Less => {
+                            updated_statuses.push(new_entry.to_proto());
+                            current_new_entry = new_statuses.next();
+                        }
+                        Ordering::Equal => {
+                            if new_entry.status != old_entry.status {
+                                updated_statuses.push(new_entry.to_proto());
+                            }
+                            current_old_entry = old_statuses.next();
+                            current_new_entry = new_statuses.next();
+                        }
+                        Ordering::Greater => {
+                            removed_statuses.push(old_entry.repo_path.to_proto());
+                            current_old_entry = old_statuses.next();
+                        }
+                    }
+                }
+                (None, Some(old_entry)) => {
+                    removed_statuses.push(old_entry.repo_path.to_proto());
+                    current_old_entry = old_statuses.next();
+                }
+                (Some(new_entry), None) => {
+                    updated_statuses.push(new_entry.to_proto());
+                    current_new_entry = new_statuses.next();
+                }
+                (None, None) => break,
+            }
+        }
+
+        proto::UpdateRepository {
+            branch_summary: self.branch.as_ref().map(branch_to_proto),
+            head_commit_details: self.head_commit.as_ref().map(commit_details_to_proto),
+            head_state: self.head_state.as_ref().map(head_state_to_proto),
+            updated_statuses,
+            removed_statuses,
+            current_merge_conflicts: self
+                .merge
+                .conflicted_paths
+                .iter()
+                .map(|path| path.to_proto())
+                .collect(),
+            merge_message: self.merge.message.as_ref().map(|msg| msg.to_string()),
+            project_id,
+            id: self.id.to_proto(),
+            abs_path: self.work_directory_abs_path.to_string_lossy().into_owned(),
+            entry_ids: vec![],
+            scan_id: self.scan_id,
+            is_last_update: true,
+            stash_entries: self
+                .stash_entries
+                .entries
+                .iter()
+                .map(stash_to_proto)
+                .collect(),
+            rebase_state: self.rebase.as_ref().map(rebase_state_to_proto),
+            lfs_statuses: self
+                .lfs_statuses
+                .iter()
+                .map(|(path, status)| lfs_file_status_to_proto(path, status))
+                .collect(),
+        }
+    }
+
+    pub fn status(&self) -> impl Iterator<Item = StatusEntry> + '_ {
+        self.statuses_by_path.iter().cloned()
+    }
+
+    pub fn status_summary(&self) -> GitSummary {
+        self.statuses_by_path.summary().item_summary
+    }
+
+    pub fn status_for_path(&self, path: &RepoPath) -> Option<StatusEntry> {
+        self.statuses_by_path
+            .get(&PathKey(path.0.clone()), ())
+            .cloned()
+    }
+
+    pub fn lfs_status_for_path(&self, path: &RepoPath) -> Option<&LfsFileStatus> {
+        self.lfs_statuses.get(path)
+    }
+
+    pub fn abs_path_to_repo_path(&self, abs_path: &Path) -> Option<RepoPath> {
+        Self::abs_path_to_repo_path_inner(&self.work_directory_abs_path, abs_path, self.path_style)
+    }
+
+    #[inline]
+    fn abs_path_to_repo_path_inner(
+        work_directory_abs_path: &Path,
+        abs_path: &Path,
+        path_style: PathStyle,
+    ) -> Option<RepoPath> {
+        abs_path
+            .strip_prefix(&work_directory_abs_path)
+            .ok()
+            .and_then(|path| RepoPath::from_std_path(path, path_style).ok())
+    }
+
+    pub fn had_conflict_on_last_merge_head_change(&self, repo_path: &RepoPath) -> bool {
+        self.merge.conflicted_paths.contains(repo_path)
+    }
+
+    pub fn has_conflict(&self, repo_path: &RepoPath) -> bool {
+        let had_conflict_on_last_merge_head_change =
+            self.merge.conflicted_paths.contains(repo_path);
+        let has_conflict_currently = self
+            .status_for_path(repo_path)
+            .is_some_and(|entry| entry.status.is_conflicted());
+        had_conflict_on_last_merge_head_change || has_conflict_currently
+    }
+
+    /// This is the name that will be displayed in the repository selector for this repository.
+    pub fn display_name(&self) -> SharedString {
+        self.work_directory_abs_path
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string()
+            .into()
+    }
+}
+
+pub fn stash_to_proto(entry: &StashEntry) -> proto::StashEntry {
+    proto::StashEntry {
+        oid: entry.oid.as_bytes().to_vec(),
+        message: entry.message.clone(),
+        branch: entry.branch.clone(),
+        index: entry.index as u64,
+        timestamp: entry.timestamp,
+    }
+}
+
+fn rebase_state_to_proto(rebase: &RebaseState) -> proto::RebaseState {
+    proto::RebaseState {
+        head_name: rebase.head_name.as_ref().map(ToString::to_string),
+        onto: rebase.onto.to_string(),
+        step: rebase.step as u64,
+        total_steps: rebase.total_steps as u64,
+        interactive: rebase.interactive,
+        stopped_at: rebase.stopped_at.as_ref().map(ToString::to_string),
+    }
+}
+
+fn head_state_to_proto(head_state: &HeadState) -> proto::HeadState {
+    use proto::head_state::Kind;
+
+    let kind = match head_state {
+        HeadState::Branch(name) => Kind::Branch(name.to_string()),
+        HeadState::Detached(sha) => Kind::DetachedSha(sha.to_string()),
+        HeadState::Unborn => Kind::Unborn(true),
+    };
+    proto::HeadState { kind: Some(kind) }
+}
+
+fn proto_to_head_state(head_state: proto::HeadState) -> Option<HeadState> {
+    use proto::head_state::Kind;
+
+    Some(match head_state.kind? {
+        Kind::Branch(name) => HeadState::Branch(name.into()),
+        Kind::DetachedSha(sha) => HeadState::Detached(sha.into()),
+        Kind::Unborn(_) => HeadState::Unborn,
+    })
+}
+
+fn lfs_file_status_to_proto(path: &RepoPath, status: &LfsFileStatus) -> proto::LfsFileStatus {
+    proto::LfsFileStatus {
+        path: path.to_proto(),
+        is_pointer: status.is_pointer,
+        lock: status.lock.as_ref().map(|lock| proto::LfsLock {
+            id: lock.id.clone(),
+            owner: lock.owner.clone(),
+            locked_at: lock.locked_at.clone(),
+        }),
+    }
+}
+
+fn proto_to_lfs_file_status(status: proto::LfsFileStatus) -> Result<(RepoPath, LfsFileStatus)> {
+    let path = RepoPath::from_proto(&status.path)?;
+    let status = LfsFileStatus {
+        is_pointer: status.is_pointer,
+        lock: status.lock.map(|lock| LfsLock {
+            id: lock.id,
+            owner: lock.owner,
+            locked_at: lock.locked_at,
+        }),
+    };
+    Ok((path, status))
+}
+
+fn proto_to_rebase_state(rebase: proto::RebaseState) -> RebaseState {
+    RebaseState {
+        head_name: rebase.head_name.map(SharedString::from),
+        onto: rebase.onto.into(),
+        step: rebase.step as usize,
+        total_steps: rebase.total_steps as usize,
+        interactive: rebase.interactive,
+        stopped_at: rebase.stopped_at.map(SharedString::from),
+    }
+}
+
+pub fn proto_to_stash(entry: &proto::StashEntry) -> Result<StashEntry> {
+    Ok(StashEntry {
+        oid: Oid::from_bytes(&entry.oid)?,
+        message: entry.message.clone(),
+        index: entry.index as usize,
+        branch: entry.branch.clone(),
+        timestamp: entry.timestamp,
+    })
+}
+
+impl MergeDetails {
+    async fn load(
+        backend: &Arc<dyn GitRepository>,
+        status: &SumTree<StatusEntry>,
+        prev_snapshot: &RepositorySnapshot,
+    ) -> Result<(MergeDetails, bool)> {
+        log::debug!("load merge details");
+        let message = backend.merge_message().await;
+        let heads = backend
+            .revparse_batch(vec![
+                "MERGE_HEAD".into(),
+                "CHERRY_PICK_HEAD".into(),
+                "REBASE_HEAD".into(),
+                "REVERT_HEAD".into(),
+                "APPLY_HEAD".into(),
+            ])
+            .await
+            .log_err()
+            .unwrap_or_default()
+            .into_iter()
+            .map(|opt| opt.map(SharedString::from))
+            .collect::<Vec<_>>();
+        let merge_heads_changed = heads != prev_snapshot.merge.heads;
+        let conflicted_paths = if merge_heads_changed {
+            let current_conflicted_paths = TreeSet::from_ordered_entries(
+                status
+                    .iter()
+                    .filter(|entry| entry.status.is_conflicted())
+                    .map(|entry| entry.repo_path.clone()),
+            );
+
+            // It can happen that we run a scan while a lengthy merge is in progress
+            // that will eventually result in conflicts, but before those conflicts
+            // are reported by `git status`. Since for the moment we only care about
+            // the merge heads state for the purposes of tracking conflicts, don't update
+            // this state until we see some conflicts.
+            if heads.iter().any(Option::is_some)
+                && !prev_snapshot.merge.heads.iter().any(Option::is_some)
+                && current_conflicted_paths.is_empty()
+            {
+                log::debug!("not updating merge heads because no conflicts found");
+                return Ok((
+                    MergeDetails {
+                        message: message.map(SharedString::from),
+                        ..prev_snapshot.merge.clone()
+                    },
+                    false,
+                ));
+            }
+
+            current_conflicted_paths
+        } else {
+            prev_snapshot.merge.conflicted_paths.clone()
+        };
+        let details = MergeDetails {
+            conflicted_paths,
+            message: message.map(SharedString::from),
+            heads,
+        };
+        Ok((details, merge_heads_changed))
+    }
+}
+
+impl Repository {
+    pub fn snapshot(&self) -> RepositorySnapshot {
+        self.snapshot.clone()
+    }
+
+    fn local(
+        id: RepositoryId,
+        work_directory_abs_path: Arc<Path>,
+        dot_git_abs_path: Arc<Path>,
+        repository_dir_abs_path: Arc<Path>,
+        common_dir_abs_path: Arc<Path>,
+        project_environment: WeakEntity<ProjectEnvironment>,
+        fs: Arc<dyn Fs>,
+        git_store: WeakEntity<GitStore>,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let snapshot =
+            RepositorySnapshot::empty(id, work_directory_abs_path.clone(), PathStyle::local());
+        Repository {
+            this: cx.weak_entity(),
+            git_store,
+            snapshot,
+            commit_message_buffer: None,
+            askpass_delegates: Default::default(),
+            paths_needing_status_update: Default::default(),
+            latest_askpass_id: 0,
+            remote_progress_senders: Default::default(),
+            remote_progress: None,
+            cached_branches: None,
+            cached_remotes: None,
+            branch_cache_generation: 0,
+            job_sender: Repository::spawn_local_git_worker(
+                work_directory_abs_path,
+                dot_git_abs_path,
+                repository_dir_abs_path,
+                common_dir_abs_path,
+                project_environment,
+                fs,
+                cx,
+            ),
+            job_id: 0,
+            jobs: Default::default(),
+        }
+    }
+
+    pub(crate) fn remote(
+        id: RepositoryId,
+        work_directory_abs_path: Arc<Path>,
+        path_style: PathStyle,
+        project_id: ProjectId,
+        client: AnyProtoClient,
+        git_store: WeakEntity<GitStore>,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let snapshot = RepositorySnapshot::empty(id, work_directory_abs_path, path_style);
+        Self {
+            this: cx.weak_entity(),
+            snapshot,
+            commit_message_buffer: None,
+            git_store,
+            paths_needing_status_update: Default::default(),
+            job_sender: Self::spawn_remote_git_worker(project_id, client, cx),
+            askpass_delegates: Default::default(),
+            latest_askpass_id: 0,
+            remote_progress_senders: Default::default(),
+            remote_progress: None,
+            cached_branches: None,
+            cached_remotes: None,
+            branch_cache_generation: 0,
+            jobs: Default::default(),
+            job_id: 0,
+        }
+    }
+
+    pub fn git_store(&self) -> Option<Entity<GitStore>> {
+        self.git_store.upgrade()
+    }
+
+    fn reload_buffer_diff_bases(&mut self, cx: &mut Context<Self>) {
+        let this = cx.weak_entity();
+        let git_store = self.git_store.clone();
+        let _ = self.send_keyed_job(
+            Some(GitJobKey::ReloadBufferDiffBases),
+            None,
+            |state, mut cx| async move {
+                let RepositoryState::Local { backend, .. } = state else {
+                    log::error!("tried to recompute diffs for a non-local repository");
+                    return Ok(());
+                };
+
+                let Some(this) = this.upgrade() else {
+                    return Ok(());
+                };
+
+                let repo_diff_state_updates = this.update(&mut cx, |this, cx| {
+                    git_store.update(cx, |git_store, cx| {
+                        git_store
+                            .diffs
+                            .iter()
+                            .filter_map(|(buffer_id, diff_state)| {
+                                let buffer_store = git_store.buffer_store.read(cx);
+                                let buffer = buffer_store.get(*buffer_id)?;
+                                let file = File::from_dyn(buffer.read(cx).file())?;
+                                let abs_path = file.worktree.read(cx).absolutize(&file.path);
+                                let repo_path = this.abs_path_to_repo_path(&abs_path)?;
+                                log::debug!(
+                                    "start reload diff bases for repo path {}",
+                                    repo_path.as_unix_str()
+                                );
+                                diff_state.update(cx, |diff_state, _| {
+                                    let has_unstaged_diff = diff_state
+                                        .unstaged_diff
+                                        .as_ref()
+                                        .is_some_and(|diff| diff.is_upgradable());
+                                    let has_uncommitted_diff = diff_state
+                                        .uncommitted_diff
+                                        .as_ref()
+                                        .is_some_and(|set| set.is_upgradable());
+
+                                    Some((
+                                        buffer,
+                                        repo_path,
+                                        has_unstaged_diff.then(|| diff_state.index_text.clone()),
+                                        has_uncommitted_diff.then(|| diff_state.head_text.clone()),
+                                    ))
+                                })
+                            })
+                            .collect::<Vec<_>>()
+                    })
+                })??;
+
+                let buffer_diff_base_changes = cx
+                    .background_spawn(async move {
+                        let mut changes = Vec::new();
+                        for (buffer, repo_path, current_index_text, current_head_text) in
+                            &repo_diff_state_updates
+                        {
+                            let index_text = if current_index_text.is_some() {
+                                backend.load_index_text(repo_path.clone()).await
+                            } else {
                                 None
                             };
                             let head_text = if current_head_text.is_some() {
@@ -3167,304 +4750,1682 @@ impl Repository {
                                 None
                             };
 
-                            let change =
-                                match (current_index_text.as_ref(), current_head_text.as_ref()) {
-                                    (Some(current_index), Some(current_head)) => {
-                                        let index_changed =
-                                            index_text.as_ref() != current_index.as_deref();
-                                        let head_changed =
-                                            head_text.as_ref() != current_head.as_deref();
-                                        if index_changed && head_changed {
-                                            if index_text == head_text {
-                                                Some(DiffBasesChange::SetBoth(head_text))
-                                            } else {
-                                                Some(DiffBasesChange::SetEach {
-                                                    index: index_text,
-                                                    head: head_text,
-                                                })
-                                            }
-                                        } else if index_changed {
-                                            Some(DiffBasesChange::SetIndex(index_text))
-                                        } else if head_changed {
-                                            Some(DiffBasesChange::SetHead(head_text))
-                                        } else {
-                                            None
-                                        }
-                                    }
-                                    (Some(current_index), None) => {
-                                        let index_changed =
-                                            index_text.as_ref() != current_index.as_deref();
-                                        index_changed
-                                            .then_some(DiffBasesChange::SetIndex(index_text))
-                                    }
-                                    (None, Some(current_head)) => {
-                                        let head_changed =
-                                            head_text.as_ref() != current_head.as_deref();
-                                        head_changed.then_some(DiffBasesChange::SetHead(head_text))
-                                    }
-                                    (None, None) => None,
-                                };
+                            let change =
+                                match (current_index_text.as_ref(), current_head_text.as_ref()) {
+                                    (Some(current_index), Some(current_head)) => {
+                                        let index_changed =
+                                            index_text.as_ref() != current_index.as_deref();
+                                        let head_changed =
+                                            head_text.as_ref() != current_head.as_deref();
+                                        if index_changed && head_changed {
+                                            if index_text == head_text {
+                                                Some(DiffBasesChange::SetBoth(head_text))
+                                            } else {
+                                                Some(DiffBasesChange::SetEach {
+                                                    index: index_text,
+                                                    head: head_text,
+                                                })
+                                            }
+                                        } else if index_changed {
+                                            Some(DiffBasesChange::SetIndex(index_text))
+                                        } else if head_changed {
+                                            Some(DiffBasesChange::SetHead(head_text))
+                                        } else {
+                                            None
+                                        }
+                                    }
+                                    (Some(current_index), None) => {
+                                        let index_changed =
+                                            index_text.as_ref() != current_index.as_deref();
+                                        index_changed
+                                            .then_some(DiffBasesChange::SetIndex(index_text))
+                                    }
+                                    (None, Some(current_head)) => {
+                                        let head_changed =
+                                            head_text.as_ref() != current_head.as_deref();
+                                        head_changed.then_some(DiffBasesChange::SetHead(head_text))
+                                    }
+                                    (None, None) => None,
+                                };
+
+                            changes.push((buffer.clone(), change))
+                        }
+                        changes
+                    })
+                    .await;
+
+                git_store.update(&mut cx, |git_store, cx| {
+                    for (buffer, diff_bases_change) in buffer_diff_base_changes {
+                        let buffer_snapshot = buffer.read(cx).text_snapshot();
+                        let buffer_id = buffer_snapshot.remote_id();
+                        let Some(diff_state) = git_store.diffs.get(&buffer_id) else {
+                            continue;
+                        };
+
+                        let downstream_client = git_store.downstream_client();
+                        diff_state.update(cx, |diff_state, cx| {
+                            use proto::update_diff_bases::Mode;
+
+                            if let Some((diff_bases_change, (client, project_id))) =
+                                diff_bases_change.clone().zip(downstream_client)
+                            {
+                                let (staged_text, committed_text, mode) = match diff_bases_change {
+                                    DiffBasesChange::SetIndex(index) => {
+                                        (index, None, Mode::IndexOnly)
+                                    }
+                                    DiffBasesChange::SetHead(head) => (None, head, Mode::HeadOnly),
+                                    DiffBasesChange::SetEach { index, head } => {
+                                        (index, head, Mode::IndexAndHead)
+                                    }
+                                    DiffBasesChange::SetBoth(text) => {
+                                        (None, text, Mode::IndexMatchesHead)
+                                    }
+                                };
+                                client
+                                    .send(proto::UpdateDiffBases {
+                                        project_id: project_id.to_proto(),
+                                        buffer_id: buffer_id.to_proto(),
+                                        staged_text,
+                                        committed_text,
+                                        mode: mode as i32,
+                                    })
+                                    .log_err();
+                            }
+
+                            diff_state.diff_bases_changed(buffer_snapshot, diff_bases_change, cx);
+                        });
+                    }
+                })
+            },
+        );
+    }
+
+    pub fn send_job<F, Fut, R>(
+        &mut self,
+        status: Option<SharedString>,
+        job: F,
+    ) -> oneshot::Receiver<R>
+    where
+        F: FnOnce(RepositoryState, AsyncApp) -> Fut + 'static,
+        Fut: Future<Output = R> + 'static,
+        R: Send + 'static,
+    {
+        self.send_keyed_job(None, status, job)
+    }
+
+    fn send_keyed_job<F, Fut, R>(
+        &mut self,
+        key: Option<GitJobKey>,
+        status: Option<SharedString>,
+        job: F,
+    ) -> oneshot::Receiver<R>
+    where
+        F: FnOnce(RepositoryState, AsyncApp) -> Fut + 'static,
+        Fut: Future<Output = R> + 'static,
+        R: Send + 'static,
+    {
+        let (result_tx, result_rx) = futures::channel::oneshot::channel();
+        let job_id = post_inc(&mut self.job_id);
+        self.jobs.insert(
+            job_id,
+            PendingJob {
+                key: key.clone(),
+                message: status.clone(),
+                queued_at: Instant::now(),
+                started_at: None,
+            },
+        );
+        let this = self.this.clone();
+        let sent = self.job_sender.unbounded_send(GitJob {
+            id: job_id,
+            key,
+            job: Box::new(move |state, cx: &mut AsyncApp| {
+                let job = job(state, cx.clone());
+                cx.spawn(async move |cx| {
+                    let _slow_job_watch = this
+                        .update(cx, |this, cx| {
+                            if let Some(job) = this.jobs.get_mut(&job_id) {
+                                job.started_at = Some(Instant::now());
+                            }
+                            if status.is_some() {
+                                cx.notify();
+                            }
+                            this.watch_for_slow_job(job_id, cx)
+                        })
+                        .ok()
+                        .flatten();
+                    let result = job.await;
+
+                    this.update(cx, |this, cx| {
+                        this.jobs.remove(&job_id);
+                        cx.notify();
+                    })
+                    .ok();
+
+                    result_tx.send(result).ok();
+                })
+            }),
+        });
+        if sent.is_err() {
+            self.jobs.remove(&job_id);
+        }
+        result_rx
+    }
+
+    /// Sends a job that writes the index, emitting [`IndexLocked`] if it fails because another git
+    /// process kept the index locked.
+    fn send_index_job<F, Fut, T>(
+        &mut self,
+        key: Option<GitJobKey>,
+        status: Option<SharedString>,
+        job: F,
+    ) -> oneshot::Receiver<Result<T>>
+    where
+        F: FnOnce(RepositoryState, AsyncApp) -> Fut + 'static,
+        Fut: Future<Output = Result<T>> + 'static,
+        T: Send + 'static,
+    {
+        let this = self.this.clone();
+        self.send_keyed_job(key, status, move |state, mut cx| async move {
+            let result = job(state, cx.clone()).await;
+            if let Err(error) = &result
+                && git::repository::IndexLocked::is_cause_of(error)
+            {
+                this.update(&mut cx, |_, cx| cx.emit(IndexLocked)).ok();
+            }
+            result
+        })
+    }
+
+    /// The jobs that are queued or running, in the order that they were queued.
+    pub fn job_queue_status(&self) -> Vec<GitJobStatus> {
+        let now = Instant::now();
+        self.jobs
+            .values()
+            .map(|job| self.job_status(job, now))
+            .collect()
+    }
+
+    fn job_status(&self, job: &PendingJob, now: Instant) -> GitJobStatus {
+        GitJobStatus {
+            repository_id: self.id,
+            message: job.message.clone(),
+            key: job.key.clone(),
+            queued_for: job
+                .started_at
+                .unwrap_or(now)
+                .saturating_duration_since(job.queued_at),
+            running_for: job
+                .started_at
+                .map(|started_at| now.saturating_duration_since(started_at)),
+        }
+    }
+
+    /// Reports the job as slow if it's still running once the configured threshold has passed.
+    fn watch_for_slow_job(&self, job_id: JobId, cx: &mut Context<Self>) -> Option<Task<()>> {
+        let threshold = ProjectSettings::get_global(cx)
+            .git
+            .slow_operation_threshold?;
+        Some(cx.spawn(async move |this, cx| {
+            cx.background_executor().timer(threshold).await;
+            this.update(cx, |this, cx| {
+                if let Some(job) = this.jobs.get(&job_id) {
+                    let status = this.job_status(job, Instant::now());
+                    cx.emit(SlowJob(status));
+                }
+            })
+            .ok();
+        }))
+    }
+
+    pub fn set_as_active_repository(&self, cx: &mut Context<Self>) {
+        let Some(git_store) = self.git_store.upgrade() else {
+            return;
+        };
+        let entity = cx.entity();
+        git_store.update(cx, |git_store, cx| {
+            let Some((&id, _)) = git_store
+                .repositories
+                .iter()
+                .find(|(_, handle)| *handle == &entity)
+            else {
+                return;
+            };
+            git_store.choose_active_repository(id, cx);
+        });
+    }
+
+    pub fn cached_status(&self) -> impl '_ + Iterator<Item = StatusEntry> {
+        self.snapshot.status()
+    }
+
+    pub fn cached_stash(&self) -> GitStash {
+        self.snapshot.stash_entries.clone()
+    }
+
+    pub fn repo_path_to_project_path(&self, path: &RepoPath, cx: &App) -> Option<ProjectPath> {
+        let git_store = self.git_store.upgrade()?;
+        let worktree_store = git_store.read(cx).worktree_store.read(cx);
+        let abs_path = self
+            .snapshot
+            .work_directory_abs_path
+            .join(path.as_std_path());
+        let abs_path = SanitizedPath::new(&abs_path);
+        let (worktree, relative_path) = worktree_store.find_worktree(abs_path, cx)?;
+        Some(ProjectPath {
+            worktree_id: worktree.read(cx).id(),
+            path: relative_path,
+        })
+    }
+
+    pub fn project_path_to_repo_path(&self, path: &ProjectPath, cx: &App) -> Option<RepoPath> {
+        let git_store = self.git_store.upgrade()?;
+        let worktree_store = git_store.read(cx).worktree_store.read(cx);
+        let abs_path = worktree_store.absolutize(path, cx)?;
+        self.snapshot.abs_path_to_repo_path(&abs_path)
+    }
+
+    pub fn contains_sub_repo(&self, other: &Entity<Self>, cx: &App) -> bool {
+        other
+            .read(cx)
+            .snapshot
+            .work_directory_abs_path
+            .starts_with(&self.snapshot.work_directory_abs_path)
+    }
+
+    /// The trailers at the end of the message in the commit message buffer, if it's open.
+    pub fn commit_message_trailers(&self, cx: &App) -> Vec<Trailer> {
+        self.commit_message_buffer
+            .as_ref()
+            .map(|buffer| parse_trailers(&buffer.read(cx).text()))
+            .unwrap_or_default()
+    }
+
+    pub fn open_commit_buffer(
+        &mut self,
+        languages: Option<Arc<LanguageRegistry>>,
+        buffer_store: Entity<BufferStore>,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Entity<Buffer>>> {
+        let id = self.id;
+        if let Some(buffer) = self.commit_message_buffer.clone() {
+            return Task::ready(Ok(buffer));
+        }
+        let this = cx.weak_entity();
+
+        let rx = self.send_job(None, move |state, mut cx| async move {
+            let Some(this) = this.upgrade() else {
+                bail!("git store was dropped");
+            };
+            match state {
+                RepositoryState::Local { backend, .. } => {
+                    // An in-progress merge's message is what the commit has to say, so it wins
+                    // over the template.
+                    let initial_message = match backend.merge_message().await {
+                        Some(merge_message) => Some(merge_message),
+                        None => backend.commit_template().await,
+                    };
+                    this.update(&mut cx, |_, cx| {
+                        Self::open_local_commit_buffer(languages, buffer_store, initial_message, cx)
+                    })?
+                    .await
+                }
+                RepositoryState::Remote { project_id, client } => {
+                    let request = client.request(proto::OpenCommitMessageBuffer {
+                        project_id: project_id.0,
+                        repository_id: id.to_proto(),
+                    });
+                    let response = request.await.context("requesting to open commit buffer")?;
+                    let buffer_id = BufferId::new(response.buffer_id)?;
+                    let buffer = buffer_store
+                        .update(&mut cx, |buffer_store, cx| {
+                            buffer_store.wait_for_remote_buffer(buffer_id, cx)
+                        })?
+                        .await?;
+                    if let Some(language_registry) = languages {
+                        let git_commit_language =
+                            language_registry.language_for_name("Git Commit").await?;
+                        buffer.update(&mut cx, |buffer, cx| {
+                            buffer.set_language(Some(git_commit_language), cx);
+                        })?;
+                    }
+                    this.update(&mut cx, |this, _| {
+                        this.commit_message_buffer = Some(buffer.clone());
+                    })?;
+                    Ok(buffer)
+                }
+            }
+        });
+
+        cx.spawn(|_, _: &mut AsyncApp| async move { rx.await? })
+    }
+
+    fn open_local_commit_buffer(
+        language_registry: Option<Arc<LanguageRegistry>>,
+        buffer_store: Entity<BufferStore>,
+        initial_message: Option<String>,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Entity<Buffer>>> {
+        cx.spawn(async move |repository, cx| {
+            let buffer = buffer_store
+                .update(cx, |buffer_store, cx| buffer_store.create_buffer(false, cx))?
+                .await?;
+            if let Some(initial_message) = initial_message {
+                buffer.update(cx, |buffer, cx| buffer.set_text(initial_message, cx))?;
+            }
+
+            if let Some(language_registry) = language_registry {
+                let git_commit_language = language_registry.language_for_name("Git Commit").await?;
+                buffer.update(cx, |buffer, cx| {
+                    buffer.set_language(Some(git_commit_language), cx);
+                })?;
+            }
+
+            repository.update(cx, |repository, _| {
+                repository.commit_message_buffer = Some(buffer.clone());
+            })?;
+            Ok(buffer)
+        })
+    }
+
+    pub fn checkout_files(
+        &mut self,
+        commit: &str,
+        paths: Vec<RepoPath>,
+        _cx: &mut App,
+    ) -> oneshot::Receiver<Result<()>> {
+        let commit = commit.to_string();
+        let id = self.id;
+
+        self.send_index_job(
+            None,
+            Some(format!("git checkout {}", commit).into()),
+            move |git_repo, _| async move {
+                match git_repo {
+                    RepositoryState::Local {
+                        backend,
+                        environment,
+                        ..
+                    } => {
+                        backend
+                            .checkout_files(commit, paths, environment.clone())
+                            .await
+                    }
+                    RepositoryState::Remote { project_id, client } => {
+                        client
+                            .request(proto::GitCheckoutFiles {
+                                project_id: project_id.0,
+                                repository_id: id.to_proto(),
+                                commit,
+                                paths: paths.into_iter().map(|p| p.to_proto()).collect(),
+                            })
+                            .await?;
+
+                        Ok(())
+                    }
+                }
+            },
+        )
+    }
+
+    pub fn reset(
+        &mut self,
+        commit: String,
+        reset_mode: ResetMode,
+        _cx: &mut App,
+    ) -> oneshot::Receiver<Result<()>> {
+        let id = self.id;
+
+        self.send_index_job(None, None, move |git_repo, _| async move {
+            match git_repo {
+                RepositoryState::Local {
+                    backend,
+                    environment,
+                    ..
+                } => backend.reset(commit, reset_mode, environment).await,
+                RepositoryState::Remote { project_id, client } => {
+                    client
+                        .request(proto::GitReset {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                            commit,
+                            mode: match reset_mode {
+                                ResetMode::Soft => git_reset::ResetMode::Soft.into(),
+                                ResetMode::Mixed => git_reset::ResetMode::Mixed.into(),
+                                ResetMode::Hard => git_reset::ResetMode::Hard.into(),
+                            },
+                        })
+                        .await?;
+
+                    Ok(())
+                }
+            }
+        })
+    }
+
+    /// Returns the paths whose working copy a hard reset to `commit` would discard, so that
+    /// callers can confirm before resetting. Besides tracked files that differ from `commit`, this
+    /// includes untracked files that `commit` tracks, since the reset overwrites them.
+    pub fn paths_discarded_by_hard_reset(
+        &mut self,
+        commit: String,
+    ) -> oneshot::Receiver<Result<Vec<RepoPath>>> {
+        let id = self.id;
+        self.send_job(None, move |git_repo, _cx| async move {
+            match git_repo {
+                RepositoryState::Local { backend, .. } => {
+                    backend.paths_discarded_by_hard_reset(commit).await
+                }
+                RepositoryState::Remote { project_id, client } => {
+                    let response = client
+                        .request(proto::GitPathsDiscardedByHardReset {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                            commit,
+                        })
+                        .await?;
+
+                    response
+                        .paths
+                        .iter()
+                        .map(|path| RepoPath::from_proto(path))
+                        .collect()
+                }
+            }
+        })
+    }
+
+    pub fn show(&mut self, commit: String) -> oneshot::Receiver<Result<CommitDetails>> {
+        let id = self.id;
+        self.send_job(None, move |git_repo, _cx| async move {
+            match git_repo {
+                RepositoryState::Local { backend, .. } => backend.show(commit).await,
+                RepositoryState::Remote { project_id, client } => {
+                    let resp = client
+                        .request(proto::GitShow {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                            commit,
+                        })
+                        .await?;
+
+                    Ok(CommitDetails {
+                        sha: resp.sha.into(),
+                        message: resp.message.into(),
+                        commit_timestamp: resp.commit_timestamp,
+                        author_email: resp.author_email.into(),
+                        author_name: resp.author_name.into(),
+                    })
+                }
+            }
+        })
+    }
+
+    /// Lists a page of the commits reachable from `HEAD`, newest first. Older commits are loaded
+    /// by skipping the ones that were already listed.
+    pub fn log(&mut self, options: LogOptions) -> oneshot::Receiver<Result<Vec<CommitDetails>>> {
+        let id = self.id;
+        self.send_job(None, move |git_repo, _cx| async move {
+            match git_repo {
+                RepositoryState::Local { backend, .. } => backend.log(options).await,
+                RepositoryState::Remote { project_id, client } => {
+                    let response = client
+                        .request(proto::GitLog {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                            limit: options.limit as u64,
+                            skip: options.skip as u64,
+                            path: options.path.as_ref().map(|path| path.to_proto()),
+                            author: options.author,
+                        })
+                        .await?;
+
+                    Ok(response
+                        .commits
+                        .into_iter()
+                        .map(|commit| CommitDetails {
+                            sha: commit.sha.into(),
+                            message: commit.message.into(),
+                            commit_timestamp: commit.commit_timestamp,
+                            author_email: commit.author_email.into(),
+                            author_name: commit.author_name.into(),
+                        })
+                        .collect())
+                }
+            }
+        })
+    }
+
+    /// Loads a page of the commits to draw in a branch graph. Large histories are loaded a page at
+    /// a time by skipping the commits loaded already.
+    pub fn commit_graph(
+        &mut self,
+        options: CommitGraphOptions,
+    ) -> oneshot::Receiver<Result<CommitGraph>> {
+        let id = self.id;
+        self.send_job(None, move |git_repo, _cx| async move {
+            match git_repo {
+                RepositoryState::Local { backend, .. } => backend.commit_graph(options).await,
+                RepositoryState::Remote { project_id, client } => {
+                    let response = client
+                        .request(proto::GitCommitGraph {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                            range: options.range,
+                            limit: options.limit as u64,
+                            skip: options.skip as u64,
+                        })
+                        .await?;
+
+                    let commits = response
+                        .commits
+                        .into_iter()
+                        .map(|commit| {
+                            Ok(CommitGraphNode {
+                                sha: commit.sha.parse()?,
+                                parents: commit
+                                    .parents
+                                    .iter()
+                                    .map(|parent| parent.parse())
+                                    .collect::<Result<_>>()?,
+                                refs: commit.refs.into_iter().map(SharedString::from).collect(),
+                                commit_timestamp: commit.commit_timestamp,
+                                author_name: commit.author_name.into(),
+                                subject: commit.subject.into(),
+                            })
+                        })
+                        .collect::<Result<_>>()?;
+                    Ok(CommitGraph {
+                        commits,
+                        has_more: response.has_more,
+                    })
+                }
+            }
+        })
+    }
+
+    /// Lists the commits that changed the file at `path`, newest first, along with the path the
+    /// file had in each of them. With `follow_renames`, the history continues past renames.
+    pub fn file_history(
+        &mut self,
+        path: RepoPath,
+        follow_renames: bool,
+    ) -> oneshot::Receiver<Result<Vec<FileHistoryEntry>>> {
+        let id = self.id;
+        self.send_job(None, move |git_repo, _cx| async move {
+            match git_repo {
+                RepositoryState::Local { backend, .. } => {
+                    backend.file_history(path, follow_renames).await
+                }
+                RepositoryState::Remote { project_id, client } => {
+                    let response = client
+                        .request(proto::GitFileHistory {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                            path: path.to_proto(),
+                            follow_renames,
+                        })
+                        .await?;
+
+                    response
+                        .entries
+                        .iter()
+                        .map(|entry| {
+                            Ok(FileHistoryEntry {
+                                commit: proto_to_commit_details(
+                                    entry.commit.as_ref().context("missing commit")?,
+                                ),
+                                path: RepoPath::from_proto(&entry.path)?,
+                            })
+                        })
+                        .collect()
+                }
+            }
+        })
+    }
+
+    /// Builds a link to the file at `path` on the website of the remote that the current branch
+    /// tracks, or `origin`. The link is to `rev` if given, and otherwise to `HEAD`.
+    pub fn permalink(
+        &mut self,
+        path: RepoPath,
+        selection: Option<Range<u32>>,
+        rev: Option<String>,
+    ) -> oneshot::Receiver<Result<url::Url>> {
+        let id = self.id;
+        let remote = self
+            .snapshot
+            .branch
+            .as_ref()
+            .and_then(|branch| branch.upstream.as_ref()?.remote_name())
+            .unwrap_or("origin")
+            .to_string();
+        self.send_job(None, move |git_repo, cx| async move {
+            match git_repo {
+                RepositoryState::Local { backend, .. } => {
+                    let sha = match rev {
+                        Some(rev) => backend
+                            .revparse_batch(vec![rev.clone()])
+                            .await?
+                            .into_iter()
+                            .next()
+                            .flatten()
+                            .with_context(|| format!("revision {rev:?} not found"))?,
+                        None => backend.head_sha().await.context("reading HEAD SHA")?,
+                    };
+                    let (provider, parsed_remote) = hosting_remote(&backend, &remote, &cx)?;
+                    Ok(provider.build_permalink(
+                        parsed_remote,
+                        BuildPermalinkParams::new(&sha, &path, selection),
+                    ))
+                }
+                RepositoryState::Remote { project_id, client } => {
+                    let response = client
+                        .request(proto::GitPermalink {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                            path: path.to_proto(),
+                            selection: selection.map(|selection| proto::Range {
+                                start: selection.start as u64,
+                                end: selection.end as u64,
+                            }),
+                            rev,
+                        })
+                        .await?;
+                    url::Url::parse(&response.url).context("failed to parse permalink")
+                }
+            }
+        })
+    }
+
+    /// Builds a link to the page for opening a pull request from `branch`, on the website of the
+    /// remote that the branch tracks, or `origin`.
+    pub fn open_pull_request_url(&mut self, branch: String) -> oneshot::Receiver<Result<url::Url>> {
+        let id = self.id;
+        self.send_job(None, move |git_repo, cx| async move {
+            match git_repo {
+                RepositoryState::Local { backend, .. } => {
+                    let branches = backend.branches().await?;
+                    // The branch may have a different name on the remote.
+                    let (remote, remote_branch) = branches
+                        .iter()
+                        .find(|candidate| candidate.name() == branch)
+                        .and_then(|branch| {
+                            let upstream = branch.upstream.as_ref()?;
+                            let remote = upstream.remote_name()?;
+                            let remote_branch = upstream
+                                .stripped_ref_name()?
+                                .strip_prefix(remote)?
+                                .strip_prefix('/')?;
+                            Some((remote.to_string(), remote_branch.to_string()))
+                        })
+                        .unwrap_or_else(|| ("origin".to_string(), branch));
+                    let (provider, parsed_remote) = hosting_remote(&backend, &remote, &cx)?;
+                    provider
+                        .build_create_pull_request_url(&parsed_remote, &remote_branch)
+                        .with_context(|| format!("{} doesn't have pull requests", provider.name()))
+                }
+                RepositoryState::Remote { project_id, client } => {
+                    let response = client
+                        .request(proto::GitOpenPullRequestUrl {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                            branch,
+                        })
+                        .await?;
+                    url::Url::parse(&response.url).context("failed to parse pull request URL")
+                }
+            }
+        })
+    }
+
+    pub fn load_commit_diff(&mut self, commit: String) -> oneshot::Receiver<Result<CommitDiff>> {
+        let id = self.id;
+        self.send_job(None, move |git_repo, cx| async move {
+            match git_repo {
+                RepositoryState::Local { backend, .. } => backend.load_commit(commit, cx).await,
+                RepositoryState::Remote {
+                    client, project_id, ..
+                } => {
+                    let response = client
+                        .request(proto::LoadCommitDiff {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                            commit,
+                        })
+                        .await?;
+                    Ok(CommitDiff {
+                        files: response
+                            .files
+                            .into_iter()
+                            .map(|file| {
+                                Ok(CommitFile {
+                                    path: RepoPath::from_proto(&file.path)?,
+                                    old_text: file.old_text,
+                                    new_text: file.new_text,
+                                })
+                            })
+                            .collect::<Result<Vec<_>>>()?,
+                    })
+                }
+            }
+        })
+    }
+
+    fn buffer_store(&self, cx: &App) -> Option<Entity<BufferStore>> {
+        Some(self.git_store.upgrade()?.read(cx).buffer_store.clone())
+    }
+
+    /// Splits off the paths that are ignored and have no status, meaning they're untracked.
+    /// Staging those would add them to the index in spite of the ignore rules.
+    fn partition_ignored_paths(
+        &self,
+        paths: Vec<RepoPath>,
+        cx: &App,
+    ) -> (Vec<RepoPath>, Vec<RepoPath>) {
+        let Some(git_store) = self.git_store.upgrade() else {
+            return (paths, Vec::new());
+        };
+        let worktree_store = git_store.read(cx).worktree_store.read(cx);
+        paths.into_iter().partition(|path| {
+            self.snapshot.status_for_path(path).is_some()
+                || self
+                    .repo_path_to_project_path(path, cx)
+                    .and_then(|project_path| worktree_store.entry_for_path(&project_path, cx))
+                    .is_none_or(|entry| !entry.is_ignored)
+        })
+    }
+
+    /// Stages the given paths, except for the untracked ones that are ignored.
+    pub fn stage_entries(
+        &self,
+        entries: Vec<RepoPath>,
+        cx: &mut Context<Self>,
+    ) -> Task<anyhow::Result<StagingReport>> {
+        let (entries, skipped_ignored) = self.partition_ignored_paths(entries, cx);
+        if entries.is_empty() {
+            return Task::ready(Ok(StagingReport {
+                skipped_ignored,
+                ..StagingReport::default()
+            }));
+        }
+        let id = self.id;
+
+        let mut save_futures = Vec::new();
+        if let Some(buffer_store) = self.buffer_store(cx) {
+            buffer_store.update(cx, |buffer_store, cx| {
+                for path in &entries {
+                    let Some(project_path) = self.repo_path_to_project_path(path, cx) else {
+                        continue;
+                    };
+                    if let Some(buffer) = buffer_store.get_by_path(&project_path)
+                        && buffer
+                            .read(cx)
+                            .file()
+                            .is_some_and(|file| file.disk_state().exists())
+                    {
+                        save_futures.push(buffer_store.save_buffer(buffer, cx));
+                    }
+                }
+            })
+        }
+
+        cx.spawn(async move |this, cx| {
+            for save_future in save_futures {
+                save_future.await?;
+            }
+
+            let mut report = this
+                .update(cx, |this, _| {
+                    this.send_index_job(None, None, move |git_repo, _cx| async move {
+                        match git_repo {
+                            RepositoryState::Local {
+                                backend,
+                                environment,
+                                ..
+                            } => {
+                                let backend = &backend;
+                                update_index_by_path(entries, move |paths| {
+                                    backend.stage_paths(paths, environment.clone())
+                                })
+                                .await
+                            }
+                            RepositoryState::Remote { project_id, client } => {
+                                let response = client
+                                    .request(proto::Stage {
+                                        project_id: project_id.0,
+                                        repository_id: id.to_proto(),
+                                        paths: entries
+                                            .into_iter()
+                                            .map(|repo_path| repo_path.to_proto())
+                                            .collect(),
+                                    })
+                                    .await
+                                    .context("sending stage request")?;
+                                StagingReport::from_proto(response)
+                            }
+                        }
+                    })
+                })?
+                .await??;
+
+            report.skipped_ignored.extend(skipped_ignored);
+            Ok(report)
+        })
+    }
+
+    /// Unstages the given paths, except for the untracked ones that are ignored.
+    pub fn unstage_entries(
+        &self,
+        entries: Vec<RepoPath>,
+        cx: &mut Context<Self>,
+    ) -> Task<anyhow::Result<StagingReport>> {
+        let (entries, skipped_ignored) = self.partition_ignored_paths(entries, cx);
+        if entries.is_empty() {
+            return Task::ready(Ok(StagingReport {
+                skipped_ignored,
+                ..StagingReport::default()
+            }));
+        }
+        let id = self.id;
+
+        let mut save_futures = Vec::new();
+        if let Some(buffer_store) = self.buffer_store(cx) {
+            buffer_store.update(cx, |buffer_store, cx| {
+                for path in &entries {
+                    let Some(project_path) = self.repo_path_to_project_path(path, cx) else {
+                        continue;
+                    };
+                    if let Some(buffer) = buffer_store.get_by_path(&project_path)
+                        && buffer
+                            .read(cx)
+                            .file()
+                            .is_some_and(|file| file.disk_state().exists())
+                    {
+                        save_futures.push(buffer_store.save_buffer(buffer, cx));
+                    }
+                }
+            })
+        }
+
+        cx.spawn(async move |this, cx| {
+            for save_future in save_futures {
+                save_future.await?;
+            }
+
+            let mut report = this
+                .update(cx, |this, _| {
+                    this.send_index_job(None, None, move |git_repo, _cx| async move {
+                        match git_repo {
+                            RepositoryState::Local {
+                                backend,
+                                environment,
+                                ..
+                            } => {
+                                let backend = &backend;
+                                update_index_by_path(entries, move |paths| {
+                                    backend.unstage_paths(paths, environment.clone())
+                                })
+                                .await
+                            }
+                            RepositoryState::Remote { project_id, client } => {
+                                let response = client
+                                    .request(proto::Unstage {
+                                        project_id: project_id.0,
+                                        repository_id: id.to_proto(),
+                                        paths: entries
+                                            .into_iter()
+                                            .map(|repo_path| repo_path.to_proto())
+                                            .collect(),
+                                    })
+                                    .await
+                                    .context("sending unstage request")?;
+                                StagingReport::from_proto(response)
+                            }
+                        }
+                    })
+                })?
+                .await??;
+
+            report.skipped_ignored.extend(skipped_ignored);
+            Ok(report)
+        })
+    }
+
+    pub fn stage_all(&self, cx: &mut Context<Self>) -> Task<anyhow::Result<StagingReport>> {
+        let to_stage = self
+            .cached_status()
+            .filter(|entry| !entry.status.staging().is_fully_staged())
+            .map(|entry| entry.repo_path)
+            .collect();
+        self.stage_entries(to_stage, cx)
+    }
+
+    pub fn unstage_all(&self, cx: &mut Context<Self>) -> Task<anyhow::Result<StagingReport>> {
+        let to_unstage = self
+            .cached_status()
+            .filter(|entry| entry.status.staging().has_staged())
+            .map(|entry| entry.repo_path)
+            .collect();
+        self.unstage_entries(to_unstage, cx)
+    }
+
+    /// Stages the hunks of the file's uncommitted diff that intersect the given ranges.
+    pub fn stage_hunks(
+        &mut self,
+        path: RepoPath,
+        ranges: Vec<Range<text::Anchor>>,
+        cx: &mut Context<Self>,
+    ) -> Task<anyhow::Result<()>> {
+        self.stage_or_unstage_hunks(true, path, ranges, cx)
+    }
+
+    /// Unstages the hunks of the file's uncommitted diff that intersect the given ranges.
+    pub fn unstage_hunks(
+        &mut self,
+        path: RepoPath,
+        ranges: Vec<Range<text::Anchor>>,
+        cx: &mut Context<Self>,
+    ) -> Task<anyhow::Result<()>> {
+        self.stage_or_unstage_hunks(false, path, ranges, cx)
+    }
+
+    fn stage_or_unstage_hunks(
+        &mut self,
+        stage: bool,
+        path: RepoPath,
+        ranges: Vec<Range<text::Anchor>>,
+        cx: &mut Context<Self>,
+    ) -> Task<anyhow::Result<()>> {
+        let Some(git_store) = self.git_store.upgrade() else {
+            return Task::ready(Err(anyhow!("git store was dropped")));
+        };
+        let Some(project_path) = self.repo_path_to_project_path(&path, cx) else {
+            return Task::ready(Err(anyhow!("{} is not in a worktree", path.as_unix_str())));
+        };
+        let buffer_store = git_store.read(cx).buffer_store.clone();
+        cx.spawn(async move |this, cx| {
+            let buffer = buffer_store
+                .update(cx, |buffer_store, cx| {
+                    buffer_store.open_buffer(project_path, cx)
+                })?
+                .await?;
+            let diff = git_store
+                .update(cx, |git_store, cx| {
+                    git_store.open_uncommitted_diff(buffer.clone(), cx)
+                })?
+                .await?;
+
+            // The diff computes the new index text and emits it, so that it's written by the
+            // same `WriteIndex` job as hunks staged from an editor, which coalesces writes of
+            // this file's index text.
+            let (snapshot, file_exists) = buffer.read_with(cx, |buffer, _| {
+                let file_exists = buffer.file().is_some_and(|file| file.disk_state().exists());
+                (buffer.text_snapshot(), file_exists)
+            })?;
+            diff.update(cx, |diff, cx| {
+                let mut hunks = ranges
+                    .into_iter()
+                    .flat_map(|range| {
+                        diff.hunks_intersecting_range(range, &snapshot, cx)
+                            .collect::<Vec<_>>()
+                    })
+                    .collect::<Vec<_>>();
+                hunks.sort_by(|hunk, other| {
+                    hunk.buffer_range
+                        .start
+                        .cmp(&other.buffer_range.start, &snapshot)
+                });
+                hunks.dedup_by(|hunk, other| hunk.buffer_range == other.buffer_range);
+                if !hunks.is_empty() {
+                    diff.stage_or_unstage_hunks(stage, &hunks, &snapshot, file_exists, cx);
+                }
+            })?;
+
+            // Failures to write the index are reported by `GitStoreEvent::IndexWriteError`.
+            this.update(cx, |this, _| this.barrier())?.await?;
+            Ok(())
+        })
+    }
+
+    /// Returns the commit that last changed each of the given rows of the file, taking its
+    /// unsaved edits into account.
+    pub fn blame(
+        &mut self,
+        path: RepoPath,
+        rows: Range<u32>,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<LineBlame>>> {
+        let id = self.id;
+        let Some(git_store) = self.git_store.upgrade() else {
+            return Task::ready(Err(anyhow!("git store was dropped")));
+        };
+        // Remote repositories blame the file on the host, using the host's replica of the buffer.
+        let buffer = if git_store.read(cx).is_local() {
+            let Some(project_path) = self.repo_path_to_project_path(&path, cx) else {
+                return Task::ready(Err(anyhow!("{} is not in a worktree", path.as_unix_str())));
+            };
+            let buffer_store = git_store.read(cx).buffer_store.clone();
+            Some(buffer_store.update(cx, |buffer_store, cx| {
+                buffer_store.open_buffer(project_path, cx)
+            }))
+        } else {
+            None
+        };
+
+        cx.spawn(async move |this, cx| {
+            let content = match buffer {
+                Some(buffer) => Some(
+                    buffer
+                        .await?
+                        .read_with(cx, |buffer, _| buffer.as_rope().clone())?,
+                ),
+                None => None,
+            };
+            this.update(cx, |this, _| {
+                this.send_job(None, move |state, _| async move {
+                    match state {
+                        RepositoryState::Local { backend, .. } => {
+                            let content = content.context("file content wasn't loaded")?;
+                            let blame = backend
+                                .blame(path.clone(), content)
+                                .await
+                                .with_context(|| format!("Failed to blame {:?}", path.0))?;
+                            Ok(blame.lines(rows))
+                        }
+                        RepositoryState::Remote { project_id, client } => {
+                            let response = client
+                                .request(proto::GitBlame {
+                                    project_id: project_id.0,
+                                    repository_id: id.to_proto(),
+                                    path: path.to_proto(),
+                                    start_row: rows.start,
+                                    end_row: rows.end,
+                                })
+                                .await
+                                .context("sending blame request")?;
+                            response
+                                .lines
+                                .into_iter()
+                                .map(proto_to_line_blame)
+                                .collect()
+                        }
+                    }
+                })
+            })?
+            .await?
+        })
+    }
+
+    pub fn stash_all(&mut self, cx: &mut Context<Self>) -> Task<anyhow::Result<()>> {
+        let to_stash = self.cached_status().map(|entry| entry.repo_path).collect();
+
+        self.stash_entries(to_stash, cx)
+    }
+
+    pub fn stash_entries(
+        &mut self,
+        entries: Vec<RepoPath>,
+        cx: &mut Context<Self>,
+    ) -> Task<anyhow::Result<()>> {
+        let id = self.id;
 
-                            changes.push((buffer.clone(), change))
+        cx.spawn(async move |this, cx| {
+            this.update(cx, |this, _| {
+                this.send_job(None, move |git_repo, _cx| async move {
+                    match git_repo {
+                        RepositoryState::Local {
+                            backend,
+                            environment,
+                            ..
+                        } => backend.stash_paths(entries, environment).await,
+                        RepositoryState::Remote { project_id, client } => {
+                            client
+                                .request(proto::Stash {
+                                    project_id: project_id.0,
+                                    repository_id: id.to_proto(),
+                                    paths: entries
+                                        .into_iter()
+                                        .map(|repo_path| repo_path.to_proto())
+                                        .collect(),
+                                })
+                                .await
+                                .context("sending stash request")?;
+                            Ok(())
                         }
-                        changes
-                    })
-                    .await;
+                    }
+                })
+            })?
+            .await??;
+            Ok(())
+        })
+    }
 
-                git_store.update(&mut cx, |git_store, cx| {
-                    for (buffer, diff_bases_change) in buffer_diff_base_changes {
-                        let buffer_snapshot = buffer.read(cx).text_snapshot();
-                        let buffer_id = buffer_snapshot.remote_id();
-                        let Some(diff_state) = git_store.diffs.get(&buffer_id) else {
-                            continue;
-                        };
+    pub fn stash_pop(
+        &mut self,
+        index: Option<usize>,
+        cx: &mut Context<Self>,
+    ) -> Task<anyhow::Result<()>> {
+        let id = self.id;
+        cx.spawn(async move |this, cx| {
+            this.update(cx, |this, _| {
+                this.send_job(None, move |git_repo, _cx| async move {
+                    match git_repo {
+                        RepositoryState::Local {
+                            backend,
+                            environment,
+                            ..
+                        } => backend.stash_pop(index, environment).await,
+                        RepositoryState::Remote { project_id, client } => {
+                            client
+                                .request(proto::StashPop {
+                                    project_id: project_id.0,
+                                    repository_id: id.to_proto(),
+                                    stash_index: index.map(|i| i as u64),
+                                })
+                                .await
+                                .context("sending stash pop request")?;
+                            Ok(())
+                        }
+                    }
+                })
+            })?
+            .await??;
+            Ok(())
+        })
+    }
 
-                        let downstream_client = git_store.downstream_client();
-                        diff_state.update(cx, |diff_state, cx| {
-                            use proto::update_diff_bases::Mode;
+    pub fn stash_apply(
+        &mut self,
+        index: Option<usize>,
+        cx: &mut Context<Self>,
+    ) -> Task<anyhow::Result<()>> {
+        let id = self.id;
+        cx.spawn(async move |this, cx| {
+            this.update(cx, |this, _| {
+                this.send_job(None, move |git_repo, _cx| async move {
+                    match git_repo {
+                        RepositoryState::Local {
+                            backend,
+                            environment,
+                            ..
+                        } => backend.stash_apply(index, environment).await,
+                        RepositoryState::Remote { project_id, client } => {
+                            client
+                                .request(proto::StashApply {
+                                    project_id: project_id.0,
+                                    repository_id: id.to_proto(),
+                                    stash_index: index.map(|i| i as u64),
+                                })
+                                .await
+                                .context("sending stash apply request")?;
+                            Ok(())
+                        }
+                    }
+                })
+            })?
+            .await??;
+            Ok(())
+        })
+    }
 
-                            if let Some((diff_bases_change, (client, project_id))) =
-                                diff_bases_change.clone().zip(downstream_client)
-                            {
-                                let (staged_text, committed_text, mode) = match diff_bases_change {
-                                    DiffBasesChange::SetIndex(index) => {
-                                        (index, None, Mode::IndexOnly)
-                                    }
-                                    DiffBasesChange::SetHead(head) => (None, head, Mode::HeadOnly),
-                                    DiffBasesChange::SetEach { index, head } => {
-                                        (index, head, Mode::IndexAndHead)
-                                    }
-                                    DiffBasesChange::SetBoth(text) => {
-                                        (None, text, Mode::IndexMatchesHead)
-                                    }
-                                };
+    /// Starts rebasing the current branch onto `onto`. An interactive rebase stops before its
+    /// first step, so that its todo list can be edited before calling [`Self::rebase_continue`].
+    pub fn rebase_start(
+        &mut self,
+        onto: String,
+        interactive: bool,
+        cx: &mut Context<Self>,
+    ) -> Task<anyhow::Result<()>> {
+        let id = self.id;
+        let status = format!("git rebase {onto}").into();
+        cx.spawn(async move |this, cx| {
+            this.update(cx, |this, _| {
+                this.send_job(Some(status), move |git_repo, _cx| async move {
+                    match git_repo {
+                        RepositoryState::Local {
+                            backend,
+                            environment,
+                            ..
+                        } => backend.rebase_start(onto, interactive, environment).await,
+                        RepositoryState::Remote { project_id, client } => {
+                            client
+                                .request(proto::GitRebaseStart {
+                                    project_id: project_id.0,
+                                    repository_id: id.to_proto(),
+                                    onto,
+                                    interactive,
+                                })
+                                .await
+                                .context("sending rebase start request")?;
+                            Ok(())
+                        }
+                    }
+                })
+            })?
+            .await??;
+            Ok(())
+        })
+    }
+
+    /// Continues the rebase in progress after its conflicts were resolved, or after it stopped to
+    /// edit a commit or its todo list.
+    pub fn rebase_continue(&mut self, cx: &mut Context<Self>) -> Task<anyhow::Result<()>> {
+        let id = self.id;
+        cx.spawn(async move |this, cx| {
+            this.update(cx, |this, _| {
+                this.send_job(
+                    Some("git rebase --continue".into()),
+                    move |git_repo, _cx| async move {
+                        match git_repo {
+                            RepositoryState::Local {
+                                backend,
+                                environment,
+                                ..
+                            } => backend.rebase_continue(environment).await,
+                            RepositoryState::Remote { project_id, client } => {
                                 client
-                                    .send(proto::UpdateDiffBases {
-                                        project_id: project_id.to_proto(),
-                                        buffer_id: buffer_id.to_proto(),
-                                        staged_text,
-                                        committed_text,
-                                        mode: mode as i32,
+                                    .request(proto::GitRebaseContinue {
+                                        project_id: project_id.0,
+                                        repository_id: id.to_proto(),
                                     })
-                                    .log_err();
+                                    .await
+                                    .context("sending rebase continue request")?;
+                                Ok(())
                             }
+                        }
+                    },
+                )
+            })?
+            .await??;
+            Ok(())
+        })
+    }
 
-                            diff_state.diff_bases_changed(buffer_snapshot, diff_bases_change, cx);
-                        });
+    pub fn rebase_abort(&mut self, cx: &mut Context<Self>) -> Task<anyhow::Result<()>> {
+        let id = self.id;
+        cx.spawn(async move |this, cx| {
+            this.update(cx, |this, _| {
+                this.send_job(
+                    Some("git rebase --abort".into()),
+                    move |git_repo, _cx| async move {
+                        match git_repo {
+                            RepositoryState::Local {
+                                backend,
+                                environment,
+                                ..
+                            } => backend.rebase_abort(environment).await,
+                            RepositoryState::Remote { project_id, client } => {
+                                client
+                                    .request(proto::GitRebaseAbort {
+                                        project_id: project_id.0,
+                                        repository_id: id.to_proto(),
+                                    })
+                                    .await
+                                    .context("sending rebase abort request")?;
+                                Ok(())
+                            }
+                        }
+                    },
+                )
+            })?
+            .await??;
+            Ok(())
+        })
+    }
+
+    /// Merges `branch` into the current branch. A merge that stops on conflicts succeeds, leaving
+    /// the conflicted paths and the merge message in the snapshot until the merge is committed or
+    /// aborted.
+    pub fn merge(
+        &mut self,
+        branch: String,
+        options: MergeOptions,
+        cx: &mut Context<Self>,
+    ) -> oneshot::Receiver<Result<()>> {
+        let id = self.id;
+        let updates_tx = self
+            .git_store()
+            .and_then(|git_store| match &git_store.read(cx).state {
+                GitStoreState::Local { downstream, .. } => downstream
+                    .as_ref()
+                    .map(|downstream| downstream.updates_tx.clone()),
+                _ => None,
+            });
+        let this = cx.weak_entity();
+        self.send_job(
+            Some(format!("git merge {branch}").into()),
+            move |git_repo, mut cx| async move {
+                match git_repo {
+                    RepositoryState::Local {
+                        backend,
+                        environment,
+                        ..
+                    } => {
+                        backend.merge(branch, options, environment).await?;
+                        // Reload the snapshot before returning, so that the conflicts are
+                        // listed by the time the caller looks for them.
+                        let this = this.upgrade().context("repository was dropped")?;
+                        Self::reload_snapshot(&this, backend, updates_tx, &mut cx).await
                     }
-                })
+                    RepositoryState::Remote { project_id, client } => {
+                        client
+                            .request(proto::GitMerge {
+                                project_id: project_id.0,
+                                repository_id: id.to_proto(),
+                                branch,
+                                no_ff: options.no_ff,
+                                ff_only: options.ff_only,
+                                strategy: options.strategy,
+                            })
+                            .await
+                            .context("sending merge request")?;
+                        Ok(())
+                    }
+                }
+            },
+        )
+    }
+
+    /// Applies `patch` to the working tree, and to the index too when `options.index` is set.
+    pub fn apply_patch(
+        &mut self,
+        patch: String,
+        options: ApplyPatchOptions,
+        cx: &mut Context<Self>,
+    ) -> oneshot::Receiver<Result<()>> {
+        let id = self.id;
+        let updates_tx = self
+            .git_store()
+            .and_then(|git_store| match &git_store.read(cx).state {
+                GitStoreState::Local { downstream, .. } => downstream
+                    .as_ref()
+                    .map(|downstream| downstream.updates_tx.clone()),
+                _ => None,
+            });
+        let this = cx.weak_entity();
+        self.send_job(
+            Some("git apply".into()),
+            move |git_repo, mut cx| async move {
+                match git_repo {
+                    RepositoryState::Local {
+                        backend,
+                        environment,
+                        ..
+                    } => {
+                        backend.apply_patch(patch, options, environment).await?;
+                        // A three-way apply can leave conflicts behind, which callers expect to
+                        // find in the snapshot as soon as this returns.
+                        let this = this.upgrade().context("repository was dropped")?;
+                        Self::reload_snapshot(&this, backend, updates_tx, &mut cx).await
+                    }
+                    RepositoryState::Remote { project_id, client } => {
+                        client
+                            .request(proto::GitApplyPatch {
+                                project_id: project_id.0,
+                                repository_id: id.to_proto(),
+                                patch,
+                                three_way: options.three_way,
+                                index: options.index,
+                            })
+                            .await
+                            .context("sending apply patch request")?;
+                        Ok(())
+                    }
+                }
             },
-        );
+        )
     }
 
-    pub fn send_job<F, Fut, R>(
-        &mut self,
-        status: Option<SharedString>,
-        job: F,
-    ) -> oneshot::Receiver<R>
-    where
-        F: FnOnce(RepositoryState, AsyncApp) -> Fut + 'static,
-        Fut: Future<Output = R> + 'static,
-        R: Send + 'static,
-    {
-        self.send_keyed_job(None, status, job)
+    /// Generates mailbox-format patches for the commits in `range`, suitable for sending by
+    /// email or applying elsewhere with `git am`.
+    pub fn format_patch(&mut self, range: String) -> oneshot::Receiver<Result<String>> {
+        let id = self.id;
+        self.send_job(None, move |repo, _cx| async move {
+            match repo {
+                RepositoryState::Local { backend, .. } => backend.format_patch(range).await,
+                RepositoryState::Remote { project_id, client } => {
+                    let response = client
+                        .request(proto::GitFormatPatch {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                            range,
+                        })
+                        .await?;
+
+                    Ok(response.patch)
+                }
+            }
+        })
     }
 
-    fn send_keyed_job<F, Fut, R>(
+    /// Runs the repository's maintenance tasks, or starts or stops running them in the
+    /// background, so that large repositories stay fast. The running task is reported as the
+    /// repository's remote progress, and to `progress` if given.
+    pub fn maintenance(
         &mut self,
-        key: Option<GitJobKey>,
-        status: Option<SharedString>,
-        job: F,
-    ) -> oneshot::Receiver<R>
-    where
-        F: FnOnce(RepositoryState, AsyncApp) -> Fut + 'static,
-        Fut: Future<Output = R> + 'static,
-        R: Send + 'static,
-    {
-        let (result_tx, result_rx) = futures::channel::oneshot::channel();
-        let job_id = post_inc(&mut self.job_id);
+        command: MaintenanceCommand,
+        progress: Option<mpsc::UnboundedSender<RemoteProgress>>,
+    ) -> oneshot::Receiver<Result<()>> {
+        let remote_progress_senders = self.remote_progress_senders.clone();
+        // Progress is identified by the same IDs as askpass prompts, like a fetch's.
+        let askpass_id = util::post_inc(&mut self.latest_askpass_id);
+        let id = self.id;
         let this = self.this.clone();
-        self.job_sender
-            .unbounded_send(GitJob {
-                key,
-                job: Box::new(move |state, cx: &mut AsyncApp| {
-                    let job = job(state, cx.clone());
-                    cx.spawn(async move |cx| {
-                        if let Some(s) = status.clone() {
-                            this.update(cx, |this, cx| {
-                                this.active_jobs.insert(
-                                    job_id,
-                                    JobInfo {
-                                        start: Instant::now(),
-                                        message: s.clone(),
-                                    },
-                                );
+        let (status, proto_command) = match command {
+            MaintenanceCommand::Run => ("git maintenance run", git_maintenance::Command::Run),
+            MaintenanceCommand::Start => ("git maintenance start", git_maintenance::Command::Start),
+            MaintenanceCommand::Stop => ("git maintenance stop", git_maintenance::Command::Stop),
+        };
 
-                                cx.notify();
-                            })
-                            .ok();
-                        }
-                        let result = job.await;
+        self.send_job(Some(status.into()), move |git_repo, cx| async move {
+            let progress_tx = Self::remote_progress_sender(this, askpass_id, progress, &cx);
+            match git_repo {
+                RepositoryState::Local {
+                    backend,
+                    environment,
+                    ..
+                } => backend.maintenance(command, progress_tx, environment).await,
+                RepositoryState::Remote { project_id, client } => {
+                    remote_progress_senders
+                        .lock()
+                        .insert(askpass_id, progress_tx);
+                    let _defer = util::defer(|| {
+                        remote_progress_senders.lock().remove(&askpass_id);
+                    });
 
-                        this.update(cx, |this, cx| {
-                            this.active_jobs.remove(&job_id);
-                            cx.notify();
+                    client
+                        .request(proto::GitMaintenance {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                            askpass_id,
+                            command: proto_command.into(),
                         })
-                        .ok();
-
-                        result_tx.send(result).ok();
-                    })
-                }),
-            })
-            .ok();
-        result_rx
+                        .await
+                        .context("sending maintenance request")?;
+                    Ok(())
+                }
+            }
+        })
     }
 
-    pub fn set_as_active_repository(&self, cx: &mut Context<Self>) {
-        let Some(git_store) = self.git_store.upgrade() else {
-            return;
+    /// Packs loose objects and removes unreachable ones. An aggressive collection packs more
+    /// tightly, but takes much longer.
+    pub fn gc(&mut self, aggressive: bool) -> oneshot::Receiver<Result<()>> {
+        let id = self.id;
+        let status = if aggressive {
+            "git gc --aggressive"
+        } else {
+            "git gc"
         };
-        let entity = cx.entity();
-        git_store.update(cx, |git_store, cx| {
-            let Some((&id, _)) = git_store
-                .repositories
-                .iter()
-                .find(|(_, handle)| *handle == &entity)
-            else {
-                return;
-            };
-            git_store.active_repo_id = Some(id);
-            cx.emit(GitStoreEvent::ActiveRepositoryChanged(Some(id)));
-        });
-    }
-
-    pub fn cached_status(&self) -> impl '_ + Iterator<Item = StatusEntry> {
-        self.snapshot.status()
-    }
-
-    pub fn cached_stash(&self) -> GitStash {
-        self.snapshot.stash_entries.clone()
-    }
-
-    pub fn repo_path_to_project_path(&self, path: &RepoPath, cx: &App) -> Option<ProjectPath> {
-        let git_store = self.git_store.upgrade()?;
-        let worktree_store = git_store.read(cx).worktree_store.read(cx);
-        let abs_path = self
-            .snapshot
-            .work_directory_abs_path
-            .join(path.as_std_path());
-        let abs_path = SanitizedPath::new(&abs_path);
-        let (worktree, relative_path) = worktree_store.find_worktree(abs_path, cx)?;
-        Some(ProjectPath {
-            worktree_id: worktree.read(cx).id(),
-            path: relative_path,
+        self.send_job(Some(status.into()), move |git_repo, _cx| async move {
+            match git_repo {
+                RepositoryState::Local {
+                    backend,
+                    environment,
+                    ..
+                } => backend.gc(aggressive, environment).await,
+                RepositoryState::Remote { project_id, client } => {
+                    client
+                        .request(proto::GitGc {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                            aggressive,
+                        })
+                        .await
+                        .context("sending gc request")?;
+                    Ok(())
+                }
+            }
         })
     }
 
-    pub fn project_path_to_repo_path(&self, path: &ProjectPath, cx: &App) -> Option<RepoPath> {
-        let git_store = self.git_store.upgrade()?;
-        let worktree_store = git_store.read(cx).worktree_store.read(cx);
-        let abs_path = worktree_store.absolutize(path, cx)?;
-        self.snapshot.abs_path_to_repo_path(&abs_path)
-    }
+    /// Counts the repository's loose objects and packs, and how much space they take up, to
+    /// tell whether it's due for maintenance.
+    pub fn health(&mut self) -> oneshot::Receiver<Result<RepositoryHealth>> {
+        let id = self.id;
+        self.send_job(None, move |repo, _cx| async move {
+            match repo {
+                RepositoryState::Local { backend, .. } => backend.health().await,
+                RepositoryState::Remote { project_id, client } => {
+                    let response = client
+                        .request(proto::GitRepositoryHealth {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                        })
+                        .await?;
 
-    pub fn contains_sub_repo(&self, other: &Entity<Self>, cx: &App) -> bool {
-        other
-            .read(cx)
-            .snapshot
-            .work_directory_abs_path
-            .starts_with(&self.snapshot.work_directory_abs_path)
+                    Ok(RepositoryHealth {
+                        loose_objects: response.loose_objects,
+                        loose_objects_size: response.loose_objects_size,
+                        packs: response.packs,
+                        packs_size: response.packs_size,
+                    })
+                }
+            }
+        })
     }
 
-    pub fn open_commit_buffer(
-        &mut self,
-        languages: Option<Arc<LanguageRegistry>>,
-        buffer_store: Entity<BufferStore>,
-        cx: &mut Context<Self>,
-    ) -> Task<Result<Entity<Buffer>>> {
+    pub fn merge_abort(&mut self, cx: &mut Context<Self>) -> oneshot::Receiver<Result<()>> {
         let id = self.id;
-        if let Some(buffer) = self.commit_message_buffer.clone() {
-            return Task::ready(Ok(buffer));
-        }
+        let updates_tx = self
+            .git_store()
+            .and_then(|git_store| match &git_store.read(cx).state {
+                GitStoreState::Local { downstream, .. } => downstream
+                    .as_ref()
+                    .map(|downstream| downstream.updates_tx.clone()),
+                _ => None,
+            });
         let this = cx.weak_entity();
-
-        let rx = self.send_job(None, move |state, mut cx| async move {
-            let Some(this) = this.upgrade() else {
-                bail!("git store was dropped");
-            };
-            match state {
-                RepositoryState::Local { .. } => {
-                    this.update(&mut cx, |_, cx| {
-                        Self::open_local_commit_buffer(languages, buffer_store, cx)
-                    })?
-                    .await
-                }
-                RepositoryState::Remote { project_id, client } => {
-                    let request = client.request(proto::OpenCommitMessageBuffer {
-                        project_id: project_id.0,
-                        repository_id: id.to_proto(),
-                    });
-                    let response = request.await.context("requesting to open commit buffer")?;
-                    let buffer_id = BufferId::new(response.buffer_id)?;
-                    let buffer = buffer_store
-                        .update(&mut cx, |buffer_store, cx| {
-                            buffer_store.wait_for_remote_buffer(buffer_id, cx)
-                        })?
-                        .await?;
-                    if let Some(language_registry) = languages {
-                        let git_commit_language =
-                            language_registry.language_for_name("Git Commit").await?;
-                        buffer.update(&mut cx, |buffer, cx| {
-                            buffer.set_language(Some(git_commit_language), cx);
-                        })?;
+        self.send_job(
+            Some("git merge --abort".into()),
+            move |git_repo, mut cx| async move {
+                match git_repo {
+                    RepositoryState::Local {
+                        backend,
+                        environment,
+                        ..
+                    } => {
+                        backend.merge_abort(environment).await?;
+                        let this = this.upgrade().context("repository was dropped")?;
+                        Self::reload_snapshot(&this, backend, updates_tx, &mut cx).await
+                    }
+                    RepositoryState::Remote { project_id, client } => {
+                        client
+                            .request(proto::GitMergeAbort {
+                                project_id: project_id.0,
+                                repository_id: id.to_proto(),
+                            })
+                            .await
+                            .context("sending merge abort request")?;
+                        Ok(())
                     }
-                    this.update(&mut cx, |this, _| {
-                        this.commit_message_buffer = Some(buffer.clone());
-                    })?;
-                    Ok(buffer)
                 }
-            }
-        });
-
-        cx.spawn(|_, _: &mut AsyncApp| async move { rx.await? })
+            },
+        )
     }
 
-    fn open_local_commit_buffer(
-        language_registry: Option<Arc<LanguageRegistry>>,
-        buffer_store: Entity<BufferStore>,
+    pub fn stash_drop(
+        &mut self,
+        index: Option<usize>,
         cx: &mut Context<Self>,
-    ) -> Task<Result<Entity<Buffer>>> {
-        cx.spawn(async move |repository, cx| {
-            let buffer = buffer_store
-                .update(cx, |buffer_store, cx| buffer_store.create_buffer(false, cx))?
-                .await?;
+    ) -> oneshot::Receiver<anyhow::Result<()>> {
+        let id = self.id;
+        let updates_tx = self
+            .git_store()
+            .and_then(|git_store| match &git_store.read(cx).state {
+                GitStoreState::Local { downstream, .. } => downstream
+                    .as_ref()
+                    .map(|downstream| downstream.updates_tx.clone()),
+                _ => None,
+            });
+        let this = cx.weak_entity();
+        self.send_job(None, move |git_repo, mut cx| async move {
+            match git_repo {
+                RepositoryState::Local {
+                    backend,
+                    environment,
+                    ..
+                } => {
+                    let result = backend.stash_drop(index, environment).await;
+                    if result.is_ok()
+                        && let Ok(stash_entries) = backend.stash_entries().await
+                    {
+                        let snapshot = this.update(&mut cx, |this, cx| {
+                            this.snapshot.stash_entries = stash_entries;
+                            let snapshot = this.snapshot.clone();
+                            cx.emit(RepositoryEvent::Updated {
+                                full_scan: false,
+                                new_instance: false,
+                            });
+                            snapshot
+                        })?;
+                        if let Some(updates_tx) = updates_tx {
+                            updates_tx
+                                .unbounded_send(DownstreamUpdate::UpdateRepository(snapshot))
+                                .ok();
+                        }
+                    }
 
-            if let Some(language_registry) = language_registry {
-                let git_commit_language = language_registry.language_for_name("Git Commit").await?;
-                buffer.update(cx, |buffer, cx| {
-                    buffer.set_language(Some(git_commit_language), cx);
-                })?;
+                    result
+                }
+                RepositoryState::Remote { project_id, client } => {
+                    client
+                        .request(proto::StashDrop {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                            stash_index: index.map(|i| i as u64),
+                        })
+                        .await
+                        .context("sending stash pop request")?;
+                    Ok(())
+                }
             }
-
-            repository.update(cx, |repository, _| {
-                repository.commit_message_buffer = Some(buffer.clone());
-            })?;
-            Ok(buffer)
         })
     }
 
-    pub fn checkout_files(
+    /// Commits with the given message, after appending the trailers that it doesn't have yet.
+    pub fn commit(
         &mut self,
-        commit: &str,
-        paths: Vec<RepoPath>,
+        message: SharedString,
+        name_and_email: Option<(SharedString, SharedString)>,
+        options: CommitOptions,
+        trailers: Vec<Trailer>,
+        askpass: AskPassDelegate,
         _cx: &mut App,
     ) -> oneshot::Receiver<Result<()>> {
-        let commit = commit.to_string();
+        let message = match append_trailers(&message, &trailers) {
+            Ok(message) => SharedString::from(message),
+            Err(error) => return ready_receiver(Err(error)),
+        };
+        let askpass_delegates = self.askpass_delegates.clone();
+        let askpass_id = util::post_inc(&mut self.latest_askpass_id);
         let id = self.id;
 
-        self.send_job(
-            Some(format!("git checkout {}", commit).into()),
-            move |git_repo, _| async move {
+        self.send_index_job(
+            None,
+            Some("git commit".into()),
+            move |git_repo, cx| async move {
                 match git_repo {
                     RepositoryState::Local {
                         backend,
@@ -3472,18 +6433,33 @@ impl Repository {
                         ..
                     } => {
                         backend
-                            .checkout_files(commit, paths, environment.clone())
+                            .commit(message, name_and_email, options, askpass, environment, cx)
                             .await
                     }
                     RepositoryState::Remote { project_id, client } => {
+                        askpass_delegates.lock().insert(askpass_id, askpass);
+                        let _defer = util::defer(|| {
+                            let askpass_delegate = askpass_delegates.lock().remove(&askpass_id);
+                            debug_assert!(askpass_delegate.is_some());
+                        });
+
+                        let (name, email) = name_and_email.unzip();
                         client
-                            .request(proto::GitCheckoutFiles {
+                            .request(proto::Commit {
                                 project_id: project_id.0,
                                 repository_id: id.to_proto(),
-                                commit,
-                                paths: paths.into_iter().map(|p| p.to_proto()).collect(),
+                                message: String::from(message),
+                                name: name.map(String::from),
+                                email: email.map(String::from),
+                                options: Some(proto::commit::CommitOptions {
+                                    amend: options.amend,
+                                    signoff: options.signoff,
+                                    sign: options.sign,
+                                }),
+                                askpass_id,
                             })
-                            .await?;
+                            .await
+                            .context("sending commit request")?;
 
                         Ok(())
                     }
@@ -3492,859 +6468,1030 @@ impl Repository {
         )
     }
 
-    pub fn reset(
+    pub fn fetch(
         &mut self,
-        commit: String,
-        reset_mode: ResetMode,
+        fetch_options: FetchOptions,
+        askpass: AskPassDelegate,
+        progress: Option<mpsc::UnboundedSender<RemoteProgress>>,
         _cx: &mut App,
-    ) -> oneshot::Receiver<Result<()>> {
+    ) -> oneshot::Receiver<Result<RemoteCommandOutput>> {
+        let askpass_delegates = self.askpass_delegates.clone();
+        let remote_progress_senders = self.remote_progress_senders.clone();
+        let askpass_id = util::post_inc(&mut self.latest_askpass_id);
         let id = self.id;
+        let this = self.this.clone();
 
-        self.send_job(None, move |git_repo, _| async move {
+        self.send_job(Some("git fetch".into()), move |git_repo, cx| async move {
+            let progress_tx = Self::remote_progress_sender(this, askpass_id, progress, &cx);
             match git_repo {
                 RepositoryState::Local {
                     backend,
                     environment,
                     ..
-                } => backend.reset(commit, reset_mode, environment).await,
+                } => {
+                    backend
+                        .fetch(fetch_options, askpass, progress_tx, environment, cx)
+                        .await
+                }
                 RepositoryState::Remote { project_id, client } => {
-                    client
-                        .request(proto::GitReset {
-                            project_id: project_id.0,
-                            repository_id: id.to_proto(),
-                            commit,
-                            mode: match reset_mode {
-                                ResetMode::Soft => git_reset::ResetMode::Soft.into(),
-                                ResetMode::Mixed => git_reset::ResetMode::Mixed.into(),
+                    let mut request = RemoteCommandRequest::new(
+                        askpass_id,
+                        askpass,
+                        progress_tx,
+                        askpass_delegates,
+                        remote_progress_senders,
+                    );
+                    let response = request
+                        .send(
+                            &client,
+                            proto::Fetch {
+                                project_id: project_id.0,
+                                repository_id: id.to_proto(),
+                                askpass_id,
+                                remote: fetch_options.to_proto(),
                             },
-                        })
-                        .await?;
+                            "fetch",
+                            true,
+                            &cx,
+                        )
+                        .await
+                        .context("sending fetch request")?;
 
-                    Ok(())
+                    Ok(RemoteCommandOutput {
+                        stdout: response.stdout,
+                        stderr: response.stderr,
+                    })
                 }
             }
         })
     }
 
-    pub fn show(&mut self, commit: String) -> oneshot::Receiver<Result<CommitDetails>> {
+    pub fn push(
+        &mut self,
+        branch: SharedString,
+        remote: SharedString,
+        options: Option<PushOptions>,
+        askpass: AskPassDelegate,
+        progress: Option<mpsc::UnboundedSender<RemoteProgress>>,
+        cx: &mut Context<Self>,
+    ) -> oneshot::Receiver<Result<RemoteCommandOutput>> {
+        let askpass_delegates = self.askpass_delegates.clone();
+        let remote_progress_senders = self.remote_progress_senders.clone();
+        let askpass_id = util::post_inc(&mut self.latest_askpass_id);
         let id = self.id;
-        self.send_job(None, move |git_repo, _cx| async move {
-            match git_repo {
-                RepositoryState::Local { backend, .. } => backend.show(commit).await,
+
+        let args = options
+            .map(|option| match option {
+                PushOptions::SetUpstream => " --set-upstream",
+                PushOptions::Force => " --force-with-lease",
+            })
+            .unwrap_or("");
+
+        let updates_tx = self
+            .git_store()
+            .and_then(|git_store| match &git_store.read(cx).state {
+                GitStoreState::Local { downstream, .. } => downstream
+                    .as_ref()
+                    .map(|downstream| downstream.updates_tx.clone()),
+                _ => None,
+            });
+
+        let this = cx.weak_entity();
+        self.send_job(
+            Some(format!("git push {} {} {}", args, branch, remote).into()),
+            move |git_repo, mut cx| async move {
+                let progress_tx =
+                    Self::remote_progress_sender(this.clone(), askpass_id, progress, &cx);
+                match git_repo {
+                    RepositoryState::Local {
+                        backend,
+                        environment,
+                        ..
+                    } => {
+                        let result = backend
+                            .push(
+                                branch.to_string(),
+                                remote.to_string(),
+                                options,
+                                askpass,
+                                progress_tx,
+                                environment.clone(),
+                                cx.clone(),
+                            )
+                            .await;
+                        if result.is_ok() {
+                            let branches = backend.branches().await?;
+                            let branch = branches.into_iter().find(|branch| branch.is_head);
+                            log::info!("head branch after scan is {branch:?}");
+                            let snapshot = this.update(&mut cx, |this, cx| {
+                                this.snapshot.branch = branch;
+                                let snapshot = this.snapshot.clone();
+                                cx.emit(RepositoryEvent::Updated {
+                                    full_scan: false,
+                                    new_instance: false,
+                                });
+                                snapshot
+                            })?;
+                            if let Some(updates_tx) = updates_tx {
+                                updates_tx
+                                    .unbounded_send(DownstreamUpdate::UpdateRepository(snapshot))
+                                    .ok();
+                            }
+                        }
+                        result
+                    }
+                    RepositoryState::Remote { project_id, client } => {
+                        let mut request = RemoteCommandRequest::new(
+                            askpass_id,
+                            askpass,
+                            progress_tx,
+                            askpass_delegates,
+                            remote_progress_senders,
+                        );
+                        let response = request
+                            .send(
+                                &client,
+                                proto::Push {
+                                    project_id: project_id.0,
+                                    repository_id: id.to_proto(),
+                                    askpass_id,
+                                    branch_name: branch.to_string(),
+                                    remote_name: remote.to_string(),
+                                    options: options.map(|options| match options {
+                                        PushOptions::Force => proto::push::PushOptions::Force,
+                                        PushOptions::SetUpstream => {
+                                            proto::push::PushOptions::SetUpstream
+                                        }
+                                    }
+                                        as i32),
+                                },
+                                "push",
+                                false,
+                                &cx,
+                            )
+                            .await
+                            .context("sending push request")?;
+
+                        Ok(RemoteCommandOutput {
+                            stdout: response.stdout,
+                            stderr: response.stderr,
+                        })
+                    }
+                }
+            },
+        )
+    }
+
+    /// Lists the repository's tags, most recently created first.
+    pub fn tags(&mut self) -> oneshot::Receiver<Result<Vec<Tag>>> {
+        let id = self.id;
+        self.send_job(None, move |repo, _| async move {
+            match repo {
+                RepositoryState::Local { backend, .. } => backend.tags().await,
                 RepositoryState::Remote { project_id, client } => {
-                    let resp = client
-                        .request(proto::GitShow {
+                    let response = client
+                        .request(proto::GitGetTags {
                             project_id: project_id.0,
                             repository_id: id.to_proto(),
-                            commit,
                         })
                         .await?;
-
-                    Ok(CommitDetails {
-                        sha: resp.sha.into(),
-                        message: resp.message.into(),
-                        commit_timestamp: resp.commit_timestamp,
-                        author_email: resp.author_email.into(),
-                        author_name: resp.author_name.into(),
-                    })
+                    Ok(response.tags.into_iter().map(proto_to_tag).collect())
                 }
             }
         })
     }
 
-    pub fn load_commit_diff(&mut self, commit: String) -> oneshot::Receiver<Result<CommitDiff>> {
+    /// Tags the given commit, creating an annotated tag if a message is given and a lightweight
+    /// one otherwise.
+    pub fn create_tag(
+        &mut self,
+        name: String,
+        sha: String,
+        message: Option<String>,
+    ) -> oneshot::Receiver<Result<()>> {
         let id = self.id;
-        self.send_job(None, move |git_repo, cx| async move {
-            match git_repo {
-                RepositoryState::Local { backend, .. } => backend.load_commit(commit, cx).await,
-                RepositoryState::Remote {
-                    client, project_id, ..
-                } => {
-                    let response = client
-                        .request(proto::LoadCommitDiff {
-                            project_id: project_id.0,
-                            repository_id: id.to_proto(),
-                            commit,
-                        })
-                        .await?;
-                    Ok(CommitDiff {
-                        files: response
-                            .files
-                            .into_iter()
-                            .map(|file| {
-                                Ok(CommitFile {
-                                    path: RepoPath::from_proto(&file.path)?,
-                                    old_text: file.old_text,
-                                    new_text: file.new_text,
-                                })
+        self.send_job(
+            Some(format!("git tag {name}").into()),
+            move |repo, _cx| async move {
+                match repo {
+                    RepositoryState::Local {
+                        backend,
+                        environment,
+                        ..
+                    } => backend.create_tag(name, sha, message, environment).await,
+                    RepositoryState::Remote { project_id, client } => {
+                        client
+                            .request(proto::GitCreateTag {
+                                project_id: project_id.0,
+                                repository_id: id.to_proto(),
+                                name,
+                                sha,
+                                message,
+                            })
+                            .await
+                            .context("sending create tag request")?;
+                        Ok(())
+                    }
+                }
+            },
+        )
+    }
+
+    pub fn delete_tag(&mut self, name: String) -> oneshot::Receiver<Result<()>> {
+        let id = self.id;
+        self.send_job(
+            Some(format!("git tag --delete {name}").into()),
+            move |repo, _cx| async move {
+                match repo {
+                    RepositoryState::Local {
+                        backend,
+                        environment,
+                        ..
+                    } => backend.delete_tag(name, environment).await,
+                    RepositoryState::Remote { project_id, client } => {
+                        client
+                            .request(proto::GitDeleteTag {
+                                project_id: project_id.0,
+                                repository_id: id.to_proto(),
+                                name,
                             })
-                            .collect::<Result<Vec<_>>>()?,
-                    })
+                            .await
+                            .context("sending delete tag request")?;
+                        Ok(())
+                    }
                 }
-            }
-        })
+            },
+        )
     }
 
-    fn buffer_store(&self, cx: &App) -> Option<Entity<BufferStore>> {
-        Some(self.git_store.upgrade()?.read(cx).buffer_store.clone())
+    pub fn push_tag(
+        &mut self,
+        tag: SharedString,
+        remote: SharedString,
+        askpass: AskPassDelegate,
+    ) -> oneshot::Receiver<Result<RemoteCommandOutput>> {
+        let askpass_delegates = self.askpass_delegates.clone();
+        let askpass_id = util::post_inc(&mut self.latest_askpass_id);
+        let id = self.id;
+
+        self.send_job(
+            Some(format!("git push {remote} {tag}").into()),
+            move |git_repo, cx| async move {
+                match git_repo {
+                    RepositoryState::Local {
+                        backend,
+                        environment,
+                        ..
+                    } => {
+                        backend
+                            .push_tag(
+                                tag.to_string(),
+                                remote.to_string(),
+                                askpass,
+                                environment.clone(),
+                                cx,
+                            )
+                            .await
+                    }
+                    RepositoryState::Remote { project_id, client } => {
+                        askpass_delegates.lock().insert(askpass_id, askpass);
+                        let _defer = util::defer(|| {
+                            let askpass_delegate = askpass_delegates.lock().remove(&askpass_id);
+                            debug_assert!(askpass_delegate.is_some());
+                        });
+                        let response = client
+                            .request(proto::GitPushTag {
+                                project_id: project_id.0,
+                                repository_id: id.to_proto(),
+                                name: tag.to_string(),
+                                remote_name: remote.to_string(),
+                                askpass_id,
+                            })
+                            .await
+                            .context("sending push tag request")?;
+
+                        Ok(RemoteCommandOutput {
+                            stdout: response.stdout,
+                            stderr: response.stderr,
+                        })
+                    }
+                }
+            },
+        )
     }
 
-    pub fn stage_entries(
-        &self,
-        entries: Vec<RepoPath>,
+    pub fn pull(
+        &mut self,
+        branch: SharedString,
+        remote: SharedString,
+        options: PullOptions,
+        askpass: AskPassDelegate,
+        progress: Option<mpsc::UnboundedSender<RemoteProgress>>,
         cx: &mut Context<Self>,
-    ) -> Task<anyhow::Result<()>> {
-        if entries.is_empty() {
-            return Task::ready(Ok(()));
-        }
+    ) -> oneshot::Receiver<Result<RemoteCommandOutput>> {
+        let askpass_delegates = self.askpass_delegates.clone();
+        let remote_progress_senders = self.remote_progress_senders.clone();
+        let askpass_id = util::post_inc(&mut self.latest_askpass_id);
         let id = self.id;
 
-        let mut save_futures = Vec::new();
-        if let Some(buffer_store) = self.buffer_store(cx) {
-            buffer_store.update(cx, |buffer_store, cx| {
-                for path in &entries {
-                    let Some(project_path) = self.repo_path_to_project_path(path, cx) else {
-                        continue;
-                    };
-                    if let Some(buffer) = buffer_store.get_by_path(&project_path)
-                        && buffer
-                            .read(cx)
-                            .file()
-                            .is_some_and(|file| file.disk_state().exists())
-                    {
-                        save_futures.push(buffer_store.save_buffer(buffer, cx));
-                    }
-                }
-            })
+        let mut args = String::new();
+        if options.rebase {
+            args.push_str(" --rebase");
+        }
+        if options.ff_only {
+            args.push_str(" --ff-only");
+        }
+        if let Some(strategy) = &options.strategy {
+            args.push_str(&format!(" --strategy={strategy}"));
         }
 
-        cx.spawn(async move |this, cx| {
-            for save_future in save_futures {
-                save_future.await?;
-            }
+        let updates_tx = self
+            .git_store()
+            .and_then(|git_store| match &git_store.read(cx).state {
+                GitStoreState::Local { downstream, .. } => downstream
+                    .as_ref()
+                    .map(|downstream| downstream.updates_tx.clone()),
+                _ => None,
+            });
 
-            this.update(cx, |this, _| {
-                this.send_job(None, move |git_repo, _cx| async move {
-                    match git_repo {
-                        RepositoryState::Local {
-                            backend,
-                            environment,
-                            ..
-                        } => backend.stage_paths(entries, environment.clone()).await,
-                        RepositoryState::Remote { project_id, client } => {
-                            client
-                                .request(proto::Stage {
-                                    project_id: project_id.0,
-                                    repository_id: id.to_proto(),
-                                    paths: entries
-                                        .into_iter()
-                                        .map(|repo_path| repo_path.to_proto())
-                                        .collect(),
-                                })
+        let this = cx.weak_entity();
+        self.send_job(
+            Some(format!("git pull{} {} {}", args, remote, branch).into()),
+            move |git_repo, mut cx| async move {
+                let progress_tx =
+                    Self::remote_progress_sender(this.clone(), askpass_id, progress, &cx);
+                match git_repo {
+                    RepositoryState::Local {
+                        backend,
+                        environment,
+                        ..
+                    } => {
+                        let result = backend
+                            .pull(
+                                branch.to_string(),
+                                remote.to_string(),
+                                options,
+                                askpass,
+                                progress_tx,
+                                environment.clone(),
+                                cx.clone(),
+                            )
+                            .await;
+                        // A pull that stops on conflicts leaves a merge or rebase in progress.
+                        // Reload the snapshot before returning, so that its merge message and
+                        // conflicted paths are up to date by the time the caller sees the error.
+                        if result.is_err()
+                            && let Some(this) = this.upgrade()
+                        {
+                            Self::reload_snapshot(&this, backend, updates_tx, &mut cx)
                                 .await
-                                .context("sending stage request")?;
-
-                            Ok(())
+                                .log_err();
                         }
+                        result
                     }
-                })
-            })?
-            .await??;
+                    RepositoryState::Remote { project_id, client } => {
+                        let mut request = RemoteCommandRequest::new(
+                            askpass_id,
+                            askpass,
+                            progress_tx,
+                            askpass_delegates,
+                            remote_progress_senders,
+                        );
+                        let response = request
+                            .send(
+                                &client,
+                                proto::Pull {
+                                    project_id: project_id.0,
+                                    repository_id: id.to_proto(),
+                                    askpass_id,
+                                    branch_name: branch.to_string(),
+                                    remote_name: remote.to_string(),
+                                    options: Some(proto::pull::PullOptions {
+                                        rebase: options.rebase,
+                                        ff_only: options.ff_only,
+                                        strategy: options.strategy,
+                                    }),
+                                },
+                                "pull",
+                                false,
+                                &cx,
+                            )
+                            .await
+                            .context("sending pull request")?;
 
-            Ok(())
-        })
+                        Ok(RemoteCommandOutput {
+                            stdout: response.stdout,
+                            stderr: response.stderr,
+                        })
+                    }
+                }
+            },
+        )
     }
 
-    pub fn unstage_entries(
-        &self,
-        entries: Vec<RepoPath>,
+    fn spawn_set_index_text_job(
+        &mut self,
+        path: RepoPath,
+        content: Option<String>,
+        hunk_staging_operation_count: Option<usize>,
         cx: &mut Context<Self>,
-    ) -> Task<anyhow::Result<()>> {
-        if entries.is_empty() {
-            return Task::ready(Ok(()));
-        }
+    ) -> oneshot::Receiver<anyhow::Result<()>> {
         let id = self.id;
-
-        let mut save_futures = Vec::new();
-        if let Some(buffer_store) = self.buffer_store(cx) {
-            buffer_store.update(cx, |buffer_store, cx| {
-                for path in &entries {
-                    let Some(project_path) = self.repo_path_to_project_path(path, cx) else {
-                        continue;
-                    };
-                    if let Some(buffer) = buffer_store.get_by_path(&project_path)
-                        && buffer
-                            .read(cx)
-                            .file()
-                            .is_some_and(|file| file.disk_state().exists())
-                    {
-                        save_futures.push(buffer_store.save_buffer(buffer, cx));
+        let this = cx.weak_entity();
+        let git_store = self.git_store.clone();
+        self.send_index_job(
+            Some(GitJobKey::WriteIndex(path.clone())),
+            None,
+            move |git_repo, mut cx| async move {
+                log::debug!(
+                    "start updating index text for buffer {}",
+                    path.as_unix_str()
+                );
+                match git_repo {
+                    RepositoryState::Local {
+                        backend,
+                        environment,
+                        ..
+                    } => {
+                        backend
+                            .set_index_text(path.clone(), content, environment.clone())
+                            .await?;
                     }
-                }
-            })
-        }
-
-        cx.spawn(async move |this, cx| {
-            for save_future in save_futures {
-                save_future.await?;
-            }
-
-            this.update(cx, |this, _| {
-                this.send_job(None, move |git_repo, _cx| async move {
-                    match git_repo {
-                        RepositoryState::Local {
-                            backend,
-                            environment,
-                            ..
-                        } => backend.unstage_paths(entries, environment).await,
-                        RepositoryState::Remote { project_id, client } => {
-                            client
-                                .request(proto::Unstage {
-                                    project_id: project_id.0,
-                                    repository_id: id.to_proto(),
-                                    paths: entries
-                                        .into_iter()
-                                        .map(|repo_path| repo_path.to_proto())
-                                        .collect(),
-                                })
-                                .await
-                                .context("sending unstage request")?;
-
-                            Ok(())
-                        }
+                    RepositoryState::Remote { project_id, client } => {
+                        client
+                            .request(proto::SetIndexText {
+                                project_id: project_id.0,
+                                repository_id: id.to_proto(),
+                                path: path.to_proto(),
+                                text: content,
+                            })
+                            .await?;
                     }
-                })
-            })?
-            .await??;
+                }
+                log::debug!(
+                    "finish updating index text for buffer {}",
+                    path.as_unix_str()
+                );
 
-            Ok(())
-        })
+                if let Some(hunk_staging_operation_count) = hunk_staging_operation_count {
+                    let project_path = this
+                        .read_with(&cx, |this, cx| this.repo_path_to_project_path(&path, cx))
+                        .ok()
+                        .flatten();
+                    git_store.update(&mut cx, |git_store, cx| {
+                        let buffer_id = git_store
+                            .buffer_store
+                            .read(cx)
+                            .get_by_path(&project_path?)?
+                            .read(cx)
+                            .remote_id();
+                        let diff_state = git_store.diffs.get(&buffer_id)?;
+                        diff_state.update(cx, |diff_state, _| {
+                            diff_state.hunk_staging_operation_count_as_of_write =
+                                hunk_staging_operation_count;
+                        });
+                        Some(())
+                    })?;
+                }
+                Ok(())
+            },
+        )
     }
 
-    pub fn stage_all(&self, cx: &mut Context<Self>) -> Task<anyhow::Result<()>> {
-        let to_stage = self
-            .cached_status()
-            .filter(|entry| !entry.status.staging().is_fully_staged())
-            .map(|entry| entry.repo_path)
-            .collect();
-        self.stage_entries(to_stage, cx)
+    pub fn get_remotes(
+        &mut self,
+        branch_name: Option<String>,
+    ) -> oneshot::Receiver<Result<Vec<Remote>>> {
+        if branch_name.is_none()
+            && let Some(remotes) = &self.cached_remotes
+        {
+            return ready_receiver(Ok(remotes.clone()));
+        }
+        let id = self.id;
+        self.send_job(None, move |repo, _cx| {
+            Self::load_remotes(id, repo, branch_name)
+        })
     }
 
-    pub fn unstage_all(&self, cx: &mut Context<Self>) -> Task<anyhow::Result<()>> {
-        let to_unstage = self
-            .cached_status()
-            .filter(|entry| entry.status.staging().has_staged())
-            .map(|entry| entry.repo_path)
-            .collect();
-        self.unstage_entries(to_unstage, cx)
+    pub fn branches(&mut self) -> oneshot::Receiver<Result<Vec<Branch>>> {
+        if let Some(branches) = &self.cached_branches {
+            return ready_receiver(Ok(branches.clone()));
+        }
+        let id = self.id;
+        self.send_job(None, move |repo, _| Self::load_branches(id, repo))
     }
 
-    pub fn stash_all(&mut self, cx: &mut Context<Self>) -> Task<anyhow::Result<()>> {
-        let to_stash = self.cached_status().map(|entry| entry.repo_path).collect();
+    async fn load_remotes(
+        id: RepositoryId,
+        repo: RepositoryState,
+        branch_name: Option<String>,
+    ) -> Result<Vec<Remote>> {
+        match repo {
+            RepositoryState::Local { backend, .. } => backend.get_remotes(branch_name).await,
+            RepositoryState::Remote { project_id, client } => {
+                let response = client
+                    .request(proto::GetRemotes {
+                        project_id: project_id.0,
+                        repository_id: id.to_proto(),
+                        branch_name,
+                    })
+                    .await?;
 
-        self.stash_entries(to_stash, cx)
+                let remotes = response
+                    .remotes
+                    .into_iter()
+                    .map(|remotes| git::repository::Remote {
+                        name: remotes.name.into(),
+                    })
+                    .collect();
+
+                Ok(remotes)
+            }
+        }
     }
 
-    pub fn stash_entries(
-        &mut self,
-        entries: Vec<RepoPath>,
-        cx: &mut Context<Self>,
-    ) -> Task<anyhow::Result<()>> {
-        let id = self.id;
+    async fn load_branches(id: RepositoryId, repo: RepositoryState) -> Result<Vec<Branch>> {
+        match repo {
+            RepositoryState::Local { backend, .. } => backend.branches().await,
+            RepositoryState::Remote { project_id, client } => {
+                let response = client
+                    .request(proto::GitGetBranches {
+                        project_id: project_id.0,
+                        repository_id: id.to_proto(),
+                    })
+                    .await?;
 
-        cx.spawn(async move |this, cx| {
-            this.update(cx, |this, _| {
-                this.send_job(None, move |git_repo, _cx| async move {
-                    match git_repo {
-                        RepositoryState::Local {
-                            backend,
-                            environment,
-                            ..
-                        } => backend.stash_paths(entries, environment).await,
-                        RepositoryState::Remote { project_id, client } => {
-                            client
-                                .request(proto::Stash {
-                                    project_id: project_id.0,
-                                    repository_id: id.to_proto(),
-                                    paths: entries
-                                        .into_iter()
-                                        .map(|repo_path| repo_path.to_proto())
-                                        .collect(),
-                                })
-                                .await
-                                .context("sending stash request")?;
-                            Ok(())
-                        }
-                    }
-                })
-            })?
-            .await??;
-            Ok(())
-        })
+                let branches = response
+                    .branches
+                    .into_iter()
+                    .map(|branch| proto_to_branch(&branch))
+                    .collect();
+
+                Ok(branches)
+            }
+        }
     }
 
-    pub fn stash_pop(
-        &mut self,
-        index: Option<usize>,
-        cx: &mut Context<Self>,
-    ) -> Task<anyhow::Result<()>> {
+    /// Clears the cached branches and remotes, listing them again in the background if
+    /// `git.prefetch_branches` is enabled.
+    fn invalidate_branch_cache(&mut self, cx: &mut Context<Self>) {
+        self.cached_branches = None;
+        self.cached_remotes = None;
+        self.branch_cache_generation += 1;
+        if !ProjectSettings::get_global(cx).git.prefetch_branches {
+            return;
+        }
+
         let id = self.id;
-        cx.spawn(async move |this, cx| {
-            this.update(cx, |this, _| {
-                this.send_job(None, move |git_repo, _cx| async move {
-                    match git_repo {
-                        RepositoryState::Local {
-                            backend,
-                            environment,
-                            ..
-                        } => backend.stash_pop(index, environment).await,
-                        RepositoryState::Remote { project_id, client } => {
-                            client
-                                .request(proto::StashPop {
-                                    project_id: project_id.0,
-                                    repository_id: id.to_proto(),
-                                    stash_index: index.map(|i| i as u64),
-                                })
-                                .await
-                                .context("sending stash pop request")?;
-                            Ok(())
-                        }
+        let generation = self.branch_cache_generation;
+        let this = cx.weak_entity();
+        let _ = self.send_keyed_job(
+            Some(GitJobKey::PrefetchBranches),
+            None,
+            move |repo, mut cx| async move {
+                let branches = Self::load_branches(id, repo.clone()).await?;
+                let remotes = Self::load_remotes(id, repo, None).await?;
+                this.update(&mut cx, |this, _| {
+                    if this.branch_cache_generation == generation {
+                        this.cached_branches = Some(branches);
+                        this.cached_remotes = Some(remotes);
                     }
                 })
-            })?
-            .await??;
-            Ok(())
-        })
+            },
+        );
     }
 
-    pub fn stash_apply(
-        &mut self,
-        index: Option<usize>,
-        cx: &mut Context<Self>,
-    ) -> Task<anyhow::Result<()>> {
+    pub fn default_branch(&mut self) -> oneshot::Receiver<Result<Option<SharedString>>> {
         let id = self.id;
-        cx.spawn(async move |this, cx| {
-            this.update(cx, |this, _| {
-                this.send_job(None, move |git_repo, _cx| async move {
-                    match git_repo {
-                        RepositoryState::Local {
-                            backend,
-                            environment,
-                            ..
-                        } => backend.stash_apply(index, environment).await,
-                        RepositoryState::Remote { project_id, client } => {
-                            client
-                                .request(proto::StashApply {
-                                    project_id: project_id.0,
-                                    repository_id: id.to_proto(),
-                                    stash_index: index.map(|i| i as u64),
-                                })
-                                .await
-                                .context("sending stash apply request")?;
-                            Ok(())
-                        }
-                    }
-                })
-            })?
-            .await??;
-            Ok(())
+        self.send_job(None, move |repo, _| async move {
+            match repo {
+                RepositoryState::Local { backend, .. } => backend.default_branch().await,
+                RepositoryState::Remote { project_id, client } => {
+                    let response = client
+                        .request(proto::GetDefaultBranch {
+                            project_id: project_id.0,
+                            repository_id: id.to_proto(),
+                        })
+                        .await?;
+
+                    anyhow::Ok(response.branch.map(SharedString::from))
+                }
+            }
         })
     }
 
-    pub fn stash_drop(
-        &mut self,
-        index: Option<usize>,
-        cx: &mut Context<Self>,
-    ) -> oneshot::Receiver<anyhow::Result<()>> {
+    pub fn diff(&mut self, diff_type: DiffType, _cx: &App) -> oneshot::Receiver<Result<String>> {
         let id = self.id;
-        let updates_tx = self
-            .git_store()
-            .and_then(|git_store| match &git_store.read(cx).state {
-                GitStoreState::Local { downstream, .. } => downstream
-                    .as_ref()
-                    .map(|downstream| downstream.updates_tx.clone()),
-                _ => None,
-            });
-        let this = cx.weak_entity();
-        self.send_job(None, move |git_repo, mut cx| async move {
-            match git_repo {
-                RepositoryState::Local {
-                    backend,
-                    environment,
-                    ..
-                } => {
-                    let result = backend.stash_drop(index, environment).await;
-                    if result.is_ok()
-                        && let Ok(stash_entries) = backend.stash_entries().await
-                    {
-                        let snapshot = this.update(&mut cx, |this, cx| {
-                            this.snapshot.stash_entries = stash_entries;
-                            let snapshot = this.snapshot.clone();
-                            cx.emit(RepositoryEvent::Updated {
-                                full_scan: false,
-                                new_instance: false,
-                            });
-                            snapshot
-                        })?;
-                        if let Some(updates_tx) = updates_tx {
-                            updates_tx
-                                .unbounded_send(DownstreamUpdate::UpdateRepository(snapshot))
-                                .ok();
-                        }
-                    }
-
-                    result
-                }
+        self.send_job(None, move |repo, _cx| async move {
+            match repo {
+                RepositoryState::Local { backend, .. } => backend.diff(diff_type).await,
                 RepositoryState::Remote { project_id, client } => {
-                    client
-                        .request(proto::StashDrop {
+                    let (diff_type, base, head) = match diff_type {
+                        DiffType::HeadToIndex => {
+                            (proto::git_diff::DiffType::HeadToIndex, None, None)
+                        }
+                        DiffType::HeadToWorktree => {
+                            (proto::git_diff::DiffType::HeadToWorktree, None, None)
+                        }
+                        DiffType::Commits { base, head } => {
+                            (proto::git_diff::DiffType::Commits, Some(base), Some(head))
+                        }
+                    };
+                    let response = client
+                        .request(proto::GitDiff {
                             project_id: project_id.0,
                             repository_id: id.to_proto(),
-                            stash_index: index.map(|i| i as u64),
+                            diff_type: diff_type.into(),
+                            base,
+                            head,
                         })
-                        .await
-                        .context("sending stash pop request")?;
-                    Ok(())
+                        .await?;
+
+                    receive_diff(&client, project_id, id, response).await
                 }
             }
         })
     }
 
-    pub fn commit(
-        &mut self,
-        message: SharedString,
-        name_and_email: Option<(SharedString, SharedString)>,
-        options: CommitOptions,
-        _cx: &mut App,
-    ) -> oneshot::Receiver<Result<()>> {
+    /// Loads the content of the file at `path` as of `rev`, which can be any revision, like a
+    /// branch, tag or SHA, so that historical versions of files can be viewed.
+    pub fn load_blob(&mut self, rev: String, path: RepoPath) -> oneshot::Receiver<Result<String>> {
         let id = self.id;
-
-        self.send_job(Some("git commit".into()), move |git_repo, _cx| async move {
-            match git_repo {
-                RepositoryState::Local {
-                    backend,
-                    environment,
-                    ..
-                } => {
-                    backend
-                        .commit(message, name_and_email, options, environment)
-                        .await
-                }
+        self.send_job(None, move |repo, _cx| async move {
+            match repo {
+                RepositoryState::Local { backend, .. } => backend.load_blob(rev, path).await,
                 RepositoryState::Remote { project_id, client } => {
-                    let (name, email) = name_and_email.unzip();
-                    client
-                        .request(proto::Commit {
+                    let response = client
+                        .request(proto::GitLoadBlob {
                             project_id: project_id.0,
                             repository_id: id.to_proto(),
-                            message: String::from(message),
-                            name: name.map(String::from),
-                            email: email.map(String::from),
-                            options: Some(proto::commit::CommitOptions {
-                                amend: options.amend,
-                                signoff: options.signoff,
-                            }),
+                            rev,
+                            path: path.to_proto(),
                         })
-                        .await
-                        .context("sending commit request")?;
+                        .await?;
 
-                    Ok(())
+                    Ok(response.content)
                 }
             }
         })
     }
 
-    pub fn fetch(
+    /// Diffs the working copy of the file at `path` against its content at `base_rev`, which can
+    /// be any revision, like a branch, tag or SHA.
+    pub fn diff_file(
         &mut self,
-        fetch_options: FetchOptions,
-        askpass: AskPassDelegate,
-        _cx: &mut App,
-    ) -> oneshot::Receiver<Result<RemoteCommandOutput>> {
-        let askpass_delegates = self.askpass_delegates.clone();
-        let askpass_id = util::post_inc(&mut self.latest_askpass_id);
+        path: RepoPath,
+        base_rev: String,
+    ) -> oneshot::Receiver<Result<String>> {
         let id = self.id;
-
-        self.send_job(Some("git fetch".into()), move |git_repo, cx| async move {
-            match git_repo {
-                RepositoryState::Local {
-                    backend,
-                    environment,
-                    ..
-                } => backend.fetch(fetch_options, askpass, environment, cx).await,
+        self.send_job(None, move |repo, _cx| async move {
+            match repo {
+                RepositoryState::Local { backend, .. } => backend.diff_file(path, base_rev).await,
                 RepositoryState::Remote { project_id, client } => {
-                    askpass_delegates.lock().insert(askpass_id, askpass);
-                    let _defer = util::defer(|| {
-                        let askpass_delegate = askpass_delegates.lock().remove(&askpass_id);
-                        debug_assert!(askpass_delegate.is_some());
-                    });
-
                     let response = client
-                        .request(proto::Fetch {
+                        .request(proto::GitDiffFile {
                             project_id: project_id.0,
                             repository_id: id.to_proto(),
-                            askpass_id,
-                            remote: fetch_options.to_proto(),
+                            path: path.to_proto(),
+                            base_rev,
                         })
-                        .await
-                        .context("sending fetch request")?;
+                        .await?;
 
-                    Ok(RemoteCommandOutput {
-                        stdout: response.stdout,
-                        stderr: response.stderr,
-                    })
+                    receive_diff(&client, project_id, id, response).await
                 }
             }
         })
     }
 
-    pub fn push(
-        &mut self,
-        branch: SharedString,
-        remote: SharedString,
-        options: Option<PushOptions>,
-        askpass: AskPassDelegate,
-        cx: &mut Context<Self>,
-    ) -> oneshot::Receiver<Result<RemoteCommandOutput>> {
-        let askpass_delegates = self.askpass_delegates.clone();
-        let askpass_id = util::post_inc(&mut self.latest_askpass_id);
+    pub fn create_branch(&mut self, branch_name: String) -> oneshot::Receiver<Result<()>> {
         let id = self.id;
+        self.send_job(
+            Some(format!("git switch -c {branch_name}").into()),
+            move |repo, _cx| async move {
+                match repo {
+                    RepositoryState::Local { backend, .. } => {
+                        backend.create_branch(branch_name).await
+                    }
+                    RepositoryState::Remote { project_id, client } => {
+                        client
+                            .request(proto::GitCreateBranch {
+                                project_id: project_id.0,
+                                repository_id: id.to_proto(),
+                                branch_name,
+                            })
+                            .await?;
 
-        let args = options
-            .map(|option| match option {
-                PushOptions::SetUpstream => " --set-upstream",
-                PushOptions::Force => " --force-with-lease",
-            })
-            .unwrap_or("");
-
-        let updates_tx = self
-            .git_store()
-            .and_then(|git_store| match &git_store.read(cx).state {
-                GitStoreState::Local { downstream, .. } => downstream
-                    .as_ref()
-                    .map(|downstream| downstream.updates_tx.clone()),
-                _ => None,
-            });
+                        Ok(())
+                    }
+                }
+            },
+        )
+    }
 
-        let this = cx.weak_entity();
+    pub fn change_branch(&mut self, branch_name: String) -> oneshot::Receiver<Result<()>> {
+        let id = self.id;
         self.send_job(
-            Some(format!("git push {} {} {}", args, branch, remote).into()),
-            move |git_repo, mut cx| async move {
-                match git_repo {
-                    RepositoryState::Local {
-                        backend,
-                        environment,
-                        ..
-                    } => {
-                        let result = backend
-                            .push(
-                                branch.to_string(),
-                                remote.to_string(),
-                                options,
-                                askpass,
-                                environment.clone(),
-                                cx.clone(),
-                            )
-                            .await;
-                        if result.is_ok() {
-                            let branches = backend.branches().await?;
-                            let branch = branches.into_iter().find(|branch| branch.is_head);
-                            log::info!("head branch after scan is {branch:?}");
-                            let snapshot = this.update(&mut cx, |this, cx| {
-                                this.snapshot.branch = branch;
-                                let snapshot = this.snapshot.clone();
-                                cx.emit(RepositoryEvent::Updated {
-                                    full_scan: false,
-                                    new_instance: false,
-                                });
-                                snapshot
-                            })?;
-                            if let Some(updates_tx) = updates_tx {
-                                updates_tx
-                                    .unbounded_send(DownstreamUpdate::UpdateRepository(snapshot))
-                                    .ok();
-                            }
-                        }
-                        result
+            Some(format!("git switch {branch_name}").into()),
+            move |repo, _cx| async move {
+                match repo {
+                    RepositoryState::Local { backend, .. } => {
+                        backend.change_branch(branch_name).await
                     }
                     RepositoryState::Remote { project_id, client } => {
-                        askpass_delegates.lock().insert(askpass_id, askpass);
-                        let _defer = util::defer(|| {
-                            let askpass_delegate = askpass_delegates.lock().remove(&askpass_id);
-                            debug_assert!(askpass_delegate.is_some());
-                        });
-                        let response = client
-                            .request(proto::Push {
+                        client
+                            .request(proto::GitChangeBranch {
                                 project_id: project_id.0,
                                 repository_id: id.to_proto(),
-                                askpass_id,
-                                branch_name: branch.to_string(),
-                                remote_name: remote.to_string(),
-                                options: options.map(|options| match options {
-                                    PushOptions::Force => proto::push::PushOptions::Force,
-                                    PushOptions::SetUpstream => {
-                                        proto::push::PushOptions::SetUpstream
-                                    }
-                                }
-                                    as i32),
+                                branch_name,
                             })
-                            .await
-                            .context("sending push request")?;
+                            .await?;
 
-                        Ok(RemoteCommandOutput {
-                            stdout: response.stdout,
-                            stderr: response.stderr,
-                        })
+                        Ok(())
                     }
                 }
             },
         )
     }
 
-    pub fn pull(
+    pub fn rename_branch(
         &mut self,
-        branch: SharedString,
-        remote: SharedString,
-        askpass: AskPassDelegate,
-        _cx: &mut App,
-    ) -> oneshot::Receiver<Result<RemoteCommandOutput>> {
-        let askpass_delegates = self.askpass_delegates.clone();
-        let askpass_id = util::post_inc(&mut self.latest_askpass_id);
+        branch: String,
+        new_name: String,
+    ) -> oneshot::Receiver<Result<()>> {
         let id = self.id;
-
         self.send_job(
-            Some(format!("git pull {} {}", remote, branch).into()),
-            move |git_repo, cx| async move {
-                match git_repo {
-                    RepositoryState::Local {
-                        backend,
-                        environment,
-                        ..
-                    } => {
-                        backend
-                            .pull(
-                                branch.to_string(),
-                                remote.to_string(),
-                                askpass,
-                                environment.clone(),
-                                cx,
-                            )
-                            .await
+            Some(format!("git branch -m {branch} {new_name}").into()),
+            move |repo, _cx| async move {
+                match repo {
+                    RepositoryState::Local { backend, .. } => {
+                        backend.rename_branch(branch, new_name).await
                     }
                     RepositoryState::Remote { project_id, client } => {
-                        askpass_delegates.lock().insert(askpass_id, askpass);
-                        let _defer = util::defer(|| {
-                            let askpass_delegate = askpass_delegates.lock().remove(&askpass_id);
-                            debug_assert!(askpass_delegate.is_some());
-                        });
-                        let response = client
-                            .request(proto::Pull {
+                        client
+                            .request(proto::GitRenameBranch {
                                 project_id: project_id.0,
                                 repository_id: id.to_proto(),
-                                askpass_id,
-                                branch_name: branch.to_string(),
-                                remote_name: remote.to_string(),
+                                branch,
+                                new_name,
                             })
-                            .await
-                            .context("sending pull request")?;
+                            .await?;
 
-                        Ok(RemoteCommandOutput {
-                            stdout: response.stdout,
-                            stderr: response.stderr,
-                        })
+                        Ok(())
                     }
                 }
             },
         )
     }
 
-    fn spawn_set_index_text_job(
+    pub fn delete_branch(
         &mut self,
-        path: RepoPath,
-        content: Option<String>,
-        hunk_staging_operation_count: Option<usize>,
-        cx: &mut Context<Self>,
-    ) -> oneshot::Receiver<anyhow::Result<()>> {
+        branch_name: String,
+        force: bool,
+    ) -> oneshot::Receiver<Result<()>> {
         let id = self.id;
-        let this = cx.weak_entity();
-        let git_store = self.git_store.clone();
-        self.send_keyed_job(
-            Some(GitJobKey::WriteIndex(path.clone())),
-            None,
-            move |git_repo, mut cx| async move {
-                log::debug!(
-                    "start updating index text for buffer {}",
-                    path.as_unix_str()
-                );
-                match git_repo {
-                    RepositoryState::Local {
-                        backend,
-                        environment,
-                        ..
-                    } => {
-                        backend
-                            .set_index_text(path.clone(), content, environment.clone())
-                            .await?;
+        let flag = if force { "-D" } else { "-d" };
+        self.send_job(
+            Some(format!("git branch {flag} {branch_name}").into()),
+            move |repo, _cx| async move {
+                match repo {
+                    RepositoryState::Local { backend, .. } => {
+                        backend.delete_branch(branch_name, force).await
                     }
                     RepositoryState::Remote { project_id, client } => {
                         client
-                            .request(proto::SetIndexText {
+                            .request(proto::GitDeleteBranch {
                                 project_id: project_id.0,
                                 repository_id: id.to_proto(),
-                                path: path.to_proto(),
-                                text: content,
+                                branch_name,
+                                force,
                             })
                             .await?;
-                    }
-                }
-                log::debug!(
-                    "finish updating index text for buffer {}",
-                    path.as_unix_str()
-                );
 
-                if let Some(hunk_staging_operation_count) = hunk_staging_operation_count {
-                    let project_path = this
-                        .read_with(&cx, |this, cx| this.repo_path_to_project_path(&path, cx))
-                        .ok()
-                        .flatten();
-                    git_store.update(&mut cx, |git_store, cx| {
-                        let buffer_id = git_store
-                            .buffer_store
-                            .read(cx)
-                            .get_by_path(&project_path?)?
-                            .read(cx)
-                            .remote_id();
-                        let diff_state = git_store.diffs.get(&buffer_id)?;
-                        diff_state.update(cx, |diff_state, _| {
-                            diff_state.hunk_staging_operation_count_as_of_write =
-                                hunk_staging_operation_count;
-                        });
-                        Some(())
-                    })?;
+                        Ok(())
+                    }
                 }
-                Ok(())
             },
         )
     }
 
-    pub fn get_remotes(
+    /// Sets the branch that `branch_name` tracks, such as `origin/main`.
+    pub fn set_upstream(
         &mut self,
-        branch_name: Option<String>,
-    ) -> oneshot::Receiver<Result<Vec<Remote>>> {
+        branch_name: String,
+        upstream: String,
+    ) -> oneshot::Receiver<Result<()>> {
         let id = self.id;
-        self.send_job(None, move |repo, _cx| async move {
-            match repo {
-                RepositoryState::Local { backend, .. } => backend.get_remotes(branch_name).await,
-                RepositoryState::Remote { project_id, client } => {
-                    let response = client
-                        .request(proto::GetRemotes {
-                            project_id: project_id.0,
-                            repository_id: id.to_proto(),
-                            branch_name,
-                        })
-                        .await?;
-
-                    let remotes = response
-                        .remotes
-                        .into_iter()
-                        .map(|remotes| git::repository::Remote {
-                            name: remotes.name.into(),
-                        })
-                        .collect();
+        self.send_job(
+            Some(format!("git branch --set-upstream-to={upstream} {branch_name}").into()),
+            move |repo, _cx| async move {
+                match repo {
+                    RepositoryState::Local { backend, .. } => {
+                        backend.set_upstream(branch_name, upstream).await
+                    }
+                    RepositoryState::Remote { project_id, client } => {
+                        client
+                            .request(proto::GitSetUpstream {
+                                project_id: project_id.0,
+                                repository_id: id.to_proto(),
+                                branch_name,
+                                upstream,
+                            })
+                            .await?;
 
-                    Ok(remotes)
+                        Ok(())
+                    }
                 }
-            }
-        })
+            },
+        )
     }
 
-    pub fn branches(&mut self) -> oneshot::Receiver<Result<Vec<Branch>>> {
+    /// Adds patterns for `paths` to an ignore file, and reloads the statuses that they affect.
+    pub fn ignore_paths(
+        &mut self,
+        paths: Vec<RepoPath>,
+        ignore_file: IgnoreFile,
+        cx: &mut Context<Self>,
+    ) -> oneshot::Receiver<Result<()>> {
         let id = self.id;
-        self.send_job(None, move |repo, _| async move {
+        let updates_tx = self
+            .git_store()
+            .and_then(|git_store| match &git_store.read(cx).state {
+                GitStoreState::Local { downstream, .. } => downstream
+                    .as_ref()
+                    .map(|downstream| downstream.updates_tx.clone()),
+                _ => None,
+            });
+        let this = cx.weak_entity();
+        self.send_job(None, move |repo, mut cx| async move {
             match repo {
-                RepositoryState::Local { backend, .. } => backend.branches().await,
+                RepositoryState::Local { backend, .. } => {
+                    backend.ignore_paths(paths, ignore_file).await?;
+                    // Reload the snapshot before returning, so that the newly ignored paths are
+                    // gone from the statuses by the time the caller looks at them.
+                    let this = this.upgrade().context("repository was dropped")?;
+                    Self::reload_snapshot(&this, backend, updates_tx, &mut cx).await
+                }
                 RepositoryState::Remote { project_id, client } => {
-                    let response = client
-                        .request(proto::GitGetBranches {
+                    client
+                        .request(proto::GitIgnorePaths {
                             project_id: project_id.0,
                             repository_id: id.to_proto(),
+                            paths: paths.iter().map(|path| path.to_proto()).collect(),
+                            ignore_file: match ignore_file {
+                                IgnoreFile::Gitignore => git_ignore_paths::IgnoreFile::Gitignore,
+                                IgnoreFile::InfoExclude => {
+                                    git_ignore_paths::IgnoreFile::InfoExclude
+                                }
+                            }
+                            .into(),
                         })
                         .await?;
 
-                    let branches = response
-                        .branches
-                        .into_iter()
-                        .map(|branch| proto_to_branch(&branch))
-                        .collect();
-
-                    Ok(branches)
+                    Ok(())
                 }
             }
         })
     }
 
-    pub fn default_branch(&mut self) -> oneshot::Receiver<Result<Option<SharedString>>> {
+    pub fn is_ignored(&mut self, path: RepoPath) -> oneshot::Receiver<Result<bool>> {
         let id = self.id;
-        self.send_job(None, move |repo, _| async move {
+        self.send_job(None, move |repo, _cx| async move {
             match repo {
-                RepositoryState::Local { backend, .. } => backend.default_branch().await,
+                RepositoryState::Local { backend, .. } => backend.is_ignored(path).await,
                 RepositoryState::Remote { project_id, client } => {
                     let response = client
-                        .request(proto::GetDefaultBranch {
+                        .request(proto::GitIsIgnored {
                             project_id: project_id.0,
                             repository_id: id.to_proto(),
+                            path: path.to_proto(),
                         })
                         .await?;
 
-                    anyhow::Ok(response.branch.map(SharedString::from))
+                    Ok(response.ignored)
                 }
             }
         })
     }
 
-    pub fn diff(&mut self, diff_type: DiffType, _cx: &App) -> oneshot::Receiver<Result<String>> {
+    /// Deletes the untracked files within `paths`, or the whole working directory if it's empty,
+    /// and returns the paths that were deleted. With [`CleanOptions::dry_run`], nothing is deleted
+    /// and the paths that would be are returned, so that they can be confirmed first.
+    pub fn clean(
+        &mut self,
+        paths: Vec<RepoPath>,
+        options: CleanOptions,
+        cx: &mut Context<Self>,
+    ) -> oneshot::Receiver<Result<Vec<RepoPath>>> {
         let id = self.id;
-        self.send_job(None, move |repo, _cx| async move {
+        let updates_tx = self
+            .git_store()
+            .and_then(|git_store| match &git_store.read(cx).state {
+                GitStoreState::Local { downstream, .. } => downstream
+                    .as_ref()
+                    .map(|downstream| downstream.updates_tx.clone()),
+                _ => None,
+            });
+        let this = cx.weak_entity();
+        let status = (!options.dry_run).then(|| "git clean".into());
+        self.send_job(status, move |repo, mut cx| async move {
             match repo {
-                RepositoryState::Local { backend, .. } => backend.diff(diff_type).await,
+                RepositoryState::Local {
+                    backend,
+                    environment,
+                    ..
+                } => {
+                    let cleaned_paths = backend.clean(paths, options, environment).await?;
+                    if !options.dry_run {
+                        // Reload the snapshot before returning, so that the deleted files are
+                        // gone from the statuses by the time the caller looks at them.
+                        let this = this.upgrade().context("repository was dropped")?;
+                        Self::reload_snapshot(&this, backend, updates_tx, &mut cx).await?;
+                    }
+                    Ok(cleaned_paths)
+                }
                 RepositoryState::Remote { project_id, client } => {
                     let response = client
-                        .request(proto::GitDiff {
+                        .request(proto::GitClean {
                             project_id: project_id.0,
                             repository_id: id.to_proto(),
-                            diff_type: match diff_type {
-                                DiffType::HeadToIndex => {
-                                    proto::git_diff::DiffType::HeadToIndex.into()
-                                }
-                                DiffType::HeadToWorktree => {
-                                    proto::git_diff::DiffType::HeadToWorktree.into()
-                                }
-                            },
+                            paths: paths.iter().map(|path| path.to_proto()).collect(),
+                            dry_run: options.dry_run,
+                            directories: options.directories,
+                            ignored: options.ignored,
                         })
                         .await?;
 
-                    Ok(response.diff)
+                    response
+                        .paths
+                        .iter()
+                        .map(|path| RepoPath::from_proto(path))
+                        .collect()
                 }
             }
         })
     }
 
-    pub fn create_branch(&mut self, branch_name: String) -> oneshot::Receiver<Result<()>> {
+    /// Resolves a conflicted file by keeping `side`'s version of it and staging it. Resolving the
+    /// same file again before this finishes replaces this resolution, rather than queueing both.
+    pub fn resolve_conflict(
+        &mut self,
+        path: RepoPath,
+        side: ConflictSide,
+        cx: &mut Context<Self>,
+    ) -> oneshot::Receiver<Result<()>> {
         let id = self.id;
-        self.send_job(
-            Some(format!("git switch -c {branch_name}").into()),
-            move |repo, _cx| async move {
-                match repo {
-                    RepositoryState::Local { backend, .. } => {
-                        backend.create_branch(branch_name).await
+        let updates_tx = self
+            .git_store()
+            .and_then(|git_store| match &git_store.read(cx).state {
+                GitStoreState::Local { downstream, .. } => downstream
+                    .as_ref()
+                    .map(|downstream| downstream.updates_tx.clone()),
+                _ => None,
+            });
+        let this = cx.weak_entity();
+        let status = match side {
+            ConflictSide::Ours => format!("git checkout --ours {}", path.as_unix_str()),
+            ConflictSide::Theirs => format!("git checkout --theirs {}", path.as_unix_str()),
+        };
+        self.send_keyed_job(
+            Some(GitJobKey::ResolveConflict(path.clone())),
+            Some(status.into()),
+            move |git_repo, mut cx| async move {
+                match git_repo {
+                    RepositoryState::Local {
+                        backend,
+                        environment,
+                        ..
+                    } => {
+                        backend
+                            .resolve_conflict(path.clone(), side, environment)
+                            .await?;
+                        let this = this.upgrade().context("repository was dropped")?;
+                        // Reloading the snapshot keeps the conflicted paths while the merge heads
+                        // are unchanged, so the resolved path is removed from them here.
+                        this.update(&mut cx, |this, cx| {
+                            if this.snapshot.merge.conflicted_paths.remove(&path) {
+                                cx.emit(RepositoryEvent::MergeHeadsChanged);
+                            }
+                        })?;
+                        Self::reload_snapshot(&this, backend, updates_tx, &mut cx).await
                     }
                     RepositoryState::Remote { project_id, client } => {
                         client
-                            .request(proto::GitCreateBranch {
+                            .request(proto::GitResolveConflict {
                                 project_id: project_id.0,
                                 repository_id: id.to_proto(),
-                                branch_name,
+                                path: path.to_proto(),
+                                side: match side {
+                                    ConflictSide::Ours => {
+                                        git_resolve_conflict::ConflictSide::Ours.into()
+                                    }
+                                    ConflictSide::Theirs => {
+                                        git_resolve_conflict::ConflictSide::Theirs.into()
+                                    }
+                                },
                             })
                             .await?;
-
                         Ok(())
                     }
                 }
@@ -4352,62 +7499,174 @@ impl Repository {
         )
     }
 
-    pub fn change_branch(&mut self, branch_name: String) -> oneshot::Receiver<Result<()>> {
+    /// Downloads the content of the files stored by Git LFS, replacing their pointers.
+    pub fn lfs_pull(
+        &mut self,
+        askpass: AskPassDelegate,
+        cx: &mut Context<Self>,
+    ) -> oneshot::Receiver<Result<RemoteCommandOutput>> {
+        let askpass_delegates = self.askpass_delegates.clone();
+        let askpass_id = util::post_inc(&mut self.latest_askpass_id);
         let id = self.id;
+        let updates_tx = self
+            .git_store()
+            .and_then(|git_store| match &git_store.read(cx).state {
+                GitStoreState::Local { downstream, .. } => downstream
+                    .as_ref()
+                    .map(|downstream| downstream.updates_tx.clone()),
+                _ => None,
+            });
+        let this = cx.weak_entity();
         self.send_job(
-            Some(format!("git switch {branch_name}").into()),
-            move |repo, _cx| async move {
-                match repo {
-                    RepositoryState::Local { backend, .. } => {
-                        backend.change_branch(branch_name).await
+            Some("git lfs pull".into()),
+            move |git_repo, mut cx| async move {
+                match git_repo {
+                    RepositoryState::Local {
+                        backend,
+                        environment,
+                        ..
+                    } => {
+                        let output = backend.lfs_pull(askpass, environment, cx.clone()).await?;
+                        // Reload the snapshot before returning, so that the downloaded files are
+                        // no longer listed as pointers by the time the caller looks at them.
+                        let this = this.upgrade().context("repository was dropped")?;
+                        Self::reload_snapshot(&this, backend, updates_tx, &mut cx).await?;
+                        Ok(output)
                     }
                     RepositoryState::Remote { project_id, client } => {
-                        client
-                            .request(proto::GitChangeBranch {
+                        askpass_delegates.lock().insert(askpass_id, askpass);
+                        let _defer = util::defer(|| {
+                            let askpass_delegate = askpass_delegates.lock().remove(&askpass_id);
+                            debug_assert!(askpass_delegate.is_some());
+                        });
+                        let response = client
+                            .request(proto::GitLfsPull {
                                 project_id: project_id.0,
                                 repository_id: id.to_proto(),
-                                branch_name,
+                                askpass_id,
                             })
-                            .await?;
+                            .await
+                            .context("sending lfs pull request")?;
 
-                        Ok(())
+                        Ok(RemoteCommandOutput {
+                            stdout: response.stdout,
+                            stderr: response.stderr,
+                        })
+                    }
+                }
+            },
+        )
+    }
+
+    /// Locks a file stored by Git LFS on the server, so that no one else can push changes to it.
+    pub fn lfs_lock(
+        &mut self,
+        path: RepoPath,
+        askpass: AskPassDelegate,
+        cx: &mut Context<Self>,
+    ) -> oneshot::Receiver<Result<()>> {
+        self.lfs_lock_job(path, None, askpass, cx)
+    }
+
+    /// Releases the lock on a file stored by Git LFS. Unless `force` is set, only locks held by
+    /// the current user are released.
+    pub fn lfs_unlock(
+        &mut self,
+        path: RepoPath,
+        force: bool,
+        askpass: AskPassDelegate,
+        cx: &mut Context<Self>,
+    ) -> oneshot::Receiver<Result<()>> {
+        self.lfs_lock_job(path, Some(force), askpass, cx)
+    }
+
+    /// Locks `path`, or unlocks it if `unlock_force` is set, and reloads the snapshot so that its
+    /// lock is up to date.
+    fn lfs_lock_job(
+        &mut self,
+        path: RepoPath,
+        unlock_force: Option<bool>,
+        askpass: AskPassDelegate,
+        cx: &mut Context<Self>,
+    ) -> oneshot::Receiver<Result<()>> {
+        let askpass_delegates = self.askpass_delegates.clone();
+        let askpass_id = util::post_inc(&mut self.latest_askpass_id);
+        let id = self.id;
+        let updates_tx = self
+            .git_store()
+            .and_then(|git_store| match &git_store.read(cx).state {
+                GitStoreState::Local { downstream, .. } => downstream
+                    .as_ref()
+                    .map(|downstream| downstream.updates_tx.clone()),
+                _ => None,
+            });
+        let this = cx.weak_entity();
+        let command = if unlock_force.is_some() {
+            "unlock"
+        } else {
+            "lock"
+        };
+        let status = format!("git lfs {command} {}", path.as_unix_str()).into();
+        self.send_job(Some(status), move |git_repo, mut cx| async move {
+            match git_repo {
+                RepositoryState::Local {
+                    backend,
+                    environment,
+                    ..
+                } => {
+                    match unlock_force {
+                        Some(force) => {
+                            backend
+                                .lfs_unlock(path, force, askpass, environment, cx.clone())
+                                .await?
+                        }
+                        None => {
+                            backend
+                                .lfs_lock(path, askpass, environment, cx.clone())
+                                .await?
+                        }
                     }
+                    let this = this.upgrade().context("repository was dropped")?;
+                    Self::reload_snapshot(&this, backend, updates_tx, &mut cx).await
                 }
-            },
-        )
-    }
-
-    pub fn rename_branch(
-        &mut self,
-        branch: String,
-        new_name: String,
-    ) -> oneshot::Receiver<Result<()>> {
-        let id = self.id;
-        self.send_job(
-            Some(format!("git branch -m {branch} {new_name}").into()),
-            move |repo, _cx| async move {
-                match repo {
-                    RepositoryState::Local { backend, .. } => {
-                        backend.rename_branch(branch, new_name).await
-                    }
-                    RepositoryState::Remote { project_id, client } => {
-                        client
-                            .request(proto::GitRenameBranch {
-                                project_id: project_id.0,
-                                repository_id: id.to_proto(),
-                                branch,
-                                new_name,
-                            })
-                            .await?;
-
-                        Ok(())
+                RepositoryState::Remote { project_id, client } => {
+                    askpass_delegates.lock().insert(askpass_id, askpass);
+                    let _defer = util::defer(|| {
+                        let askpass_delegate = askpass_delegates.lock().remove(&askpass_id);
+                        debug_assert!(askpass_delegate.is_some());
+                    });
+                    match unlock_force {
+                        Some(force) => {
+                            client
+                                .request(proto::GitLfsUnlock {
+                                    project_id: project_id.0,
+                                    repository_id: id.to_proto(),
+                                    path: path.to_proto(),
+                                    force,
+                                    askpass_id,
+                                })
+                                .await
+                                .context("sending lfs unlock request")?;
+                        }
+                        None => {
+                            client
+                                .request(proto::GitLfsLock {
+                                    project_id: project_id.0,
+                                    repository_id: id.to_proto(),
+                                    path: path.to_proto(),
+                                    askpass_id,
+                                })
+                                .await
+                                .context("sending lfs lock request")?;
+                        }
                     }
+                    Ok(())
                 }
-            },
-        )
+            }
+        })
     }
 
-    pub fn check_for_pushed_commits(&mut self) -> oneshot::Receiver<Result<Vec<SharedString>>> {
+    pub fn check_for_pushed_commits(&mut self) -> oneshot::Receiver<Result<Vec<PushedBranch>>> {
         let id = self.id;
         self.send_job(None, move |repo, _cx| async move {
             match repo {
@@ -4420,7 +7679,11 @@ impl Repository {
                         })
                         .await?;
 
-                    let branches = response.pushed_to.into_iter().map(Into::into).collect();
+                    let branches = response
+                        .pushed_to
+                        .into_iter()
+                        .map(proto_to_pushed_branch)
+                        .collect();
 
                     Ok(branches)
                 }
@@ -4428,6 +7691,22 @@ impl Repository {
         })
     }
 
+    /// Loads the raw contents of a file at HEAD, which is used to compare binary files such as
+    /// images against their committed version.
+    pub fn load_committed_bytes(
+        &mut self,
+        repo_path: RepoPath,
+    ) -> oneshot::Receiver<Result<Option<Vec<u8>>>> {
+        self.send_job(None, move |repo, _cx| async move {
+            match repo {
+                RepositoryState::Local { backend, .. } => {
+                    Ok(backend.load_committed_bytes(repo_path).await)
+                }
+                RepositoryState::Remote { .. } => anyhow::bail!("not implemented yet"),
+            }
+        })
+    }
+
     pub fn checkpoint(&mut self) -> oneshot::Receiver<Result<GitRepositoryCheckpoint>> {
         self.send_job(None, |repo, _cx| async move {
             match repo {
@@ -4463,12 +7742,18 @@ impl Repository {
                 .into_iter()
                 .filter_map(|path| RepoPath::from_proto(&path).log_err()),
         );
+        // The host sends an update each time it reloads the repository's git state, but doesn't
+        // say which refs changed, so the branches are listed again after every update.
+        self.invalidate_branch_cache(cx);
         self.snapshot.branch = update.branch_summary.as_ref().map(proto_to_branch);
         self.snapshot.head_commit = update
             .head_commit_details
             .as_ref()
             .map(proto_to_commit_details);
+        self.snapshot.head_state = update.head_state.and_then(proto_to_head_state);
 
+        // Merge heads aren't replicated, so a change in the conflicted paths stands in for them.
+        let merge_conflicts_changed = conflicted_paths != self.snapshot.merge.conflicted_paths;
         self.snapshot.merge.conflicted_paths = conflicted_paths;
         self.snapshot.merge.message = update.merge_message.map(SharedString::from);
         self.snapshot.stash_entries = GitStash {
@@ -4478,6 +7763,15 @@ impl Repository {
                 .filter_map(|entry| proto_to_stash(entry).ok())
                 .collect(),
         };
+        let rebase = update.rebase_state.map(proto_to_rebase_state);
+        let rebase_changed = rebase != self.snapshot.rebase;
+        self.snapshot.rebase = rebase;
+        let lfs_statuses = update
+            .lfs_statuses
+            .into_iter()
+            .filter_map(|status| proto_to_lfs_file_status(status).log_err())
+            .collect::<BTreeMap<_, _>>();
+        self.snapshot.lfs_statuses = TreeMap::from_ordered_entries(lfs_statuses);
 
         let edits = update
             .removed_statuses
@@ -4496,6 +7790,13 @@ impl Repository {
                     }),
             )
             .collect::<Vec<_>>();
+        let status_changed_paths = edits
+            .iter()
+            .map(|edit| match edit {
+                sum_tree::Edit::Insert(entry) => entry.repo_path.clone(),
+                sum_tree::Edit::Remove(PathKey(path)) => RepoPath(path.clone()),
+            })
+            .collect::<Arc<[_]>>();
         self.snapshot.statuses_by_path.edit(edits, ());
         if update.is_last_update {
             self.snapshot.scan_id = update.scan_id;
@@ -4504,6 +7805,15 @@ impl Repository {
             full_scan: true,
             new_instance: is_new,
         });
+        if !is_new && !status_changed_paths.is_empty() {
+            cx.emit(RepositoryEvent::StatusesChanged(status_changed_paths));
+        }
+        if merge_conflicts_changed {
+            cx.emit(RepositoryEvent::MergeHeadsChanged);
+        }
+        if rebase_changed {
+            cx.emit(RepositoryEvent::RebaseStateChanged);
+        }
         Ok(())
     }
 
@@ -4544,6 +7854,8 @@ impl Repository {
         updates_tx: Option<mpsc::UnboundedSender<DownstreamUpdate>>,
         cx: &mut Context<Self>,
     ) {
+        self.invalidate_branch_cache(cx);
+
         let this = cx.weak_entity();
         let _ = self.send_keyed_job(
             Some(GitJobKey::ReloadGitState),
@@ -4557,33 +7869,70 @@ impl Repository {
                 let RepositoryState::Local { backend, .. } = state else {
                     bail!("not a local repository")
                 };
-                let (snapshot, events) = this
-                    .update(&mut cx, |this, _| {
-                        this.paths_needing_status_update.clear();
-                        compute_snapshot(
-                            this.id,
-                            this.work_directory_abs_path.clone(),
-                            this.snapshot.clone(),
-                            backend.clone(),
-                        )
-                    })?
-                    .await?;
-                this.update(&mut cx, |this, cx| {
-                    this.snapshot = snapshot.clone();
-                    for event in events {
-                        cx.emit(event);
-                    }
-                })?;
-                if let Some(updates_tx) = updates_tx {
-                    updates_tx
-                        .unbounded_send(DownstreamUpdate::UpdateRepository(snapshot))
-                        .ok();
-                }
-                Ok(())
+                Self::reload_snapshot(&this, backend, updates_tx, &mut cx).await
             },
         );
     }
 
+    /// Reloads the snapshot right away. Remote repositories are kept up to date by the host,
+    /// so there is nothing to do for them.
+    pub fn refresh_status(&mut self, cx: &mut Context<Self>) -> oneshot::Receiver<Result<()>> {
+        let updates_tx = self.downstream_updates_tx(cx);
+        let this = cx.weak_entity();
+        self.send_job(None, move |state, mut cx| async move {
+            match state {
+                RepositoryState::Local { backend, .. } => {
+                    let this = this.upgrade().context("repository was dropped")?;
+                    Self::reload_snapshot(&this, backend, updates_tx, &mut cx).await
+                }
+                RepositoryState::Remote { .. } => Ok(()),
+            }
+        })
+    }
+
+    fn downstream_updates_tx(&self, cx: &App) -> Option<mpsc::UnboundedSender<DownstreamUpdate>> {
+        self.git_store()
+            .and_then(|git_store| match &git_store.read(cx).state {
+                GitStoreState::Local { downstream, .. } => downstream
+                    .as_ref()
+                    .map(|downstream| downstream.updates_tx.clone()),
+                _ => None,
+            })
+    }
+
+    /// Recomputes the snapshot, emitting events for what changed and forwarding it to
+    /// downstream clients.
+    async fn reload_snapshot(
+        this: &Entity<Self>,
+        backend: Arc<dyn GitRepository>,
+        updates_tx: Option<mpsc::UnboundedSender<DownstreamUpdate>>,
+        cx: &mut AsyncApp,
+    ) -> Result<()> {
+        let (snapshot, events) = this
+            .update(cx, |this, _| {
+                this.paths_needing_status_update.clear();
+                compute_snapshot(
+                    this.id,
+                    this.work_directory_abs_path.clone(),
+                    this.snapshot.clone(),
+                    backend,
+                )
+            })?
+            .await?;
+        this.update(cx, |this, cx| {
+            this.snapshot = snapshot.clone();
+            for event in events {
+                cx.emit(event);
+            }
+        })?;
+        if let Some(updates_tx) = updates_tx {
+            updates_tx
+                .unbounded_send(DownstreamUpdate::UpdateRepository(snapshot))
+                .ok();
+        }
+        Ok(())
+    }
+
     fn spawn_local_git_worker(
         work_directory_abs_path: Arc<Path>,
         dot_git_abs_path: Arc<Path>,
@@ -4595,23 +7944,31 @@ impl Repository {
     ) -> mpsc::UnboundedSender<GitJob> {
         let (job_tx, mut job_rx) = mpsc::unbounded::<GitJob>();
 
-        cx.spawn(async move |_, cx| {
-            let environment = project_environment
+        cx.spawn(async move |this, cx| {
+            let (environment, git_settings) = project_environment
                 .upgrade()
                 .context("missing project environment")?
                 .update(cx, |project_environment, cx| {
-                    project_environment.get_local_directory_environment(&Shell::System, work_directory_abs_path.clone(), cx)
-                })?
+                    (
+                        project_environment.get_local_directory_environment(&Shell::System, work_directory_abs_path.clone(), cx),
+                        project_environment.git_settings(&work_directory_abs_path, cx),
+                    )
+                })?;
+            let mut environment = environment
                 .await
                 .unwrap_or_else(|| {
                     log::error!("failed to get working directory environment for repository {work_directory_abs_path:?}");
                     HashMap::default()
                 });
+            // What the git settings ask for takes precedence over the directory's environment.
+            environment.extend(git_settings.command_environment());
             let search_paths = environment.get("PATH").map(|val| val.to_owned());
             let backend = cx
                 .background_spawn(async move {
-                    let system_git_binary_path = search_paths.and_then(|search_paths| which::which_in("git", Some(search_paths), &work_directory_abs_path).ok())
-                        .or_else(|| which::which("git").ok());
+                    let system_git_binary_path = git_settings.binary_path.or_else(|| {
+                        search_paths.and_then(|search_paths| which::which_in("git", Some(search_paths), &work_directory_abs_path).ok())
+                            .or_else(|| which::which("git").ok())
+                    });
                     fs.open_repo(&dot_git_abs_path, system_git_binary_path.as_deref())
                         .with_context(|| format!("opening repository at {dot_git_abs_path:?}"))
                 })
@@ -4642,6 +7999,7 @@ impl Repository {
                             .iter()
                             .any(|other_job| other_job.key.as_ref() == Some(current_key))
                         {
+                            this.update(cx, |this, _| this.jobs.remove(&job.id)).ok();
                             continue;
                         }
                     (job.job)(state.clone(), cx).await;
@@ -4665,7 +8023,7 @@ impl Repository {
     ) -> mpsc::UnboundedSender<GitJob> {
         let (job_tx, mut job_rx) = mpsc::unbounded::<GitJob>();
 
-        cx.spawn(async move |_, cx| {
+        cx.spawn(async move |this, cx| {
             let state = RepositoryState::Remote { project_id, client };
             let mut jobs = VecDeque::new();
             loop {
@@ -4679,6 +8037,7 @@ impl Repository {
                             .iter()
                             .any(|other_job| other_job.key.as_ref() == Some(current_key))
                     {
+                        this.update(cx, |this, _| this.jobs.remove(&job.id)).ok();
                         continue;
                     }
                     (job.job)(state.clone(), cx).await;
@@ -4775,90 +8134,183 @@ impl Repository {
         self.paths_needing_status_update.extend(paths);
 
         let this = cx.weak_entity();
-        let _ = self.send_keyed_job(
-            Some(GitJobKey::RefreshStatuses),
-            None,
-            |state, mut cx| async move {
-                let (prev_snapshot, mut changed_paths) = this.update(&mut cx, |this, _| {
-                    (
-                        this.snapshot.clone(),
-                        mem::take(&mut this.paths_needing_status_update),
-                    )
-                })?;
-                let RepositoryState::Local { backend, .. } = state else {
-                    bail!("not a local repository")
-                };
+        let _ = self.send_keyed_job(Some(GitJobKey::RefreshStatuses), None, |state, cx| {
+            Self::refresh_statuses_of_changed_paths(this, state, updates_tx, cx)
+        });
+    }
 
-                let paths = changed_paths.iter().cloned().collect::<Vec<_>>();
-                if paths.is_empty() {
-                    return Ok(());
+    /// Rescans the status of every path in the repository, without reloading the rest of the
+    /// snapshot. This catches changes made by tools that bypass the file system watcher.
+    /// Remote repositories are kept up to date by the host, so there is nothing to do for them.
+    pub fn refresh_statuses(&mut self, cx: &mut Context<Self>) -> oneshot::Receiver<Result<()>> {
+        let updates_tx = self.downstream_updates_tx(cx);
+        let this = cx.weak_entity();
+        // Not keyed, so that a scan queued after it can't cancel it while it's awaited.
+        self.send_job(None, move |state, mut cx| async move {
+            match state {
+                RepositoryState::Local { .. } => {
+                    this.update(&mut cx, |this, _| {
+                        // Paths that no longer have a status are only removed if they're rescanned.
+                        let paths_with_status = this
+                            .snapshot
+                            .statuses_by_path
+                            .iter()
+                            .map(|entry| entry.repo_path.clone());
+                        this.paths_needing_status_update
+                            .insert(RelPath::empty().into());
+                        this.paths_needing_status_update.extend(paths_with_status);
+                    })?;
+                    Self::refresh_statuses_of_changed_paths(this, state, updates_tx, cx).await
                 }
-                let statuses = backend.status(&paths).await?;
-                let stash_entries = backend.stash_entries().await?;
-
-                let changed_path_statuses = cx
-                    .background_spawn(async move {
-                        let mut changed_path_statuses = Vec::new();
-                        let prev_statuses = prev_snapshot.statuses_by_path.clone();
-                        let mut cursor = prev_statuses.cursor::<PathProgress>(());
-
-                        for (repo_path, status) in &*statuses.entries {
-                            changed_paths.remove(repo_path);
-                            if cursor.seek_forward(&PathTarget::Path(repo_path), Bias::Left)
-                                && cursor.item().is_some_and(|entry| entry.status == *status)
-                            {
-                                continue;
-                            }
+                RepositoryState::Remote { .. } => Ok(()),
+            }
+        })
+    }
 
-                            changed_path_statuses.push(Edit::Insert(StatusEntry {
-                                repo_path: repo_path.clone(),
-                                status: *status,
-                            }));
-                        }
-                        let mut cursor = prev_statuses.cursor::<PathProgress>(());
-                        for path in changed_paths.into_iter() {
-                            if cursor.seek_forward(&PathTarget::Path(&path), Bias::Left) {
-                                changed_path_statuses.push(Edit::Remove(PathKey(path.0)));
-                            }
-                        }
-                        changed_path_statuses
-                    })
-                    .await;
+    async fn refresh_statuses_of_changed_paths(
+        this: WeakEntity<Self>,
+        state: RepositoryState,
+        updates_tx: Option<mpsc::UnboundedSender<DownstreamUpdate>>,
+        mut cx: AsyncApp,
+    ) -> Result<()> {
+        let (prev_snapshot, mut changed_paths) = this.update(&mut cx, |this, _| {
+            (
+                this.snapshot.clone(),
+                mem::take(&mut this.paths_needing_status_update),
+            )
+        })?;
+        let RepositoryState::Local { backend, .. } = state else {
+            bail!("not a local repository")
+        };
 
-                this.update(&mut cx, |this, cx| {
-                    let needs_update = !changed_path_statuses.is_empty()
-                        || this.snapshot.stash_entries != stash_entries;
-                    this.snapshot.stash_entries = stash_entries;
-                    if !changed_path_statuses.is_empty() {
-                        this.snapshot
-                            .statuses_by_path
-                            .edit(changed_path_statuses, ());
-                        this.snapshot.scan_id += 1;
+        let paths = changed_paths.iter().cloned().collect::<Vec<_>>();
+        if paths.is_empty() {
+            return Ok(());
+        }
+        let statuses = backend.status(&paths).await?;
+        let stash_entries = backend.stash_entries().await?;
+
+        let changed_path_statuses = cx
+            .background_spawn(async move {
+                let mut changed_path_statuses = Vec::new();
+                let prev_statuses = prev_snapshot.statuses_by_path.clone();
+                let mut cursor = prev_statuses.cursor::<PathProgress>(());
+
+                for (repo_path, status) in &*statuses.entries {
+                    changed_paths.remove(repo_path);
+                    if cursor.seek_forward(&PathTarget::Path(repo_path), Bias::Left)
+                        && cursor.item().is_some_and(|entry| entry.status == *status)
+                    {
+                        continue;
                     }
 
-                    if needs_update {
-                        cx.emit(RepositoryEvent::Updated {
-                            full_scan: false,
-                            new_instance: false,
-                        });
+                    changed_path_statuses.push(Edit::Insert(StatusEntry {
+                        repo_path: repo_path.clone(),
+                        status: *status,
+                    }));
+                }
+                let mut cursor = prev_statuses.cursor::<PathProgress>(());
+                for path in changed_paths.into_iter() {
+                    if cursor.seek_forward(&PathTarget::Path(&path), Bias::Left) {
+                        changed_path_statuses.push(Edit::Remove(PathKey(path.0)));
                     }
+                }
+                changed_path_statuses
+            })
+            .await;
 
-                    if let Some(updates_tx) = updates_tx {
-                        updates_tx
-                            .unbounded_send(DownstreamUpdate::UpdateRepository(
-                                this.snapshot.clone(),
-                            ))
-                            .ok();
-                    }
-                    cx.emit(RepositoryEvent::PathsChanged);
+        this.update(&mut cx, |this, cx| {
+            let needs_update =
+                !changed_path_statuses.is_empty() || this.snapshot.stash_entries != stash_entries;
+            this.snapshot.stash_entries = stash_entries;
+            let status_changed_paths = changed_path_statuses
+                .iter()
+                .map(|edit| match edit {
+                    Edit::Insert(entry) => entry.repo_path.clone(),
+                    Edit::Remove(PathKey(path)) => RepoPath(path.clone()),
                 })
-            },
-        );
+                .collect::<Arc<[_]>>();
+            if !changed_path_statuses.is_empty() {
+                this.snapshot
+                    .statuses_by_path
+                    .edit(changed_path_statuses, ());
+                this.snapshot.scan_id += 1;
+            }
+
+            if needs_update {
+                cx.emit(RepositoryEvent::Updated {
+                    full_scan: false,
+                    new_instance: false,
+                });
+            }
+            if !status_changed_paths.is_empty() {
+                cx.emit(RepositoryEvent::StatusesChanged(status_changed_paths));
+            }
+
+            if let Some(updates_tx) = updates_tx {
+                updates_tx
+                    .unbounded_send(DownstreamUpdate::UpdateRepository(this.snapshot.clone()))
+                    .ok();
+            }
+            cx.emit(RepositoryEvent::PathsChanged);
+        })
     }
 
     /// currently running git command and when it started
     pub fn current_job(&self) -> Option<JobInfo> {
-        self.active_jobs.values().next().cloned()
+        self.jobs.values().find_map(|job| {
+            Some(JobInfo {
+                start: job.started_at?,
+                message: job.message.clone()?,
+            })
+        })
+    }
+
+    /// The progress of the running fetch, push, pull or maintenance, once it has been reported.
+    pub fn remote_progress(&self) -> Option<&RemoteProgress> {
+        self.remote_progress.as_ref().map(|(_, progress)| progress)
+    }
+
+    /// Returns a sender for the progress of the remote operation with the given askpass ID. The
+    /// progress is shown as the repository's until the sender is dropped, and is also sent to
+    /// `forward_to`, if given.
+    fn remote_progress_sender(
+        this: WeakEntity<Self>,
+        askpass_id: u64,
+        forward_to: Option<mpsc::UnboundedSender<RemoteProgress>>,
+        cx: &AsyncApp,
+    ) -> mpsc::UnboundedSender<RemoteProgress> {
+        let (progress_tx, mut progress_rx) = mpsc::unbounded::<RemoteProgress>();
+        cx.spawn(async move |cx| {
+            while let Some(progress) = progress_rx.next().await {
+                if let Some(forward_to) = &forward_to {
+                    forward_to.unbounded_send(progress.clone()).ok();
+                }
+                let updated = this.update(cx, |this, cx| {
+                    this.remote_progress = Some((askpass_id, progress));
+                    cx.emit(JobsUpdated);
+                    cx.notify();
+                });
+                if updated.is_err() {
+                    return;
+                }
+            }
+            this.update(cx, |this, cx| {
+                // A later operation's progress may have been reported already.
+                if this
+                    .remote_progress
+                    .as_ref()
+                    .is_some_and(|(id, _)| *id == askpass_id)
+                {
+                    this.remote_progress = None;
+                    cx.emit(JobsUpdated);
+                    cx.notify();
+                }
+            })
+            .ok();
+        })
+        .detach();
+        progress_tx
     }
 
     pub fn barrier(&mut self) -> oneshot::Receiver<()> {
@@ -4866,6 +8318,21 @@ impl Repository {
     }
 }
 
+/// Finds the hosting provider of the remote with the given name, along with the repository's
+/// owner and name on it.
+fn hosting_remote(
+    backend: &Arc<dyn GitRepository>,
+    remote: &str,
+    cx: &AsyncApp,
+) -> Result<(Arc<dyn GitHostingProvider + Send + Sync>, ParsedGitRemote)> {
+    let remote_url = backend
+        .remote_url(remote)
+        .with_context(|| format!("remote \"{remote}\" not found"))?;
+    let provider_registry = cx.update(GitHostingProviderRegistry::default_global)?;
+    parse_git_remote_url(provider_registry, &remote_url)
+        .with_context(|| format!("no known git hosting provider for {remote_url}"))
+}
+
 fn get_permalink_in_rust_registry_src(
     provider_registry: Arc<GitHostingProviderRegistry>,
     path: PathBuf,
@@ -5006,6 +8473,66 @@ fn deserialize_blame_buffer_response(
     })
 }
 
+fn tag_to_proto(tag: &Tag) -> proto::GitTag {
+    proto::GitTag {
+        name: tag.name.to_string(),
+        sha: tag.sha.to_string(),
+        message: tag.message.as_ref().map(ToString::to_string),
+    }
+}
+
+fn proto_to_tag(tag: proto::GitTag) -> Tag {
+    Tag {
+        name: tag.name.into(),
+        sha: tag.sha.into(),
+        message: tag.message.map(SharedString::from),
+    }
+}
+
+fn pushed_branch_to_proto(branch: &PushedBranch) -> proto::PushedBranch {
+    proto::PushedBranch {
+        name: branch.name.to_string(),
+        remote_name: branch.remote_name.to_string(),
+        remote_url: branch.remote_url.as_ref().map(ToString::to_string),
+        tip_sha: branch.tip_sha.to_string(),
+        tip_subject: branch.tip_subject.to_string(),
+    }
+}
+
+fn proto_to_pushed_branch(branch: proto::PushedBranch) -> PushedBranch {
+    PushedBranch {
+        name: branch.name.into(),
+        remote_name: branch.remote_name.into(),
+        remote_url: branch.remote_url.map(SharedString::from),
+        tip_sha: branch.tip_sha.into(),
+        tip_subject: branch.tip_subject.into(),
+    }
+}
+
+fn line_blame_to_proto(line: &LineBlame) -> proto::LineBlame {
+    proto::LineBlame {
+        row: line.row,
+        sha: line.sha.as_bytes().into(),
+        author: line.author.clone(),
+        author_mail: line.author_mail.clone(),
+        author_time: line.author_time,
+        author_tz: line.author_tz.clone(),
+        summary: line.summary.clone(),
+    }
+}
+
+fn proto_to_line_blame(line: proto::LineBlame) -> Result<LineBlame> {
+    Ok(LineBlame {
+        row: line.row,
+        sha: git::Oid::from_bytes(&line.sha)?,
+        author: line.author,
+        author_mail: line.author_mail,
+        author_time: line.author_time,
+        author_tz: line.author_tz,
+        summary: line.summary,
+    })
+}
+
 fn branch_to_proto(branch: &git::repository::Branch) -> proto::Branch {
     proto::Branch {
         is_head: branch.is_head,
@@ -5088,6 +8615,47 @@ fn proto_to_commit_details(proto: &proto::GitCommitDetails) -> CommitDetails {
     }
 }
 
+fn ready_receiver<T>(value: T) -> oneshot::Receiver<T> {
+    let (sender, receiver) = oneshot::channel();
+    sender.send(value).ok();
+    receiver
+}
+
+/// Stages or unstages all of the paths at once and, if that fails, each half of them on its own,
+/// splitting further until the paths that fail are found, so that one bad path doesn't keep the
+/// rest from being updated without running git once per path. Failing to take the index lock has
+/// nothing to do with the paths, so it fails the whole operation.
+async fn update_index_by_path<Fut>(
+    paths: Vec<RepoPath>,
+    update_index: impl Fn(Vec<RepoPath>) -> Fut,
+) -> Result<StagingReport>
+where
+    Fut: Future<Output = Result<()>>,
+{
+    let mut report = StagingReport::default();
+    let mut batches = vec![paths];
+    while let Some(mut batch) = batches.pop() {
+        if batch.is_empty() {
+            continue;
+        }
+        match update_index(batch.clone()).await {
+            Ok(()) => report.succeeded.extend(batch),
+            Err(error) if git::repository::IndexLocked::is_cause_of(&error) => return Err(error),
+            Err(error) => {
+                if let [path] = batch.as_slice() {
+                    report
+                        .failed
+                        .push((path.clone(), format!("{error:#}").into()));
+                } else {
+                    let second_half = batch.split_off(batch.len() / 2);
+                    batches.extend([second_half, batch]);
+                }
+            }
+        }
+    }
+    Ok(report)
+}
+
 async fn compute_snapshot(
     id: RepositoryId,
     work_directory_abs_path: Arc<Path>,
@@ -5113,9 +8681,35 @@ async fn compute_snapshot(
         MergeDetails::load(&backend, &statuses_by_path, &prev_snapshot).await?;
     log::debug!("new merge details (changed={merge_heads_changed:?}): {merge_details:?}");
 
+    // Pointer files show up as modified, so only the files with statuses need checking.
+    let lfs_statuses = backend
+        .lfs_statuses(
+            statuses
+                .entries
+                .iter()
+                .map(|(repo_path, _)| repo_path.clone())
+                .collect(),
+        )
+        .await
+        .log_err()
+        .unwrap_or_default()
+        .into_iter()
+        .collect::<BTreeMap<_, _>>();
+    let lfs_statuses = TreeMap::from_ordered_entries(lfs_statuses);
+
+    // Useful when branch is None in detached head state
+    let head_sha = backend.head_sha().await;
+    let head_state = match (&head_sha, &branch) {
+        (None, _) => HeadState::Unborn,
+        (Some(_), Some(branch)) => HeadState::Branch(branch.name().to_string().into()),
+        (Some(head_sha), None) => HeadState::Detached(head_sha.clone().into()),
+    };
+
     if merge_heads_changed
         || branch != prev_snapshot.branch
+        || prev_snapshot.head_state.as_ref() != Some(&head_state)
         || statuses_by_path != prev_snapshot.statuses_by_path
+        || lfs_statuses != prev_snapshot.lfs_statuses
     {
         events.push(RepositoryEvent::Updated {
             full_scan: true,
@@ -5129,8 +8723,12 @@ async fn compute_snapshot(
         events.push(RepositoryEvent::MergeHeadsChanged);
     }
 
-    // Useful when branch is None in detached head state
-    let head_commit = match backend.head_sha().await {
+    let rebase = backend.rebase_state().await;
+    if rebase != prev_snapshot.rebase {
+        events.push(RepositoryEvent::RebaseStateChanged);
+    }
+
+    let head_commit = match head_sha {
         Some(head_sha) => backend.show(head_sha).await.log_err(),
         None => None,
     };
@@ -5147,10 +8745,13 @@ async fn compute_snapshot(
         scan_id: prev_snapshot.scan_id + 1,
         branch,
         head_commit,
+        head_state: Some(head_state),
         merge: merge_details,
         remote_origin_url,
         remote_upstream_url,
         stash_entries,
+        rebase,
+        lfs_statuses,
     };
 
     Ok((snapshot, events))
@@ -5287,3 +8888,41 @@ fn tracked_status_to_proto(code: StatusCode) -> i32 {
         StatusCode::Unmodified => proto::GitStatus::Unmodified as _,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use git::repository::repo_path;
+    use std::cell::RefCell;
+
+    #[test]
+    fn test_update_index_by_path() {
+        let paths = ["a", "b", "c", "d", "e", "f", "g", "h"].map(repo_path);
+        let batch_sizes = RefCell::new(Vec::new());
+        let report = smol::block_on(update_index_by_path(paths.to_vec(), |paths| {
+            batch_sizes.borrow_mut().push(paths.len());
+            let failed = paths
+                .iter()
+                .any(|path| path.as_unix_str() == "c" || path.as_unix_str() == "f");
+            async move {
+                anyhow::ensure!(!failed, "bad path");
+                Ok(())
+            }
+        }))
+        .unwrap();
+
+        assert_eq!(
+            report.succeeded,
+            ["a", "b", "d", "e", "g", "h"].map(repo_path)
+        );
+        assert_eq!(
+            report.failed,
+            [
+                (repo_path("c"), SharedString::from("bad path")),
+                (repo_path("f"), SharedString::from("bad path")),
+            ]
+        );
+        // Only the halves with a failing path in them are split further.
+        assert_eq!(*batch_sizes.borrow(), [8, 4, 2, 2, 1, 1, 4, 2, 1, 1, 2]);
+    }
+}
@@ -5,7 +5,7 @@ use crate::{
     DocumentHighlight, DocumentSymbol, Hover, HoverBlock, HoverBlockKind, InlayHint,
     InlayHintLabel, InlayHintLabelPart, InlayHintLabelPartTooltip, InlayHintTooltip, Location,
     LocationLink, LspAction, LspPullDiagnostics, MarkupContent, PrepareRenameResponse,
-    ProjectTransaction, PulledDiagnostics, ResolveState,
+    ProjectTransaction, PulledDiagnostics, ResolveState, TypeHierarchyItem,
     lsp_store::{LocalLspStore, LspStore},
 };
 use anyhow::{Context as _, Result};
@@ -32,7 +32,13 @@ use lsp::{
 use serde_json::Value;
 use signature_help::{lsp_to_proto_signature, proto_to_lsp_signature};
 use std::{
-    cmp::Reverse, collections::hash_map, mem, ops::Range, path::Path, str::FromStr, sync::Arc,
+    cmp::Reverse,
+    collections::hash_map,
+    mem,
+    ops::Range,
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::Arc,
 };
 use text::{BufferId, LineEnding};
 use util::{ResultExt as _, debug_panic};
@@ -204,6 +210,21 @@ pub(crate) struct GetDocumentHighlights {
 #[derive(Debug, Copy, Clone)]
 pub(crate) struct GetDocumentSymbols;
 
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct PrepareTypeHierarchy {
+    pub position: PointUtf16,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct GetTypeHierarchySupertypes {
+    pub item: TypeHierarchyItem,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct GetTypeHierarchySubtypes {
+    pub item: TypeHierarchyItem,
+}
+
 #[derive(Clone, Debug)]
 pub(crate) struct GetSignatureHelp {
     pub position: PointUtf16,
@@ -1798,6 +1819,344 @@ impl LspCommand for GetDocumentSymbols {
     }
 }
 
+// `ServerCapabilities` has no field for `typeHierarchyProvider`, so the type hierarchy requests
+// are sent to any server, and the servers that don't support them respond with an error.
+#[async_trait(?Send)]
+impl LspCommand for PrepareTypeHierarchy {
+    type Response = Vec<TypeHierarchyItem>;
+    type LspRequest = lsp::request::TypeHierarchyPrepare;
+    type ProtoRequest = proto::PrepareTypeHierarchy;
+
+    fn display_name(&self) -> &str {
+        "Prepare type hierarchy"
+    }
+
+    fn check_capabilities(&self, _: AdapterServerCapabilities) -> bool {
+        true
+    }
+
+    fn to_lsp(
+        &self,
+        path: &Path,
+        _: &Buffer,
+        _: &Arc<LanguageServer>,
+        _: &App,
+    ) -> Result<lsp::TypeHierarchyPrepareParams> {
+        Ok(lsp::TypeHierarchyPrepareParams {
+            text_document_position_params: make_lsp_text_document_position(path, self.position)?,
+            work_done_progress_params: Default::default(),
+        })
+    }
+
+    async fn response_from_lsp(
+        self,
+        lsp_items: Option<Vec<lsp::TypeHierarchyItem>>,
+        _: Entity<LspStore>,
+        _: Entity<Buffer>,
+        server_id: LanguageServerId,
+        _: AsyncApp,
+    ) -> Result<Vec<TypeHierarchyItem>> {
+        Ok(type_hierarchy_items_from_lsp(lsp_items, server_id))
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::PrepareTypeHierarchy {
+        proto::PrepareTypeHierarchy {
+            project_id,
+            buffer_id: buffer.remote_id().into(),
+            position: Some(language::proto::serialize_anchor(
+                &buffer.anchor_before(self.position),
+            )),
+            version: serialize_version(&buffer.version()),
+        }
+    }
+
+    async fn from_proto(
+        message: proto::PrepareTypeHierarchy,
+        _: Entity<LspStore>,
+        buffer: Entity<Buffer>,
+        mut cx: AsyncApp,
+    ) -> Result<Self> {
+        let position = message
+            .position
+            .and_then(deserialize_anchor)
+            .context("invalid position")?;
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_version(deserialize_version(&message.version))
+            })?
+            .await?;
+        Ok(Self {
+            position: buffer.read_with(&cx, |buffer, _| position.to_point_utf16(buffer))?,
+        })
+    }
+
+    fn response_to_proto(
+        items: Vec<TypeHierarchyItem>,
+        _: &mut LspStore,
+        _: PeerId,
+        _: &clock::Global,
+        _: &mut App,
+    ) -> proto::TypeHierarchyResponse {
+        type_hierarchy_response_to_proto(items)
+    }
+
+    async fn response_from_proto(
+        self,
+        message: proto::TypeHierarchyResponse,
+        _: Entity<LspStore>,
+        _: Entity<Buffer>,
+        _: AsyncApp,
+    ) -> Result<Vec<TypeHierarchyItem>> {
+        type_hierarchy_response_from_proto(message)
+    }
+
+    fn buffer_id_from_proto(message: &proto::PrepareTypeHierarchy) -> Result<BufferId> {
+        BufferId::new(message.buffer_id)
+    }
+}
+
+#[async_trait(?Send)]
+impl LspCommand for GetTypeHierarchySupertypes {
+    type Response = Vec<TypeHierarchyItem>;
+    type LspRequest = lsp::request::TypeHierarchySupertypes;
+    type ProtoRequest = proto::GetTypeHierarchySupertypes;
+
+    fn display_name(&self) -> &str {
+        "Get type hierarchy supertypes"
+    }
+
+    fn check_capabilities(&self, _: AdapterServerCapabilities) -> bool {
+        true
+    }
+
+    fn to_lsp(
+        &self,
+        _: &Path,
+        _: &Buffer,
+        _: &Arc<LanguageServer>,
+        _: &App,
+    ) -> Result<lsp::TypeHierarchySupertypesParams> {
+        Ok(lsp::TypeHierarchySupertypesParams {
+            item: self.item.lsp_item.clone(),
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        })
+    }
+
+    async fn response_from_lsp(
+        self,
+        lsp_items: Option<Vec<lsp::TypeHierarchyItem>>,
+        _: Entity<LspStore>,
+        _: Entity<Buffer>,
+        server_id: LanguageServerId,
+        _: AsyncApp,
+    ) -> Result<Vec<TypeHierarchyItem>> {
+        Ok(type_hierarchy_items_from_lsp(lsp_items, server_id))
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::GetTypeHierarchySupertypes {
+        proto::GetTypeHierarchySupertypes {
+            project_id,
+            buffer_id: buffer.remote_id().into(),
+            item: Some(type_hierarchy_item_to_proto(&self.item)),
+        }
+    }
+
+    async fn from_proto(
+        message: proto::GetTypeHierarchySupertypes,
+        _: Entity<LspStore>,
+        _: Entity<Buffer>,
+        _: AsyncApp,
+    ) -> Result<Self> {
+        let item = message.item.context("invalid type hierarchy item")?;
+        Ok(Self {
+            item: type_hierarchy_item_from_proto(item)?,
+        })
+    }
+
+    fn response_to_proto(
+        items: Vec<TypeHierarchyItem>,
+        _: &mut LspStore,
+        _: PeerId,
+        _: &clock::Global,
+        _: &mut App,
+    ) -> proto::TypeHierarchyResponse {
+        type_hierarchy_response_to_proto(items)
+    }
+
+    async fn response_from_proto(
+        self,
+        message: proto::TypeHierarchyResponse,
+        _: Entity<LspStore>,
+        _: Entity<Buffer>,
+        _: AsyncApp,
+    ) -> Result<Vec<TypeHierarchyItem>> {
+        type_hierarchy_response_from_proto(message)
+    }
+
+    fn buffer_id_from_proto(message: &proto::GetTypeHierarchySupertypes) -> Result<BufferId> {
+        BufferId::new(message.buffer_id)
+    }
+}
+
+#[async_trait(?Send)]
+impl LspCommand for GetTypeHierarchySubtypes {
+    type Response = Vec<TypeHierarchyItem>;
+    type LspRequest = lsp::request::TypeHierarchySubtypes;
+    type ProtoRequest = proto::GetTypeHierarchySubtypes;
+
+    fn display_name(&self) -> &str {
+        "Get type hierarchy subtypes"
+    }
+
+    fn check_capabilities(&self, _: AdapterServerCapabilities) -> bool {
+        true
+    }
+
+    fn to_lsp(
+        &self,
+        _: &Path,
+        _: &Buffer,
+        _: &Arc<LanguageServer>,
+        _: &App,
+    ) -> Result<lsp::TypeHierarchySubtypesParams> {
+        Ok(lsp::TypeHierarchySubtypesParams {
+            item: self.item.lsp_item.clone(),
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        })
+    }
+
+    async fn response_from_lsp(
+        self,
+        lsp_items: Option<Vec<lsp::TypeHierarchyItem>>,
+        _: Entity<LspStore>,
+        _: Entity<Buffer>,
+        server_id: LanguageServerId,
+        _: AsyncApp,
+    ) -> Result<Vec<TypeHierarchyItem>> {
+        Ok(type_hierarchy_items_from_lsp(lsp_items, server_id))
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::GetTypeHierarchySubtypes {
+        proto::GetTypeHierarchySubtypes {
+            project_id,
+            buffer_id: buffer.remote_id().into(),
+            item: Some(type_hierarchy_item_to_proto(&self.item)),
+        }
+    }
+
+    async fn from_proto(
+        message: proto::GetTypeHierarchySubtypes,
+        _: Entity<LspStore>,
+        _: Entity<Buffer>,
+        _: AsyncApp,
+    ) -> Result<Self> {
+        let item = message.item.context("invalid type hierarchy item")?;
+        Ok(Self {
+            item: type_hierarchy_item_from_proto(item)?,
+        })
+    }
+
+    fn response_to_proto(
+        items: Vec<TypeHierarchyItem>,
+        _: &mut LspStore,
+        _: PeerId,
+        _: &clock::Global,
+        _: &mut App,
+    ) -> proto::TypeHierarchyResponse {
+        type_hierarchy_response_to_proto(items)
+    }
+
+    async fn response_from_proto(
+        self,
+        message: proto::TypeHierarchyResponse,
+        _: Entity<LspStore>,
+        _: Entity<Buffer>,
+        _: AsyncApp,
+    ) -> Result<Vec<TypeHierarchyItem>> {
+        type_hierarchy_response_from_proto(message)
+    }
+
+    fn buffer_id_from_proto(message: &proto::GetTypeHierarchySubtypes) -> Result<BufferId> {
+        BufferId::new(message.buffer_id)
+    }
+}
+
+fn type_hierarchy_items_from_lsp(
+    lsp_items: Option<Vec<lsp::TypeHierarchyItem>>,
+    server_id: LanguageServerId,
+) -> Vec<TypeHierarchyItem> {
+    lsp_items
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|lsp_item| {
+            // Items in virtual documents, such as decompiled sources, can't be opened.
+            let abs_path = lsp_item.uri.to_file_path().ok()?;
+            Some(TypeHierarchyItem {
+                name: lsp_item.name.clone(),
+                kind: lsp_item.kind,
+                detail: lsp_item.detail.clone(),
+                abs_path,
+                selection_range: range_from_lsp(lsp_item.selection_range),
+                server_id,
+                lsp_item,
+            })
+        })
+        .collect()
+}
+
+fn type_hierarchy_item_to_proto(item: &TypeHierarchyItem) -> proto::TypeHierarchyItem {
+    proto::TypeHierarchyItem {
+        name: item.name.clone(),
+        kind: unsafe { mem::transmute::<lsp::SymbolKind, i32>(item.kind) },
+        detail: item.detail.clone(),
+        abs_path: item.abs_path.to_string_lossy().into_owned(),
+        selection_start: Some(proto::PointUtf16 {
+            row: item.selection_range.start.0.row,
+            column: item.selection_range.start.0.column,
+        }),
+        selection_end: Some(proto::PointUtf16 {
+            row: item.selection_range.end.0.row,
+            column: item.selection_range.end.0.column,
+        }),
+        server_id: item.server_id.to_proto(),
+        lsp_item: serde_json::to_string(&item.lsp_item).unwrap_or_default(),
+    }
+}
+
+fn type_hierarchy_item_from_proto(item: proto::TypeHierarchyItem) -> Result<TypeHierarchyItem> {
+    let selection_start = item.selection_start.context("invalid selection start")?;
+    let selection_end = item.selection_end.context("invalid selection end")?;
+    Ok(TypeHierarchyItem {
+        name: item.name,
+        kind: unsafe { mem::transmute::<i32, lsp::SymbolKind>(item.kind) },
+        detail: item.detail,
+        abs_path: PathBuf::from(item.abs_path),
+        selection_range: Unclipped(PointUtf16::new(selection_start.row, selection_start.column))
+            ..Unclipped(PointUtf16::new(selection_end.row, selection_end.column)),
+        server_id: LanguageServerId::from_proto(item.server_id),
+        lsp_item: serde_json::from_str(&item.lsp_item)
+            .context("deserializing type hierarchy item")?,
+    })
+}
+
+fn type_hierarchy_response_to_proto(items: Vec<TypeHierarchyItem>) -> proto::TypeHierarchyResponse {
+    proto::TypeHierarchyResponse {
+        items: items.iter().map(type_hierarchy_item_to_proto).collect(),
+    }
+}
+
+fn type_hierarchy_response_from_proto(
+    message: proto::TypeHierarchyResponse,
+) -> Result<Vec<TypeHierarchyItem>> {
+    message
+        .items
+        .into_iter()
+        .map(type_hierarchy_item_from_proto)
+        .collect()
+}
+
 #[async_trait(?Send)]
 impl LspCommand for GetSignatureHelp {
     type Response = Option<SignatureHelp>;
@@ -2916,13 +3275,27 @@ impl InlayHints {
             _ => None,
         });
 
-        let position = buffer_handle.read_with(cx, |buffer, _| {
+        let lsp_text_edits = lsp_hint.text_edits.unwrap_or_default();
+        let (position, text_edits) = buffer_handle.read_with(cx, |buffer, _| {
             let position = buffer.clip_point_utf16(point_from_lsp(lsp_hint.position), Bias::Left);
-            if kind == Some(InlayHintKind::Parameter) {
+            let position = if kind == Some(InlayHintKind::Parameter) {
                 buffer.anchor_before(position)
             } else {
                 buffer.anchor_after(position)
-            }
+            };
+            let text_edits = lsp_text_edits
+                .into_iter()
+                .map(|edit| {
+                    let start =
+                        buffer.clip_point_utf16(point_from_lsp(edit.range.start), Bias::Left);
+                    let end = buffer.clip_point_utf16(point_from_lsp(edit.range.end), Bias::Left);
+                    (
+                        buffer.anchor_after(start)..buffer.anchor_before(end),
+                        edit.new_text,
+                    )
+                })
+                .collect::<Vec<_>>();
+            (position, text_edits)
         })?;
         let label = Self::lsp_inlay_label_to_project(lsp_hint.label, server_id)
             .await
@@ -2951,6 +3324,7 @@ impl InlayHints {
                     })
                 }
             }),
+            text_edits,
             resolve_state,
         })
     }
@@ -3059,6 +3433,15 @@ impl InlayHints {
                 }
             }),
             resolve_state,
+            text_edits: response_hint
+                .text_edits
+                .into_iter()
+                .map(|(range, new_text)| proto::InlayHintTextEdit {
+                    start: Some(language::proto::serialize_anchor(&range.start)),
+                    end: Some(language::proto::serialize_anchor(&range.end)),
+                    new_text,
+                })
+                .collect(),
         }
     }
 
@@ -3182,6 +3565,21 @@ impl InlayHints {
                     }
                 })
             }),
+            text_edits: message_hint
+                .text_edits
+                .into_iter()
+                .map(|edit| {
+                    let start = edit
+                        .start
+                        .and_then(language::proto::deserialize_anchor)
+                        .context("invalid text edit start")?;
+                    let end = edit
+                        .end
+                        .and_then(language::proto::deserialize_anchor)
+                        .context("invalid text edit end")?;
+                    anyhow::Ok((start..end, edit.new_text))
+                })
+                .collect::<Result<_>>()?,
             resolve_state,
         })
     }
@@ -3193,7 +3591,17 @@ impl InlayHints {
                 InlayHintKind::Type => lsp::InlayHintKind::TYPE,
                 InlayHintKind::Parameter => lsp::InlayHintKind::PARAMETER,
             }),
-            text_edits: None,
+            text_edits: (!hint.text_edits.is_empty()).then(|| {
+                hint.text_edits
+                    .iter()
+                    .filter_map(|(range, new_text)| {
+                        Some(lsp::TextEdit {
+                            range: range_to_lsp(range.to_point_utf16(snapshot)).ok()?,
+                            new_text: new_text.clone(),
+                        })
+                    })
+                    .collect()
+            }),
             tooltip: hint.tooltip.and_then(|tooltip| {
                 Some(match tooltip {
                     InlayHintTooltip::String(s) => lsp::InlayHintTooltip::String(s),
@@ -3330,6 +3738,11 @@ impl LspCommand for InlayHints {
         // Hence let's use a heuristic first to handle the most awkward case and look for more.
         let force_no_type_left_padding =
             lsp_adapter.name.0.as_ref() == "typescript-language-server";
+        let inlay_hint_settings = buffer.read_with(&cx, |buffer, cx| {
+            language_settings(buffer.language().map(|l| l.name()), buffer.file(), cx)
+                .inlay_hints
+                .clone()
+        })?;
 
         let hints = message.unwrap_or_default().into_iter().map(|lsp_hint| {
             let resolve_state = if InlayHints::can_resolve_inlays(&lsp_server.capabilities()) {
@@ -3351,11 +3764,27 @@ impl LspCommand for InlayHints {
                 .await
             })
         });
-        future::join_all(hints)
+        let hints = future::join_all(hints)
             .await
             .into_iter()
-            .collect::<anyhow::Result<_>>()
-            .context("lsp to project inlay hints conversion")
+            .collect::<anyhow::Result<Vec<_>>>()
+            .context("lsp to project inlay hints conversion")?;
+        Ok(hints
+            .into_iter()
+            .filter(|hint| {
+                !inlay_hint_settings.is_hint_suppressed(
+                    &lsp_adapter.name,
+                    hint.kind,
+                    &hint.text().to_string(),
+                )
+            })
+            .map(|mut hint| {
+                if inlay_hint_settings.max_hint_length > 0 {
+                    hint.truncate_label(inlay_hint_settings.max_hint_length);
+                }
+                hint
+            })
+            .collect())
     }
 
     fn to_proto(&self, project_id: u64, buffer: &Buffer) -> proto::InlayHints {
@@ -4594,4 +5023,60 @@ mod tests {
         let result = GetDocumentDiagnostics::deserialize_lsp_diagnostic(proto_diagnostic);
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_type_hierarchy_items() {
+        let lsp_item = |name: &str, uri: &str| lsp::TypeHierarchyItem {
+            name: name.to_string(),
+            kind: lsp::SymbolKind::CLASS,
+            tags: None,
+            detail: Some("pkg".to_string()),
+            uri: lsp::Uri::from_str(uri).unwrap(),
+            range: lsp::Range {
+                start: lsp::Position::new(1, 0),
+                end: lsp::Position::new(4, 1),
+            },
+            selection_range: lsp::Range {
+                start: lsp::Position::new(1, 6),
+                end: lsp::Position::new(1, 10),
+            },
+            data: Some(json!({ "id": name })),
+        };
+        let file_uri = file_path_to_lsp_url(Path::new(util::path!("/dir/base.rs"))).unwrap();
+        let server_id = LanguageServerId(3);
+
+        let items = type_hierarchy_items_from_lsp(
+            Some(vec![
+                lsp_item("Base", file_uri.as_str()),
+                lsp_item("Decompiled", "jdt://contents/rt.jar/Object.class"),
+            ]),
+            server_id,
+        );
+        assert_eq!(items.len(), 1, "items in virtual documents are skipped");
+        let item = &items[0];
+        assert_eq!(item.name, "Base");
+        assert_eq!(item.kind, lsp::SymbolKind::CLASS);
+        assert_eq!(item.detail.as_deref(), Some("pkg"));
+        assert_eq!(item.abs_path, PathBuf::from(util::path!("/dir/base.rs")));
+        assert_eq!(
+            item.selection_range,
+            Unclipped(PointUtf16::new(1, 6))..Unclipped(PointUtf16::new(1, 10))
+        );
+        assert_eq!(item.server_id, server_id);
+        assert!(type_hierarchy_items_from_lsp(None, server_id).is_empty());
+
+        let round_tripped =
+            type_hierarchy_item_from_proto(type_hierarchy_item_to_proto(item)).unwrap();
+        assert_eq!(round_tripped.name, item.name);
+        assert_eq!(round_tripped.kind, item.kind);
+        assert_eq!(round_tripped.detail, item.detail);
+        assert_eq!(round_tripped.abs_path, item.abs_path);
+        assert_eq!(round_tripped.selection_range, item.selection_range);
+        assert_eq!(round_tripped.server_id, item.server_id);
+        assert_eq!(round_tripped.lsp_item, item.lsp_item);
+
+        let mut missing_selection = type_hierarchy_item_to_proto(item);
+        missing_selection.selection_end = None;
+        assert!(type_hierarchy_item_from_proto(missing_selection).is_err());
+    }
 }
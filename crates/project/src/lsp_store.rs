@@ -22,7 +22,7 @@ use crate::{
     LspPullDiagnostics, ManifestProvidersStore, Project, ProjectItem, ProjectPath,
     ProjectTransaction, PulledDiagnostics, ResolveState, Symbol,
     buffer_store::{BufferStore, BufferStoreEvent},
-    environment::ProjectEnvironment,
+    environment::{ProjectEnvironment, ProjectEnvironmentEvent},
     lsp_command::{self, *},
     lsp_store::{
         self,
@@ -62,7 +62,9 @@ use language::{
     LanguageRegistry, LocalFile, LspAdapter, LspAdapterDelegate, LspInstaller, ManifestDelegate,
     ManifestName, Patch, PointUtf16, TextBufferSnapshot, ToOffset, ToPointUtf16, Toolchain,
     Transaction, Unclipped,
-    language_settings::{FormatOnSave, Formatter, LanguageSettings, language_settings},
+    language_settings::{
+        FormatOnSave, Formatter, FormatterErrorPolicy, LanguageSettings, language_settings,
+    },
     point_to_lsp,
     proto::{
         deserialize_anchor, deserialize_lsp_edit, deserialize_version, serialize_anchor,
@@ -89,7 +91,7 @@ use rpc::{
     proto::{LspRequestId, LspRequestMessage as _},
 };
 use serde::Serialize;
-use settings::{Settings, SettingsLocation, SettingsStore};
+use settings::{ExtendingVec, Settings, SettingsFile, SettingsLocation, SettingsStore};
 use sha2::{Digest, Sha256};
 use smol::channel::Sender;
 use snippet::Snippet;
@@ -1342,38 +1344,42 @@ impl LocalLspStore {
             (trigger, &settings.format_on_save),
             (FormatTrigger::Save, &FormatOnSave::Off)
         );
-        if should_run_code_actions_on_format {
-            let have_code_actions_to_run_on_format = settings
-                .code_actions_on_format
-                .values()
-                .any(|enabled| *enabled);
-            if have_code_actions_to_run_on_format {
-                zlog::trace!(logger => "going to run code actions on format");
-                code_actions_on_format_formatters = Some(
-                    settings
-                        .code_actions_on_format
-                        .iter()
-                        .filter_map(|(action, enabled)| enabled.then_some(action))
-                        .cloned()
-                        .map(Formatter::CodeAction)
-                        .collect::<Vec<_>>(),
-                );
-            }
+        if should_run_code_actions_on_format && !settings.code_actions_on_format.is_empty() {
+            zlog::trace!(logger => "going to run code actions on format");
+            code_actions_on_format_formatters = Some(
+                settings
+                    .code_actions_on_format
+                    .iter()
+                    .map(|code_action| {
+                        (
+                            Formatter::CodeAction(code_action.action.clone()),
+                            code_action.timeout_ms,
+                        )
+                    })
+                    .collect::<Vec<_>>(),
+            );
         }
 
         let formatters = match (trigger, &settings.format_on_save) {
-            (FormatTrigger::Save, FormatOnSave::Off) => &[],
+            (FormatTrigger::Save, FormatOnSave::Off) => Vec::new(),
             (FormatTrigger::Manual, _) | (FormatTrigger::Save, FormatOnSave::On) => {
-                settings.formatter.as_ref()
+                settings.formatter.steps()
             }
         };
 
         let formatters = code_actions_on_format_formatters
             .iter()
             .flatten()
-            .chain(formatters);
+            .map(|(formatter, timeout_ms)| {
+                (formatter, FormatterErrorPolicy::default(), *timeout_ms)
+            })
+            .chain(
+                formatters
+                    .into_iter()
+                    .map(|(formatter, on_error)| (formatter, on_error, None)),
+            );
 
-        for formatter in formatters {
+        for (formatter, on_error, timeout_ms) in formatters {
             let formatter = if formatter == &Formatter::Auto {
                 if settings.prettier.allowed {
                     zlog::trace!(logger => "Formatter set to auto: defaulting to prettier");
@@ -1385,497 +1391,523 @@ impl LocalLspStore {
             } else {
                 formatter
             };
-            match formatter {
-                Formatter::Auto => unreachable!("Auto resolved above"),
-                Formatter::Prettier => {
-                    let logger = zlog::scoped!(logger => "prettier");
-                    zlog::trace!(logger => "formatting");
-                    let _timer = zlog::time!(logger => "Formatting buffer via prettier");
-
-                    let prettier = lsp_store.read_with(cx, |lsp_store, _cx| {
-                        lsp_store.prettier_store().unwrap().downgrade()
-                    })?;
-                    let diff = prettier_store::format_with_prettier(&prettier, &buffer.handle, cx)
-                        .await
-                        .transpose()?;
-                    let Some(diff) = diff else {
-                        zlog::trace!(logger => "No changes");
-                        continue;
-                    };
-
-                    extend_formatting_transaction(
-                        buffer,
-                        formatting_transaction_id,
-                        cx,
-                        |buffer, cx| {
-                            buffer.apply_diff(diff, cx);
-                        },
-                    )?;
-                }
-                Formatter::External { command, arguments } => {
-                    let logger = zlog::scoped!(logger => "command");
-                    zlog::trace!(logger => "formatting");
-                    let _timer = zlog::time!(logger => "Formatting buffer via external command");
-
-                    let diff = Self::format_via_external_command(
-                        buffer,
-                        command.as_ref(),
-                        arguments.as_deref(),
-                        cx,
-                    )
-                    .await
-                    .with_context(|| {
-                        format!("Failed to format buffer via external command: {}", command)
-                    })?;
-                    let Some(diff) = diff else {
-                        zlog::trace!(logger => "No changes");
-                        continue;
-                    };
-
-                    extend_formatting_transaction(
-                        buffer,
-                        formatting_transaction_id,
-                        cx,
-                        |buffer, cx| {
-                            buffer.apply_diff(diff, cx);
-                        },
-                    )?;
-                }
-                Formatter::LanguageServer(specifier) => {
-                    let logger = zlog::scoped!(logger => "language-server");
-                    zlog::trace!(logger => "formatting");
-                    let _timer = zlog::time!(logger => "Formatting buffer using language server");
-
-                    let Some(buffer_path_abs) = buffer.abs_path.as_ref() else {
-                        zlog::warn!(logger => "Cannot format buffer that is not backed by a file on disk using language servers. Skipping");
-                        continue;
-                    };
-
-                    let language_server = match specifier {
-                        settings::LanguageServerFormatterSpecifier::Specific { name } => {
-                            adapters_and_servers.iter().find_map(|(adapter, server)| {
-                                if adapter.name.0.as_ref() == name {
-                                    Some(server.clone())
-                                } else {
-                                    None
-                                }
-                            })
-                        }
-                        settings::LanguageServerFormatterSpecifier::Current => {
-                            adapters_and_servers.first().map(|e| e.1.clone())
-                        }
-                    };
-
-                    let Some(language_server) = language_server else {
-                        log::debug!(
-                            "No language server found to format buffer '{:?}'. Skipping",
-                            buffer_path_abs.as_path().to_string_lossy()
-                        );
-                        continue;
-                    };
-
-                    zlog::trace!(
-                        logger =>
-                        "Formatting buffer '{:?}' using language server '{:?}'",
-                        buffer_path_abs.as_path().to_string_lossy(),
-                        language_server.name()
-                    );
+            // A slow code action is skipped, so that the formatters after it
+            // still get to run before the editor stops waiting for the format.
+            let timeout = timeout_ms.map(Duration::from_millis);
+            let timer = timeout.map(|timeout| cx.background_executor().timer(timeout));
+            let format = async {
+                match formatter {
+                    Formatter::Auto => unreachable!("Auto resolved above"),
+                    Formatter::Prettier => {
+                        let logger = zlog::scoped!(logger => "prettier");
+                        zlog::trace!(logger => "formatting");
+                        let _timer = zlog::time!(logger => "Formatting buffer via prettier");
+
+                        let prettier = lsp_store.read_with(cx, |lsp_store, _cx| {
+                            lsp_store.prettier_store().unwrap().downgrade()
+                        })?;
+                        let diff = prettier_store::format_with_prettier(&prettier, &buffer.handle, cx)
+                            .await
+                            .transpose()?;
+                        let Some(diff) = diff else {
+                            zlog::trace!(logger => "No changes");
+                            return Ok(());
+                        };
 
-                    let edits = if let Some(ranges) = buffer.ranges.as_ref() {
-                        zlog::trace!(logger => "formatting ranges");
-                        Self::format_ranges_via_lsp(
-                            &lsp_store,
-                            &buffer.handle,
-                            ranges,
-                            buffer_path_abs,
-                            &language_server,
-                            &settings,
+                        extend_formatting_transaction(
+                            buffer,
+                            formatting_transaction_id,
                             cx,
-                        )
-                        .await
-                        .context("Failed to format ranges via language server")?
-                    } else {
-                        zlog::trace!(logger => "formatting full");
-                        Self::format_via_lsp(
-                            &lsp_store,
-                            &buffer.handle,
-                            buffer_path_abs,
-                            &language_server,
-                            &settings,
+                            |buffer, cx| {
+                                buffer.apply_diff(diff, cx);
+                            },
+                        )?;
+                    }
+                    Formatter::External { command, arguments } => {
+                        let logger = zlog::scoped!(logger => "command");
+                        zlog::trace!(logger => "formatting");
+                        let _timer = zlog::time!(logger => "Formatting buffer via external command");
+
+                        let diff = Self::format_via_external_command(
+                            buffer,
+                            command.as_ref(),
+                            arguments.as_deref(),
                             cx,
                         )
                         .await
-                        .context("failed to format via language server")?
-                    };
+                        .with_context(|| {
+                            format!("Failed to format buffer via external command: {}", command)
+                        })?;
+                        let Some(diff) = diff else {
+                            zlog::trace!(logger => "No changes");
+                            return Ok(());
+                        };
 
-                    if edits.is_empty() {
-                        zlog::trace!(logger => "No changes");
-                        continue;
+                        extend_formatting_transaction(
+                            buffer,
+                            formatting_transaction_id,
+                            cx,
+                            |buffer, cx| {
+                                buffer.apply_diff(diff, cx);
+                            },
+                        )?;
                     }
-                    extend_formatting_transaction(
-                        buffer,
-                        formatting_transaction_id,
-                        cx,
-                        |buffer, cx| {
-                            buffer.edit(edits, None, cx);
-                        },
-                    )?;
-                }
-                Formatter::CodeAction(code_action_name) => {
-                    let logger = zlog::scoped!(logger => "code-actions");
-                    zlog::trace!(logger => "formatting");
-                    let _timer = zlog::time!(logger => "Formatting buffer using code actions");
+                    Formatter::LanguageServer(specifier) => {
+                        let logger = zlog::scoped!(logger => "language-server");
+                        zlog::trace!(logger => "formatting");
+                        let _timer = zlog::time!(logger => "Formatting buffer using language server");
+
+                        let Some(buffer_path_abs) = buffer.abs_path.as_ref() else {
+                            zlog::warn!(logger => "Cannot format buffer that is not backed by a file on disk using language servers. Skipping");
+                            return Ok(());
+                        };
 
-                    let Some(buffer_path_abs) = buffer.abs_path.as_ref() else {
-                        zlog::warn!(logger => "Cannot format buffer that is not backed by a file on disk using code actions. Skipping");
-                        continue;
-                    };
+                        let language_server = match specifier {
+                            settings::LanguageServerFormatterSpecifier::Specific { name } => {
+                                adapters_and_servers.iter().find_map(|(adapter, server)| {
+                                    if adapter.name.0.as_ref() == name {
+                                        Some(server.clone())
+                                    } else {
+                                        None
+                                    }
+                                })
+                            }
+                            settings::LanguageServerFormatterSpecifier::Current => {
+                                adapters_and_servers.first().map(|e| e.1.clone())
+                            }
+                        };
 
-                    let code_action_kind: CodeActionKind = code_action_name.clone().into();
-                    zlog::trace!(logger => "Attempting to resolve code actions {:?}", &code_action_kind);
+                        let Some(language_server) = language_server else {
+                            log::debug!(
+                                "No language server found to format buffer '{:?}'. Skipping",
+                                buffer_path_abs.as_path().to_string_lossy()
+                            );
+                            return Ok(());
+                        };
 
-                    let mut actions_and_servers = Vec::new();
+                        zlog::trace!(
+                            logger =>
+                            "Formatting buffer '{:?}' using language server '{:?}'",
+                            buffer_path_abs.as_path().to_string_lossy(),
+                            language_server.name()
+                        );
 
-                    for (index, (_, language_server)) in adapters_and_servers.iter().enumerate() {
-                        let actions_result = Self::get_server_code_actions_from_action_kinds(
-                            &lsp_store,
-                            language_server.server_id(),
-                            vec![code_action_kind.clone()],
-                            &buffer.handle,
-                            cx,
-                        )
-                        .await
-                        .with_context(|| {
-                            format!(
-                                "Failed to resolve code action {:?} with language server {}",
-                                code_action_kind,
-                                language_server.name()
+                        let edits = if let Some(ranges) = buffer.ranges.as_ref() {
+                            zlog::trace!(logger => "formatting ranges");
+                            Self::format_ranges_via_lsp(
+                                &lsp_store,
+                                &buffer.handle,
+                                ranges,
+                                buffer_path_abs,
+                                &language_server,
+                                &settings,
+                                cx,
                             )
-                        });
-                        let Ok(actions) = actions_result else {
-                            // note: it may be better to set result to the error and break formatters here
-                            // but for now we try to execute the actions that we can resolve and skip the rest
-                            zlog::error!(
-                                logger =>
-                                "Failed to resolve code action {:?} with language server {}",
-                                code_action_kind,
-                                language_server.name()
-                            );
-                            continue;
+                            .await
+                            .context("Failed to format ranges via language server")?
+                        } else {
+                            zlog::trace!(logger => "formatting full");
+                            Self::format_via_lsp(
+                                &lsp_store,
+                                &buffer.handle,
+                                buffer_path_abs,
+                                &language_server,
+                                &settings,
+                                cx,
+                            )
+                            .await
+                            .context("failed to format via language server")?
                         };
-                        for action in actions {
-                            actions_and_servers.push((action, index));
-                        }
-                    }
 
-                    if actions_and_servers.is_empty() {
-                        zlog::warn!(logger => "No code actions were resolved, continuing");
-                        continue;
+                        if edits.is_empty() {
+                            zlog::trace!(logger => "No changes");
+                            return Ok(());
+                        }
+                        extend_formatting_transaction(
+                            buffer,
+                            formatting_transaction_id,
+                            cx,
+                            |buffer, cx| {
+                                buffer.edit(edits, None, cx);
+                            },
+                        )?;
                     }
-
-                    'actions: for (mut action, server_index) in actions_and_servers {
-                        let server = &adapters_and_servers[server_index].1;
-
-                        let describe_code_action = |action: &CodeAction| {
-                            format!(
-                                "code action '{}' with title \"{}\" on server {}",
-                                action
-                                    .lsp_action
-                                    .action_kind()
-                                    .unwrap_or("unknown".into())
-                                    .as_str(),
-                                action.lsp_action.title(),
-                                server.name(),
-                            )
+                    Formatter::CodeAction(code_action_name) => {
+                        let logger = zlog::scoped!(logger => "code-actions");
+                        zlog::trace!(logger => "formatting");
+                        let _timer = zlog::time!(logger => "Formatting buffer using code actions");
+
+                        let Some(buffer_path_abs) = buffer.abs_path.as_ref() else {
+                            zlog::warn!(logger => "Cannot format buffer that is not backed by a file on disk using code actions. Skipping");
+                            return Ok(());
                         };
 
-                        zlog::trace!(logger => "Executing {}", describe_code_action(&action));
+                        let code_action_kind: CodeActionKind = code_action_name.clone().into();
+                        zlog::trace!(logger => "Attempting to resolve code actions {:?}", &code_action_kind);
 
-                        if let Err(err) = Self::try_resolve_code_action(server, &mut action).await {
-                            zlog::error!(
-                                logger =>
-                                "Failed to resolve {}. Error: {}",
-                                describe_code_action(&action),
-                                err
-                            );
-                            continue;
-                        }
+                        let mut actions_and_servers = Vec::new();
 
-                        if let Some(edit) = action.lsp_action.edit().cloned() {
-                            // NOTE: code below duplicated from `Self::deserialize_workspace_edit`
-                            // but filters out and logs warnings for code actions that require unreasonably
-                            // difficult handling on our part, such as:
-                            // - applying edits that call commands
-                            //   which can result in arbitrary workspace edits being sent from the server that
-                            //   have no way of being tied back to the command that initiated them (i.e. we
-                            //   can't know which edits are part of the format request, or if the server is done sending
-                            //   actions in response to the command)
-                            // - actions that create/delete/modify/rename files other than the one we are formatting
-                            //   as we then would need to handle such changes correctly in the local history as well
-                            //   as the remote history through the ProjectTransaction
-                            // - actions with snippet edits, as these simply don't make sense in the context of a format request
-                            // Supporting these actions is not impossible, but not supported as of yet.
-                            if edit.changes.is_none() && edit.document_changes.is_none() {
-                                zlog::trace!(
+                        for (index, (_, language_server)) in adapters_and_servers.iter().enumerate() {
+                            let actions_result = Self::get_server_code_actions_from_action_kinds(
+                                &lsp_store,
+                                language_server.server_id(),
+                                vec![code_action_kind.clone()],
+                                &buffer.handle,
+                                cx,
+                            )
+                            .await
+                            .with_context(|| {
+                                format!(
+                                    "Failed to resolve code action {:?} with language server {}",
+                                    code_action_kind,
+                                    language_server.name()
+                                )
+                            });
+                            let Ok(actions) = actions_result else {
+                                // note: it may be better to set result to the error and break formatters here
+                                // but for now we try to execute the actions that we can resolve and skip the rest
+                                zlog::error!(
                                     logger =>
-                                    "No changes for code action. Skipping {}",
-                                    describe_code_action(&action),
+                                    "Failed to resolve code action {:?} with language server {}",
+                                    code_action_kind,
+                                    language_server.name()
                                 );
                                 continue;
+                            };
+                            for action in actions {
+                                actions_and_servers.push((action, index));
                             }
+                        }
 
-                            let mut operations = Vec::new();
-                            if let Some(document_changes) = edit.document_changes {
-                                match document_changes {
-                                    lsp::DocumentChanges::Edits(edits) => operations.extend(
-                                        edits.into_iter().map(lsp::DocumentChangeOperation::Edit),
-                                    ),
-                                    lsp::DocumentChanges::Operations(ops) => operations = ops,
-                                }
-                            } else if let Some(changes) = edit.changes {
-                                operations.extend(changes.into_iter().map(|(uri, edits)| {
-                                    lsp::DocumentChangeOperation::Edit(lsp::TextDocumentEdit {
-                                        text_document:
-                                            lsp::OptionalVersionedTextDocumentIdentifier {
-                                                uri,
-                                                version: None,
-                                            },
-                                        edits: edits.into_iter().map(Edit::Plain).collect(),
-                                    })
-                                }));
-                            }
+                        if actions_and_servers.is_empty() {
+                            zlog::warn!(logger => "No code actions were resolved, continuing");
+                            return Ok(());
+                        }
+
+                        'actions: for (mut action, server_index) in actions_and_servers {
+                            let server = &adapters_and_servers[server_index].1;
+
+                            let describe_code_action = |action: &CodeAction| {
+                                format!(
+                                    "code action '{}' with title \"{}\" on server {}",
+                                    action
+                                        .lsp_action
+                                        .action_kind()
+                                        .unwrap_or("unknown".into())
+                                        .as_str(),
+                                    action.lsp_action.title(),
+                                    server.name(),
+                                )
+                            };
 
-                            let mut edits = Vec::with_capacity(operations.len());
+                            zlog::trace!(logger => "Executing {}", describe_code_action(&action));
 
-                            if operations.is_empty() {
-                                zlog::trace!(
+                            if let Err(err) = Self::try_resolve_code_action(server, &mut action).await {
+                                zlog::error!(
                                     logger =>
-                                    "No changes for code action. Skipping {}",
+                                    "Failed to resolve {}. Error: {}",
                                     describe_code_action(&action),
+                                    err
                                 );
                                 continue;
                             }
-                            for operation in operations {
-                                let op = match operation {
-                                    lsp::DocumentChangeOperation::Edit(op) => op,
-                                    lsp::DocumentChangeOperation::Op(_) => {
-                                        zlog::warn!(
-                                            logger =>
-                                            "Code actions which create, delete, or rename files are not supported on format. Skipping {}",
-                                            describe_code_action(&action),
-                                        );
-                                        continue 'actions;
-                                    }
-                                };
-                                let Ok(file_path) = op.text_document.uri.to_file_path() else {
-                                    zlog::warn!(
+
+                            if let Some(edit) = action.lsp_action.edit().cloned() {
+                                // NOTE: code below duplicated from `Self::deserialize_workspace_edit`
+                                // but filters out and logs warnings for code actions that require unreasonably
+                                // difficult handling on our part, such as:
+                                // - applying edits that call commands
+                                //   which can result in arbitrary workspace edits being sent from the server that
+                                //   have no way of being tied back to the command that initiated them (i.e. we
+                                //   can't know which edits are part of the format request, or if the server is done sending
+                                //   actions in response to the command)
+                                // - actions that create/delete/modify/rename files other than the one we are formatting
+                                //   as we then would need to handle such changes correctly in the local history as well
+                                //   as the remote history through the ProjectTransaction
+                                // - actions with snippet edits, as these simply don't make sense in the context of a format request
+                                // Supporting these actions is not impossible, but not supported as of yet.
+                                if edit.changes.is_none() && edit.document_changes.is_none() {
+                                    zlog::trace!(
                                         logger =>
-                                        "Failed to convert URI '{:?}' to file path. Skipping {}",
-                                        &op.text_document.uri,
+                                        "No changes for code action. Skipping {}",
                                         describe_code_action(&action),
                                     );
-                                    continue 'actions;
-                                };
-                                if &file_path != buffer_path_abs {
-                                    zlog::warn!(
+                                    continue;
+                                }
+
+                                let mut operations = Vec::new();
+                                if let Some(document_changes) = edit.document_changes {
+                                    match document_changes {
+                                        lsp::DocumentChanges::Edits(edits) => operations.extend(
+                                            edits.into_iter().map(lsp::DocumentChangeOperation::Edit),
+                                        ),
+                                        lsp::DocumentChanges::Operations(ops) => operations = ops,
+                                    }
+                                } else if let Some(changes) = edit.changes {
+                                    operations.extend(changes.into_iter().map(|(uri, edits)| {
+                                        lsp::DocumentChangeOperation::Edit(lsp::TextDocumentEdit {
+                                            text_document:
+                                                lsp::OptionalVersionedTextDocumentIdentifier {
+                                                    uri,
+                                                    version: None,
+                                                },
+                                            edits: edits.into_iter().map(Edit::Plain).collect(),
+                                        })
+                                    }));
+                                }
+
+                                let mut edits = Vec::with_capacity(operations.len());
+
+                                if operations.is_empty() {
+                                    zlog::trace!(
                                         logger =>
-                                        "File path '{:?}' does not match buffer path '{:?}'. Skipping {}",
-                                        file_path,
-                                        buffer_path_abs,
+                                        "No changes for code action. Skipping {}",
                                         describe_code_action(&action),
                                     );
-                                    continue 'actions;
+                                    continue;
                                 }
-
-                                let mut lsp_edits = Vec::new();
-                                for edit in op.edits {
-                                    match edit {
-                                        Edit::Plain(edit) => {
-                                            if !lsp_edits.contains(&edit) {
-                                                lsp_edits.push(edit);
-                                            }
-                                        }
-                                        Edit::Annotated(edit) => {
-                                            if !lsp_edits.contains(&edit.text_edit) {
-                                                lsp_edits.push(edit.text_edit);
-                                            }
-                                        }
-                                        Edit::Snippet(_) => {
+                                for operation in operations {
+                                    let op = match operation {
+                                        lsp::DocumentChangeOperation::Edit(op) => op,
+                                        lsp::DocumentChangeOperation::Op(_) => {
                                             zlog::warn!(
                                                 logger =>
-                                                "Code actions which produce snippet edits are not supported during formatting. Skipping {}",
+                                                "Code actions which create, delete, or rename files are not supported on format. Skipping {}",
                                                 describe_code_action(&action),
                                             );
                                             continue 'actions;
                                         }
+                                    };
+                                    let Ok(file_path) = op.text_document.uri.to_file_path() else {
+                                        zlog::warn!(
+                                            logger =>
+                                            "Failed to convert URI '{:?}' to file path. Skipping {}",
+                                            &op.text_document.uri,
+                                            describe_code_action(&action),
+                                        );
+                                        continue 'actions;
+                                    };
+                                    if &file_path != buffer_path_abs {
+                                        zlog::warn!(
+                                            logger =>
+                                            "File path '{:?}' does not match buffer path '{:?}'. Skipping {}",
+                                            file_path,
+                                            buffer_path_abs,
+                                            describe_code_action(&action),
+                                        );
+                                        continue 'actions;
                                     }
-                                }
-                                let edits_result = lsp_store
-                                    .update(cx, |lsp_store, cx| {
-                                        lsp_store.as_local_mut().unwrap().edits_from_lsp(
-                                            &buffer.handle,
-                                            lsp_edits,
-                                            server.server_id(),
-                                            op.text_document.version,
-                                            cx,
-                                        )
-                                    })?
-                                    .await;
-                                let Ok(resolved_edits) = edits_result else {
-                                    zlog::warn!(
-                                        logger =>
-                                        "Failed to resolve edits from LSP for buffer {:?} while handling {}",
-                                        buffer_path_abs.as_path(),
-                                        describe_code_action(&action),
-                                    );
-                                    continue 'actions;
-                                };
-                                edits.extend(resolved_edits);
-                            }
 
-                            if edits.is_empty() {
-                                zlog::warn!(logger => "No edits resolved from LSP");
-                                continue;
-                            }
+                                    let mut lsp_edits = Vec::new();
+                                    for edit in op.edits {
+                                        match edit {
+                                            Edit::Plain(edit) => {
+                                                if !lsp_edits.contains(&edit) {
+                                                    lsp_edits.push(edit);
+                                                }
+                                            }
+                                            Edit::Annotated(edit) => {
+                                                if !lsp_edits.contains(&edit.text_edit) {
+                                                    lsp_edits.push(edit.text_edit);
+                                                }
+                                            }
+                                            Edit::Snippet(_) => {
+                                                zlog::warn!(
+                                                    logger =>
+                                                    "Code actions which produce snippet edits are not supported during formatting. Skipping {}",
+                                                    describe_code_action(&action),
+                                                );
+                                                continue 'actions;
+                                            }
+                                        }
+                                    }
+                                    let edits_result = lsp_store
+                                        .update(cx, |lsp_store, cx| {
+                                            lsp_store.as_local_mut().unwrap().edits_from_lsp(
+                                                &buffer.handle,
+                                                lsp_edits,
+                                                server.server_id(),
+                                                op.text_document.version,
+                                                cx,
+                                            )
+                                        })?
+                                        .await;
+                                    let Ok(resolved_edits) = edits_result else {
+                                        zlog::warn!(
+                                            logger =>
+                                            "Failed to resolve edits from LSP for buffer {:?} while handling {}",
+                                            buffer_path_abs.as_path(),
+                                            describe_code_action(&action),
+                                        );
+                                        continue 'actions;
+                                    };
+                                    edits.extend(resolved_edits);
+                                }
 
-                            extend_formatting_transaction(
-                                buffer,
-                                formatting_transaction_id,
-                                cx,
-                                |buffer, cx| {
-                                    zlog::info!(
-                                        "Applying edits {edits:?}. Content: {:?}",
-                                        buffer.text()
-                                    );
-                                    buffer.edit(edits, None, cx);
-                                    zlog::info!("Applied edits. New Content: {:?}", buffer.text());
-                                },
-                            )?;
-                        }
+                                if edits.is_empty() {
+                                    zlog::warn!(logger => "No edits resolved from LSP");
+                                    continue;
+                                }
 
-                        if let Some(command) = action.lsp_action.command() {
-                            zlog::warn!(
-                                logger =>
-                                "Executing code action command '{}'. This may cause formatting to abort unnecessarily as well as splitting formatting into two entries in the undo history",
-                                &command.command,
-                            );
+                                extend_formatting_transaction(
+                                    buffer,
+                                    formatting_transaction_id,
+                                    cx,
+                                    |buffer, cx| {
+                                        zlog::info!(
+                                            "Applying edits {edits:?}. Content: {:?}",
+                                            buffer.text()
+                                        );
+                                        buffer.edit(edits, None, cx);
+                                        zlog::info!("Applied edits. New Content: {:?}", buffer.text());
+                                    },
+                                )?;
+                            }
 
-                            // bail early if command is invalid
-                            let server_capabilities = server.capabilities();
-                            let available_commands = server_capabilities
-                                .execute_command_provider
-                                .as_ref()
-                                .map(|options| options.commands.as_slice())
-                                .unwrap_or_default();
-                            if !available_commands.contains(&command.command) {
+                            if let Some(command) = action.lsp_action.command() {
                                 zlog::warn!(
                                     logger =>
-                                    "Cannot execute a command {} not listed in the language server capabilities of server {}",
-                                    command.command,
-                                    server.name(),
+                                    "Executing code action command '{}'. This may cause formatting to abort unnecessarily as well as splitting formatting into two entries in the undo history",
+                                    &command.command,
                                 );
-                                continue;
-                            }
 
-                            // noop so we just ensure buffer hasn't been edited since resolving code actions
-                            extend_formatting_transaction(
-                                buffer,
-                                formatting_transaction_id,
-                                cx,
-                                |_, _| {},
-                            )?;
-                            zlog::info!(logger => "Executing command {}", &command.command);
-
-                            lsp_store.update(cx, |this, _| {
-                                this.as_local_mut()
-                                    .unwrap()
-                                    .last_workspace_edits_by_language_server
-                                    .remove(&server.server_id());
-                            })?;
-
-                            let execute_command_result = server
-                                .request::<lsp::request::ExecuteCommand>(
-                                    lsp::ExecuteCommandParams {
-                                        command: command.command.clone(),
-                                        arguments: command.arguments.clone().unwrap_or_default(),
-                                        ..Default::default()
-                                    },
-                                )
-                                .await
-                                .into_response();
+                                // bail early if command is invalid
+                                let server_capabilities = server.capabilities();
+                                let available_commands = server_capabilities
+                                    .execute_command_provider
+                                    .as_ref()
+                                    .map(|options| options.commands.as_slice())
+                                    .unwrap_or_default();
+                                if !available_commands.contains(&command.command) {
+                                    zlog::warn!(
+                                        logger =>
+                                        "Cannot execute a command {} not listed in the language server capabilities of server {}",
+                                        command.command,
+                                        server.name(),
+                                    );
+                                    continue;
+                                }
 
-                            if execute_command_result.is_err() {
-                                zlog::error!(
-                                    logger =>
-                                    "Failed to execute command '{}' as part of {}",
-                                    &command.command,
-                                    describe_code_action(&action),
-                                );
-                                continue 'actions;
-                            }
+                                // noop so we just ensure buffer hasn't been edited since resolving code actions
+                                extend_formatting_transaction(
+                                    buffer,
+                                    formatting_transaction_id,
+                                    cx,
+                                    |_, _| {},
+                                )?;
+                                zlog::info!(logger => "Executing command {}", &command.command);
 
-                            let mut project_transaction_command =
                                 lsp_store.update(cx, |this, _| {
                                     this.as_local_mut()
                                         .unwrap()
                                         .last_workspace_edits_by_language_server
-                                        .remove(&server.server_id())
-                                        .unwrap_or_default()
+                                        .remove(&server.server_id());
                                 })?;
 
-                            if let Some(transaction) =
-                                project_transaction_command.0.remove(&buffer.handle)
-                            {
-                                zlog::trace!(
-                                    logger =>
-                                    "Successfully captured {} edits that resulted from command {}",
-                                    transaction.edit_ids.len(),
-                                    &command.command,
-                                );
-                                let transaction_id_project_transaction = transaction.id;
-                                buffer.handle.update(cx, |buffer, _| {
-                                    // it may have been removed from history if push_to_history was
-                                    // false in deserialize_workspace_edit. If so push it so we
-                                    // can merge it with the format transaction
-                                    // and pop the combined transaction off the history stack
-                                    // later if push_to_history is false
-                                    if buffer.get_transaction(transaction.id).is_none() {
-                                        buffer.push_transaction(transaction, Instant::now());
-                                    }
-                                    buffer.merge_transactions(
-                                        transaction_id_project_transaction,
-                                        formatting_transaction_id,
+                                let execute_command_result = server
+                                    .request::<lsp::request::ExecuteCommand>(
+                                        lsp::ExecuteCommandParams {
+                                            command: command.command.clone(),
+                                            arguments: command.arguments.clone().unwrap_or_default(),
+                                            ..Default::default()
+                                        },
+                                    )
+                                    .await
+                                    .into_response();
+
+                                if execute_command_result.is_err() {
+                                    zlog::error!(
+                                        logger =>
+                                        "Failed to execute command '{}' as part of {}",
+                                        &command.command,
+                                        describe_code_action(&action),
                                     );
-                                })?;
-                            }
+                                    continue 'actions;
+                                }
 
-                            if !project_transaction_command.0.is_empty() {
-                                let mut extra_buffers = String::new();
-                                for buffer in project_transaction_command.0.keys() {
-                                    buffer
-                                        .read_with(cx, |b, cx| {
-                                            if let Some(path) = b.project_path(cx) {
-                                                if !extra_buffers.is_empty() {
-                                                    extra_buffers.push_str(", ");
+                                let mut project_transaction_command =
+                                    lsp_store.update(cx, |this, _| {
+                                        this.as_local_mut()
+                                            .unwrap()
+                                            .last_workspace_edits_by_language_server
+                                            .remove(&server.server_id())
+                                            .unwrap_or_default()
+                                    })?;
+
+                                if let Some(transaction) =
+                                    project_transaction_command.0.remove(&buffer.handle)
+                                {
+                                    zlog::trace!(
+                                        logger =>
+                                        "Successfully captured {} edits that resulted from command {}",
+                                        transaction.edit_ids.len(),
+                                        &command.command,
+                                    );
+                                    let transaction_id_project_transaction = transaction.id;
+                                    buffer.handle.update(cx, |buffer, _| {
+                                        // it may have been removed from history if push_to_history was
+                                        // false in deserialize_workspace_edit. If so push it so we
+                                        // can merge it with the format transaction
+                                        // and pop the combined transaction off the history stack
+                                        // later if push_to_history is false
+                                        if buffer.get_transaction(transaction.id).is_none() {
+                                            buffer.push_transaction(transaction, Instant::now());
+                                        }
+                                        buffer.merge_transactions(
+                                            transaction_id_project_transaction,
+                                            formatting_transaction_id,
+                                        );
+                                    })?;
+                                }
+
+                                if !project_transaction_command.0.is_empty() {
+                                    let mut extra_buffers = String::new();
+                                    for buffer in project_transaction_command.0.keys() {
+                                        buffer
+                                            .read_with(cx, |b, cx| {
+                                                if let Some(path) = b.project_path(cx) {
+                                                    if !extra_buffers.is_empty() {
+                                                        extra_buffers.push_str(", ");
+                                                    }
+                                                    extra_buffers.push_str(path.path.as_unix_str());
                                                 }
-                                                extra_buffers.push_str(path.path.as_unix_str());
-                                            }
-                                        })
-                                        .ok();
+                                            })
+                                            .ok();
+                                    }
+                                    zlog::warn!(
+                                        logger =>
+                                        "Unexpected edits to buffers other than the buffer actively being formatted due to command {}. Impacted buffers: [{}].",
+                                        &command.command,
+                                        extra_buffers,
+                                    );
+                                    // NOTE: if this case is hit, the proper thing to do is to for each buffer, merge the extra transaction
+                                    // into the existing transaction in project_transaction if there is one, and if there isn't one in project_transaction,
+                                    // add it so it's included, and merge it into the format transaction when its created later
                                 }
-                                zlog::warn!(
-                                    logger =>
-                                    "Unexpected edits to buffers other than the buffer actively being formatted due to command {}. Impacted buffers: [{}].",
-                                    &command.command,
-                                    extra_buffers,
-                                );
-                                // NOTE: if this case is hit, the proper thing to do is to for each buffer, merge the extra transaction
-                                // into the existing transaction in project_transaction if there is one, and if there isn't one in project_transaction,
-                                // add it so it's included, and merge it into the format transaction when its created later
                             }
                         }
                     }
                 }
+                anyhow::Ok(())
+            }
+            .boxed_local();
+            let result = match timer {
+                Some(timer) => match select(format, timer).await {
+                    Either::Left((result, _)) => result,
+                    Either::Right(_) => {
+                        zlog::warn!(logger => "Skipping formatter {formatter:?} after it timed out after {timeout:?}");
+                        continue;
+                    }
+                },
+                None => format.await,
+            };
+            if let Err(error) = result {
+                match on_error {
+                    FormatterErrorPolicy::Abort => return Err(error),
+                    FormatterErrorPolicy::Skip => {
+                        zlog::warn!(logger => "Skipping formatter {formatter:?} after it failed: {error:#}");
+                    }
+                }
             }
         }
 
@@ -3502,6 +3534,11 @@ pub struct LspStore {
     lsp_document_colors: HashMap<BufferId, DocumentColorData>,
     lsp_code_lens: HashMap<BufferId, CodeLensData>,
     running_lsp_requests: HashMap<TypeId, (Global, HashMap<LspRequestId, Task<()>>)>,
+    /// The `file_types` setting from each settings file, to re-detect the
+    /// languages of open buffers when it changes.
+    file_types: Vec<(SettingsFile, HashMap<Arc<str>, ExtendingVec<String>>)>,
+    /// Buffers whose language was picked by the user, which re-detection leaves alone.
+    pub(crate) buffers_with_selected_language: HashSet<BufferId>,
 }
 
 #[derive(Debug, Default, Clone)]
@@ -3637,6 +3674,9 @@ impl LspStore {
         client.add_entity_request_handler(Self::handle_lsp_command::<GetCompletions>);
         client.add_entity_request_handler(Self::handle_lsp_command::<GetDocumentHighlights>);
         client.add_entity_request_handler(Self::handle_lsp_command::<GetDocumentSymbols>);
+        client.add_entity_request_handler(Self::handle_lsp_command::<PrepareTypeHierarchy>);
+        client.add_entity_request_handler(Self::handle_lsp_command::<GetTypeHierarchySupertypes>);
+        client.add_entity_request_handler(Self::handle_lsp_command::<GetTypeHierarchySubtypes>);
         client.add_entity_request_handler(Self::handle_lsp_command::<PrepareRename>);
         client.add_entity_request_handler(Self::handle_lsp_command::<PerformRename>);
         client.add_entity_request_handler(Self::handle_lsp_command::<LinkedEditingRange>);
@@ -3715,6 +3755,8 @@ impl LspStore {
             .detach();
         cx.subscribe(&toolchain_store, Self::on_toolchain_store_event)
             .detach();
+        cx.subscribe(&environment, Self::on_environment_event)
+            .detach();
         cx.observe_global::<SettingsStore>(Self::on_settings_changed)
             .detach();
         subscribe_to_binary_statuses(&languages, cx).detach();
@@ -3775,6 +3817,8 @@ impl LspStore {
             lsp_document_colors: HashMap::default(),
             lsp_code_lens: HashMap::default(),
             running_lsp_requests: HashMap::default(),
+            file_types: file_types_by_settings_file(cx),
+            buffers_with_selected_language: HashSet::default(),
             active_entry: None,
             _maintain_workspace_config,
             _maintain_buffer_languages: Self::maintain_buffer_languages(languages, cx),
@@ -3836,6 +3880,8 @@ impl LspStore {
             lsp_document_colors: HashMap::default(),
             lsp_code_lens: HashMap::default(),
             running_lsp_requests: HashMap::default(),
+            file_types: file_types_by_settings_file(cx),
+            buffers_with_selected_language: HashSet::default(),
             active_entry: None,
 
             _maintain_workspace_config,
@@ -3865,6 +3911,7 @@ impl LspStore {
                     }
                 }
 
+                self.buffers_with_selected_language.remove(&buffer_id);
                 self.detect_language_for_buffer(buffer, cx);
                 if let Some(local) = self.as_local_mut() {
                     local.initialize_buffer(buffer, cx);
@@ -3873,6 +3920,9 @@ impl LspStore {
                     }
                 }
             }
+            BufferStoreEvent::BufferDropped(buffer_id) => {
+                self.buffers_with_selected_language.remove(buffer_id);
+            }
             _ => {}
         }
     }
@@ -3945,6 +3995,31 @@ impl LspStore {
         }
     }
 
+    fn on_environment_event(
+        &mut self,
+        _: Entity<ProjectEnvironment>,
+        event: &ProjectEnvironmentEvent,
+        cx: &mut Context<Self>,
+    ) {
+        // Language servers only read their environment when they start.
+        if let ProjectEnvironmentEvent::OverridesChanged(worktree_ids) = event {
+            let buffers = self
+                .buffer_store
+                .read(cx)
+                .buffers()
+                .filter(|buffer| {
+                    buffer
+                        .read(cx)
+                        .file()
+                        .is_some_and(|file| worktree_ids.contains(&file.worktree_id(cx)))
+                })
+                .collect::<Vec<_>>();
+            if !buffers.is_empty() {
+                self.restart_language_servers_for_buffers(buffers, HashSet::default(), cx);
+            }
+        }
+    }
+
     fn request_workspace_config_refresh(&mut self) {
         *self._maintain_workspace_config.1.borrow_mut() = ();
     }
@@ -4173,6 +4248,53 @@ impl LspStore {
         })
     }
 
+    /// Re-detects the languages of open buffers whose paths match any of the
+    /// `file_types` globs that changed, other than those whose language was
+    /// picked by the user.
+    fn redetect_buffer_languages(&mut self, changed_globs: &GlobSet, cx: &mut Context<Self>) {
+        let buffers = self.buffer_store.read(cx).buffers().collect::<Vec<_>>();
+        for buffer_handle in buffers {
+            let buffer = buffer_handle.read(cx);
+            if self
+                .buffers_with_selected_language
+                .contains(&buffer.remote_id())
+            {
+                continue;
+            }
+            let Some(file) = buffer.file() else {
+                continue;
+            };
+            // Globs are matched against the same parts of the path as when
+            // detecting languages.
+            let full_path = file.full_path(cx);
+            let file_name = full_path.file_name().and_then(|name| name.to_str());
+            let extension = file_name.and_then(|name| name.split('.').next_back());
+            if ![extension, file_name, full_path.to_str()]
+                .into_iter()
+                .flatten()
+                .any(|suffix| changed_globs.is_match(suffix))
+            {
+                continue;
+            }
+            let detected_language = self
+                .languages
+                .language_for_file(file, Some(buffer.as_rope()), cx)
+                .map(|language| language.name());
+            let current_language = buffer
+                .language()
+                .map(|language| language.name())
+                .filter(|name| *name != language::PLAIN_TEXT.name());
+            if detected_language == current_language {
+                continue;
+            }
+            if detected_language.is_some() {
+                self.detect_language_for_buffer(&buffer_handle, cx);
+            } else {
+                self.set_language_for_buffer(&buffer_handle, language::PLAIN_TEXT.clone(), cx);
+            }
+        }
+    }
+
     fn detect_language_for_buffer(
         &mut self,
         buffer_handle: &Entity<Buffer>,
@@ -4495,6 +4617,13 @@ impl LspStore {
             }
         }
 
+        let file_types = file_types_by_settings_file(cx);
+        if file_types != self.file_types {
+            let changed_globs = changed_file_type_globs(&self.file_types, &file_types);
+            self.file_types = file_types;
+            self.redetect_buffer_languages(&changed_globs, cx);
+        }
+
         self.request_workspace_config_refresh();
 
         if let Some(prettier_store) = self.as_local().map(|s| s.prettier_store.clone()) {
@@ -11988,6 +12117,52 @@ fn lsp_workspace_diagnostics_refresh(
     })
 }
 
+/// Returns the `file_types` setting from each settings file that sets it.
+fn file_types_by_settings_file(
+    cx: &App,
+) -> Vec<(SettingsFile, HashMap<Arc<str>, ExtendingVec<String>>)> {
+    let store = cx.global::<SettingsStore>();
+    store
+        .get_all_files()
+        .into_iter()
+        .filter_map(|file| {
+            let content = store.get_content_for_file(file.clone())?;
+            let file_types = content.project.all_languages.file_types.clone()?;
+            Some((file, file_types))
+        })
+        .collect()
+}
+
+/// Builds a glob set from the `file_types` patterns that were added, removed or
+/// changed, including exclusions, which can change the language of the files
+/// that they match too.
+fn changed_file_type_globs(
+    old: &[(SettingsFile, HashMap<Arc<str>, ExtendingVec<String>>)],
+    new: &[(SettingsFile, HashMap<Arc<str>, ExtendingVec<String>>)],
+) -> GlobSet {
+    let mut builder = GlobSetBuilder::new();
+    for (file_types, other_file_types) in [(old, new), (new, old)] {
+        for (file, languages) in file_types {
+            let other_languages = other_file_types
+                .iter()
+                .find(|(other_file, _)| other_file == file)
+                .map(|(_, other_languages)| other_languages);
+            for (language, patterns) in languages {
+                if other_languages.and_then(|languages| languages.get(language)) == Some(patterns) {
+                    continue;
+                }
+                for pattern in &patterns.0 {
+                    let pattern = pattern.strip_prefix('!').unwrap_or(pattern);
+                    if let Some(glob) = Glob::new(pattern).log_err() {
+                        builder.add(glob);
+                    }
+                }
+            }
+        }
+    }
+    builder.build().log_err().unwrap_or_else(GlobSet::empty)
+}
+
 fn resolve_word_completion(snapshot: &BufferSnapshot, completion: &mut Completion) {
     let CompletionSource::BufferWord {
         word_range,
@@ -13100,6 +13275,40 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_changed_file_type_globs() {
+        let file_types = |c_patterns: &[&str]| {
+            vec![(
+                SettingsFile::User,
+                HashMap::from_iter([
+                    (Arc::from("JSON"), vec!["*.jsonc".to_string()].into()),
+                    (
+                        Arc::from("C"),
+                        c_patterns
+                            .iter()
+                            .map(|pattern| pattern.to_string())
+                            .collect::<Vec<_>>()
+                            .into(),
+                    ),
+                ]),
+            )]
+        };
+
+        // Only the patterns of languages whose patterns changed are included.
+        let globs = changed_file_type_globs(
+            &file_types(&["*.h"]),
+            &file_types(&["*.h", "!**/legacy/**"]),
+        );
+        assert!(globs.is_match("a.h"));
+        assert!(globs.is_match("src/legacy/b.c"));
+        assert!(!globs.is_match("a.jsonc"));
+
+        // So are those of settings files that were removed.
+        let globs = changed_file_type_globs(&file_types(&["*.h"]), &[]);
+        assert!(globs.is_match("a.h"));
+        assert!(globs.is_match("a.jsonc"));
+    }
+
     #[test]
     fn test_multi_len_chars_normalization() {
         let mut label = CodeLabel {
@@ -700,7 +700,7 @@ impl PrettierStore {
 pub fn prettier_plugins_for_language(
     language_settings: &LanguageSettings,
 ) -> Option<&HashSet<String>> {
-    let formatters = language_settings.formatter.as_ref();
+    let formatters = &language_settings.formatter;
     if formatters.contains(&Formatter::Prettier) || formatters.contains(&Formatter::Auto) {
         return Some(&language_settings.prettier.plugins);
     }
@@ -328,6 +328,8 @@ pub enum Event {
     SnippetEdit(BufferId, Vec<(lsp::Range, Snippet)>),
     ExpandedAllForEntry(WorktreeId, ProjectEntryId),
     EntryRenamed(ProjectTransaction),
+    /// The `.zed/keymap.json` file of one of the project's worktrees changed.
+    LocalKeymapUpdated,
 }
 
 pub enum DebugAdapterClientState {
@@ -391,6 +393,9 @@ pub struct InlayHint {
     pub padding_left: bool,
     pub padding_right: bool,
     pub tooltip: Option<InlayHintTooltip>,
+    /// The edits that make the hinted text explicit in the code, such as inserting the type
+    /// annotation that a type hint shows.
+    pub text_edits: Vec<(Range<language::Anchor>, String)>,
     pub resolve_state: ResolveState,
 }
 
@@ -678,6 +683,49 @@ impl InlayHint {
             InlayHintLabel::LabelParts(parts) => parts.iter().map(|part| &*part.value).collect(),
         }
     }
+
+    /// Shortens the label to at most `max_length` characters, replacing its
+    /// end with an ellipsis.
+    pub fn truncate_label(&mut self, max_length: usize) {
+        const ELLIPSIS: &str = "…";
+        if self.text().chars().count() <= max_length {
+            return;
+        }
+        let mut remaining = max_length.saturating_sub(ELLIPSIS.chars().count());
+        match &mut self.label {
+            InlayHintLabel::String(label) => {
+                truncate_to_chars(label, remaining);
+                label.push_str(ELLIPSIS);
+            }
+            InlayHintLabel::LabelParts(parts) => {
+                let mut kept_parts = 0;
+                for part in parts.iter_mut() {
+                    if remaining == 0 {
+                        break;
+                    }
+                    let part_length = part.value.chars().count();
+                    truncate_to_chars(&mut part.value, remaining);
+                    remaining = remaining.saturating_sub(part_length);
+                    kept_parts += 1;
+                }
+                parts.truncate(kept_parts);
+                match parts.last_mut() {
+                    Some(last_part) => last_part.value.push_str(ELLIPSIS),
+                    None => parts.push(InlayHintLabelPart {
+                        value: ELLIPSIS.to_string(),
+                        tooltip: None,
+                        location: None,
+                    }),
+                }
+            }
+        }
+    }
+}
+
+fn truncate_to_chars(text: &mut String, max_chars: usize) {
+    if let Some((byte_index, _)) = text.char_indices().nth(max_chars) {
+        text.truncate(byte_index);
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -744,6 +792,19 @@ pub struct DocumentSymbol {
     pub children: Vec<DocumentSymbol>,
 }
 
+/// A type that a language server reported while navigating the type hierarchy, along with the
+/// server's own representation of it, which the server needs to find its supertypes and subtypes.
+#[derive(Clone, Debug)]
+pub struct TypeHierarchyItem {
+    pub name: String,
+    pub kind: lsp::SymbolKind,
+    pub detail: Option<String>,
+    pub abs_path: PathBuf,
+    pub selection_range: Range<Unclipped<PointUtf16>>,
+    pub server_id: LanguageServerId,
+    pub(crate) lsp_item: lsp::TypeHierarchyItem,
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct HoverBlock {
     pub text: String,
@@ -1009,7 +1070,7 @@ impl Project {
             cx.subscribe(&worktree_store, Self::on_worktree_store_event)
                 .detach();
 
-            let environment = cx.new(|_| ProjectEnvironment::new(env));
+            let environment = cx.new(|cx| ProjectEnvironment::new(env, &worktree_store, cx));
             let manifest_tree = ManifestTree::new(worktree_store.clone(), cx);
             let toolchain_store = cx.new(|cx| {
                 ToolchainStore::local(
@@ -1225,7 +1286,7 @@ impl Project {
             cx.subscribe(&settings_observer, Self::on_settings_observer_event)
                 .detach();
 
-            let environment = cx.new(|_| ProjectEnvironment::new(None));
+            let environment = cx.new(|cx| ProjectEnvironment::new(None, &worktree_store, cx));
 
             let lsp_store = cx.new(|cx| {
                 LspStore::new_remote(
@@ -1430,7 +1491,7 @@ impl Project {
             ImageStore::remote(worktree_store.clone(), client.clone().into(), remote_id, cx)
         })?;
 
-        let environment = cx.new(|_| ProjectEnvironment::new(None))?;
+        let environment = cx.new(|cx| ProjectEnvironment::new(None, &worktree_store, cx))?;
 
         let breakpoint_store =
             cx.new(|_| BreakpointStore::remote(remote_id, client.clone().into()))?;
@@ -1902,6 +1963,11 @@ impl Project {
         &self.task_store
     }
 
+    /// Returns the contents of the project's `.zed/keymap.json` files.
+    pub fn local_keymaps(&self, cx: &App) -> Vec<String> {
+        self.settings_observer.read(cx).local_keymaps(cx)
+    }
+
     pub fn snippets(&self) -> &Entity<SnippetProvider> {
         &self.snippets
     }
@@ -3048,6 +3114,7 @@ impl Project {
                 }),
                 Err(_) => {}
             },
+            SettingsObserverEvent::LocalKeymapUpdated => cx.emit(Event::LocalKeymapUpdated),
         }
     }
 
@@ -3236,6 +3303,9 @@ impl Project {
         cx: &mut Context<Self>,
     ) {
         self.lsp_store.update(cx, |lsp_store, cx| {
+            lsp_store
+                .buffers_with_selected_language
+                .insert(buffer.read(cx).remote_id());
             lsp_store.set_language_for_buffer(buffer, new_language, cx)
         })
     }
@@ -3569,6 +3639,53 @@ impl Project {
         )
     }
 
+    pub fn prepare_type_hierarchy<T: ToPointUtf16>(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        position: T,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<TypeHierarchyItem>>> {
+        let position = position.to_point_utf16(buffer.read(cx));
+        self.request_lsp(
+            buffer.clone(),
+            LanguageServerToQuery::FirstCapable,
+            PrepareTypeHierarchy { position },
+            cx,
+        )
+    }
+
+    /// Requests the supertypes of an item that [`Project::prepare_type_hierarchy`] returned for
+    /// the given buffer, from the language server that returned it.
+    pub fn type_hierarchy_supertypes(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        item: &TypeHierarchyItem,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<TypeHierarchyItem>>> {
+        self.request_lsp(
+            buffer.clone(),
+            LanguageServerToQuery::Other(item.server_id),
+            GetTypeHierarchySupertypes { item: item.clone() },
+            cx,
+        )
+    }
+
+    /// Requests the subtypes of an item that [`Project::prepare_type_hierarchy`] returned for
+    /// the given buffer, from the language server that returned it.
+    pub fn type_hierarchy_subtypes(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        item: &TypeHierarchyItem,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<TypeHierarchyItem>>> {
+        self.request_lsp(
+            buffer.clone(),
+            LanguageServerToQuery::Other(item.server_id),
+            GetTypeHierarchySubtypes { item: item.clone() },
+            cx,
+        )
+    }
+
     pub fn symbols(&self, query: &str, cx: &mut Context<Self>) -> Task<Result<Vec<Symbol>>> {
         self.lsp_store
             .update(cx, |lsp_store, cx| lsp_store.symbols(query, cx))
@@ -5198,7 +5315,9 @@ impl Project {
         };
         cx.spawn(async move |cx| {
             let file = worktree
-                .update(cx, |worktree, cx| worktree.load_file(&rel_path, cx))?
+                .update(cx, |worktree, cx| {
+                    worktree.load_file(&rel_path, Encoding::default(), cx)
+                })?
                 .await
                 .context("Failed to load settings file")?;
 
@@ -5208,7 +5327,13 @@ impl Project {
             worktree
                 .update(cx, |worktree, cx| {
                     let line_ending = text::LineEnding::detect(&new_text);
-                    worktree.write_file(rel_path.clone(), new_text.into(), line_ending, cx)
+                    worktree.write_file(
+                        rel_path.clone(),
+                        new_text.into(),
+                        line_ending,
+                        Encoding::default(),
+                        cx,
+                    )
                 })?
                 .await
                 .context("Failed to write settings file")?;
@@ -1,14 +1,14 @@
 use anyhow::Context as _;
-use collections::HashMap;
+use collections::{BTreeMap, HashMap};
 use dap::adapters::DebugAdapterName;
 use fs::Fs;
 use futures::StreamExt as _;
-use gpui::{AsyncApp, BorrowAppContext, Context, Entity, EventEmitter, Subscription, Task};
+use gpui::{App, AsyncApp, BorrowAppContext, Context, Entity, EventEmitter, Subscription, Task};
 use lsp::LanguageServerName;
 use paths::{
-    EDITORCONFIG_NAME, local_debug_file_relative_path, local_settings_file_relative_path,
-    local_tasks_file_relative_path, local_vscode_launch_file_relative_path,
-    local_vscode_tasks_file_relative_path, task_file_name,
+    EDITORCONFIG_NAME, local_debug_file_relative_path, local_keymap_file_relative_path,
+    local_settings_file_relative_path, local_tasks_file_relative_path,
+    local_vscode_launch_file_relative_path, local_vscode_tasks_file_relative_path, task_file_name,
 };
 use rpc::{
     AnyProtoClient, TypedEnvelope,
@@ -66,6 +66,9 @@ pub struct ProjectSettings {
     /// Configuration for how direnv configuration should be loaded
     pub load_direnv: DirenvSettings,
 
+    /// Environment variables that take precedence over the environment loaded from the shell.
+    pub env: HashMap<String, String>,
+
     /// Configuration for session-related features
     pub session: SessionSettings,
 }
@@ -218,7 +221,7 @@ impl GoToDiagnosticSeverityFilter {
     }
 }
 
-#[derive(Copy, Clone, Debug)]
+#[derive(Clone, Debug)]
 pub struct GitSettings {
     /// Whether or not to show the git gutter.
     ///
@@ -243,6 +246,35 @@ pub struct GitSettings {
     ///
     /// Default: staged_hollow
     pub hunk_style: settings::GitHunkStyleSetting,
+    /// The git executable to run, instead of the one found on the `PATH`.
+    ///
+    /// Default: None
+    pub binary_path: Option<PathBuf>,
+    /// Environment variables to set for git commands.
+    ///
+    /// Default: {}
+    pub env: HashMap<String, String>,
+    /// Configuration values to apply to git commands.
+    ///
+    /// Default: {}
+    pub config: HashMap<String, String>,
+    /// How long a git operation can run before it's reported as slow, if they're reported.
+    ///
+    /// Default: 10 seconds
+    pub slow_operation_threshold: Option<Duration>,
+    /// How long to wait for the host of a shared project to respond to a fetch, push or pull,
+    /// or to report its progress, if there's a limit.
+    ///
+    /// Default: 60 seconds
+    pub remote_request_timeout: Option<Duration>,
+    /// How many more times to try a fetch in a shared project after the host didn't respond.
+    ///
+    /// Default: 2
+    pub remote_request_retries: u32,
+    /// Whether to list the branches and remotes of each repository in the background.
+    ///
+    /// Default: false
+    pub prefetch_branches: bool,
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -288,6 +320,24 @@ impl GitSettings {
             None
         }
     }
+
+    /// The variables to add to the environment of git commands. Configuration values are passed
+    /// through `GIT_CONFIG_COUNT`, `GIT_CONFIG_KEY_<n>` and `GIT_CONFIG_VALUE_<n>`, which git
+    /// reads like `-c` arguments, so that they reach every command without changing how it is
+    /// invoked.
+    pub fn command_environment(&self) -> HashMap<String, String> {
+        let mut environment = self.env.clone();
+        if !self.config.is_empty() {
+            let mut config = self.config.iter().collect::<Vec<_>>();
+            config.sort();
+            for (index, (key, value)) in config.into_iter().enumerate() {
+                environment.insert(format!("GIT_CONFIG_KEY_{index}"), key.clone());
+                environment.insert(format!("GIT_CONFIG_VALUE_{index}"), value.clone());
+            }
+            environment.insert("GIT_CONFIG_COUNT".into(), self.config.len().to_string());
+        }
+        environment
+    }
 }
 
 #[derive(Clone, Copy, Debug, Serialize, Deserialize, JsonSchema)]
@@ -396,6 +446,19 @@ impl Settings for ProjectSettings {
                 }
             },
             hunk_style: git.hunk_style.unwrap(),
+            binary_path: git.binary_path.clone(),
+            env: git.env.clone().unwrap_or_default(),
+            config: git.config.clone().unwrap_or_default(),
+            slow_operation_threshold: git
+                .slow_operation_threshold_ms
+                .filter(|threshold| *threshold > 0)
+                .map(Duration::from_millis),
+            remote_request_timeout: git
+                .remote_request_timeout_ms
+                .filter(|timeout| *timeout > 0)
+                .map(Duration::from_millis),
+            remote_request_retries: git.remote_request_retries.unwrap(),
+            prefetch_branches: git.prefetch_branches.unwrap(),
         };
         Self {
             lsp: project
@@ -436,6 +499,7 @@ impl Settings for ProjectSettings {
             git: git_settings,
             node: content.node.clone().unwrap().into(),
             load_direnv: project.load_direnv.clone().unwrap(),
+            env: project.env.clone(),
             session: SessionSettings {
                 restore_unsaved_buffers: content.session.unwrap().restore_unsaved_buffers.unwrap(),
             },
@@ -478,6 +542,7 @@ pub enum SettingsObserverEvent {
     LocalSettingsUpdated(Result<PathBuf, InvalidSettingsError>),
     LocalTasksUpdated(Result<PathBuf, InvalidSettingsError>),
     LocalDebugScenariosUpdated(Result<PathBuf, InvalidSettingsError>),
+    LocalKeymapUpdated,
 }
 
 impl EventEmitter<SettingsObserverEvent> for SettingsObserver {}
@@ -488,6 +553,8 @@ pub struct SettingsObserver {
     worktree_store: Entity<WorktreeStore>,
     project_id: u64,
     task_store: Entity<TaskStore>,
+    /// The contents of the `.zed/keymap.json` file at the root of each worktree.
+    local_keymaps: BTreeMap<WorktreeId, String>,
     _user_settings_watcher: Option<Subscription>,
     _global_task_config_watcher: Task<()>,
     _global_debug_config_watcher: Task<()>,
@@ -518,6 +585,7 @@ impl SettingsObserver {
             task_store,
             mode: SettingsObserverMode::Local(fs.clone()),
             downstream_client: None,
+            local_keymaps: BTreeMap::default(),
             _user_settings_watcher: None,
             project_id: REMOTE_SERVER_PROJECT_ID,
             _global_task_config_watcher: Self::subscribe_to_global_task_file_changes(
@@ -569,6 +637,7 @@ impl SettingsObserver {
             task_store,
             mode: SettingsObserverMode::Remote,
             downstream_client: None,
+            local_keymaps: BTreeMap::default(),
             project_id: REMOTE_SERVER_PROJECT_ID,
             _user_settings_watcher: user_settings_watcher,
             _global_task_config_watcher: Self::subscribe_to_global_task_file_changes(
@@ -626,6 +695,16 @@ impl SettingsObserver {
         }
     }
 
+    /// Returns the contents of the `.zed/keymap.json` files at the roots of the project's
+    /// worktrees.
+    pub fn local_keymaps(&self, cx: &App) -> Vec<String> {
+        self.worktree_store
+            .read(cx)
+            .worktrees()
+            .filter_map(|worktree| self.local_keymaps.get(&worktree.read(cx).id()).cloned())
+            .collect()
+    }
+
     pub fn unshared(&mut self, _: &mut Context<Self>) {
         self.downstream_client = None;
     }
@@ -684,13 +763,20 @@ impl SettingsObserver {
         event: &WorktreeStoreEvent,
         cx: &mut Context<Self>,
     ) {
-        if let WorktreeStoreEvent::WorktreeAdded(worktree) = event {
-            cx.subscribe(worktree, |this, worktree, event, cx| {
-                if let worktree::Event::UpdatedEntries(changes) = event {
-                    this.update_local_worktree_settings(&worktree, changes, cx)
+        match event {
+            WorktreeStoreEvent::WorktreeAdded(worktree) => cx
+                .subscribe(worktree, |this, worktree, event, cx| {
+                    if let worktree::Event::UpdatedEntries(changes) = event {
+                        this.update_local_worktree_settings(&worktree, changes, cx)
+                    }
+                })
+                .detach(),
+            WorktreeStoreEvent::WorktreeRemoved(_, worktree_id) => {
+                if self.local_keymaps.remove(worktree_id).is_some() {
+                    cx.emit(SettingsObserverEvent::LocalKeymapUpdated);
                 }
-            })
-            .detach()
+            }
+            _ => {}
         }
     }
 
@@ -761,6 +847,8 @@ impl SettingsObserver {
                     .unwrap()
                     .into();
                 (settings_dir, LocalSettingsKind::Debug)
+            } else if path.as_ref() == local_keymap_file_relative_path() {
+                (RelPath::empty().into(), LocalSettingsKind::Keymap)
             } else if path.ends_with(RelPath::unix(EDITORCONFIG_NAME).unwrap()) {
                 let Some(settings_dir) = path.parent().map(Arc::from) else {
                     continue;
@@ -919,6 +1007,15 @@ impl SettingsObserver {
                         }
                     }
                 }
+                LocalSettingsKind::Keymap => {
+                    let previous_keymap = match file_content.clone() {
+                        Some(keymap) => self.local_keymaps.insert(worktree_id, keymap),
+                        None => self.local_keymaps.remove(&worktree_id),
+                    };
+                    if previous_keymap != file_content {
+                        cx.emit(SettingsObserverEvent::LocalKeymapUpdated);
+                    }
+                }
                 LocalSettingsKind::Debug => {
                     let result = task_store.update(cx, |task_store, cx| {
                         task_store.update_user_debug_scenarios(
@@ -1084,6 +1181,7 @@ pub fn local_settings_kind_from_proto(kind: proto::LocalSettingsKind) -> LocalSe
         proto::LocalSettingsKind::Tasks => LocalSettingsKind::Tasks,
         proto::LocalSettingsKind::Editorconfig => LocalSettingsKind::Editorconfig,
         proto::LocalSettingsKind::Debug => LocalSettingsKind::Debug,
+        proto::LocalSettingsKind::Keymap => LocalSettingsKind::Keymap,
     }
 }
 
@@ -1093,6 +1191,7 @@ pub fn local_settings_kind_to_proto(kind: LocalSettingsKind) -> proto::LocalSett
         LocalSettingsKind::Tasks => proto::LocalSettingsKind::Tasks,
         LocalSettingsKind::Editorconfig => proto::LocalSettingsKind::Editorconfig,
         LocalSettingsKind::Debug => proto::LocalSettingsKind::Debug,
+        LocalSettingsKind::Keymap => proto::LocalSettingsKind::Keymap,
     }
 }
 
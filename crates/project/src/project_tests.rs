@@ -2,7 +2,10 @@
 
 use crate::{
     Event,
-    git_store::{GitStoreEvent, RepositoryEvent, StatusEntry},
+    git_store::{
+        BatchOperation, GitStoreEvent, HeadState, Repository, RepositoryEvent, RepositoryId,
+        StagingReport, StatusEntry,
+    },
     task_inventory::TaskContexts,
     task_store::TaskSettingsLocation,
     *,
@@ -12,12 +15,16 @@ use buffer_diff::{
     BufferDiffEvent, CALCULATE_DIFF_TASK, DiffHunkSecondaryStatus, DiffHunkStatus,
     DiffHunkStatusKind, assert_hunks,
 };
-use fs::FakeFs;
-use futures::{StreamExt, future};
+use fs::{Encoding, FakeFs};
+use futures::{FutureExt as _, StreamExt, future, future::BoxFuture};
 use git::{
     GitHostingProviderRegistry,
-    repository::{RepoPath, repo_path},
-    status::{StatusCode, TrackedStatus},
+    lfs::LfsFileStatus,
+    repository::{
+        AskPassDelegate, CleanOptions, ConflictSide, FetchOptions, IgnoreFile, MergeOptions,
+        RemoteCommandTimedOut, RepoPath, repo_path,
+    },
+    status::{StatusCode, TrackedStatus, UnmergedStatus, UnmergedStatusCode},
 };
 use git2::RepositoryInitOptions;
 use gpui::{App, BackgroundExecutor, SemanticVersion, UpdateGlobal};
@@ -27,7 +34,10 @@ use language::{
     DiskState, FakeLspAdapter, LanguageConfig, LanguageMatcher, LanguageName, LineEnding,
     ManifestName, ManifestProvider, ManifestQuery, OffsetRangeExt, Point, ToPoint, ToolchainList,
     ToolchainLister,
-    language_settings::{LanguageSettingsContent, language_settings},
+    language_settings::{
+        AllLanguageSettings, EditorconfigProvenance, LanguageSettingSource,
+        LanguageSettingsContent, SoftWrap, language_settings,
+    },
     tree_sitter_rust, tree_sitter_typescript,
 };
 use lsp::{
@@ -39,6 +49,7 @@ use paths::{config_dir, global_gitignore_path, tasks_file};
 use postage::stream::Stream as _;
 use pretty_assertions::{assert_eq, assert_matches};
 use rand::{Rng as _, rngs::StdRng};
+use rpc::{AnyProtoClient, ProtoClient, ProtoMessageHandlerSet};
 use serde_json::json;
 #[cfg(not(windows))]
 use std::os;
@@ -244,6 +255,410 @@ async fn test_editorconfig_support(cx: &mut gpui::TestAppContext) {
     });
 }
 
+#[gpui::test]
+async fn test_language_settings_glob_overrides(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let dir = TempTree::new(json!({
+        ".zed": {
+            "settings.json": r#"{
+                "tab_size": 4,
+                "overrides": [
+                    { "globs": ["generated/**"], "settings": { "tab_size": 3 } }
+                ],
+                "languages": {
+                    "JavaScript": {
+                        "tab_size": 2,
+                        "overrides": [
+                            { "globs": ["*.min.js"], "settings": { "tab_size": 8 } },
+                            { "globs": ["*.js"], "settings": { "hard_tabs": true } }
+                        ]
+                    }
+                }
+            }"#,
+        },
+        "app.js": "",
+        "app.min.js": "",
+        "generated": {
+            "a.txt": "",
+        },
+    }));
+
+    let path = dir.path();
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree_from_real_fs(path, path).await;
+    let project = Project::test(fs.clone(), [path], cx).await;
+    let worktree = project.update(cx, |project, cx| project.worktrees(cx).next().unwrap());
+
+    cx.executor().run_until_parked();
+
+    cx.update(|cx| {
+        let tree = worktree.read(cx);
+        let settings_for = |language: Option<&str>, path: &str| {
+            let file_entry = tree.entry_for_path(rel_path(path)).unwrap().clone();
+            let file = File::for_entry(file_entry, worktree.clone()) as _;
+            language_settings(language.map(|name| name.into()), Some(&file), cx).into_owned()
+        };
+
+        // Only the first matching override applies, on top of the language's settings.
+        let settings_min_js = settings_for(Some("JavaScript"), "app.min.js");
+        assert_eq!(settings_min_js.tab_size.get(), 8);
+        assert!(!settings_min_js.hard_tabs);
+
+        let settings_js = settings_for(Some("JavaScript"), "app.js");
+        assert_eq!(settings_js.tab_size.get(), 2);
+        assert!(settings_js.hard_tabs);
+
+        // Languages' own overrides replace the default ones.
+        let settings_generated_js = settings_for(Some("JavaScript"), "generated/a.txt");
+        assert_eq!(settings_generated_js.tab_size.get(), 2);
+
+        let settings_generated = settings_for(None, "generated/a.txt");
+        assert_eq!(settings_generated.tab_size.get(), 3);
+    });
+}
+
+#[gpui::test]
+async fn test_explain_language_settings(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let dir = TempTree::new(json!({
+        ".editorconfig": r#"
+        root = true
+        [*]
+            indent_size = 3
+        "#,
+        ".zed": {
+            "settings.json": r#"{
+                "preferred_line_length": 100,
+            }"#,
+        },
+        "a.js": "",
+    }));
+
+    let path = dir.path();
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree_from_real_fs(path, path).await;
+    fs.atomic_write(paths::settings_file().clone(), "{}".into())
+        .await
+        .unwrap();
+    let project = Project::test(fs.clone(), [path], cx).await;
+    let worktree = project.update(cx, |project, cx| project.worktrees(cx).next().unwrap());
+
+    let vscode_settings = settings::VsCodeSettings::from_str(
+        r#"{ "editor.insertSpaces": false }"#,
+        settings::VsCodeSettingsSource::VsCode,
+    )
+    .unwrap();
+    let import = cx.update(|cx| {
+        SettingsStore::update_global(cx, |store, _| {
+            store.import_vscode_settings(fs.clone(), vscode_settings)
+        })
+    });
+    import.await.unwrap().unwrap();
+    let user_settings = fs.load(paths::settings_file()).await.unwrap();
+    cx.update(|cx| {
+        SettingsStore::update_global(cx, |store, cx| {
+            store.set_user_settings(&user_settings, cx).unwrap();
+            store.update_user_settings(cx, |settings| {
+                settings.project.all_languages.defaults.soft_wrap = Some(SoftWrap::None);
+            });
+        });
+    });
+
+    cx.executor().run_until_parked();
+
+    cx.update(|cx| {
+        let tree = worktree.read(cx);
+        let location = SettingsLocation {
+            worktree_id: tree.id(),
+            path: rel_path("a.js"),
+        };
+        let sources = AllLanguageSettings::get_global(cx).explain(
+            Some(location),
+            Some(&LanguageName::new("JavaScript")),
+            cx,
+        );
+
+        assert_eq!(
+            sources.get("tab_size"),
+            Some(&LanguageSettingSource::Editorconfig)
+        );
+        assert_matches!(
+            sources.get("preferred_line_length"),
+            Some(LanguageSettingSource::Project(_))
+        );
+        assert_eq!(
+            sources.get("hard_tabs"),
+            Some(&LanguageSettingSource::VsCodeImport)
+        );
+        assert_eq!(sources.get("soft_wrap"), Some(&LanguageSettingSource::User));
+        assert_eq!(
+            sources.get("format_on_save"),
+            Some(&LanguageSettingSource::Default)
+        );
+    });
+}
+
+#[gpui::test]
+async fn test_file_types_change_redetects_buffer_languages(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({
+            "src": { "a.js": "" },
+            "legacy": { "b.js": "", "c.js": "" },
+        }),
+    )
+    .await;
+    let project = Project::test(fs, [path!("/dir").as_ref()], cx).await;
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(js_lang());
+    language_registry.add(json_lang());
+
+    let buffer_a = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer(path!("/dir/src/a.js"), cx)
+        })
+        .await
+        .unwrap();
+    let buffer_b = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer(path!("/dir/legacy/b.js"), cx)
+        })
+        .await
+        .unwrap();
+    let buffer_c = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer(path!("/dir/legacy/c.js"), cx)
+        })
+        .await
+        .unwrap();
+    cx.executor().run_until_parked();
+
+    // The language that the user picks for a buffer is kept.
+    let json = language_registry.language_for_name("JSON").await.unwrap();
+    project.update(cx, |project, cx| {
+        project.set_language_for_buffer(&buffer_c, json, cx)
+    });
+
+    let language_names = |cx: &mut gpui::TestAppContext| {
+        [&buffer_a, &buffer_b, &buffer_c].map(|buffer| {
+            buffer.read_with(cx, |buffer, _| {
+                buffer.language().map(|language| language.name())
+            })
+        })
+    };
+    assert_eq!(
+        language_names(cx),
+        [
+            Some("JavaScript".into()),
+            Some("JavaScript".into()),
+            Some("JSON".into())
+        ]
+    );
+
+    cx.update(|cx| {
+        SettingsStore::update_global(cx, |store, cx| {
+            store.update_user_settings(cx, |settings| {
+                settings.project.all_languages.file_types = Some(HashMap::from_iter([(
+                    "JSON".into(),
+                    vec!["**/*.js".to_string(), "!**/legacy/**".to_string()].into(),
+                )]));
+            });
+        });
+    });
+    cx.executor().run_until_parked();
+    assert_eq!(
+        language_names(cx),
+        [
+            Some("JSON".into()),
+            Some("JavaScript".into()),
+            Some("JSON".into())
+        ]
+    );
+
+    cx.update(|cx| {
+        SettingsStore::update_global(cx, |store, cx| {
+            store.update_user_settings(cx, |settings| {
+                settings.project.all_languages.file_types = None;
+            });
+        });
+    });
+    cx.executor().run_until_parked();
+    assert_eq!(
+        language_names(cx),
+        [
+            Some("JavaScript".into()),
+            Some("JavaScript".into()),
+            Some("JSON".into())
+        ]
+    );
+}
+
+#[gpui::test]
+async fn test_editorconfig_charset_and_provenance(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let dir = TempTree::new(json!({
+        ".editorconfig": r#"
+        root = true
+        [*]
+            trim_trailing_whitespace = true
+        [*.md]
+            trim_trailing_whitespace = false
+        [*.rs]
+            charset = latin1
+        "#,
+        ".zed": {
+            "settings.json": r#"{
+                "remove_trailing_whitespace_on_save": false,
+            }"#,
+        },
+        "a.rs": "fn a() {}\n",
+        "README.md": "readme\n",
+    }));
+
+    let path = dir.path();
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree_from_real_fs(path, path).await;
+    let project = Project::test(fs.clone(), [path], cx).await;
+    let worktree = project.update(cx, |project, cx| project.worktrees(cx).next().unwrap());
+
+    cx.executor().run_until_parked();
+
+    cx.update(|cx| {
+        let tree = worktree.read(cx);
+        let settings_for = |path: &str| {
+            let file_entry = tree.entry_for_path(rel_path(path)).unwrap().clone();
+            let file = File::for_entry(file_entry, worktree.clone()) as _;
+            language_settings(None, Some(&file), cx).into_owned()
+        };
+
+        let settings_rs = settings_for("a.rs");
+        assert_eq!(settings_rs.remove_trailing_whitespace_on_save, true);
+        assert_eq!(settings_rs.encoding, Encoding::Latin1);
+        assert_eq!(
+            settings_rs.editorconfig,
+            EditorconfigProvenance {
+                remove_trailing_whitespace_on_save: true,
+                encoding: true,
+                ..EditorconfigProvenance::default()
+            }
+        );
+
+        // A later section overrides the whitespace setting for its glob.
+        let settings_md = settings_for("README.md");
+        assert_eq!(settings_md.remove_trailing_whitespace_on_save, false);
+        assert_eq!(settings_md.encoding, Encoding::Utf8);
+        assert_eq!(
+            settings_md.editorconfig,
+            EditorconfigProvenance {
+                remove_trailing_whitespace_on_save: true,
+                ..EditorconfigProvenance::default()
+            }
+        );
+    });
+
+    let buffer = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer(path.join("a.rs"), cx)
+        })
+        .await
+        .unwrap();
+    buffer.update(cx, |buffer, cx| {
+        buffer.edit([(0..0, "// café\n")], None, cx)
+    });
+    project
+        .update(cx, |project, cx| project.save_buffer(buffer.clone(), cx))
+        .await
+        .unwrap();
+    assert_eq!(
+        fs.load_bytes(&path.join("a.rs")).await.unwrap(),
+        b"// caf\xe9\nfn a() {}\n"
+    );
+
+    // The file is decoded as latin1 when it's reloaded or opened again.
+    fs.write(&path.join("a.rs"), b"// na\xefve\n")
+        .await
+        .unwrap();
+    buffer
+        .update(cx, |buffer, cx| buffer.reload(cx))
+        .await
+        .unwrap();
+    cx.executor().run_until_parked();
+    buffer.read_with(cx, |buffer, _| assert_eq!(buffer.text(), "// naïve\n"));
+
+    let project = Project::test(fs.clone(), [path], cx).await;
+    let buffer = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer(path.join("a.rs"), cx)
+        })
+        .await
+        .unwrap();
+    buffer.read_with(cx, |buffer, _| assert_eq!(buffer.text(), "// naïve\n"));
+}
+
+#[gpui::test]
+async fn test_large_file_thresholds(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+    cx.update(|cx| {
+        SettingsStore::update_global(cx, |store, cx| {
+            store.update_user_settings(cx, |settings| {
+                let all_languages = &mut settings.project.all_languages;
+                all_languages.large_file_thresholds = Some(settings::LargeFileThresholdsContent {
+                    inlay_hints: Some(10),
+                    word_completions: Some(0),
+                    indent_guides: Some(100),
+                });
+                all_languages
+                    .defaults
+                    .inlay_hints
+                    .get_or_insert_default()
+                    .enabled = Some(true);
+            });
+        });
+    });
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({
+            "small.txt": "small",
+            "large.txt": "large".repeat(10),
+        }),
+    )
+    .await;
+    let project = Project::test(fs, [path!("/dir").as_ref()], cx).await;
+    let worktree = project.update(cx, |project, cx| project.worktrees(cx).next().unwrap());
+
+    cx.executor().run_until_parked();
+
+    cx.update(|cx| {
+        let tree = worktree.read(cx);
+        let settings_for = |path: &str| {
+            let file_entry = tree.entry_for_path(rel_path(path)).unwrap().clone();
+            let file = File::for_entry(file_entry, worktree.clone()) as _;
+            language_settings(None, Some(&file), cx).into_owned()
+        };
+
+        let settings_small = settings_for("small.txt");
+        assert!(settings_small.inlay_hints.enabled);
+        assert!(settings_small.indent_guides.enabled);
+
+        let settings_large = settings_for("large.txt");
+        assert!(!settings_large.inlay_hints.enabled);
+        assert!(settings_large.indent_guides.enabled);
+        // A threshold of 0 never disables its feature.
+        assert_ne!(
+            settings_large.completions.words,
+            language::language_settings::WordsCompletionMode::Disabled
+        );
+    });
+}
+
 #[gpui::test]
 async fn test_git_provider_project_setting(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -310,26 +725,153 @@ async fn test_git_provider_project_setting(cx: &mut gpui::TestAppContext) {
 }
 
 #[gpui::test]
-async fn test_managing_project_specific_settings(cx: &mut gpui::TestAppContext) {
+async fn test_environment_overrides_from_settings(cx: &mut gpui::TestAppContext) {
     init_test(cx);
-    TaskStore::init(None);
 
     let fs = FakeFs::new(cx.executor());
     fs.insert_tree(
         path!("/dir"),
         json!({
             ".zed": {
-                "settings.json": r#"{ "tab_size": 8 }"#,
-                "tasks.json": r#"[{
-                    "label": "cargo check all",
-                    "command": "cargo",
-                    "args": ["check", "--all"]
-                },]"#,
+                "settings.json": r#"{ "env": { "RUST_LOG": "debug" } }"#
             },
-            "a": {
-                "a.rs": "fn a() {\n    A\n}"
-            },
-            "b": {
+            "a.rs": "",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs.clone(), [path!("/dir").as_ref()], cx).await;
+    cx.executor().run_until_parked();
+    let worktree = project.update(cx, |project, cx| project.worktrees(cx).next().unwrap());
+    let worktree_id = worktree.read_with(cx, |worktree, _| worktree.id());
+    let environment = project.read_with(cx, |project, _| project.environment().clone());
+
+    let changed_worktrees = Arc::new(Mutex::new(Vec::new()));
+    cx.update(|cx| {
+        cx.subscribe(&environment, {
+            let changed_worktrees = changed_worktrees.clone();
+            move |_, event, _| {
+                if let ProjectEnvironmentEvent::OverridesChanged(worktree_ids) = event {
+                    changed_worktrees
+                        .lock()
+                        .extend(worktree_ids.iter().copied());
+                }
+            }
+        })
+        .detach();
+    });
+
+    let worktree_environment = environment
+        .update(cx, |environment, cx| {
+            environment.get_worktree_environment(worktree.clone(), cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(
+        worktree_environment.get("RUST_LOG").map(String::as_str),
+        Some("debug")
+    );
+
+    fs.atomic_write(
+        Path::new(path!("/dir/.zed/settings.json")).to_owned(),
+        r#"{ "env": { "RUST_LOG": "trace" } }"#.into(),
+    )
+    .await
+    .unwrap();
+    cx.run_until_parked();
+
+    assert_eq!(*changed_worktrees.lock(), vec![worktree_id]);
+    let worktree_environment = environment
+        .update(cx, |environment, cx| {
+            environment.get_worktree_environment(worktree.clone(), cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(
+        worktree_environment.get("RUST_LOG").map(String::as_str),
+        Some("trace")
+    );
+}
+
+#[gpui::test]
+async fn test_git_settings_environment(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({
+            ".zed": {
+                "settings.json": r#"{
+                    "git": {
+                        "binary_path": "/opt/git/bin/git",
+                        "env": { "GIT_SSH_COMMAND": "ssh -i key" },
+                        "config": { "user.name": "Someone", "core.autocrlf": "false" }
+                    }
+                }"#
+            },
+            "a.rs": "",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs.clone(), [path!("/dir").as_ref()], cx).await;
+    cx.executor().run_until_parked();
+    let environment = project.read_with(cx, |project, _| project.environment().clone());
+
+    let git_settings = environment.read_with(cx, |environment, cx| {
+        environment.git_settings(Path::new(path!("/dir")), cx)
+    });
+    assert_eq!(
+        git_settings.binary_path.as_deref(),
+        Some(Path::new("/opt/git/bin/git"))
+    );
+    let command_environment = git_settings.command_environment();
+    let expected = [
+        ("GIT_SSH_COMMAND", "ssh -i key"),
+        ("GIT_CONFIG_COUNT", "2"),
+        ("GIT_CONFIG_KEY_0", "core.autocrlf"),
+        ("GIT_CONFIG_VALUE_0", "false"),
+        ("GIT_CONFIG_KEY_1", "user.name"),
+        ("GIT_CONFIG_VALUE_1", "Someone"),
+    ];
+    assert_eq!(command_environment.len(), expected.len());
+    for (key, value) in expected {
+        assert_eq!(
+            command_environment.get(key).map(String::as_str),
+            Some(value),
+            "{key}"
+        );
+    }
+
+    let global_settings = environment.read_with(cx, |environment, cx| {
+        environment.git_settings(Path::new(path!("/elsewhere")), cx)
+    });
+    assert_eq!(global_settings.binary_path, None);
+    assert!(global_settings.command_environment().is_empty());
+}
+
+#[gpui::test]
+async fn test_managing_project_specific_settings(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+    TaskStore::init(None);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({
+            ".zed": {
+                "settings.json": r#"{ "tab_size": 8 }"#,
+                "tasks.json": r#"[{
+                    "label": "cargo check all",
+                    "command": "cargo",
+                    "args": ["check", "--all"]
+                },]"#,
+            },
+            "a": {
+                "a.rs": "fn a() {\n    A\n}"
+            },
+            "b": {
                 ".zed": {
                     "settings.json": r#"{ "tab_size": 2 }"#,
                     "tasks.json": r#"[{
@@ -602,6 +1144,90 @@ async fn test_fallback_to_single_worktree_tasks(cx: &mut gpui::TestAppContext) {
     );
 }
 
+#[gpui::test]
+async fn test_language_task_settings_in_task_context(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+    TaskStore::init(None);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({
+            ".env": "FROM_ENV_FILE=1\nOVERRIDDEN=first",
+            ".env.local": "OVERRIDDEN=second",
+            "crates": {
+                "a": {
+                    "Cargo.toml": "",
+                    "src": {
+                        "lib.rs": "fn a() {}"
+                    }
+                }
+            },
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs.clone(), [path!("/dir").as_ref()], cx).await;
+    cx.update(|cx| {
+        SettingsStore::update_global(cx, |store, cx| {
+            store.update_user_settings(cx, |settings| {
+                let tasks = settings
+                    .project
+                    .all_languages
+                    .defaults
+                    .tasks
+                    .get_or_insert_default();
+                tasks.env_files = Some(vec![".env".to_string(), ".env.local".to_string()]);
+                tasks.cwd_strategy = Some(settings::TaskCwdStrategy::PackageRoot);
+                tasks.shell = Some(settings::Shell::Program("nu".to_string()));
+            });
+        });
+    });
+
+    let buffer = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer(path!("/dir/crates/a/src/lib.rs"), cx)
+        })
+        .await
+        .unwrap();
+    let task_context = project
+        .update(cx, |project, cx| {
+            let location = Location {
+                buffer: buffer.clone(),
+                range: Anchor::MIN..Anchor::MIN,
+            };
+            project.task_store().update(cx, |task_store, cx| {
+                task_store.task_context_for_location(task::TaskVariables::default(), location, cx)
+            })
+        })
+        .await
+        .unwrap();
+
+    assert_eq!(
+        task_context.cwd,
+        Some(PathBuf::from(path!("/dir/crates/a")))
+    );
+    assert_eq!(
+        task_context.shell,
+        Some(task::Shell::Program("nu".to_string()))
+    );
+    assert_eq!(
+        task_context
+            .project_env
+            .get("FROM_ENV_FILE")
+            .map(String::as_str),
+        Some("1")
+    );
+    assert_eq!(
+        task_context
+            .project_env
+            .get("OVERRIDDEN")
+            .map(String::as_str),
+        Some("second"),
+        "later env files should take precedence"
+    );
+}
+
 #[gpui::test]
 async fn test_running_multiple_instances_of_a_single_server_in_one_worktree(
     cx: &mut gpui::TestAppContext,
@@ -3864,7 +4490,7 @@ async fn test_rename_file_to_new_directory(cx: &mut gpui::TestAppContext) {
     assert_eq!(
         worktree
             .update(cx, |worktree, cx| {
-                worktree.load_file(rel_path("dir1/dir2/dir3/test.txt"), cx)
+                worktree.load_file(rel_path("dir1/dir2/dir3/test.txt"), Encoding::default(), cx)
             })
             .await
             .unwrap()
@@ -3911,7 +4537,7 @@ async fn test_rename_file_to_new_directory(cx: &mut gpui::TestAppContext) {
     assert_eq!(
         worktree
             .update(cx, |worktree, cx| {
-                worktree.load_file(rel_path("dir1/dir2/test.txt"), cx)
+                worktree.load_file(rel_path("dir1/dir2/test.txt"), Encoding::default(), cx)
             })
             .await
             .unwrap()
@@ -4245,6 +4871,69 @@ async fn test_save_as(cx: &mut gpui::TestAppContext) {
     assert_eq!(opened_buffer, buffer);
 }
 
+#[gpui::test]
+async fn test_save_new_file_with_default_encoding_and_line_ending(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+    cx.update(|cx| {
+        SettingsStore::update_global(cx, |store, cx| {
+            store.update_user_settings(cx, |settings| {
+                let defaults = &mut settings.project.all_languages.defaults;
+                defaults.default_encoding = Some(settings::FileEncoding::Utf16Le);
+                defaults.default_line_ending = Some(settings::DefaultLineEnding::Crlf);
+            });
+        });
+    });
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(path!("/dir"), json!({ "existing.txt": "one\n" }))
+        .await;
+    let project = Project::test(fs.clone(), [path!("/dir").as_ref()], cx).await;
+
+    // New files are saved with the defaults.
+    let buffer = project.update(cx, |project, cx| {
+        project.create_local_buffer("a\nb", None, false, cx)
+    });
+    project
+        .update(cx, |project, cx| {
+            let worktree_id = project.worktrees(cx).next().unwrap().read(cx).id();
+            let path = ProjectPath {
+                worktree_id,
+                path: rel_path("new.txt").into(),
+            };
+            project.save_buffer_as(buffer.clone(), path, cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(
+        fs.load_bytes(Path::new(path!("/dir/new.txt")))
+            .await
+            .unwrap(),
+        [0xff, 0xfe, b'a', 0, b'\r', 0, b'\n', 0, b'b', 0]
+    );
+    buffer.read_with(cx, |buffer, _| {
+        assert_eq!(buffer.line_ending(), LineEnding::Windows)
+    });
+
+    // Existing files keep their own encoding and line ending.
+    let buffer = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer(path!("/dir/existing.txt"), cx)
+        })
+        .await
+        .unwrap();
+    buffer.update(cx, |buffer, cx| buffer.edit([(0..0, "zero\n")], None, cx));
+    project
+        .update(cx, |project, cx| project.save_buffer(buffer.clone(), cx))
+        .await
+        .unwrap();
+    assert_eq!(
+        fs.load(Path::new(path!("/dir/existing.txt")))
+            .await
+            .unwrap(),
+        "zero\none\n"
+    );
+}
+
 #[gpui::test(retries = 5)]
 async fn test_rescan_and_remote_updates(cx: &mut gpui::TestAppContext) {
     use worktree::WorktreeModelHandle as _;
@@ -7571,18 +8260,15 @@ async fn test_staging_hunks(cx: &mut gpui::TestAppContext) {
     });
 }
 
-#[gpui::test(seeds(340, 472))]
-async fn test_staging_hunks_with_delayed_fs_event(cx: &mut gpui::TestAppContext) {
-    use DiffHunkSecondaryStatus::*;
+#[gpui::test]
+async fn test_staging_hunks_by_range(cx: &mut gpui::TestAppContext) {
     init_test(cx);
 
     let committed_contents = r#"
-        zero
         one
         two
         three
         four
-        five
     "#
     .unindent();
     let file_contents = r#"
@@ -7590,376 +8276,2042 @@ async fn test_staging_hunks_with_delayed_fs_event(cx: &mut gpui::TestAppContext)
         TWO
         three
         FOUR
-        five
     "#
     .unindent();
 
     let fs = FakeFs::new(cx.background_executor.clone());
     fs.insert_tree(
-        "/dir",
+        path!("/dir"),
         json!({
             ".git": {},
             "file.txt": file_contents.clone()
         }),
     )
     .await;
-
-    fs.set_head_for_repo(
-        "/dir/.git".as_ref(),
-        &[("file.txt", committed_contents.clone())],
-        "deadbeef",
-    );
-    fs.set_index_for_repo(
-        "/dir/.git".as_ref(),
+    fs.set_head_and_index_for_repo(
+        path!("/dir/.git").as_ref(),
         &[("file.txt", committed_contents.clone())],
     );
 
-    let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
-
+    let project = Project::test(fs.clone(), [path!("/dir").as_ref()], cx).await;
     let buffer = project
         .update(cx, |project, cx| {
-            project.open_local_buffer("/dir/file.txt", cx)
+            project.open_local_buffer(path!("/dir/file.txt"), cx)
         })
         .await
         .unwrap();
+    cx.run_until_parked();
     let snapshot = buffer.read_with(cx, |buffer, _| buffer.snapshot());
-    let uncommitted_diff = project
-        .update(cx, |project, cx| {
-            project.open_uncommitted_diff(buffer.clone(), cx)
+    let repository = project.read_with(cx, |project, cx| {
+        project.repositories(cx).values().next().unwrap().clone()
+    });
+    let index_text = || {
+        fs.with_git_state(path!("/dir/.git").as_ref(), false, |state| {
+            state.index_contents.get(&repo_path("file.txt")).cloned()
+        })
+        .unwrap()
+    };
+
+    // Only the hunk intersecting the range is staged, even if the range intersects it twice.
+    let second_line =
+        snapshot.anchor_before(Point::new(1, 0))..snapshot.anchor_after(Point::new(1, 3));
+    repository
+        .update(cx, |repository, cx| {
+            repository.stage_hunks(
+                repo_path("file.txt"),
+                vec![second_line.clone(), second_line.clone()],
+                cx,
+            )
         })
         .await
         .unwrap();
+    cx.run_until_parked();
+    assert_eq!(index_text().as_deref(), Some("one\nTWO\nthree\nfour\n"));
 
-    // The hunks are initially unstaged.
-    uncommitted_diff.read_with(cx, |diff, cx| {
-        assert_hunks(
-            diff.hunks(&snapshot, cx),
-            &snapshot,
-            &diff.base_text_string().unwrap(),
-            &[
-                (
-                    0..0,
-                    "zero\n",
-                    "",
-                    DiffHunkStatus::deleted(HasSecondaryHunk),
-                ),
-                (
-                    1..2,
-                    "two\n",
-                    "TWO\n",
-                    DiffHunkStatus::modified(HasSecondaryHunk),
-                ),
-                (
-                    3..4,
-                    "four\n",
-                    "FOUR\n",
-                    DiffHunkStatus::modified(HasSecondaryHunk),
-                ),
-            ],
-        );
-    });
+    let whole_file =
+        snapshot.anchor_before(Point::zero())..snapshot.anchor_after(snapshot.max_point());
+    repository
+        .update(cx, |repository, cx| {
+            repository.stage_hunks(repo_path("file.txt"), vec![whole_file.clone()], cx)
+        })
+        .await
+        .unwrap();
+    cx.run_until_parked();
+    assert_eq!(index_text().as_deref(), Some(file_contents.as_str()));
 
-    // Pause IO events
-    fs.pause_events();
+    repository
+        .update(cx, |repository, cx| {
+            repository.unstage_hunks(repo_path("file.txt"), vec![second_line], cx)
+        })
+        .await
+        .unwrap();
+    cx.run_until_parked();
+    assert_eq!(index_text().as_deref(), Some("one\ntwo\nthree\nFOUR\n"));
+}
 
-    // Stage the first hunk.
-    uncommitted_diff.update(cx, |diff, cx| {
+#[gpui::test]
+async fn test_staging_entries_skips_ignored_paths(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.background_executor.clone());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({
+            ".git": {},
+            ".gitignore": "*.log\n",
+            "a.txt": "a",
+            "debug.log": "log",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs.clone(), [path!("/dir").as_ref()], cx).await;
+    cx.run_until_parked();
+    let repository = project.read_with(cx, |project, cx| {
+        project.repositories(cx).values().next().unwrap().clone()
+    });
+
+    let report = repository
+        .update(cx, |repository, cx| {
+            repository.stage_entries(vec![repo_path("a.txt"), repo_path("debug.log")], cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(
+        report,
+        StagingReport {
+            succeeded: vec![repo_path("a.txt")],
+            skipped_ignored: vec![repo_path("debug.log")],
+            failed: Vec::new(),
+        }
+    );
+    report.ensure_succeeded().unwrap();
+
+    let index_paths = fs
+        .with_git_state(path!("/dir/.git").as_ref(), false, |state| {
+            state.index_contents.keys().cloned().collect::<Vec<_>>()
+        })
+        .unwrap();
+    assert_eq!(index_paths, [repo_path("a.txt")]);
+}
+
+#[gpui::test]
+async fn test_repository_blame(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.background_executor.clone());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({
+            ".git": {},
+            "file.txt": "one\ntwo\nthree\n",
+        }),
+    )
+    .await;
+    let sha = git::Oid::from_bytes(&[1; 20]).unwrap();
+    fs.set_blame_for_repo(
+        path!("/dir/.git").as_ref(),
+        vec![(
+            repo_path("file.txt"),
+            git::blame::Blame {
+                entries: vec![git::blame::BlameEntry {
+                    sha,
+                    range: 1..3,
+                    author: Some("Alice".to_string()),
+                    author_time: Some(1_700_000_000),
+                    summary: Some("Add lines".to_string()),
+                    ..Default::default()
+                }],
+                ..Default::default()
+            },
+        )],
+    );
+
+    let project = Project::test(fs.clone(), [path!("/dir").as_ref()], cx).await;
+    cx.run_until_parked();
+    let repository = project.read_with(cx, |project, cx| {
+        project.repositories(cx).values().next().unwrap().clone()
+    });
+
+    let lines = repository
+        .update(cx, |repository, cx| {
+            repository.blame(repo_path("file.txt"), 0..2, cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(
+        lines,
+        [git::blame::LineBlame {
+            row: 1,
+            sha,
+            author: Some("Alice".to_string()),
+            author_mail: None,
+            author_time: Some(1_700_000_000),
+            author_tz: None,
+            summary: Some("Add lines".to_string()),
+        }]
+    );
+}
+
+#[gpui::test]
+async fn test_repository_log(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.background_executor.clone());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({
+            ".git": {},
+            "a.txt": "a",
+            "b.txt": "b",
+        }),
+    )
+    .await;
+    let commit = |sha: &str, changed_path: &str| fs::FakeCommit {
+        details: git::repository::CommitDetails {
+            sha: sha.to_string().into(),
+            message: format!("Change {changed_path}").into(),
+            ..Default::default()
+        },
+        changed_paths: vec![repo_path(changed_path)],
+    };
+    fs.set_commits_for_repo(
+        path!("/dir/.git").as_ref(),
+        vec![
+            commit("3333", "a.txt"),
+            commit("2222", "b.txt"),
+            commit("1111", "a.txt"),
+        ],
+    );
+
+    let project = Project::test(fs.clone(), [path!("/dir").as_ref()], cx).await;
+    cx.run_until_parked();
+    let repository = project.read_with(cx, |project, cx| {
+        project.repositories(cx).values().next().unwrap().clone()
+    });
+    let mut log = |options: git::repository::LogOptions| {
+        let commits = repository.update(cx, |repository, _| repository.log(options));
+        async move {
+            commits
+                .await
+                .unwrap()
+                .unwrap()
+                .into_iter()
+                .map(|commit| commit.sha.to_string())
+                .collect::<Vec<_>>()
+        }
+    };
+
+    // Older commits are loaded page by page.
+    assert_eq!(
+        log(git::repository::LogOptions {
+            limit: 2,
+            ..Default::default()
+        })
+        .await,
+        ["3333", "2222"]
+    );
+    assert_eq!(
+        log(git::repository::LogOptions {
+            limit: 2,
+            skip: 2,
+            ..Default::default()
+        })
+        .await,
+        ["1111"]
+    );
+    assert_eq!(
+        log(git::repository::LogOptions {
+            limit: 10,
+            path: Some(repo_path("a.txt")),
+            ..Default::default()
+        })
+        .await,
+        ["3333", "1111"]
+    );
+
+    let history = repository
+        .update(cx, |repository, _| {
+            repository.file_history(repo_path("b.txt"), true)
+        })
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        history
+            .iter()
+            .map(|entry| (entry.commit.sha.as_ref(), entry.path.as_unix_str()))
+            .collect::<Vec<_>>(),
+        [("2222", "b.txt")]
+    );
+}
+
+#[gpui::test]
+async fn test_repository_tags(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.background_executor.clone());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({
+            ".git": {},
+            "a.txt": "a",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs.clone(), [path!("/dir").as_ref()], cx).await;
+    cx.run_until_parked();
+    let repository = project.read_with(cx, |project, cx| {
+        project.repositories(cx).values().next().unwrap().clone()
+    });
+
+    repository
+        .update(cx, |repository, _| {
+            repository.create_tag("v1".into(), "abc".into(), None)
+        })
+        .await
+        .unwrap()
+        .unwrap();
+    repository
+        .update(cx, |repository, _| {
+            repository.create_tag("v2".into(), "abc".into(), Some("Release 2".into()))
+        })
+        .await
+        .unwrap()
+        .unwrap();
+    let tags = repository
+        .update(cx, |repository, _| repository.tags())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        tags,
+        [
+            git::repository::Tag {
+                name: "v2".into(),
+                sha: "abc".into(),
+                message: Some("Release 2".into()),
+            },
+            git::repository::Tag {
+                name: "v1".into(),
+                sha: "abc".into(),
+                message: None,
+            },
+        ]
+    );
+
+    repository
+        .update(cx, |repository, _| repository.delete_tag("v2".into()))
+        .await
+        .unwrap()
+        .unwrap();
+    let tags = repository
+        .update(cx, |repository, _| repository.tags())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        tags.iter().map(|tag| tag.name.as_ref()).collect::<Vec<_>>(),
+        ["v1"]
+    );
+}
+
+#[gpui::test]
+async fn test_paths_discarded_by_hard_reset(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.background_executor.clone());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({
+            ".git": {},
+            "modified.txt": "modified",
+            "added.txt": "added",
+            "unchanged.txt": "unchanged",
+            "untracked.txt": "untracked",
+            "overwritten.txt": "untracked",
+        }),
+    )
+    .await;
+    fs.set_head_for_repo(
+        path!("/dir/.git").as_ref(),
+        &[
+            ("modified.txt", "original".into()),
+            ("unchanged.txt", "unchanged".into()),
+            ("overwritten.txt", "committed".into()),
+        ],
+        "deadbeef",
+    );
+    fs.set_index_for_repo(
+        path!("/dir/.git").as_ref(),
+        &[
+            ("modified.txt", "original".into()),
+            ("added.txt", "added".into()),
+            ("unchanged.txt", "unchanged".into()),
+        ],
+    );
+
+    let project = Project::test(fs.clone(), [path!("/dir").as_ref()], cx).await;
+    project
+        .update(cx, |project, cx| project.git_scans_complete(cx))
+        .await;
+    cx.run_until_parked();
+    let repository = project.read_with(cx, |project, cx| {
+        project.repositories(cx).values().next().unwrap().clone()
+    });
+
+    let mut paths = repository
+        .update(cx, |repository, _| {
+            repository.paths_discarded_by_hard_reset("HEAD".into())
+        })
+        .await
+        .unwrap()
+        .unwrap();
+    paths.sort();
+    assert_eq!(
+        paths,
+        [
+            repo_path("added.txt"),
+            repo_path("modified.txt"),
+            repo_path("overwritten.txt")
+        ]
+    );
+}
+
+#[gpui::test(seeds(340, 472))]
+async fn test_staging_hunks_with_delayed_fs_event(cx: &mut gpui::TestAppContext) {
+    use DiffHunkSecondaryStatus::*;
+    init_test(cx);
+
+    let committed_contents = r#"
+        zero
+        one
+        two
+        three
+        four
+        five
+    "#
+    .unindent();
+    let file_contents = r#"
+        one
+        TWO
+        three
+        FOUR
+        five
+    "#
+    .unindent();
+
+    let fs = FakeFs::new(cx.background_executor.clone());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            ".git": {},
+            "file.txt": file_contents.clone()
+        }),
+    )
+    .await;
+
+    fs.set_head_for_repo(
+        "/dir/.git".as_ref(),
+        &[("file.txt", committed_contents.clone())],
+        "deadbeef",
+    );
+    fs.set_index_for_repo(
+        "/dir/.git".as_ref(),
+        &[("file.txt", committed_contents.clone())],
+    );
+
+    let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+
+    let buffer = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer("/dir/file.txt", cx)
+        })
+        .await
+        .unwrap();
+    let snapshot = buffer.read_with(cx, |buffer, _| buffer.snapshot());
+    let uncommitted_diff = project
+        .update(cx, |project, cx| {
+            project.open_uncommitted_diff(buffer.clone(), cx)
+        })
+        .await
+        .unwrap();
+
+    // The hunks are initially unstaged.
+    uncommitted_diff.read_with(cx, |diff, cx| {
+        assert_hunks(
+            diff.hunks(&snapshot, cx),
+            &snapshot,
+            &diff.base_text_string().unwrap(),
+            &[
+                (
+                    0..0,
+                    "zero\n",
+                    "",
+                    DiffHunkStatus::deleted(HasSecondaryHunk),
+                ),
+                (
+                    1..2,
+                    "two\n",
+                    "TWO\n",
+                    DiffHunkStatus::modified(HasSecondaryHunk),
+                ),
+                (
+                    3..4,
+                    "four\n",
+                    "FOUR\n",
+                    DiffHunkStatus::modified(HasSecondaryHunk),
+                ),
+            ],
+        );
+    });
+
+    // Pause IO events
+    fs.pause_events();
+
+    // Stage the first hunk.
+    uncommitted_diff.update(cx, |diff, cx| {
         let hunk = diff.hunks(&snapshot, cx).next().unwrap();
         diff.stage_or_unstage_hunks(true, &[hunk], &snapshot, true, cx);
         assert_hunks(
-            diff.hunks(&snapshot, cx),
+            diff.hunks(&snapshot, cx),
+            &snapshot,
+            &diff.base_text_string().unwrap(),
+            &[
+                (
+                    0..0,
+                    "zero\n",
+                    "",
+                    DiffHunkStatus::deleted(SecondaryHunkRemovalPending),
+                ),
+                (
+                    1..2,
+                    "two\n",
+                    "TWO\n",
+                    DiffHunkStatus::modified(HasSecondaryHunk),
+                ),
+                (
+                    3..4,
+                    "four\n",
+                    "FOUR\n",
+                    DiffHunkStatus::modified(HasSecondaryHunk),
+                ),
+            ],
+        );
+    });
+
+    // Stage the second hunk *before* receiving the FS event for the first hunk.
+    cx.run_until_parked();
+    uncommitted_diff.update(cx, |diff, cx| {
+        let hunk = diff.hunks(&snapshot, cx).nth(1).unwrap();
+        diff.stage_or_unstage_hunks(true, &[hunk], &snapshot, true, cx);
+        assert_hunks(
+            diff.hunks(&snapshot, cx),
+            &snapshot,
+            &diff.base_text_string().unwrap(),
+            &[
+                (
+                    0..0,
+                    "zero\n",
+                    "",
+                    DiffHunkStatus::deleted(SecondaryHunkRemovalPending),
+                ),
+                (
+                    1..2,
+                    "two\n",
+                    "TWO\n",
+                    DiffHunkStatus::modified(SecondaryHunkRemovalPending),
+                ),
+                (
+                    3..4,
+                    "four\n",
+                    "FOUR\n",
+                    DiffHunkStatus::modified(HasSecondaryHunk),
+                ),
+            ],
+        );
+    });
+
+    // Process the FS event for staging the first hunk (second event is still pending).
+    fs.flush_events(1);
+    cx.run_until_parked();
+
+    // Stage the third hunk before receiving the second FS event.
+    uncommitted_diff.update(cx, |diff, cx| {
+        let hunk = diff.hunks(&snapshot, cx).nth(2).unwrap();
+        diff.stage_or_unstage_hunks(true, &[hunk], &snapshot, true, cx);
+    });
+
+    // Wait for all remaining IO.
+    cx.run_until_parked();
+    fs.flush_events(fs.buffered_event_count());
+
+    // Now all hunks are staged.
+    cx.run_until_parked();
+    uncommitted_diff.update(cx, |diff, cx| {
+        assert_hunks(
+            diff.hunks(&snapshot, cx),
+            &snapshot,
+            &diff.base_text_string().unwrap(),
+            &[
+                (0..0, "zero\n", "", DiffHunkStatus::deleted(NoSecondaryHunk)),
+                (
+                    1..2,
+                    "two\n",
+                    "TWO\n",
+                    DiffHunkStatus::modified(NoSecondaryHunk),
+                ),
+                (
+                    3..4,
+                    "four\n",
+                    "FOUR\n",
+                    DiffHunkStatus::modified(NoSecondaryHunk),
+                ),
+            ],
+        );
+    });
+}
+
+#[gpui::test(iterations = 25)]
+async fn test_staging_random_hunks(
+    mut rng: StdRng,
+    executor: BackgroundExecutor,
+    cx: &mut gpui::TestAppContext,
+) {
+    let operations = env::var("OPERATIONS")
+        .map(|i| i.parse().expect("invalid `OPERATIONS` variable"))
+        .unwrap_or(20);
+
+    // Try to induce races between diff recalculation and index writes.
+    if rng.random_bool(0.5) {
+        executor.deprioritize(*CALCULATE_DIFF_TASK);
+    }
+
+    use DiffHunkSecondaryStatus::*;
+    init_test(cx);
+
+    let committed_text = (0..30).map(|i| format!("line {i}\n")).collect::<String>();
+    let index_text = committed_text.clone();
+    let buffer_text = (0..30)
+        .map(|i| match i % 5 {
+            0 => format!("line {i} (modified)\n"),
+            _ => format!("line {i}\n"),
+        })
+        .collect::<String>();
+
+    let fs = FakeFs::new(cx.background_executor.clone());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({
+            ".git": {},
+            "file.txt": buffer_text.clone()
+        }),
+    )
+    .await;
+    fs.set_head_for_repo(
+        path!("/dir/.git").as_ref(),
+        &[("file.txt", committed_text.clone())],
+        "deadbeef",
+    );
+    fs.set_index_for_repo(
+        path!("/dir/.git").as_ref(),
+        &[("file.txt", index_text.clone())],
+    );
+    let repo = fs
+        .open_repo(path!("/dir/.git").as_ref(), Some("git".as_ref()))
+        .unwrap();
+
+    let project = Project::test(fs.clone(), [path!("/dir").as_ref()], cx).await;
+    let buffer = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer(path!("/dir/file.txt"), cx)
+        })
+        .await
+        .unwrap();
+    let snapshot = buffer.read_with(cx, |buffer, _| buffer.snapshot());
+    let uncommitted_diff = project
+        .update(cx, |project, cx| {
+            project.open_uncommitted_diff(buffer.clone(), cx)
+        })
+        .await
+        .unwrap();
+
+    let mut hunks =
+        uncommitted_diff.update(cx, |diff, cx| diff.hunks(&snapshot, cx).collect::<Vec<_>>());
+    assert_eq!(hunks.len(), 6);
+
+    for _i in 0..operations {
+        let hunk_ix = rng.random_range(0..hunks.len());
+        let hunk = &mut hunks[hunk_ix];
+        let row = hunk.range.start.row;
+
+        if hunk.status().has_secondary_hunk() {
+            log::info!("staging hunk at {row}");
+            uncommitted_diff.update(cx, |diff, cx| {
+                diff.stage_or_unstage_hunks(true, std::slice::from_ref(hunk), &snapshot, true, cx);
+            });
+            hunk.secondary_status = SecondaryHunkRemovalPending;
+        } else {
+            log::info!("unstaging hunk at {row}");
+            uncommitted_diff.update(cx, |diff, cx| {
+                diff.stage_or_unstage_hunks(false, std::slice::from_ref(hunk), &snapshot, true, cx);
+            });
+            hunk.secondary_status = SecondaryHunkAdditionPending;
+        }
+
+        for _ in 0..rng.random_range(0..10) {
+            log::info!("yielding");
+            cx.executor().simulate_random_delay().await;
+        }
+    }
+
+    cx.executor().run_until_parked();
+
+    for hunk in &mut hunks {
+        if hunk.secondary_status == SecondaryHunkRemovalPending {
+            hunk.secondary_status = NoSecondaryHunk;
+        } else if hunk.secondary_status == SecondaryHunkAdditionPending {
+            hunk.secondary_status = HasSecondaryHunk;
+        }
+    }
+
+    log::info!(
+        "index text:\n{}",
+        repo.load_index_text(rel_path("file.txt").into())
+            .await
+            .unwrap()
+    );
+
+    uncommitted_diff.update(cx, |diff, cx| {
+        let expected_hunks = hunks
+            .iter()
+            .map(|hunk| (hunk.range.start.row, hunk.secondary_status))
+            .collect::<Vec<_>>();
+        let actual_hunks = diff
+            .hunks(&snapshot, cx)
+            .map(|hunk| (hunk.range.start.row, hunk.secondary_status))
+            .collect::<Vec<_>>();
+        assert_eq!(actual_hunks, expected_hunks);
+    });
+}
+
+#[gpui::test]
+async fn test_single_file_diffs(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let committed_contents = r#"
+        fn main() {
+            println!("hello from HEAD");
+        }
+    "#
+    .unindent();
+    let file_contents = r#"
+        fn main() {
+            println!("hello from the working copy");
+        }
+    "#
+    .unindent();
+
+    let fs = FakeFs::new(cx.background_executor.clone());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            ".git": {},
+           "src": {
+               "main.rs": file_contents,
+           }
+        }),
+    )
+    .await;
+
+    fs.set_head_for_repo(
+        Path::new("/dir/.git"),
+        &[("src/main.rs", committed_contents.clone())],
+        "deadbeef",
+    );
+    fs.set_index_for_repo(
+        Path::new("/dir/.git"),
+        &[("src/main.rs", committed_contents.clone())],
+    );
+
+    let project = Project::test(fs.clone(), ["/dir/src/main.rs".as_ref()], cx).await;
+
+    let buffer = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer("/dir/src/main.rs", cx)
+        })
+        .await
+        .unwrap();
+    let uncommitted_diff = project
+        .update(cx, |project, cx| {
+            project.open_uncommitted_diff(buffer.clone(), cx)
+        })
+        .await
+        .unwrap();
+
+    cx.run_until_parked();
+    uncommitted_diff.update(cx, |uncommitted_diff, cx| {
+        let snapshot = buffer.read(cx).snapshot();
+        assert_hunks(
+            uncommitted_diff.hunks(&snapshot, cx),
             &snapshot,
-            &diff.base_text_string().unwrap(),
-            &[
-                (
-                    0..0,
-                    "zero\n",
-                    "",
-                    DiffHunkStatus::deleted(SecondaryHunkRemovalPending),
-                ),
-                (
-                    1..2,
-                    "two\n",
-                    "TWO\n",
-                    DiffHunkStatus::modified(HasSecondaryHunk),
-                ),
-                (
-                    3..4,
-                    "four\n",
-                    "FOUR\n",
-                    DiffHunkStatus::modified(HasSecondaryHunk),
-                ),
-            ],
+            &uncommitted_diff.base_text_string().unwrap(),
+            &[(
+                1..2,
+                "    println!(\"hello from HEAD\");\n",
+                "    println!(\"hello from the working copy\");\n",
+                DiffHunkStatus {
+                    kind: DiffHunkStatusKind::Modified,
+                    secondary: DiffHunkSecondaryStatus::HasSecondaryHunk,
+                },
+            )],
+        );
+    });
+}
+
+#[gpui::test]
+async fn test_interactive_rebase(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.background_executor.clone());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({
+            ".git": {},
+            "a.txt": "a",
+        }),
+    )
+    .await;
+    fs.set_branch_name(Path::new(path!("/dir/.git")), Some("feature"));
+
+    let project = Project::test(fs.clone(), [path!("/dir").as_ref()], cx).await;
+    cx.run_until_parked();
+    let repository = project.read_with(cx, |project, cx| {
+        project.repositories(cx).values().next().unwrap().clone()
+    });
+    let repository_updates = Arc::new(Mutex::new(Vec::new()));
+    project.update(cx, |project, cx| {
+        let repository_updates = repository_updates.clone();
+        cx.subscribe(project.git_store(), move |_, _, event, _| {
+            if let GitStoreEvent::RepositoryUpdated(_, event, _) = event {
+                repository_updates.lock().push(event.clone());
+            }
+        })
+        .detach();
+    });
+
+    repository
+        .update(cx, |repository, cx| {
+            repository.rebase_start("main".to_string(), true, cx)
+        })
+        .await
+        .unwrap();
+    cx.run_until_parked();
+    repository.read_with(cx, |repository, _| {
+        let rebase = repository.snapshot().rebase.unwrap();
+        assert_eq!(rebase.head_name.as_deref(), Some("feature"));
+        assert_eq!(rebase.onto.as_ref(), "main");
+        assert!(rebase.interactive);
+    });
+    assert!(
+        repository_updates
+            .lock()
+            .contains(&RepositoryEvent::RebaseStateChanged)
+    );
+
+    // Only one rebase can be in progress at a time.
+    assert!(
+        repository
+            .update(cx, |repository, cx| {
+                repository.rebase_start("main".to_string(), true, cx)
+            })
+            .await
+            .is_err()
+    );
+
+    repository_updates.lock().clear();
+    repository
+        .update(cx, |repository, cx| repository.rebase_abort(cx))
+        .await
+        .unwrap();
+    cx.run_until_parked();
+    repository.read_with(cx, |repository, _| {
+        assert_eq!(repository.snapshot().rebase, None);
+    });
+    assert!(
+        repository_updates
+            .lock()
+            .contains(&RepositoryEvent::RebaseStateChanged)
+    );
+}
+
+#[gpui::test]
+async fn test_delete_branch_and_set_upstream(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.background_executor.clone());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({
+            ".git": {},
+            "a.txt": "a",
+        }),
+    )
+    .await;
+    fs.insert_branches(Path::new(path!("/dir/.git")), &["main", "feature"]);
+
+    let project = Project::test(fs.clone(), [path!("/dir").as_ref()], cx).await;
+    cx.run_until_parked();
+    let repository = project.read_with(cx, |project, cx| {
+        project.repositories(cx).values().next().unwrap().clone()
+    });
+
+    repository
+        .update(cx, |repository, _| {
+            repository.set_upstream("main".to_string(), "origin/main".to_string())
+        })
+        .await
+        .unwrap()
+        .unwrap();
+    cx.run_until_parked();
+    repository.read_with(cx, |repository, _| {
+        let branch = repository.snapshot().branch.unwrap();
+        assert_eq!(branch.name(), "main");
+        assert_eq!(
+            branch.upstream.unwrap().ref_name.as_ref(),
+            "refs/remotes/origin/main"
+        );
+    });
+
+    // The current branch can't be deleted.
+    assert!(
+        repository
+            .update(cx, |repository, _| {
+                repository.delete_branch("main".to_string(), false)
+            })
+            .await
+            .unwrap()
+            .is_err()
+    );
+    repository
+        .update(cx, |repository, _| {
+            repository.delete_branch("feature".to_string(), false)
+        })
+        .await
+        .unwrap()
+        .unwrap();
+    let branches = repository
+        .update(cx, |repository, _| repository.branches())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        branches
+            .iter()
+            .map(|branch| branch.name().to_string())
+            .collect::<Vec<_>>(),
+        ["main"]
+    );
+}
+
+#[gpui::test]
+async fn test_prefetching_branches(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+    cx.update(|cx| {
+        SettingsStore::update_global(cx, |store, cx| {
+            store.update_user_settings(cx, |settings| {
+                settings.git.get_or_insert_default().prefetch_branches = Some(true);
+            });
+        });
+    });
+
+    let fs = FakeFs::new(cx.background_executor.clone());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({
+            ".git": {},
+            "a.txt": "a",
+        }),
+    )
+    .await;
+    fs.insert_branches(path!("/dir/.git").as_ref(), &["main"]);
+    fs.with_git_state(path!("/dir/.git").as_ref(), false, |state| {
+        state
+            .upstreams
+            .insert("main".to_string(), "origin/main".to_string());
+    })
+    .unwrap();
+
+    let project = Project::test(fs.clone(), [path!("/dir").as_ref()], cx).await;
+    cx.run_until_parked();
+    let repository = project.read_with(cx, |project, cx| {
+        project.repositories(cx).values().next().unwrap().clone()
+    });
+    let branch_names = |cx: &mut gpui::TestAppContext| {
+        // The branches are already listed, so they're available without waiting for the worker.
+        let mut branches = repository.update(cx, |repository, _| repository.branches());
+        let mut names = branches
+            .try_recv()
+            .unwrap()
+            .expect("branches weren't cached")
+            .unwrap()
+            .iter()
+            .map(|branch| branch.name().to_string())
+            .collect::<Vec<_>>();
+        names.sort();
+        names
+    };
+    assert_eq!(branch_names(cx), ["main"]);
+    let remotes = repository
+        .update(cx, |repository, _| repository.get_remotes(None))
+        .try_recv()
+        .unwrap()
+        .expect("remotes weren't cached")
+        .unwrap();
+    assert_eq!(
+        remotes
+            .iter()
+            .map(|remote| remote.name.to_string())
+            .collect::<Vec<_>>(),
+        ["origin"]
+    );
+
+    // Creating a branch changes the git state, so they're listed again.
+    fs.insert_branches(path!("/dir/.git").as_ref(), &["feature"]);
+    cx.run_until_parked();
+    assert_eq!(branch_names(cx), ["feature", "main"]);
+}
+
+#[gpui::test]
+async fn test_head_state(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.background_executor.clone());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({
+            ".git": {},
+            "a.txt": "a",
+        }),
+    )
+    .await;
+    let dot_git = Path::new(path!("/dir/.git"));
+    // A new repository's branch has no commits.
+    fs.with_git_state(dot_git, false, |state| {
+        state.refs.remove("HEAD");
+    })
+    .unwrap();
+    fs.set_branch_name(dot_git, Some("main"));
+
+    let project = Project::test(fs.clone(), [path!("/dir").as_ref()], cx).await;
+    cx.run_until_parked();
+    let repository = project.read_with(cx, |project, cx| {
+        project.repositories(cx).values().next().unwrap().clone()
+    });
+    let head_state = |cx: &mut gpui::TestAppContext| {
+        repository.read_with(cx, |repository, _| repository.head_state.clone())
+    };
+    assert_eq!(head_state(cx), Some(HeadState::Unborn));
+
+    fs.with_git_state(dot_git, true, |state| {
+        state.refs.insert("HEAD".into(), "abc".into());
+    })
+    .unwrap();
+    cx.run_until_parked();
+    assert_eq!(head_state(cx), Some(HeadState::Branch("main".into())));
+
+    fs.set_branch_name(dot_git, None::<String>);
+    cx.run_until_parked();
+    assert_eq!(head_state(cx), Some(HeadState::Detached("abc".into())));
+}
+
+#[gpui::test]
+async fn test_ignore_paths(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.background_executor.clone());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({
+            ".git": {},
+            ".gitignore": "target",
+            "a.txt": "a",
+            "b.txt": "b",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs.clone(), [path!("/dir").as_ref()], cx).await;
+    cx.run_until_parked();
+    let repository = project.read_with(cx, |project, cx| {
+        project.repositories(cx).values().next().unwrap().clone()
+    });
+    repository.read_with(cx, |repository, _| {
+        assert!(repository.status_for_path(&repo_path("a.txt")).is_some());
+    });
+
+    repository
+        .update(cx, |repository, cx| {
+            repository.ignore_paths(vec![repo_path("a.txt")], IgnoreFile::Gitignore, cx)
+        })
+        .await
+        .unwrap()
+        .unwrap();
+    cx.run_until_parked();
+
+    assert_eq!(
+        fs.load(Path::new(path!("/dir/.gitignore"))).await.unwrap(),
+        "target\n/a.txt\n"
+    );
+    for (path, expected_ignored) in [("a.txt", true), ("b.txt", false)] {
+        let is_ignored = repository
+            .update(cx, |repository, _| repository.is_ignored(repo_path(path)))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(is_ignored, expected_ignored, "{path}");
+    }
+    repository.read_with(cx, |repository, _| {
+        assert!(repository.status_for_path(&repo_path("a.txt")).is_none());
+        assert!(repository.status_for_path(&repo_path("b.txt")).is_some());
+    });
+}
+
+#[gpui::test]
+async fn test_clean(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.background_executor.clone());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({
+            ".git": {},
+            ".gitignore": "*.log",
+            "tracked.txt": "tracked",
+            "untracked.txt": "untracked",
+            "ignored.log": "ignored",
+            "new_dir": {
+                "file.txt": "untracked",
+            },
+        }),
+    )
+    .await;
+    fs.set_index_for_repo(
+        Path::new(path!("/dir/.git")),
+        &[
+            (".gitignore", "*.log".into()),
+            ("tracked.txt", "tracked".into()),
+        ],
+    );
+
+    let project = Project::test(fs.clone(), [path!("/dir").as_ref()], cx).await;
+    cx.run_until_parked();
+    let repository = project.read_with(cx, |project, cx| {
+        project.repositories(cx).values().next().unwrap().clone()
+    });
+
+    let dry_run = CleanOptions {
+        dry_run: true,
+        directories: true,
+        ..CleanOptions::default()
+    };
+    let paths_to_clean = repository
+        .update(cx, |repository, cx| {
+            repository.clean(Vec::new(), dry_run, cx)
+        })
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        paths_to_clean,
+        [repo_path("new_dir"), repo_path("untracked.txt")]
+    );
+    assert!(fs.is_file(Path::new(path!("/dir/untracked.txt"))).await);
+
+    let cleaned_paths = repository
+        .update(cx, |repository, cx| {
+            repository.clean(
+                Vec::new(),
+                CleanOptions {
+                    dry_run: false,
+                    ..dry_run
+                },
+                cx,
+            )
+        })
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(cleaned_paths, paths_to_clean);
+    cx.run_until_parked();
+    assert!(!fs.is_file(Path::new(path!("/dir/untracked.txt"))).await);
+    assert!(!fs.is_dir(Path::new(path!("/dir/new_dir"))).await);
+    assert!(fs.is_file(Path::new(path!("/dir/ignored.log"))).await);
+    repository.read_with(cx, |repository, _| {
+        assert!(
+            repository
+                .status_for_path(&repo_path("untracked.txt"))
+                .is_none()
+        );
+        assert!(
+            repository
+                .status_for_path(&repo_path("new_dir/file.txt"))
+                .is_none()
         );
     });
+}
+
+#[gpui::test]
+async fn test_commit_buffer_initial_message(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.background_executor.clone());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({
+            "plain": {
+                ".git": {},
+                "a.txt": "a",
+            },
+            "merging": {
+                ".git": {},
+                "b.txt": "b",
+            },
+        }),
+    )
+    .await;
+    for repository in ["plain", "merging"] {
+        fs.with_git_state(
+            &Path::new(path!("/dir")).join(repository).join(".git"),
+            true,
+            |state| {
+                state.commit_template = Some("Template\n".into());
+            },
+        )
+        .unwrap();
+    }
+    // A merge's message takes precedence over the template.
+    fs.with_git_state(Path::new(path!("/dir/merging/.git")), true, |state| {
+        state.merge_message = Some("Merge branch 'feature'\n".into());
+    })
+    .unwrap();
+
+    let project = Project::test(fs.clone(), [path!("/dir").as_ref()], cx).await;
+    cx.run_until_parked();
+    let buffer_store = project.read_with(cx, |project, _| project.buffer_store().clone());
+    for (repository_name, expected_message) in [
+        ("plain", "Template\n"),
+        ("merging", "Merge branch 'feature'\n"),
+    ] {
+        let repository = project.read_with(cx, |project, cx| {
+            project
+                .repositories(cx)
+                .values()
+                .find(|repository| {
+                    repository
+                        .read(cx)
+                        .work_directory_abs_path
+                        .ends_with(repository_name)
+                })
+                .unwrap()
+                .clone()
+        });
+        let commit_buffer = repository
+            .update(cx, |repository, cx| {
+                repository.open_commit_buffer(None, buffer_store.clone(), cx)
+            })
+            .await
+            .unwrap();
+        commit_buffer.read_with(cx, |buffer, _| {
+            assert_eq!(buffer.text(), expected_message, "{repository_name}")
+        });
+    }
+}
+
+#[gpui::test]
+async fn test_statuses_changed_event(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.background_executor.clone());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({
+            ".git": {},
+            "a.txt": "a",
+            "b.txt": "b",
+        }),
+    )
+    .await;
+    fs.set_head_and_index_for_repo(
+        Path::new(path!("/dir/.git")),
+        &[("a.txt", "a".into()), ("b.txt", "b".into())],
+    );
+
+    let project = Project::test(fs.clone(), [path!("/dir").as_ref()], cx).await;
+    cx.run_until_parked();
+    let repository_id = project.read_with(cx, |project, cx| {
+        *project.repositories(cx).keys().next().unwrap()
+    });
+
+    let changed_paths = Arc::new(Mutex::new(Vec::new()));
+    project.update(cx, |project, cx| {
+        let changed_paths = changed_paths.clone();
+        cx.subscribe(project.git_store(), move |_, _, event, _| {
+            if let GitStoreEvent::RepositoryUpdated(
+                id,
+                RepositoryEvent::StatusesChanged(paths),
+                _,
+            ) = event
+            {
+                changed_paths.lock().push((*id, paths.to_vec()));
+            }
+        })
+        .detach();
+    });
+
+    fs.save(
+        path!("/dir/a.txt").as_ref(),
+        &"modified".into(),
+        Default::default(),
+    )
+    .await
+    .unwrap();
+    cx.run_until_parked();
+    assert_eq!(
+        *changed_paths.lock(),
+        [(repository_id, vec![repo_path("a.txt")])]
+    );
+}
+
+#[gpui::test]
+async fn test_preferred_active_repository(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.background_executor.clone());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({
+            "first": {
+                ".git": {},
+                "a.txt": "a",
+            },
+            "second": {
+                "b.txt": "b",
+            },
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs.clone(), [path!("/dir").as_ref()], cx).await;
+    cx.run_until_parked();
+    let active_work_directory = |cx: &mut gpui::TestAppContext| {
+        project.read_with(cx, |project, cx| {
+            project
+                .active_repository(cx)
+                .map(|repository| repository.read(cx).work_directory_abs_path.clone())
+        })
+    };
+    assert_eq!(
+        active_work_directory(cx).as_deref(),
+        Some(Path::new(path!("/dir/first")))
+    );
+
+    let active_repository_changes = Arc::new(Mutex::new(0));
+    project.update(cx, |project, cx| {
+        let active_repository_changes = active_repository_changes.clone();
+        cx.subscribe(project.git_store(), move |_, _, event, _| {
+            if let GitStoreEvent::ActiveRepositoryChanged(_) = event {
+                *active_repository_changes.lock() += 1;
+            }
+        })
+        .detach();
+    });
+
+    // Preferring a repository that isn't open yet makes it active once it's opened.
+    project.update(cx, |project, cx| {
+        project.git_store().update(cx, |git_store, cx| {
+            git_store.set_preferred_active_repository(Path::new(path!("/dir/second")).into(), cx)
+        })
+    });
+    cx.run_until_parked();
+    assert_eq!(*active_repository_changes.lock(), 0);
+
+    fs.create_dir(Path::new(path!("/dir/second/.git")))
+        .await
+        .unwrap();
+    cx.run_until_parked();
+    assert_eq!(
+        active_work_directory(cx).as_deref(),
+        Some(Path::new(path!("/dir/second")))
+    );
+    assert_eq!(*active_repository_changes.lock(), 1);
+
+    // Rescans don't change the active repository.
+    fs.save(
+        path!("/dir/first/a.txt").as_ref(),
+        &"changed".into(),
+        Default::default(),
+    )
+    .await
+    .unwrap();
+    cx.run_until_parked();
+    assert_eq!(*active_repository_changes.lock(), 1);
+}
+
+#[gpui::test]
+async fn test_status_refresh_all(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.background_executor.clone());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({
+            ".git": {},
+            "a.txt": "a",
+            "nested": {
+                ".git": {},
+                "b.txt": "b",
+            },
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs.clone(), [path!("/dir").as_ref()], cx).await;
+    cx.run_until_parked();
+    let mut repository_ids = project.read_with(cx, |project, cx| {
+        project.repositories(cx).keys().copied().collect::<Vec<_>>()
+    });
+    repository_ids.sort();
+    assert_eq!(repository_ids.len(), 2);
 
-    // Stage the second hunk *before* receiving the FS event for the first hunk.
+    let batch_reports = Arc::new(Mutex::new(Vec::new()));
+    project.update(cx, |project, cx| {
+        let batch_reports = batch_reports.clone();
+        cx.subscribe(project.git_store(), move |_, _, event, _| {
+            if let GitStoreEvent::BatchOperationCompleted(report) = event {
+                batch_reports.lock().push(report.clone());
+            }
+        })
+        .detach();
+    });
+
+    let report = project
+        .update(cx, |project, cx| {
+            project
+                .git_store()
+                .update(cx, |git_store, cx| git_store.status_refresh_all(cx))
+        })
+        .await;
     cx.run_until_parked();
-    uncommitted_diff.update(cx, |diff, cx| {
-        let hunk = diff.hunks(&snapshot, cx).nth(1).unwrap();
-        diff.stage_or_unstage_hunks(true, &[hunk], &snapshot, true, cx);
-        assert_hunks(
-            diff.hunks(&snapshot, cx),
-            &snapshot,
-            &diff.base_text_string().unwrap(),
-            &[
-                (
-                    0..0,
-                    "zero\n",
-                    "",
-                    DiffHunkStatus::deleted(SecondaryHunkRemovalPending),
-                ),
-                (
-                    1..2,
-                    "two\n",
-                    "TWO\n",
-                    DiffHunkStatus::modified(SecondaryHunkRemovalPending),
-                ),
-                (
-                    3..4,
-                    "four\n",
-                    "FOUR\n",
-                    DiffHunkStatus::modified(HasSecondaryHunk),
-                ),
-            ],
-        );
+    assert_eq!(report.operation, BatchOperation::StatusRefresh);
+    assert_eq!(report.succeeded, repository_ids);
+    assert!(report.failed.is_empty());
+
+    let batch_reports = batch_reports.lock();
+    assert_eq!(batch_reports.len(), 1);
+    assert_eq!(batch_reports[0].succeeded, repository_ids);
+}
+
+#[gpui::test]
+async fn test_refresh_statuses(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.background_executor.clone());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({
+            ".git": {},
+            "a.txt": "a",
+            "b.txt": "b",
+        }),
+    )
+    .await;
+    fs.set_head_and_index_for_repo(
+        path!("/dir/.git").as_ref(),
+        &[("a.txt", "a".into()), ("b.txt", "b".into())],
+    );
+
+    let project = Project::test(fs.clone(), [path!("/dir").as_ref()], cx).await;
+    cx.run_until_parked();
+    let repository = project.read_with(cx, |project, cx| {
+        project.repositories(cx).values().next().unwrap().clone()
     });
+    let statuses = |cx: &mut gpui::TestAppContext| {
+        repository.read_with(cx, |repository, _| {
+            repository
+                .cached_status()
+                .map(|entry| (entry.repo_path, entry.status))
+                .collect::<Vec<_>>()
+        })
+    };
+    assert!(statuses(cx).is_empty());
 
-    // Process the FS event for staging the first hunk (second event is still pending).
-    fs.flush_events(1);
+    // Change the index without a file system event, as an external tool might.
+    let set_index_text = |text: &str| {
+        fs.with_git_state(path!("/dir/.git").as_ref(), false, |state| {
+            state.index_contents.insert(repo_path("b.txt"), text.into());
+        })
+        .unwrap();
+    };
+    set_index_text("B");
     cx.run_until_parked();
+    assert!(statuses(cx).is_empty());
 
-    // Stage the third hunk before receiving the second FS event.
-    uncommitted_diff.update(cx, |diff, cx| {
-        let hunk = diff.hunks(&snapshot, cx).nth(2).unwrap();
-        diff.stage_or_unstage_hunks(true, &[hunk], &snapshot, true, cx);
+    repository
+        .update(cx, |repository, cx| repository.refresh_statuses(cx))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        statuses(cx),
+        [(
+            repo_path("b.txt"),
+            FileStatus::Tracked(TrackedStatus {
+                index_status: StatusCode::Modified,
+                worktree_status: StatusCode::Modified,
+            })
+        )]
+    );
+
+    // Paths whose status went away are removed too.
+    set_index_text("b");
+    let report = project
+        .update(cx, |project, cx| {
+            project
+                .git_store()
+                .update(cx, |git_store, cx| git_store.refresh_statuses(cx))
+        })
+        .await;
+    assert_eq!(report.operation, BatchOperation::RefreshStatuses);
+    assert!(report.failed.is_empty());
+    assert!(statuses(cx).is_empty());
+}
+
+#[gpui::test]
+async fn test_job_queue_status(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.background_executor.clone());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({
+            ".git": {},
+            "a.txt": "a",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs.clone(), [path!("/dir").as_ref()], cx).await;
+    cx.run_until_parked();
+    let git_store = project.read_with(cx, |project, _| project.git_store().clone());
+    let repository = project.read_with(cx, |project, cx| {
+        project.repositories(cx).values().next().unwrap().clone()
     });
+    let repository_id = repository.read_with(cx, |repository, _| repository.id);
+    assert!(
+        git_store
+            .read_with(cx, |git_store, cx| git_store.job_queue_status(cx))
+            .is_empty()
+    );
 
-    // Wait for all remaining IO.
+    let slow_jobs = Arc::new(Mutex::new(Vec::new()));
+    cx.update(|cx| {
+        let slow_jobs = slow_jobs.clone();
+        cx.subscribe(&git_store, move |_, event, _| {
+            if let GitStoreEvent::SlowJob(status) = event {
+                slow_jobs.lock().push(status.clone());
+            }
+        })
+        .detach();
+    });
+
+    let (slow_job, next_job) = repository.update(cx, |repository, _| {
+        (
+            repository.send_job(Some("slow job".into()), |_, cx| async move {
+                cx.background_executor()
+                    .timer(Duration::from_secs(15))
+                    .await;
+            }),
+            repository.send_job(None, |_, _| async {}),
+        )
+    });
     cx.run_until_parked();
-    fs.flush_events(fs.buffered_event_count());
+    let statuses = git_store.read_with(cx, |git_store, cx| git_store.job_queue_status(cx));
+    assert_eq!(
+        statuses
+            .iter()
+            .map(|status| (
+                status.repository_id,
+                status.message.clone(),
+                status.running_for.is_some()
+            ))
+            .collect::<Vec<_>>(),
+        [
+            (repository_id, Some(SharedString::from("slow job")), true),
+            (repository_id, None, false),
+        ]
+    );
+    assert!(slow_jobs.lock().is_empty());
 
-    // Now all hunks are staged.
+    // The default threshold is 10 seconds.
+    cx.executor().advance_clock(Duration::from_secs(10));
     cx.run_until_parked();
-    uncommitted_diff.update(cx, |diff, cx| {
-        assert_hunks(
-            diff.hunks(&snapshot, cx),
-            &snapshot,
-            &diff.base_text_string().unwrap(),
-            &[
-                (0..0, "zero\n", "", DiffHunkStatus::deleted(NoSecondaryHunk)),
-                (
-                    1..2,
-                    "two\n",
-                    "TWO\n",
-                    DiffHunkStatus::modified(NoSecondaryHunk),
-                ),
-                (
-                    3..4,
-                    "four\n",
-                    "FOUR\n",
-                    DiffHunkStatus::modified(NoSecondaryHunk),
-                ),
-            ],
+    let slow_jobs = slow_jobs.lock().clone();
+    assert_eq!(slow_jobs.len(), 1);
+    assert_eq!(slow_jobs[0].message, Some("slow job".into()));
+
+    cx.executor().advance_clock(Duration::from_secs(5));
+    slow_job.await.unwrap();
+    next_job.await.unwrap();
+    cx.run_until_parked();
+    assert!(
+        git_store
+            .read_with(cx, |git_store, cx| git_store.job_queue_status(cx))
+            .is_empty()
+    );
+}
+
+#[gpui::test]
+async fn test_load_blob(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.background_executor.clone());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({
+            ".git": {},
+            "a.txt": "modified",
+        }),
+    )
+    .await;
+    fs.set_head_and_index_for_repo(
+        Path::new(path!("/dir/.git")),
+        &[("a.txt", "committed".into())],
+    );
+
+    let project = Project::test(fs.clone(), [path!("/dir").as_ref()], cx).await;
+    cx.run_until_parked();
+    let repository = project.read_with(cx, |project, cx| {
+        project.repositories(cx).values().next().unwrap().clone()
+    });
+
+    let content = repository
+        .update(cx, |repository, _| {
+            repository.load_blob("HEAD".into(), repo_path("a.txt"))
+        })
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(content, "committed");
+    assert!(
+        repository
+            .update(cx, |repository, _| {
+                repository.load_blob("HEAD".into(), repo_path("b.txt"))
+            })
+            .await
+            .unwrap()
+            .is_err()
+    );
+}
+
+#[gpui::test]
+async fn test_lfs_statuses_and_locks(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let pointer = "version https://git-lfs.github.com/spec/v1\noid sha256:4d7a214614ab2935c943f9e0ff69d22eadbb8f32b1258daaa5e2ca24d17e2393\nsize 12345\n";
+    let fs = FakeFs::new(cx.background_executor.clone());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({
+            ".git": {},
+            "pointer.bin": pointer,
+            "downloaded.bin": "binary content",
+            "text.txt": "new text",
+        }),
+    )
+    .await;
+    fs.set_index_for_repo(
+        Path::new(path!("/dir/.git")),
+        &[
+            ("pointer.bin", "binary content".into()),
+            ("downloaded.bin", "old binary content".into()),
+            ("text.txt", "old text".into()),
+        ],
+    );
+    fs.with_git_state(Path::new(path!("/dir/.git")), true, |state| {
+        state.lfs_paths = [repo_path("pointer.bin"), repo_path("downloaded.bin")]
+            .into_iter()
+            .collect();
+    })
+    .unwrap();
+
+    let project = Project::test(fs.clone(), [path!("/dir").as_ref()], cx).await;
+    cx.run_until_parked();
+    let repository = project.read_with(cx, |project, cx| {
+        project.repositories(cx).values().next().unwrap().clone()
+    });
+    repository.read_with(cx, |repository, _| {
+        let snapshot = repository.snapshot();
+        assert_eq!(
+            snapshot.lfs_status_for_path(&repo_path("pointer.bin")),
+            Some(&LfsFileStatus {
+                is_pointer: true,
+                lock: None,
+            })
+        );
+        assert_eq!(
+            snapshot.lfs_status_for_path(&repo_path("downloaded.bin")),
+            Some(&LfsFileStatus {
+                is_pointer: false,
+                lock: None,
+            })
         );
+        assert_eq!(snapshot.lfs_status_for_path(&repo_path("text.txt")), None);
+    });
+
+    let askpass = AskPassDelegate::new(&mut cx.to_async(), |_, _, _| {});
+    repository
+        .update(cx, |repository, cx| {
+            repository.lfs_lock(repo_path("downloaded.bin"), askpass, cx)
+        })
+        .await
+        .unwrap()
+        .unwrap();
+    repository.read_with(cx, |repository, _| {
+        let status = repository
+            .snapshot()
+            .lfs_status_for_path(&repo_path("downloaded.bin"))
+            .cloned()
+            .unwrap();
+        assert!(status.lock.is_some());
+    });
+
+    let askpass = AskPassDelegate::new(&mut cx.to_async(), |_, _, _| {});
+    repository
+        .update(cx, |repository, cx| {
+            repository.lfs_unlock(repo_path("downloaded.bin"), false, askpass, cx)
+        })
+        .await
+        .unwrap()
+        .unwrap();
+    repository.read_with(cx, |repository, _| {
+        let status = repository
+            .snapshot()
+            .lfs_status_for_path(&repo_path("downloaded.bin"))
+            .cloned()
+            .unwrap();
+        assert_eq!(status.lock, None);
     });
 }
 
-#[gpui::test(iterations = 25)]
-async fn test_staging_random_hunks(
-    mut rng: StdRng,
-    executor: BackgroundExecutor,
-    cx: &mut gpui::TestAppContext,
-) {
-    let operations = env::var("OPERATIONS")
-        .map(|i| i.parse().expect("invalid `OPERATIONS` variable"))
-        .unwrap_or(20);
+/// A host that accepts every request and never responds to any of them.
+#[derive(Default)]
+struct StalledHost {
+    requests: Mutex<Vec<&'static str>>,
+    message_handlers: Mutex<ProtoMessageHandlerSet>,
+}
 
-    // Try to induce races between diff recalculation and index writes.
-    if rng.random_bool(0.5) {
-        executor.deprioritize(*CALCULATE_DIFF_TASK);
+impl ProtoClient for StalledHost {
+    fn request(
+        &self,
+        _envelope: proto::Envelope,
+        request_type: &'static str,
+    ) -> BoxFuture<'static, Result<proto::Envelope>> {
+        self.requests.lock().push(request_type);
+        future::pending().boxed()
     }
 
-    use DiffHunkSecondaryStatus::*;
+    fn send(&self, _envelope: proto::Envelope, _message_type: &'static str) -> Result<()> {
+        Ok(())
+    }
+
+    fn send_response(&self, _envelope: proto::Envelope, _message_type: &'static str) -> Result<()> {
+        Ok(())
+    }
+
+    fn message_handler_set(&self) -> &Mutex<ProtoMessageHandlerSet> {
+        &self.message_handlers
+    }
+
+    fn is_via_collab(&self) -> bool {
+        true
+    }
+}
+
+#[gpui::test]
+async fn test_remote_command_timeout(cx: &mut gpui::TestAppContext) {
     init_test(cx);
+    cx.update(|cx| {
+        SettingsStore::update_global(cx, |store, cx| {
+            store.update_user_settings(cx, |settings| {
+                let git = settings.git.get_or_insert_default();
+                git.remote_request_timeout_ms = Some(1000);
+                git.remote_request_retries = Some(2);
+            });
+        });
+    });
 
-    let committed_text = (0..30).map(|i| format!("line {i}\n")).collect::<String>();
-    let index_text = committed_text.clone();
-    let buffer_text = (0..30)
-        .map(|i| match i % 5 {
-            0 => format!("line {i} (modified)\n"),
-            _ => format!("line {i}\n"),
+    let host = Arc::new(StalledHost::default());
+    let repository = cx.new(|cx| {
+        Repository::remote(
+            RepositoryId(1),
+            Path::new(path!("/project")).into(),
+            PathStyle::local(),
+            client::ProjectId(1),
+            AnyProtoClient::new(host.clone()),
+            WeakEntity::new_invalid(),
+            cx,
+        )
+    });
+
+    // Pushes may have taken effect on the host, so they aren't retried.
+    let askpass = AskPassDelegate::new(&mut cx.to_async(), |_, _, _| {});
+    let push = repository.update(cx, |repository, cx| {
+        repository.push("main".into(), "origin".into(), None, askpass, None, cx)
+    });
+    for _ in 0..10 {
+        cx.executor().advance_clock(Duration::from_secs(1));
+        cx.run_until_parked();
+    }
+    let error = push.await.unwrap().unwrap_err();
+    assert_eq!(
+        error.downcast_ref::<RemoteCommandTimedOut>(),
+        Some(&RemoteCommandTimedOut {
+            command: "push",
+            timeout: Duration::from_secs(1),
+            attempts: 1,
         })
-        .collect::<String>();
+    );
+    assert_eq!(mem::take(&mut *host.requests.lock()), ["Push"]);
+
+    // Fetches are sent again until the retries run out.
+    let askpass = AskPassDelegate::new(&mut cx.to_async(), |_, _, _| {});
+    let fetch = repository.update(cx, |repository, cx| {
+        repository.fetch(FetchOptions::All, askpass, None, cx)
+    });
+    for _ in 0..10 {
+        cx.executor().advance_clock(Duration::from_secs(1));
+        cx.run_until_parked();
+    }
+    let error = fetch.await.unwrap().unwrap_err();
+    assert_eq!(
+        error.downcast_ref::<RemoteCommandTimedOut>(),
+        Some(&RemoteCommandTimedOut {
+            command: "fetch",
+            timeout: Duration::from_secs(1),
+            attempts: 3,
+        })
+    );
+    assert_eq!(
+        mem::take(&mut *host.requests.lock()),
+        ["Fetch", "Fetch", "Fetch"]
+    );
+}
+
+#[gpui::test]
+async fn test_merge_conflicts(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
 
     let fs = FakeFs::new(cx.background_executor.clone());
     fs.insert_tree(
         path!("/dir"),
         json!({
             ".git": {},
-            "file.txt": buffer_text.clone()
+            "a.txt": "a",
+            "b.txt": "b",
         }),
     )
     .await;
-    fs.set_head_for_repo(
-        path!("/dir/.git").as_ref(),
-        &[("file.txt", committed_text.clone())],
-        "deadbeef",
-    );
-    fs.set_index_for_repo(
-        path!("/dir/.git").as_ref(),
-        &[("file.txt", index_text.clone())],
-    );
-    let repo = fs
-        .open_repo(path!("/dir/.git").as_ref(), Some("git".as_ref()))
-        .unwrap();
+    fs.insert_branches(Path::new(path!("/dir/.git")), &["main", "feature"]);
 
     let project = Project::test(fs.clone(), [path!("/dir").as_ref()], cx).await;
-    let buffer = project
-        .update(cx, |project, cx| {
-            project.open_local_buffer(path!("/dir/file.txt"), cx)
+    cx.run_until_parked();
+    let repository = project.read_with(cx, |project, cx| {
+        project.repositories(cx).values().next().unwrap().clone()
+    });
+    let repository_updates = Arc::new(Mutex::new(Vec::new()));
+    project.update(cx, |project, cx| {
+        let repository_updates = repository_updates.clone();
+        cx.subscribe(project.git_store(), move |_, _, event, _| {
+            if let GitStoreEvent::RepositoryUpdated(_, event, _) = event {
+                repository_updates.lock().push(event.clone());
+            }
         })
-        .await
-        .unwrap();
-    let snapshot = buffer.read_with(cx, |buffer, _| buffer.snapshot());
-    let uncommitted_diff = project
-        .update(cx, |project, cx| {
-            project.open_uncommitted_diff(buffer.clone(), cx)
+        .detach();
+    });
+
+    // The fake repository stops the merge on conflicts in its unmerged paths.
+    fs.set_unmerged_paths_for_repo(
+        Path::new(path!("/dir/.git")),
+        &[(
+            repo_path("a.txt"),
+            UnmergedStatus {
+                first_head: UnmergedStatusCode::Updated,
+                second_head: UnmergedStatusCode::Updated,
+            },
+        )],
+    );
+    cx.run_until_parked();
+    repository_updates.lock().clear();
+
+    repository
+        .update(cx, |repository, cx| {
+            repository.merge("feature".to_string(), MergeOptions::default(), cx)
         })
         .await
+        .unwrap()
         .unwrap();
+    // The snapshot is up to date as soon as the merge finishes.
+    repository.read_with(cx, |repository, _| {
+        let merge = repository.snapshot().merge;
+        assert_eq!(
+            merge.conflicted_paths.iter().cloned().collect::<Vec<_>>(),
+            [repo_path("a.txt")]
+        );
+        assert_eq!(merge.message.as_deref(), Some("Merge branch 'feature'\n"));
+    });
+    assert!(
+        repository_updates
+            .lock()
+            .contains(&RepositoryEvent::MergeHeadsChanged)
+    );
 
-    let mut hunks =
-        uncommitted_diff.update(cx, |diff, cx| diff.hunks(&snapshot, cx).collect::<Vec<_>>());
-    assert_eq!(hunks.len(), 6);
-
-    for _i in 0..operations {
-        let hunk_ix = rng.random_range(0..hunks.len());
-        let hunk = &mut hunks[hunk_ix];
-        let row = hunk.range.start.row;
-
-        if hunk.status().has_secondary_hunk() {
-            log::info!("staging hunk at {row}");
-            uncommitted_diff.update(cx, |diff, cx| {
-                diff.stage_or_unstage_hunks(true, std::slice::from_ref(hunk), &snapshot, true, cx);
-            });
-            hunk.secondary_status = SecondaryHunkRemovalPending;
-        } else {
-            log::info!("unstaging hunk at {row}");
-            uncommitted_diff.update(cx, |diff, cx| {
-                diff.stage_or_unstage_hunks(false, std::slice::from_ref(hunk), &snapshot, true, cx);
-            });
-            hunk.secondary_status = SecondaryHunkAdditionPending;
-        }
-
-        for _ in 0..rng.random_range(0..10) {
-            log::info!("yielding");
-            cx.executor().simulate_random_delay().await;
-        }
-    }
-
-    cx.executor().run_until_parked();
-
-    for hunk in &mut hunks {
-        if hunk.secondary_status == SecondaryHunkRemovalPending {
-            hunk.secondary_status = NoSecondaryHunk;
-        } else if hunk.secondary_status == SecondaryHunkAdditionPending {
-            hunk.secondary_status = HasSecondaryHunk;
-        }
-    }
-
-    log::info!(
-        "index text:\n{}",
-        repo.load_index_text(rel_path("file.txt").into())
+    // Only one merge can be in progress at a time.
+    assert!(
+        repository
+            .update(cx, |repository, cx| {
+                repository.merge("feature".to_string(), MergeOptions::default(), cx)
+            })
             .await
             .unwrap()
+            .is_err()
     );
 
-    uncommitted_diff.update(cx, |diff, cx| {
-        let expected_hunks = hunks
-            .iter()
-            .map(|hunk| (hunk.range.start.row, hunk.secondary_status))
-            .collect::<Vec<_>>();
-        let actual_hunks = diff
-            .hunks(&snapshot, cx)
-            .map(|hunk| (hunk.range.start.row, hunk.secondary_status))
-            .collect::<Vec<_>>();
-        assert_eq!(actual_hunks, expected_hunks);
+    repository_updates.lock().clear();
+    repository
+        .update(cx, |repository, cx| repository.merge_abort(cx))
+        .await
+        .unwrap()
+        .unwrap();
+    repository.read_with(cx, |repository, _| {
+        let merge = repository.snapshot().merge;
+        assert!(merge.conflicted_paths.is_empty());
+        assert_eq!(merge.message, None);
     });
+    assert!(
+        repository_updates
+            .lock()
+            .contains(&RepositoryEvent::MergeHeadsChanged)
+    );
 }
 
 #[gpui::test]
-async fn test_single_file_diffs(cx: &mut gpui::TestAppContext) {
+async fn test_resolve_conflict(cx: &mut gpui::TestAppContext) {
     init_test(cx);
 
-    let committed_contents = r#"
-        fn main() {
-            println!("hello from HEAD");
-        }
-    "#
-    .unindent();
-    let file_contents = r#"
-        fn main() {
-            println!("hello from the working copy");
-        }
-    "#
-    .unindent();
-
     let fs = FakeFs::new(cx.background_executor.clone());
     fs.insert_tree(
-        "/dir",
+        path!("/dir"),
         json!({
             ".git": {},
-           "src": {
-               "main.rs": file_contents,
-           }
+            "a.txt": "theirs",
+            "b.txt": "ours",
         }),
     )
     .await;
-
-    fs.set_head_for_repo(
-        Path::new("/dir/.git"),
-        &[("src/main.rs", committed_contents.clone())],
-        "deadbeef",
-    );
-    fs.set_index_for_repo(
-        Path::new("/dir/.git"),
-        &[("src/main.rs", committed_contents.clone())],
+    fs.insert_branches(Path::new(path!("/dir/.git")), &["main", "feature"]);
+    fs.set_unmerged_paths_for_repo(
+        Path::new(path!("/dir/.git")),
+        &[
+            (
+                repo_path("a.txt"),
+                UnmergedStatus {
+                    first_head: UnmergedStatusCode::Updated,
+                    second_head: UnmergedStatusCode::Updated,
+                },
+            ),
+            (
+                repo_path("b.txt"),
+                UnmergedStatus {
+                    first_head: UnmergedStatusCode::Updated,
+                    second_head: UnmergedStatusCode::Deleted,
+                },
+            ),
+        ],
     );
 
-    let project = Project::test(fs.clone(), ["/dir/src/main.rs".as_ref()], cx).await;
-
-    let buffer = project
-        .update(cx, |project, cx| {
-            project.open_local_buffer("/dir/src/main.rs", cx)
+    let project = Project::test(fs.clone(), [path!("/dir").as_ref()], cx).await;
+    cx.run_until_parked();
+    let repository = project.read_with(cx, |project, cx| {
+        project.repositories(cx).values().next().unwrap().clone()
+    });
+    repository
+        .update(cx, |repository, cx| {
+            repository.merge("feature".to_string(), MergeOptions::default(), cx)
         })
         .await
+        .unwrap()
         .unwrap();
-    let uncommitted_diff = project
-        .update(cx, |project, cx| {
-            project.open_uncommitted_diff(buffer.clone(), cx)
+
+    repository
+        .update(cx, |repository, cx| {
+            repository.resolve_conflict(repo_path("a.txt"), ConflictSide::Theirs, cx)
         })
         .await
+        .unwrap()
         .unwrap();
+    repository.read_with(cx, |repository, _| {
+        assert_eq!(
+            repository
+                .snapshot()
+                .merge
+                .conflicted_paths
+                .iter()
+                .cloned()
+                .collect::<Vec<_>>(),
+            [repo_path("b.txt")]
+        );
+        assert!(
+            !repository
+                .status_for_path(&repo_path("a.txt"))
+                .unwrap()
+                .status
+                .is_conflicted()
+        );
+    });
+    assert_eq!(
+        fs.with_git_state(Path::new(path!("/dir/.git")), false, |state| {
+            state.index_contents.get(&repo_path("a.txt")).cloned()
+        })
+        .unwrap()
+        .as_deref(),
+        Some("theirs")
+    );
 
+    // Taking the side that deleted the file deletes it.
+    repository
+        .update(cx, |repository, cx| {
+            repository.resolve_conflict(repo_path("b.txt"), ConflictSide::Theirs, cx)
+        })
+        .await
+        .unwrap()
+        .unwrap();
     cx.run_until_parked();
-    uncommitted_diff.update(cx, |uncommitted_diff, cx| {
-        let snapshot = buffer.read(cx).snapshot();
-        assert_hunks(
-            uncommitted_diff.hunks(&snapshot, cx),
-            &snapshot,
-            &uncommitted_diff.base_text_string().unwrap(),
-            &[(
-                1..2,
-                "    println!(\"hello from HEAD\");\n",
-                "    println!(\"hello from the working copy\");\n",
-                DiffHunkStatus {
-                    kind: DiffHunkStatusKind::Modified,
-                    secondary: DiffHunkSecondaryStatus::HasSecondaryHunk,
-                },
-            )],
-        );
+    assert!(!fs.is_file(Path::new(path!("/dir/b.txt"))).await);
+    repository.read_with(cx, |repository, _| {
+        assert!(repository.snapshot().merge.conflicted_paths.is_empty());
     });
+
+    assert!(
+        repository
+            .update(cx, |repository, cx| {
+                repository.resolve_conflict(repo_path("a.txt"), ConflictSide::Ours, cx)
+            })
+            .await
+            .unwrap()
+            .is_err()
+    );
 }
 
 #[gpui::test]
@@ -8905,7 +11257,11 @@ async fn test_ignored_dirs_events(cx: &mut gpui::TestAppContext) {
     let tree = project.read_with(cx, |project, cx| project.worktrees(cx).next().unwrap());
     tree.flush_fs_events(cx).await;
     tree.update(cx, |tree, cx| {
-        tree.load_file(rel_path("project/target/debug/important_text.txt"), cx)
+        tree.load_file(
+            rel_path("project/target/debug/important_text.txt"),
+            Encoding::default(),
+            cx,
+        )
     })
     .await
     .unwrap();
@@ -9070,7 +11426,7 @@ async fn test_odd_events_for_ignored_dirs(
 
     let tree = project.read_with(cx, |project, cx| project.worktrees(cx).next().unwrap());
     tree.update(cx, |tree, cx| {
-        tree.load_file(rel_path("target/debug/foo.txt"), cx)
+        tree.load_file(rel_path("target/debug/foo.txt"), Encoding::default(), cx)
     })
     .await
     .unwrap();
@@ -9,13 +9,14 @@ use fs::Fs;
 use gpui::{App, AsyncApp, Context, Entity, EventEmitter, Task, WeakEntity};
 use language::{
     ContextLocation, ContextProvider as _, LanguageToolchainStore, Location,
+    language_settings::{LanguageTaskSettings, TaskCwdStrategy, language_settings},
     proto::{deserialize_anchor, serialize_anchor},
 };
 use rpc::{AnyProtoClient, TypedEnvelope, proto};
 use settings::{InvalidSettingsError, SettingsLocation};
 use task::{TaskContext, TaskVariables, VariableName};
 use text::{BufferId, OffsetRangeExt};
-use util::ResultExt;
+use util::{ResultExt, rel_path::RelPath};
 
 use crate::{
     BasicContextProvider, Inventory, ProjectEnvironment, buffer_store::BufferStore,
@@ -313,6 +314,21 @@ fn local_task_context_for_location(
         .and_then(|worktree_id| worktree_store.read(cx).worktree_for_id(worktree_id, cx))
         .and_then(|worktree| worktree.read(cx).root_dir());
     let fs = worktree_store.read(cx).fs();
+    let task_settings = language_task_settings(&location, cx);
+    let cwd = language_task_cwd(&worktree_store, &location, task_settings.cwd_strategy, cx)
+        .or_else(|| worktree_abs_path.as_ref().map(|path| path.to_path_buf()));
+    let env_files = task_settings
+        .env_files
+        .iter()
+        .filter_map(|env_file| {
+            let env_file = PathBuf::from(env_file);
+            if env_file.is_absolute() {
+                Some(env_file)
+            } else {
+                Some(worktree_abs_path.as_ref()?.join(env_file))
+            }
+        })
+        .collect::<Vec<_>>();
 
     cx.spawn(async move |cx| {
         let project_env = environment
@@ -326,7 +342,7 @@ fn local_task_context_for_location(
             .update(|cx| {
                 combine_task_variables(
                     captured_variables,
-                    fs,
+                    fs.clone(),
                     worktree_store.clone(),
                     location,
                     project_env.clone(),
@@ -341,10 +357,16 @@ fn local_task_context_for_location(
         // Remove all custom entries starting with _, as they're not intended for use by the end user.
         task_variables.sweep();
 
+        let mut project_env = project_env.unwrap_or_default();
+        if let Some(fs) = fs {
+            project_env.extend(load_env_files(fs.as_ref(), &env_files).await);
+        }
+
         Some(TaskContext {
-            project_env: project_env.unwrap_or_default(),
-            cwd: worktree_abs_path.map(|p| p.to_path_buf()),
+            project_env,
+            cwd,
             task_variables,
+            shell: task_settings.shell,
         })
     })
 }
@@ -382,8 +404,13 @@ fn remote_task_context_for_location(
             .unwrap_or_default();
         remote_context.extend(captured_variables);
 
-        let buffer_id = cx
-            .update(|cx| location.buffer.read(cx).remote_id().to_proto())
+        let (buffer_id, shell) = cx
+            .update(|cx| {
+                (
+                    location.buffer.read(cx).remote_id().to_proto(),
+                    language_task_settings(&location, cx).shell,
+                )
+            })
             .ok()?;
         let context_task = upstream_client.request(proto::TaskContextForLocation {
             project_id,
@@ -414,10 +441,81 @@ fn remote_task_context_for_location(
                 )
                 .collect(),
             project_env: task_context.project_env.into_iter().collect(),
+            shell,
         })
     })
 }
 
+fn language_task_settings(location: &Location, cx: &App) -> LanguageTaskSettings {
+    let buffer = location.buffer.read(cx);
+    language_settings(
+        buffer.language().map(|language| language.name()),
+        buffer.file(),
+        cx,
+    )
+    .tasks
+    .clone()
+}
+
+/// Files whose directory is the root of a package, for [`TaskCwdStrategy::PackageRoot`].
+const PACKAGE_MANIFESTS: &[&str] = &[
+    "Cargo.toml",
+    "package.json",
+    "deno.json",
+    "go.mod",
+    "pyproject.toml",
+    "setup.py",
+    "Gemfile",
+    "composer.json",
+    "mix.exs",
+    "pom.xml",
+    "build.gradle",
+    "build.gradle.kts",
+    "Package.swift",
+];
+
+/// The directory picked by the language's `cwd_strategy` for tasks at the location, or `None`
+/// when tasks should run in the worktree root.
+fn language_task_cwd(
+    worktree_store: &Entity<WorktreeStore>,
+    location: &Location,
+    cwd_strategy: TaskCwdStrategy,
+    cx: &App,
+) -> Option<PathBuf> {
+    let file = location.buffer.read(cx).file()?;
+    let worktree = worktree_store
+        .read(cx)
+        .worktree_for_id(file.worktree_id(cx), cx)?;
+    let worktree = worktree.read(cx);
+    let file_directory = file.path().parent()?;
+    let directory = match cwd_strategy {
+        TaskCwdStrategy::WorktreeRoot => return None,
+        TaskCwdStrategy::FileDirectory => file_directory,
+        TaskCwdStrategy::PackageRoot => file_directory.ancestors().find(|directory| {
+            PACKAGE_MANIFESTS.iter().any(|manifest| {
+                RelPath::unix(manifest).is_ok_and(|manifest| {
+                    worktree.entry_for_path(&directory.join(manifest)).is_some()
+                })
+            })
+        })?,
+    };
+    Some(worktree.absolutize(directory))
+}
+
+/// Reads the variables of dotenv files, with later files taking precedence.
+async fn load_env_files(fs: &dyn Fs, paths: &[PathBuf]) -> HashMap<String, String> {
+    let mut env = HashMap::default();
+    for path in paths {
+        match fs.load(path).await {
+            Ok(content) => env.extend(
+                dotenvy::from_read_iter(content.as_bytes()).filter_map(|entry| entry.log_err()),
+            ),
+            Err(error) => log::warn!("Failed to load task env file {path:?}: {error:#}"),
+        }
+    }
+    env
+}
+
 fn worktree_root(
     worktree_store: &Entity<WorktreeStore>,
     location: &Location,
@@ -5,6 +5,7 @@ use anyhow::{Context as _, Result};
 use client::{ErrorCode, ErrorExt};
 use collections::{BTreeSet, HashMap, hash_map};
 use command_palette_hooks::CommandPaletteFilter;
+use coverage::CoverageStore;
 use db::kvp::KEY_VALUE_STORE;
 use editor::{
     Editor, EditorEvent,
@@ -207,6 +208,7 @@ struct EntryDetails {
     sticky: Option<StickyDetails>,
     filename_text_color: Color,
     diagnostic_severity: Option<DiagnosticSeverity>,
+    coverage_percentage: Option<f32>,
     git_status: GitSummary,
     is_private: bool,
     worktree_id: WorktreeId,
@@ -495,6 +497,10 @@ impl ProjectPanel {
             })
             .detach();
 
+            if let Some(coverage_store) = CoverageStore::global(cx) {
+                cx.observe(&coverage_store, |_, _, cx| cx.notify()).detach();
+            }
+
             cx.subscribe_in(
                 &git_store,
                 window,
@@ -4272,6 +4278,7 @@ impl ProjectPanel {
 
         let filename_text_color = details.filename_text_color;
         let diagnostic_severity = details.diagnostic_severity;
+        let coverage_percentage = details.coverage_percentage;
         let item_colors = get_item_color(is_sticky, cx);
 
         let canonical_path = details
@@ -4659,6 +4666,25 @@ impl ProjectPanel {
                         }
                     })
                     .selectable(false)
+                    .when_some(coverage_percentage, |this, percentage| {
+                        let color = if percentage >= 80. {
+                            Color::Success
+                        } else if percentage >= 50. {
+                            Color::Warning
+                        } else {
+                            Color::Error
+                        };
+                        this.end_slot::<AnyElement>(
+                            div()
+                                .pr_3()
+                                .child(
+                                    Label::new(format!("{percentage:.0}%"))
+                                        .size(LabelSize::XSmall)
+                                        .color(color),
+                                )
+                                .into_any_element(),
+                        )
+                    })
                     .when_some(canonical_path, |this, path| {
                         this.end_slot::<AnyElement>(
                             div()
@@ -5001,6 +5027,12 @@ impl ProjectPanel {
             .get(&(worktree_id, entry.path.clone()))
             .cloned();
 
+        let coverage_percentage = if entry.is_file() {
+            self.coverage_percentage(worktree_id, &entry.path, cx)
+        } else {
+            None
+        };
+
         let filename_text_color =
             entry_git_aware_label_color(git_status, entry.is_ignored, is_marked);
 
@@ -5025,6 +5057,7 @@ impl ProjectPanel {
             sticky,
             filename_text_color,
             diagnostic_severity,
+            coverage_percentage,
             git_status,
             is_private: entry.is_private,
             worktree_id,
@@ -5032,6 +5065,22 @@ impl ProjectPanel {
         }
     }
 
+    fn coverage_percentage(
+        &self,
+        worktree_id: WorktreeId,
+        path: &RelPath,
+        cx: &App,
+    ) -> Option<f32> {
+        let coverage_store = CoverageStore::global(cx)?;
+        let coverage_store = coverage_store.read(cx);
+        if coverage_store.is_empty() {
+            return None;
+        }
+        let worktree = self.project.read(cx).worktree_for_id(worktree_id, cx)?;
+        let abs_path = worktree.read(cx).absolutize(path);
+        coverage_store.coverage_for_path(&abs_path)?.percentage()
+    }
+
     fn dispatch_context(&self, window: &Window, cx: &Context<Self>) -> KeyContext {
         let mut dispatch_context = KeyContext::new_with_defaults();
         dispatch_context.add("ProjectPanel");
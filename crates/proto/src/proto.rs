@@ -95,6 +95,10 @@ messages!(
     (GetDocumentHighlightsResponse, Background),
     (GetDocumentSymbols, Background),
     (GetDocumentSymbolsResponse, Background),
+    (PrepareTypeHierarchy, Background),
+    (GetTypeHierarchySupertypes, Background),
+    (GetTypeHierarchySubtypes, Background),
+    (TypeHierarchyResponse, Background),
     (GetHover, Background),
     (GetHoverResponse, Background),
     (GetNotifications, Foreground),
@@ -260,6 +264,28 @@ messages!(
     (StashPop, Background),
     (StashApply, Background),
     (StashDrop, Background),
+    (GitRebaseStart, Background),
+    (GitRebaseContinue, Background),
+    (GitRebaseAbort, Background),
+    (GitBlame, Background),
+    (GitBlameResponse, Background),
+    (GitLog, Background),
+    (GitLogResponse, Background),
+    (GitFileHistory, Background),
+    (GitFileHistoryResponse, Background),
+    (GitCommitGraph, Background),
+    (GitCommitGraphResponse, Background),
+    (StagingResponse, Background),
+    (GitPermalink, Background),
+    (GitOpenPullRequestUrl, Background),
+    (GitUrlResponse, Background),
+    (GitGetTags, Background),
+    (GitTagsResponse, Background),
+    (GitCreateTag, Background),
+    (GitDeleteTag, Background),
+    (GitPushTag, Background),
+    (GitMerge, Background),
+    (GitMergeAbort, Background),
     (UpdateBuffer, Foreground),
     (UpdateBufferFile, Foreground),
     (UpdateChannelBuffer, Foreground),
@@ -286,6 +312,8 @@ messages!(
     (RemoveRepository, Foreground),
     (UsersResponse, Foreground),
     (GitReset, Background),
+    (GitPathsDiscardedByHardReset, Background),
+    (GitPathsDiscardedByHardResetResponse, Background),
     (GitCheckoutFiles, Background),
     (GitShow, Background),
     (GitCommitDetails, Background),
@@ -301,10 +329,34 @@ messages!(
     (GitCreateBranch, Background),
     (GitChangeBranch, Background),
     (GitRenameBranch, Background),
+    (GitDeleteBranch, Background),
+    (GitSetUpstream, Background),
+    (GitIgnorePaths, Background),
+    (GitIsIgnored, Background),
+    (GitIsIgnoredResponse, Background),
+    (GitClean, Background),
+    (GitCleanResponse, Background),
+    (GitLfsPull, Background),
+    (GitLfsLock, Background),
+    (GitLfsUnlock, Background),
+    (GitResolveConflict, Background),
+    (RemoteProgress, Background),
     (CheckForPushedCommits, Background),
     (CheckForPushedCommitsResponse, Background),
     (GitDiff, Background),
+    (GitDiffFile, Background),
+    (GitLoadBlob, Background),
+    (GitLoadBlobResponse, Background),
+    (GitApplyPatch, Background),
+    (GitFormatPatch, Background),
+    (GitFormatPatchResponse, Background),
+    (GitMaintenance, Background),
+    (GitGc, Background),
+    (GitRepositoryHealth, Background),
+    (GitRepositoryHealthResponse, Background),
     (GitDiffResponse, Background),
+    (GitDiffChunk, Background),
+    (GitDiffChunkResponse, Background),
     (GitInit, Background),
     (GetDebugAdapterBinary, Background),
     (DebugAdapterBinary, Background),
@@ -361,6 +413,9 @@ request_messages!(
     (GetImplementation, GetImplementationResponse),
     (GetDocumentHighlights, GetDocumentHighlightsResponse),
     (GetDocumentSymbols, GetDocumentSymbolsResponse),
+    (PrepareTypeHierarchy, TypeHierarchyResponse),
+    (GetTypeHierarchySupertypes, TypeHierarchyResponse),
+    (GetTypeHierarchySubtypes, TypeHierarchyResponse),
     (GetHover, GetHoverResponse),
     (GetNotifications, GetNotificationsResponse),
     (GetProjectSymbols, GetProjectSymbolsResponse),
@@ -419,7 +474,7 @@ request_messages!(
     (RespondToChannelInvite, Ack),
     (RespondToContactRequest, Ack),
     (SaveBuffer, BufferSaved),
-    (Stage, Ack),
+    (Stage, StagingResponse),
     (FindSearchCandidates, FindSearchCandidatesResponse),
     (SendChannelMessage, SendChannelMessageResponse),
     (SetChannelMemberRole, Ack),
@@ -428,11 +483,26 @@ request_messages!(
     (SynchronizeBuffers, SynchronizeBuffersResponse),
     (TaskContextForLocation, TaskContext),
     (Test, Test),
-    (Unstage, Ack),
+    (Unstage, StagingResponse),
     (Stash, Ack),
     (StashPop, Ack),
     (StashApply, Ack),
     (StashDrop, Ack),
+    (GitRebaseStart, Ack),
+    (GitRebaseContinue, Ack),
+    (GitRebaseAbort, Ack),
+    (GitBlame, GitBlameResponse),
+    (GitLog, GitLogResponse),
+    (GitFileHistory, GitFileHistoryResponse),
+    (GitCommitGraph, GitCommitGraphResponse),
+    (GitPermalink, GitUrlResponse),
+    (GitOpenPullRequestUrl, GitUrlResponse),
+    (GitGetTags, GitTagsResponse),
+    (GitCreateTag, Ack),
+    (GitDeleteTag, Ack),
+    (GitPushTag, RemoteMessageResponse),
+    (GitMerge, Ack),
+    (GitMergeAbort, Ack),
     (UpdateBuffer, Ack),
     (UpdateParticipantLocation, Ack),
     (UpdateProject, Ack),
@@ -478,6 +548,10 @@ request_messages!(
     (RegisterBufferWithLanguageServers, Ack),
     (GitShow, GitCommitDetails),
     (GitReset, Ack),
+    (
+        GitPathsDiscardedByHardReset,
+        GitPathsDiscardedByHardResetResponse
+    ),
     (GitCheckoutFiles, Ack),
     (SetIndexText, Ack),
     (Push, RemoteMessageResponse),
@@ -488,8 +562,25 @@ request_messages!(
     (GitCreateBranch, Ack),
     (GitChangeBranch, Ack),
     (GitRenameBranch, Ack),
+    (GitDeleteBranch, Ack),
+    (GitSetUpstream, Ack),
+    (GitIgnorePaths, Ack),
+    (GitIsIgnored, GitIsIgnoredResponse),
+    (GitClean, GitCleanResponse),
+    (GitLfsPull, RemoteMessageResponse),
+    (GitLfsLock, Ack),
+    (GitLfsUnlock, Ack),
+    (GitResolveConflict, Ack),
     (CheckForPushedCommits, CheckForPushedCommitsResponse),
     (GitDiff, GitDiffResponse),
+    (GitDiffFile, GitDiffResponse),
+    (GitDiffChunk, GitDiffChunkResponse),
+    (GitLoadBlob, GitLoadBlobResponse),
+    (GitApplyPatch, Ack),
+    (GitFormatPatch, GitFormatPatchResponse),
+    (GitMaintenance, Ack),
+    (GitGc, Ack),
+    (GitRepositoryHealth, GitRepositoryHealthResponse),
     (GitInit, Ack),
     (ToggleBreakpoint, Ack),
     (GetDebugAdapterBinary, DebugAdapterBinary),
@@ -549,6 +640,9 @@ entity_messages!(
     GetImplementation,
     GetDocumentHighlights,
     GetDocumentSymbols,
+    PrepareTypeHierarchy,
+    GetTypeHierarchySupertypes,
+    GetTypeHierarchySubtypes,
     GetHover,
     GetProjectSymbols,
     GetReferences,
@@ -591,6 +685,21 @@ entity_messages!(
     StashPop,
     StashApply,
     StashDrop,
+    GitRebaseStart,
+    GitRebaseContinue,
+    GitRebaseAbort,
+    GitBlame,
+    GitLog,
+    GitFileHistory,
+    GitCommitGraph,
+    GitPermalink,
+    GitOpenPullRequestUrl,
+    GitGetTags,
+    GitCreateTag,
+    GitDeleteTag,
+    GitPushTag,
+    GitMerge,
+    GitMergeAbort,
     UpdateBuffer,
     UpdateBufferFile,
     UpdateDiagnosticSummary,
@@ -634,6 +743,7 @@ entity_messages!(
     RegisterBufferWithLanguageServers,
     GitShow,
     GitReset,
+    GitPathsDiscardedByHardReset,
     GitCheckoutFiles,
     SetIndexText,
     ToggleLspLogs,
@@ -646,9 +756,27 @@ entity_messages!(
     AskPassRequest,
     GitChangeBranch,
     GitRenameBranch,
+    GitDeleteBranch,
+    GitSetUpstream,
+    GitIgnorePaths,
+    GitIsIgnored,
+    GitClean,
+    GitLfsPull,
+    GitLfsLock,
+    GitLfsUnlock,
+    GitResolveConflict,
+    RemoteProgress,
     GitCreateBranch,
     CheckForPushedCommits,
     GitDiff,
+    GitDiffFile,
+    GitDiffChunk,
+    GitLoadBlob,
+    GitApplyPatch,
+    GitFormatPatch,
+    GitMaintenance,
+    GitGc,
+    GitRepositoryHealth,
     GitInit,
     BreakpointsForFile,
     ToggleBreakpoint,
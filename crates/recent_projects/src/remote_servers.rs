@@ -1426,6 +1426,7 @@ impl RemoteServerProjects {
                     nickname: None,
                     args: connection_options.args.unwrap_or_default(),
                     upload_binary_over_ssh: None,
+                    forward_agent: None,
                     port_forwards: connection_options.port_forwards,
                 })
         });
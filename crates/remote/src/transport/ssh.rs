@@ -54,6 +54,7 @@ pub struct SshConnectionOptions {
 
     pub nickname: Option<String>,
     pub upload_binary_over_ssh: bool,
+    pub forward_agent: bool,
 }
 
 impl From<settings::SshConnection> for SshConnectionOptions {
@@ -66,6 +67,7 @@ impl From<settings::SshConnection> for SshConnectionOptions {
             args: Some(val.args),
             nickname: val.nickname,
             upload_binary_over_ssh: val.upload_binary_over_ssh.unwrap_or_default(),
+            forward_agent: val.forward_agent.unwrap_or_default(),
             port_forwards: val.port_forwards,
         }
     }
@@ -972,6 +974,7 @@ impl SshConnectionOptions {
             password: None,
             nickname: None,
             upload_binary_over_ssh: false,
+            forward_agent: false,
         })
     }
 
@@ -1012,6 +1015,10 @@ impl SshConnectionOptions {
             }));
         }
 
+        if self.forward_agent {
+            args.push("-A".to_string());
+        }
+
         args
     }
 
@@ -1169,4 +1176,17 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_additional_args_forward_agent() {
+        let mut options = SshConnectionOptions {
+            host: "example.com".to_string(),
+            args: Some(vec!["-v".to_string()]),
+            ..Default::default()
+        };
+        assert_eq!(options.additional_args(), ["-v"]);
+
+        options.forward_agent = true;
+        assert_eq!(options.additional_args(), ["-v", "-A"]);
+    }
 }
@@ -7,6 +7,7 @@ use collections::HashSet;
 
 use extension::ExtensionHostProxy;
 use fs::{FakeFs, Fs};
+use git::repository::DiffType;
 use gpui::{AppContext as _, Entity, SemanticVersion, TestAppContext};
 use http_client::{BlockedHttpClient, FakeHttpClient};
 use language::{
@@ -1576,6 +1577,80 @@ async fn test_remote_git_diffs_when_recv_update_repository_delay(
     });
 }
 
+#[gpui::test]
+async fn test_remote_git_diff_larger_than_one_message(
+    cx: &mut TestAppContext,
+    server_cx: &mut TestAppContext,
+) {
+    let fs = FakeFs::new(server_cx.executor());
+    fs.insert_tree(
+        path!("/code"),
+        json!({
+            "project1": {
+                ".git": {},
+                "README.md": "# project 1",
+            },
+        }),
+    )
+    .await;
+    // Multi-byte characters make chunk boundaries likely to fall inside a character.
+    let head_text = (0..100_000)
+        .map(|ix| format!("ünïcödé line {ix}\n"))
+        .collect::<String>();
+    let index_text = (0..100_000)
+        .map(|ix| format!("ŝtäged line {ix}\n"))
+        .collect::<String>();
+    fs.set_head_for_repo(
+        Path::new(path!("/code/project1/.git")),
+        &[("README.md", head_text)],
+        "deadbeef",
+    );
+    fs.set_index_for_repo(
+        Path::new(path!("/code/project1/.git")),
+        &[("README.md", index_text)],
+    );
+
+    let (project, headless_project) = init_test(&fs, cx, server_cx).await;
+    project
+        .update(cx, |project, cx| {
+            project.find_or_create_worktree(path!("/code/project1"), true, cx)
+        })
+        .await
+        .unwrap();
+    cx.run_until_parked();
+
+    let repository = project.update(cx, |project, cx| project.active_repository(cx).unwrap());
+    let remote_diff = repository
+        .update(cx, |repository, cx| {
+            repository.diff(DiffType::HeadToIndex, cx)
+        })
+        .await
+        .unwrap()
+        .unwrap();
+
+    let server_repository = server_cx.update(|cx| {
+        headless_project
+            .read(cx)
+            .git_store
+            .read(cx)
+            .repositories()
+            .values()
+            .next()
+            .unwrap()
+            .clone()
+    });
+    let local_diff = server_repository
+        .update(server_cx, |repository, cx| {
+            repository.diff(DiffType::HeadToIndex, cx)
+        })
+        .await
+        .unwrap()
+        .unwrap();
+
+    assert!(local_diff.len() > 3_000_000);
+    assert_eq!(remote_diff, local_diff);
+}
+
 #[gpui::test]
 async fn test_remote_git_branches(cx: &mut TestAppContext, server_cx: &mut TestAppContext) {
     let fs = FakeFs::new(server_cx.executor());
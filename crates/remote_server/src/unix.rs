@@ -406,12 +406,14 @@ pub fn execute_run(
             let node_settings_rx = initialize_settings(session.clone(), fs.clone(), cx);
 
             let proxy_url = read_proxy_settings(cx);
+            let no_proxy = ProxySettings::get_global(cx).no_proxy();
 
             let http_client = {
                 let _guard = Tokio::handle(cx).enter();
                 Arc::new(
                     ReqwestClient::proxy_and_user_agent(
                         proxy_url,
+                        no_proxy.as_deref(),
                         &format!(
                             "Zed-Server/{} ({}; {})",
                             env!("CARGO_PKG_VERSION"),
@@ -480,6 +482,7 @@ struct ServerPaths {
     stdin_socket: PathBuf,
     stdout_socket: PathBuf,
     stderr_socket: PathBuf,
+    agent_socket: PathBuf,
 }
 
 impl ServerPaths {
@@ -501,6 +504,7 @@ impl ServerPaths {
         let stdin_socket = server_dir.join("stdin.sock");
         let stdout_socket = server_dir.join("stdout.sock");
         let stderr_socket = server_dir.join("stderr.sock");
+        let agent_socket = server_dir.join("ssh-agent.sock");
         let log_file = logs_dir().join(format!("server-{}.log", identifier));
 
         Ok(Self {
@@ -508,6 +512,7 @@ impl ServerPaths {
             stdin_socket,
             stdout_socket,
             stderr_socket,
+            agent_socket,
             log_file,
         })
     }
@@ -595,6 +600,7 @@ pub(crate) fn execute_proxy(
         };
         Ok(())
     })?;
+    link_agent_socket(&server_paths);
 
     let stdin_task = smol::spawn(async move {
         let stdin = Async::new(std::io::stdin())?;
@@ -648,6 +654,36 @@ pub(crate) fn execute_proxy(
     Ok(())
 }
 
+/// Points the server's agent socket at the agent forwarded to this proxy, or removes it if
+/// this proxy has no forwarded agent.
+///
+/// The server outlives any single SSH session, and the socket path ssh creates for a
+/// forwarded agent changes with every connection. Git commands run by the server use
+/// this stable symlink instead, so they keep authenticating with the client's keys
+/// after a reconnect.
+fn link_agent_socket(paths: &ServerPaths) {
+    let forwarded_socket = std::env::var_os("SSH_AUTH_SOCK");
+    if forwarded_socket
+        .as_deref()
+        .is_some_and(|socket| Path::new(socket) == paths.agent_socket)
+    {
+        return;
+    }
+    // A link left by an earlier connection would keep pointing at that connection's agent.
+    std::fs::remove_file(&paths.agent_socket).ok();
+    let Some(forwarded_socket) = forwarded_socket else {
+        return;
+    };
+    if let Err(error) = std::os::unix::fs::symlink(&forwarded_socket, &paths.agent_socket) {
+        log::warn!(
+            "failed to link forwarded ssh agent {:?} to {:?}: {}",
+            forwarded_socket,
+            paths.agent_socket,
+            error
+        );
+    }
+}
+
 async fn kill_running_server(pid: u32, paths: &ServerPaths) -> Result<(), ExecuteProxyError> {
     log::info!("killing existing server with PID {}", pid);
     smol::process::Command::new("kill")
@@ -661,6 +697,7 @@ async fn kill_running_server(pid: u32, paths: &ServerPaths) -> Result<(), Execut
         &paths.stdin_socket,
         &paths.stdout_socket,
         &paths.stderr_socket,
+        &paths.agent_socket,
     ] {
         log::debug!("cleaning up file {:?} before starting new server", file);
         std::fs::remove_file(file).ok();
@@ -713,7 +750,8 @@ async fn spawn_server(paths: &ServerPaths) -> Result<(), SpawnServerError> {
         .arg("--stdout-socket")
         .arg(&paths.stdout_socket)
         .arg("--stderr-socket")
-        .arg(&paths.stderr_socket);
+        .arg(&paths.stderr_socket)
+        .env("SSH_AUTH_SOCK", &paths.agent_socket);
 
     let status = server_process
         .status()
@@ -16,6 +16,7 @@ use ui::ListItemSpacing;
 use gpui::SharedString;
 use gpui::Task;
 use ui::{ListItem, PopoverMenu, PopoverMenuHandle, PopoverTrigger, prelude::*};
+use util::rel_path::RelPath;
 
 type OnSelect = Box<dyn Fn(KernelSpecification, &mut Window, &mut App)>;
 
@@ -259,7 +260,8 @@ where
             .cloned()
             .collect();
 
-        let selected_kernelspec = store.active_kernelspec(self.worktree_id, None, cx);
+        let selected_kernelspec =
+            store.active_kernelspec(self.worktree_id, RelPath::empty(), None, cx);
 
         let delegate = KernelPickerDelegate {
             on_select: self.on_select,
@@ -1,17 +1,17 @@
 mod native_kernel;
-use std::{fmt::Debug, future::Future, path::PathBuf};
+use std::{fmt::Debug, future::Future, path::PathBuf, sync::Arc};
 
 use futures::{
     channel::mpsc::{self, Receiver},
     future::Shared,
     stream,
 };
-use gpui::{App, Entity, Task, Window};
+use gpui::{App, Context, Entity, EntityId, Task, Window};
 use language::LanguageName;
 pub use native_kernel::*;
 
 mod remote_kernels;
-use project::{Project, ProjectPath, Toolchains, WorktreeId};
+use project::{Fs, Project, ProjectPath, Toolchains, WorktreeId};
 pub use remote_kernels::*;
 
 use anyhow::Result;
@@ -166,6 +166,44 @@ pub trait RunningKernel: Send + Debug {
     fn force_shutdown(&mut self, window: &mut Window, cx: &mut App) -> Task<anyhow::Result<()>>;
 }
 
+/// An entity that a running kernel routes the messages it receives to, such as an editor's REPL
+/// session or a notebook.
+pub trait KernelSession: Sized + 'static {
+    fn route(&mut self, message: &JupyterMessage, window: &mut Window, cx: &mut Context<Self>);
+    fn kernel_errored(&mut self, error_message: String, cx: &mut Context<Self>);
+}
+
+/// Launches a kernel for the given specification, routing its messages to `session`.
+pub fn start_kernel<S: KernelSession>(
+    kernel_specification: KernelSpecification,
+    entity_id: EntityId,
+    working_directory: PathBuf,
+    fs: Arc<dyn Fs>,
+    session: Entity<S>,
+    window: &mut Window,
+    cx: &mut App,
+) -> Task<Result<Box<dyn RunningKernel>>> {
+    match kernel_specification {
+        KernelSpecification::Jupyter(kernel_specification)
+        | KernelSpecification::PythonEnv(kernel_specification) => NativeRunningKernel::new(
+            kernel_specification,
+            entity_id,
+            working_directory,
+            fs,
+            session,
+            window,
+            cx,
+        ),
+        KernelSpecification::Remote(remote_kernel_specification) => RemoteRunningKernel::new(
+            remote_kernel_specification,
+            working_directory,
+            session,
+            window,
+            cx,
+        ),
+    }
+}
+
 #[derive(Debug, Clone)]
 pub enum KernelStatus {
     Idle,
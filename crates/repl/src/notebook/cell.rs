@@ -1,22 +1,30 @@
 #![allow(unused, dead_code)]
 use std::sync::Arc;
 
-use editor::{Editor, EditorMode, MultiBuffer};
+use anyhow::Result;
+use editor::{Editor, EditorEvent, EditorMode, MultiBuffer};
 use futures::future::Shared;
 use gpui::{
-    App, Entity, Hsla, RetainAllImageCache, Task, TextStyleRefinement, image_cache, prelude::*,
+    App, ClickEvent, Entity, EventEmitter, Hsla, RetainAllImageCache, Subscription, Task,
+    TextStyleRefinement, WeakEntity, image_cache, prelude::*,
 };
 use language::{Buffer, Language, LanguageRegistry};
 use markdown_preview::{markdown_parser::parse_markdown, markdown_renderer::render_markdown_block};
 use nbformat::v4::{CellId, CellMetadata, CellType};
+use runtimelib::{JupyterMessageContent, MimeBundle};
+use serde_json::json;
 use settings::Settings as _;
 use theme::ThemeSettings;
 use ui::{IconButtonShape, prelude::*};
 use util::ResultExt;
+use uuid::Uuid;
+use workspace::Workspace;
 
 use crate::{
     notebook::{CODE_BLOCK_INSET, GUTTER_WIDTH},
-    outputs::{Output, plain::TerminalOutput, user_error::ErrorView},
+    outputs::{
+        ExecutionStatus, ExecutionView, Output, plain::TerminalOutput, user_error::ErrorView,
+    },
 };
 
 #[derive(Copy, Clone, PartialEq, PartialOrd)]
@@ -112,11 +120,49 @@ fn convert_outputs(
         .collect()
 }
 
+/// Creates the editor a cell's source is edited in.
+fn cell_editor(buffer: Entity<Buffer>, window: &mut Window, cx: &mut App) -> Entity<Editor> {
+    let multi_buffer = cx.new(|cx| MultiBuffer::singleton(buffer, cx));
+    cx.new(|cx| {
+        let mut editor = Editor::new(
+            EditorMode::AutoHeight {
+                min_lines: 1,
+                max_lines: Some(1024),
+            },
+            multi_buffer,
+            None,
+            window,
+            cx,
+        );
+
+        let theme = ThemeSettings::get_global(cx);
+
+        let refinement = TextStyleRefinement {
+            font_family: Some(theme.buffer_font.family.clone()),
+            font_size: Some(theme.buffer_font_size(cx).into()),
+            color: Some(cx.theme().colors().editor_foreground),
+            background_color: Some(gpui::transparent_black()),
+            ..Default::default()
+        };
+
+        editor.set_show_gutter(false, cx);
+        editor.set_text_style_refinement(refinement);
+        editor
+    })
+}
+
+/// Splits a cell's source into lines the way notebook files store it, each line keeping its line
+/// ending.
+fn source_lines(source: &str) -> Vec<&str> {
+    source.split_inclusive('\n').collect()
+}
+
 impl Cell {
     pub fn load(
         cell: &nbformat::v4::Cell,
         languages: &Arc<LanguageRegistry>,
         notebook_language: Shared<Task<Option<Arc<Language>>>>,
+        workspace: WeakEntity<Workspace>,
         window: &mut Window,
         cx: &mut App,
     ) -> Self {
@@ -128,37 +174,48 @@ impl Cell {
                 ..
             } => {
                 let source = source.join("");
+                // Attachments aren't displayed, but are kept so that saving doesn't drop them.
+                let attachments = serde_json::to_value(cell)
+                    .ok()
+                    .and_then(|mut cell| cell.get_mut("attachments").map(serde_json::Value::take))
+                    .filter(|attachments| !attachments.is_null());
 
                 let entity = cx.new(|cx| {
-                    let markdown_parsing_task = {
-                        let languages = languages.clone();
-                        let source = source.clone();
-
-                        cx.spawn_in(window, async move |this, cx| {
-                            let parsed_markdown = cx
-                                .background_spawn(async move {
-                                    parse_markdown(&source, None, Some(languages)).await
-                                })
-                                .await;
-
-                            this.update(cx, |cell: &mut MarkdownCell, _| {
-                                cell.parsed_markdown = Some(parsed_markdown);
-                            })
-                            .log_err();
-                        })
-                    };
-
-                    MarkdownCell {
-                        markdown_parsing_task,
+                    let buffer = cx.new(|cx| Buffer::local(source.clone(), cx));
+                    let editor = cell_editor(buffer.clone(), window, cx);
+                    let editor_subscription =
+                        cx.subscribe(&editor, |this: &mut MarkdownCell, _, event, cx| {
+                            if let EditorEvent::Blurred = event {
+                                this.finish_editing(cx);
+                            }
+                        });
+
+                    let markdown_language = languages.language_for_name("Markdown");
+                    cx.spawn(async move |_, cx| {
+                        let language = markdown_language.await.ok();
+                        buffer
+                            .update(cx, |buffer, cx| buffer.set_language(language, cx))
+                            .ok();
+                    })
+                    .detach();
+
+                    let mut cell = MarkdownCell {
+                        markdown_parsing_task: Task::ready(()),
                         image_cache: RetainAllImageCache::new(cx),
                         languages: languages.clone(),
                         id: id.clone(),
                         metadata: metadata.clone(),
-                        source: source.clone(),
+                        attachments,
+                        source,
+                        editor,
+                        editing: false,
                         parsed_markdown: None,
                         selected: false,
                         cell_position: None,
-                    }
+                        _editor_subscription: editor_subscription,
+                    };
+                    cell.parse_markdown(cx);
+                    cell
                 });
 
                 Cell::Markdown(entity)
@@ -173,39 +230,8 @@ impl Cell {
                 let text = source.join("");
 
                 let buffer = cx.new(|cx| Buffer::local(text.clone(), cx));
-                let multi_buffer = cx.new(|cx| MultiBuffer::singleton(buffer.clone(), cx));
-
-                let editor_view = cx.new(|cx| {
-                    let mut editor = Editor::new(
-                        EditorMode::AutoHeight {
-                            min_lines: 1,
-                            max_lines: Some(1024),
-                        },
-                        multi_buffer,
-                        None,
-                        window,
-                        cx,
-                    );
-
-                    let theme = ThemeSettings::get_global(cx);
-
-                    let refinement = TextStyleRefinement {
-                        font_family: Some(theme.buffer_font.family.clone()),
-                        font_size: Some(theme.buffer_font_size(cx).into()),
-                        color: Some(cx.theme().colors().editor_foreground),
-                        background_color: Some(gpui::transparent_black()),
-                        ..Default::default()
-                    };
-
-                    editor.set_text(text, window, cx);
-                    editor.set_show_gutter(false, cx);
-                    editor.set_text_style_refinement(refinement);
+                let editor_view = cell_editor(buffer.clone(), window, cx);
 
-                    // editor.set_read_only(true);
-                    editor
-                });
-
-                let buffer = buffer.clone();
                 let language_task = cx.spawn_in(window, async move |this, cx| {
                     let language = notebook_language.await;
 
@@ -214,13 +240,28 @@ impl Cell {
                     });
                 });
 
+                let execution_view = cx.new(|cx| {
+                    let mut execution_view =
+                        ExecutionView::new(ExecutionStatus::Finished, workspace, cx);
+                    execution_view.outputs = convert_outputs(outputs, window, cx);
+                    execution_view
+                });
+
                 CodeCell {
                     id: id.clone(),
                     metadata: metadata.clone(),
                     execution_count: *execution_count,
-                    source: source.join(""),
+                    source: text,
                     editor: editor_view,
-                    outputs: convert_outputs(outputs, window, cx),
+                    execution_view,
+                    saved_outputs: outputs
+                        .iter()
+                        .map(|output| SavedOutput {
+                            display_id: None,
+                            output: output.clone(),
+                        })
+                        .collect(),
+                    clear_outputs_on_next_output: false,
                     selected: false,
                     language_task,
                     cell_position: None,
@@ -239,6 +280,98 @@ impl Cell {
             })),
         }
     }
+
+    /// Creates an empty cell of the given type, with a new id, in the form it's stored in
+    /// notebook files.
+    pub fn empty(cell_type: CellType) -> Result<nbformat::v4::Cell> {
+        let id = Uuid::new_v4().to_string();
+        let cell = match cell_type {
+            CellType::Code => json!({
+                "cell_type": "code",
+                "id": id,
+                "metadata": {},
+                "execution_count": null,
+                "source": [],
+                "outputs": [],
+            }),
+            CellType::Markdown => json!({
+                "cell_type": "markdown",
+                "id": id,
+                "metadata": {},
+                "source": [],
+            }),
+            CellType::Raw => json!({
+                "cell_type": "raw",
+                "id": id,
+                "metadata": {},
+                "source": [],
+            }),
+        };
+        Ok(serde_json::from_value(cell)?)
+    }
+
+    pub fn id(&self, cx: &App) -> CellId {
+        match self {
+            Cell::Code(cell) => cell.read(cx).id().clone(),
+            Cell::Markdown(cell) => cell.read(cx).id().clone(),
+            Cell::Raw(cell) => cell.read(cx).id().clone(),
+        }
+    }
+
+    /// The editor the cell's source is edited in, if it can be edited.
+    pub fn editor(&self, cx: &App) -> Option<Entity<Editor>> {
+        match self {
+            Cell::Code(cell) => Some(cell.read(cx).editor.clone()),
+            Cell::Markdown(cell) => Some(cell.read(cx).editor.clone()),
+            Cell::Raw(_) => None,
+        }
+    }
+
+    /// Converts the cell back into the form it's stored in notebook files.
+    pub fn to_nbformat(&self, cx: &App) -> Result<nbformat::v4::Cell> {
+        let cell = match self {
+            Cell::Code(cell) => {
+                let cell = cell.read(cx);
+                let source = cell.editor.read(cx).text(cx);
+                json!({
+                    "cell_type": "code",
+                    "id": cell.id,
+                    "metadata": cell.metadata,
+                    "execution_count": cell.execution_count,
+                    "source": source_lines(&source),
+                    "outputs": cell
+                        .saved_outputs
+                        .iter()
+                        .map(|saved_output| &saved_output.output)
+                        .collect::<Vec<_>>(),
+                })
+            }
+            Cell::Markdown(cell) => {
+                let cell = cell.read(cx);
+                let source = cell.editor.read(cx).text(cx);
+                let mut value = json!({
+                    "cell_type": "markdown",
+                    "id": cell.id,
+                    "metadata": cell.metadata,
+                    "source": source_lines(&source),
+                });
+                if let Some(attachments) = &cell.attachments {
+                    value["attachments"] = attachments.clone();
+                }
+                value
+            }
+            Cell::Raw(cell) => {
+                let cell = cell.read(cx);
+                json!({
+                    "cell_type": "raw",
+                    "id": cell.id,
+                    "metadata": cell.metadata,
+                    "source": source_lines(&cell.source),
+                })
+            }
+        };
+        Ok(serde_json::from_value(cell)?)
+    }
 }
 
 pub trait RenderableCell: Render {
@@ -335,13 +468,59 @@ pub trait RunnableCell: RenderableCell {
 pub struct MarkdownCell {
     id: CellId,
     metadata: CellMetadata,
+    attachments: Option<serde_json::Value>,
     image_cache: Entity<RetainAllImageCache>,
     source: String,
+    editor: Entity<Editor>,
+    /// Whether the source is shown in an editor, rather than rendered.
+    editing: bool,
     parsed_markdown: Option<markdown_preview::markdown_elements::ParsedMarkdown>,
     markdown_parsing_task: Task<()>,
     selected: bool,
     cell_position: Option<CellPosition>,
     languages: Arc<LanguageRegistry>,
+    _editor_subscription: Subscription,
+}
+
+impl MarkdownCell {
+    pub fn is_editing(&self) -> bool {
+        self.editing
+    }
+
+    pub fn start_editing(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.editing = true;
+        window.focus(&self.editor.focus_handle(cx));
+        cx.notify();
+    }
+
+    /// Renders the edited source again.
+    pub fn finish_editing(&mut self, cx: &mut Context<Self>) {
+        if !self.editing {
+            return;
+        }
+        self.editing = false;
+        self.source = self.editor.read(cx).text(cx);
+        self.parse_markdown(cx);
+        cx.notify();
+    }
+
+    fn parse_markdown(&mut self, cx: &mut Context<Self>) {
+        let languages = self.languages.clone();
+        let source = self.source.clone();
+        self.markdown_parsing_task = cx.spawn(async move |this, cx| {
+            let parsed_markdown = cx
+                .background_spawn(async move {
+                    parse_markdown(&source, None, Some(languages), true).await
+                })
+                .await;
+
+            this.update(cx, |cell, cx| {
+                cell.parsed_markdown = Some(parsed_markdown);
+                cx.notify();
+            })
+            .log_err();
+        });
+    }
 }
 
 impl RenderableCell for MarkdownCell {
@@ -386,15 +565,71 @@ impl RenderableCell for MarkdownCell {
     }
 }
 
-impl Render for MarkdownCell {
-    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
-        let Some(parsed) = self.parsed_markdown.as_ref() else {
-            return div();
-        };
+impl MarkdownCell {
+    fn render_editor(&self, cx: &mut Context<Self>) -> AnyElement {
+        div()
+            .py_1p5()
+            .w_full()
+            .child(
+                div()
+                    .flex()
+                    .size_full()
+                    .flex_1()
+                    .py_3()
+                    .px_5()
+                    .rounded_lg()
+                    .border_1()
+                    .border_color(cx.theme().colors().border)
+                    .bg(cx.theme().colors().editor_background)
+                    .child(div().w_full().child(self.editor.clone())),
+            )
+            .into_any_element()
+    }
 
+    fn render_markdown(&self, window: &mut Window, cx: &mut Context<Self>) -> AnyElement {
         let mut markdown_render_context =
             markdown_preview::markdown_renderer::RenderContext::new(None, window, cx);
 
+        v_flex()
+            .id("rendered-markdown")
+            .image_cache(self.image_cache.clone())
+            .size_full()
+            .flex_1()
+            .p_3()
+            .font_ui(cx)
+            .text_size(TextSize::Default.rems(cx))
+            .when(self.source.trim().is_empty(), |this| {
+                this.child(Label::new("Double-click to edit Markdown").color(Color::Muted))
+            })
+            .children(
+                self.parsed_markdown
+                    .iter()
+                    .flat_map(|parsed| parsed.children.iter())
+                    .map(|child| {
+                        div().relative().child(
+                            div()
+                                .relative()
+                                .child(render_markdown_block(child, &mut markdown_render_context)),
+                        )
+                    }),
+            )
+            .on_click(cx.listener(|this, event: &ClickEvent, window, cx| {
+                if event.click_count() >= 2 {
+                    this.start_editing(window, cx);
+                }
+            }))
+            .into_any_element()
+    }
+}
+
+impl Render for MarkdownCell {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let content = if self.editing {
+            self.render_editor(cx)
+        } else {
+            self.render_markdown(window, cx)
+        };
+
         v_flex()
             .size_full()
             // TODO: Move base cell render into trait impl so we don't have to repeat this
@@ -408,48 +643,209 @@ impl Render for MarkdownCell {
                     .gap(DynamicSpacing::Base08.rems(cx))
                     .bg(self.selected_bg_color(window, cx))
                     .child(self.gutter(window, cx))
-                    .child(
-                        v_flex()
-                            .image_cache(self.image_cache.clone())
-                            .size_full()
-                            .flex_1()
-                            .p_3()
-                            .font_ui(cx)
-                            .text_size(TextSize::Default.rems(cx))
-                            .children(parsed.children.iter().map(|child| {
-                                div().relative().child(div().relative().child(
-                                    render_markdown_block(child, &mut markdown_render_context),
-                                ))
-                            })),
-                    ),
+                    .child(content),
             )
             // TODO: Move base cell render into trait impl so we don't have to repeat this
             .children(self.cell_position_spacer(false, window, cx))
     }
 }
 
+pub enum CellEvent {
+    /// The cell's run control was clicked.
+    Run,
+}
+
+/// An output of a code cell in the form it's saved to the notebook file.
+struct SavedOutput {
+    /// The id the kernel can update a display by, which isn't saved.
+    display_id: Option<String>,
+    output: nbformat::v4::Output,
+}
+
+impl SavedOutput {
+    /// Converts a kernel message carrying an output, returning `None` for other messages.
+    fn from_message(content: &JupyterMessageContent) -> Option<Self> {
+        let (output_type, content, display_id) = match content {
+            JupyterMessageContent::StreamContent(stream) => {
+                ("stream", serde_json::to_value(stream), None)
+            }
+            JupyterMessageContent::DisplayData(display_data) => (
+                "display_data",
+                serde_json::to_value(display_data),
+                display_data
+                    .transient
+                    .as_ref()
+                    .and_then(|transient| transient.display_id.clone()),
+            ),
+            JupyterMessageContent::ExecuteResult(result) => (
+                "execute_result",
+                serde_json::to_value(result),
+                result
+                    .transient
+                    .as_ref()
+                    .and_then(|transient| transient.display_id.clone()),
+            ),
+            JupyterMessageContent::ErrorOutput(error) => {
+                ("error", serde_json::to_value(error), None)
+            }
+            _ => return None,
+        };
+
+        // Message contents and notebook outputs share a format, except that outputs are tagged
+        // with their type and don't include transient data.
+        let mut content = content.log_err()?;
+        let fields = content.as_object_mut()?;
+        fields.remove("transient");
+        fields.insert("output_type".into(), output_type.into());
+        let output = serde_json::from_value(content).log_err()?;
+        Some(Self { display_id, output })
+    }
+}
+
 pub struct CodeCell {
     id: CellId,
     metadata: CellMetadata,
     execution_count: Option<i32>,
     source: String,
     editor: Entity<editor::Editor>,
-    outputs: Vec<Output>,
+    execution_view: Entity<ExecutionView>,
+    saved_outputs: Vec<SavedOutput>,
+    /// Set when the kernel asks for the outputs to be cleared once the next output arrives.
+    clear_outputs_on_next_output: bool,
     selected: bool,
     cell_position: Option<CellPosition>,
     language_task: Task<()>,
 }
 
+impl EventEmitter<CellEvent> for CodeCell {}
+
 impl CodeCell {
+    pub fn editor(&self) -> &Entity<Editor> {
+        &self.editor
+    }
+
     pub fn is_dirty(&self, cx: &App) -> bool {
         self.editor.read(cx).buffer().read(cx).is_dirty(cx)
     }
+
     pub fn has_outputs(&self) -> bool {
-        !self.outputs.is_empty()
+        !self.saved_outputs.is_empty()
+    }
+
+    pub fn clear_outputs(&mut self, cx: &mut Context<Self>) {
+        self.saved_outputs.clear();
+        self.clear_outputs_on_next_output = false;
+        self.execution_view.update(cx, |execution_view, cx| {
+            execution_view.outputs.clear();
+            cx.notify();
+        });
+        cx.notify();
+    }
+
+    pub fn set_workspace(&mut self, workspace: WeakEntity<Workspace>, cx: &mut Context<Self>) {
+        self.execution_view.update(cx, |execution_view, _| {
+            execution_view.workspace = workspace;
+        });
+    }
+
+    /// Clears the outputs of the previous run, as the cell is run again.
+    pub fn start_execution(
+        &mut self,
+        status: ExecutionStatus,
+        execution_count: i32,
+        cx: &mut Context<Self>,
+    ) {
+        self.clear_outputs(cx);
+        self.execution_count = Some(execution_count);
+        self.set_execution_status(status, cx);
+    }
+
+    pub fn execution_status(&self, cx: &App) -> ExecutionStatus {
+        self.execution_view.read(cx).status.clone()
+    }
+
+    pub fn set_execution_status(&mut self, status: ExecutionStatus, cx: &mut Context<Self>) {
+        self.execution_view.update(cx, |execution_view, cx| {
+            execution_view.status = status;
+            cx.notify();
+        });
+        cx.notify();
+    }
+
+    /// Accepts a message the kernel sent in reply to running this cell.
+    pub fn handle_message(
+        &mut self,
+        content: &JupyterMessageContent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.execution_view.update(cx, |execution_view, cx| {
+            execution_view.push_message(content, window, cx);
+        });
+
+        if let JupyterMessageContent::ClearOutput(options) = content {
+            if options.wait {
+                self.clear_outputs_on_next_output = true;
+            } else {
+                self.saved_outputs.clear();
+            }
+        } else if let Some(saved_output) = SavedOutput::from_message(content) {
+            if std::mem::take(&mut self.clear_outputs_on_next_output) {
+                self.saved_outputs.clear();
+            }
+            // Like other notebook frontends, consecutive writes to the same stream are saved as
+            // a single output.
+            if let nbformat::v4::Output::Stream { name, text } = &saved_output.output
+                && let Some(SavedOutput {
+                    output:
+                        nbformat::v4::Output::Stream {
+                            name: last_name,
+                            text: last_text,
+                        },
+                    ..
+                }) = self.saved_outputs.last_mut()
+                && last_name == name
+            {
+                last_text.0.push_str(&text.0);
+            } else {
+                self.saved_outputs.push(saved_output);
+            }
+        }
+        cx.notify();
     }
 
-    pub fn clear_outputs(&mut self) {
-        self.outputs.clear();
+    pub fn update_display_data(
+        &mut self,
+        data: &MimeBundle,
+        display_id: &str,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.execution_view.update(cx, |execution_view, cx| {
+            execution_view.update_display_data(data, display_id, window, cx);
+        });
+
+        for saved_output in &mut self.saved_outputs {
+            if saved_output.display_id.as_deref() != Some(display_id) {
+                continue;
+            }
+            match &mut saved_output.output {
+                nbformat::v4::Output::DisplayData(display_data) => display_data.data = data.clone(),
+                nbformat::v4::Output::ExecuteResult(result) => result.data = data.clone(),
+                _ => {}
+            }
+        }
+    }
+
+    /// Whether there's anything to show below the cell's source: its outputs, or the status of
+    /// a run that hasn't finished.
+    fn shows_execution_view(&self, cx: &App) -> bool {
+        let execution_view = self.execution_view.read(cx);
+        !execution_view.outputs.is_empty()
+            || !matches!(
+                execution_view.status,
+                ExecutionStatus::Finished | ExecutionStatus::Unknown
+            )
     }
 
     fn output_control(&self) -> Option<CellControlType> {
@@ -526,8 +922,8 @@ impl RenderableCell for CodeCell {
             CellControl::new("rerun-cell", CellControlType::RerunCell)
         } else {
             CellControl::new("run-cell", CellControlType::RunCell)
-                .on_click(cx.listener(move |this, _, window, cx| this.run(window, cx)))
-        };
+        }
+        .on_click(cx.listener(move |this, _, window, cx| this.run(window, cx)));
 
         Some(cell_control)
     }
@@ -552,8 +948,8 @@ impl RenderableCell for CodeCell {
 }
 
 impl RunnableCell for CodeCell {
-    fn run(&mut self, window: &mut Window, cx: &mut Context<Self>) {
-        println!("Running code cell: {}", self.id);
+    fn run(&mut self, _window: &mut Window, cx: &mut Context<Self>) {
+        cx.emit(CellEvent::Run);
     }
 
     fn execution_count(&self) -> Option<i32> {
@@ -600,69 +996,25 @@ impl Render for CodeCell {
                     ),
             )
             // Output portion
-            .child(
-                h_flex()
-                    .w_full()
-                    .pr_6()
-                    .rounded_xs()
-                    .items_start()
-                    .gap(DynamicSpacing::Base08.rems(cx))
-                    .bg(self.selected_bg_color(window, cx))
-                    .child(self.gutter_output(window, cx))
-                    .child(
-                        div().py_1p5().w_full().child(
+            .when(self.shows_execution_view(cx), |this| {
+                this.child(
+                    h_flex()
+                        .w_full()
+                        .pr_6()
+                        .rounded_xs()
+                        .items_start()
+                        .gap(DynamicSpacing::Base08.rems(cx))
+                        .bg(self.selected_bg_color(window, cx))
+                        .child(self.gutter_output(window, cx))
+                        .child(
                             div()
-                                .flex()
-                                .size_full()
-                                .flex_1()
-                                .py_3()
+                                .py_1p5()
                                 .px_5()
-                                .rounded_lg()
-                                .border_1()
-                                // .border_color(cx.theme().colors().border)
-                                // .bg(cx.theme().colors().editor_background)
-                                .child(div().w_full().children(self.outputs.iter().map(
-                                    |output| {
-                                        let content = match output {
-                                            Output::Plain { content, .. } => {
-                                                Some(content.clone().into_any_element())
-                                            }
-                                            Output::Markdown { content, .. } => {
-                                                Some(content.clone().into_any_element())
-                                            }
-                                            Output::Stream { content, .. } => {
-                                                Some(content.clone().into_any_element())
-                                            }
-                                            Output::Image { content, .. } => {
-                                                Some(content.clone().into_any_element())
-                                            }
-                                            Output::Message(message) => Some(
-                                                div().child(message.clone()).into_any_element(),
-                                            ),
-                                            Output::Table { content, .. } => {
-                                                Some(content.clone().into_any_element())
-                                            }
-                                            Output::ErrorOutput(error_view) => {
-                                                error_view.render(window, cx)
-                                            }
-                                            Output::ClearOutputWaitMarker => None,
-                                        };
-
-                                        div()
-                                            // .w_full()
-                                            // .mt_3()
-                                            // .p_3()
-                                            // .rounded_sm()
-                                            // .bg(cx.theme().colors().editor_background)
-                                            // .border(px(1.))
-                                            // .border_color(cx.theme().colors().border)
-                                            // .shadow_xs()
-                                            .children(content)
-                                    },
-                                ))),
+                                .w_full()
+                                .child(self.execution_view.clone()),
                         ),
-                    ),
-            )
+                )
+            })
             // TODO: Move base cell render into trait impl so we don't have to repeat this
             .children(self.cell_position_spacer(false, window, cx))
     }
@@ -5,24 +5,34 @@ use std::{path::PathBuf, sync::Arc};
 use anyhow::{Context as _, Result};
 use client::proto::ViewId;
 use collections::HashMap;
-use feature_flags::{FeatureFlagAppExt as _, NotebookFeatureFlag};
+use editor::EditorEvent;
 use futures::FutureExt;
 use futures::future::Shared;
 use gpui::{
     AnyElement, App, Entity, EventEmitter, FocusHandle, Focusable, ListScrollEvent, ListState,
-    Point, Task, actions, list, prelude::*,
+    Point, Subscription, Task, WeakEntity, actions, list, prelude::*,
 };
 use language::{Language, LanguageRegistry};
 use project::{Project, ProjectEntryId, ProjectPath};
+use runtimelib::{
+    ExecuteRequest, ExecutionState, InterruptRequest, JupyterMessage, JupyterMessageContent,
+    ShutdownRequest,
+};
+use serde::Serialize as _;
 use ui::{Tooltip, prelude::*};
+use util::ResultExt as _;
 use workspace::item::{ItemEvent, SaveOptions, TabContentParams};
 use workspace::searchable::SearchableItemHandle;
-use workspace::{Item, ItemHandle, Pane, ProjectItem, ToolbarItemLocation};
+use workspace::{Item, ItemHandle, Pane, ProjectItem, ToolbarItemLocation, Workspace};
 use workspace::{ToolbarItemEvent, ToolbarItemView};
 
-use super::{Cell, CellPosition, RenderableCell};
+use super::{Cell, CellEvent, CellPosition, CodeCell, RenderableCell};
+use crate::kernels::{Kernel, KernelSession, KernelSpecification, start_kernel};
+use crate::outputs::ExecutionStatus;
+use crate::repl_store::ReplStore;
 
 use nbformat::v4::CellId;
+use nbformat::v4::CellType;
 use nbformat::v4::Metadata as NotebookMetadata;
 
 actions!(
@@ -32,8 +42,14 @@ actions!(
         OpenNotebook,
         /// Runs all cells in the notebook.
         RunAll,
+        /// Runs the selected cell and selects the next one.
+        RunCell,
         /// Clears all cell outputs.
         ClearOutputs,
+        /// Interrupts the notebook's kernel.
+        InterruptKernel,
+        /// Restarts the notebook's kernel.
+        RestartKernel,
         /// Moves the current cell up.
         MoveCellUp,
         /// Moves the current cell down.
@@ -54,29 +70,24 @@ pub(crate) const CODE_BLOCK_INSET: f32 = MEDIUM_SPACING_SIZE;
 pub(crate) const CONTROL_SIZE: f32 = 20.0;
 
 pub fn init(cx: &mut App) {
-    if cx.has_flag::<NotebookFeatureFlag>() || std::env::var("LOCAL_NOTEBOOK_DEV").is_ok() {
-        workspace::register_project_item::<NotebookEditor>(cx);
-    }
+    workspace::register_project_item::<NotebookEditor>(cx);
+}
 
-    cx.observe_flag::<NotebookFeatureFlag, _>({
-        move |is_enabled, cx| {
-            if is_enabled {
-                workspace::register_project_item::<NotebookEditor>(cx);
-            } else {
-                // todo: there is no way to unregister a project item, so if the feature flag
-                // gets turned off they need to restart Zed.
-            }
-        }
-    })
-    .detach();
+pub enum NotebookEvent {
+    /// The notebook was changed since it was last saved.
+    Edited,
+    /// The notebook was saved or reloaded, so it matches the file again.
+    Saved,
 }
 
 pub struct NotebookEditor {
     languages: Arc<LanguageRegistry>,
     project: Entity<Project>,
+    workspace: WeakEntity<Workspace>,
 
     focus_handle: FocusHandle,
     notebook_item: Entity<NotebookItem>,
+    notebook_language: Shared<Task<Option<Arc<Language>>>>,
 
     remote_id: Option<ViewId>,
     cell_list: ListState,
@@ -84,6 +95,13 @@ pub struct NotebookEditor {
     selected_cell_index: usize,
     cell_order: Vec<CellId>,
     cell_map: HashMap<CellId, Cell>,
+
+    kernel: Kernel,
+    /// The cell each pending execution request was sent for, by the request's message id.
+    executions: HashMap<String, CellId>,
+    execution_count: i32,
+    is_dirty: bool,
+    _cell_subscriptions: Vec<Subscription>,
 }
 
 impl NotebookEditor {
@@ -96,73 +114,361 @@ impl NotebookEditor {
         let focus_handle = cx.focus_handle();
 
         let languages = project.read(cx).languages().clone();
-        let language_name = notebook_item.read(cx).language_name();
 
         let notebook_language = notebook_item.read(cx).notebook_language();
         let notebook_language = cx
             .spawn_in(window, async move |_, _| notebook_language.await)
             .shared();
 
-        let mut cell_order = vec![]; // Vec<CellId>
-        let mut cell_map = HashMap::default(); // HashMap<CellId, Cell>
-
-        for (index, cell) in notebook_item
-            .read(cx)
-            .notebook
-            .clone()
-            .cells
-            .iter()
-            .enumerate()
-        {
-            let cell_id = cell.id();
-            cell_order.push(cell_id.clone());
-            cell_map.insert(
-                cell_id.clone(),
-                Cell::load(cell, &languages, notebook_language.clone(), window, cx),
-            );
-        }
-
-        let notebook_handle = cx.entity().downgrade();
-        let cell_count = cell_order.len();
-
-        let this = cx.entity();
-        let cell_list = ListState::new(cell_count, gpui::ListAlignment::Top, px(1000.));
+        let notebook = notebook_item.read(cx).notebook.clone();
 
-        Self {
+        let mut this = Self {
             project,
-            languages: languages.clone(),
+            languages,
+            workspace: WeakEntity::new_invalid(),
             focus_handle,
             notebook_item,
+            notebook_language,
             remote_id: None,
-            cell_list,
+            cell_list: ListState::new(0, gpui::ListAlignment::Top, px(1000.)),
             selected_cell_index: 0,
-            cell_order: cell_order.clone(),
-            cell_map: cell_map.clone(),
+            cell_order: Vec::new(),
+            cell_map: HashMap::default(),
+            kernel: Kernel::Shutdown,
+            executions: HashMap::default(),
+            execution_count: 0,
+            is_dirty: false,
+            _cell_subscriptions: Vec::new(),
+        };
+        this.load_cells(&notebook, window, cx);
+        this
+    }
+
+    /// Replaces the cells with the ones in `notebook`.
+    fn load_cells(
+        &mut self,
+        notebook: &nbformat::v4::Notebook,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.cell_order.clear();
+        self.cell_map.clear();
+        self.executions.clear();
+        self._cell_subscriptions.clear();
+
+        for cell in &notebook.cells {
+            let cell = Cell::load(
+                cell,
+                &self.languages,
+                self.notebook_language.clone(),
+                self.workspace.clone(),
+                window,
+                cx,
+            );
+            let cell_id = cell.id(cx);
+            self.subscribe_to_cell(&cell, &cell_id, window, cx);
+            self.cell_order.push(cell_id.clone());
+            self.cell_map.insert(cell_id, cell);
+        }
+
+        self.cell_list.reset(self.cell_order.len());
+        self.selected_cell_index = self
+            .selected_cell_index
+            .min(self.cell_order.len().saturating_sub(1));
+        cx.notify();
+    }
+
+    fn subscribe_to_cell(
+        &mut self,
+        cell: &Cell,
+        cell_id: &CellId,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if let Cell::Code(code_cell) = cell {
+            let cell_id = cell_id.clone();
+            self._cell_subscriptions.push(cx.subscribe_in(
+                code_cell,
+                window,
+                move |this, _, event: &CellEvent, window, cx| match event {
+                    CellEvent::Run => this.execute_cell(&cell_id, window, cx),
+                },
+            ));
         }
+
+        if let Some(editor) = cell.editor(cx) {
+            let cell_id = cell_id.clone();
+            self._cell_subscriptions.push(cx.subscribe_in(
+                &editor,
+                window,
+                move |this, _, event: &EditorEvent, window, cx| match event {
+                    EditorEvent::BufferEdited => this.mark_dirty(cx),
+                    EditorEvent::Focused => {
+                        if let Some(index) = this.cell_order.iter().position(|id| id == &cell_id) {
+                            this.set_selected_index(index, false, window, cx);
+                            cx.notify();
+                        }
+                    }
+                    _ => {}
+                },
+            ));
+        }
+    }
+
+    fn mark_dirty(&mut self, cx: &mut Context<Self>) {
+        self.is_dirty = true;
+        cx.emit(NotebookEvent::Edited);
+    }
+
+    fn code_cells(&self) -> impl Iterator<Item = &Entity<CodeCell>> {
+        self.cell_order
+            .iter()
+            .filter_map(|cell_id| match self.cell_map.get(cell_id) {
+                Some(Cell::Code(code_cell)) => Some(code_cell),
+                _ => None,
+            })
     }
 
     fn has_outputs(&self, window: &mut Window, cx: &mut Context<Self>) -> bool {
-        self.cell_map.values().any(|cell| {
-            if let Cell::Code(code_cell) = cell {
-                code_cell.read(cx).has_outputs()
-            } else {
-                false
-            }
-        })
+        self.code_cells()
+            .any(|code_cell| code_cell.read(cx).has_outputs())
     }
 
     fn clear_outputs(&mut self, window: &mut Window, cx: &mut Context<Self>) {
-        for cell in self.cell_map.values() {
-            if let Cell::Code(code_cell) = cell {
-                code_cell.update(cx, |cell, _cx| {
-                    cell.clear_outputs();
-                });
-            }
+        if !self.has_outputs(window, cx) {
+            return;
+        }
+        for code_cell in self.code_cells() {
+            code_cell.update(cx, |cell, cx| {
+                cell.clear_outputs(cx);
+            });
         }
+        self.mark_dirty(cx);
     }
 
     fn run_cells(&mut self, window: &mut Window, cx: &mut Context<Self>) {
-        println!("Cells would all run here, if that was implemented!");
+        let cell_ids = self
+            .code_cells()
+            .map(|code_cell| code_cell.read(cx).id().clone())
+            .collect::<Vec<_>>();
+        for cell_id in cell_ids {
+            self.execute_cell(&cell_id, window, cx);
+        }
+    }
+
+    fn run_selected_cell(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(cell) = self
+            .cell_order
+            .get(self.selected_cell_index)
+            .and_then(|cell_id| self.cell_map.get(cell_id))
+            .cloned()
+        else {
+            return;
+        };
+
+        match &cell {
+            Cell::Code(code_cell) => {
+                let cell_id = code_cell.read(cx).id().clone();
+                self.execute_cell(&cell_id, window, cx);
+            }
+            Cell::Markdown(markdown_cell) => {
+                markdown_cell.update(cx, |markdown_cell, cx| markdown_cell.finish_editing(cx));
+            }
+            Cell::Raw(_) => {}
+        }
+
+        // Running a cell moves on to the next one, so a notebook can be stepped through.
+        if self.selected_cell_index + 1 < self.cell_count() {
+            self.set_selected_index(self.selected_cell_index + 1, true, window, cx);
+        }
+        self.focus_selected_cell(window, cx);
+        cx.notify();
+    }
+
+    /// Focuses the selected cell's editor if it's a code cell, or the notebook otherwise, so
+    /// that keystrokes go to the selected cell.
+    fn focus_selected_cell(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let selected_cell = self
+            .cell_order
+            .get(self.selected_cell_index)
+            .and_then(|cell_id| self.cell_map.get(cell_id));
+        if let Some(Cell::Code(code_cell)) = selected_cell {
+            window.focus(&code_cell.read(cx).editor().focus_handle(cx));
+        } else {
+            window.focus(&self.focus_handle);
+        }
+    }
+
+    /// The kernel named in the notebook's metadata, falling back to the kernel that would be
+    /// used for the notebook's language in an editor.
+    fn kernel_specification(&self, cx: &App) -> Option<KernelSpecification> {
+        let notebook_item = self.notebook_item.read(cx);
+        let worktree_id = notebook_item.project_path.worktree_id;
+        let store = ReplStore::global(cx);
+        let store = store.read(cx);
+
+        notebook_item
+            .notebook
+            .metadata
+            .kernelspec
+            .as_ref()
+            .and_then(|kernelspec| {
+                store
+                    .kernel_specifications_for_worktree(worktree_id)
+                    .find(|specification| specification.name().as_ref() == kernelspec.name)
+                    .cloned()
+            })
+            .or_else(|| {
+                let language = self.notebook_language.clone().now_or_never().flatten();
+                store.active_kernelspec(worktree_id, &notebook_item.project_path.path, language, cx)
+            })
+    }
+
+    fn start_kernel(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(kernel_specification) = self.kernel_specification(cx) else {
+            self.kernel = Kernel::ErroredLaunch(
+                "No kernel was found for this notebook. Install a Jupyter kernel for its language."
+                    .into(),
+            );
+            cx.notify();
+            return;
+        };
+
+        let working_directory = self
+            .notebook_item
+            .read(cx)
+            .path
+            .parent()
+            .map(|parent| parent.to_path_buf())
+            .unwrap_or_else(std::env::temp_dir);
+        let fs = self.project.read(cx).fs().clone();
+
+        let kernel = start_kernel(
+            kernel_specification,
+            cx.entity_id(),
+            working_directory,
+            fs,
+            cx.entity(),
+            window,
+            cx,
+        );
+
+        let pending_kernel = cx
+            .spawn(async move |this, cx| match kernel.await {
+                Ok(kernel) => {
+                    this.update(cx, |notebook, cx| {
+                        notebook.kernel = Kernel::RunningKernel(kernel);
+                        cx.notify();
+                    })
+                    .ok();
+                }
+                Err(error) => {
+                    this.update(cx, |notebook, cx| {
+                        notebook.kernel_errored(error.to_string(), cx);
+                    })
+                    .ok();
+                }
+            })
+            .shared();
+
+        self.kernel = Kernel::StartingKernel(pending_kernel);
+        cx.notify();
+    }
+
+    fn send(&mut self, message: JupyterMessage) {
+        if let Kernel::RunningKernel(kernel) = &mut self.kernel {
+            kernel.request_tx().try_send(message).ok();
+        }
+    }
+
+    fn execute_cell(&mut self, cell_id: &CellId, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(Cell::Code(code_cell)) = self.cell_map.get(cell_id).cloned() else {
+            return;
+        };
+        let code = code_cell.read(cx).editor().read(cx).text(cx);
+        if code.trim().is_empty() {
+            return;
+        }
+
+        if let Kernel::Shutdown | Kernel::ErroredLaunch(_) = self.kernel {
+            self.start_kernel(window, cx);
+        }
+
+        let status = match &self.kernel {
+            Kernel::Restarting => ExecutionStatus::Restarting,
+            Kernel::RunningKernel(_) => ExecutionStatus::Queued,
+            Kernel::StartingKernel(_) => ExecutionStatus::ConnectingToKernel,
+            Kernel::ErroredLaunch(error) => ExecutionStatus::KernelErrored(error.clone()),
+            Kernel::ShuttingDown => ExecutionStatus::ShuttingDown,
+            Kernel::Shutdown => ExecutionStatus::Shutdown,
+        };
+
+        let message: JupyterMessage = ExecuteRequest {
+            code,
+            ..ExecuteRequest::default()
+        }
+        .into();
+        self.executions
+            .insert(message.header.msg_id.clone(), cell_id.clone());
+
+        self.execution_count += 1;
+        let execution_count = self.execution_count;
+        code_cell.update(cx, |code_cell, cx| {
+            code_cell.start_execution(status, execution_count, cx);
+        });
+        self.mark_dirty(cx);
+
+        match &self.kernel {
+            Kernel::RunningKernel(_) => self.send(message),
+            Kernel::StartingKernel(task) => {
+                // Queue up the execution to run after the kernel starts
+                let task = task.clone();
+                cx.spawn(async move |this, cx| {
+                    task.await;
+                    this.update(cx, |notebook, _| notebook.send(message)).ok();
+                })
+                .detach();
+            }
+            _ => {}
+        }
+    }
+
+    fn interrupt_kernel(&mut self, cx: &mut Context<Self>) {
+        if let Kernel::RunningKernel(_) = self.kernel {
+            self.send(InterruptRequest {}.into());
+        }
+    }
+
+    fn restart_kernel(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let kernel = std::mem::replace(&mut self.kernel, Kernel::Restarting);
+        self.execution_count = 0;
+
+        match kernel {
+            Kernel::Restarting => {}
+            Kernel::RunningKernel(mut kernel) => {
+                let mut request_tx = kernel.request_tx();
+                let forced = kernel.force_shutdown(window, cx);
+
+                cx.spawn_in(window, async move |this, cx| {
+                    let message: JupyterMessage = ShutdownRequest { restart: true }.into();
+                    request_tx.try_send(message).ok();
+
+                    // Give the kernel a moment to shut down before killing it
+                    cx.background_executor()
+                        .timer(std::time::Duration::from_secs(1))
+                        .await;
+                    forced.await.log_err();
+
+                    this.update_in(cx, |notebook, window, cx| {
+                        notebook.start_kernel(window, cx);
+                    })
+                    .ok();
+                })
+                .detach();
+            }
+            _ => self.start_kernel(window, cx),
+        }
+        cx.notify();
     }
 
     fn open_notebook(&mut self, _: &OpenNotebook, _window: &mut Window, _cx: &mut Context<Self>) {
@@ -170,19 +476,87 @@ impl NotebookEditor {
     }
 
     fn move_cell_up(&mut self, window: &mut Window, cx: &mut Context<Self>) {
-        println!("Move cell up triggered");
+        let index = self.selected_cell_index;
+        if index == 0 || index >= self.cell_count() {
+            return;
+        }
+        self.cell_order.swap(index - 1, index);
+        self.cell_list.splice(index - 1..index + 1, 2);
+        self.set_selected_index(index - 1, true, window, cx);
+        self.mark_dirty(cx);
+        cx.notify();
     }
 
     fn move_cell_down(&mut self, window: &mut Window, cx: &mut Context<Self>) {
-        println!("Move cell down triggered");
+        let index = self.selected_cell_index;
+        if index + 1 >= self.cell_count() {
+            return;
+        }
+        self.cell_order.swap(index, index + 1);
+        self.cell_list.splice(index..index + 2, 2);
+        self.set_selected_index(index + 1, true, window, cx);
+        self.mark_dirty(cx);
+        cx.notify();
+    }
+
+    /// Inserts an empty cell after the selected one, and selects it.
+    fn add_cell(
+        &mut self,
+        cell_type: CellType,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Option<Cell> {
+        let cell = Cell::empty(cell_type).log_err()?;
+        let cell = Cell::load(
+            &cell,
+            &self.languages,
+            self.notebook_language.clone(),
+            self.workspace.clone(),
+            window,
+            cx,
+        );
+        let cell_id = cell.id(cx);
+        self.subscribe_to_cell(&cell, &cell_id, window, cx);
+
+        let index = if self.cell_order.is_empty() {
+            0
+        } else {
+            self.selected_cell_index + 1
+        };
+        self.cell_order.insert(index, cell_id.clone());
+        self.cell_map.insert(cell_id, cell.clone());
+        self.cell_list.splice(index..index, 1);
+        self.set_selected_index(index, true, window, cx);
+        self.mark_dirty(cx);
+        cx.notify();
+        Some(cell)
     }
 
     fn add_markdown_block(&mut self, window: &mut Window, cx: &mut Context<Self>) {
-        println!("Add markdown block triggered");
+        if let Some(Cell::Markdown(markdown_cell)) = self.add_cell(CellType::Markdown, window, cx) {
+            markdown_cell.update(cx, |markdown_cell, cx| {
+                markdown_cell.start_editing(window, cx)
+            });
+        }
     }
 
     fn add_code_block(&mut self, window: &mut Window, cx: &mut Context<Self>) {
-        println!("Add code block triggered");
+        if self.add_cell(CellType::Code, window, cx).is_some() {
+            self.focus_selected_cell(window, cx);
+        }
+    }
+
+    /// Builds the notebook as it would be saved, from the notebook as it was loaded and the
+    /// cells as they are now.
+    fn to_notebook(&self, cx: &App) -> Result<nbformat::v4::Notebook> {
+        let mut notebook = self.notebook_item.read(cx).notebook.clone();
+        notebook.cells = self
+            .cell_order
+            .iter()
+            .filter_map(|cell_id| self.cell_map.get(cell_id))
+            .map(|cell| cell.to_nbformat(cx))
+            .collect::<Result<_>>()?;
+        Ok(notebook)
     }
 
     fn cell_count(&self) -> usize {
@@ -304,6 +678,11 @@ impl NotebookEditor {
         cx: &mut Context<Self>,
     ) -> impl IntoElement {
         let has_outputs = self.has_outputs(window, cx);
+        let kernel_status = self.kernel.status();
+        let kernel_tooltip = match &self.kernel {
+            Kernel::ErroredLaunch(error) => format!("Kernel: {error}"),
+            _ => format!("Kernel: {}", kernel_status.to_string()),
+        };
 
         v_flex()
             .max_w(px(CONTROL_SIZE + 4.0))
@@ -433,7 +812,49 @@ impl NotebookEditor {
                     ))
                     .child(
                         Self::button_group(window, cx)
-                            .child(IconButton::new("repl", IconName::ReplNeutral)),
+                            .child(
+                                Self::render_notebook_control(
+                                    "interrupt-kernel",
+                                    IconName::Stop,
+                                    window,
+                                    cx,
+                                )
+                                .disabled(!kernel_status.is_connected())
+                                .tooltip(move |window, cx| {
+                                    Tooltip::for_action(
+                                        "Interrupt kernel",
+                                        &InterruptKernel,
+                                        window,
+                                        cx,
+                                    )
+                                })
+                                .on_click(|_, window, cx| {
+                                    window.dispatch_action(Box::new(InterruptKernel), cx);
+                                }),
+                            )
+                            .child(
+                                Self::render_notebook_control(
+                                    "restart-kernel",
+                                    IconName::RotateCw,
+                                    window,
+                                    cx,
+                                )
+                                .tooltip(move |window, cx| {
+                                    Tooltip::for_action(
+                                        "Restart kernel",
+                                        &RestartKernel,
+                                        window,
+                                        cx,
+                                    )
+                                })
+                                .on_click(|_, window, cx| {
+                                    window.dispatch_action(Box::new(RestartKernel), cx);
+                                }),
+                            )
+                            .child(
+                                IconButton::new("repl", IconName::ReplNeutral)
+                                    .tooltip(Tooltip::text(kernel_tooltip)),
+                            ),
                     ),
             )
     }
@@ -495,6 +916,11 @@ impl Render for NotebookEditor {
                 cx.listener(|this, &ClearOutputs, window, cx| this.clear_outputs(window, cx)),
             )
             .on_action(cx.listener(|this, &RunAll, window, cx| this.run_cells(window, cx)))
+            .on_action(cx.listener(|this, &RunCell, window, cx| this.run_selected_cell(window, cx)))
+            .on_action(cx.listener(|this, &InterruptKernel, _, cx| this.interrupt_kernel(cx)))
+            .on_action(
+                cx.listener(|this, &RestartKernel, window, cx| this.restart_kernel(window, cx)),
+            )
             .on_action(cx.listener(|this, &MoveCellUp, window, cx| this.move_cell_up(window, cx)))
             .on_action(
                 cx.listener(|this, &MoveCellDown, window, cx| this.move_cell_down(window, cx)),
@@ -563,6 +989,12 @@ impl project::ProjectItem for NotebookItem {
         path: &ProjectPath,
         cx: &mut App,
     ) -> Option<Task<anyhow::Result<Entity<Self>>>> {
+        // Notebooks are read straight from the local file system, where remote projects'
+        // paths don't exist, so those open as plain text instead.
+        if !project.read(cx).is_local() {
+            return None;
+        }
+
         let path = path.clone();
         let project = project.clone();
         let fs = project.read(cx).fs().clone();
@@ -576,22 +1008,7 @@ impl project::ProjectItem for NotebookItem {
 
                 // todo: watch for changes to the file
                 let file_content = fs.load(abs_path.as_path()).await?;
-                let notebook = nbformat::parse_notebook(&file_content);
-
-                let notebook = match notebook {
-                    Ok(nbformat::Notebook::V4(notebook)) => notebook,
-                    // 4.1 - 4.4 are converted to 4.5
-                    Ok(nbformat::Notebook::Legacy(legacy_notebook)) => {
-                        // TODO: Decide if we want to mutate the notebook by including Cell IDs
-                        // and any other conversions
-
-                        nbformat::upgrade_legacy_notebook(legacy_notebook)?
-                    }
-                    // Bad notebooks and notebooks v4.0 and below are not supported
-                    Err(e) => {
-                        anyhow::bail!("Failed to parse notebook: {:?}", e);
-                    }
-                };
+                let notebook = parse_notebook(&file_content)?;
 
                 let id = project
                     .update(cx, |project, cx| {
@@ -625,6 +1042,37 @@ impl project::ProjectItem for NotebookItem {
     }
 }
 
+fn parse_notebook(content: &str) -> Result<nbformat::v4::Notebook> {
+    match nbformat::parse_notebook(content) {
+        Ok(nbformat::Notebook::V4(notebook)) => Ok(notebook),
+        // 4.1 - 4.4 are converted to 4.5
+        Ok(nbformat::Notebook::Legacy(legacy_notebook)) => {
+            // TODO: Decide if we want to mutate the notebook by including Cell IDs
+            // and any other conversions
+
+            Ok(nbformat::upgrade_legacy_notebook(legacy_notebook)?)
+        }
+        // Bad notebooks and notebooks v4.0 and below are not supported
+        Err(e) => {
+            anyhow::bail!("Failed to parse notebook: {:?}", e);
+        }
+    }
+}
+
+/// Serializes a notebook the way Jupyter writes notebook files, with sorted keys and one space
+/// of indentation, so that saving doesn't reformat the whole file.
+fn serialize_notebook(notebook: &nbformat::v4::Notebook) -> Result<String> {
+    let mut value = serde_json::to_value(notebook)?;
+    value.sort_all_objects();
+
+    let mut content = Vec::new();
+    let formatter = serde_json::ser::PrettyFormatter::with_indent(b" ");
+    let mut serializer = serde_json::Serializer::with_formatter(&mut content, formatter);
+    value.serialize(&mut serializer)?;
+    content.push(b'\n');
+    Ok(String::from_utf8(content)?)
+}
+
 impl NotebookItem {
     pub fn language_name(&self) -> Option<String> {
         self.notebook
@@ -654,7 +1102,90 @@ impl NotebookItem {
     }
 }
 
-impl EventEmitter<()> for NotebookEditor {}
+impl EventEmitter<NotebookEvent> for NotebookEditor {}
+
+impl KernelSession for NotebookEditor {
+    fn route(&mut self, message: &JupyterMessage, window: &mut Window, cx: &mut Context<Self>) {
+        match &message.content {
+            JupyterMessageContent::Status(status) => {
+                self.kernel.set_execution_state(&status.execution_state);
+                cx.notify();
+            }
+            JupyterMessageContent::KernelInfoReply(reply) => {
+                self.kernel.set_kernel_info(reply);
+                cx.notify();
+            }
+            JupyterMessageContent::UpdateDisplayData(update) => {
+                let Some(display_id) = update.transient.display_id.clone() else {
+                    return;
+                };
+                // A display can be updated from any cell, not just the one that created it.
+                for code_cell in self.code_cells() {
+                    code_cell.update(cx, |code_cell, cx| {
+                        code_cell.update_display_data(&update.data, &display_id, window, cx);
+                    });
+                }
+                self.mark_dirty(cx);
+                return;
+            }
+            _ => {}
+        }
+
+        let Some(parent_message_id) = message
+            .parent_header
+            .as_ref()
+            .map(|header| header.msg_id.clone())
+        else {
+            return;
+        };
+        let Some(Cell::Code(code_cell)) = self
+            .executions
+            .get(&parent_message_id)
+            .and_then(|cell_id| self.cell_map.get(cell_id))
+            .cloned()
+        else {
+            return;
+        };
+
+        code_cell.update(cx, |code_cell, cx| {
+            code_cell.handle_message(&message.content, window, cx);
+        });
+
+        match &message.content {
+            JupyterMessageContent::StreamContent(_)
+            | JupyterMessageContent::DisplayData(_)
+            | JupyterMessageContent::ExecuteResult(_)
+            | JupyterMessageContent::ErrorOutput(_)
+            | JupyterMessageContent::ClearOutput(_) => self.mark_dirty(cx),
+            // The kernel goes idle once it's done with a request.
+            JupyterMessageContent::Status(status)
+                if matches!(status.execution_state, ExecutionState::Idle) =>
+            {
+                self.executions.remove(&parent_message_id);
+            }
+            _ => {}
+        }
+    }
+
+    fn kernel_errored(&mut self, error_message: String, cx: &mut Context<Self>) {
+        self.kernel = Kernel::ErroredLaunch(error_message.clone());
+
+        for cell_id in self.executions.values() {
+            if let Some(Cell::Code(code_cell)) = self.cell_map.get(cell_id) {
+                code_cell.update(cx, |code_cell, cx| {
+                    if !matches!(code_cell.execution_status(cx), ExecutionStatus::Finished) {
+                        code_cell.set_execution_status(
+                            ExecutionStatus::KernelErrored(error_message.clone()),
+                            cx,
+                        );
+                    }
+                });
+            }
+        }
+        self.executions.clear();
+        cx.notify();
+    }
+}
 
 // pub struct NotebookControls {
 //     pane_focused: bool,
@@ -702,7 +1233,30 @@ impl EventEmitter<()> for NotebookEditor {}
 // }
 
 impl Item for NotebookEditor {
-    type Event = ();
+    type Event = NotebookEvent;
+
+    fn to_item_events(event: &Self::Event, mut f: impl FnMut(ItemEvent)) {
+        match event {
+            NotebookEvent::Edited => {
+                f(ItemEvent::Edit);
+                f(ItemEvent::UpdateTab);
+            }
+            NotebookEvent::Saved => f(ItemEvent::UpdateTab),
+        }
+    }
+
+    fn added_to_workspace(
+        &mut self,
+        workspace: &mut Workspace,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.workspace = workspace.weak_handle();
+        for code_cell in self.code_cells() {
+            let workspace = self.workspace.clone();
+            code_cell.update(cx, |code_cell, cx| code_cell.set_workspace(workspace, cx));
+        }
+    }
 
     fn clone_on_split(
         &self,
@@ -773,19 +1327,37 @@ impl Item for NotebookEditor {
         // TODO
     }
 
-    // TODO
     fn can_save(&self, _cx: &App) -> bool {
-        false
+        true
     }
-    // TODO
+
     fn save(
         &mut self,
         _options: SaveOptions,
         _project: Entity<Project>,
         _window: &mut Window,
-        _cx: &mut Context<Self>,
+        cx: &mut Context<Self>,
     ) -> Task<Result<()>> {
-        unimplemented!("save() must be implemented if can_save() returns true")
+        let notebook = match self.to_notebook(cx) {
+            Ok(notebook) => notebook,
+            Err(error) => return Task::ready(Err(error)),
+        };
+        let content = match serialize_notebook(&notebook) {
+            Ok(content) => content,
+            Err(error) => return Task::ready(Err(error)),
+        };
+        let path = self.notebook_item.read(cx).path.clone();
+        let fs = self.project.read(cx).fs().clone();
+
+        cx.spawn(async move |this, cx| {
+            fs.atomic_write(path, content).await?;
+            this.update(cx, |this, cx| {
+                this.notebook_item
+                    .update(cx, |notebook_item, _| notebook_item.notebook = notebook);
+                this.is_dirty = false;
+                cx.emit(NotebookEvent::Saved);
+            })
+        })
     }
 
     // TODO
@@ -798,24 +1370,32 @@ impl Item for NotebookEditor {
     ) -> Task<Result<()>> {
         unimplemented!("save_as() must be implemented if can_save() returns true")
     }
-    // TODO
+
     fn reload(
         &mut self,
         _project: Entity<Project>,
-        _window: &mut Window,
-        _cx: &mut Context<Self>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
     ) -> Task<Result<()>> {
-        unimplemented!("reload() must be implemented if can_save() returns true")
+        let path = self.notebook_item.read(cx).path.clone();
+        let fs = self.project.read(cx).fs().clone();
+
+        cx.spawn_in(window, async move |this, cx| {
+            let content = fs.load(&path).await?;
+            let notebook = parse_notebook(&content)?;
+            this.update_in(cx, |this, window, cx| {
+                this.notebook_item.update(cx, |notebook_item, _| {
+                    notebook_item.notebook = notebook.clone()
+                });
+                this.load_cells(&notebook, window, cx);
+                this.is_dirty = false;
+                cx.emit(NotebookEvent::Saved);
+            })
+        })
     }
 
-    fn is_dirty(&self, cx: &App) -> bool {
-        self.cell_map.values().any(|cell| {
-            if let Cell::Code(code_cell) = cell {
-                code_cell.read(cx).is_dirty(cx)
-            } else {
-                false
-            }
-        })
+    fn is_dirty(&self, _cx: &App) -> bool {
+        self.is_dirty
     }
 }
 
@@ -838,3 +1418,68 @@ impl ProjectItem for NotebookEditor {
         Self::new(project, item, window, cx)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+
+    #[test]
+    fn test_serialize_notebook_round_trips() {
+        let content = indoc! {r##"
+            {
+             "cells": [
+              {
+               "cell_type": "markdown",
+               "id": "a1",
+               "metadata": {},
+               "source": [
+                "# Title\n",
+                "Some text"
+               ]
+              },
+              {
+               "cell_type": "code",
+               "execution_count": 1,
+               "id": "b2",
+               "metadata": {},
+               "outputs": [
+                {
+                 "name": "stdout",
+                 "output_type": "stream",
+                 "text": [
+                  "hello\n"
+                 ]
+                }
+               ],
+               "source": [
+                "print(\"hello\")"
+               ]
+              }
+             ],
+             "metadata": {
+              "kernelspec": {
+               "display_name": "Python 3",
+               "language": "python",
+               "name": "python3"
+              }
+             },
+             "nbformat": 4,
+             "nbformat_minor": 5
+            }
+        "##};
+
+        let notebook = parse_notebook(content).unwrap();
+        let serialized = serialize_notebook(&notebook).unwrap();
+
+        // Jupyter sorts keys and indents by one space.
+        assert!(serialized.starts_with("{\n \"cells\": [\n  {\n"));
+        assert!(serialized.ends_with("}\n"));
+
+        let reparsed = parse_notebook(&serialized).unwrap();
+        assert_eq!(
+            serde_json::to_value(&reparsed).unwrap(),
+            serde_json::to_value(&notebook).unwrap()
+        );
+    }
+}
@@ -7,7 +7,7 @@ use anyhow::{Context as _, Result};
 use editor::Editor;
 use gpui::{App, Entity, WeakEntity, Window, prelude::*};
 use language::{BufferSnapshot, Language, LanguageName, Point};
-use project::{ProjectItem as _, WorktreeId};
+use project::{ProjectItem as _, ProjectPath, WorktreeId};
 
 use crate::repl_store::ReplStore;
 use crate::session::SessionEvent;
@@ -106,7 +106,12 @@ pub fn run(
 
         let kernel_specification = store
             .read(cx)
-            .active_kernelspec(project_path.worktree_id, Some(language.clone()), cx)
+            .active_kernelspec(
+                project_path.worktree_id,
+                &project_path.path,
+                Some(language.clone()),
+                cx,
+            )
             .with_context(|| format!("No kernel found for language: {}", language.name()))?;
 
         let fs = store.read(cx).fs().clone();
@@ -178,6 +183,10 @@ pub enum SessionSupport {
 }
 
 pub fn worktree_id_for_editor(editor: WeakEntity<Editor>, cx: &mut App) -> Option<WorktreeId> {
+    project_path_for_editor(editor, cx).map(|path| path.worktree_id)
+}
+
+pub fn project_path_for_editor(editor: WeakEntity<Editor>, cx: &mut App) -> Option<ProjectPath> {
     editor.upgrade().and_then(|editor| {
         editor
             .read(cx)
@@ -186,7 +195,6 @@ pub fn worktree_id_for_editor(editor: WeakEntity<Editor>, cx: &mut App) -> Optio
             .as_singleton()?
             .read(cx)
             .project_path(cx)
-            .map(|path| path.worktree_id)
     })
 }
 
@@ -202,15 +210,18 @@ pub fn session(editor: WeakEntity<Editor>, cx: &mut App) -> SessionSupport {
         return SessionSupport::Unsupported;
     };
 
-    let worktree_id = worktree_id_for_editor(editor, cx);
+    let project_path = project_path_for_editor(editor, cx);
 
-    let Some(worktree_id) = worktree_id else {
+    let Some(project_path) = project_path else {
         return SessionSupport::Unsupported;
     };
 
-    let kernelspec = store
-        .read(cx)
-        .active_kernelspec(worktree_id, Some(language.clone()), cx);
+    let kernelspec = store.read(cx).active_kernelspec(
+        project_path.worktree_id,
+        &project_path.path,
+        Some(language.clone()),
+        cx,
+    );
 
     match kernelspec {
         Some(kernelspec) => SessionSupport::Inactive(kernelspec),
@@ -812,4 +823,85 @@ mod tests {
             },]
         );
     }
+
+    #[gpui::test]
+    fn test_preferred_kernel_from_language_settings(cx: &mut App) {
+        use crate::kernels::LocalKernelSpecification;
+        use jupyter_protocol::JupyterKernelspec;
+        use settings::{LocalSettingsKind, Settings as _, SettingsStore};
+        use util::rel_path::rel_path;
+
+        settings::init(cx);
+        language::init(cx);
+        editor::init(cx);
+        JupyterSettings::register(cx);
+
+        let fs = Arc::new(project::RealFs::new(None, cx.background_executor().clone()));
+        ReplStore::init(fs, cx);
+
+        let python_kernel = |name: &str| {
+            KernelSpecification::Jupyter(LocalKernelSpecification {
+                name: name.into(),
+                kernelspec: JupyterKernelspec {
+                    argv: vec![],
+                    display_name: name.into(),
+                    language: "python".into(),
+                    interrupt_mode: None,
+                    metadata: None,
+                    env: None,
+                },
+                path: std::path::PathBuf::new(),
+            })
+        };
+        let store = ReplStore::global(cx);
+        store.update(cx, |store, cx| {
+            store.set_kernel_specs_for_testing(
+                vec![python_kernel("python3"), python_kernel("analysis")],
+                cx,
+            );
+        });
+
+        let worktree_id = WorktreeId::from_usize(1);
+        cx.update_global::<SettingsStore, _>(|settings_store, cx| {
+            settings_store
+                .set_local_settings(
+                    worktree_id,
+                    rel_path("notebooks").into(),
+                    LocalSettingsKind::Settings,
+                    Some(r#"{ "languages": { "Python": { "repl": { "kernel": "Analysis" } } } }"#),
+                    cx,
+                )
+                .unwrap();
+        });
+
+        let python = Arc::new(Language::new(
+            LanguageConfig {
+                name: "Python".into(),
+                ..Default::default()
+            },
+            None,
+        ));
+        let kernel_name = |path: &str, cx: &App| {
+            store
+                .read(cx)
+                .active_kernelspec(worktree_id, rel_path(path), Some(python.clone()), cx)
+                .map(|kernel_specification| kernel_specification.name())
+        };
+
+        // The worktree-local setting only applies to files in its directory.
+        assert_eq!(
+            kernel_name("notebooks/report.py", cx),
+            Some("analysis".into())
+        );
+        assert_eq!(kernel_name("main.py", cx), Some("python3".into()));
+
+        // A kernel picked explicitly for the worktree still wins over the setting.
+        store.update(cx, |store, cx| {
+            store.set_active_kernelspec(worktree_id, python_kernel("python3"), cx);
+        });
+        assert_eq!(
+            kernel_name("notebooks/report.py", cx),
+            Some("python3".into())
+        );
+    }
 }
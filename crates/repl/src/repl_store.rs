@@ -5,9 +5,10 @@ use collections::HashMap;
 use command_palette_hooks::CommandPaletteFilter;
 use gpui::{App, Context, Entity, EntityId, Global, Subscription, Task, prelude::*};
 use jupyter_websocket_client::RemoteServer;
-use language::Language;
+use language::{Language, language_settings::AllLanguageSettings};
 use project::{Fs, Project, WorktreeId};
-use settings::{Settings, SettingsStore};
+use settings::{Settings, SettingsLocation, SettingsStore};
+use util::rel_path::RelPath;
 
 use crate::kernels::{
     list_remote_kernelspecs, local_kernel_specifications, python_env_kernel_specifications,
@@ -208,6 +209,7 @@ impl ReplStore {
     pub fn active_kernelspec(
         &self,
         worktree_id: WorktreeId,
+        path: &RelPath,
         language_at_cursor: Option<Arc<Language>>,
         cx: &App,
     ) -> Option<KernelSpecification> {
@@ -215,12 +217,48 @@ impl ReplStore {
 
         if let Some(language_at_cursor) = language_at_cursor {
             selected_kernelspec
+                .or_else(|| {
+                    self.kernelspec_from_language_settings(
+                        worktree_id,
+                        path,
+                        &language_at_cursor,
+                        cx,
+                    )
+                })
                 .or_else(|| self.kernelspec_legacy_by_lang_only(language_at_cursor, cx))
         } else {
             selected_kernelspec
         }
     }
 
+    /// The kernel pinned by the `repl.kernel` language setting, which is
+    /// looked up in the worktree's kernels too so that it can name a kernel
+    /// from one of the project's virtual environments.
+    ///
+    /// The settings are resolved at the file's path, so that worktree-local
+    /// settings in nested directories apply.
+    fn kernelspec_from_language_settings(
+        &self,
+        worktree_id: WorktreeId,
+        path: &RelPath,
+        language_at_cursor: &Language,
+        cx: &App,
+    ) -> Option<KernelSpecification> {
+        let location = SettingsLocation { worktree_id, path };
+        let language_settings = AllLanguageSettings::get(Some(location), cx).language(
+            Some(location),
+            Some(&language_at_cursor.name()),
+            cx,
+        );
+        let kernel = language_settings.repl.kernel.as_ref()?;
+
+        self.kernel_specifications_for_worktree(worktree_id)
+            .find(|kernel_specification| {
+                kernel_specification.name().to_lowercase() == kernel.to_lowercase()
+            })
+            .cloned()
+    }
+
     fn kernelspec_legacy_by_lang_only(
         &self,
         language_at_cursor: Arc<Language>,
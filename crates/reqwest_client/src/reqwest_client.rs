@@ -5,7 +5,7 @@ use std::{any::type_name, borrow::Cow, mem, pin::Pin, task::Poll, time::Duration
 use anyhow::anyhow;
 use bytes::{BufMut, Bytes, BytesMut};
 use futures::{AsyncRead, FutureExt as _, TryStreamExt as _};
-use http_client::{RedirectPolicy, Url, http};
+use http_client::{RedirectPolicy, Url, http, is_proxy_bypassed, read_no_proxy_from_env};
 use regex::Regex;
 use reqwest::{
     header::{HeaderMap, HeaderValue},
@@ -44,7 +44,14 @@ impl ReqwestClient {
         Ok(client.into())
     }
 
-    pub fn proxy_and_user_agent(proxy: Option<Url>, user_agent: &str) -> anyhow::Result<Self> {
+    /// Creates a client that sends requests through `proxy`, except to the hosts matched by
+    /// `no_proxy`, a comma-separated list in the format of the `NO_PROXY` environment variable.
+    /// When `no_proxy` isn't given, the `NO_PROXY` environment variable is used.
+    pub fn proxy_and_user_agent(
+        proxy: Option<Url>,
+        no_proxy: Option<&str>,
+        user_agent: &str,
+    ) -> anyhow::Result<Self> {
         let user_agent = HeaderValue::from_str(user_agent)?;
 
         let mut map = HeaderMap::new();
@@ -52,7 +59,7 @@ impl ReqwestClient {
         let mut client = Self::builder().default_headers(map);
         let client_has_proxy;
 
-        if let Some(proxy) = proxy.as_ref().and_then(|proxy_url| {
+        if let Some(proxy_url) = proxy.clone().filter(|proxy_url| {
             reqwest::Proxy::all(proxy_url.clone())
                 .inspect_err(|e| {
                     log::error!(
@@ -61,10 +68,20 @@ impl ReqwestClient {
                         e.source().unwrap_or(&e as &_)
                     )
                 })
-                .ok()
+                .is_ok()
         }) {
-            // Respect NO_PROXY env var
-            client = client.proxy(proxy.no_proxy(reqwest::NoProxy::from_env()));
+            // Bypassed hosts are matched like they are for connections that don't go through
+            // reqwest, such as the one to collab.
+            let no_proxy = no_proxy
+                .map(ToOwned::to_owned)
+                .or_else(read_no_proxy_from_env);
+            client = client.proxy(reqwest::Proxy::custom(move |url| {
+                let bypassed = no_proxy
+                    .as_deref()
+                    .zip(url.host_str())
+                    .is_some_and(|(no_proxy, host)| is_proxy_bypassed(host, no_proxy));
+                (!bypassed).then(|| proxy_url.clone())
+            }));
             client_has_proxy = true;
         } else {
             client_has_proxy = false;
@@ -306,34 +323,40 @@ mod tests {
         assert_eq!(client.proxy(), None);
 
         let proxy = Url::parse("http://localhost:10809").unwrap();
-        let client = ReqwestClient::proxy_and_user_agent(Some(proxy.clone()), "test").unwrap();
+        let client =
+            ReqwestClient::proxy_and_user_agent(Some(proxy.clone()), None, "test").unwrap();
         assert_eq!(client.proxy(), Some(&proxy));
 
         let proxy = Url::parse("https://localhost:10809").unwrap();
-        let client = ReqwestClient::proxy_and_user_agent(Some(proxy.clone()), "test").unwrap();
+        let client =
+            ReqwestClient::proxy_and_user_agent(Some(proxy.clone()), None, "test").unwrap();
         assert_eq!(client.proxy(), Some(&proxy));
 
         let proxy = Url::parse("socks4://localhost:10808").unwrap();
-        let client = ReqwestClient::proxy_and_user_agent(Some(proxy.clone()), "test").unwrap();
+        let client =
+            ReqwestClient::proxy_and_user_agent(Some(proxy.clone()), None, "test").unwrap();
         assert_eq!(client.proxy(), Some(&proxy));
 
         let proxy = Url::parse("socks4a://localhost:10808").unwrap();
-        let client = ReqwestClient::proxy_and_user_agent(Some(proxy.clone()), "test").unwrap();
+        let client =
+            ReqwestClient::proxy_and_user_agent(Some(proxy.clone()), None, "test").unwrap();
         assert_eq!(client.proxy(), Some(&proxy));
 
         let proxy = Url::parse("socks5://localhost:10808").unwrap();
-        let client = ReqwestClient::proxy_and_user_agent(Some(proxy.clone()), "test").unwrap();
+        let client =
+            ReqwestClient::proxy_and_user_agent(Some(proxy.clone()), None, "test").unwrap();
         assert_eq!(client.proxy(), Some(&proxy));
 
         let proxy = Url::parse("socks5h://localhost:10808").unwrap();
-        let client = ReqwestClient::proxy_and_user_agent(Some(proxy.clone()), "test").unwrap();
+        let client =
+            ReqwestClient::proxy_and_user_agent(Some(proxy.clone()), None, "test").unwrap();
         assert_eq!(client.proxy(), Some(&proxy));
     }
 
     #[test]
     fn test_invalid_proxy_uri() {
         let proxy = Url::parse("socks://127.0.0.1:20170").unwrap();
-        let client = ReqwestClient::proxy_and_user_agent(Some(proxy), "test").unwrap();
+        let client = ReqwestClient::proxy_and_user_agent(Some(proxy), None, "test").unwrap();
         assert!(
             client.proxy.is_none(),
             "An invalid proxy URL should add no proxy to the client!"
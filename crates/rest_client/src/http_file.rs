@@ -0,0 +1,336 @@
+use anyhow::{Context as _, Result};
+use collections::HashMap;
+
+/// How deeply variables may refer to other variables, which also stops variables that refer to
+/// themselves.
+const MAX_SUBSTITUTION_DEPTH: usize = 8;
+
+const METHODS: &[&str] = &[
+    "GET", "POST", "PUT", "PATCH", "DELETE", "HEAD", "OPTIONS", "TRACE", "CONNECT",
+];
+
+/// A request in an `.http` file, as written, before variables are substituted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HttpRequest {
+    /// The row of the request line.
+    pub row: u32,
+    pub method: String,
+    pub url: String,
+    pub headers: Vec<(String, String)>,
+    pub body: Option<String>,
+}
+
+/// The requests in an `.http` file, and the variables it defines.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct HttpFile {
+    /// The variables defined in the file with `@name = value`, which apply to every request.
+    pub variables: HashMap<String, String>,
+    pub requests: Vec<HttpRequest>,
+}
+
+impl HttpFile {
+    /// The request the given row is part of, which is the last one starting at or above it.
+    pub fn request_at_row(&self, row: u32) -> Option<&HttpRequest> {
+        self.requests
+            .iter()
+            .take_while(|request| request.row <= row)
+            .last()
+    }
+}
+
+enum ParseState {
+    BeforeRequest,
+    Headers,
+    Body,
+}
+
+fn is_comment(line: &str) -> bool {
+    line.starts_with('#') || line.starts_with("//")
+}
+
+/// Parses an `.http` file, in the format shared by the common REST client extensions: requests
+/// separated by `###` lines, each with a request line, headers, a blank line and a body.
+pub fn parse(text: &str) -> HttpFile {
+    let mut http_file = HttpFile::default();
+    let mut request: Option<HttpRequest> = None;
+    let mut body_lines: Vec<&str> = Vec::new();
+    let mut state = ParseState::BeforeRequest;
+
+    for (row, line) in text.lines().enumerate() {
+        let trimmed = line.trim();
+        if trimmed.starts_with("###") {
+            finish_request(&mut http_file, request.take(), &mut body_lines);
+            state = ParseState::BeforeRequest;
+            continue;
+        }
+
+        match state {
+            ParseState::BeforeRequest => {
+                if trimmed.is_empty() || is_comment(trimmed) {
+                    continue;
+                }
+                if let Some(definition) = trimmed.strip_prefix('@') {
+                    if let Some((name, value)) = definition.split_once('=') {
+                        http_file
+                            .variables
+                            .insert(name.trim().to_string(), value.trim().to_string());
+                    }
+                    continue;
+                }
+
+                let mut parts = trimmed.split_whitespace();
+                let (method, url) = match parts.next() {
+                    Some(first) if METHODS.contains(&first.to_ascii_uppercase().as_str()) => {
+                        (first.to_ascii_uppercase(), parts.next())
+                    }
+                    first => ("GET".to_string(), first),
+                };
+                let Some(url) = url else {
+                    continue;
+                };
+                request = Some(HttpRequest {
+                    row: row as u32,
+                    method,
+                    url: url.to_string(),
+                    headers: Vec::new(),
+                    body: None,
+                });
+                state = ParseState::Headers;
+            }
+            ParseState::Headers => {
+                let Some(request) = request.as_mut() else {
+                    continue;
+                };
+                if trimmed.is_empty() {
+                    state = ParseState::Body;
+                } else if is_comment(trimmed) {
+                    continue;
+                } else if request.headers.is_empty()
+                    && (trimmed.starts_with('?') || trimmed.starts_with('&'))
+                {
+                    // Long query strings can be continued on the lines after the request line.
+                    request.url.push_str(trimmed);
+                } else if let Some((name, value)) = trimmed.split_once(':') {
+                    request
+                        .headers
+                        .push((name.trim().to_string(), value.trim().to_string()));
+                }
+            }
+            ParseState::Body => body_lines.push(line),
+        }
+    }
+    finish_request(&mut http_file, request, &mut body_lines);
+
+    http_file
+}
+
+fn finish_request(
+    http_file: &mut HttpFile,
+    request: Option<HttpRequest>,
+    body_lines: &mut Vec<&str>,
+) {
+    while body_lines.last().is_some_and(|line| line.trim().is_empty()) {
+        body_lines.pop();
+    }
+    if let Some(mut request) = request {
+        if !body_lines.is_empty() {
+            request.body = Some(body_lines.join("\n"));
+        }
+        http_file.requests.push(request);
+    }
+    body_lines.clear();
+}
+
+/// Parses the variables in a `.env` file.
+pub fn parse_env_file(text: &str) -> HashMap<String, String> {
+    text.lines()
+        .filter_map(|line| {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                return None;
+            }
+            let line = line.strip_prefix("export ").unwrap_or(line);
+            let (name, value) = line.split_once('=')?;
+            let value = value.trim();
+            let value = value
+                .strip_prefix('"')
+                .and_then(|value| value.strip_suffix('"'))
+                .or_else(|| {
+                    value
+                        .strip_prefix('\'')
+                        .and_then(|value| value.strip_suffix('\''))
+                })
+                .unwrap_or(value);
+            Some((name.trim().to_string(), value.to_string()))
+        })
+        .collect()
+}
+
+/// Replaces the `{{name}}` references in `text` with the values of the variables they name.
+pub fn substitute(text: &str, variables: &HashMap<String, String>) -> Result<String> {
+    substitute_at_depth(text, variables, 0)
+}
+
+fn substitute_at_depth(
+    text: &str,
+    variables: &HashMap<String, String>,
+    depth: usize,
+) -> Result<String> {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find("{{") {
+        let Some(length) = rest[start + 2..].find("}}") else {
+            break;
+        };
+        let name = rest[start + 2..start + 2 + length].trim();
+        let value = variables
+            .get(name)
+            .with_context(|| format!("Undefined variable `{name}`"))?;
+        anyhow::ensure!(
+            depth < MAX_SUBSTITUTION_DEPTH,
+            "Variable `{name}` refers to itself"
+        );
+
+        result.push_str(&rest[..start]);
+        result.push_str(&substitute_at_depth(value, variables, depth + 1)?);
+        rest = &rest[start + 2 + length + 2..];
+    }
+    result.push_str(rest);
+    Ok(result)
+}
+
+impl HttpRequest {
+    /// Returns the request with the variables it refers to substituted.
+    pub fn substitute(&self, variables: &HashMap<String, String>) -> Result<Self> {
+        Ok(Self {
+            row: self.row,
+            method: self.method.clone(),
+            url: substitute(&self.url, variables)?,
+            headers: self
+                .headers
+                .iter()
+                .map(|(name, value)| Ok((name.clone(), substitute(value, variables)?)))
+                .collect::<Result<_>>()?,
+            body: self
+                .body
+                .as_deref()
+                .map(|body| substitute(body, variables))
+                .transpose()?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+
+    #[test]
+    fn test_parse_requests() {
+        let http_file = parse(indoc! {r#"
+            @host = https://example.com
+
+            # List the users
+            GET {{host}}/users
+                ?page=2
+                &per_page=10
+            Accept: application/json
+
+            ###
+
+            // Create a user
+            post {{host}}/users HTTP/1.1
+            Content-Type: application/json
+
+            {
+              "name": "Ada"
+            }
+
+
+            ### Without a method
+            {{host}}/health
+        "#});
+
+        assert_eq!(
+            http_file.variables,
+            HashMap::from_iter([("host".to_string(), "https://example.com".to_string())])
+        );
+        assert_eq!(
+            http_file.requests,
+            vec![
+                HttpRequest {
+                    row: 3,
+                    method: "GET".into(),
+                    url: "{{host}}/users?page=2&per_page=10".into(),
+                    headers: vec![("Accept".into(), "application/json".into())],
+                    body: None,
+                },
+                HttpRequest {
+                    row: 11,
+                    method: "POST".into(),
+                    url: "{{host}}/users".into(),
+                    headers: vec![("Content-Type".into(), "application/json".into())],
+                    body: Some("{\n  \"name\": \"Ada\"\n}".into()),
+                },
+                HttpRequest {
+                    row: 20,
+                    method: "GET".into(),
+                    url: "{{host}}/health".into(),
+                    headers: Vec::new(),
+                    body: None,
+                },
+            ]
+        );
+
+        assert_eq!(http_file.request_at_row(0), None);
+        assert_eq!(
+            http_file.request_at_row(8).map(|request| request.row),
+            Some(3)
+        );
+        assert_eq!(
+            http_file.request_at_row(15).map(|request| request.row),
+            Some(11)
+        );
+    }
+
+    #[test]
+    fn test_substitute() {
+        let variables = HashMap::from_iter([
+            ("host".to_string(), "https://{{domain}}".to_string()),
+            ("domain".to_string(), "example.com".to_string()),
+            ("token".to_string(), "secret".to_string()),
+            ("loop".to_string(), "{{loop}}".to_string()),
+        ]);
+
+        assert_eq!(
+            substitute("{{host}}/users?token={{ token }}", &variables).unwrap(),
+            "https://example.com/users?token=secret"
+        );
+        assert_eq!(
+            substitute("unterminated {{host", &variables).unwrap(),
+            "unterminated {{host"
+        );
+        assert!(substitute("{{missing}}", &variables).is_err());
+        assert!(substitute("{{loop}}", &variables).is_err());
+    }
+
+    #[test]
+    fn test_parse_env_file() {
+        let variables = parse_env_file(indoc! {r#"
+            # Credentials
+            TOKEN=abc123
+            export HOST = "https://example.com"
+            NAME='Ada Lovelace'
+            not a variable
+        "#});
+
+        assert_eq!(
+            variables,
+            HashMap::from_iter([
+                ("TOKEN".to_string(), "abc123".to_string()),
+                ("HOST".to_string(), "https://example.com".to_string()),
+                ("NAME".to_string(), "Ada Lovelace".to_string()),
+            ])
+        );
+    }
+}
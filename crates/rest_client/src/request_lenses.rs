@@ -0,0 +1,234 @@
+use std::{any::Any, sync::Arc};
+
+use collections::HashSet;
+use editor::{
+    Anchor, Editor, EditorEvent, ToPoint as _,
+    display_map::{BlockContext, BlockPlacement, BlockProperties, BlockStyle, CustomBlockId},
+};
+use gpui::{App, Context, Entity, KeyContext, Subscription, WeakEntity, Window};
+use language::Point;
+use ui::prelude::*;
+use workspace::{SplitDirection, Workspace};
+
+use crate::{SendRequest, http_file, response_view::ResponseView};
+
+const HTTP_FILE_EXTENSIONS: &[&str] = &["http", "rest"];
+
+/// Shows a "Send Request" button above each request in the editor of an `.http` file.
+struct RequestLensesAddon {
+    /// The start of each request's line, with the block showing its button.
+    lenses: Vec<(Anchor, CustomBlockId)>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl editor::Addon for RequestLensesAddon {
+    fn extend_key_context(&self, key_context: &mut KeyContext, _: &App) {
+        key_context.add("http_file");
+    }
+
+    fn to_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn to_any_mut(&mut self) -> Option<&mut dyn Any> {
+        Some(self)
+    }
+}
+
+pub(crate) fn register_editor(editor: &mut Editor, window: &mut Window, cx: &mut Context<Editor>) {
+    if !editor.mode().is_full() {
+        return;
+    }
+    let Some(buffer) = editor.buffer().read(cx).as_singleton() else {
+        return;
+    };
+    let is_http_file = buffer
+        .read(cx)
+        .file()
+        .and_then(|file| file.path().extension())
+        .is_some_and(|extension| HTTP_FILE_EXTENSIONS.contains(&extension));
+    if !is_http_file {
+        return;
+    }
+
+    let editor_handle = cx.weak_entity();
+    let send_request_action = editor.register_action(move |_: &SendRequest, window, cx| {
+        let Some(editor) = editor_handle.upgrade() else {
+            return;
+        };
+        let row = editor.update(cx, |editor, cx| {
+            editor.selections.newest::<Point>(cx).head().row
+        });
+        send_request(&editor, row, window, cx);
+    });
+    let edit_subscription = cx.subscribe_in(
+        &cx.entity(),
+        window,
+        |editor, _, event: &EditorEvent, _, cx| {
+            if let EditorEvent::BufferEdited = event {
+                update_lenses(editor, cx);
+            }
+        },
+    );
+
+    editor.register_addon(RequestLensesAddon {
+        lenses: Vec::new(),
+        _subscriptions: vec![send_request_action, edit_subscription],
+    });
+    update_lenses(editor, cx);
+}
+
+/// Moves the buttons to the requests' lines, if the requests were added, removed or moved.
+fn update_lenses(editor: &mut Editor, cx: &mut Context<Editor>) {
+    let Some(buffer) = editor.buffer().read(cx).as_singleton() else {
+        return;
+    };
+    let http_file = http_file::parse(&buffer.read(cx).text());
+    let snapshot = editor.buffer().read(cx).snapshot(cx);
+    let Some(addon) = editor.addon::<RequestLensesAddon>() else {
+        return;
+    };
+
+    let rows = http_file
+        .requests
+        .iter()
+        .map(|request| request.row)
+        .collect::<Vec<_>>();
+    let lens_rows = addon
+        .lenses
+        .iter()
+        .map(|(anchor, _)| anchor.to_point(&snapshot).row)
+        .collect::<Vec<_>>();
+    if rows == lens_rows {
+        return;
+    }
+
+    let old_block_ids = addon
+        .lenses
+        .iter()
+        .map(|(_, block_id)| *block_id)
+        .collect::<HashSet<_>>();
+    editor.remove_blocks(old_block_ids, None, cx);
+
+    let editor_handle = cx.weak_entity();
+    let anchors = rows
+        .into_iter()
+        .map(|row| snapshot.anchor_before(Point::new(row, 0)))
+        .collect::<Vec<_>>();
+    let blocks = anchors
+        .iter()
+        .map(|anchor| {
+            let anchor = *anchor;
+            let editor_handle = editor_handle.clone();
+            BlockProperties {
+                placement: BlockPlacement::Above(anchor),
+                height: Some(1),
+                style: BlockStyle::Fixed,
+                render: Arc::new(move |cx| render_lens(anchor, editor_handle.clone(), cx)),
+                priority: 0,
+            }
+        })
+        .collect::<Vec<_>>();
+    let block_ids = editor.insert_blocks(blocks, None, cx);
+
+    if let Some(addon) = editor.addon_mut::<RequestLensesAddon>() {
+        addon.lenses = anchors.into_iter().zip(block_ids).collect();
+    }
+}
+
+fn render_lens(anchor: Anchor, editor: WeakEntity<Editor>, cx: &mut BlockContext) -> AnyElement {
+    h_flex()
+        .id(cx.block_id)
+        .h(cx.line_height)
+        .ml(cx.margins.gutter.width)
+        .items_end()
+        .bg(cx.theme().colors().editor_background)
+        .child(
+            Button::new("send-request", "Send Request")
+                .label_size(LabelSize::Small)
+                .icon(IconName::Send)
+                .icon_size(IconSize::XSmall)
+                .icon_position(IconPosition::Start)
+                .icon_color(Color::Muted)
+                .on_click(move |_, window, cx| {
+                    let Some(editor) = editor.upgrade() else {
+                        return;
+                    };
+                    let snapshot = editor.read(cx).buffer().read(cx).snapshot(cx);
+                    let row = anchor.to_point(&snapshot).row;
+                    send_request(&editor, row, window, cx);
+                }),
+        )
+        .into_any_element()
+}
+
+/// Sends the request the given row is part of, and shows the response beside the editor.
+fn send_request(editor: &Entity<Editor>, row: u32, window: &mut Window, cx: &mut App) {
+    let editor = editor.read(cx);
+    let Some(workspace) = editor.workspace() else {
+        return;
+    };
+    let Some(buffer) = editor.buffer().read(cx).as_singleton() else {
+        return;
+    };
+    let buffer = buffer.read(cx);
+    let http_file = http_file::parse(&buffer.text());
+    let Some(request) = http_file.request_at_row(row).cloned() else {
+        return;
+    };
+    // Secrets are usually kept out of `.http` files, in a `.env` file beside them.
+    let env_path = buffer
+        .file()
+        .and_then(|file| file.as_local())
+        .and_then(|file| Some(file.abs_path(cx).parent()?.join(".env")));
+    let file_variables = http_file.variables;
+
+    workspace.update(cx, |workspace, cx| {
+        let response_view = open_response_view(workspace, window, cx);
+        response_view.update(cx, |response_view, cx| {
+            response_view.send(request, file_variables, env_path, window, cx);
+        });
+    });
+}
+
+/// Reuses the response view if one is open, or opens one in a split to the right.
+fn open_response_view(
+    workspace: &mut Workspace,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) -> Entity<ResponseView> {
+    for pane in workspace.panes().to_vec() {
+        let existing_item = pane
+            .read(cx)
+            .items()
+            .enumerate()
+            .find_map(|(index, item)| Some((index, item.downcast::<ResponseView>()?)));
+        if let Some((index, response_view)) = existing_item {
+            pane.update(cx, |pane, cx| {
+                pane.activate_item(index, false, false, window, cx);
+            });
+            return response_view;
+        }
+    }
+
+    let languages = workspace.project().read(cx).languages().clone();
+    let fs = workspace.app_state().fs.clone();
+    let http_client = cx.http_client();
+    let response_view = cx.new(|cx| ResponseView::new(languages, fs, http_client, window, cx));
+    let pane = workspace.split_pane(
+        workspace.active_pane().clone(),
+        SplitDirection::Right,
+        window,
+        cx,
+    );
+    workspace.add_item(
+        pane,
+        Box::new(response_view.clone()),
+        None,
+        false,
+        false,
+        window,
+        cx,
+    );
+    response_view
+}
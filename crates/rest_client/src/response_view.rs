@@ -0,0 +1,447 @@
+use std::{
+    path::PathBuf,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use anyhow::{Context as _, Result};
+use collections::HashMap;
+use editor::Editor;
+use fs::Fs;
+use futures::AsyncReadExt as _;
+use gpui::{App, Context, Entity, EventEmitter, FocusHandle, Focusable, Task, Window};
+use http_client::{
+    AsyncBody, HttpClient, HttpRequestExt as _, RedirectPolicy, Request, StatusCode,
+};
+use language::LanguageRegistry;
+use ui::{Tooltip, prelude::*};
+use util::size::format_file_size;
+use workspace::item::{Item, ItemEvent};
+
+use crate::http_file::{self, HttpRequest};
+
+/// Bodies larger than this are shown as they were received, as formatting them would make the
+/// editor slow.
+const MAX_FORMATTED_BODY_LENGTH: usize = 1024 * 1024;
+
+/// Shows the response to the last request sent from an `.http` file.
+pub struct ResponseView {
+    languages: Arc<LanguageRegistry>,
+    fs: Arc<dyn Fs>,
+    http_client: Arc<dyn HttpClient>,
+    request_line: SharedString,
+    state: ResponseState,
+    body_editor: Entity<Editor>,
+    show_headers: bool,
+    request_task: Task<()>,
+    language_task: Task<()>,
+}
+
+enum ResponseState {
+    Sending,
+    Received(ReceivedResponse),
+    Failed(SharedString),
+}
+
+struct ReceivedResponse {
+    status: StatusCode,
+    duration: Duration,
+    size: usize,
+    headers: Vec<(SharedString, SharedString)>,
+}
+
+struct Response {
+    request_line: String,
+    status: StatusCode,
+    duration: Duration,
+    headers: Vec<(SharedString, SharedString)>,
+    content_type: Option<String>,
+    body: Vec<u8>,
+}
+
+impl ResponseView {
+    pub fn new(
+        languages: Arc<LanguageRegistry>,
+        fs: Arc<dyn Fs>,
+        http_client: Arc<dyn HttpClient>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let body_editor = cx.new(|cx| {
+            let mut editor = Editor::multi_line(window, cx);
+            editor.set_show_git_diff_gutter(false, cx);
+            editor.set_show_runnables(false, cx);
+            editor.set_show_breakpoints(false, cx);
+            editor.set_read_only(true);
+            editor
+        });
+
+        Self {
+            languages,
+            fs,
+            http_client,
+            request_line: SharedString::default(),
+            state: ResponseState::Sending,
+            body_editor,
+            show_headers: true,
+            request_task: Task::ready(()),
+            language_task: Task::ready(()),
+        }
+    }
+
+    /// Sends `request`, substituting the variables defined in its file and in the `.env` file at
+    /// `env_path`, and shows the response.
+    pub fn send(
+        &mut self,
+        request: HttpRequest,
+        file_variables: HashMap<String, String>,
+        env_path: Option<PathBuf>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.request_line = format!("{} {}", request.method, request.url).into();
+        self.state = ResponseState::Sending;
+        self.set_body(String::new(), None, window, cx);
+        cx.notify();
+
+        let fs = self.fs.clone();
+        let http_client = self.http_client.clone();
+        self.request_task = cx.spawn_in(window, async move |this, cx| {
+            let response = send_request(request, file_variables, env_path, fs, http_client).await;
+            this.update_in(cx, |this, window, cx| {
+                this.show_response(response, window, cx);
+            })
+            .ok();
+        });
+    }
+
+    fn show_response(
+        &mut self,
+        response: Result<Response>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        match response {
+            Ok(response) => {
+                let (body, language_name) =
+                    format_body(response.content_type.as_deref(), &response.body);
+                self.request_line = response.request_line.into();
+                self.state = ResponseState::Received(ReceivedResponse {
+                    status: response.status,
+                    duration: response.duration,
+                    size: response.body.len(),
+                    headers: response.headers,
+                });
+                self.set_body(body, language_name, window, cx);
+            }
+            Err(error) => self.state = ResponseState::Failed(format!("{error:#}").into()),
+        }
+        cx.notify();
+    }
+
+    fn set_body(
+        &mut self,
+        body: String,
+        language_name: Option<&'static str>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.body_editor.update(cx, |editor, cx| {
+            editor.set_text(body, window, cx);
+        });
+
+        let Some(buffer) = self.body_editor.read(cx).buffer().read(cx).as_singleton() else {
+            return;
+        };
+        let language = language_name.map(|name| self.languages.language_for_name(name));
+        self.language_task = cx.spawn(async move |_, cx| {
+            let language = match language {
+                Some(language) => language.await.ok(),
+                None => None,
+            };
+            buffer
+                .update(cx, |buffer, cx| buffer.set_language(language, cx))
+                .ok();
+        });
+    }
+
+    fn toggle_headers(&mut self, cx: &mut Context<Self>) {
+        self.show_headers = !self.show_headers;
+        cx.notify();
+    }
+
+    fn render_status(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let status = match &self.state {
+            ResponseState::Sending => Label::new("Sending…")
+                .size(LabelSize::Small)
+                .color(Color::Muted)
+                .into_any_element(),
+            ResponseState::Failed(error) => Label::new(error.clone())
+                .size(LabelSize::Small)
+                .color(Color::Error)
+                .into_any_element(),
+            ResponseState::Received(response) => {
+                let status_color = if response.status.is_success() {
+                    Color::Success
+                } else if response.status.is_redirection() {
+                    Color::Warning
+                } else if response.status.is_informational() {
+                    Color::Muted
+                } else {
+                    Color::Error
+                };
+
+                h_flex()
+                    .gap_2()
+                    .child(
+                        Label::new(format!(
+                            "{} {}",
+                            response.status.as_u16(),
+                            response.status.canonical_reason().unwrap_or_default()
+                        ))
+                        .size(LabelSize::Small)
+                        .color(status_color),
+                    )
+                    .child(
+                        Label::new(format!("{} ms", response.duration.as_millis()))
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    )
+                    .child(
+                        Label::new(format_file_size(response.size as u64, true))
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    )
+                    .child(
+                        Button::new(
+                            "toggle-headers",
+                            format!("Headers ({})", response.headers.len()),
+                        )
+                        .label_size(LabelSize::Small)
+                        .toggle_state(self.show_headers)
+                        .tooltip(Tooltip::text("Toggle Response Headers"))
+                        .on_click(cx.listener(|this, _, _, cx| this.toggle_headers(cx))),
+                    )
+                    .into_any_element()
+            }
+        };
+
+        h_flex()
+            .w_full()
+            .px_2()
+            .py_1()
+            .gap_3()
+            .justify_between()
+            .border_b_1()
+            .border_color(cx.theme().colors().border_variant)
+            .child(
+                div().min_w_0().overflow_hidden().child(
+                    Label::new(self.request_line.clone())
+                        .size(LabelSize::Small)
+                        .truncate(),
+                ),
+            )
+            .child(status)
+    }
+
+    fn render_headers(&self, cx: &mut Context<Self>) -> Option<impl IntoElement> {
+        let ResponseState::Received(response) = &self.state else {
+            return None;
+        };
+        if !self.show_headers || response.headers.is_empty() {
+            return None;
+        }
+
+        Some(
+            v_flex()
+                .id("response-headers")
+                .max_h(rems(12.))
+                .overflow_y_scroll()
+                .px_2()
+                .py_1()
+                .border_b_1()
+                .border_color(cx.theme().colors().border_variant)
+                .children(response.headers.iter().map(|(name, value)| {
+                    h_flex()
+                        .gap_2()
+                        .child(
+                            Label::new(name.clone())
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        )
+                        .child(Label::new(value.clone()).size(LabelSize::Small))
+                })),
+        )
+    }
+}
+
+async fn send_request(
+    request: HttpRequest,
+    file_variables: HashMap<String, String>,
+    env_path: Option<PathBuf>,
+    fs: Arc<dyn Fs>,
+    http_client: Arc<dyn HttpClient>,
+) -> Result<Response> {
+    let mut variables = match env_path {
+        Some(env_path) if fs.is_file(&env_path).await => {
+            let content = fs
+                .load(&env_path)
+                .await
+                .with_context(|| format!("reading {}", env_path.display()))?;
+            http_file::parse_env_file(&content)
+        }
+        _ => HashMap::default(),
+    };
+    variables.extend(file_variables);
+    let request = request.substitute(&variables)?;
+    let request_line = format!("{} {}", request.method, request.url);
+
+    let mut builder = Request::builder()
+        .method(request.method.as_str())
+        .uri(request.url.as_str())
+        .follow_redirects(RedirectPolicy::FollowAll);
+    for (name, value) in &request.headers {
+        builder = builder.header(name.as_str(), value.as_str());
+    }
+    let http_request = builder
+        .body(request.body.map(AsyncBody::from).unwrap_or_default())
+        .context("invalid request")?;
+
+    let start = Instant::now();
+    let mut response = http_client.send(http_request).await?;
+    let mut body = Vec::new();
+    response
+        .body_mut()
+        .read_to_end(&mut body)
+        .await
+        .context("reading the response body")?;
+    let duration = start.elapsed();
+
+    let headers = response
+        .headers()
+        .iter()
+        .map(|(name, value)| {
+            (
+                SharedString::from(name.to_string()),
+                SharedString::from(String::from_utf8_lossy(value.as_bytes()).into_owned()),
+            )
+        })
+        .collect();
+    let content_type = response
+        .headers()
+        .get("content-type")
+        .and_then(|content_type| content_type.to_str().ok())
+        .map(|content_type| content_type.to_string());
+
+    Ok(Response {
+        request_line,
+        status: response.status(),
+        duration,
+        headers,
+        content_type,
+        body,
+    })
+}
+
+/// Formats a response body for display, returning it with the name of the language to highlight
+/// it as.
+fn format_body(content_type: Option<&str>, body: &[u8]) -> (String, Option<&'static str>) {
+    let Ok(text) = std::str::from_utf8(body) else {
+        return (
+            format!(
+                "Binary data ({})",
+                format_file_size(body.len() as u64, true)
+            ),
+            None,
+        );
+    };
+
+    let mime_type = content_type
+        .and_then(|content_type| content_type.split(';').next())
+        .map(|mime_type| mime_type.trim().to_ascii_lowercase())
+        .unwrap_or_default();
+
+    if mime_type.ends_with("json") {
+        let formatted = (text.len() <= MAX_FORMATTED_BODY_LENGTH)
+            .then(|| serde_json::from_str::<serde_json::Value>(text).ok())
+            .flatten()
+            .and_then(|value| serde_json::to_string_pretty(&value).ok());
+        (formatted.unwrap_or_else(|| text.to_string()), Some("JSON"))
+    } else if mime_type.ends_with("yaml") {
+        (text.to_string(), Some("YAML"))
+    } else {
+        (text.to_string(), None)
+    }
+}
+
+impl Item for ResponseView {
+    type Event = ();
+
+    fn to_item_events(_event: &Self::Event, _f: impl FnMut(ItemEvent)) {}
+
+    fn tab_content_text(&self, _: usize, _: &App) -> SharedString {
+        "Response".into()
+    }
+
+    fn tab_tooltip_text(&self, _: &App) -> Option<SharedString> {
+        Some(self.request_line.clone())
+    }
+
+    fn tab_icon(&self, _: &Window, _: &App) -> Option<Icon> {
+        Some(Icon::new(IconName::Server))
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        Some("REST Client Response Opened")
+    }
+}
+
+impl EventEmitter<()> for ResponseView {}
+
+impl Focusable for ResponseView {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.body_editor.focus_handle(cx)
+    }
+}
+
+impl Render for ResponseView {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .size_full()
+            .bg(cx.theme().colors().editor_background)
+            .child(self.render_status(cx))
+            .children(self.render_headers(cx))
+            .child(div().flex_1().min_h_0().child(self.body_editor.clone()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_body() {
+        assert_eq!(
+            format_body(
+                Some("application/json; charset=utf-8"),
+                br#"{"name":"Ada","tags":[1,2]}"#
+            ),
+            (
+                "{\n  \"name\": \"Ada\",\n  \"tags\": [\n    1,\n    2\n  ]\n}".to_string(),
+                Some("JSON")
+            )
+        );
+        assert_eq!(
+            format_body(Some("application/problem+json"), b"not json"),
+            ("not json".to_string(), Some("JSON"))
+        );
+        assert_eq!(
+            format_body(Some("text/plain"), b"hello"),
+            ("hello".to_string(), None)
+        );
+        assert_eq!(
+            format_body(None, &[0xff, 0xfe]),
+            ("Binary data (2B)".to_string(), None)
+        );
+    }
+}
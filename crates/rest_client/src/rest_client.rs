@@ -0,0 +1,25 @@
+mod http_file;
+mod request_lenses;
+mod response_view;
+
+use editor::Editor;
+use gpui::{App, actions};
+
+pub use crate::response_view::ResponseView;
+
+actions!(
+    rest_client,
+    [
+        /// Sends the request under the cursor in an `.http` file.
+        SendRequest,
+    ]
+);
+
+pub fn init(cx: &mut App) {
+    cx.observe_new(|editor: &mut Editor, window, cx| {
+        if let Some(window) = window {
+            request_lenses::register_editor(editor, window, cx);
+        }
+    })
+    .detach();
+}
@@ -0,0 +1,228 @@
+use anyhow::{Context as _, Result};
+use collections::HashMap;
+use fs::Fs;
+use std::{path::Path, sync::Arc};
+
+/// A code style scheme exported from a JetBrains IDE as IntelliJ IDEA code
+/// style XML.
+pub struct JetBrainsCodeStyle {
+    pub path: Arc<Path>,
+    general: JetBrainsOptions,
+    /// Keyed by the IDE's language id, like "JavaScript" or "ObjectiveC".
+    languages: HashMap<String, JetBrainsOptions>,
+}
+
+/// The `<option name="..." value="..." />` entries of a code style scheme.
+#[derive(Default, Debug)]
+pub struct JetBrainsOptions {
+    values: HashMap<String, String>,
+}
+
+impl JetBrainsCodeStyle {
+    #[cfg(any(test, feature = "test-support"))]
+    pub fn from_xml(content: &str) -> Result<Self> {
+        Self::parse(Path::new("/example-path/code-style.xml").into(), content)
+    }
+
+    pub async fn load(path: &Path, fs: Arc<dyn Fs>) -> Result<Self> {
+        let content = fs.load(path).await.with_context(|| {
+            format!("Error loading JetBrains code style from {}", path.display())
+        })?;
+        Self::parse(path.into(), &content)
+            .with_context(|| format!("Error parsing JetBrains code style from {}", path.display()))
+    }
+
+    /// Options that apply to all languages.
+    pub fn general_options(&self) -> &JetBrainsOptions {
+        &self.general
+    }
+
+    /// Options for specific languages, along with the IDE's id for each
+    /// language.
+    pub fn language_options(&self) -> impl Iterator<Item = (&str, &JetBrainsOptions)> {
+        self.languages
+            .iter()
+            .map(|(language_id, options)| (language_id.as_str(), options))
+    }
+
+    fn parse(path: Arc<Path>, content: &str) -> Result<Self> {
+        let mut general = JetBrainsOptions::default();
+        let mut languages: HashMap<String, JetBrainsOptions> = HashMap::default();
+        // The language each open element applies to, if any.
+        let mut open_elements: Vec<Option<String>> = Vec::new();
+
+        let mut rest = content;
+        while let Some(start) = rest.find('<') {
+            rest = &rest[start + 1..];
+            if let Some(comment) = rest.strip_prefix("!--") {
+                let end = comment.find("-->").context("unterminated comment")?;
+                rest = &comment[end + "-->".len()..];
+                continue;
+            }
+            let end = tag_end(rest).context("unterminated tag")?;
+            let tag = &rest[..end];
+            rest = &rest[end + 1..];
+
+            // Declarations like `<?xml ... ?>` don't carry any settings.
+            if tag.starts_with('?') || tag.starts_with('!') {
+                continue;
+            }
+            if tag.starts_with('/') {
+                open_elements.pop();
+                continue;
+            }
+
+            let is_self_closing = tag.ends_with('/');
+            let tag = tag.trim_end_matches('/');
+            let (name, attributes) = tag.split_once(char::is_whitespace).unwrap_or((tag, ""));
+            let attributes = parse_attributes(attributes)?;
+
+            let language = match name {
+                "codeStyleSettings" => attributes.get("language").cloned(),
+                _ => language_id_for_custom_settings(name).map(ToString::to_string),
+            }
+            .or_else(|| open_elements.iter().rev().find_map(Clone::clone));
+
+            if name == "option"
+                && let Some(option_name) = attributes.get("name")
+                && let Some(value) = attributes.get("value")
+            {
+                let options = match &language {
+                    Some(language) => languages.entry(language.clone()).or_default(),
+                    None => &mut general,
+                };
+                options.values.insert(option_name.clone(), value.clone());
+            }
+
+            if !is_self_closing {
+                open_elements.push(language);
+            }
+        }
+
+        Ok(Self {
+            path,
+            general,
+            languages,
+        })
+    }
+}
+
+impl JetBrainsOptions {
+    pub fn read_string(&self, option: &str) -> Option<&str> {
+        self.values.get(option).map(String::as_str)
+    }
+
+    pub fn read_bool(&self, option: &str) -> Option<bool> {
+        self.read_string(option)?.parse().ok()
+    }
+
+    pub fn read_u32(&self, option: &str) -> Option<u32> {
+        self.read_string(option)?.parse().ok()
+    }
+}
+
+/// Language-specific options that the IDE stores in their own element rather
+/// than under `<codeStyleSettings language="...">`.
+fn language_id_for_custom_settings(element_name: &str) -> Option<&'static str> {
+    match element_name {
+        "JSCodeStyleSettings" => Some("JavaScript"),
+        "TypeScriptCodeStyleSettings" => Some("TypeScript"),
+        "Python" => Some("Python"),
+        _ => None,
+    }
+}
+
+/// Returns the index of the `>` that ends the tag, skipping any inside
+/// attribute values.
+fn tag_end(tag: &str) -> Option<usize> {
+    let mut quote = None;
+    for (index, character) in tag.char_indices() {
+        match (character, quote) {
+            ('"' | '\'', None) => quote = Some(character),
+            (_, Some(open_quote)) if character == open_quote => quote = None,
+            ('>', None) => return Some(index),
+            _ => {}
+        }
+    }
+    None
+}
+
+fn parse_attributes(mut attributes: &str) -> Result<HashMap<String, String>> {
+    let mut parsed = HashMap::default();
+    loop {
+        attributes = attributes.trim_start();
+        if attributes.is_empty() {
+            return Ok(parsed);
+        }
+        let (name, rest) = attributes
+            .split_once('=')
+            .context("attribute without a value")?;
+        let rest = rest.trim_start();
+        let quote = rest
+            .chars()
+            .next()
+            .filter(|character| matches!(character, '"' | '\''))
+            .context("unquoted attribute value")?;
+        let rest = &rest[1..];
+        let end = rest.find(quote).context("unterminated attribute value")?;
+        parsed.insert(name.trim().to_string(), unescape(&rest[..end]));
+        attributes = &rest[end + 1..];
+    }
+}
+
+fn unescape(value: &str) -> String {
+    value
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_code_style() {
+        let code_style = JetBrainsCodeStyle::from_xml(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+            <code_scheme name="Project" version="173">
+              <!-- <option name="RIGHT_MARGIN" value="1" /> -->
+              <option name="RIGHT_MARGIN" value="100" />
+              <option name="OTHER_INDENT_OPTIONS">
+                <value>
+                  <option name="INDENT_SIZE" value="4" />
+                </value>
+              </option>
+              <Python>
+                <option name="OPTIMIZE_IMPORTS_SORT_IMPORTS" value="false" />
+              </Python>
+              <codeStyleSettings language="JavaScript">
+                <option name="SOFT_MARGINS" value="80,120" />
+                <indentOptions>
+                  <option name="INDENT_SIZE" value="2" />
+                  <option name="USE_TAB_CHARACTER" value="true" />
+                </indentOptions>
+              </codeStyleSettings>
+            </code_scheme>"#,
+        )
+        .unwrap();
+
+        let general = code_style.general_options();
+        assert_eq!(general.read_u32("RIGHT_MARGIN"), Some(100));
+        assert_eq!(general.read_u32("INDENT_SIZE"), Some(4));
+
+        let languages: HashMap<_, _> = code_style.language_options().collect();
+        assert_eq!(languages.len(), 2);
+        assert_eq!(
+            languages["Python"].read_bool("OPTIMIZE_IMPORTS_SORT_IMPORTS"),
+            Some(false)
+        );
+        let javascript = languages["JavaScript"];
+        assert_eq!(javascript.read_string("SOFT_MARGINS"), Some("80,120"));
+        assert_eq!(javascript.read_u32("INDENT_SIZE"), Some(2));
+        assert_eq!(javascript.read_bool("USE_TAB_CHARACTER"), Some(true));
+        assert_eq!(javascript.read_u32("RIGHT_MARGIN"), None);
+    }
+}
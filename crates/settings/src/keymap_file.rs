@@ -949,14 +949,18 @@ impl<'a> KeybindUpdateTarget<'a> {
     }
 }
 
-#[derive(Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Debug)]
+/// Where a keybinding comes from. The discriminants are the bindings' [`KeyBindingMetaIndex`]es,
+/// so they must stay stable; use [`KeybindSource::precedence`] (or `Ord`) to compare sources.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
 pub enum KeybindSource {
-    User,
-    Vim,
-    Base,
+    User = 0,
+    Vim = 1,
+    Base = 2,
     #[default]
-    Default,
-    Unknown,
+    Default = 3,
+    Unknown = 4,
+    /// A project's `.zed/keymap.json`, which is layered over the user keymap.
+    Project = 5,
 }
 
 impl KeybindSource {
@@ -964,10 +968,24 @@ impl KeybindSource {
     const DEFAULT: KeyBindingMetaIndex = KeyBindingMetaIndex(KeybindSource::Default as u32);
     const VIM: KeyBindingMetaIndex = KeyBindingMetaIndex(KeybindSource::Vim as u32);
     const USER: KeyBindingMetaIndex = KeyBindingMetaIndex(KeybindSource::User as u32);
+    const PROJECT: KeyBindingMetaIndex = KeyBindingMetaIndex(KeybindSource::Project as u32);
+
+    /// The rank of this source when bindings conflict. Lower ranks win.
+    pub fn precedence(&self) -> u32 {
+        match self {
+            KeybindSource::Project => 0,
+            KeybindSource::User => 1,
+            KeybindSource::Vim => 2,
+            KeybindSource::Base => 3,
+            KeybindSource::Default => 4,
+            KeybindSource::Unknown => 5,
+        }
+    }
 
     pub fn name(&self) -> &'static str {
         match self {
             KeybindSource::User => "User",
+            KeybindSource::Project => "Project",
             KeybindSource::Default => "Default",
             KeybindSource::Base => "Base",
             KeybindSource::Vim => "Vim",
@@ -978,6 +996,7 @@ impl KeybindSource {
     pub fn meta(&self) -> KeyBindingMetaIndex {
         match self {
             KeybindSource::User => Self::USER,
+            KeybindSource::Project => Self::PROJECT,
             KeybindSource::Default => Self::DEFAULT,
             KeybindSource::Base => Self::BASE,
             KeybindSource::Vim => Self::VIM,
@@ -988,6 +1007,7 @@ impl KeybindSource {
     pub fn from_meta(index: KeyBindingMetaIndex) -> Self {
         match index {
             Self::USER => KeybindSource::User,
+            Self::PROJECT => KeybindSource::Project,
             Self::BASE => KeybindSource::Base,
             Self::DEFAULT => KeybindSource::Default,
             Self::VIM => KeybindSource::Vim,
@@ -996,6 +1016,18 @@ impl KeybindSource {
     }
 }
 
+impl PartialOrd for KeybindSource {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for KeybindSource {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.precedence().cmp(&other.precedence())
+    }
+}
+
 impl From<KeyBindingMetaIndex> for KeybindSource {
     fn from(index: KeyBindingMetaIndex) -> Self {
         Self::from_meta(index)
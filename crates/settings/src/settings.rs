@@ -1,5 +1,6 @@
 mod base_keymap_setting;
 mod editable_setting_control;
+mod jetbrains_import;
 mod keymap_file;
 pub mod merge_from;
 mod settings_content;
@@ -24,10 +25,11 @@ pub use keymap_file::{
 pub use settings_file::*;
 pub use settings_json::*;
 pub use settings_store::{
-    InvalidSettingsError, LocalSettingsKind, Settings, SettingsFile, SettingsKey, SettingsLocation,
-    SettingsStore,
+    InvalidSettingsError, LocalSettingsKind, Settings, SettingsFile, SettingsFileConflict,
+    SettingsKey, SettingsLocation, SettingsStore,
 };
 
+pub use jetbrains_import::{JetBrainsCodeStyle, JetBrainsOptions};
 pub use vscode_import::{VsCodeSettings, VsCodeSettingsSource};
 
 pub use keymap_file::ActionSequence;
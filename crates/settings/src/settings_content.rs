@@ -62,6 +62,9 @@ pub struct SettingsContent {
     pub tab_bar: Option<TabBarSettingsContent>,
     pub status_bar: Option<StatusBarSettingsContent>,
 
+    /// Settings for the popup listing possible continuations of a pending keybinding.
+    pub which_key: Option<WhichKeySettingsContent>,
+
     pub preview_tabs: Option<PreviewTabsSettingsContent>,
 
     pub agent: Option<AgentSettingsContent>,
@@ -90,6 +93,9 @@ pub struct SettingsContent {
     /// Configuration for Diagnostics-related features.
     pub diagnostics: Option<DiagnosticsSettingsContent>,
 
+    /// Configuration for the panel that hosts the views contributed by extensions.
+    pub extension_panel: Option<PanelSettingsContent>,
+
     /// Configuration for Git-related features
     pub git: Option<GitSettings>,
 
@@ -108,6 +114,9 @@ pub struct SettingsContent {
 
     pub journal: Option<JournalSettingsContent>,
 
+    /// Settings for the snapshots of edited files that are kept independently of version control.
+    pub local_history: Option<LocalHistorySettingsContent>,
+
     /// A map of log scopes to the desired log level.
     /// Useful for filtering out noisy logs or enabling more verbose logging.
     ///
@@ -133,6 +142,12 @@ pub struct SettingsContent {
 
     pub proxy: Option<String>,
 
+    /// A comma-separated list of hosts that are connected to directly rather than through the
+    /// proxy, in the format of the `NO_PROXY` environment variable.
+    ///
+    /// Default: null
+    pub no_proxy: Option<String>,
+
     /// The URL of the Zed server to connect to.
     pub server_url: Option<String>,
 
@@ -144,8 +159,14 @@ pub struct SettingsContent {
     /// Configuration of the terminal in Zed.
     pub terminal: Option<TerminalSettingsContent>,
 
+    /// Configuration for the panel that lists the project's tests.
+    pub test_explorer: Option<PanelSettingsContent>,
+
     pub title_bar: Option<TitleBarSettingsContent>,
 
+    /// Configuration for the panel that shows the supertypes and subtypes of a type.
+    pub type_hierarchy: Option<PanelSettingsContent>,
+
     /// Whether or not to enable Vim mode.
     ///
     /// Default: false
@@ -710,6 +731,25 @@ pub struct JournalSettingsContent {
     pub hour_format: Option<HourFormat>,
 }
 
+/// Settings for the local history of files.
+#[skip_serializing_none]
+#[derive(Clone, Debug, Default, Serialize, Deserialize, JsonSchema, MergeFrom, PartialEq)]
+pub struct LocalHistorySettingsContent {
+    /// Whether to keep snapshots of files when they are saved or reloaded, and periodically
+    /// while they have unsaved edits.
+    ///
+    /// Default: true
+    pub enabled: Option<bool>,
+    /// How often to snapshot files with unsaved edits, in seconds.
+    ///
+    /// Default: 60
+    pub snapshot_interval_seconds: Option<u64>,
+    /// How much disk space the snapshots may use, in megabytes, before the oldest are deleted.
+    ///
+    /// Default: 256
+    pub max_disk_usage_mb: Option<u64>,
+}
+
 #[derive(Clone, Debug, Default, Serialize, Deserialize, JsonSchema, MergeFrom, PartialEq)]
 #[serde(rename_all = "snake_case")]
 pub enum HourFormat {
@@ -886,6 +926,9 @@ pub struct SshConnection {
     // and then upload it over the SSH connection. Useful if your SSH server has
     // limited outbound internet access.
     pub upload_binary_over_ssh: Option<bool>,
+    // Forward the local SSH agent to the remote server, so that git operations
+    // run there (e.g. pushing over SSH) can authenticate with your local keys.
+    pub forward_agent: Option<bool>,
 
     pub port_forwards: Option<Vec<SshPortForwardOption>>,
 }
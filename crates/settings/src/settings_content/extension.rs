@@ -22,6 +22,20 @@ pub struct ExtensionSettingsContent {
     /// The capabilities granted to extensions.
     #[serde(default)]
     pub granted_extension_capabilities: Option<Vec<ExtensionCapabilityContent>>,
+    /// The capabilities that have been granted to individual extensions, keyed by extension ID.
+    ///
+    /// Zed prompts for these when an extension that declares capabilities is installed or
+    /// upgraded. Until then, the extension is denied the kinds of capabilities it declares.
+    #[serde(default)]
+    pub extension_capability_grants: HashMap<Arc<str>, Vec<ExtensionCapabilityContent>>,
+    /// The URL of a self-hosted extension registry to install and update extensions from,
+    /// instead of Zed's extension registry.
+    ///
+    /// The registry must serve the same API as Zed's extension registry.
+    ///
+    /// Default: null
+    #[serde(default)]
+    pub extension_registry_url: Option<String>,
 }
 
 /// A capability for an extension.
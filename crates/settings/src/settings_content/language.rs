@@ -8,7 +8,7 @@ use serde_with::skip_serializing_none;
 use settings_macros::MergeFrom;
 use std::sync::Arc;
 
-use crate::{merge_from, ExtendingVec};
+use crate::{merge_from, ExtendingVec, Shell};
 
 #[skip_serializing_none]
 #[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
@@ -22,11 +22,16 @@ pub struct AllLanguageSettingsContent {
     /// Settings for associating file extensions and filenames
     /// with languages.
     pub file_types: Option<HashMap<Arc<str>, ExtendingVec<String>>>,
+    /// File sizes above which features are disabled, to keep large files
+    /// responsive.
+    pub large_file_thresholds: Option<LargeFileThresholdsContent>,
 }
 
 impl merge_from::MergeFrom for AllLanguageSettingsContent {
     fn merge_from(&mut self, other: &Self) {
         self.file_types.merge_from(&other.file_types);
+        self.large_file_thresholds
+            .merge_from(&other.large_file_thresholds);
 
         // A user's global settings override the default global settings and
         // all default language-specific settings.
@@ -50,6 +55,25 @@ impl merge_from::MergeFrom for AllLanguageSettingsContent {
     }
 }
 
+/// File sizes, in bytes, above which features are disabled for a file. A
+/// threshold of 0 never disables the feature.
+#[skip_serializing_none]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema, MergeFrom)]
+pub struct LargeFileThresholdsContent {
+    /// The size above which inlay hints are disabled.
+    ///
+    /// Default: 5000000
+    pub inlay_hints: Option<u64>,
+    /// The size above which words from the buffer aren't completed.
+    ///
+    /// Default: 5000000
+    pub word_completions: Option<u64>,
+    /// The size above which indent guides are hidden.
+    ///
+    /// Default: 20000000
+    pub indent_guides: Option<u64>,
+}
+
 /// Controls the soft-wrapping behavior in the editor.
 #[derive(
     Copy,
@@ -102,6 +126,11 @@ pub struct LanguageSettingsContent {
     ///
     /// Default: 80
     pub preferred_line_length: Option<u32>,
+    /// The column at which the rewrap action wraps comments, when it should
+    /// differ from the one for code, given by 'preferred_line_length'.
+    ///
+    /// Default: null
+    pub comment_wrap_column: Option<u32>,
     /// Whether to show wrap guides in the editor. Setting this to true will
     /// show a guide at the 'preferred_line_length' value if softwrap is set to
     /// 'preferred_line_length', and will show any additional guides as specified
@@ -119,6 +148,13 @@ pub struct LanguageSettingsContent {
     ///
     /// Default: on
     pub format_on_save: Option<FormatOnSave>,
+    /// How long to wait for formatting when saving, in milliseconds, before
+    /// saving without it.
+    ///
+    /// Set to 0 to wait for formatting indefinitely.
+    ///
+    /// Default: 5000
+    pub format_on_save_timeout_ms: Option<u64>,
     /// Whether or not to remove any trailing whitespace from lines of a buffer
     /// before saving it.
     ///
@@ -129,6 +165,14 @@ pub struct LanguageSettingsContent {
     ///
     /// Default: true
     pub ensure_final_newline_on_save: Option<bool>,
+    /// The encoding to save new files with.
+    ///
+    /// Default: utf-8
+    pub default_encoding: Option<FileEncoding>,
+    /// The line ending to save new files with.
+    ///
+    /// Default: platform
+    pub default_line_ending: Option<DefaultLineEnding>,
     /// How to perform a buffer format.
     ///
     /// Default: auto
@@ -165,8 +209,13 @@ pub struct LanguageSettingsContent {
     pub show_whitespaces: Option<ShowWhitespaceSetting>,
     /// Visible characters used to render whitespace when show_whitespaces is enabled.
     ///
-    /// Default: "•" for spaces, "→" for tabs.
+    /// Default: "•" for spaces, "→" for tabs, "□" for ideographic spaces, "°" for
+    /// non-breaking spaces and "¦" for zero-width characters.
     pub whitespace_map: Option<WhitespaceMapContent>,
+    /// Which classes of whitespace to render when show_whitespaces is enabled.
+    ///
+    /// Default: all of them
+    pub whitespace_classes: Option<WhitespaceClassesContent>,
     /// Whether to start a new line with a comment when a previous line is a comment as well.
     ///
     /// Default: true
@@ -196,11 +245,11 @@ pub struct LanguageSettingsContent {
     ///
     /// Default: true
     pub use_on_type_format: Option<bool>,
-    /// Which code actions to run on save after the formatter.
+    /// Which code actions to run on save before the formatter, in order.
     /// These are not run if formatting is off.
     ///
-    /// Default: {} (or {"source.organizeImports": true} for Go).
-    pub code_actions_on_format: Option<HashMap<String, bool>>,
+    /// Default: [] (or [{"action": "source.organizeImports"}] for Go).
+    pub code_actions_on_format: Option<Vec<CodeActionOnFormat>>,
     /// Whether to perform linked edits of associated ranges, if the language server supports it.
     /// For example, when editing opening <html> tag, the contents of the closing </html> tag will be edited as well.
     ///
@@ -230,10 +279,41 @@ pub struct LanguageSettingsContent {
     pub show_completion_documentation: Option<bool>,
     /// Controls how completions are processed for this language.
     pub completions: Option<CompletionSettingsContent>,
+    /// Controls how spelling is checked for this language.
+    pub spellcheck: Option<SpellcheckSettingsContent>,
     /// Preferred debuggers for this language.
     ///
     /// Default: []
     pub debuggers: Option<Vec<String>>,
+    /// Defaults for debug sessions started for this language without a
+    /// scenario from `debug.json`.
+    pub debug: Option<LanguageDebugSettingsContent>,
+    /// Preferences for the Jupyter kernel used by the REPL for this language.
+    pub repl: Option<LanguageReplSettingsContent>,
+    /// Extra keymap contexts to add to editors for this language, so that key
+    /// bindings can target it (e.g. "Editor && markdown_prose").
+    ///
+    /// Default: []
+    pub key_context: Option<Vec<String>>,
+    /// Settings that apply only to files of this language whose paths match
+    /// one of the given globs, e.g. a different tab size for `*.min.js`.
+    ///
+    /// The first entry whose globs match the file's path is used.
+    ///
+    /// Default: []
+    pub overrides: Option<Vec<LanguageSettingsOverrideContent>>,
+}
+
+/// Language settings that apply to files matching a set of globs.
+#[skip_serializing_none]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema, MergeFrom)]
+pub struct LanguageSettingsOverrideContent {
+    /// Globs matched against the file's path relative to its worktree root.
+    pub globs: Vec<String>,
+    /// The settings to apply on top of the language's settings.
+    ///
+    /// Nested `overrides` are ignored.
+    pub settings: Box<LanguageSettingsContent>,
 }
 
 /// Controls how whitespace should be displayedin the editor.
@@ -267,6 +347,9 @@ pub enum ShowWhitespaceSetting {
     Boundary,
     /// Draw whitespaces only after non-whitespace characters.
     Trailing,
+    /// Draw only whitespace that is easily mistaken for a regular space:
+    /// ideographic spaces, non-breaking spaces and zero-width characters.
+    Unusual,
 }
 
 #[skip_serializing_none]
@@ -274,6 +357,23 @@ pub enum ShowWhitespaceSetting {
 pub struct WhitespaceMapContent {
     pub space: Option<char>,
     pub tab: Option<char>,
+    /// The full-width space (U+3000) used alongside CJK text.
+    pub ideographic_space: Option<char>,
+    /// Spaces that prevent line breaks, like U+00A0 and U+202F.
+    pub non_breaking_space: Option<char>,
+    /// Characters without any width, like U+200B and the byte order mark.
+    pub zero_width: Option<char>,
+}
+
+/// Toggles for each class of whitespace in [`WhitespaceMapContent`].
+#[skip_serializing_none]
+#[derive(Clone, Debug, Default, Serialize, Deserialize, JsonSchema, MergeFrom, PartialEq)]
+pub struct WhitespaceClassesContent {
+    pub space: Option<bool>,
+    pub tab: Option<bool>,
+    pub ideographic_space: Option<bool>,
+    pub non_breaking_space: Option<bool>,
+    pub zero_width: Option<bool>,
 }
 
 /// The behavior of `editor::Rewrap`.
@@ -308,6 +408,29 @@ pub struct JsxTagAutoCloseSettingsContent {
     pub enabled: Option<bool>,
 }
 
+/// A code action to run when formatting.
+#[skip_serializing_none]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+pub struct CodeActionOnFormat {
+    /// The kind of code action to run, like "source.organizeImports".
+    pub action: String,
+    /// How long to wait for the code action, in milliseconds, before skipping
+    /// it and continuing with the rest of the formatting.
+    ///
+    /// Default: null
+    pub timeout_ms: Option<u64>,
+}
+
+impl CodeActionOnFormat {
+    /// Returns a code action to run with the default timeout.
+    pub fn new(action: impl Into<String>) -> Self {
+        Self {
+            action: action.into(),
+            timeout_ms: None,
+        }
+    }
+}
+
 /// The settings for inlay hints.
 #[skip_serializing_none]
 #[derive(Clone, Default, Debug, Serialize, Deserialize, JsonSchema, MergeFrom, PartialEq, Eq)]
@@ -357,10 +480,28 @@ pub struct InlayHintSettingsContent {
     ///
     /// Default: null
     pub toggle_on_modifiers_press: Option<Modifiers>,
+    /// The maximum number of characters to show for a hint, longer hints are
+    /// truncated with an ellipsis.
+    ///
+    /// Set to 0 to show hints in full.
+    ///
+    /// Default: 0
+    pub max_hint_length: Option<usize>,
+    /// Kinds of hints to hide from specific language servers, keyed by the
+    /// language server name.
+    ///
+    /// Default: {}
+    pub excluded_kinds_per_server: Option<HashMap<String, Vec<InlayHintKind>>>,
+    /// Regular expressions for hints to hide. A hint is hidden if any of the
+    /// patterns matches its text.
+    ///
+    /// Default: []
+    pub suppress_patterns: Option<Vec<String>>,
 }
 
 /// The kind of an inlay hint.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
 pub enum InlayHintKind {
     /// An inlay hint for a type.
     Type,
@@ -420,6 +561,106 @@ pub struct CompletionSettingsContent {
     pub lsp_insert_mode: Option<LspInsertMode>,
 }
 
+/// The character encoding of a file, named as in EditorConfig's `charset`.
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    Default,
+    Serialize,
+    Deserialize,
+    PartialEq,
+    Eq,
+    JsonSchema,
+    MergeFrom,
+    strum::VariantArray,
+    strum::VariantNames,
+)]
+pub enum FileEncoding {
+    #[default]
+    #[serde(rename = "utf-8")]
+    Utf8,
+    /// UTF-8, preceded by a byte order mark.
+    #[serde(rename = "utf-8-bom")]
+    Utf8Bom,
+    #[serde(rename = "utf-16le")]
+    Utf16Le,
+    #[serde(rename = "utf-16be")]
+    Utf16Be,
+    #[serde(rename = "latin1")]
+    Latin1,
+}
+
+/// The line ending to save new files with.
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    Default,
+    Serialize,
+    Deserialize,
+    PartialEq,
+    Eq,
+    JsonSchema,
+    MergeFrom,
+    strum::VariantArray,
+    strum::VariantNames,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum DefaultLineEnding {
+    /// Unix line endings (`\n`).
+    Lf,
+    /// Windows line endings (`\r\n`).
+    Crlf,
+    /// The line ending of the platform that Zed is running on.
+    #[default]
+    Platform,
+}
+
+/// Controls how spelling is checked for this language.
+#[skip_serializing_none]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, JsonSchema, MergeFrom, Default)]
+#[serde(rename_all = "snake_case")]
+pub struct SpellcheckSettingsContent {
+    /// Whether to check spelling.
+    ///
+    /// Default: false
+    pub enabled: Option<bool>,
+    /// The dictionaries to check words against, by locale.
+    ///
+    /// Default: ["en_US"]
+    pub dictionaries: Option<Vec<String>>,
+    /// Which parts of the code to check.
+    ///
+    /// Default: ["comments", "strings"]
+    pub targets: Option<Vec<SpellcheckTarget>>,
+}
+
+/// A part of the code whose spelling can be checked.
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    Serialize,
+    Deserialize,
+    PartialEq,
+    Eq,
+    Hash,
+    JsonSchema,
+    MergeFrom,
+    strum::VariantArray,
+    strum::VariantNames,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum SpellcheckTarget {
+    /// Comments, including doc comments.
+    Comments,
+    /// String literals.
+    Strings,
+    /// Names of variables, functions, types and so on.
+    Identifiers,
+}
+
 #[derive(
     Copy,
     Clone,
@@ -524,6 +765,8 @@ pub enum FormatOnSave {
 pub enum FormatterList {
     Single(Formatter),
     Vec(Vec<Formatter>),
+    /// A chain of formatters, some of which say what to do when they fail.
+    Steps(Vec<FormatterStep>),
 }
 
 impl Default for FormatterList {
@@ -532,13 +775,62 @@ impl Default for FormatterList {
     }
 }
 
-impl AsRef<[Formatter]> for FormatterList {
-    fn as_ref(&self) -> &[Formatter] {
-        match &self {
-            Self::Single(single) => std::slice::from_ref(single),
-            Self::Vec(v) => v,
+impl FormatterList {
+    /// Returns the formatters in the order that they run, along with what to
+    /// do when each of them fails.
+    pub fn steps(&self) -> Vec<(&Formatter, FormatterErrorPolicy)> {
+        match self {
+            Self::Single(formatter) => vec![(formatter, FormatterErrorPolicy::default())],
+            Self::Vec(formatters) => formatters
+                .iter()
+                .map(|formatter| (formatter, FormatterErrorPolicy::default()))
+                .collect(),
+            Self::Steps(steps) => steps.iter().map(FormatterStep::as_parts).collect(),
         }
     }
+
+    /// Returns whether any step in the chain uses the given formatter.
+    pub fn contains(&self, formatter: &Formatter) -> bool {
+        self.steps().into_iter().any(|(step, _)| step == formatter)
+    }
+}
+
+/// A formatter in a chain of formatters.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, JsonSchema, MergeFrom)]
+#[serde(untagged)]
+pub enum FormatterStep {
+    /// A formatter with an explicit policy for when it fails.
+    WithPolicy {
+        formatter: Formatter,
+        on_error: FormatterErrorPolicy,
+    },
+    /// A formatter that aborts the rest of the chain when it fails.
+    Formatter(Formatter),
+}
+
+impl FormatterStep {
+    fn as_parts(&self) -> (&Formatter, FormatterErrorPolicy) {
+        match self {
+            Self::WithPolicy {
+                formatter,
+                on_error,
+            } => (formatter, *on_error),
+            Self::Formatter(formatter) => (formatter, FormatterErrorPolicy::default()),
+        }
+    }
+}
+
+/// What to do when a formatter in a chain fails.
+#[derive(
+    Copy, Clone, Default, Debug, Serialize, Deserialize, PartialEq, Eq, JsonSchema, MergeFrom,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum FormatterErrorPolicy {
+    /// Stop formatting, keeping the changes made by the previous formatters.
+    #[default]
+    Abort,
+    /// Log the error and continue with the next formatter.
+    Skip,
 }
 
 /// Controls which formatter should be used when formatting code. If there are multiple formatters, they are executed in the order of declaration.
@@ -670,6 +962,89 @@ pub struct LanguageTaskSettingsContent {
     /// * Zed task from either of the task config file
     /// * Zed task from history (e.g. one-off task was spawned before)
     pub prefer_lsp: Option<bool>,
+    /// The shell to run tasks in, for tasks that don't specify their own.
+    ///
+    /// Default: null, which uses the system shell
+    pub shell: Option<Shell>,
+    /// Dotenv files to load into the environment of tasks, relative to the
+    /// worktree root. Variables from later files take precedence.
+    ///
+    /// Default: []
+    pub env_files: Option<Vec<String>>,
+    /// The directory to run tasks in, for tasks that don't specify their own
+    /// `cwd`.
+    ///
+    /// Default: "worktree_root"
+    pub cwd_strategy: Option<TaskCwdStrategy>,
+}
+
+/// How to pick the directory that tasks run in.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    JsonSchema,
+    MergeFrom,
+    strum::VariantArray,
+    strum::VariantNames,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskCwdStrategy {
+    /// Run tasks in the root of the file's worktree.
+    #[default]
+    WorktreeRoot,
+    /// Run tasks in the directory containing the file.
+    FileDirectory,
+    /// Run tasks in the nearest directory above the file that contains a
+    /// package manifest, like `Cargo.toml` or `package.json`, falling back to
+    /// the worktree root.
+    PackageRoot,
+}
+
+/// The defaults for debug sessions of a particular language.
+#[skip_serializing_none]
+#[derive(Debug, Clone, Default, Deserialize, PartialEq, Serialize, JsonSchema, MergeFrom)]
+pub struct LanguageDebugSettingsContent {
+    /// The program to launch, along with its arguments. Task variables like
+    /// `$ZED_FILE` are substituted when the session starts.
+    ///
+    /// Default: null
+    pub program: Option<String>,
+    /// Environment variables to launch the program with.
+    ///
+    /// Default: {}
+    pub env: Option<HashMap<String, String>>,
+    /// The directory to launch the program in, which can also use task
+    /// variables.
+    ///
+    /// Default: null
+    pub cwd: Option<String>,
+}
+
+/// The REPL preferences of a particular language.
+#[skip_serializing_none]
+#[derive(Debug, Clone, Default, Deserialize, PartialEq, Serialize, JsonSchema, MergeFrom)]
+pub struct LanguageReplSettingsContent {
+    /// The name of the kernel to use, like `python3` or the name of a
+    /// kernelspec installed into a virtual environment. Falls back to the
+    /// first kernel for the language when no kernel has that name.
+    ///
+    /// Default: null
+    pub kernel: Option<String>,
+    /// Code to run silently in every kernel started for this language.
+    ///
+    /// Default: null
+    pub startup_code: Option<String>,
+    /// Whether to start a kernel as soon as an editor for this language is
+    /// focused, rather than on the first run.
+    ///
+    /// Default: false
+    pub auto_attach: Option<bool>,
 }
 
 /// Map from language name to settings.
@@ -785,6 +1160,53 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_formatter_steps_deserialization() {
+        let raw = r#"{"formatter": [
+            "language_server",
+            {"formatter": {"external": {"command": "sed"}}, "on_error": "skip"}
+        ]}"#;
+        let settings: LanguageSettingsContent = serde_json::from_str(raw).unwrap();
+        let formatter = settings.formatter.unwrap();
+        let external = Formatter::External {
+            command: "sed".into(),
+            arguments: None,
+        };
+        assert_eq!(
+            formatter,
+            FormatterList::Steps(vec![
+                FormatterStep::Formatter(Formatter::LanguageServer(
+                    LanguageServerFormatterSpecifier::Current
+                )),
+                FormatterStep::WithPolicy {
+                    formatter: external.clone(),
+                    on_error: FormatterErrorPolicy::Skip,
+                },
+            ])
+        );
+        assert_eq!(
+            formatter.steps(),
+            vec![
+                (
+                    &Formatter::LanguageServer(LanguageServerFormatterSpecifier::Current),
+                    FormatterErrorPolicy::Abort
+                ),
+                (&external, FormatterErrorPolicy::Skip),
+            ]
+        );
+
+        // Lists without any policies keep their existing representation.
+        let raw = r#"{"formatter": ["prettier", "language_server"]}"#;
+        let settings: LanguageSettingsContent = serde_json::from_str(raw).unwrap();
+        assert_eq!(
+            settings.formatter,
+            Some(FormatterList::Vec(vec![
+                Formatter::Prettier,
+                Formatter::LanguageServer(LanguageServerFormatterSpecifier::Current),
+            ]))
+        );
+    }
+
     #[test]
     fn test_formatter_deserialization_invalid() {
         let raw_auto = "{\"formatter\": {}}";
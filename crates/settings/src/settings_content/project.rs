@@ -45,11 +45,31 @@ pub struct ProjectSettingsContent {
     /// Configuration for how direnv configuration should be loaded
     pub load_direnv: Option<DirenvSettings>,
 
+    /// Environment variables to set for language servers, tasks and Git, on top of the
+    /// environment loaded from the shell.
+    ///
+    /// Default: {}
+    #[serde(default)]
+    pub env: HashMap<String, String>,
+
     /// Settings for slash commands.
     pub slash_commands: Option<SlashCommandSettings>,
 
     /// The list of custom Git hosting providers.
     pub git_hosting_providers: Option<ExtendingVec<GitHostingProviderConfig>>,
+
+    /// Settings for the Markdown preview.
+    pub markdown_preview: Option<MarkdownPreviewSettingsContent>,
+}
+
+/// The settings for the Markdown preview.
+#[skip_serializing_none]
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize, JsonSchema, MergeFrom)]
+pub struct MarkdownPreviewSettingsContent {
+    /// Whether to render `$...$` and `$$...$$` as math, rather than as plain text.
+    ///
+    /// Default: true
+    pub math: Option<bool>,
 }
 
 #[skip_serializing_none]
@@ -246,7 +266,7 @@ impl std::fmt::Debug for ContextServerCommand {
 }
 
 #[skip_serializing_none]
-#[derive(Copy, Clone, Debug, PartialEq, Default, Serialize, Deserialize, JsonSchema, MergeFrom)]
+#[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize, JsonSchema, MergeFrom)]
 pub struct GitSettings {
     /// Whether or not to show the git gutter.
     ///
@@ -271,6 +291,40 @@ pub struct GitSettings {
     ///
     /// Default: staged_hollow
     pub hunk_style: Option<GitHunkStyleSetting>,
+    /// Path to the git executable to run, instead of the one found on the `PATH`.
+    ///
+    /// Default: null
+    pub binary_path: Option<PathBuf>,
+    /// Environment variables to set for git commands, e.g. `GIT_SSH_COMMAND`.
+    ///
+    /// Default: {}
+    pub env: Option<HashMap<String, String>>,
+    /// Configuration values to apply to git commands, as if passed with `git -c key=value`.
+    /// Set them in a project's settings to override the configuration of its repositories.
+    ///
+    /// Default: {}
+    pub config: Option<HashMap<String, String>>,
+    /// How long a git operation can run, in milliseconds, before it's reported as slow.
+    /// Set to 0 to never report slow operations.
+    ///
+    /// Default: 10000
+    pub slow_operation_threshold_ms: Option<u64>,
+    /// How long to wait, in milliseconds, for the host of a shared project to respond to a
+    /// fetch, push or pull, or to report its progress, before giving up. Set to 0 to wait
+    /// indefinitely.
+    ///
+    /// Default: 60000
+    pub remote_request_timeout_ms: Option<u64>,
+    /// How many more times to try a fetch in a shared project after the host didn't respond
+    /// in time. Pushes and pulls aren't retried, as they may have taken effect.
+    ///
+    /// Default: 2
+    pub remote_request_retries: Option<u32>,
+    /// Whether to list the branches and remotes of each repository in the background once it's
+    /// found, so that the branch picker opens without waiting for git.
+    ///
+    /// Default: false
+    pub prefetch_branches: Option<bool>,
 }
 
 #[derive(
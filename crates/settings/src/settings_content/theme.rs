@@ -66,6 +66,9 @@ pub struct ThemeSettingsContent {
     /// The name of the icon theme to use.
     #[serde(default)]
     pub icon_theme: Option<IconThemeSelection>,
+    /// When to switch between the light and dark themes if the `schedule` mode is used.
+    #[serde(default)]
+    pub theme_schedule: Option<ThemeScheduleContent>,
 
     /// UNSTABLE: Expect many elements to be broken.
     ///
@@ -142,6 +145,16 @@ pub enum ThemeSelection {
     },
 }
 
+impl ThemeSelection {
+    /// Returns the [`ThemeMode`] of a dynamic selection.
+    pub fn mode(&self) -> Option<ThemeMode> {
+        match self {
+            ThemeSelection::Static(_) => None,
+            ThemeSelection::Dynamic { mode, .. } => Some(*mode),
+        }
+    }
+}
+
 /// Represents the selection of an icon theme, which can be either static or dynamic.
 #[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, MergeFrom, PartialEq, Eq)]
 #[serde(untagged)]
@@ -160,12 +173,24 @@ pub enum IconThemeSelection {
     },
 }
 
+impl IconThemeSelection {
+    /// Returns the [`ThemeMode`] of a dynamic selection.
+    pub fn mode(&self) -> Option<ThemeMode> {
+        match self {
+            IconThemeSelection::Static(_) => None,
+            IconThemeSelection::Dynamic { mode, .. } => Some(*mode),
+        }
+    }
+}
+
 // TODO: Rename ThemeMode -> ThemeAppearanceMode
 /// The mode use to select a theme.
 ///
 /// `Light` and `Dark` will select their respective themes.
 ///
 /// `System` will select the theme based on the system's appearance.
+///
+/// `Schedule` will select the theme based on the time of day, as configured by `theme_schedule`.
 #[derive(
     Debug, PartialEq, Eq, Clone, Copy, Default, Serialize, Deserialize, JsonSchema, MergeFrom,
 )]
@@ -180,6 +205,34 @@ pub enum ThemeMode {
     /// Use the theme based on the system's appearance.
     #[default]
     System,
+
+    /// Use the theme based on the time of day, as configured by `theme_schedule`.
+    Schedule,
+}
+
+/// When to switch between the light and dark themes in the `schedule` theme mode.
+#[skip_serializing_none]
+#[derive(Clone, Debug, Default, Serialize, Deserialize, JsonSchema, MergeFrom, PartialEq)]
+pub struct ThemeScheduleContent {
+    /// The local time at which to switch to the light theme, in 24-hour `HH:MM` format.
+    ///
+    /// Default: "07:00"
+    pub light_start: Option<String>,
+    /// The local time at which to switch to the dark theme, in 24-hour `HH:MM` format.
+    ///
+    /// Default: "19:00"
+    pub dark_start: Option<String>,
+    /// The latitude of your location, in degrees.
+    ///
+    /// When both `latitude` and `longitude` are set, the theme switches at local
+    /// sunrise and sunset instead of at `light_start` and `dark_start`.
+    ///
+    /// Default: null
+    pub latitude: Option<f64>,
+    /// The longitude of your location, in degrees. Positive values are east of Greenwich.
+    ///
+    /// Default: null
+    pub longitude: Option<f64>,
 }
 
 /// Specifies the density of the UI.
@@ -311,6 +364,12 @@ pub struct ThemeStyleContent {
     /// The styles for syntax nodes.
     #[serde(default)]
     pub syntax: IndexMap<String, HighlightStyleContent>,
+
+    /// The styles for syntax nodes in buffers of specific languages, keyed by language name.
+    ///
+    /// These are applied on top of `syntax`.
+    #[serde(default)]
+    pub language_syntax: HashMap<String, IndexMap<String, HighlightStyleContent>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, MergeFrom, PartialEq)]
@@ -379,6 +379,20 @@ pub struct StatusBarSettingsContent {
     pub cursor_position_button: Option<bool>,
 }
 
+#[skip_serializing_none]
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema, MergeFrom, Debug, PartialEq, Eq)]
+pub struct WhichKeySettingsContent {
+    /// Whether to show a popup listing the possible continuations
+    /// of a partially typed multi-keystroke binding.
+    ///
+    /// Default: false
+    pub enabled: Option<bool>,
+    /// How long to wait after a keystroke before showing the popup, in milliseconds.
+    ///
+    /// Default: 600
+    pub delay_ms: Option<u64>,
+}
+
 #[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq, Eq, JsonSchema, MergeFrom)]
 #[serde(rename_all = "snake_case")]
 pub enum AutosaveSetting {
@@ -14,6 +14,97 @@ pub struct SettingsJsonSchemaParams<'a> {
     pub icon_theme_names: &'a [SharedString],
 }
 
+/// The result of merging two sets of changes to the same JSON file.
+#[derive(Debug, PartialEq)]
+pub struct JsonMerge {
+    pub text: String,
+    /// The dot-separated paths of the keys that both sets of changes set to different values.
+    /// These take the value from `ours`.
+    pub conflicting_keys: Vec<String>,
+}
+
+/// Applies the changes from `base_text` to `ours_text` on top of `theirs_text` key by key, so
+/// that keys only changed in `theirs_text` keep their values, comments and formatting.
+pub fn merge_json_text_changes(
+    base_text: &str,
+    ours_text: &str,
+    theirs_text: &str,
+    tab_size: usize,
+) -> Result<JsonMerge> {
+    let base_value = parse_json_with_comments::<Value>(base_text).unwrap_or(Value::Null);
+    let ours_value = parse_json_with_comments::<Value>(ours_text)?;
+    let theirs_value = parse_json_with_comments::<Value>(theirs_text)?;
+
+    let mut conflicting_keys = Vec::new();
+    let merged_value = merge_json_values(
+        &mut Vec::new(),
+        Some(&base_value),
+        Some(&ours_value),
+        Some(&theirs_value),
+        &mut conflicting_keys,
+    )
+    .unwrap_or(Value::Null);
+
+    let mut text = theirs_text.to_string();
+    update_value_in_json_text(
+        &mut text,
+        &mut Vec::new(),
+        tab_size,
+        &theirs_value,
+        &merged_value,
+        &mut Vec::new(),
+    );
+    Ok(JsonMerge {
+        text,
+        conflicting_keys,
+    })
+}
+
+fn merge_json_values(
+    key_path: &mut Vec<String>,
+    base: Option<&Value>,
+    ours: Option<&Value>,
+    theirs: Option<&Value>,
+    conflicting_keys: &mut Vec<String>,
+) -> Option<Value> {
+    if ours == base {
+        return theirs.cloned();
+    }
+    if theirs == base || ours == theirs {
+        return ours.cloned();
+    }
+    if let (Some(Value::Object(ours_object)), Some(Value::Object(theirs_object))) = (ours, theirs) {
+        let base_object = base.and_then(Value::as_object);
+        let keys = theirs_object
+            .keys()
+            .chain(
+                ours_object
+                    .keys()
+                    .filter(|key| !theirs_object.contains_key(*key)),
+            )
+            .cloned()
+            .collect::<Vec<_>>();
+        let mut merged_object = serde_json::Map::new();
+        for key in keys {
+            key_path.push(key.clone());
+            let merged_value = merge_json_values(
+                key_path,
+                base_object.and_then(|object| object.get(&key)),
+                ours_object.get(&key),
+                theirs_object.get(&key),
+                conflicting_keys,
+            );
+            key_path.pop();
+            if let Some(merged_value) = merged_value {
+                merged_object.insert(key, merged_value);
+            }
+        }
+        return Some(Value::Object(merged_object));
+    }
+    conflicting_keys.push(key_path.join("."));
+    ours.cloned()
+}
+
 pub fn update_value_in_json_text<'a>(
     text: &mut String,
     key_path: &mut Vec<&'a str>,
@@ -2486,4 +2577,71 @@ mod tests {
             .unindent(),
         )
     }
+
+    #[test]
+    fn merge_changes() {
+        let base = r#"{
+            "theme": "One Dark",
+            "buffer_font_size": 14,
+            "terminal": {
+                "font_size": 12
+            }
+        }"#
+        .unindent();
+        // Changed by an update computed from `base`.
+        let ours = r#"{
+            "theme": "One Light",
+            "buffer_font_size": 14,
+            "terminal": {
+                "font_size": 13
+            }
+        }"#
+        .unindent();
+        // Saved to disk while the update was computed.
+        let theirs = r#"{
+            // Keep this comment.
+            "theme": "One Dark",
+            "buffer_font_size": 16,
+            "terminal": {
+                "font_size": 11
+            },
+            "vim_mode": true
+        }"#
+        .unindent();
+
+        let merge = merge_json_text_changes(&base, &ours, &theirs, 4).unwrap();
+        assert_eq!(merge.conflicting_keys, ["terminal.font_size"]);
+        assert!(merge.text.contains("// Keep this comment."));
+        assert_eq!(
+            parse_json_with_comments::<Value>(&merge.text).unwrap(),
+            json!({
+                "theme": "One Light",
+                "buffer_font_size": 16,
+                "terminal": {
+                    "font_size": 13
+                },
+                "vim_mode": true
+            })
+        );
+
+        // Keys removed by either side stay removed, unless the other side changed them.
+        let ours = r#"{
+            "theme": "One Dark",
+            "buffer_font_size": 14
+        }"#
+        .unindent();
+        let theirs = r#"{
+            "buffer_font_size": 14,
+            "terminal": {
+                "font_size": 12
+            }
+        }"#
+        .unindent();
+        let merge = merge_json_text_changes(&base, &ours, &theirs, 4).unwrap();
+        assert!(merge.conflicting_keys.is_empty());
+        assert_eq!(
+            parse_json_with_comments::<Value>(&merge.text).unwrap(),
+            json!({ "buffer_font_size": 14 })
+        );
+    }
 }
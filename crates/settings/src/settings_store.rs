@@ -9,7 +9,10 @@ use futures::{
 };
 use gpui::{App, AsyncApp, BorrowAppContext, Global, Task, UpdateGlobal};
 
-use paths::{EDITORCONFIG_NAME, local_settings_file_relative_path, task_file_name};
+use paths::{
+    EDITORCONFIG_NAME, local_keymap_file_relative_path, local_settings_file_relative_path,
+    task_file_name,
+};
 use schemars::{JsonSchema, json_schema};
 use serde_json::Value;
 use smallvec::SmallVec;
@@ -31,10 +34,11 @@ use util::{
 pub type EditorconfigProperties = ec4rs::Properties;
 
 use crate::{
-    ActiveSettingsProfileName, FontFamilyName, IconThemeName, LanguageSettingsContent,
-    LanguageToSettingsMap, SettingsJsonSchemaParams, ThemeName, VsCodeSettings, WorktreeId,
+    ActiveSettingsProfileName, FontFamilyName, IconThemeName, JetBrainsCodeStyle,
+    LanguageSettingsContent, LanguageToSettingsMap, SettingsJsonSchemaParams, ThemeName,
+    VsCodeSettings, WorktreeId,
     merge_from::MergeFrom,
-    parse_json_with_comments,
+    merge_json_text_changes, parse_json_with_comments,
     settings_content::{
         ExtensionsSettingsContent, ProjectSettingsContent, SettingsContent, UserSettingsContent,
     },
@@ -73,6 +77,10 @@ pub trait Settings: 'static + Send + Sync + Sized {
     /// equivalent settings from a vscode config to our config
     fn import_from_vscode(_vscode: &VsCodeSettings, _current: &mut SettingsContent) {}
 
+    /// Apply known equivalent settings from an exported JetBrains code style to
+    /// our config
+    fn import_from_jetbrains(_jetbrains: &JetBrainsCodeStyle, _current: &mut SettingsContent) {}
+
     #[track_caller]
     fn register(cx: &mut App)
     where
@@ -142,6 +150,9 @@ pub struct SettingsStore {
 
     extension_settings: Option<Box<SettingsContent>>,
     server_settings: Option<Box<SettingsContent>>,
+    /// The settings written to the user settings file by the most recent VS
+    /// Code settings import in this session.
+    vscode_imported_settings: Option<Box<SettingsContent>>,
 
     merged_settings: Rc<SettingsContent>,
 
@@ -151,6 +162,20 @@ pub struct SettingsStore {
     _setting_file_updates: Task<()>,
     setting_file_updates_tx:
         mpsc::UnboundedSender<Box<dyn FnOnce(AsyncApp) -> LocalBoxFuture<'static, Result<()>>>>,
+    file_conflicts_txs: Vec<mpsc::UnboundedSender<SettingsFileConflict>>,
+}
+
+/// Changes to the user settings file that were saved while Zed was updating it, and that Zed's
+/// update set to different values.
+#[derive(Clone, Debug)]
+pub struct SettingsFileConflict {
+    /// The dot-separated paths of the keys that were changed on both sides. These take the value
+    /// from Zed's update.
+    pub keys: Vec<String>,
+    /// The text of the file before Zed's update was merged into it.
+    pub disk_text: String,
+    /// The text Zed wrote.
+    pub merged_text: String,
 }
 
 #[derive(Clone, PartialEq, Debug)]
@@ -188,6 +213,7 @@ pub enum LocalSettingsKind {
     Tasks,
     Editorconfig,
     Debug,
+    Keymap,
 }
 
 impl Global for SettingsStore {}
@@ -212,6 +238,11 @@ trait AnySettingValue: 'static + Send + Sync {
         vscode_settings: &VsCodeSettings,
         settings_content: &mut SettingsContent,
     );
+    fn import_from_jetbrains(
+        &self,
+        jetbrains_code_style: &JetBrainsCodeStyle,
+        settings_content: &mut SettingsContent,
+    );
 }
 
 impl SettingsStore {
@@ -226,11 +257,13 @@ impl SettingsStore {
             server_settings: None,
             user_settings: None,
             extension_settings: None,
+            vscode_imported_settings: None,
 
             merged_settings: default_settings,
             local_settings: BTreeMap::default(),
             raw_editorconfig_settings: BTreeMap::default(),
             setting_file_updates_tx,
+            file_conflicts_txs: Vec::new(),
             _setting_file_updates: cx.spawn(async move |cx| {
                 while let Some(setting_file_update) = setting_file_updates_rx.next().await {
                     (setting_file_update)(cx.clone()).await.log_err();
@@ -330,6 +363,12 @@ impl SettingsStore {
         self.user_settings.as_ref()
     }
 
+    /// The settings written by the most recent VS Code settings import in this
+    /// session, if any.
+    pub fn vscode_imported_settings(&self) -> Option<&SettingsContent> {
+        self.vscode_imported_settings.as_deref()
+    }
+
     /// Get the default settings content as a raw JSON value.
     pub fn raw_default_settings(&self) -> &SettingsContent {
         &self.default_settings
@@ -392,7 +431,38 @@ impl SettingsStore {
                 async move {
                     let res = async move {
                         let old_text = Self::load_settings(&fs).await?;
-                        let new_text = update(old_text, cx)?;
+                        let new_text = update(old_text.clone(), cx.clone())?;
+                        // The file may have been saved elsewhere, such as from an editor, while the
+                        // update was computed. Apply the update key by key on top of those changes
+                        // rather than overwriting them.
+                        let disk_text = Self::load_settings(&fs).await?;
+                        let new_text = if disk_text == old_text {
+                            new_text
+                        } else {
+                            let merge = cx
+                                .read_global(|store: &SettingsStore, _| {
+                                    merge_json_text_changes(
+                                        &old_text,
+                                        &new_text,
+                                        &disk_text,
+                                        store.json_tab_size(),
+                                    )
+                                })?
+                                .context("Failed to merge settings with changes on disk")?;
+                            if !merge.conflicting_keys.is_empty() {
+                                let conflict = SettingsFileConflict {
+                                    keys: merge.conflicting_keys,
+                                    disk_text,
+                                    merged_text: merge.text.clone(),
+                                };
+                                cx.update_global(|store: &mut SettingsStore, _| {
+                                    store
+                                        .file_conflicts_txs
+                                        .retain(|tx| tx.unbounded_send(conflict.clone()).is_ok());
+                                })?;
+                            }
+                            merge.text
+                        };
                         let settings_path = paths::settings_file().as_path();
                         if fs.is_file(settings_path).await {
                             let resolved_path =
@@ -434,6 +504,14 @@ impl SettingsStore {
         return rx;
     }
 
+    /// Returns a stream of the conflicts that arise when the user settings file changes on disk
+    /// while Zed is updating it.
+    pub fn file_conflicts(&mut self) -> mpsc::UnboundedReceiver<SettingsFileConflict> {
+        let (tx, rx) = mpsc::unbounded();
+        self.file_conflicts_txs.push(tx);
+        rx
+    }
+
     pub fn update_settings_file(
         &self,
         fs: Arc<dyn Fs>,
@@ -447,10 +525,16 @@ impl SettingsStore {
     }
 
     pub fn import_vscode_settings(
-        &self,
+        &mut self,
         fs: Arc<dyn Fs>,
         vscode_settings: VsCodeSettings,
     ) -> oneshot::Receiver<Result<()>> {
+        let mut imported_settings = SettingsContent::default();
+        for setting_value in self.setting_values.values() {
+            setting_value.import_from_vscode(&vscode_settings, &mut imported_settings);
+        }
+        self.vscode_imported_settings = Some(Box::new(imported_settings));
+
         self.update_settings_file_inner(fs, move |old_text: String, cx: AsyncApp| {
             cx.read_global(|store: &SettingsStore, _cx| {
                 store.get_vscode_edits(old_text, &vscode_settings)
@@ -458,6 +542,18 @@ impl SettingsStore {
         })
     }
 
+    pub fn import_jetbrains_settings(
+        &self,
+        fs: Arc<dyn Fs>,
+        jetbrains_code_style: JetBrainsCodeStyle,
+    ) -> oneshot::Receiver<Result<()>> {
+        self.update_settings_file_inner(fs, move |old_text: String, cx: AsyncApp| {
+            cx.read_global(|store: &SettingsStore, _cx| {
+                store.get_jetbrains_edits(old_text, &jetbrains_code_style)
+            })
+        })
+    }
+
     pub fn get_all_files(&self) -> Vec<SettingsFile> {
         let mut files = Vec::from_iter(
             self.local_settings
@@ -620,6 +716,18 @@ impl SettingsStore {
         })
     }
 
+    pub fn get_jetbrains_edits(
+        &self,
+        old_text: String,
+        jetbrains_code_style: &JetBrainsCodeStyle,
+    ) -> String {
+        self.new_text_for_update(old_text, |settings_content| {
+            for v in self.setting_values.values() {
+                v.import_from_jetbrains(jetbrains_code_style, settings_content)
+            }
+        })
+    }
+
     /// Updates the value of a setting in a JSON file, returning a list
     /// of edits to apply to the JSON file.
     pub fn edits_for_update(
@@ -750,6 +858,12 @@ impl SettingsStore {
                         .to_path_buf(),
                 });
             }
+            (LocalSettingsKind::Keymap, _) => {
+                return Err(InvalidSettingsError::LocalSettings {
+                    path: directory_path.join(local_keymap_file_relative_path()),
+                    message: "Attempted to submit a keymap into the settings store".to_string(),
+                });
+            }
             (LocalSettingsKind::Settings, None) => {
                 zed_settings_changed = self
                     .local_settings
@@ -1140,6 +1254,14 @@ impl<T: Settings> AnySettingValue for SettingValue<T> {
     ) {
         T::import_from_vscode(vscode_settings, settings_content);
     }
+
+    fn import_from_jetbrains(
+        &self,
+        jetbrains_code_style: &JetBrainsCodeStyle,
+        settings_content: &mut SettingsContent,
+    ) {
+        T::import_from_jetbrains(jetbrains_code_style, settings_content);
+    }
 }
 
 #[cfg(test)]
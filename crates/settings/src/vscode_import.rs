@@ -79,6 +79,27 @@ impl VsCodeSettings {
         })
     }
 
+    /// Returns the language-specific sections, like `"[rust]": { ... }`, along
+    /// with the VS Code language identifiers that each one applies to.
+    pub fn language_scoped_settings(&self) -> Vec<(Vec<&str>, VsCodeSettings)> {
+        self.content
+            .iter()
+            .filter_map(|(key, value)| {
+                // A section can apply to several languages, as in `"[javascript][typescript]"`.
+                let language_ids = key.strip_prefix('[')?.strip_suffix(']')?;
+                let content = value.as_object()?.clone();
+                Some((
+                    language_ids.split("][").collect(),
+                    VsCodeSettings {
+                        source: self.source,
+                        path: self.path.clone(),
+                        content,
+                    },
+                ))
+            })
+            .collect()
+    }
+
     pub fn read_value(&self, setting: &str) -> Option<&Value> {
         self.content.get(setting)
     }
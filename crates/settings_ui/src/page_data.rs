@@ -4991,6 +4991,22 @@ pub(crate) fn settings_data(cx: &App) -> Vec<SettingsPage> {
                     })),
                     files: USER,
                 }),
+                SettingsPageItem::SettingItem(SettingItem {
+                    title: "No Proxy",
+                    description: "Comma-separated hosts to connect to without the proxy",
+                    field: Box::new(
+                        SettingField {
+                            pick: |settings_content| &settings_content.no_proxy,
+                            pick_mut: |settings_content| &mut settings_content.no_proxy,
+                        }
+                        .unimplemented(),
+                    ),
+                    metadata: Some(Box::new(SettingsFieldMetadata {
+                        placeholder: Some("localhost,.internal.example.com,10.0.0.0/8"),
+                        ..Default::default()
+                    })),
+                    files: USER,
+                }),
                 SettingsPageItem::SettingItem(SettingItem {
                     title: "Server URL",
                     description: "The URL of the Zed server to connect to",
@@ -5987,6 +6003,25 @@ fn language_settings_data() -> Vec<SettingsPageItem> {
             metadata: None,
             files: USER | LOCAL,
         }),
+        SettingsPageItem::SettingItem(SettingItem {
+            title: "Key Context",
+            description: "Extra keymap contexts for editors in this language",
+            field: Box::new(
+                SettingField {
+                    pick: |settings_content| {
+                        language_settings_field(settings_content, |language| &language.key_context)
+                    },
+                    pick_mut: |settings_content| {
+                        language_settings_field_mut(settings_content, |language| {
+                            &mut language.key_context
+                        })
+                    },
+                }
+                .unimplemented(),
+            ),
+            metadata: None,
+            files: USER | LOCAL,
+        }),
         SettingsPageItem::SettingItem(SettingItem {
             title: "Middle Click Paste",
             description: "Enable middle-click paste on Linux",
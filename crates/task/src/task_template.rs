@@ -267,7 +267,10 @@ impl TaskTemplate {
                 reveal: self.reveal,
                 reveal_target: self.reveal_target,
                 hide: self.hide,
-                shell: self.shell.clone(),
+                shell: match (&self.shell, &cx.shell) {
+                    (Shell::System, Some(context_shell)) => context_shell.clone(),
+                    (task_shell, _) => task_shell.clone(),
+                },
                 show_summary: self.show_summary,
                 show_command: self.show_command,
                 show_rerun: true,
@@ -490,6 +493,7 @@ mod tests {
             cwd: None,
             task_variables: TaskVariables::default(),
             project_env: HashMap::default(),
+            shell: None,
         };
         assert_eq!(
             resolved_task(&task_without_cwd, &cx).cwd,
@@ -502,6 +506,7 @@ mod tests {
             cwd: Some(context_cwd.clone()),
             task_variables: TaskVariables::default(),
             project_env: HashMap::default(),
+            shell: None,
         };
         assert_eq!(
             resolved_task(&task_without_cwd, &cx).cwd,
@@ -518,6 +523,7 @@ mod tests {
             cwd: None,
             task_variables: TaskVariables::default(),
             project_env: HashMap::default(),
+            shell: None,
         };
         assert_eq!(
             resolved_task(&task_with_cwd, &cx).cwd,
@@ -529,6 +535,7 @@ mod tests {
             cwd: Some(context_cwd),
             task_variables: TaskVariables::default(),
             project_env: HashMap::default(),
+            shell: None,
         };
         assert_eq!(
             resolved_task(&task_with_cwd, &cx).cwd,
@@ -537,6 +544,46 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_template_shell_resolution() {
+        let task_with_system_shell = TaskTemplate {
+            label: "test task".to_string(),
+            command: "echo 4".to_string(),
+            ..TaskTemplate::default()
+        };
+        let task_with_own_shell = TaskTemplate {
+            shell: Shell::Program("fish".to_string()),
+            ..task_with_system_shell.clone()
+        };
+        let cx = TaskContext {
+            shell: Some(Shell::Program("nu".to_string())),
+            ..TaskContext::default()
+        };
+
+        let resolved_shell = |task_template: &TaskTemplate, task_cx| {
+            task_template
+                .resolve_task(TEST_ID_BASE, task_cx)
+                .unwrap_or_else(|| panic!("failed to resolve task {task_template:?}"))
+                .resolved
+                .shell
+        };
+
+        assert_eq!(
+            resolved_shell(&task_with_system_shell, &TaskContext::default()),
+            Shell::System
+        );
+        assert_eq!(
+            resolved_shell(&task_with_system_shell, &cx),
+            Shell::Program("nu".to_string()),
+            "TaskContext's shell should be taken on resolve if the task uses the system shell"
+        );
+        assert_eq!(
+            resolved_shell(&task_with_own_shell, &cx),
+            Shell::Program("fish".to_string()),
+            "TaskTemplate's shell should be taken on resolve if it has one"
+        );
+    }
+
     #[test]
     fn test_template_variables_resolution() {
         let custom_variable_1 = VariableName::Custom(Cow::Borrowed("custom_variable_1"));
@@ -605,6 +652,7 @@ mod tests {
                     cwd: None,
                     task_variables: TaskVariables::from_iter(all_variables.clone()),
                     project_env: HashMap::default(),
+                    shell: None,
                 },
             ).unwrap_or_else(|| panic!("Should successfully resolve task {task_with_all_variables:?} with variables {all_variables:?}"));
 
@@ -693,6 +741,7 @@ mod tests {
                     cwd: None,
                     task_variables: TaskVariables::from_iter(not_all_variables),
                     project_env: HashMap::default(),
+                    shell: None,
                 },
             );
             assert_eq!(
@@ -750,6 +799,7 @@ mod tests {
                 "test_symbol".to_string(),
             ))),
             project_env: HashMap::default(),
+            shell: None,
         };
 
         for (i, symbol_dependent_task) in [
@@ -878,6 +928,7 @@ mod tests {
             cwd: None,
             task_variables: TaskVariables::from_iter(all_variables),
             project_env,
+            shell: None,
         };
 
         let resolved = template
@@ -917,6 +968,7 @@ mod tests {
                 (VariableName::Row, "123".to_string()),
             ]),
             project_env: HashMap::default(),
+            shell: None,
         };
 
         let resolved = task_with_defaults
@@ -939,6 +991,7 @@ mod tests {
             cwd: None,
             task_variables: TaskVariables::from_iter(vec![(VariableName::Row, "456".to_string())]),
             project_env: HashMap::default(),
+            shell: None,
         };
 
         let resolved = task_with_defaults
@@ -385,6 +385,7 @@ fn worktree_context(worktree_abs_path: &Path) -> TaskContext {
         cwd: Some(worktree_abs_path.to_path_buf()),
         task_variables,
         project_env: HashMap::default(),
+        shell: None,
     }
 }
 
@@ -533,6 +534,7 @@ mod tests {
                     (VariableName::Column, "1".into()),
                 ]),
                 project_env: HashMap::default(),
+                shell: None,
             }
         );
 
@@ -567,6 +569,7 @@ mod tests {
                     (VariableName::Symbol, "this_is_a_rust_file".into()),
                 ]),
                 project_env: HashMap::default(),
+                shell: None,
             }
         );
 
@@ -595,6 +598,7 @@ mod tests {
                     (VariableName::Symbol, "this_is_a_test".into()),
                 ]),
                 project_env: HashMap::default(),
+                shell: None,
             }
         );
     }
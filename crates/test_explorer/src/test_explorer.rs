@@ -0,0 +1,58 @@
+mod test_explorer_panel;
+mod test_tree;
+
+use gpui::{App, AppContext as _, Pixels, actions, px};
+use settings::Settings;
+use workspace::{Workspace, dock::DockPosition};
+
+pub use crate::test_explorer_panel::TestExplorerPanel;
+
+actions!(
+    test_explorer,
+    [
+        /// Toggles focus on the test explorer panel.
+        ToggleFocus,
+        /// Scans the project for tests again.
+        RefreshTests,
+        /// Toggles between showing every test and only the tests that failed.
+        ToggleOnlyFailed,
+        /// Collapses every file in the test explorer.
+        CollapseAllFiles,
+    ]
+);
+
+pub fn init(cx: &mut App) {
+    TestExplorerSettings::register(cx);
+
+    cx.observe_new(|workspace: &mut Workspace, window, cx| {
+        let Some(window) = window else {
+            return;
+        };
+        workspace.register_action(|workspace, _: &ToggleFocus, window, cx| {
+            workspace.toggle_panel_focus::<TestExplorerPanel>(window, cx);
+        });
+
+        let panel = cx.new(|cx| TestExplorerPanel::new(workspace, cx));
+        workspace.add_panel(panel, window, cx);
+    })
+    .detach();
+}
+
+#[derive(Debug)]
+pub struct TestExplorerSettings {
+    pub button: bool,
+    pub dock: DockPosition,
+    pub default_width: Pixels,
+}
+
+impl Settings for TestExplorerSettings {
+    fn from_settings(content: &settings::SettingsContent) -> Self {
+        let panel = content.test_explorer.as_ref().unwrap();
+
+        Self {
+            button: panel.button.unwrap(),
+            dock: panel.dock.unwrap().into(),
+            default_width: panel.default_width.map(px).unwrap(),
+        }
+    }
+}
@@ -0,0 +1,1035 @@
+use std::{ops::Range, path::PathBuf, sync::Arc, time::Duration};
+
+use anyhow::{Context as _, Result};
+use collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use editor::Editor;
+use fs::Fs;
+use gpui::{
+    Action, App, AsyncApp, Context, Entity, EventEmitter, FocusHandle, Focusable, Pixels,
+    Subscription, Task, UniformListScrollHandle, WeakEntity, Window, uniform_list,
+};
+use language::{
+    Bias, Buffer, BufferId, BufferSnapshot, File, Language, LanguageServerName, Location,
+    OffsetRangeExt as _, Point, Rope, ToPoint as _, language_settings::language_settings,
+};
+use project::{
+    Project, ProjectPath, TaskSourceKind, WorktreeId, project_settings::ProjectSettings,
+};
+use settings::Settings as _;
+use task::{ResolvedTask, TaskContext, TaskTemplate, TaskVariables, VariableName};
+use ui::{ListItem, ListItemSpacing, Tooltip, prelude::*};
+use util::ResultExt as _;
+use workspace::{
+    Workspace,
+    dock::{DockPosition, Panel, PanelEvent},
+};
+
+use crate::{
+    CollapseAllFiles, RefreshTests, TestExplorerSettings, ToggleFocus, ToggleOnlyFailed,
+    test_tree::{self, DiscoveredTest, TestNode, TestOutcome},
+};
+
+/// How long to wait for a burst of file changes to settle before scanning the changed files.
+const SCAN_DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// A test, identified by its file and by its qualified name, so that its outcome outlives scans
+/// that move it to another row.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+struct TestId {
+    path: ProjectPath,
+    qualified_name: String,
+}
+
+#[derive(Clone, Debug)]
+enum ListEntry {
+    File(ProjectPath),
+    Test { path: ProjectPath, test_ix: usize },
+}
+
+struct FileToScan {
+    path: ProjectPath,
+    abs_path: PathBuf,
+    /// The buffer of the file, if it is open, whose language server may report more tests.
+    open_buffer: Option<Entity<Buffer>>,
+}
+
+/// A dock panel that lists the tests in the project's files as a tree, runs or debugs them one at
+/// a time, and remembers whether they passed.
+pub struct TestExplorerPanel {
+    workspace: WeakEntity<Workspace>,
+    project: Entity<Project>,
+    fs: Arc<dyn Fs>,
+    focus_handle: FocusHandle,
+    scroll_handle: UniformListScrollHandle,
+    width: Option<Pixels>,
+    files: BTreeMap<ProjectPath, Vec<TestNode>>,
+    outcomes: HashMap<TestId, TestOutcome>,
+    expanded_files: HashSet<ProjectPath>,
+    collapsed_tests: HashSet<TestId>,
+    only_failed: bool,
+    entries: Vec<ListEntry>,
+    pending_paths: BTreeSet<ProjectPath>,
+    scan_task: Option<Task<()>>,
+    _project_subscription: Subscription,
+}
+
+impl TestExplorerPanel {
+    pub(crate) fn new(workspace: &Workspace, cx: &mut Context<Self>) -> Self {
+        let project = workspace.project().clone();
+        let project_subscription = cx.subscribe(&project, |this, _, event, cx| match event {
+            project::Event::WorktreeUpdatedEntries(worktree_id, changes) => {
+                this.queue_scan(
+                    changes.iter().map(|(path, _, _)| ProjectPath {
+                        worktree_id: *worktree_id,
+                        path: path.clone(),
+                    }),
+                    cx,
+                );
+            }
+            project::Event::WorktreeRemoved(worktree_id) => {
+                this.files
+                    .retain(|path, _| path.worktree_id != *worktree_id);
+                this.update_entries(cx);
+            }
+            _ => {}
+        });
+
+        let mut this = Self {
+            workspace: workspace.weak_handle(),
+            project,
+            fs: workspace.app_state().fs.clone(),
+            focus_handle: cx.focus_handle(),
+            scroll_handle: UniformListScrollHandle::new(),
+            width: None,
+            files: BTreeMap::default(),
+            outcomes: HashMap::default(),
+            expanded_files: HashSet::default(),
+            collapsed_tests: HashSet::default(),
+            only_failed: false,
+            entries: Vec::new(),
+            pending_paths: BTreeSet::default(),
+            scan_task: None,
+            _project_subscription: project_subscription,
+        };
+        this.refresh(cx);
+        this
+    }
+
+    fn refresh(&mut self, cx: &mut Context<Self>) {
+        let mut paths = BTreeSet::default();
+        for worktree in self.project.read(cx).visible_worktrees(cx) {
+            let snapshot = worktree.read(cx).snapshot();
+            let worktree_id = snapshot.id();
+            paths.extend(snapshot.files(false, 0).map(|entry| ProjectPath {
+                worktree_id,
+                path: entry.path.clone(),
+            }));
+        }
+        self.files.retain(|path, _| paths.contains(path));
+        self.update_entries(cx);
+        self.queue_scan(paths, cx);
+    }
+
+    /// Scans the given files for tests, after the changes that are still coming in settle.
+    fn queue_scan(&mut self, paths: impl IntoIterator<Item = ProjectPath>, cx: &mut Context<Self>) {
+        if !self.project.read(cx).is_local() {
+            return;
+        }
+        self.pending_paths.extend(paths);
+        if self.scan_task.is_some() || self.pending_paths.is_empty() {
+            return;
+        }
+
+        let languages = self.project.read(cx).languages().clone();
+        let fs = self.fs.clone();
+        let project = self.project.clone();
+        self.scan_task = Some(cx.spawn(async move |this, cx| {
+            cx.background_executor().timer(SCAN_DEBOUNCE).await;
+            while let Ok(Some(file)) = this.update(cx, |this, cx| this.next_file_to_scan(cx)) {
+                let tests = scan_file(&file, &project, &fs, &languages, cx).await;
+                let result = this.update(cx, |this, cx| {
+                    if tests.is_empty() {
+                        this.files.remove(&file.path);
+                    } else {
+                        this.files
+                            .insert(file.path, test_tree::build_test_tree(tests));
+                    }
+                    this.update_entries(cx);
+                });
+                if result.is_err() {
+                    return;
+                }
+            }
+        }));
+        cx.notify();
+    }
+
+    /// Takes the next pending file that may contain tests, forgetting the tests of pending files
+    /// that were removed or ignored. Ends the scan when no files are left.
+    fn next_file_to_scan(&mut self, cx: &mut Context<Self>) -> Option<FileToScan> {
+        while let Some(path) = self.pending_paths.pop_first() {
+            let project = self.project.read(cx);
+            let file = project
+                .worktree_for_id(path.worktree_id, cx)
+                .filter(|worktree| worktree.read(cx).is_visible())
+                .and_then(|worktree| {
+                    let worktree = worktree.read(cx);
+                    let entry = worktree.entry_for_path(&path.path)?;
+                    let abs_path = worktree.absolutize(&path.path);
+                    let has_language = project
+                        .languages()
+                        .language_for_file_path(&abs_path)
+                        .is_some();
+                    (entry.is_file() && !entry.is_ignored && has_language).then_some(abs_path)
+                });
+            match file {
+                Some(abs_path) => {
+                    return Some(FileToScan {
+                        open_buffer: project.get_open_buffer(&path, cx),
+                        path,
+                        abs_path,
+                    });
+                }
+                None => {
+                    if self.files.remove(&path).is_some() {
+                        self.update_entries(cx);
+                    }
+                }
+            }
+        }
+        self.scan_task = None;
+        cx.notify();
+        None
+    }
+
+    fn update_entries(&mut self, cx: &mut Context<Self>) {
+        let mut entries = Vec::new();
+        for (path, tests) in &self.files {
+            if self.only_failed
+                && self.subtree_outcome(path, tests, 0..tests.len()) != Some(TestOutcome::Failed)
+            {
+                continue;
+            }
+            entries.push(ListEntry::File(path.clone()));
+            if !self.expanded_files.contains(path) {
+                continue;
+            }
+
+            let mut collapsed_depth = None;
+            for (test_ix, test) in tests.iter().enumerate() {
+                if let Some(depth) = collapsed_depth {
+                    if test.depth > depth {
+                        continue;
+                    }
+                    collapsed_depth = None;
+                }
+                if self.only_failed
+                    && self.subtree_outcome(path, tests, subtree_range(tests, test_ix))
+                        != Some(TestOutcome::Failed)
+                {
+                    continue;
+                }
+                entries.push(ListEntry::Test {
+                    path: path.clone(),
+                    test_ix,
+                });
+                if test.has_children && self.collapsed_tests.contains(&test_id(path, test)) {
+                    collapsed_depth = Some(test.depth);
+                }
+            }
+        }
+        self.entries = entries;
+        cx.notify();
+    }
+
+    fn subtree_outcome(
+        &self,
+        path: &ProjectPath,
+        tests: &[TestNode],
+        range: Range<usize>,
+    ) -> Option<TestOutcome> {
+        test_tree::aggregate_outcome(
+            tests[range]
+                .iter()
+                .map(|test| self.outcomes.get(&test_id(path, test)).copied()),
+        )
+    }
+
+    fn toggle_file_expanded(&mut self, path: &ProjectPath, cx: &mut Context<Self>) {
+        if !self.expanded_files.remove(path) {
+            self.expanded_files.insert(path.clone());
+        }
+        self.update_entries(cx);
+    }
+
+    fn toggle_test_expanded(&mut self, test_id: TestId, cx: &mut Context<Self>) {
+        if !self.collapsed_tests.remove(&test_id) {
+            self.collapsed_tests.insert(test_id);
+        }
+        self.update_entries(cx);
+    }
+
+    fn toggle_only_failed(&mut self, _: &ToggleOnlyFailed, _: &mut Window, cx: &mut Context<Self>) {
+        self.only_failed = !self.only_failed;
+        self.update_entries(cx);
+    }
+
+    fn collapse_all_files(&mut self, _: &CollapseAllFiles, _: &mut Window, cx: &mut Context<Self>) {
+        self.expanded_files.clear();
+        self.update_entries(cx);
+    }
+
+    fn test(&self, test_id: &TestId) -> Option<&TestNode> {
+        self.files
+            .get(&test_id.path)?
+            .iter()
+            .find(|test| test.qualified_name == test_id.qualified_name)
+    }
+
+    /// Records a test's outcome. A test that passed passes every test nested in it too, while
+    /// the outcomes of the tests nested in one that failed are no longer known.
+    fn record_outcome(&mut self, test_id: TestId, outcome: Option<TestOutcome>) {
+        let nested_test_ids = self
+            .files
+            .get(&test_id.path)
+            .and_then(|tests| {
+                let test_ix = tests
+                    .iter()
+                    .position(|test| test.qualified_name == test_id.qualified_name)?;
+                Some(
+                    tests[subtree_range(tests, test_ix)]
+                        .iter()
+                        .skip(1)
+                        .map(|test| TestId {
+                            path: test_id.path.clone(),
+                            qualified_name: test.qualified_name.clone(),
+                        })
+                        .collect::<Vec<_>>(),
+                )
+            })
+            .unwrap_or_default();
+        match outcome {
+            Some(TestOutcome::Passed) => {
+                for nested_test_id in nested_test_ids {
+                    self.outcomes.insert(nested_test_id, TestOutcome::Passed);
+                }
+            }
+            Some(TestOutcome::Failed) => {
+                for nested_test_id in nested_test_ids {
+                    self.outcomes.remove(&nested_test_id);
+                }
+            }
+            Some(TestOutcome::Running) | None => {}
+        }
+        match outcome {
+            Some(outcome) => self.outcomes.insert(test_id, outcome),
+            None => self.outcomes.remove(&test_id),
+        };
+    }
+
+    fn run_test(&mut self, test_id: TestId, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(test) = self.test(&test_id).cloned() else {
+            return;
+        };
+        let project = self.project.clone();
+        let workspace = self.workspace.clone();
+        self.record_outcome(test_id.clone(), Some(TestOutcome::Running));
+        self.update_entries(cx);
+
+        cx.spawn_in(window, async move |this, cx| {
+            let result = async {
+                let test_task = resolve_test_task(project, test_id.path.clone(), test, cx).await?;
+                let exit_status = workspace
+                    .update_in(cx, |workspace, window, cx| {
+                        workspace.spawn_in_terminal(test_task.task.resolved, window, cx)
+                    })?
+                    .await;
+                exit_status.transpose()
+            }
+            .await;
+
+            this.update(cx, |this, cx| {
+                let outcome = match result {
+                    Ok(Some(exit_status)) if exit_status.success() => Some(TestOutcome::Passed),
+                    Ok(Some(_)) => Some(TestOutcome::Failed),
+                    Ok(None) => None,
+                    Err(error) => {
+                        this.show_error(error, cx);
+                        None
+                    }
+                };
+                this.record_outcome(test_id, outcome);
+                this.update_entries(cx);
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    fn debug_test(&mut self, test_id: TestId, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(test) = self.test(&test_id).cloned() else {
+            return;
+        };
+        let project = self.project.clone();
+        let workspace = self.workspace.clone();
+
+        cx.spawn_in(window, async move |this, cx| {
+            let result = async {
+                let test_name = test.qualified_name.clone();
+                let test_task = resolve_test_task(project.clone(), test_id.path, test, cx).await?;
+                let scenario = cx
+                    .update(|_, cx| {
+                        let adapter = debug_adapter(test_task.buffer.read(cx), cx)?;
+                        let dap_store = project.read(cx).dap_store();
+                        Some(dap_store.update(cx, |dap_store, cx| {
+                            dap_store.debug_scenario_for_build_task(
+                                test_task.task.original_task().clone(),
+                                adapter.into(),
+                                test_task.task.display_label().to_owned().into(),
+                                cx,
+                            )
+                        }))
+                    })?
+                    .with_context(|| format!("No debugger is configured for `{test_name}`"))?
+                    .await
+                    .with_context(|| format!("`{test_name}` can't be debugged"))?;
+
+                workspace.update_in(cx, |workspace, window, cx| {
+                    workspace.start_debug_session(
+                        scenario,
+                        test_task.context,
+                        Some(test_task.buffer),
+                        Some(test_task.worktree_id),
+                        window,
+                        cx,
+                    );
+                })
+            }
+            .await;
+
+            if let Err(error) = result {
+                this.update(cx, |this, cx| this.show_error(error, cx)).ok();
+            }
+        })
+        .detach();
+    }
+
+    fn open_test(&mut self, test_id: TestId, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(point) = self
+            .test(&test_id)
+            .map(|test| Point::new(test.row, test.column))
+        else {
+            return;
+        };
+        let Some(open_task) = self
+            .workspace
+            .update(cx, |workspace, cx| {
+                workspace.open_path(test_id.path, None, true, window, cx)
+            })
+            .log_err()
+        else {
+            return;
+        };
+
+        cx.spawn_in(window, async move |_, cx| {
+            let item = open_task.await?;
+            if let Some(editor) = item.downcast::<Editor>() {
+                editor.update_in(cx, |editor, window, cx| {
+                    editor.go_to_singleton_buffer_point(point, window, cx);
+                })?;
+            }
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn show_error(&self, error: anyhow::Error, cx: &mut App) {
+        self.workspace
+            .update(cx, |workspace, cx| workspace.show_error(&error, cx))
+            .log_err();
+    }
+
+    fn render_header(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let test_count = self.files.values().map(Vec::len).sum::<usize>();
+        let passed_count = self
+            .outcomes
+            .values()
+            .filter(|outcome| **outcome == TestOutcome::Passed)
+            .count();
+        let failed_count = self
+            .outcomes
+            .values()
+            .filter(|outcome| **outcome == TestOutcome::Failed)
+            .count();
+
+        h_flex()
+            .h(Tab::container_height(cx))
+            .px_2()
+            .gap_1()
+            .justify_between()
+            .border_b_1()
+            .border_color(cx.theme().colors().border)
+            .child(
+                h_flex()
+                    .gap_2()
+                    .overflow_x_hidden()
+                    .child(
+                        Label::new(format!("{test_count} tests in {} files", self.files.len()))
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    )
+                    .when(passed_count > 0, |this| {
+                        this.child(
+                            Label::new(format!("{passed_count} passed"))
+                                .size(LabelSize::Small)
+                                .color(Color::Success),
+                        )
+                    })
+                    .when(failed_count > 0, |this| {
+                        this.child(
+                            Label::new(format!("{failed_count} failed"))
+                                .size(LabelSize::Small)
+                                .color(Color::Error),
+                        )
+                    }),
+            )
+            .child(
+                h_flex()
+                    .gap_1()
+                    .child(
+                        IconButton::new("toggle-only-failed", IconName::Filter)
+                            .icon_size(IconSize::Small)
+                            .toggle_state(self.only_failed)
+                            .tooltip(Tooltip::for_action_title(
+                                "Only Show Failed Tests",
+                                &ToggleOnlyFailed,
+                            ))
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.toggle_only_failed(&ToggleOnlyFailed, window, cx)
+                            })),
+                    )
+                    .child(
+                        IconButton::new("refresh-tests", IconName::RotateCw)
+                            .icon_size(IconSize::Small)
+                            .disabled(self.scan_task.is_some())
+                            .tooltip(Tooltip::for_action_title("Refresh", &RefreshTests))
+                            .on_click(cx.listener(|this, _, _, cx| this.refresh(cx))),
+                    ),
+            )
+    }
+
+    fn render_entry(&self, entry: &ListEntry, cx: &mut Context<Self>) -> Option<AnyElement> {
+        match entry {
+            ListEntry::File(path) => {
+                let tests = self.files.get(path)?;
+                let is_expanded = self.expanded_files.contains(path);
+                let path_style = self.project.read(cx).path_style(cx);
+                let file_name = path.path.file_name().unwrap_or_default().to_string();
+                let directory = path
+                    .path
+                    .parent()
+                    .map(|parent| parent.display(path_style).to_string())
+                    .unwrap_or_default();
+                let outcome = self.subtree_outcome(path, tests, 0..tests.len());
+
+                Some(
+                    ListItem::new(SharedString::from(format!(
+                        "file-{}-{}",
+                        path.worktree_id.to_usize(),
+                        path.path.as_unix_str()
+                    )))
+                    .spacing(ListItemSpacing::Sparse)
+                    .toggle(Some(is_expanded))
+                    .on_toggle(cx.listener({
+                        let path = path.clone();
+                        move |this, _, _, cx| this.toggle_file_expanded(&path, cx)
+                    }))
+                    .start_slot(outcome_icon(outcome))
+                    .child(
+                        h_flex()
+                            .gap_2()
+                            .child(Label::new(file_name).truncate())
+                            .child(
+                                Label::new(directory)
+                                    .size(LabelSize::Small)
+                                    .color(Color::Muted)
+                                    .truncate(),
+                            ),
+                    )
+                    .on_click(cx.listener({
+                        let path = path.clone();
+                        move |this, _, _, cx| this.toggle_file_expanded(&path, cx)
+                    }))
+                    .into_any_element(),
+                )
+            }
+            ListEntry::Test { path, test_ix } => {
+                let tests = self.files.get(path)?;
+                let test = tests.get(*test_ix)?;
+                let test_id = test_id(path, test);
+                let is_expanded = !self.collapsed_tests.contains(&test_id);
+                let outcome = self.subtree_outcome(path, tests, subtree_range(tests, *test_ix));
+                let element_id = format!(
+                    "{}-{}-{}",
+                    path.worktree_id.to_usize(),
+                    path.path.as_unix_str(),
+                    test.qualified_name
+                );
+
+                Some(
+                    ListItem::new(SharedString::from(format!("test-{element_id}")))
+                        .spacing(ListItemSpacing::Sparse)
+                        .indent_level(test.depth + 1)
+                        .indent_step_size(px(12.))
+                        .toggle(test.has_children.then_some(is_expanded))
+                        .on_toggle(cx.listener({
+                            let test_id = test_id.clone();
+                            move |this, _, _, cx| this.toggle_test_expanded(test_id.clone(), cx)
+                        }))
+                        .start_slot(outcome_icon(outcome))
+                        .child(Label::new(test.name.clone()).truncate())
+                        .end_hover_slot(
+                            h_flex()
+                                .gap_1()
+                                .child(
+                                    IconButton::new(
+                                        SharedString::from(format!("run-{element_id}")),
+                                        IconName::PlayOutlined,
+                                    )
+                                    .icon_size(IconSize::Small)
+                                    .disabled(
+                                        self.outcomes.get(&test_id) == Some(&TestOutcome::Running),
+                                    )
+                                    .tooltip(Tooltip::text("Run Test"))
+                                    .on_click(cx.listener({
+                                        let test_id = test_id.clone();
+                                        move |this, _, window, cx| {
+                                            this.run_test(test_id.clone(), window, cx)
+                                        }
+                                    })),
+                                )
+                                .child(
+                                    IconButton::new(
+                                        SharedString::from(format!("debug-{element_id}")),
+                                        IconName::Debug,
+                                    )
+                                    .icon_size(IconSize::Small)
+                                    .tooltip(Tooltip::text("Debug Test"))
+                                    .on_click(cx.listener({
+                                        let test_id = test_id.clone();
+                                        move |this, _, window, cx| {
+                                            this.debug_test(test_id.clone(), window, cx)
+                                        }
+                                    })),
+                                ),
+                        )
+                        .on_click(cx.listener(move |this, _, window, cx| {
+                            this.open_test(test_id.clone(), window, cx)
+                        }))
+                        .into_any_element(),
+                )
+            }
+        }
+    }
+}
+
+fn test_id(path: &ProjectPath, test: &TestNode) -> TestId {
+    TestId {
+        path: path.clone(),
+        qualified_name: test.qualified_name.clone(),
+    }
+}
+
+/// The range of a test and of the tests nested in it, which follow it in the tree.
+fn subtree_range(tests: &[TestNode], test_ix: usize) -> Range<usize> {
+    let depth = tests[test_ix].depth;
+    let end = tests[test_ix + 1..]
+        .iter()
+        .position(|test| test.depth <= depth)
+        .map_or(tests.len(), |len| test_ix + 1 + len);
+    test_ix..end
+}
+
+fn outcome_icon(outcome: Option<TestOutcome>) -> Icon {
+    let (icon, color) = match outcome {
+        Some(TestOutcome::Running) => (IconName::ArrowCircle, Color::Accent),
+        Some(TestOutcome::Passed) => (IconName::Check, Color::Success),
+        Some(TestOutcome::Failed) => (IconName::XCircle, Color::Error),
+        None => (IconName::Circle, Color::Disabled),
+    };
+    Icon::new(icon).size(IconSize::Small).color(color)
+}
+
+/// Finds the tests in a file with its language's runnables query and, when the file is open, with
+/// its language server. Files without tests, or that can no longer be read, have none.
+async fn scan_file(
+    file: &FileToScan,
+    project: &Entity<Project>,
+    fs: &Arc<dyn Fs>,
+    languages: &Arc<language::LanguageRegistry>,
+    cx: &mut AsyncApp,
+) -> Vec<DiscoveredTest> {
+    let Ok(language) = languages.load_language_for_file_path(&file.abs_path).await else {
+        return Vec::new();
+    };
+    if !language
+        .grammar()
+        .is_some_and(|grammar| grammar.has_runnables())
+    {
+        return Vec::new();
+    }
+    let Ok(text) = fs.load(&file.abs_path).await else {
+        return Vec::new();
+    };
+    let Ok(snapshot) = cx.update(|cx| {
+        Buffer::build_snapshot(
+            Rope::from(text.as_str()),
+            Some(language),
+            Some(languages.clone()),
+            cx,
+        )
+    }) else {
+        return Vec::new();
+    };
+    let mut tests = cx
+        .background_spawn(async move { runnable_tests(&snapshot.await) })
+        .await;
+
+    if let Some(buffer) = &file.open_buffer {
+        tests.extend(lsp_tests(project, buffer, cx).await);
+    }
+    tests
+}
+
+fn runnable_tests(snapshot: &BufferSnapshot) -> Vec<DiscoveredTest> {
+    snapshot
+        .runnable_ranges(0..snapshot.len())
+        .filter_map(|runnable| {
+            let tags = runnable
+                .runnable
+                .tags
+                .iter()
+                .map(|tag| tag.0.to_string())
+                .collect::<Vec<_>>();
+            let test_tag = tags.iter().find(|tag| test_tree::is_test_tag(tag))?;
+            let start = snapshot.offset_to_point(runnable.run_range.start);
+            let run_text = snapshot
+                .text_for_range(runnable.run_range.clone())
+                .collect::<String>();
+            Some(DiscoveredTest {
+                name: test_tree::test_name(&run_text, test_tag, start.row),
+                row: start.row,
+                column: start.column,
+                range: runnable.full_range,
+                tags,
+                extra_variables: runnable.extra_captures,
+            })
+        })
+        .collect()
+}
+
+/// The tests that the language server of an open buffer reports, which includes tests that
+/// runnables queries can't find, such as those generated by macros.
+async fn lsp_tests(
+    project: &Entity<Project>,
+    buffer: &Entity<Buffer>,
+    cx: &mut AsyncApp,
+) -> Vec<DiscoveredTest> {
+    let Ok(lsp_tasks) = cx.update(|cx| {
+        let task_sources = lsp_task_sources(buffer, cx);
+        editor::lsp_tasks(project.clone(), &task_sources, None, cx)
+    }) else {
+        return Vec::new();
+    };
+    let lsp_tasks = lsp_tasks.await;
+    let Ok(snapshot) = buffer.read_with(cx, |buffer, _| buffer.snapshot()) else {
+        return Vec::new();
+    };
+
+    lsp_tasks
+        .into_iter()
+        .flat_map(|(_, tasks)| tasks)
+        .filter_map(|(location, task)| {
+            let target = location?.target;
+            if target.buffer != *buffer {
+                return None;
+            }
+            let start = target.range.start.to_point(&snapshot);
+            Some(DiscoveredTest {
+                name: test_tree::lsp_test_name(&task.original_task().label)?,
+                row: start.row,
+                column: start.column,
+                range: target.range.to_offset(&snapshot),
+                tags: Vec::new(),
+                extra_variables: HashMap::default(),
+            })
+        })
+        .collect()
+}
+
+fn lsp_task_sources(
+    buffer: &Entity<Buffer>,
+    cx: &App,
+) -> HashMap<LanguageServerName, Vec<BufferId>> {
+    let lsp_settings = &ProjectSettings::get_global(cx).lsp;
+    let buffer = buffer.read(cx);
+    buffer
+        .language()
+        .and_then(|language| language.context_provider()?.lsp_task_source())
+        .filter(|lsp_task_source| {
+            lsp_settings
+                .get(lsp_task_source)
+                .is_none_or(|settings| settings.enable_lsp_tasks)
+        })
+        .map(|lsp_task_source| HashMap::from_iter([(lsp_task_source, vec![buffer.remote_id()])]))
+        .unwrap_or_default()
+}
+
+/// A test's task, resolved in the context of the test's position in its file.
+struct TestTask {
+    buffer: Entity<Buffer>,
+    worktree_id: WorktreeId,
+    task: ResolvedTask,
+    context: TaskContext,
+}
+
+/// Finds the task that runs a test, the same way the gutter's run button does: from the task
+/// templates tagged like the test's runnable, or from the language server's runnables when the
+/// language prefers them.
+async fn resolve_test_task(
+    project: Entity<Project>,
+    path: ProjectPath,
+    test: TestNode,
+    cx: &mut AsyncApp,
+) -> Result<TestTask> {
+    let buffer = project
+        .update(cx, |project, cx| project.open_buffer(path.clone(), cx))?
+        .await?;
+    let snapshot = buffer.read_with(cx, |buffer, _| buffer.snapshot())?;
+    let position = snapshot.anchor_at(Point::new(test.row, test.column), Bias::Right);
+    let language = snapshot.language().cloned();
+    let file = snapshot.file().cloned();
+    let (task_sources, prefer_lsp) = cx.update(|cx| {
+        let language_settings = language_settings(
+            language.as_ref().map(|language| language.name()),
+            file.as_ref(),
+            cx,
+        );
+        (
+            lsp_task_sources(&buffer, cx),
+            language_settings.tasks.prefer_lsp,
+        )
+    })?;
+
+    let lsp_tasks = cx
+        .update(|cx| editor::lsp_tasks(project.clone(), &task_sources, Some(position), cx))?
+        .await;
+    let lsp_task =
+        lsp_tasks
+            .into_iter()
+            .flat_map(|(_, tasks)| tasks)
+            .find_map(|(task_location, task)| {
+                let target = task_location?.target;
+                (target.buffer == buffer && target.range.start.to_point(&snapshot).row == test.row)
+                    .then_some(task)
+            });
+    let location = Location {
+        buffer: buffer.clone(),
+        range: position..position,
+    };
+
+    let mut captured_variables = TaskVariables::default();
+    for (name, value) in &test.extra_variables {
+        captured_variables.insert(VariableName::Custom(name.clone().into()), value.clone());
+    }
+    let context = project
+        .update(cx, |project, cx| {
+            project.task_store().update(cx, |task_store, cx| {
+                task_store.task_context_for_location(captured_variables, location, cx)
+            })
+        })?
+        .await
+        .with_context(|| format!("Failed to resolve the context of `{}`", test.qualified_name))?;
+
+    let templates = cx
+        .update(|cx| {
+            templates_for_tags(&project, &test.tags, file, language, path.worktree_id, cx)
+        })?
+        .await;
+    let template_task = templates
+        .into_iter()
+        .find_map(|(kind, template)| template.resolve_task(&kind.to_id_base(), &context));
+
+    let task = match (lsp_task, template_task) {
+        (Some(lsp_task), _) if prefer_lsp => lsp_task,
+        (_, Some(template_task)) => template_task,
+        (Some(lsp_task), None) => lsp_task,
+        (None, None) => anyhow::bail!("No task runs `{}`", test.qualified_name),
+    };
+    Ok(TestTask {
+        buffer,
+        worktree_id: path.worktree_id,
+        task,
+        context,
+    })
+}
+
+fn templates_for_tags(
+    project: &Entity<Project>,
+    tags: &[String],
+    file: Option<Arc<dyn File>>,
+    language: Option<Arc<Language>>,
+    worktree_id: WorktreeId,
+    cx: &mut App,
+) -> Task<Vec<(TaskSourceKind, TaskTemplate)>> {
+    let Some(inventory) = project
+        .read(cx)
+        .task_store()
+        .read(cx)
+        .task_inventory()
+        .cloned()
+    else {
+        return Task::ready(Vec::new());
+    };
+    let tags = tags.to_vec();
+    let templates = inventory
+        .read(cx)
+        .list_tasks(file, language, Some(worktree_id), cx);
+    cx.background_spawn(async move {
+        let mut templates = templates
+            .await
+            .into_iter()
+            .filter(|(_, template)| template.tags.iter().any(|tag| tags.contains(tag)))
+            .collect::<Vec<_>>();
+        templates.sort_by_key(|(kind, _)| kind.clone());
+        // As in the gutter, the strongest source wins: worktree tasks over global ones, and
+        // global tasks over the language's.
+        let first_weaker_ix = templates.first().and_then(|(strongest_kind, _)| {
+            templates
+                .iter()
+                .position(|(kind, _)| kind != strongest_kind)
+        });
+        if let Some(first_weaker_ix) = first_weaker_ix {
+            templates.truncate(first_weaker_ix);
+        }
+        templates
+    })
+}
+
+fn debug_adapter(buffer: &Buffer, cx: &App) -> Option<SharedString> {
+    let language = buffer.language()?;
+    language_settings(Some(language.name()), buffer.file(), cx)
+        .debuggers
+        .first()
+        .map(SharedString::from)
+        .or_else(|| language.config().debuggers.first().map(SharedString::from))
+}
+
+impl Render for TestExplorerPanel {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let content = if !self.project.read(cx).is_local() {
+            v_flex()
+                .p_2()
+                .child(
+                    Label::new("Tests can only be discovered in local projects.")
+                        .color(Color::Muted),
+                )
+                .into_any_element()
+        } else if self.entries.is_empty() {
+            let message = if self.scan_task.is_some() {
+                "Discovering tests…"
+            } else if self.only_failed {
+                "No tests failed."
+            } else {
+                "No tests found."
+            };
+            v_flex()
+                .p_2()
+                .child(Label::new(message).color(Color::Muted))
+                .into_any_element()
+        } else {
+            uniform_list(
+                "test-explorer-entries",
+                self.entries.len(),
+                cx.processor(|this, range: Range<usize>, _, cx| {
+                    this.entries[range]
+                        .to_vec()
+                        .iter()
+                        .filter_map(|entry| this.render_entry(entry, cx))
+                        .collect()
+                }),
+            )
+            .size_full()
+            .p_1()
+            .track_scroll(self.scroll_handle.clone())
+            .into_any_element()
+        };
+
+        v_flex()
+            .key_context("TestExplorerPanel")
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(|this, _: &RefreshTests, _, cx| this.refresh(cx)))
+            .on_action(cx.listener(Self::toggle_only_failed))
+            .on_action(cx.listener(Self::collapse_all_files))
+            .size_full()
+            .bg(cx.theme().colors().panel_background)
+            .child(self.render_header(cx))
+            .child(content)
+    }
+}
+
+impl Focusable for TestExplorerPanel {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl EventEmitter<PanelEvent> for TestExplorerPanel {}
+
+impl Panel for TestExplorerPanel {
+    fn persistent_name() -> &'static str {
+        "TestExplorerPanel"
+    }
+
+    fn position(&self, _: &Window, cx: &App) -> DockPosition {
+        TestExplorerSettings::get_global(cx).dock
+    }
+
+    fn position_is_valid(&self, position: DockPosition) -> bool {
+        matches!(position, DockPosition::Left | DockPosition::Right)
+    }
+
+    fn set_position(&mut self, position: DockPosition, _: &mut Window, cx: &mut Context<Self>) {
+        settings::update_settings_file(self.fs.clone(), cx, move |settings, _| {
+            settings.test_explorer.get_or_insert_default().dock = Some(position.into())
+        });
+    }
+
+    fn size(&self, _: &Window, cx: &App) -> Pixels {
+        self.width
+            .unwrap_or_else(|| TestExplorerSettings::get_global(cx).default_width)
+    }
+
+    fn set_size(&mut self, size: Option<Pixels>, _: &mut Window, cx: &mut Context<Self>) {
+        self.width = size;
+        cx.notify();
+    }
+
+    fn icon(&self, _: &Window, cx: &App) -> Option<IconName> {
+        TestExplorerSettings::get_global(cx)
+            .button
+            .then_some(IconName::ListTodo)
+    }
+
+    fn icon_tooltip(&self, _window: &Window, _cx: &App) -> Option<&'static str> {
+        Some("Test Explorer")
+    }
+
+    fn toggle_action(&self) -> Box<dyn Action> {
+        Box::new(ToggleFocus)
+    }
+
+    fn activation_priority(&self) -> u32 {
+        7
+    }
+}
@@ -0,0 +1,237 @@
+use std::{cmp::Reverse, ops::Range};
+
+use collections::HashMap;
+
+/// Names longer than this are more likely to be code than a test's name.
+const MAX_NAME_LEN: usize = 120;
+
+/// A runnable that a language's runnables query or a language server reported as a test.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiscoveredTest {
+    pub name: String,
+    pub row: u32,
+    pub column: u32,
+    /// The byte range of the whole test, which contains the tests nested in it.
+    pub range: Range<usize>,
+    pub tags: Vec<String>,
+    /// The values of the runnables query's captures, which tasks can refer to.
+    pub extra_variables: HashMap<String, String>,
+}
+
+/// A test in the tree shown for a file, listed in the order the tree is displayed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TestNode {
+    pub name: String,
+    /// The names of the enclosing tests and of this one, joined by `::`, which identify the test
+    /// when its file is scanned again.
+    pub qualified_name: String,
+    pub depth: usize,
+    pub row: u32,
+    pub column: u32,
+    pub tags: Vec<String>,
+    pub extra_variables: HashMap<String, String>,
+    pub has_children: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TestOutcome {
+    Running,
+    Passed,
+    Failed,
+}
+
+/// Whether a runnable's tag marks it as a test, such as `rust-test`, `go-subtest` or
+/// `python-pytest-method`, rather than as a main function or a script.
+pub fn is_test_tag(tag: &str) -> bool {
+    tag.contains("test")
+}
+
+/// The name shown for a test, which is the first line of the text its runnable captured, unless
+/// that doesn't look like a name, such as the comment that a documentation test starts with.
+pub fn test_name(run_text: &str, tag: &str, row: u32) -> String {
+    let name = run_text.lines().next().unwrap_or_default().trim();
+    let is_name = !name.is_empty()
+        && name.len() <= MAX_NAME_LEN
+        && name.chars().any(char::is_alphanumeric)
+        && !name.starts_with("//")
+        && !name.starts_with('#');
+    if is_name {
+        name.to_string()
+    } else {
+        format!("{tag} on line {}", row + 1)
+    }
+}
+
+/// The name of the test that a language server's runnable runs, if it runs a test. Language
+/// servers label them by kind and then by path, such as `test tests::it_works` or
+/// `test-mod tests`.
+pub fn lsp_test_name(label: &str) -> Option<String> {
+    let (kind, path) = label.trim().split_once(' ')?;
+    if !is_test_tag(kind) {
+        return None;
+    }
+    let path = path.trim();
+    let name = path.rsplit("::").next().unwrap_or(path);
+    (!name.is_empty()).then(|| name.to_string())
+}
+
+/// Arranges the tests of a file into a tree, where each test is nested in the closest test whose
+/// range contains it, such as a test function in a test module. Tests that start on the same row
+/// are merged, since a language server and the runnables query often both report them.
+pub fn build_test_tree(mut tests: Vec<DiscoveredTest>) -> Vec<TestNode> {
+    tests.sort_by_key(|test| (test.range.start, Reverse(test.range.end)));
+
+    let mut nodes: Vec<TestNode> = Vec::with_capacity(tests.len());
+    let mut node_ix_by_row = HashMap::default();
+    // The nodes that contain the current test, innermost last, with the offsets they end at.
+    let mut ancestors: Vec<(usize, usize)> = Vec::new();
+    for test in tests {
+        if let Some(&node_ix) = node_ix_by_row.get(&test.row) {
+            let node = &mut nodes[node_ix];
+            for tag in test.tags {
+                if !node.tags.contains(&tag) {
+                    node.tags.push(tag);
+                }
+            }
+            for (name, value) in test.extra_variables {
+                node.extra_variables.entry(name).or_insert(value);
+            }
+            continue;
+        }
+
+        while ancestors
+            .last()
+            .is_some_and(|(_, ancestor_end)| *ancestor_end < test.range.end)
+        {
+            ancestors.pop();
+        }
+        let qualified_name = match ancestors.last() {
+            Some(&(parent_ix, _)) => {
+                let parent = &mut nodes[parent_ix];
+                parent.has_children = true;
+                format!("{}::{}", parent.qualified_name, test.name)
+            }
+            None => test.name.clone(),
+        };
+
+        node_ix_by_row.insert(test.row, nodes.len());
+        ancestors.push((nodes.len(), test.range.end));
+        nodes.push(TestNode {
+            name: test.name,
+            qualified_name,
+            depth: ancestors.len() - 1,
+            row: test.row,
+            column: test.column,
+            tags: test.tags,
+            extra_variables: test.extra_variables,
+            has_children: false,
+        });
+    }
+    nodes
+}
+
+/// The outcome shown for a group of tests: running while any of them run, failed when any of
+/// them failed, and passed only when all of them passed.
+pub fn aggregate_outcome(
+    outcomes: impl IntoIterator<Item = Option<TestOutcome>>,
+) -> Option<TestOutcome> {
+    let mut all_passed = true;
+    let mut any_failed = false;
+    let mut is_empty = true;
+    for outcome in outcomes {
+        is_empty = false;
+        match outcome {
+            Some(TestOutcome::Running) => return Some(TestOutcome::Running),
+            Some(TestOutcome::Failed) => any_failed = true,
+            Some(TestOutcome::Passed) => {}
+            None => all_passed = false,
+        }
+    }
+    if any_failed {
+        Some(TestOutcome::Failed)
+    } else if all_passed && !is_empty {
+        Some(TestOutcome::Passed)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn discovered(name: &str, row: u32, range: Range<usize>, tag: &str) -> DiscoveredTest {
+        DiscoveredTest {
+            name: name.to_string(),
+            row,
+            column: 4,
+            range,
+            tags: vec![tag.to_string()],
+            extra_variables: HashMap::default(),
+        }
+    }
+
+    #[test]
+    fn test_build_test_tree() {
+        let nodes = build_test_tree(vec![
+            discovered("second", 8, 120..160, "rust-test"),
+            discovered("tests", 2, 20..200, "rust-mod-test"),
+            discovered("first", 4, 40..100, "rust-test"),
+            discovered("first", 4, 38..100, "lsp-test"),
+            discovered("other", 20, 210..260, "rust-test"),
+        ]);
+
+        assert_eq!(
+            nodes
+                .iter()
+                .map(|node| (
+                    node.qualified_name.as_str(),
+                    node.depth,
+                    node.has_children,
+                    node.tags.join(","),
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                ("tests", 0, true, "rust-mod-test".to_string()),
+                ("tests::first", 1, false, "lsp-test,rust-test".to_string()),
+                ("tests::second", 1, false, "rust-test".to_string()),
+                ("other", 0, false, "rust-test".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_test_names() {
+        assert_eq!(test_name("it_works", "rust-test", 3), "it_works");
+        assert_eq!(
+            test_name("/// ```\n/// assert!(true);", "rust-doc-test", 9),
+            "rust-doc-test on line 10"
+        );
+        assert_eq!(test_name("adds numbers", "js-test", 0), "adds numbers");
+
+        assert_eq!(
+            lsp_test_name("test tests::it_works"),
+            Some("it_works".into())
+        );
+        assert_eq!(lsp_test_name("test-mod tests"), Some("tests".into()));
+        assert_eq!(lsp_test_name("cargo test -p editor"), None);
+        assert_eq!(lsp_test_name("run main"), None);
+    }
+
+    #[test]
+    fn test_aggregate_outcome() {
+        use TestOutcome::*;
+
+        assert_eq!(aggregate_outcome([]), None);
+        assert_eq!(
+            aggregate_outcome([Some(Passed), Some(Passed)]),
+            Some(Passed)
+        );
+        assert_eq!(aggregate_outcome([Some(Passed), None]), None);
+        assert_eq!(aggregate_outcome([Some(Failed), None]), Some(Failed));
+        assert_eq!(
+            aggregate_outcome([Some(Failed), Some(Running)]),
+            Some(Running)
+        );
+    }
+}
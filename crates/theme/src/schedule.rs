@@ -0,0 +1,265 @@
+use std::f64::consts::PI;
+use std::time::Duration;
+
+use chrono::{DateTime, Datelike, Local, NaiveDate, NaiveTime, TimeZone, Utc};
+use gpui::{App, Global, Task};
+use settings::{Settings, ThemeMode};
+
+use crate::{Appearance, GlobalTheme, ThemeSettings};
+
+const SCHEDULE_POLL_INTERVAL: Duration = Duration::from_secs(60);
+
+fn default_light_start() -> NaiveTime {
+    NaiveTime::from_hms_opt(7, 0, 0).unwrap_or_default()
+}
+
+fn default_dark_start() -> NaiveTime {
+    NaiveTime::from_hms_opt(19, 0, 0).unwrap_or_default()
+}
+
+/// When to switch between the light and dark themes in the [`ThemeMode::Schedule`] mode.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ThemeSchedule {
+    /// The local time at which to switch to the light theme.
+    pub light_start: NaiveTime,
+    /// The local time at which to switch to the dark theme.
+    pub dark_start: NaiveTime,
+    /// The latitude and longitude, in degrees, at whose sunrise and sunset to switch themes
+    /// instead of at `light_start` and `dark_start`.
+    pub location: Option<(f64, f64)>,
+}
+
+impl Default for ThemeSchedule {
+    fn default() -> Self {
+        Self {
+            light_start: default_light_start(),
+            dark_start: default_dark_start(),
+            location: None,
+        }
+    }
+}
+
+impl From<&settings::ThemeScheduleContent> for ThemeSchedule {
+    fn from(content: &settings::ThemeScheduleContent) -> Self {
+        fn parse_time(time: Option<&str>, default: NaiveTime) -> NaiveTime {
+            let Some(time) = time else {
+                return default;
+            };
+            match NaiveTime::parse_from_str(time, "%H:%M") {
+                Ok(time) => time,
+                Err(error) => {
+                    log::error!("invalid theme schedule time {time:?}: {error}");
+                    default
+                }
+            }
+        }
+
+        Self {
+            light_start: parse_time(content.light_start.as_deref(), default_light_start()),
+            dark_start: parse_time(content.dark_start.as_deref(), default_dark_start()),
+            location: content.latitude.zip(content.longitude),
+        }
+    }
+}
+
+impl ThemeSchedule {
+    /// Returns the [`Appearance`] the schedule prescribes at the given time.
+    pub fn appearance_at(&self, now: DateTime<Local>) -> Appearance {
+        let is_light = match self.location {
+            Some((latitude, longitude)) => match sun_times(now.date_naive(), latitude, longitude) {
+                SunTimes::RisesAndSets { sunrise, sunset } => {
+                    let now = now.with_timezone(&Utc);
+                    sunrise <= now && now < sunset
+                }
+                SunTimes::AlwaysUp => true,
+                SunTimes::AlwaysDown => false,
+            },
+            None => {
+                let time = now.time();
+                if self.light_start <= self.dark_start {
+                    self.light_start <= time && time < self.dark_start
+                } else {
+                    time >= self.light_start || time < self.dark_start
+                }
+            }
+        };
+        if is_light {
+            Appearance::Light
+        } else {
+            Appearance::Dark
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum SunTimes {
+    RisesAndSets {
+        sunrise: DateTime<Utc>,
+        sunset: DateTime<Utc>,
+    },
+    AlwaysUp,
+    AlwaysDown,
+}
+
+/// Approximates sunrise and sunset on the given date using the NOAA solar equations, which
+/// are accurate to within a few minutes outside of the polar regions.
+fn sun_times(date: NaiveDate, latitude: f64, longitude: f64) -> SunTimes {
+    let fractional_year = 2.0 * PI / 365.0 * (date.ordinal0() as f64);
+    let equation_of_time_minutes = 229.18
+        * (0.000075 + 0.001868 * fractional_year.cos()
+            - 0.032077 * fractional_year.sin()
+            - 0.014615 * (2.0 * fractional_year).cos()
+            - 0.040849 * (2.0 * fractional_year).sin());
+    let declination = 0.006918 - 0.399912 * fractional_year.cos()
+        + 0.070257 * fractional_year.sin()
+        - 0.006758 * (2.0 * fractional_year).cos()
+        + 0.000907 * (2.0 * fractional_year).sin()
+        - 0.002697 * (3.0 * fractional_year).cos()
+        + 0.00148 * (3.0 * fractional_year).sin();
+
+    let latitude = latitude.to_radians();
+    let hour_angle_cos = 90.833_f64.to_radians().cos() / (latitude.cos() * declination.cos())
+        - latitude.tan() * declination.tan();
+    if hour_angle_cos < -1.0 {
+        return SunTimes::AlwaysUp;
+    }
+    if hour_angle_cos > 1.0 {
+        return SunTimes::AlwaysDown;
+    }
+    let hour_angle = hour_angle_cos.acos().to_degrees();
+
+    let midnight = Utc.from_utc_datetime(&date.and_time(NaiveTime::MIN));
+    let at_minutes = |minutes: f64| midnight + chrono::Duration::seconds((minutes * 60.0) as i64);
+    SunTimes::RisesAndSets {
+        sunrise: at_minutes(720.0 - 4.0 * (longitude + hour_angle) - equation_of_time_minutes),
+        sunset: at_minutes(720.0 - 4.0 * (longitude - hour_angle) - equation_of_time_minutes),
+    }
+}
+
+/// The appearance prescribed by the configured [`ThemeSchedule`].
+#[derive(Debug, Clone, Copy)]
+pub struct ScheduledAppearance(pub Appearance);
+
+#[derive(Default)]
+struct GlobalScheduledAppearance {
+    appearance: Option<Appearance>,
+    poll_task: Option<Task<()>>,
+}
+
+impl Global for GlobalScheduledAppearance {}
+
+impl ScheduledAppearance {
+    /// Initializes the [`ScheduledAppearance`] for the application.
+    pub fn init(cx: &mut App) {
+        cx.default_global::<GlobalScheduledAppearance>();
+        Self::refresh(cx);
+    }
+
+    /// Returns the global [`ScheduledAppearance`].
+    pub fn global(cx: &App) -> Self {
+        let appearance = cx
+            .try_global::<GlobalScheduledAppearance>()
+            .and_then(|scheduled| scheduled.appearance);
+        Self(appearance.unwrap_or_else(|| {
+            ThemeSettings::get_global(cx)
+                .theme_schedule
+                .appearance_at(Local::now())
+        }))
+    }
+
+    /// Recomputes the scheduled appearance, reloading the themes if it changed, and polls
+    /// for further changes while a theme selection uses the [`ThemeMode::Schedule`] mode.
+    pub(crate) fn refresh(cx: &mut App) {
+        let settings = ThemeSettings::get_global(cx);
+        let schedule_in_use = settings.theme.mode() == Some(ThemeMode::Schedule)
+            || settings.icon_theme.mode() == Some(ThemeMode::Schedule);
+        let appearance = settings.theme_schedule.appearance_at(Local::now());
+
+        let global = cx.global_mut::<GlobalScheduledAppearance>();
+        let previous_appearance = global.appearance.replace(appearance);
+        if !schedule_in_use {
+            global.poll_task = None;
+        } else if global.poll_task.is_none() {
+            let poll_task = cx.spawn(async move |cx| {
+                loop {
+                    cx.background_executor().timer(SCHEDULE_POLL_INTERVAL).await;
+                    if cx.update(Self::refresh).is_err() {
+                        break;
+                    }
+                }
+            });
+            cx.global_mut::<GlobalScheduledAppearance>().poll_task = Some(poll_task);
+        }
+
+        if previous_appearance.is_some_and(|previous| previous != appearance) && schedule_in_use {
+            GlobalTheme::reload_theme(cx);
+            GlobalTheme::reload_icon_theme(cx);
+        }
+    }
+}
+
+/// Returns the appearance that a dynamic theme selection in the given [`ThemeMode`] follows.
+pub fn appearance_for_mode(mode: Option<ThemeMode>, cx: &App) -> Appearance {
+    match mode {
+        Some(ThemeMode::Schedule) => ScheduledAppearance::global(cx).0,
+        _ => crate::SystemAppearance::global(cx).0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Timelike;
+
+    use super::*;
+
+    fn local_time(hour: u32, minute: u32) -> DateTime<Local> {
+        Local
+            .from_local_datetime(
+                &NaiveDate::from_ymd_opt(2024, 6, 21)
+                    .and_then(|date| date.and_hms_opt(hour, minute, 0))
+                    .expect("valid date"),
+            )
+            .earliest()
+            .expect("valid local time")
+    }
+
+    #[test]
+    fn test_fixed_time_schedule() {
+        let schedule = ThemeSchedule::default();
+        assert_eq!(schedule.appearance_at(local_time(6, 59)), Appearance::Dark);
+        assert_eq!(schedule.appearance_at(local_time(7, 0)), Appearance::Light);
+        assert_eq!(
+            schedule.appearance_at(local_time(18, 59)),
+            Appearance::Light
+        );
+        assert_eq!(schedule.appearance_at(local_time(19, 0)), Appearance::Dark);
+
+        let overnight_light = ThemeSchedule {
+            light_start: NaiveTime::from_hms_opt(22, 0, 0).expect("valid time"),
+            dark_start: NaiveTime::from_hms_opt(6, 0, 0).expect("valid time"),
+            location: None,
+        };
+        assert_eq!(
+            overnight_light.appearance_at(local_time(23, 0)),
+            Appearance::Light
+        );
+        assert_eq!(
+            overnight_light.appearance_at(local_time(12, 0)),
+            Appearance::Dark
+        );
+    }
+
+    #[test]
+    fn test_sun_times() {
+        let midsummer = NaiveDate::from_ymd_opt(2024, 6, 21).expect("valid date");
+
+        // Greenwich sees the sun rise just before 04:00 UTC and set just after 20:20 UTC.
+        let SunTimes::RisesAndSets { sunrise, sunset } = sun_times(midsummer, 51.48, 0.0) else {
+            panic!("expected the sun to rise and set in Greenwich");
+        };
+        assert_eq!((sunrise.hour(), sunset.hour()), (3, 20));
+
+        assert_eq!(sun_times(midsummer, 80.0, 0.0), SunTimes::AlwaysUp);
+        assert_eq!(sun_times(midsummer, -80.0, 0.0), SunTimes::AlwaysDown);
+    }
+}
@@ -5,8 +5,9 @@ use palette::FromColor;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 pub use settings::{FontWeightContent, WindowBackgroundContent};
+use std::sync::Arc;
 
-use crate::{StatusColorsRefinement, ThemeColorsRefinement};
+use crate::{StatusColorsRefinement, SyntaxTheme, ThemeColorsRefinement};
 
 fn ensure_non_opaque(color: Hsla) -> Hsla {
     const MAXIMUM_OPACITY: f32 = 0.7;
@@ -49,8 +50,37 @@ pub struct ThemeContent {
 
 /// Returns the syntax style overrides in the [`ThemeContent`].
 pub fn syntax_overrides(this: &settings::ThemeStyleContent) -> Vec<(String, HighlightStyle)> {
-    this.syntax
+    highlight_styles(&this.syntax)
+}
+
+/// Returns the per-language syntax style overrides in the [`ThemeContent`], paired with the
+/// name of the language they apply to.
+pub fn language_syntax_overrides(
+    this: &settings::ThemeStyleContent,
+) -> Vec<(String, Vec<(String, HighlightStyle)>)> {
+    this.language_syntax
         .iter()
+        .map(|(language_name, syntax)| (language_name.clone(), highlight_styles(syntax)))
+        .collect()
+}
+
+/// Merges the syntax style overrides in the [`ThemeContent`] into the given [`SyntaxTheme`].
+pub fn merge_syntax_overrides(
+    base: Arc<SyntaxTheme>,
+    this: &settings::ThemeStyleContent,
+) -> Arc<SyntaxTheme> {
+    let mut syntax_theme = SyntaxTheme::merge(base, syntax_overrides(this));
+    for (language_name, overrides) in language_syntax_overrides(this) {
+        syntax_theme = SyntaxTheme::merge_for_language(syntax_theme, &language_name, overrides);
+    }
+    syntax_theme
+}
+
+fn highlight_styles<'a>(
+    syntax: impl IntoIterator<Item = (&'a String, &'a settings::HighlightStyleContent)>,
+) -> Vec<(String, HighlightStyle)> {
+    syntax
+        .into_iter()
         .map(|(key, style)| {
             (
                 key.clone(),
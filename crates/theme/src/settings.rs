@@ -1,6 +1,6 @@
 use crate::{
-    Appearance, DEFAULT_ICON_THEME_NAME, SyntaxTheme, Theme, status_colors_refinement,
-    syntax_overrides, theme_colors_refinement,
+    Appearance, DEFAULT_ICON_THEME_NAME, Theme, ThemeSchedule, merge_syntax_overrides,
+    status_colors_refinement, theme_colors_refinement,
 };
 use collections::HashMap;
 use derive_more::{Deref, DerefMut};
@@ -131,6 +131,8 @@ pub struct ThemeSettings {
     pub theme_overrides: HashMap<String, settings::ThemeStyleContent>,
     /// The current icon theme selection.
     pub icon_theme: IconThemeSelection,
+    /// When to switch between light and dark themes in the [ThemeMode::Schedule] mode.
+    pub theme_schedule: ThemeSchedule,
     /// The density of the UI.
     /// Note: This setting is still experimental. See [this tracking issue](
     pub ui_density: UiDensity,
@@ -229,13 +231,16 @@ impl From<settings::ThemeSelection> for ThemeSelection {
 
 impl ThemeSelection {
     /// Returns the theme name for the selected [ThemeMode].
-    pub fn name(&self, system_appearance: Appearance) -> ThemeName {
+    ///
+    /// `appearance` is followed by the [ThemeMode::System] and [ThemeMode::Schedule] modes,
+    /// see [crate::appearance_for_mode].
+    pub fn name(&self, appearance: Appearance) -> ThemeName {
         match self {
             Self::Static(theme) => theme.clone(),
             Self::Dynamic { mode, light, dark } => match mode {
                 ThemeMode::Light => light.clone(),
                 ThemeMode::Dark => dark.clone(),
-                ThemeMode::System => match system_appearance {
+                ThemeMode::System | ThemeMode::Schedule => match appearance {
                     Appearance::Light => light.clone(),
                     Appearance::Dark => dark.clone(),
                 },
@@ -281,13 +286,13 @@ impl From<settings::IconThemeSelection> for IconThemeSelection {
 
 impl IconThemeSelection {
     /// Returns the icon theme name based on the given [`Appearance`].
-    pub fn name(&self, system_appearance: Appearance) -> IconThemeName {
+    pub fn name(&self, appearance: Appearance) -> IconThemeName {
         match self {
             Self::Static(theme) => theme.clone(),
             Self::Dynamic { mode, light, dark } => match mode {
                 ThemeMode::Light => light.clone(),
                 ThemeMode::Dark => dark.clone(),
-                ThemeMode::System => match system_appearance {
+                ThemeMode::System | ThemeMode::Schedule => match appearance {
                     Appearance::Light => light.clone(),
                     Appearance::Dark => dark.clone(),
                 },
@@ -317,7 +322,7 @@ pub fn set_theme(
             settings::ThemeSelection::Dynamic { mode, light, dark } => match mode {
                 ThemeMode::Light => light,
                 ThemeMode::Dark => dark,
-                ThemeMode::System => match appearance {
+                ThemeMode::System | ThemeMode::Schedule => match appearance {
                     Appearance::Light => light,
                     Appearance::Dark => dark,
                 },
@@ -344,7 +349,7 @@ pub fn set_icon_theme(
             settings::IconThemeSelection::Dynamic { mode, light, dark } => match mode {
                 ThemeMode::Light => light,
                 ThemeMode::Dark => dark,
-                ThemeMode::System => match appearance {
+                ThemeMode::System | ThemeMode::Schedule => match appearance {
                     Appearance::Light => light,
                     Appearance::Dark => dark,
                 },
@@ -552,10 +557,8 @@ impl ThemeSettings {
         base_theme.styles.status.refine(&status_color_refinement);
         base_theme.styles.player.merge(&theme_overrides.players);
         base_theme.styles.accents.merge(&theme_overrides.accents);
-        base_theme.styles.syntax = SyntaxTheme::merge(
-            base_theme.styles.syntax.clone(),
-            syntax_overrides(&theme_overrides),
-        );
+        base_theme.styles.syntax =
+            merge_syntax_overrides(base_theme.styles.syntax.clone(), theme_overrides);
     }
 }
 
@@ -723,6 +726,11 @@ impl settings::Settings for ThemeSettings {
             experimental_theme_overrides: content.experimental_theme_overrides.clone(),
             theme_overrides: content.theme_overrides.clone(),
             icon_theme: icon_theme_selection,
+            theme_schedule: content
+                .theme_schedule
+                .as_ref()
+                .map(ThemeSchedule::from)
+                .unwrap_or_default(),
             ui_density: content.ui_density.unwrap_or_default().into(),
             unnecessary_code_fade: content.unnecessary_code_fade.unwrap().0.clamp(0.0, 0.9),
         }
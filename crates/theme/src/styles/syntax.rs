@@ -85,8 +85,42 @@ impl SyntaxTheme {
             highlights: merged_highlights,
         })
     }
+
+    /// Returns the key under which a style that only applies to buffers of the given language
+    /// is stored.
+    pub fn language_key(language_name: &str, name: &str) -> String {
+        format!("{language_name}{LANGUAGE_KEY_SEPARATOR}{name}")
+    }
+
+    /// Returns a new [`Arc<SyntaxTheme>`] with the given syntax styles merged in for buffers of
+    /// the given language only. Any attributes left unset inherit the style of the same name
+    /// that applies to all languages.
+    pub fn merge_for_language(
+        base: Arc<Self>,
+        language_name: &str,
+        user_syntax_styles: Vec<(String, HighlightStyle)>,
+    ) -> Arc<Self> {
+        let mut language_syntax_styles = Vec::new();
+        for (name, highlight) in user_syntax_styles {
+            let language_key = Self::language_key(language_name, &name);
+            if base.highlight_id(&language_key).is_none()
+                && let Some(inherited_highlight_id) = base.highlight_id(&name)
+            {
+                language_syntax_styles.push((
+                    language_key.clone(),
+                    base.highlights[inherited_highlight_id as usize].1,
+                ));
+            }
+            language_syntax_styles.push((language_key, highlight));
+        }
+        Self::merge(base, language_syntax_styles)
+    }
 }
 
+/// Separates the language name from the style name in keys of styles that only apply to
+/// buffers of a specific language.
+pub const LANGUAGE_KEY_SEPARATOR: char = '/';
+
 #[cfg(test)]
 mod tests {
     use gpui::FontStyle;
@@ -194,4 +228,66 @@ mod tests {
             ]))
         );
     }
+
+    #[test]
+    fn test_syntax_theme_merge_for_language() {
+        let base = Arc::new(SyntaxTheme::new_test_styles([(
+            "foo",
+            HighlightStyle {
+                color: Some(gpui::red()),
+                font_style: Some(FontStyle::Italic),
+                ..Default::default()
+            },
+        )]));
+
+        let syntax_theme = SyntaxTheme::merge_for_language(
+            base,
+            "Rust",
+            vec![
+                (
+                    "foo".to_string(),
+                    HighlightStyle {
+                        color: Some(gpui::green()),
+                        ..Default::default()
+                    },
+                ),
+                (
+                    "bar".to_string(),
+                    HighlightStyle {
+                        color: Some(gpui::blue()),
+                        ..Default::default()
+                    },
+                ),
+            ],
+        );
+
+        assert_eq!(
+            syntax_theme,
+            Arc::new(SyntaxTheme::new_test_styles([
+                (
+                    "foo",
+                    HighlightStyle {
+                        color: Some(gpui::red()),
+                        font_style: Some(FontStyle::Italic),
+                        ..Default::default()
+                    }
+                ),
+                (
+                    "Rust/foo",
+                    HighlightStyle {
+                        color: Some(gpui::green()),
+                        font_style: Some(FontStyle::Italic),
+                        ..Default::default()
+                    }
+                ),
+                (
+                    "Rust/bar",
+                    HighlightStyle {
+                        color: Some(gpui::blue()),
+                        ..Default::default()
+                    }
+                ),
+            ]))
+        );
+    }
 }
@@ -15,6 +15,7 @@ mod icon_theme;
 mod icon_theme_schema;
 mod registry;
 mod scale;
+mod schedule;
 mod schema;
 mod settings;
 mod styles;
@@ -43,6 +44,7 @@ pub use crate::icon_theme::*;
 pub use crate::icon_theme_schema::*;
 pub use crate::registry::*;
 pub use crate::scale::*;
+pub use crate::schedule::*;
 pub use crate::schema::*;
 pub use crate::settings::*;
 pub use crate::styles::*;
@@ -109,6 +111,7 @@ pub fn init(themes_to_load: LoadThemes, cx: &mut App) {
     }
 
     ThemeSettings::register(cx);
+    ScheduledAppearance::init(cx);
     FontFamilyCache::init_global(cx);
 
     let theme = GlobalTheme::configured_theme(cx);
@@ -121,22 +124,31 @@ pub fn init(themes_to_load: LoadThemes, cx: &mut App) {
     let mut prev_ui_font_size_settings = settings.ui_font_size_settings();
     let mut prev_agent_ui_font_size_settings = settings.agent_ui_font_size_settings();
     let mut prev_agent_buffer_font_size_settings = settings.agent_buffer_font_size_settings();
-    let mut prev_theme_name = settings.theme.name(SystemAppearance::global(cx).0);
-    let mut prev_icon_theme_name = settings.icon_theme.name(SystemAppearance::global(cx).0);
+    let mut prev_theme_name = settings
+        .theme
+        .name(appearance_for_mode(settings.theme.mode(), cx));
+    let mut prev_icon_theme_name = settings
+        .icon_theme
+        .name(appearance_for_mode(settings.icon_theme.mode(), cx));
     let mut prev_theme_overrides = (
         settings.experimental_theme_overrides.clone(),
         settings.theme_overrides.clone(),
     );
 
     cx.observe_global::<SettingsStore>(move |cx| {
+        ScheduledAppearance::refresh(cx);
         let settings = ThemeSettings::get_global(cx);
 
         let buffer_font_size_settings = settings.buffer_font_size_settings();
         let ui_font_size_settings = settings.ui_font_size_settings();
         let agent_ui_font_size_settings = settings.agent_ui_font_size_settings();
         let agent_buffer_font_size_settings = settings.agent_buffer_font_size_settings();
-        let theme_name = settings.theme.name(SystemAppearance::global(cx).0);
-        let icon_theme_name = settings.icon_theme.name(SystemAppearance::global(cx).0);
+        let theme_name = settings
+            .theme
+            .name(appearance_for_mode(settings.theme.mode(), cx));
+        let icon_theme_name = settings
+            .icon_theme
+            .name(appearance_for_mode(settings.icon_theme.mode(), cx));
         let theme_overrides = (
             settings.experimental_theme_overrides.clone(),
             settings.theme_overrides.clone(),
@@ -271,7 +283,11 @@ impl ThemeFamily {
                 )
             })
             .collect::<Vec<_>>();
-        let syntax_theme = SyntaxTheme::merge(Arc::new(SyntaxTheme::default()), syntax_highlights);
+        let mut syntax_theme =
+            SyntaxTheme::merge(Arc::new(SyntaxTheme::default()), syntax_highlights);
+        for (language_name, overrides) in language_syntax_overrides(&theme.style) {
+            syntax_theme = SyntaxTheme::merge_for_language(syntax_theme, &language_name, overrides);
+        }
 
         let window_background_appearance = theme
             .style
@@ -452,7 +468,9 @@ impl GlobalTheme {
         let theme_settings = ThemeSettings::get_global(cx);
         let system_appearance = SystemAppearance::global(cx);
 
-        let theme_name = theme_settings.theme.name(*system_appearance);
+        let theme_name = theme_settings
+            .theme
+            .name(appearance_for_mode(theme_settings.theme.mode(), cx));
 
         let theme = match themes.get(&theme_name.0) {
             Ok(theme) => theme,
@@ -482,9 +500,9 @@ impl GlobalTheme {
     fn configured_icon_theme(cx: &mut App) -> Arc<IconTheme> {
         let themes = ThemeRegistry::default_global(cx);
         let theme_settings = ThemeSettings::get_global(cx);
-        let system_appearance = SystemAppearance::global(cx);
-
-        let icon_theme_name = theme_settings.icon_theme.name(*system_appearance);
+        let icon_theme_name = theme_settings
+            .icon_theme
+            .name(appearance_for_mode(theme_settings.icon_theme.mode(), cx));
 
         match themes.get_icon_theme(&icon_theme_name.0) {
             Ok(theme) => theme,
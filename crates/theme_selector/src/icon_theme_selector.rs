@@ -7,10 +7,7 @@ use gpui::{
 use picker::{Picker, PickerDelegate};
 use settings::{Settings as _, SettingsStore, update_settings_file};
 use std::sync::Arc;
-use theme::{
-    Appearance, IconThemeName, IconThemeSelection, SystemAppearance, ThemeMeta, ThemeRegistry,
-    ThemeSettings,
-};
+use theme::{IconThemeName, IconThemeSelection, ThemeMeta, ThemeRegistry, ThemeSettings};
 use ui::{ListItem, ListItemSpacing, prelude::*, v_flex};
 use util::ResultExt;
 use workspace::{ModalView, ui::HighlightedLabel};
@@ -69,9 +66,10 @@ impl IconThemeSelectorDelegate {
         cx: &mut Context<IconThemeSelector>,
     ) -> Self {
         let theme_settings = ThemeSettings::get_global(cx);
-        let original_theme = theme_settings
-            .icon_theme
-            .name(SystemAppearance::global(cx).0);
+        let original_theme = theme_settings.icon_theme.name(theme::appearance_for_mode(
+            theme_settings.icon_theme.mode(),
+            cx,
+        ));
 
         let registry = ThemeRegistry::global(cx);
         let mut themes = registry
@@ -157,19 +155,25 @@ impl PickerDelegate for IconThemeSelectorDelegate {
     fn confirm(
         &mut self,
         _: bool,
-        window: &mut Window,
+        _: &mut Window,
         cx: &mut Context<Picker<IconThemeSelectorDelegate>>,
     ) {
         self.selection_completed = true;
 
         let theme_settings = ThemeSettings::get_global(cx);
-        let theme_name = theme_settings
-            .icon_theme
-            .name(SystemAppearance::global(cx).0);
-
-        let appearance = Appearance::from(window.appearance());
-
-        update_settings_file(self.fs.clone(), cx, move |settings, _| {
+        let theme_name = theme_settings.icon_theme.name(theme::appearance_for_mode(
+            theme_settings.icon_theme.mode(),
+            cx,
+        ));
+
+        update_settings_file(self.fs.clone(), cx, move |settings, cx| {
+            // The preview overrides the selection in memory, so the mode comes from the file.
+            let mode = settings
+                .theme
+                .icon_theme
+                .as_ref()
+                .and_then(|icon_theme| icon_theme.mode());
+            let appearance = theme::appearance_for_mode(mode, cx);
             theme::set_icon_theme(settings, theme_name, appearance);
         });
 
@@ -9,7 +9,7 @@ use gpui::{
 use picker::{Picker, PickerDelegate};
 use settings::{Settings, SettingsStore, update_settings_file};
 use std::sync::Arc;
-use theme::{Appearance, Theme, ThemeMeta, ThemeRegistry, ThemeSettings};
+use theme::{Theme, ThemeMeta, ThemeRegistry, ThemeSettings};
 use ui::{ListItem, ListItemSpacing, prelude::*, v_flex};
 use util::ResultExt;
 use workspace::{ModalView, Workspace, ui::HighlightedLabel, with_active_or_new_workspace};
@@ -226,15 +226,18 @@ impl PickerDelegate for ThemeSelectorDelegate {
     fn confirm(
         &mut self,
         _: bool,
-        window: &mut Window,
+        _: &mut Window,
         cx: &mut Context<Picker<ThemeSelectorDelegate>>,
     ) {
         self.selection_completed = true;
 
-        let appearance = Appearance::from(window.appearance());
-        let theme_name = ThemeSettings::get_global(cx).theme.name(appearance).0;
+        let theme = &ThemeSettings::get_global(cx).theme;
+        let theme_name = theme.name(theme::appearance_for_mode(theme.mode(), cx)).0;
 
-        update_settings_file(self.fs.clone(), cx, move |settings, _| {
+        update_settings_file(self.fs.clone(), cx, move |settings, cx| {
+            // The preview overrides the selection in memory, so the mode comes from the file.
+            let mode = settings.theme.theme.as_ref().and_then(|theme| theme.mode());
+            let appearance = theme::appearance_for_mode(mode, cx);
             theme::set_theme(settings, theme_name.to_string(), appearance);
         });
 
@@ -395,3 +398,74 @@ impl PickerDelegate for ThemeSelectorDelegate {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::TestAppContext;
+    use menu::Confirm;
+    use project::{FakeFs, Project};
+    use serde_json::json;
+    use workspace::AppState;
+
+    #[gpui::test]
+    async fn test_confirm_in_schedule_mode(cx: &mut TestAppContext) {
+        // A schedule that starts and ends the light period at the same time is always dark,
+        // while the test window has a light appearance.
+        let settings_json = json!({
+            "theme": {
+                "mode": "schedule",
+                "light": "Ayu Light",
+                "dark": "Ayu Dark",
+            },
+            "theme_schedule": {
+                "light_start": "12:00",
+                "dark_start": "12:00",
+            },
+        });
+        cx.update(|cx| {
+            let app_state = AppState::test(cx);
+            language::init(cx);
+            editor::init(cx);
+            workspace::init(app_state, cx);
+            Project::init_settings(cx);
+            super::init(cx);
+            SettingsStore::update_global(cx, |store, cx| {
+                store
+                    .set_user_settings(&settings_json.to_string(), cx)
+                    .unwrap();
+            });
+        });
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_file(
+            paths::settings_file(),
+            settings_json.to_string().into_bytes(),
+        )
+        .await;
+        let project = Project::test(fs.clone(), [], cx).await;
+        let (workspace, cx) =
+            cx.add_window_view(|window, cx| Workspace::test_new(project, window, cx));
+
+        cx.dispatch_action(zed_actions::theme_selector::Toggle::default());
+        cx.run_until_parked();
+        workspace.update(cx, |workspace, cx| {
+            assert!(workspace.active_modal::<ThemeSelector>(cx).is_some());
+        });
+
+        cx.dispatch_action(Confirm);
+        cx.run_until_parked();
+
+        let settings_text = fs.load(paths::settings_file()).await.unwrap();
+        let settings: serde_json::Value = serde_json_lenient::from_str(&settings_text).unwrap();
+        assert_eq!(
+            settings["theme"],
+            json!({
+                "mode": "schedule",
+                "light": "Ayu Light",
+                "dark": "One Dark",
+            }),
+            "the theme should be selected for the scheduled appearance, not the window's"
+        );
+    }
+}
@@ -0,0 +1,74 @@
+mod type_hierarchy_panel;
+
+use gpui::{App, AppContext as _, Pixels, actions, px};
+use settings::Settings;
+use workspace::{Workspace, dock::DockPosition};
+
+use crate::type_hierarchy_panel::HierarchyDirection;
+pub use crate::type_hierarchy_panel::TypeHierarchyPanel;
+
+actions!(
+    type_hierarchy,
+    [
+        /// Toggles focus on the type hierarchy panel.
+        ToggleFocus,
+        /// Shows the types that the type under the cursor extends or implements.
+        ShowSupertypes,
+        /// Shows the types that extend or implement the type under the cursor.
+        ShowSubtypes,
+        /// Switches the type hierarchy between supertypes and subtypes.
+        ToggleDirection,
+    ]
+);
+
+pub fn init(cx: &mut App) {
+    TypeHierarchySettings::register(cx);
+
+    cx.observe_new(|workspace: &mut Workspace, window, cx| {
+        let Some(window) = window else {
+            return;
+        };
+        workspace.register_action(|workspace, _: &ToggleFocus, window, cx| {
+            workspace.toggle_panel_focus::<TypeHierarchyPanel>(window, cx);
+        });
+        workspace.register_action(|workspace, _: &ShowSupertypes, window, cx| {
+            type_hierarchy_panel::show_type_hierarchy(
+                workspace,
+                HierarchyDirection::Supertypes,
+                window,
+                cx,
+            );
+        });
+        workspace.register_action(|workspace, _: &ShowSubtypes, window, cx| {
+            type_hierarchy_panel::show_type_hierarchy(
+                workspace,
+                HierarchyDirection::Subtypes,
+                window,
+                cx,
+            );
+        });
+
+        let panel = cx.new(|cx| TypeHierarchyPanel::new(workspace, cx));
+        workspace.add_panel(panel, window, cx);
+    })
+    .detach();
+}
+
+#[derive(Debug)]
+pub struct TypeHierarchySettings {
+    pub button: bool,
+    pub dock: DockPosition,
+    pub default_width: Pixels,
+}
+
+impl Settings for TypeHierarchySettings {
+    fn from_settings(content: &settings::SettingsContent) -> Self {
+        let panel = content.type_hierarchy.as_ref().unwrap();
+
+        Self {
+            button: panel.button.unwrap(),
+            dock: panel.dock.unwrap().into(),
+            default_width: panel.default_width.map(px).unwrap(),
+        }
+    }
+}
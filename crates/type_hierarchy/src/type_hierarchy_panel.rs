@@ -0,0 +1,496 @@
+use std::{ops::Range, sync::Arc};
+
+use anyhow::Context as _;
+use editor::{Editor, SelectionEffects, scroll::Autoscroll};
+use fs::Fs;
+use gpui::{
+    Action, App, Context, Entity, EventEmitter, FocusHandle, Focusable, Pixels, Task,
+    UniformListScrollHandle, WeakEntity, Window, uniform_list,
+};
+use language::{Anchor, Bias, Buffer};
+use project::{Project, TypeHierarchyItem};
+use settings::Settings as _;
+use ui::{ListItem, ListItemSpacing, Tooltip, prelude::*};
+use util::ResultExt as _;
+use workspace::{
+    Workspace,
+    dock::{DockPosition, Panel, PanelEvent},
+};
+
+use crate::{ShowSubtypes, ShowSupertypes, ToggleDirection, ToggleFocus, TypeHierarchySettings};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum HierarchyDirection {
+    Supertypes,
+    Subtypes,
+}
+
+struct HierarchyNode {
+    item: TypeHierarchyItem,
+    depth: usize,
+    /// The nodes of the item's supertypes or subtypes, once the language server reported them.
+    children: Option<Vec<usize>>,
+    is_expanded: bool,
+    is_loading: bool,
+}
+
+/// A dock panel that shows the supertypes or the subtypes of the type under the cursor as a tree,
+/// whose nodes request their own supertypes or subtypes when they are expanded.
+pub struct TypeHierarchyPanel {
+    workspace: WeakEntity<Workspace>,
+    project: Entity<Project>,
+    fs: Arc<dyn Fs>,
+    focus_handle: FocusHandle,
+    scroll_handle: UniformListScrollHandle,
+    width: Option<Pixels>,
+    direction: HierarchyDirection,
+    /// The buffer that the hierarchy was requested from. Language servers only answer requests
+    /// for documents that are open, so the requests for every node are made through it.
+    buffer: Option<Entity<Buffer>>,
+    roots: Vec<TypeHierarchyItem>,
+    nodes: Vec<HierarchyNode>,
+    entries: Vec<usize>,
+    /// Incremented whenever the nodes are rebuilt, so that responses for the old nodes are
+    /// dropped.
+    generation: usize,
+    prepare_task: Option<Task<()>>,
+}
+
+/// Shows the type hierarchy of the type under the cursor of the active editor in the panel.
+pub(crate) fn show_type_hierarchy(
+    workspace: &mut Workspace,
+    direction: HierarchyDirection,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let Some(editor) = workspace.active_item_as::<Editor>(cx) else {
+        return;
+    };
+    let Some((buffer, position)) = editor.update(cx, |editor, cx| {
+        let head = editor.selections.newest::<usize>(cx).head();
+        editor.buffer().read(cx).text_anchor_for_position(head, cx)
+    }) else {
+        return;
+    };
+    let Some(panel) = workspace.focus_panel::<TypeHierarchyPanel>(window, cx) else {
+        return;
+    };
+    panel.update(cx, |panel, cx| {
+        panel.show_hierarchy(buffer, position, direction, window, cx)
+    });
+}
+
+impl TypeHierarchyPanel {
+    pub(crate) fn new(workspace: &Workspace, cx: &mut Context<Self>) -> Self {
+        Self {
+            workspace: workspace.weak_handle(),
+            project: workspace.project().clone(),
+            fs: workspace.app_state().fs.clone(),
+            focus_handle: cx.focus_handle(),
+            scroll_handle: UniformListScrollHandle::new(),
+            width: None,
+            direction: HierarchyDirection::Supertypes,
+            buffer: None,
+            roots: Vec::new(),
+            nodes: Vec::new(),
+            entries: Vec::new(),
+            generation: 0,
+            prepare_task: None,
+        }
+    }
+
+    fn show_hierarchy(
+        &mut self,
+        buffer: Entity<Buffer>,
+        position: Anchor,
+        direction: HierarchyDirection,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.direction = direction;
+        self.buffer = Some(buffer.clone());
+        self.roots.clear();
+        self.rebuild_nodes(cx);
+
+        let prepare = self.project.update(cx, |project, cx| {
+            project.prepare_type_hierarchy(&buffer, position, cx)
+        });
+        self.prepare_task = Some(cx.spawn_in(window, async move |this, cx| {
+            let result = prepare.await;
+            this.update(cx, |this, cx| {
+                this.prepare_task = None;
+                match result {
+                    Ok(roots) => {
+                        this.roots = roots;
+                        this.rebuild_nodes(cx);
+                    }
+                    Err(error) => {
+                        this.show_error(error, cx);
+                        cx.notify();
+                    }
+                }
+            })
+            .ok();
+        }));
+    }
+
+    fn set_direction(&mut self, direction: HierarchyDirection, cx: &mut Context<Self>) {
+        if self.direction != direction {
+            self.direction = direction;
+            self.rebuild_nodes(cx);
+        }
+    }
+
+    fn toggle_direction(&mut self, _: &ToggleDirection, _: &mut Window, cx: &mut Context<Self>) {
+        let direction = match self.direction {
+            HierarchyDirection::Supertypes => HierarchyDirection::Subtypes,
+            HierarchyDirection::Subtypes => HierarchyDirection::Supertypes,
+        };
+        self.set_direction(direction, cx);
+    }
+
+    /// Replaces the tree with the roots alone, and requests their supertypes or subtypes right
+    /// away, since a root on its own says nothing about the hierarchy.
+    fn rebuild_nodes(&mut self, cx: &mut Context<Self>) {
+        self.generation += 1;
+        self.nodes = self
+            .roots
+            .iter()
+            .map(|item| HierarchyNode {
+                item: item.clone(),
+                depth: 0,
+                children: None,
+                is_expanded: true,
+                is_loading: false,
+            })
+            .collect();
+        for node_ix in 0..self.nodes.len() {
+            self.load_children(node_ix, cx);
+        }
+        self.update_entries(cx);
+    }
+
+    fn load_children(&mut self, node_ix: usize, cx: &mut Context<Self>) {
+        let Some(buffer) = self.buffer.clone() else {
+            return;
+        };
+        let Some(node) = self.nodes.get_mut(node_ix) else {
+            return;
+        };
+        node.is_loading = true;
+        let item = node.item.clone();
+        let direction = self.direction;
+        let generation = self.generation;
+        let request = self.project.update(cx, |project, cx| match direction {
+            HierarchyDirection::Supertypes => project.type_hierarchy_supertypes(&buffer, &item, cx),
+            HierarchyDirection::Subtypes => project.type_hierarchy_subtypes(&buffer, &item, cx),
+        });
+
+        cx.spawn(async move |this, cx| {
+            let result = request.await;
+            this.update(cx, |this, cx| {
+                if this.generation != generation {
+                    return;
+                }
+                let items = result
+                    .context("requesting the type hierarchy")
+                    .unwrap_or_else(|error| {
+                        this.show_error(error, cx);
+                        Vec::new()
+                    });
+                let depth = this.nodes[node_ix].depth + 1;
+                let first_child_ix = this.nodes.len();
+                this.nodes
+                    .extend(items.into_iter().map(|item| HierarchyNode {
+                        item,
+                        depth,
+                        children: None,
+                        is_expanded: false,
+                        is_loading: false,
+                    }));
+                let node = &mut this.nodes[node_ix];
+                node.children = Some((first_child_ix..this.nodes.len()).collect());
+                node.is_loading = false;
+                this.update_entries(cx);
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    fn toggle_expanded(&mut self, node_ix: usize, cx: &mut Context<Self>) {
+        let Some(node) = self.nodes.get_mut(node_ix) else {
+            return;
+        };
+        node.is_expanded = !node.is_expanded;
+        if node.is_expanded && node.children.is_none() && !node.is_loading {
+            self.load_children(node_ix, cx);
+        }
+        self.update_entries(cx);
+    }
+
+    fn update_entries(&mut self, cx: &mut Context<Self>) {
+        let mut entries = Vec::new();
+        let mut stack = (0..self.roots.len().min(self.nodes.len()))
+            .rev()
+            .collect::<Vec<_>>();
+        while let Some(node_ix) = stack.pop() {
+            entries.push(node_ix);
+            let node = &self.nodes[node_ix];
+            if node.is_expanded
+                && let Some(children) = &node.children
+            {
+                stack.extend(children.iter().rev());
+            }
+        }
+        self.entries = entries;
+        cx.notify();
+    }
+
+    fn open_item(&mut self, node_ix: usize, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(item) = self.nodes.get(node_ix).map(|node| node.item.clone()) else {
+            return;
+        };
+        let open_buffer = self.project.update(cx, |project, cx| {
+            if let Some(project_path) = project.find_project_path(&item.abs_path, cx) {
+                project.open_buffer(project_path, cx)
+            } else if project.is_local() {
+                project.open_local_buffer(&item.abs_path, cx)
+            } else {
+                Task::ready(Err(anyhow::anyhow!(
+                    "{} is not in the project",
+                    item.abs_path.display()
+                )))
+            }
+        });
+        let workspace = self.workspace.clone();
+
+        cx.spawn_in(window, async move |_, cx| {
+            let buffer = open_buffer.await?;
+            workspace.update_in(cx, |workspace, window, cx| {
+                let position = buffer
+                    .read(cx)
+                    .clip_point_utf16(item.selection_range.start, Bias::Left);
+                let pane = workspace.active_pane().clone();
+                let editor =
+                    workspace.open_project_item::<Editor>(pane, buffer, true, true, window, cx);
+                editor.update(cx, |editor, cx| {
+                    editor.change_selections(
+                        SelectionEffects::scroll(Autoscroll::center()),
+                        window,
+                        cx,
+                        |selections| selections.select_ranges([position..position]),
+                    );
+                });
+            })?;
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn show_error(&self, error: anyhow::Error, cx: &mut App) {
+        self.workspace
+            .update(cx, |workspace, cx| workspace.show_error(&error, cx))
+            .log_err();
+    }
+
+    fn render_header(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let title = match (self.direction, self.roots.as_slice()) {
+            (HierarchyDirection::Supertypes, [root]) => format!("Supertypes of {}", root.name),
+            (HierarchyDirection::Subtypes, [root]) => format!("Subtypes of {}", root.name),
+            (HierarchyDirection::Supertypes, _) => "Supertypes".to_string(),
+            (HierarchyDirection::Subtypes, _) => "Subtypes".to_string(),
+        };
+
+        h_flex()
+            .h(Tab::container_height(cx))
+            .px_2()
+            .gap_1()
+            .justify_between()
+            .border_b_1()
+            .border_color(cx.theme().colors().border)
+            .child(
+                Label::new(title)
+                    .size(LabelSize::Small)
+                    .color(Color::Muted)
+                    .truncate(),
+            )
+            .child(
+                h_flex()
+                    .gap_1()
+                    .child(
+                        IconButton::new("show-supertypes", IconName::ArrowUp)
+                            .icon_size(IconSize::Small)
+                            .toggle_state(self.direction == HierarchyDirection::Supertypes)
+                            .tooltip(Tooltip::for_action_title(
+                                "Show Supertypes",
+                                &ShowSupertypes,
+                            ))
+                            .on_click(cx.listener(|this, _, _, cx| {
+                                this.set_direction(HierarchyDirection::Supertypes, cx)
+                            })),
+                    )
+                    .child(
+                        IconButton::new("show-subtypes", IconName::ArrowDown)
+                            .icon_size(IconSize::Small)
+                            .toggle_state(self.direction == HierarchyDirection::Subtypes)
+                            .tooltip(Tooltip::for_action_title("Show Subtypes", &ShowSubtypes))
+                            .on_click(cx.listener(|this, _, _, cx| {
+                                this.set_direction(HierarchyDirection::Subtypes, cx)
+                            })),
+                    ),
+            )
+    }
+
+    fn render_entry(&self, node_ix: usize, cx: &mut Context<Self>) -> Option<AnyElement> {
+        let node = self.nodes.get(node_ix)?;
+        let has_children = node
+            .children
+            .as_ref()
+            .is_none_or(|children| !children.is_empty());
+        let file_name = node
+            .item
+            .abs_path
+            .file_name()
+            .map(|file_name| file_name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let location = format!("{file_name}:{}", node.item.selection_range.start.0.row + 1);
+
+        Some(
+            ListItem::new(("type-hierarchy-node", node_ix))
+                .spacing(ListItemSpacing::Sparse)
+                .indent_level(node.depth)
+                .indent_step_size(px(12.))
+                .toggle(has_children.then_some(node.is_expanded))
+                .on_toggle(cx.listener(move |this, _, _, cx| this.toggle_expanded(node_ix, cx)))
+                .when(node.is_loading, |this| {
+                    this.end_slot(
+                        Icon::new(IconName::ArrowCircle)
+                            .size(IconSize::Small)
+                            .color(Color::Muted),
+                    )
+                })
+                .child(
+                    h_flex()
+                        .gap_2()
+                        .child(Label::new(node.item.name.clone()).truncate())
+                        .when_some(node.item.detail.clone(), |this, detail| {
+                            this.child(
+                                Label::new(detail)
+                                    .size(LabelSize::Small)
+                                    .color(Color::Muted)
+                                    .truncate(),
+                            )
+                        })
+                        .child(
+                            Label::new(location)
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        ),
+                )
+                .on_click(
+                    cx.listener(move |this, _, window, cx| this.open_item(node_ix, window, cx)),
+                )
+                .into_any_element(),
+        )
+    }
+}
+
+impl Render for TypeHierarchyPanel {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let content = if self.entries.is_empty() {
+            let message = if self.prepare_task.is_some() {
+                "Loading type hierarchy…"
+            } else if self.buffer.is_some() {
+                "No type found under the cursor."
+            } else {
+                "Show the supertypes or subtypes of the type under the cursor to explore them here."
+            };
+            v_flex()
+                .p_2()
+                .child(Label::new(message).color(Color::Muted))
+                .into_any_element()
+        } else {
+            uniform_list(
+                "type-hierarchy-entries",
+                self.entries.len(),
+                cx.processor(|this, range: Range<usize>, _, cx| {
+                    this.entries[range]
+                        .to_vec()
+                        .into_iter()
+                        .filter_map(|node_ix| this.render_entry(node_ix, cx))
+                        .collect()
+                }),
+            )
+            .size_full()
+            .p_1()
+            .track_scroll(self.scroll_handle.clone())
+            .into_any_element()
+        };
+
+        v_flex()
+            .key_context("TypeHierarchyPanel")
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::toggle_direction))
+            .size_full()
+            .bg(cx.theme().colors().panel_background)
+            .child(self.render_header(cx))
+            .child(content)
+    }
+}
+
+impl Focusable for TypeHierarchyPanel {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl EventEmitter<PanelEvent> for TypeHierarchyPanel {}
+
+impl Panel for TypeHierarchyPanel {
+    fn persistent_name() -> &'static str {
+        "TypeHierarchyPanel"
+    }
+
+    fn position(&self, _: &Window, cx: &App) -> DockPosition {
+        TypeHierarchySettings::get_global(cx).dock
+    }
+
+    fn position_is_valid(&self, position: DockPosition) -> bool {
+        matches!(position, DockPosition::Left | DockPosition::Right)
+    }
+
+    fn set_position(&mut self, position: DockPosition, _: &mut Window, cx: &mut Context<Self>) {
+        settings::update_settings_file(self.fs.clone(), cx, move |settings, _| {
+            settings.type_hierarchy.get_or_insert_default().dock = Some(position.into())
+        });
+    }
+
+    fn size(&self, _: &Window, cx: &App) -> Pixels {
+        self.width
+            .unwrap_or_else(|| TypeHierarchySettings::get_global(cx).default_width)
+    }
+
+    fn set_size(&mut self, size: Option<Pixels>, _: &mut Window, cx: &mut Context<Self>) {
+        self.width = size;
+        cx.notify();
+    }
+
+    fn icon(&self, _: &Window, cx: &App) -> Option<IconName> {
+        TypeHierarchySettings::get_global(cx)
+            .button
+            .then_some(IconName::ListTree)
+    }
+
+    fn icon_tooltip(&self, _window: &Window, _cx: &App) -> Option<&'static str> {
+        Some("Type Hierarchy")
+    }
+
+    fn toggle_action(&self) -> Box<dyn Action> {
+        Box::new(ToggleFocus)
+    }
+
+    fn activation_priority(&self) -> u32 {
+        11
+    }
+}
@@ -29,7 +29,7 @@ use std::{
 use task::{HideStrategy, RevealStrategy, SpawnInTerminal, TaskId};
 use ui::ActiveTheme;
 use util::{ResultExt, rel_path::RelPath};
-use workspace::{Item, SaveIntent, notifications::NotifyResultExt};
+use workspace::{Item, SaveIntent, WorkspaceSettings, notifications::NotifyResultExt};
 use workspace::{SplitDirection, notifications::DetachAndPromptErr};
 use zed_actions::{OpenDocs, RevealTarget};
 
@@ -907,6 +907,18 @@ impl VimCommand {
                     offset: Self::parse_offset(chars),
                 })
             }
+            '/' | '?' => {
+                // Without a closing delimiter this is a search (`:/foo`), not an address.
+                let mut lookahead = chars.clone();
+                let delimiter = lookahead.next()?;
+                let pattern = parse_pattern(&mut lookahead, delimiter)?;
+                *chars = lookahead;
+                Some(Position::Pattern {
+                    pattern,
+                    backwards: delimiter == '?',
+                    offset: Self::parse_offset(chars),
+                })
+            }
             _ => None,
         }
     }
@@ -938,10 +950,25 @@ impl VimCommand {
 
 #[derive(Clone, Debug, Deserialize, JsonSchema, PartialEq)]
 enum Position {
-    Line { row: u32, offset: i32 },
-    Mark { name: char, offset: i32 },
-    LastLine { offset: i32 },
-    CurrentLine { offset: i32 },
+    Line {
+        row: u32,
+        offset: i32,
+    },
+    Mark {
+        name: char,
+        offset: i32,
+    },
+    LastLine {
+        offset: i32,
+    },
+    CurrentLine {
+        offset: i32,
+    },
+    Pattern {
+        pattern: String,
+        backwards: bool,
+        offset: i32,
+    },
 }
 
 impl Position {
@@ -995,6 +1022,40 @@ impl Position {
                 .to_point(&snapshot.buffer_snapshot())
                 .row
                 .saturating_add_signed(*offset),
+            Position::Pattern {
+                pattern,
+                backwards,
+                offset,
+            } => {
+                let regex = Regex::new(pattern)?;
+                let buffer = snapshot.buffer_snapshot();
+                let current_row = editor
+                    .selections
+                    .newest_anchor()
+                    .head()
+                    .to_point(buffer)
+                    .row;
+                let max_row = buffer.max_row().0;
+                // Like vim, the search starts on the line after (or before) the cursor and
+                // wraps around the end of the buffer.
+                let mut rows: Box<dyn Iterator<Item = u32>> = if *backwards {
+                    Box::new((0..current_row).rev().chain((current_row..=max_row).rev()))
+                } else {
+                    Box::new((current_row + 1..=max_row).chain(0..=current_row))
+                };
+                let mut line = String::new();
+                let Some(row) = rows.find(|row| {
+                    line.clear();
+                    line.extend(buffer.text_for_range(
+                        Point::new(*row, 0)
+                            ..Point::new(*row, buffer.line_len(MultiBufferRow(*row))),
+                    ));
+                    regex.is_match(&line)
+                }) else {
+                    anyhow::bail!("pattern not found: {pattern}");
+                };
+                row.saturating_add_signed(*offset)
+            }
         };
 
         Ok(MultiBufferRow(target).min(snapshot.buffer_snapshot().max_row()))
@@ -1392,7 +1453,8 @@ pub fn command_interceptor(mut input: &str, cx: &App) -> Vec<CommandInterceptRes
 
     let (range, query) = VimCommand::parse_range(input);
     let range_prefix = input[0..(input.len() - query.len())].to_string();
-    let query = query.as_str().trim();
+    let query = expand_command_alias(query.trim(), cx);
+    let query = query.as_str();
 
     let action = if range.is_some() && query.is_empty() {
         Some(
@@ -1492,6 +1554,21 @@ pub fn command_interceptor(mut input: &str, cx: &App) -> Vec<CommandInterceptRes
     Vec::default()
 }
 
+/// Expands a user-defined command alias, keeping any range before and arguments after the
+/// command name intact, so that e.g. `:'<,'>Sort` works with an alias for `Sort`.
+fn expand_command_alias(query: &str, cx: &App) -> String {
+    let name_len = query
+        .find(|c: char| !c.is_alphanumeric())
+        .unwrap_or(query.len());
+    let (name, arguments) = query.split_at(name_len);
+    match WorkspaceSettings::get_global(cx).command_aliases.get(name) {
+        Some(alias) if !name.is_empty() => {
+            format!("{}{arguments}", alias.trim_start_matches(':'))
+        }
+        _ => query.to_string(),
+    }
+}
+
 fn generate_positions(string: &str, query: &str) -> Vec<usize> {
     let mut positions = Vec::new();
     let mut chars = query.chars();
@@ -1514,6 +1591,38 @@ fn generate_positions(string: &str, query: &str) -> Vec<usize> {
     positions
 }
 
+/// Reads a vim pattern up to (and consuming) the closing `delimiter`, flipping escaped and
+/// unescaped parens so that common vim idioms work as Rust regexes.
+///
+/// Returns `None` if the pattern isn't terminated by `delimiter`.
+fn parse_pattern(chars: &mut Peekable<Chars>, delimiter: char) -> Option<String> {
+    let mut search = String::new();
+    let mut escaped = false;
+
+    for c in chars.by_ref() {
+        if escaped {
+            escaped = false;
+            // unescape escaped parens
+            if c != '(' && c != ')' && c != delimiter {
+                search.push('\\')
+            }
+            search.push(c)
+        } else if c == '\\' {
+            escaped = true;
+        } else if c == delimiter {
+            return Some(search);
+        } else {
+            // escape unescaped parens
+            if c == '(' || c == ')' {
+                search.push('\\')
+            }
+            search.push(c)
+        }
+    }
+
+    None
+}
+
 /// Applies a command to all lines matching a pattern.
 #[derive(Debug, PartialEq, Clone, Action)]
 #[action(namespace = vim, no_json, no_register)]
@@ -1539,30 +1648,8 @@ impl OnMatchingLines {
             !c.is_alphanumeric() && *c != '"' && *c != '|' && *c != '\'' && *c != '!'
         })?;
 
-        let mut search = String::new();
-        let mut escaped = false;
-
-        for c in chars.by_ref() {
-            if escaped {
-                escaped = false;
-                // unescape escaped parens
-                if c != '(' && c != ')' && c != delimiter {
-                    search.push('\\')
-                }
-                search.push(c)
-            } else if c == '\\' {
-                escaped = true;
-            } else if c == delimiter {
-                break;
-            } else {
-                // escape unescaped parens
-                if c == '(' || c == ')' {
-                    search.push('\\')
-                }
-                search.push(c)
-            }
-        }
-
+        // Without a closing delimiter there's no command to run on the matching lines.
+        let search = parse_pattern(&mut chars, delimiter)?;
         let command: String = chars.collect();
 
         let action = WrappedAction(
@@ -2060,15 +2147,16 @@ impl ShellExec {
 mod test {
     use std::path::Path;
 
+    use super::{OnMatchingLines, command_interceptor};
     use crate::{
         VimAddon,
         state::Mode,
         test::{NeovimBackedTestContext, VimTestContext},
     };
     use editor::{Editor, EditorSettings};
-    use gpui::{Context, TestAppContext};
+    use gpui::{App, Context, TestAppContext};
     use indoc::indoc;
-    use settings::Settings;
+    use settings::{Settings, SettingsStore};
     use util::path;
     use workspace::Workspace;
 
@@ -2416,6 +2504,96 @@ mod test {
             ˇa"});
     }
 
+    #[gpui::test]
+    async fn test_command_matching_lines_without_command(cx: &mut TestAppContext) {
+        let cx = VimTestContext::new(cx, true).await;
+        let runs_on_matching_lines = |input: &str, cx: &App| {
+            command_interceptor(input, cx).iter().any(|result| {
+                result
+                    .action
+                    .as_any()
+                    .downcast_ref::<OnMatchingLines>()
+                    .is_some()
+            })
+        };
+        cx.read(|cx| {
+            assert!(runs_on_matching_lines("g/a/d", cx));
+            assert!(!runs_on_matching_lines("g/a", cx));
+            assert!(!runs_on_matching_lines("v/a", cx));
+        });
+    }
+
+    #[gpui::test]
+    async fn test_command_pattern_ranges(cx: &mut TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state(
+            indoc! {"
+            ˇa
+            foo
+            b
+            bar
+            c"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes(": / f o o / , / b a r / d enter");
+        cx.assert_state(
+            indoc! {"
+            a
+            ˇc"},
+            Mode::Normal,
+        );
+
+        cx.set_state(
+            indoc! {"
+            foo
+            a
+            b
+            ˇc"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes(": ? f o o ? + 1 enter");
+        cx.assert_state(
+            indoc! {"
+            foo
+            ˇa
+            b
+            c"},
+            Mode::Normal,
+        );
+    }
+
+    #[gpui::test]
+    async fn test_command_alias_with_range(cx: &mut TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+        cx.update_global(|store: &mut SettingsStore, cx| {
+            store.update_user_settings(cx, |settings| {
+                settings
+                    .workspace
+                    .command_aliases
+                    .insert("Srt".to_string(), "sort".to_string());
+            });
+        });
+
+        cx.set_state(
+            indoc! {"
+            ˇd
+            c
+            b
+            a"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes(": 2 , 4 S r t enter");
+        cx.assert_state(
+            indoc! {"
+            d
+            ˇa
+            b
+            c"},
+            Mode::Normal,
+        );
+    }
+
     #[gpui::test]
     async fn test_del_marks(cx: &mut TestAppContext) {
         let mut cx = NeovimBackedTestContext::new(cx).await;
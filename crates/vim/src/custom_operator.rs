@@ -0,0 +1,227 @@
+use crate::{
+    Vim,
+    motion::Motion,
+    object::Object,
+    state::{Mode, Operator},
+};
+use editor::{Editor, SelectionEffects};
+use gpui::{Action, Context, Window};
+use language::SelectionGoal;
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+/// Starts an operator that runs the given action on the text covered by the following
+/// motion or text object, e.g. `["vim::PushCustomOperator", { "action": "editor::SortLinesCaseSensitive" }]`.
+/// In visual mode the action runs on the selection immediately.
+#[derive(Clone, Deserialize, JsonSchema, PartialEq, Action)]
+#[action(namespace = vim)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct PushCustomOperator {
+    action: String,
+    #[serde(default)]
+    arguments: Option<serde_json::Value>,
+}
+
+pub(crate) fn register(editor: &mut Editor, cx: &mut Context<Vim>) {
+    Vim::action(
+        editor,
+        cx,
+        |vim, action: &PushCustomOperator, window, cx| {
+            if vim.mode.is_visual() {
+                vim.record_current_action(cx);
+                Vim::take_count(cx);
+                Vim::take_forced_motion(cx);
+                vim.store_visual_marks(window, cx);
+                vim.run_custom_operator(&action.action, action.arguments.clone(), window, cx);
+            } else {
+                vim.push_operator(
+                    Operator::Custom {
+                        action: action.action.clone(),
+                        arguments: action.arguments.clone(),
+                    },
+                    cx,
+                );
+            }
+        },
+    );
+}
+
+impl Vim {
+    pub(crate) fn custom_operator_motion(
+        &mut self,
+        action: &str,
+        arguments: Option<serde_json::Value>,
+        motion: Motion,
+        times: Option<usize>,
+        forced_motion: bool,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.stop_recording(cx);
+        self.update_editor(cx, |_, editor, cx| {
+            let text_layout_details = editor.text_layout_details(window);
+            editor.change_selections(SelectionEffects::no_scroll(), window, cx, |s| {
+                s.move_with(|map, selection| {
+                    motion.expand_selection(
+                        map,
+                        selection,
+                        times,
+                        &text_layout_details,
+                        forced_motion,
+                    );
+                });
+            });
+        });
+        self.run_custom_operator(action, arguments, window, cx);
+    }
+
+    pub(crate) fn custom_operator_object(
+        &mut self,
+        action: &str,
+        arguments: Option<serde_json::Value>,
+        object: Object,
+        around: bool,
+        times: Option<usize>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.stop_recording(cx);
+        self.update_editor(cx, |_, editor, cx| {
+            editor.change_selections(SelectionEffects::no_scroll(), window, cx, |s| {
+                s.move_with(|map, selection| {
+                    object.expand_selection(map, selection, around, times);
+                });
+            });
+        });
+        self.run_custom_operator(action, arguments, window, cx);
+    }
+
+    fn run_custom_operator(
+        &mut self,
+        action: &str,
+        arguments: Option<serde_json::Value>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let action = match cx.build_action(action, arguments) {
+            Ok(action) => action,
+            Err(error) => {
+                log::error!("Failed to build action for custom vim operator: {error}");
+                self.switch_mode(Mode::Normal, true, window, cx);
+                return;
+            }
+        };
+        // The action is dispatched on the next effect cycle, so the selections are only
+        // collapsed once it has been handled.
+        window.dispatch_action(action, cx);
+        cx.defer_in(window, |vim, window, cx| {
+            vim.update_editor(cx, |_, editor, cx| {
+                editor.change_selections(SelectionEffects::no_scroll(), window, cx, |s| {
+                    s.move_with(|_, selection| {
+                        let start = selection.start;
+                        selection.collapse_to(start, SelectionGoal::None);
+                    });
+                });
+            });
+            vim.switch_mode(Mode::Normal, true, window, cx);
+        });
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use gpui::KeyBinding;
+    use indoc::indoc;
+
+    use crate::{custom_operator::PushCustomOperator, state::Mode, test::VimTestContext};
+
+    async fn init_test(cx: &mut gpui::TestAppContext) -> VimTestContext {
+        let mut cx = VimTestContext::new(cx, true).await;
+        cx.update(|_, cx| {
+            cx.bind_keys([KeyBinding::new(
+                "g o",
+                PushCustomOperator {
+                    action: "editor::SortLinesCaseSensitive".into(),
+                    arguments: None,
+                },
+                Some("VimControl"),
+            )]);
+        });
+        cx
+    }
+
+    #[gpui::test]
+    async fn test_custom_operator_with_motion(cx: &mut gpui::TestAppContext) {
+        let mut cx = init_test(cx).await;
+
+        cx.set_state(
+            indoc! {"
+                ˇcherry
+                banana
+                apple
+                date"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("g o j");
+        cx.assert_state(
+            indoc! {"
+                ˇbanana
+                cherry
+                apple
+                date"},
+            Mode::Normal,
+        );
+
+        // The action runs on the lines covered by a text object too.
+        cx.simulate_keystrokes("g o i p");
+        cx.assert_state(
+            indoc! {"
+                ˇapple
+                banana
+                cherry
+                date"},
+            Mode::Normal,
+        );
+    }
+
+    #[gpui::test]
+    async fn test_custom_operator_with_count(cx: &mut gpui::TestAppContext) {
+        let mut cx = init_test(cx).await;
+
+        cx.set_state(
+            indoc! {"
+                date
+                ˇcherry
+                banana
+                apple"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("2 g o j");
+        cx.assert_state(
+            indoc! {"
+                date
+                ˇapple
+                banana
+                cherry"},
+            Mode::Normal,
+        );
+
+        cx.set_state(
+            indoc! {"
+                ˇdate
+                cherry
+                banana
+                apple"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("g o 3 j");
+        cx.assert_state(
+            indoc! {"
+                ˇapple
+                banana
+                cherry
+                date"},
+            Mode::Normal,
+        );
+    }
+}
@@ -0,0 +1,90 @@
+use editor::{Anchor, Editor, MultiBuffer};
+use gpui::{Action, App, Context, Entity, Window, actions};
+use workspace::pane;
+
+use crate::Vim;
+
+actions!(
+    vim,
+    [
+        /// Navigates to an older position in the jump list, including jumps from previous sessions.
+        JumpListOlder,
+        /// Navigates to a newer position in the jump list, including jumps from previous sessions.
+        JumpListNewer
+    ]
+);
+
+pub(crate) fn register(editor: &mut Editor, cx: &mut Context<Vim>) {
+    Vim::action(editor, cx, |vim, _: &JumpListOlder, window, cx| {
+        vim.move_in_jump_list(true, window, cx);
+    });
+    Vim::action(editor, cx, |vim, _: &JumpListNewer, window, cx| {
+        vim.move_in_jump_list(false, window, cx);
+    });
+}
+
+impl Vim {
+    fn move_in_jump_list(&mut self, older: bool, window: &mut Window, cx: &mut Context<Self>) {
+        let count = Vim::take_count(cx).unwrap_or(1);
+        Vim::take_forced_motion(cx);
+        let Some(workspace) = self.workspace(window) else {
+            return;
+        };
+        let Some(pane) = self.pane(window, cx) else {
+            return;
+        };
+        let can_navigate_backward = pane.read(cx).can_navigate_backward();
+        let entity_id = workspace.entity_id();
+        let Some(marks_state) = Vim::globals(cx).marks.get(&entity_id).cloned() else {
+            return;
+        };
+
+        // The in-memory navigation history of the pane always takes precedence; jumps
+        // restored from previous sessions are only visited once it has been exhausted.
+        let restored_jump = marks_state.update(cx, |marks_state, _| {
+            if older {
+                if marks_state.is_navigating_restored_jumps() || !can_navigate_backward {
+                    return marks_state.older_restored_jump(count);
+                }
+            } else if marks_state.is_navigating_restored_jumps() {
+                return marks_state.newer_restored_jump(count);
+            }
+            None
+        });
+
+        if let Some((path, point)) = restored_jump {
+            self.open_path_mark(false, path, vec![point], window, cx);
+            return;
+        }
+
+        let action = if older {
+            pane::GoBack.boxed_clone()
+        } else {
+            pane::GoForward.boxed_clone()
+        };
+        for _ in 0..count {
+            window.dispatch_action(action.boxed_clone(), cx);
+        }
+    }
+
+    pub(crate) fn push_to_jump_list(
+        &self,
+        anchor: Anchor,
+        multi_buffer: &Entity<MultiBuffer>,
+        window: &mut Window,
+        cx: &mut App,
+    ) {
+        let Some(workspace) = self.workspace(window) else {
+            return;
+        };
+        let entity_id = workspace.entity_id();
+        Vim::update_globals(cx, |vim_globals, cx| {
+            let Some(marks_state) = vim_globals.marks.get(&entity_id) else {
+                return;
+            };
+            marks_state.update(cx, |marks_state, cx| {
+                marks_state.push_jump(multi_buffer, anchor, cx);
+            });
+        });
+    }
+}
@@ -437,6 +437,15 @@ impl Vim {
             Some(Operator::Exchange) => {
                 self.exchange_motion(motion, times, forced_motion, window, cx)
             }
+            Some(Operator::Custom { action, arguments }) => self.custom_operator_motion(
+                &action,
+                arguments,
+                motion,
+                times,
+                forced_motion,
+                window,
+                cx,
+            ),
             Some(operator) => {
                 // Can't do anything for text objects, Ignoring
                 error!("Unexpected normal mode motion operator: {:?}", operator)
@@ -473,6 +482,8 @@ impl Vim {
                     self.shell_command_object(object, around, window, cx);
                 }
                 Some(Operator::Rewrap) => self.rewrap_object(object, around, times, window, cx),
+                Some(Operator::Custom { action, arguments }) => self
+                    .custom_operator_object(&action, arguments, object, around, times, window, cx),
                 Some(Operator::Lowercase) => {
                     self.convert_object(object, around, ConvertTarget::LowerCase, times, window, cx)
                 }
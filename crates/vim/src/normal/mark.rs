@@ -122,7 +122,7 @@ impl Vim {
         });
     }
 
-    fn open_path_mark(
+    pub(crate) fn open_path_mark(
         &mut self,
         line: bool,
         path: Arc<Path>,
@@ -39,6 +39,7 @@ pub enum Object {
     AngleBrackets,
     Argument,
     IndentObj { include_below: bool },
+    Delimiters { open: char, close: char },
     Tag,
     Method,
     Class,
@@ -72,6 +73,16 @@ struct IndentObj {
     include_below: bool,
 }
 
+/// Selects text within a user-defined pair of delimiters, e.g. `*` and `*` for markdown
+/// emphasis. Bind it in the operator-pending context to define a custom text object.
+#[derive(Clone, Deserialize, JsonSchema, PartialEq, Action)]
+#[action(namespace = vim)]
+#[serde(deny_unknown_fields)]
+struct Delimiters {
+    open: char,
+    close: char,
+}
+
 #[derive(Debug, Clone)]
 pub struct CandidateRange {
     pub start: DisplayPoint,
@@ -417,6 +428,13 @@ pub fn register(editor: &mut Editor, cx: &mut Context<Vim>) {
             vim.object(Object::IndentObj { include_below }, window, cx)
         },
     );
+    Vim::action(
+        editor,
+        cx,
+        |vim, &Delimiters { open, close }: &Delimiters, window, cx| {
+            vim.object(Object::Delimiters { open, close }, window, cx)
+        },
+    );
 }
 
 impl Vim {
@@ -458,6 +476,8 @@ impl Object {
             | Object::MiniQuotes
             | Object::VerticalBars
             | Object::DoubleQuotes => false,
+            // Symmetric delimiters behave like quotes, asymmetric ones like brackets.
+            Object::Delimiters { open, close } => open != close,
             Object::Sentence
             | Object::Paragraph
             | Object::AnyBrackets
@@ -500,7 +520,8 @@ impl Object {
             | Object::Comment
             | Object::EntireFile
             | Object::CurlyBrackets
-            | Object::AngleBrackets => true,
+            | Object::AngleBrackets
+            | Object::Delimiters { .. } => true,
         }
     }
 
@@ -530,6 +551,7 @@ impl Object {
             | Object::Tag
             | Object::Comment
             | Object::Argument
+            | Object::Delimiters { .. }
             | Object::IndentObj { .. } => Mode::Visual,
             Object::Method | Object::Class => {
                 if around {
@@ -747,6 +769,9 @@ impl Object {
             ),
             Object::Argument => argument(map, relative_to, around),
             Object::IndentObj { include_below } => indent(map, relative_to, around, include_below),
+            Object::Delimiters { open, close } => {
+                surrounding_markers(map, relative_to, around, self.is_multiline(), open, close)
+            }
             Object::EntireFile => entire_file(map),
         }
     }
@@ -1740,7 +1765,7 @@ mod test {
     use indoc::indoc;
 
     use crate::{
-        object::{AnyBrackets, AnyQuotes, MiniBrackets},
+        object::{AnyBrackets, AnyQuotes, Delimiters, MiniBrackets},
         state::Mode,
         test::{NeovimBackedTestContext, VimTestContext},
     };
@@ -2970,6 +2995,88 @@ mod test {
         }
     }
 
+    #[gpui::test]
+    async fn test_delimiters_object(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+        cx.update(|_, cx| {
+            cx.bind_keys([
+                KeyBinding::new(
+                    "m",
+                    Delimiters {
+                        open: '<',
+                        close: '>',
+                    },
+                    Some("vim_operator == a || vim_operator == i || vim_operator == cs"),
+                ),
+                KeyBinding::new(
+                    "*",
+                    Delimiters {
+                        open: '*',
+                        close: '*',
+                    },
+                    Some("vim_operator == a || vim_operator == i || vim_operator == cs"),
+                ),
+            ]);
+        });
+
+        const TEST_CASES: &[(&str, &str, &str, Mode)] = &[
+            // Nested asymmetric delimiters
+            ("d i m", "a <b <ˇc> d> e", "a <b <ˇ> d> e", Mode::Normal),
+            ("d i m", "a <b <c> ˇd> e", "a <ˇ> e", Mode::Normal),
+            ("d a m", "a <b <c> ˇd> e", "a ˇ e", Mode::Normal),
+            // Asymmetric delimiters span lines, like brackets
+            (
+                "c i m",
+                indoc! {"
+                    <
+                        <
+                            ˇone
+                            two
+                        >
+                    >
+                "},
+                indoc! {"
+                    <
+                        <
+                            ˇ
+                        >
+                    >
+                "},
+                Mode::Insert,
+            ),
+            // Symmetric delimiters stay on one line, like quotes
+            (
+                "d i *",
+                "some *emˇphasis* here",
+                "some *ˇ* here",
+                Mode::Normal,
+            ),
+            ("d a *", "some *emˇphasis* here", "some ˇhere", Mode::Normal),
+        ];
+
+        for (keystrokes, initial_state, expected_state, expected_mode) in TEST_CASES {
+            cx.set_state(initial_state, Mode::Normal);
+
+            cx.simulate_keystrokes(keystrokes);
+
+            cx.assert_state(expected_state, *expected_mode);
+        }
+
+        const INVALID_CASES: &[(&str, &str, Mode)] = &[
+            ("d i m", "a <b ˇc d", Mode::Normal), // Missing closing delimiter
+            ("d a m", "a <b ˇc d", Mode::Normal), // Missing closing delimiter
+            ("d i *", "some *emˇphasis\nhere*", Mode::Normal), // Symmetric delimiters on different lines
+        ];
+
+        for (keystrokes, initial_state, mode) in INVALID_CASES {
+            cx.set_state(initial_state, Mode::Normal);
+
+            cx.simulate_keystrokes(keystrokes);
+
+            cx.assert_state(initial_state, *mode);
+        }
+    }
+
     #[gpui::test]
     async fn test_minibrackets_object(cx: &mut gpui::TestAppContext) {
         let mut cx = VimTestContext::new(cx, true).await;
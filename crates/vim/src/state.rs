@@ -26,7 +26,8 @@ use settings::{Settings, SettingsStore};
 use std::borrow::BorrowMut;
 use std::collections::HashSet;
 use std::path::Path;
-use std::{fmt::Display, ops::Range, sync::Arc};
+use std::time::Duration;
+use std::{fmt::Display, mem, ops::Range, sync::Arc};
 use text::{Bias, ToPoint};
 use theme::ThemeSettings;
 use ui::{
@@ -147,6 +148,10 @@ pub enum Operator {
     HelixPrevious {
         around: bool,
     },
+    Custom {
+        action: String,
+        arguments: Option<serde_json::Value>,
+    },
 }
 
 #[derive(Default, Clone, Debug)]
@@ -251,6 +256,17 @@ pub struct MarksState {
     serialized_marks: HashMap<Arc<Path>, HashMap<String, Vec<Point>>>,
     global_marks: HashMap<String, MarkLocation>,
 
+    /// Every jump recorded in this workspace, oldest first, as persisted to the database.
+    jumps: Vec<SerializedJump>,
+    /// The jumps recorded by previous sessions, which `ctrl-o` falls back to once the
+    /// panes' in-memory navigation history has been exhausted.
+    restored_jumps: Vec<SerializedJump>,
+    restored_jump_index: Option<usize>,
+    /// Whether the jumps from previous sessions have been loaded, before which writing the
+    /// jumps would replace them.
+    jumps_loaded: bool,
+    serialize_jumps_task: Option<Task<()>>,
+
     _subscription: Subscription,
 }
 
@@ -283,6 +299,11 @@ impl MarksState {
                 watched_buffers: HashMap::default(),
                 serialized_marks: HashMap::default(),
                 global_marks: HashMap::default(),
+                jumps: Vec::new(),
+                restored_jumps: Vec::new(),
+                restored_jump_index: None,
+                jumps_loaded: false,
+                serialize_jumps_task: None,
                 _subscription: subscription,
             };
 
@@ -309,18 +330,35 @@ impl MarksState {
             let Some(workspace_id) = this.update(cx, |this, cx| this.workspace_id(cx))? else {
                 return Ok(());
             };
-            let (marks, paths) = cx
+            let (marks, paths, jumps) = cx
                 .background_spawn(async move {
                     let marks = DB.get_marks(workspace_id)?;
                     let paths = DB.get_global_marks_paths(workspace_id)?;
-                    anyhow::Ok((marks, paths))
+                    let jumps = DB.get_jumps(workspace_id)?;
+                    anyhow::Ok((marks, paths, jumps))
                 })
                 .await?;
-            this.update(cx, |this, cx| this.loaded(marks, paths, cx))
+            this.update(cx, |this, cx| {
+                this.jumps_loaded(jumps, cx);
+                this.loaded(marks, paths, cx)
+            })
         })
         .detach_and_log_err(cx);
     }
 
+    /// Puts the jumps from previous sessions before those recorded while they were loading.
+    fn jumps_loaded(&mut self, jumps: Vec<SerializedJump>, cx: &mut Context<Self>) {
+        let recorded_jumps = mem::replace(&mut self.jumps, jumps.clone());
+        self.restored_jumps = jumps;
+        self.jumps_loaded = true;
+        if !recorded_jumps.is_empty() {
+            for jump in recorded_jumps {
+                self.insert_jump(jump);
+            }
+            self.serialize_jumps(cx);
+        }
+    }
+
     fn loaded(
         &mut self,
         marks: Vec<SerializedMark>,
@@ -454,6 +492,77 @@ impl MarksState {
         }
     }
 
+    pub fn push_jump(
+        &mut self,
+        multi_buffer: &Entity<MultiBuffer>,
+        anchor: Anchor,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(buffer) = multi_buffer.read(cx).as_singleton() else {
+            return;
+        };
+        let Some(path) = self.path_for_buffer(&buffer, cx) else {
+            return;
+        };
+        let point = anchor.to_point(&multi_buffer.read(cx).snapshot(cx));
+        self.insert_jump(SerializedJump { path, point });
+        self.serialize_jumps(cx);
+    }
+
+    fn insert_jump(&mut self, jump: SerializedJump) {
+        // Like vim, keep at most one jump per line so that repeated jumps don't flood the list.
+        self.jumps
+            .retain(|existing| existing.path != jump.path || existing.point.row != jump.point.row);
+        self.jumps.push(jump);
+        if self.jumps.len() > MAX_JUMPS {
+            self.jumps.drain(..self.jumps.len() - MAX_JUMPS);
+        }
+    }
+
+    /// Writes the jumps to the database once they stop changing, rather than on every jump.
+    fn serialize_jumps(&mut self, cx: &mut Context<Self>) {
+        if !self.jumps_loaded {
+            return;
+        }
+        let Some(workspace_id) = self.workspace_id(cx) else {
+            return;
+        };
+        self.serialize_jumps_task = Some(cx.spawn(async move |this, cx| {
+            cx.background_executor()
+                .timer(JUMPS_SERIALIZATION_DELAY)
+                .await;
+            let Ok(jumps) = this.read_with(cx, |this, _| this.jumps.clone()) else {
+                return;
+            };
+            DB.set_jumps(workspace_id, jumps).await.log_err();
+        }));
+    }
+
+    pub fn older_restored_jump(&mut self, count: usize) -> Option<(Arc<Path>, Point)> {
+        let index = self
+            .restored_jump_index
+            .unwrap_or(self.restored_jumps.len())
+            .checked_sub(count)?;
+        self.restored_jump_index = Some(index);
+        let jump = self.restored_jumps.get(index)?;
+        Some((jump.path.clone(), jump.point))
+    }
+
+    pub fn newer_restored_jump(&mut self, count: usize) -> Option<(Arc<Path>, Point)> {
+        let index = self.restored_jump_index? + count;
+        if index >= self.restored_jumps.len() {
+            self.restored_jump_index = None;
+            return None;
+        }
+        self.restored_jump_index = Some(index);
+        let jump = self.restored_jumps.get(index)?;
+        Some((jump.path.clone(), jump.point))
+    }
+
+    pub fn is_navigating_restored_jumps(&self) -> bool {
+        self.restored_jump_index.is_some()
+    }
+
     fn is_global_mark(&self, key: &str) -> bool {
         key.chars()
             .next()
@@ -1037,6 +1146,7 @@ impl Operator {
             Operator::HelixMatch => "helix_m",
             Operator::HelixNext { .. } => "helix_next",
             Operator::HelixPrevious { .. } => "helix_previous",
+            Operator::Custom { .. } => "custom",
         }
     }
 
@@ -1104,7 +1214,8 @@ impl Operator {
             | Operator::ToggleComments
             | Operator::HelixMatch
             | Operator::HelixNext { .. }
-            | Operator::HelixPrevious { .. } => false,
+            | Operator::HelixPrevious { .. }
+            | Operator::Custom { .. } => false,
         }
     }
 
@@ -1130,7 +1241,8 @@ impl Operator {
             | Operator::DeleteSurrounds
             | Operator::Exchange
             | Operator::HelixNext { .. }
-            | Operator::HelixPrevious { .. } => true,
+            | Operator::HelixPrevious { .. }
+            | Operator::Custom { .. } => true,
             Operator::Yank
             | Operator::Object { .. }
             | Operator::FindForward { .. }
@@ -1716,6 +1828,16 @@ impl Domain for VimDb {
             CREATE UNIQUE INDEX idx_vim_global_marks_paths
             ON vim_global_marks_paths(workspace_id, mark_name);
         ),
+        sql! (
+            CREATE TABLE vim_jumps(
+                workspace_id INTEGER,
+                position INTEGER,
+                path BLOB,
+                row INTEGER,
+                column INTEGER
+            );
+            CREATE UNIQUE INDEX idx_vim_jumps ON vim_jumps(workspace_id, position);
+        ),
     ];
 }
 
@@ -1727,6 +1849,15 @@ struct SerializedMark {
     points: Vec<Point>,
 }
 
+const MAX_JUMPS: usize = 100;
+const JUMPS_SERIALIZATION_DELAY: Duration = Duration::from_millis(500);
+
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct SerializedJump {
+    path: Arc<Path>,
+    point: Point,
+}
+
 impl VimDb {
     pub(crate) async fn set_marks(
         &self,
@@ -1821,6 +1952,51 @@ impl VimDb {
         ))?(workspace_id)
     }
 
+    pub(crate) async fn set_jumps(
+        &self,
+        workspace_id: WorkspaceId,
+        jumps: Vec<SerializedJump>,
+    ) -> Result<()> {
+        self.write(move |conn| {
+            conn.exec_bound(sql!(
+                DELETE FROM vim_jumps WHERE workspace_id = ?
+            ))?(workspace_id)?;
+            let mut query = conn.exec_bound(sql!(
+                INSERT INTO vim_jumps
+                    (workspace_id, position, path, row, column)
+                VALUES
+                    (?, ?, ?, ?, ?)
+            ))?;
+            for (position, jump) in jumps.into_iter().enumerate() {
+                query((
+                    workspace_id,
+                    position,
+                    jump.path,
+                    jump.point.row,
+                    jump.point.column,
+                ))?;
+            }
+            Ok(())
+        })
+        .await
+    }
+
+    fn get_jumps(&self, workspace_id: WorkspaceId) -> Result<Vec<SerializedJump>> {
+        let result: Vec<(Arc<Path>, u32, u32)> = self.select_bound(sql!(
+            SELECT path, row, column FROM vim_jumps
+                WHERE workspace_id = ?
+                ORDER BY position
+        ))?(workspace_id)?;
+
+        Ok(result
+            .into_iter()
+            .map(|(path, row, column)| SerializedJump {
+                path,
+                point: Point { row, column },
+            })
+            .collect())
+    }
+
     pub(crate) async fn delete_global_marks_path(
         &self,
         workspace_id: WorkspaceId,
@@ -1835,3 +2011,121 @@ impl VimDb {
         .await
     }
 }
+
+#[cfg(test)]
+mod test {
+    use editor::MultiBufferSnapshot;
+    use gpui::TestAppContext;
+    use indoc::indoc;
+
+    use super::*;
+    use crate::test::VimTestContext;
+
+    fn marks_state(cx: &mut VimTestContext) -> Entity<MarksState> {
+        let workspace_id = cx.workspace(|_, _, cx| cx.entity_id());
+        cx.update(|_, cx| Vim::globals(cx).marks[&workspace_id].clone())
+    }
+
+    fn jump_rows(marks_state: &Entity<MarksState>, cx: &mut VimTestContext) -> Vec<u32> {
+        cx.update(|_, cx| {
+            marks_state
+                .read(cx)
+                .jumps
+                .iter()
+                .map(|jump| jump.point.row)
+                .collect()
+        })
+    }
+
+    #[gpui::test]
+    async fn test_jump_list_order(cx: &mut TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+        let marks_state = marks_state(&mut cx);
+
+        cx.set_state("ˇone\ntwo\nthree\nfour\nfive", Mode::Normal);
+        cx.simulate_keystrokes("shift-g g g");
+        assert_eq!(jump_rows(&marks_state, &mut cx), [0, 4]);
+        // Jumping from a line again moves its jump to the end rather than adding another.
+        cx.simulate_keystrokes("shift-g");
+        assert_eq!(jump_rows(&marks_state, &mut cx), [4, 0]);
+
+        cx.simulate_keystrokes("ctrl-o");
+        cx.assert_state("ˇone\ntwo\nthree\nfour\nfive", Mode::Normal);
+        cx.simulate_keystrokes("ctrl-o");
+        cx.assert_state("one\ntwo\nthree\nfour\nˇfive", Mode::Normal);
+        cx.simulate_keystrokes("ctrl-i");
+        cx.assert_state("ˇone\ntwo\nthree\nfour\nfive", Mode::Normal);
+    }
+
+    #[gpui::test]
+    async fn test_jump_list_truncation(cx: &mut TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+        let marks_state = marks_state(&mut cx);
+        cx.set_state(&format!("ˇ{}", "line\n".repeat(120)), Mode::Normal);
+
+        let multi_buffer = cx.update_editor(|editor, _, _| editor.buffer().clone());
+        let anchor_at_row =
+            |snapshot: &MultiBufferSnapshot, row| snapshot.anchor_before(Point::new(row, 0));
+        cx.update(|_, cx| {
+            marks_state.update(cx, |marks_state, cx| {
+                let snapshot = multi_buffer.read(cx).snapshot(cx);
+                for row in 0..110 {
+                    marks_state.push_jump(&multi_buffer, anchor_at_row(&snapshot, row), cx);
+                }
+            })
+        });
+        assert_eq!(
+            jump_rows(&marks_state, &mut cx),
+            (10..110).collect::<Vec<_>>()
+        );
+
+        cx.update(|_, cx| {
+            marks_state.update(cx, |marks_state, cx| {
+                let snapshot = multi_buffer.read(cx).snapshot(cx);
+                marks_state.push_jump(&multi_buffer, anchor_at_row(&snapshot, 50), cx);
+            })
+        });
+        let rows = jump_rows(&marks_state, &mut cx);
+        assert_eq!(rows.len(), MAX_JUMPS);
+        assert_eq!(rows.first(), Some(&10));
+        assert_eq!(rows.last(), Some(&50));
+    }
+
+    #[gpui::test]
+    async fn test_restored_jumps(cx: &mut TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+        let marks_state = marks_state(&mut cx);
+
+        cx.set_state(
+            indoc! {"
+                ˇone
+                two
+                three
+                four
+                five"},
+            Mode::Normal,
+        );
+        // Jumps recorded before the previous session's have loaded are kept after them.
+        cx.simulate_keystrokes("shift-g");
+        cx.update(|_, cx| {
+            marks_state.update(cx, |marks_state, cx| {
+                let path = marks_state.jumps[0].path.clone();
+                marks_state.jumps_loaded(
+                    vec![SerializedJump {
+                        path,
+                        point: Point::new(2, 0),
+                    }],
+                    cx,
+                );
+            })
+        });
+        assert_eq!(jump_rows(&marks_state, &mut cx), [2, 0]);
+
+        // The pane's history is visited first, then the restored jumps.
+        cx.simulate_keystrokes("ctrl-o");
+        cx.assert_state("ˇone\ntwo\nthree\nfour\nfive", Mode::Normal);
+        cx.simulate_keystrokes("ctrl-o");
+        cx.run_until_parked();
+        cx.assert_state("one\ntwo\nˇthree\nfour\nfive", Mode::Normal);
+    }
+}
@@ -488,6 +488,13 @@ impl Vim {
                 surround: true,
                 newline: false,
             }),
+            Object::Delimiters { open, close } => Some(BracketPair {
+                start: open.to_string(),
+                end: close.to_string(),
+                close: true,
+                surround: true,
+                newline: false,
+            }),
             Object::AnyBrackets => {
                 // If we're dealing with `AnyBrackets`, which can map to multiple
                 // bracket pairs, we'll need to first determine which `BracketPair` to
@@ -5,10 +5,12 @@ mod test;
 
 mod change_list;
 mod command;
+mod custom_operator;
 mod digraph;
 mod helix;
 mod indent;
 mod insert;
+mod jump_list;
 mod mode_indicator;
 mod motion;
 mod normal;
@@ -836,9 +838,11 @@ impl Vim {
             replace::register(editor, cx);
             indent::register(editor, cx);
             rewrap::register(editor, cx);
+            custom_operator::register(editor, cx);
             object::register(editor, cx);
             visual::register(editor, cx);
             change_list::register(editor, cx);
+            jump_list::register(editor, cx);
             digraph::register(editor, cx);
 
             cx.defer_in(window, |vim, window, cx| {
@@ -981,6 +985,7 @@ impl Vim {
                         "'".to_string()
                     };
                     vim.set_mark(mark, vec![*anchor], editor.buffer(), window, cx);
+                    vim.push_to_jump_list(*anchor, editor.buffer(), window, cx);
                 });
             }
             _ => {}
@@ -0,0 +1,110 @@
+use std::time::Duration;
+
+use gpui::{KeyBinding, Subscription, Task};
+use settings::Settings;
+use ui::prelude::*;
+
+use crate::WhichKeySettings;
+
+const MAX_VISIBLE_BINDINGS: usize = 30;
+
+/// Lists the bindings that could complete the currently pending keystrokes, once the user has
+/// paused for longer than the configured delay.
+pub struct WhichKeyLayer {
+    pending_keystroke_count: usize,
+    bindings: Vec<KeyBinding>,
+    show_task: Option<Task<()>>,
+    _pending_input_subscription: Subscription,
+}
+
+impl WhichKeyLayer {
+    pub fn new(window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let pending_input_subscription =
+            cx.observe_pending_input(window, |this: &mut Self, window, cx| {
+                this.update_pending_input(window, cx);
+            });
+        Self {
+            pending_keystroke_count: 0,
+            bindings: Vec::new(),
+            show_task: None,
+            _pending_input_subscription: pending_input_subscription,
+        }
+    }
+
+    fn update_pending_input(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.show_task.take();
+        if !self.bindings.is_empty() {
+            self.bindings.clear();
+            cx.notify();
+        }
+
+        let settings = WhichKeySettings::get_global(cx);
+        if !settings.enabled || !window.has_pending_keystrokes() {
+            return;
+        }
+
+        let delay = Duration::from_millis(settings.delay_ms);
+        self.show_task = Some(cx.spawn_in(window, async move |this, cx| {
+            cx.background_executor().timer(delay).await;
+            this.update_in(cx, |this, window, cx| {
+                let Some(keystrokes) = window.pending_input_keystrokes() else {
+                    return;
+                };
+                this.pending_keystroke_count = keystrokes.len();
+                this.bindings = window.pending_input_bindings();
+                cx.notify();
+            })
+            .ok();
+        }));
+    }
+}
+
+impl Render for WhichKeyLayer {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        if self.bindings.is_empty() {
+            return div();
+        }
+
+        let hidden_count = self.bindings.len().saturating_sub(MAX_VISIBLE_BINDINGS);
+        let rows = self
+            .bindings
+            .iter()
+            .take(MAX_VISIBLE_BINDINGS)
+            .map(|binding| {
+                let remaining_keystrokes = binding
+                    .keystrokes()
+                    .iter()
+                    .skip(self.pending_keystroke_count)
+                    .cloned()
+                    .collect();
+                h_flex()
+                    .gap_2()
+                    .justify_between()
+                    .child(ui::KeyBinding::new(remaining_keystrokes, cx))
+                    .child(
+                        Label::new(binding.action().name())
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    )
+            })
+            .collect::<Vec<_>>();
+
+        div().absolute().size_full().bottom_0().left_0().child(
+            v_flex()
+                .absolute()
+                .bottom_8()
+                .right_4()
+                .p_2()
+                .gap_1()
+                .elevation_2(cx)
+                .children(rows)
+                .when(hidden_count > 0, |this| {
+                    this.child(
+                        Label::new(format!("…and {hidden_count} more"))
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    )
+                }),
+        )
+    }
+}
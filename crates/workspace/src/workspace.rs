@@ -15,12 +15,14 @@ pub mod tasks;
 mod theme_preview;
 mod toast_layer;
 mod toolbar;
+mod which_key;
 mod workspace_settings;
 
 pub use crate::notifications::NotificationFrame;
 pub use dock::Panel;
 pub use path_list::PathList;
 pub use toast_layer::{ToastAction, ToastLayer, ToastView};
+pub use which_key::WhichKeyLayer;
 
 use anyhow::{Context as _, Result, anyhow};
 use call::{ActiveCall, call_settings::CallSettings};
@@ -42,9 +44,9 @@ use gpui::{
     Action, AnyEntity, AnyView, AnyWeakView, App, AsyncApp, AsyncWindowContext, Bounds, Context,
     CursorStyle, Decorations, DragMoveEvent, Entity, EntityId, EventEmitter, FocusHandle,
     Focusable, Global, HitboxBehavior, Hsla, KeyContext, Keystroke, ManagedView, MouseButton,
-    PathPromptOptions, Point, PromptLevel, Render, ResizeEdge, Size, Stateful, Subscription,
-    SystemWindowTabController, Task, Tiling, WeakEntity, WindowBounds, WindowHandle, WindowId,
-    WindowOptions, actions, canvas, point, relative, size, transparent_black,
+    MouseUpEvent, PathPromptOptions, Point, PromptLevel, Render, ResizeEdge, Size, Stateful,
+    Subscription, SystemWindowTabController, Task, Tiling, WeakEntity, WindowBounds, WindowHandle,
+    WindowId, WindowOptions, actions, canvas, point, relative, size, transparent_black,
 };
 pub use history_manager::*;
 pub use item::{
@@ -96,6 +98,7 @@ use std::{
     collections::{VecDeque, hash_map::DefaultHasher},
     env,
     hash::{Hash, Hasher},
+    mem,
     path::{Path, PathBuf},
     process::ExitStatus,
     rc::Rc,
@@ -116,7 +119,7 @@ use util::{
 use uuid::Uuid;
 pub use workspace_settings::{
     AutosaveSetting, BottomDockLayout, RestoreOnStartupBehavior, StatusBarSettings, TabBarSettings,
-    WorkspaceSettings,
+    WhichKeySettings, WorkspaceSettings,
 };
 use zed_actions::{Spawn, feedback::FileBugReport};
 
@@ -509,6 +512,7 @@ pub fn init_settings(cx: &mut App) {
     PreviewTabsSettings::register(cx);
     TabBarSettings::register(cx);
     StatusBarSettings::register(cx);
+    WhichKeySettings::register(cx);
 }
 
 fn prompt_and_open_paths(app_state: Arc<AppState>, options: PathPromptOptions, cx: &mut App) {
@@ -1108,6 +1112,7 @@ pub struct Workspace {
     status_bar: Entity<StatusBar>,
     modal_layer: Entity<ModalLayer>,
     toast_layer: Entity<ToastLayer>,
+    which_key_layer: Entity<WhichKeyLayer>,
     titlebar_item: Option<AnyView>,
     notifications: Notifications,
     suppressed_notifications: HashSet<NotificationId>,
@@ -1139,6 +1144,14 @@ pub struct Workspace {
     _items_serializer: Task<Result<()>>,
     session_id: Option<String>,
     scheduled_tasks: Vec<Task<()>>,
+    deferred_panels: Vec<DeferredPanel>,
+}
+
+/// A panel that is constructed the first time it's shown, rather than when the workspace opens.
+struct DeferredPanel {
+    type_id: TypeId,
+    persistent_name: &'static str,
+    load: Box<dyn FnOnce(&mut Window, &mut Context<Workspace>) -> Task<Result<()>>>,
 }
 
 impl EventEmitter<Event> for Workspace {}
@@ -1342,6 +1355,7 @@ impl Workspace {
         cx.emit(Event::WorkspaceCreated(weak_handle.clone()));
         let modal_layer = cx.new(|_| ModalLayer::new());
         let toast_layer = cx.new(|_| ToastLayer::new());
+        let which_key_layer = cx.new(|cx| WhichKeyLayer::new(window, cx));
         cx.subscribe(
             &modal_layer,
             |_, _, _: &modal_layer::ModalOpenedEvent, cx| {
@@ -1442,6 +1456,7 @@ impl Workspace {
             status_bar,
             modal_layer,
             toast_layer,
+            which_key_layer,
             titlebar_item: None,
             notifications: Notifications::default(),
             suppressed_notifications: HashSet::default(),
@@ -1479,6 +1494,7 @@ impl Workspace {
             session_id: Some(session_id),
 
             scheduled_tasks: Vec::new(),
+            deferred_panels: Vec::new(),
         }
     }
 
@@ -1727,6 +1743,102 @@ impl Workspace {
         });
     }
 
+    /// Registers a panel that is loaded the first time it's focused or opened, so that panels
+    /// which aren't visible don't slow down opening the workspace. A panel that was visible
+    /// when the workspace was last closed is loaded right away.
+    pub fn add_deferred_panel<T: Panel, F>(
+        &mut self,
+        load: impl FnOnce(WeakEntity<Workspace>, AsyncWindowContext) -> F + 'static,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) where
+        F: Future<Output = Result<Entity<T>>> + 'static,
+    {
+        if self.panel::<T>(cx).is_some() {
+            return;
+        }
+        self.deferred_panels.push(DeferredPanel {
+            type_id: TypeId::of::<T>(),
+            persistent_name: T::persistent_name(),
+            load: Box::new(move |window, cx| {
+                let panel = load(cx.weak_entity(), window.to_async(cx));
+                cx.spawn_in(window, async move |workspace, cx| {
+                    let panel = panel.await?;
+                    workspace.update_in(cx, |workspace, window, cx| {
+                        workspace.add_panel(panel, window, cx)
+                    })
+                })
+            }),
+        });
+        self.load_restored_panels(window, cx);
+    }
+
+    /// Loads every panel whose loading was deferred.
+    pub fn load_deferred_panels(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        for panel in mem::take(&mut self.deferred_panels) {
+            (panel.load)(window, cx).detach_and_log_err(cx);
+        }
+    }
+
+    /// Loads the deferred panels that the serialized docks show as active, since restoring a
+    /// dock only activates panels that were already added to it.
+    fn load_restored_panels(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let restored_panels = self
+            .deferred_panels
+            .iter()
+            .filter(|panel| {
+                self.all_docks().iter().any(|dock| {
+                    dock.read(cx)
+                        .serialized_dock
+                        .as_ref()
+                        .is_some_and(|serialized| {
+                            serialized.visible
+                                && serialized.active_panel.as_deref() == Some(panel.persistent_name)
+                        })
+                })
+            })
+            .map(|panel| panel.type_id)
+            .collect::<Vec<_>>();
+        for type_id in restored_panels {
+            if let Some(load) = self.load_deferred_panel(type_id, window, cx) {
+                load.detach_and_log_err(cx);
+            }
+        }
+    }
+
+    fn load_deferred_panel(
+        &mut self,
+        type_id: TypeId,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Option<Task<Result<()>>> {
+        let ix = self
+            .deferred_panels
+            .iter()
+            .position(|panel| panel.type_id == type_id)?;
+        let panel = self.deferred_panels.remove(ix);
+        Some((panel.load)(window, cx))
+    }
+
+    /// Loads the panel of the given type if its loading was deferred, and then runs the given
+    /// callback. Returns whether the panel is being loaded.
+    fn load_deferred_panel_then<T: Panel>(
+        &mut self,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+        then: impl FnOnce(&mut Self, &mut Window, &mut Context<Self>) + 'static,
+    ) -> bool {
+        let Some(load) = self.load_deferred_panel(TypeId::of::<T>(), window, cx) else {
+            return false;
+        };
+        cx.spawn_in(window, async move |workspace, cx| {
+            load.await?;
+            workspace.update_in(cx, |workspace, window, cx| then(workspace, window, cx))
+        })
+        .detach_and_log_err(cx);
+        true
+    }
+
     pub fn remove_panel<T: Panel>(
         &mut self,
         panel: &Entity<T>,
@@ -3029,6 +3141,11 @@ impl Workspace {
         window: &mut Window,
         cx: &mut Context<Self>,
     ) -> Option<Entity<T>> {
+        if self.load_deferred_panel_then::<T>(window, cx, |workspace, window, cx| {
+            workspace.focus_panel::<T>(window, cx);
+        }) {
+            return None;
+        }
         let panel = self.focus_or_unfocus_panel::<T>(window, cx, |_, _, _| true)?;
         panel.to_any().downcast().ok()
     }
@@ -3040,6 +3157,11 @@ impl Workspace {
         window: &mut Window,
         cx: &mut Context<Self>,
     ) -> bool {
+        if self.load_deferred_panel_then::<T>(window, cx, |workspace, window, cx| {
+            workspace.focus_panel::<T>(window, cx);
+        }) {
+            return true;
+        }
         let mut did_focus_panel = false;
         self.focus_or_unfocus_panel::<T>(window, cx, |panel, window, cx| {
             did_focus_panel = !panel.panel_focus_handle(cx).contains_focused(window, cx);
@@ -3122,6 +3244,11 @@ impl Workspace {
 
     /// Open the panel of the given type
     pub fn open_panel<T: Panel>(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if self.load_deferred_panel_then::<T>(window, cx, |workspace, window, cx| {
+            workspace.open_panel::<T>(window, cx)
+        }) {
+            return;
+        }
         for dock in self.all_docks() {
             if let Some(panel_index) = dock.read(cx).panel_index_for_type::<T>() {
                 dock.update(cx, |dock, cx| {
@@ -4102,6 +4229,118 @@ impl Workspace {
         maybe_pane_handle
     }
 
+    /// Moves a tab that was dropped outside of this window into the window of the same project
+    /// under the cursor, or into a new window for this project if there is none.
+    fn move_dragged_tab_to_window(
+        &mut self,
+        event: &MouseUpEvent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(dragged_tab) = cx.active_drag_value::<DraggedTab>().cloned() else {
+            return;
+        };
+        cx.stop_active_drag(window);
+
+        let screen_position = window.bounds().origin + event.position;
+        let current_window_id = window.window_handle().window_id();
+        let target_window = cx
+            .windows()
+            .into_iter()
+            .filter_map(|handle| handle.downcast::<Workspace>())
+            .filter(|handle| handle.window_id() != current_window_id)
+            .find(|handle| {
+                handle
+                    .update(cx, |workspace, window, _| {
+                        workspace.project == self.project
+                            && window.bounds().contains(&screen_position)
+                    })
+                    .unwrap_or(false)
+            });
+        let new_window_bounds = Bounds::new(screen_position, window.bounds().size);
+        self.move_item_to_window(
+            dragged_tab.item,
+            dragged_tab.pane,
+            target_window,
+            new_window_bounds,
+            window,
+            cx,
+        )
+        .detach_and_log_err(cx);
+    }
+
+    /// Moves `item` out of `pane` into `target_window`, or into a new window for this project
+    /// with the given bounds.
+    ///
+    /// The item is cloned into the other window rather than moved, because items hold
+    /// subscriptions tied to the window they were created in. Clones share the underlying
+    /// buffers, so unsaved edits and collaboration on them carry over. Items that can't be
+    /// cloned stay where they are, and a toast explains why.
+    fn move_item_to_window(
+        &mut self,
+        item: Box<dyn ItemHandle>,
+        pane: Entity<Pane>,
+        target_window: Option<WindowHandle<Workspace>>,
+        new_window_bounds: Bounds<Pixels>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        let project = self.project.clone();
+        let app_state = self.app_state.clone();
+        cx.spawn_in(window, async move |this, cx| {
+            let (target_window, opened_window) = match target_window {
+                Some(target_window) => (target_window, false),
+                None => {
+                    let workspace_id = DB.next_id().await.log_err();
+                    let new_window = cx.update(|_, cx| {
+                        let mut options = (app_state.build_window_options)(None, cx);
+                        options.window_bounds = Some(WindowBounds::Windowed(new_window_bounds));
+                        cx.open_window(options, |window, cx| {
+                            cx.new(|cx| {
+                                Workspace::new(workspace_id, project, app_state, window, cx)
+                            })
+                        })
+                    })??;
+                    (new_window, true)
+                }
+            };
+
+            let moved = target_window.update(cx, |workspace, window, cx| {
+                let Some(clone) = item.clone_on_split(workspace.database_id(), window, cx) else {
+                    return false;
+                };
+                workspace.active_pane.update(cx, |pane, cx| {
+                    pane.add_item(clone, true, true, None, window, cx)
+                });
+                window.activate_window();
+                true
+            })?;
+
+            if moved {
+                pane.update_in(cx, |pane, window, cx| {
+                    pane.remove_item(item.item_id(), false, true, window, cx)
+                })?;
+            } else {
+                if opened_window {
+                    target_window.update(cx, |_, window, _| window.remove_window())?;
+                }
+                this.update(cx, |workspace, cx| {
+                    struct UnmovableTabToast;
+                    let tab_name = item.tab_content_text(0, cx);
+                    workspace.show_toast(
+                        Toast::new(
+                            NotificationId::unique::<UnmovableTabToast>(),
+                            format!("\"{tab_name}\" can't be moved to another window"),
+                        )
+                        .autohide(),
+                        cx,
+                    );
+                })?;
+            }
+            Ok(())
+        })
+    }
+
     pub fn join_all_panes(&mut self, window: &mut Window, cx: &mut Context<Self>) {
         let active_item = self.active_pane.read(cx).active_item();
         for pane in &self.panes {
@@ -5445,6 +5684,7 @@ impl Workspace {
                         dock.restore_state(window, cx);
                     });
                 }
+                workspace.load_restored_panels(window, cx);
 
                 cx.notify();
             })?;
@@ -6282,6 +6522,12 @@ impl Render for Workspace {
                         cx.notify(id);
                     }
                 })
+                .on_mouse_up_out(
+                    MouseButton::Left,
+                    cx.listener(|workspace, event: &MouseUpEvent, window, cx| {
+                        workspace.move_dragged_tab_to_window(event, window, cx)
+                    }),
+                )
                 .child(
                     div()
                         .size_full()
@@ -6657,7 +6903,8 @@ impl Render for Workspace {
                             parent.child(self.status_bar.clone())
                         })
                         .child(self.modal_layer.clone())
-                        .child(self.toast_layer.clone()),
+                        .child(self.toast_layer.clone())
+                        .child(self.which_key_layer.clone()),
                 ),
             window,
             cx,
@@ -8171,6 +8418,121 @@ mod tests {
         item3.read_with(cx, |item, _| assert_eq!(item.tab_detail.get(), Some(3)));
     }
 
+    #[gpui::test]
+    async fn test_move_item_to_new_window(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        let project = Project::test(fs, [], cx).await;
+        let (workspace, cx) =
+            cx.add_window_view(|window, cx| Workspace::test_new(project.clone(), window, cx));
+        let pane = workspace.read_with(cx, |workspace, _| workspace.active_pane().clone());
+        let item = cx.new(|cx| TestItem::new(cx).with_label("moved"));
+        workspace.update_in(cx, |workspace, window, cx| {
+            workspace.add_item_to_active_pane(Box::new(item.clone()), None, true, window, cx)
+        });
+
+        let new_window_bounds = Bounds::new(point(px(100.), px(100.)), size(px(800.), px(600.)));
+        workspace
+            .update_in(cx, |workspace, window, cx| {
+                workspace.move_item_to_window(
+                    Box::new(item.clone()),
+                    pane.clone(),
+                    None,
+                    new_window_bounds,
+                    window,
+                    cx,
+                )
+            })
+            .await
+            .unwrap();
+        cx.run_until_parked();
+
+        pane.read_with(cx, |pane, _| assert_eq!(pane.items_len(), 0));
+        let new_workspace = cx
+            .windows()
+            .into_iter()
+            .filter_map(|window| window.downcast::<Workspace>()?.root(cx).ok())
+            .find(|root| *root != workspace)
+            .unwrap();
+        new_workspace.read_with(cx, |new_workspace, cx| {
+            assert!(new_workspace.database_id().is_some());
+            assert_eq!(new_workspace.project(), &project);
+            let moved_item = new_workspace
+                .active_item(cx)
+                .and_then(|item| item.downcast::<TestItem>())
+                .unwrap();
+            assert_ne!(moved_item, item);
+            assert_eq!(moved_item.read(cx).label, "moved");
+        });
+    }
+
+    #[gpui::test]
+    async fn test_move_unsplittable_item_to_new_window(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        struct UnsplittableItem {
+            focus_handle: FocusHandle,
+        }
+
+        impl Item for UnsplittableItem {
+            type Event = ();
+            fn tab_content_text(&self, _detail: usize, _cx: &App) -> SharedString {
+                "unsplittable".into()
+            }
+        }
+        impl EventEmitter<()> for UnsplittableItem {}
+        impl Focusable for UnsplittableItem {
+            fn focus_handle(&self, _cx: &App) -> FocusHandle {
+                self.focus_handle.clone()
+            }
+        }
+        impl Render for UnsplittableItem {
+            fn render(
+                &mut self,
+                _window: &mut Window,
+                _cx: &mut Context<Self>,
+            ) -> impl IntoElement {
+                Empty
+            }
+        }
+
+        let fs = FakeFs::new(cx.executor());
+        let project = Project::test(fs, [], cx).await;
+        let (workspace, cx) =
+            cx.add_window_view(|window, cx| Workspace::test_new(project.clone(), window, cx));
+        let pane = workspace.read_with(cx, |workspace, _| workspace.active_pane().clone());
+        let item = cx.new(|cx| UnsplittableItem {
+            focus_handle: cx.focus_handle(),
+        });
+        workspace.update_in(cx, |workspace, window, cx| {
+            workspace.add_item_to_active_pane(Box::new(item.clone()), None, true, window, cx)
+        });
+
+        let new_window_bounds = Bounds::new(point(px(100.), px(100.)), size(px(800.), px(600.)));
+        workspace
+            .update_in(cx, |workspace, window, cx| {
+                workspace.move_item_to_window(
+                    Box::new(item.clone()),
+                    pane.clone(),
+                    None,
+                    new_window_bounds,
+                    window,
+                    cx,
+                )
+            })
+            .await
+            .unwrap();
+        cx.run_until_parked();
+
+        // The item stays put, the window opened for it is closed again, and the user is told why.
+        pane.read_with(cx, |pane, _| assert_eq!(pane.items_len(), 1));
+        assert_eq!(cx.windows().len(), 1);
+        workspace.read_with(cx, |workspace, _| {
+            assert_eq!(workspace.notification_ids().len(), 1)
+        });
+    }
+
     #[gpui::test]
     async fn test_tracking_active_path(cx: &mut TestAppContext) {
         init_test(cx);
@@ -8957,6 +9319,43 @@ mod tests {
         });
     }
 
+    #[gpui::test]
+    async fn test_deferred_panel_loads_when_focused(cx: &mut gpui::TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+
+        let project = Project::test(fs, [], cx).await;
+        let (workspace, cx) =
+            cx.add_window_view(|window, cx| Workspace::test_new(project, window, cx));
+
+        workspace.update_in(cx, |workspace, window, cx| {
+            workspace.add_deferred_panel(
+                |workspace: WeakEntity<Workspace>, mut cx: AsyncWindowContext| async move {
+                    workspace.update_in(&mut cx, |_, _, cx| {
+                        cx.new(|cx| TestPanel::new(DockPosition::Right, cx))
+                    })
+                },
+                window,
+                cx,
+            );
+            assert!(workspace.panel::<TestPanel>(cx).is_none());
+        });
+
+        // Focusing the panel constructs it before focusing it.
+        workspace.update_in(cx, |workspace, window, cx| {
+            workspace.toggle_panel_focus::<TestPanel>(window, cx);
+        });
+        cx.run_until_parked();
+
+        workspace.update_in(cx, |workspace, window, cx| {
+            let panel = workspace
+                .panel::<TestPanel>(cx)
+                .expect("deferred panel was not loaded");
+            assert!(workspace.right_dock().read(cx).is_open());
+            assert!(panel.read(cx).focus_handle(cx).contains_focused(window, cx));
+        });
+    }
+
     #[gpui::test]
     async fn test_join_pane_into_next(cx: &mut gpui::TestAppContext) {
         init_test(cx);
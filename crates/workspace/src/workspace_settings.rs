@@ -248,3 +248,18 @@ impl Settings for StatusBarSettings {
         }
     }
 }
+
+pub struct WhichKeySettings {
+    pub enabled: bool,
+    pub delay_ms: u64,
+}
+
+impl Settings for WhichKeySettings {
+    fn from_settings(content: &settings::SettingsContent) -> Self {
+        let which_key = content.which_key.clone().unwrap();
+        WhichKeySettings {
+            enabled: which_key.enabled.unwrap(),
+            delay_ms: which_key.delay_ms.unwrap(),
+        }
+    }
+}
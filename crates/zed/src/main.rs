@@ -44,7 +44,7 @@ use workspace::{
     WorkspaceStore, notifications::NotificationId,
 };
 use zed::{
-    OpenListener, OpenRequest, RawOpenRequest, app_menus, build_window_options,
+    OpenListener, OpenRequest, RawOpenRequest, StartupTimings, app_menus, build_window_options,
     derive_paths_with_position, handle_cli_connection, handle_keymap_file_changes,
     handle_settings_changed, handle_settings_file_changes, initialize_workspace,
     open_paths_with_positions,
@@ -162,6 +162,7 @@ pub fn main() {
     util::prevent_root_execution();
 
     let args = Args::parse();
+    let startup_timings = args.timings.then(StartupTimings::start);
 
     // `zed --askpass` Makes zed operate in nc/netcat mode for use with askpass
     #[cfg(not(target_os = "windows"))]
@@ -170,6 +171,14 @@ pub fn main() {
         return;
     }
 
+    // Set custom data directory. This happens before the crash handler starts so that, in portable
+    // mode, crash reports are written next to the executable rather than the home directory.
+    if let Some(dir) = &args.user_data_dir {
+        paths::set_custom_data_dir(dir);
+    } else if let Some(dir) = paths::portable_data_dir() {
+        paths::set_custom_data_dir(&dir.to_string_lossy());
+    }
+
     // `zed --crash-handler` Makes zed operate in minidump crash handler mode
     if let Some(socket) = &args.crash_handler {
         crashes::crash_server(socket.as_path());
@@ -198,11 +207,6 @@ pub fn main() {
         return;
     }
 
-    // Set custom data directory.
-    if let Some(dir) = &args.user_data_dir {
-        paths::set_custom_data_dir(dir);
-    }
-
     #[cfg(all(not(debug_assertions), target_os = "windows"))]
     unsafe {
         use windows::Win32::System::Console::{ATTACH_PARENT_PROCESS, AttachConsole};
@@ -380,6 +384,10 @@ pub fn main() {
     });
 
     app.run(move |cx| {
+        if let Some(startup_timings) = startup_timings {
+            cx.set_global(startup_timings);
+            StartupTimings::observe_worktree_scans(cx);
+        }
         menu::init();
         zed_actions::init();
 
@@ -397,6 +405,7 @@ pub fn main() {
             handle_settings_changed,
         );
         handle_keymap_file_changes(user_keymap_file_rx, cx);
+        StartupTimings::record_global("settings load", cx);
         client::init_settings(cx);
         let user_agent = format!(
             "Zed/{} ({}; {})",
@@ -405,10 +414,11 @@ pub fn main() {
             std::env::consts::ARCH
         );
         let proxy_url = ProxySettings::get_global(cx).proxy_url();
+        let no_proxy = ProxySettings::get_global(cx).no_proxy();
         let http = {
             let _guard = Tokio::handle(cx).enter();
 
-            ReqwestClient::proxy_and_user_agent(proxy_url, &user_agent)
+            ReqwestClient::proxy_and_user_agent(proxy_url, no_proxy.as_deref(), &user_agent)
                 .expect("could not start HTTP client")
         };
         cx.set_http_client(Arc::new(http));
@@ -520,6 +530,7 @@ pub fn main() {
             ThemeRegistry::global(cx),
             cx.background_executor().clone(),
         );
+        StartupTimings::record_global("extension load", cx);
         command_palette::init(cx);
         snippet_provider::init(cx);
         repl::init(app_state.fs.clone(), cx);
@@ -529,8 +540,12 @@ pub fn main() {
 
         editor::init(cx);
         image_viewer::init(cx);
+        hex_editor::init(cx);
+        log_viewer::init(cx);
+        rest_client::init(cx);
         repl::notebook::init(cx);
         diagnostics::init(cx);
+        coverage::init(app_state.fs.clone(), cx);
 
         audio::init(cx);
         workspace::init(app_state.clone(), cx);
@@ -543,12 +558,15 @@ pub fn main() {
         project_panel::init(cx);
         outline_panel::init(cx);
         tasks_ui::init(cx);
+        test_explorer::init(cx);
+        type_hierarchy::init(cx);
         snippets_ui::init(cx);
         channel::init(&app_state.client.clone(), app_state.user_store.clone(), cx);
         search::init(cx);
         vim::init(cx);
         terminal_view::init(cx);
         journal::init(app_state.clone(), cx);
+        local_history::init(app_state.fs.clone(), cx);
         language_selector::init(cx);
         line_ending_selector::init(cx);
         toolchain_selector::init(cx);
@@ -561,6 +579,7 @@ pub fn main() {
         git_ui::init(cx);
         markdown_preview::init(cx);
         svg_preview::init(cx);
+        csv_preview::init(cx);
         onboarding::init(cx);
         settings_ui::init(cx);
         keymap_editor::init(cx);
@@ -608,6 +627,7 @@ pub fn main() {
         let menus = app_menus(cx);
         cx.set_menus(menus);
         initialize_workspace(app_state.clone(), cx);
+        StartupTimings::record_global("app initialization", cx);
 
         cx.activate(true);
 
@@ -1166,6 +1186,11 @@ struct Args {
     /// Output current environment variables as JSON to stdout
     #[arg(long, hide = true)]
     printenv: bool,
+
+    /// Prints how long each stage of startup took, such as loading settings and extensions,
+    /// constructing panels and starting to scan worktrees, once the first window has opened.
+    #[arg(long)]
+    timings: bool,
 }
 
 fn parse_url_arg(arg: &str, cx: &App) -> String {
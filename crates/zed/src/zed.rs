@@ -2,9 +2,12 @@ mod app_menus;
 pub mod component_preview;
 #[cfg(target_os = "macos")]
 pub(crate) mod mac_only_instance;
+mod memory_usage;
 mod migrate;
 mod open_listener;
 mod quick_action_bar;
+mod renderer_diagnostics;
+mod startup_timings;
 #[cfg(target_os = "windows")]
 pub(crate) mod windows_only_instance;
 
@@ -26,10 +29,10 @@ use futures::{StreamExt, channel::mpsc, select_biased};
 use git_ui::git_panel::GitPanel;
 use git_ui::project_diff::ProjectDiffToolbar;
 use gpui::{
-    Action, App, AppContext as _, Context, DismissEvent, Element, Entity, Focusable, KeyBinding,
-    ParentElement, PathPromptOptions, PromptLevel, ReadGlobal, SharedString, Styled, Task,
-    TitlebarOptions, UpdateGlobal, Window, WindowKind, WindowOptions, actions, image_cache, point,
-    px, retain_all,
+    Action, App, AppContext as _, Context, DismissEvent, Element, Entity, Focusable, Global,
+    KeyBinding, ParentElement, PathPromptOptions, PromptLevel, ReadGlobal, SharedString, Styled,
+    Task, TitlebarOptions, UpdateGlobal, Window, WindowKind, WindowOptions, actions, image_cache,
+    point, px, retain_all,
 };
 use image_viewer::ImageInfo;
 use language::Capability;
@@ -43,8 +46,8 @@ use onboarding::multibuffer_hint::MultibufferHint;
 pub use open_listener::*;
 use outline_panel::OutlinePanel;
 use paths::{
-    local_debug_file_relative_path, local_settings_file_relative_path,
-    local_tasks_file_relative_path,
+    local_debug_file_relative_path, local_keymap_file_relative_path,
+    local_settings_file_relative_path, local_tasks_file_relative_path,
 };
 use project::{DirectoryLister, ProjectItem};
 use project_panel::ProjectPanel;
@@ -55,10 +58,11 @@ use rope::Rope;
 use search::project_search::ProjectSearchBar;
 use settings::{
     BaseKeymap, DEFAULT_KEYMAP_PATH, InvalidSettingsError, KeybindSource, KeymapFile,
-    KeymapFileLoadResult, Settings, SettingsStore, VIM_KEYMAP_PATH,
+    KeymapFileLoadResult, Settings, SettingsFileConflict, SettingsStore, VIM_KEYMAP_PATH,
     initial_local_debug_tasks_content, initial_project_settings_content, initial_tasks_content,
     update_settings_file,
 };
+pub use startup_timings::StartupTimings;
 use std::time::Duration;
 use std::{
     borrow::Cow,
@@ -67,9 +71,9 @@ use std::{
     sync::atomic::{self, AtomicBool},
 };
 use terminal_view::terminal_panel::{self, TerminalPanel};
-use theme::{ActiveTheme, GlobalTheme, SystemAppearance, ThemeRegistry, ThemeSettings};
+use theme::{ActiveTheme, GlobalTheme, ThemeRegistry, ThemeSettings};
 use ui::{PopoverMenuHandle, prelude::*};
-use util::markdown::MarkdownString;
+use util::markdown::{MarkdownCodeBlock, MarkdownString};
 use util::rel_path::RelPath;
 use util::{ResultExt, asset_str};
 use uuid::Uuid;
@@ -96,6 +100,8 @@ actions!(
     [
         /// Opens the element inspector for debugging UI.
         DebugElements,
+        /// Drops caches that are rebuilt when next needed, such as glyph layouts and decoded images.
+        DropCaches,
         /// Hides the application window.
         Hide,
         /// Hides all other application windows.
@@ -104,8 +110,12 @@ actions!(
         Minimize,
         /// Opens the default settings file.
         OpenDefaultSettings,
+        /// Opens a breakdown of the memory used by Zed and its language servers.
+        OpenMemoryUsage,
         /// Opens project-specific settings.
         OpenProjectSettings,
+        /// Opens a report of the GPU, driver and graphics API used for rendering.
+        OpenRendererDiagnostics,
         /// Opens the project tasks configuration.
         OpenProjectTasks,
         /// Opens the tasks panel.
@@ -243,6 +253,19 @@ pub fn init(cx: &mut App) {
             );
         });
     });
+    cx.on_action(|_: &OpenRendererDiagnostics, cx| {
+        with_active_or_new_workspace(cx, |workspace, window, cx| {
+            open_bundled_file(
+                workspace,
+                renderer_diagnostics::report(window.gpu_specs().as_ref()).into(),
+                "Renderer Diagnostics",
+                "Markdown",
+                window,
+                cx,
+            );
+        });
+    });
+    cx.on_action(|_: &DropCaches, cx| memory_usage::drop_caches(cx));
     cx.on_action(|_: &zed_actions::OpenDefaultKeymap, cx| {
         with_active_or_new_workspace(cx, |workspace, window, cx| {
             open_bundled_file(
@@ -347,12 +370,12 @@ pub fn initialize_workspace(app_state: Arc<AppState>, cx: &mut App) {
         #[cfg(not(target_os = "macos"))]
         initialize_file_watcher(window, cx);
 
-        if let Some(specs) = window.gpu_specs() {
+        let gpu_specs = window.gpu_specs();
+        if let Some(specs) = &gpu_specs {
             log::info!("Using GPU: {:?}", specs);
-            show_software_emulation_warning_if_needed(specs.clone(), window, cx);
             if let Some((crash_server, message)) = crashes::CRASH_HANDLER
                 .get()
-                .zip(bincode::serialize(&specs).ok())
+                .zip(bincode::serialize(specs).ok())
                 && let Err(err) = crash_server.send_message(3, message)
             {
                 log::warn!(
@@ -389,6 +412,8 @@ pub fn initialize_workspace(app_state: Arc<AppState>, cx: &mut App) {
 
         let cursor_position =
             cx.new(|_| go_to_line::cursor_position::CursorPosition::new(workspace));
+        let software_rendering_indicator =
+            cx.new(|_| renderer_diagnostics::SoftwareRenderingIndicator::new(gpu_specs));
         workspace.status_bar().update(cx, |status_bar, cx| {
             status_bar.add_left_item(search_button, window, cx);
             status_bar.add_left_item(lsp_button, window, cx);
@@ -399,6 +424,7 @@ pub fn initialize_workspace(app_state: Arc<AppState>, cx: &mut App) {
             status_bar.add_right_item(vim_mode_indicator, window, cx);
             status_bar.add_right_item(cursor_position, window, cx);
             status_bar.add_right_item(image_info, window, cx);
+            status_bar.add_right_item(software_rendering_indicator, window, cx);
         });
 
         let handle = cx.entity().downgrade();
@@ -412,7 +438,8 @@ pub fn initialize_workspace(app_state: Arc<AppState>, cx: &mut App) {
                 .unwrap_or(true)
         });
 
-        initialize_panels(window, cx);
+        initialize_panels(workspace, window, cx);
+        initialize_project_keymap(workspace, window, cx);
         register_actions(app_state.clone(), workspace, window, cx);
 
         workspace.focus_handle(cx).focus(window);
@@ -482,97 +509,41 @@ fn initialize_file_watcher(window: &mut Window, cx: &mut Context<Workspace>) {
     }
 }
 
-fn show_software_emulation_warning_if_needed(
-    specs: gpui::GpuSpecs,
-    window: &mut Window,
-    cx: &mut Context<Workspace>,
-) {
-    if specs.is_software_emulated && std::env::var("ZED_ALLOW_EMULATED_GPU").is_err() {
-        let (graphics_api, docs_url, open_url) = if cfg!(target_os = "windows") {
-            (
-                "DirectX",
-                "https://zed.dev/docs/windows",
-                "https://zed.dev/docs/windows",
-            )
-        } else {
-            (
-                "Vulkan",
-                "https://zed.dev/docs/linux",
-                "https://zed.dev/docs/linux#zed-fails-to-open-windows",
-            )
-        };
-        let message = format!(
-            db::indoc! {r#"
-            Zed uses {} for rendering and requires a compatible GPU.
-
-            Currently you are using a software emulated GPU ({}) which
-            will result in awful performance.
-
-            For troubleshooting see: {}
-            Set ZED_ALLOW_EMULATED_GPU=1 env var to permanently override.
-            "#},
-            graphics_api, specs.device_name, docs_url
-        );
-        let prompt = window.prompt(
-            PromptLevel::Critical,
-            "Unsupported GPU",
-            Some(&message),
-            &["Skip", "Troubleshoot and Quit"],
-            cx,
-        );
-        cx.spawn(async move |_, cx| {
-            if prompt.await == Ok(1) {
-                cx.update(|cx| {
-                    cx.open_url(open_url);
-                    cx.quit();
-                })
-                .ok();
-            }
-        })
-        .detach()
-    }
-}
+fn initialize_panels(workspace: &mut Workspace, window: &mut Window, cx: &mut Context<Workspace>) {
+    // These panels are rarely visible when Zed starts, so they are constructed when they're first
+    // shown, or once the window has been drawn, instead of holding up opening the workspace.
+    workspace.add_deferred_panel(OutlinePanel::load, window, cx);
+    workspace.add_deferred_panel(collab_ui::collab_panel::CollabPanel::load, window, cx);
+    workspace.add_deferred_panel(
+        collab_ui::notification_panel::NotificationPanel::load,
+        window,
+        cx,
+    );
 
-fn initialize_panels(window: &mut Window, cx: &mut Context<Workspace>) {
     cx.spawn_in(window, async move |workspace_handle, cx| {
         let project_panel = ProjectPanel::load(workspace_handle.clone(), cx.clone());
-        let outline_panel = OutlinePanel::load(workspace_handle.clone(), cx.clone());
         let terminal_panel = TerminalPanel::load(workspace_handle.clone(), cx.clone());
         let git_panel = GitPanel::load(workspace_handle.clone(), cx.clone());
-        let channels_panel =
-            collab_ui::collab_panel::CollabPanel::load(workspace_handle.clone(), cx.clone());
-        let notification_panel = collab_ui::notification_panel::NotificationPanel::load(
-            workspace_handle.clone(),
-            cx.clone(),
-        );
         let debug_panel = DebugPanel::load(workspace_handle.clone(), cx);
 
-        let (
-            project_panel,
-            outline_panel,
-            terminal_panel,
-            git_panel,
-            channels_panel,
-            notification_panel,
-            debug_panel,
-        ) = futures::try_join!(
-            project_panel,
-            outline_panel,
-            git_panel,
-            terminal_panel,
-            channels_panel,
-            notification_panel,
-            debug_panel,
-        )?;
+        let (project_panel, terminal_panel, git_panel, debug_panel) =
+            futures::try_join!(project_panel, terminal_panel, git_panel, debug_panel)?;
 
         workspace_handle.update_in(cx, |workspace, window, cx| {
             workspace.add_panel(project_panel, window, cx);
-            workspace.add_panel(outline_panel, window, cx);
             workspace.add_panel(terminal_panel, window, cx);
             workspace.add_panel(git_panel, window, cx);
-            workspace.add_panel(channels_panel, window, cx);
-            workspace.add_panel(notification_panel, window, cx);
             workspace.add_panel(debug_panel, window, cx);
+            StartupTimings::finish_global("panel construction", cx);
+
+            let workspace_handle = cx.weak_entity();
+            window.on_next_frame(move |window, cx| {
+                workspace_handle
+                    .update(cx, |workspace, cx| {
+                        workspace.load_deferred_panels(window, cx)
+                    })
+                    .ok();
+            });
         })?;
 
         anyhow::Ok(())
@@ -788,6 +759,9 @@ fn register_actions(
                 |_, _, _| None,
             );
         })
+        .register_action(|workspace, _: &OpenMemoryUsage, window, cx| {
+            memory_usage::MemoryUsage::open(workspace, window, cx)
+        })
         .register_action(open_project_settings_file)
         .register_action(open_project_tasks_file)
         .register_action(open_project_debug_tasks_file)
@@ -1266,6 +1240,169 @@ pub fn handle_settings_file_changes(
         }
     })
     .detach();
+
+    let mut file_conflicts_rx = SettingsStore::update_global(cx, |store, _| store.file_conflicts());
+    cx.spawn(async move |cx| {
+        while let Some(conflict) = file_conflicts_rx.next().await {
+            if cx
+                .update(|cx| show_settings_file_conflict(conflict, cx))
+                .is_err()
+            {
+                break;
+            }
+        }
+    })
+    .detach();
+}
+
+fn show_settings_file_conflict(conflict: SettingsFileConflict, cx: &mut App) {
+    struct SettingsFileConflictNotification;
+    let keys = conflict
+        .keys
+        .iter()
+        .map(|key| format!("`{key}`"))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let diff: Arc<str> = language::unified_diff(&conflict.disk_text, &conflict.merged_text).into();
+    show_app_notification(
+        NotificationId::unique::<SettingsFileConflictNotification>(),
+        cx,
+        move |cx| {
+            let diff = diff.clone();
+            cx.new(|cx| {
+                MessageNotification::new(
+                    format!(
+                        "Your settings file was saved while Zed was updating it. The other \
+                        changes were kept, but Zed's values replaced the saved ones for: {keys}"
+                    ),
+                    cx,
+                )
+                .primary_message("View Changes")
+                .primary_icon(IconName::Diff)
+                .primary_on_click(move |window, cx| {
+                    if let Some(workspace) = window.root::<Workspace>().flatten() {
+                        let diff = diff.clone();
+                        workspace.update(cx, |workspace, cx| {
+                            open_bundled_file(
+                                workspace,
+                                diff.to_string().into(),
+                                "Settings Conflict",
+                                "Diff",
+                                window,
+                                cx,
+                            );
+                        });
+                    }
+                    cx.emit(DismissEvent);
+                })
+                .secondary_message("Open Settings File")
+                .secondary_on_click(|window, cx| {
+                    window.dispatch_action(zed_actions::OpenSettingsFile.boxed_clone(), cx);
+                    cx.emit(DismissEvent);
+                })
+            })
+        },
+    );
+}
+
+/// The `.zed/keymap.json` files of the project in the active window, whose bindings are layered
+/// over the user keymap.
+struct ActiveProjectKeymaps {
+    contents: Vec<String>,
+    tx: mpsc::UnboundedSender<Vec<String>>,
+}
+
+impl Global for ActiveProjectKeymaps {}
+
+fn initialize_project_keymap(
+    workspace: &mut Workspace,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    fn activate_project_keymaps(workspace: &Workspace, window: &Window, cx: &mut App) {
+        if !window.is_window_active() || !cx.has_global::<ActiveProjectKeymaps>() {
+            return;
+        }
+        let contents = workspace.project().read(cx).local_keymaps(cx);
+        let active_keymaps = cx.global_mut::<ActiveProjectKeymaps>();
+        if active_keymaps.contents != contents {
+            active_keymaps.contents = contents.clone();
+            active_keymaps.tx.unbounded_send(contents).ok();
+        }
+    }
+
+    cx.observe_window_activation(window, |workspace, window, cx| {
+        activate_project_keymaps(workspace, window, cx)
+    })
+    .detach();
+    cx.subscribe_in(
+        workspace.project(),
+        window,
+        |workspace, _, event, window, cx| {
+            if let project::Event::LocalKeymapUpdated = event {
+                activate_project_keymaps(workspace, window, cx)
+            }
+        },
+    )
+    .detach();
+}
+
+fn load_project_key_bindings(
+    project_keymaps: &[String],
+    notification_id: &NotificationId,
+    cx: &mut App,
+) -> Vec<KeyBinding> {
+    let mut key_bindings = Vec::new();
+    let mut error_messages = Vec::new();
+    for project_keymap in project_keymaps {
+        match KeymapFile::load(project_keymap, cx) {
+            KeymapFileLoadResult::Success {
+                key_bindings: project_key_bindings,
+            } => key_bindings.extend(project_key_bindings),
+            KeymapFileLoadResult::SomeFailedToLoad {
+                key_bindings: project_key_bindings,
+                error_message,
+            } => {
+                key_bindings.extend(project_key_bindings);
+                error_messages.push(error_message.0);
+            }
+            KeymapFileLoadResult::JsonParseFailure { error } => {
+                error_messages.push(format!(
+                    "JSON parse error in project keymap file. Its bindings were not loaded.\n\n{}",
+                    MarkdownCodeBlock {
+                        tag: "",
+                        text: &error.to_string(),
+                    }
+                ));
+            }
+        }
+    }
+
+    if error_messages.is_empty() {
+        dismiss_app_notification(notification_id, cx);
+    } else {
+        show_markdown_app_notification(
+            notification_id.clone(),
+            MarkdownString(error_messages.join("\n\n")),
+            "Open Project Keymap".into(),
+            |window, cx| {
+                if let Some(workspace) = window.root::<Workspace>().flatten() {
+                    workspace.update(cx, |workspace, cx| {
+                        open_local_file(
+                            workspace,
+                            local_keymap_file_relative_path(),
+                            "[]\n".into(),
+                            window,
+                            cx,
+                        );
+                    });
+                }
+                cx.emit(DismissEvent);
+            },
+            cx,
+        );
+    }
+    key_bindings
 }
 
 pub fn handle_keymap_file_changes(
@@ -1325,18 +1462,37 @@ pub fn handle_keymap_file_changes(
         .detach();
     }
 
+    let (project_keymaps_tx, mut project_keymaps_rx) = mpsc::unbounded();
+    cx.set_global(ActiveProjectKeymaps {
+        contents: Vec::new(),
+        tx: project_keymaps_tx,
+    });
+
+    // Both the user and the project keymap are written by the user, so their `NoAction`
+    // bindings should disable keystrokes rather than defer to less specific bindings.
+    cx.key_bindings()
+        .borrow_mut()
+        .set_user_defined_metas([KeybindSource::User.meta(), KeybindSource::Project.meta()]);
     load_default_keymap(cx);
 
     struct KeymapParseErrorNotification;
     let notification_id = NotificationId::unique::<KeymapParseErrorNotification>();
+    struct ProjectKeymapParseErrorNotification;
+    let project_notification_id = NotificationId::unique::<ProjectKeymapParseErrorNotification>();
 
     cx.spawn(async move |cx| {
         let mut user_keymap_content = String::new();
+        let mut project_keymaps = Vec::new();
         let mut migrating_in_memory = false;
         loop {
             select_biased! {
                 _ = base_keymap_rx.next() => {},
                 _ = keyboard_layout_rx.next() => {},
+                contents = project_keymaps_rx.next() => {
+                    if let Some(contents) = contents {
+                        project_keymaps = contents;
+                    }
+                }
                 content = user_keymap_file_rx.next() => {
                     if let Some(content) = content {
                         if let Ok(Some(migrated_content)) = migrate_keymap(&content) {
@@ -1358,10 +1514,12 @@ pub fn handle_keymap_file_changes(
                         });
                     });
                 }
+                let project_key_bindings =
+                    load_project_key_bindings(&project_keymaps, &project_notification_id, cx);
                 let load_result = KeymapFile::load(&user_keymap_content, cx);
                 match load_result {
                     KeymapFileLoadResult::Success { key_bindings } => {
-                        reload_keymaps(cx, key_bindings);
+                        reload_keymaps(cx, key_bindings, project_key_bindings);
                         dismiss_app_notification(&notification_id.clone(), cx);
                     }
                     KeymapFileLoadResult::SomeFailedToLoad {
@@ -1369,7 +1527,7 @@ pub fn handle_keymap_file_changes(
                         error_message,
                     } => {
                         if !key_bindings.is_empty() {
-                            reload_keymaps(cx, key_bindings);
+                            reload_keymaps(cx, key_bindings, project_key_bindings);
                         }
                         show_keymap_file_load_error(notification_id.clone(), error_message, cx);
                     }
@@ -1472,7 +1630,11 @@ fn show_markdown_app_notification<F>(
     .detach();
 }
 
-fn reload_keymaps(cx: &mut App, mut user_key_bindings: Vec<KeyBinding>) {
+fn reload_keymaps(
+    cx: &mut App,
+    mut user_key_bindings: Vec<KeyBinding>,
+    mut project_key_bindings: Vec<KeyBinding>,
+) {
     cx.clear_key_bindings();
     load_default_keymap(cx);
 
@@ -1481,6 +1643,12 @@ fn reload_keymaps(cx: &mut App, mut user_key_bindings: Vec<KeyBinding>) {
     }
     cx.bind_keys(user_key_bindings);
 
+    // Bound after the user keymap so that project bindings take precedence.
+    for key_binding in &mut project_key_bindings {
+        key_binding.set_meta(KeybindSource::Project.meta());
+    }
+    cx.bind_keys(project_key_bindings);
+
     let menus = app_menus(cx);
     cx.set_menus(menus);
     // On Windows, this is set in the `update_jump_list` method of the `HistoryManager`.
@@ -1869,15 +2037,19 @@ pub(crate) fn eager_load_active_theme_and_icon_theme(fs: Arc<dyn Fs>, cx: &mut A
     let extension_store = ExtensionStore::global(cx);
     let theme_registry = ThemeRegistry::global(cx);
     let theme_settings = ThemeSettings::get_global(cx);
-    let appearance = SystemAppearance::global(cx).0;
 
     enum LoadTarget {
         Theme(PathBuf),
         IconTheme((PathBuf, PathBuf)),
     }
 
-    let theme_name = theme_settings.theme.name(appearance);
-    let icon_theme_name = theme_settings.icon_theme.name(appearance);
+    let theme_name = theme_settings
+        .theme
+        .name(theme::appearance_for_mode(theme_settings.theme.mode(), cx));
+    let icon_theme_name = theme_settings.icon_theme.name(theme::appearance_for_mode(
+        theme_settings.icon_theme.mode(),
+        cx,
+    ));
     let themes_to_load = [
         theme_registry
             .get(&theme_name.0)
@@ -4354,6 +4526,76 @@ mod tests {
         assert_key_bindings_for(workspace.into(), cx, vec![("6", &Deploy)], line!());
     }
 
+    #[gpui::test]
+    async fn test_project_keymap(cx: &mut gpui::TestAppContext) {
+        let executor = cx.executor();
+        let app_state = init_keymap_test(cx);
+        app_state
+            .fs
+            .as_fake()
+            .insert_tree(
+                path!("/project"),
+                json!({
+                    ".zed": {
+                        "keymap.json": r#"[{"bindings": {"backspace": "test_only::ActionB"}}]"#,
+                    },
+                    "file.txt": "",
+                }),
+            )
+            .await;
+        app_state
+            .fs
+            .save(
+                "/keymap.json".as_ref(),
+                &r#"[{"bindings": {"backspace": "test_only::ActionA"}}]"#.into(),
+                Default::default(),
+            )
+            .await
+            .unwrap();
+        let project = Project::test(app_state.fs.clone(), [path!("/project").as_ref()], cx).await;
+        let workspace =
+            cx.add_window(|window, cx| Workspace::test_new(project.clone(), window, cx));
+
+        cx.update(|cx| {
+            let keymap_rx = watch_config_file(
+                &executor,
+                app_state.fs.clone(),
+                PathBuf::from("/keymap.json"),
+            );
+            handle_keymap_file_changes(keymap_rx, cx);
+        });
+        workspace
+            .update(cx, |workspace, window, cx| {
+                workspace.register_action(|_, _: &ActionA, _window, _cx| {});
+                workspace.register_action(|_, _: &ActionB, _window, _cx| {});
+                initialize_project_keymap(workspace, window, cx);
+                window.activate_window();
+            })
+            .unwrap();
+        executor.run_until_parked();
+
+        let backspace_action = |cx: &mut TestAppContext| {
+            cx.update(|cx| {
+                let (bindings, _) = cx
+                    .key_bindings()
+                    .borrow()
+                    .bindings_for_input(&[gpui::Keystroke::parse("backspace").unwrap()], &[]);
+                bindings[0].action().boxed_clone()
+            })
+        };
+
+        // The project keymap is bound over the user keymap
+        assert!(backspace_action(cx).partial_eq(&ActionB));
+
+        // Closing the worktree removes its bindings
+        let worktree_id = project.read_with(cx, |project, cx| {
+            project.worktrees(cx).next().unwrap().read(cx).id()
+        });
+        project.update(cx, |project, cx| project.remove_worktree(worktree_id, cx));
+        executor.run_until_parked();
+        assert!(backspace_action(cx).partial_eq(&ActionA));
+    }
+
     #[gpui::test]
     async fn test_generate_keymap_json_schema_for_registered_actions(
         cx: &mut gpui::TestAppContext,
@@ -4488,24 +4730,31 @@ mod tests {
                 "console",
                 "context_server",
                 "copilot",
+                "coverage",
+                "csv",
                 "debug_panel",
                 "debugger",
                 "dev",
                 "diagnostics",
                 "edit_prediction",
                 "editor",
+                "extension_panel",
                 "feedback",
                 "file_finder",
                 "git",
                 "git_onboarding",
                 "git_panel",
                 "go_to_line",
+                "hex_editor",
                 "icon_theme_selector",
+                "image_viewer",
                 "journal",
                 "keymap_editor",
                 "keystroke_input",
                 "language_selector",
                 "line_ending",
+                "local_history",
+                "log_viewer",
                 "lsp_tool",
                 "markdown",
                 "menu",
@@ -4522,6 +4771,7 @@ mod tests {
                 "project_symbols",
                 "projects",
                 "repl",
+                "rest_client",
                 "rules_library",
                 "search",
                 "settings_editor",
@@ -4535,9 +4785,11 @@ mod tests {
                 "task",
                 "terminal",
                 "terminal_panel",
+                "test_explorer",
                 "theme_selector",
                 "toast",
                 "toolchain",
+                "type_hierarchy",
                 "variable_list",
                 "vim",
                 "window",
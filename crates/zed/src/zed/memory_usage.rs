@@ -0,0 +1,322 @@
+use super::DropCaches;
+use gpui::{App, Entity, EventEmitter, FocusHandle, Focusable, Task};
+use project::Project;
+use std::time::Duration;
+use sysinfo::{Pid, Process, ProcessRefreshKind, ProcessesToUpdate, System};
+use ui::{Tooltip, prelude::*};
+use util::size::format_file_size;
+use workspace::{
+    Workspace,
+    item::{Item, ItemEvent},
+};
+
+const REFRESH_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Shows how much memory Zed and its language servers use, and which parts of the project the
+/// memory of Zed's own process can be attributed to.
+pub struct MemoryUsage {
+    project: Entity<Project>,
+    focus_handle: FocusHandle,
+    system: System,
+    report: MemoryReport,
+    _refresh_task: Task<()>,
+}
+
+#[derive(Default)]
+struct MemoryReport {
+    /// The resident memory of Zed's own process.
+    resident: Option<u64>,
+    buffer_count: usize,
+    buffer_bytes: u64,
+    worktrees: Vec<WorktreeUsage>,
+    language_servers: Vec<LanguageServerUsage>,
+    sprite_atlas_bytes: u64,
+}
+
+struct WorktreeUsage {
+    name: SharedString,
+    entry_count: usize,
+    estimated_bytes: u64,
+}
+
+struct LanguageServerUsage {
+    name: SharedString,
+    /// The resident memory of the server's process, which isn't known for servers that run on
+    /// another machine.
+    resident: Option<u64>,
+}
+
+impl MemoryReport {
+    /// The part of Zed's resident memory that isn't attributed to buffers, worktrees or the
+    /// sprite atlas.
+    fn unattributed(&self) -> Option<u64> {
+        let attributed = self.buffer_bytes
+            + self.sprite_atlas_bytes
+            + self
+                .worktrees
+                .iter()
+                .map(|worktree| worktree.estimated_bytes)
+                .sum::<u64>();
+        Some(self.resident?.saturating_sub(attributed))
+    }
+}
+
+impl MemoryUsage {
+    pub fn open(workspace: &mut Workspace, window: &mut Window, cx: &mut Context<Workspace>) {
+        if let Some(existing) = workspace.item_of_type::<Self>(cx) {
+            workspace.activate_item(&existing, true, true, window, cx);
+            return;
+        }
+        let project = workspace.project().clone();
+        let memory_usage = cx.new(|cx| Self::new(project, window, cx));
+        workspace.add_item_to_active_pane(Box::new(memory_usage), None, true, window, cx);
+    }
+
+    fn new(project: Entity<Project>, window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let refresh_task = cx.spawn_in(window, async move |this, cx| {
+            loop {
+                cx.background_executor().timer(REFRESH_INTERVAL).await;
+                if this
+                    .update_in(cx, |this, window, cx| this.refresh(window, cx))
+                    .is_err()
+                {
+                    break;
+                }
+            }
+        });
+        let mut this = Self {
+            project,
+            focus_handle: cx.focus_handle(),
+            system: System::new(),
+            report: MemoryReport::default(),
+            _refresh_task: refresh_task,
+        };
+        this.refresh(window, cx);
+        this
+    }
+
+    fn refresh(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let project = self.project.read(cx);
+
+        let mut buffer_count = 0;
+        let mut buffer_bytes = 0;
+        for buffer in project.buffer_store().read(cx).buffers() {
+            buffer_count += 1;
+            buffer_bytes += buffer.read(cx).len() as u64;
+        }
+
+        let worktrees = project
+            .worktrees(cx)
+            .map(|worktree| {
+                let worktree = worktree.read(cx);
+                WorktreeUsage {
+                    name: worktree.root_name_str().to_string().into(),
+                    entry_count: worktree.entry_count(),
+                    estimated_bytes: worktree.estimated_memory_size() as u64,
+                }
+            })
+            .collect();
+
+        let lsp_store = project.lsp_store().read(cx);
+        let language_servers = lsp_store
+            .language_server_statuses()
+            .map(|(server_id, status)| {
+                let process_id = lsp_store
+                    .language_server_for_id(server_id)
+                    .and_then(|server| server.process_id())
+                    .map(Pid::from_u32);
+                (status.name.0.clone(), process_id)
+            })
+            .collect::<Vec<_>>();
+
+        let process_ids = language_servers
+            .iter()
+            .filter_map(|(_, process_id)| *process_id)
+            .chain(sysinfo::get_current_pid().ok())
+            .collect::<Vec<_>>();
+        self.system.refresh_processes_specifics(
+            ProcessesToUpdate::Some(&process_ids),
+            true,
+            ProcessRefreshKind::nothing().with_memory(),
+        );
+        let resident =
+            |process_id: Option<Pid>| self.system.process(process_id?).map(Process::memory);
+
+        self.report = MemoryReport {
+            resident: resident(sysinfo::get_current_pid().ok()),
+            buffer_count,
+            buffer_bytes,
+            worktrees,
+            language_servers: language_servers
+                .into_iter()
+                .map(|(name, process_id)| LanguageServerUsage {
+                    name,
+                    resident: resident(process_id),
+                })
+                .collect(),
+            sprite_atlas_bytes: window.sprite_atlas_memory() as u64,
+        };
+        cx.notify();
+    }
+
+    fn render_section(
+        &self,
+        title: &'static str,
+        rows: Vec<(SharedString, SharedString)>,
+        cx: &Context<Self>,
+    ) -> impl IntoElement {
+        v_flex()
+            .gap_1()
+            .child(Label::new(title).size(LabelSize::Large))
+            .children(rows.into_iter().map(|(name, value)| {
+                h_flex()
+                    .w_full()
+                    .max_w_128()
+                    .justify_between()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .child(Label::new(name))
+                    .child(Label::new(value).color(Color::Muted))
+            }))
+    }
+}
+
+/// Drops the caches GPUI rebuilds when they are next needed.
+pub fn drop_caches(cx: &mut App) {
+    cx.text_system().clear_caches();
+    cx.clear_asset_cache();
+    cx.refresh_windows();
+}
+
+fn format_bytes(bytes: Option<u64>) -> SharedString {
+    match bytes {
+        Some(bytes) => format_file_size(bytes, false).into(),
+        None => "Unavailable".into(),
+    }
+}
+
+impl Render for MemoryUsage {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let report = &self.report;
+        let zed_rows = vec![
+            ("Resident memory".into(), format_bytes(report.resident)),
+            (
+                format!("{} buffers", report.buffer_count).into(),
+                format_bytes(Some(report.buffer_bytes)),
+            ),
+            (
+                "GPU texture atlas (this window)".into(),
+                format_bytes(Some(report.sprite_atlas_bytes)),
+            ),
+            ("Other".into(), format_bytes(report.unattributed())),
+        ];
+        let worktree_rows = report
+            .worktrees
+            .iter()
+            .map(|worktree| {
+                (
+                    format!("{} ({} entries)", worktree.name, worktree.entry_count).into(),
+                    format_bytes(Some(worktree.estimated_bytes)),
+                )
+            })
+            .collect();
+        let language_server_rows = report
+            .language_servers
+            .iter()
+            .map(|server| (server.name.clone(), format_bytes(server.resident)))
+            .collect();
+
+        v_flex()
+            .id("memory-usage")
+            .track_focus(&self.focus_handle)
+            .size_full()
+            .p_4()
+            .gap_4()
+            .overflow_y_scroll()
+            .bg(cx.theme().colors().editor_background)
+            .child(
+                h_flex()
+                    .gap_2()
+                    .child(
+                        Button::new("refresh-memory-usage", "Refresh")
+                            .icon(IconName::RotateCw)
+                            .icon_size(IconSize::Small)
+                            .icon_position(IconPosition::Start)
+                            .on_click(cx.listener(|this, _, window, cx| this.refresh(window, cx))),
+                    )
+                    .child(
+                        Button::new("drop-caches", "Drop Caches")
+                            .icon(IconName::Trash)
+                            .icon_size(IconSize::Small)
+                            .icon_position(IconPosition::Start)
+                            .tooltip(Tooltip::text(
+                                "Drop cached glyph layouts and decoded images, which are rebuilt when next needed",
+                            ))
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                window.dispatch_action(Box::new(DropCaches), cx);
+                                this.refresh(window, cx);
+                            })),
+                    ),
+            )
+            .child(self.render_section("Zed", zed_rows, cx))
+            .child(self.render_section("Worktree Snapshots (estimated)", worktree_rows, cx))
+            .child(self.render_section(
+                "Language Servers",
+                language_server_rows,
+                cx,
+            ))
+    }
+}
+
+impl Focusable for MemoryUsage {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl EventEmitter<ItemEvent> for MemoryUsage {}
+
+impl Item for MemoryUsage {
+    type Event = ItemEvent;
+
+    fn tab_content_text(&self, _detail: usize, _cx: &App) -> SharedString {
+        "Memory Usage".into()
+    }
+
+    fn show_toolbar(&self) -> bool {
+        false
+    }
+
+    fn to_item_events(event: &Self::Event, mut f: impl FnMut(ItemEvent)) {
+        f(*event)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unattributed() {
+        let mut report = MemoryReport {
+            resident: Some(1000),
+            buffer_count: 2,
+            buffer_bytes: 100,
+            worktrees: vec![WorktreeUsage {
+                name: "zed".into(),
+                entry_count: 3,
+                estimated_bytes: 300,
+            }],
+            language_servers: Vec::new(),
+            sprite_atlas_bytes: 200,
+        };
+        assert_eq!(report.unattributed(), Some(400));
+
+        // The estimates can exceed what the process has resident, such as when it's swapped out.
+        report.sprite_atlas_bytes = 2000;
+        assert_eq!(report.unattributed(), Some(0));
+
+        report.resident = None;
+        assert_eq!(report.unattributed(), None);
+    }
+}
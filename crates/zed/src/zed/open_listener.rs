@@ -4,7 +4,7 @@ use anyhow::{Context as _, Result, anyhow};
 use cli::{CliRequest, CliResponse, ipc::IpcSender};
 use cli::{IpcHandshake, ipc};
 use client::parse_zed_link;
-use collections::HashMap;
+use collections::{HashMap, HashSet};
 use db::kvp::KEY_VALUE_STORE;
 use editor::Editor;
 use fs::Fs;
@@ -24,8 +24,8 @@ use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::thread;
 use std::time::Duration;
-use util::ResultExt;
 use util::paths::PathWithPosition;
+use util::{ResultExt, maybe};
 use workspace::PathList;
 use workspace::item::ItemHandle;
 use workspace::{AppState, OpenOptions, SerializedWorkspaceLocation, Workspace};
@@ -275,15 +275,23 @@ pub async fn open_paths_with_positions(
         .update(|cx| workspace::open_paths(&paths, app_state, open_options, cx))?
         .await?;
 
-    for diff_pair in diff_paths {
-        let old_path = Path::new(&diff_pair[0]).canonicalize()?;
-        let new_path = Path::new(&diff_pair[1]).canonicalize()?;
-        if let Ok(diff_view) = workspace.update(cx, |workspace, window, cx| {
-            FileDiffView::open(old_path, new_path, workspace, window, cx)
-        }) && let Some(diff_view) = diff_view.await.log_err()
-        {
-            items.push(Some(Ok(Box::new(diff_view))))
-        }
+    // Diffs that fail to open are reported like files that fail to open, so that the CLI exits
+    // with an error rather than silently skipping them.
+    let mut diff_items = Vec::with_capacity(diff_paths.len());
+    for [old_path, new_path] in diff_paths {
+        let diff_view = maybe!(async {
+            let old_path = Path::new(old_path).canonicalize()?;
+            let new_path = Path::new(new_path).canonicalize()?;
+            let diff_view = workspace
+                .update(cx, |workspace, window, cx| {
+                    FileDiffView::open(old_path, new_path, workspace, window, cx)
+                })?
+                .await?;
+            anyhow::Ok(Box::new(diff_view) as Box<dyn ItemHandle>)
+        })
+        .await
+        .map_err(|error| anyhow!("error opening diff of {old_path:?} and {new_path:?}: {error}"));
+        diff_items.push(Some(diff_view));
     }
 
     for (item, path) in items.iter_mut().zip(&paths) {
@@ -308,6 +316,7 @@ pub async fn open_paths_with_positions(
         }
     }
 
+    items.extend(diff_items);
     Ok((workspace, items))
 }
 
@@ -508,21 +517,25 @@ async fn open_local_workspace(
     .await
     {
         Ok((workspace, items)) => {
-            let mut item_release_futures = Vec::new();
+            // Items that `--wait` waits for, which are done once they're closed in every pane or
+            // released, since closing a tab doesn't release an item that something else retains.
+            let mut open_item_ids = HashSet::default();
+            let (closed_item_tx, mut closed_item_rx) = mpsc::unbounded();
+            let mut subscriptions = Vec::new();
 
             for item in items {
                 match item {
                     Some(Ok(item)) => {
+                        let item_id = item.item_id();
+                        open_item_ids.insert(item_id);
                         cx.update(|cx| {
-                            let released = oneshot::channel();
-                            item.on_release(
+                            let closed_item_tx = closed_item_tx.clone();
+                            subscriptions.push(item.on_release(
                                 cx,
                                 Box::new(move |_| {
-                                    let _ = released.0.send(());
+                                    closed_item_tx.unbounded_send(item_id).ok();
                                 }),
-                            )
-                            .detach();
-                            item_release_futures.push(released.1);
+                            ));
                         })
                         .log_err();
                     }
@@ -539,6 +552,27 @@ async fn open_local_workspace(
             }
 
             if wait {
+                if let Ok(workspace) = workspace.entity(cx) {
+                    let closed_item_tx = closed_item_tx.clone();
+                    cx.update(|cx| {
+                        subscriptions.push(cx.subscribe(
+                            &workspace,
+                            move |workspace, event, cx| {
+                                if let workspace::Event::ItemRemoved { item_id } = event
+                                    && !workspace
+                                        .read(cx)
+                                        .items(cx)
+                                        .any(|item| item.item_id() == *item_id)
+                                {
+                                    closed_item_tx.unbounded_send(*item_id).ok();
+                                }
+                            },
+                        ));
+                    })
+                    .log_err();
+                }
+                drop(closed_item_tx);
+
                 let background = cx.background_executor().clone();
                 let wait = async move {
                     if paths_with_position.is_empty() && diff_paths.is_empty() {
@@ -550,7 +584,12 @@ async fn open_local_workspace(
                         });
                         let _ = done_rx.await;
                     } else {
-                        let _ = futures::future::try_join_all(item_release_futures).await;
+                        while !open_item_ids.is_empty() {
+                            let Some(item_id) = closed_item_rx.next().await else {
+                                break;
+                            };
+                            open_item_ids.remove(&item_id);
+                        }
                     };
                 }
                 .fuse();
@@ -613,7 +652,10 @@ mod tests {
     use gpui::TestAppContext;
     use remote::SshConnectionOptions;
     use serde_json::json;
-    use std::sync::Arc;
+    use std::sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering::SeqCst},
+    };
     use util::path;
     use workspace::{AppState, Workspace};
 
@@ -644,6 +686,7 @@ mod tests {
                 port_forwards: None,
                 nickname: None,
                 upload_binary_over_ssh: false,
+                forward_agent: false,
             })
         );
         assert_eq!(request.open_paths, vec!["/"]);
@@ -760,6 +803,94 @@ mod tests {
             .unwrap();
     }
 
+    #[gpui::test]
+    async fn test_open_workspace_with_invalid_diff(cx: &mut TestAppContext) {
+        let app_state = init_test(cx);
+
+        app_state
+            .fs
+            .as_fake()
+            .insert_tree(path!("/root"), json!({ "file.txt": "content" }))
+            .await;
+
+        let (response_tx, response_rx) = ipc::channel::<CliResponse>().unwrap();
+        let errored = cx
+            .spawn(|mut cx| async move {
+                open_local_workspace(
+                    vec![path!("/root/file.txt").to_owned()],
+                    vec![[
+                        path!("/nonexistent/old.txt").to_owned(),
+                        path!("/nonexistent/new.txt").to_owned(),
+                    ]],
+                    None,
+                    false,
+                    &response_tx,
+                    None,
+                    &app_state,
+                    &mut cx,
+                )
+                .await
+            })
+            .await;
+
+        assert!(errored, "a diff that fails to open should be reported");
+        match response_rx.try_recv().unwrap() {
+            CliResponse::Stderr { message } => {
+                assert!(message.starts_with("error opening diff of"), "{message}")
+            }
+            response => panic!("unexpected response {response:?}"),
+        }
+        assert_eq!(cx.windows().len(), 1);
+    }
+
+    #[gpui::test]
+    async fn test_wait_for_closed_tab(cx: &mut TestAppContext) {
+        let app_state = init_test(cx);
+
+        app_state
+            .fs
+            .as_fake()
+            .insert_tree(path!("/root"), json!({ "file.txt": "content" }))
+            .await;
+
+        let (response_tx, _response_rx) = ipc::channel::<CliResponse>().unwrap();
+        let done = Arc::new(AtomicBool::new(false));
+        cx.spawn({
+            let done = done.clone();
+            |mut cx| async move {
+                open_local_workspace(
+                    vec![path!("/root/file.txt").to_owned()],
+                    vec![],
+                    None,
+                    true,
+                    &response_tx,
+                    None,
+                    &app_state,
+                    &mut cx,
+                )
+                .await;
+                done.store(true, SeqCst);
+            }
+        })
+        .detach();
+        cx.run_until_parked();
+
+        assert!(!done.load(SeqCst), "should wait while the file is open");
+        let workspace = cx.windows()[0].downcast::<Workspace>().unwrap();
+        workspace
+            .update(cx, |workspace, window, cx| {
+                workspace.active_pane().update(cx, |pane, cx| {
+                    pane.close_active_item(&Default::default(), window, cx)
+                })
+            })
+            .unwrap()
+            .await
+            .unwrap();
+        cx.run_until_parked();
+
+        assert!(done.load(SeqCst), "closing the tab should stop the wait");
+    }
+
     async fn open_workspace_file(
         path: &str,
         open_new_workspace: Option<bool>,
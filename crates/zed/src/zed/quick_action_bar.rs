@@ -1,6 +1,9 @@
+mod format_menu;
+mod outline_menu;
 mod preview;
 mod repl_menu;
 
+use collections::HashSet;
 use editor::actions::{
     AddSelectionAbove, AddSelectionBelow, CodeActionSource, DuplicateLineDown, GoToDiagnostic,
     GoToHunk, GoToPreviousDiagnostic, GoToPreviousHunk, MoveLineDown, MoveLineUp, SelectAll,
@@ -8,11 +11,11 @@ use editor::actions::{
     ToggleDiagnostics, ToggleGoToLine, ToggleInlineDiagnostics,
 };
 use editor::code_context_menus::{CodeContextMenu, ContextMenuOrigin};
-use editor::{Editor, EditorSettings};
+use editor::{Editor, EditorSettings, SoftWrap};
 use gpui::{
     anchored, deferred, point, Action, AnchoredPositionMode, ClickEvent, Context, Corner,
-    ElementId, Entity, EventEmitter, FocusHandle, Focusable, InteractiveElement, ParentElement,
-    Render, Styled, Subscription, WeakEntity, Window,
+    ElementId, Entity, EntityId, EventEmitter, FocusHandle, Focusable, InteractiveElement,
+    ParentElement, Render, Styled, Subscription, WeakEntity, Window,
 };
 use project::project_settings::DiagnosticSeverity;
 use search::{buffer_search, BufferSearchBar};
@@ -24,7 +27,7 @@ use ui::{
 use vim_mode_setting::VimModeSetting;
 use workspace::item::ItemBufferKind;
 use workspace::{
-    item::ItemHandle, ToolbarItemEvent, ToolbarItemLocation, ToolbarItemView, Workspace,
+    item::ItemHandle, Pane, ToolbarItemEvent, ToolbarItemLocation, ToolbarItemView, Workspace,
 };
 use zed_actions::outline::ToggleOutline;
 
@@ -32,7 +35,9 @@ const MAX_CODE_ACTION_MENU_LINES: u32 = 16;
 
 pub struct QuickActionBar {
     _inlay_hints_enabled_subscription: Option<Subscription>,
+    _repl_auto_attach_subscription: Option<Subscription>,
     active_item: Option<Box<dyn ItemHandle>>,
+    auto_attached_repl_editors: HashSet<EntityId>,
     buffer_search_bar: Entity<BufferSearchBar>,
     show: bool,
     toggle_selections_handle: PopoverMenuHandle<ContextMenu>,
@@ -48,7 +53,9 @@ impl QuickActionBar {
     ) -> Self {
         let mut this = Self {
             _inlay_hints_enabled_subscription: None,
+            _repl_auto_attach_subscription: None,
             active_item: None,
+            auto_attached_repl_editors: HashSet::default(),
             buffer_search_bar,
             show: true,
             toggle_selections_handle: Default::default(),
@@ -104,7 +111,16 @@ impl Render for QuickActionBar {
         let git_blame_inline_enabled = editor_value.git_blame_inline_enabled();
         let show_git_blame_gutter = editor_value.show_git_blame_gutter();
         let auto_signature_help_enabled = editor_value.auto_signature_help_enabled(cx);
+        let snippet_suggestions_enabled = editor_value.snippet_suggestions_enabled(cx);
+        let word_completions_enabled = editor_value.word_completions_enabled(cx);
         let show_line_numbers = editor_value.line_numbers_enabled(cx);
+        let soft_wrap_label = match editor_value.soft_wrap_mode(cx) {
+            SoftWrap::GitDiff | SoftWrap::None => None,
+            SoftWrap::EditorWidth => Some("Soft Wrap: Editor Width"),
+            SoftWrap::Column(_) | SoftWrap::Bounded(_) => Some("Soft Wrap: Preferred Length"),
+        };
+        let read_only = editor_value.read_only(cx);
+        let buffer_read_only = editor_value.buffer().read(cx).read_only();
         let supports_minimap = editor_value.supports_minimap(cx);
         let minimap_enabled = supports_minimap && editor_value.minimap().is_some();
         let has_available_code_actions = editor_value.has_available_code_actions();
@@ -264,6 +280,7 @@ impl Render for QuickActionBar {
         let editor = editor.downgrade();
         let editor_settings_dropdown = {
             let vim_mode_enabled = VimModeSetting::get_global(cx).0;
+            let workspace = self.workspace.clone();
 
             PopoverMenu::new("editor-settings")
                 .trigger_with_tooltip(
@@ -276,6 +293,14 @@ impl Render for QuickActionBar {
                 .anchor(Corner::TopRight)
                 .with_handle(self.toggle_settings_handle.clone())
                 .menu(move |window, cx| {
+                    // Read when the menu opens, as the toolbar isn't notified
+                    // when these change.
+                    let LayoutToggles {
+                        centered_layout,
+                        pane,
+                        pane_zoomed,
+                    } = LayoutToggles::read(&workspace, &editor, cx);
+
                     let menu = ContextMenu::build(window, cx, {
                         let focus_handle = editor_focus_handle.clone();
                         |mut menu, _, _| {
@@ -414,6 +439,48 @@ impl Render for QuickActionBar {
                                 },
                             );
 
+                            menu = menu.toggleable_entry(
+                                soft_wrap_label.unwrap_or("Soft Wrap"),
+                                soft_wrap_label.is_some(),
+                                IconPosition::Start,
+                                Some(editor::actions::CycleSoftWrap.boxed_clone()),
+                                {
+                                    let editor = editor.clone();
+                                    move |window, cx| {
+                                        editor
+                                            .update(cx, |editor, cx| {
+                                                editor.cycle_soft_wrap(
+                                                    &editor::actions::CycleSoftWrap,
+                                                    window,
+                                                    cx,
+                                                );
+                                            })
+                                            .ok();
+                                    }
+                                },
+                            );
+
+                            menu = menu.item(
+                                ContextMenuEntry::new("Read-Only")
+                                    .toggleable(IconPosition::Start, read_only)
+                                    .action(editor::actions::ToggleReadOnly.boxed_clone())
+                                    .disabled(buffer_read_only)
+                                    .handler({
+                                        let editor = editor.clone();
+                                        move |window, cx| {
+                                            editor
+                                                .update(cx, |editor, cx| {
+                                                    editor.toggle_read_only(
+                                                        &editor::actions::ToggleReadOnly,
+                                                        window,
+                                                        cx,
+                                                    );
+                                                })
+                                                .ok();
+                                        }
+                                    }),
+                            );
+
                             menu = menu.toggleable_entry(
                                 "Selection Menu",
                                 selection_menu_enabled,
@@ -456,6 +523,48 @@ impl Render for QuickActionBar {
                                 },
                             );
 
+                            menu = menu.toggleable_entry(
+                                "Snippet Suggestions",
+                                snippet_suggestions_enabled,
+                                IconPosition::Start,
+                                Some(editor::actions::ToggleSnippetSuggestions.boxed_clone()),
+                                {
+                                    let editor = editor.clone();
+                                    move |window, cx| {
+                                        editor
+                                            .update(cx, |editor, cx| {
+                                                editor.toggle_snippet_suggestions(
+                                                    &editor::actions::ToggleSnippetSuggestions,
+                                                    window,
+                                                    cx,
+                                                );
+                                            })
+                                            .ok();
+                                    }
+                                },
+                            );
+
+                            menu = menu.toggleable_entry(
+                                "Word Completions",
+                                word_completions_enabled,
+                                IconPosition::Start,
+                                Some(editor::actions::ToggleWordCompletions.boxed_clone()),
+                                {
+                                    let editor = editor.clone();
+                                    move |window, cx| {
+                                        editor
+                                            .update(cx, |editor, cx| {
+                                                editor.toggle_word_completions(
+                                                    &editor::actions::ToggleWordCompletions,
+                                                    window,
+                                                    cx,
+                                                );
+                                            })
+                                            .ok();
+                                    }
+                                },
+                            );
+
                             menu = menu.separator();
 
                             menu = menu.toggleable_entry(
@@ -498,6 +607,45 @@ impl Render for QuickActionBar {
 
                             menu = menu.separator();
 
+                            menu = menu.toggleable_entry(
+                                "Centered Layout",
+                                centered_layout,
+                                IconPosition::Start,
+                                Some(workspace::ToggleCenteredLayout.boxed_clone()),
+                                {
+                                    let workspace = workspace.clone();
+                                    move |window, cx| {
+                                        workspace
+                                            .update(cx, |workspace, cx| {
+                                                workspace.toggle_centered_layout(
+                                                    &workspace::ToggleCenteredLayout,
+                                                    window,
+                                                    cx,
+                                                );
+                                            })
+                                            .ok();
+                                    }
+                                },
+                            );
+
+                            if let Some(pane) = pane {
+                                let pane = pane.downgrade();
+                                menu = menu.toggleable_entry(
+                                    "Zoom Pane",
+                                    pane_zoomed,
+                                    IconPosition::Start,
+                                    Some(workspace::ToggleZoom.boxed_clone()),
+                                    move |window, cx| {
+                                        pane.update(cx, |pane, cx| {
+                                            pane.toggle_zoom(&workspace::ToggleZoom, window, cx);
+                                        })
+                                        .ok();
+                                    },
+                                );
+                            }
+
+                            menu = menu.separator();
+
                             menu = menu.toggleable_entry(
                                 "Vim Mode",
                                 vim_mode_enabled,
@@ -527,7 +675,9 @@ impl Render for QuickActionBar {
             .gap(DynamicSpacing::Base01.rems(cx))
             .children(self.render_repl_menu(cx))
             .children(self.render_preview_button(self.workspace.clone(), cx))
+            .children(self.render_outline_menu(cx))
             .children(search_button)
+            .children(self.render_format_menu(cx))
             .children(code_actions_dropdown)
             .children(editor_selections_dropdown)
             .child(editor_settings_dropdown)
@@ -585,44 +735,141 @@ impl RenderOnce for QuickActionBarButton {
     }
 }
 
+/// The state of the workspace layout toggles in the editor controls menu.
+struct LayoutToggles {
+    centered_layout: bool,
+    /// The pane containing the editor, which is the one that gets zoomed.
+    pane: Option<Entity<Pane>>,
+    pane_zoomed: bool,
+}
+
+impl LayoutToggles {
+    fn read(workspace: &WeakEntity<Workspace>, editor: &WeakEntity<Editor>, cx: &App) -> Self {
+        let workspace = workspace.upgrade();
+        let centered_layout = workspace
+            .as_ref()
+            .is_some_and(|workspace| workspace.read(cx).centered_layout);
+        let pane = workspace
+            .as_ref()
+            .zip(editor.upgrade())
+            .and_then(|(workspace, editor)| workspace.read(cx).pane_for(&editor));
+        let pane_zoomed = pane.as_ref().is_some_and(|pane| pane.read(cx).is_zoomed());
+        Self {
+            centered_layout,
+            pane,
+            pane_zoomed,
+        }
+    }
+}
+
 impl ToolbarItemView for QuickActionBar {
     fn set_active_pane_item(
         &mut self,
         active_pane_item: Option<&dyn ItemHandle>,
-        _: &mut Window,
+        window: &mut Window,
         cx: &mut Context<Self>,
     ) -> ToolbarItemLocation {
         self.active_item = active_pane_item.map(ItemHandle::boxed_clone);
         if let Some(active_item) = active_pane_item {
             self._inlay_hints_enabled_subscription.take();
+            self._repl_auto_attach_subscription.take();
 
             if let Some(editor) = active_item.downcast::<Editor>() {
-                let (mut inlay_hints_enabled, mut supports_inlay_hints) =
-                    editor.update(cx, |editor, cx| {
+                let (mut inlay_hints_enabled, mut supports_inlay_hints, mut is_formatting) = editor
+                    .update(cx, |editor, cx| {
                         (
                             editor.inlay_hints_enabled(),
                             editor.supports_inlay_hints(cx),
+                            editor.is_formatting(),
                         )
                     });
                 self._inlay_hints_enabled_subscription =
                     Some(cx.observe(&editor, move |_, editor, cx| {
-                        let (new_inlay_hints_enabled, new_supports_inlay_hints) =
+                        let (new_inlay_hints_enabled, new_supports_inlay_hints, new_is_formatting) =
                             editor.update(cx, |editor, cx| {
                                 (
                                     editor.inlay_hints_enabled(),
                                     editor.supports_inlay_hints(cx),
+                                    editor.is_formatting(),
                                 )
                             });
                         let should_notify = inlay_hints_enabled != new_inlay_hints_enabled
-                            || supports_inlay_hints != new_supports_inlay_hints;
+                            || supports_inlay_hints != new_supports_inlay_hints
+                            || is_formatting != new_is_formatting;
                         inlay_hints_enabled = new_inlay_hints_enabled;
                         supports_inlay_hints = new_supports_inlay_hints;
+                        is_formatting = new_is_formatting;
                         if should_notify {
                             cx.notify()
                         }
                     }));
+
+                self.auto_attach_repl(&editor, window, cx);
+                self._repl_auto_attach_subscription =
+                    Some(cx.observe_in(&editor, window, |this, editor, window, cx| {
+                        this.auto_attach_repl(&editor, window, cx);
+                    }));
             }
         }
         self.get_toolbar_item_location()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::zed::tests::init_test;
+    use gpui::{TestAppContext, VisualTestContext};
+    use project::Project;
+    use util::path;
+
+    #[gpui::test]
+    async fn test_layout_toggles(cx: &mut TestAppContext) {
+        let app_state = init_test(cx);
+        app_state
+            .fs
+            .as_fake()
+            .insert_tree(path!("/root"), serde_json::json!({}))
+            .await;
+        let project = Project::test(app_state.fs.clone(), [path!("/root").as_ref()], cx).await;
+        let window = cx.add_window(|window, cx| Workspace::test_new(project, window, cx));
+        let workspace = window.root(cx).unwrap();
+        let cx = &mut VisualTestContext::from_window(*window, cx);
+
+        let editor = cx.new_window_entity(|window, cx| Editor::single_line(window, cx));
+        workspace.update_in(cx, |workspace, window, cx| {
+            workspace.add_item_to_active_pane(Box::new(editor.clone()), None, true, window, cx);
+        });
+        let read_toggles = |cx: &mut VisualTestContext| {
+            cx.update(|_, cx| LayoutToggles::read(&workspace.downgrade(), &editor.downgrade(), cx))
+        };
+
+        let toggles = read_toggles(cx);
+        assert!(!toggles.centered_layout);
+        assert!(!toggles.pane_zoomed);
+        let pane = toggles.pane.expect("the editor's pane should be found");
+        assert_eq!(
+            pane.entity_id(),
+            workspace.read_with(cx, |workspace, _| workspace.active_pane().entity_id())
+        );
+
+        workspace.update_in(cx, |workspace, window, cx| {
+            workspace.toggle_centered_layout(&workspace::ToggleCenteredLayout, window, cx);
+        });
+        pane.update_in(cx, |pane, window, cx| {
+            pane.toggle_zoom(&workspace::ToggleZoom, window, cx);
+        });
+        cx.run_until_parked();
+        let toggles = read_toggles(cx);
+        assert!(toggles.centered_layout);
+        assert!(toggles.pane_zoomed);
+
+        // An editor that isn't open in the workspace has no pane to zoom.
+        let other_editor = cx.new_window_entity(|window, cx| Editor::single_line(window, cx));
+        let toggles = cx.update(|_, cx| {
+            LayoutToggles::read(&workspace.downgrade(), &other_editor.downgrade(), cx)
+        });
+        assert!(toggles.pane.is_none());
+        assert!(!toggles.pane_zoomed);
+    }
+}
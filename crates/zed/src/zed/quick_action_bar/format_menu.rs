@@ -0,0 +1,81 @@
+use editor::actions::{Format, FormatSelections, OrganizeImports};
+use gpui::{AnyElement, Corner};
+use ui::{ButtonLike, CommonAnimationExt, ContextMenu, PopoverMenu, Tooltip, prelude::*};
+
+use super::QuickActionBar;
+
+impl QuickActionBar {
+    pub fn render_format_menu(&self, cx: &mut Context<Self>) -> Option<AnyElement> {
+        let editor = self.active_editor()?;
+        let editor = editor.read(cx);
+        if !editor.mode().is_full() || editor.read_only(cx) {
+            return None;
+        }
+
+        let is_formatting = editor.is_formatting();
+        let has_formatter = editor
+            .buffer()
+            .read(cx)
+            .language_settings(cx)
+            .has_formatter();
+        let focus_handle = editor.focus_handle(cx);
+
+        let button = ButtonLike::new_rounded_left("format-button")
+            .child(if is_formatting {
+                Icon::new(IconName::ArrowCircle)
+                    .size(IconSize::Small)
+                    .color(Color::Muted)
+                    .with_rotate_animation(2)
+                    .into_any_element()
+            } else {
+                Icon::new(IconName::TextSnippet)
+                    .size(IconSize::Small)
+                    .into_any_element()
+            })
+            .size(ButtonSize::Compact)
+            .style(ButtonStyle::Subtle)
+            .disabled(!has_formatter || is_formatting)
+            .map(|button| {
+                if is_formatting {
+                    button.tooltip(Tooltip::text("Formatting..."))
+                } else if has_formatter {
+                    button.tooltip(Tooltip::for_action_title("Format", &Format))
+                } else {
+                    button.tooltip(Tooltip::text("No Formatter Configured"))
+                }
+            })
+            .on_click({
+                let focus_handle = focus_handle.clone();
+                move |_, window, cx| focus_handle.dispatch_action(&Format, window, cx)
+            });
+
+        let dropdown_menu = PopoverMenu::new("format-menu")
+            .trigger_with_tooltip(
+                ButtonLike::new_rounded_right("format-dropdown")
+                    .child(
+                        Icon::new(IconName::ChevronDown)
+                            .size(IconSize::XSmall)
+                            .color(Color::Muted),
+                    )
+                    .width(rems(1.))
+                    .disabled(!has_formatter || is_formatting),
+                Tooltip::text("Format Menu"),
+            )
+            .anchor(Corner::TopRight)
+            .menu(move |window, cx| {
+                let focus_handle = focus_handle.clone();
+                Some(ContextMenu::build(window, cx, move |menu, _, _| {
+                    menu.context(focus_handle)
+                        .action("Format Selection", Box::new(FormatSelections))
+                        .action("Organize Imports", Box::new(OrganizeImports))
+                }))
+            });
+
+        Some(
+            h_flex()
+                .child(button)
+                .child(dropdown_menu)
+                .into_any_element(),
+        )
+    }
+}
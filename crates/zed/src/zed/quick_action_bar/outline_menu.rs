@@ -0,0 +1,330 @@
+use std::sync::Arc;
+
+use editor::{Editor, SelectionEffects, scroll::Autoscroll};
+use fuzzy::{StringMatch, StringMatchCandidate, match_strings};
+use gpui::{AnyElement, Corner, DismissEvent, Task, WeakEntity};
+use language::{Bias, PointUtf16, Unclipped};
+use picker::{Picker, PickerDelegate};
+use project::DocumentSymbol;
+use ui::{HighlightedLabel, ListItem, ListItemSpacing, PopoverMenu, Tooltip, prelude::*};
+use util::ResultExt;
+use workspace::item::ItemBufferKind;
+use zed_actions::outline::ToggleOutline;
+
+use super::QuickActionBar;
+
+struct OutlineSymbol {
+    depth: usize,
+    position: Unclipped<PointUtf16>,
+}
+
+pub struct OutlineMenuDelegate {
+    editor: WeakEntity<Editor>,
+    symbols: Vec<OutlineSymbol>,
+    candidates: Vec<StringMatchCandidate>,
+    matches: Vec<StringMatch>,
+    selected_index: usize,
+    is_loading: bool,
+}
+
+impl OutlineMenuDelegate {
+    fn new(editor: WeakEntity<Editor>) -> Self {
+        Self {
+            editor,
+            symbols: Vec::new(),
+            candidates: Vec::new(),
+            matches: Vec::new(),
+            selected_index: 0,
+            is_loading: true,
+        }
+    }
+
+    fn set_symbols(&mut self, document_symbols: Vec<DocumentSymbol>) {
+        self.symbols.clear();
+        self.candidates.clear();
+        self.push_symbols(document_symbols, 0);
+        self.is_loading = false;
+    }
+
+    /// Flattens the symbol tree in document order, so that nested symbols
+    /// are listed right after their parent.
+    fn push_symbols(&mut self, document_symbols: Vec<DocumentSymbol>, depth: usize) {
+        for symbol in document_symbols {
+            self.candidates
+                .push(StringMatchCandidate::new(self.symbols.len(), &symbol.name));
+            self.symbols.push(OutlineSymbol {
+                depth,
+                position: symbol.selection_range.start,
+            });
+            self.push_symbols(symbol.children, depth + 1);
+        }
+    }
+}
+
+impl PickerDelegate for OutlineMenuDelegate {
+    type ListItem = ListItem;
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(
+        &mut self,
+        ix: usize,
+        _window: &mut Window,
+        _: &mut Context<Picker<Self>>,
+    ) {
+        self.selected_index = ix;
+    }
+
+    fn placeholder_text(&self, _window: &mut Window, _cx: &mut App) -> Arc<str> {
+        "Search symbols...".into()
+    }
+
+    fn no_matches_text(&self, _window: &mut Window, _cx: &mut App) -> Option<SharedString> {
+        if self.is_loading {
+            Some("Loading symbols...".into())
+        } else if self.symbols.is_empty() {
+            Some("No symbols".into())
+        } else {
+            Some("No matches".into())
+        }
+    }
+
+    fn update_matches(
+        &mut self,
+        query: String,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Task<()> {
+        let background = cx.background_executor().clone();
+        let candidates = self.candidates.clone();
+        cx.spawn_in(window, async move |this, cx| {
+            // Without a query, keep the document order so the list reads like an outline.
+            let matches = if query.is_empty() {
+                candidates
+                    .into_iter()
+                    .map(|candidate| StringMatch {
+                        candidate_id: candidate.id,
+                        string: candidate.string,
+                        positions: Vec::new(),
+                        score: 0.0,
+                    })
+                    .collect()
+            } else {
+                match_strings(
+                    &candidates,
+                    &query,
+                    false,
+                    true,
+                    100,
+                    &Default::default(),
+                    background,
+                )
+                .await
+            };
+
+            this.update(cx, |this, cx| {
+                let delegate = &mut this.delegate;
+                delegate.matches = matches;
+                delegate.selected_index = delegate
+                    .selected_index
+                    .min(delegate.matches.len().saturating_sub(1));
+                cx.notify();
+            })
+            .log_err();
+        })
+    }
+
+    fn confirm(&mut self, _secondary: bool, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let Some(symbol) = self
+            .matches
+            .get(self.selected_index)
+            .and_then(|mat| self.symbols.get(mat.candidate_id))
+        else {
+            return;
+        };
+
+        let position = symbol.position;
+        self.editor
+            .update(cx, |editor, cx| {
+                let snapshot = editor.buffer().read(cx).snapshot(cx);
+                let point = snapshot.clip_point_utf16(position, Bias::Left);
+                let offset = snapshot.point_utf16_to_offset(point);
+                editor.change_selections(
+                    SelectionEffects::scroll(Autoscroll::center()),
+                    window,
+                    cx,
+                    |selections| selections.select_ranges([offset..offset]),
+                );
+                window.focus(&editor.focus_handle(cx));
+            })
+            .log_err();
+        cx.emit(DismissEvent);
+    }
+
+    fn dismissed(&mut self, _window: &mut Window, _cx: &mut Context<Picker<Self>>) {}
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _window: &mut Window,
+        _cx: &mut Context<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let mat = self.matches.get(ix)?;
+        let symbol = self.symbols.get(mat.candidate_id)?;
+        Some(
+            ListItem::new(ix)
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .toggle_state(selected)
+                .indent_level(symbol.depth)
+                .child(HighlightedLabel::new(
+                    mat.string.clone(),
+                    mat.positions.clone(),
+                )),
+        )
+    }
+}
+
+impl QuickActionBar {
+    pub fn render_outline_menu(&self, cx: &mut Context<Self>) -> Option<AnyElement> {
+        let editor = self.active_editor()?;
+        if editor.read(cx).buffer_kind(cx) != ItemBufferKind::Singleton {
+            return None;
+        }
+        let project = editor.read(cx).project()?.clone();
+        let focus_handle = editor.read(cx).focus_handle(cx);
+
+        let menu = PopoverMenu::new("outline-menu")
+            .trigger_with_tooltip(
+                IconButton::new("toggle_outline_menu", IconName::ListTree)
+                    .icon_size(IconSize::Small)
+                    .style(ButtonStyle::Subtle),
+                Tooltip::for_action_title_in("Outline", &ToggleOutline, &focus_handle),
+            )
+            .anchor(Corner::TopRight)
+            .menu(move |window, cx| {
+                let buffer = editor.read(cx).buffer().read(cx).as_singleton()?;
+                let delegate = OutlineMenuDelegate::new(editor.downgrade());
+                let symbols =
+                    project.update(cx, |project, cx| project.document_symbols(&buffer, cx));
+                Some(cx.new(|cx| {
+                    // Symbols come from the language server, so the picker opens
+                    // empty and fills in once the request completes.
+                    cx.spawn_in(window, async move |picker, cx| {
+                        let symbols = symbols.await.log_err().unwrap_or_default();
+                        picker
+                            .update_in(cx, |picker, window, cx| {
+                                picker.delegate.set_symbols(symbols);
+                                picker.refresh(window, cx);
+                            })
+                            .log_err();
+                    })
+                    .detach();
+                    Picker::uniform_list(delegate, window, cx)
+                        .width(rems(24.))
+                        .max_height(Some(rems(20.).into()))
+                }))
+            });
+
+        Some(menu.into_any_element())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::zed::tests::init_test;
+    use gpui::TestAppContext;
+    use language::Point;
+
+    fn symbol(name: &str, row: u32, column: u32, children: Vec<DocumentSymbol>) -> DocumentSymbol {
+        let position = Unclipped(PointUtf16::new(row, column));
+        DocumentSymbol {
+            name: name.to_string(),
+            kind: lsp::SymbolKind::STRUCT,
+            range: position..position,
+            selection_range: position..position,
+            children,
+        }
+    }
+
+    #[gpui::test]
+    async fn test_outline_menu_symbols(cx: &mut TestAppContext) {
+        init_test(cx);
+        let (editor, cx) = cx.add_window_view(|window, cx| {
+            let mut editor = Editor::multi_line(window, cx);
+            editor.set_text(
+                "struct Outer {\n    inner: Inner,\n}\nfn helper() {}\n",
+                window,
+                cx,
+            );
+            editor
+        });
+        let picker = cx.new_window_entity(|window, cx| {
+            let mut delegate = OutlineMenuDelegate::new(editor.downgrade());
+            delegate.set_symbols(vec![
+                symbol(
+                    "Outer",
+                    0,
+                    7,
+                    vec![symbol("inner", 1, 4, vec![symbol("nested", 1, 11, vec![])])],
+                ),
+                symbol("helper", 3, 3, vec![]),
+            ]);
+            Picker::uniform_list(delegate, window, cx)
+        });
+
+        // Without a query, symbols are listed in document order, with children after their parent.
+        picker
+            .update_in(cx, |picker, window, cx| {
+                picker.delegate.update_matches(String::new(), window, cx)
+            })
+            .await;
+        picker.read_with(cx, |picker, _| {
+            let delegate = &picker.delegate;
+            assert!(!delegate.is_loading);
+            assert_eq!(
+                delegate
+                    .matches
+                    .iter()
+                    .map(|mat| (
+                        mat.string.as_str(),
+                        delegate.symbols[mat.candidate_id].depth
+                    ))
+                    .collect::<Vec<_>>(),
+                [("Outer", 0), ("inner", 1), ("nested", 2), ("helper", 0)]
+            );
+        });
+
+        picker
+            .update_in(cx, |picker, window, cx| {
+                picker
+                    .delegate
+                    .update_matches("help".to_string(), window, cx)
+            })
+            .await;
+        picker.update_in(cx, |picker, window, cx| {
+            let names = picker
+                .delegate
+                .matches
+                .iter()
+                .map(|mat| mat.string.as_str())
+                .collect::<Vec<_>>();
+            assert_eq!(names, ["helper"]);
+            picker.delegate.confirm(false, window, cx);
+        });
+        editor.update(cx, |editor, cx| {
+            assert_eq!(
+                editor.selections.newest::<Point>(cx).head(),
+                Point::new(3, 3)
+            );
+        });
+    }
+}
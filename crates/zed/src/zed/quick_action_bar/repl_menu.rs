@@ -1,3 +1,4 @@
+use editor::Editor;
 use gpui::ElementId;
 use gpui::{AnyElement, Entity};
 use picker::Picker;
@@ -246,6 +247,55 @@ impl QuickActionBar {
                 .into_any_element(),
         )
     }
+
+    /// Starts a kernel for the editor when its language sets
+    /// `repl.auto_attach`. Each editor is only attached once, so a kernel
+    /// that was shut down isn't restarted behind the user's back.
+    pub fn auto_attach_repl(
+        &mut self,
+        editor: &Entity<Editor>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self
+            .auto_attached_repl_editors
+            .contains(&editor.entity_id())
+            || !JupyterSettings::enabled(cx)
+        {
+            return;
+        }
+
+        let is_local_project = editor
+            .read(cx)
+            .workspace()
+            .map(|workspace| workspace.read(cx).project().read(cx).is_local())
+            .unwrap_or(false);
+        let auto_attach = editor
+            .read(cx)
+            .buffer()
+            .read(cx)
+            .language_settings(cx)
+            .repl
+            .auto_attach;
+        if !is_local_project || !auto_attach {
+            return;
+        }
+
+        match repl::session(editor.downgrade(), cx) {
+            SessionSupport::ActiveSession(_) => {
+                self.auto_attached_repl_editors.insert(editor.entity_id());
+            }
+            SessionSupport::Inactive(kernel_specification) => {
+                self.auto_attached_repl_editors.insert(editor.entity_id());
+                repl::assign_kernelspec(kernel_specification, editor.downgrade(), window, cx)
+                    .log_err();
+            }
+            // The buffer's language or the kernels may still be loading, so
+            // try again when the editor changes.
+            SessionSupport::RequiresSetup(_) | SessionSupport::Unsupported => {}
+        }
+    }
+
     pub fn render_repl_launch_menu(
         &self,
         kernel_specification: KernelSpecification,
@@ -0,0 +1,133 @@
+use super::OpenRendererDiagnostics;
+use gpui::{Empty, GpuSpecs};
+use std::fmt::Write as _;
+use ui::{Tooltip, prelude::*};
+use workspace::{ItemHandle, StatusItemView};
+
+/// Warns in the status bar when a window is rendered by a software rasterizer, which is much
+/// slower than a GPU, and opens the renderer diagnostics when clicked.
+pub struct SoftwareRenderingIndicator {
+    specs: Option<GpuSpecs>,
+}
+
+impl SoftwareRenderingIndicator {
+    pub fn new(specs: Option<GpuSpecs>) -> Self {
+        // Users who knowingly run on a software GPU, such as in a virtual machine, can hide it.
+        let specs = specs.filter(|specs| {
+            specs.is_software_emulated && std::env::var_os("ZED_ALLOW_EMULATED_GPU").is_none()
+        });
+        Self { specs }
+    }
+}
+
+impl Render for SoftwareRenderingIndicator {
+    fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+        let Some(specs) = &self.specs else {
+            return Empty.into_any_element();
+        };
+        let tooltip = if specs.fallback_reason.is_some() {
+            "The GPU failed to initialize, so rendering is done in software"
+        } else {
+            "Rendering is done by a software emulated GPU"
+        };
+        IconButton::new("software-rendering", IconName::Warning)
+            .icon_size(IconSize::Small)
+            .icon_color(Color::Warning)
+            .tooltip(Tooltip::text(tooltip))
+            .on_click(|_, window, cx| window.dispatch_action(Box::new(OpenRendererDiagnostics), cx))
+            .into_any_element()
+    }
+}
+
+impl StatusItemView for SoftwareRenderingIndicator {
+    fn set_active_pane_item(
+        &mut self,
+        _active_pane_item: Option<&dyn ItemHandle>,
+        _window: &mut Window,
+        _cx: &mut Context<Self>,
+    ) {
+    }
+}
+
+/// Describes the renderer a window uses, as Markdown.
+pub fn report(specs: Option<&GpuSpecs>) -> String {
+    let mut report = String::from("# Renderer Diagnostics\n\n");
+    let Some(specs) = specs else {
+        report.push_str("Renderer information isn't available on this platform.\n");
+        return report;
+    };
+
+    writeln!(report, "| | |\n|---|---|").ok();
+    for (name, value) in [
+        ("Backend", specs.backend.as_str()),
+        ("Adapter", specs.device_name.as_str()),
+        ("Driver", specs.driver_name.as_str()),
+        ("Driver info", specs.driver_info.as_str()),
+        (
+            "Software rendering",
+            if specs.is_software_emulated {
+                "Yes"
+            } else {
+                "No"
+            },
+        ),
+    ] {
+        writeln!(report, "| {name} | {value} |").ok();
+    }
+
+    if let Some(reason) = &specs.fallback_reason {
+        writeln!(
+            report,
+            "\n## Software Fallback\n\n\
+            The GPU failed to initialize, so Zed fell back to rendering on the CPU:\n\n\
+            ```\n{reason}\n```"
+        )
+        .ok();
+    }
+    if specs.is_software_emulated {
+        let docs_url = if cfg!(target_os = "windows") {
+            "https://zed.dev/docs/windows"
+        } else {
+            "https://zed.dev/docs/linux#zed-fails-to-open-windows"
+        };
+        writeln!(
+            report,
+            "\nSoftware rendering is much slower than rendering on a GPU. \
+            Installing up-to-date drivers for your GPU usually fixes this. \
+            For troubleshooting, see {docs_url}.\n\n\
+            Set the `ZED_ALLOW_EMULATED_GPU=1` environment variable to hide the status bar warning."
+        )
+        .ok();
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report() {
+        let specs = GpuSpecs {
+            is_software_emulated: true,
+            device_name: "llvmpipe (LLVM 17.0.6, 256 bits)".to_string(),
+            driver_name: "llvmpipe".to_string(),
+            driver_info: "Mesa 24.0.5".to_string(),
+            backend: "Vulkan".to_string(),
+            fallback_reason: Some("NoSupportedDeviceFound".to_string()),
+        };
+        let markdown = report(Some(&specs));
+        assert!(markdown.contains("| Adapter | llvmpipe (LLVM 17.0.6, 256 bits) |\n"));
+        assert!(markdown.contains("| Software rendering | Yes |\n"));
+        assert!(markdown.contains("```\nNoSupportedDeviceFound\n```"));
+
+        let specs = GpuSpecs {
+            is_software_emulated: false,
+            fallback_reason: None,
+            ..specs
+        };
+        let markdown = report(Some(&specs));
+        assert!(markdown.contains("| Software rendering | No |\n"));
+        assert!(!markdown.contains("Software Fallback"));
+    }
+}
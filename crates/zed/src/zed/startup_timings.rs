@@ -0,0 +1,121 @@
+use gpui::{App, Global};
+use project::Project;
+use std::{
+    fmt::Write as _,
+    time::{Duration, Instant},
+};
+
+/// How long each stage of startup took, which `zed --timings` prints once the first workspace
+/// has constructed its panels.
+pub struct StartupTimings {
+    started_at: Instant,
+    /// Each stage, with the time since startup began at which it finished.
+    stages: Vec<(&'static str, Duration)>,
+    reported: bool,
+}
+
+impl Global for StartupTimings {}
+
+impl StartupTimings {
+    pub fn start() -> Self {
+        Self {
+            started_at: Instant::now(),
+            stages: Vec::new(),
+            reported: false,
+        }
+    }
+
+    /// Records that a stage finished now. Stages that finish again, such as when another window
+    /// opens, keep the time they first finished at.
+    pub fn record(&mut self, stage: &'static str) {
+        if self.reported || self.stages.iter().any(|(name, _)| *name == stage) {
+            return;
+        }
+        self.stages.push((stage, self.started_at.elapsed()));
+    }
+
+    /// Records that a stage finished now, if startup is being timed.
+    pub fn record_global(stage: &'static str, cx: &mut App) {
+        if cx.has_global::<Self>() {
+            cx.global_mut::<Self>().record(stage);
+        }
+    }
+
+    /// Records the last stage of startup and prints the timings of every stage.
+    pub fn finish_global(stage: &'static str, cx: &mut App) {
+        if !cx.has_global::<Self>() {
+            return;
+        }
+        let timings = cx.global_mut::<Self>();
+        if timings.reported {
+            return;
+        }
+        timings.record(stage);
+        timings.reported = true;
+        eprint!("{}", timings.report());
+    }
+
+    /// Records when the first worktree starts scanning, which happens before the window that
+    /// shows it is opened.
+    pub fn observe_worktree_scans(cx: &mut App) {
+        cx.observe_new(|_: &mut Project, _, cx| {
+            cx.subscribe_self(|_, event, cx| {
+                if let project::Event::WorktreeAdded(_) = event {
+                    Self::record_global("worktree scan start", cx);
+                }
+            })
+            .detach();
+        })
+        .detach();
+    }
+
+    fn report(&self) -> String {
+        let name_width = self
+            .stages
+            .iter()
+            .map(|(name, _)| name.len())
+            .max()
+            .unwrap_or_default();
+        let mut report = String::from("Startup timings:\n");
+        let mut previous = Duration::ZERO;
+        for (name, finished_at) in &self.stages {
+            let took = finished_at.saturating_sub(previous);
+            writeln!(
+                report,
+                "  {name:<name_width$}  {:>8.1}ms  (at {:.1}ms)",
+                took.as_secs_f64() * 1000.0,
+                finished_at.as_secs_f64() * 1000.0,
+            )
+            .ok();
+            previous = *finished_at;
+        }
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report() {
+        let timings = StartupTimings {
+            started_at: Instant::now(),
+            stages: vec![
+                ("settings load", Duration::from_millis(12)),
+                ("extension load", Duration::from_millis(40)),
+                ("panel construction", Duration::from_millis(95)),
+            ],
+            reported: false,
+        };
+        assert_eq!(
+            timings.report(),
+            concat!(
+                "Startup timings:\n",
+                "  settings load           12.0ms  (at 12.0ms)\n",
+                "  extension load          28.0ms  (at 40.0ms)\n",
+                "  panel construction      55.0ms  (at 95.0ms)\n",
+            )
+        );
+    }
+}
@@ -172,6 +172,18 @@ pub mod workspace {
     );
 }
 
+pub mod hex_editor {
+    use gpui::actions;
+
+    actions!(
+        hex_editor,
+        [
+            /// Reopens the active file in the hex editor.
+            ReopenAsHex
+        ]
+    );
+}
+
 pub mod git {
     use gpui::actions;
 
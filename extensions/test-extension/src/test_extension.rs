@@ -1,6 +1,6 @@
 use std::fs;
 use zed::lsp::CompletionKind;
-use zed::{CodeLabel, CodeLabelSpan, LanguageServerId};
+use zed::{CodeLabel, CodeLabelSpan, LanguageServerId, PanelItem};
 use zed_extension_api::process::Command;
 use zed_extension_api::{self as zed, Result};
 
@@ -187,6 +187,26 @@ impl zed::Extension for TestExtension {
             code,
         })
     }
+
+    fn panel_items(
+        &mut self,
+        panel_id: String,
+        parent_item_id: Option<String>,
+    ) -> Result<Vec<PanelItem>> {
+        match (panel_id.as_str(), parent_item_id) {
+            ("test-panel", None) => Ok(vec![PanelItem {
+                id: "greeting".into(),
+                label: "Hello from the test extension!".into(),
+                detail: None,
+                icon: None,
+                path: None,
+                expandable: false,
+                commands: Vec::new(),
+            }]),
+            ("test-panel", Some(_)) => Ok(Vec::new()),
+            (panel_id, _) => Err(format!("unknown panel: {panel_id}")),
+        }
+    }
 }
 
 zed::register_extension!(TestExtension);